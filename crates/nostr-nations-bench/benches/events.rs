@@ -0,0 +1,90 @@
+//! Event batching and compression for network transmission.
+
+use criterion::{criterion_group, criterion_main, BatchSize, Criterion, Throughput};
+use nostr_nations_bench::bench_events;
+use nostr_nations_network::{
+    BatchConfig, EventBatch, EventBatcher, EventUnbatcher, PayloadCompressor,
+};
+use std::hint::black_box;
+
+const EVENTS: usize = 1000;
+
+fn bench_batching(c: &mut Criterion) {
+    let events = bench_events(EVENTS);
+    let mut group = c.benchmark_group("events");
+    group.throughput(Throughput::Elements(EVENTS as u64));
+
+    group.bench_function("batch", |b| {
+        b.iter_batched(
+            || events.clone(),
+            |events| {
+                let mut batcher = EventBatcher::new(BatchConfig {
+                    adaptive_flush: false,
+                    ..BatchConfig::default()
+                });
+                let mut batches = Vec::new();
+                for event in events {
+                    batcher.add_event(event);
+                    if batcher.is_batch_ready() {
+                        batches.extend(batcher.flush());
+                    }
+                }
+                batches.extend(batcher.flush());
+                batches
+            },
+            BatchSize::SmallInput,
+        )
+    });
+
+    let batches: Vec<EventBatch> = events
+        .chunks(50)
+        .enumerate()
+        .map(|(i, chunk)| EventBatch::new(i as u64, chunk.to_vec()))
+        .collect();
+    group.bench_function("unbatch", |b| {
+        b.iter_batched(
+            || batches.clone(),
+            |batches| {
+                let mut unbatcher = EventUnbatcher::new();
+                for batch in batches {
+                    black_box(unbatcher.process_batch(batch));
+                }
+            },
+            BatchSize::SmallInput,
+        )
+    });
+
+    let bytes: Vec<Vec<u8>> = batches
+        .iter()
+        .map(|batch| batch.to_bytes().expect("batches serialize"))
+        .collect();
+    group.bench_function("compress", |b| {
+        let mut compressor = PayloadCompressor::with_defaults();
+        b.iter(|| {
+            for data in &bytes {
+                black_box(compressor.compress(data));
+            }
+        })
+    });
+
+    let mut compressor = PayloadCompressor::with_defaults();
+    let compressed: Vec<_> = bytes
+        .iter()
+        .filter_map(|data| compressor.compress(data))
+        .collect();
+    group.bench_function("decompress", |b| {
+        b.iter(|| {
+            for payload in &compressed {
+                black_box(
+                    compressor
+                        .decompress(payload)
+                        .expect("payload decompresses"),
+                );
+            }
+        })
+    });
+    group.finish();
+}
+
+criterion_group!(benches, bench_batching);
+criterion_main!(benches);
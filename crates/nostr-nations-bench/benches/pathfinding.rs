@@ -0,0 +1,79 @@
+//! Pathfinding on large maps: plain A*, the hierarchical path cache, and
+//! reachability for movement overlays.
+
+use criterion::{criterion_group, criterion_main, BenchmarkId, Criterion, Throughput};
+use nostr_nations_bench::{bench_map, path_queries};
+use nostr_nations_core::{find_path, find_reachable, HexCoord, PathCache, PathConfig};
+use std::hint::black_box;
+
+/// Map sizes to benchmark: the huge preset and a larger custom map.
+const SIZES: [(u32, u32); 2] = [(120, 75), (160, 100)];
+
+const QUERIES: usize = 20;
+
+fn bench_find_path(c: &mut Criterion) {
+    let mut group = c.benchmark_group("pathfinding/find_path");
+    group.sample_size(20);
+    group.throughput(Throughput::Elements(QUERIES as u64));
+    let config = PathConfig::default();
+    for (width, height) in SIZES {
+        let map = bench_map(width, height);
+        let queries = path_queries(width, height, QUERIES);
+        group.bench_with_input(
+            BenchmarkId::from_parameter(format!("{}x{}", width, height)),
+            &queries,
+            |b, queries| {
+                b.iter(|| {
+                    for (from, to) in queries {
+                        black_box(find_path(&map, *from, *to, &config));
+                    }
+                })
+            },
+        );
+    }
+    group.finish();
+}
+
+fn bench_path_cache(c: &mut Criterion) {
+    let mut group = c.benchmark_group("pathfinding/path_cache_warm");
+    group.throughput(Throughput::Elements(QUERIES as u64));
+    let config = PathConfig::default();
+    for (width, height) in SIZES {
+        let map = bench_map(width, height);
+        let queries = path_queries(width, height, QUERIES);
+        let mut cache = PathCache::default();
+        for (from, to) in &queries {
+            cache.find_path(&map, *from, *to, &config);
+        }
+        group.bench_with_input(
+            BenchmarkId::from_parameter(format!("{}x{}", width, height)),
+            &queries,
+            |b, queries| {
+                b.iter(|| {
+                    for (from, to) in queries {
+                        black_box(cache.find_path(&map, *from, *to, &config));
+                    }
+                })
+            },
+        );
+    }
+    group.finish();
+}
+
+fn bench_find_reachable(c: &mut Criterion) {
+    let (width, height) = SIZES[0];
+    let map = bench_map(width, height);
+    let config = PathConfig::default();
+    let start = HexCoord::new(width as i32 / 4, height as i32 / 4);
+    c.bench_function("pathfinding/find_reachable", |b| {
+        b.iter(|| black_box(find_reachable(&map, start, &config)))
+    });
+}
+
+criterion_group!(
+    benches,
+    bench_find_path,
+    bench_path_cache,
+    bench_find_reachable
+);
+criterion_main!(benches);
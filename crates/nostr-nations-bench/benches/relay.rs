@@ -0,0 +1,69 @@
+//! Local relay throughput: publishing events and answering queries.
+
+use criterion::{criterion_group, criterion_main, BatchSize, Criterion, Throughput};
+use nostr_nations_bench::{bench_events, GAME_ID};
+use nostr_nations_core::{event_kinds, GameId};
+use nostr_nations_network::{Filter, LocalRelay};
+use std::hint::black_box;
+
+const EVENTS: usize = 5000;
+
+fn bench_publish(c: &mut Criterion) {
+    let events = bench_events(EVENTS);
+    let mut group = c.benchmark_group("relay/publish");
+    group.sample_size(10);
+    group.throughput(Throughput::Elements(EVENTS as u64));
+    group.bench_function("one_by_one", |b| {
+        b.iter_batched(
+            || LocalRelay::new_in_memory().expect("in-memory relay opens"),
+            |relay| {
+                for event in &events {
+                    relay.publish(event).expect("event is stored");
+                }
+                relay
+            },
+            BatchSize::PerIteration,
+        )
+    });
+    group.bench_function("batch", |b| {
+        b.iter_batched(
+            || LocalRelay::new_in_memory().expect("in-memory relay opens"),
+            |relay| {
+                relay.publish_batch(&events).expect("events are stored");
+                relay
+            },
+            BatchSize::PerIteration,
+        )
+    });
+    group.finish();
+}
+
+fn bench_query(c: &mut Criterion) {
+    let relay = LocalRelay::new_in_memory().expect("in-memory relay opens");
+    relay
+        .publish_batch(&bench_events(EVENTS))
+        .expect("events are stored");
+
+    let mut group = c.benchmark_group("relay/query");
+    let filters = [
+        ("game", Filter::game(GameId::new(GAME_ID))),
+        (
+            "game_limit_100",
+            Filter::game(GameId::new(GAME_ID)).limit(100),
+        ),
+        ("kind", Filter::kinds(vec![event_kinds::GAME_ACTION])),
+        (
+            "time_window",
+            Filter::new().since(1_700_001_000).until(1_700_002_000),
+        ),
+    ];
+    for (name, filter) in filters {
+        group.bench_function(name, |b| {
+            b.iter(|| black_box(relay.query(&filter).expect("query succeeds")))
+        });
+    }
+    group.finish();
+}
+
+criterion_group!(benches, bench_publish, bench_query);
+criterion_main!(benches);
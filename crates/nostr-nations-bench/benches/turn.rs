@@ -0,0 +1,41 @@
+//! Full-turn processing: every player of an eight-player game ending their
+//! turn, which runs growth, production, research and upkeep for all cities.
+
+use criterion::{criterion_group, criterion_main, BatchSize, Criterion};
+use nostr_nations_bench::{eight_player_game, PLAYER_COUNT};
+use nostr_nations_core::{process_end_turn, GameAction, GameEngine, PlayerSlot};
+use std::hint::black_box;
+
+fn bench_full_turn(c: &mut Criterion) {
+    let engine = eight_player_game();
+    let (state, seed) = (engine.state.clone(), engine.state.seed);
+
+    let mut group = c.benchmark_group("turn");
+    group.bench_function("full_turn_8_players", |b| {
+        b.iter_batched(
+            || GameEngine::from_state(state.clone(), seed),
+            |mut engine| {
+                for player_id in PlayerSlot::all(PLAYER_COUNT as usize) {
+                    let result = engine.apply_action(player_id, &GameAction::EndTurn);
+                    black_box(result.expect("ending a turn succeeds"));
+                }
+                engine
+            },
+            BatchSize::SmallInput,
+        )
+    });
+    group.bench_function("process_end_turn", |b| {
+        b.iter_batched(
+            || state.clone(),
+            |mut state| {
+                black_box(process_end_turn(&mut state).expect("turn processing succeeds"));
+                state
+            },
+            BatchSize::SmallInput,
+        )
+    });
+    group.finish();
+}
+
+criterion_group!(benches, bench_full_turn);
+criterion_main!(benches);
@@ -0,0 +1,34 @@
+//! Visibility recomputation: rebuilding each player's view of the game and
+//! filtering the state sent to them.
+
+use criterion::{criterion_group, criterion_main, Criterion};
+use nostr_nations_bench::{eight_player_game, PLAYER_COUNT};
+use nostr_nations_core::{PlayerSlot, VisibilityFilter};
+use std::hint::black_box;
+
+fn bench_visibility(c: &mut Criterion) {
+    let game = eight_player_game().state;
+
+    let mut group = c.benchmark_group("visibility");
+    group.bench_function("update_all_players", |b| {
+        let mut filters: Vec<VisibilityFilter> = PlayerSlot::all(PLAYER_COUNT as usize)
+            .map(VisibilityFilter::new)
+            .collect();
+        b.iter(|| {
+            for filter in &mut filters {
+                filter.update_from_game_state(&game);
+            }
+            black_box(&filters);
+        })
+    });
+
+    let mut filter = VisibilityFilter::new(PlayerSlot(0));
+    filter.update_from_game_state(&game);
+    group.bench_function("filter_game_state", |b| {
+        b.iter(|| black_box(filter.filter_game_state(&game)))
+    });
+    group.finish();
+}
+
+criterion_group!(benches, bench_visibility);
+criterion_main!(benches);
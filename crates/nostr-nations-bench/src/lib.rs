@@ -0,0 +1,197 @@
+//! Shared fixtures for the Nostr Nations benchmarks.
+//!
+//! The benchmarks themselves live in `benches/` and run with Criterion:
+//!
+//! ```text
+//! cargo bench -p nostr-nations-bench
+//! cargo bench -p nostr-nations-bench --bench pathfinding -- --save-baseline main
+//! cargo bench -p nostr-nations-bench --bench pathfinding -- --baseline main
+//! ```
+//!
+//! Criterion compares each run with the previous one (or a saved baseline)
+//! and reports any statistically significant change, so a regression in one
+//! of these paths shows up as a number rather than a feeling.
+//!
+//! Every fixture here is deterministic: the same call builds the same map,
+//! game and events, so runs are comparable across machines and commits.
+
+use nostr_nations_core::terrain::{Feature, Terrain};
+use nostr_nations_core::{
+    GameAction, GameEngine, GameEvent, GameId, GameSettings, HexCoord, Map, MapSize, PlayerSlot,
+    UnitId, UnitType,
+};
+
+/// Number of players in the full-turn benchmark game.
+pub const PLAYER_COUNT: u8 = 8;
+
+/// Game ID used by generated events.
+pub const GAME_ID: &str = "bench-game";
+
+/// Small xorshift generator so fixtures don't depend on `rand`.
+struct XorShift(u32);
+
+impl XorShift {
+    fn next(&mut self) -> u32 {
+        self.0 ^= self.0 << 13;
+        self.0 ^= self.0 >> 17;
+        self.0 ^= self.0 << 5;
+        self.0
+    }
+}
+
+/// A varied map with forests, hills, mountain ridges and a central lake.
+///
+/// Ridges run every 20 columns with a pass every 16 rows, so long paths
+/// have to detour.
+pub fn bench_map(width: u32, height: u32) -> Map {
+    let mut map = Map::filled(width, height, Terrain::Grassland);
+    let mut rng = XorShift(0x2545_f491);
+    let (cq, cr) = (width as i32 / 2, height as i32 / 2);
+
+    let mut coords: Vec<HexCoord> = map.tiles.keys().copied().collect();
+    coords.sort_by_key(|c| (c.q, c.r));
+    for coord in coords {
+        let tile = map.get_mut(&coord).expect("coordinate from the map");
+        tile.feature = match rng.next() % 10 {
+            0..=2 => Some(Feature::Forest),
+            3 => Some(Feature::Hills),
+            _ => None,
+        };
+        if coord.q % 20 == 10 && coord.r % 16 != 8 {
+            tile.feature = Some(Feature::Mountains);
+        }
+        if (coord.q - cq).abs() < 6 && (coord.r - cr).abs() < 6 {
+            tile.feature = None;
+            tile.terrain = Terrain::Coast;
+        }
+    }
+    map
+}
+
+/// Path queries crossing a map from its left edge to its right edge.
+pub fn path_queries(width: u32, height: u32, count: usize) -> Vec<(HexCoord, HexCoord)> {
+    let (width, height) = (width as i32, height as i32);
+    (0..count as i32)
+        .map(|i| {
+            let start = HexCoord::new((i * 7) % 20, (i * 11) % height);
+            let goal = HexCoord::new(width - 1 - (i * 5) % 20, (i * 13 + 17) % height);
+            (start, goal)
+        })
+        .collect()
+}
+
+/// A started game with [`PLAYER_COUNT`] players on a standard map, where
+/// every player has founded a city with their settler.
+pub fn eight_player_game() -> GameEngine {
+    let mut settings = GameSettings::new("Benchmark".to_string());
+    settings.map_size = MapSize::Standard;
+    settings.player_count = PLAYER_COUNT;
+    let mut engine = GameEngine::new(settings, [7u8; 32]);
+
+    let civilizations = [
+        "rome", "egypt", "greece", "china", "persia", "aztec", "japan", "england",
+    ];
+    for (i, civilization) in civilizations.iter().enumerate() {
+        engine
+            .apply_action(
+                PlayerSlot(i as u8),
+                &GameAction::JoinGame {
+                    player_name: format!("Player {}", i + 1),
+                    civilization_id: civilization.to_string(),
+                },
+            )
+            .expect("joining a new game succeeds");
+    }
+    engine
+        .apply_action(PlayerSlot(0), &GameAction::StartGame)
+        .expect("starting a full game succeeds");
+
+    // Each player founds a city on their turn so turn processing has
+    // cities to grow and produce in
+    for player_id in PlayerSlot::all(PLAYER_COUNT as usize) {
+        let settler = engine
+            .state
+            .units
+            .values()
+            .find(|u| u.owner == player_id && u.unit_type == UnitType::Settler)
+            .map(|u| u.id);
+        if let Some(settler_id) = settler {
+            let _ = engine.apply_action(
+                player_id,
+                &GameAction::FoundCity {
+                    settler_id,
+                    name: format!("Capital {}", player_id.get() + 1),
+                },
+            );
+        }
+        engine
+            .apply_action(player_id, &GameAction::EndTurn)
+            .expect("ending a turn succeeds");
+    }
+    engine
+}
+
+/// A chain of `count` game events, mostly unit moves with some combat and
+/// turn ends, spread over [`PLAYER_COUNT`] players.
+pub fn bench_events(count: usize) -> Vec<GameEvent> {
+    let mut rng = XorShift(0x9e37_79b9);
+    let mut prev: Option<String> = None;
+    (0..count)
+        .map(|i| {
+            let player_id = PlayerSlot((i % PLAYER_COUNT as usize) as u8);
+            let unit_id = UnitId((rng.next() % 500) as u64);
+            let action = match rng.next() % 10 {
+                0 => GameAction::EndTurn,
+                1 => GameAction::AttackUnit {
+                    attacker_id: unit_id,
+                    defender_id: unit_id.next(),
+                    random: (rng.next() % 1000) as f32 / 1000.0,
+                },
+                _ => GameAction::MoveUnit {
+                    unit_id,
+                    path: (0..4)
+                        .map(|step| HexCoord::new((i % 80) as i32 + step, (i % 50) as i32))
+                        .collect(),
+                },
+            };
+            let mut event = GameEvent::new(
+                GameId::new(GAME_ID),
+                player_id,
+                prev.take(),
+                (i / 64) as u32 + 1,
+                (i % 64) as u32,
+                action,
+            );
+            event.id = format!("{:064x}", i);
+            event.timestamp = 1_700_000_000 + i as u64;
+            prev = Some(event.id.clone());
+            event
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use nostr_nations_core::GamePhase;
+
+    #[test]
+    fn test_fixtures_are_deterministic() {
+        assert_eq!(
+            serde_json::to_string(&bench_events(50)).unwrap(),
+            serde_json::to_string(&bench_events(50)).unwrap()
+        );
+        let map = bench_map(40, 30);
+        assert_eq!(map.tiles.len(), 40 * 30);
+        assert_eq!(path_queries(40, 30, 5).len(), 5);
+    }
+
+    #[test]
+    fn test_eight_player_game_has_cities() {
+        let engine = eight_player_game();
+        assert_eq!(engine.state.phase, GamePhase::Playing);
+        assert_eq!(engine.state.players.len(), PLAYER_COUNT as usize);
+        assert_eq!(engine.state.cities.len(), PLAYER_COUNT as usize);
+        assert_eq!(engine.state.turn, 2);
+    }
+}
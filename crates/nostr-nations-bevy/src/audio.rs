@@ -0,0 +1,398 @@
+//! Sound cues for game events.
+//!
+//! The [`AudioPlugin`] turns engine effects into [`SoundCue`]s and plays the
+//! sound the active [`SoundLibrary`] maps each cue to, at the volume set in
+//! [`GameSettingsResource::audio`].
+//!
+//! Sound packs are described by a [`SoundManifest`], a JSON file listing the
+//! asset path for each cue. Registering a manifest replaces the paths it
+//! lists and keeps the others, so a pack may override only some cues:
+//!
+//! ```json
+//! {
+//!     "name": "Retro",
+//!     "sounds": {
+//!         "move": "packs/retro/step.ogg",
+//!         "your_turn": "packs/retro/bell.ogg"
+//!     }
+//! }
+//! ```
+//!
+//! The plugin does not add Bevy's own `AudioPlugin`; it is part of
+//! `DefaultPlugins`. Without an `AssetServer` cues are still sent as
+//! [`SoundCueEvent`]s but nothing is played.
+
+use bevy::audio::Volume;
+use bevy::prelude::*;
+use nostr_nations_core::replay::ActionEffect;
+use nostr_nations_core::PlayerSlot;
+use serde::{Deserialize, Serialize};
+use std::collections::{BTreeMap, BTreeSet, HashMap};
+
+use crate::plugins::ActionEffectEvent;
+use crate::resources::GameSettingsResource;
+use crate::systems::GameSystemSet;
+
+/// A sound played in response to a game event.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash, PartialOrd, Ord, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum SoundCue {
+    /// A unit moved.
+    Move,
+    /// A unit or city took damage or was destroyed.
+    Combat,
+    /// A city was founded.
+    CityFounded,
+    /// The local player's turn started.
+    YourTurn,
+    /// Something the local player should look at happened.
+    Notification,
+}
+
+impl SoundCue {
+    /// All cues.
+    pub const ALL: [SoundCue; 5] = [
+        SoundCue::Move,
+        SoundCue::Combat,
+        SoundCue::CityFounded,
+        SoundCue::YourTurn,
+        SoundCue::Notification,
+    ];
+
+    /// The cue for an effect, if it has one.
+    ///
+    /// `player_id` is the player whose action caused the effect. Turn and
+    /// notification cues only play for the local player.
+    pub fn for_effect(
+        effect: &ActionEffect,
+        player_id: PlayerSlot,
+        local_player_id: PlayerSlot,
+    ) -> Option<Self> {
+        let local = player_id == local_player_id;
+        match effect {
+            ActionEffect::UnitMoved { .. } => Some(SoundCue::Move),
+            ActionEffect::UnitDamaged { .. }
+            | ActionEffect::UnitDestroyed { .. }
+            | ActionEffect::CityDamaged { .. }
+            | ActionEffect::CityBombarded { .. }
+            | ActionEffect::CityCaptured { .. } => Some(SoundCue::Combat),
+            ActionEffect::CityFounded { .. } => Some(SoundCue::CityFounded),
+            ActionEffect::TurnStarted { player_id, .. } if *player_id == local_player_id => {
+                Some(SoundCue::YourTurn)
+            }
+            ActionEffect::TechResearched { player_id, .. }
+            | ActionEffect::EraEntered { player_id, .. }
+            | ActionEffect::FirstContact { player_id, .. }
+            | ActionEffect::Circumnavigated { player_id, .. }
+                if *player_id == local_player_id =>
+            {
+                Some(SoundCue::Notification)
+            }
+            ActionEffect::PromotionAvailable { .. }
+            | ActionEffect::BuildingCompleted { .. }
+            | ActionEffect::WonderCompleted { .. }
+                if local =>
+            {
+                Some(SoundCue::Notification)
+            }
+            ActionEffect::GameEnded { .. } => Some(SoundCue::Notification),
+            _ => None,
+        }
+    }
+}
+
+/// Asset paths of a sound pack.
+#[derive(Clone, Debug, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct SoundManifest {
+    /// Pack name.
+    pub name: String,
+    /// Asset path for each cue the pack provides.
+    #[serde(default)]
+    pub sounds: BTreeMap<SoundCue, String>,
+}
+
+impl SoundManifest {
+    /// The built-in pack.
+    pub fn builtin() -> Self {
+        let sounds = [
+            (SoundCue::Move, "sounds/move.ogg"),
+            (SoundCue::Combat, "sounds/combat.ogg"),
+            (SoundCue::CityFounded, "sounds/city_founded.ogg"),
+            (SoundCue::YourTurn, "sounds/your_turn.ogg"),
+            (SoundCue::Notification, "sounds/notification.ogg"),
+        ];
+        Self {
+            name: "Default".to_string(),
+            sounds: sounds
+                .into_iter()
+                .map(|(cue, path)| (cue, path.to_string()))
+                .collect(),
+        }
+    }
+
+    /// Parse a manifest from JSON.
+    pub fn from_json(json: &str) -> Result<Self, serde_json::Error> {
+        serde_json::from_str(json)
+    }
+}
+
+/// Resource mapping cues to sound assets.
+///
+/// Starts with the built-in pack. Manifests registered later take
+/// precedence for the cues they list.
+#[derive(Resource, Clone, Debug)]
+pub struct SoundLibrary {
+    packs: Vec<String>,
+    paths: BTreeMap<SoundCue, String>,
+    handles: HashMap<SoundCue, Handle<AudioSource>>,
+}
+
+impl SoundLibrary {
+    /// Create a library with only the built-in pack.
+    pub fn new() -> Self {
+        Self {
+            packs: Vec::new(),
+            paths: BTreeMap::new(),
+            handles: HashMap::new(),
+        }
+        .with_pack(SoundManifest::builtin())
+    }
+
+    /// Add a pack, builder style.
+    pub fn with_pack(mut self, manifest: SoundManifest) -> Self {
+        self.register(manifest);
+        self
+    }
+
+    /// Register a sound pack.
+    pub fn register(&mut self, manifest: SoundManifest) {
+        for (cue, path) in manifest.sounds {
+            self.handles.remove(&cue);
+            self.paths.insert(cue, path);
+        }
+        self.packs.push(manifest.name);
+    }
+
+    /// Names of the registered packs, in registration order.
+    pub fn packs(&self) -> &[String] {
+        &self.packs
+    }
+
+    /// Asset path for a cue.
+    pub fn path(&self, cue: SoundCue) -> Option<&str> {
+        self.paths.get(&cue).map(String::as_str)
+    }
+
+    /// Handle for a cue, loading it on first use.
+    pub fn handle(
+        &mut self,
+        cue: SoundCue,
+        asset_server: &AssetServer,
+    ) -> Option<Handle<AudioSource>> {
+        if let Some(handle) = self.handles.get(&cue) {
+            return Some(handle.clone());
+        }
+        let handle: Handle<AudioSource> = asset_server.load(self.path(cue)?.to_string());
+        self.handles.insert(cue, handle.clone());
+        Some(handle)
+    }
+}
+
+impl Default for SoundLibrary {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// Event fired for each cue to play.
+#[derive(Event, Clone, Copy, Debug, PartialEq, Eq)]
+pub struct SoundCueEvent(pub SoundCue);
+
+/// Plugin for sound cues.
+///
+/// Add it next to [`NostrNationsPlugin`](crate::plugins::NostrNationsPlugin).
+/// Its name clashes with Bevy's `AudioPlugin`, so import it by path rather
+/// than through both preludes.
+#[derive(Default)]
+pub struct AudioPlugin {
+    /// Extra sound packs to register, in order.
+    pub packs: Vec<SoundManifest>,
+}
+
+impl Plugin for AudioPlugin {
+    fn build(&self, app: &mut App) {
+        let library = self
+            .packs
+            .iter()
+            .cloned()
+            .fold(SoundLibrary::new(), SoundLibrary::with_pack);
+        app.insert_resource(library);
+        app.add_event::<SoundCueEvent>();
+
+        app.add_systems(
+            Update,
+            (sound_cue_system, play_sound_system)
+                .chain()
+                .after(GameSystemSet::Sync),
+        );
+    }
+}
+
+/// Map action effects to sound cues.
+///
+/// A cue plays at most once per frame, so a unit moving several tiles or an
+/// attack with several damage effects is heard once.
+pub fn sound_cue_system(
+    settings: Res<GameSettingsResource>,
+    mut effects: EventReader<ActionEffectEvent>,
+    mut cues: EventWriter<SoundCueEvent>,
+) {
+    let frame: BTreeSet<SoundCue> = effects
+        .read()
+        .filter_map(|event| {
+            SoundCue::for_effect(&event.effect, event.player_id, settings.local_player_id)
+        })
+        .collect();
+    cues.send_batch(frame.into_iter().map(SoundCueEvent));
+}
+
+/// Play queued sound cues at their configured volume.
+pub fn play_sound_system(
+    mut commands: Commands,
+    settings: Res<GameSettingsResource>,
+    mut library: ResMut<SoundLibrary>,
+    asset_server: Option<Res<AssetServer>>,
+    mut cues: EventReader<SoundCueEvent>,
+) {
+    let Some(asset_server) = asset_server else {
+        cues.clear();
+        return;
+    };
+    for SoundCueEvent(cue) in cues.read() {
+        let volume = settings.audio.volume(*cue);
+        if volume <= 0.0 {
+            continue;
+        }
+        let Some(source) = library.handle(*cue, &asset_server) else {
+            continue;
+        };
+        commands.spawn(AudioBundle {
+            source,
+            settings: PlaybackSettings::DESPAWN.with_volume(Volume::new(volume)),
+        });
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::plugins::create_test_app;
+    use nostr_nations_core::types::{CityId, UnitId};
+    use nostr_nations_core::HexCoord;
+
+    fn moved() -> ActionEffect {
+        ActionEffect::UnitMoved {
+            unit_id: UnitId(1),
+            from: HexCoord::new(0, 0),
+            to: HexCoord::new(1, 0),
+        }
+    }
+
+    #[test]
+    fn test_cue_for_effect() {
+        let local = PlayerSlot(0);
+        let other = PlayerSlot(1);
+        assert_eq!(
+            SoundCue::for_effect(&moved(), other, local),
+            Some(SoundCue::Move)
+        );
+        let damaged = ActionEffect::CityDamaged {
+            city_id: CityId(1),
+            damage: 5,
+        };
+        assert_eq!(
+            SoundCue::for_effect(&damaged, other, local),
+            Some(SoundCue::Combat)
+        );
+        let founded = ActionEffect::CityFounded {
+            city_id: CityId(1),
+            name: "Rome".to_string(),
+            position: HexCoord::new(0, 0),
+        };
+        assert_eq!(
+            SoundCue::for_effect(&founded, local, local),
+            Some(SoundCue::CityFounded)
+        );
+    }
+
+    #[test]
+    fn test_turn_and_notification_cues_only_for_local_player() {
+        let local = PlayerSlot(0);
+        let other = PlayerSlot(1);
+        let turn = |player_id| ActionEffect::TurnStarted { player_id, turn: 2 };
+        assert_eq!(
+            SoundCue::for_effect(&turn(local), other, local),
+            Some(SoundCue::YourTurn)
+        );
+        assert_eq!(SoundCue::for_effect(&turn(other), local, local), None);
+
+        let tech = |player_id| ActionEffect::TechResearched {
+            player_id,
+            tech_id: "pottery".to_string(),
+        };
+        assert_eq!(
+            SoundCue::for_effect(&tech(local), local, local),
+            Some(SoundCue::Notification)
+        );
+        assert_eq!(SoundCue::for_effect(&tech(other), other, local), None);
+
+        let promotion = ActionEffect::PromotionAvailable { unit_id: UnitId(1) };
+        assert_eq!(SoundCue::for_effect(&promotion, other, local), None);
+    }
+
+    #[test]
+    fn test_manifest_overrides_only_listed_cues() {
+        let manifest = SoundManifest::from_json(
+            r#"{"name": "Retro", "sounds": {"move": "packs/retro/step.ogg"}}"#,
+        )
+        .unwrap();
+        let library = SoundLibrary::new().with_pack(manifest);
+
+        assert_eq!(library.packs(), ["Default", "Retro"]);
+        assert_eq!(library.path(SoundCue::Move), Some("packs/retro/step.ogg"));
+        assert_eq!(
+            library.path(SoundCue::YourTurn),
+            Some("sounds/your_turn.ogg")
+        );
+        assert!(
+            SoundManifest::from_json(r#"{"name": "Bad", "sounds": {"boom": "x.ogg"}}"#).is_err()
+        );
+    }
+
+    #[test]
+    fn test_builtin_manifest_covers_every_cue() {
+        let library = SoundLibrary::new();
+        for cue in SoundCue::ALL {
+            assert!(library.path(cue).is_some(), "{:?} has no sound", cue);
+        }
+    }
+
+    #[test]
+    fn test_cues_sent_once_per_frame() {
+        let mut app = create_test_app();
+        app.add_plugins(AudioPlugin::default());
+
+        for _ in 0..3 {
+            app.world_mut().send_event(ActionEffectEvent {
+                player_id: PlayerSlot(1),
+                effect: moved(),
+            });
+        }
+        app.update();
+
+        let events = app.world().resource::<Events<SoundCueEvent>>();
+        let mut reader = events.get_reader();
+        let sent: Vec<SoundCueEvent> = reader.read(events).copied().collect();
+        assert_eq!(sent, vec![SoundCueEvent(SoundCue::Move)]);
+    }
+}
@@ -0,0 +1,449 @@
+//! Map screenshots and timelapses.
+//!
+//! [`MapCapture::render`] draws the map into an RGBA buffer on the CPU, so
+//! captures work without a window or GPU: terrain in flat colors,
+//! territory tinted (and hatched, with an accessible palette) in each
+//! player's color, cities as squares and units as dots. A capture can be
+//! saved as PNG or turned into a Bevy [`Image`] for display.
+//!
+//! A capture covers either the whole map or only what the player has
+//! explored, cropped to the explored region. Capturing once per turn and
+//! numbering the files by turn gives a timelapse image sequence.
+//!
+//! In a Bevy app, send a [`CaptureMapEvent`]; the [`MapCapturePlugin`]
+//! renders it into [`LastCapture`] and writes the PNG if a path was given.
+
+use bevy::prelude::*;
+use bevy::render::render_asset::RenderAssetUsages;
+use bevy::render::render_resource::{Extent3d, TextureDimension, TextureFormat};
+use nostr_nations_core::{GameState, HexCoord, PlayerSlot, Terrain};
+use serde::{Deserialize, Serialize};
+use std::fmt;
+use std::path::{Path, PathBuf};
+
+use crate::palette::PaletteSettings;
+use crate::resources::{GameSettingsResource, GameStateResource};
+use crate::strategic::{StrategicLayout, HEX_HALF_HEIGHT, HEX_HALF_WIDTH};
+use crate::systems::{hex_to_world, GameSystemSet};
+
+/// Background behind unexplored or off-map areas.
+const BACKGROUND: [u8; 4] = [16, 16, 20, 255];
+/// How strongly territory tints the terrain.
+const TERRITORY_TINT: f32 = 0.5;
+
+/// Part of the map a capture covers.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum CaptureRegion {
+    /// Every tile, unit and city, ignoring fog of war.
+    FullMap,
+    /// What the player has explored, cropped to it.
+    #[default]
+    Explored,
+}
+
+/// How to draw a capture.
+#[derive(Clone, Debug, PartialEq)]
+pub struct CaptureOptions {
+    /// Part of the map to capture.
+    pub region: CaptureRegion,
+    /// Player whose view is captured.
+    pub player_id: PlayerSlot,
+    /// Width of one hex in pixels.
+    pub hex_pixels: u32,
+    /// Player colors and patterns.
+    pub palette: PaletteSettings,
+}
+
+impl CaptureOptions {
+    /// Capture a region as seen by a player.
+    pub fn new(region: CaptureRegion, player_id: PlayerSlot) -> Self {
+        Self {
+            region,
+            player_id,
+            ..Self::default()
+        }
+    }
+}
+
+impl Default for CaptureOptions {
+    fn default() -> Self {
+        Self {
+            region: CaptureRegion::Explored,
+            player_id: PlayerSlot(0),
+            hex_pixels: 16,
+            palette: PaletteSettings::default(),
+        }
+    }
+}
+
+/// Errors from capturing the map.
+#[derive(Debug)]
+pub enum CaptureError {
+    /// Nothing to draw: the map is empty or unexplored.
+    EmptyMap,
+    /// The image couldn't be encoded.
+    Encode(String),
+    /// The file couldn't be written.
+    Io(std::io::Error),
+}
+
+impl fmt::Display for CaptureError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            CaptureError::EmptyMap => write!(f, "Nothing of the map to capture"),
+            CaptureError::Encode(e) => write!(f, "Failed to encode capture: {}", e),
+            CaptureError::Io(e) => write!(f, "Failed to write capture: {}", e),
+        }
+    }
+}
+
+impl std::error::Error for CaptureError {}
+
+impl From<std::io::Error> for CaptureError {
+    fn from(e: std::io::Error) -> Self {
+        CaptureError::Io(e)
+    }
+}
+
+/// Flat color of a terrain type.
+pub fn terrain_color(terrain: Terrain) -> Color {
+    match terrain {
+        Terrain::Grassland => Color::srgb_u8(96, 148, 64),
+        Terrain::Plains => Color::srgb_u8(168, 160, 88),
+        Terrain::Desert => Color::srgb_u8(222, 200, 140),
+        Terrain::Tundra => Color::srgb_u8(140, 140, 120),
+        Terrain::Snow => Color::srgb_u8(236, 240, 244),
+        Terrain::Coast => Color::srgb_u8(88, 140, 196),
+        Terrain::Ocean => Color::srgb_u8(40, 76, 140),
+    }
+}
+
+/// A rendered image of the map, as 8-bit sRGB RGBA pixels.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct MapCapture {
+    /// Width in pixels.
+    pub width: u32,
+    /// Height in pixels.
+    pub height: u32,
+    /// Pixels, row by row from the top left.
+    pub pixels: Vec<u8>,
+}
+
+impl MapCapture {
+    /// Draw the map.
+    pub fn render(state: &GameState, options: &CaptureOptions) -> Result<Self, CaptureError> {
+        let fog = options.region == CaptureRegion::Explored;
+        let layout = StrategicLayout::new(state, options.player_id, fog);
+        let tiles: Vec<HexCoord> = layout.land.iter().chain(&layout.water).copied().collect();
+        if tiles.is_empty() {
+            return Err(CaptureError::EmptyMap);
+        }
+
+        // World bounds of the drawn tiles, flipped so y grows downwards
+        let scale = options.hex_pixels.max(2) as f32 / (HEX_HALF_WIDTH * 2.0);
+        let centers: Vec<Vec2> = tiles
+            .iter()
+            .map(|c| hex_to_world(*c) * Vec2::new(1.0, -1.0))
+            .collect();
+        let half = Vec2::new(HEX_HALF_WIDTH, HEX_HALF_HEIGHT);
+        let min = centers.iter().fold(Vec2::MAX, |m, c| m.min(*c)) - half;
+        let max = centers.iter().fold(Vec2::MIN, |m, c| m.max(*c)) + half;
+        let size = ((max - min) * scale).ceil();
+
+        let mut capture = Self {
+            width: size.x as u32,
+            height: size.y as u32,
+            pixels: BACKGROUND.repeat((size.x * size.y) as usize),
+        };
+        let to_pixels =
+            |coord: HexCoord| (hex_to_world(coord) * Vec2::new(1.0, -1.0) - min) * scale;
+
+        let owners: std::collections::HashMap<HexCoord, PlayerSlot> = layout
+            .territory
+            .iter()
+            .flat_map(|(owner, coords)| coords.iter().map(move |c| (*c, *owner)))
+            .collect();
+        for coord in &tiles {
+            let terrain = state.map.get(coord).map_or(Terrain::Ocean, |t| t.terrain);
+            let base = terrain_color(terrain);
+            let owner = owners.get(coord).map(|o| options.palette.appearance(*o));
+            capture.fill_hex(to_pixels(*coord), half * scale, |x, y| match owner {
+                Some(appearance) => base.mix(&appearance.texel(x, y), TERRITORY_TINT),
+                None => base,
+            });
+        }
+
+        for (_, owner, position, _) in &layout.cities {
+            let color = options.palette.appearance(*owner).bevy_color();
+            let center = to_pixels(*position);
+            let outer = HEX_HALF_WIDTH * 0.45 * scale;
+            capture.fill_rect(center, outer + 1.0, Color::BLACK);
+            capture.fill_rect(center, outer, color);
+        }
+        for (_, owner, position) in &layout.units {
+            let color = options.palette.appearance(*owner).bevy_color();
+            let center = to_pixels(*position) + Vec2::new(0.0, HEX_HALF_HEIGHT * 0.5 * scale);
+            let radius = HEX_HALF_WIDTH * 0.2 * scale;
+            capture.fill_circle(center, radius + 1.0, Color::BLACK);
+            capture.fill_circle(center, radius, color);
+        }
+
+        Ok(capture)
+    }
+
+    /// Fill a flat-topped hex, coloring each pixel with `color(x, y)`.
+    fn fill_hex(&mut self, center: Vec2, half: Vec2, color: impl Fn(u32, u32) -> Color) {
+        self.fill_where(center, half, color, |d| {
+            d.y <= half.y && half.y * d.x + half.x * 0.5 * d.y <= half.x * half.y
+        });
+    }
+
+    /// Fill a square.
+    fn fill_rect(&mut self, center: Vec2, half: f32, color: Color) {
+        self.fill_where(center, Vec2::splat(half), |_, _| color, |_| true);
+    }
+
+    /// Fill a circle.
+    fn fill_circle(&mut self, center: Vec2, radius: f32, color: Color) {
+        self.fill_where(
+            center,
+            Vec2::splat(radius),
+            |_, _| color,
+            |d| d.length() <= radius,
+        );
+    }
+
+    /// Color the pixels around `center` whose absolute offset from it
+    /// passes `inside`.
+    fn fill_where(
+        &mut self,
+        center: Vec2,
+        half: Vec2,
+        color: impl Fn(u32, u32) -> Color,
+        inside: impl Fn(Vec2) -> bool,
+    ) {
+        let min = (center - half).floor().max(Vec2::ZERO);
+        let max = (center + half)
+            .ceil()
+            .min(Vec2::new(self.width as f32, self.height as f32));
+        for y in min.y as u32..max.y as u32 {
+            for x in min.x as u32..max.x as u32 {
+                let offset = (Vec2::new(x as f32, y as f32) + 0.5 - center).abs();
+                if inside(offset) {
+                    let i = ((y * self.width + x) * 4) as usize;
+                    self.pixels[i..i + 4].copy_from_slice(&color(x, y).to_srgba().to_u8_array());
+                }
+            }
+        }
+    }
+
+    /// Encode as PNG.
+    pub fn to_png(&self) -> Result<Vec<u8>, CaptureError> {
+        let mut bytes = Vec::new();
+        let mut encoder = png::Encoder::new(&mut bytes, self.width, self.height);
+        encoder.set_color(png::ColorType::Rgba);
+        encoder.set_depth(png::BitDepth::Eight);
+        encoder
+            .write_header()
+            .and_then(|mut writer| writer.write_image_data(&self.pixels))
+            .map_err(|e| CaptureError::Encode(e.to_string()))?;
+        Ok(bytes)
+    }
+
+    /// Save as a PNG file, creating its directory if needed.
+    pub fn save_png(&self, path: &Path) -> Result<(), CaptureError> {
+        if let Some(dir) = path.parent() {
+            std::fs::create_dir_all(dir)?;
+        }
+        std::fs::write(path, self.to_png()?)?;
+        Ok(())
+    }
+
+    /// The capture as a Bevy texture.
+    pub fn to_image(&self) -> Image {
+        Image::new(
+            Extent3d {
+                width: self.width,
+                height: self.height,
+                depth_or_array_layers: 1,
+            },
+            TextureDimension::D2,
+            self.pixels.clone(),
+            TextureFormat::Rgba8UnormSrgb,
+            RenderAssetUsages::default(),
+        )
+    }
+}
+
+/// File name of a timelapse frame, e.g. `turn_0042.png`.
+///
+/// Frames sort by turn when listed by name.
+pub fn timelapse_frame_name(turn: u32) -> String {
+    format!("turn_{:04}.png", turn)
+}
+
+/// Event requesting a capture of the local player's map.
+#[derive(Event, Clone, Debug)]
+pub struct CaptureMapEvent {
+    /// Part of the map to capture.
+    pub region: CaptureRegion,
+    /// PNG file to write, if any.
+    pub path: Option<PathBuf>,
+}
+
+/// Resource with the most recent capture.
+#[derive(Resource, Clone, Debug, Default)]
+pub struct LastCapture {
+    /// The rendered map.
+    pub capture: Option<MapCapture>,
+    /// The capture as a texture, if the app has image assets.
+    pub image: Option<Handle<Image>>,
+}
+
+/// Plugin for map captures.
+pub struct MapCapturePlugin;
+
+impl Plugin for MapCapturePlugin {
+    fn build(&self, app: &mut App) {
+        app.init_resource::<LastCapture>();
+        app.add_event::<CaptureMapEvent>();
+        app.add_systems(Update, capture_map_system.after(GameSystemSet::Sync));
+    }
+}
+
+/// Render requested captures.
+///
+/// Failures are logged; the previous capture is kept.
+pub fn capture_map_system(
+    mut events: EventReader<CaptureMapEvent>,
+    game_state: Res<GameStateResource>,
+    settings: Res<GameSettingsResource>,
+    mut last: ResMut<LastCapture>,
+    images: Option<ResMut<Assets<Image>>>,
+) {
+    let Some(event) = events.read().last() else {
+        return;
+    };
+    let options = CaptureOptions {
+        palette: settings.palette.clone(),
+        ..CaptureOptions::new(event.region, settings.local_player_id)
+    };
+    let capture = match MapCapture::render(game_state.state(), &options) {
+        Ok(capture) => capture,
+        Err(e) => {
+            warn!("Map capture failed: {}", e);
+            return;
+        }
+    };
+    if let Some(path) = &event.path {
+        match capture.save_png(path) {
+            Ok(()) => info!("Saved map capture to {}", path.display()),
+            Err(e) => warn!("Map capture failed: {}", e),
+        }
+    }
+    last.image = images.map(|mut images| images.add(capture.to_image()));
+    last.capture = Some(capture);
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use nostr_nations_core::map::{Map, Tile};
+    use nostr_nations_core::player::{Civilization, Player};
+    use nostr_nations_core::types::{GameId, Npub};
+    use nostr_nations_core::{City, CityId, GameSettings, Unit, UnitId, UnitType};
+
+    fn create_state() -> GameState {
+        let mut state = GameState::new(
+            GameId::new("capture"),
+            GameSettings::new("Capture".to_string()),
+            [5; 32],
+        );
+        state.players.push(Player::new(
+            PlayerSlot(0),
+            Npub::new("npub0"),
+            "Alice".to_string(),
+            Civilization::generic(),
+        ));
+        state.map = Map::filled(12, 8, Terrain::Grassland);
+        state
+            .map
+            .set(Tile::new(HexCoord::new(11, 7), Terrain::Ocean));
+        state
+    }
+
+    fn pixel(capture: &MapCapture, x: u32, y: u32) -> [u8; 4] {
+        let i = ((y * capture.width + x) * 4) as usize;
+        capture.pixels[i..i + 4].try_into().unwrap()
+    }
+
+    #[test]
+    fn test_full_map_capture_covers_every_tile() {
+        let mut state = create_state();
+        state.cities.insert(
+            CityId(1),
+            City::new(
+                CityId(1),
+                PlayerSlot(0),
+                "Rome".to_string(),
+                HexCoord::new(0, 0),
+                true,
+            ),
+        );
+        let options = CaptureOptions::new(CaptureRegion::FullMap, PlayerSlot(0));
+        let capture = MapCapture::render(&state, &options).unwrap();
+
+        // 12 columns of 16px hexes overlap by a quarter; 8.5 rows of 18.5px
+        assert_eq!((capture.width, capture.height), (148, 158));
+        assert_eq!(capture.pixels.len(), (148 * 158 * 4) as usize);
+        // The city sits on the top left hex
+        let red = PaletteSettings::default()
+            .appearance(PlayerSlot(0))
+            .bevy_color();
+        assert_eq!(pixel(&capture, 8, 9), red.to_srgba().to_u8_array());
+        assert_eq!(pixel(&capture, 0, 0), BACKGROUND);
+    }
+
+    #[test]
+    fn test_explored_capture_is_cropped() {
+        let mut state = create_state();
+        let unit = Unit::new(
+            UnitId(1),
+            PlayerSlot(0),
+            UnitType::Warrior,
+            HexCoord::new(5, 4),
+        );
+        state.units.insert(UnitId(1), unit);
+
+        let full = MapCapture::render(
+            &state,
+            &CaptureOptions::new(CaptureRegion::FullMap, PlayerSlot(0)),
+        )
+        .unwrap();
+        let explored = MapCapture::render(&state, &CaptureOptions::default()).unwrap();
+        assert!(explored.width < full.width && explored.height < full.height);
+
+        state.units.clear();
+        assert!(matches!(
+            MapCapture::render(&state, &CaptureOptions::default()),
+            Err(CaptureError::EmptyMap)
+        ));
+    }
+
+    #[test]
+    fn test_png_round_trip() {
+        let state = create_state();
+        let options = CaptureOptions::new(CaptureRegion::FullMap, PlayerSlot(0));
+        let capture = MapCapture::render(&state, &options).unwrap();
+        let bytes = capture.to_png().unwrap();
+
+        let decoder = png::Decoder::new(bytes.as_slice());
+        let mut reader = decoder.read_info().unwrap();
+        let mut pixels = vec![0; reader.output_buffer_size()];
+        let info = reader.next_frame(&mut pixels).unwrap();
+        assert_eq!((info.width, info.height), (capture.width, capture.height));
+        assert_eq!(pixels, capture.pixels);
+        assert_eq!(timelapse_frame_name(7), "turn_0007.png");
+    }
+}
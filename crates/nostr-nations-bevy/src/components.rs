@@ -5,7 +5,7 @@
 
 use bevy::prelude::*;
 use nostr_nations_core::{
-    types::{CityId, PlayerId, UnitId},
+    types::{CityId, PlayerSlot, UnitId},
     City, HexCoord, Player, Tile, Unit,
 };
 
@@ -57,7 +57,7 @@ impl UnitComponent {
     }
 
     /// Get the owner player ID.
-    pub fn owner(&self) -> PlayerId {
+    pub fn owner(&self) -> PlayerSlot {
         self.unit.owner
     }
 
@@ -75,6 +75,11 @@ impl UnitComponent {
     pub fn can_attack(&self) -> bool {
         self.unit.can_attack()
     }
+
+    /// Check if the unit has leveled up and is waiting on a promotion choice.
+    pub fn needs_promotion(&self) -> bool {
+        self.unit.can_promote() && !self.unit.available_promotions().is_empty()
+    }
 }
 
 impl From<Unit> for UnitComponent {
@@ -104,7 +109,7 @@ impl CityComponent {
     }
 
     /// Get the owner player ID.
-    pub fn owner(&self) -> PlayerId {
+    pub fn owner(&self) -> PlayerSlot {
         self.city.owner
     }
 
@@ -151,7 +156,7 @@ impl PlayerComponent {
     }
 
     /// Get the player's unique identifier.
-    pub fn id(&self) -> PlayerId {
+    pub fn id(&self) -> PlayerSlot {
         self.player.id
     }
 
@@ -304,16 +309,20 @@ pub struct LocalPlayerOwned;
 #[derive(Component, Clone, Copy, Debug, Default)]
 pub struct OtherPlayerOwned {
     /// The owner's player ID.
-    pub owner: PlayerId,
+    pub owner: PlayerSlot,
 }
 
 impl OtherPlayerOwned {
     /// Create a new component with the specified owner.
-    pub fn new(owner: PlayerId) -> Self {
+    pub fn new(owner: PlayerSlot) -> Self {
         Self { owner }
     }
 }
 
+/// Marker component for the network debug overlay text node.
+#[derive(Component, Clone, Copy, Debug, Default)]
+pub struct NetworkDebugOverlayText;
+
 /// Component tracking movement animation state for units.
 #[derive(Component, Clone, Debug, Default)]
 pub struct MovementAnimation {
@@ -435,6 +444,7 @@ impl CityBundle {
 #[cfg(test)]
 mod tests {
     use super::*;
+    use nostr_nations_core::types::Npub;
     use nostr_nations_core::{player::Civilization, unit::UnitType, Terrain};
 
     // ============================================
@@ -476,45 +486,88 @@ mod tests {
 
     #[test]
     fn test_unit_component_creation() {
-        let unit = Unit::new(1, 0, UnitType::Warrior, HexCoord::new(0, 0));
+        let unit = Unit::new(
+            UnitId(1),
+            PlayerSlot(0),
+            UnitType::Warrior,
+            HexCoord::new(0, 0),
+        );
         let component = UnitComponent::new(unit);
-        assert_eq!(component.id(), 1);
-        assert_eq!(component.owner(), 0);
+        assert_eq!(component.id(), UnitId(1));
+        assert_eq!(component.owner(), PlayerSlot(0));
         assert_eq!(component.position(), HexCoord::new(0, 0));
     }
 
     #[test]
     fn test_unit_component_can_move() {
-        let unit = Unit::new(1, 0, UnitType::Warrior, HexCoord::new(0, 0));
+        let unit = Unit::new(
+            UnitId(1),
+            PlayerSlot(0),
+            UnitType::Warrior,
+            HexCoord::new(0, 0),
+        );
         let component = UnitComponent::new(unit);
         assert!(component.can_move());
     }
 
     #[test]
     fn test_unit_component_can_attack() {
-        let unit = Unit::new(1, 0, UnitType::Warrior, HexCoord::new(0, 0));
+        let unit = Unit::new(
+            UnitId(1),
+            PlayerSlot(0),
+            UnitType::Warrior,
+            HexCoord::new(0, 0),
+        );
         let component = UnitComponent::new(unit);
         assert!(component.can_attack());
     }
 
+    #[test]
+    fn test_unit_component_needs_promotion() {
+        let mut unit = Unit::new(
+            UnitId(1),
+            PlayerSlot(0),
+            UnitType::Warrior,
+            HexCoord::new(0, 0),
+        );
+        assert!(!UnitComponent::new(unit.clone()).needs_promotion());
+        unit.gain_experience(10);
+        assert!(UnitComponent::new(unit).needs_promotion());
+    }
+
     #[test]
     fn test_unit_component_civilian_cannot_attack() {
-        let unit = Unit::new(1, 0, UnitType::Settler, HexCoord::new(0, 0));
+        let unit = Unit::new(
+            UnitId(1),
+            PlayerSlot(0),
+            UnitType::Settler,
+            HexCoord::new(0, 0),
+        );
         let component = UnitComponent::new(unit);
         assert!(!component.can_attack());
     }
 
     #[test]
     fn test_unit_component_from_unit() {
-        let unit = Unit::new(42, 1, UnitType::Archer, HexCoord::new(3, 4));
+        let unit = Unit::new(
+            UnitId(42),
+            PlayerSlot(1),
+            UnitType::Archer,
+            HexCoord::new(3, 4),
+        );
         let component: UnitComponent = unit.into();
-        assert_eq!(component.id(), 42);
-        assert_eq!(component.owner(), 1);
+        assert_eq!(component.id(), UnitId(42));
+        assert_eq!(component.owner(), PlayerSlot(1));
     }
 
     #[test]
     fn test_unit_component_clone() {
-        let unit = Unit::new(1, 0, UnitType::Warrior, HexCoord::new(0, 0));
+        let unit = Unit::new(
+            UnitId(1),
+            PlayerSlot(0),
+            UnitType::Warrior,
+            HexCoord::new(0, 0),
+        );
         let component = UnitComponent::new(unit);
         let cloned = component.clone();
         assert_eq!(component.id(), cloned.id());
@@ -527,9 +580,15 @@ mod tests {
 
     #[test]
     fn test_city_component_creation() {
-        let city = City::new(1, 0, "Rome".to_string(), HexCoord::new(5, 5), true);
+        let city = City::new(
+            CityId(1),
+            PlayerSlot(0),
+            "Rome".to_string(),
+            HexCoord::new(5, 5),
+            true,
+        );
         let component = CityComponent::new(city);
-        assert_eq!(component.id(), 1);
+        assert_eq!(component.id(), CityId(1));
         assert_eq!(component.name(), "Rome");
         assert!(component.is_capital());
         assert_eq!(component.position(), HexCoord::new(5, 5));
@@ -537,29 +596,53 @@ mod tests {
 
     #[test]
     fn test_city_component_owner() {
-        let city = City::new(1, 2, "Athens".to_string(), HexCoord::new(3, 3), false);
+        let city = City::new(
+            CityId(1),
+            PlayerSlot(2),
+            "Athens".to_string(),
+            HexCoord::new(3, 3),
+            false,
+        );
         let component = CityComponent::new(city);
-        assert_eq!(component.owner(), 2);
+        assert_eq!(component.owner(), PlayerSlot(2));
     }
 
     #[test]
     fn test_city_component_population() {
-        let city = City::new(1, 0, "Paris".to_string(), HexCoord::new(0, 0), false);
+        let city = City::new(
+            CityId(1),
+            PlayerSlot(0),
+            "Paris".to_string(),
+            HexCoord::new(0, 0),
+            false,
+        );
         let component = CityComponent::new(city);
         assert_eq!(component.population(), 1); // Default population
     }
 
     #[test]
     fn test_city_component_from_city() {
-        let city = City::new(5, 1, "London".to_string(), HexCoord::new(7, 8), true);
+        let city = City::new(
+            CityId(5),
+            PlayerSlot(1),
+            "London".to_string(),
+            HexCoord::new(7, 8),
+            true,
+        );
         let component: CityComponent = city.into();
-        assert_eq!(component.id(), 5);
+        assert_eq!(component.id(), CityId(5));
         assert_eq!(component.name(), "London");
     }
 
     #[test]
     fn test_city_component_clone() {
-        let city = City::new(1, 0, "Rome".to_string(), HexCoord::new(5, 5), true);
+        let city = City::new(
+            CityId(1),
+            PlayerSlot(0),
+            "Rome".to_string(),
+            HexCoord::new(5, 5),
+            true,
+        );
         let component = CityComponent::new(city);
         let cloned = component.clone();
         assert_eq!(component.id(), cloned.id());
@@ -573,13 +656,13 @@ mod tests {
     #[test]
     fn test_player_component_creation() {
         let player = Player::new(
-            0,
-            "npub123".to_string(),
+            PlayerSlot(0),
+            Npub::new("npub123"),
             "TestPlayer".to_string(),
             Civilization::rome(),
         );
         let component = PlayerComponent::new(player);
-        assert_eq!(component.id(), 0);
+        assert_eq!(component.id(), PlayerSlot(0));
         assert_eq!(component.name(), "TestPlayer");
         assert!(!component.is_eliminated());
     }
@@ -587,8 +670,8 @@ mod tests {
     #[test]
     fn test_player_component_gold() {
         let mut player = Player::new(
-            0,
-            "npub123".to_string(),
+            PlayerSlot(0),
+            Npub::new("npub123"),
             "TestPlayer".to_string(),
             Civilization::rome(),
         );
@@ -600,8 +683,8 @@ mod tests {
     #[test]
     fn test_player_component_has_explored() {
         let mut player = Player::new(
-            0,
-            "npub123".to_string(),
+            PlayerSlot(0),
+            Npub::new("npub123"),
             "TestPlayer".to_string(),
             Civilization::rome(),
         );
@@ -615,21 +698,21 @@ mod tests {
     #[test]
     fn test_player_component_from_player() {
         let player = Player::new(
-            1,
-            "npub456".to_string(),
+            PlayerSlot(1),
+            Npub::new("npub456"),
             "Player2".to_string(),
             Civilization::egypt(),
         );
         let component: PlayerComponent = player.into();
-        assert_eq!(component.id(), 1);
+        assert_eq!(component.id(), PlayerSlot(1));
         assert_eq!(component.name(), "Player2");
     }
 
     #[test]
     fn test_player_component_clone() {
         let player = Player::new(
-            0,
-            "npub123".to_string(),
+            PlayerSlot(0),
+            Npub::new("npub123"),
             "TestPlayer".to_string(),
             Civilization::rome(),
         );
@@ -785,14 +868,14 @@ mod tests {
 
     #[test]
     fn test_other_player_owned_new() {
-        let owned = OtherPlayerOwned::new(2);
-        assert_eq!(owned.owner, 2);
+        let owned = OtherPlayerOwned::new(PlayerSlot(2));
+        assert_eq!(owned.owner, PlayerSlot(2));
     }
 
     #[test]
     fn test_other_player_owned_default() {
         let owned = OtherPlayerOwned::default();
-        assert_eq!(owned.owner, 0);
+        assert_eq!(owned.owner, PlayerSlot(0));
     }
 
     // ============================================
@@ -960,15 +1043,25 @@ mod tests {
 
     #[test]
     fn test_unit_bundle_new() {
-        let unit = Unit::new(1, 0, UnitType::Warrior, HexCoord::new(5, 5));
+        let unit = Unit::new(
+            UnitId(1),
+            PlayerSlot(0),
+            UnitType::Warrior,
+            HexCoord::new(5, 5),
+        );
         let bundle = UnitBundle::new(unit);
         assert_eq!(bundle.position.coord, HexCoord::new(5, 5));
-        assert_eq!(bundle.unit.id(), 1);
+        assert_eq!(bundle.unit.id(), UnitId(1));
     }
 
     #[test]
     fn test_unit_bundle_clone() {
-        let unit = Unit::new(1, 0, UnitType::Archer, HexCoord::new(3, 4));
+        let unit = Unit::new(
+            UnitId(1),
+            PlayerSlot(0),
+            UnitType::Archer,
+            HexCoord::new(3, 4),
+        );
         let bundle = UnitBundle::new(unit);
         let cloned = bundle.clone();
         assert_eq!(bundle.unit.id(), cloned.unit.id());
@@ -977,14 +1070,24 @@ mod tests {
 
     #[test]
     fn test_unit_bundle_different_unit_types() {
-        let warrior = Unit::new(1, 0, UnitType::Warrior, HexCoord::new(0, 0));
-        let settler = Unit::new(2, 0, UnitType::Settler, HexCoord::new(1, 1));
+        let warrior = Unit::new(
+            UnitId(1),
+            PlayerSlot(0),
+            UnitType::Warrior,
+            HexCoord::new(0, 0),
+        );
+        let settler = Unit::new(
+            UnitId(2),
+            PlayerSlot(0),
+            UnitType::Settler,
+            HexCoord::new(1, 1),
+        );
 
         let warrior_bundle = UnitBundle::new(warrior);
         let settler_bundle = UnitBundle::new(settler);
 
-        assert_eq!(warrior_bundle.unit.id(), 1);
-        assert_eq!(settler_bundle.unit.id(), 2);
+        assert_eq!(warrior_bundle.unit.id(), UnitId(1));
+        assert_eq!(settler_bundle.unit.id(), UnitId(2));
     }
 
     // ============================================
@@ -993,16 +1096,28 @@ mod tests {
 
     #[test]
     fn test_city_bundle_new() {
-        let city = City::new(1, 0, "TestCity".to_string(), HexCoord::new(10, 10), true);
+        let city = City::new(
+            CityId(1),
+            PlayerSlot(0),
+            "TestCity".to_string(),
+            HexCoord::new(10, 10),
+            true,
+        );
         let bundle = CityBundle::new(city);
         assert_eq!(bundle.position.coord, HexCoord::new(10, 10));
-        assert_eq!(bundle.city.id(), 1);
+        assert_eq!(bundle.city.id(), CityId(1));
         assert!(bundle.city.is_capital());
     }
 
     #[test]
     fn test_city_bundle_clone() {
-        let city = City::new(5, 1, "Cloned".to_string(), HexCoord::new(7, 8), false);
+        let city = City::new(
+            CityId(5),
+            PlayerSlot(1),
+            "Cloned".to_string(),
+            HexCoord::new(7, 8),
+            false,
+        );
         let bundle = CityBundle::new(city);
         let cloned = bundle.clone();
         assert_eq!(bundle.city.id(), cloned.city.id());
@@ -1011,7 +1126,13 @@ mod tests {
 
     #[test]
     fn test_city_bundle_non_capital() {
-        let city = City::new(2, 0, "Secondary".to_string(), HexCoord::new(15, 15), false);
+        let city = City::new(
+            CityId(2),
+            PlayerSlot(0),
+            "Secondary".to_string(),
+            HexCoord::new(15, 15),
+            false,
+        );
         let bundle = CityBundle::new(city);
         assert!(!bundle.city.is_capital());
     }
@@ -1036,7 +1157,12 @@ mod tests {
     #[test]
     fn test_spawn_unit_bundle_in_world() {
         let mut world = World::new();
-        let unit = Unit::new(1, 0, UnitType::Warrior, HexCoord::new(5, 5));
+        let unit = Unit::new(
+            UnitId(1),
+            PlayerSlot(0),
+            UnitType::Warrior,
+            HexCoord::new(5, 5),
+        );
         let bundle = UnitBundle::new(unit);
 
         let entity = world.spawn(bundle).id();
@@ -1048,7 +1174,13 @@ mod tests {
     #[test]
     fn test_spawn_city_bundle_in_world() {
         let mut world = World::new();
-        let city = City::new(1, 0, "Test".to_string(), HexCoord::new(3, 3), true);
+        let city = City::new(
+            CityId(1),
+            PlayerSlot(0),
+            "Test".to_string(),
+            HexCoord::new(3, 3),
+            true,
+        );
         let bundle = CityBundle::new(city);
 
         let entity = world.spawn(bundle).id();
@@ -1063,7 +1195,12 @@ mod tests {
 
         // Spawn multiple units
         for i in 0..3 {
-            let unit = Unit::new(i, 0, UnitType::Warrior, HexCoord::new(i as i32, 0));
+            let unit = Unit::new(
+                UnitId(i),
+                PlayerSlot(0),
+                UnitType::Warrior,
+                HexCoord::new(i as i32, 0),
+            );
             world.spawn(UnitBundle::new(unit));
         }
 
@@ -1076,7 +1213,12 @@ mod tests {
     #[test]
     fn test_modify_component_in_world() {
         let mut world = World::new();
-        let unit = Unit::new(1, 0, UnitType::Warrior, HexCoord::new(0, 0));
+        let unit = Unit::new(
+            UnitId(1),
+            PlayerSlot(0),
+            UnitType::Warrior,
+            HexCoord::new(0, 0),
+        );
         let entity = world.spawn(UnitBundle::new(unit)).id();
 
         // Modify position
@@ -0,0 +1,505 @@
+//! Input mapping for keyboard, mouse and gamepad.
+//!
+//! Game systems don't read devices directly. The [`input_mapping_system`]
+//! turns key, mouse and gamepad button state into [`InputAction`]s using
+//! the [`InputBindings`] in [`GameSettingsResource::input`], and stores the
+//! result in [`ActionState`]. Rebinding an action changes the settings
+//! only; the bindings serialize to JSON so they can be saved with the
+//! player's other settings.
+//!
+//! Gamepads also drive a [`HexCursor`]. The D-pad and the left stick move
+//! it to the neighboring hex closest to the pushed direction, and the
+//! select button picks what is under it.
+
+use bevy::input::gamepad::{GamepadAxis, GamepadAxisType, GamepadButton, GamepadButtonType};
+use bevy::prelude::*;
+use nostr_nations_core::HexCoord;
+use serde::{Deserialize, Serialize};
+use std::collections::{BTreeMap, HashSet};
+
+use crate::resources::GameSettingsResource;
+use crate::systems::{hex_to_world, GameSystemSet, DEBUG_OVERLAY_KEY};
+
+/// A game command that can be bound to inputs.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash, PartialOrd, Ord, Serialize, Deserialize)]
+pub enum InputAction {
+    /// Select what is under the pointer or cursor.
+    Select,
+    /// Clear the selection.
+    Cancel,
+    /// End the local player's turn.
+    EndTurn,
+    /// Show or hide the network debug overlay.
+    ToggleDebugOverlay,
+    /// Jump to the next unit, city or choice that needs attention.
+    NextPendingItem,
+    /// Switch between the detailed and strategic map.
+    ToggleStrategicView,
+    /// Move the hex cursor up.
+    CursorUp,
+    /// Move the hex cursor down.
+    CursorDown,
+    /// Move the hex cursor left.
+    CursorLeft,
+    /// Move the hex cursor right.
+    CursorRight,
+}
+
+impl InputAction {
+    /// All actions.
+    pub const ALL: [InputAction; 10] = [
+        InputAction::Select,
+        InputAction::Cancel,
+        InputAction::EndTurn,
+        InputAction::ToggleDebugOverlay,
+        InputAction::NextPendingItem,
+        InputAction::ToggleStrategicView,
+        InputAction::CursorUp,
+        InputAction::CursorDown,
+        InputAction::CursorLeft,
+        InputAction::CursorRight,
+    ];
+
+    /// Screen direction the action moves the hex cursor in, if it is a
+    /// cursor action.
+    pub fn cursor_direction(&self) -> Option<Vec2> {
+        match self {
+            InputAction::CursorUp => Some(Vec2::Y),
+            InputAction::CursorDown => Some(Vec2::NEG_Y),
+            InputAction::CursorLeft => Some(Vec2::NEG_X),
+            InputAction::CursorRight => Some(Vec2::X),
+            _ => None,
+        }
+    }
+}
+
+/// A physical input an action can be bound to.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub enum InputBinding {
+    /// A keyboard key.
+    Key(KeyCode),
+    /// A mouse button.
+    Mouse(MouseButton),
+    /// A button on any connected gamepad.
+    Gamepad(GamepadButtonType),
+}
+
+/// Inputs bound to each action.
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct InputBindings {
+    bindings: BTreeMap<InputAction, Vec<InputBinding>>,
+}
+
+impl InputBindings {
+    /// Bindings with no inputs at all.
+    pub fn empty() -> Self {
+        Self {
+            bindings: BTreeMap::new(),
+        }
+    }
+
+    /// Inputs bound to an action.
+    pub fn get(&self, action: InputAction) -> &[InputBinding] {
+        self.bindings.get(&action).map_or(&[], Vec::as_slice)
+    }
+
+    /// Action an input is bound to, if any.
+    pub fn action_for(&self, binding: InputBinding) -> Option<InputAction> {
+        self.bindings
+            .iter()
+            .find(|(_, bound)| bound.contains(&binding))
+            .map(|(action, _)| *action)
+    }
+
+    /// Add an input to an action.
+    ///
+    /// An input triggers one action only, so it is removed from any other
+    /// action first. Returns that action.
+    pub fn bind(&mut self, action: InputAction, binding: InputBinding) -> Option<InputAction> {
+        let previous = self.action_for(binding).filter(|a| *a != action);
+        if let Some(previous) = previous {
+            self.unbind(previous, binding);
+        }
+        let bound = self.bindings.entry(action).or_default();
+        if !bound.contains(&binding) {
+            bound.push(binding);
+        }
+        previous
+    }
+
+    /// Replace the inputs of an action of the same device type.
+    ///
+    /// Rebinding a key keeps the action's gamepad buttons and vice versa.
+    /// Returns the action the input was taken from, if any.
+    pub fn rebind(&mut self, action: InputAction, binding: InputBinding) -> Option<InputAction> {
+        if let Some(bound) = self.bindings.get_mut(&action) {
+            bound.retain(|b| std::mem::discriminant(b) != std::mem::discriminant(&binding));
+        }
+        self.bind(action, binding)
+    }
+
+    /// Remove an input from an action.
+    pub fn unbind(&mut self, action: InputAction, binding: InputBinding) {
+        if let Some(bound) = self.bindings.get_mut(&action) {
+            bound.retain(|b| *b != binding);
+        }
+    }
+
+    /// Restore one action's default inputs.
+    pub fn reset(&mut self, action: InputAction) {
+        for binding in Self::default().get(action) {
+            self.bind(action, *binding);
+        }
+    }
+
+    /// Serialize to JSON for saving with the settings.
+    pub fn to_json(&self) -> Result<String, serde_json::Error> {
+        serde_json::to_string(self)
+    }
+
+    /// Load bindings saved with [`to_json`](Self::to_json).
+    pub fn from_json(json: &str) -> Result<Self, serde_json::Error> {
+        serde_json::from_str(json)
+    }
+}
+
+impl Default for InputBindings {
+    fn default() -> Self {
+        use InputBinding::{Gamepad, Key, Mouse};
+
+        let defaults = [
+            (
+                InputAction::Select,
+                vec![Mouse(MouseButton::Left), Gamepad(GamepadButtonType::South)],
+            ),
+            (
+                InputAction::Cancel,
+                vec![
+                    Key(KeyCode::Escape),
+                    Mouse(MouseButton::Right),
+                    Gamepad(GamepadButtonType::East),
+                ],
+            ),
+            (
+                InputAction::EndTurn,
+                vec![
+                    Key(KeyCode::Enter),
+                    Key(KeyCode::KeyE),
+                    Gamepad(GamepadButtonType::Start),
+                ],
+            ),
+            (
+                InputAction::ToggleDebugOverlay,
+                vec![Key(DEBUG_OVERLAY_KEY), Gamepad(GamepadButtonType::Select)],
+            ),
+            (
+                InputAction::NextPendingItem,
+                vec![
+                    Key(KeyCode::Period),
+                    Gamepad(GamepadButtonType::RightTrigger),
+                ],
+            ),
+            (
+                InputAction::ToggleStrategicView,
+                vec![Key(KeyCode::KeyM), Gamepad(GamepadButtonType::North)],
+            ),
+            (
+                InputAction::CursorUp,
+                vec![Key(KeyCode::ArrowUp), Gamepad(GamepadButtonType::DPadUp)],
+            ),
+            (
+                InputAction::CursorDown,
+                vec![
+                    Key(KeyCode::ArrowDown),
+                    Gamepad(GamepadButtonType::DPadDown),
+                ],
+            ),
+            (
+                InputAction::CursorLeft,
+                vec![
+                    Key(KeyCode::ArrowLeft),
+                    Gamepad(GamepadButtonType::DPadLeft),
+                ],
+            ),
+            (
+                InputAction::CursorRight,
+                vec![
+                    Key(KeyCode::ArrowRight),
+                    Gamepad(GamepadButtonType::DPadRight),
+                ],
+            ),
+        ];
+        Self {
+            bindings: defaults.into_iter().collect(),
+        }
+    }
+}
+
+/// Resource with the actions triggered this frame.
+#[derive(Resource, Clone, Debug, Default)]
+pub struct ActionState {
+    pressed: HashSet<InputAction>,
+    just_pressed: HashSet<InputAction>,
+}
+
+impl ActionState {
+    /// Check if an action's input is held.
+    pub fn pressed(&self, action: InputAction) -> bool {
+        self.pressed.contains(&action)
+    }
+
+    /// Check if an action's input was pressed this frame.
+    pub fn just_pressed(&self, action: InputAction) -> bool {
+        self.just_pressed.contains(&action)
+    }
+
+    /// Trigger an action for this frame, as if its input was pressed.
+    pub fn press(&mut self, action: InputAction) {
+        if self.pressed.insert(action) {
+            self.just_pressed.insert(action);
+        }
+    }
+
+    /// Forget all actions.
+    pub fn clear(&mut self) {
+        self.pressed.clear();
+        self.just_pressed.clear();
+    }
+}
+
+/// Resource with the hex the gamepad cursor points at.
+#[derive(Resource, Clone, Debug, Default)]
+pub struct HexCursor {
+    /// Hex under the cursor; `None` until the cursor is first moved.
+    pub coord: Option<HexCoord>,
+    /// Whether the stick is pushed past the dead zone and has already moved
+    /// the cursor.
+    stick_engaged: bool,
+}
+
+impl HexCursor {
+    /// Stick deflection that moves the cursor.
+    pub const STICK_THRESHOLD: f32 = 0.6;
+    /// Stick deflection below which the stick counts as released.
+    pub const STICK_RELEASE: f32 = 0.3;
+
+    /// Move the cursor one hex in a screen direction.
+    ///
+    /// Picks the neighbor whose on-screen offset is closest in angle to
+    /// `direction`. Starts at the origin if the cursor wasn't placed yet.
+    pub fn step(&mut self, direction: Vec2) -> HexCoord {
+        let from = self.coord.unwrap_or(HexCoord::new(0, 0));
+        let origin = hex_to_world(from);
+        let direction = direction.normalize_or_zero();
+        let to = from
+            .neighbors()
+            .into_iter()
+            .max_by(|a, b| {
+                let a = (hex_to_world(*a) - origin).normalize().dot(direction);
+                let b = (hex_to_world(*b) - origin).normalize().dot(direction);
+                a.total_cmp(&b)
+            })
+            .unwrap_or(from);
+        self.coord = Some(to);
+        to
+    }
+
+    /// Move the cursor with the analog stick.
+    ///
+    /// Moves once per push: the stick has to return near the center before
+    /// it moves the cursor again.
+    pub fn stick(&mut self, stick: Vec2) -> Option<HexCoord> {
+        let length = stick.length();
+        if length < Self::STICK_RELEASE {
+            self.stick_engaged = false;
+            return None;
+        }
+        if length < Self::STICK_THRESHOLD || self.stick_engaged {
+            return None;
+        }
+        self.stick_engaged = true;
+        Some(self.step(stick))
+    }
+}
+
+/// Plugin for the input mapping layer.
+pub struct InputMappingPlugin;
+
+impl Plugin for InputMappingPlugin {
+    fn build(&self, app: &mut App) {
+        app.insert_resource(ActionState::default());
+        app.insert_resource(HexCursor::default());
+
+        app.add_systems(
+            Update,
+            (input_mapping_system, hex_cursor_system)
+                .chain()
+                .in_set(GameSystemSet::Input),
+        );
+    }
+}
+
+/// System that maps device input to actions.
+///
+/// Runs first in [`GameSystemSet::Input`]; systems reading [`ActionState`]
+/// in that set are ordered after it.
+pub fn input_mapping_system(
+    settings: Res<GameSettingsResource>,
+    keyboard: Option<Res<ButtonInput<KeyCode>>>,
+    mouse: Option<Res<ButtonInput<MouseButton>>>,
+    gamepads: Option<Res<Gamepads>>,
+    gamepad_buttons: Option<Res<ButtonInput<GamepadButton>>>,
+    mut actions: ResMut<ActionState>,
+) {
+    actions.clear();
+    for action in InputAction::ALL {
+        for binding in settings.input.get(action) {
+            let (pressed, just_pressed) = match binding {
+                InputBinding::Key(key) => keyboard
+                    .as_ref()
+                    .map_or((false, false), |k| (k.pressed(*key), k.just_pressed(*key))),
+                InputBinding::Mouse(button) => mouse.as_ref().map_or((false, false), |m| {
+                    (m.pressed(*button), m.just_pressed(*button))
+                }),
+                InputBinding::Gamepad(button_type) => match (&gamepads, &gamepad_buttons) {
+                    (Some(gamepads), Some(buttons)) => {
+                        gamepads.iter().fold((false, false), |(p, j), gamepad| {
+                            let button = GamepadButton::new(gamepad, *button_type);
+                            (
+                                p || buttons.pressed(button),
+                                j || buttons.just_pressed(button),
+                            )
+                        })
+                    }
+                    _ => (false, false),
+                },
+            };
+            if pressed {
+                actions.pressed.insert(action);
+            }
+            if just_pressed {
+                actions.just_pressed.insert(action);
+            }
+        }
+    }
+}
+
+/// System that moves the hex cursor from cursor actions and the left stick.
+pub fn hex_cursor_system(
+    actions: Res<ActionState>,
+    gamepads: Option<Res<Gamepads>>,
+    axes: Option<Res<Axis<GamepadAxis>>>,
+    mut cursor: ResMut<HexCursor>,
+) {
+    for action in InputAction::ALL {
+        if let Some(direction) = action.cursor_direction() {
+            if actions.just_pressed(action) {
+                cursor.step(direction);
+            }
+        }
+    }
+
+    let (Some(gamepads), Some(axes)) = (gamepads, axes) else {
+        return;
+    };
+    let stick = gamepads
+        .iter()
+        .map(|gamepad| {
+            let x = axes.get(GamepadAxis::new(gamepad, GamepadAxisType::LeftStickX));
+            let y = axes.get(GamepadAxis::new(gamepad, GamepadAxisType::LeftStickY));
+            Vec2::new(x.unwrap_or(0.0), y.unwrap_or(0.0))
+        })
+        .max_by(|a, b| a.length().total_cmp(&b.length()))
+        .unwrap_or(Vec2::ZERO);
+    cursor.stick(stick);
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_bind_moves_input_between_actions() {
+        let mut bindings = InputBindings::default();
+        let key = InputBinding::Key(KeyCode::KeyE);
+        assert_eq!(bindings.action_for(key), Some(InputAction::EndTurn));
+
+        let previous = bindings.bind(InputAction::Select, key);
+        assert_eq!(previous, Some(InputAction::EndTurn));
+        assert_eq!(bindings.action_for(key), Some(InputAction::Select));
+        assert!(!bindings.get(InputAction::EndTurn).contains(&key));
+    }
+
+    #[test]
+    fn test_rebind_keeps_other_devices() {
+        let mut bindings = InputBindings::default();
+        bindings.rebind(InputAction::EndTurn, InputBinding::Key(KeyCode::Space));
+
+        assert_eq!(
+            bindings.get(InputAction::EndTurn),
+            [
+                InputBinding::Gamepad(GamepadButtonType::Start),
+                InputBinding::Key(KeyCode::Space),
+            ]
+        );
+
+        bindings.reset(InputAction::EndTurn);
+        assert!(bindings
+            .get(InputAction::EndTurn)
+            .contains(&InputBinding::Key(KeyCode::Enter)));
+    }
+
+    #[test]
+    fn test_bindings_json_round_trip() {
+        let mut bindings = InputBindings::default();
+        bindings.rebind(InputAction::Cancel, InputBinding::Key(KeyCode::KeyQ));
+
+        let json = bindings.to_json().unwrap();
+        assert_eq!(InputBindings::from_json(&json).unwrap(), bindings);
+    }
+
+    #[test]
+    fn test_cursor_snaps_to_neighbors() {
+        let mut cursor = HexCursor::default();
+        let start = HexCoord::new(2, 2);
+        cursor.coord = Some(start);
+
+        assert_eq!(cursor.step(Vec2::Y), HexCoord::new(2, 1));
+        assert_eq!(cursor.step(Vec2::NEG_Y), start);
+        let right = cursor.step(Vec2::X);
+        assert!(start.neighbors().contains(&right));
+        assert!(right.q > start.q);
+    }
+
+    #[test]
+    fn test_stick_moves_once_per_push() {
+        let mut cursor = HexCursor::default();
+        assert!(cursor.stick(Vec2::new(0.0, 0.9)).is_some());
+        assert!(cursor.stick(Vec2::new(0.0, 1.0)).is_none());
+        assert!(cursor.stick(Vec2::new(0.0, 0.1)).is_none());
+        assert!(cursor.stick(Vec2::new(0.0, 0.9)).is_some());
+        assert!(cursor.stick(Vec2::new(0.4, 0.0)).is_none());
+    }
+
+    #[test]
+    fn test_keyboard_input_mapped_to_actions() {
+        let mut app = App::new();
+        app.add_plugins(MinimalPlugins);
+        app.init_resource::<ButtonInput<KeyCode>>();
+        app.insert_resource(GameSettingsResource::default());
+        app.add_plugins(InputMappingPlugin);
+
+        app.world_mut()
+            .resource_mut::<ButtonInput<KeyCode>>()
+            .press(KeyCode::ArrowUp);
+        app.update();
+
+        let actions = app.world().resource::<ActionState>();
+        assert!(actions.just_pressed(InputAction::CursorUp));
+        assert!(!actions.pressed(InputAction::EndTurn));
+        assert_eq!(
+            app.world().resource::<HexCursor>().coord,
+            Some(HexCoord::new(0, -1))
+        );
+    }
+}
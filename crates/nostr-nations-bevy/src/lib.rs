@@ -5,12 +5,18 @@
 //!
 //! # Architecture
 //!
-//! The crate is organized into four main modules:
+//! The crate is organized into ten main modules:
 //!
 //! - **[`components`]**: ECS components that wrap core game data types (units, cities, tiles)
 //! - **[`resources`]**: Singleton resources for game-wide state (game engine, selection, settings)
 //! - **[`systems`]**: Game loop systems (input, update, render, animation)
 //! - **[`plugins`]**: Bevy plugins for modular initialization
+//! - **[`audio`]**: Sound cues for game events, volumes, and sound packs
+//! - **[`theme`]**: Sprite themes loaded from RON manifests
+//! - **[`palette`]**: Colorblind-safe player colors and patterns
+//! - **[`input`]**: Rebindable keyboard, mouse, and gamepad input
+//! - **[`strategic`]**: Flat strategic map shown at far zoom
+//! - **[`capture`]**: Map screenshots and timelapse frames as PNG
 //!
 //! # Quick Start
 //!
@@ -70,21 +76,30 @@
 //! - [`GameStateResource`](resources::GameStateResource) - The core game engine
 //! - [`SelectedEntity`](resources::SelectedEntity) - Currently selected entity
 //! - [`CurrentTurn`](resources::CurrentTurn) - Turn state and timing
-//! - [`GameSettingsResource`](resources::GameSettingsResource) - Game configuration
+//! - [`GameSettingsResource`](resources::GameSettingsResource) - Game configuration and volumes
+//! - [`SoundLibrary`](audio::SoundLibrary) - Sound asset for each cue
+//! - [`ActiveTheme`](theme::ActiveTheme) - Sprite theme and its loaded images
+//! - [`NetworkDebugOverlay`](resources::NetworkDebugOverlay) - Network debug overlay (F9)
 //!
 //! # System Sets
 //!
 //! Systems are organized into sets for ordering:
 //!
-//! 1. `GameSystemSet::Input` - Selection and input handling
+//! 1. `GameSystemSet::Input` - Input mapping and selection
 //! 2. `GameSystemSet::Update` - Game logic and turn processing
 //! 3. `GameSystemSet::Sync` - ECS/core state synchronization
 //! 4. `GameSystemSet::Animation` - Visual animations
 
+pub mod audio;
+pub mod capture;
 pub mod components;
+pub mod input;
+pub mod palette;
 pub mod plugins;
 pub mod resources;
+pub mod strategic;
 pub mod systems;
+pub mod theme;
 
 // Re-export core types for convenience
 pub use nostr_nations_core;
@@ -99,24 +114,50 @@ pub mod prelude {
     // Components
     pub use crate::components::{
         CityBundle, CityComponent, CombatAnimation, LocalPlayerOwned, MovementAnimation,
-        OtherPlayerOwned, PlayerComponent, PositionComponent, SelectionComponent, TileBundle,
-        TileComponent, UnitBundle, UnitComponent, VisibleComponent,
+        NetworkDebugOverlayText, OtherPlayerOwned, PlayerComponent, PositionComponent,
+        SelectionComponent, TileBundle, TileComponent, UnitBundle, UnitComponent, VisibleComponent,
     };
 
     // Resources
     pub use crate::resources::{
-        CameraState, CityEntityMap, CurrentTurn, GameSettingsResource, GameStateResource,
-        PendingAction, PendingActionType, SelectedEntity, SelectionType, TileEntityMap, UiState,
+        AudioSettings, CameraState, CityEntityMap, CombatPreviewTooltip, CurrentTurn,
+        GameSettingsResource, GameStateResource, NetworkDebugOverlay, PendingAction,
+        PendingActionType, PendingItemCycle, SelectedEntity, SelectionType, TileEntityMap, UiState,
         UnitEntityMap,
     };
 
     // Systems
     pub use crate::systems::GameSystemSet;
 
+    // Audio (`AudioPlugin` is left out; Bevy's prelude has one too)
+    pub use crate::audio::{SoundCue, SoundCueEvent, SoundLibrary, SoundManifest};
+
+    // Map captures
+    pub use crate::capture::{
+        CaptureMapEvent, CaptureOptions, CaptureRegion, LastCapture, MapCapture, MapCapturePlugin,
+    };
+
+    // Input
+    pub use crate::input::{
+        ActionState, HexCursor, InputAction, InputBinding, InputBindings, InputMappingPlugin,
+    };
+
+    // Player colors
+    pub use crate::palette::{PaletteKind, PaletteSettings, Pattern, PlayerAppearance};
+
+    // Strategic map
+    pub use crate::strategic::{MapRenderMode, MapView, StrategicGlyph, StrategicMapPlugin};
+
+    // Themes
+    pub use crate::theme::{
+        ActiveTheme, LoadThemeEvent, ThemeKey, ThemeManifest, ThemePlugin, ThemeSource,
+        ThemedSprite,
+    };
+
     // Plugins
     pub use crate::plugins::{
-        AnimationPlugin, CameraPlugin, GameStateEvent, GameStatePlugin, NostrNationsPlugin,
-        SelectionEvent, SelectionPlugin, UiPlugin, VisibilityPlugin,
+        ActionEffectEvent, AnimationPlugin, CameraPlugin, GameStateEvent, GameStatePlugin,
+        NostrNationsPlugin, SelectionEvent, SelectionPlugin, UiPlugin, VisibilityPlugin,
     };
 
     // Re-export commonly used core types
@@ -201,19 +242,19 @@ pub fn create_app_with_settings(
 ///
 /// ```ignore
 /// use nostr_nations_bevy::create_networked_app;
-/// use nostr_nations_core::GameSettings;
+/// use nostr_nations_core::{GameSettings, PlayerSlot};
 ///
 /// fn main() {
 ///     let settings = GameSettings::new("Multiplayer Match".to_string());
 ///     let seed = [42u8; 32]; // Received from game host
-///     let local_player_id = 1; // Assigned by matchmaking
+///     let local_player_id = PlayerSlot(1); // Assigned by matchmaking
 ///     create_networked_app(settings, seed, local_player_id).run();
 /// }
 /// ```
 pub fn create_networked_app(
     settings: nostr_nations_core::GameSettings,
     seed: [u8; 32],
-    local_player_id: u8,
+    local_player_id: nostr_nations_core::PlayerSlot,
 ) -> bevy::app::App {
     use bevy::prelude::*;
 
@@ -236,6 +277,7 @@ pub fn create_networked_app(
 mod tests {
     use super::*;
     use bevy::prelude::*;
+    use nostr_nations_core::types::{CityId, PlayerSlot, UnitId};
     use nostr_nations_core::{unit::UnitType, City, HexCoord, Terrain, Tile, Unit};
 
     // ============================================
@@ -287,7 +329,7 @@ mod tests {
         let selected = SelectedEntity::none();
         assert!(!selected.has_selection());
 
-        let turn = CurrentTurn::new(1, 0);
+        let turn = CurrentTurn::new(1, PlayerSlot(0));
         assert_eq!(turn.turn, 1);
     }
 
@@ -339,7 +381,7 @@ mod tests {
     #[test]
     fn test_plugin_builds_with_networked_settings() {
         let settings = nostr_nations_core::GameSettings::new("Networked Game".to_string());
-        let plugin = plugins::NostrNationsPlugin::networked(settings, [1u8; 32], 2);
+        let plugin = plugins::NostrNationsPlugin::networked(settings, [1u8; 32], PlayerSlot(2));
 
         let mut app = App::new();
         app.add_plugins(MinimalPlugins);
@@ -347,7 +389,7 @@ mod tests {
 
         let game_settings = app.world().resource::<resources::GameSettingsResource>();
         assert!(game_settings.is_networked);
-        assert_eq!(game_settings.local_player_id, 2);
+        assert_eq!(game_settings.local_player_id, PlayerSlot(2));
     }
 
     // ============================================
@@ -398,7 +440,7 @@ mod tests {
         let local_player_id = 1;
 
         // This will create the app (may need windowed environment)
-        let _app = create_networked_app(settings, seed, local_player_id);
+        let _app = create_networked_app(settings, seed, PlayerSlot(local_player_id));
     }
 
     // ============================================
@@ -434,7 +476,7 @@ mod tests {
 
         let turn = app.world().resource::<resources::CurrentTurn>();
         assert_eq!(turn.turn, 0);
-        assert_eq!(turn.current_player, 0);
+        assert_eq!(turn.current_player, PlayerSlot(0));
     }
 
     #[test]
@@ -493,7 +535,12 @@ mod tests {
         app.add_plugins(plugins::NostrNationsPlugin::default());
 
         // Spawn a unit
-        let unit = Unit::new(1, 0, UnitType::Warrior, HexCoord::new(5, 5));
+        let unit = Unit::new(
+            UnitId(1),
+            PlayerSlot(0),
+            UnitType::Warrior,
+            HexCoord::new(5, 5),
+        );
         let entity = app
             .world_mut()
             .spawn(components::UnitBundle::new(unit))
@@ -512,11 +559,11 @@ mod tests {
         // Track in entity map
         app.world_mut()
             .resource_mut::<resources::UnitEntityMap>()
-            .insert(1, entity);
+            .insert(UnitId(1), entity);
 
         // Verify can retrieve
         let unit_map = app.world().resource::<resources::UnitEntityMap>();
-        assert_eq!(unit_map.get(1), Some(entity));
+        assert_eq!(unit_map.get(UnitId(1)), Some(entity));
     }
 
     #[test]
@@ -526,7 +573,13 @@ mod tests {
         app.add_plugins(plugins::NostrNationsPlugin::default());
 
         // Spawn a city
-        let city = City::new(1, 0, "Test City".to_string(), HexCoord::new(10, 10), true);
+        let city = City::new(
+            CityId(1),
+            PlayerSlot(0),
+            "Test City".to_string(),
+            HexCoord::new(10, 10),
+            true,
+        );
         let entity = app
             .world_mut()
             .spawn(components::CityBundle::new(city))
@@ -552,7 +605,12 @@ mod tests {
         app.add_plugins(plugins::NostrNationsPlugin::default());
 
         // Spawn a unit
-        let unit = Unit::new(1, 0, UnitType::Warrior, HexCoord::new(0, 0));
+        let unit = Unit::new(
+            UnitId(1),
+            PlayerSlot(0),
+            UnitType::Warrior,
+            HexCoord::new(0, 0),
+        );
         let entity = app
             .world_mut()
             .spawn(components::UnitBundle::new(unit))
@@ -561,7 +619,7 @@ mod tests {
         // Select the unit
         {
             let mut selected = app.world_mut().resource_mut::<resources::SelectedEntity>();
-            *selected = resources::SelectedEntity::unit(entity, 1, HexCoord::new(0, 0));
+            *selected = resources::SelectedEntity::unit(entity, UnitId(1), HexCoord::new(0, 0));
         }
 
         // Verify selection
@@ -672,7 +730,12 @@ mod tests {
         app.add_plugins(plugins::NostrNationsPlugin::default());
 
         // Spawn entities
-        let unit = Unit::new(1, 0, UnitType::Warrior, HexCoord::new(0, 0));
+        let unit = Unit::new(
+            UnitId(1),
+            PlayerSlot(0),
+            UnitType::Warrior,
+            HexCoord::new(0, 0),
+        );
         let entity = app
             .world_mut()
             .spawn(components::UnitBundle::new(unit))
@@ -731,7 +794,12 @@ mod tests {
         // Spawn multiple units
         let mut entities = Vec::new();
         for i in 0..3 {
-            let unit = Unit::new(i, 0, UnitType::Warrior, HexCoord::new(i as i32, 0));
+            let unit = Unit::new(
+                UnitId(i),
+                PlayerSlot(0),
+                UnitType::Warrior,
+                HexCoord::new(i as i32, 0),
+            );
             let entity = app
                 .world_mut()
                 .spawn(components::UnitBundle::new(unit))
@@ -743,14 +811,14 @@ mod tests {
         {
             let mut unit_map = app.world_mut().resource_mut::<resources::UnitEntityMap>();
             for (id, entity) in &entities {
-                unit_map.insert(*id, *entity);
+                unit_map.insert(UnitId(*id), *entity);
             }
         }
 
         // Verify all can be retrieved
         let unit_map = app.world().resource::<resources::UnitEntityMap>();
         for (id, entity) in &entities {
-            assert_eq!(unit_map.get(*id), Some(*entity));
+            assert_eq!(unit_map.get(UnitId(*id)), Some(*entity));
         }
     }
 
@@ -761,7 +829,12 @@ mod tests {
         app.add_plugins(plugins::NostrNationsPlugin::default());
 
         // Spawn and select unit
-        let unit = Unit::new(1, 0, UnitType::Warrior, HexCoord::new(5, 5));
+        let unit = Unit::new(
+            UnitId(1),
+            PlayerSlot(0),
+            UnitType::Warrior,
+            HexCoord::new(5, 5),
+        );
         let entity = app
             .world_mut()
             .spawn((
@@ -787,7 +860,12 @@ mod tests {
         app.add_plugins(plugins::NostrNationsPlugin::default());
 
         // Spawn local player's unit
-        let unit1 = Unit::new(1, 0, UnitType::Warrior, HexCoord::new(0, 0));
+        let unit1 = Unit::new(
+            UnitId(1),
+            PlayerSlot(0),
+            UnitType::Warrior,
+            HexCoord::new(0, 0),
+        );
         let entity1 = app
             .world_mut()
             .spawn((
@@ -797,12 +875,17 @@ mod tests {
             .id();
 
         // Spawn enemy unit
-        let unit2 = Unit::new(2, 1, UnitType::Warrior, HexCoord::new(1, 1));
+        let unit2 = Unit::new(
+            UnitId(2),
+            PlayerSlot(1),
+            UnitType::Warrior,
+            HexCoord::new(1, 1),
+        );
         let entity2 = app
             .world_mut()
             .spawn((
                 components::UnitBundle::new(unit2),
-                components::OtherPlayerOwned::new(1),
+                components::OtherPlayerOwned::new(PlayerSlot(1)),
             ))
             .id();
 
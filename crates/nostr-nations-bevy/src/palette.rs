@@ -0,0 +1,259 @@
+//! Player colors and patterns.
+//!
+//! Players are told apart on the map by a color and, optionally, a hatching
+//! pattern drawn over their territory and units. Both are assigned from the
+//! player slot alone, so every client shows the same player the same way
+//! without exchanging any settings.
+//!
+//! Besides the standard colors stored in the game state, colorblind-safe
+//! palettes are available. Patterns keep players distinguishable even when
+//! two colors look alike, and when there are more players than colors.
+
+use bevy::prelude::*;
+use nostr_nations_core::types::{PlayerColor, PlayerSlot};
+use serde::{Deserialize, Serialize};
+
+/// A set of player colors.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub enum PaletteKind {
+    /// The colors stored in the game state.
+    #[default]
+    Standard,
+    /// Okabe-Ito palette, distinguishable with all common color vision
+    /// deficiencies.
+    OkabeIto,
+    /// Paul Tol's muted palette, safe for deuteranopia and protanopia.
+    TolMuted,
+}
+
+/// Okabe-Ito colors, without black.
+const OKABE_ITO: [PlayerColor; 7] = [
+    PlayerColor::new(230, 159, 0),
+    PlayerColor::new(86, 180, 233),
+    PlayerColor::new(0, 158, 115),
+    PlayerColor::new(240, 228, 66),
+    PlayerColor::new(0, 114, 178),
+    PlayerColor::new(213, 94, 0),
+    PlayerColor::new(204, 121, 167),
+];
+
+/// Paul Tol's muted colors.
+const TOL_MUTED: [PlayerColor; 9] = [
+    PlayerColor::new(51, 34, 136),
+    PlayerColor::new(136, 204, 238),
+    PlayerColor::new(68, 170, 153),
+    PlayerColor::new(17, 119, 51),
+    PlayerColor::new(153, 153, 51),
+    PlayerColor::new(221, 204, 119),
+    PlayerColor::new(204, 102, 119),
+    PlayerColor::new(136, 34, 85),
+    PlayerColor::new(170, 68, 153),
+];
+
+impl PaletteKind {
+    /// All palettes.
+    pub const ALL: [PaletteKind; 3] = [
+        PaletteKind::Standard,
+        PaletteKind::OkabeIto,
+        PaletteKind::TolMuted,
+    ];
+
+    /// Whether the palette is designed for color vision deficiencies.
+    pub fn is_colorblind_safe(&self) -> bool {
+        !matches!(self, PaletteKind::Standard)
+    }
+
+    /// Number of distinct colors before they repeat.
+    pub fn color_count(&self) -> usize {
+        match self {
+            PaletteKind::Standard => 8,
+            PaletteKind::OkabeIto => OKABE_ITO.len(),
+            PaletteKind::TolMuted => TOL_MUTED.len(),
+        }
+    }
+
+    /// Color for a player slot.
+    pub fn color(&self, slot: PlayerSlot) -> PlayerColor {
+        let index = slot.0 as usize % self.color_count();
+        match self {
+            PaletteKind::Standard => PlayerColor::default_for_player(PlayerSlot(index as u8)),
+            PaletteKind::OkabeIto => OKABE_ITO[index],
+            PaletteKind::TolMuted => TOL_MUTED[index],
+        }
+    }
+}
+
+/// Hatching drawn over a player's color.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub enum Pattern {
+    /// No hatching.
+    #[default]
+    Solid,
+    /// Lines from bottom left to top right.
+    Diagonal,
+    /// Lines from top left to bottom right.
+    AntiDiagonal,
+    /// Horizontal lines.
+    Horizontal,
+    /// Vertical lines.
+    Vertical,
+    /// Horizontal and vertical lines.
+    Grid,
+    /// Both diagonals.
+    CrossHatch,
+    /// Dots.
+    Dots,
+}
+
+impl Pattern {
+    /// All patterns, in assignment order.
+    pub const ALL: [Pattern; 8] = [
+        Pattern::Solid,
+        Pattern::Diagonal,
+        Pattern::Horizontal,
+        Pattern::Dots,
+        Pattern::AntiDiagonal,
+        Pattern::Vertical,
+        Pattern::CrossHatch,
+        Pattern::Grid,
+    ];
+
+    /// Repeat length of the pattern in texels.
+    pub const PERIOD: u32 = 4;
+
+    /// Pattern for a player slot.
+    pub fn for_slot(slot: PlayerSlot) -> Self {
+        Self::ALL[slot.0 as usize % Self::ALL.len()]
+    }
+
+    /// Whether the hatching covers a texel.
+    ///
+    /// Used to build overlay textures; covered texels are drawn darker than
+    /// the player color.
+    pub fn covers(&self, x: u32, y: u32) -> bool {
+        let (x, y) = (x % Self::PERIOD, y % Self::PERIOD);
+        match self {
+            Pattern::Solid => false,
+            Pattern::Diagonal => (x + y) % Self::PERIOD == Self::PERIOD - 1,
+            Pattern::AntiDiagonal => x == y,
+            Pattern::Horizontal => y == 0,
+            Pattern::Vertical => x == 0,
+            Pattern::Grid => x == 0 || y == 0,
+            Pattern::CrossHatch => x == y || (x + y) % Self::PERIOD == Self::PERIOD - 1,
+            Pattern::Dots => x == 1 && y == 1,
+        }
+    }
+}
+
+/// Player color settings.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct PaletteSettings {
+    /// Palette to take colors from.
+    pub palette: PaletteKind,
+    /// Whether to draw a pattern over each player's color.
+    pub patterns: bool,
+}
+
+impl PaletteSettings {
+    /// Colorblind-safe colors with patterns.
+    pub fn accessible() -> Self {
+        Self {
+            palette: PaletteKind::OkabeIto,
+            patterns: true,
+        }
+    }
+
+    /// How a player is drawn.
+    pub fn appearance(&self, slot: PlayerSlot) -> PlayerAppearance {
+        PlayerAppearance {
+            color: self.palette.color(slot),
+            pattern: if self.patterns {
+                Pattern::for_slot(slot)
+            } else {
+                Pattern::Solid
+            },
+        }
+    }
+}
+
+/// How a player is drawn on the map.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub struct PlayerAppearance {
+    /// Base color.
+    pub color: PlayerColor,
+    /// Hatching over the color.
+    pub pattern: Pattern,
+}
+
+impl PlayerAppearance {
+    /// The base color as a Bevy color.
+    pub fn bevy_color(&self) -> Color {
+        Color::srgb_u8(self.color.r, self.color.g, self.color.b)
+    }
+
+    /// Color of a texel, with covered texels darkened.
+    pub fn texel(&self, x: u32, y: u32) -> Color {
+        let color = self.bevy_color();
+        if self.pattern.covers(x, y) {
+            color.mix(&Color::BLACK, 0.6)
+        } else {
+            color
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::collections::HashSet;
+
+    #[test]
+    fn test_standard_palette_matches_game_state_colors() {
+        for slot in 0..8 {
+            assert_eq!(
+                PaletteKind::Standard.color(PlayerSlot(slot)),
+                PlayerColor::default_for_player(PlayerSlot(slot))
+            );
+        }
+    }
+
+    #[test]
+    fn test_palette_colors_distinct_within_length() {
+        for palette in PaletteKind::ALL {
+            let colors: HashSet<PlayerColor> = (0..palette.color_count() as u8)
+                .map(|slot| palette.color(PlayerSlot(slot)))
+                .collect();
+            assert_eq!(colors.len(), palette.color_count(), "{:?}", palette);
+            assert_eq!(
+                palette.color(PlayerSlot(palette.color_count() as u8)),
+                palette.color(PlayerSlot(0))
+            );
+        }
+    }
+
+    #[test]
+    fn test_patterns_keep_players_distinct_past_palette_length() {
+        let settings = PaletteSettings::accessible();
+        let appearances: HashSet<PlayerAppearance> = (0..16)
+            .map(|slot| settings.appearance(PlayerSlot(slot)))
+            .collect();
+        assert_eq!(appearances.len(), 16);
+
+        let plain = PaletteSettings::default();
+        assert_eq!(plain.appearance(PlayerSlot(3)).pattern, Pattern::Solid);
+    }
+
+    #[test]
+    fn test_patterns_are_distinct_masks() {
+        let masks: HashSet<Vec<bool>> = Pattern::ALL
+            .iter()
+            .map(|pattern| {
+                (0..Pattern::PERIOD * Pattern::PERIOD)
+                    .map(|i| pattern.covers(i % Pattern::PERIOD, i / Pattern::PERIOD))
+                    .collect()
+            })
+            .collect();
+        assert_eq!(masks.len(), Pattern::ALL.len());
+        assert!(Pattern::Grid.covers(4, 7));
+    }
+}
@@ -4,16 +4,20 @@
 //! for modular initialization of the game.
 
 use bevy::prelude::*;
-use nostr_nations_core::GameSettings;
+use nostr_nations_core::{replay::ActionEffect, GameSettings, PlayerSlot};
 
+use crate::input::{input_mapping_system, InputMappingPlugin};
 use crate::resources::{
     CameraState, CityEntityMap, CurrentTurn, GameSettingsResource, GameStateResource,
-    PendingAction, SelectedEntity, TileEntityMap, UiState, UnitEntityMap,
+    NetworkDebugOverlay, PendingAction, PendingItemCycle, SelectedEntity, TileEntityMap, UiState,
+    UnitEntityMap,
 };
 use crate::systems::{
+    combat_preview_system, debug_overlay_render_system, debug_overlay_toggle_system,
     despawn_removed_entities_system, game_tick_system, movement_animation_system,
-    pending_action_system, selection_changed_system, selection_system, spawn_new_entities_system,
-    sync_game_state_system, turn_system, visibility_system, GameSystemSet,
+    next_pending_item_system, pending_action_system, selection_changed_system, selection_system,
+    spawn_new_entities_system, sync_game_state_system, turn_system, visibility_system,
+    GameSystemSet,
 };
 
 /// Main plugin for Nostr Nations game.
@@ -41,14 +45,14 @@ pub struct NostrNationsPlugin {
     /// Random seed for deterministic game initialization.
     pub seed: [u8; 32],
     /// Local player's ID.
-    pub local_player_id: u8,
+    pub local_player_id: PlayerSlot,
     /// Whether this is a networked game.
     pub is_networked: bool,
 }
 
 impl NostrNationsPlugin {
     /// Create a new plugin with custom settings.
-    pub fn new(settings: GameSettings, seed: [u8; 32], local_player_id: u8) -> Self {
+    pub fn new(settings: GameSettings, seed: [u8; 32], local_player_id: PlayerSlot) -> Self {
         Self {
             settings,
             seed,
@@ -58,7 +62,7 @@ impl NostrNationsPlugin {
     }
 
     /// Create a plugin for a networked game.
-    pub fn networked(settings: GameSettings, seed: [u8; 32], local_player_id: u8) -> Self {
+    pub fn networked(settings: GameSettings, seed: [u8; 32], local_player_id: PlayerSlot) -> Self {
         Self {
             settings,
             seed,
@@ -72,7 +76,7 @@ impl NostrNationsPlugin {
         Self {
             settings,
             seed,
-            local_player_id: 0,
+            local_player_id: PlayerSlot(0),
             is_networked: false,
         }
     }
@@ -88,6 +92,7 @@ impl Plugin for NostrNationsPlugin {
                 local_player_id: self.local_player_id,
                 is_networked: self.is_networked,
             },
+            InputMappingPlugin,
             SelectionPlugin,
             VisibilityPlugin,
             AnimationPlugin,
@@ -106,6 +111,13 @@ impl Plugin for NostrNationsPlugin {
         );
 
         // Add game logic systems
+        app.add_systems(Update, combat_preview_system.in_set(GameSystemSet::Input));
+        app.add_systems(
+            Update,
+            next_pending_item_system
+                .after(input_mapping_system)
+                .in_set(GameSystemSet::Input),
+        );
         app.add_systems(
             Update,
             (game_tick_system, turn_system, pending_action_system)
@@ -138,7 +150,7 @@ pub struct GameStatePlugin {
     /// Random seed.
     pub seed: [u8; 32],
     /// Local player ID.
-    pub local_player_id: u8,
+    pub local_player_id: PlayerSlot,
     /// Whether networked.
     pub is_networked: bool,
 }
@@ -156,19 +168,21 @@ impl Plugin for GameStatePlugin {
         };
 
         // Initialize turn state
-        let current_turn = CurrentTurn::new(0, 0);
+        let current_turn = CurrentTurn::new(0, PlayerSlot(0));
 
         // Add resources
         app.insert_resource(game_state)
             .insert_resource(game_settings)
             .insert_resource(current_turn)
             .insert_resource(PendingAction::default())
+            .insert_resource(PendingItemCycle::default())
             .insert_resource(TileEntityMap::default())
             .insert_resource(UnitEntityMap::default())
             .insert_resource(CityEntityMap::default());
 
         // Add game state events
         app.add_event::<GameStateEvent>();
+        app.add_event::<ActionEffectEvent>();
     }
 }
 
@@ -188,6 +202,7 @@ impl Plugin for SelectionPlugin {
             Update,
             (selection_system, selection_changed_system)
                 .chain()
+                .after(input_mapping_system)
                 .in_set(GameSystemSet::Input),
         );
 
@@ -251,6 +266,15 @@ impl Plugin for UiPlugin {
     fn build(&self, app: &mut App) {
         // Add UI state resource
         app.insert_resource(UiState::default());
+        app.insert_resource(NetworkDebugOverlay::default());
+
+        app.add_systems(
+            Update,
+            (debug_overlay_toggle_system, debug_overlay_render_system)
+                .chain()
+                .after(input_mapping_system)
+                .in_set(GameSystemSet::Input),
+        );
 
         // UI systems would be added here
         // (ui_panel_system, tooltip_system, etc.)
@@ -293,6 +317,11 @@ pub enum GameStateEvent {
         /// New owner.
         new_owner: u8,
     },
+    /// A unit earned a promotion and needs the player to pick one.
+    PromotionAvailable {
+        /// Unit ID.
+        unit_id: u64,
+    },
     /// A technology was researched.
     TechResearched {
         /// Player ID.
@@ -309,6 +338,19 @@ pub enum GameStateEvent {
     },
 }
 
+/// Event fired for each effect of an action the game engine applied.
+///
+/// Systems read these to react to single changes (a unit moving, a city
+/// being founded, combat damage) instead of comparing [`GameStateResource`]
+/// every frame. Effects arrive in the order the engine produced them.
+#[derive(Event, Clone, Debug)]
+pub struct ActionEffectEvent {
+    /// Player whose action caused the effect.
+    pub player_id: PlayerSlot,
+    /// The effect.
+    pub effect: ActionEffect,
+}
+
 /// Event fired when selection changes.
 #[derive(Event, Clone, Debug)]
 pub enum SelectionEvent {
@@ -348,7 +390,7 @@ mod tests {
     #[test]
     fn test_nostr_nations_plugin_default() {
         let plugin = NostrNationsPlugin::default();
-        assert_eq!(plugin.local_player_id, 0);
+        assert_eq!(plugin.local_player_id, PlayerSlot(0));
         assert!(!plugin.is_networked);
         assert_eq!(plugin.seed, [0u8; 32]);
     }
@@ -357,9 +399,9 @@ mod tests {
     fn test_nostr_nations_plugin_new() {
         let settings = GameSettings::new("Custom Game".to_string());
         let seed = [42u8; 32];
-        let plugin = NostrNationsPlugin::new(settings, seed, 2);
+        let plugin = NostrNationsPlugin::new(settings, seed, PlayerSlot(2));
 
-        assert_eq!(plugin.local_player_id, 2);
+        assert_eq!(plugin.local_player_id, PlayerSlot(2));
         assert!(!plugin.is_networked);
         assert_eq!(plugin.seed, seed);
     }
@@ -369,16 +411,16 @@ mod tests {
         let settings = GameSettings::new("Local Test".to_string());
         let plugin = NostrNationsPlugin::local(settings, [42u8; 32]);
 
-        assert_eq!(plugin.local_player_id, 0);
+        assert_eq!(plugin.local_player_id, PlayerSlot(0));
         assert!(!plugin.is_networked);
     }
 
     #[test]
     fn test_nostr_nations_plugin_networked() {
         let settings = GameSettings::new("Networked Test".to_string());
-        let plugin = NostrNationsPlugin::networked(settings, [42u8; 32], 3);
+        let plugin = NostrNationsPlugin::networked(settings, [42u8; 32], PlayerSlot(3));
 
-        assert_eq!(plugin.local_player_id, 3);
+        assert_eq!(plugin.local_player_id, PlayerSlot(3));
         assert!(plugin.is_networked);
     }
 
@@ -417,7 +459,7 @@ mod tests {
     fn test_nostr_nations_plugin_networked_settings() {
         let settings = GameSettings::new("Multiplayer".to_string());
         let seed = [5u8; 32];
-        let plugin = NostrNationsPlugin::networked(settings, seed, 1);
+        let plugin = NostrNationsPlugin::networked(settings, seed, PlayerSlot(1));
 
         let mut app = App::new();
         app.add_plugins(MinimalPlugins);
@@ -426,7 +468,7 @@ mod tests {
         // Verify networked settings
         let game_settings = app.world().resource::<GameSettingsResource>();
         assert!(game_settings.is_networked);
-        assert_eq!(game_settings.local_player_id, 1);
+        assert_eq!(game_settings.local_player_id, PlayerSlot(1));
     }
 
     // ============================================
@@ -436,7 +478,7 @@ mod tests {
     #[test]
     fn test_game_state_plugin_default() {
         let plugin = GameStatePlugin::default();
-        assert_eq!(plugin.local_player_id, 0);
+        assert_eq!(plugin.local_player_id, PlayerSlot(0));
         assert!(!plugin.is_networked);
         assert_eq!(plugin.seed, [0u8; 32]);
     }
@@ -465,7 +507,7 @@ mod tests {
         let plugin = GameStatePlugin {
             settings,
             seed,
-            local_player_id: 0,
+            local_player_id: PlayerSlot(0),
             is_networked: false,
         };
 
@@ -483,7 +525,7 @@ mod tests {
         let plugin = GameStatePlugin {
             settings: settings.clone(),
             seed: [0u8; 32],
-            local_player_id: 0,
+            local_player_id: PlayerSlot(0),
             is_networked: false,
         };
 
@@ -502,7 +544,7 @@ mod tests {
         let plugin = GameStatePlugin {
             settings: settings.clone(),
             seed: [0u8; 32],
-            local_player_id: 2,
+            local_player_id: PlayerSlot(2),
             is_networked: true,
         };
 
@@ -512,7 +554,7 @@ mod tests {
 
         let game_settings = app.world().resource::<GameSettingsResource>();
         assert!(game_settings.is_networked);
-        assert_eq!(game_settings.local_player_id, 2);
+        assert_eq!(game_settings.local_player_id, PlayerSlot(2));
     }
 
     #[test]
@@ -901,7 +943,7 @@ mod tests {
 
         let current_turn = app.world().resource::<CurrentTurn>();
         assert_eq!(current_turn.turn, 0);
-        assert_eq!(current_turn.current_player, 0);
+        assert_eq!(current_turn.current_player, PlayerSlot(0));
         assert!(!current_turn.turn_ended);
     }
 
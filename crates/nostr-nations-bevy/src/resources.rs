@@ -6,10 +6,15 @@
 use bevy::prelude::*;
 use nostr_nations_core::{
     settings::{Difficulty, GameSpeed},
-    types::{CityId, PlayerId, UnitId},
-    GameEngine, GameSettings, GameState, HexCoord,
+    types::{CityId, PlayerSlot, UnitId},
+    CombatPreview, Fixed, GameEngine, GameSettings, GameState, HexCoord, PendingItem, Promotion,
+    TurnAdvisor,
 };
 
+use crate::audio::SoundCue;
+use crate::input::InputBindings;
+use crate::palette::{PaletteSettings, PlayerAppearance};
+
 /// Main game state resource holding the core GameEngine.
 ///
 /// This is the primary interface between Bevy systems and the
@@ -44,7 +49,7 @@ impl GameStateResource {
     }
 
     /// Get the current player's ID.
-    pub fn current_player(&self) -> PlayerId {
+    pub fn current_player(&self) -> PlayerSlot {
         self.engine.state.current_player
     }
 
@@ -164,7 +169,7 @@ pub struct CurrentTurn {
     /// Current turn number.
     pub turn: u32,
     /// Player whose turn it currently is.
-    pub current_player: PlayerId,
+    pub current_player: PlayerSlot,
     /// Time remaining for this turn (if timer enabled).
     pub time_remaining: Option<f32>,
     /// Whether the local player has ended their turn.
@@ -175,7 +180,7 @@ pub struct CurrentTurn {
 
 impl CurrentTurn {
     /// Create turn state for the beginning of a game.
-    pub fn new(turn: u32, current_player: PlayerId) -> Self {
+    pub fn new(turn: u32, current_player: PlayerSlot) -> Self {
         Self {
             turn,
             current_player,
@@ -186,7 +191,7 @@ impl CurrentTurn {
     }
 
     /// Create turn state with a timer.
-    pub fn with_timer(turn: u32, current_player: PlayerId, seconds: f32) -> Self {
+    pub fn with_timer(turn: u32, current_player: PlayerSlot, seconds: f32) -> Self {
         Self {
             turn,
             current_player,
@@ -197,7 +202,7 @@ impl CurrentTurn {
     }
 
     /// Check if it's the specified player's turn.
-    pub fn is_player_turn(&self, player_id: PlayerId) -> bool {
+    pub fn is_player_turn(&self, player_id: PlayerSlot) -> bool {
         self.current_player == player_id && !self.turn_ended
     }
 
@@ -207,7 +212,7 @@ impl CurrentTurn {
     }
 
     /// Advance to the next turn.
-    pub fn next_turn(&mut self, turn: u32, current_player: PlayerId) {
+    pub fn next_turn(&mut self, turn: u32, current_player: PlayerSlot) {
         self.turn = turn;
         self.current_player = current_player;
         self.turn_ended = false;
@@ -229,7 +234,7 @@ impl CurrentTurn {
 
 impl Default for CurrentTurn {
     fn default() -> Self {
-        Self::new(1, 0)
+        Self::new(1, PlayerSlot(0))
     }
 }
 
@@ -242,7 +247,7 @@ pub struct GameSettingsResource {
     /// The game settings.
     pub settings: GameSettings,
     /// Local player's ID in this game.
-    pub local_player_id: PlayerId,
+    pub local_player_id: PlayerSlot,
     /// Whether this is a local (offline) or networked game.
     pub is_networked: bool,
     /// Whether fog of war is enabled.
@@ -251,11 +256,20 @@ pub struct GameSettingsResource {
     pub game_speed: GameSpeed,
     /// Difficulty setting.
     pub difficulty: Difficulty,
+    /// Sound volumes.
+    pub audio: AudioSettings,
+    /// Player colors and patterns.
+    pub palette: PaletteSettings,
+    /// Inputs bound to each action.
+    pub input: InputBindings,
+    /// Refuse to end the turn while research or a city's production is
+    /// unchosen.
+    pub block_end_turn_on_pending: bool,
 }
 
 impl GameSettingsResource {
     /// Create settings for a local game.
-    pub fn local(settings: GameSettings, local_player_id: PlayerId) -> Self {
+    pub fn local(settings: GameSettings, local_player_id: PlayerSlot) -> Self {
         let fog_of_war = settings.fog_of_war;
         let game_speed = settings.game_speed;
         let difficulty = settings.difficulty;
@@ -266,11 +280,15 @@ impl GameSettingsResource {
             fog_of_war,
             game_speed,
             difficulty,
+            audio: AudioSettings::default(),
+            palette: PaletteSettings::default(),
+            input: InputBindings::default(),
+            block_end_turn_on_pending: true,
         }
     }
 
     /// Create settings for a networked game.
-    pub fn networked(settings: GameSettings, local_player_id: PlayerId) -> Self {
+    pub fn networked(settings: GameSettings, local_player_id: PlayerSlot) -> Self {
         let fog_of_war = settings.fog_of_war;
         let game_speed = settings.game_speed;
         let difficulty = settings.difficulty;
@@ -281,6 +299,10 @@ impl GameSettingsResource {
             fog_of_war,
             game_speed,
             difficulty,
+            audio: AudioSettings::default(),
+            palette: PaletteSettings::default(),
+            input: InputBindings::default(),
+            block_end_turn_on_pending: true,
         }
     }
 
@@ -290,19 +312,98 @@ impl GameSettingsResource {
     }
 
     /// Get the production multiplier based on game speed.
-    pub fn production_multiplier(&self) -> f32 {
+    pub fn production_multiplier(&self) -> Fixed {
         self.game_speed.production_multiplier()
     }
 
     /// Get the research multiplier based on game speed.
-    pub fn research_multiplier(&self) -> f32 {
+    pub fn research_multiplier(&self) -> Fixed {
         self.game_speed.research_multiplier()
     }
+
+    /// Get how a player is drawn with the configured palette.
+    pub fn player_appearance(&self, player_id: PlayerSlot) -> PlayerAppearance {
+        self.palette.appearance(player_id)
+    }
 }
 
 impl Default for GameSettingsResource {
     fn default() -> Self {
-        Self::local(GameSettings::default(), 0)
+        Self::local(GameSettings::default(), PlayerSlot(0))
+    }
+}
+
+/// Per-category sound volumes, from 0.0 (muted) to 1.0.
+///
+/// The volume a cue plays at is its category volume times `master`.
+#[derive(Clone, Debug, PartialEq)]
+pub struct AudioSettings {
+    /// Volume applied to every sound.
+    pub master: f32,
+    /// Unit movement.
+    pub movement: f32,
+    /// Combat.
+    pub combat: f32,
+    /// City founding.
+    pub city: f32,
+    /// Start of the local player's turn.
+    pub turn: f32,
+    /// Notifications.
+    pub notifications: f32,
+}
+
+impl AudioSettings {
+    /// Settings with every sound muted.
+    pub fn muted() -> Self {
+        Self {
+            master: 0.0,
+            ..Self::default()
+        }
+    }
+
+    /// Volume to play a cue at.
+    pub fn volume(&self, cue: SoundCue) -> f32 {
+        let category = match cue {
+            SoundCue::Move => self.movement,
+            SoundCue::Combat => self.combat,
+            SoundCue::CityFounded => self.city,
+            SoundCue::YourTurn => self.turn,
+            SoundCue::Notification => self.notifications,
+        };
+        (self.master * category).clamp(0.0, 1.0)
+    }
+}
+
+impl Default for AudioSettings {
+    fn default() -> Self {
+        Self {
+            master: 0.8,
+            movement: 0.5,
+            combat: 1.0,
+            city: 1.0,
+            turn: 1.0,
+            notifications: 0.7,
+        }
+    }
+}
+
+/// Resource tracking which pending item the "next" hotkey is on.
+#[derive(Resource, Clone, Debug, Default)]
+pub struct PendingItemCycle {
+    /// Item the player was last sent to.
+    pub current: Option<PendingItem>,
+}
+
+impl PendingItemCycle {
+    /// Advance to the player's next pending item, wrapping around.
+    pub fn next_pending_item(
+        &mut self,
+        state: &GameState,
+        player_id: PlayerSlot,
+    ) -> Option<PendingItem> {
+        let advisor = TurnAdvisor::new(state, player_id);
+        self.current = advisor.next_after(self.current.as_ref()).cloned();
+        self.current.clone()
     }
 }
 
@@ -401,6 +502,14 @@ pub enum PendingActionType {
         attacker_id: UnitId,
         city_id: CityId,
     },
+    /// Picking a promotion for a unit that leveled up.
+    ///
+    /// Waits until the player fills in `selected`.
+    ChoosePromotion {
+        unit_id: UnitId,
+        options: Vec<Promotion>,
+        selected: Option<Promotion>,
+    },
 }
 
 impl PendingAction {
@@ -414,6 +523,32 @@ impl PendingAction {
     pub fn has_pending(&self) -> bool {
         self.action.is_some()
     }
+
+    /// Ask the player to pick a promotion for a unit.
+    pub fn choose_promotion(&mut self, unit: &nostr_nations_core::Unit) {
+        self.action = Some(PendingActionType::ChoosePromotion {
+            unit_id: unit.id,
+            options: unit.available_promotions(),
+            selected: None,
+        });
+        self.target = Some(unit.position);
+    }
+
+    /// Record the player's promotion pick.
+    ///
+    /// Returns false if no promotion choice is pending or the pick isn't
+    /// one of the offered options.
+    pub fn select_promotion(&mut self, promotion: Promotion) -> bool {
+        match &mut self.action {
+            Some(PendingActionType::ChoosePromotion {
+                options, selected, ..
+            }) if options.contains(&promotion) => {
+                *selected = Some(promotion);
+                true
+            }
+            _ => false,
+        }
+    }
 }
 
 /// Resource for UI state that persists across frames.
@@ -427,12 +562,16 @@ pub struct UiState {
     pub tech_tree_open: bool,
     /// Whether the diplomacy panel is open.
     pub diplomacy_open: bool,
+    /// Whether the promotion picker is open.
+    pub promotion_panel_open: bool,
     /// Currently hovered hex coordinate.
     pub hovered_hex: Option<HexCoord>,
     /// Whether we're in unit command mode.
     pub command_mode: bool,
     /// Current tooltip text.
     pub tooltip: Option<String>,
+    /// Preview of the attack waiting for confirmation.
+    pub combat_preview: Option<CombatPreviewTooltip>,
 }
 
 impl UiState {
@@ -442,6 +581,7 @@ impl UiState {
         self.production_panel_open = false;
         self.tech_tree_open = false;
         self.diplomacy_open = false;
+        self.promotion_panel_open = false;
     }
 
     /// Toggle the main menu.
@@ -451,10 +591,71 @@ impl UiState {
             self.production_panel_open = false;
             self.tech_tree_open = false;
             self.diplomacy_open = false;
+            self.promotion_panel_open = false;
         }
     }
 }
 
+/// Tooltip payload describing the expected outcome of an attack.
+#[derive(Clone, Debug)]
+pub struct CombatPreviewTooltip {
+    /// Attacking unit.
+    pub attacker_id: UnitId,
+    /// Defending unit.
+    pub defender_id: UnitId,
+    /// Expected results for both sides.
+    pub preview: CombatPreview,
+}
+
+impl CombatPreviewTooltip {
+    /// Get the preview as tooltip text.
+    pub fn text(&self) -> String {
+        let defender = &self.preview.defender_damage;
+        let attacker = &self.preview.attacker_damage;
+        format!(
+            "Enemy takes {}-{} damage (avg {}), {}% chance to destroy\n\
+             You take {}-{} damage (avg {}), {}% chance to lose the unit",
+            defender.min,
+            defender.max,
+            defender.avg,
+            self.preview.defender_kill_chance,
+            attacker.min,
+            attacker.max,
+            attacker.avg,
+            self.preview.attacker_kill_chance,
+        )
+    }
+}
+
+/// Network debug overlay toggled with F9.
+///
+/// The host application fills `lines` from the network layer's debug
+/// report; this crate only renders them.
+#[derive(Resource, Clone, Debug, Default)]
+pub struct NetworkDebugOverlay {
+    /// Whether the overlay is shown.
+    pub visible: bool,
+    /// Lines of text to display.
+    pub lines: Vec<String>,
+}
+
+impl NetworkDebugOverlay {
+    /// Show or hide the overlay.
+    pub fn toggle(&mut self) {
+        self.visible = !self.visible;
+    }
+
+    /// Replace the displayed lines.
+    pub fn set_lines(&mut self, lines: Vec<String>) {
+        self.lines = lines;
+    }
+
+    /// Get the overlay contents as a single block of text.
+    pub fn text(&self) -> String {
+        self.lines.join("\n")
+    }
+}
+
 /// Lookup table mapping hex coordinates to tile entities.
 #[derive(Resource, Clone, Debug, Default)]
 pub struct TileEntityMap {
@@ -555,6 +756,7 @@ impl CityEntityMap {
 #[cfg(test)]
 mod tests {
     use super::*;
+    use nostr_nations_core::types::PlayerColor;
 
     // ============================================
     // GameStateResource Tests
@@ -601,7 +803,7 @@ mod tests {
         let resource = GameStateResource::new(settings, [0u8; 32]);
 
         let player_id = resource.current_player();
-        assert_eq!(player_id, 0);
+        assert_eq!(player_id, PlayerSlot(0));
     }
 
     #[test]
@@ -634,14 +836,14 @@ mod tests {
     #[test]
     fn test_selected_entity_unit() {
         let entity = Entity::from_raw(1);
-        let selection = SelectedEntity::unit(entity, 42, HexCoord::new(5, 5));
+        let selection = SelectedEntity::unit(entity, UnitId(42), HexCoord::new(5, 5));
 
         assert!(selection.has_selection());
         assert!(selection.is_unit());
         assert!(!selection.is_city());
         assert!(!selection.is_tile());
         assert_eq!(selection.entity, Some(entity));
-        assert_eq!(selection.unit_id, Some(42));
+        assert_eq!(selection.unit_id, Some(UnitId(42)));
         assert_eq!(selection.city_id, None);
         assert_eq!(selection.coord, Some(HexCoord::new(5, 5)));
     }
@@ -649,14 +851,14 @@ mod tests {
     #[test]
     fn test_selected_entity_city() {
         let entity = Entity::from_raw(2);
-        let selection = SelectedEntity::city(entity, 100, HexCoord::new(10, 10));
+        let selection = SelectedEntity::city(entity, CityId(100), HexCoord::new(10, 10));
 
         assert!(selection.has_selection());
         assert!(!selection.is_unit());
         assert!(selection.is_city());
         assert!(!selection.is_tile());
         assert_eq!(selection.entity, Some(entity));
-        assert_eq!(selection.city_id, Some(100));
+        assert_eq!(selection.city_id, Some(CityId(100)));
         assert_eq!(selection.unit_id, None);
     }
 
@@ -678,7 +880,7 @@ mod tests {
     #[test]
     fn test_selected_entity_clear() {
         let entity = Entity::from_raw(1);
-        let mut selection = SelectedEntity::unit(entity, 42, HexCoord::new(5, 5));
+        let mut selection = SelectedEntity::unit(entity, UnitId(42), HexCoord::new(5, 5));
 
         assert!(selection.has_selection());
 
@@ -698,7 +900,7 @@ mod tests {
     #[test]
     fn test_selected_entity_clone() {
         let entity = Entity::from_raw(1);
-        let selection = SelectedEntity::unit(entity, 42, HexCoord::new(5, 5));
+        let selection = SelectedEntity::unit(entity, UnitId(42), HexCoord::new(5, 5));
         let cloned = selection.clone();
 
         assert_eq!(selection.entity, cloned.entity);
@@ -733,9 +935,9 @@ mod tests {
 
     #[test]
     fn test_current_turn_new() {
-        let turn = CurrentTurn::new(1, 0);
+        let turn = CurrentTurn::new(1, PlayerSlot(0));
         assert_eq!(turn.turn, 1);
-        assert_eq!(turn.current_player, 0);
+        assert_eq!(turn.current_player, PlayerSlot(0));
         assert!(turn.time_remaining.is_none());
         assert!(!turn.turn_ended);
         assert!(!turn.waiting_for_players);
@@ -743,31 +945,31 @@ mod tests {
 
     #[test]
     fn test_current_turn_with_timer() {
-        let turn = CurrentTurn::with_timer(1, 0, 60.0);
+        let turn = CurrentTurn::with_timer(1, PlayerSlot(0), 60.0);
         assert_eq!(turn.turn, 1);
         assert_eq!(turn.time_remaining, Some(60.0));
     }
 
     #[test]
     fn test_current_turn_is_player_turn() {
-        let turn = CurrentTurn::new(1, 0);
-        assert!(turn.is_player_turn(0));
-        assert!(!turn.is_player_turn(1));
-        assert!(!turn.is_player_turn(2));
+        let turn = CurrentTurn::new(1, PlayerSlot(0));
+        assert!(turn.is_player_turn(PlayerSlot(0)));
+        assert!(!turn.is_player_turn(PlayerSlot(1)));
+        assert!(!turn.is_player_turn(PlayerSlot(2)));
     }
 
     #[test]
     fn test_current_turn_is_player_turn_after_end() {
-        let mut turn = CurrentTurn::new(1, 0);
+        let mut turn = CurrentTurn::new(1, PlayerSlot(0));
         turn.end_turn();
 
         // Even the current player can't act after ending turn
-        assert!(!turn.is_player_turn(0));
+        assert!(!turn.is_player_turn(PlayerSlot(0)));
     }
 
     #[test]
     fn test_current_turn_end_turn() {
-        let mut turn = CurrentTurn::new(1, 0);
+        let mut turn = CurrentTurn::new(1, PlayerSlot(0));
         assert!(!turn.turn_ended);
 
         turn.end_turn();
@@ -777,21 +979,21 @@ mod tests {
 
     #[test]
     fn test_current_turn_next_turn() {
-        let mut turn = CurrentTurn::new(1, 0);
+        let mut turn = CurrentTurn::new(1, PlayerSlot(0));
         turn.end_turn();
         turn.waiting_for_players = true;
 
-        turn.next_turn(2, 1);
+        turn.next_turn(2, PlayerSlot(1));
 
         assert_eq!(turn.turn, 2);
-        assert_eq!(turn.current_player, 1);
+        assert_eq!(turn.current_player, PlayerSlot(1));
         assert!(!turn.turn_ended);
         assert!(!turn.waiting_for_players);
     }
 
     #[test]
     fn test_current_turn_update_timer_not_expired() {
-        let mut turn = CurrentTurn::with_timer(1, 0, 60.0);
+        let mut turn = CurrentTurn::with_timer(1, PlayerSlot(0), 60.0);
 
         let expired = turn.update_timer(30.0);
 
@@ -801,7 +1003,7 @@ mod tests {
 
     #[test]
     fn test_current_turn_update_timer_expired() {
-        let mut turn = CurrentTurn::with_timer(1, 0, 60.0);
+        let mut turn = CurrentTurn::with_timer(1, PlayerSlot(0), 60.0);
 
         let expired = turn.update_timer(65.0);
 
@@ -811,7 +1013,7 @@ mod tests {
 
     #[test]
     fn test_current_turn_update_timer_exact() {
-        let mut turn = CurrentTurn::with_timer(1, 0, 60.0);
+        let mut turn = CurrentTurn::with_timer(1, PlayerSlot(0), 60.0);
 
         let expired = turn.update_timer(60.0);
 
@@ -821,7 +1023,7 @@ mod tests {
 
     #[test]
     fn test_current_turn_update_timer_no_timer() {
-        let mut turn = CurrentTurn::new(1, 0);
+        let mut turn = CurrentTurn::new(1, PlayerSlot(0));
 
         let expired = turn.update_timer(100.0);
 
@@ -833,12 +1035,12 @@ mod tests {
     fn test_current_turn_default() {
         let turn = CurrentTurn::default();
         assert_eq!(turn.turn, 1);
-        assert_eq!(turn.current_player, 0);
+        assert_eq!(turn.current_player, PlayerSlot(0));
     }
 
     #[test]
     fn test_current_turn_clone() {
-        let turn = CurrentTurn::with_timer(5, 2, 45.0);
+        let turn = CurrentTurn::with_timer(5, PlayerSlot(2), 45.0);
         let cloned = turn.clone();
 
         assert_eq!(turn.turn, cloned.turn);
@@ -853,26 +1055,26 @@ mod tests {
     #[test]
     fn test_game_settings_resource_local() {
         let settings = GameSettings::new("Test".to_string());
-        let resource = GameSettingsResource::local(settings, 0);
+        let resource = GameSettingsResource::local(settings, PlayerSlot(0));
 
         assert!(!resource.is_networked);
-        assert_eq!(resource.local_player_id, 0);
+        assert_eq!(resource.local_player_id, PlayerSlot(0));
     }
 
     #[test]
     fn test_game_settings_resource_networked() {
         let settings = GameSettings::new("Networked Test".to_string());
-        let resource = GameSettingsResource::networked(settings, 2);
+        let resource = GameSettingsResource::networked(settings, PlayerSlot(2));
 
         assert!(resource.is_networked);
-        assert_eq!(resource.local_player_id, 2);
+        assert_eq!(resource.local_player_id, PlayerSlot(2));
     }
 
     #[test]
     fn test_game_settings_resource_has_fog_of_war() {
         let mut settings = GameSettings::new("Test".to_string());
         settings.fog_of_war = true;
-        let resource = GameSettingsResource::local(settings, 0);
+        let resource = GameSettingsResource::local(settings, PlayerSlot(0));
 
         assert!(resource.has_fog_of_war());
     }
@@ -881,7 +1083,7 @@ mod tests {
     fn test_game_settings_resource_no_fog_of_war() {
         let mut settings = GameSettings::new("Test".to_string());
         settings.fog_of_war = false;
-        let resource = GameSettingsResource::local(settings, 0);
+        let resource = GameSettingsResource::local(settings, PlayerSlot(0));
 
         assert!(!resource.has_fog_of_war());
     }
@@ -889,19 +1091,19 @@ mod tests {
     #[test]
     fn test_game_settings_resource_production_multiplier() {
         let settings = GameSettings::new("Test".to_string());
-        let resource = GameSettingsResource::local(settings, 0);
+        let resource = GameSettingsResource::local(settings, PlayerSlot(0));
 
         let multiplier = resource.production_multiplier();
-        assert!(multiplier > 0.0);
+        assert!(multiplier > Fixed::ZERO);
     }
 
     #[test]
     fn test_game_settings_resource_research_multiplier() {
         let settings = GameSettings::new("Test".to_string());
-        let resource = GameSettingsResource::local(settings, 0);
+        let resource = GameSettingsResource::local(settings, PlayerSlot(0));
 
         let multiplier = resource.research_multiplier();
-        assert!(multiplier > 0.0);
+        assert!(multiplier > Fixed::ZERO);
     }
 
     #[test]
@@ -909,13 +1111,74 @@ mod tests {
         let resource = GameSettingsResource::default();
 
         assert!(!resource.is_networked);
-        assert_eq!(resource.local_player_id, 0);
+        assert_eq!(resource.local_player_id, PlayerSlot(0));
+    }
+
+    #[test]
+    fn test_game_settings_resource_player_appearance() {
+        let mut resource = GameSettingsResource::default();
+        assert_eq!(
+            resource.player_appearance(PlayerSlot(1)).color,
+            PlayerColor::default_for_player(PlayerSlot(1))
+        );
+
+        resource.palette = PaletteSettings::accessible();
+        let appearance = resource.player_appearance(PlayerSlot(1));
+        assert_ne!(
+            appearance.color,
+            PlayerColor::default_for_player(PlayerSlot(1))
+        );
+        assert_ne!(appearance.pattern, crate::palette::Pattern::Solid);
+    }
+
+    #[test]
+    fn test_pending_item_cycle_wraps() {
+        let resource = GameStateResource::default();
+        let mut state = resource.state().clone();
+        state.players.push(nostr_nations_core::Player::new(
+            PlayerSlot(0),
+            nostr_nations_core::Npub::new("npub0"),
+            "Alice".to_string(),
+            nostr_nations_core::Civilization::generic(),
+        ));
+        state.players[0].current_research = Some("pottery".to_string());
+        for id in [1, 2] {
+            let unit = nostr_nations_core::Unit::new(
+                UnitId(id),
+                PlayerSlot(0),
+                nostr_nations_core::unit::UnitType::Warrior,
+                HexCoord::new(id as i32, 0),
+            );
+            state.units.insert(UnitId(id), unit);
+        }
+
+        let mut cycle = PendingItemCycle::default();
+        let first = cycle.next_pending_item(&state, PlayerSlot(0));
+        let second = cycle.next_pending_item(&state, PlayerSlot(0));
+        assert!(first.is_some());
+        assert_ne!(first, second);
+        assert_eq!(cycle.next_pending_item(&state, PlayerSlot(0)), first);
+    }
+
+    #[test]
+    fn test_audio_settings_volume() {
+        let mut audio = AudioSettings {
+            master: 0.5,
+            movement: 0.4,
+            ..AudioSettings::default()
+        };
+        assert!((audio.volume(SoundCue::Move) - 0.2).abs() < f32::EPSILON);
+        assert!((audio.volume(SoundCue::Combat) - 0.5).abs() < f32::EPSILON);
+
+        audio.master = 3.0;
+        assert_eq!(audio.volume(SoundCue::Combat), 1.0);
+        assert_eq!(AudioSettings::muted().volume(SoundCue::YourTurn), 0.0);
     }
 
     #[test]
     fn test_game_settings_resource_clone() {
         let settings = GameSettings::new("Test".to_string());
-        let resource = GameSettingsResource::local(settings, 1);
+        let resource = GameSettingsResource::local(settings, PlayerSlot(1));
         let cloned = resource.clone();
 
         assert_eq!(resource.local_player_id, cloned.local_player_id);
@@ -1023,7 +1286,9 @@ mod tests {
     #[test]
     fn test_pending_action_clear() {
         let mut action = PendingAction {
-            action: Some(PendingActionType::FoundCity { settler_id: 1 }),
+            action: Some(PendingActionType::FoundCity {
+                settler_id: UnitId(1),
+            }),
             target: Some(HexCoord::new(5, 5)),
         };
 
@@ -1040,7 +1305,7 @@ mod tests {
     fn test_pending_action_has_pending_true() {
         let action = PendingAction {
             action: Some(PendingActionType::MoveUnit {
-                unit_id: 1,
+                unit_id: UnitId(1),
                 path: vec![HexCoord::new(0, 0), HexCoord::new(1, 0)],
             }),
             target: None,
@@ -1052,13 +1317,13 @@ mod tests {
     #[test]
     fn test_pending_action_type_move_unit() {
         let action = PendingActionType::MoveUnit {
-            unit_id: 42,
+            unit_id: UnitId(42),
             path: vec![HexCoord::new(0, 0), HexCoord::new(1, 1)],
         };
 
         match action {
             PendingActionType::MoveUnit { unit_id, path } => {
-                assert_eq!(unit_id, 42);
+                assert_eq!(unit_id, UnitId(42));
                 assert_eq!(path.len(), 2);
             }
             _ => panic!("Wrong action type"),
@@ -1068,8 +1333,8 @@ mod tests {
     #[test]
     fn test_pending_action_type_attack_unit() {
         let action = PendingActionType::AttackUnit {
-            attacker_id: 1,
-            defender_id: 2,
+            attacker_id: UnitId(1),
+            defender_id: UnitId(2),
         };
 
         match action {
@@ -1077,8 +1342,8 @@ mod tests {
                 attacker_id,
                 defender_id,
             } => {
-                assert_eq!(attacker_id, 1);
-                assert_eq!(defender_id, 2);
+                assert_eq!(attacker_id, UnitId(1));
+                assert_eq!(defender_id, UnitId(2));
             }
             _ => panic!("Wrong action type"),
         }
@@ -1086,11 +1351,13 @@ mod tests {
 
     #[test]
     fn test_pending_action_type_found_city() {
-        let action = PendingActionType::FoundCity { settler_id: 5 };
+        let action = PendingActionType::FoundCity {
+            settler_id: UnitId(5),
+        };
 
         match action {
             PendingActionType::FoundCity { settler_id } => {
-                assert_eq!(settler_id, 5);
+                assert_eq!(settler_id, UnitId(5));
             }
             _ => panic!("Wrong action type"),
         }
@@ -1099,8 +1366,8 @@ mod tests {
     #[test]
     fn test_pending_action_type_attack_city() {
         let action = PendingActionType::AttackCity {
-            attacker_id: 10,
-            city_id: 20,
+            attacker_id: UnitId(10),
+            city_id: CityId(20),
         };
 
         match action {
@@ -1108,8 +1375,37 @@ mod tests {
                 attacker_id,
                 city_id,
             } => {
-                assert_eq!(attacker_id, 10);
-                assert_eq!(city_id, 20);
+                assert_eq!(attacker_id, UnitId(10));
+                assert_eq!(city_id, CityId(20));
+            }
+            _ => panic!("Wrong action type"),
+        }
+    }
+
+    #[test]
+    fn test_pending_action_choose_promotion() {
+        let mut unit = nostr_nations_core::Unit::new(
+            UnitId(3),
+            PlayerSlot(0),
+            nostr_nations_core::UnitType::Warrior,
+            HexCoord::new(1, 1),
+        );
+        unit.gain_experience(10);
+
+        let mut pending = PendingAction::default();
+        assert!(!pending.select_promotion(Promotion::ShockI));
+
+        pending.choose_promotion(&unit);
+        assert_eq!(pending.target, Some(HexCoord::new(1, 1)));
+        assert!(!pending.select_promotion(Promotion::AccuracyI));
+        assert!(pending.select_promotion(Promotion::ShockI));
+
+        match pending.action {
+            Some(PendingActionType::ChoosePromotion {
+                unit_id, selected, ..
+            }) => {
+                assert_eq!(unit_id, UnitId(3));
+                assert_eq!(selected, Some(Promotion::ShockI));
             }
             _ => panic!("Wrong action type"),
         }
@@ -1130,6 +1426,7 @@ mod tests {
         assert!(ui.hovered_hex.is_none());
         assert!(!ui.command_mode);
         assert!(ui.tooltip.is_none());
+        assert!(ui.combat_preview.is_none());
     }
 
     #[test]
@@ -1139,9 +1436,11 @@ mod tests {
             production_panel_open: true,
             tech_tree_open: true,
             diplomacy_open: true,
+            promotion_panel_open: true,
             hovered_hex: None,
             command_mode: false,
             tooltip: None,
+            combat_preview: None,
         };
 
         ui.close_all();
@@ -1150,6 +1449,7 @@ mod tests {
         assert!(!ui.production_panel_open);
         assert!(!ui.tech_tree_open);
         assert!(!ui.diplomacy_open);
+        assert!(!ui.promotion_panel_open);
     }
 
     #[test]
@@ -1190,6 +1490,32 @@ mod tests {
         assert!(!ui.diplomacy_open);
     }
 
+    // ============================================
+    // NetworkDebugOverlay Tests
+    // ============================================
+
+    #[test]
+    fn test_network_debug_overlay_default_hidden() {
+        let overlay = NetworkDebugOverlay::default();
+
+        assert!(!overlay.visible);
+        assert!(overlay.lines.is_empty());
+    }
+
+    #[test]
+    fn test_network_debug_overlay_toggle_and_text() {
+        let mut overlay = NetworkDebugOverlay::default();
+
+        overlay.toggle();
+        overlay.set_lines(vec!["peers: 2".to_string(), "sync.respond x1".to_string()]);
+
+        assert!(overlay.visible);
+        assert_eq!(overlay.text(), "peers: 2\nsync.respond x1");
+
+        overlay.toggle();
+        assert!(!overlay.visible);
+    }
+
     // ============================================
     // TileEntityMap Tests
     // ============================================
@@ -1302,7 +1628,7 @@ mod tests {
         let unit_id: u64 = 42;
         let entity = Entity::from_raw(1);
 
-        map.insert(unit_id, entity);
+        map.insert(UnitId(unit_id), entity);
 
         assert_eq!(map.units.len(), 1);
     }
@@ -1313,16 +1639,16 @@ mod tests {
         let unit_id: u64 = 42;
         let entity = Entity::from_raw(1);
 
-        map.insert(unit_id, entity);
+        map.insert(UnitId(unit_id), entity);
 
-        assert_eq!(map.get(unit_id), Some(entity));
+        assert_eq!(map.get(UnitId(unit_id)), Some(entity));
     }
 
     #[test]
     fn test_unit_entity_map_get_nonexistent() {
         let map = UnitEntityMap::default();
 
-        assert_eq!(map.get(999), None);
+        assert_eq!(map.get(UnitId(999)), None);
     }
 
     #[test]
@@ -1331,19 +1657,19 @@ mod tests {
         let unit_id: u64 = 42;
         let entity = Entity::from_raw(1);
 
-        map.insert(unit_id, entity);
-        let removed = map.remove(unit_id);
+        map.insert(UnitId(unit_id), entity);
+        let removed = map.remove(UnitId(unit_id));
 
         assert_eq!(removed, Some(entity));
-        assert!(map.get(unit_id).is_none());
+        assert!(map.get(UnitId(unit_id)).is_none());
     }
 
     #[test]
     fn test_unit_entity_map_clear() {
         let mut map = UnitEntityMap::default();
 
-        map.insert(1, Entity::from_raw(1));
-        map.insert(2, Entity::from_raw(2));
+        map.insert(UnitId(1), Entity::from_raw(1));
+        map.insert(UnitId(2), Entity::from_raw(2));
 
         map.clear();
 
@@ -1366,7 +1692,7 @@ mod tests {
         let city_id: u64 = 100;
         let entity = Entity::from_raw(1);
 
-        map.insert(city_id, entity);
+        map.insert(CityId(city_id), entity);
 
         assert_eq!(map.cities.len(), 1);
     }
@@ -1377,16 +1703,16 @@ mod tests {
         let city_id: u64 = 100;
         let entity = Entity::from_raw(1);
 
-        map.insert(city_id, entity);
+        map.insert(CityId(city_id), entity);
 
-        assert_eq!(map.get(city_id), Some(entity));
+        assert_eq!(map.get(CityId(city_id)), Some(entity));
     }
 
     #[test]
     fn test_city_entity_map_get_nonexistent() {
         let map = CityEntityMap::default();
 
-        assert_eq!(map.get(999), None);
+        assert_eq!(map.get(CityId(999)), None);
     }
 
     #[test]
@@ -1395,19 +1721,19 @@ mod tests {
         let city_id: u64 = 100;
         let entity = Entity::from_raw(1);
 
-        map.insert(city_id, entity);
-        let removed = map.remove(city_id);
+        map.insert(CityId(city_id), entity);
+        let removed = map.remove(CityId(city_id));
 
         assert_eq!(removed, Some(entity));
-        assert!(map.get(city_id).is_none());
+        assert!(map.get(CityId(city_id)).is_none());
     }
 
     #[test]
     fn test_city_entity_map_clear() {
         let mut map = CityEntityMap::default();
 
-        map.insert(1, Entity::from_raw(1));
-        map.insert(2, Entity::from_raw(2));
+        map.insert(CityId(1), Entity::from_raw(1));
+        map.insert(CityId(2), Entity::from_raw(2));
 
         map.clear();
 
@@ -1435,11 +1761,11 @@ mod tests {
 
         // Add units
         for i in 0..3 {
-            unit_map.insert(i as u64, Entity::from_raw(100 + i));
+            unit_map.insert(UnitId(i as u64), Entity::from_raw(100 + i));
         }
 
         // Add cities
-        city_map.insert(1, Entity::from_raw(200));
+        city_map.insert(CityId(1), Entity::from_raw(200));
 
         // Verify counts
         assert_eq!(tile_map.len(), 25);
@@ -1448,7 +1774,7 @@ mod tests {
 
         // Remove some
         tile_map.remove(&HexCoord::new(0, 0));
-        unit_map.remove(0);
+        unit_map.remove(UnitId(0));
 
         assert_eq!(tile_map.len(), 24);
         assert_eq!(unit_map.units.len(), 2);
@@ -1460,12 +1786,12 @@ mod tests {
 
         // Select a unit
         let unit_entity = Entity::from_raw(1);
-        selection = SelectedEntity::unit(unit_entity, 42, HexCoord::new(5, 5));
+        selection = SelectedEntity::unit(unit_entity, UnitId(42), HexCoord::new(5, 5));
         assert!(selection.is_unit());
 
         // Switch to city
         let city_entity = Entity::from_raw(2);
-        selection = SelectedEntity::city(city_entity, 100, HexCoord::new(10, 10));
+        selection = SelectedEntity::city(city_entity, CityId(100), HexCoord::new(10, 10));
         assert!(selection.is_city());
         assert!(!selection.is_unit());
 
@@ -1482,26 +1808,26 @@ mod tests {
 
     #[test]
     fn test_turn_state_full_cycle() {
-        let mut turn = CurrentTurn::new(1, 0);
+        let mut turn = CurrentTurn::new(1, PlayerSlot(0));
 
         // Player 0's turn
-        assert!(turn.is_player_turn(0));
+        assert!(turn.is_player_turn(PlayerSlot(0)));
 
         // End turn
         turn.end_turn();
-        assert!(!turn.is_player_turn(0));
+        assert!(!turn.is_player_turn(PlayerSlot(0)));
 
         // Advance to player 1
-        turn.next_turn(1, 1);
-        assert!(!turn.is_player_turn(0));
-        assert!(turn.is_player_turn(1));
+        turn.next_turn(1, PlayerSlot(1));
+        assert!(!turn.is_player_turn(PlayerSlot(0)));
+        assert!(turn.is_player_turn(PlayerSlot(1)));
 
         // End turn
         turn.end_turn();
 
         // Back to player 0, turn 2
-        turn.next_turn(2, 0);
+        turn.next_turn(2, PlayerSlot(0));
         assert_eq!(turn.turn, 2);
-        assert!(turn.is_player_turn(0));
+        assert!(turn.is_player_turn(PlayerSlot(0)));
     }
 }
@@ -0,0 +1,632 @@
+//! Strategic map view.
+//!
+//! Zoomed far out, terrain art turns into noise. The strategic view
+//! replaces it with a flat map: land and water in two neutral colors,
+//! territory in each player's color, an icon per unit and a banner per
+//! city.
+//!
+//! [`MapView`] decides which view is shown. Zooming out past
+//! [`MapView::strategic_zoom`] switches to the strategic view and zooming
+//! back in switches back; the [`InputAction::ToggleStrategicView`] hotkey
+//! overrides the zoom until the zoom crosses the threshold again.
+//!
+//! The strategic view doesn't use sprites. Each layer is one mesh built
+//! from the game state: all of a player's territory is a single mesh, and
+//! units and cities share one icon mesh each. Detailed tile, unit and city
+//! entities are hidden while it is shown.
+
+use bevy::ecs::system::EntityCommands;
+use bevy::prelude::*;
+use bevy::render::mesh::{Indices, PrimitiveTopology};
+use bevy::render::render_asset::RenderAssetUsages;
+use bevy::sprite::{MaterialMesh2dBundle, Mesh2dHandle};
+use nostr_nations_core::visibility::VisibilityFilter;
+use nostr_nations_core::{CityId, GameState, HexCoord, PlayerSlot, UnitId};
+use std::collections::{BTreeMap, HashMap};
+
+use crate::components::{CityComponent, TileComponent, UnitComponent};
+use crate::input::{input_mapping_system, ActionState, InputAction};
+use crate::resources::{CameraState, GameSettingsResource, GameStateResource};
+use crate::systems::{hex_to_world, GameSystemSet};
+
+/// Half the width of a hex, matching the tile layout.
+pub(crate) const HEX_HALF_WIDTH: f32 = 32.0;
+/// Half the height of a hex, matching the tile layout.
+pub(crate) const HEX_HALF_HEIGHT: f32 = 37.0;
+/// Radius of a unit icon.
+const UNIT_ICON_RADIUS: f32 = 10.0;
+/// Size of a city banner.
+const CITY_BANNER_SIZE: Vec2 = Vec2::new(56.0, 14.0);
+
+/// Neutral color of explored land.
+const LAND_COLOR: Color = Color::srgb(0.76, 0.72, 0.60);
+/// Neutral color of explored water.
+const WATER_COLOR: Color = Color::srgb(0.36, 0.52, 0.70);
+/// Opacity of territory drawn over the land.
+const TERRITORY_ALPHA: f32 = 0.55;
+
+/// How the map is drawn.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Hash)]
+pub enum MapRenderMode {
+    /// Terrain, unit and city sprites.
+    #[default]
+    Detailed,
+    /// Flat territory colors, unit icons and city banners.
+    Strategic,
+}
+
+impl MapRenderMode {
+    /// The other mode.
+    pub fn toggled(self) -> Self {
+        match self {
+            MapRenderMode::Detailed => MapRenderMode::Strategic,
+            MapRenderMode::Strategic => MapRenderMode::Detailed,
+        }
+    }
+}
+
+/// Resource choosing between the detailed and strategic map.
+#[derive(Resource, Clone, Debug, PartialEq)]
+pub struct MapView {
+    /// Camera zoom at or below which the strategic view is shown.
+    pub strategic_zoom: f32,
+    /// Mode picked by the zoom level.
+    tier: MapRenderMode,
+    /// Mode picked by the hotkey, until the zoom tier changes.
+    forced: Option<MapRenderMode>,
+}
+
+impl MapView {
+    /// Create a view switching to the strategic map at `strategic_zoom`.
+    pub fn new(strategic_zoom: f32) -> Self {
+        Self {
+            strategic_zoom,
+            tier: MapRenderMode::Detailed,
+            forced: None,
+        }
+    }
+
+    /// The mode shown.
+    pub fn mode(&self) -> MapRenderMode {
+        self.forced.unwrap_or(self.tier)
+    }
+
+    /// Check if the strategic map is shown.
+    pub fn is_strategic(&self) -> bool {
+        self.mode() == MapRenderMode::Strategic
+    }
+
+    /// Mode for a camera zoom level.
+    pub fn tier_for_zoom(&self, zoom: f32) -> MapRenderMode {
+        if zoom <= self.strategic_zoom {
+            MapRenderMode::Strategic
+        } else {
+            MapRenderMode::Detailed
+        }
+    }
+
+    /// Follow the camera zoom.
+    ///
+    /// Crossing the threshold drops any hotkey override.
+    pub fn set_zoom(&mut self, zoom: f32) {
+        let tier = self.tier_for_zoom(zoom);
+        if tier != self.tier {
+            self.tier = tier;
+            self.forced = None;
+        }
+    }
+
+    /// Switch to the other mode, regardless of zoom.
+    pub fn toggle(&mut self) {
+        self.forced = Some(self.mode().toggled());
+    }
+}
+
+impl Default for MapView {
+    fn default() -> Self {
+        Self::new(0.5)
+    }
+}
+
+/// What the strategic map shows, with fog of war applied.
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct StrategicLayout {
+    /// Explored land tiles.
+    pub land: Vec<HexCoord>,
+    /// Explored water tiles.
+    pub water: Vec<HexCoord>,
+    /// Explored owned tiles of each player.
+    pub territory: BTreeMap<PlayerSlot, Vec<HexCoord>>,
+    /// Visible units.
+    pub units: Vec<(UnitId, PlayerSlot, HexCoord)>,
+    /// Visible cities, with their names.
+    pub cities: Vec<(CityId, PlayerSlot, HexCoord, String)>,
+}
+
+impl StrategicLayout {
+    /// Collect what `player_id` can see.
+    ///
+    /// Without fog of war, or once the map is revealed, everything is
+    /// shown.
+    pub fn new(state: &GameState, player_id: PlayerSlot, fog_of_war: bool) -> Self {
+        let all = !fog_of_war || state.revealed;
+        let mut filter = VisibilityFilter::new(player_id);
+        filter.update_from_game_state(state);
+        let player = state.get_player(player_id);
+        let explored = |coord: &HexCoord| {
+            all || filter.can_see_tile(coord) || player.is_some_and(|p| p.has_explored(coord))
+        };
+
+        let mut layout = Self::default();
+        let mut tiles: Vec<_> = state
+            .map
+            .tiles
+            .values()
+            .filter(|t| explored(&t.coord))
+            .collect();
+        tiles.sort_by_key(|t| (t.coord.q, t.coord.r));
+        for tile in tiles {
+            if tile.terrain.is_water() {
+                layout.water.push(tile.coord);
+            } else {
+                layout.land.push(tile.coord);
+            }
+            if let Some(owner) = tile.owner {
+                layout.territory.entry(owner).or_default().push(tile.coord);
+            }
+        }
+
+        let mut units: Vec<_> = state
+            .units
+            .values()
+            .filter(|u| all || filter.can_see_unit(u.id))
+            .map(|u| (u.id, u.owner, u.position))
+            .collect();
+        units.sort_by_key(|(id, _, _)| *id);
+        layout.units = units;
+
+        let mut cities: Vec<_> = state
+            .cities
+            .values()
+            .filter(|c| all || filter.can_see_city(c.id) || explored(&c.position))
+            .map(|c| (c.id, c.owner, c.position, c.name.clone()))
+            .collect();
+        cities.sort_by_key(|(id, _, _, _)| *id);
+        layout.cities = cities;
+
+        layout
+    }
+}
+
+/// Build one flat mesh covering a set of hexes.
+pub fn hex_mesh(coords: &[HexCoord]) -> Mesh {
+    let mut positions = Vec::with_capacity(coords.len() * 7);
+    let mut indices = Vec::with_capacity(coords.len() * 18);
+    for coord in coords {
+        let center = hex_to_world(*coord);
+        let base = positions.len() as u32;
+        positions.push([center.x, center.y, 0.0]);
+        for i in 0..6 {
+            let angle = std::f32::consts::FRAC_PI_3 * i as f32;
+            positions.push([
+                center.x + HEX_HALF_WIDTH * angle.cos(),
+                center.y + HEX_HALF_HEIGHT / 60f32.to_radians().sin() * angle.sin(),
+                0.0,
+            ]);
+        }
+        for i in 0..6 {
+            indices.extend([base, base + 1 + i, base + 1 + (i + 1) % 6]);
+        }
+    }
+    Mesh::new(
+        PrimitiveTopology::TriangleList,
+        RenderAssetUsages::default(),
+    )
+    .with_inserted_attribute(Mesh::ATTRIBUTE_POSITION, positions)
+    .with_inserted_indices(Indices::U32(indices))
+}
+
+/// Marker for entities drawn by the strategic view.
+#[derive(Component, Clone, Copy, Debug, PartialEq, Eq)]
+pub enum StrategicGlyph {
+    /// Explored land or water.
+    Base,
+    /// A player's territory.
+    Territory(PlayerSlot),
+    /// A unit's icon.
+    Unit(UnitId),
+    /// A city's banner.
+    City(CityId),
+}
+
+/// Meshes and materials reused between strategic map rebuilds.
+#[derive(Resource, Default)]
+pub struct StrategicAssets {
+    unit_icon: Option<Handle<Mesh>>,
+    city_banner: Option<Handle<Mesh>>,
+    materials: HashMap<(PlayerSlot, bool), Handle<ColorMaterial>>,
+}
+
+/// Plugin for the strategic map view.
+#[derive(Default)]
+pub struct StrategicMapPlugin {
+    /// Initial view settings.
+    pub view: MapView,
+}
+
+impl Plugin for StrategicMapPlugin {
+    fn build(&self, app: &mut App) {
+        app.insert_resource(self.view.clone());
+        app.init_resource::<StrategicAssets>();
+
+        app.add_systems(
+            Update,
+            map_view_system
+                .after(input_mapping_system)
+                .in_set(GameSystemSet::Input),
+        );
+        app.add_systems(
+            Update,
+            (detail_visibility_system, strategic_map_system)
+                .chain()
+                .after(GameSystemSet::Sync),
+        );
+    }
+}
+
+/// Switch views when the zoom tier changes or the hotkey is pressed.
+pub fn map_view_system(
+    mut view: ResMut<MapView>,
+    camera: Option<Res<CameraState>>,
+    actions: Option<Res<ActionState>>,
+) {
+    let mut next = view.clone();
+    if let Some(camera) = camera {
+        next.set_zoom(camera.zoom);
+    }
+    if actions.is_some_and(|a| a.just_pressed(InputAction::ToggleStrategicView)) {
+        next.toggle();
+    }
+    if view.set_if_neq(next) {
+        info!("Map view: {:?}", view.mode());
+    }
+}
+
+/// Hide tile, unit and city sprites while the strategic map is shown.
+#[allow(clippy::type_complexity)]
+pub fn detail_visibility_system(
+    mut commands: Commands,
+    view: Res<MapView>,
+    all: Query<
+        Entity,
+        Or<(
+            With<TileComponent>,
+            With<UnitComponent>,
+            With<CityComponent>,
+        )>,
+    >,
+    added: Query<
+        Entity,
+        Or<(
+            Added<TileComponent>,
+            Added<UnitComponent>,
+            Added<CityComponent>,
+        )>,
+    >,
+) {
+    let visibility = if view.is_strategic() {
+        Visibility::Hidden
+    } else {
+        Visibility::Inherited
+    };
+    if view.is_changed() {
+        for entity in all.iter() {
+            commands.entity(entity).insert(visibility);
+        }
+    } else if view.is_strategic() {
+        for entity in added.iter() {
+            commands.entity(entity).insert(visibility);
+        }
+    }
+}
+
+/// Rebuild the strategic map when it is shown and the game changes.
+///
+/// Does nothing without mesh and material assets, e.g. in headless apps.
+#[allow(clippy::too_many_arguments)]
+pub fn strategic_map_system(
+    mut commands: Commands,
+    view: Res<MapView>,
+    game_state: Res<GameStateResource>,
+    settings: Res<GameSettingsResource>,
+    glyphs: Query<Entity, With<StrategicGlyph>>,
+    mut cache: ResMut<StrategicAssets>,
+    meshes: Option<ResMut<Assets<Mesh>>>,
+    materials: Option<ResMut<Assets<ColorMaterial>>>,
+) {
+    if !view.is_changed() && !game_state.is_changed() && !settings.is_changed() {
+        return;
+    }
+    for entity in glyphs.iter() {
+        commands.entity(entity).despawn_recursive();
+    }
+    if !view.is_strategic() {
+        return;
+    }
+    let (Some(mut meshes), Some(mut materials)) = (meshes, materials) else {
+        return;
+    };
+    if settings.is_changed() {
+        cache.materials.clear();
+    }
+
+    let layout = StrategicLayout::new(
+        game_state.state(),
+        settings.local_player_id,
+        settings.has_fog_of_war(),
+    );
+    let land = meshes.add(hex_mesh(&layout.land));
+    let water = meshes.add(hex_mesh(&layout.water));
+    spawn_glyph(
+        &mut commands,
+        StrategicGlyph::Base,
+        land,
+        materials.add(LAND_COLOR),
+        Vec3::ZERO,
+    );
+    spawn_glyph(
+        &mut commands,
+        StrategicGlyph::Base,
+        water,
+        materials.add(WATER_COLOR),
+        Vec3::ZERO,
+    );
+
+    let unit_icon = cache
+        .unit_icon
+        .get_or_insert_with(|| meshes.add(Circle::new(UNIT_ICON_RADIUS)))
+        .clone();
+    let city_banner = cache
+        .city_banner
+        .get_or_insert_with(|| meshes.add(Rectangle::from_size(CITY_BANNER_SIZE)))
+        .clone();
+    let mut material = |slot: PlayerSlot, solid: bool| {
+        cache
+            .materials
+            .entry((slot, solid))
+            .or_insert_with(|| {
+                let color = settings.player_appearance(slot).bevy_color();
+                let alpha = if solid { 1.0 } else { TERRITORY_ALPHA };
+                materials.add(color.with_alpha(alpha))
+            })
+            .clone()
+    };
+
+    for (owner, coords) in &layout.territory {
+        let glyph = StrategicGlyph::Territory(*owner);
+        let mesh = meshes.add(hex_mesh(coords));
+        spawn_glyph(&mut commands, glyph, mesh, material(*owner, false), Vec3::Z);
+    }
+
+    for (unit_id, owner, position) in &layout.units {
+        let glyph = StrategicGlyph::Unit(*unit_id);
+        let at = hex_to_world(*position).extend(2.0);
+        spawn_glyph(
+            &mut commands,
+            glyph,
+            unit_icon.clone(),
+            material(*owner, true),
+            at,
+        );
+    }
+
+    for (city_id, owner, position, name) in &layout.cities {
+        let glyph = StrategicGlyph::City(*city_id);
+        let at = (hex_to_world(*position) + Vec2::Y * HEX_HALF_HEIGHT).extend(3.0);
+        spawn_glyph(
+            &mut commands,
+            glyph,
+            city_banner.clone(),
+            material(*owner, true),
+            at,
+        )
+        .with_children(|banner| {
+            banner.spawn(Text2dBundle {
+                text: Text::from_section(
+                    name.clone(),
+                    TextStyle {
+                        font_size: 12.0,
+                        color: Color::WHITE,
+                        ..default()
+                    },
+                ),
+                transform: Transform::from_xyz(0.0, 0.0, 0.1),
+                ..default()
+            });
+        });
+    }
+}
+
+/// Spawn one flat mesh of the strategic map.
+fn spawn_glyph<'a>(
+    commands: &'a mut Commands,
+    glyph: StrategicGlyph,
+    mesh: Handle<Mesh>,
+    material: Handle<ColorMaterial>,
+    translation: Vec3,
+) -> EntityCommands<'a> {
+    commands.spawn((
+        MaterialMesh2dBundle {
+            mesh: Mesh2dHandle(mesh),
+            material,
+            transform: Transform::from_translation(translation),
+            ..default()
+        },
+        glyph,
+    ))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use nostr_nations_core::map::{Map, Tile};
+    use nostr_nations_core::player::{Civilization, Player};
+    use nostr_nations_core::types::{GameId, Npub};
+    use nostr_nations_core::{City, GameSettings, Terrain, Unit, UnitType};
+
+    fn create_state() -> GameState {
+        let mut state = GameState::new(
+            GameId::new("strategic"),
+            GameSettings::new("Strategic".to_string()),
+            [3; 32],
+        );
+        for slot in 0..2 {
+            state.players.push(Player::new(
+                PlayerSlot(slot),
+                Npub::new(format!("npub{}", slot)),
+                format!("Player {}", slot),
+                Civilization::generic(),
+            ));
+        }
+        state.map = Map::filled(20, 10, Terrain::Grassland);
+        for q in 15..20 {
+            for r in 0..10 {
+                state
+                    .map
+                    .set(Tile::new(HexCoord::new(q, r), Terrain::Ocean));
+            }
+        }
+        state
+    }
+
+    #[test]
+    fn test_zoom_tier_and_hotkey_override() {
+        let mut view = MapView::default();
+        assert_eq!(view.mode(), MapRenderMode::Detailed);
+
+        view.set_zoom(0.4);
+        assert!(view.is_strategic());
+
+        view.toggle();
+        assert_eq!(view.mode(), MapRenderMode::Detailed);
+        view.set_zoom(0.3);
+        assert_eq!(view.mode(), MapRenderMode::Detailed);
+
+        // Zooming back in past the threshold hands control back to the zoom
+        view.set_zoom(1.0);
+        assert_eq!(view.mode(), MapRenderMode::Detailed);
+        view.set_zoom(0.25);
+        assert!(view.is_strategic());
+    }
+
+    #[test]
+    fn test_layout_applies_fog() {
+        let mut state = create_state();
+        state.map.get_mut(&HexCoord::new(1, 1)).unwrap().owner = Some(PlayerSlot(0));
+        state.units.insert(
+            UnitId(1),
+            Unit::new(
+                UnitId(1),
+                PlayerSlot(0),
+                UnitType::Warrior,
+                HexCoord::new(1, 1),
+            ),
+        );
+        state.units.insert(
+            UnitId(2),
+            Unit::new(
+                UnitId(2),
+                PlayerSlot(1),
+                UnitType::Warrior,
+                HexCoord::new(2, 1),
+            ),
+        );
+        state.units.insert(
+            UnitId(3),
+            Unit::new(
+                UnitId(3),
+                PlayerSlot(1),
+                UnitType::Warrior,
+                HexCoord::new(12, 8),
+            ),
+        );
+        state.cities.insert(
+            CityId(1),
+            City::new(
+                CityId(1),
+                PlayerSlot(1),
+                "Far".to_string(),
+                HexCoord::new(18, 8),
+                true,
+            ),
+        );
+
+        let layout = StrategicLayout::new(&state, PlayerSlot(0), true);
+        let unit_ids: Vec<_> = layout.units.iter().map(|(id, _, _)| *id).collect();
+        assert_eq!(unit_ids, vec![UnitId(1), UnitId(2)]);
+        assert!(layout.cities.is_empty());
+        assert_eq!(layout.territory[&PlayerSlot(0)], vec![HexCoord::new(1, 1)]);
+        assert!(!layout.land.contains(&HexCoord::new(12, 8)));
+
+        let open = StrategicLayout::new(&state, PlayerSlot(0), false);
+        assert_eq!(open.units.len(), 3);
+        assert_eq!(open.cities.len(), 1);
+        assert_eq!(open.land.len() + open.water.len(), 200);
+        assert_eq!(open.water.len(), 50);
+    }
+
+    #[test]
+    fn test_hex_mesh_has_a_fan_per_hex() {
+        let mesh = hex_mesh(&[HexCoord::new(0, 0), HexCoord::new(1, 0)]);
+        assert_eq!(mesh.count_vertices(), 14);
+        assert_eq!(mesh.indices().unwrap().len(), 36);
+    }
+
+    #[test]
+    fn test_strategic_view_swaps_entities() {
+        let mut app = App::new();
+        app.add_plugins(MinimalPlugins);
+        app.init_resource::<Assets<Mesh>>();
+        app.init_resource::<Assets<ColorMaterial>>();
+        app.insert_resource(GameStateResource::default());
+        app.insert_resource(GameSettingsResource::default());
+        app.add_plugins(StrategicMapPlugin::default());
+        app.world_mut()
+            .resource_mut::<GameStateResource>()
+            .state_mut()
+            .map
+            .set(Tile::new(HexCoord::new(0, 0), Terrain::Grassland));
+        let tile = app
+            .world_mut()
+            .spawn(TileComponent::new(Tile::new(
+                HexCoord::new(0, 0),
+                Terrain::Grassland,
+            )))
+            .id();
+        app.update();
+
+        let glyphs = |app: &mut App| {
+            app.world_mut()
+                .query::<&StrategicGlyph>()
+                .iter(app.world())
+                .count()
+        };
+        assert_eq!(glyphs(&mut app), 0);
+
+        app.world_mut().resource_mut::<MapView>().toggle();
+        app.update();
+        assert_eq!(glyphs(&mut app), 2);
+        assert_eq!(
+            app.world().get::<Visibility>(tile),
+            Some(&Visibility::Hidden)
+        );
+
+        app.world_mut().resource_mut::<MapView>().toggle();
+        app.update();
+        assert_eq!(glyphs(&mut app), 0);
+        assert_eq!(
+            app.world().get::<Visibility>(tile),
+            Some(&Visibility::Inherited)
+        );
+    }
+}
@@ -4,17 +4,25 @@
 //! They query for entities with specific components and update them.
 
 use bevy::prelude::*;
-use nostr_nations_core::{events::GameAction, replay::ActionEffect, HexCoord};
+use nostr_nations_core::{
+    events::GameAction, replay::ActionEffect, HexCoord, PendingItem, PlayerSlot, TurnAdvisor,
+};
 
 use crate::components::{
-    CityComponent, LocalPlayerOwned, MovementAnimation, PositionComponent, SelectionComponent,
-    TileComponent, UnitComponent, VisibleComponent,
+    CityComponent, LocalPlayerOwned, MovementAnimation, NetworkDebugOverlayText, PositionComponent,
+    SelectionComponent, TileComponent, UnitComponent, VisibleComponent,
 };
+use crate::input::{ActionState, HexCursor, InputAction};
+use crate::plugins::ActionEffectEvent;
 use crate::resources::{
-    CityEntityMap, CurrentTurn, GameSettingsResource, GameStateResource, PendingAction,
-    PendingActionType, SelectedEntity, TileEntityMap, UnitEntityMap,
+    CityEntityMap, CombatPreviewTooltip, CurrentTurn, GameSettingsResource, GameStateResource,
+    NetworkDebugOverlay, PendingAction, PendingActionType, PendingItemCycle, SelectedEntity,
+    TileEntityMap, UiState, UnitEntityMap,
 };
 
+/// Default key that toggles the network debug overlay.
+pub const DEBUG_OVERLAY_KEY: KeyCode = KeyCode::F9;
+
 /// System that processes game tick updates.
 ///
 /// This system updates the game state based on elapsed time and
@@ -24,17 +32,25 @@ pub fn game_tick_system(
     mut game_state: ResMut<GameStateResource>,
     mut current_turn: ResMut<CurrentTurn>,
     settings: Res<GameSettingsResource>,
+    mut effects: EventWriter<ActionEffectEvent>,
 ) {
-    // Update turn timer if enabled
-    if current_turn.time_remaining.is_some() {
+    // Update turn timer if enabled; it halts while the game is paused
+    if current_turn.time_remaining.is_some() && !game_state.engine.state.pause.is_paused() {
         let delta = time.delta_seconds();
         if current_turn.update_timer(delta) {
             // Timer expired - auto end turn
             if settings.local_player_id == current_turn.current_player {
                 // End turn for local player
-                let _ = game_state
+                if let Ok(action_result) = game_state
                     .engine
-                    .apply_action(settings.local_player_id, &GameAction::EndTurn);
+                    .apply_action(settings.local_player_id, &GameAction::EndTurn)
+                {
+                    send_effects(
+                        &mut effects,
+                        settings.local_player_id,
+                        &action_result.effects,
+                    );
+                }
             }
         }
     }
@@ -48,25 +64,25 @@ pub fn game_tick_system(
 
 /// System that handles entity selection.
 ///
-/// This system manages the selection state when entities are clicked
-/// and updates the SelectionComponent markers accordingly.
+/// This system manages the selection state when the select action fires
+/// and updates the SelectionComponent markers accordingly. The cancel
+/// action clears the selection.
 #[allow(clippy::too_many_arguments)]
 pub fn selection_system(
     mut commands: Commands,
-    mouse_button: Res<ButtonInput<MouseButton>>,
-    _selected: ResMut<SelectedEntity>,
+    actions: Res<ActionState>,
+    cursor: Res<HexCursor>,
+    mut selected: ResMut<SelectedEntity>,
     current_turn: Res<CurrentTurn>,
     settings: Res<GameSettingsResource>,
     _units_query: Query<(Entity, &UnitComponent, &PositionComponent), Without<SelectionComponent>>,
     _cities_query: Query<(Entity, &CityComponent, &PositionComponent), Without<SelectionComponent>>,
     _tiles_query: Query<(Entity, &TileComponent, &PositionComponent), Without<SelectionComponent>>,
     selected_query: Query<Entity, With<SelectionComponent>>,
-    _tile_map: Res<TileEntityMap>,
-    // Note: In a real implementation, you'd need to convert mouse position to hex coordinates
-    // This is simplified for the example
+    tile_map: Res<TileEntityMap>,
 ) {
-    // Only process selection on left click
-    if !mouse_button.just_pressed(MouseButton::Left) {
+    let select = actions.just_pressed(InputAction::Select);
+    if !select && !actions.just_pressed(InputAction::Cancel) {
         return;
     }
 
@@ -80,15 +96,27 @@ pub fn selection_system(
     for entity in selected_query.iter() {
         commands.entity(entity).remove::<SelectionComponent>();
     }
+    if !select {
+        selected.clear();
+        return;
+    }
 
-    // In a real implementation, you would:
+    // Select the tile under the gamepad cursor.
+    //
+    // In a real implementation, mouse clicks would also:
     // 1. Convert mouse screen position to world position
     // 2. Convert world position to hex coordinate
     // 3. Find entities at that coordinate
     // 4. Prioritize units > cities > tiles
     // 5. Select the appropriate entity
-
-    // For now, this is a placeholder that would be connected to actual input handling
+    if let Some(coord) = cursor.coord {
+        if let Some(entity) = tile_map.get(&coord) {
+            commands
+                .entity(entity)
+                .insert(SelectionComponent::primary());
+            *selected = SelectedEntity::tile(entity, coord);
+        }
+    }
 }
 
 /// System that processes selection changes and updates UI.
@@ -127,10 +155,48 @@ pub fn selection_changed_system(
     }
 }
 
+/// System that jumps to the next pending item on its hotkey.
+///
+/// Moves the hex cursor to the item and selects it if it is a unit.
+pub fn next_pending_item_system(
+    actions: Res<ActionState>,
+    game_state: Res<GameStateResource>,
+    settings: Res<GameSettingsResource>,
+    unit_map: Res<UnitEntityMap>,
+    mut cycle: ResMut<PendingItemCycle>,
+    mut cursor: ResMut<HexCursor>,
+    mut selected: ResMut<SelectedEntity>,
+) {
+    if !actions.just_pressed(InputAction::NextPendingItem) {
+        return;
+    }
+    let Some(item) = cycle.next_pending_item(game_state.state(), settings.local_player_id) else {
+        return;
+    };
+
+    if let Some(position) = item.position() {
+        cursor.coord = Some(position);
+    }
+    match item {
+        PendingItem::UnitNeedsOrders {
+            unit_id, position, ..
+        }
+        | PendingItem::PromotionAvailable {
+            unit_id, position, ..
+        } => {
+            if let Some(entity) = unit_map.get(unit_id) {
+                *selected = SelectedEntity::unit(entity, unit_id, position);
+            }
+        }
+        PendingItem::ChooseResearch | PendingItem::CityIdle { .. } => {}
+    }
+}
+
 /// System that updates visibility (fog of war).
 ///
 /// This system calculates which tiles are visible to the current player
 /// based on unit positions and updates VisibleComponent accordingly.
+/// Once the map is revealed at game end, every tile is shown.
 pub fn visibility_system(
     game_state: Res<GameStateResource>,
     settings: Res<GameSettingsResource>,
@@ -138,8 +204,8 @@ pub fn visibility_system(
     units_query: Query<(&UnitComponent, &PositionComponent), With<LocalPlayerOwned>>,
     cities_query: Query<(&CityComponent, &PositionComponent), With<LocalPlayerOwned>>,
 ) {
-    // Skip if fog of war is disabled
-    if !settings.has_fog_of_war() {
+    // Skip if fog of war is disabled or the map has been revealed
+    if !settings.has_fog_of_war() || game_state.state().revealed {
         // Make all tiles visible
         for (_, mut visible) in tiles_query.iter_mut() {
             visible.in_sight = true;
@@ -196,6 +262,63 @@ pub fn visibility_system(
     }
 }
 
+/// System that toggles the network debug overlay (F9 by default).
+pub fn debug_overlay_toggle_system(
+    actions: Res<ActionState>,
+    mut overlay: ResMut<NetworkDebugOverlay>,
+) {
+    if actions.just_pressed(InputAction::ToggleDebugOverlay) {
+        overlay.toggle();
+    }
+}
+
+/// System that keeps the network debug overlay text in sync.
+///
+/// Spawns the text node when the overlay is shown, updates it when the
+/// lines change, and despawns it when hidden.
+pub fn debug_overlay_render_system(
+    mut commands: Commands,
+    overlay: Res<NetworkDebugOverlay>,
+    mut text_query: Query<(Entity, &mut Text), With<NetworkDebugOverlayText>>,
+) {
+    if !overlay.is_changed() {
+        return;
+    }
+
+    if !overlay.visible {
+        for (entity, _) in text_query.iter() {
+            commands.entity(entity).despawn_recursive();
+        }
+        return;
+    }
+
+    if let Ok((_, mut text)) = text_query.get_single_mut() {
+        if let Some(section) = text.sections.first_mut() {
+            section.value = overlay.text();
+        }
+        return;
+    }
+
+    commands.spawn((
+        TextBundle::from_section(
+            overlay.text(),
+            TextStyle {
+                font_size: 14.0,
+                color: Color::WHITE,
+                ..default()
+            },
+        )
+        .with_style(Style {
+            position_type: PositionType::Absolute,
+            top: Val::Px(8.0),
+            left: Val::Px(8.0),
+            ..default()
+        })
+        .with_background_color(Color::srgba(0.0, 0.0, 0.0, 0.6)),
+        NetworkDebugOverlayText,
+    ));
+}
+
 /// System that manages turn transitions.
 ///
 /// This system handles end turn actions and transitions between players.
@@ -203,14 +326,13 @@ pub fn turn_system(
     mut game_state: ResMut<GameStateResource>,
     mut current_turn: ResMut<CurrentTurn>,
     settings: Res<GameSettingsResource>,
-    keyboard: Res<ButtonInput<KeyCode>>,
+    actions: Res<ActionState>,
+    mut cycle: ResMut<PendingItemCycle>,
     mut units_query: Query<&mut UnitComponent, With<LocalPlayerOwned>>,
+    mut effects: EventWriter<ActionEffectEvent>,
 ) {
-    // Check for end turn key press (Enter or E)
-    let end_turn_pressed =
-        keyboard.just_pressed(KeyCode::Enter) || keyboard.just_pressed(KeyCode::KeyE);
-
-    if !end_turn_pressed {
+    // Check for the end turn action (Enter or E by default)
+    if !actions.just_pressed(InputAction::EndTurn) {
         return;
     }
 
@@ -219,6 +341,17 @@ pub fn turn_system(
         return;
     }
 
+    // Send the player to anything they must decide first
+    if settings.block_end_turn_on_pending {
+        let advisor = TurnAdvisor::new(game_state.state(), settings.local_player_id);
+        let blocking = advisor.mandatory().next().cloned();
+        if let Some(item) = blocking {
+            info!("Cannot end turn yet: {:?}", item);
+            cycle.current = Some(item);
+            return;
+        }
+    }
+
     // Apply end turn action
     let result = game_state
         .engine
@@ -226,6 +359,12 @@ pub fn turn_system(
 
     match result {
         Ok(action_result) => {
+            send_effects(
+                &mut effects,
+                settings.local_player_id,
+                &action_result.effects,
+            );
+
             // Process action effects
             for effect in action_result.effects {
                 match effect {
@@ -266,6 +405,7 @@ pub fn pending_action_system(
     current_turn: Res<CurrentTurn>,
     mut unit_map: ResMut<UnitEntityMap>,
     mut commands: Commands,
+    mut effects: EventWriter<ActionEffectEvent>,
 ) {
     // Only process actions on local player's turn
     if !current_turn.is_player_turn(settings.local_player_id) {
@@ -307,6 +447,16 @@ pub fn pending_action_system(
             city_id,
             random: 0.5,
         },
+        PendingActionType::ChoosePromotion {
+            unit_id,
+            selected: Some(promotion),
+            ..
+        } => GameAction::ChoosePromotion { unit_id, promotion },
+        waiting @ PendingActionType::ChoosePromotion { selected: None, .. } => {
+            // Keep waiting for the player to pick from the promotion panel
+            pending.action = Some(waiting);
+            return;
+        }
     };
 
     let result = game_state
@@ -316,9 +466,14 @@ pub fn pending_action_system(
     match result {
         Ok(action_result) => {
             // Process action effects
-            for effect in action_result.effects {
-                process_action_effect(&effect, &mut commands, &mut unit_map);
+            for effect in &action_result.effects {
+                process_action_effect(effect, &mut commands, &mut unit_map);
             }
+            send_effects(
+                &mut effects,
+                settings.local_player_id,
+                &action_result.effects,
+            );
         }
         Err(e) => {
             error!("Action failed: {:?}", e);
@@ -328,6 +483,63 @@ pub fn pending_action_system(
     pending.clear();
 }
 
+/// System that previews a pending attack in the tooltip.
+///
+/// The preview is recomputed whenever the pending action changes and
+/// cleared once no attack is pending.
+pub fn combat_preview_system(
+    game_state: Res<GameStateResource>,
+    pending: Res<PendingAction>,
+    ui: Option<ResMut<UiState>>,
+) {
+    let Some(mut ui) = ui else {
+        return;
+    };
+    if !pending.is_changed() {
+        return;
+    }
+
+    let preview = match pending.action {
+        Some(PendingActionType::AttackUnit {
+            attacker_id,
+            defender_id,
+        }) => game_state
+            .engine
+            .preview_attack(attacker_id, defender_id)
+            .map(|preview| CombatPreviewTooltip {
+                attacker_id,
+                defender_id,
+                preview,
+            }),
+        _ => None,
+    };
+
+    match preview {
+        Some(preview) => {
+            ui.tooltip = Some(preview.text());
+            ui.combat_preview = Some(preview);
+        }
+        None => {
+            if ui.combat_preview.take().is_some() {
+                ui.tooltip = None;
+            }
+        }
+    }
+}
+
+/// Helper function to forward action effects to systems reading
+/// [`ActionEffectEvent`]s.
+fn send_effects(
+    writer: &mut EventWriter<ActionEffectEvent>,
+    player_id: PlayerSlot,
+    effects: &[ActionEffect],
+) {
+    writer.send_batch(effects.iter().map(|effect| ActionEffectEvent {
+        player_id,
+        effect: effect.clone(),
+    }));
+}
+
 /// Helper function to process action effects and update ECS state.
 fn process_action_effect(
     effect: &ActionEffect,
@@ -383,6 +595,81 @@ fn process_action_effect(
         } => {
             info!("City {} grew to population {}", city_id, new_population);
         }
+        ActionEffect::UnitHealed {
+            unit_id,
+            amount,
+            new_health,
+        } => {
+            info!("Unit {} healed {}, health: {}", unit_id, amount, new_health);
+        }
+        ActionEffect::PromotionAvailable { unit_id } => {
+            info!("Unit {} can be promoted", unit_id);
+        }
+        ActionEffect::UnitPromoted {
+            unit_id,
+            promotion,
+            new_health,
+        } => {
+            info!(
+                "Unit {} promoted with {:?}, health: {}",
+                unit_id, promotion, new_health
+            );
+        }
+        ActionEffect::TileClaimed {
+            city_id,
+            owner,
+            coord,
+        } => {
+            info!(
+                "City {} (player {}) claimed tile {:?}",
+                city_id, owner, coord
+            );
+        }
+        ActionEffect::RoadStarted {
+            unit_id,
+            road,
+            turns,
+        } => {
+            info!("Unit {} started {:?} ({} turns)", unit_id, road, turns);
+        }
+        ActionEffect::RoadBuilt { coord, road } => {
+            info!("{:?} built at {:?}", road, coord);
+        }
+        ActionEffect::FeatureClearing {
+            unit_id,
+            feature,
+            turns,
+        } => {
+            info!("Unit {} clearing {:?} ({} turns)", unit_id, feature, turns);
+        }
+        ActionEffect::FeatureCleared {
+            coord,
+            feature,
+            city_id,
+            production,
+        } => {
+            info!(
+                "{:?} cleared at {:?} ({} production to {:?})",
+                feature, coord, production, city_id
+            );
+        }
+        ActionEffect::ResourceDepleted {
+            player_id,
+            coord,
+            resource,
+        } => {
+            info!(
+                "{:?} at {:?} ran dry for player {}",
+                resource, coord, player_id
+            );
+        }
+        ActionEffect::TerrainChanged {
+            coord,
+            terrain,
+            feature,
+        } => {
+            info!("Climate changed {:?} to {:?} {:?}", coord, terrain, feature);
+        }
         ActionEffect::TechResearched { player_id, tech_id } => {
             info!("Player {} researched {}", player_id, tech_id);
         }
@@ -395,6 +682,135 @@ fn process_action_effect(
         } => {
             info!("Game ended! Winner: {} by {}", winner_id, victory_type);
         }
+        ActionEffect::PlayerConceded {
+            player_id,
+            to_player,
+        } => {
+            info!("Player {} conceded (cities to {:?})", player_id, to_player);
+        }
+        ActionEffect::PlayerDefeated { player_id, rule_id } => {
+            info!("Player {} defeated by scenario rule {}", player_id, rule_id);
+        }
+        ActionEffect::CityTransferred { city_id, new_owner } => {
+            info!("City {} transferred to player {}", city_id, new_owner);
+        }
+        ActionEffect::CityRazed { city_id } => {
+            info!("City {} razed", city_id);
+        }
+        ActionEffect::PauseRequested { player_id, reason } => {
+            info!("Player {} requested a pause: {:?}", player_id, reason);
+        }
+        ActionEffect::PauseVoted { player_id, approve } => {
+            info!("Player {} voted on pause: {}", player_id, approve);
+        }
+        ActionEffect::GamePaused { requested_by } => {
+            info!("Game paused at the request of player {}", requested_by);
+        }
+        ActionEffect::PauseRejected { requested_by } => {
+            info!("Pause requested by player {} was rejected", requested_by);
+        }
+        ActionEffect::PlayerSubstituted { player_id, pubkey } => {
+            info!("Player {} is now controlled by {}", player_id, pubkey);
+        }
+        ActionEffect::MapRevealed { player_id } => {
+            info!("Player {} revealed the map", player_id);
+        }
+        ActionEffect::ResearchAgreementCompleted {
+            player_id,
+            partner,
+            science,
+        } => {
+            info!(
+                "Player {} gained {} science from the agreement with player {}",
+                player_id, science, partner
+            );
+        }
+        ActionEffect::ResearchAgreementBroken { player_id, partner } => {
+            info!(
+                "Player {} broke the research agreement with player {}",
+                player_id, partner
+            );
+        }
+        ActionEffect::CityBombarded {
+            city_id,
+            unit_id,
+            damage,
+        } => {
+            info!(
+                "City {} bombarded unit {} for {} damage",
+                city_id, unit_id, damage
+            );
+        }
+        ActionEffect::CityCaptured {
+            city_id,
+            player_id,
+            previous_owner,
+            population_lost,
+            buildings_destroyed,
+        } => {
+            info!(
+                "Player {} captured city {} from player {} (lost {} population, {} buildings)",
+                player_id,
+                city_id,
+                previous_owner,
+                population_lost,
+                buildings_destroyed.len()
+            );
+        }
+        ActionEffect::CaptureResolved { city_id, choice } => {
+            info!("Captured city {} resolved: {:?}", city_id, choice);
+        }
+        ActionEffect::UnitGifted { unit_id, from, to } => {
+            info!("Player {} gifted unit {} to player {}", from, unit_id, to);
+        }
+        ActionEffect::EraEntered { player_id, era } => {
+            info!("Player {} entered the {}", player_id, era);
+        }
+        ActionEffect::FirstContact {
+            player_id,
+            met_player,
+        } => {
+            info!("Player {} met player {}", player_id, met_player);
+        }
+        ActionEffect::Circumnavigated { player_id, gold } => {
+            info!(
+                "Player {} circumnavigated the world for {} gold",
+                player_id, gold
+            );
+        }
+        ActionEffect::BuildingCompleted { city_id, building } => {
+            info!("City {} completed {:?}", city_id, building);
+        }
+        ActionEffect::WonderCompleted {
+            city_id,
+            player_id,
+            wonder,
+        } => {
+            info!(
+                "Player {} completed {:?} in city {}",
+                player_id, wonder, city_id
+            );
+        }
+        ActionEffect::WonderLost {
+            city_id,
+            wonder,
+            gold,
+            ..
+        } => {
+            info!(
+                "City {} lost the race for {:?}, refunded {} gold",
+                city_id, wonder, gold
+            );
+        }
+        ActionEffect::GameResumed {
+            player_id,
+            countdown_secs,
+        } => {
+            info!(
+                "Player {} resumed the game, play continues in {}s",
+                player_id, countdown_secs
+            );
+        }
     }
 }
 
@@ -585,7 +1001,7 @@ pub fn despawn_removed_entities_system(
 /// Convert a hex coordinate to world position.
 ///
 /// Uses pointy-top hex layout with odd-q offset coordinates.
-fn hex_to_world(coord: HexCoord) -> Vec2 {
+pub(crate) fn hex_to_world(coord: HexCoord) -> Vec2 {
     // Hex dimensions (these would typically come from a config)
     let hex_width = 64.0f32;
     let hex_height = 74.0f32; // height = width * sqrt(3) / 2 * 2 for pointy-top
@@ -634,6 +1050,7 @@ pub enum GameSystemSet {
 mod tests {
     use super::*;
     use crate::components::{CityBundle, TileBundle, TileComponent, UnitBundle};
+    use nostr_nations_core::types::{CityId, Npub, UnitId};
     use nostr_nations_core::{unit::UnitType, City, Terrain, Tile, Unit};
 
     // ============================================
@@ -790,7 +1207,12 @@ mod tests {
         let tile_entity = world.spawn(TileBundle::new(tile)).id();
 
         // Spawn a unit
-        let unit = Unit::new(1, 0, UnitType::Warrior, HexCoord::new(0, 0));
+        let unit = Unit::new(
+            UnitId(1),
+            PlayerSlot(0),
+            UnitType::Warrior,
+            HexCoord::new(0, 0),
+        );
         let unit_entity = world.spawn(UnitBundle::new(unit)).id();
 
         // Verify entities exist
@@ -810,7 +1232,12 @@ mod tests {
 
         // Spawn multiple units at different positions
         for i in 0..5 {
-            let unit = Unit::new(i, 0, UnitType::Warrior, HexCoord::new(i as i32, 0));
+            let unit = Unit::new(
+                UnitId(i),
+                PlayerSlot(0),
+                UnitType::Warrior,
+                HexCoord::new(i as i32, 0),
+            );
             world.spawn(UnitBundle::new(unit));
         }
 
@@ -826,11 +1253,21 @@ mod tests {
         let mut world = World::new();
 
         // Spawn player-owned unit
-        let unit1 = Unit::new(1, 0, UnitType::Warrior, HexCoord::new(0, 0));
+        let unit1 = Unit::new(
+            UnitId(1),
+            PlayerSlot(0),
+            UnitType::Warrior,
+            HexCoord::new(0, 0),
+        );
         world.spawn((UnitBundle::new(unit1), LocalPlayerOwned));
 
         // Spawn enemy unit
-        let unit2 = Unit::new(2, 1, UnitType::Warrior, HexCoord::new(1, 1));
+        let unit2 = Unit::new(
+            UnitId(2),
+            PlayerSlot(1),
+            UnitType::Warrior,
+            HexCoord::new(1, 1),
+        );
         world.spawn(UnitBundle::new(unit2));
 
         // Query only local player's units
@@ -845,7 +1282,12 @@ mod tests {
     fn test_despawn_entity() {
         let mut world = World::new();
 
-        let unit = Unit::new(1, 0, UnitType::Warrior, HexCoord::new(0, 0));
+        let unit = Unit::new(
+            UnitId(1),
+            PlayerSlot(0),
+            UnitType::Warrior,
+            HexCoord::new(0, 0),
+        );
         let entity = world.spawn(UnitBundle::new(unit)).id();
 
         // Verify exists
@@ -858,6 +1300,201 @@ mod tests {
         assert!(world.get_entity(entity).is_none());
     }
 
+    // ============================================
+    // Action Effect Event Tests
+    // ============================================
+
+    #[test]
+    fn test_pending_action_sends_effect_events() {
+        use crate::plugins::{ActionEffectEvent, GameStatePlugin};
+        use bevy::ecs::event::Events;
+        use nostr_nations_core::player::{Civilization, Player};
+        use nostr_nations_core::Map;
+
+        let mut app = App::new();
+        app.add_plugins(MinimalPlugins);
+        app.add_plugins(GameStatePlugin::default());
+        app.add_systems(Update, pending_action_system);
+
+        let unit_id = {
+            let mut game_state = app.world_mut().resource_mut::<GameStateResource>();
+            let state = game_state.state_mut();
+            for id in 0..2 {
+                state
+                    .add_player(Player::new(
+                        PlayerSlot(id),
+                        Npub(format!("npub{}", id)),
+                        format!("Player {}", id),
+                        Civilization::generic(),
+                    ))
+                    .unwrap();
+            }
+            state.start().unwrap();
+            state.map = Map::filled(10, 10, Terrain::Grassland);
+            let id = state.allocate_unit_id();
+            state.units.insert(
+                id,
+                Unit::new(id, PlayerSlot(0), UnitType::Warrior, HexCoord::new(2, 2)),
+            );
+            id
+        };
+
+        app.world_mut().resource_mut::<PendingAction>().action =
+            Some(PendingActionType::MoveUnit {
+                unit_id,
+                path: vec![HexCoord::new(3, 2)],
+            });
+        app.update();
+
+        let events = app.world().resource::<Events<ActionEffectEvent>>();
+        let mut reader = events.get_reader();
+        let sent: Vec<&ActionEffectEvent> = reader.read(events).collect();
+        assert!(sent.iter().all(|event| event.player_id == PlayerSlot(0)));
+        assert!(sent.iter().any(|event| matches!(
+            event.effect,
+            ActionEffect::UnitMoved { unit_id: id, to, .. }
+                if id == unit_id && to == HexCoord::new(3, 2)
+        )));
+    }
+
+    // ============================================
+    // Combat Preview Tests
+    // ============================================
+
+    #[test]
+    fn test_combat_preview_system_fills_tooltip() {
+        use crate::plugins::GameStatePlugin;
+
+        let mut app = App::new();
+        app.add_plugins(MinimalPlugins);
+        app.add_plugins(GameStatePlugin::default());
+        app.insert_resource(UiState::default());
+        app.add_systems(Update, combat_preview_system);
+
+        {
+            let mut game_state = app.world_mut().resource_mut::<GameStateResource>();
+            let state = game_state.state_mut();
+            state.units.insert(
+                UnitId(1),
+                Unit::new(
+                    UnitId(1),
+                    PlayerSlot(0),
+                    UnitType::Warrior,
+                    HexCoord::new(0, 0),
+                ),
+            );
+            state.units.insert(
+                UnitId(2),
+                Unit::new(
+                    UnitId(2),
+                    PlayerSlot(1),
+                    UnitType::Warrior,
+                    HexCoord::new(1, 0),
+                ),
+            );
+        }
+
+        app.world_mut().resource_mut::<PendingAction>().action =
+            Some(PendingActionType::AttackUnit {
+                attacker_id: UnitId(1),
+                defender_id: UnitId(2),
+            });
+        app.update();
+
+        let ui = app.world().resource::<UiState>();
+        let preview = ui.combat_preview.as_ref().unwrap();
+        assert_eq!(
+            (preview.attacker_id, preview.defender_id),
+            (UnitId(1), UnitId(2))
+        );
+        assert!(preview.preview.defender_damage.max > 0);
+        assert_eq!(ui.tooltip.as_deref(), Some(preview.text().as_str()));
+
+        app.world_mut().resource_mut::<PendingAction>().clear();
+        app.update();
+
+        let ui = app.world().resource::<UiState>();
+        assert!(ui.combat_preview.is_none());
+        assert!(ui.tooltip.is_none());
+    }
+
+    // ============================================
+    // Debug Overlay Tests
+    // ============================================
+
+    fn create_overlay_app() -> App {
+        let mut app = App::new();
+        app.add_plugins(MinimalPlugins);
+        app.init_resource::<ButtonInput<KeyCode>>();
+        app.insert_resource(GameSettingsResource::default());
+        app.insert_resource(NetworkDebugOverlay::default());
+        app.add_plugins(crate::input::InputMappingPlugin);
+        app.add_systems(
+            Update,
+            (debug_overlay_toggle_system, debug_overlay_render_system)
+                .chain()
+                .after(crate::input::input_mapping_system),
+        );
+        app
+    }
+
+    fn overlay_text_count(app: &mut App) -> usize {
+        let world = app.world_mut();
+        world
+            .query_filtered::<Entity, With<NetworkDebugOverlayText>>()
+            .iter(world)
+            .count()
+    }
+
+    #[test]
+    fn test_debug_overlay_toggles_on_f9() {
+        let mut app = create_overlay_app();
+
+        app.world_mut()
+            .resource_mut::<ButtonInput<KeyCode>>()
+            .press(DEBUG_OVERLAY_KEY);
+        app.update();
+
+        assert!(app.world().resource::<NetworkDebugOverlay>().visible);
+        assert_eq!(overlay_text_count(&mut app), 1);
+    }
+
+    #[test]
+    fn test_debug_overlay_despawns_when_hidden() {
+        let mut app = create_overlay_app();
+        app.world_mut()
+            .resource_mut::<NetworkDebugOverlay>()
+            .visible = true;
+        app.update();
+        assert_eq!(overlay_text_count(&mut app), 1);
+
+        app.world_mut()
+            .resource_mut::<NetworkDebugOverlay>()
+            .visible = false;
+        app.update();
+        assert_eq!(overlay_text_count(&mut app), 0);
+    }
+
+    #[test]
+    fn test_debug_overlay_updates_text() {
+        let mut app = create_overlay_app();
+        app.world_mut()
+            .resource_mut::<NetworkDebugOverlay>()
+            .visible = true;
+        app.update();
+
+        app.world_mut()
+            .resource_mut::<NetworkDebugOverlay>()
+            .set_lines(vec!["peers: 3".to_string()]);
+        app.update();
+
+        let world = app.world_mut();
+        let text = world
+            .query_filtered::<&Text, With<NetworkDebugOverlayText>>()
+            .single(world);
+        assert_eq!(text.sections[0].value, "peers: 3");
+    }
+
     // ============================================
     // Resource State Tests
     // ============================================
@@ -881,7 +1518,7 @@ mod tests {
     #[test]
     fn test_modify_current_turn_resource() {
         let mut world = World::new();
-        world.insert_resource(CurrentTurn::new(1, 0));
+        world.insert_resource(CurrentTurn::new(1, PlayerSlot(0)));
 
         // End turn
         {
@@ -900,17 +1537,22 @@ mod tests {
         world.insert_resource(UnitEntityMap::default());
 
         // Spawn unit and track in map
-        let unit = Unit::new(42, 0, UnitType::Warrior, HexCoord::new(0, 0));
+        let unit = Unit::new(
+            UnitId(42),
+            PlayerSlot(0),
+            UnitType::Warrior,
+            HexCoord::new(0, 0),
+        );
         let entity = world.spawn(UnitBundle::new(unit)).id();
 
         {
             let mut unit_map = world.resource_mut::<UnitEntityMap>();
-            unit_map.insert(42, entity);
+            unit_map.insert(UnitId(42), entity);
         }
 
         // Verify can retrieve
         let unit_map = world.resource::<UnitEntityMap>();
-        assert_eq!(unit_map.get(42), Some(entity));
+        assert_eq!(unit_map.get(UnitId(42)), Some(entity));
     }
 
     #[test]
@@ -919,12 +1561,17 @@ mod tests {
         world.insert_resource(SelectedEntity::none());
 
         // Select unit
-        let unit = Unit::new(1, 0, UnitType::Warrior, HexCoord::new(0, 0));
+        let unit = Unit::new(
+            UnitId(1),
+            PlayerSlot(0),
+            UnitType::Warrior,
+            HexCoord::new(0, 0),
+        );
         let entity = world.spawn(UnitBundle::new(unit)).id();
 
         {
             let mut selection = world.resource_mut::<SelectedEntity>();
-            *selection = SelectedEntity::unit(entity, 1, HexCoord::new(0, 0));
+            *selection = SelectedEntity::unit(entity, UnitId(1), HexCoord::new(0, 0));
         }
 
         // Verify selection
@@ -942,7 +1589,12 @@ mod tests {
     fn test_modify_position_component() {
         let mut world = World::new();
 
-        let unit = Unit::new(1, 0, UnitType::Warrior, HexCoord::new(0, 0));
+        let unit = Unit::new(
+            UnitId(1),
+            PlayerSlot(0),
+            UnitType::Warrior,
+            HexCoord::new(0, 0),
+        );
         let entity = world.spawn(UnitBundle::new(unit)).id();
 
         // Modify position
@@ -989,7 +1641,12 @@ mod tests {
     fn test_add_component_to_entity() {
         let mut world = World::new();
 
-        let unit = Unit::new(1, 0, UnitType::Warrior, HexCoord::new(0, 0));
+        let unit = Unit::new(
+            UnitId(1),
+            PlayerSlot(0),
+            UnitType::Warrior,
+            HexCoord::new(0, 0),
+        );
         let entity = world.spawn(UnitBundle::new(unit)).id();
 
         // Initially no selection component
@@ -1009,7 +1666,12 @@ mod tests {
     fn test_remove_component_from_entity() {
         let mut world = World::new();
 
-        let unit = Unit::new(1, 0, UnitType::Warrior, HexCoord::new(0, 0));
+        let unit = Unit::new(
+            UnitId(1),
+            PlayerSlot(0),
+            UnitType::Warrior,
+            HexCoord::new(0, 0),
+        );
         let entity = world
             .spawn((UnitBundle::new(unit), SelectionComponent::primary()))
             .id();
@@ -1032,7 +1694,12 @@ mod tests {
     fn test_movement_animation_component_added() {
         let mut world = World::new();
 
-        let unit = Unit::new(1, 0, UnitType::Warrior, HexCoord::new(0, 0));
+        let unit = Unit::new(
+            UnitId(1),
+            PlayerSlot(0),
+            UnitType::Warrior,
+            HexCoord::new(0, 0),
+        );
         let entity = world.spawn(UnitBundle::new(unit)).id();
 
         // Add movement animation
@@ -1091,7 +1758,7 @@ mod tests {
         {
             let mut pending = world.resource_mut::<PendingAction>();
             pending.action = Some(PendingActionType::MoveUnit {
-                unit_id: 1,
+                unit_id: UnitId(1),
                 path: vec![HexCoord::new(0, 0), HexCoord::new(1, 0)],
             });
             pending.target = Some(HexCoord::new(1, 0));
@@ -1106,7 +1773,9 @@ mod tests {
     fn test_pending_action_clear() {
         let mut world = World::new();
         world.insert_resource(PendingAction {
-            action: Some(PendingActionType::FoundCity { settler_id: 1 }),
+            action: Some(PendingActionType::FoundCity {
+                settler_id: UnitId(1),
+            }),
             target: Some(HexCoord::new(5, 5)),
         });
 
@@ -1137,12 +1806,23 @@ mod tests {
 
         // Spawn units
         for i in 0..2 {
-            let unit = Unit::new(i, 0, UnitType::Warrior, HexCoord::new(i as i32, 0));
+            let unit = Unit::new(
+                UnitId(i),
+                PlayerSlot(0),
+                UnitType::Warrior,
+                HexCoord::new(i as i32, 0),
+            );
             world.spawn(UnitBundle::new(unit));
         }
 
         // Spawn city
-        let city = City::new(1, 0, "Test".to_string(), HexCoord::new(1, 0), true);
+        let city = City::new(
+            CityId(1),
+            PlayerSlot(0),
+            "Test".to_string(),
+            HexCoord::new(1, 0),
+            true,
+        );
         world.spawn(CityBundle::new(city));
 
         // Query each type
@@ -1169,12 +1849,17 @@ mod tests {
 
         // Spawn entities at various positions
         for i in 0..5 {
-            let unit = Unit::new(i, 0, UnitType::Warrior, HexCoord::new(i as i32, 0));
+            let unit = Unit::new(
+                UnitId(i),
+                PlayerSlot(0),
+                UnitType::Warrior,
+                HexCoord::new(i as i32, 0),
+            );
             world.spawn(UnitBundle::new(unit));
         }
 
         // Spawn unit at target position
-        let unit = Unit::new(100, 0, UnitType::Warrior, target_coord);
+        let unit = Unit::new(UnitId(100), PlayerSlot(0), UnitType::Warrior, target_coord);
         world.spawn(UnitBundle::new(unit));
 
         // Query and filter by position
@@ -0,0 +1,362 @@
+//! Sprite themes for tiles, units and buildings.
+//!
+//! A [`ThemeManifest`] maps terrain, unit and building types to image
+//! assets. Manifests are RON files, so mods and accessibility themes such
+//! as high contrast can be added without recompiling:
+//!
+//! ```ron
+//! (
+//!     name: "High Contrast",
+//!     root: "themes/high_contrast",
+//!     terrain: { Ocean: "themes/high_contrast/water.png" },
+//!     units: {},
+//!     buildings: {},
+//! )
+//! ```
+//!
+//! Types not listed fall back to `{root}/{category}/{type}.png`, for
+//! example `themes/high_contrast/units/warrior.png`.
+//!
+//! The [`ThemePlugin`] keeps the active theme in [`ActiveTheme`] and gives
+//! every tile and unit entity the image for its type. Sending a
+//! [`LoadThemeEvent`] swaps the theme while the game runs; entities pick
+//! up the new images on the next frame.
+
+use bevy::prelude::*;
+use nostr_nations_core::city::BuildingType;
+use nostr_nations_core::terrain::Terrain;
+use nostr_nations_core::unit::UnitType;
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::path::PathBuf;
+
+use crate::components::{TileComponent, UnitComponent};
+use crate::systems::GameSystemSet;
+
+/// Something a theme provides an image for.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum ThemeKey {
+    /// A terrain type.
+    Terrain(Terrain),
+    /// A unit type.
+    Unit(UnitType),
+    /// A building type.
+    Building(BuildingType),
+}
+
+impl ThemeKey {
+    /// Directory under the theme root for this key's category.
+    pub fn category(&self) -> &'static str {
+        match self {
+            ThemeKey::Terrain(_) => "terrain",
+            ThemeKey::Unit(_) => "units",
+            ThemeKey::Building(_) => "buildings",
+        }
+    }
+
+    /// File stem for this key, e.g. `great_scientist`.
+    pub fn file_stem(&self) -> String {
+        let name = match self {
+            ThemeKey::Terrain(terrain) => format!("{:?}", terrain),
+            ThemeKey::Unit(unit_type) => format!("{:?}", unit_type),
+            ThemeKey::Building(building) => format!("{:?}", building),
+        };
+        let mut stem = String::with_capacity(name.len() + 4);
+        for (i, c) in name.chars().enumerate() {
+            if c.is_uppercase() && i > 0 {
+                stem.push('_');
+            }
+            stem.push(c.to_ascii_lowercase());
+        }
+        stem
+    }
+}
+
+/// Image assets of a theme.
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ThemeManifest {
+    /// Theme name.
+    pub name: String,
+    /// Asset directory for images not listed below.
+    pub root: String,
+    /// Terrain images.
+    #[serde(default)]
+    pub terrain: HashMap<Terrain, String>,
+    /// Unit images.
+    #[serde(default)]
+    pub units: HashMap<UnitType, String>,
+    /// Building images.
+    #[serde(default)]
+    pub buildings: HashMap<BuildingType, String>,
+}
+
+impl ThemeManifest {
+    /// Create a theme whose images all follow the naming convention.
+    pub fn new(name: impl Into<String>, root: impl Into<String>) -> Self {
+        Self {
+            name: name.into(),
+            root: root.into(),
+            terrain: HashMap::new(),
+            units: HashMap::new(),
+            buildings: HashMap::new(),
+        }
+    }
+
+    /// The built-in theme.
+    pub fn builtin() -> Self {
+        Self::new("Default", "sprites")
+    }
+
+    /// The built-in high contrast theme.
+    pub fn high_contrast() -> Self {
+        Self::new("High Contrast", "themes/high_contrast")
+    }
+
+    /// Parse a manifest from RON.
+    pub fn from_ron(source: &str) -> Result<Self, ron::error::SpannedError> {
+        ron::from_str(source)
+    }
+
+    /// Asset path of the image for a key.
+    pub fn path(&self, key: ThemeKey) -> String {
+        let listed = match key {
+            ThemeKey::Terrain(terrain) => self.terrain.get(&terrain),
+            ThemeKey::Unit(unit_type) => self.units.get(&unit_type),
+            ThemeKey::Building(building) => self.buildings.get(&building),
+        };
+        match listed {
+            Some(path) => path.clone(),
+            None => format!("{}/{}/{}.png", self.root, key.category(), key.file_stem()),
+        }
+    }
+}
+
+impl Default for ThemeManifest {
+    fn default() -> Self {
+        Self::builtin()
+    }
+}
+
+/// Resource holding the active theme and its loaded images.
+#[derive(Resource, Debug, Default)]
+pub struct ActiveTheme {
+    manifest: ThemeManifest,
+    handles: HashMap<ThemeKey, Handle<Image>>,
+    generation: u32,
+}
+
+impl ActiveTheme {
+    /// Create with a theme.
+    pub fn new(manifest: ThemeManifest) -> Self {
+        Self {
+            manifest,
+            handles: HashMap::new(),
+            generation: 0,
+        }
+    }
+
+    /// The active manifest.
+    pub fn manifest(&self) -> &ThemeManifest {
+        &self.manifest
+    }
+
+    /// Counter bumped on every theme change.
+    pub fn generation(&self) -> u32 {
+        self.generation
+    }
+
+    /// Switch to another theme.
+    pub fn set(&mut self, manifest: ThemeManifest) {
+        self.manifest = manifest;
+        self.handles.clear();
+        self.generation = self.generation.wrapping_add(1);
+    }
+
+    /// Image for a key, loading it on first use.
+    pub fn handle(&mut self, key: ThemeKey, asset_server: &AssetServer) -> Handle<Image> {
+        if let Some(handle) = self.handles.get(&key) {
+            return handle.clone();
+        }
+        let handle: Handle<Image> = asset_server.load(self.manifest.path(key));
+        self.handles.insert(key, handle.clone());
+        handle
+    }
+}
+
+/// Component recording which themed image an entity shows.
+#[derive(Component, Clone, Copy, Debug, PartialEq, Eq)]
+pub struct ThemedSprite {
+    /// What the image is for.
+    pub key: ThemeKey,
+    /// Theme generation the image came from.
+    pub generation: u32,
+}
+
+/// Where a theme to load comes from.
+#[derive(Clone, Debug)]
+pub enum ThemeSource {
+    /// An already parsed manifest.
+    Manifest(ThemeManifest),
+    /// A RON manifest file on disk.
+    File(PathBuf),
+}
+
+/// Event requesting a theme change.
+#[derive(Event, Clone, Debug)]
+pub struct LoadThemeEvent(pub ThemeSource);
+
+/// Plugin for sprite themes.
+#[derive(Default)]
+pub struct ThemePlugin {
+    /// Theme to start with.
+    pub theme: ThemeManifest,
+}
+
+impl Plugin for ThemePlugin {
+    fn build(&self, app: &mut App) {
+        app.insert_resource(ActiveTheme::new(self.theme.clone()));
+        app.add_event::<LoadThemeEvent>();
+
+        app.add_systems(
+            Update,
+            (load_theme_system, themed_sprite_system)
+                .chain()
+                .after(GameSystemSet::Sync),
+        );
+    }
+}
+
+/// Apply requested theme changes.
+///
+/// A file that can't be read or parsed is logged and the current theme is
+/// kept.
+pub fn load_theme_system(mut theme: ResMut<ActiveTheme>, mut events: EventReader<LoadThemeEvent>) {
+    for LoadThemeEvent(source) in events.read() {
+        let manifest = match source {
+            ThemeSource::Manifest(manifest) => manifest.clone(),
+            ThemeSource::File(path) => {
+                let parsed = std::fs::read_to_string(path)
+                    .map_err(|e| e.to_string())
+                    .and_then(|s| ThemeManifest::from_ron(&s).map_err(|e| e.to_string()));
+                match parsed {
+                    Ok(manifest) => manifest,
+                    Err(e) => {
+                        warn!("Failed to load theme {}: {}", path.display(), e);
+                        continue;
+                    }
+                }
+            }
+        };
+        info!("Switching to theme {}", manifest.name);
+        theme.set(manifest);
+    }
+}
+
+/// Give tile and unit entities the image for their type.
+///
+/// Entities are updated when they have no image yet, when their type
+/// changed (e.g. terrain transformed) or when the theme changed. Does
+/// nothing without an `AssetServer`.
+pub fn themed_sprite_system(
+    mut commands: Commands,
+    mut theme: ResMut<ActiveTheme>,
+    asset_server: Option<Res<AssetServer>>,
+    tiles: Query<(Entity, &TileComponent, Option<&ThemedSprite>)>,
+    units: Query<(Entity, &UnitComponent, Option<&ThemedSprite>)>,
+) {
+    let Some(asset_server) = asset_server else {
+        return;
+    };
+    let generation = theme.generation();
+    let tiles = tiles
+        .iter()
+        .map(|(entity, tile, themed)| (entity, ThemeKey::Terrain(tile.tile.terrain), themed));
+    let units = units
+        .iter()
+        .map(|(entity, unit, themed)| (entity, ThemeKey::Unit(unit.unit.unit_type), themed));
+
+    for (entity, key, themed) in tiles.chain(units) {
+        let current = ThemedSprite { key, generation };
+        if themed == Some(&current) {
+            continue;
+        }
+        let handle = theme.handle(key, &asset_server);
+        commands.entity(entity).insert((handle, current));
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_unlisted_types_follow_naming_convention() {
+        let theme = ThemeManifest::high_contrast();
+        assert_eq!(
+            theme.path(ThemeKey::Terrain(Terrain::Grassland)),
+            "themes/high_contrast/terrain/grassland.png"
+        );
+        assert_eq!(
+            theme.path(ThemeKey::Unit(UnitType::Warrior)),
+            "themes/high_contrast/units/warrior.png"
+        );
+        assert_eq!(
+            ThemeKey::Unit(UnitType::GreatScientist).file_stem(),
+            "great_scientist"
+        );
+    }
+
+    #[test]
+    fn test_manifest_from_ron() {
+        let theme = ThemeManifest::from_ron(
+            r#"(
+                name: "Mod",
+                root: "mods/pixel",
+                terrain: { Ocean: "mods/pixel/water.png" },
+            )"#,
+        )
+        .unwrap();
+
+        assert_eq!(theme.name, "Mod");
+        assert_eq!(
+            theme.path(ThemeKey::Terrain(Terrain::Ocean)),
+            "mods/pixel/water.png"
+        );
+        assert_eq!(
+            theme.path(ThemeKey::Terrain(Terrain::Coast)),
+            "mods/pixel/terrain/coast.png"
+        );
+        assert!(ThemeManifest::from_ron("(name: \"Broken\")").is_err());
+    }
+
+    #[test]
+    fn test_set_theme_bumps_generation() {
+        let mut theme = ActiveTheme::default();
+        assert_eq!(theme.manifest().name, "Default");
+
+        theme.set(ThemeManifest::high_contrast());
+        assert_eq!(theme.generation(), 1);
+        assert_eq!(theme.manifest().name, "High Contrast");
+    }
+
+    #[test]
+    fn test_load_theme_event() {
+        let mut app = App::new();
+        app.add_plugins(MinimalPlugins);
+        app.add_plugins(ThemePlugin::default());
+
+        app.world_mut()
+            .send_event(LoadThemeEvent(ThemeSource::Manifest(
+                ThemeManifest::high_contrast(),
+            )));
+        app.world_mut()
+            .send_event(LoadThemeEvent(ThemeSource::File(PathBuf::from(
+                "/nonexistent/theme.ron",
+            ))));
+        app.update();
+
+        let theme = app.world().resource::<ActiveTheme>();
+        assert_eq!(theme.manifest().name, "High Contrast");
+        assert_eq!(theme.generation(), 1);
+    }
+}
@@ -0,0 +1,281 @@
+# English message catalog.
+#
+# Fluent-style syntax: `key = text`, with `{ $name }` placeables for
+# parameters. Lines starting with `#` are comments.
+
+## Civilizations
+
+civ-rome-name = Rome
+civ-rome-leader = Augustus Caesar
+civ-rome-ability = Glory of Rome
+civ-rome-ability-description = +25% production towards buildings that already exist in the Capital.
+
+civ-egypt-name = Egypt
+civ-egypt-leader = Ramesses II
+civ-egypt-ability = Monument Builders
+civ-egypt-ability-description = +20% production towards Wonders.
+
+civ-greece-name = Greece
+civ-greece-leader = Alexander
+civ-greece-ability = Hellenic League
+civ-greece-ability-description = City-state influence degrades half as fast.
+
+civ-china-name = China
+civ-china-leader = Wu Zetian
+civ-china-ability = Art of War
+civ-china-ability-description = Great General spawn rate increased by 50%.
+
+civ-generic-name = Generic Nation
+civ-generic-leader = Leader
+civ-generic-ability = Balanced
+civ-generic-ability-description = No special bonuses or penalties.
+
+## Cities
+
+city-name-ordinal = { $name } { $ordinal }
+
+city-rome-rome = Rome
+city-rome-antium = Antium
+city-rome-cumae = Cumae
+city-rome-neapolis = Neapolis
+city-rome-ravenna = Ravenna
+city-rome-arretium = Arretium
+city-rome-mediolanum = Mediolanum
+city-rome-arpinum = Arpinum
+
+city-egypt-thebes = Thebes
+city-egypt-memphis = Memphis
+city-egypt-heliopolis = Heliopolis
+city-egypt-elephantine = Elephantine
+city-egypt-alexandria = Alexandria
+city-egypt-pi-ramesses = Pi-Ramesses
+city-egypt-giza = Giza
+city-egypt-byblos = Byblos
+
+city-greece-athens = Athens
+city-greece-sparta = Sparta
+city-greece-corinth = Corinth
+city-greece-argos = Argos
+city-greece-knossos = Knossos
+city-greece-mycenae = Mycenae
+city-greece-pharsalos = Pharsalos
+city-greece-ephesus = Ephesus
+
+city-china-beijing = Beijing
+city-china-shanghai = Shanghai
+city-china-guangzhou = Guangzhou
+city-china-nanjing = Nanjing
+city-china-xian = Xian
+city-china-chengdu = Chengdu
+city-china-hangzhou = Hangzhou
+city-china-luoyang = Luoyang
+
+city-generic-new-haven = New Haven
+city-generic-riverside = Riverside
+city-generic-highpoint = Highpoint
+city-generic-stonebridge = Stonebridge
+city-generic-greenfield = Greenfield
+city-generic-eastmarch = Eastmarch
+
+## Technologies
+
+tech-agriculture = Agriculture
+tech-pottery = Pottery
+tech-animal_husbandry = Animal Husbandry
+tech-archery = Archery
+tech-mining = Mining
+tech-sailing = Sailing
+tech-calendar = Calendar
+tech-writing = Writing
+tech-trapping = Trapping
+tech-the_wheel = The Wheel
+tech-masonry = Masonry
+tech-bronze_working = Bronze Working
+tech-optics = Optics
+tech-philosophy = Philosophy
+tech-drama = Drama
+tech-mathematics = Mathematics
+tech-construction = Construction
+tech-iron_working = Iron Working
+tech-horseback_riding = Horseback Riding
+tech-currency = Currency
+tech-civil_service = Civil Service
+tech-chivalry = Chivalry
+tech-education = Education
+tech-steel = Steel
+tech-machinery = Machinery
+tech-compass = Compass
+tech-physics = Physics
+tech-banking = Banking
+tech-astronomy = Astronomy
+tech-printing_press = Printing Press
+tech-gunpowder = Gunpowder
+tech-metallurgy = Metallurgy
+tech-navigation = Navigation
+tech-economics = Economics
+tech-chemistry = Chemistry
+tech-acoustics = Acoustics
+tech-rifling = Rifling
+tech-military_science = Military Science
+tech-steam_power = Steam Power
+tech-dynamite = Dynamite
+tech-electricity = Electricity
+tech-biology = Biology
+tech-telegraph = Telegraph
+tech-replaceable_parts = Replaceable Parts
+tech-combustion = Combustion
+tech-ballistics = Ballistics
+tech-flight = Flight
+tech-electronics = Electronics
+tech-radar = Radar
+tech-rocketry = Rocketry
+tech-nuclear_fission = Nuclear Fission
+tech-computers = Computers
+tech-spaceflight = Spaceflight
+tech-scientific_theory = Scientific Theory
+tech-industrialization = Industrialization
+
+## Notifications
+
+notify-combat-victory-title = Victory!
+notify-combat-victory = Your unit destroyed the enemy { $unit }!
+notify-combat-unit-lost-title = Unit Lost
+notify-combat-unit-lost = Your { $unit } was destroyed in combat!
+notify-combat-title = Combat
+notify-combat = Combat: { $attacker } dealt { $dealt } damage, received { $received } damage
+notify-city-bombard-title = Bombardment
+notify-city-bombard = { $city } bombarded the enemy { $unit } for { $damage } damage
+notify-city-bombard-kill = { $city } destroyed the enemy { $unit }!
+notify-city-captured-title = City Captured
+notify-city-captured = You captured { $city }! Choose whether to raze, annex or puppet it.
+notify-promotion-available-title = Promotion Available
+notify-promotion-available = Your { $unit } has earned a promotion. Choose one to continue.
+notify-peer-connected-title = Peer Connected
+notify-peer-connected = Successfully connected. { $count } peer(s) online.
+notify-peer-disconnected-title = Peer Disconnected
+notify-peer-disconnected = { $count } peer(s) remaining.
+notify-peer-incompatible-title = Cannot Connect
+notify-peer-incompatible = This peer can't join: { $reason }
+notify-offline-discarded-title = Offline Actions Discarded
+notify-offline-discarded = { $count } action(s) conflicted with moves made while you were offline.
+notify-tournament-complete-title = Tournament Complete
+notify-tournament-complete = { $tournament } has been won by { $champion }
+notify-match-ready-title = Match Ready
+notify-match-ready = { $first } vs { $second }
+notify-war-declared-title = War Declared
+notify-war-declared = You are now at war with player { $player }
+notify-trade-accepted-title = Trade Accepted
+notify-trade-accepted = Trade with player { $player } completed
+notify-game-started-title = Game Started
+notify-game-started = The game has begun! { $count } players competing.
+notify-your-turn-title = Your Turn
+notify-your-turn = Turn { $turn } has begun. It's your move!
+notify-era-entered-title = New Era
+notify-era-entered = { $player } has entered the { $era }.
+notify-first-contact-title = First Contact
+notify-first-contact = You have met { $player }. Diplomacy with them is now possible.
+notify-circumnavigated-title = Circumnavigation
+notify-circumnavigated = { $player } was the first to sail around the world and earned { $gold } gold.
+notify-wonder-completed-title = Wonder Completed
+notify-wonder-completed = { $player } has completed { $wonder } in { $city }.
+notify-wonder-lost-title = Wonder Lost
+notify-wonder-lost = { $wonder } was completed elsewhere first. { $city } was refunded { $gold } gold.
+notify-player-substituted-title = Substitute Joined
+notify-player-substituted = { $name } has taken over player { $player }.
+notify-map-revealed-title = Map Revealed
+notify-map-revealed = The game is over. The whole map and every player's stats are now visible.
+notify-pause-requested-title = Pause Requested
+notify-pause-requested = Player { $player } asked to pause the game.
+notify-game-paused-title = Game Paused
+notify-game-paused = The game is paused at the request of player { $player }.
+notify-pause-rejected-title = Pause Rejected
+notify-pause-rejected = The pause requested by player { $player } was turned down.
+notify-game-resumed-title = Resuming
+notify-game-resumed = Play continues in { $seconds } seconds.
+notify-timelapse-ready-title = Timelapse Ready
+notify-timelapse-ready = { $frames } turn captures were saved to { $directory }.
+notify-pitboss-turn = It's your turn ({ $turn }) in game { $game }
+notify-pitboss-reminder = Reminder: it's still your turn ({ $turn }) in game { $game }
+
+## Turn digest
+
+digest-title = While You Were Away
+digest-summary = { $count } thing(s) happened since turn { $turn }.
+digest-unit-attacked = { $player } attacked your { $unit } for { $damage } damage.
+digest-unit-destroyed = { $player } destroyed your { $unit }.
+digest-city-attacked = { $player } attacked { $city } for { $damage } damage.
+digest-city-lost = { $player } captured { $city }.
+digest-tech-researched = Your scholars discovered { $tech }.
+digest-city-grew = { $city } grew to size { $population }.
+digest-city-shrank = { $city } shrank to size { $population }.
+digest-first-contact = You met { $player }.
+digest-war-declared = { $player } declared war on you.
+digest-peace-proposed = { $player } offered peace.
+digest-peace-accepted = { $player } accepted your peace offer.
+digest-peace-rejected = { $player } rejected your peace offer.
+digest-treaty-proposed = { $player } proposed a { $treaty } treaty.
+digest-trade-proposed = { $player } offered you a trade.
+digest-trade-accepted = { $player } accepted your trade.
+digest-trade-rejected = { $player } rejected your trade.
+
+## Turn advisor
+
+advisor-choose-research = Choose a technology to research.
+advisor-city-idle = { $city } has nothing to build.
+advisor-promotion-available = Your { $unit } can be promoted.
+advisor-unit-needs-orders = Your { $unit } needs orders.
+advisor-recommend = { $item } is a good choice here.
+advisor-recommend-defense = Build { $item } to defend against nearby enemies.
+advisor-recommend-growth = Build { $item } to get the city growing again.
+advisor-recommend-science = Build { $item } to catch up in science.
+
+## Game log
+
+log-combat-unit = { $player }'s { $unit } attacked { $defender }'s { $target }, dealing { $dealt } and taking { $taken } damage.
+log-combat-city = { $player }'s { $unit } attacked { $city }, dealing { $dealt } and taking { $taken } damage.
+log-city-bombard = { $city } bombarded { $player }'s { $unit } for { $damage } damage.
+log-unit-destroyed = { $player }'s { $unit } was destroyed.
+log-unit-disbanded = { $player } disbanded a unit they could not pay for.
+log-unit-promoted = A unit of { $player } was promoted to { $promotion }.
+log-unit-trained = { $player } trained a { $unit }.
+log-war-declared = { $player } declared war on { $target }.
+log-peace-made = { $player } made peace with { $target }.
+log-trade-completed = { $player } accepted a trade with { $target }.
+log-first-contact = { $player } met { $target }.
+log-circumnavigated = { $player } circumnavigated the world for { $gold } gold.
+log-city-founded = { $player } founded { $city }.
+log-city-captured = { $player } captured { $city } from { $previous }.
+log-city-razed = { $player } razed { $city }.
+log-building-completed = { $city } completed a { $building }.
+log-wonder-completed = { $player } completed { $wonder } in { $city }.
+log-tech-researched = { $player } researched { $tech }.
+log-era-entered = { $player } entered the { $era }.
+log-player-conceded = { $player } conceded.
+log-player-defeated = { $player } was defeated.
+log-game-ended = { $player } won a { $victory } victory.
+
+## Errors
+
+error-invalid_state = Something went wrong: { $detail }
+error-game_not_found = That game isn't open: { $detail }
+error-game_already_active = A game is already in progress.
+error-tournament_not_found = That tournament doesn't exist: { $detail }
+error-pending_decisions = Decide what to research and build before ending your turn.
+error-game_already_started = The game has already started.
+error-too_many_players = The game is full.
+error-player_already_joined = You have already joined this game.
+error-invalid_phase = That can't be done at this stage of the game.
+error-not_enough_players = More players are needed to start.
+error-not_player_turn = It's not your turn.
+error-invalid_action = That action isn't allowed.
+error-unit_not_found = That unit no longer exists.
+error-city_not_found = That city no longer exists.
+error-not_owner = You don't control that.
+error-invalid_position = That position isn't valid.
+error-invalid_event_chain = The game history is invalid or incomplete.
+error-invalid_settings = The game settings are invalid.
+error-invalid_randomness = A dice roll could not be verified.
+error-invalid_snapshot = The saved snapshot can't be used: { $detail }
+error-network = Network problem: { $detail }
+error-storage = Couldn't read or write saved data: { $detail }
+error-serialization = Couldn't read or write game data: { $detail }
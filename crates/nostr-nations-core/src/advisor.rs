@@ -0,0 +1,299 @@
+//! End-of-turn advisor.
+//!
+//! Lists what still needs the player's attention before they end their
+//! turn: units that have moves left and no orders, units that can be
+//! promoted, cities with nothing to build, and research that hasn't been
+//! chosen.
+//!
+//! Idle cities and unchosen research are mandatory; clients may refuse to
+//! end the turn while any are left. The rules themselves never block
+//! `EndTurn`, so whether to enforce this is up to each client's settings.
+//!
+//! Clients cycle through the list with a hotkey using
+//! [`TurnAdvisor::next_after`], which wraps around to the first item.
+
+use crate::game_state::GameState;
+use crate::hex::HexCoord;
+use crate::locale::LocalizedMessage;
+use crate::technology::TechTree;
+use crate::types::{CityId, PlayerSlot, UnitId};
+use crate::unit::{Unit, UnitType};
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+use ts_rs::TS;
+
+/// Something the player should deal with before ending their turn.
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize, JsonSchema, TS)]
+pub enum PendingItem {
+    /// No technology is being researched.
+    ChooseResearch,
+    /// A city has nothing in production.
+    CityIdle {
+        city_id: CityId,
+        name: String,
+        position: HexCoord,
+    },
+    /// A unit has enough experience for a promotion.
+    PromotionAvailable {
+        unit_id: UnitId,
+        unit_type: UnitType,
+        position: HexCoord,
+    },
+    /// A unit has moves left and no orders.
+    UnitNeedsOrders {
+        unit_id: UnitId,
+        unit_type: UnitType,
+        position: HexCoord,
+    },
+}
+
+impl PendingItem {
+    /// Whether the player must resolve this before ending the turn.
+    pub fn is_mandatory(&self) -> bool {
+        matches!(
+            self,
+            PendingItem::ChooseResearch | PendingItem::CityIdle { .. }
+        )
+    }
+
+    /// Where on the map the item is, for centering the camera.
+    pub fn position(&self) -> Option<HexCoord> {
+        match self {
+            PendingItem::ChooseResearch => None,
+            PendingItem::CityIdle { position, .. }
+            | PendingItem::PromotionAvailable { position, .. }
+            | PendingItem::UnitNeedsOrders { position, .. } => Some(*position),
+        }
+    }
+
+    /// Localized description of the item.
+    pub fn message(&self) -> LocalizedMessage {
+        match self {
+            PendingItem::ChooseResearch => LocalizedMessage::new("advisor-choose-research"),
+            PendingItem::CityIdle { name, .. } => {
+                LocalizedMessage::new("advisor-city-idle").with_arg("city", name)
+            }
+            PendingItem::PromotionAvailable { unit_type, .. } => {
+                LocalizedMessage::new("advisor-promotion-available")
+                    .with_arg("unit", format!("{:?}", unit_type))
+            }
+            PendingItem::UnitNeedsOrders { unit_type, .. } => {
+                LocalizedMessage::new("advisor-unit-needs-orders")
+                    .with_arg("unit", format!("{:?}", unit_type))
+            }
+        }
+    }
+}
+
+/// Whether a unit is waiting for orders.
+fn needs_orders(unit: &Unit) -> bool {
+    unit.movement > 0
+        && !unit.has_acted
+        && !unit.fortified
+        && !unit.sleeping
+        && !unit.healing
+        && unit.queued_path.is_none()
+        && unit.road_work.is_none()
+        && unit.feature_work.is_none()
+}
+
+/// A player's pending items, in cycling order.
+#[derive(Clone, Debug, Default, PartialEq, Eq, Serialize, Deserialize, JsonSchema, TS)]
+pub struct TurnAdvisor {
+    /// Pending items: research first, then cities, promotions and units,
+    /// each by ID.
+    pub items: Vec<PendingItem>,
+}
+
+impl TurnAdvisor {
+    /// Collect a player's pending items.
+    pub fn new(state: &GameState, player_id: PlayerSlot) -> Self {
+        let mut items = Vec::new();
+        let Some(player) = state.get_player(player_id) else {
+            return Self { items };
+        };
+
+        if player.current_research.is_none()
+            && player.research_queue.is_empty()
+            && !TechTree::new()
+                .available_techs(&player.technologies)
+                .is_empty()
+        {
+            items.push(PendingItem::ChooseResearch);
+        }
+
+        let mut cities: Vec<_> = state
+            .cities
+            .values()
+            .filter(|c| c.owner == player_id)
+            .filter(|c| c.production.is_none() && c.production_queue.is_empty())
+            .collect();
+        cities.sort_by_key(|c| c.id);
+        items.extend(cities.into_iter().map(|c| PendingItem::CityIdle {
+            city_id: c.id,
+            name: c.name.clone(),
+            position: c.position,
+        }));
+
+        let mut units: Vec<&Unit> = state
+            .units
+            .values()
+            .filter(|u| u.owner == player_id)
+            .collect();
+        units.sort_by_key(|u| u.id);
+        items.extend(units.iter().filter(|u| u.can_promote()).map(|u| {
+            PendingItem::PromotionAvailable {
+                unit_id: u.id,
+                unit_type: u.unit_type,
+                position: u.position,
+            }
+        }));
+        items.extend(units.iter().filter(|u| needs_orders(u)).map(|u| {
+            PendingItem::UnitNeedsOrders {
+                unit_id: u.id,
+                unit_type: u.unit_type,
+                position: u.position,
+            }
+        }));
+
+        Self { items }
+    }
+
+    /// Check if nothing is pending.
+    pub fn is_empty(&self) -> bool {
+        self.items.is_empty()
+    }
+
+    /// Items that must be resolved before ending the turn.
+    pub fn mandatory(&self) -> impl Iterator<Item = &PendingItem> {
+        self.items.iter().filter(|item| item.is_mandatory())
+    }
+
+    /// Check if the turn can be ended without skipping a mandatory item.
+    pub fn can_end_turn(&self) -> bool {
+        self.mandatory().next().is_none()
+    }
+
+    /// The item after `current`, wrapping around.
+    ///
+    /// Returns the first item if `current` is `None` or no longer pending,
+    /// and `None` if nothing is pending.
+    pub fn next_after(&self, current: Option<&PendingItem>) -> Option<&PendingItem> {
+        let start = current
+            .and_then(|current| self.items.iter().position(|item| item == current))
+            .map_or(0, |i| i + 1);
+        self.items.get(start).or(self.items.first())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::city::{City, ProductionItem};
+    use crate::player::{Civilization, Player};
+    use crate::settings::GameSettings;
+    use crate::types::{GameId, Npub};
+
+    fn create_state() -> GameState {
+        let mut state = GameState::new(
+            GameId::new("advisor"),
+            GameSettings::new("Advisor".to_string()),
+            [1; 32],
+        );
+        state.players.push(Player::new(
+            PlayerSlot(0),
+            Npub::new("npub0"),
+            "Alice".to_string(),
+            Civilization::generic(),
+        ));
+        state
+    }
+
+    fn add_unit(state: &mut GameState, id: u64, owner: PlayerSlot) -> &mut Unit {
+        let unit = Unit::new(
+            UnitId(id),
+            owner,
+            UnitType::Warrior,
+            HexCoord::new(id as i32, 0),
+        );
+        state.units.insert(UnitId(id), unit);
+        state.units.get_mut(&UnitId(id)).unwrap()
+    }
+
+    #[test]
+    fn test_lists_pending_items_in_order() {
+        let mut state = create_state();
+        add_unit(&mut state, 2, PlayerSlot(0));
+        add_unit(&mut state, 1, PlayerSlot(0)).experience = 50;
+        add_unit(&mut state, 3, PlayerSlot(1));
+        let city = City::new(
+            CityId(1),
+            PlayerSlot(0),
+            "Rome".to_string(),
+            HexCoord::new(0, 0),
+            true,
+        );
+        state.cities.insert(CityId(1), city);
+
+        let advisor = TurnAdvisor::new(&state, PlayerSlot(0));
+        let kinds: Vec<&str> = advisor
+            .items
+            .iter()
+            .map(|item| match item {
+                PendingItem::ChooseResearch => "research",
+                PendingItem::CityIdle { .. } => "city",
+                PendingItem::PromotionAvailable { .. } => "promotion",
+                PendingItem::UnitNeedsOrders { .. } => "unit",
+            })
+            .collect();
+        assert_eq!(kinds, vec!["research", "city", "promotion", "unit", "unit"]);
+        assert!(!advisor.can_end_turn());
+        assert_eq!(advisor.mandatory().count(), 2);
+    }
+
+    #[test]
+    fn test_units_with_orders_are_not_pending() {
+        let mut state = create_state();
+        state.players[0].current_research = Some("pottery".to_string());
+        add_unit(&mut state, 1, PlayerSlot(0)).fortified = true;
+        add_unit(&mut state, 2, PlayerSlot(0)).sleeping = true;
+        add_unit(&mut state, 3, PlayerSlot(0)).movement = 0;
+        add_unit(&mut state, 4, PlayerSlot(0)).queued_path = Some(vec![HexCoord::new(5, 0)]);
+        let mut city = City::new(
+            CityId(1),
+            PlayerSlot(0),
+            "Rome".to_string(),
+            HexCoord::new(0, 0),
+            true,
+        );
+        city.production = Some(ProductionItem::Unit(UnitType::Warrior));
+        state.cities.insert(CityId(1), city);
+
+        let advisor = TurnAdvisor::new(&state, PlayerSlot(0));
+        assert!(advisor.is_empty());
+        assert!(advisor.can_end_turn());
+        assert_eq!(advisor.next_after(None), None);
+    }
+
+    #[test]
+    fn test_next_after_cycles() {
+        let mut state = create_state();
+        state.players[0].current_research = Some("pottery".to_string());
+        add_unit(&mut state, 1, PlayerSlot(0));
+        add_unit(&mut state, 2, PlayerSlot(0));
+
+        let advisor = TurnAdvisor::new(&state, PlayerSlot(0));
+        assert!(advisor.can_end_turn());
+        let first = advisor.next_after(None).unwrap().clone();
+        let second = advisor.next_after(Some(&first)).unwrap().clone();
+        assert_ne!(first, second);
+        assert_eq!(advisor.next_after(Some(&second)), Some(&first));
+
+        let gone = PendingItem::UnitNeedsOrders {
+            unit_id: UnitId(9),
+            unit_type: UnitType::Warrior,
+            position: HexCoord::new(9, 0),
+        };
+        assert_eq!(advisor.next_after(Some(&gone)), Some(&first));
+    }
+}
@@ -0,0 +1,951 @@
+//! Computer-controlled players.
+//!
+//! Every AI seat plays one of four [`Persona`]s. A persona is a set of
+//! weights over a handful of [`Objective`]s, loaded from the
+//! `rulesets/ai_personas.json` ruleset. Each turn the AI lists the actions
+//! open to it, values every candidate against the objectives it serves and
+//! takes the valid candidate with the highest weighted score, repeating
+//! until nothing worthwhile is left.
+//!
+//! Planning is incremental: an [`AiPlanner`] spreads a turn's evaluation
+//! across as many engine ticks as it needs, within an [`AiBudget`]. The
+//! budget counts candidates evaluated rather than time, so how fast the
+//! machine is never changes what the AI does.
+//!
+//! The AI never draws randomness of its own. Candidates are listed in ID
+//! order, ties go to the first candidate, and combat rolls are derived from
+//! the game seed, so the same state, seed and persona assignment always
+//! produce the same actions on every peer.
+
+use crate::audit::{fnv1a, FNV_OFFSET};
+use crate::demographics::Demographic;
+use crate::events::GameAction;
+use crate::fixed::Fixed;
+use crate::game_state::GameState;
+use crate::hex::HexCoord;
+use crate::locale::Catalog;
+use crate::recommend::production_options;
+use crate::replay::{GameEngine, ReplayError};
+use crate::technology::TechTree;
+use crate::types::{CityId, PlayerSlot, UnitId};
+use crate::unit::{Unit, UnitType};
+use serde::{Deserialize, Serialize};
+use std::collections::{BTreeMap, VecDeque};
+use std::fmt;
+use std::sync::OnceLock;
+
+const AI_PERSONAS_RULESET: &str = include_str!("../rulesets/ai_personas.json");
+
+/// Most actions an AI takes in one turn.
+pub const MAX_ACTIONS_PER_TURN: usize = 256;
+
+/// How far a settler looks for a place to found a city.
+pub const SETTLE_SEARCH_RADIUS: u32 = 6;
+
+/// Closest a new city may be founded to an existing one.
+pub const MIN_CITY_DISTANCE: u32 = 4;
+
+/// Errors loading AI rulesets.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum AiError {
+    /// A ruleset file was malformed.
+    InvalidRuleset(String),
+}
+
+impl fmt::Display for AiError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            AiError::InvalidRuleset(e) => write!(f, "Invalid AI ruleset: {}", e),
+        }
+    }
+}
+
+impl std::error::Error for AiError {}
+
+/// An AI play style.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash, PartialOrd, Ord, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum Persona {
+    /// Builds armies and goes to war.
+    Aggressive,
+    /// Settles as much land as it can.
+    Expansionist,
+    /// Prioritizes research.
+    Scientific,
+    /// Fortifies and guards its cities.
+    Defensive,
+}
+
+impl Persona {
+    /// All personas, in assignment order.
+    pub const ALL: [Persona; 4] = [
+        Persona::Aggressive,
+        Persona::Expansionist,
+        Persona::Scientific,
+        Persona::Defensive,
+    ];
+}
+
+/// Something an AI can work towards.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub enum Objective {
+    /// Attacking and conquering other players.
+    Military,
+    /// Founding cities and exploring.
+    Expansion,
+    /// Researching technologies.
+    Science,
+    /// Protecting the player's own cities.
+    Defense,
+    /// Growing cities and their economy.
+    Growth,
+}
+
+/// How much a persona values each objective.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ObjectiveWeights {
+    pub military: i32,
+    pub expansion: i32,
+    pub science: i32,
+    pub defense: i32,
+    pub growth: i32,
+}
+
+impl ObjectiveWeights {
+    /// Equal weight on every objective, for advising human players.
+    pub const BALANCED: ObjectiveWeights = ObjectiveWeights {
+        military: 1,
+        expansion: 1,
+        science: 1,
+        defense: 1,
+        growth: 1,
+    };
+
+    /// The weight of one objective.
+    pub fn weight(&self, objective: Objective) -> i32 {
+        match objective {
+            Objective::Military => self.military,
+            Objective::Expansion => self.expansion,
+            Objective::Science => self.science,
+            Objective::Defense => self.defense,
+            Objective::Growth => self.growth,
+        }
+    }
+
+    /// Weighted score of an action's value towards each objective.
+    pub fn score(&self, values: &[(Objective, i32)]) -> i32 {
+        values
+            .iter()
+            .map(|(objective, value)| self.weight(*objective) * value)
+            .sum()
+    }
+}
+
+/// Objective weights for every persona.
+#[derive(Clone, Debug, Default, Serialize, Deserialize)]
+pub struct PersonaRuleset {
+    pub personas: BTreeMap<Persona, ObjectiveWeights>,
+}
+
+impl PersonaRuleset {
+    /// Load a ruleset from JSON.
+    pub fn from_json(json: &str) -> Result<Self, AiError> {
+        serde_json::from_str(json).map_err(|e| AiError::InvalidRuleset(e.to_string()))
+    }
+
+    /// The built-in ruleset.
+    pub fn builtin() -> &'static PersonaRuleset {
+        static BUILTIN: OnceLock<PersonaRuleset> = OnceLock::new();
+        BUILTIN.get_or_init(|| {
+            PersonaRuleset::from_json(AI_PERSONAS_RULESET).expect("built-in ruleset is valid")
+        })
+    }
+
+    /// Weights for a persona. Personas missing from the ruleset value
+    /// nothing.
+    pub fn weights(&self, persona: Persona) -> ObjectiveWeights {
+        self.personas.get(&persona).copied().unwrap_or_default()
+    }
+}
+
+/// The persona an AI seat plays when the game settings don't choose one.
+pub fn assign_persona(seed: &[u8; 32], player_id: PlayerSlot) -> Persona {
+    let hash = fnv1a(fnv1a(FNV_OFFSET, seed), &[player_id.get()]);
+    Persona::ALL[(hash % Persona::ALL.len() as u64) as usize]
+}
+
+/// An action the AI could take, with its value towards each objective.
+#[derive(Clone, Debug)]
+pub struct Candidate {
+    pub action: GameAction,
+    pub values: Vec<(Objective, i32)>,
+}
+
+impl Candidate {
+    fn new(action: GameAction, values: &[(Objective, i32)]) -> Self {
+        Self {
+            action,
+            values: values.to_vec(),
+        }
+    }
+}
+
+/// List the actions open to a player, in deterministic order.
+///
+/// Candidates aren't checked against the rules; [`choose_action`] skips
+/// the ones the engine would reject.
+pub fn candidates(engine: &GameEngine, player_id: PlayerSlot) -> Vec<Candidate> {
+    let state = &engine.state;
+    let mut candidates = Vec::new();
+
+    research_candidates(state, player_id, &mut candidates);
+    for city_id in idle_cities(state, player_id) {
+        city_candidates(state, city_id, &mut candidates);
+    }
+    for unit_id in ready_units(state, player_id) {
+        unit_candidates(engine, unit_id, &mut candidates);
+    }
+    war_candidates(state, player_id, &mut candidates);
+    candidates
+}
+
+/// Pick the action a player should take next.
+///
+/// Returns `None` when no valid candidate scores above zero.
+pub fn choose_action(
+    engine: &GameEngine,
+    player_id: PlayerSlot,
+    weights: &ObjectiveWeights,
+) -> Option<GameAction> {
+    best_candidate(engine, player_id, weights, candidates(engine, player_id))
+}
+
+/// Play an AI seat's turn, up to but not including ending it, with no
+/// budget limit.
+///
+/// Returns the actions taken, in order. Does nothing for human seats.
+pub fn play_turn(
+    engine: &mut GameEngine,
+    player_id: PlayerSlot,
+) -> Result<Vec<GameAction>, ReplayError> {
+    let tick = AiPlanner::new(player_id).tick(engine, &AiBudget::UNLIMITED)?;
+    Ok(tick.actions)
+}
+
+/// Limits on how much the AI may think, in candidates evaluated.
+///
+/// Every planning step counts at least one candidate, even if it had none
+/// to evaluate.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct AiBudget {
+    /// Most candidates a single [`AiPlanner::tick`] may evaluate. A tick
+    /// always makes at least one planning step, however small this is.
+    pub tick_nodes: u64,
+    /// Total candidates across all ticks of a turn. Once they are spent
+    /// the planner gives up on the rest of its plan.
+    pub turn_nodes: u64,
+}
+
+impl AiBudget {
+    /// No limits, for tests and headless games.
+    pub const UNLIMITED: AiBudget = AiBudget {
+        tick_nodes: u64::MAX,
+        turn_nodes: u64::MAX,
+    };
+}
+
+impl Default for AiBudget {
+    fn default() -> Self {
+        Self {
+            tick_nodes: 256,
+            turn_nodes: 65_536,
+        }
+    }
+}
+
+/// What an AI still has to plan this turn.
+#[derive(Clone, Debug, PartialEq, Eq)]
+enum PlanStage {
+    Research,
+    /// Units left to move, by ID.
+    Units(VecDeque<UnitId>),
+    /// Cities left to choose production for, by ID.
+    Cities(VecDeque<CityId>),
+    Diplomacy,
+    Done,
+}
+
+/// The outcome of one planning tick.
+#[derive(Clone, Debug, Default, Serialize, Deserialize)]
+pub struct AiTick {
+    /// Actions taken during the tick, in order.
+    pub actions: Vec<GameAction>,
+    /// Whether the turn's plan is complete and the seat can end its turn.
+    pub finished: bool,
+    /// Whether the plan was cut short because the turn budget ran out.
+    pub out_of_budget: bool,
+}
+
+/// Plans an AI seat's turn a little at a time.
+///
+/// Large empires take a while to evaluate, so rather than planning a whole
+/// turn at once the planner works through research, then each unit, then
+/// each city, then diplomacy, and stops whenever a tick's budget is spent.
+/// The remaining work is kept for the next tick. Splitting a turn across
+/// ticks doesn't change the actions chosen, only when they are taken;
+/// only running out of the turn budget does.
+///
+/// Actions go through [`GameEngine::submit_action`], so they're committed
+/// with the seat's end of turn like a local player's.
+#[derive(Clone, Debug)]
+pub struct AiPlanner {
+    player_id: PlayerSlot,
+    turn: Option<u32>,
+    stage: PlanStage,
+    /// Candidates evaluated this turn.
+    spent: u64,
+    /// Actions taken this turn.
+    taken: usize,
+}
+
+impl AiPlanner {
+    /// Create a planner for a seat.
+    pub fn new(player_id: PlayerSlot) -> Self {
+        Self {
+            player_id,
+            turn: None,
+            stage: PlanStage::Research,
+            spent: 0,
+            taken: 0,
+        }
+    }
+
+    /// The seat being planned for.
+    pub fn player_id(&self) -> PlayerSlot {
+        self.player_id
+    }
+
+    /// Whether this turn's plan is complete.
+    pub fn is_finished(&self) -> bool {
+        self.stage == PlanStage::Done
+    }
+
+    /// Plan until the tick budget is spent or the turn's plan is complete.
+    pub fn tick(
+        &mut self,
+        engine: &mut GameEngine,
+        budget: &AiBudget,
+    ) -> Result<AiTick, ReplayError> {
+        if self.turn != Some(engine.state.turn) {
+            *self = Self::new(self.player_id);
+            self.turn = Some(engine.state.turn);
+        }
+        let Some(persona) = engine.state.ai_persona(self.player_id) else {
+            self.stage = PlanStage::Done;
+            return Ok(AiTick {
+                finished: true,
+                ..AiTick::default()
+            });
+        };
+        let weights = PersonaRuleset::builtin().weights(persona);
+
+        let mut tick = AiTick::default();
+        let tick_start = self.spent;
+        while !self.is_finished() {
+            if self.spent >= budget.turn_nodes {
+                self.stage = PlanStage::Done;
+                tick.out_of_budget = true;
+                break;
+            }
+            if let Some(action) = self.step(engine, &weights)? {
+                tick.actions.push(action);
+            }
+            if self.spent - tick_start >= budget.tick_nodes {
+                break;
+            }
+        }
+        tick.finished = self.is_finished();
+        Ok(tick)
+    }
+
+    /// Do one unit of planning, taking at most one action.
+    fn step(
+        &mut self,
+        engine: &mut GameEngine,
+        weights: &ObjectiveWeights,
+    ) -> Result<Option<GameAction>, ReplayError> {
+        if self.taken >= MAX_ACTIONS_PER_TURN {
+            self.stage = PlanStage::Done;
+            return Ok(None);
+        }
+
+        let state = &engine.state;
+        let mut candidates = Vec::new();
+        match &self.stage {
+            PlanStage::Research => research_candidates(state, self.player_id, &mut candidates),
+            PlanStage::Units(queue) => {
+                if let Some(&unit_id) = queue.front() {
+                    unit_candidates(engine, unit_id, &mut candidates);
+                }
+            }
+            PlanStage::Cities(queue) => {
+                if let Some(&city_id) = queue.front() {
+                    city_candidates(state, city_id, &mut candidates);
+                }
+            }
+            PlanStage::Diplomacy => war_candidates(state, self.player_id, &mut candidates),
+            PlanStage::Done => {}
+        }
+        self.spent = self.spent.saturating_add(candidates.len().max(1) as u64);
+
+        let Some(action) = best_candidate(engine, self.player_id, weights, candidates) else {
+            // Nothing left worth doing with this entity
+            self.advance(state);
+            return Ok(None);
+        };
+
+        let result = engine.submit_action(self.player_id, &action)?;
+        if !result.success {
+            self.stage = PlanStage::Done;
+            return Ok(None);
+        }
+        self.taken += 1;
+        Ok(Some(action))
+    }
+
+    /// Move on to the next entity to plan for.
+    fn advance(&mut self, state: &GameState) {
+        self.stage = match std::mem::replace(&mut self.stage, PlanStage::Done) {
+            PlanStage::Research => PlanStage::Units(ready_units(state, self.player_id).into()),
+            PlanStage::Units(mut queue) => {
+                queue.pop_front();
+                if queue.is_empty() {
+                    PlanStage::Cities(idle_cities(state, self.player_id).into())
+                } else {
+                    PlanStage::Units(queue)
+                }
+            }
+            PlanStage::Cities(mut queue) => {
+                queue.pop_front();
+                if queue.is_empty() {
+                    PlanStage::Diplomacy
+                } else {
+                    PlanStage::Cities(queue)
+                }
+            }
+            PlanStage::Diplomacy | PlanStage::Done => PlanStage::Done,
+        };
+    }
+}
+
+/// The valid candidate with the highest positive score, preferring the
+/// first on ties.
+fn best_candidate(
+    engine: &GameEngine,
+    player_id: PlayerSlot,
+    weights: &ObjectiveWeights,
+    candidates: Vec<Candidate>,
+) -> Option<GameAction> {
+    let mut best: Option<(i32, GameAction)> = None;
+    for candidate in candidates {
+        let score = weights.score(&candidate.values);
+        if score <= 0 || best.as_ref().is_some_and(|(b, _)| score <= *b) {
+            continue;
+        }
+        if engine.is_valid_action(player_id, &candidate.action) {
+            best = Some((score, candidate.action));
+        }
+    }
+    best.map(|(_, action)| action)
+}
+
+/// A player's units that can still act this turn, by ID.
+fn ready_units(state: &GameState, player_id: PlayerSlot) -> Vec<UnitId> {
+    let mut units: Vec<UnitId> = state
+        .units
+        .values()
+        .filter(|u| u.owner == player_id)
+        .map(|u| u.id)
+        .collect();
+    units.sort_unstable();
+    units
+}
+
+/// A player's cities with nothing in production, by ID.
+fn idle_cities(state: &GameState, player_id: PlayerSlot) -> Vec<CityId> {
+    let mut cities: Vec<CityId> = state
+        .cities
+        .values()
+        .filter(|c| c.owner == player_id && c.production.is_none() && !c.puppet)
+        .map(|c| c.id)
+        .collect();
+    cities.sort_unstable();
+    cities
+}
+
+fn unit_candidates(engine: &GameEngine, unit_id: UnitId, out: &mut Vec<Candidate>) {
+    let Some(unit) = engine.state.units.get(&unit_id) else {
+        return;
+    };
+    if unit.has_acted || unit.sleeping || unit.movement == 0 {
+        return;
+    }
+    if unit.unit_type == UnitType::Settler {
+        settler_candidates(&engine.state, unit, out);
+    } else if unit.is_military() && unit.can_attack() {
+        military_candidates(engine, unit, out);
+    }
+}
+
+fn research_candidates(state: &GameState, player_id: PlayerSlot, out: &mut Vec<Candidate>) {
+    let Some(player) = state.get_player(player_id) else {
+        return;
+    };
+    if player.current_research.is_some() {
+        return;
+    }
+    let tree = TechTree::new();
+    let mut techs = tree.available_techs(&player.technologies);
+    techs.sort_unstable_by(|a, b| a.id.cmp(&b.id));
+    for tech in techs {
+        let unlocks = &tech.unlocks;
+        let military = unlocks
+            .units
+            .iter()
+            .filter(|u| u.stats().combat_strength > 0)
+            .count() as i32;
+        let values = [
+            (Objective::Science, 3),
+            (Objective::Military, 2 * military),
+            (Objective::Growth, unlocks.buildings.len() as i32),
+            (Objective::Growth, unlocks.improvements.len() as i32),
+        ];
+        out.push(Candidate::new(
+            GameAction::SetResearch {
+                tech_id: tech.id.clone(),
+            },
+            &values,
+        ));
+    }
+}
+
+fn city_candidates(state: &GameState, city_id: CityId, out: &mut Vec<Candidate>) {
+    let Some(city) = state.cities.get(&city_id) else {
+        return;
+    };
+    if city.production.is_some() {
+        return;
+    }
+    for option in production_options(state, city) {
+        out.push(Candidate {
+            action: GameAction::SetProduction {
+                city_id,
+                item: option.item,
+            },
+            values: option.values,
+        });
+    }
+}
+
+fn settler_candidates(state: &GameState, settler: &Unit, out: &mut Vec<Candidate>) {
+    let Some(site) = settle_site(state, settler.owner, settler.position) else {
+        return;
+    };
+    if site == settler.position {
+        out.push(Candidate::new(
+            GameAction::FoundCity {
+                settler_id: settler.id,
+                name: city_name(state, settler.owner),
+            },
+            &[(Objective::Expansion, 8), (Objective::Growth, 2)],
+        ));
+    } else if let Some(step) = step_toward(state, settler.position, site) {
+        out.push(Candidate::new(
+            move_to(settler, step),
+            &[(Objective::Expansion, 4)],
+        ));
+    }
+}
+
+fn military_candidates(engine: &GameEngine, unit: &Unit, out: &mut Vec<Candidate>) {
+    let state = &engine.state;
+    let at_war = |owner: PlayerSlot| state.diplomacy.are_at_war(unit.owner, owner);
+    let range = if unit.is_ranged() { unit.range() } else { 1 };
+    let near_home = nearest_own_city(state, unit.owner, unit.position)
+        .is_some_and(|city| city.distance(&unit.position) <= 2);
+
+    let mut enemies: Vec<&Unit> = state
+        .units
+        .values()
+        .filter(|u| at_war(u.owner) && u.position.distance(&unit.position) <= range)
+        .collect();
+    enemies.sort_unstable_by_key(|u| u.id);
+    for enemy in enemies {
+        let Some(preview) = engine.preview_attack(unit.id, enemy.id) else {
+            continue;
+        };
+        if preview.attacker_kill_chance > 50 {
+            continue;
+        }
+        let gain = 4 + preview.defender_kill_chance as i32 / 10;
+        let defense = if near_home { gain } else { 0 };
+        out.push(Candidate::new(
+            GameAction::AttackUnit {
+                attacker_id: unit.id,
+                defender_id: enemy.id,
+                random: combat_roll(state, unit).to_f32(),
+            },
+            &[(Objective::Military, gain), (Objective::Defense, defense)],
+        ));
+    }
+
+    let mut targets: Vec<_> = state.cities.values().filter(|c| at_war(c.owner)).collect();
+    targets.sort_unstable_by_key(|c| (c.position.distance(&unit.position), c.id));
+    if let Some(city) = targets.first() {
+        if city.position.distance(&unit.position) <= range {
+            out.push(Candidate::new(
+                GameAction::AttackCity {
+                    attacker_id: unit.id,
+                    city_id: city.id,
+                    random: combat_roll(state, unit).to_f32(),
+                },
+                &[(Objective::Military, 6)],
+            ));
+        } else if let Some(step) = step_toward(state, unit.position, city.position) {
+            out.push(Candidate::new(
+                move_to(unit, step),
+                &[(Objective::Military, 2)],
+            ));
+        }
+    }
+
+    if near_home && !unit.fortified {
+        out.push(Candidate::new(
+            GameAction::FortifyUnit { unit_id: unit.id },
+            &[(Objective::Defense, 2)],
+        ));
+    } else if let Some(step) = explore_step(state, unit) {
+        out.push(Candidate::new(
+            move_to(unit, step),
+            &[(Objective::Expansion, 1)],
+        ));
+    }
+}
+
+fn war_candidates(state: &GameState, player_id: PlayerSlot, out: &mut Vec<Candidate>) {
+    let soldiers = Demographic::Soldiers.value(state, player_id);
+    for other in &state.players {
+        if other.id == player_id
+            || other.eliminated
+            || !state.has_met(player_id, other.id)
+            || state.diplomacy.are_at_war(player_id, other.id)
+        {
+            continue;
+        }
+        // Only worth considering with twice the rival's army
+        if soldiers < 2 * Demographic::Soldiers.value(state, other.id).max(1) {
+            continue;
+        }
+        out.push(Candidate::new(
+            GameAction::DeclareWar {
+                target_player: other.id,
+            },
+            &[
+                (Objective::Military, 6),
+                (Objective::Growth, -4),
+                (Objective::Defense, -3),
+            ],
+        ));
+    }
+}
+
+/// The nearest tile a player's settler could found a city on, without
+/// leaving the continent it stands on.
+fn settle_site(state: &GameState, player_id: PlayerSlot, from: HexCoord) -> Option<HexCoord> {
+    state
+        .map
+        .tiles
+        .iter()
+        .filter(|(coord, tile)| {
+            coord.distance(&from) <= SETTLE_SEARCH_RADIUS
+                && tile.can_found_city()
+                && state.map.same_continent(coord, &from)
+                && tile.owner.is_none_or(|owner| owner == player_id)
+                && state
+                    .cities
+                    .values()
+                    .all(|city| city.position.distance(coord) >= MIN_CITY_DISTANCE)
+        })
+        .map(|(coord, _)| *coord)
+        .min_by_key(|coord| (coord.distance(&from), coord.q, coord.r))
+}
+
+fn nearest_own_city(state: &GameState, player_id: PlayerSlot, from: HexCoord) -> Option<HexCoord> {
+    state
+        .cities
+        .values()
+        .filter(|c| c.owner == player_id)
+        .map(|c| c.position)
+        .min_by_key(|pos| (pos.distance(&from), pos.q, pos.r))
+}
+
+/// The neighbor of `from` that gets closest to `target`, if any gets
+/// closer at all.
+fn step_toward(state: &GameState, from: HexCoord, target: HexCoord) -> Option<HexCoord> {
+    let current = from.distance(&target);
+    from.neighbors()
+        .into_iter()
+        .filter(|n| n.distance(&target) < current && state.map.get(n).is_some())
+        .min_by_key(|n| n.distance(&target))
+}
+
+/// The neighbor that reveals the most unexplored tiles.
+fn explore_step(state: &GameState, unit: &Unit) -> Option<HexCoord> {
+    let explored = &state.get_player(unit.owner)?.explored_tiles;
+    let unexplored = |coord: &HexCoord| {
+        coord
+            .neighbors()
+            .iter()
+            .filter(|n| state.map.get(n).is_some() && !explored.contains(n))
+            .count()
+    };
+    let mut best = None;
+    let mut best_count = 0;
+    for step in unit.position.neighbors() {
+        if state.map.get(&step).is_none() {
+            continue;
+        }
+        let count = unexplored(&step);
+        if count > best_count {
+            best = Some(step);
+            best_count = count;
+        }
+    }
+    best
+}
+
+fn move_to(unit: &Unit, step: HexCoord) -> GameAction {
+    GameAction::MoveUnit {
+        unit_id: unit.id,
+        path: vec![step],
+    }
+}
+
+/// A combat roll derived from the game seed, the turn and the attacker.
+fn combat_roll(state: &GameState, attacker: &Unit) -> Fixed {
+    let hash = fnv1a(
+        fnv1a(FNV_OFFSET, &state.seed),
+        &[
+            &state.turn.to_le_bytes()[..],
+            &attacker.id.get().to_le_bytes(),
+        ]
+        .concat(),
+    );
+    Fixed::from_ratio((hash % 1000) as i64, 1000)
+}
+
+/// An English name for a player's next city.
+fn city_name(state: &GameState, player_id: PlayerSlot) -> String {
+    let used: Vec<String> = state.cities.values().map(|c| c.name.clone()).collect();
+    state
+        .get_player(player_id)
+        .and_then(|p| p.civilization.suggest_city_name(&used))
+        .map(|name| Catalog::english().format(&name))
+        .unwrap_or_else(|| format!("City {}", used.len() + 1))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::city::{BuildingType, ProductionItem};
+    use crate::settings::GameSettings;
+    use crate::types::Era;
+
+    fn ai_duel(seed: [u8; 32], persona: Persona) -> GameEngine {
+        let mut settings = GameSettings::new("Test".to_string());
+        settings.map_size = crate::types::MapSize::Duel;
+        settings.ai_players = vec![PlayerSlot(0)];
+        settings.ai_personas.insert(PlayerSlot(0), persona);
+        let mut engine = GameEngine::new(settings, seed);
+        for (id, civ) in [(0, "rome"), (1, "egypt")] {
+            engine
+                .apply_action(
+                    PlayerSlot(id),
+                    &GameAction::JoinGame {
+                        player_name: format!("P{}", id),
+                        civilization_id: civ.to_string(),
+                    },
+                )
+                .unwrap();
+        }
+        engine
+            .apply_action(PlayerSlot(0), &GameAction::StartGame)
+            .unwrap();
+        engine
+    }
+
+    #[test]
+    fn test_builtin_ruleset_defines_every_persona() {
+        let ruleset = PersonaRuleset::builtin();
+        for persona in Persona::ALL {
+            assert!(ruleset.personas.contains_key(&persona));
+        }
+        assert_eq!(ruleset.weights(Persona::Aggressive).military, 10);
+        assert_eq!(ruleset.weights(Persona::Scientific).science, 10);
+
+        assert!(matches!(
+            PersonaRuleset::from_json("{\"personas\": {\"reckless\": {}}}"),
+            Err(AiError::InvalidRuleset(_))
+        ));
+    }
+
+    #[test]
+    fn test_assign_persona_is_deterministic() {
+        let seed = [7u8; 32];
+        assert_eq!(
+            assign_persona(&seed, PlayerSlot(1)),
+            assign_persona(&seed, PlayerSlot(1))
+        );
+
+        // Different seats and seeds spread across the personas
+        let assigned: std::collections::HashSet<Persona> = (0..16)
+            .map(|id| assign_persona(&[id; 32], PlayerSlot(id)))
+            .collect();
+        assert!(assigned.len() > 1);
+    }
+
+    #[test]
+    fn test_ai_persona_only_for_ai_seats() {
+        let engine = ai_duel([42u8; 32], Persona::Defensive);
+        assert_eq!(
+            engine.state.ai_persona(PlayerSlot(0)),
+            Some(Persona::Defensive)
+        );
+        assert_eq!(engine.state.ai_persona(PlayerSlot(1)), None);
+
+        let mut state = engine.state.clone();
+        state.settings.ai_personas.clear();
+        assert_eq!(
+            state.ai_persona(PlayerSlot(0)),
+            Some(assign_persona(&state.seed, PlayerSlot(0)))
+        );
+    }
+
+    #[test]
+    fn test_score_weights_objectives() {
+        let weights = PersonaRuleset::builtin().weights(Persona::Defensive);
+        let war = [
+            (Objective::Military, 6),
+            (Objective::Growth, -4),
+            (Objective::Defense, -3),
+        ];
+        assert!(weights.score(&war) <= 0);
+        let aggressive = PersonaRuleset::builtin().weights(Persona::Aggressive);
+        assert!(aggressive.score(&war) > 0);
+    }
+
+    #[test]
+    fn test_personas_choose_differently() {
+        let first_build = |persona, era| {
+            let mut engine = ai_duel([42u8; 32], persona);
+            engine.state.players[0].era = era;
+            play_turn(&mut engine, PlayerSlot(0)).unwrap();
+            assert!(engine.state.players[0].current_research.is_some());
+            engine
+                .state
+                .cities
+                .values()
+                .find(|c| c.owner == PlayerSlot(0))
+                .and_then(|c| c.production.clone())
+        };
+
+        assert_eq!(
+            first_build(Persona::Expansionist, Era::Ancient),
+            Some(ProductionItem::Unit(UnitType::Settler))
+        );
+        assert_eq!(
+            first_build(Persona::Defensive, Era::Ancient),
+            Some(ProductionItem::Building(BuildingType::Walls))
+        );
+        assert_eq!(
+            first_build(Persona::Aggressive, Era::Ancient),
+            Some(ProductionItem::Unit(UnitType::Warrior))
+        );
+        // Libraries only become available in the Classical era
+        assert_eq!(
+            first_build(Persona::Scientific, Era::Classical),
+            Some(ProductionItem::Building(BuildingType::Library))
+        );
+    }
+
+    #[test]
+    fn test_play_turn_is_deterministic() {
+        let run = || {
+            let mut engine = ai_duel([9u8; 32], Persona::Aggressive);
+            let actions = play_turn(&mut engine, PlayerSlot(0)).unwrap();
+            (
+                serde_json::to_string(&actions).unwrap(),
+                crate::audit::state_hash(&engine.state),
+            )
+        };
+        let first = run();
+        assert_ne!(first.0, "[]");
+        assert_eq!(run(), first);
+
+        // Human seats are left alone
+        let mut engine = ai_duel([9u8; 32], Persona::Aggressive);
+        assert!(play_turn(&mut engine, PlayerSlot(1)).unwrap().is_empty());
+    }
+
+    #[test]
+    fn test_planner_spreads_turn_across_ticks() {
+        let mut engine = ai_duel([9u8; 32], Persona::Aggressive);
+        let expected =
+            play_turn(&mut ai_duel([9u8; 32], Persona::Aggressive), PlayerSlot(0)).unwrap();
+
+        let budget = AiBudget {
+            tick_nodes: 2,
+            turn_nodes: u64::MAX,
+        };
+        let mut planner = AiPlanner::new(PlayerSlot(0));
+        let mut actions = Vec::new();
+        let mut ticks = 0;
+        while !planner.is_finished() {
+            let tick = planner.tick(&mut engine, &budget).unwrap();
+            assert!(!tick.out_of_budget);
+            actions.extend(tick.actions);
+            ticks += 1;
+        }
+        assert!(ticks > 1);
+        assert_eq!(
+            serde_json::to_string(&actions).unwrap(),
+            serde_json::to_string(&expected).unwrap()
+        );
+
+        // A finished plan does nothing more until the next turn
+        let tick = planner.tick(&mut engine, &budget).unwrap();
+        assert!(tick.finished && tick.actions.is_empty());
+    }
+
+    #[test]
+    fn test_planner_stops_when_turn_budget_is_spent() {
+        let mut engine = ai_duel([9u8; 32], Persona::Aggressive);
+        let budget = AiBudget {
+            tick_nodes: 1,
+            turn_nodes: 2,
+        };
+        let mut planner = AiPlanner::new(PlayerSlot(0));
+        let first = planner.tick(&mut engine, &budget).unwrap();
+        assert!(!first.finished);
+        let mut last = first;
+        while !last.finished {
+            last = planner.tick(&mut engine, &budget).unwrap();
+        }
+        assert!(last.out_of_budget);
+
+        // The budget is renewed on the next turn
+        engine.state.turn += 1;
+        let tick = planner.tick(&mut engine, &budget).unwrap();
+        assert!(!tick.out_of_budget);
+    }
+}
@@ -0,0 +1,705 @@
+//! Determinism audit log for diagnosing desyncs.
+//!
+//! When audit mode is enabled on a [`GameEngine`](crate::replay::GameEngine),
+//! every applied action is recorded together with the random values it
+//! consumed, the integer inputs it read from the game state, and a hash of
+//! the resulting state. Two clients that replay the same event chain must
+//! produce identical logs; [`AuditLog::compare`] finds the first entry where
+//! they disagree and reports what differed.
+//!
+//! State hashes use FNV-1a over a canonical encoding of the game state.
+//! Hash maps and sets are visited in sorted order so the hash does not
+//! depend on iteration order, which differs between processes.
+
+use crate::events::GameAction;
+use crate::game_state::GameState;
+use crate::hex::HexCoord;
+use crate::settings::GameSettings;
+use crate::types::{CityId, PlayerSlot, UnitId};
+use serde::{Deserialize, Serialize};
+use std::fmt;
+
+pub(crate) const FNV_OFFSET: u64 = 0xcbf2_9ce4_8422_2325;
+const FNV_PRIME: u64 = 0x0000_0100_0000_01b3;
+
+/// A random value consumed while applying an action.
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct RngDraw {
+    /// Where the value was consumed (e.g. "combat", "map_seed").
+    pub source: String,
+    /// Raw bits of the value, so comparisons are exact.
+    pub bits: u64,
+}
+
+impl RngDraw {
+    /// Record a combat roll.
+    pub fn combat(random: f32) -> Self {
+        Self {
+            source: "combat".to_string(),
+            bits: random.to_bits() as u64,
+        }
+    }
+
+    /// Record a 32-byte seed, folded into 64 bits.
+    pub fn seed(source: &str, seed: &[u8; 32]) -> Self {
+        Self {
+            source: source.to_string(),
+            bits: fnv1a(FNV_OFFSET, seed),
+        }
+    }
+}
+
+/// An integer input read from the game state before an action was applied.
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct AuditInput {
+    /// Dotted name of the input (e.g. "unit.3.health").
+    pub name: String,
+    /// Value at the time the action was applied.
+    pub value: i64,
+}
+
+impl AuditInput {
+    fn new(name: impl Into<String>, value: impl Into<i64>) -> Self {
+        Self {
+            name: name.into(),
+            value: value.into(),
+        }
+    }
+}
+
+/// A single audited action.
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct AuditEntry {
+    /// Position of this action in the audit log.
+    pub index: usize,
+    /// Player who submitted the action.
+    pub player_id: PlayerSlot,
+    /// Canonical JSON encoding of the action.
+    pub action: String,
+    /// Random values consumed by the action.
+    pub rng_draws: Vec<RngDraw>,
+    /// State inputs read by the action.
+    pub inputs: Vec<AuditInput>,
+    /// Whether the action was accepted.
+    pub accepted: bool,
+    /// Hash of the game state after the action.
+    pub state_hash: u64,
+}
+
+/// What differed between two audit entries.
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub enum DivergenceKind {
+    /// One log has an entry the other lacks.
+    MissingEntry,
+    /// The actions themselves differ.
+    Action,
+    /// A random draw differs.
+    RngDraw { draw: usize },
+    /// A state input differs.
+    Input { name: String },
+    /// One client accepted the action and the other rejected it.
+    Outcome,
+    /// Inputs matched but the resulting state did not.
+    StateHash,
+}
+
+impl fmt::Display for DivergenceKind {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            DivergenceKind::MissingEntry => write!(f, "missing entry"),
+            DivergenceKind::Action => write!(f, "different action"),
+            DivergenceKind::RngDraw { draw } => write!(f, "random draw {} differs", draw),
+            DivergenceKind::Input { name } => write!(f, "input {} differs", name),
+            DivergenceKind::Outcome => write!(f, "action outcome differs"),
+            DivergenceKind::StateHash => write!(f, "resulting state differs"),
+        }
+    }
+}
+
+/// The first point at which two audit logs disagree.
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct AuditDivergence {
+    /// Index of the first differing entry.
+    pub index: usize,
+    /// What differed.
+    pub kind: DivergenceKind,
+    /// Entry from the local log, if present.
+    pub local: Option<AuditEntry>,
+    /// Entry from the remote log, if present.
+    pub remote: Option<AuditEntry>,
+}
+
+impl fmt::Display for AuditDivergence {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "desync at action {}: {}", self.index, self.kind)
+    }
+}
+
+/// Ordered log of audited actions.
+#[derive(Clone, Debug, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct AuditLog {
+    entries: Vec<AuditEntry>,
+}
+
+impl AuditLog {
+    /// Create an empty audit log.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Record an applied action.
+    pub fn record(
+        &mut self,
+        player_id: PlayerSlot,
+        action: &GameAction,
+        inputs: Vec<AuditInput>,
+        accepted: bool,
+        state_hash: u64,
+    ) {
+        let action_json = serde_json::to_value(action)
+            .map(|v| v.to_string())
+            .unwrap_or_else(|_| format!("{:?}", action));
+
+        self.entries.push(AuditEntry {
+            index: self.entries.len(),
+            player_id,
+            action: action_json,
+            rng_draws: rng_draws(action),
+            inputs,
+            accepted,
+            state_hash,
+        });
+    }
+
+    /// All recorded entries.
+    pub fn entries(&self) -> &[AuditEntry] {
+        &self.entries
+    }
+
+    /// Number of recorded entries.
+    pub fn len(&self) -> usize {
+        self.entries.len()
+    }
+
+    /// Whether nothing has been recorded.
+    pub fn is_empty(&self) -> bool {
+        self.entries.is_empty()
+    }
+
+    /// State hash after the most recent entry.
+    pub fn last_hash(&self) -> Option<u64> {
+        self.entries.last().map(|e| e.state_hash)
+    }
+
+    /// Find the first entry where this log and `other` disagree.
+    ///
+    /// Returns `None` if the logs are identical.
+    pub fn compare(&self, other: &AuditLog) -> Option<AuditDivergence> {
+        let len = self.entries.len().max(other.entries.len());
+        (0..len).find_map(|index| {
+            let local = self.entries.get(index);
+            let remote = other.entries.get(index);
+            let kind = match (local, remote) {
+                (Some(a), Some(b)) => diff_entries(a, b)?,
+                _ => DivergenceKind::MissingEntry,
+            };
+            Some(AuditDivergence {
+                index,
+                kind,
+                local: local.cloned(),
+                remote: remote.cloned(),
+            })
+        })
+    }
+}
+
+/// Compare two entries, checking causes before effects.
+fn diff_entries(a: &AuditEntry, b: &AuditEntry) -> Option<DivergenceKind> {
+    if a.player_id != b.player_id || a.action != b.action {
+        return Some(DivergenceKind::Action);
+    }
+    if a.rng_draws.len() != b.rng_draws.len() {
+        return Some(DivergenceKind::RngDraw {
+            draw: a.rng_draws.len().min(b.rng_draws.len()),
+        });
+    }
+    if let Some(draw) = a
+        .rng_draws
+        .iter()
+        .zip(&b.rng_draws)
+        .position(|(x, y)| x != y)
+    {
+        return Some(DivergenceKind::RngDraw { draw });
+    }
+    if let Some((x, _)) = a.inputs.iter().zip(&b.inputs).find(|(x, y)| x != y) {
+        return Some(DivergenceKind::Input {
+            name: x.name.clone(),
+        });
+    }
+    if a.inputs.len() != b.inputs.len() {
+        let longer = if a.inputs.len() > b.inputs.len() {
+            a
+        } else {
+            b
+        };
+        return Some(DivergenceKind::Input {
+            name: longer.inputs[a.inputs.len().min(b.inputs.len())]
+                .name
+                .clone(),
+        });
+    }
+    if a.accepted != b.accepted {
+        return Some(DivergenceKind::Outcome);
+    }
+    if a.state_hash != b.state_hash {
+        return Some(DivergenceKind::StateHash);
+    }
+    None
+}
+
+/// Random values carried by an action.
+fn rng_draws(action: &GameAction) -> Vec<RngDraw> {
+    match action {
+        GameAction::AttackUnit { random, .. }
+        | GameAction::AttackCity { random, .. }
+        | GameAction::BombardUnit { random, .. } => {
+            vec![RngDraw::combat(*random)]
+        }
+        GameAction::CreateGame { seed, .. } => vec![RngDraw::seed("game_seed", seed)],
+        _ => Vec::new(),
+    }
+}
+
+/// Capture the state inputs an action is about to read.
+pub fn capture_inputs(
+    state: &GameState,
+    player_id: PlayerSlot,
+    action: &GameAction,
+) -> Vec<AuditInput> {
+    let mut inputs = vec![
+        AuditInput::new("turn", state.turn),
+        AuditInput::new("current_player", state.current_player.get()),
+    ];
+    if let Some(player) = state.players.get(player_id.index()) {
+        inputs.push(AuditInput::new("player.gold", player.gold));
+    }
+
+    let push_unit = |inputs: &mut Vec<AuditInput>, id: UnitId| {
+        if let Some(unit) = state.units.get(&id) {
+            inputs.push(AuditInput::new(format!("unit.{}.health", id), unit.health));
+            inputs.push(AuditInput::new(
+                format!("unit.{}.movement", id),
+                unit.movement,
+            ));
+            inputs.push(AuditInput::new(
+                format!("unit.{}.experience", id),
+                unit.experience,
+            ));
+            inputs.push(AuditInput::new(format!("unit.{}.q", id), unit.position.q));
+            inputs.push(AuditInput::new(format!("unit.{}.r", id), unit.position.r));
+        }
+    };
+    let push_city = |inputs: &mut Vec<AuditInput>, id: CityId| {
+        if let Some(city) = state.cities.get(&id) {
+            inputs.push(AuditInput::new(
+                format!("city.{}.population", id),
+                city.population,
+            ));
+            inputs.push(AuditInput::new(format!("city.{}.health", id), city.health));
+            inputs.push(AuditInput::new(
+                format!("city.{}.production_progress", id),
+                city.production_progress,
+            ));
+        }
+    };
+
+    match action {
+        GameAction::AttackUnit {
+            attacker_id,
+            defender_id,
+            ..
+        } => {
+            push_unit(&mut inputs, *attacker_id);
+            push_unit(&mut inputs, *defender_id);
+        }
+        GameAction::AttackCity {
+            attacker_id,
+            city_id,
+            ..
+        } => {
+            push_unit(&mut inputs, *attacker_id);
+            push_city(&mut inputs, *city_id);
+        }
+        GameAction::BombardUnit {
+            city_id, target_id, ..
+        } => {
+            push_city(&mut inputs, *city_id);
+            push_unit(&mut inputs, *target_id);
+        }
+        GameAction::ResolveCapture { city_id, .. } => push_city(&mut inputs, *city_id),
+        GameAction::GiftUnit { unit_id, .. } => push_unit(&mut inputs, *unit_id),
+        GameAction::StartGame => {
+            inputs.push(AuditInput::new("players", state.players.len() as i64));
+            inputs.push(AuditInput::new(
+                "seed",
+                fnv1a(FNV_OFFSET, &state.seed) as i64,
+            ));
+        }
+        GameAction::MoveUnit { unit_id, .. }
+        | GameAction::FortifyUnit { unit_id, .. }
+        | GameAction::FortifyUntilHealed { unit_id, .. }
+        | GameAction::SleepUnit { unit_id, .. }
+        | GameAction::WakeUnit { unit_id, .. }
+        | GameAction::DeleteUnit { unit_id, .. }
+        | GameAction::UpgradeUnit { unit_id, .. }
+        | GameAction::ChoosePromotion { unit_id, .. }
+        | GameAction::BuildImprovement { unit_id, .. }
+        | GameAction::BuildRoad { unit_id, .. }
+        | GameAction::RemoveFeature { unit_id, .. } => push_unit(&mut inputs, *unit_id),
+        GameAction::FoundCity { settler_id, .. } => push_unit(&mut inputs, *settler_id),
+        GameAction::SetProduction { city_id, .. }
+        | GameAction::BuyItem { city_id, .. }
+        | GameAction::AssignCitizen { city_id, .. }
+        | GameAction::UnassignCitizen { city_id, .. }
+        | GameAction::SellBuilding { city_id, .. }
+        | GameAction::BuyTile { city_id, .. } => push_city(&mut inputs, *city_id),
+        _ => {}
+    }
+
+    inputs
+}
+
+/// Compute a process-independent hash of the game state.
+pub fn state_hash(state: &GameState) -> u64 {
+    let mut h = StateHasher::new();
+
+    h.u64(state.turn as u64);
+    h.u64(state.current_player.get() as u64);
+    h.str(&format!("{:?}", state.phase));
+    h.str(&format!("{:?}", state.winner));
+    h.str(&format!("{:?}", state.pause));
+    h.u64(state.revealed as u64);
+    h.str(&format!("{:?}", state.circumnavigated_by));
+    let mut wonders: Vec<String> = state.wonders.values().map(|w| format!("{:?}", w)).collect();
+    wonders.sort();
+    for wonder in wonders {
+        h.str(&wonder);
+    }
+    h.u64(state.next_unit_id.get());
+    h.u64(state.next_city_id.get());
+
+    for player in &state.players {
+        h.u64(player.id.get() as u64);
+        h.i64(player.gold as i64);
+        h.u64(player.research_progress as u64);
+        h.str(&format!("{:?}", player.current_research));
+        h.str(&format!("{:?}", player.research_queue));
+        let mut techs: Vec<&String> = player.technologies.iter().collect();
+        techs.sort();
+        for tech in techs {
+            h.str(tech);
+        }
+        let mut traded: Vec<&String> = player.traded_techs.iter().collect();
+        traded.sort();
+        for tech in traded {
+            h.str(tech);
+        }
+        h.str(&format!("{:?}", player.capital));
+        h.u64(player.eliminated as u64);
+        h.u64(player.conceded as u64);
+        h.u64(player.is_ai as u64);
+        h.i64(player.happiness as i64);
+        h.u64(player.era.index() as u64);
+        for (resource, amount) in &player.stockpile {
+            h.str(&format!("{:?}", resource));
+            h.u64(*amount as u64);
+        }
+        let mut met: Vec<PlayerSlot> = player.met_players.iter().copied().collect();
+        met.sort_unstable();
+        for other in met {
+            h.u64(other.get() as u64);
+        }
+    }
+
+    let mut unit_ids: Vec<_> = state.units.keys().copied().collect();
+    unit_ids.sort_unstable();
+    for id in unit_ids {
+        let unit = &state.units[&id];
+        h.u64(id.get());
+        h.u64(unit.owner.get() as u64);
+        h.str(&format!("{:?}", unit.unit_type));
+        h.coord(unit.position);
+        h.u64(unit.health as u64);
+        h.u64(unit.movement as u64);
+        h.u64(unit.experience as u64);
+        h.u64(unit.fortified as u64);
+        h.u64(unit.embarked as u64);
+        h.u64(unit.has_acted as u64);
+        h.u64(unit.healing as u64);
+        h.str(&format!("{:?}", unit.road_work));
+        h.str(&format!("{:?}", unit.feature_work));
+    }
+
+    let mut city_ids: Vec<_> = state.cities.keys().copied().collect();
+    city_ids.sort_unstable();
+    for id in city_ids {
+        let city = &state.cities[&id];
+        h.u64(id.get());
+        h.u64(city.owner.get() as u64);
+        h.coord(city.position);
+        h.u64(city.population as u64);
+        h.u64(city.food_stored as u64);
+        h.u64(city.health as u64);
+        h.u64(city.production_progress as u64);
+        h.str(&format!("{:?}", city.production));
+        h.u64(city.culture as u64);
+        h.str(&format!("{:?}", city.last_bombard_turn));
+        h.u64(city.awaiting_capture_choice as u64);
+        h.u64(city.puppet as u64);
+        let mut wonders: Vec<String> = city.wonders.iter().map(|w| format!("{:?}", w)).collect();
+        wonders.sort();
+        for wonder in wonders {
+            h.str(&wonder);
+        }
+        let mut buildings: Vec<String> =
+            city.buildings.iter().map(|b| format!("{:?}", b)).collect();
+        buildings.sort();
+        for building in buildings {
+            h.str(&building);
+        }
+    }
+
+    let mut coords: Vec<HexCoord> = state.map.tiles.keys().copied().collect();
+    coords.sort_unstable_by_key(|c| (c.q, c.r));
+    for coord in coords {
+        let tile = &state.map.tiles[&coord];
+        h.coord(coord);
+        h.str(&format!(
+            "{:?}{:?}{:?}{:?}{:?}",
+            tile.terrain, tile.feature, tile.improvement, tile.road, tile.resource
+        ));
+        h.str(&format!("{:?}{:?}", tile.owner, tile.city_id));
+    }
+
+    let mut pairs: Vec<_> = state.diplomacy.relationships.iter().collect();
+    pairs.sort_unstable_by_key(|(k, _)| **k);
+    for ((a, b), rel) in pairs {
+        h.u64(a.get() as u64);
+        h.u64(b.get() as u64);
+        h.str(&format!("{:?}", rel.status));
+        h.u64(rel.turns_at_war as u64);
+        h.u64(rel.turns_at_peace as u64);
+    }
+
+    h.finish()
+}
+
+/// Compute a hash identifying the rules a game is played under.
+///
+/// Covers the engine version and every game setting except the display
+/// name, so two peers with the same hash will simulate the game the same
+/// way.
+pub fn ruleset_hash(settings: &GameSettings) -> u64 {
+    let mut h = StateHasher::new();
+    h.str(env!("CARGO_PKG_VERSION"));
+    let rules = GameSettings {
+        name: String::new(),
+        ..settings.clone()
+    };
+    h.str(&serde_json::to_string(&rules).unwrap_or_default());
+    h.finish()
+}
+
+/// FNV-1a over a byte slice, continuing from `hash`.
+pub(crate) fn fnv1a(mut hash: u64, bytes: &[u8]) -> u64 {
+    for byte in bytes {
+        hash ^= *byte as u64;
+        hash = hash.wrapping_mul(FNV_PRIME);
+    }
+    hash
+}
+
+struct StateHasher(u64);
+
+impl StateHasher {
+    fn new() -> Self {
+        Self(FNV_OFFSET)
+    }
+
+    fn u64(&mut self, value: u64) {
+        self.0 = fnv1a(self.0, &value.to_le_bytes());
+    }
+
+    fn i64(&mut self, value: i64) {
+        self.0 = fnv1a(self.0, &value.to_le_bytes());
+    }
+
+    fn str(&mut self, value: &str) {
+        self.u64(value.len() as u64);
+        self.0 = fnv1a(self.0, value.as_bytes());
+    }
+
+    fn coord(&mut self, coord: HexCoord) {
+        self.i64(coord.q as i64);
+        self.i64(coord.r as i64);
+    }
+
+    fn finish(&self) -> u64 {
+        self.0
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::types::GameId;
+
+    fn sample_state() -> GameState {
+        GameState::new(
+            GameId::new("g"),
+            GameSettings::new("T".to_string()),
+            [7u8; 32],
+        )
+    }
+
+    fn entry_log(actions: &[(PlayerSlot, GameAction, u64)]) -> AuditLog {
+        let mut log = AuditLog::new();
+        for (player, action, hash) in actions {
+            log.record(*player, action, Vec::new(), true, *hash);
+        }
+        log
+    }
+
+    // ==================== State Hash Tests ====================
+
+    #[test]
+    fn test_state_hash_is_stable_across_clones() {
+        let state = sample_state();
+        assert_eq!(state_hash(&state), state_hash(&state.clone()));
+    }
+
+    #[test]
+    fn test_state_hash_changes_with_state() {
+        let state = sample_state();
+        let mut changed = state.clone();
+        changed.turn += 1;
+        assert_ne!(state_hash(&state), state_hash(&changed));
+    }
+
+    #[test]
+    fn test_ruleset_hash_ignores_name() {
+        let settings = GameSettings::new("A".to_string());
+        let renamed = GameSettings::new("B".to_string());
+        assert_eq!(ruleset_hash(&settings), ruleset_hash(&renamed));
+
+        let mut changed = settings.clone();
+        changed.barbarians = !changed.barbarians;
+        assert_ne!(ruleset_hash(&settings), ruleset_hash(&changed));
+    }
+
+    // ==================== Comparator Tests ====================
+
+    #[test]
+    fn test_identical_logs_do_not_diverge() {
+        let actions = vec![
+            (PlayerSlot(0), GameAction::EndTurn, 1),
+            (PlayerSlot(1), GameAction::EndTurn, 2),
+        ];
+        assert!(entry_log(&actions).compare(&entry_log(&actions)).is_none());
+    }
+
+    #[test]
+    fn test_detects_state_hash_divergence() {
+        let a = entry_log(&[
+            (PlayerSlot(0), GameAction::EndTurn, 1),
+            (PlayerSlot(1), GameAction::EndTurn, 2),
+        ]);
+        let b = entry_log(&[
+            (PlayerSlot(0), GameAction::EndTurn, 1),
+            (PlayerSlot(1), GameAction::EndTurn, 3),
+        ]);
+        let div = a.compare(&b).unwrap();
+        assert_eq!(div.index, 1);
+        assert_eq!(div.kind, DivergenceKind::StateHash);
+        assert_eq!(
+            div.to_string(),
+            "desync at action 1: resulting state differs"
+        );
+    }
+
+    #[test]
+    fn test_detects_rng_divergence_before_hash() {
+        let attack = |random| GameAction::AttackUnit {
+            attacker_id: UnitId(1),
+            defender_id: UnitId(2),
+            random,
+        };
+        let a = entry_log(&[(PlayerSlot(0), attack(0.25), 1)]);
+        let b = entry_log(&[(PlayerSlot(0), attack(0.75), 2)]);
+        let div = a.compare(&b).unwrap();
+        // The action JSON also differs, so the action is reported first.
+        assert_eq!(div.kind, DivergenceKind::Action);
+
+        let mut b = a.clone();
+        b.entries[0].rng_draws[0].bits ^= 1;
+        b.entries[0].state_hash = 2;
+        assert_eq!(
+            a.compare(&b).unwrap().kind,
+            DivergenceKind::RngDraw { draw: 0 }
+        );
+    }
+
+    #[test]
+    fn test_detects_input_divergence() {
+        let mut a = AuditLog::new();
+        a.record(
+            PlayerSlot(0),
+            &GameAction::EndTurn,
+            vec![AuditInput::new("unit.1.health", 100)],
+            true,
+            1,
+        );
+        let mut b = a.clone();
+        b.entries[0].inputs[0].value = 90;
+        assert_eq!(
+            a.compare(&b).unwrap().kind,
+            DivergenceKind::Input {
+                name: "unit.1.health".to_string()
+            }
+        );
+    }
+
+    #[test]
+    fn test_detects_missing_entry() {
+        let a = entry_log(&[(PlayerSlot(0), GameAction::EndTurn, 1)]);
+        let b = AuditLog::new();
+        let div = a.compare(&b).unwrap();
+        assert_eq!(div.index, 0);
+        assert_eq!(div.kind, DivergenceKind::MissingEntry);
+        assert!(div.local.is_some());
+        assert!(div.remote.is_none());
+    }
+
+    #[test]
+    fn test_capture_inputs_for_attack() {
+        let mut state = sample_state();
+        let unit = crate::unit::Unit::new(
+            UnitId(1),
+            PlayerSlot(0),
+            crate::unit::UnitType::Warrior,
+            HexCoord::new(2, 3),
+        );
+        state.units.insert(UnitId(1), unit);
+        let inputs = capture_inputs(
+            &state,
+            PlayerSlot(0),
+            &GameAction::AttackUnit {
+                attacker_id: UnitId(1),
+                defender_id: UnitId(9),
+                random: 0.5,
+            },
+        );
+        assert!(inputs.iter().any(|i| i.name == "unit.1.q" && i.value == 2));
+        assert!(!inputs.iter().any(|i| i.name.starts_with("unit.9")));
+    }
+}
@@ -0,0 +1,271 @@
+//! Cultural border expansion.
+//!
+//! Cities claim territory one tile at a time as they accumulate culture.
+//! Candidate tiles are unowned tiles adjacent to a city's territory and
+//! within [`MAX_BORDER_RADIUS`] of the city center. The best candidate is
+//! picked by [`score_tile`]; ties are broken by coordinate so every peer
+//! claims the same tile. Tiles can also be bought outright with gold.
+
+use crate::city::City;
+use crate::game_state::GameState;
+use crate::hex::HexCoord;
+use crate::map::{Map, Tile};
+use crate::terrain::ResourceCategory;
+use crate::types::{CityId, PlayerSlot};
+
+/// Farthest a city's borders can reach from its center.
+pub const MAX_BORDER_RADIUS: u32 = 3;
+
+/// Culture every city produces per turn before buildings.
+pub const BASE_CITY_CULTURE: u32 = 1;
+
+/// Score a tile as a border expansion target (higher is better).
+///
+/// Food is weighted highest, then production and gold. Resources add a
+/// flat bonus even before they are improved, and distant tiles are
+/// penalized so borders grow outward evenly.
+pub fn score_tile(tile: &Tile, distance: u32) -> i32 {
+    let yields = tile.yields();
+    let mut score =
+        yields.food * 3 + yields.production * 2 + yields.gold * 2 + yields.science + yields.culture;
+
+    if let Some(resource) = &tile.resource {
+        score += match resource.category() {
+            ResourceCategory::Luxury => 6,
+            ResourceCategory::Strategic => 5,
+            ResourceCategory::Bonus => 3,
+        };
+    }
+    if tile.has_river() {
+        score += 1;
+    }
+
+    score - distance as i32 * 3
+}
+
+/// Check whether a city could claim a tile.
+pub fn can_claim(city: &City, map: &Map, coord: HexCoord) -> bool {
+    if city.territory.contains(&coord) || city.position.distance(&coord) > MAX_BORDER_RADIUS {
+        return false;
+    }
+    let Some(tile) = map.get(&coord) else {
+        return false;
+    };
+    tile.owner.is_none() && coord.neighbors().iter().any(|n| city.territory.contains(n))
+}
+
+/// Get all tiles a city could claim next, sorted by coordinate.
+pub fn border_candidates(city: &City, map: &Map) -> Vec<HexCoord> {
+    let mut candidates: Vec<HexCoord> = city
+        .position
+        .hexes_in_radius(MAX_BORDER_RADIUS)
+        .into_iter()
+        .filter(|coord| can_claim(city, map, *coord))
+        .collect();
+    candidates.sort_by_key(|c| (c.q, c.r));
+    candidates
+}
+
+/// Pick the tile a city claims on its next border expansion.
+pub fn next_border_tile(city: &City, map: &Map) -> Option<HexCoord> {
+    let mut best: Option<(i32, HexCoord)> = None;
+    for coord in border_candidates(city, map) {
+        let Some(tile) = map.get(&coord) else {
+            continue;
+        };
+        let score = score_tile(tile, city.position.distance(&coord));
+        // Candidates are sorted, so keeping the first maximum breaks ties
+        // by coordinate.
+        if best.is_none_or(|(s, _)| score > s) {
+            best = Some((score, coord));
+        }
+    }
+    best.map(|(_, coord)| coord)
+}
+
+/// Gold cost for a city to buy a tile.
+pub fn tile_purchase_cost(city: &City, coord: HexCoord) -> i32 {
+    let distance = city.position.distance(&coord) as i32;
+    25 + distance * 10 + city.territory.len() as i32 * 5
+}
+
+/// A tile that changed hands, for syncing borders to peers.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct TileClaim {
+    pub city_id: CityId,
+    pub owner: PlayerSlot,
+    pub coord: HexCoord,
+}
+
+/// Assign a tile to a city, updating both the city and the map.
+pub fn claim_tile(state: &mut GameState, city_id: CityId, coord: HexCoord) -> Option<TileClaim> {
+    let city = state.cities.get_mut(&city_id)?;
+    city.territory.insert(coord);
+    let owner = city.owner;
+
+    if let Some(tile) = state.map.get_mut(&coord) {
+        tile.owner = Some(owner);
+        tile.city_id = Some(city_id);
+    }
+
+    Some(TileClaim {
+        city_id,
+        owner,
+        coord,
+    })
+}
+
+/// Add a turn of culture to each of a player's cities and expand borders.
+///
+/// Cities are processed in ID order so claims are deterministic when two
+/// cities compete for the same tile.
+pub fn grow_borders(state: &mut GameState, player_id: PlayerSlot) -> Vec<TileClaim> {
+    // A claim only changes the claiming city's yields, so every city's
+    // culture can be computed up front
+    let culture_table = state.city_yield_table(player_id);
+
+    let mut claims = Vec::new();
+    for (city_id, yields) in culture_table {
+        let culture = yields.culture.max(0) as u32;
+        let Some(city) = state.cities.get_mut(&city_id) else {
+            continue;
+        };
+        city.culture += BASE_CITY_CULTURE + culture;
+
+        while state.cities[&city_id].should_expand_borders() {
+            let city = &state.cities[&city_id];
+            let Some(coord) = next_border_tile(city, &state.map) else {
+                break;
+            };
+            if let Some(city) = state.cities.get_mut(&city_id) {
+                city.expand_borders(coord);
+            }
+            claims.extend(claim_tile(state, city_id, coord));
+        }
+    }
+    claims
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::settings::GameSettings;
+    use crate::terrain::{Resource, Terrain};
+    use crate::types::GameId;
+
+    fn state_with_city() -> (GameState, CityId) {
+        let mut state = GameState::new(
+            GameId::new("test"),
+            GameSettings::new("Test".to_string()),
+            [0u8; 32],
+        );
+        state.map = Map::filled(20, 20, Terrain::Grassland);
+        let city = City::new(
+            CityId(1),
+            PlayerSlot(0),
+            "Rome".to_string(),
+            HexCoord::new(10, 10),
+            true,
+        );
+        for coord in city.territory.clone() {
+            if let Some(tile) = state.map.get_mut(&coord) {
+                tile.owner = Some(PlayerSlot(0));
+                tile.city_id = Some(CityId(1));
+            }
+        }
+        state.cities.insert(CityId(1), city);
+        (state, CityId(1))
+    }
+
+    // ==================== Scoring Tests ====================
+
+    #[test]
+    fn test_score_prefers_resources_and_nearby_tiles() {
+        let plain = Tile::new(HexCoord::new(0, 0), Terrain::Grassland);
+        let mut luxury = plain.clone();
+        luxury.resource = Some(Resource::Silk);
+
+        assert!(score_tile(&luxury, 2) > score_tile(&plain, 2));
+        assert!(score_tile(&plain, 2) > score_tile(&plain, 3));
+    }
+
+    #[test]
+    fn test_next_tile_is_adjacent_and_unowned() {
+        let (state, city_id) = state_with_city();
+        let city = &state.cities[&city_id];
+        let next = next_border_tile(city, &state.map).unwrap();
+
+        assert!(!city.territory.contains(&next));
+        assert_eq!(city.position.distance(&next), 2);
+        assert!(state.map.get(&next).unwrap().owner.is_none());
+    }
+
+    #[test]
+    fn test_next_tile_picks_resource() {
+        let (mut state, city_id) = state_with_city();
+        let target = border_candidates(&state.cities[&city_id], &state.map)[3];
+        state.map.get_mut(&target).unwrap().resource = Some(Resource::Iron);
+
+        assert_eq!(
+            next_border_tile(&state.cities[&city_id], &state.map),
+            Some(target)
+        );
+    }
+
+    #[test]
+    fn test_cannot_claim_out_of_range_or_owned() {
+        let (mut state, city_id) = state_with_city();
+        let far = HexCoord::new(10, 15);
+        assert!(!can_claim(&state.cities[&city_id], &state.map, far));
+
+        let next = next_border_tile(&state.cities[&city_id], &state.map).unwrap();
+        state.map.get_mut(&next).unwrap().owner = Some(PlayerSlot(1));
+        assert!(!can_claim(&state.cities[&city_id], &state.map, next));
+    }
+
+    // ==================== Growth Tests ====================
+
+    #[test]
+    fn test_grow_borders_claims_when_culture_reached() {
+        let (mut state, city_id) = state_with_city();
+        assert!(grow_borders(&mut state, PlayerSlot(0)).is_empty());
+
+        let needed = state.cities[&city_id].culture_for_next_tile();
+        state.cities.get_mut(&city_id).unwrap().culture = needed;
+        let claims = grow_borders(&mut state, PlayerSlot(0));
+
+        assert_eq!(claims.len(), 1);
+        let claim = claims[0];
+        assert_eq!(claim.owner, PlayerSlot(0));
+        assert!(state.cities[&city_id].territory.contains(&claim.coord));
+        assert_eq!(
+            state.map.get(&claim.coord).unwrap().owner,
+            Some(PlayerSlot(0))
+        );
+        assert_eq!(state.cities[&city_id].territory.len(), 8);
+        assert_eq!(state.cities[&city_id].culture, BASE_CITY_CULTURE);
+    }
+
+    #[test]
+    fn test_grow_borders_is_deterministic() {
+        let (mut a, city_id) = state_with_city();
+        let (mut b, _) = state_with_city();
+        for state in [&mut a, &mut b] {
+            state.cities.get_mut(&city_id).unwrap().culture = 1000;
+        }
+        assert_eq!(
+            grow_borders(&mut a, PlayerSlot(0)),
+            grow_borders(&mut b, PlayerSlot(0))
+        );
+    }
+
+    #[test]
+    fn test_purchase_cost_grows_with_distance() {
+        let (state, city_id) = state_with_city();
+        let city = &state.cities[&city_id];
+        assert!(
+            tile_purchase_cost(city, HexCoord::new(10, 13))
+                > tile_purchase_cost(city, HexCoord::new(10, 12))
+        );
+    }
+}
@@ -19,13 +19,16 @@
 //! - **Exploration**: Goody hut outcomes, barbarian spawns
 //! - **Diplomacy**: AI decision variance
 
+use crate::types::{GameId, UnitId};
+use schemars::JsonSchema;
 use serde::{Deserialize, Serialize};
+use ts_rs::TS;
 
 /// A proof of randomness from a Cashu mint.
 ///
 /// This proof can be verified by anyone to confirm the randomness
 /// was generated fairly by a third-party mint.
-#[derive(Clone, Debug, Serialize, Deserialize)]
+#[derive(Clone, Debug, Serialize, Deserialize, JsonSchema, TS)]
 pub struct RandomnessProof {
     /// The mint's public key (keyset ID).
     pub mint_keyset_id: String,
@@ -107,26 +110,26 @@ pub struct RandomnessRequest {
 #[derive(Clone, Debug, Serialize, Deserialize)]
 pub enum RandomnessContext {
     /// Map generation seed.
-    MapGeneration { game_id: String },
+    MapGeneration { game_id: GameId },
     /// Combat resolution.
     Combat {
-        game_id: String,
+        game_id: GameId,
         turn: u32,
-        attacker_id: u64,
-        defender_id: u64,
+        attacker_id: UnitId,
+        defender_id: UnitId,
     },
     /// Exploration/goody hut.
     Exploration {
-        game_id: String,
+        game_id: GameId,
         turn: u32,
         hex_q: i32,
         hex_r: i32,
     },
     /// Barbarian spawn.
-    BarbarianSpawn { game_id: String, turn: u32 },
+    BarbarianSpawn { game_id: GameId, turn: u32 },
     /// Generic game event.
     GameEvent {
-        game_id: String,
+        game_id: GameId,
         turn: u32,
         event_type: String,
     },
@@ -320,13 +323,13 @@ impl RandomnessManager {
     /// Request randomness for combat.
     pub fn combat_random(
         &mut self,
-        game_id: &str,
+        game_id: &GameId,
         turn: u32,
-        attacker_id: u64,
-        defender_id: u64,
+        attacker_id: UnitId,
+        defender_id: UnitId,
     ) -> Result<RandomnessProof, RandomnessError> {
         let context = RandomnessContext::Combat {
-            game_id: game_id.to_string(),
+            game_id: game_id.clone(),
             turn,
             attacker_id,
             defender_id,
@@ -335,9 +338,9 @@ impl RandomnessManager {
     }
 
     /// Request randomness for map generation.
-    pub fn map_seed(&mut self, game_id: &str) -> Result<RandomnessProof, RandomnessError> {
+    pub fn map_seed(&mut self, game_id: &GameId) -> Result<RandomnessProof, RandomnessError> {
         let context = RandomnessContext::MapGeneration {
-            game_id: game_id.to_string(),
+            game_id: game_id.clone(),
         };
         self.request_with_cache(context)
     }
@@ -345,13 +348,13 @@ impl RandomnessManager {
     /// Request randomness for exploration.
     pub fn exploration_random(
         &mut self,
-        game_id: &str,
+        game_id: &GameId,
         turn: u32,
         hex_q: i32,
         hex_r: i32,
     ) -> Result<RandomnessProof, RandomnessError> {
         let context = RandomnessContext::Exploration {
-            game_id: game_id.to_string(),
+            game_id: game_id.clone(),
             turn,
             hex_q,
             hex_r,
@@ -423,10 +426,10 @@ mod tests {
         let mut rng2 = DeterministicRandomness::new(seed);
 
         let context = RandomnessContext::Combat {
-            game_id: "test".to_string(),
+            game_id: GameId::new("test"),
             turn: 1,
-            attacker_id: 1,
-            defender_id: 2,
+            attacker_id: UnitId(1),
+            defender_id: UnitId(2),
         };
 
         let proof1 = rng1.request_randomness(context.clone()).unwrap();
@@ -442,17 +445,17 @@ mod tests {
         let mut rng = DeterministicRandomness::new(seed);
 
         let context1 = RandomnessContext::Combat {
-            game_id: "test".to_string(),
+            game_id: GameId::new("test"),
             turn: 1,
-            attacker_id: 1,
-            defender_id: 2,
+            attacker_id: UnitId(1),
+            defender_id: UnitId(2),
         };
 
         let context2 = RandomnessContext::Combat {
-            game_id: "test".to_string(),
+            game_id: GameId::new("test"),
             turn: 1,
-            attacker_id: 1,
-            defender_id: 3, // Different defender
+            attacker_id: UnitId(1),
+            defender_id: UnitId(3), // Different defender
         };
 
         let proof1 = rng.request_randomness(context1).unwrap();
@@ -471,7 +474,7 @@ mod tests {
 
         for i in 0..100 {
             let context = RandomnessContext::GameEvent {
-                game_id: "test".to_string(),
+                game_id: GameId::new("test"),
                 turn: i,
                 event_type: "test".to_string(),
             };
@@ -490,7 +493,7 @@ mod tests {
 
         for i in 0..100 {
             let context = RandomnessContext::GameEvent {
-                game_id: "test".to_string(),
+                game_id: GameId::new("test"),
                 turn: i,
                 event_type: "test".to_string(),
             };
@@ -508,14 +511,20 @@ mod tests {
         let mut manager = RandomnessManager::new(config, seed);
 
         // Request same randomness twice
-        let proof1 = manager.combat_random("game1", 1, 1, 2).unwrap();
-        let proof2 = manager.combat_random("game1", 1, 1, 2).unwrap();
+        let proof1 = manager
+            .combat_random(&GameId::new("game1"), 1, UnitId(1), UnitId(2))
+            .unwrap();
+        let proof2 = manager
+            .combat_random(&GameId::new("game1"), 1, UnitId(1), UnitId(2))
+            .unwrap();
 
         // Should return cached result
         assert_eq!(proof1.random_bytes, proof2.random_bytes);
 
         // Different request should be different
-        let proof3 = manager.combat_random("game1", 1, 1, 3).unwrap();
+        let proof3 = manager
+            .combat_random(&GameId::new("game1"), 1, UnitId(1), UnitId(3))
+            .unwrap();
         assert_ne!(proof1.random_bytes, proof3.random_bytes);
     }
 
@@ -534,14 +543,14 @@ mod tests {
         let seed = [3u8; 32];
         let mut manager = RandomnessManager::new(config, seed);
 
-        let proof = manager.map_seed("game1").unwrap();
+        let proof = manager.map_seed(&GameId::new("game1")).unwrap();
         let map_seed = proof.to_seed();
 
         // Should produce 32-byte seed
         assert_eq!(map_seed.len(), 32);
 
         // Same game should produce same seed
-        let proof2 = manager.map_seed("game1").unwrap();
+        let proof2 = manager.map_seed(&GameId::new("game1")).unwrap();
         assert_eq!(proof.to_seed(), proof2.to_seed());
     }
 
@@ -552,7 +561,7 @@ mod tests {
 
         let mut rng_clone = rng.clone();
         let context = RandomnessContext::MapGeneration {
-            game_id: "test".to_string(),
+            game_id: GameId::new("test"),
         };
         let proof = rng_clone.request_randomness(context).unwrap();
 
@@ -563,17 +572,17 @@ mod tests {
     #[test]
     fn test_context_to_bytes_deterministic() {
         let context1 = RandomnessContext::Combat {
-            game_id: "test".to_string(),
+            game_id: GameId::new("test"),
             turn: 5,
-            attacker_id: 10,
-            defender_id: 20,
+            attacker_id: UnitId(10),
+            defender_id: UnitId(20),
         };
 
         let context2 = RandomnessContext::Combat {
-            game_id: "test".to_string(),
+            game_id: GameId::new("test"),
             turn: 5,
-            attacker_id: 10,
-            defender_id: 20,
+            attacker_id: UnitId(10),
+            defender_id: UnitId(20),
         };
 
         // Same context should produce same bytes
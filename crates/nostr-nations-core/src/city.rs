@@ -1,11 +1,14 @@
 //! City system - settlements, production, and growth.
 
+use crate::fixed::Fixed;
 use crate::hex::HexCoord;
-use crate::types::{CityId, PlayerId};
+use crate::types::{CityId, Era, PlayerSlot};
 use crate::unit::UnitType;
 use crate::yields::Yields;
+use schemars::JsonSchema;
 use serde::{Deserialize, Serialize};
 use std::collections::HashSet;
+use ts_rs::TS;
 
 /// A city on the game map.
 #[derive(Clone, Debug, Serialize, Deserialize)]
@@ -13,7 +16,7 @@ pub struct City {
     /// Unique identifier.
     pub id: CityId,
     /// Owning player.
-    pub owner: PlayerId,
+    pub owner: PlayerSlot,
     /// City name.
     pub name: String,
     /// Location on the map.
@@ -50,13 +53,26 @@ pub struct City {
     pub age: u32,
     /// Was city founded or conquered?
     pub founded: bool,
+    /// Turn the city last bombarded a unit.
+    #[serde(default)]
+    pub last_bombard_turn: Option<u32>,
+    /// Captured city whose new owner hasn't chosen to raze, annex or
+    /// puppet it.
+    #[serde(default)]
+    pub awaiting_capture_choice: bool,
+    /// Puppet cities can't have their production directed.
+    #[serde(default)]
+    pub puppet: bool,
+    /// World wonders built in this city.
+    #[serde(default)]
+    pub wonders: HashSet<WonderType>,
 }
 
 impl City {
     /// Create a new city.
     pub fn new(
         id: CityId,
-        owner: PlayerId,
+        owner: PlayerSlot,
         name: String,
         position: HexCoord,
         is_capital: bool,
@@ -93,6 +109,10 @@ impl City {
             culture: 0,
             age: 0,
             founded: true,
+            last_bombard_turn: None,
+            awaiting_capture_choice: false,
+            puppet: false,
+            wonders: HashSet::new(),
         }
     }
 
@@ -106,7 +126,14 @@ impl City {
     pub fn food_for_growth(&self) -> u32 {
         // Formula: 15 + 6 * (population - 1) + population^1.8
         let base = 15 + 6 * (self.population.saturating_sub(1));
-        let exp = (self.population as f32).powf(1.8) as u32;
+        // population^1.8 == population * population^(4/5)
+        let pop = Fixed::from_int(self.population as i64);
+        let exp = pop
+            .checked_powi(4)
+            .unwrap_or_default()
+            .nth_root(5)
+            .mul_int(self.population as i64);
+        let exp = exp.trunc() as u32;
         base + exp
     }
 
@@ -153,7 +180,7 @@ impl City {
 
                 // Keep a portion of food based on buildings
                 let keep_ratio = self.food_keep_ratio();
-                self.food_stored = (self.food_stored as f32 * keep_ratio) as u32;
+                self.food_stored = keep_ratio.mul_int(self.food_stored as i64).trunc() as u32;
             }
         } else {
             // Starvation
@@ -168,50 +195,55 @@ impl City {
 
     /// Process production.
     fn process_production(&mut self, production: i32, result: &mut CityTurnResult) {
+        result.completed_production = self.progress_production(production);
+    }
+
+    /// Put production toward the current item, returning it if completed.
+    pub fn progress_production(&mut self, production: i32) -> Option<ProductionItem> {
         if production <= 0 {
-            return;
+            return None;
         }
 
-        if let Some(ref item) = self.production {
-            self.production_progress += production as u32;
-
-            let cost = item.cost();
-            if self.production_progress >= cost {
-                result.completed_production = Some(item.clone());
-                self.production_progress = 0;
-
-                // Move to next item in queue
-                self.production = self.production_queue.pop();
-            }
+        let item = self.production.clone()?;
+        self.production_progress += production as u32;
+        if self.production_progress < item.cost() {
+            return None;
         }
+        self.production_progress = 0;
+
+        // Move to next item in queue
+        self.production = self.production_queue.pop();
+        Some(item)
     }
 
     /// Get the ratio of food kept on growth.
-    fn food_keep_ratio(&self) -> f32 {
-        let mut ratio: f32 = 0.0;
+    fn food_keep_ratio(&self) -> Fixed {
+        let mut ratio = Fixed::ZERO;
         if self.buildings.contains(&BuildingType::Granary) {
-            ratio += 0.5;
+            ratio += Fixed::from_percent(50);
         }
         if self.buildings.contains(&BuildingType::Aqueduct) {
-            ratio += 0.4;
+            ratio += Fixed::from_percent(40);
         }
-        ratio.min(1.0)
+        ratio.min(Fixed::ONE)
     }
 
-    /// Check if city should expand borders.
-    fn should_expand_borders(&self) -> bool {
+    /// Culture needed to claim the next tile.
+    pub fn culture_for_next_tile(&self) -> u32 {
         // Culture needed increases with territory size
         let tiles = self.territory.len() as u32;
-        let needed = 10 + tiles * tiles * 2;
-        self.culture >= needed
+        10 + tiles * tiles * 2
+    }
+
+    /// Check if city should expand borders.
+    pub fn should_expand_borders(&self) -> bool {
+        self.culture >= self.culture_for_next_tile()
     }
 
-    /// Expand borders to include a new tile.
+    /// Expand borders to include a new tile, spending the culture it cost.
     pub fn expand_borders(&mut self, tile: HexCoord) {
+        self.culture = self.culture.saturating_sub(self.culture_for_next_tile());
         self.territory.insert(tile);
-        // Reset culture for next expansion
-        let tiles = self.territory.len() as u32;
-        self.culture = self.culture.saturating_sub(10 + tiles * tiles * 2);
     }
 
     /// Set production to a new item.
@@ -245,6 +277,27 @@ impl City {
         }
     }
 
+    /// Remove a building, undoing its effects.
+    pub fn remove_building(&mut self, building: BuildingType) -> bool {
+        if !self.buildings.remove(&building) {
+            return false;
+        }
+
+        match building {
+            BuildingType::Walls => {
+                self.max_health -= 50;
+                self.combat_strength -= 5;
+            }
+            BuildingType::Castle => {
+                self.max_health -= 75;
+                self.combat_strength -= 8;
+            }
+            _ => {}
+        }
+        self.health = self.health.min(self.max_health);
+        true
+    }
+
     /// Check if a building can be built.
     pub fn can_build(&self, building: BuildingType) -> bool {
         if self.buildings.contains(&building) {
@@ -325,15 +378,17 @@ impl City {
             yields.science += effects.science_per_2_pop * (self.population as i32 / 2);
 
             // Percentage modifiers
-            if effects.gold_modifier > 0.0 {
-                yields.gold = (yields.gold as f32 * (1.0 + effects.gold_modifier)) as i32;
+            let apply = |value: i32, modifier: Fixed| {
+                (Fixed::ONE + modifier).mul_int(value as i64).trunc() as i32
+            };
+            if effects.gold_modifier > Fixed::ZERO {
+                yields.gold = apply(yields.gold, effects.gold_modifier);
             }
-            if effects.science_modifier > 0.0 {
-                yields.science = (yields.science as f32 * (1.0 + effects.science_modifier)) as i32;
+            if effects.science_modifier > Fixed::ZERO {
+                yields.science = apply(yields.science, effects.science_modifier);
             }
-            if effects.production_modifier > 0.0 {
-                yields.production =
-                    (yields.production as f32 * (1.0 + effects.production_modifier)) as i32;
+            if effects.production_modifier > Fixed::ZERO {
+                yields.production = apply(yields.production, effects.production_modifier);
             }
         }
 
@@ -378,7 +433,7 @@ impl Specialists {
 }
 
 /// Items that can be produced by a city.
-#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize, JsonSchema, TS)]
 pub enum ProductionItem {
     Unit(UnitType),
     Building(BuildingType),
@@ -406,10 +461,20 @@ impl ProductionItem {
             ProductionItem::Project(pt) => format!("{:?}", pt),
         }
     }
+
+    /// Get the era a player must reach to produce this item.
+    pub const fn era(&self) -> Era {
+        match self {
+            ProductionItem::Unit(ut) => ut.era(),
+            ProductionItem::Building(bt) => bt.era(),
+            ProductionItem::Wonder(wt) => wt.era(),
+            ProductionItem::Project(pt) => pt.era(),
+        }
+    }
 }
 
 /// Building types.
-#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash, Serialize, Deserialize, JsonSchema, TS)]
 pub enum BuildingType {
     Monument,
     Granary,
@@ -433,6 +498,34 @@ pub enum BuildingType {
 }
 
 impl BuildingType {
+    /// Get the era this building belongs to.
+    pub const fn era(&self) -> Era {
+        match self {
+            BuildingType::Monument
+            | BuildingType::Granary
+            | BuildingType::Barracks
+            | BuildingType::Walls
+            | BuildingType::Temple
+            | BuildingType::Lighthouse => Era::Ancient,
+
+            BuildingType::Library
+            | BuildingType::Market
+            | BuildingType::Aqueduct
+            | BuildingType::Amphitheater
+            | BuildingType::Colosseum
+            | BuildingType::Courthouse => Era::Classical,
+
+            BuildingType::University
+            | BuildingType::Castle
+            | BuildingType::Workshop
+            | BuildingType::Armory => Era::Medieval,
+
+            BuildingType::Bank => Era::Renaissance,
+
+            BuildingType::Factory | BuildingType::Hospital => Era::Industrial,
+        }
+    }
+
     /// Get the production cost.
     pub const fn cost(&self) -> u32 {
         match self {
@@ -458,6 +551,29 @@ impl BuildingType {
         }
     }
 
+    /// Get the gold maintenance paid each turn.
+    pub const fn maintenance(&self) -> i32 {
+        match self {
+            BuildingType::Monument
+            | BuildingType::Granary
+            | BuildingType::Lighthouse
+            | BuildingType::Library
+            | BuildingType::Barracks
+            | BuildingType::Walls
+            | BuildingType::Workshop
+            | BuildingType::Temple => 1,
+            BuildingType::Aqueduct | BuildingType::Amphitheater | BuildingType::Armory => 2,
+            BuildingType::University
+            | BuildingType::Castle
+            | BuildingType::Factory
+            | BuildingType::Colosseum
+            | BuildingType::Hospital => 3,
+            BuildingType::Courthouse => 4,
+            // Gold buildings pay for themselves
+            BuildingType::Market | BuildingType::Bank => 0,
+        }
+    }
+
     /// Get building effects.
     pub const fn effects(&self) -> BuildingEffects {
         match self {
@@ -466,11 +582,11 @@ impl BuildingType {
             BuildingType::Library => BuildingEffects::science_per_pop(1),
             BuildingType::Barracks => BuildingEffects::xp_bonus(15),
             BuildingType::Walls => BuildingEffects::defense(5),
-            BuildingType::Market => BuildingEffects::gold_modifier(0.25),
+            BuildingType::Market => BuildingEffects::gold_modifier(Fixed::from_percent(25)),
             BuildingType::Aqueduct => BuildingEffects::food(2),
-            BuildingType::University => BuildingEffects::science_modifier(0.33),
-            BuildingType::Bank => BuildingEffects::gold_modifier(0.25),
-            BuildingType::Factory => BuildingEffects::production_modifier(0.25),
+            BuildingType::University => BuildingEffects::science_modifier(Fixed::from_percent(33)),
+            BuildingType::Bank => BuildingEffects::gold_modifier(Fixed::from_percent(25)),
+            BuildingType::Factory => BuildingEffects::production_modifier(Fixed::from_percent(25)),
             BuildingType::Hospital => BuildingEffects::food(5),
             BuildingType::Castle => BuildingEffects::defense(8),
             BuildingType::Workshop => BuildingEffects::production(2),
@@ -494,10 +610,10 @@ pub struct BuildingEffects {
     pub culture: i32,
     pub defense: i32,
     pub xp_bonus: u32,
-    pub food_modifier: f32,
-    pub gold_modifier: f32,
-    pub science_modifier: f32,
-    pub production_modifier: f32,
+    pub food_modifier: Fixed,
+    pub gold_modifier: Fixed,
+    pub science_modifier: Fixed,
+    pub production_modifier: Fixed,
     pub science_per_2_pop: i32,
 }
 
@@ -511,10 +627,10 @@ impl BuildingEffects {
             culture: 0,
             defense: 0,
             xp_bonus: 0,
-            food_modifier: 0.0,
-            gold_modifier: 0.0,
-            science_modifier: 0.0,
-            production_modifier: 0.0,
+            food_modifier: Fixed::ZERO,
+            gold_modifier: Fixed::ZERO,
+            science_modifier: Fixed::ZERO,
+            production_modifier: Fixed::ZERO,
             science_per_2_pop: 0,
         }
     }
@@ -554,21 +670,21 @@ impl BuildingEffects {
         }
     }
 
-    pub const fn gold_modifier(amount: f32) -> Self {
+    pub const fn gold_modifier(amount: Fixed) -> Self {
         Self {
             gold_modifier: amount,
             ..Self::default()
         }
     }
 
-    pub const fn science_modifier(amount: f32) -> Self {
+    pub const fn science_modifier(amount: Fixed) -> Self {
         Self {
             science_modifier: amount,
             ..Self::default()
         }
     }
 
-    pub const fn production_modifier(amount: f32) -> Self {
+    pub const fn production_modifier(amount: Fixed) -> Self {
         Self {
             production_modifier: amount,
             ..Self::default()
@@ -584,7 +700,7 @@ impl BuildingEffects {
 }
 
 /// World wonder types.
-#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash, Serialize, Deserialize, JsonSchema, TS)]
 pub enum WonderType {
     Pyramids,
     GreatLibrary,
@@ -601,6 +717,25 @@ pub enum WonderType {
 }
 
 impl WonderType {
+    /// Get the era this wonder belongs to.
+    pub const fn era(&self) -> Era {
+        match self {
+            WonderType::Pyramids
+            | WonderType::GreatLibrary
+            | WonderType::Stonehenge
+            | WonderType::HangingGardens
+            | WonderType::Oracle
+            | WonderType::Colossus
+            | WonderType::GreatLighthouse => Era::Ancient,
+
+            WonderType::Parthenon | WonderType::TerracottaArmy | WonderType::GreatWall => {
+                Era::Classical
+            }
+
+            WonderType::MachuPicchu | WonderType::NotreDame => Era::Medieval,
+        }
+    }
+
     pub const fn cost(&self) -> u32 {
         match self {
             WonderType::Pyramids => 185,
@@ -620,7 +755,7 @@ impl WonderType {
 }
 
 /// Special project types (like spaceship parts).
-#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash, Serialize, Deserialize, JsonSchema, TS)]
 pub enum ProjectType {
     SpaceshipEngine,
     SpaceshipFuelTank,
@@ -630,6 +765,17 @@ pub enum ProjectType {
 }
 
 impl ProjectType {
+    /// Get the era this project belongs to.
+    pub const fn era(&self) -> Era {
+        match self {
+            ProjectType::ManhattanProject => Era::Atomic,
+            ProjectType::SpaceshipEngine
+            | ProjectType::SpaceshipFuelTank
+            | ProjectType::SpaceshipCockpit
+            | ProjectType::SpaceshipHull => Era::Information,
+        }
+    }
+
     pub const fn cost(&self) -> u32 {
         match self {
             ProjectType::SpaceshipEngine => 1500,
@@ -647,10 +793,16 @@ mod tests {
 
     #[test]
     fn test_city_creation() {
-        let city = City::new(1, 0, "Rome".to_string(), HexCoord::new(5, 5), true);
-
-        assert_eq!(city.id, 1);
-        assert_eq!(city.owner, 0);
+        let city = City::new(
+            CityId(1),
+            PlayerSlot(0),
+            "Rome".to_string(),
+            HexCoord::new(5, 5),
+            true,
+        );
+
+        assert_eq!(city.id, CityId(1));
+        assert_eq!(city.owner, PlayerSlot(0));
         assert_eq!(city.population, 1);
         assert!(city.is_capital);
         assert!(city.territory.contains(&city.position));
@@ -659,7 +811,13 @@ mod tests {
 
     #[test]
     fn test_food_for_growth() {
-        let city = City::new(1, 0, "Test".to_string(), HexCoord::new(0, 0), false);
+        let city = City::new(
+            CityId(1),
+            PlayerSlot(0),
+            "Test".to_string(),
+            HexCoord::new(0, 0),
+            false,
+        );
 
         // Population 1: 15 + 0 + 1 = 16
         assert!(city.food_for_growth() >= 15);
@@ -667,7 +825,13 @@ mod tests {
 
     #[test]
     fn test_city_growth() {
-        let mut city = City::new(1, 0, "Test".to_string(), HexCoord::new(0, 0), false);
+        let mut city = City::new(
+            CityId(1),
+            PlayerSlot(0),
+            "Test".to_string(),
+            HexCoord::new(0, 0),
+            false,
+        );
 
         // Simulate excess food
         let yields = Yields::new(10, 0, 0, 0, 0); // 10 food, 2 consumed = +8
@@ -682,7 +846,13 @@ mod tests {
 
     #[test]
     fn test_city_production() {
-        let mut city = City::new(1, 0, "Test".to_string(), HexCoord::new(0, 0), false);
+        let mut city = City::new(
+            CityId(1),
+            PlayerSlot(0),
+            "Test".to_string(),
+            HexCoord::new(0, 0),
+            false,
+        );
         city.set_production(ProductionItem::Unit(UnitType::Warrior));
 
         let yields = Yields::new(2, 10, 0, 0, 0);
@@ -702,7 +872,13 @@ mod tests {
 
     #[test]
     fn test_city_buildings() {
-        let mut city = City::new(1, 0, "Test".to_string(), HexCoord::new(0, 0), false);
+        let mut city = City::new(
+            CityId(1),
+            PlayerSlot(0),
+            "Test".to_string(),
+            HexCoord::new(0, 0),
+            false,
+        );
 
         assert!(city.can_build(BuildingType::Library));
         city.add_building(BuildingType::Library);
@@ -727,7 +903,13 @@ mod tests {
 
     #[test]
     fn test_city_damage() {
-        let mut city = City::new(1, 0, "Test".to_string(), HexCoord::new(0, 0), false);
+        let mut city = City::new(
+            CityId(1),
+            PlayerSlot(0),
+            "Test".to_string(),
+            HexCoord::new(0, 0),
+            false,
+        );
 
         city.take_damage(150);
         assert_eq!(city.health, 50);
@@ -752,7 +934,13 @@ mod tests {
 
     #[test]
     fn test_city_serialization() {
-        let city = City::new(1, 0, "TestCity".to_string(), HexCoord::new(3, 7), true);
+        let city = City::new(
+            CityId(1),
+            PlayerSlot(0),
+            "TestCity".to_string(),
+            HexCoord::new(3, 7),
+            true,
+        );
         let json = serde_json::to_string(&city).unwrap();
         let restored: City = serde_json::from_str(&json).unwrap();
 
@@ -4,9 +4,13 @@
 //! Cashu-based randomness for fairness. The combat resolver takes
 //! a random value (from Cashu unblinded signature) to determine outcomes.
 
+use crate::city::City;
+use crate::fixed::{deterministic, Fixed};
 use crate::map::Tile;
 use crate::unit::{Promotion, Unit, UnitCategory};
+use schemars::JsonSchema;
 use serde::{Deserialize, Serialize};
+use ts_rs::TS;
 
 /// Result of a combat engagement.
 #[derive(Clone, Debug, Serialize, Deserialize)]
@@ -27,25 +31,47 @@ pub struct CombatResult {
     pub log: CombatLog,
 }
 
+deterministic!(struct CombatResult {
+    defender_damage,
+    attacker_damage,
+    defender_destroyed,
+    attacker_destroyed,
+    attacker_xp,
+    defender_xp,
+    log,
+});
+
 /// Detailed combat log for UI display and replay.
-#[derive(Clone, Debug, Serialize, Deserialize)]
+#[derive(Clone, Debug, Serialize, Deserialize, JsonSchema, TS)]
 pub struct CombatLog {
     pub attacker_base_strength: u32,
     pub defender_base_strength: u32,
     pub attacker_modifiers: Vec<CombatModifier>,
     pub defender_modifiers: Vec<CombatModifier>,
-    pub attacker_final_strength: f32,
-    pub defender_final_strength: f32,
-    pub random_factor: f32,
+    pub attacker_final_strength: Fixed,
+    pub defender_final_strength: Fixed,
+    pub random_factor: Fixed,
 }
 
+deterministic!(struct CombatLog {
+    attacker_base_strength,
+    defender_base_strength,
+    attacker_modifiers,
+    defender_modifiers,
+    attacker_final_strength,
+    defender_final_strength,
+    random_factor,
+});
+
 /// A modifier that affects combat strength.
-#[derive(Clone, Debug, Serialize, Deserialize)]
+#[derive(Clone, Debug, Serialize, Deserialize, JsonSchema, TS)]
 pub struct CombatModifier {
     pub name: String,
     pub percentage: i32,
 }
 
+deterministic!(struct CombatModifier { name, percentage });
+
 /// Context for combat calculations.
 pub struct CombatContext<'a> {
     pub attacker: &'a Unit,
@@ -53,16 +79,37 @@ pub struct CombatContext<'a> {
     pub attacker_tile: &'a Tile,
     pub defender_tile: &'a Tile,
     /// Random value from Cashu (0.0 to 1.0).
-    pub random: f32,
+    pub random: Fixed,
     /// Is this a ranged attack?
     pub is_ranged: bool,
 }
 
+deterministic!(struct CombatContext<'a> {
+    attacker,
+    defender,
+    attacker_tile,
+    defender_tile,
+    random,
+    is_ranged,
+});
+
 /// Resolve combat between two units.
 ///
 /// The random value should come from a Cashu unblinded signature
 /// to ensure neither player can bias the outcome.
 pub fn resolve_combat(ctx: &CombatContext) -> CombatResult {
+    resolve_combat_with_difficulty(ctx, 0, 0)
+}
+
+/// Resolve combat with difficulty bonuses applied to each side.
+///
+/// Bonuses are percentages from [`crate::settings::DifficultyModifiers`];
+/// a nonzero bonus shows up as a "Difficulty" entry in the combat log.
+pub fn resolve_combat_with_difficulty(
+    ctx: &CombatContext,
+    attacker_bonus: i32,
+    defender_bonus: i32,
+) -> CombatResult {
     let mut attacker_modifiers = Vec::new();
     let mut defender_modifiers = Vec::new();
 
@@ -76,14 +123,20 @@ pub fn resolve_combat(ctx: &CombatContext) -> CombatResult {
     let defender_base = ctx.defender.effective_combat_strength();
 
     // Calculate attacker modifiers
-    let attacker_mod = calculate_attacker_modifiers(ctx, &mut attacker_modifiers);
+    let attacker_mod = calculate_attacker_modifiers(ctx, &mut attacker_modifiers)
+        + difficulty_modifier(attacker_bonus, &mut attacker_modifiers);
 
     // Calculate defender modifiers
-    let defender_mod = calculate_defender_modifiers(ctx, &mut defender_modifiers);
+    let defender_mod = calculate_defender_modifiers(
+        ctx.defender,
+        ctx.defender_tile,
+        ctx.is_ranged,
+        &mut defender_modifiers,
+    ) + difficulty_modifier(defender_bonus, &mut defender_modifiers);
 
     // Apply modifiers to get final strengths
-    let attacker_final = attacker_base as f32 * (1.0 + attacker_mod / 100.0);
-    let defender_final = defender_base as f32 * (1.0 + defender_mod / 100.0);
+    let attacker_final = Fixed::from_int(attacker_base as i64) * (Fixed::ONE + attacker_mod);
+    let defender_final = Fixed::from_int(defender_base as i64) * (Fixed::ONE + defender_mod);
 
     // Calculate damage using combat formula
     let (defender_damage, attacker_damage) =
@@ -122,8 +175,8 @@ pub fn resolve_combat(ctx: &CombatContext) -> CombatResult {
 }
 
 /// Calculate attacker combat modifiers.
-fn calculate_attacker_modifiers(ctx: &CombatContext, mods: &mut Vec<CombatModifier>) -> f32 {
-    let mut total = 0.0f32;
+fn calculate_attacker_modifiers(ctx: &CombatContext, mods: &mut Vec<CombatModifier>) -> Fixed {
+    let mut total = Fixed::ZERO;
 
     // Great General bonus (if nearby, +15%)
     // This would need to check for nearby great generals
@@ -139,7 +192,7 @@ fn calculate_attacker_modifiers(ctx: &CombatContext, mods: &mut Vec<CombatModifi
                 name: format!("{:?}", promo),
                 percentage: bonus,
             });
-            total += bonus as f32;
+            total += Fixed::from_percent(bonus as i64);
         }
     }
 
@@ -149,42 +202,59 @@ fn calculate_attacker_modifiers(ctx: &CombatContext, mods: &mut Vec<CombatModifi
     total
 }
 
+/// Record a difficulty combat bonus, if any.
+fn difficulty_modifier(bonus: i32, mods: &mut Vec<CombatModifier>) -> Fixed {
+    if bonus == 0 {
+        return Fixed::ZERO;
+    }
+    mods.push(CombatModifier {
+        name: "Difficulty".to_string(),
+        percentage: bonus,
+    });
+    Fixed::from_percent(bonus as i64)
+}
+
 /// Calculate defender combat modifiers.
-fn calculate_defender_modifiers(ctx: &CombatContext, mods: &mut Vec<CombatModifier>) -> f32 {
-    let mut total = 0.0f32;
+fn calculate_defender_modifiers(
+    defender: &Unit,
+    defender_tile: &Tile,
+    is_ranged: bool,
+    mods: &mut Vec<CombatModifier>,
+) -> Fixed {
+    let mut total = Fixed::ZERO;
 
     // Terrain defense bonus
-    let terrain_bonus = ctx.defender_tile.defense_bonus();
+    let terrain_bonus = defender_tile.defense_bonus();
     if terrain_bonus != 0 {
         mods.push(CombatModifier {
             name: "Terrain".to_string(),
             percentage: terrain_bonus,
         });
-        total += terrain_bonus as f32;
+        total += Fixed::from_percent(terrain_bonus as i64);
     }
 
     // Fortification bonus
-    let fort_bonus = ctx.defender.fortification_bonus();
+    let fort_bonus = defender.fortification_bonus();
     if fort_bonus != 0 {
         mods.push(CombatModifier {
             name: "Fortified".to_string(),
             percentage: fort_bonus,
         });
-        total += fort_bonus as f32;
+        total += Fixed::from_percent(fort_bonus as i64);
     }
 
     // River crossing penalty for attacker (becomes defender bonus)
     // Would need to check if attacker crossed a river
 
     // Promotion bonuses
-    for promo in &ctx.defender.promotions {
-        let bonus = get_defender_promotion_bonus(promo, ctx);
+    for promo in &defender.promotions {
+        let bonus = get_defender_promotion_bonus(promo, is_ranged);
         if bonus != 0 {
             mods.push(CombatModifier {
                 name: format!("{:?}", promo),
                 percentage: bonus,
             });
-            total += bonus as f32;
+            total += Fixed::from_percent(bonus as i64);
         }
     }
 
@@ -248,11 +318,11 @@ fn get_attacker_promotion_bonus(promo: &Promotion, ctx: &CombatContext) -> i32 {
 }
 
 /// Get defender bonus from a promotion.
-fn get_defender_promotion_bonus(promo: &Promotion, ctx: &CombatContext) -> i32 {
+fn get_defender_promotion_bonus(promo: &Promotion, is_ranged: bool) -> i32 {
     match promo {
         // Cover: defense vs ranged attacks
-        Promotion::CoverI if ctx.is_ranged => 25,
-        Promotion::CoverII if ctx.is_ranged => 50,
+        Promotion::CoverI if is_ranged => 25,
+        Promotion::CoverII if is_ranged => 50,
         _ => 0,
     }
 }
@@ -263,12 +333,12 @@ fn get_defender_promotion_bonus(promo: &Promotion, ctx: &CombatContext) -> i32 {
 /// - Equal strength: ~30 damage to each side
 /// - 2:1 advantage: ~50 to defender, ~15 to attacker
 fn calculate_damage(
-    attacker_strength: f32,
-    defender_strength: f32,
-    random: f32,
+    attacker_strength: Fixed,
+    defender_strength: Fixed,
+    random: Fixed,
     is_ranged: bool,
 ) -> (u32, u32) {
-    if attacker_strength <= 0.0 || defender_strength <= 0.0 {
+    if attacker_strength <= Fixed::ZERO || defender_strength <= Fixed::ZERO {
         return (0, 0);
     }
 
@@ -276,28 +346,33 @@ fn calculate_damage(
     let ratio = attacker_strength / defender_strength;
 
     // Base damage calculation (normalized around 30)
-    let base_damage = 30.0;
+    let base_damage = Fixed::from_int(30);
 
     // Attacker's damage to defender scales with ratio
     // At 1:1 ratio = 30 damage, at 2:1 = ~45 damage, at 0.5:1 = ~20 damage
-    let defender_damage_base = base_damage * ratio.powf(0.5);
+    let defender_damage_base = base_damage * ratio.sqrt();
 
     // Add randomness (±20%)
-    let random_factor = 0.8 + random * 0.4; // 0.8 to 1.2
+    let random_factor = random_spread(random); // 0.8 to 1.2
     let defender_damage = (defender_damage_base * random_factor).round() as u32;
 
     // Attacker takes counter-attack damage (unless ranged)
     let attacker_damage = if is_ranged {
         0 // Ranged attacks don't receive counter-attack
     } else {
-        let attacker_damage_base = base_damage / ratio.powf(0.5);
-        let random_factor_def = 0.8 + (1.0 - random) * 0.4;
+        let attacker_damage_base = base_damage / ratio.sqrt();
+        let random_factor_def = random_spread(Fixed::ONE - random);
         (attacker_damage_base * random_factor_def).round() as u32
     };
 
     (defender_damage.min(100), attacker_damage.min(100))
 }
 
+/// Map a random value in `[0, 1]` to a damage multiplier in `[0.8, 1.2]`.
+fn random_spread(random: Fixed) -> Fixed {
+    Fixed::from_percent(80) + random * Fixed::from_percent(40)
+}
+
 /// Calculate experience gained from combat.
 fn calculate_experience(
     attacker_strength: u32,
@@ -310,8 +385,12 @@ fn calculate_experience(
     let base_xp = 2u32;
 
     // Bonus for fighting stronger enemies
-    let strength_ratio = defender_strength as f32 / attacker_strength.max(1) as f32;
-    let strength_bonus = ((strength_ratio - 1.0).max(0.0) * 2.0) as u32;
+    let strength_ratio =
+        Fixed::from_ratio(defender_strength as i64, attacker_strength.max(1) as i64);
+    let strength_bonus = (strength_ratio - Fixed::ONE)
+        .max(Fixed::ZERO)
+        .mul_int(2)
+        .trunc() as u32;
 
     // Attacker XP
     let mut attacker_xp = base_xp + strength_bonus;
@@ -336,10 +415,19 @@ pub struct CityCombatContext<'a> {
     pub city_strength: u32,
     pub city_health: u32,
     pub attacker_tile: &'a Tile,
-    pub random: f32,
+    pub random: Fixed,
     pub is_ranged: bool,
 }
 
+deterministic!(struct CityCombatContext<'a> {
+    attacker,
+    city_strength,
+    city_health,
+    attacker_tile,
+    random,
+    is_ranged,
+});
+
 /// Result of attacking a city.
 #[derive(Clone, Debug, Serialize, Deserialize)]
 pub struct CityCombatResult {
@@ -355,6 +443,14 @@ pub struct CityCombatResult {
     pub attacker_xp: u32,
 }
 
+deterministic!(struct CityCombatResult {
+    city_damage,
+    attacker_damage,
+    city_captured,
+    attacker_destroyed,
+    attacker_xp,
+});
+
 /// Resolve combat against a city.
 pub fn resolve_city_combat(ctx: &CityCombatContext) -> CityCombatResult {
     let attacker_strength = if ctx.is_ranged {
@@ -364,12 +460,12 @@ pub fn resolve_city_combat(ctx: &CityCombatContext) -> CityCombatResult {
     };
 
     // Cities have inherent combat strength
-    let city_strength = ctx.city_strength as f32;
-    let attacker_final = attacker_strength as f32;
+    let city_strength = Fixed::from_int(ctx.city_strength.max(1) as i64);
+    let attacker_final = Fixed::from_int(attacker_strength as i64);
 
     // Calculate damage
-    let ratio = attacker_final / city_strength.max(1.0);
-    let base_damage = 20.0; // Lower base damage vs cities
+    let ratio = attacker_final / city_strength;
+    let base_damage = Fixed::from_int(20); // Lower base damage vs cities
 
     // Check for Barrage promotions (bonus vs cities)
     let mut city_bonus = 0;
@@ -382,16 +478,17 @@ pub fn resolve_city_combat(ctx: &CityCombatContext) -> CityCombatResult {
         }
     }
 
-    let city_damage_base = base_damage * ratio.powf(0.5) * (1.0 + city_bonus as f32 / 100.0);
-    let random_factor = 0.8 + ctx.random * 0.4;
+    let city_damage_base =
+        base_damage * ratio.sqrt() * (Fixed::ONE + Fixed::from_percent(city_bonus));
+    let random_factor = random_spread(ctx.random);
     let city_damage = (city_damage_base * random_factor).round() as u32;
 
     // City counter-attack (unless ranged)
     let attacker_damage = if ctx.is_ranged {
         0
     } else {
-        let counter_base = base_damage / ratio.powf(0.5);
-        let random_factor_def = 0.8 + (1.0 - ctx.random) * 0.4;
+        let counter_base = base_damage / ratio.sqrt();
+        let random_factor_def = random_spread(Fixed::ONE - ctx.random);
         (counter_base * random_factor_def).round() as u32
     };
 
@@ -407,6 +504,150 @@ pub fn resolve_city_combat(ctx: &CityCombatContext) -> CityCombatResult {
     }
 }
 
+/// How far a city can bombard, in hexes.
+pub const CITY_BOMBARD_RANGE: u32 = 2;
+
+/// Ranged strength of a city.
+///
+/// Cities shoot with their own combat strength, which walls and castles
+/// raise, plus half the strength of their garrison.
+pub fn city_ranged_strength(city: &City, garrison: Option<&Unit>) -> u32 {
+    city.combat_strength + garrison.map_or(0, |unit| unit.effective_combat_strength() / 2)
+}
+
+/// Context for a city bombarding a unit.
+pub struct CityBombardContext<'a> {
+    /// From [`city_ranged_strength`].
+    pub city_strength: u32,
+    pub target: &'a Unit,
+    pub target_tile: &'a Tile,
+    /// Random value from Cashu (0.0 to 1.0).
+    pub random: Fixed,
+}
+
+deterministic!(struct CityBombardContext<'a> {
+    city_strength,
+    target,
+    target_tile,
+    random,
+});
+
+/// Result of a city bombardment.
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct CityBombardResult {
+    /// Damage dealt to the target.
+    pub damage: u32,
+    /// Was the target destroyed?
+    pub target_destroyed: bool,
+    /// Combat log; the city is the attacker.
+    pub log: CombatLog,
+}
+
+deterministic!(struct CityBombardResult {
+    damage,
+    target_destroyed,
+    log,
+});
+
+/// Resolve a city's ranged attack on a unit.
+///
+/// Bombardment is a ranged attack, so the target can't strike back and
+/// defends with terrain, fortification and cover like against archers.
+pub fn resolve_city_bombard(ctx: &CityBombardContext) -> CityBombardResult {
+    let mut defender_modifiers = Vec::new();
+    let defender_base = ctx.target.effective_combat_strength();
+    let defender_mod =
+        calculate_defender_modifiers(ctx.target, ctx.target_tile, true, &mut defender_modifiers);
+
+    let attacker_final = Fixed::from_int(ctx.city_strength as i64);
+    let defender_final = Fixed::from_int(defender_base as i64) * (Fixed::ONE + defender_mod);
+    let (damage, _) = calculate_damage(attacker_final, defender_final, ctx.random, true);
+
+    CityBombardResult {
+        damage,
+        target_destroyed: ctx.target.health <= damage,
+        log: CombatLog {
+            attacker_base_strength: ctx.city_strength,
+            defender_base_strength: defender_base,
+            attacker_modifiers: Vec::new(),
+            defender_modifiers,
+            attacker_final_strength: attacker_final,
+            defender_final_strength: defender_final,
+            random_factor: ctx.random,
+        },
+    }
+}
+
+/// Number of evenly spaced random values a [`CombatPreview`] samples.
+pub const PREVIEW_SAMPLES: i64 = 101;
+
+/// Spread of the damage one side of a combat may take.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Serialize, Deserialize, JsonSchema, TS)]
+pub struct DamageRange {
+    pub min: u32,
+    /// Mean over the random span, rounded.
+    pub avg: u32,
+    pub max: u32,
+}
+
+/// Expected outcome of a combat across every possible random value.
+#[derive(Clone, Debug, Serialize, Deserialize, JsonSchema, TS)]
+pub struct CombatPreview {
+    /// Damage the defender may take.
+    pub defender_damage: DamageRange,
+    /// Damage the attacker may take.
+    pub attacker_damage: DamageRange,
+    /// Chance the defender is destroyed, in percent.
+    pub defender_kill_chance: u32,
+    /// Chance the attacker is destroyed, in percent.
+    pub attacker_kill_chance: u32,
+    /// Strengths and modifiers, with the average random value.
+    pub log: CombatLog,
+}
+
+/// Preview a combat without consuming randomness.
+///
+/// `ctx.random` is ignored; the combat is resolved for
+/// [`PREVIEW_SAMPLES`] random values spread evenly over `[0, 1]`.
+pub fn preview(ctx: &CombatContext) -> CombatPreview {
+    preview_with_difficulty(ctx, 0, 0)
+}
+
+/// Preview a combat with difficulty bonuses applied to each side.
+pub fn preview_with_difficulty(
+    ctx: &CombatContext,
+    attacker_bonus: i32,
+    defender_bonus: i32,
+) -> CombatPreview {
+    let resolve = |random: Fixed| {
+        let sample = CombatContext { random, ..*ctx };
+        resolve_combat_with_difficulty(&sample, attacker_bonus, defender_bonus)
+    };
+
+    let results: Vec<CombatResult> = (0..PREVIEW_SAMPLES)
+        .map(|i| resolve(Fixed::from_ratio(i, PREVIEW_SAMPLES - 1)))
+        .collect();
+    let range = |damage: fn(&CombatResult) -> u32| {
+        let total: u64 = results.iter().map(|r| damage(r) as u64).sum();
+        DamageRange {
+            min: results.iter().map(damage).min().unwrap_or(0),
+            avg: ((total * 2 + PREVIEW_SAMPLES as u64) / (PREVIEW_SAMPLES as u64 * 2)) as u32,
+            max: results.iter().map(damage).max().unwrap_or(0),
+        }
+    };
+    let chance = |destroyed: fn(&CombatResult) -> bool| {
+        (results.iter().filter(|r| destroyed(r)).count() as i64 * 100 / PREVIEW_SAMPLES) as u32
+    };
+
+    CombatPreview {
+        defender_damage: range(|r| r.defender_damage),
+        attacker_damage: range(|r| r.attacker_damage),
+        defender_kill_chance: chance(|r| r.defender_destroyed),
+        attacker_kill_chance: chance(|r| r.attacker_destroyed),
+        log: resolve(Fixed::HALF).log,
+    }
+}
+
 /// Calculate the combat preview (expected outcome without randomness).
 pub fn preview_combat(
     attacker: &Unit,
@@ -420,7 +661,7 @@ pub fn preview_combat(
         defender,
         attacker_tile,
         defender_tile,
-        random: 0.5, // Use average for preview
+        random: Fixed::HALF, // Use average for preview
         is_ranged,
     };
 
@@ -433,6 +674,7 @@ mod tests {
     use super::*;
     use crate::hex::HexCoord;
     use crate::terrain::{Feature, Terrain};
+    use crate::types::{CityId, PlayerSlot, UnitId};
     use crate::unit::UnitType;
 
     fn create_test_tile(terrain: Terrain, feature: Option<Feature>) -> Tile {
@@ -443,8 +685,18 @@ mod tests {
 
     #[test]
     fn test_equal_strength_combat() {
-        let attacker = Unit::new(1, 0, UnitType::Warrior, HexCoord::new(0, 0));
-        let defender = Unit::new(2, 1, UnitType::Warrior, HexCoord::new(1, 0));
+        let attacker = Unit::new(
+            UnitId(1),
+            PlayerSlot(0),
+            UnitType::Warrior,
+            HexCoord::new(0, 0),
+        );
+        let defender = Unit::new(
+            UnitId(2),
+            PlayerSlot(1),
+            UnitType::Warrior,
+            HexCoord::new(1, 0),
+        );
         let tile = create_test_tile(Terrain::Grassland, None);
 
         let ctx = CombatContext {
@@ -452,7 +704,7 @@ mod tests {
             defender: &defender,
             attacker_tile: &tile,
             defender_tile: &tile,
-            random: 0.5,
+            random: Fixed::HALF,
             is_ranged: false,
         };
 
@@ -468,8 +720,18 @@ mod tests {
 
     #[test]
     fn test_stronger_attacker() {
-        let attacker = Unit::new(1, 0, UnitType::Swordsman, HexCoord::new(0, 0)); // 14 strength
-        let defender = Unit::new(2, 1, UnitType::Warrior, HexCoord::new(1, 0)); // 8 strength
+        let attacker = Unit::new(
+            UnitId(1),
+            PlayerSlot(0),
+            UnitType::Swordsman,
+            HexCoord::new(0, 0),
+        ); // 14 strength
+        let defender = Unit::new(
+            UnitId(2),
+            PlayerSlot(1),
+            UnitType::Warrior,
+            HexCoord::new(1, 0),
+        ); // 8 strength
         let tile = create_test_tile(Terrain::Grassland, None);
 
         let ctx = CombatContext {
@@ -477,7 +739,7 @@ mod tests {
             defender: &defender,
             attacker_tile: &tile,
             defender_tile: &tile,
-            random: 0.5,
+            random: Fixed::HALF,
             is_ranged: false,
         };
 
@@ -489,8 +751,18 @@ mod tests {
 
     #[test]
     fn test_terrain_defense_bonus() {
-        let attacker = Unit::new(1, 0, UnitType::Warrior, HexCoord::new(0, 0));
-        let defender = Unit::new(2, 1, UnitType::Warrior, HexCoord::new(1, 0));
+        let attacker = Unit::new(
+            UnitId(1),
+            PlayerSlot(0),
+            UnitType::Warrior,
+            HexCoord::new(0, 0),
+        );
+        let defender = Unit::new(
+            UnitId(2),
+            PlayerSlot(1),
+            UnitType::Warrior,
+            HexCoord::new(1, 0),
+        );
         let flat_tile = create_test_tile(Terrain::Grassland, None);
         let hill_tile = create_test_tile(Terrain::Grassland, Some(Feature::Hills));
 
@@ -500,7 +772,7 @@ mod tests {
             defender: &defender,
             attacker_tile: &flat_tile,
             defender_tile: &flat_tile,
-            random: 0.5,
+            random: Fixed::HALF,
             is_ranged: false,
         };
         let result_flat = resolve_combat(&ctx_flat);
@@ -511,7 +783,7 @@ mod tests {
             defender: &defender,
             attacker_tile: &flat_tile,
             defender_tile: &hill_tile,
-            random: 0.5,
+            random: Fixed::HALF,
             is_ranged: false,
         };
         let result_hills = resolve_combat(&ctx_hills);
@@ -522,8 +794,18 @@ mod tests {
 
     #[test]
     fn test_ranged_no_counter() {
-        let attacker = Unit::new(1, 0, UnitType::Archer, HexCoord::new(0, 0));
-        let defender = Unit::new(2, 1, UnitType::Warrior, HexCoord::new(2, 0));
+        let attacker = Unit::new(
+            UnitId(1),
+            PlayerSlot(0),
+            UnitType::Archer,
+            HexCoord::new(0, 0),
+        );
+        let defender = Unit::new(
+            UnitId(2),
+            PlayerSlot(1),
+            UnitType::Warrior,
+            HexCoord::new(2, 0),
+        );
         let tile = create_test_tile(Terrain::Grassland, None);
 
         let ctx = CombatContext {
@@ -531,7 +813,7 @@ mod tests {
             defender: &defender,
             attacker_tile: &tile,
             defender_tile: &tile,
-            random: 0.5,
+            random: Fixed::HALF,
             is_ranged: true,
         };
 
@@ -544,8 +826,18 @@ mod tests {
 
     #[test]
     fn test_fortification_bonus() {
-        let attacker = Unit::new(1, 0, UnitType::Warrior, HexCoord::new(0, 0));
-        let mut defender = Unit::new(2, 1, UnitType::Warrior, HexCoord::new(1, 0));
+        let attacker = Unit::new(
+            UnitId(1),
+            PlayerSlot(0),
+            UnitType::Warrior,
+            HexCoord::new(0, 0),
+        );
+        let mut defender = Unit::new(
+            UnitId(2),
+            PlayerSlot(1),
+            UnitType::Warrior,
+            HexCoord::new(1, 0),
+        );
         let tile = create_test_tile(Terrain::Grassland, None);
 
         // Non-fortified combat
@@ -554,7 +846,7 @@ mod tests {
             defender: &defender,
             attacker_tile: &tile,
             defender_tile: &tile,
-            random: 0.5,
+            random: Fixed::HALF,
             is_ranged: false,
         };
         let result1 = resolve_combat(&ctx1);
@@ -568,7 +860,7 @@ mod tests {
             defender: &defender,
             attacker_tile: &tile,
             defender_tile: &tile,
-            random: 0.5,
+            random: Fixed::HALF,
             is_ranged: false,
         };
         let result2 = resolve_combat(&ctx2);
@@ -579,8 +871,18 @@ mod tests {
 
     #[test]
     fn test_experience_gain() {
-        let attacker = Unit::new(1, 0, UnitType::Warrior, HexCoord::new(0, 0));
-        let defender = Unit::new(2, 1, UnitType::Warrior, HexCoord::new(1, 0));
+        let attacker = Unit::new(
+            UnitId(1),
+            PlayerSlot(0),
+            UnitType::Warrior,
+            HexCoord::new(0, 0),
+        );
+        let defender = Unit::new(
+            UnitId(2),
+            PlayerSlot(1),
+            UnitType::Warrior,
+            HexCoord::new(1, 0),
+        );
         let tile = create_test_tile(Terrain::Grassland, None);
 
         let ctx = CombatContext {
@@ -588,7 +890,7 @@ mod tests {
             defender: &defender,
             attacker_tile: &tile,
             defender_tile: &tile,
-            random: 0.5,
+            random: Fixed::HALF,
             is_ranged: false,
         };
 
@@ -601,8 +903,18 @@ mod tests {
 
     #[test]
     fn test_combat_preview() {
-        let attacker = Unit::new(1, 0, UnitType::Warrior, HexCoord::new(0, 0));
-        let defender = Unit::new(2, 1, UnitType::Warrior, HexCoord::new(1, 0));
+        let attacker = Unit::new(
+            UnitId(1),
+            PlayerSlot(0),
+            UnitType::Warrior,
+            HexCoord::new(0, 0),
+        );
+        let defender = Unit::new(
+            UnitId(2),
+            PlayerSlot(1),
+            UnitType::Warrior,
+            HexCoord::new(1, 0),
+        );
         let tile = create_test_tile(Terrain::Grassland, None);
 
         let (def_dmg, atk_dmg) = preview_combat(&attacker, &defender, &tile, &tile, false);
@@ -611,9 +923,95 @@ mod tests {
         assert!(atk_dmg > 0);
     }
 
+    #[test]
+    fn test_preview_spans_random_range() {
+        let attacker = Unit::new(
+            UnitId(1),
+            PlayerSlot(0),
+            UnitType::Warrior,
+            HexCoord::new(0, 0),
+        );
+        let defender = Unit::new(
+            UnitId(2),
+            PlayerSlot(1),
+            UnitType::Warrior,
+            HexCoord::new(1, 0),
+        );
+        let tile = create_test_tile(Terrain::Grassland, None);
+        let ctx = |random| CombatContext {
+            attacker: &attacker,
+            defender: &defender,
+            attacker_tile: &tile,
+            defender_tile: &tile,
+            random,
+            is_ranged: false,
+        };
+
+        let preview = preview(&ctx(Fixed::ZERO));
+        let low = resolve_combat(&ctx(Fixed::ZERO));
+        let high = resolve_combat(&ctx(Fixed::ONE));
+
+        assert_eq!(preview.defender_damage.min, low.defender_damage);
+        assert_eq!(preview.defender_damage.max, high.defender_damage);
+        assert!(preview.defender_damage.min <= preview.defender_damage.avg);
+        assert!(preview.defender_damage.avg <= preview.defender_damage.max);
+        assert!(preview.attacker_damage.min <= preview.attacker_damage.max);
+        // Full-health warriors can't destroy each other in one fight
+        assert_eq!(preview.defender_kill_chance, 0);
+        assert_eq!(preview.attacker_kill_chance, 0);
+        assert_eq!(preview.log.random_factor, Fixed::HALF);
+
+        // The random value in the context doesn't change the preview
+        let other = super::preview(&ctx(Fixed::ONE));
+        assert_eq!(other.defender_damage, preview.defender_damage);
+    }
+
+    #[test]
+    fn test_preview_kill_chance() {
+        let attacker = Unit::new(
+            UnitId(1),
+            PlayerSlot(0),
+            UnitType::Warrior,
+            HexCoord::new(0, 0),
+        );
+        let mut defender = Unit::new(
+            UnitId(2),
+            PlayerSlot(1),
+            UnitType::Warrior,
+            HexCoord::new(1, 0),
+        );
+        let tile = create_test_tile(Terrain::Grassland, None);
+        let preview_against = |defender: &Unit| {
+            preview(&CombatContext {
+                attacker: &attacker,
+                defender,
+                attacker_tile: &tile,
+                defender_tile: &tile,
+                random: Fixed::HALF,
+                is_ranged: false,
+            })
+        };
+
+        // A wounded defender dies to good rolls only
+        defender.health = 45;
+        let preview = preview_against(&defender);
+        assert!(preview.defender_damage.min < 45 && preview.defender_damage.max >= 45);
+        assert!(preview.defender_kill_chance > 0);
+        assert!(preview.defender_kill_chance < 100);
+
+        // A nearly dead defender dies to every roll
+        defender.health = 10;
+        assert_eq!(preview_against(&defender).defender_kill_chance, 100);
+    }
+
     #[test]
     fn test_city_combat() {
-        let attacker = Unit::new(1, 0, UnitType::Warrior, HexCoord::new(0, 0));
+        let attacker = Unit::new(
+            UnitId(1),
+            PlayerSlot(0),
+            UnitType::Warrior,
+            HexCoord::new(0, 0),
+        );
         let tile = create_test_tile(Terrain::Grassland, None);
 
         let ctx = CityCombatContext {
@@ -621,7 +1019,7 @@ mod tests {
             city_strength: 10,
             city_health: 200,
             attacker_tile: &tile,
-            random: 0.5,
+            random: Fixed::HALF,
             is_ranged: false,
         };
 
@@ -631,4 +1029,123 @@ mod tests {
         assert!(result.attacker_damage > 0);
         assert!(!result.city_captured); // City has too much health
     }
+
+    #[test]
+    fn test_city_ranged_strength_from_walls_and_garrison() {
+        use crate::city::BuildingType;
+
+        let mut city = City::new(
+            CityId(1),
+            PlayerSlot(0),
+            "Test".to_string(),
+            HexCoord::new(0, 0),
+            true,
+        );
+        let garrison = Unit::new(
+            UnitId(1),
+            PlayerSlot(0),
+            UnitType::Warrior,
+            HexCoord::new(0, 0),
+        );
+        let base = city_ranged_strength(&city, None);
+
+        assert_eq!(
+            city_ranged_strength(&city, Some(&garrison)),
+            base + garrison.effective_combat_strength() / 2
+        );
+        city.add_building(BuildingType::Walls);
+        assert!(city_ranged_strength(&city, None) > base);
+    }
+
+    #[test]
+    fn test_city_bombard() {
+        let target = Unit::new(
+            UnitId(1),
+            PlayerSlot(1),
+            UnitType::Warrior,
+            HexCoord::new(2, 0),
+        );
+        let grassland = create_test_tile(Terrain::Grassland, None);
+        let hills = create_test_tile(Terrain::Grassland, Some(Feature::Hills));
+        let bombard = |city_strength, target_tile| {
+            resolve_city_bombard(&CityBombardContext {
+                city_strength,
+                target: &target,
+                target_tile,
+                random: Fixed::HALF,
+            })
+        };
+
+        let result = bombard(10, &grassland);
+        assert!(result.damage > 0);
+        assert!(!result.target_destroyed);
+        assert!(bombard(20, &grassland).damage > result.damage);
+        // Rough terrain protects the target
+        assert!(bombard(10, &hills).damage < result.damage);
+    }
+
+    // ==================== Determinism Tests ====================
+
+    #[test]
+    fn test_combat_types_are_sealed_deterministic() {
+        use crate::fixed::assert_deterministic;
+
+        assert_deterministic::<CombatContext>();
+        assert_deterministic::<CityCombatContext>();
+        assert_deterministic::<CombatResult>();
+        assert_deterministic::<CityCombatResult>();
+        assert_deterministic::<CityBombardContext>();
+        assert_deterministic::<CityBombardResult>();
+        assert_deterministic::<crate::yields::Yields>();
+    }
+
+    #[test]
+    fn test_calculation_paths_are_float_free() {
+        let sources = [
+            ("combat.rs", include_str!("combat.rs")),
+            ("yields.rs", include_str!("yields.rs")),
+            ("city.rs", include_str!("city.rs")),
+            ("map.rs", include_str!("map.rs")),
+            ("terrain.rs", include_str!("terrain.rs")),
+            ("settings.rs", include_str!("settings.rs")),
+            ("trading.rs", include_str!("trading.rs")),
+            ("ai.rs", include_str!("ai.rs")),
+        ];
+        for (file, source) in sources {
+            let floats = crate::fixed::float_tokens(source);
+            assert!(floats.is_empty(), "float types in {}: {:?}", file, floats);
+        }
+    }
+
+    #[test]
+    fn test_combat_is_bit_identical_for_same_inputs() {
+        let attacker = Unit::new(
+            UnitId(1),
+            PlayerSlot(0),
+            UnitType::Swordsman,
+            HexCoord::new(0, 0),
+        );
+        let defender = Unit::new(
+            UnitId(2),
+            PlayerSlot(1),
+            UnitType::Warrior,
+            HexCoord::new(1, 0),
+        );
+        let tile = create_test_tile(Terrain::Grassland, Some(Feature::Hills));
+        let ctx = CombatContext {
+            attacker: &attacker,
+            defender: &defender,
+            attacker_tile: &tile,
+            defender_tile: &tile,
+            random: Fixed::from_ratio(1, 3),
+            is_ranged: false,
+        };
+
+        let a = resolve_combat(&ctx);
+        let b = resolve_combat(&ctx);
+        assert_eq!(a.log.attacker_final_strength, b.log.attacker_final_strength);
+        assert_eq!(a.log.defender_final_strength, b.log.defender_final_strength);
+        assert_eq!(a.defender_damage, b.defender_damage);
+        assert_eq!(a.attacker_damage, b.attacker_damage);
+    }
 }
@@ -0,0 +1,263 @@
+//! Conceding a game.
+//!
+//! A player who can no longer win may concede instead of playing on. Their
+//! cities are either handed to another player or razed, depending on the
+//! game's [`ConcessionPolicy`], their units are disbanded, and they are
+//! eliminated. If only one player is left they win by
+//! [`VictoryType::Concession`].
+
+use crate::game_state::{GamePhase, GameState};
+use crate::settings::ConcessionPolicy;
+use crate::types::{CityId, PlayerSlot, UnitId, VictoryType};
+
+/// Result of a player conceding.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct Concession {
+    /// The player who conceded.
+    pub player_id: PlayerSlot,
+    /// The player who received their cities, if any were transferred.
+    pub recipient: Option<PlayerSlot>,
+    /// Cities handed to the recipient.
+    pub transferred: Vec<CityId>,
+    /// Cities destroyed.
+    pub razed: Vec<CityId>,
+    /// Units disbanded.
+    pub disbanded: Vec<UnitId>,
+    /// The winner, if the concession ended the game.
+    pub winner: Option<(PlayerSlot, VictoryType)>,
+}
+
+/// Pick who receives a conceding player's cities.
+///
+/// Uses the requested player if given, otherwise the remaining player with
+/// the highest score. Ties go to the lowest player ID.
+pub fn concession_recipient(
+    state: &GameState,
+    player_id: PlayerSlot,
+    to_player: Option<PlayerSlot>,
+) -> Option<PlayerSlot> {
+    if let Some(target) = to_player {
+        return Some(target);
+    }
+    state
+        .players
+        .iter()
+        .filter(|p| p.id != player_id && !p.eliminated)
+        .max_by(|a, b| a.score.total.cmp(&b.score.total).then(b.id.cmp(&a.id)))
+        .map(|p| p.id)
+}
+
+/// Remove a player from the game by concession.
+///
+/// The caller is expected to have checked that the player may concede.
+pub fn concede(
+    state: &mut GameState,
+    player_id: PlayerSlot,
+    to_player: Option<PlayerSlot>,
+) -> Concession {
+    let recipient = match state.settings.concession_policy {
+        ConcessionPolicy::Transfer => concession_recipient(state, player_id, to_player),
+        ConcessionPolicy::Raze => None,
+    };
+    let mut concession = Concession {
+        player_id,
+        recipient,
+        ..Default::default()
+    };
+
+    let mut city_ids: Vec<CityId> = state
+        .cities
+        .values()
+        .filter(|c| c.owner == player_id)
+        .map(|c| c.id)
+        .collect();
+    city_ids.sort_unstable();
+
+    for city_id in city_ids {
+        match recipient {
+            Some(new_owner) => {
+                let has_capital = state
+                    .get_player(new_owner)
+                    .is_some_and(|p| p.capital.is_some());
+                let Some(city) = state.cities.get_mut(&city_id) else {
+                    continue;
+                };
+                city.owner = new_owner;
+                // A second capital would confuse domination checks
+                city.is_capital &= !has_capital;
+                let territory: Vec<_> = city.territory.iter().copied().collect();
+                for coord in territory {
+                    if let Some(tile) = state.map.get_mut(&coord) {
+                        tile.owner = Some(new_owner);
+                    }
+                }
+                concession.transferred.push(city_id);
+            }
+            None => {
+                let Some(city) = state.cities.remove(&city_id) else {
+                    continue;
+                };
+                for coord in city.territory.iter().chain([&city.position]) {
+                    if let Some(tile) = state.map.get_mut(coord) {
+                        tile.owner = None;
+                        if tile.city_id == Some(city_id) {
+                            tile.city_id = None;
+                        }
+                    }
+                }
+                concession.razed.push(city_id);
+            }
+        }
+    }
+
+    let mut unit_ids: Vec<UnitId> = state
+        .units
+        .values()
+        .filter(|u| u.owner == player_id)
+        .map(|u| u.id)
+        .collect();
+    unit_ids.sort_unstable();
+    for unit_id in &unit_ids {
+        state.units.remove(unit_id);
+    }
+    concession.disbanded = unit_ids;
+
+    if let Some(player) = state.get_player_mut(player_id) {
+        player.eliminate();
+        player.conceded = true;
+        player.capital = None;
+    }
+
+    let mut remaining = state.players.iter().filter(|p| !p.eliminated);
+    if let (Some(winner), None) = (remaining.next(), remaining.next()) {
+        let winner = (winner.id, VictoryType::Concession);
+        state.winner = Some(winner);
+        state.phase = GamePhase::Ended;
+        concession.winner = Some(winner);
+    }
+
+    concession
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::city::City;
+    use crate::hex::HexCoord;
+    use crate::map::Map;
+    use crate::player::{Civilization, Player};
+    use crate::settings::GameSettings;
+    use crate::terrain::Terrain;
+    use crate::types::{GameId, Npub};
+    use crate::unit::{Unit, UnitType};
+
+    fn create_test_game(players: u8) -> GameState {
+        let mut settings = GameSettings::new("Concession".to_string());
+        settings.player_count = players;
+        let mut state = GameState::new(GameId::new("g"), settings, [0u8; 32]);
+        state.map = Map::filled(8, 8, Terrain::Grassland);
+        for id in 0..players {
+            let player = Player::new(
+                PlayerSlot(id),
+                Npub(format!("npub{}", id)),
+                format!("P{}", id),
+                Civilization::default(),
+            );
+            state.add_player(player).unwrap();
+        }
+        state.start().unwrap();
+        state
+    }
+
+    fn add_city(state: &mut GameState, owner: PlayerSlot, position: HexCoord) -> CityId {
+        let id = state.allocate_city_id();
+        let city = City::new(id, owner, format!("City {}", id), position, true);
+        state.cities.insert(id, city);
+        if let Some(tile) = state.map.get_mut(&position) {
+            tile.owner = Some(owner);
+            tile.city_id = Some(id);
+        }
+        id
+    }
+
+    // ==================== Transfer Tests ====================
+
+    #[test]
+    fn test_concede_transfers_cities() {
+        let mut state = create_test_game(3);
+        let position = HexCoord::new(2, 2);
+        let city_id = add_city(&mut state, PlayerSlot(0), position);
+        let unit_id = state.allocate_unit_id();
+        state.units.insert(
+            unit_id,
+            Unit::new(unit_id, PlayerSlot(0), UnitType::Warrior, position),
+        );
+
+        let result = concede(&mut state, PlayerSlot(0), Some(PlayerSlot(2)));
+
+        assert_eq!(result.recipient, Some(PlayerSlot(2)));
+        assert_eq!(result.transferred, vec![city_id]);
+        assert_eq!(result.disbanded, vec![unit_id]);
+        assert_eq!(state.cities[&city_id].owner, PlayerSlot(2));
+        assert_eq!(state.map.get(&position).unwrap().owner, Some(PlayerSlot(2)));
+        assert!(state.units.is_empty());
+
+        let player = state.get_player(PlayerSlot(0)).unwrap();
+        assert!(player.eliminated);
+        assert!(player.conceded);
+        assert_eq!(result.winner, None);
+        assert_eq!(state.phase, GamePhase::Playing);
+    }
+
+    #[test]
+    fn test_recipient_defaults_to_highest_score() {
+        let mut state = create_test_game(3);
+        state.get_player_mut(PlayerSlot(2)).unwrap().score.total = 50;
+        assert_eq!(
+            concession_recipient(&state, PlayerSlot(0), None),
+            Some(PlayerSlot(2))
+        );
+
+        // Ties go to the lowest ID
+        state.get_player_mut(PlayerSlot(1)).unwrap().score.total = 50;
+        assert_eq!(
+            concession_recipient(&state, PlayerSlot(0), None),
+            Some(PlayerSlot(1))
+        );
+    }
+
+    // ==================== Raze Tests ====================
+
+    #[test]
+    fn test_concede_razes_cities() {
+        let mut state = create_test_game(3);
+        state.settings.concession_policy = ConcessionPolicy::Raze;
+        let position = HexCoord::new(2, 2);
+        let city_id = add_city(&mut state, PlayerSlot(0), position);
+
+        let result = concede(&mut state, PlayerSlot(0), Some(PlayerSlot(1)));
+
+        assert_eq!(result.recipient, None);
+        assert_eq!(result.razed, vec![city_id]);
+        assert!(state.cities.is_empty());
+        let tile = state.map.get(&position).unwrap();
+        assert_eq!(tile.owner, None);
+        assert_eq!(tile.city_id, None);
+    }
+
+    // ==================== Victory Tests ====================
+
+    #[test]
+    fn test_last_opponent_conceding_ends_game() {
+        let mut state = create_test_game(2);
+
+        let result = concede(&mut state, PlayerSlot(1), None);
+
+        assert_eq!(
+            result.winner,
+            Some((PlayerSlot(0), VictoryType::Concession))
+        );
+        assert_eq!(state.winner, Some((PlayerSlot(0), VictoryType::Concession)));
+        assert_eq!(state.phase, GamePhase::Ended);
+    }
+}
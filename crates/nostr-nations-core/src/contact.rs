@@ -0,0 +1,131 @@
+//! First contact between civilizations.
+//!
+//! Players start the game knowing nobody. Two players meet the first time
+//! either one sights a unit or city of the other; from then on both appear
+//! in each other's diplomacy screen and demographics, and diplomatic
+//! actions between them are allowed.
+//!
+//! Contacts are checked after a player's units move or found a city, and
+//! for every player at the end of each turn once vision is recomputed.
+
+use crate::game_state::GameState;
+use crate::types::PlayerSlot;
+use crate::visibility::VisibilityFilter;
+use std::collections::BTreeSet;
+
+/// Owners of the units and cities a visibility filter can see, other than
+/// the viewer.
+pub fn sighted_players(state: &GameState, filter: &VisibilityFilter) -> BTreeSet<PlayerSlot> {
+    let units = filter
+        .visible_units()
+        .iter()
+        .filter_map(|id| state.units.get(id))
+        .map(|u| u.owner);
+    let cities = filter
+        .visible_cities()
+        .iter()
+        .filter_map(|id| state.cities.get(id))
+        .map(|c| c.owner);
+    units
+        .chain(cities)
+        .filter(|owner| *owner != filter.player_id())
+        .collect()
+}
+
+/// Record that two players have met.
+///
+/// Returns `false` if they had already met.
+pub fn meet(state: &mut GameState, a: PlayerSlot, b: PlayerSlot) -> bool {
+    if a == b || state.get_player(a).is_none() || state.get_player(b).is_none() {
+        return false;
+    }
+    let new = state.players[a.index()].met_players.insert(b);
+    state.players[b.index()].met_players.insert(a);
+    new
+}
+
+/// Meet everyone a player can currently see.
+///
+/// Returns the players met for the first time, by ID.
+pub fn update_contacts(state: &mut GameState, player_id: PlayerSlot) -> Vec<PlayerSlot> {
+    let mut filter = VisibilityFilter::new(player_id);
+    filter.update_from_game_state(state);
+    record_sightings(state, player_id, sighted_players(state, &filter))
+}
+
+/// Meet every player in `sighted`, returning those met for the first time.
+pub fn record_sightings(
+    state: &mut GameState,
+    player_id: PlayerSlot,
+    sighted: BTreeSet<PlayerSlot>,
+) -> Vec<PlayerSlot> {
+    sighted
+        .into_iter()
+        .filter(|&other| meet(state, player_id, other))
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::hex::HexCoord;
+    use crate::map::Map;
+    use crate::player::{Civilization, Player};
+    use crate::settings::GameSettings;
+    use crate::terrain::Terrain;
+    use crate::types::{GameId, Npub, UnitId};
+    use crate::unit::{Unit, UnitType};
+
+    fn create_state() -> GameState {
+        let mut settings = GameSettings::new("Test".to_string());
+        settings.player_count = 3;
+        let mut state = GameState::new(GameId::new("test"), settings, [0; 32]);
+        for id in 0..3 {
+            state
+                .add_player(Player::new(
+                    PlayerSlot(id),
+                    Npub(format!("pk{}", id)),
+                    format!("P{}", id),
+                    Civilization::default(),
+                ))
+                .unwrap();
+        }
+        state.map = Map::filled(30, 10, Terrain::Grassland);
+        for (id, owner, q) in [(1, 0, 2), (2, 1, 4), (3, 2, 25)] {
+            state.units.insert(
+                UnitId(id),
+                Unit::new(
+                    UnitId(id),
+                    PlayerSlot(owner),
+                    UnitType::Warrior,
+                    HexCoord::new(q, 5),
+                ),
+            );
+        }
+        state
+    }
+
+    #[test]
+    fn test_first_contact_on_sighting() {
+        let mut state = create_state();
+        assert!(!state.has_met(PlayerSlot(0), PlayerSlot(1)));
+
+        assert_eq!(update_contacts(&mut state, PlayerSlot(0)), [PlayerSlot(1)]);
+        assert!(state.has_met(PlayerSlot(0), PlayerSlot(1)));
+        assert!(state.has_met(PlayerSlot(1), PlayerSlot(0)));
+        assert!(!state.has_met(PlayerSlot(0), PlayerSlot(2)));
+
+        // Contact is only made once
+        assert!(update_contacts(&mut state, PlayerSlot(0)).is_empty());
+        assert!(update_contacts(&mut state, PlayerSlot(1)).is_empty());
+    }
+
+    #[test]
+    fn test_meet() {
+        let mut state = create_state();
+        assert!(meet(&mut state, PlayerSlot(0), PlayerSlot(2)));
+        assert!(!meet(&mut state, PlayerSlot(2), PlayerSlot(0)));
+        assert!(!meet(&mut state, PlayerSlot(1), PlayerSlot(1)));
+        assert!(!meet(&mut state, PlayerSlot(1), PlayerSlot(9)));
+    }
+}
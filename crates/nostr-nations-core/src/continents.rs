@@ -0,0 +1,213 @@
+//! Continents: the connected landmasses of the map.
+//!
+//! Map generation labels every land tile with the continent it belongs to
+//! by flood-filling over adjacent land (see [`label_continents`]). The
+//! labels drive a few mechanics:
+//!
+//! - Each luxury resource is found on at most [`MAX_LUXURY_CONTINENTS`]
+//!   continents, so some luxuries can only be had by trading overseas.
+//! - The first player to circumnavigate a wrapping world, by exploring a
+//!   tile in every column, earns [`CIRCUMNAVIGATION_GOLD`].
+//! - AI settlers only look for city sites on the landmass they stand on.
+
+use crate::game_state::GameState;
+use crate::hex::HexCoord;
+use crate::map::Map;
+use crate::types::PlayerSlot;
+use std::collections::{BTreeMap, BTreeSet, VecDeque};
+
+/// Most continents a single luxury resource is placed on.
+pub const MAX_LUXURY_CONTINENTS: usize = 2;
+
+/// Gold for the first player to circumnavigate the world.
+pub const CIRCUMNAVIGATION_GOLD: i32 = 100;
+
+/// Label every land tile with its continent and return how many there are.
+///
+/// Continents are numbered from zero in row-major order of their first
+/// tile, so the same terrain always gets the same labels. Water tiles are
+/// cleared.
+pub fn label_continents(map: &mut Map) -> u16 {
+    let mut coords: Vec<HexCoord> = map.tiles.keys().copied().collect();
+    coords.sort();
+    for coord in &coords {
+        if let Some(tile) = map.get_mut(coord) {
+            tile.continent = None;
+        }
+    }
+
+    let mut next = 0u16;
+    let mut queue = VecDeque::new();
+    for start in coords {
+        match map.get(&start) {
+            Some(tile) if !tile.terrain.is_water() && tile.continent.is_none() => {}
+            _ => continue,
+        }
+
+        if let Some(tile) = map.get_mut(&start) {
+            tile.continent = Some(next);
+        }
+        queue.push_back(start);
+        while let Some(coord) = queue.pop_front() {
+            for neighbor in map.neighbors(&coord) {
+                if let Some(tile) = map.get_mut(&neighbor) {
+                    if !tile.terrain.is_water() && tile.continent.is_none() {
+                        tile.continent = Some(next);
+                        queue.push_back(neighbor);
+                    }
+                }
+            }
+        }
+        next += 1;
+    }
+    next
+}
+
+/// Number of land tiles on each continent.
+pub fn continent_sizes(map: &Map) -> BTreeMap<u16, usize> {
+    let mut sizes = BTreeMap::new();
+    for tile in map.tiles.values() {
+        if let Some(continent) = tile.continent {
+            *sizes.entry(continent).or_default() += 1;
+        }
+    }
+    sizes
+}
+
+/// Check if a player has explored a tile in every column of a wrapping
+/// map.
+pub fn has_circumnavigated(state: &GameState, player_id: PlayerSlot) -> bool {
+    if !state.map.wrap_x || state.map.width == 0 {
+        return false;
+    }
+    let Some(player) = state.get_player(player_id) else {
+        return false;
+    };
+    let columns: BTreeSet<i32> = player
+        .explored_tiles
+        .iter()
+        .filter(|coord| state.map.in_bounds(coord))
+        .map(|coord| state.map.wrap_coord(coord).q)
+        .collect();
+    columns.len() == state.map.width as usize
+}
+
+/// Award the circumnavigation bonus if the player is the first to earn it.
+///
+/// Returns the gold awarded.
+pub fn check_circumnavigation(state: &mut GameState, player_id: PlayerSlot) -> Option<i32> {
+    if state.circumnavigated_by.is_some() || !has_circumnavigated(state, player_id) {
+        return None;
+    }
+    state.circumnavigated_by = Some(player_id);
+    let player = state.get_player_mut(player_id)?;
+    player.gold += CIRCUMNAVIGATION_GOLD;
+    Some(CIRCUMNAVIGATION_GOLD)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::player::{Civilization, Player};
+    use crate::settings::GameSettings;
+    use crate::terrain::Terrain;
+    use crate::types::{GameId, Npub};
+
+    /// Ocean map with two islands: columns 1-2 and column 5.
+    fn two_islands(wrap_x: bool) -> Map {
+        let mut map = Map::filled(8, 4, Terrain::Ocean);
+        map.wrap_x = wrap_x;
+        for q in [1, 2, 5] {
+            for r in 0..4 {
+                let tile = map.get_mut(&HexCoord::new(q, r)).unwrap();
+                tile.terrain = Terrain::Grassland;
+            }
+        }
+        map
+    }
+
+    #[test]
+    fn test_label_continents() {
+        let mut map = two_islands(false);
+        assert_eq!(label_continents(&mut map), 2);
+
+        assert_eq!(map.continent(&HexCoord::new(1, 0)), Some(0));
+        assert_eq!(map.continent(&HexCoord::new(2, 3)), Some(0));
+        assert_eq!(map.continent(&HexCoord::new(5, 1)), Some(1));
+        assert_eq!(map.continent(&HexCoord::new(3, 0)), None);
+        assert!(map.same_continent(&HexCoord::new(1, 0), &HexCoord::new(2, 3)));
+        assert!(!map.same_continent(&HexCoord::new(1, 0), &HexCoord::new(5, 1)));
+        assert!(!map.same_continent(&HexCoord::new(3, 0), &HexCoord::new(3, 1)));
+        assert_eq!(continent_sizes(&map), BTreeMap::from([(0, 8), (1, 4)]));
+
+        // Relabeling gives the same answer
+        assert_eq!(label_continents(&mut map), 2);
+        assert_eq!(map.continent(&HexCoord::new(5, 1)), Some(1));
+    }
+
+    #[test]
+    fn test_landmass_joins_across_wrap() {
+        let mut map = two_islands(true);
+        for r in 0..4 {
+            map.get_mut(&HexCoord::new(7, r)).unwrap().terrain = Terrain::Plains;
+            map.get_mut(&HexCoord::new(0, r)).unwrap().terrain = Terrain::Plains;
+        }
+        // Columns 7, 0, 1 and 2 join across the seam
+        assert_eq!(label_continents(&mut map), 2);
+        assert!(map.same_continent(&HexCoord::new(7, 2), &HexCoord::new(2, 2)));
+    }
+
+    fn explorer_game() -> GameState {
+        let mut state = GameState::new(
+            GameId::new("continents"),
+            GameSettings::new("Test".to_string()),
+            [0u8; 32],
+        );
+        state.map = two_islands(true);
+        for id in 0..2 {
+            let player = Player::new(
+                PlayerSlot(id),
+                Npub(format!("pk{}", id)),
+                format!("P{}", id),
+                Civilization::default(),
+            );
+            state.add_player(player).unwrap();
+        }
+        state
+    }
+
+    #[test]
+    fn test_first_circumnavigation_earns_gold() {
+        let mut state = explorer_game();
+        for q in 0..7 {
+            state.players[0].explore_tile(HexCoord::new(q, 1));
+            state.players[1].explore_tile(HexCoord::new(q, 1));
+        }
+        assert_eq!(check_circumnavigation(&mut state, PlayerSlot(0)), None);
+
+        // Column 7 reached through the seam
+        state.players[0].explore_tile(HexCoord::new(-1, 2));
+        let gold = state.players[0].gold;
+        assert_eq!(
+            check_circumnavigation(&mut state, PlayerSlot(0)),
+            Some(CIRCUMNAVIGATION_GOLD)
+        );
+        assert_eq!(state.players[0].gold, gold + CIRCUMNAVIGATION_GOLD);
+        assert_eq!(state.circumnavigated_by, Some(PlayerSlot(0)));
+
+        // Only the first player gets the bonus, and only once
+        state.players[1].explore_tile(HexCoord::new(7, 0));
+        assert_eq!(check_circumnavigation(&mut state, PlayerSlot(1)), None);
+        assert_eq!(check_circumnavigation(&mut state, PlayerSlot(0)), None);
+    }
+
+    #[test]
+    fn test_no_circumnavigation_without_wrap() {
+        let mut state = explorer_game();
+        state.map.wrap_x = false;
+        for q in 0..8 {
+            state.players[0].explore_tile(HexCoord::new(q, 0));
+        }
+        assert!(!has_circumnavigated(&state, PlayerSlot(0)));
+    }
+}
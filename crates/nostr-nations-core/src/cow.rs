@@ -0,0 +1,149 @@
+//! Copy-on-write storage for large game collections.
+//!
+//! [`Shared`] wraps a value in an [`Arc`]. Cloning it only bumps a reference
+//! count; the first mutable access through a shared handle copies the value
+//! so other handles are unaffected. `GameState` keeps its map, units and
+//! cities in `Shared`, which makes snapshots for AI lookahead and action
+//! previews cheap until a hypothetical move actually changes something.
+
+use serde::{Deserialize, Deserializer, Serialize, Serializer};
+use std::ops::{Deref, DerefMut};
+use std::sync::Arc;
+
+/// A copy-on-write value shared between game state snapshots.
+#[derive(Default, PartialEq, Eq)]
+pub struct Shared<T>(Arc<T>);
+
+impl<T> Shared<T> {
+    /// Wrap a value.
+    pub fn new(value: T) -> Self {
+        Self(Arc::new(value))
+    }
+
+    /// Check if two handles point at the same storage.
+    pub fn ptr_eq(a: &Self, b: &Self) -> bool {
+        Arc::ptr_eq(&a.0, &b.0)
+    }
+
+    /// Check if the storage is shared with another handle.
+    pub fn is_shared(&self) -> bool {
+        Arc::strong_count(&self.0) > 1
+    }
+}
+
+impl<T: Clone> Shared<T> {
+    /// Take the value out, cloning it only if it is shared.
+    pub fn into_inner(self) -> T {
+        Arc::try_unwrap(self.0).unwrap_or_else(|arc| (*arc).clone())
+    }
+}
+
+impl<T> Clone for Shared<T> {
+    fn clone(&self) -> Self {
+        Self(Arc::clone(&self.0))
+    }
+}
+
+impl<T> Deref for Shared<T> {
+    type Target = T;
+
+    fn deref(&self) -> &T {
+        &self.0
+    }
+}
+
+impl<T: Clone> DerefMut for Shared<T> {
+    fn deref_mut(&mut self) -> &mut T {
+        Arc::make_mut(&mut self.0)
+    }
+}
+
+impl<T> From<T> for Shared<T> {
+    fn from(value: T) -> Self {
+        Self::new(value)
+    }
+}
+
+impl<T: std::fmt::Debug> std::fmt::Debug for Shared<T> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        self.0.fmt(f)
+    }
+}
+
+impl<T: Serialize> Serialize for Shared<T> {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        self.0.serialize(serializer)
+    }
+}
+
+impl<'de, T: Deserialize<'de>> Deserialize<'de> for Shared<T> {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        T::deserialize(deserializer).map(Self::new)
+    }
+}
+
+impl<'a, T> IntoIterator for &'a Shared<T>
+where
+    &'a T: IntoIterator,
+{
+    type Item = <&'a T as IntoIterator>::Item;
+    type IntoIter = <&'a T as IntoIterator>::IntoIter;
+
+    fn into_iter(self) -> Self::IntoIter {
+        (&*self.0).into_iter()
+    }
+}
+
+impl<'a, T: Clone> IntoIterator for &'a mut Shared<T>
+where
+    &'a mut T: IntoIterator,
+{
+    type Item = <&'a mut T as IntoIterator>::Item;
+    type IntoIter = <&'a mut T as IntoIterator>::IntoIter;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.deref_mut().into_iter()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::collections::HashMap;
+
+    #[test]
+    fn test_clone_shares_storage() {
+        let a = Shared::new(vec![1, 2, 3]);
+        let b = a.clone();
+        assert!(Shared::ptr_eq(&a, &b));
+        assert!(a.is_shared());
+    }
+
+    #[test]
+    fn test_write_copies_shared_storage() {
+        let a = Shared::new(vec![1, 2, 3]);
+        let mut b = a.clone();
+        b.push(4);
+        assert!(!Shared::ptr_eq(&a, &b));
+        assert_eq!(*a, vec![1, 2, 3]);
+        assert_eq!(*b, vec![1, 2, 3, 4]);
+    }
+
+    #[test]
+    fn test_write_to_unique_storage_does_not_copy() {
+        let mut a = Shared::new(vec![1]);
+        let before = a.as_ptr();
+        a[0] = 2;
+        assert_eq!(a.as_ptr(), before);
+        assert!(!a.is_shared());
+    }
+
+    #[test]
+    fn test_serializes_transparently() {
+        let map: Shared<HashMap<u32, String>> = HashMap::from([(1, "a".to_string())]).into();
+        let json = serde_json::to_string(&map).unwrap();
+        assert_eq!(json, r#"{"1":"a"}"#);
+        let back: Shared<HashMap<u32, String>> = serde_json::from_str(&json).unwrap();
+        assert_eq!(back, map);
+    }
+}
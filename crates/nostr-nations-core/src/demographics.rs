@@ -0,0 +1,301 @@
+//! Demographic rankings of the players in a game.
+//!
+//! Demographics compare every player still in the game across a few
+//! categories, in the spirit of an empire's census report. A player sees
+//! their own exact values but only the rank of everyone else, so the
+//! report can't be used to read other empires' numbers; build it through
+//! [`VisibilityFilter::filter_demographics`].
+//!
+//! Ranks start at 1 for the highest value. Players with equal values share
+//! a rank. Every player still in the game counts towards the ranks, but
+//! only players the viewer has met are listed by ID.
+//!
+//! [`VisibilityFilter::filter_demographics`]: crate::visibility::VisibilityFilter::filter_demographics
+
+use crate::game_state::GameState;
+use crate::technology::TechTree;
+use crate::types::PlayerSlot;
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+use ts_rs::TS;
+
+/// A category players are ranked by.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash, Serialize, Deserialize, JsonSchema, TS)]
+pub enum Demographic {
+    /// Citizens across all cities.
+    Population,
+    /// Military units.
+    Soldiers,
+    /// Gold, production and science produced per turn.
+    Gnp,
+    /// Tiles inside the player's borders.
+    LandArea,
+    /// Percentage of the tech tree researched.
+    Literacy,
+}
+
+impl Demographic {
+    /// All categories, in report order.
+    pub const ALL: [Demographic; 5] = [
+        Demographic::Population,
+        Demographic::Soldiers,
+        Demographic::Gnp,
+        Demographic::LandArea,
+        Demographic::Literacy,
+    ];
+
+    /// A player's exact value in this category.
+    pub fn value(self, state: &GameState, player_id: PlayerSlot) -> i64 {
+        match self {
+            Demographic::Population => state
+                .cities
+                .values()
+                .filter(|c| c.owner == player_id)
+                .map(|c| c.population as i64)
+                .sum(),
+            Demographic::Soldiers => state
+                .units
+                .values()
+                .filter(|u| u.owner == player_id && u.is_military())
+                .count() as i64,
+            Demographic::Gnp => {
+                let yields = state.player_yields(player_id);
+                (yields.gold + yields.production + yields.science) as i64
+            }
+            Demographic::LandArea => state
+                .map
+                .tiles
+                .values()
+                .filter(|t| t.owner == Some(player_id))
+                .count() as i64,
+            Demographic::Literacy => {
+                let total = TechTree::new().all_ids().len() as i64;
+                let known = state
+                    .get_player(player_id)
+                    .map_or(0, |p| p.technologies.len() as i64);
+                if total == 0 {
+                    0
+                } else {
+                    known * 100 / total
+                }
+            }
+        }
+    }
+}
+
+/// Another player's rank in a category.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize, Deserialize, JsonSchema, TS)]
+pub struct PlayerRank {
+    pub player_id: PlayerSlot,
+    pub rank: u32,
+}
+
+/// One category of a player's demographics report.
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize, JsonSchema, TS)]
+pub struct DemographicRow {
+    pub category: Demographic,
+    /// The viewing player's exact value.
+    pub value: i64,
+    /// The viewing player's rank.
+    pub rank: u32,
+    /// Ranks of the other players still in the game that the viewing
+    /// player has met, by player ID.
+    pub others: Vec<PlayerRank>,
+}
+
+/// A player's demographics report.
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize, JsonSchema, TS)]
+pub struct Demographics {
+    /// The viewing player.
+    pub player_id: PlayerSlot,
+    /// One row per category, in [`Demographic::ALL`] order.
+    pub rows: Vec<DemographicRow>,
+    /// Number of players ranked.
+    pub player_count: usize,
+}
+
+impl Demographics {
+    /// Build the report for a player.
+    pub fn for_player(state: &GameState, player_id: PlayerSlot) -> Self {
+        let ranked: Vec<PlayerSlot> = state
+            .players
+            .iter()
+            .filter(|p| !p.eliminated || p.id == player_id)
+            .map(|p| p.id)
+            .collect();
+
+        let rows = Demographic::ALL
+            .iter()
+            .map(|&category| {
+                let values: Vec<(PlayerSlot, i64)> = ranked
+                    .iter()
+                    .map(|&id| (id, category.value(state, id)))
+                    .collect();
+                let rank_of =
+                    |value: i64| 1 + values.iter().filter(|(_, v)| *v > value).count() as u32;
+
+                let value = category.value(state, player_id);
+                DemographicRow {
+                    category,
+                    value,
+                    rank: rank_of(value),
+                    others: values
+                        .iter()
+                        .filter(|(id, _)| *id != player_id && state.has_met(player_id, *id))
+                        .map(|&(player_id, value)| PlayerRank {
+                            player_id,
+                            rank: rank_of(value),
+                        })
+                        .collect(),
+                }
+            })
+            .collect();
+
+        Self {
+            player_id,
+            rows,
+            player_count: ranked.len(),
+        }
+    }
+
+    /// The row for a category.
+    pub fn row(&self, category: Demographic) -> Option<&DemographicRow> {
+        self.rows.iter().find(|row| row.category == category)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::city::City;
+    use crate::hex::HexCoord;
+    use crate::player::{Civilization, Player};
+    use crate::settings::GameSettings;
+    use crate::types::{CityId, GameId, Npub, UnitId};
+    use crate::unit::{Unit, UnitType};
+
+    fn create_state() -> GameState {
+        let mut settings = GameSettings::new("Test".to_string());
+        settings.player_count = 3;
+        let mut state = GameState::new(GameId::new("test"), settings, [0; 32]);
+        for id in 0..3 {
+            state
+                .add_player(Player::new(
+                    PlayerSlot(id),
+                    Npub(format!("pk{}", id)),
+                    format!("P{}", id),
+                    Civilization::default(),
+                ))
+                .unwrap();
+        }
+
+        for (id, population) in [(1, 3), (2, 7)] {
+            let mut city = City::new(
+                CityId(id),
+                PlayerSlot(id as u8),
+                format!("C{}", id),
+                HexCoord::new(id as i32 * 5, 0),
+                true,
+            );
+            city.population = population;
+            state.cities.insert(CityId(id), city);
+        }
+        for other in 1..3 {
+            crate::contact::meet(&mut state, PlayerSlot(0), PlayerSlot(other));
+        }
+        for (id, owner) in [(1, 0), (2, 0), (3, 2)] {
+            state.units.insert(
+                UnitId(id),
+                Unit::new(
+                    UnitId(id),
+                    PlayerSlot(owner),
+                    UnitType::Warrior,
+                    HexCoord::new(0, id as i32),
+                ),
+            );
+        }
+        state
+    }
+
+    #[test]
+    fn test_ranks_with_ties() {
+        let state = create_state();
+        let report = Demographics::for_player(&state, PlayerSlot(0));
+        assert_eq!(report.player_count, 3);
+
+        let population = report.row(Demographic::Population).unwrap();
+        assert_eq!((population.value, population.rank), (0, 3));
+        assert_eq!(
+            population.others,
+            [
+                PlayerRank {
+                    player_id: PlayerSlot(1),
+                    rank: 2
+                },
+                PlayerRank {
+                    player_id: PlayerSlot(2),
+                    rank: 1
+                }
+            ]
+        );
+
+        // Player 0 has two warriors, player 2 one and player 1 none
+        let soldiers = report.row(Demographic::Soldiers).unwrap();
+        assert_eq!((soldiers.value, soldiers.rank), (2, 1));
+        assert_eq!(
+            soldiers.others,
+            [
+                PlayerRank {
+                    player_id: PlayerSlot(1),
+                    rank: 3
+                },
+                PlayerRank {
+                    player_id: PlayerSlot(2),
+                    rank: 2
+                }
+            ]
+        );
+
+        // Nobody owns land yet, so everyone shares first place
+        let land = report.row(Demographic::LandArea).unwrap();
+        assert_eq!(land.rank, 1);
+        assert!(land.others.iter().all(|other| other.rank == 1));
+    }
+
+    #[test]
+    fn test_unmet_players_are_hidden() {
+        let mut state = create_state();
+        state.players[0].met_players.remove(&PlayerSlot(2));
+        state.players[2].met_players.remove(&PlayerSlot(0));
+
+        let report = Demographics::for_player(&state, PlayerSlot(0));
+        let population = report.row(Demographic::Population).unwrap();
+        // Player 2 still outranks player 0 but isn't named
+        assert_eq!(population.rank, 3);
+        assert_eq!(
+            population.others,
+            [PlayerRank {
+                player_id: PlayerSlot(1),
+                rank: 2
+            }]
+        );
+    }
+
+    #[test]
+    fn test_eliminated_players_are_not_ranked() {
+        let mut state = create_state();
+        state.players[2].eliminated = true;
+
+        let report = Demographics::for_player(&state, PlayerSlot(0));
+        assert_eq!(report.player_count, 2);
+        let population = report.row(Demographic::Population).unwrap();
+        assert_eq!(population.rank, 2);
+        assert_eq!(
+            population.others,
+            [PlayerRank {
+                player_id: PlayerSlot(1),
+                rank: 1
+            }]
+        );
+    }
+}
@@ -0,0 +1,298 @@
+//! Differences between two game states.
+//!
+//! Frontends keep a view of the units, cities and tiles they draw and update
+//! it from partial state updates. [`StateDiff::between`] compares the state
+//! before and after an action and lists exactly the entries whose visible
+//! fields changed, so a view fed only by diffs can't drift from the actual
+//! state.
+//!
+//! Only the fields a frontend view carries are compared. Removed units and
+//! cities are reported with their last known fields and `destroyed` set.
+//! Tile owner changes are listed separately from improvements and roads,
+//! since border growth and conquest change many tiles at once and are best
+//! sent run-encoded.
+
+use crate::city::City;
+use crate::cow::Shared;
+use crate::game_state::GameState;
+use crate::hex::HexCoord;
+use crate::map::Tile;
+use crate::terrain::{Improvement, Road};
+use crate::types::{CityId, PlayerSlot, UnitId};
+use crate::unit::{Unit, UnitType};
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+
+/// Changed fields of a unit.
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct UnitDiff {
+    pub id: UnitId,
+    pub owner: PlayerSlot,
+    pub unit_type: UnitType,
+    pub position: HexCoord,
+    pub health: u32,
+    pub movement: u32,
+    /// Whether the unit no longer exists.
+    pub destroyed: bool,
+}
+
+impl UnitDiff {
+    fn of(unit: &Unit) -> Self {
+        Self {
+            id: unit.id,
+            owner: unit.owner,
+            unit_type: unit.unit_type,
+            position: unit.position,
+            health: unit.health,
+            movement: unit.movement,
+            destroyed: false,
+        }
+    }
+}
+
+/// Changed fields of a city.
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct CityDiff {
+    pub id: CityId,
+    pub owner: PlayerSlot,
+    pub name: String,
+    pub position: HexCoord,
+    pub population: u32,
+    pub health: u32,
+    /// Whether the city no longer exists.
+    pub destroyed: bool,
+}
+
+impl CityDiff {
+    fn of(city: &City) -> Self {
+        Self {
+            id: city.id,
+            owner: city.owner,
+            name: city.name.clone(),
+            position: city.position,
+            population: city.population,
+            health: city.health,
+            destroyed: false,
+        }
+    }
+}
+
+/// Changed improvement or road of a tile.
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct TileDiff {
+    pub position: HexCoord,
+    pub improvement: Option<Improvement>,
+    pub road: Option<Road>,
+    pub owner: Option<PlayerSlot>,
+}
+
+impl TileDiff {
+    fn of(tile: &Tile) -> Self {
+        Self {
+            position: tile.coord,
+            improvement: tile.improvement,
+            road: tile.road,
+            owner: tile.owner,
+        }
+    }
+}
+
+/// Units, cities and tiles that changed between two states.
+#[derive(Clone, Debug, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct StateDiff {
+    /// Changed, created and destroyed units, by ID.
+    pub units: Vec<UnitDiff>,
+    /// Changed, founded and destroyed cities, by ID.
+    pub cities: Vec<CityDiff>,
+    /// Tiles whose improvement or road changed, by row then column.
+    pub tiles: Vec<TileDiff>,
+    /// Tiles whose owner changed, with the new owner, by row then column.
+    pub territory: Vec<(HexCoord, Option<PlayerSlot>)>,
+}
+
+impl StateDiff {
+    /// Compare the state before and after a change.
+    pub fn between(before: &GameState, after: &GameState) -> Self {
+        let mut units = diff_entries(&before.units, &after.units, UnitDiff::of);
+        for unit in &mut units {
+            unit.destroyed = !after.units.contains_key(&unit.id);
+        }
+        units.sort_by_key(|unit| unit.id);
+
+        let mut cities = diff_entries(&before.cities, &after.cities, CityDiff::of);
+        for city in &mut cities {
+            city.destroyed = !after.cities.contains_key(&city.id);
+        }
+        cities.sort_by_key(|city| city.id);
+
+        // Tiles are never removed, so only changed ones are listed
+        let mut tiles = Vec::new();
+        let mut territory = Vec::new();
+        if !Shared::ptr_eq(&before.map.tiles, &after.map.tiles) {
+            for tile in after.map.tiles.values() {
+                let Some(old) = before.map.tiles.get(&tile.coord) else {
+                    tiles.push(TileDiff::of(tile));
+                    territory.push((tile.coord, tile.owner));
+                    continue;
+                };
+                if old.improvement != tile.improvement || old.road != tile.road {
+                    tiles.push(TileDiff::of(tile));
+                }
+                if old.owner != tile.owner {
+                    territory.push((tile.coord, tile.owner));
+                }
+            }
+        }
+        tiles.sort_by_key(|tile| (tile.position.r, tile.position.q));
+        territory.sort_by_key(|(coord, _)| (coord.r, coord.q));
+
+        Self {
+            units,
+            cities,
+            tiles,
+            territory,
+        }
+    }
+
+    /// Check if nothing changed.
+    pub fn is_empty(&self) -> bool {
+        self.units.is_empty()
+            && self.cities.is_empty()
+            && self.tiles.is_empty()
+            && self.territory.is_empty()
+    }
+}
+
+/// Entries that were added or changed in `after`, plus entries removed
+/// from `before` with their last known fields.
+fn diff_entries<K, V, D>(
+    before: &Shared<HashMap<K, V>>,
+    after: &Shared<HashMap<K, V>>,
+    view: impl Fn(&V) -> D,
+) -> Vec<D>
+where
+    K: std::hash::Hash + Eq,
+    D: PartialEq,
+{
+    if Shared::ptr_eq(before, after) {
+        return Vec::new();
+    }
+
+    let changed = after.iter().filter_map(|(id, value)| {
+        let current = view(value);
+        (before.get(id).map(&view).as_ref() != Some(&current)).then_some(current)
+    });
+    let removed = before
+        .iter()
+        .filter(|(id, _)| !after.contains_key(*id))
+        .map(|(_, value)| view(value));
+    changed.chain(removed).collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::settings::GameSettings;
+    use crate::terrain::Terrain;
+    use crate::types::GameId;
+
+    fn create_state() -> GameState {
+        let mut state = GameState::new(
+            GameId::new("test"),
+            GameSettings::new("Test".to_string()),
+            [0; 32],
+        );
+        state.map = crate::map::Map::filled(10, 10, Terrain::Grassland);
+        for id in 1..=2 {
+            state.units.insert(
+                UnitId(id),
+                Unit::new(
+                    UnitId(id),
+                    PlayerSlot(0),
+                    UnitType::Warrior,
+                    HexCoord::new(id as i32, 0),
+                ),
+            );
+        }
+        state
+    }
+
+    #[test]
+    fn test_unchanged_state_has_empty_diff() {
+        let state = create_state();
+        assert!(StateDiff::between(&state, &state.clone()).is_empty());
+    }
+
+    #[test]
+    fn test_diff_lists_changed_created_and_destroyed() {
+        let before = create_state();
+        let mut after = before.clone();
+
+        after.units.get_mut(&UnitId(1)).unwrap().health = 60;
+        after.units.remove(&UnitId(2));
+        after.units.insert(
+            UnitId(3),
+            Unit::new(
+                UnitId(3),
+                PlayerSlot(1),
+                UnitType::Settler,
+                HexCoord::new(5, 5),
+            ),
+        );
+        let city = City::new(
+            CityId(1),
+            PlayerSlot(1),
+            "Rome".to_string(),
+            HexCoord::new(4, 4),
+            true,
+        );
+        after.cities.insert(CityId(1), city);
+        after.map.get_mut(&HexCoord::new(4, 4)).unwrap().owner = Some(PlayerSlot(1));
+
+        let diff = StateDiff::between(&before, &after);
+        let units: Vec<(UnitId, u32, bool)> = diff
+            .units
+            .iter()
+            .map(|u| (u.id, u.health, u.destroyed))
+            .collect();
+        assert_eq!(
+            units,
+            vec![
+                (UnitId(1), 60, false),
+                (UnitId(2), 100, true),
+                (UnitId(3), 100, false)
+            ]
+        );
+
+        assert_eq!(diff.cities.len(), 1);
+        assert_eq!(diff.cities[0].name, "Rome");
+        assert_eq!(
+            diff.territory,
+            vec![(HexCoord::new(4, 4), Some(PlayerSlot(1)))]
+        );
+        assert!(diff.tiles.is_empty());
+
+        let mut roads = after.clone();
+        roads.map.get_mut(&HexCoord::new(4, 4)).unwrap().road = Some(Road::Road);
+        let diff = StateDiff::between(&after, &roads);
+        assert_eq!(
+            diff.tiles,
+            vec![TileDiff {
+                position: HexCoord::new(4, 4),
+                improvement: None,
+                road: Some(Road::Road),
+                owner: Some(PlayerSlot(1)),
+            }]
+        );
+        assert!(diff.territory.is_empty());
+    }
+
+    #[test]
+    fn test_fields_outside_the_view_are_ignored() {
+        let before = create_state();
+        let mut after = before.clone();
+        after.units.get_mut(&UnitId(1)).unwrap().experience = 10;
+
+        assert!(StateDiff::between(&before, &after).is_empty());
+    }
+}
@@ -0,0 +1,653 @@
+//! Recap of what happened to a player between their sessions.
+//!
+//! In asynchronous games a player may be away for many turns. A
+//! [`TurnDigest`] lists what happened to them since they last ended their
+//! turn: attacks on their units and cities, research that completed, how
+//! their cities grew, and what other players did diplomatically.
+//!
+//! The digest is computed from the event chain by replaying it, so every
+//! peer that has the events can build it. [`TurnDigest::since_last_turn`]
+//! does the whole replay; [`DigestBuilder`] is for callers that already
+//! step an engine through the events.
+//!
+//! The window starts at the player's last `EndTurn` and includes it, since
+//! research and production complete while that turn ends and the player
+//! hasn't seen the results yet.
+
+use crate::events::{GameAction, GameEvent};
+use crate::game_state::{GameState, TreatyType};
+use crate::locale::LocalizedMessage;
+use crate::replay::{ActionEffect, GameEngine, ReplayError};
+use crate::types::{CityId, PlayerSlot, TechId, UnitId};
+use crate::unit::UnitType;
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use ts_rs::TS;
+
+/// Something that happened to a player while they were away.
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize, JsonSchema, TS)]
+pub enum DigestItem {
+    /// Another player attacked one of the player's units.
+    UnitAttacked {
+        attacker: PlayerSlot,
+        unit_id: UnitId,
+        unit_type: UnitType,
+        damage: u32,
+        destroyed: bool,
+    },
+    /// Another player attacked one of the player's cities.
+    CityAttacked {
+        attacker: PlayerSlot,
+        city_id: CityId,
+        name: String,
+        damage: u32,
+    },
+    /// Another player captured one of the player's cities.
+    CityLost {
+        city_id: CityId,
+        name: String,
+        captured_by: PlayerSlot,
+    },
+    /// The player finished researching a technology.
+    TechResearched { tech_id: TechId },
+    /// One of the player's cities changed size.
+    CityGrew {
+        city_id: CityId,
+        name: String,
+        from: u32,
+        to: u32,
+    },
+    /// The player met another civilization.
+    FirstContact { player: PlayerSlot },
+    /// Another player declared war on the player.
+    WarDeclared { by: PlayerSlot },
+    /// Another player offered peace.
+    PeaceProposed { by: PlayerSlot },
+    /// Another player answered the player's peace offer.
+    PeaceAnswered { by: PlayerSlot, accepted: bool },
+    /// Another player proposed a treaty.
+    TreatyProposed {
+        by: PlayerSlot,
+        treaty_type: TreatyType,
+    },
+    /// Another player offered a trade.
+    TradeProposed { by: PlayerSlot },
+    /// Another player answered the player's trade offer.
+    TradeAnswered { by: PlayerSlot, accepted: bool },
+}
+
+impl DigestItem {
+    /// Describe the item, naming players and units as `state` knows them.
+    pub fn message(&self, state: &GameState) -> LocalizedMessage {
+        let player_name = |player_id: &PlayerSlot| {
+            state
+                .get_player(*player_id)
+                .map(|p| p.name.clone())
+                .unwrap_or_else(|| format!("Player {}", player_id))
+        };
+        match self {
+            DigestItem::UnitAttacked {
+                attacker,
+                unit_type,
+                damage,
+                destroyed,
+                ..
+            } => {
+                let key = if *destroyed {
+                    "digest-unit-destroyed"
+                } else {
+                    "digest-unit-attacked"
+                };
+                LocalizedMessage::new(key)
+                    .with_arg("player", player_name(attacker))
+                    .with_arg("unit", format!("{:?}", unit_type))
+                    .with_arg("damage", damage)
+            }
+            DigestItem::CityAttacked {
+                attacker,
+                name,
+                damage,
+                ..
+            } => LocalizedMessage::new("digest-city-attacked")
+                .with_arg("player", player_name(attacker))
+                .with_arg("city", name)
+                .with_arg("damage", damage),
+            DigestItem::CityLost {
+                name, captured_by, ..
+            } => LocalizedMessage::new("digest-city-lost")
+                .with_arg("player", player_name(captured_by))
+                .with_arg("city", name),
+            DigestItem::TechResearched { tech_id } => {
+                LocalizedMessage::new("digest-tech-researched").with_arg("tech", tech_id)
+            }
+            DigestItem::CityGrew { name, from, to, .. } => {
+                let key = if to > from {
+                    "digest-city-grew"
+                } else {
+                    "digest-city-shrank"
+                };
+                LocalizedMessage::new(key)
+                    .with_arg("city", name)
+                    .with_arg("population", to)
+            }
+            DigestItem::FirstContact { player } => LocalizedMessage::new("digest-first-contact")
+                .with_arg("player", player_name(player)),
+            DigestItem::WarDeclared { by } => {
+                LocalizedMessage::new("digest-war-declared").with_arg("player", player_name(by))
+            }
+            DigestItem::PeaceProposed { by } => {
+                LocalizedMessage::new("digest-peace-proposed").with_arg("player", player_name(by))
+            }
+            DigestItem::PeaceAnswered { by, accepted } => {
+                let key = if *accepted {
+                    "digest-peace-accepted"
+                } else {
+                    "digest-peace-rejected"
+                };
+                LocalizedMessage::new(key).with_arg("player", player_name(by))
+            }
+            DigestItem::TreatyProposed { by, treaty_type } => {
+                LocalizedMessage::new("digest-treaty-proposed")
+                    .with_arg("player", player_name(by))
+                    .with_arg("treaty", format!("{:?}", treaty_type))
+            }
+            DigestItem::TradeProposed { by } => {
+                LocalizedMessage::new("digest-trade-proposed").with_arg("player", player_name(by))
+            }
+            DigestItem::TradeAnswered { by, accepted } => {
+                let key = if *accepted {
+                    "digest-trade-accepted"
+                } else {
+                    "digest-trade-rejected"
+                };
+                LocalizedMessage::new(key).with_arg("player", player_name(by))
+            }
+        }
+    }
+}
+
+/// What happened to one player since they last ended their turn.
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize, JsonSchema, TS)]
+pub struct TurnDigest {
+    /// Player the digest is for.
+    pub player_id: PlayerSlot,
+    /// Turn the player last ended.
+    pub since_turn: u32,
+    /// Current turn.
+    pub turn: u32,
+    /// What happened, in event order. City size changes come last.
+    pub items: Vec<DigestItem>,
+}
+
+impl TurnDigest {
+    /// Build the digest for `player_id` by replaying `events`.
+    ///
+    /// The events must be in chain order. If the player has never ended a
+    /// turn, the digest covers the whole game.
+    pub fn since_last_turn(
+        events: &[GameEvent],
+        player_id: PlayerSlot,
+    ) -> Result<Self, ReplayError> {
+        let start = events
+            .iter()
+            .rposition(|e| e.player_id == player_id && matches!(e.action, GameAction::EndTurn))
+            .unwrap_or(1)
+            .min(events.len());
+        let mut engine = GameEngine::from_events(&events[..start])?;
+
+        let mut builder = DigestBuilder::new(player_id, &engine.state);
+        for event in &events[start..] {
+            let before = engine.state.clone();
+            let result = engine.apply_event(event)?;
+            builder.record(&before, event, &result.effects);
+        }
+        Ok(builder.finish(&engine.state))
+    }
+
+    /// Check if nothing happened to the player.
+    pub fn is_empty(&self) -> bool {
+        self.items.is_empty()
+    }
+
+    /// Describe every item, naming players as `state` knows them.
+    pub fn messages(&self, state: &GameState) -> Vec<LocalizedMessage> {
+        self.items.iter().map(|item| item.message(state)).collect()
+    }
+}
+
+/// Builds a [`TurnDigest`] while stepping an engine through events.
+pub struct DigestBuilder {
+    player_id: PlayerSlot,
+    since_turn: u32,
+    /// Population of the player's cities when the window opened.
+    populations: BTreeMap<CityId, u32>,
+    items: Vec<DigestItem>,
+}
+
+impl DigestBuilder {
+    /// Start a digest for `player_id` from `state`.
+    pub fn new(player_id: PlayerSlot, state: &GameState) -> Self {
+        Self {
+            player_id,
+            since_turn: state.turn,
+            populations: state
+                .cities
+                .values()
+                .filter(|c| c.owner == player_id)
+                .map(|c| (c.id, c.population))
+                .collect(),
+            items: Vec::new(),
+        }
+    }
+
+    /// Record one applied event, given the state before it and its effects.
+    pub fn record(&mut self, before: &GameState, event: &GameEvent, effects: &[ActionEffect]) {
+        let player = self.player_id;
+        let by = event.player_id;
+        let item = match &event.action {
+            GameAction::DeclareWar { target_player } if *target_player == player => {
+                Some(DigestItem::WarDeclared { by })
+            }
+            GameAction::ProposePeace { target_player } if *target_player == player => {
+                Some(DigestItem::PeaceProposed { by })
+            }
+            GameAction::AcceptPeace { from_player } if *from_player == player => {
+                Some(DigestItem::PeaceAnswered { by, accepted: true })
+            }
+            GameAction::RejectPeace { from_player } if *from_player == player => {
+                Some(DigestItem::PeaceAnswered {
+                    by,
+                    accepted: false,
+                })
+            }
+            GameAction::ProposeTreaty {
+                target_player,
+                treaty_type,
+            } if *target_player == player => Some(DigestItem::TreatyProposed {
+                by,
+                treaty_type: *treaty_type,
+            }),
+            GameAction::ProposeTrade { to_player, .. } if *to_player == player => {
+                Some(DigestItem::TradeProposed { by })
+            }
+            GameAction::RespondTrade {
+                from_player,
+                accept,
+                ..
+            } if *from_player == player => Some(DigestItem::TradeAnswered {
+                by,
+                accepted: *accept,
+            }),
+            _ => None,
+        };
+        self.items.extend(item);
+
+        for effect in effects {
+            self.record_effect(before, by, effect);
+        }
+    }
+
+    fn record_effect(&mut self, before: &GameState, by: PlayerSlot, effect: &ActionEffect) {
+        let player = self.player_id;
+        let own_unit = |unit_id: &UnitId| {
+            before
+                .units
+                .get(unit_id)
+                .filter(|u| u.owner == player)
+                .map(|u| u.unit_type)
+        };
+        let own_city = |city_id: &CityId| {
+            before
+                .cities
+                .get(city_id)
+                .filter(|c| c.owner == player)
+                .map(|c| c.name.clone())
+        };
+        match effect {
+            ActionEffect::UnitDamaged {
+                unit_id, damage, ..
+            } if by != player => {
+                if let Some(unit_type) = own_unit(unit_id) {
+                    self.items.push(DigestItem::UnitAttacked {
+                        attacker: by,
+                        unit_id: *unit_id,
+                        unit_type,
+                        damage: *damage,
+                        destroyed: false,
+                    });
+                }
+            }
+            ActionEffect::UnitDestroyed { unit_id } if by != player => {
+                let Some(unit_type) = own_unit(unit_id) else {
+                    return;
+                };
+                // Fold the kill into the attack that caused it
+                match self.items.last_mut() {
+                    Some(DigestItem::UnitAttacked {
+                        unit_id: attacked,
+                        destroyed,
+                        ..
+                    }) if attacked == unit_id => *destroyed = true,
+                    _ => self.items.push(DigestItem::UnitAttacked {
+                        attacker: by,
+                        unit_id: *unit_id,
+                        unit_type,
+                        damage: 0,
+                        destroyed: true,
+                    }),
+                }
+            }
+            ActionEffect::CityDamaged { city_id, damage } if by != player => {
+                if let Some(name) = own_city(city_id) {
+                    self.items.push(DigestItem::CityAttacked {
+                        attacker: by,
+                        city_id: *city_id,
+                        name,
+                        damage: *damage,
+                    });
+                }
+            }
+            ActionEffect::CityCaptured {
+                city_id,
+                player_id,
+                previous_owner,
+                ..
+            } if *previous_owner == player => {
+                let name = before
+                    .cities
+                    .get(city_id)
+                    .map(|c| c.name.clone())
+                    .unwrap_or_default();
+                self.populations.remove(city_id);
+                self.items.push(DigestItem::CityLost {
+                    city_id: *city_id,
+                    name,
+                    captured_by: *player_id,
+                });
+            }
+            ActionEffect::TechResearched { player_id, tech_id } if *player_id == player => {
+                self.items.push(DigestItem::TechResearched {
+                    tech_id: tech_id.clone(),
+                });
+            }
+            ActionEffect::FirstContact {
+                player_id,
+                met_player,
+            } if *player_id == player => {
+                self.items.push(DigestItem::FirstContact {
+                    player: *met_player,
+                });
+            }
+            _ => {}
+        }
+    }
+
+    /// Finish the digest at `state`, adding how the player's cities changed
+    /// size.
+    pub fn finish(mut self, state: &GameState) -> TurnDigest {
+        for (city_id, from) in &self.populations {
+            let Some(city) = state
+                .cities
+                .get(city_id)
+                .filter(|c| c.owner == self.player_id)
+            else {
+                continue;
+            };
+            if city.population != *from {
+                self.items.push(DigestItem::CityGrew {
+                    city_id: *city_id,
+                    name: city.name.clone(),
+                    from: *from,
+                    to: city.population,
+                });
+            }
+        }
+        TurnDigest {
+            player_id: self.player_id,
+            since_turn: self.since_turn,
+            turn: state.turn,
+            items: self.items,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::city::City;
+    use crate::contact;
+    use crate::settings::GameSettings;
+    use crate::types::MapSize;
+
+    fn duel_events() -> (Vec<GameEvent>, GameEngine) {
+        let mut settings = GameSettings::new("Test".to_string());
+        settings.map_size = MapSize::Duel;
+        let seed = [42u8; 32];
+        let mut engine = GameEngine::new(settings.clone(), seed);
+        let mut events = vec![GameEvent::new(
+            engine.state.id.clone(),
+            PlayerSlot(0),
+            None,
+            0,
+            0,
+            GameAction::CreateGame {
+                settings_json: serde_json::to_string(&settings).unwrap(),
+                seed,
+            },
+        )];
+        let mut push = |engine: &mut GameEngine, player: u8, action: GameAction| {
+            engine.apply_action(PlayerSlot(player), &action).unwrap();
+            let sequence = events.len() as u32;
+            events.push(GameEvent::new(
+                engine.state.id.clone(),
+                PlayerSlot(player),
+                None,
+                engine.state.turn,
+                sequence,
+                action,
+            ));
+        };
+        for (player, civ) in [(0, "rome"), (1, "egypt")] {
+            push(
+                &mut engine,
+                player,
+                GameAction::JoinGame {
+                    player_name: format!("P{}", player),
+                    civilization_id: civ.to_string(),
+                },
+            );
+        }
+        push(&mut engine, 0, GameAction::StartGame);
+        push(&mut engine, 0, GameAction::EndTurn);
+        push(&mut engine, 1, GameAction::EndTurn);
+        (events, engine)
+    }
+
+    #[test]
+    fn test_quiet_turn_has_empty_digest() {
+        let (events, engine) = duel_events();
+        let digest = TurnDigest::since_last_turn(&events, PlayerSlot(0)).unwrap();
+
+        assert_eq!(digest.player_id, PlayerSlot(0));
+        assert_eq!(digest.since_turn, 1);
+        assert_eq!(digest.turn, engine.state.turn);
+        assert!(digest.is_empty());
+    }
+
+    #[test]
+    fn test_player_without_turns_covers_whole_game() {
+        let (events, _) = duel_events();
+        let digest = TurnDigest::since_last_turn(&events[..3], PlayerSlot(1)).unwrap();
+        assert_eq!(digest.since_turn, 0);
+    }
+
+    #[test]
+    fn test_records_attacks_and_diplomacy() {
+        let (_, mut engine) = duel_events();
+        contact::meet(&mut engine.state, PlayerSlot(0), PlayerSlot(1));
+        let mut builder = DigestBuilder::new(PlayerSlot(0), &engine.state);
+
+        let war = GameAction::DeclareWar {
+            target_player: PlayerSlot(0),
+        };
+        let event = GameEvent::new(engine.state.id.clone(), PlayerSlot(1), None, 2, 0, war);
+        builder.record(&engine.state, &event, &[]);
+
+        let (unit_id, unit_type) = engine
+            .state
+            .units
+            .values()
+            .find(|u| u.owner == PlayerSlot(0))
+            .map(|u| (u.id, u.unit_type))
+            .unwrap();
+        let attack = GameAction::AttackUnit {
+            attacker_id: UnitId(999),
+            defender_id: unit_id,
+            random: 0.5,
+        };
+        let event = GameEvent::new(engine.state.id.clone(), PlayerSlot(1), None, 2, 1, attack);
+        builder.record(
+            &engine.state,
+            &event,
+            &[
+                ActionEffect::UnitDamaged {
+                    unit_id,
+                    damage: 100,
+                    new_health: 0,
+                },
+                ActionEffect::UnitDestroyed { unit_id },
+                ActionEffect::UnitDamaged {
+                    unit_id: UnitId(999),
+                    damage: 10,
+                    new_health: 90,
+                },
+            ],
+        );
+
+        let digest = builder.finish(&engine.state);
+        assert_eq!(
+            digest.items,
+            vec![
+                DigestItem::WarDeclared { by: PlayerSlot(1) },
+                DigestItem::UnitAttacked {
+                    attacker: PlayerSlot(1),
+                    unit_id,
+                    unit_type,
+                    damage: 100,
+                    destroyed: true,
+                },
+            ]
+        );
+        let messages = digest.messages(&engine.state);
+        assert_eq!(messages[0].key, "digest-war-declared");
+        assert_eq!(messages[0].args["player"], "P1");
+        assert_eq!(messages[1].key, "digest-unit-destroyed");
+    }
+
+    #[test]
+    fn test_own_actions_and_other_players_are_ignored() {
+        let (_, engine) = duel_events();
+        let mut builder = DigestBuilder::new(PlayerSlot(0), &engine.state);
+
+        let war = GameAction::DeclareWar {
+            target_player: PlayerSlot(1),
+        };
+        let event = GameEvent::new(engine.state.id.clone(), PlayerSlot(0), None, 2, 0, war);
+        let enemy = engine
+            .state
+            .units
+            .values()
+            .find(|u| u.owner == PlayerSlot(1))
+            .unwrap()
+            .id;
+        builder.record(
+            &engine.state,
+            &event,
+            &[
+                ActionEffect::UnitDestroyed { unit_id: enemy },
+                ActionEffect::TechResearched {
+                    player_id: PlayerSlot(1),
+                    tech_id: "pottery".to_string(),
+                },
+            ],
+        );
+
+        assert!(builder.finish(&engine.state).is_empty());
+    }
+
+    #[test]
+    fn test_city_growth_research_and_capture() {
+        let (_, mut engine) = duel_events();
+        for (id, name) in [(1, "Rome"), (2, "Antium")] {
+            let city = City::new(
+                CityId(id),
+                PlayerSlot(0),
+                name.to_string(),
+                Default::default(),
+                id == 1,
+            );
+            engine.state.cities.insert(CityId(id), city);
+        }
+        let mut builder = DigestBuilder::new(PlayerSlot(0), &engine.state);
+
+        let event = GameEvent::new(
+            engine.state.id.clone(),
+            PlayerSlot(0),
+            None,
+            2,
+            0,
+            GameAction::EndTurn,
+        );
+        builder.record(
+            &engine.state,
+            &event,
+            &[ActionEffect::TechResearched {
+                player_id: PlayerSlot(0),
+                tech_id: "pottery".to_string(),
+            }],
+        );
+        let event = GameEvent::new(
+            engine.state.id.clone(),
+            PlayerSlot(1),
+            None,
+            2,
+            1,
+            GameAction::EndTurn,
+        );
+        builder.record(
+            &engine.state,
+            &event,
+            &[ActionEffect::CityCaptured {
+                city_id: CityId(2),
+                player_id: PlayerSlot(1),
+                previous_owner: PlayerSlot(0),
+                population_lost: 0,
+                buildings_destroyed: Vec::new(),
+            }],
+        );
+        engine.state.cities.get_mut(&CityId(1)).unwrap().population += 2;
+        engine.state.cities.get_mut(&CityId(2)).unwrap().owner = PlayerSlot(1);
+
+        let digest = builder.finish(&engine.state);
+        assert_eq!(
+            digest.items,
+            vec![
+                DigestItem::TechResearched {
+                    tech_id: "pottery".to_string()
+                },
+                DigestItem::CityLost {
+                    city_id: CityId(2),
+                    name: "Antium".to_string(),
+                    captured_by: PlayerSlot(1),
+                },
+                DigestItem::CityGrew {
+                    city_id: CityId(1),
+                    name: "Rome".to_string(),
+                    from: 1,
+                    to: 3,
+                },
+            ]
+        );
+    }
+}
@@ -0,0 +1,186 @@
+//! Era progression and what each era unlocks.
+//!
+//! A player's era is the latest era of any technology they know, and never
+//! earlier than the game's starting era. Players only move forward: the
+//! first tech of a new era moves them into it, which is reported once as
+//! an era-entry event.
+//!
+//! Eras gate production, since units, buildings, wonders and projects each
+//! belong to an era (see [`ProductionItem::era`]), and they scale how much
+//! other players resent aggression. Early wars are expected; the same war
+//! declared in a later era costs more goodwill.
+//!
+//! [`ProductionItem::era`]: crate::city::ProductionItem::era
+
+use crate::city::ProductionItem;
+use crate::game_state::GameState;
+use crate::player::Player;
+use crate::technology::TechTree;
+use crate::types::{Era, PlayerSlot};
+
+/// Relationship score lost with every other player when capturing a city,
+/// before era scaling.
+pub const CITY_CAPTURE_WARMONGER_PENALTY: i32 = -10;
+
+/// The era a player's technologies put them in.
+pub fn era_from_techs(player: &Player, tree: &TechTree, starting_era: Era) -> Era {
+    player
+        .technologies
+        .iter()
+        .filter_map(|tech_id| tree.get(tech_id))
+        .map(|tech| tech.era)
+        .fold(starting_era, |latest, era| {
+            if era.index() > latest.index() {
+                era
+            } else {
+                latest
+            }
+        })
+}
+
+/// Move a player into the era their technologies put them in.
+///
+/// Returns the new era if the player entered one.
+pub fn update_era(state: &mut GameState, player_id: PlayerSlot) -> Option<Era> {
+    let tree = TechTree::new();
+    let starting_era = state.settings.starting_era;
+    let player = state.get_player_mut(player_id)?;
+    let era = era_from_techs(player, &tree, starting_era);
+    if era.index() <= player.era.index() {
+        return None;
+    }
+    player.era = era;
+    Some(era)
+}
+
+/// Whether a player's era allows producing an item.
+pub fn can_produce(player: &Player, item: &ProductionItem) -> bool {
+    item.era().index() <= player.era.index()
+}
+
+/// Percentage applied to warmonger and war declaration penalties for an
+/// aggressor in this era: 50% in the Ancient era, rising by 25% per era.
+pub const fn warmonger_percent(era: Era) -> i32 {
+    50 + 25 * era.index() as i32
+}
+
+/// Scale a relationship penalty by the aggressor's era.
+pub fn scale_penalty(penalty: i32, era: Era) -> i32 {
+    penalty * warmonger_percent(era) / 100
+}
+
+/// Sour every other player's relationship with a player who captured a
+/// city, scaled by the captor's era.
+pub fn apply_warmonger_penalty(state: &mut GameState, aggressor: PlayerSlot) {
+    let Some(era) = state.get_player(aggressor).map(|p| p.era) else {
+        return;
+    };
+    let penalty = scale_penalty(CITY_CAPTURE_WARMONGER_PENALTY, era);
+    let others: Vec<PlayerSlot> = state
+        .players
+        .iter()
+        .filter(|p| p.id != aggressor && !p.eliminated)
+        .map(|p| p.id)
+        .collect();
+    for other in others {
+        if let Some(rel) = state.diplomacy.get_mut(aggressor, other) {
+            rel.relationship_score = (rel.relationship_score + penalty).clamp(-100, 100);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::city::BuildingType;
+    use crate::player::Civilization;
+    use crate::settings::GameSettings;
+    use crate::types::{GameId, Npub};
+    use crate::unit::UnitType;
+
+    fn create_state() -> GameState {
+        let mut state = GameState::new(
+            GameId::new("test"),
+            GameSettings::new("Test".to_string()),
+            [0; 32],
+        );
+        for id in 0..2 {
+            state
+                .add_player(Player::new(
+                    PlayerSlot(id),
+                    Npub(format!("pk{}", id)),
+                    format!("P{}", id),
+                    Civilization::default(),
+                ))
+                .unwrap();
+        }
+        state.start().unwrap();
+        state
+    }
+
+    #[test]
+    fn test_update_era() {
+        let mut state = create_state();
+        assert_eq!(update_era(&mut state, PlayerSlot(0)), None);
+
+        state.players[0].add_tech("agriculture".to_string());
+        state.players[0].add_tech("mathematics".to_string());
+        assert_eq!(update_era(&mut state, PlayerSlot(0)), Some(Era::Classical));
+        assert_eq!(state.players[0].era, Era::Classical);
+        // Entering an era is reported once
+        assert_eq!(update_era(&mut state, PlayerSlot(0)), None);
+
+        state.players[0].add_tech("spaceflight".to_string());
+        assert_eq!(
+            update_era(&mut state, PlayerSlot(0)),
+            Some(Era::Information)
+        );
+    }
+
+    #[test]
+    fn test_starting_era() {
+        let mut state = create_state();
+        state.settings.starting_era = Era::Medieval;
+        state.players[0].add_tech("agriculture".to_string());
+        assert_eq!(update_era(&mut state, PlayerSlot(0)), Some(Era::Medieval));
+    }
+
+    #[test]
+    fn test_can_produce() {
+        let mut state = create_state();
+        let player = &state.players[0];
+        assert!(can_produce(
+            player,
+            &ProductionItem::Building(BuildingType::Monument)
+        ));
+        assert!(!can_produce(
+            player,
+            &ProductionItem::Unit(UnitType::Knight)
+        ));
+
+        state.players[0].era = Era::Medieval;
+        assert!(can_produce(
+            &state.players[0],
+            &ProductionItem::Unit(UnitType::Knight)
+        ));
+    }
+
+    #[test]
+    fn test_warmonger_penalty_scales_by_era() {
+        assert_eq!(scale_penalty(-40, Era::Ancient), -20);
+        assert_eq!(scale_penalty(-40, Era::Medieval), -40);
+        assert_eq!(scale_penalty(-40, Era::Information), -90);
+
+        let mut state = create_state();
+        state.players[0].era = Era::Renaissance;
+        apply_warmonger_penalty(&mut state, PlayerSlot(0));
+        assert_eq!(
+            state
+                .diplomacy
+                .get(PlayerSlot(0), PlayerSlot(1))
+                .unwrap()
+                .relationship_score,
+            scale_penalty(CITY_CAPTURE_WARMONGER_PENALTY, Era::Renaissance)
+        );
+    }
+}
@@ -0,0 +1,367 @@
+//! Registry of Nostr event kinds used by the game.
+//!
+//! Every event the game publishes uses a stable kind number from the
+//! 30100-30199 block. Kinds are grouped into categories (lobby, action,
+//! randomness, snapshot, chat, result), and 30150-30199 is reserved for
+//! extension kinds that clients may register for their own events.
+//!
+//! Pings and presence updates use kinds from the ephemeral 20100-20199
+//! block, which relays forward without storing.
+//!
+//! Only some kinds change game state. Events of any other kind, including
+//! kinds this build doesn't know, are stored and relayed unchanged but
+//! never executed, so newer clients can add events without breaking older
+//! ones.
+
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use std::ops::RangeInclusive;
+
+/// Game created (settings and seed).
+pub const GAME_CREATE: u32 = 30100;
+/// Player joined a game.
+pub const PLAYER_JOIN: u32 = 30101;
+/// Game started.
+pub const GAME_START: u32 = 30102;
+/// In-game action.
+pub const GAME_ACTION: u32 = 30103;
+/// Player ended their turn.
+pub const TURN_END: u32 = 30104;
+/// Game ended.
+pub const GAME_END: u32 = 30105;
+/// Request for verifiable randomness.
+pub const RANDOM_REQUEST: u32 = 30106;
+/// Verifiable randomness response.
+pub const RANDOM_RESPONSE: u32 = 30107;
+/// State snapshot for chain compaction.
+pub const STATE_SNAPSHOT: u32 = 30108;
+/// Tournament bracket (participants, rounds, match state).
+pub const TOURNAMENT_BRACKET: u32 = 30110;
+/// Signed match result.
+pub const MATCH_RESULT: u32 = 30111;
+/// Victory proof for a finished game.
+pub const VICTORY_PROOF: u32 = 30112;
+/// Signed report that a player abandoned a game.
+pub const ABANDON_REPORT: u32 = 30113;
+/// Chat message between players.
+pub const CHAT_MESSAGE: u32 = 30120;
+
+/// Map ping or other short-lived signal to other players (ephemeral).
+pub const PING: u32 = 20100;
+/// Player presence update (ephemeral).
+pub const PRESENCE: u32 = 20101;
+
+/// All kinds reserved for the game.
+pub const GAME_KINDS: RangeInclusive<u32> = 30100..=30199;
+
+/// Ephemeral kinds reserved for the game.
+pub const EPHEMERAL_GAME_KINDS: RangeInclusive<u32> = 20100..=20199;
+
+/// Kinds clients may register for extension events.
+pub const EXTENSION_KINDS: RangeInclusive<u32> = 30150..=30199;
+
+/// Broad category of an event kind.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub enum KindCategory {
+    /// Creating, joining and starting games.
+    Lobby,
+    /// Moves made during play.
+    Action,
+    /// Verifiable randomness requests and responses.
+    Randomness,
+    /// State snapshots.
+    Snapshot,
+    /// Player chat.
+    Chat,
+    /// Pings and presence updates.
+    Presence,
+    /// Game, match and tournament outcomes.
+    Result,
+    /// Registered extension kind.
+    Extension,
+}
+
+/// How relays treat a kind, per NIP-01.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub enum NipClass {
+    /// Stored as is.
+    Regular,
+    /// Only the latest event per pubkey and kind is kept.
+    Replaceable,
+    /// Not stored.
+    Ephemeral,
+    /// Only the latest event per pubkey, kind and `d` tag is kept.
+    Addressable,
+}
+
+/// Get how relays treat a kind.
+pub fn nip_class(kind: u32) -> NipClass {
+    match kind {
+        10000..=19999 | 0 | 3 => NipClass::Replaceable,
+        20000..=29999 => NipClass::Ephemeral,
+        30000..=39999 => NipClass::Addressable,
+        _ => NipClass::Regular,
+    }
+}
+
+/// Description of a registered kind.
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct KindSpec {
+    /// Kind number.
+    pub kind: u32,
+    /// Short name, e.g. `"turn_end"`.
+    pub name: String,
+    /// Category of the kind.
+    pub category: KindCategory,
+    /// Whether events of this kind are applied to the game state.
+    pub executable: bool,
+}
+
+/// Built-in kinds: (kind, name, category, executable).
+const BUILTIN_KINDS: &[(u32, &str, KindCategory, bool)] = &[
+    (GAME_CREATE, "game_create", KindCategory::Lobby, true),
+    (PLAYER_JOIN, "player_join", KindCategory::Lobby, true),
+    (GAME_START, "game_start", KindCategory::Lobby, true),
+    (GAME_ACTION, "game_action", KindCategory::Action, true),
+    (TURN_END, "turn_end", KindCategory::Action, true),
+    (GAME_END, "game_end", KindCategory::Result, true),
+    (
+        RANDOM_REQUEST,
+        "random_request",
+        KindCategory::Randomness,
+        true,
+    ),
+    (
+        RANDOM_RESPONSE,
+        "random_response",
+        KindCategory::Randomness,
+        true,
+    ),
+    (
+        STATE_SNAPSHOT,
+        "state_snapshot",
+        KindCategory::Snapshot,
+        true,
+    ),
+    (
+        TOURNAMENT_BRACKET,
+        "tournament_bracket",
+        KindCategory::Result,
+        false,
+    ),
+    (MATCH_RESULT, "match_result", KindCategory::Result, false),
+    (VICTORY_PROOF, "victory_proof", KindCategory::Result, false),
+    (
+        ABANDON_REPORT,
+        "abandon_report",
+        KindCategory::Result,
+        false,
+    ),
+    (CHAT_MESSAGE, "chat_message", KindCategory::Chat, false),
+    (PING, "ping", KindCategory::Presence, false),
+    (PRESENCE, "presence", KindCategory::Presence, false),
+];
+
+/// Get the category of a built-in kind.
+pub fn category(kind: u32) -> Option<KindCategory> {
+    BUILTIN_KINDS
+        .iter()
+        .find(|(k, ..)| *k == kind)
+        .map(|(_, _, category, _)| *category)
+}
+
+/// Check whether events of a kind are applied to the game state.
+///
+/// Unknown and extension kinds are never executed.
+pub fn is_executable(kind: u32) -> bool {
+    BUILTIN_KINDS
+        .iter()
+        .any(|(k, _, _, executable)| *k == kind && *executable)
+}
+
+/// Known kinds, including any registered extensions.
+#[derive(Clone, Debug)]
+pub struct KindRegistry {
+    kinds: BTreeMap<u32, KindSpec>,
+}
+
+impl Default for KindRegistry {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl KindRegistry {
+    /// Create a registry holding the built-in kinds.
+    pub fn new() -> Self {
+        let kinds = BUILTIN_KINDS
+            .iter()
+            .map(|&(kind, name, category, executable)| {
+                (
+                    kind,
+                    KindSpec {
+                        kind,
+                        name: name.to_string(),
+                        category,
+                        executable,
+                    },
+                )
+            })
+            .collect();
+        Self { kinds }
+    }
+
+    /// Register an extension kind.
+    ///
+    /// The kind must be in [`EXTENSION_KINDS`] and not already taken.
+    /// Extension events are never executed by the game engine.
+    pub fn register(&mut self, kind: u32, name: impl Into<String>) -> Result<(), KindError> {
+        if !EXTENSION_KINDS.contains(&kind) {
+            return Err(KindError::OutOfRange(kind));
+        }
+        if let Some(existing) = self.kinds.get(&kind) {
+            return Err(KindError::AlreadyRegistered {
+                kind,
+                name: existing.name.clone(),
+            });
+        }
+        self.kinds.insert(
+            kind,
+            KindSpec {
+                kind,
+                name: name.into(),
+                category: KindCategory::Extension,
+                executable: false,
+            },
+        );
+        Ok(())
+    }
+
+    /// Look up a kind.
+    pub fn get(&self, kind: u32) -> Option<&KindSpec> {
+        self.kinds.get(&kind)
+    }
+
+    /// Check whether a kind is known.
+    pub fn is_known(&self, kind: u32) -> bool {
+        self.kinds.contains_key(&kind)
+    }
+
+    /// Check whether events of a kind are applied to the game state.
+    pub fn is_executable(&self, kind: u32) -> bool {
+        self.kinds.get(&kind).is_some_and(|spec| spec.executable)
+    }
+
+    /// Get all kinds in a category, in kind order.
+    pub fn kinds_in(&self, category: KindCategory) -> Vec<u32> {
+        self.kinds
+            .values()
+            .filter(|spec| spec.category == category)
+            .map(|spec| spec.kind)
+            .collect()
+    }
+
+    /// Iterate over every known kind, in kind order.
+    pub fn iter(&self) -> impl Iterator<Item = &KindSpec> {
+        self.kinds.values()
+    }
+}
+
+/// Why a kind could not be registered.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum KindError {
+    /// The kind is outside the extension range.
+    OutOfRange(u32),
+    /// The kind is already registered.
+    AlreadyRegistered { kind: u32, name: String },
+}
+
+impl std::fmt::Display for KindError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            KindError::OutOfRange(kind) => write!(
+                f,
+                "Kind {} is outside the extension range {}-{}",
+                kind,
+                EXTENSION_KINDS.start(),
+                EXTENSION_KINDS.end()
+            ),
+            KindError::AlreadyRegistered { kind, name } => {
+                write!(f, "Kind {} is already registered as {}", kind, name)
+            }
+        }
+    }
+}
+
+impl std::error::Error for KindError {}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // ==================== Built-in Kind Tests ====================
+
+    #[test]
+    fn test_builtin_kinds_are_unique_and_reserved() {
+        let registry = KindRegistry::new();
+        assert_eq!(registry.iter().count(), BUILTIN_KINDS.len());
+        for spec in registry.iter() {
+            assert!(GAME_KINDS.contains(&spec.kind) || EPHEMERAL_GAME_KINDS.contains(&spec.kind));
+            assert!(!EXTENSION_KINDS.contains(&spec.kind));
+        }
+        assert_eq!(nip_class(PING), NipClass::Ephemeral);
+        assert_eq!(nip_class(PRESENCE), NipClass::Ephemeral);
+    }
+
+    #[test]
+    fn test_categories_and_execution() {
+        assert_eq!(category(TURN_END), Some(KindCategory::Action));
+        assert_eq!(category(CHAT_MESSAGE), Some(KindCategory::Chat));
+        assert_eq!(category(30199), None);
+
+        assert!(is_executable(GAME_ACTION));
+        assert!(is_executable(STATE_SNAPSHOT));
+        assert!(!is_executable(CHAT_MESSAGE));
+        assert!(!is_executable(MATCH_RESULT));
+        assert!(!is_executable(12345));
+    }
+
+    #[test]
+    fn test_nip_class() {
+        assert_eq!(nip_class(1), NipClass::Regular);
+        assert_eq!(nip_class(10002), NipClass::Replaceable);
+        assert_eq!(nip_class(20001), NipClass::Ephemeral);
+        assert_eq!(nip_class(GAME_ACTION), NipClass::Addressable);
+    }
+
+    // ==================== Registry Tests ====================
+
+    #[test]
+    fn test_register_extension_kind() {
+        let mut registry = KindRegistry::new();
+        registry.register(30150, "emote").unwrap();
+
+        let spec = registry.get(30150).unwrap();
+        assert_eq!(spec.category, KindCategory::Extension);
+        assert!(!registry.is_executable(30150));
+        assert_eq!(registry.kinds_in(KindCategory::Extension), vec![30150]);
+    }
+
+    #[test]
+    fn test_register_rejects_reserved_and_duplicate_kinds() {
+        let mut registry = KindRegistry::new();
+        assert_eq!(
+            registry.register(TURN_END, "mine"),
+            Err(KindError::OutOfRange(TURN_END))
+        );
+
+        registry.register(30160, "emote").unwrap();
+        assert_eq!(
+            registry.register(30160, "other"),
+            Err(KindError::AlreadyRegistered {
+                kind: 30160,
+                name: "emote".to_string()
+            })
+        );
+    }
+}
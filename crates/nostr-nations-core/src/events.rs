@@ -5,44 +5,37 @@
 //! - Chain validation (each event references the previous)
 //! - Distributed game state
 //!
-//! Event kinds used:
-//! - 30100: Game creation
-//! - 30101: Player join
-//! - 30102: Game start
-//! - 30103: Game action (move, attack, build, etc.)
-//! - 30104: Turn end
-//! - 30105: Game end
-//! - 30106: Randomness request
-//! - 30107: Randomness response (from Cashu)
+//! Event kinds are defined in [`crate::event_kinds`]. Actions this build
+//! doesn't recognize are kept as [`GameAction::Unrecognized`] so they can
+//! be stored and relayed unchanged.
 
 use crate::cashu::RandomnessProof;
 use crate::city::ProductionItem;
+use crate::game_state::TreatyType;
 use crate::hex::HexCoord;
+use crate::siege::CaptureChoice;
+use crate::snapshot::StateSnapshot;
 use crate::terrain::Improvement;
-use crate::types::{CityId, GameId, PlayerId, TechId, UnitId};
+use crate::trading::TradeItems;
+use crate::types::{CityId, GameId, Npub, PlayerSlot, TechId, UnitId};
+use crate::unit::Promotion;
+use schemars::JsonSchema;
 use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+use ts_rs::TS;
 
 /// Nostr event kind constants for game events.
-pub mod kinds {
-    pub const GAME_CREATE: u32 = 30100;
-    pub const PLAYER_JOIN: u32 = 30101;
-    pub const GAME_START: u32 = 30102;
-    pub const GAME_ACTION: u32 = 30103;
-    pub const TURN_END: u32 = 30104;
-    pub const GAME_END: u32 = 30105;
-    pub const RANDOM_REQUEST: u32 = 30106;
-    pub const RANDOM_RESPONSE: u32 = 30107;
-}
+pub use crate::event_kinds as kinds;
 
 /// A game event that will be serialized into a Nostr event.
-#[derive(Clone, Debug, Serialize, Deserialize)]
+#[derive(Clone, Debug, Serialize, Deserialize, JsonSchema, TS)]
 pub struct GameEvent {
     /// Event ID (Nostr event ID / hash).
     pub id: String,
     /// Game this event belongs to.
     pub game_id: GameId,
     /// Player who created this event.
-    pub player_id: PlayerId,
+    pub player_id: PlayerSlot,
     /// Previous event ID in the chain.
     pub prev_event_id: Option<String>,
     /// Turn number when this event occurred.
@@ -50,18 +43,24 @@ pub struct GameEvent {
     /// Sequence number within the turn.
     pub sequence: u32,
     /// The actual action.
+    #[serde(with = "action_serde")]
+    #[schemars(with = "GameAction")]
+    #[ts(as = "GameAction")]
     pub action: GameAction,
     /// Unix timestamp.
     pub timestamp: u64,
     /// Cashu randomness proof if randomness was used.
     pub randomness_proof: Option<RandomnessProof>,
+    /// NIP-40 expiration timestamp, after which relays drop the event.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub expiration: Option<u64>,
 }
 
 impl GameEvent {
     /// Create a new game event.
     pub fn new(
         game_id: GameId,
-        player_id: PlayerId,
+        player_id: PlayerSlot,
         prev_event_id: Option<String>,
         turn: u32,
         sequence: u32,
@@ -77,13 +76,14 @@ impl GameEvent {
             action,
             timestamp: 0, // Will be set when signed
             randomness_proof: None,
+            expiration: None,
         }
     }
 
     /// Create a new game event with a randomness proof.
     pub fn with_randomness(
         game_id: GameId,
-        player_id: PlayerId,
+        player_id: PlayerSlot,
         prev_event_id: Option<String>,
         turn: u32,
         sequence: u32,
@@ -100,6 +100,7 @@ impl GameEvent {
             action,
             timestamp: 0,
             randomness_proof: Some(proof),
+            expiration: None,
         }
     }
 
@@ -113,19 +114,48 @@ impl GameEvent {
             GameAction::EndGame { .. } => kinds::GAME_END,
             GameAction::RequestRandom { .. } => kinds::RANDOM_REQUEST,
             GameAction::ProvideRandom { .. } => kinds::RANDOM_RESPONSE,
+            GameAction::Snapshot { .. } => kinds::STATE_SNAPSHOT,
+            GameAction::Extension { kind, .. } => *kind,
             _ => kinds::GAME_ACTION,
         }
     }
 
+    /// Set a NIP-40 expiration timestamp (Unix seconds).
+    pub fn with_expiration(mut self, expiration: u64) -> Self {
+        self.expiration = Some(expiration);
+        self
+    }
+
+    /// Check whether the event has expired at `now` (Unix seconds).
+    pub fn is_expired(&self, now: u64) -> bool {
+        self.expiration.is_some_and(|expiration| expiration <= now)
+    }
+
+    /// Check whether relays should pass this event on without storing it.
+    pub fn is_ephemeral(&self) -> bool {
+        kinds::nip_class(self.kind()) == kinds::NipClass::Ephemeral
+    }
+
+    /// Check whether this event should be applied to the game state.
+    ///
+    /// Extension events and actions this build doesn't recognize are kept
+    /// in the chain but not executed.
+    pub fn is_executable(&self) -> bool {
+        !matches!(
+            self.action,
+            GameAction::Extension { .. } | GameAction::Unrecognized { .. }
+        ) && kinds::is_executable(self.kind())
+    }
+
     /// Serialize the event content for signing.
     pub fn content(&self) -> String {
-        serde_json::to_string(&self.action).unwrap_or_default()
+        self.action.content()
     }
 
     /// Generate Nostr tags for this event.
     pub fn tags(&self) -> Vec<Vec<String>> {
         let mut tags = vec![
-            vec!["g".to_string(), self.game_id.clone()], // Game ID tag
+            vec!["g".to_string(), self.game_id.to_string()], // Game ID tag
             vec!["p".to_string(), self.player_id.to_string()], // Player tag
             vec!["turn".to_string(), self.turn.to_string()],
             vec!["seq".to_string(), self.sequence.to_string()],
@@ -143,9 +173,33 @@ impl GameEvent {
             }
         }
 
+        if let Some(expiration) = self.expiration {
+            tags.push(vec!["expiration".to_string(), expiration.to_string()]);
+        }
+
         tags
     }
 
+    /// Compute the NIP-01 ID this event gets when `pubkey` (hex x-only
+    /// key) publishes it with `timestamp` as its `created_at`.
+    ///
+    /// Lets a batch link each event to the one before it before any of
+    /// them are signed.
+    pub fn nostr_id(&self, pubkey: &str) -> String {
+        let serialized = serde_json::json!([
+            0,
+            pubkey,
+            self.timestamp,
+            self.kind(),
+            self.tags(),
+            self.content()
+        ]);
+        Sha256::digest(serialized.to_string().as_bytes())
+            .iter()
+            .map(|b| format!("{:02x}", b))
+            .collect()
+    }
+
     /// Get the random value from the proof if present.
     pub fn random_value(&self) -> Option<f32> {
         self.randomness_proof.as_ref().map(|p| p.to_f32())
@@ -158,7 +212,7 @@ impl GameEvent {
 }
 
 /// All possible game actions.
-#[derive(Clone, Debug, Serialize, Deserialize)]
+#[derive(Clone, Debug, Serialize, Deserialize, JsonSchema, TS)]
 #[serde(tag = "type")]
 pub enum GameAction {
     // Game lifecycle
@@ -173,9 +227,35 @@ pub enum GameAction {
     StartGame,
     EndTurn,
     EndGame {
-        winner_id: PlayerId,
+        winner_id: PlayerSlot,
         victory_type: String,
     },
+    /// Leave the game, handing cities to `to_player` (or the leading
+    /// player) or razing them, depending on the game settings.
+    Concede {
+        to_player: Option<PlayerSlot>,
+    },
+    /// Ask the other players to pause the game.
+    RequestPause {
+        reason: Option<String>,
+    },
+    /// Vote on the pending pause request.
+    VotePause {
+        approve: bool,
+    },
+    /// Resume a paused game once the countdown ends.
+    ResumeGame {
+        countdown_secs: u32,
+    },
+    /// Hand an inactive player's seat to a new key (host only).
+    SubstitutePlayer {
+        player_slot: PlayerSlot,
+        new_pubkey: Npub,
+        new_name: Option<String>,
+    },
+    /// Reveal the full map and every player's stats once the game has
+    /// ended (host only).
+    RevealMap,
 
     // Unit actions
     MoveUnit {
@@ -192,6 +272,19 @@ pub enum GameAction {
         city_id: CityId,
         random: f32,
     },
+    BombardUnit {
+        city_id: CityId,
+        target_id: UnitId,
+        random: f32,
+    },
+    ResolveCapture {
+        city_id: CityId,
+        choice: CaptureChoice,
+    },
+    GiftUnit {
+        unit_id: UnitId,
+        recipient: PlayerSlot,
+    },
     FoundCity {
         settler_id: UnitId,
         name: String,
@@ -199,6 +292,9 @@ pub enum GameAction {
     FortifyUnit {
         unit_id: UnitId,
     },
+    FortifyUntilHealed {
+        unit_id: UnitId,
+    },
     SleepUnit {
         unit_id: UnitId,
     },
@@ -212,6 +308,10 @@ pub enum GameAction {
         unit_id: UnitId,
         gold_cost: i32,
     },
+    ChoosePromotion {
+        unit_id: UnitId,
+        promotion: Promotion,
+    },
 
     // Worker actions
     BuildImprovement {
@@ -247,24 +347,53 @@ pub enum GameAction {
         city_id: CityId,
         building: String,
     },
+    BuyTile {
+        city_id: CityId,
+        tile: HexCoord,
+    },
 
     // Research
     SetResearch {
         tech_id: TechId,
     },
+    /// Research toward a tech, queuing its missing prerequisites.
+    QueueResearch {
+        tech_id: TechId,
+    },
+    /// Replace the techs queued after the current research.
+    SetResearchQueue {
+        queue: Vec<TechId>,
+    },
 
     // Diplomacy
     DeclareWar {
-        target_player: PlayerId,
+        target_player: PlayerSlot,
     },
     ProposePeace {
-        target_player: PlayerId,
+        target_player: PlayerSlot,
     },
     AcceptPeace {
-        from_player: PlayerId,
+        from_player: PlayerSlot,
     },
     RejectPeace {
-        from_player: PlayerId,
+        from_player: PlayerSlot,
+    },
+    ProposeTreaty {
+        target_player: PlayerSlot,
+        treaty_type: TreatyType,
+    },
+
+    // Trading
+    // Boxed so trade offers don't grow every action
+    ProposeTrade {
+        to_player: PlayerSlot,
+        offer: Box<TradeItems>,
+        request: Box<TradeItems>,
+    },
+    RespondTrade {
+        offer_id: u64,
+        from_player: PlayerSlot,
+        accept: bool,
     },
 
     // Randomness (Cashu integration)
@@ -276,19 +405,107 @@ pub enum GameAction {
         request_id: String,
         blind_signature: String,
     },
+
+    // Event chain compaction
+    Snapshot {
+        snapshot: StateSnapshot,
+    },
+
+    // Forward compatibility
+    /// Event of a registered extension kind. Never executed.
+    Extension {
+        kind: u32,
+        content: String,
+    },
+    /// An action from a newer version, kept as raw JSON. Never executed.
+    #[serde(skip)]
+    Unrecognized {
+        payload: serde_json::Value,
+    },
+}
+
+/// Serde helpers for [`GameEvent::action`] that keep unrecognized actions
+/// intact instead of failing the whole event.
+mod action_serde {
+    use super::GameAction;
+    use serde::{Deserialize, Deserializer, Serialize, Serializer};
+    use serde_json::Value;
+
+    pub fn to_value(action: &GameAction) -> Result<Value, serde_json::Error> {
+        match action {
+            GameAction::Unrecognized { payload } => Ok(payload.clone()),
+            action => serde_json::to_value(action),
+        }
+    }
+
+    pub fn serialize<S: Serializer>(action: &GameAction, serializer: S) -> Result<S::Ok, S::Error> {
+        match action {
+            GameAction::Unrecognized { payload } => payload.serialize(serializer),
+            action => action.serialize(serializer),
+        }
+    }
+
+    pub fn deserialize<'de, D: Deserializer<'de>>(deserializer: D) -> Result<GameAction, D::Error> {
+        let value = Value::deserialize(deserializer)?;
+        match serde_json::from_value(value.clone()) {
+            Ok(action) => Ok(action),
+            // Only a new action type is kept; a known one that fails to
+            // parse is malformed
+            Err(e) if e.to_string().starts_with("unknown variant") => {
+                Ok(GameAction::Unrecognized { payload: value })
+            }
+            Err(e) => Err(serde::de::Error::custom(e)),
+        }
+    }
 }
 
 impl GameAction {
+    /// Serialize the action as event content.
+    ///
+    /// Object keys are sorted, so the same action always gives the same
+    /// string.
+    pub fn content(&self) -> String {
+        action_serde::to_value(self)
+            .map(|value| value.to_string())
+            .unwrap_or_default()
+    }
+
     /// Check if this action requires randomness.
     pub fn requires_random(&self) -> bool {
         matches!(
             self,
             GameAction::AttackUnit { .. }
                 | GameAction::AttackCity { .. }
+                | GameAction::BombardUnit { .. }
                 | GameAction::CreateGame { .. }
         )
     }
 
+    /// Check if this action may only be taken on the acting player's turn.
+    ///
+    /// Lobby actions and responses to diplomatic proposals can arrive at
+    /// any time.
+    pub fn requires_turn(&self) -> bool {
+        !matches!(
+            self,
+            GameAction::CreateGame { .. }
+                | GameAction::JoinGame { .. }
+                | GameAction::StartGame
+                | GameAction::Concede { .. }
+                | GameAction::RequestPause { .. }
+                | GameAction::VotePause { .. }
+                | GameAction::ResumeGame { .. }
+                | GameAction::SubstitutePlayer { .. }
+                | GameAction::RevealMap
+                | GameAction::Extension { .. }
+                | GameAction::Unrecognized { .. }
+                | GameAction::AcceptPeace { .. }
+                | GameAction::RejectPeace { .. }
+                | GameAction::RespondTrade { .. }
+                | GameAction::Snapshot { .. }
+        )
+    }
+
     /// Get a human-readable description of the action.
     pub fn description(&self) -> String {
         match self {
@@ -302,6 +519,20 @@ impl GameAction {
             } => {
                 format!("Player {} won by {}", winner_id, victory_type)
             }
+            GameAction::Concede {
+                to_player: Some(to),
+            } => format!("Conceded to player {}", to),
+            GameAction::Concede { to_player: None } => "Conceded".to_string(),
+            GameAction::RequestPause { .. } => "Requested a pause".to_string(),
+            GameAction::VotePause { approve: true } => "Voted to pause".to_string(),
+            GameAction::VotePause { approve: false } => "Voted against pausing".to_string(),
+            GameAction::ResumeGame { countdown_secs } => {
+                format!("Resuming in {} seconds", countdown_secs)
+            }
+            GameAction::SubstitutePlayer { player_slot, .. } => {
+                format!("Substitute took over player {}", player_slot)
+            }
+            GameAction::RevealMap => "Revealed the map".to_string(),
             GameAction::MoveUnit { unit_id, path } => {
                 format!("Unit {} moved to {:?}", unit_id, path.last())
             }
@@ -319,15 +550,58 @@ impl GameAction {
             } => {
                 format!("Unit {} attacked city {}", attacker_id, city_id)
             }
+            GameAction::BombardUnit {
+                city_id, target_id, ..
+            } => {
+                format!("City {} bombarded unit {}", city_id, target_id)
+            }
+            GameAction::ResolveCapture { city_id, choice } => {
+                format!("Chose {:?} for captured city {}", choice, city_id)
+            }
+            GameAction::GiftUnit { unit_id, recipient } => {
+                format!("Gifted unit {} to player {}", unit_id, recipient)
+            }
             GameAction::FoundCity { name, .. } => format!("Founded city {}", name),
             GameAction::FortifyUnit { unit_id } => format!("Unit {} fortified", unit_id),
+            GameAction::FortifyUntilHealed { unit_id } => {
+                format!("Unit {} fortified until healed", unit_id)
+            }
+            GameAction::ChoosePromotion { unit_id, promotion } => {
+                format!("Unit {} promoted with {:?}", unit_id, promotion)
+            }
             GameAction::SetProduction { city_id, item } => {
                 format!("City {} producing {:?}", city_id, item)
             }
+            GameAction::BuyTile { city_id, tile } => {
+                format!("City {} bought tile ({}, {})", city_id, tile.q, tile.r)
+            }
             GameAction::SetResearch { tech_id } => format!("Researching {}", tech_id),
+            GameAction::QueueResearch { tech_id } => format!("Queued research toward {}", tech_id),
+            GameAction::SetResearchQueue { queue } => {
+                format!("Set research queue ({} techs)", queue.len())
+            }
             GameAction::DeclareWar { target_player } => {
                 format!("Declared war on player {}", target_player)
             }
+            GameAction::ProposePeace { target_player } => {
+                format!("Proposed peace to player {}", target_player)
+            }
+            GameAction::ProposeTreaty {
+                target_player,
+                treaty_type,
+            } => {
+                format!("Proposed {:?} to player {}", treaty_type, target_player)
+            }
+            GameAction::ProposeTrade { to_player, .. } => {
+                format!("Offered trade to player {}", to_player)
+            }
+            GameAction::RespondTrade {
+                offer_id, accept, ..
+            } => {
+                let verb = if *accept { "Accepted" } else { "Rejected" };
+                format!("{} trade offer {}", verb, offer_id)
+            }
+            GameAction::Snapshot { snapshot } => format!("Snapshot of turn {}", snapshot.turn),
             _ => format!("{:?}", self),
         }
     }
@@ -474,7 +748,7 @@ impl std::error::Error for EventChainError {}
 /// Builder for creating game events with proper chaining.
 pub struct EventBuilder {
     game_id: GameId,
-    player_id: PlayerId,
+    player_id: PlayerSlot,
     turn: u32,
     sequence: u32,
     last_event_id: Option<String>,
@@ -482,7 +756,7 @@ pub struct EventBuilder {
 
 impl EventBuilder {
     /// Create a new event builder.
-    pub fn new(game_id: GameId, player_id: PlayerId) -> Self {
+    pub fn new(game_id: GameId, player_id: PlayerSlot) -> Self {
         Self {
             game_id,
             player_id,
@@ -545,8 +819,8 @@ mod tests {
 
     fn create_test_event(id: &str, prev: Option<&str>, turn: u32, seq: u32) -> GameEvent {
         let mut event = GameEvent::new(
-            "test_game".to_string(),
-            0,
+            GameId::new("test_game"),
+            PlayerSlot(0),
             prev.map(|s| s.to_string()),
             turn,
             seq,
@@ -556,6 +830,12 @@ mod tests {
         event
     }
 
+    #[test]
+    fn test_trade_payload_is_boxed() {
+        // A trade's items would otherwise set the size of every action
+        assert!(std::mem::size_of::<GameAction>() < std::mem::size_of::<TradeItems>());
+    }
+
     #[test]
     fn test_event_chain_add() {
         let mut chain = EventChain::new();
@@ -601,7 +881,7 @@ mod tests {
 
     #[test]
     fn test_event_builder() {
-        let mut builder = EventBuilder::new("game1".to_string(), 0);
+        let mut builder = EventBuilder::new(GameId::new("game1"), PlayerSlot(0));
         builder.set_turn(1);
 
         let event1 = builder.build(GameAction::EndTurn);
@@ -621,21 +901,21 @@ mod tests {
     #[test]
     fn test_action_requires_random() {
         assert!(GameAction::AttackUnit {
-            attacker_id: 1,
-            defender_id: 2,
+            attacker_id: UnitId(1),
+            defender_id: UnitId(2),
             random: 0.5,
         }
         .requires_random());
 
         assert!(!GameAction::EndTurn.requires_random());
-        assert!(!GameAction::FortifyUnit { unit_id: 1 }.requires_random());
+        assert!(!GameAction::FortifyUnit { unit_id: UnitId(1) }.requires_random());
     }
 
     #[test]
     fn test_event_tags() {
         let event = GameEvent::new(
-            "game123".to_string(),
-            0,
+            GameId::new("game123"),
+            PlayerSlot(0),
             Some("prev_evt".to_string()),
             5,
             3,
@@ -648,11 +928,87 @@ mod tests {
         assert!(tags.iter().any(|t| t[0] == "e" && t[1] == "prev_evt"));
     }
 
+    #[test]
+    fn test_nostr_id_matches_nip01_serialization() {
+        let mut event = GameEvent::new(
+            GameId::new("game123"),
+            PlayerSlot(0),
+            Some("prev_evt".to_string()),
+            5,
+            3,
+            GameAction::EndTurn,
+        );
+        event.timestamp = 1_700_000_000;
+        let pubkey = "79be667ef9dcbbac55a06295ce870b07029bfcdb2dce28d959f2815b16f81798";
+
+        // sha256 of [0,pubkey,created_at,kind,tags,content] with no whitespace
+        assert_eq!(
+            event.nostr_id(pubkey),
+            "4b4c72515546ad0cf4fcb9bf9cb0bf86433cc23645f786f4b0197ba2175ac021"
+        );
+
+        // Any field covered by the ID changes it
+        event.prev_event_id = Some("other".to_string());
+        assert_ne!(
+            event.nostr_id(pubkey),
+            "4b4c72515546ad0cf4fcb9bf9cb0bf86433cc23645f786f4b0197ba2175ac021"
+        );
+    }
+
+    #[test]
+    fn test_event_expiration() {
+        let event = GameEvent::new(
+            GameId::new("game123"),
+            PlayerSlot(0),
+            None,
+            1,
+            1,
+            GameAction::EndTurn,
+        );
+        assert!(!event.is_expired(u64::MAX));
+        assert!(!event.tags().iter().any(|t| t[0] == "expiration"));
+
+        let event = event.with_expiration(1000);
+        assert!(!event.is_expired(999));
+        assert!(event.is_expired(1000));
+        assert!(event
+            .tags()
+            .iter()
+            .any(|t| t[0] == "expiration" && t[1] == "1000"));
+    }
+
+    #[test]
+    fn test_ping_events_are_ephemeral() {
+        let ping = GameEvent::new(
+            GameId::new("game123"),
+            PlayerSlot(0),
+            None,
+            1,
+            1,
+            GameAction::Extension {
+                kind: kinds::PING,
+                content: "{}".to_string(),
+            },
+        );
+        assert!(ping.is_ephemeral());
+        assert!(!ping.is_executable());
+
+        let end_turn = GameEvent::new(
+            GameId::new("game123"),
+            PlayerSlot(0),
+            None,
+            1,
+            1,
+            GameAction::EndTurn,
+        );
+        assert!(!end_turn.is_ephemeral());
+    }
+
     #[test]
     fn test_event_kind() {
         let create = GameEvent::new(
-            "g".to_string(),
-            0,
+            GameId::new("g"),
+            PlayerSlot(0),
             None,
             0,
             1,
@@ -664,19 +1020,59 @@ mod tests {
         assert_eq!(create.kind(), kinds::GAME_CREATE);
 
         let action = GameEvent::new(
-            "g".to_string(),
-            0,
+            GameId::new("g"),
+            PlayerSlot(0),
             None,
             1,
             1,
             GameAction::MoveUnit {
-                unit_id: 1,
+                unit_id: UnitId(1),
                 path: vec![],
             },
         );
         assert_eq!(action.kind(), kinds::GAME_ACTION);
     }
 
+    #[test]
+    fn test_extension_event_not_executed() {
+        let event = GameEvent::new(
+            GameId::new("g"),
+            PlayerSlot(0),
+            None,
+            1,
+            1,
+            GameAction::Extension {
+                kind: 30150,
+                content: "wave".to_string(),
+            },
+        );
+        assert_eq!(event.kind(), 30150);
+        assert!(!event.is_executable());
+        assert!(!event.action.requires_turn());
+    }
+
+    #[test]
+    fn test_unrecognized_action_round_trips() {
+        let json = r#"{"id":"e1","game_id":"g","player_id":1,"prev_event_id":null,"turn":3,"sequence":1,"action":{"type":"SummonDragon","size":9},"timestamp":0,"randomness_proof":null}"#;
+
+        let event: GameEvent = serde_json::from_str(json).unwrap();
+        assert!(matches!(event.action, GameAction::Unrecognized { .. }));
+        assert!(!event.is_executable());
+        assert_eq!(event.content(), r#"{"size":9,"type":"SummonDragon"}"#);
+
+        // Relayed unchanged
+        let reencoded: serde_json::Value =
+            serde_json::from_str(&serde_json::to_string(&event).unwrap()).unwrap();
+        let original: serde_json::Value = serde_json::from_str(json).unwrap();
+        assert_eq!(reencoded, original);
+    }
+
+    #[test]
+    fn test_malformed_known_action_rejected() {
+        let json = r#"{"id":"e1","game_id":"g","player_id":1,"prev_event_id":null,"turn":3,"sequence":1,"action":{"type":"MoveUnit","unit_id":"x"},"timestamp":0,"randomness_proof":null}"#;
+        assert!(serde_json::from_str::<GameEvent>(json).is_err());
+    }
+
     #[test]
     fn test_event_with_randomness_proof() {
         let proof = RandomnessProof {
@@ -690,14 +1086,14 @@ mod tests {
         };
 
         let event = GameEvent::with_randomness(
-            "game1".to_string(),
-            0,
+            GameId::new("game1"),
+            PlayerSlot(0),
             None,
             1,
             1,
             GameAction::AttackUnit {
-                attacker_id: 1,
-                defender_id: 2,
+                attacker_id: UnitId(1),
+                defender_id: UnitId(2),
                 random: 0.5,
             },
             proof.clone(),
@@ -0,0 +1,407 @@
+//! Fixed-point arithmetic for deterministic game calculations.
+//!
+//! Floating point results can differ between platforms, compilers and
+//! optimization levels, which is enough to desync two clients replaying
+//! the same event chain. Every calculation that feeds game state (combat,
+//! yields, growth, movement, trade evaluation) uses [`Fixed`] instead.
+//!
+//! # Sealing
+//!
+//! Types on deterministic code paths implement the sealed [`Deterministic`]
+//! marker trait. It is implemented for integers, `bool`, strings and
+//! [`Fixed`], never for `f32` or `f64`. Structs opt in through the
+//! crate-internal `deterministic!` macro, which destructures every field and
+//! requires each one to be `Deterministic`, so adding a float field to a
+//! sealed struct is a compile error.
+
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+use std::fmt;
+use std::ops::{Add, AddAssign, Div, Mul, Neg, Sub, SubAssign};
+use ts_rs::TS;
+
+/// Number of fractional bits in a [`Fixed`] value.
+pub const FRAC_BITS: u32 = 16;
+
+const SCALE: i64 = 1 << FRAC_BITS;
+
+/// Signed fixed-point number with 16 fractional bits.
+#[derive(
+    Clone,
+    Copy,
+    Debug,
+    Default,
+    PartialEq,
+    Eq,
+    PartialOrd,
+    Ord,
+    Hash,
+    Serialize,
+    Deserialize,
+    JsonSchema,
+    TS,
+)]
+#[serde(transparent)]
+#[ts(type = "number")]
+pub struct Fixed(i64);
+
+impl Fixed {
+    pub const ZERO: Fixed = Fixed(0);
+    pub const ONE: Fixed = Fixed(SCALE);
+    pub const HALF: Fixed = Fixed(SCALE / 2);
+
+    /// Create from the raw underlying representation.
+    pub const fn from_raw(raw: i64) -> Self {
+        Fixed(raw)
+    }
+
+    /// The raw underlying representation.
+    pub const fn raw(self) -> i64 {
+        self.0
+    }
+
+    /// Create from an integer.
+    pub const fn from_int(value: i64) -> Self {
+        Fixed(value * SCALE)
+    }
+
+    /// Create from `num / den`. Returns zero if `den` is zero.
+    pub const fn from_ratio(num: i64, den: i64) -> Self {
+        if den == 0 {
+            return Fixed::ZERO;
+        }
+        Fixed(((num as i128 * SCALE as i128) / den as i128) as i64)
+    }
+
+    /// Create from a percentage (e.g. `25` is `0.25`).
+    pub const fn from_percent(percent: i64) -> Self {
+        Self::from_ratio(percent, 100)
+    }
+
+    /// Convert a float received at a system boundary (e.g. a combat roll
+    /// carried in an event).
+    ///
+    /// Scaling by a power of two and truncating are exact IEEE operations,
+    /// so the result is the same on every platform.
+    pub fn from_f32(value: f32) -> Self {
+        Fixed((value as f64 * SCALE as f64) as i64)
+    }
+
+    /// Convert to a float for display only. Never feed the result back into
+    /// game state.
+    pub fn to_f32(self) -> f32 {
+        self.0 as f32 / SCALE as f32
+    }
+
+    /// Round towards negative infinity.
+    pub const fn floor(self) -> i64 {
+        self.0 >> FRAC_BITS
+    }
+
+    /// Round towards positive infinity.
+    pub const fn ceil(self) -> i64 {
+        (self.0 + SCALE - 1) >> FRAC_BITS
+    }
+
+    /// Round towards zero.
+    pub const fn trunc(self) -> i64 {
+        self.0 / SCALE
+    }
+
+    /// Round to the nearest integer, halves away from zero.
+    pub const fn round(self) -> i64 {
+        if self.0 >= 0 {
+            (self.0 + SCALE / 2) >> FRAC_BITS
+        } else {
+            -((-self.0 + SCALE / 2) >> FRAC_BITS)
+        }
+    }
+
+    /// Multiply by an integer.
+    pub const fn mul_int(self, value: i64) -> Self {
+        Fixed(self.0 * value)
+    }
+
+    /// Square root, rounded down. Negative values return zero.
+    pub fn sqrt(self) -> Self {
+        self.nth_root(2)
+    }
+
+    /// `n`th root, rounded down to the nearest representable value.
+    /// Negative values and `n == 0` return zero.
+    pub fn nth_root(self, n: u32) -> Self {
+        if self.0 <= 0 || n == 0 {
+            return Fixed::ZERO;
+        }
+        if n == 1 {
+            return self;
+        }
+        // Largest raw r such that (r / SCALE)^n <= self.
+        let (mut lo, mut hi) = (0i64, self.0.max(SCALE));
+        while lo < hi {
+            let mid = lo + (hi - lo + 1) / 2;
+            if Fixed(mid).checked_powi(n).is_some_and(|p| p <= self) {
+                lo = mid;
+            } else {
+                hi = mid - 1;
+            }
+        }
+        Fixed(lo)
+    }
+
+    /// Integer power, or `None` on overflow.
+    pub fn checked_powi(self, n: u32) -> Option<Self> {
+        let mut result = Fixed::ONE;
+        for _ in 0..n {
+            result = result.checked_mul(self)?;
+        }
+        Some(result)
+    }
+
+    /// Multiply, or `None` on overflow.
+    pub fn checked_mul(self, rhs: Self) -> Option<Self> {
+        let product = (self.0 as i128 * rhs.0 as i128) >> FRAC_BITS;
+        i64::try_from(product).ok().map(Fixed)
+    }
+}
+
+impl fmt::Display for Fixed {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let sign = if self.0 < 0 { "-" } else { "" };
+        let abs = self.0.unsigned_abs();
+        let frac = (abs % SCALE as u64) * 10_000 / SCALE as u64;
+        write!(f, "{}{}.{:04}", sign, abs >> FRAC_BITS, frac)
+    }
+}
+
+impl Add for Fixed {
+    type Output = Fixed;
+
+    fn add(self, rhs: Fixed) -> Fixed {
+        Fixed(self.0 + rhs.0)
+    }
+}
+
+impl AddAssign for Fixed {
+    fn add_assign(&mut self, rhs: Fixed) {
+        self.0 += rhs.0;
+    }
+}
+
+impl Sub for Fixed {
+    type Output = Fixed;
+
+    fn sub(self, rhs: Fixed) -> Fixed {
+        Fixed(self.0 - rhs.0)
+    }
+}
+
+impl SubAssign for Fixed {
+    fn sub_assign(&mut self, rhs: Fixed) {
+        self.0 -= rhs.0;
+    }
+}
+
+impl Mul for Fixed {
+    type Output = Fixed;
+
+    fn mul(self, rhs: Fixed) -> Fixed {
+        Fixed(((self.0 as i128 * rhs.0 as i128) >> FRAC_BITS) as i64)
+    }
+}
+
+impl Div for Fixed {
+    type Output = Fixed;
+
+    /// Division by zero yields zero rather than panicking, matching how the
+    /// game treats zero-strength combatants.
+    fn div(self, rhs: Fixed) -> Fixed {
+        if rhs.0 == 0 {
+            return Fixed::ZERO;
+        }
+        Fixed(((self.0 as i128 * SCALE as i128) / rhs.0 as i128) as i64)
+    }
+}
+
+impl Neg for Fixed {
+    type Output = Fixed;
+
+    fn neg(self) -> Fixed {
+        Fixed(-self.0)
+    }
+}
+
+#[doc(hidden)]
+pub mod sealed {
+    pub trait Sealed {}
+}
+
+/// Marker for types whose values are computed identically on every platform.
+///
+/// This trait is sealed: it cannot be implemented outside this crate, and it
+/// is deliberately not implemented for `f32` or `f64`.
+pub trait Deterministic: sealed::Sealed {}
+
+macro_rules! seal_primitives {
+    ($($ty:ty),* $(,)?) => {
+        $(
+            impl sealed::Sealed for $ty {}
+            impl Deterministic for $ty {}
+        )*
+    };
+}
+
+seal_primitives!(i8, i16, i32, i64, u8, u16, u32, u64, usize, bool, String, Fixed);
+
+impl<T: Deterministic + ?Sized> sealed::Sealed for &T {}
+impl<T: Deterministic + ?Sized> Deterministic for &T {}
+impl<T: Deterministic> sealed::Sealed for Option<T> {}
+impl<T: Deterministic> Deterministic for Option<T> {}
+impl<T: Deterministic> sealed::Sealed for Vec<T> {}
+impl<T: Deterministic> Deterministic for Vec<T> {}
+impl<T: Deterministic, const N: usize> sealed::Sealed for [T; N] {}
+impl<T: Deterministic, const N: usize> Deterministic for [T; N] {}
+
+/// Compile-time check that `T` is [`Deterministic`].
+pub const fn assert_deterministic<T: Deterministic + ?Sized>() {}
+
+/// Seal a type as [`Deterministic`].
+///
+/// For structs every field must be listed; the generated code destructures
+/// the struct without `..`, so a new field fails to compile until it is added
+/// here, and a float field fails because floats are not `Deterministic`.
+/// Newtypes name their inner type. Enums must be fieldless.
+macro_rules! deterministic {
+    (struct $ty:ident $(<$lt:lifetime>)? { $($field:ident),* $(,)? }) => {
+        impl $(<$lt>)? $crate::fixed::sealed::Sealed for $ty $(<$lt>)? {}
+        impl $(<$lt>)? $crate::fixed::Deterministic for $ty $(<$lt>)? {}
+        const _: () = {
+            #[allow(dead_code)]
+            fn check $(<$lt>)? (value: &$ty $(<$lt>)?) {
+                fn field<T: $crate::fixed::Deterministic + ?Sized>(_: &T) {}
+                let $ty { $($field),* } = value;
+                $(field($field);)*
+            }
+        };
+    };
+    (struct $ty:ident($inner:ty)) => {
+        impl $crate::fixed::sealed::Sealed for $ty {}
+        impl $crate::fixed::Deterministic for $ty {}
+        const _: () = $crate::fixed::assert_deterministic::<$inner>();
+    };
+    (enum $($ty:ident),* $(,)?) => {
+        $(
+            impl $crate::fixed::sealed::Sealed for $ty {}
+            impl $crate::fixed::Deterministic for $ty {}
+        )*
+    };
+}
+
+pub(crate) use deterministic;
+
+/// Strip the `#[cfg(test)]` section from a source file and report any float
+/// type tokens left in it.
+#[cfg(test)]
+pub(crate) fn float_tokens(source: &str) -> Vec<(usize, String)> {
+    let code = source.split("#[cfg(test)]").next().unwrap_or(source);
+    code.lines()
+        .enumerate()
+        .filter(|(_, line)| !line.trim_start().starts_with("//"))
+        .filter(|(_, line)| {
+            line.split(|c: char| !c.is_alphanumeric() && c != '_')
+                .any(|token| token == "f32" || token == "f64")
+        })
+        .map(|(i, line)| (i + 1, line.trim().to_string()))
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // ==================== Conversion Tests ====================
+
+    #[test]
+    fn test_from_int_and_ratio() {
+        assert_eq!(Fixed::from_int(3).raw(), 3 * SCALE);
+        assert_eq!(Fixed::from_ratio(1, 2), Fixed::HALF);
+        assert_eq!(Fixed::from_percent(100), Fixed::ONE);
+        assert_eq!(Fixed::from_ratio(5, 0), Fixed::ZERO);
+    }
+
+    #[test]
+    fn test_from_f32_is_exact_for_dyadic_values() {
+        assert_eq!(Fixed::from_f32(0.5), Fixed::HALF);
+        assert_eq!(Fixed::from_f32(0.25), Fixed::from_ratio(1, 4));
+        assert_eq!(Fixed::from_f32(1.0), Fixed::ONE);
+    }
+
+    #[test]
+    fn test_rounding() {
+        let x = Fixed::from_ratio(5, 2);
+        assert_eq!(x.floor(), 2);
+        assert_eq!(x.ceil(), 3);
+        assert_eq!(x.trunc(), 2);
+        assert_eq!(x.round(), 3);
+        assert_eq!((-x).round(), -3);
+        assert_eq!((-x).floor(), -3);
+        assert_eq!(Fixed::from_int(4).ceil(), 4);
+    }
+
+    #[test]
+    fn test_display() {
+        assert_eq!(Fixed::from_ratio(5, 4).to_string(), "1.2500");
+        assert_eq!((-Fixed::HALF).to_string(), "-0.5000");
+    }
+
+    // ==================== Arithmetic Tests ====================
+
+    #[test]
+    fn test_arithmetic() {
+        let a = Fixed::from_int(6);
+        let b = Fixed::from_int(4);
+        assert_eq!(a + b, Fixed::from_int(10));
+        assert_eq!(a - b, Fixed::from_int(2));
+        assert_eq!(a * b, Fixed::from_int(24));
+        assert_eq!(a / b, Fixed::from_ratio(3, 2));
+        assert_eq!(a / Fixed::ZERO, Fixed::ZERO);
+        assert_eq!(a.mul_int(2), Fixed::from_int(12));
+    }
+
+    #[test]
+    fn test_roots() {
+        assert_eq!(Fixed::from_int(9).sqrt(), Fixed::from_int(3));
+        assert_eq!(Fixed::from_int(32).nth_root(5), Fixed::from_int(2));
+        assert_eq!(Fixed::from_int(-4).sqrt(), Fixed::ZERO);
+        let root2 = Fixed::from_int(2).sqrt();
+        assert_eq!(root2.to_string(), "1.4142");
+        // Fractional inputs below one have roots above themselves.
+        assert_eq!(Fixed::from_ratio(1, 4).sqrt(), Fixed::HALF);
+    }
+
+    #[test]
+    fn test_checked_powi_overflow() {
+        assert_eq!(
+            Fixed::from_int(3).checked_powi(3),
+            Some(Fixed::from_int(27))
+        );
+        assert!(Fixed::from_int(1 << 20).checked_powi(4).is_none());
+    }
+
+    // ==================== Sealing Tests ====================
+
+    #[test]
+    fn test_float_scanner_ignores_tests_and_comments() {
+        let source = "let a: u32 = 1;\n// f32 in a comment\n#[cfg(test)]\nlet b: f32 = 0.0;";
+        assert!(float_tokens(source).is_empty());
+        assert_eq!(float_tokens("let x: f64 = 1.0;").len(), 1);
+        assert!(float_tokens("let buf32 = 0;").is_empty());
+    }
+
+    #[test]
+    fn test_primitives_are_sealed() {
+        assert_deterministic::<Fixed>();
+        assert_deterministic::<Option<Vec<u32>>>();
+        assert_deterministic::<[bool; 6]>();
+    }
+}
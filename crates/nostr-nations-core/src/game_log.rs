@@ -0,0 +1,968 @@
+//! History of what happened in a game, for the event log panel.
+//!
+//! [`GameEngine`](crate::replay::GameEngine) records an entry in its
+//! [`GameLog`] for each notable outcome of an applied action: combat,
+//! cities founded and captured, research, diplomacy and the end of the
+//! game. Entries are localized messages tagged with the turn, a category
+//! and the units, cities, players and tiles they refer to, so the UI can
+//! filter them and focus the map on a clicked entry.
+//!
+//! The log is a ring buffer holding the newest [`DEFAULT_LOG_CAPACITY`]
+//! entries. It isn't part of the game state, so it never affects replay or
+//! state hashes. Entries of an undone action are taken back out.
+
+use crate::events::GameAction;
+use crate::game_state::GameState;
+use crate::hex::HexCoord;
+use crate::locale::LocalizedMessage;
+use crate::replay::ActionEffect;
+use crate::types::{CityId, PlayerSlot, UnitId};
+use crate::unit::UnitType;
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+use std::collections::{HashMap, VecDeque};
+use ts_rs::TS;
+
+/// Number of entries kept by default.
+pub const DEFAULT_LOG_CAPACITY: usize = 500;
+
+/// What a log entry is about.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash, Serialize, Deserialize, JsonSchema, TS)]
+pub enum LogCategory {
+    /// Attacks, bombardment, units lost and promotions.
+    Combat,
+    /// Cities founded, captured or razed, and what they completed.
+    City,
+    /// Technologies and eras.
+    Research,
+    /// War, peace, trades and first contact.
+    Diplomacy,
+    /// Players conceding or defeated, and the end of the game.
+    Game,
+}
+
+/// Something a log entry refers to.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash, Serialize, Deserialize, JsonSchema, TS)]
+pub enum EntityRef {
+    Player(PlayerSlot),
+    Unit(UnitId),
+    City(CityId),
+    Tile(HexCoord),
+}
+
+/// One entry in the game log.
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize, JsonSchema, TS)]
+pub struct LogEntry {
+    /// Increasing ID, unique within the log.
+    pub id: u64,
+    /// Turn the entry happened on.
+    pub turn: u32,
+    pub category: LogCategory,
+    /// Description of what happened.
+    pub message: LocalizedMessage,
+    /// Players, units, cities and tiles involved.
+    pub entities: Vec<EntityRef>,
+    /// Where to focus the map, if anywhere.
+    pub position: Option<HexCoord>,
+    /// Action the entry was recorded for, for taking back undone actions.
+    #[serde(skip)]
+    action: u64,
+}
+
+impl LogEntry {
+    /// Check if the entry refers to `entity`.
+    pub fn refers_to(&self, entity: &EntityRef) -> bool {
+        self.entities.contains(entity)
+    }
+
+    /// Check if the entry involves `player`.
+    pub fn involves(&self, player: PlayerSlot) -> bool {
+        self.refers_to(&EntityRef::Player(player))
+    }
+}
+
+/// Which log entries to return.
+///
+/// Every field left unset matches everything.
+#[derive(Clone, Debug, Default, PartialEq, Eq, Serialize, Deserialize, JsonSchema, TS)]
+#[serde(default)]
+pub struct LogFilter {
+    /// Only entries in these categories.
+    pub categories: Vec<LogCategory>,
+    /// Only entries involving this player.
+    pub player: Option<PlayerSlot>,
+    /// Only entries referring to this unit, city or tile.
+    pub entity: Option<EntityRef>,
+    /// Only entries from this turn on.
+    pub from_turn: Option<u32>,
+    /// Only entries up to and including this turn.
+    pub to_turn: Option<u32>,
+    /// Only entries newer than this ID, for fetching what was added since
+    /// the last query.
+    pub after_id: Option<u64>,
+    /// Return at most this many entries, newest first.
+    pub limit: Option<usize>,
+}
+
+impl LogFilter {
+    /// Check if an entry matches the filter, ignoring the limit.
+    pub fn matches(&self, entry: &LogEntry) -> bool {
+        (self.categories.is_empty() || self.categories.contains(&entry.category))
+            && self.player.is_none_or(|p| entry.involves(p))
+            && self.entity.as_ref().is_none_or(|e| entry.refers_to(e))
+            && self.from_turn.is_none_or(|t| entry.turn >= t)
+            && self.to_turn.is_none_or(|t| entry.turn <= t)
+            && self.after_id.is_none_or(|id| entry.id > id)
+    }
+}
+
+/// Owner, type and position of a unit before an action.
+#[derive(Clone, Copy, Debug)]
+struct UnitInfo {
+    owner: PlayerSlot,
+    unit_type: UnitType,
+    position: HexCoord,
+}
+
+/// What the log needs to know about the state before an action, for
+/// units and cities the action may remove.
+#[derive(Clone, Debug, Default)]
+pub struct LogContext {
+    turn: u32,
+    units: HashMap<UnitId, UnitInfo>,
+    cities: HashMap<CityId, (PlayerSlot, String)>,
+}
+
+impl LogContext {
+    /// Capture the units and cities `action` refers to.
+    pub fn capture(state: &GameState, action: &GameAction) -> Self {
+        let (units, cities): (Vec<UnitId>, Vec<CityId>) = match action {
+            GameAction::AttackUnit {
+                attacker_id,
+                defender_id,
+                ..
+            } => (vec![*attacker_id, *defender_id], vec![]),
+            GameAction::AttackCity {
+                attacker_id,
+                city_id,
+                ..
+            } => (vec![*attacker_id], vec![*city_id]),
+            GameAction::BombardUnit {
+                city_id, target_id, ..
+            } => (vec![*target_id], vec![*city_id]),
+            GameAction::ResolveCapture { city_id, .. } => (vec![], vec![*city_id]),
+            _ => {
+                return Self {
+                    turn: state.turn,
+                    ..Self::default()
+                }
+            }
+        };
+        Self {
+            turn: state.turn,
+            units: units
+                .into_iter()
+                .filter_map(|id| {
+                    let unit = state.units.get(&id)?;
+                    Some((
+                        id,
+                        UnitInfo {
+                            owner: unit.owner,
+                            unit_type: unit.unit_type,
+                            position: unit.position,
+                        },
+                    ))
+                })
+                .collect(),
+            cities: cities
+                .into_iter()
+                .filter_map(|id| {
+                    let city = state.cities.get(&id)?;
+                    Some((id, (city.owner, city.name.clone())))
+                })
+                .collect(),
+        }
+    }
+}
+
+/// Ring buffer of log entries.
+#[derive(Clone, Debug)]
+pub struct GameLog {
+    entries: VecDeque<LogEntry>,
+    capacity: usize,
+    next_id: u64,
+    /// Number of actions recorded, less those taken back.
+    actions: u64,
+}
+
+impl Default for GameLog {
+    fn default() -> Self {
+        Self::new(DEFAULT_LOG_CAPACITY)
+    }
+}
+
+impl GameLog {
+    /// Create a log keeping the newest `capacity` entries.
+    pub fn new(capacity: usize) -> Self {
+        Self {
+            entries: VecDeque::with_capacity(capacity.min(DEFAULT_LOG_CAPACITY)),
+            capacity,
+            next_id: 1,
+            actions: 0,
+        }
+    }
+
+    /// Number of entries held.
+    pub fn len(&self) -> usize {
+        self.entries.len()
+    }
+
+    /// Check if the log holds no entries.
+    pub fn is_empty(&self) -> bool {
+        self.entries.is_empty()
+    }
+
+    /// All entries held, oldest first.
+    pub fn entries(&self) -> impl Iterator<Item = &LogEntry> {
+        self.entries.iter()
+    }
+
+    /// Entries matching `filter`, newest first.
+    pub fn query(&self, filter: &LogFilter) -> Vec<LogEntry> {
+        self.entries
+            .iter()
+            .rev()
+            .filter(|e| filter.matches(e))
+            .take(filter.limit.unwrap_or(usize::MAX))
+            .cloned()
+            .collect()
+    }
+
+    /// Record the outcome of a successfully applied action.
+    ///
+    /// `context` is captured before the action and `state` is the state
+    /// after it.
+    pub fn record(
+        &mut self,
+        context: &LogContext,
+        player_id: PlayerSlot,
+        action: &GameAction,
+        state: &GameState,
+        effects: &[ActionEffect],
+    ) {
+        self.actions += 1;
+        let mut recorder = Recorder {
+            log: self,
+            context,
+            state,
+        };
+        recorder.action(player_id, action, effects);
+        for effect in effects {
+            recorder.effect(player_id, action, effect);
+        }
+    }
+
+    /// Take back the entries of the most recently recorded action.
+    pub fn undo_action(&mut self) {
+        let action = self.actions;
+        while self.entries.back().is_some_and(|e| e.action == action) {
+            self.entries.pop_back();
+        }
+        self.actions = self.actions.saturating_sub(1);
+    }
+
+    fn push(
+        &mut self,
+        turn: u32,
+        category: LogCategory,
+        message: LocalizedMessage,
+        entities: Vec<EntityRef>,
+        position: Option<HexCoord>,
+    ) {
+        if self.capacity == 0 {
+            return;
+        }
+        if self.entries.len() == self.capacity {
+            self.entries.pop_front();
+        }
+        self.entries.push_back(LogEntry {
+            id: self.next_id,
+            turn,
+            category,
+            message,
+            entities,
+            position,
+            action: self.actions,
+        });
+        self.next_id += 1;
+    }
+}
+
+/// Turns one action's outcome into log entries.
+struct Recorder<'a> {
+    log: &'a mut GameLog,
+    context: &'a LogContext,
+    state: &'a GameState,
+}
+
+impl Recorder<'_> {
+    fn player_name(&self, player_id: PlayerSlot) -> String {
+        self.state
+            .get_player(player_id)
+            .map(|p| p.name.clone())
+            .unwrap_or_else(|| format!("Player {}", player_id))
+    }
+
+    fn city_name(&self, city_id: CityId) -> String {
+        self.state
+            .cities
+            .get(&city_id)
+            .map(|c| c.name.clone())
+            .or_else(|| {
+                self.context
+                    .cities
+                    .get(&city_id)
+                    .map(|(_, name)| name.clone())
+            })
+            .unwrap_or_else(|| format!("City {}", city_id))
+    }
+
+    fn city_position(&self, city_id: CityId) -> Option<HexCoord> {
+        self.state.cities.get(&city_id).map(|c| c.position)
+    }
+
+    fn push(
+        &mut self,
+        category: LogCategory,
+        message: LocalizedMessage,
+        entities: Vec<EntityRef>,
+        position: Option<HexCoord>,
+    ) {
+        self.log
+            .push(self.context.turn, category, message, entities, position);
+    }
+
+    /// Entries for what the action itself did.
+    fn action(&mut self, player_id: PlayerSlot, action: &GameAction, effects: &[ActionEffect]) {
+        let damage_to = |unit: UnitId| {
+            effects
+                .iter()
+                .find_map(|e| match e {
+                    ActionEffect::UnitDamaged {
+                        unit_id, damage, ..
+                    } if *unit_id == unit => Some(*damage),
+                    _ => None,
+                })
+                .unwrap_or(0)
+        };
+        match action {
+            GameAction::AttackUnit {
+                attacker_id,
+                defender_id,
+                ..
+            } => {
+                let (Some(attacker), Some(defender)) = (
+                    self.context.units.get(attacker_id).copied(),
+                    self.context.units.get(defender_id).copied(),
+                ) else {
+                    return;
+                };
+                let message = LocalizedMessage::new("log-combat-unit")
+                    .with_arg("player", self.player_name(attacker.owner))
+                    .with_arg("unit", format!("{:?}", attacker.unit_type))
+                    .with_arg("defender", self.player_name(defender.owner))
+                    .with_arg("target", format!("{:?}", defender.unit_type))
+                    .with_arg("dealt", damage_to(*defender_id))
+                    .with_arg("taken", damage_to(*attacker_id));
+                self.push(
+                    LogCategory::Combat,
+                    message,
+                    vec![
+                        EntityRef::Player(attacker.owner),
+                        EntityRef::Player(defender.owner),
+                        EntityRef::Unit(*attacker_id),
+                        EntityRef::Unit(*defender_id),
+                    ],
+                    Some(defender.position),
+                );
+                for (unit_id, info) in [(attacker_id, attacker), (defender_id, defender)] {
+                    if !self.state.units.contains_key(unit_id) {
+                        self.unit_lost(*unit_id, info);
+                    }
+                }
+            }
+            GameAction::AttackCity {
+                attacker_id,
+                city_id,
+                ..
+            } => {
+                let Some(attacker) = self.context.units.get(attacker_id).copied() else {
+                    return;
+                };
+                let owner = self.context.cities.get(city_id).map(|(owner, _)| *owner);
+                let city_damage = effects
+                    .iter()
+                    .find_map(|e| match e {
+                        ActionEffect::CityDamaged { damage, .. } => Some(*damage),
+                        _ => None,
+                    })
+                    .unwrap_or(0);
+                let message = LocalizedMessage::new("log-combat-city")
+                    .with_arg("player", self.player_name(attacker.owner))
+                    .with_arg("unit", format!("{:?}", attacker.unit_type))
+                    .with_arg("city", self.city_name(*city_id))
+                    .with_arg("dealt", city_damage)
+                    .with_arg("taken", damage_to(*attacker_id));
+                let mut entities = vec![EntityRef::Player(attacker.owner)];
+                entities.extend(owner.map(EntityRef::Player));
+                entities.extend([EntityRef::Unit(*attacker_id), EntityRef::City(*city_id)]);
+                let position = self.city_position(*city_id);
+                self.push(LogCategory::Combat, message, entities, position);
+                if !self.state.units.contains_key(attacker_id) {
+                    self.unit_lost(*attacker_id, attacker);
+                }
+            }
+            GameAction::BombardUnit {
+                city_id, target_id, ..
+            } => {
+                let Some(target) = self.context.units.get(target_id).copied() else {
+                    return;
+                };
+                let owner = self.context.cities.get(city_id).map(|(owner, _)| *owner);
+                let message = LocalizedMessage::new("log-city-bombard")
+                    .with_arg("city", self.city_name(*city_id))
+                    .with_arg("player", self.player_name(target.owner))
+                    .with_arg("unit", format!("{:?}", target.unit_type))
+                    .with_arg("damage", damage_to(*target_id));
+                let mut entities: Vec<EntityRef> =
+                    owner.map(EntityRef::Player).into_iter().collect();
+                entities.extend([
+                    EntityRef::Player(target.owner),
+                    EntityRef::City(*city_id),
+                    EntityRef::Unit(*target_id),
+                ]);
+                self.push(
+                    LogCategory::Combat,
+                    message,
+                    entities,
+                    Some(target.position),
+                );
+                if !self.state.units.contains_key(target_id) {
+                    self.unit_lost(*target_id, target);
+                }
+            }
+            GameAction::DeclareWar { target_player } => {
+                let message = LocalizedMessage::new("log-war-declared")
+                    .with_arg("player", self.player_name(player_id))
+                    .with_arg("target", self.player_name(*target_player));
+                self.push(
+                    LogCategory::Diplomacy,
+                    message,
+                    vec![
+                        EntityRef::Player(player_id),
+                        EntityRef::Player(*target_player),
+                    ],
+                    None,
+                );
+            }
+            GameAction::AcceptPeace { from_player } => {
+                let message = LocalizedMessage::new("log-peace-made")
+                    .with_arg("player", self.player_name(player_id))
+                    .with_arg("target", self.player_name(*from_player));
+                self.push(
+                    LogCategory::Diplomacy,
+                    message,
+                    vec![
+                        EntityRef::Player(player_id),
+                        EntityRef::Player(*from_player),
+                    ],
+                    None,
+                );
+            }
+            GameAction::RespondTrade {
+                from_player,
+                accept: true,
+                ..
+            } => {
+                let message = LocalizedMessage::new("log-trade-completed")
+                    .with_arg("player", self.player_name(player_id))
+                    .with_arg("target", self.player_name(*from_player));
+                self.push(
+                    LogCategory::Diplomacy,
+                    message,
+                    vec![
+                        EntityRef::Player(player_id),
+                        EntityRef::Player(*from_player),
+                    ],
+                    None,
+                );
+            }
+            _ => {}
+        }
+    }
+
+    fn unit_lost(&mut self, unit_id: UnitId, info: UnitInfo) {
+        let message = LocalizedMessage::new("log-unit-destroyed")
+            .with_arg("player", self.player_name(info.owner))
+            .with_arg("unit", format!("{:?}", info.unit_type));
+        self.push(
+            LogCategory::Combat,
+            message,
+            vec![EntityRef::Player(info.owner), EntityRef::Unit(unit_id)],
+            Some(info.position),
+        );
+    }
+
+    /// Entries for side effects that aren't covered by the action's own
+    /// entry.
+    fn effect(&mut self, player_id: PlayerSlot, action: &GameAction, effect: &ActionEffect) {
+        let owner_of = |city_id: &CityId| {
+            self.state
+                .cities
+                .get(city_id)
+                .map(|c| c.owner)
+                .unwrap_or(player_id)
+        };
+        match effect {
+            ActionEffect::CityFounded {
+                city_id,
+                name,
+                position,
+            } => {
+                let owner = owner_of(city_id);
+                let message = LocalizedMessage::new("log-city-founded")
+                    .with_arg("player", self.player_name(owner))
+                    .with_arg("city", name);
+                self.push(
+                    LogCategory::City,
+                    message,
+                    vec![
+                        EntityRef::Player(owner),
+                        EntityRef::City(*city_id),
+                        EntityRef::Tile(*position),
+                    ],
+                    Some(*position),
+                );
+            }
+            ActionEffect::CityCaptured {
+                city_id,
+                player_id,
+                previous_owner,
+                ..
+            } => {
+                let message = LocalizedMessage::new("log-city-captured")
+                    .with_arg("player", self.player_name(*player_id))
+                    .with_arg("city", self.city_name(*city_id))
+                    .with_arg("previous", self.player_name(*previous_owner));
+                let position = self.city_position(*city_id);
+                self.push(
+                    LogCategory::City,
+                    message,
+                    vec![
+                        EntityRef::Player(*player_id),
+                        EntityRef::Player(*previous_owner),
+                        EntityRef::City(*city_id),
+                    ],
+                    position,
+                );
+            }
+            ActionEffect::CityRazed { city_id } => {
+                let owner = self
+                    .context
+                    .cities
+                    .get(city_id)
+                    .map(|(owner, _)| *owner)
+                    .unwrap_or(player_id);
+                let message = LocalizedMessage::new("log-city-razed")
+                    .with_arg("player", self.player_name(player_id))
+                    .with_arg("city", self.city_name(*city_id));
+                let mut entities = vec![EntityRef::Player(player_id)];
+                if owner != player_id {
+                    entities.push(EntityRef::Player(owner));
+                }
+                entities.push(EntityRef::City(*city_id));
+                self.push(LogCategory::City, message, entities, None);
+            }
+            ActionEffect::BuildingCompleted { city_id, building } => {
+                let owner = owner_of(city_id);
+                let message = LocalizedMessage::new("log-building-completed")
+                    .with_arg("city", self.city_name(*city_id))
+                    .with_arg("building", format!("{:?}", building));
+                let position = self.city_position(*city_id);
+                self.push(
+                    LogCategory::City,
+                    message,
+                    vec![EntityRef::Player(owner), EntityRef::City(*city_id)],
+                    position,
+                );
+            }
+            ActionEffect::WonderCompleted {
+                city_id,
+                player_id,
+                wonder,
+            } => {
+                let message = LocalizedMessage::new("log-wonder-completed")
+                    .with_arg("player", self.player_name(*player_id))
+                    .with_arg("city", self.city_name(*city_id))
+                    .with_arg("wonder", format!("{:?}", wonder));
+                let position = self.city_position(*city_id);
+                self.push(
+                    LogCategory::City,
+                    message,
+                    vec![EntityRef::Player(*player_id), EntityRef::City(*city_id)],
+                    position,
+                );
+            }
+            // Units made by cities; starting units aren't news
+            ActionEffect::UnitCreated {
+                unit_id,
+                unit_type,
+                position,
+            } if matches!(action, GameAction::EndTurn | GameAction::BuyItem { .. }) => {
+                let message = LocalizedMessage::new("log-unit-trained")
+                    .with_arg("player", self.player_name(player_id))
+                    .with_arg("unit", format!("{:?}", unit_type));
+                self.push(
+                    LogCategory::City,
+                    message,
+                    vec![EntityRef::Player(player_id), EntityRef::Unit(*unit_id)],
+                    Some(*position),
+                );
+            }
+            // Units disbanded by upkeep; combat losses are logged with the
+            // attack
+            ActionEffect::UnitDestroyed { unit_id } if matches!(action, GameAction::EndTurn) => {
+                let message = LocalizedMessage::new("log-unit-disbanded")
+                    .with_arg("player", self.player_name(player_id));
+                self.push(
+                    LogCategory::Combat,
+                    message,
+                    vec![EntityRef::Player(player_id), EntityRef::Unit(*unit_id)],
+                    None,
+                );
+            }
+            ActionEffect::UnitPromoted {
+                unit_id, promotion, ..
+            } => {
+                let message = LocalizedMessage::new("log-unit-promoted")
+                    .with_arg("player", self.player_name(player_id))
+                    .with_arg("promotion", format!("{:?}", promotion));
+                let position = self.state.units.get(unit_id).map(|u| u.position);
+                self.push(
+                    LogCategory::Combat,
+                    message,
+                    vec![EntityRef::Player(player_id), EntityRef::Unit(*unit_id)],
+                    position,
+                );
+            }
+            ActionEffect::TechResearched { player_id, tech_id } => {
+                let message = LocalizedMessage::new("log-tech-researched")
+                    .with_arg("player", self.player_name(*player_id))
+                    .with_arg("tech", tech_id);
+                self.push(
+                    LogCategory::Research,
+                    message,
+                    vec![EntityRef::Player(*player_id)],
+                    None,
+                );
+            }
+            ActionEffect::EraEntered { player_id, era } => {
+                let message = LocalizedMessage::new("log-era-entered")
+                    .with_arg("player", self.player_name(*player_id))
+                    .with_arg("era", era);
+                self.push(
+                    LogCategory::Research,
+                    message,
+                    vec![EntityRef::Player(*player_id)],
+                    None,
+                );
+            }
+            ActionEffect::FirstContact {
+                player_id,
+                met_player,
+            } => {
+                let message = LocalizedMessage::new("log-first-contact")
+                    .with_arg("player", self.player_name(*player_id))
+                    .with_arg("target", self.player_name(*met_player));
+                self.push(
+                    LogCategory::Diplomacy,
+                    message,
+                    vec![
+                        EntityRef::Player(*player_id),
+                        EntityRef::Player(*met_player),
+                    ],
+                    None,
+                );
+            }
+            ActionEffect::Circumnavigated { player_id, gold } => {
+                let message = LocalizedMessage::new("log-circumnavigated")
+                    .with_arg("player", self.player_name(*player_id))
+                    .with_arg("gold", *gold);
+                self.push(
+                    LogCategory::Game,
+                    message,
+                    vec![EntityRef::Player(*player_id)],
+                    None,
+                );
+            }
+            ActionEffect::PlayerConceded {
+                player_id,
+                to_player,
+            } => {
+                let message = LocalizedMessage::new("log-player-conceded")
+                    .with_arg("player", self.player_name(*player_id));
+                let mut entities = vec![EntityRef::Player(*player_id)];
+                entities.extend(to_player.map(EntityRef::Player));
+                self.push(LogCategory::Game, message, entities, None);
+            }
+            ActionEffect::PlayerDefeated { player_id, .. } => {
+                let message = LocalizedMessage::new("log-player-defeated")
+                    .with_arg("player", self.player_name(*player_id));
+                self.push(
+                    LogCategory::Game,
+                    message,
+                    vec![EntityRef::Player(*player_id)],
+                    None,
+                );
+            }
+            ActionEffect::GameEnded {
+                winner_id,
+                victory_type,
+            } => {
+                let message = LocalizedMessage::new("log-game-ended")
+                    .with_arg("player", self.player_name(*winner_id))
+                    .with_arg("victory", victory_type);
+                let entities = self
+                    .state
+                    .players
+                    .iter()
+                    .map(|p| EntityRef::Player(p.id))
+                    .collect();
+                self.push(LogCategory::Game, message, entities, None);
+            }
+            _ => {}
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::contact;
+    use crate::replay::GameEngine;
+    use crate::settings::GameSettings;
+    use crate::types::MapSize;
+
+    fn started_duel() -> GameEngine {
+        let mut settings = GameSettings::new("Test".to_string());
+        settings.map_size = MapSize::Duel;
+        let mut engine = GameEngine::new(settings, [42u8; 32]);
+        for (id, civ) in [(0, "rome"), (1, "egypt")] {
+            engine
+                .apply_action(
+                    PlayerSlot(id),
+                    &GameAction::JoinGame {
+                        player_name: format!("P{}", id),
+                        civilization_id: civ.to_string(),
+                    },
+                )
+                .unwrap();
+        }
+        engine
+            .apply_action(PlayerSlot(0), &GameAction::StartGame)
+            .unwrap();
+        contact::meet(&mut engine.state, PlayerSlot(0), PlayerSlot(1));
+        engine
+    }
+
+    fn entry(id: u64, turn: u32, category: LogCategory, entities: Vec<EntityRef>) -> LogEntry {
+        LogEntry {
+            id,
+            turn,
+            category,
+            message: LocalizedMessage::new("test"),
+            entities,
+            position: None,
+            action: 0,
+        }
+    }
+
+    // ==================== Ring Buffer Tests ====================
+
+    #[test]
+    fn test_ring_buffer_keeps_newest() {
+        let mut log = GameLog::new(3);
+        for turn in 1..=5 {
+            log.push(
+                turn,
+                LogCategory::Game,
+                LocalizedMessage::new("test"),
+                vec![],
+                None,
+            );
+        }
+
+        let turns: Vec<u32> = log.entries().map(|e| e.turn).collect();
+        assert_eq!(turns, vec![3, 4, 5]);
+        assert_eq!(log.entries().last().unwrap().id, 5);
+    }
+
+    #[test]
+    fn test_filter() {
+        let unit = EntityRef::Unit(UnitId(7));
+        let combat = entry(
+            1,
+            2,
+            LogCategory::Combat,
+            vec![EntityRef::Player(PlayerSlot(0)), unit],
+        );
+        let research = entry(
+            2,
+            5,
+            LogCategory::Research,
+            vec![EntityRef::Player(PlayerSlot(1))],
+        );
+
+        let filter = LogFilter {
+            categories: vec![LogCategory::Combat],
+            ..LogFilter::default()
+        };
+        assert!(filter.matches(&combat));
+        assert!(!filter.matches(&research));
+
+        let filter = LogFilter {
+            player: Some(PlayerSlot(1)),
+            ..LogFilter::default()
+        };
+        assert!(!filter.matches(&combat));
+        assert!(filter.matches(&research));
+
+        let filter = LogFilter {
+            entity: Some(unit),
+            to_turn: Some(2),
+            ..LogFilter::default()
+        };
+        assert!(filter.matches(&combat));
+        assert!(!filter.matches(&research));
+
+        let filter = LogFilter {
+            after_id: Some(1),
+            from_turn: Some(3),
+            ..LogFilter::default()
+        };
+        assert!(!filter.matches(&combat));
+        assert!(filter.matches(&research));
+    }
+
+    #[test]
+    fn test_query_newest_first_with_limit() {
+        let mut log = GameLog::default();
+        for turn in 1..=4 {
+            log.push(
+                turn,
+                LogCategory::Game,
+                LocalizedMessage::new("test"),
+                vec![],
+                None,
+            );
+        }
+
+        let filter = LogFilter {
+            limit: Some(2),
+            ..LogFilter::default()
+        };
+        let turns: Vec<u32> = log.query(&filter).into_iter().map(|e| e.turn).collect();
+        assert_eq!(turns, vec![4, 3]);
+    }
+
+    // ==================== Engine Tests ====================
+
+    #[test]
+    fn test_engine_logs_war_and_combat() {
+        let mut engine = started_duel();
+        let warrior = engine
+            .state
+            .units
+            .values()
+            .find(|u| u.owner == PlayerSlot(0) && u.unit_type == UnitType::Warrior)
+            .cloned()
+            .unwrap();
+        let enemy = engine
+            .state
+            .units
+            .values()
+            .find(|u| u.owner == PlayerSlot(1) && u.unit_type == UnitType::Warrior)
+            .map(|u| u.id)
+            .unwrap();
+        let target = warrior
+            .position
+            .neighbors()
+            .into_iter()
+            .find(|n| {
+                engine.state.map.get(n).is_some()
+                    && !engine.state.units.values().any(|u| u.position == *n)
+            })
+            .unwrap();
+        engine.state.units.get_mut(&enemy).unwrap().position = target;
+
+        engine
+            .apply_action(
+                PlayerSlot(0),
+                &GameAction::DeclareWar {
+                    target_player: PlayerSlot(1),
+                },
+            )
+            .unwrap();
+        engine
+            .apply_action(
+                PlayerSlot(0),
+                &GameAction::AttackUnit {
+                    attacker_id: warrior.id,
+                    defender_id: enemy,
+                    random: 0.5,
+                },
+            )
+            .unwrap();
+
+        let entries = engine.log.query(&LogFilter::default());
+        assert_eq!(entries[entries.len() - 1].message.key, "log-war-declared");
+        let combat = entries
+            .iter()
+            .find(|e| e.message.key == "log-combat-unit")
+            .unwrap();
+        assert_eq!(combat.category, LogCategory::Combat);
+        assert_eq!(combat.position, Some(target));
+        assert!(combat.involves(PlayerSlot(1)));
+        assert!(combat.refers_to(&EntityRef::Unit(enemy)));
+        assert_eq!(combat.message.args["player"], "P0");
+    }
+
+    #[test]
+    fn test_undo_takes_back_entries() {
+        let mut engine = started_duel();
+        let settler = engine
+            .state
+            .units
+            .values()
+            .find(|u| u.owner == PlayerSlot(0) && u.unit_type == UnitType::Settler)
+            .map(|u| u.id)
+            .unwrap();
+        let before = engine.log.len();
+
+        engine
+            .submit_action(
+                PlayerSlot(0),
+                &GameAction::FoundCity {
+                    settler_id: settler,
+                    name: "Rome".to_string(),
+                },
+            )
+            .unwrap();
+        let founded = engine.log.query(&LogFilter::default());
+        assert_eq!(founded[0].message.key, "log-city-founded");
+        assert_eq!(founded[0].category, LogCategory::City);
+
+        engine.undo().unwrap();
+        assert_eq!(engine.log.len(), before);
+    }
+}
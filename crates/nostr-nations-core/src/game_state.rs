@@ -1,13 +1,26 @@
 //! Root game state containing all game data.
 
-use crate::city::City;
+use crate::ai::{self, Persona};
+use crate::city::{City, WonderType};
+use crate::cow::Shared;
 use crate::map::Map;
+use crate::parallel::par_map;
+use crate::pathfinding::PathConfig;
+use crate::pause::PauseState;
 use crate::player::Player;
-use crate::settings::GameSettings;
-use crate::types::{CityId, EventId, GameId, PlayerId, UnitId, VictoryType};
-use crate::unit::Unit;
+use crate::roads;
+use crate::scenario::ScenarioProgress;
+use crate::settings::{BarbarianAggression, DifficultyModifiers, GameSettings};
+use crate::terraform::GlobalWarming;
+use crate::trading::TradeManager;
+use crate::types::{CityId, EventId, GameId, PlayerSlot, UnitId, VictoryType};
+use crate::unit::{HealingSite, Promotion, Unit, UnitTurnContext};
+use crate::wonders::BuiltWonder;
+use crate::yields::Yields;
+use schemars::JsonSchema;
 use serde::{Deserialize, Serialize};
-use std::collections::HashMap;
+use std::collections::{HashMap, HashSet};
+use ts_rs::TS;
 
 /// The complete state of a game at any point in time.
 ///
@@ -24,17 +37,20 @@ pub struct GameState {
     /// Current turn number (starts at 1).
     pub turn: u32,
     /// Which player's turn it currently is.
-    pub current_player: PlayerId,
+    pub current_player: PlayerSlot,
     /// All players in the game.
     pub players: Vec<Player>,
     /// The game map.
     pub map: Map,
     /// All units in the game, indexed by ID.
-    pub units: HashMap<UnitId, Unit>,
+    pub units: Shared<HashMap<UnitId, Unit>>,
     /// All cities in the game, indexed by ID.
-    pub cities: HashMap<CityId, City>,
+    pub cities: Shared<HashMap<CityId, City>>,
     /// Diplomatic relationships.
     pub diplomacy: DiplomacyState,
+    /// Trade offers between players.
+    #[serde(default)]
+    pub trades: TradeManager,
     /// Random seed for deterministic replay.
     pub seed: [u8; 32],
     /// Chain of Nostr event IDs for validation.
@@ -46,7 +62,26 @@ pub struct GameState {
     /// Game phase.
     pub phase: GamePhase,
     /// Victor (if game has ended).
-    pub winner: Option<(PlayerId, VictoryType)>,
+    pub winner: Option<(PlayerSlot, VictoryType)>,
+    /// Whether play is paused.
+    #[serde(default)]
+    pub pause: PauseState,
+    /// Whether the map and all players' stats have been revealed after
+    /// the game ended.
+    #[serde(default)]
+    pub revealed: bool,
+    /// World wonders built so far.
+    #[serde(default)]
+    pub wonders: HashMap<WonderType, BuiltWonder>,
+    /// Progress toward the settings' scenario rules.
+    #[serde(default)]
+    pub scenario: ScenarioProgress,
+    /// Pollution and climate change.
+    #[serde(default)]
+    pub warming: GlobalWarming,
+    /// First player to circumnavigate the world, if anyone has.
+    #[serde(default)]
+    pub circumnavigated_by: Option<PlayerSlot>,
 }
 
 impl GameState {
@@ -57,21 +92,45 @@ impl GameState {
             id,
             settings,
             turn: 0, // Will be 1 when game starts
-            current_player: 0,
+            current_player: PlayerSlot(0),
             players: Vec::new(),
             map: Map::new(width, height, false),
-            units: HashMap::new(),
-            cities: HashMap::new(),
+            units: Shared::default(),
+            cities: Shared::default(),
             diplomacy: DiplomacyState::default(),
+            trades: TradeManager::new(),
             seed,
             event_chain: Vec::new(),
-            next_unit_id: 1,
-            next_city_id: 1,
+            next_unit_id: UnitId(1),
+            next_city_id: CityId(1),
             phase: GamePhase::Setup,
             winner: None,
+            pause: PauseState::default(),
+            revealed: false,
+            wonders: HashMap::new(),
+            scenario: ScenarioProgress::default(),
+            warming: GlobalWarming::default(),
+            circumnavigated_by: None,
         }
     }
 
+    /// Fork a hypothetical copy of this state.
+    ///
+    /// The map, units and cities are shared with the original and only
+    /// copied when the fork first modifies them, so snapshots for AI search
+    /// and action previews are cheap.
+    pub fn snapshot(&self) -> GameState {
+        self.clone()
+    }
+
+    /// Check if a snapshot still shares all of its large collections with
+    /// this state.
+    pub fn shares_storage_with(&self, other: &GameState) -> bool {
+        Shared::ptr_eq(&self.map.tiles, &other.map.tiles)
+            && Shared::ptr_eq(&self.units, &other.units)
+            && Shared::ptr_eq(&self.cities, &other.cities)
+    }
+
     /// Add a player to the game.
     pub fn add_player(&mut self, player: Player) -> Result<(), GameError> {
         if self.phase != GamePhase::Setup {
@@ -97,22 +156,53 @@ impl GameState {
         }
         self.phase = GamePhase::Playing;
         self.turn = 1;
-        self.current_player = 0;
+        self.current_player = PlayerSlot(0);
 
         // Initialize diplomacy for all player pairs
         self.diplomacy.initialize(&self.players);
 
+        for player in &mut self.players {
+            player.era = self.settings.starting_era;
+        }
+
         Ok(())
     }
 
+    /// The host player: the one marked as host, or the first player.
+    pub fn host(&self) -> PlayerSlot {
+        self.players
+            .iter()
+            .find(|p| p.is_host)
+            .map(|p| p.id)
+            .unwrap_or(PlayerSlot(0))
+    }
+
+    /// Find the seat currently controlled by a key.
+    ///
+    /// After a substitution only the substitute's key maps to the seat.
+    pub fn player_for_pubkey(&self, pubkey: &str) -> Option<PlayerSlot> {
+        self.players
+            .iter()
+            .find(|p| p.pubkey == pubkey)
+            .map(|p| p.id)
+    }
+
     /// Get a player by ID.
-    pub fn get_player(&self, id: PlayerId) -> Option<&Player> {
-        self.players.get(id as usize)
+    pub fn get_player(&self, id: PlayerSlot) -> Option<&Player> {
+        self.players.get(id.index())
     }
 
     /// Get a mutable player by ID.
-    pub fn get_player_mut(&mut self, id: PlayerId) -> Option<&mut Player> {
-        self.players.get_mut(id as usize)
+    pub fn get_player_mut(&mut self, id: PlayerSlot) -> Option<&mut Player> {
+        self.players.get_mut(id.index())
+    }
+
+    /// Check whether two players have met.
+    pub fn has_met(&self, a: PlayerSlot, b: PlayerSlot) -> bool {
+        a == b
+            || self
+                .get_player(a)
+                .is_some_and(|p| p.met_players.contains(&b))
     }
 
     /// Get the current player.
@@ -132,10 +222,10 @@ impl GameState {
         }
 
         // Find next non-eliminated player
-        let mut next = (self.current_player + 1) % self.players.len() as u8;
+        let mut next = self.current_player.after(self.players.len());
         let mut attempts = 0;
-        while self.players[next as usize].eliminated && attempts < self.players.len() {
-            next = (next + 1) % self.players.len() as u8;
+        while self.players[next.index()].eliminated && attempts < self.players.len() {
+            next = next.after(self.players.len());
             attempts += 1;
         }
 
@@ -182,14 +272,14 @@ impl GameState {
     /// Allocate a new unit ID.
     pub fn allocate_unit_id(&mut self) -> UnitId {
         let id = self.next_unit_id;
-        self.next_unit_id += 1;
+        self.next_unit_id = id.next();
         id
     }
 
     /// Allocate a new city ID.
     pub fn allocate_city_id(&mut self) -> CityId {
         let id = self.next_city_id;
-        self.next_city_id += 1;
+        self.next_city_id = id.next();
         id
     }
 
@@ -209,9 +299,141 @@ impl GameState {
     }
 
     /// Check if it's a specific player's turn.
-    pub fn is_player_turn(&self, player_id: PlayerId) -> bool {
+    pub fn is_player_turn(&self, player_id: PlayerSlot) -> bool {
         self.phase == GamePhase::Playing && self.current_player == player_id
     }
+
+    /// Check if a seat is controlled by the AI.
+    pub fn is_ai_player(&self, player_id: PlayerSlot) -> bool {
+        self.settings.ai_players.contains(&player_id)
+    }
+
+    /// The persona an AI seat plays, or `None` for human seats.
+    pub fn ai_persona(&self, player_id: PlayerSlot) -> Option<Persona> {
+        if !self.is_ai_player(player_id) {
+            return None;
+        }
+        Some(
+            self.settings
+                .ai_personas
+                .get(&player_id)
+                .copied()
+                .unwrap_or_else(|| ai::assign_persona(&self.seed, player_id)),
+        )
+    }
+
+    /// Get the difficulty modifiers that apply to a player.
+    pub fn difficulty_modifiers(&self, player_id: PlayerSlot) -> DifficultyModifiers {
+        self.settings
+            .difficulty
+            .modifiers(self.is_ai_player(player_id))
+    }
+
+    /// Get barbarian behavior for this game, or `None` if barbarians are off.
+    pub fn barbarian_aggression(&self) -> Option<BarbarianAggression> {
+        self.settings
+            .barbarians
+            .then(|| self.settings.difficulty.barbarian_aggression())
+    }
+
+    /// Get a city's yields with the owner's difficulty modifiers applied.
+    pub fn city_yields(&self, city_id: CityId) -> Option<Yields> {
+        let city = self.cities.get(&city_id)?;
+        Some(self.yields_for(city, roads::is_connected_to_capital(self, city)))
+    }
+
+    /// Get the yields of all of a player's cities, sorted by city ID.
+    ///
+    /// Cities are evaluated in parallel; the road network is only walked
+    /// once for the whole player.
+    pub fn city_yield_table(&self, player_id: PlayerSlot) -> Vec<(CityId, Yields)> {
+        let mut ids: Vec<CityId> = self
+            .cities
+            .values()
+            .filter(|c| c.owner == player_id)
+            .map(|c| c.id)
+            .collect();
+        ids.sort_unstable();
+        let connected: HashSet<CityId> = roads::connected_cities(self, player_id)
+            .into_iter()
+            .collect();
+
+        let yields = par_map(&ids, |id| {
+            self.yields_for(&self.cities[id], connected.contains(id))
+        });
+        ids.into_iter().zip(yields).collect()
+    }
+
+    fn yields_for(&self, city: &City, connected: bool) -> Yields {
+        let mut base = city
+            .calculate_yields(|coord| self.map.get(coord).map(|t| t.yields()).unwrap_or_default());
+        if connected {
+            base.gold += roads::connection_gold(city);
+        }
+        self.difficulty_modifiers(city.owner).apply_yields(base)
+    }
+
+    /// Get the strongest military unit the owner keeps in a city.
+    pub fn city_garrison(&self, city: &City) -> Option<&Unit> {
+        self.units
+            .values()
+            .filter(|u| u.owner == city.owner && u.position == city.position && u.is_military())
+            .max_by_key(|u| (u.effective_combat_strength(), std::cmp::Reverse(u.id)))
+    }
+
+    /// Get the pathfinding config for a unit.
+    ///
+    /// Units may enter their own territory, that of allies and open-borders
+    /// partners, and that of players they are at war with; every other
+    /// player's borders are closed to them.
+    pub fn path_config(&self, unit: &Unit) -> PathConfig {
+        let mut config = PathConfig::for_unit(unit);
+        config.closed_borders = self
+            .players
+            .iter()
+            .map(|p| p.id)
+            .filter(|&other| {
+                !self.diplomacy.can_units_pass(unit.owner, other)
+                    && !self.diplomacy.are_at_war(unit.owner, other)
+            })
+            .collect();
+        config
+    }
+
+    /// Get the surroundings that affect a unit at the start of its turn.
+    pub fn unit_turn_context(&self, unit: &Unit) -> UnitTurnContext {
+        let garrisoned = self
+            .cities
+            .values()
+            .any(|c| c.owner == unit.owner && c.position == unit.position);
+        let site = if garrisoned {
+            HealingSite::City
+        } else {
+            match self.map.get(&unit.position).and_then(|t| t.owner) {
+                Some(owner) if owner == unit.owner => HealingSite::Friendly,
+                Some(_) => HealingSite::Enemy,
+                None => HealingSite::Neutral,
+            }
+        };
+        let medic_nearby = self.units.values().any(|u| {
+            u.owner == unit.owner
+                && u.promotions.contains(&Promotion::Medic)
+                && u.position.distance(&unit.position) <= 1
+        });
+
+        UnitTurnContext {
+            site,
+            medic_nearby,
+            garrisoned,
+        }
+    }
+
+    /// Get a player's total yields across all of their cities.
+    pub fn player_yields(&self, player_id: PlayerSlot) -> Yields {
+        self.city_yield_table(player_id)
+            .into_iter()
+            .fold(Yields::default(), |acc, (_, y)| acc + y)
+    }
 }
 
 /// Phases of the game.
@@ -232,7 +454,10 @@ pub struct DiplomacyState {
     /// Relationships between player pairs.
     /// Serialized as a sequence of key-value pairs since JSON requires string keys.
     #[serde(with = "tuple_key_map")]
-    pub relationships: HashMap<(PlayerId, PlayerId), Relationship>,
+    pub relationships: HashMap<(PlayerSlot, PlayerSlot), Relationship>,
+    /// Outstanding peace proposals as (from, to) pairs.
+    #[serde(default)]
+    pub peace_proposals: Vec<(PlayerSlot, PlayerSlot)>,
 }
 
 /// Custom serialization module for HashMap with tuple keys.
@@ -242,7 +467,7 @@ mod tuple_key_map {
     use serde::{Deserializer, Serializer};
 
     pub fn serialize<S>(
-        map: &HashMap<(PlayerId, PlayerId), Relationship>,
+        map: &HashMap<(PlayerSlot, PlayerSlot), Relationship>,
         serializer: S,
     ) -> Result<S::Ok, S::Error>
     where
@@ -258,11 +483,11 @@ mod tuple_key_map {
 
     pub fn deserialize<'de, D>(
         deserializer: D,
-    ) -> Result<HashMap<(PlayerId, PlayerId), Relationship>, D::Error>
+    ) -> Result<HashMap<(PlayerSlot, PlayerSlot), Relationship>, D::Error>
     where
         D: Deserializer<'de>,
     {
-        let pairs: Vec<((PlayerId, PlayerId), Relationship)> =
+        let pairs: Vec<((PlayerSlot, PlayerSlot), Relationship)> =
             Deserialize::deserialize(deserializer)?;
         Ok(pairs.into_iter().collect())
     }
@@ -298,19 +523,31 @@ impl DiplomacyState {
     }
 
     /// Get the relationship between two players.
-    pub fn get(&self, a: PlayerId, b: PlayerId) -> Option<&Relationship> {
+    pub fn get(&self, a: PlayerSlot, b: PlayerSlot) -> Option<&Relationship> {
         let key = if a < b { (a, b) } else { (b, a) };
         self.relationships.get(&key)
     }
 
     /// Get mutable relationship between two players.
-    pub fn get_mut(&mut self, a: PlayerId, b: PlayerId) -> Option<&mut Relationship> {
+    pub fn get_mut(&mut self, a: PlayerSlot, b: PlayerSlot) -> Option<&mut Relationship> {
         let key = if a < b { (a, b) } else { (b, a) };
         self.relationships.get_mut(&key)
     }
 
     /// Declare war between two players. Breaks all treaties and sets status to War.
-    pub fn declare_war(&mut self, a: PlayerId, b: PlayerId, turn: u32) {
+    pub fn declare_war(&mut self, a: PlayerSlot, b: PlayerSlot, turn: u32) {
+        self.declare_war_with_penalty(a, b, turn, WAR_DECLARATION_SCORE_PENALTY);
+    }
+
+    /// Declare war with a custom relationship penalty, such as one scaled
+    /// by the aggressor's era.
+    pub fn declare_war_with_penalty(
+        &mut self,
+        a: PlayerSlot,
+        b: PlayerSlot,
+        turn: u32,
+        penalty: i32,
+    ) {
         if let Some(rel) = self.get_mut(a, b) {
             // Can't declare war if already at war
             if rel.status == DiplomaticStatus::War {
@@ -322,13 +559,12 @@ impl DiplomacyState {
             rel.turns_at_war = 0;
             rel.turns_at_peace = 0;
             rel.last_interaction_turn = turn;
-            rel.relationship_score =
-                (rel.relationship_score + WAR_DECLARATION_SCORE_PENALTY).clamp(-100, 100);
+            rel.relationship_score = (rel.relationship_score + penalty).clamp(-100, 100);
         }
     }
 
     /// Make peace between two players. Sets status to Neutral and adds Peace treaty.
-    pub fn make_peace(&mut self, a: PlayerId, b: PlayerId, turn: u32) {
+    pub fn make_peace(&mut self, a: PlayerSlot, b: PlayerSlot, turn: u32) {
         if let Some(rel) = self.get_mut(a, b) {
             // Can only make peace if at war
             if rel.status != DiplomaticStatus::War {
@@ -349,6 +585,42 @@ impl DiplomacyState {
         }
     }
 
+    /// Record a peace proposal from one player to another.
+    /// Returns false if the players are not at war.
+    pub fn propose_peace(&mut self, from: PlayerSlot, to: PlayerSlot) -> bool {
+        if !self.are_at_war(from, to) {
+            return false;
+        }
+        if !self.has_peace_proposal(from, to) {
+            self.peace_proposals.push((from, to));
+        }
+        true
+    }
+
+    /// Check if a peace proposal from one player to another is outstanding.
+    pub fn has_peace_proposal(&self, from: PlayerSlot, to: PlayerSlot) -> bool {
+        self.peace_proposals.contains(&(from, to))
+    }
+
+    /// Accept an outstanding peace proposal, making peace.
+    /// Returns false if there was no such proposal.
+    pub fn accept_peace(&mut self, from: PlayerSlot, to: PlayerSlot, turn: u32) -> bool {
+        if !self.withdraw_peace_proposal(from, to) {
+            return false;
+        }
+        self.peace_proposals
+            .retain(|&(a, b)| !(a == to && b == from));
+        self.make_peace(from, to, turn);
+        true
+    }
+
+    /// Remove an outstanding peace proposal. Returns true if one was removed.
+    pub fn withdraw_peace_proposal(&mut self, from: PlayerSlot, to: PlayerSlot) -> bool {
+        let initial_len = self.peace_proposals.len();
+        self.peace_proposals.retain(|&p| p != (from, to));
+        self.peace_proposals.len() < initial_len
+    }
+
     /// Propose a treaty between two players. Returns true if treaty was accepted.
     ///
     /// Treaty requirements:
@@ -357,8 +629,8 @@ impl DiplomacyState {
     /// - DefensivePact requires Allied status
     pub fn propose_treaty(
         &mut self,
-        a: PlayerId,
-        b: PlayerId,
+        a: PlayerSlot,
+        b: PlayerSlot,
         treaty_type: TreatyType,
         turn: u32,
     ) -> bool {
@@ -407,7 +679,13 @@ impl DiplomacyState {
     }
 
     /// Break a treaty between two players.
-    pub fn break_treaty(&mut self, a: PlayerId, b: PlayerId, treaty_type: TreatyType, turn: u32) {
+    pub fn break_treaty(
+        &mut self,
+        a: PlayerSlot,
+        b: PlayerSlot,
+        treaty_type: TreatyType,
+        turn: u32,
+    ) {
         if let Some(rel) = self.get_mut(a, b) {
             if rel.remove_treaty(treaty_type) {
                 rel.last_interaction_turn = turn;
@@ -418,14 +696,14 @@ impl DiplomacyState {
     }
 
     /// Check if two players have a specific treaty.
-    pub fn has_treaty(&self, a: PlayerId, b: PlayerId, treaty_type: TreatyType) -> bool {
+    pub fn has_treaty(&self, a: PlayerSlot, b: PlayerSlot, treaty_type: TreatyType) -> bool {
         self.get(a, b)
             .map(|rel| rel.has_treaty(treaty_type))
             .unwrap_or(false)
     }
 
     /// Check if two players are at war.
-    pub fn are_at_war(&self, a: PlayerId, b: PlayerId) -> bool {
+    pub fn are_at_war(&self, a: PlayerSlot, b: PlayerSlot) -> bool {
         self.get(a, b)
             .map(|rel| rel.status == DiplomaticStatus::War)
             .unwrap_or(false)
@@ -433,7 +711,7 @@ impl DiplomacyState {
 
     /// Check if units from player a can pass through player b's territory.
     /// Returns true if they are allied or have an open borders treaty.
-    pub fn can_units_pass(&self, a: PlayerId, b: PlayerId) -> bool {
+    pub fn can_units_pass(&self, a: PlayerSlot, b: PlayerSlot) -> bool {
         if a == b {
             return true;
         }
@@ -468,7 +746,7 @@ impl DiplomacyState {
 
     /// Modify the relationship score between two players.
     /// Score is clamped to -100..=100.
-    pub fn modify_relationship_score(&mut self, a: PlayerId, b: PlayerId, delta: i32) {
+    pub fn modify_relationship_score(&mut self, a: PlayerSlot, b: PlayerSlot, delta: i32) {
         if let Some(rel) = self.get_mut(a, b) {
             rel.relationship_score = (rel.relationship_score + delta).clamp(-100, 100);
         }
@@ -476,7 +754,7 @@ impl DiplomacyState {
 
     /// Get the relationship score between two players.
     /// Returns 0 if no relationship exists.
-    pub fn get_relationship_score(&self, a: PlayerId, b: PlayerId) -> i32 {
+    pub fn get_relationship_score(&self, a: PlayerSlot, b: PlayerSlot) -> i32 {
         self.get(a, b)
             .map(|rel| rel.relationship_score)
             .unwrap_or(0)
@@ -484,13 +762,13 @@ impl DiplomacyState {
 
     /// Check if the relationship score suggests war is likely.
     /// Used for AI decision making.
-    pub fn is_war_likely(&self, a: PlayerId, b: PlayerId) -> bool {
+    pub fn is_war_likely(&self, a: PlayerSlot, b: PlayerSlot) -> bool {
         self.get_relationship_score(a, b) < WAR_LIKELIHOOD_THRESHOLD
     }
 
     /// Check if the relationship score allows friendly treaties.
     /// Used for AI and UI decisions.
-    pub fn can_propose_friendly_treaty(&self, a: PlayerId, b: PlayerId) -> bool {
+    pub fn can_propose_friendly_treaty(&self, a: PlayerSlot, b: PlayerSlot) -> bool {
         let score = self.get_relationship_score(a, b);
         score >= FRIENDLY_TREATY_THRESHOLD
     }
@@ -574,7 +852,7 @@ pub enum DiplomaticStatus {
 }
 
 /// Types of treaties that can be signed between players.
-#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize, Deserialize)]
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize, Deserialize, JsonSchema, TS)]
 pub enum TreatyType {
     /// End war between nations
     Peace,
@@ -630,17 +908,19 @@ impl std::error::Error for GameError {}
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::hex::HexCoord;
     use crate::player::Civilization;
+    use crate::types::Npub;
 
     fn create_test_game() -> GameState {
         let settings = GameSettings::new("Test".to_string());
-        GameState::new("game1".to_string(), settings, [0u8; 32])
+        GameState::new(GameId::new("game1"), settings, [0u8; 32])
     }
 
-    fn create_test_player(id: PlayerId, name: &str) -> Player {
+    fn create_test_player(id: PlayerSlot, name: &str) -> Player {
         Player::new(
             id,
-            format!("npub{}", id),
+            Npub(format!("npub{}", id)),
             name.to_string(),
             Civilization::generic(),
         )
@@ -657,8 +937,8 @@ mod tests {
     #[test]
     fn test_add_players() {
         let mut game = create_test_game();
-        let p1 = create_test_player(0, "Player1");
-        let p2 = create_test_player(1, "Player2");
+        let p1 = create_test_player(PlayerSlot(0), "Player1");
+        let p2 = create_test_player(PlayerSlot(1), "Player2");
 
         assert!(game.add_player(p1).is_ok());
         assert!(game.add_player(p2).is_ok());
@@ -668,8 +948,8 @@ mod tests {
     #[test]
     fn test_duplicate_player() {
         let mut game = create_test_game();
-        let p1 = create_test_player(0, "Player1");
-        let p1_dup = create_test_player(0, "Player1Clone");
+        let p1 = create_test_player(PlayerSlot(0), "Player1");
+        let p1_dup = create_test_player(PlayerSlot(0), "Player1Clone");
 
         assert!(game.add_player(p1).is_ok());
         assert_eq!(game.add_player(p1_dup), Err(GameError::PlayerAlreadyJoined));
@@ -678,13 +958,15 @@ mod tests {
     #[test]
     fn test_start_game() {
         let mut game = create_test_game();
-        game.add_player(create_test_player(0, "P1")).unwrap();
-        game.add_player(create_test_player(1, "P2")).unwrap();
+        game.add_player(create_test_player(PlayerSlot(0), "P1"))
+            .unwrap();
+        game.add_player(create_test_player(PlayerSlot(1), "P2"))
+            .unwrap();
 
         assert!(game.start().is_ok());
         assert_eq!(game.phase, GamePhase::Playing);
         assert_eq!(game.turn, 1);
-        assert_eq!(game.current_player, 0);
+        assert_eq!(game.current_player, PlayerSlot(0));
     }
 
     #[test]
@@ -696,43 +978,49 @@ mod tests {
     #[test]
     fn test_next_turn() {
         let mut game = create_test_game();
-        game.add_player(create_test_player(0, "P1")).unwrap();
-        game.add_player(create_test_player(1, "P2")).unwrap();
+        game.add_player(create_test_player(PlayerSlot(0), "P1"))
+            .unwrap();
+        game.add_player(create_test_player(PlayerSlot(1), "P2"))
+            .unwrap();
         game.start().unwrap();
 
-        assert_eq!(game.current_player, 0);
+        assert_eq!(game.current_player, PlayerSlot(0));
         game.next_turn().unwrap();
-        assert_eq!(game.current_player, 1);
+        assert_eq!(game.current_player, PlayerSlot(1));
         game.next_turn().unwrap();
-        assert_eq!(game.current_player, 0);
+        assert_eq!(game.current_player, PlayerSlot(0));
         assert_eq!(game.turn, 2);
     }
 
     #[test]
     fn test_diplomacy_initialization() {
         let mut game = create_test_game();
-        game.add_player(create_test_player(0, "P1")).unwrap();
-        game.add_player(create_test_player(1, "P2")).unwrap();
+        game.add_player(create_test_player(PlayerSlot(0), "P1"))
+            .unwrap();
+        game.add_player(create_test_player(PlayerSlot(1), "P2"))
+            .unwrap();
         game.start().unwrap();
 
-        let rel = game.diplomacy.get(0, 1).unwrap();
+        let rel = game.diplomacy.get(PlayerSlot(0), PlayerSlot(1)).unwrap();
         assert_eq!(rel.status, DiplomaticStatus::Neutral);
     }
 
     #[test]
     fn test_id_allocation() {
         let mut game = create_test_game();
-        assert_eq!(game.allocate_unit_id(), 1);
-        assert_eq!(game.allocate_unit_id(), 2);
-        assert_eq!(game.allocate_city_id(), 1);
-        assert_eq!(game.allocate_city_id(), 2);
+        assert_eq!(game.allocate_unit_id(), UnitId(1));
+        assert_eq!(game.allocate_unit_id(), UnitId(2));
+        assert_eq!(game.allocate_city_id(), CityId(1));
+        assert_eq!(game.allocate_city_id(), CityId(2));
     }
 
     #[test]
     fn test_game_serialization() {
         let mut game = create_test_game();
-        game.add_player(create_test_player(0, "P1")).unwrap();
-        game.add_player(create_test_player(1, "P2")).unwrap();
+        game.add_player(create_test_player(PlayerSlot(0), "P1"))
+            .unwrap();
+        game.add_player(create_test_player(PlayerSlot(1), "P2"))
+            .unwrap();
         game.start().unwrap();
 
         let json = serde_json::to_string(&game).unwrap();
@@ -744,12 +1032,39 @@ mod tests {
         assert_eq!(restored.phase, game.phase);
     }
 
+    #[test]
+    fn test_snapshot_shares_until_modified() {
+        let mut game = create_test_game();
+        game.map = Map::filled(10, 10, crate::terrain::Terrain::Plains);
+        let unit_id = game.allocate_unit_id();
+        game.units.insert(
+            unit_id,
+            Unit::new(
+                unit_id,
+                PlayerSlot(0),
+                crate::unit::UnitType::Warrior,
+                HexCoord::new(1, 1),
+            ),
+        );
+
+        let mut fork = game.snapshot();
+        assert!(fork.shares_storage_with(&game));
+
+        fork.units.get_mut(&unit_id).unwrap().position = HexCoord::new(2, 2);
+        assert!(!fork.shares_storage_with(&game));
+        assert!(Shared::ptr_eq(&fork.map.tiles, &game.map.tiles));
+        assert_eq!(game.units[&unit_id].position, HexCoord::new(1, 1));
+        assert_eq!(fork.units[&unit_id].position, HexCoord::new(2, 2));
+    }
+
     // ========== Treaty System Tests ==========
 
     fn create_started_game() -> GameState {
         let mut game = create_test_game();
-        game.add_player(create_test_player(0, "P1")).unwrap();
-        game.add_player(create_test_player(1, "P2")).unwrap();
+        game.add_player(create_test_player(PlayerSlot(0), "P1"))
+            .unwrap();
+        game.add_player(create_test_player(PlayerSlot(1), "P2"))
+            .unwrap();
         game.start().unwrap();
         game
     }
@@ -758,11 +1073,11 @@ mod tests {
     fn test_declare_war() {
         let mut game = create_started_game();
 
-        assert!(!game.diplomacy.are_at_war(0, 1));
-        game.diplomacy.declare_war(0, 1, 1);
-        assert!(game.diplomacy.are_at_war(0, 1));
+        assert!(!game.diplomacy.are_at_war(PlayerSlot(0), PlayerSlot(1)));
+        game.diplomacy.declare_war(PlayerSlot(0), PlayerSlot(1), 1);
+        assert!(game.diplomacy.are_at_war(PlayerSlot(0), PlayerSlot(1)));
 
-        let rel = game.diplomacy.get(0, 1).unwrap();
+        let rel = game.diplomacy.get(PlayerSlot(0), PlayerSlot(1)).unwrap();
         assert_eq!(rel.status, DiplomaticStatus::War);
         assert_eq!(rel.last_interaction_turn, 1);
         assert_eq!(rel.relationship_score, WAR_DECLARATION_SCORE_PENALTY);
@@ -773,28 +1088,39 @@ mod tests {
         let mut game = create_started_game();
 
         // Set up a high relationship score to allow treaty
-        game.diplomacy.modify_relationship_score(0, 1, 60);
+        game.diplomacy
+            .modify_relationship_score(PlayerSlot(0), PlayerSlot(1), 60);
+        assert!(game.diplomacy.propose_treaty(
+            PlayerSlot(0),
+            PlayerSlot(1),
+            TreatyType::OpenBorders,
+            1
+        ));
         assert!(game
             .diplomacy
-            .propose_treaty(0, 1, TreatyType::OpenBorders, 1));
-        assert!(game.diplomacy.has_treaty(0, 1, TreatyType::OpenBorders));
+            .has_treaty(PlayerSlot(0), PlayerSlot(1), TreatyType::OpenBorders));
 
         // Declare war - should break all treaties
-        game.diplomacy.declare_war(0, 1, 2);
-        assert!(!game.diplomacy.has_treaty(0, 1, TreatyType::OpenBorders));
+        game.diplomacy.declare_war(PlayerSlot(0), PlayerSlot(1), 2);
+        assert!(!game
+            .diplomacy
+            .has_treaty(PlayerSlot(0), PlayerSlot(1), TreatyType::OpenBorders));
     }
 
     #[test]
     fn test_declare_war_while_at_war() {
         let mut game = create_started_game();
 
-        game.diplomacy.declare_war(0, 1, 1);
-        let score_after_first = game.diplomacy.get_relationship_score(0, 1);
+        game.diplomacy.declare_war(PlayerSlot(0), PlayerSlot(1), 1);
+        let score_after_first = game
+            .diplomacy
+            .get_relationship_score(PlayerSlot(0), PlayerSlot(1));
 
         // Declaring war again should do nothing
-        game.diplomacy.declare_war(0, 1, 2);
+        game.diplomacy.declare_war(PlayerSlot(0), PlayerSlot(1), 2);
         assert_eq!(
-            game.diplomacy.get_relationship_score(0, 1),
+            game.diplomacy
+                .get_relationship_score(PlayerSlot(0), PlayerSlot(1)),
             score_after_first
         );
     }
@@ -803,13 +1129,13 @@ mod tests {
     fn test_make_peace() {
         let mut game = create_started_game();
 
-        game.diplomacy.declare_war(0, 1, 1);
-        assert!(game.diplomacy.are_at_war(0, 1));
+        game.diplomacy.declare_war(PlayerSlot(0), PlayerSlot(1), 1);
+        assert!(game.diplomacy.are_at_war(PlayerSlot(0), PlayerSlot(1)));
 
-        game.diplomacy.make_peace(0, 1, 5);
-        assert!(!game.diplomacy.are_at_war(0, 1));
+        game.diplomacy.make_peace(PlayerSlot(0), PlayerSlot(1), 5);
+        assert!(!game.diplomacy.are_at_war(PlayerSlot(0), PlayerSlot(1)));
 
-        let rel = game.diplomacy.get(0, 1).unwrap();
+        let rel = game.diplomacy.get(PlayerSlot(0), PlayerSlot(1)).unwrap();
         assert_eq!(rel.status, DiplomaticStatus::Neutral);
         assert!(rel.has_treaty(TreatyType::Peace));
         assert_eq!(rel.last_interaction_turn, 5);
@@ -819,13 +1145,15 @@ mod tests {
     fn test_make_peace_adds_peace_treaty() {
         let mut game = create_started_game();
 
-        game.diplomacy.declare_war(0, 1, 1);
-        game.diplomacy.make_peace(0, 1, 5);
+        game.diplomacy.declare_war(PlayerSlot(0), PlayerSlot(1), 1);
+        game.diplomacy.make_peace(PlayerSlot(0), PlayerSlot(1), 5);
 
-        assert!(game.diplomacy.has_treaty(0, 1, TreatyType::Peace));
+        assert!(game
+            .diplomacy
+            .has_treaty(PlayerSlot(0), PlayerSlot(1), TreatyType::Peace));
 
         // Peace treaty should have 10 turn duration
-        let rel = game.diplomacy.get(0, 1).unwrap();
+        let rel = game.diplomacy.get(PlayerSlot(0), PlayerSlot(1)).unwrap();
         let peace_treaty = rel
             .treaties
             .iter()
@@ -840,53 +1168,113 @@ mod tests {
         let mut game = create_started_game();
 
         // Should do nothing if not at war
-        game.diplomacy.make_peace(0, 1, 1);
-        assert!(!game.diplomacy.has_treaty(0, 1, TreatyType::Peace));
+        game.diplomacy.make_peace(PlayerSlot(0), PlayerSlot(1), 1);
+        assert!(!game
+            .diplomacy
+            .has_treaty(PlayerSlot(0), PlayerSlot(1), TreatyType::Peace));
     }
 
     #[test]
-    fn test_propose_treaty_requires_score() {
+    fn test_peace_proposal_accept() {
         let mut game = create_started_game();
 
-        // Default score is 0, should fail
+        // Can't propose peace when not at war
+        assert!(!game.diplomacy.propose_peace(PlayerSlot(0), PlayerSlot(1)));
+
+        game.diplomacy.declare_war(PlayerSlot(0), PlayerSlot(1), 1);
+        assert!(game.diplomacy.propose_peace(PlayerSlot(0), PlayerSlot(1)));
+        assert!(game
+            .diplomacy
+            .has_peace_proposal(PlayerSlot(0), PlayerSlot(1)));
         assert!(!game
             .diplomacy
-            .propose_treaty(0, 1, TreatyType::OpenBorders, 1));
+            .has_peace_proposal(PlayerSlot(1), PlayerSlot(0)));
 
-        // Increase score to threshold
-        game.diplomacy
-            .modify_relationship_score(0, 1, FRIENDLY_TREATY_THRESHOLD);
+        // Only the recipient's acceptance of an existing proposal counts
+        assert!(!game.diplomacy.accept_peace(PlayerSlot(1), PlayerSlot(0), 3));
+        assert!(game.diplomacy.accept_peace(PlayerSlot(0), PlayerSlot(1), 3));
+        assert!(!game.diplomacy.are_at_war(PlayerSlot(0), PlayerSlot(1)));
+        assert!(game.diplomacy.peace_proposals.is_empty());
+    }
+
+    #[test]
+    fn test_peace_proposal_withdraw() {
+        let mut game = create_started_game();
+
+        game.diplomacy.declare_war(PlayerSlot(0), PlayerSlot(1), 1);
+        game.diplomacy.propose_peace(PlayerSlot(0), PlayerSlot(1));
         assert!(game
             .diplomacy
-            .propose_treaty(0, 1, TreatyType::OpenBorders, 1));
+            .withdraw_peace_proposal(PlayerSlot(0), PlayerSlot(1)));
+        assert!(!game
+            .diplomacy
+            .withdraw_peace_proposal(PlayerSlot(0), PlayerSlot(1)));
+        assert!(game.diplomacy.are_at_war(PlayerSlot(0), PlayerSlot(1)));
+    }
+
+    #[test]
+    fn test_propose_treaty_requires_score() {
+        let mut game = create_started_game();
+
+        // Default score is 0, should fail
+        assert!(!game.diplomacy.propose_treaty(
+            PlayerSlot(0),
+            PlayerSlot(1),
+            TreatyType::OpenBorders,
+            1
+        ));
+
+        // Increase score to threshold
+        game.diplomacy.modify_relationship_score(
+            PlayerSlot(0),
+            PlayerSlot(1),
+            FRIENDLY_TREATY_THRESHOLD,
+        );
+        assert!(game.diplomacy.propose_treaty(
+            PlayerSlot(0),
+            PlayerSlot(1),
+            TreatyType::OpenBorders,
+            1
+        ));
     }
 
     #[test]
     fn test_propose_treaty_while_at_war() {
         let mut game = create_started_game();
 
-        game.diplomacy.modify_relationship_score(0, 1, 60);
-        game.diplomacy.declare_war(0, 1, 1);
+        game.diplomacy
+            .modify_relationship_score(PlayerSlot(0), PlayerSlot(1), 60);
+        game.diplomacy.declare_war(PlayerSlot(0), PlayerSlot(1), 1);
 
         // Can't propose treaties while at war
-        assert!(!game
-            .diplomacy
-            .propose_treaty(0, 1, TreatyType::OpenBorders, 2));
+        assert!(!game.diplomacy.propose_treaty(
+            PlayerSlot(0),
+            PlayerSlot(1),
+            TreatyType::OpenBorders,
+            2
+        ));
     }
 
     #[test]
     fn test_propose_duplicate_treaty() {
         let mut game = create_started_game();
 
-        game.diplomacy.modify_relationship_score(0, 1, 60);
-        assert!(game
-            .diplomacy
-            .propose_treaty(0, 1, TreatyType::OpenBorders, 1));
+        game.diplomacy
+            .modify_relationship_score(PlayerSlot(0), PlayerSlot(1), 60);
+        assert!(game.diplomacy.propose_treaty(
+            PlayerSlot(0),
+            PlayerSlot(1),
+            TreatyType::OpenBorders,
+            1
+        ));
 
         // Can't propose same treaty twice
-        assert!(!game
-            .diplomacy
-            .propose_treaty(0, 1, TreatyType::OpenBorders, 2));
+        assert!(!game.diplomacy.propose_treaty(
+            PlayerSlot(0),
+            PlayerSlot(1),
+            TreatyType::OpenBorders,
+            2
+        ));
     }
 
     #[test]
@@ -894,16 +1282,26 @@ mod tests {
         let mut game = create_started_game();
 
         // High score but not allied - should fail
-        game.diplomacy.modify_relationship_score(0, 1, 100);
-        assert!(!game
-            .diplomacy
-            .propose_treaty(0, 1, TreatyType::DefensivePact, 1));
+        game.diplomacy
+            .modify_relationship_score(PlayerSlot(0), PlayerSlot(1), 100);
+        assert!(!game.diplomacy.propose_treaty(
+            PlayerSlot(0),
+            PlayerSlot(1),
+            TreatyType::DefensivePact,
+            1
+        ));
 
         // Set status to Allied
-        game.diplomacy.get_mut(0, 1).unwrap().status = DiplomaticStatus::Allied;
-        assert!(game
-            .diplomacy
-            .propose_treaty(0, 1, TreatyType::DefensivePact, 1));
+        game.diplomacy
+            .get_mut(PlayerSlot(0), PlayerSlot(1))
+            .unwrap()
+            .status = DiplomaticStatus::Allied;
+        assert!(game.diplomacy.propose_treaty(
+            PlayerSlot(0),
+            PlayerSlot(1),
+            TreatyType::DefensivePact,
+            1
+        ));
     }
 
     #[test]
@@ -911,26 +1309,41 @@ mod tests {
         let mut game = create_started_game();
 
         // Peace treaties must be made via make_peace
-        game.diplomacy.modify_relationship_score(0, 1, 100);
-        assert!(!game.diplomacy.propose_treaty(0, 1, TreatyType::Peace, 1));
+        game.diplomacy
+            .modify_relationship_score(PlayerSlot(0), PlayerSlot(1), 100);
+        assert!(!game
+            .diplomacy
+            .propose_treaty(PlayerSlot(0), PlayerSlot(1), TreatyType::Peace, 1));
     }
 
     #[test]
     fn test_break_treaty() {
         let mut game = create_started_game();
 
-        game.diplomacy.modify_relationship_score(0, 1, 60);
         game.diplomacy
-            .propose_treaty(0, 1, TreatyType::TradeAgreement, 1);
-        assert!(game.diplomacy.has_treaty(0, 1, TreatyType::TradeAgreement));
-
-        let score_before = game.diplomacy.get_relationship_score(0, 1);
+            .modify_relationship_score(PlayerSlot(0), PlayerSlot(1), 60);
+        game.diplomacy
+            .propose_treaty(PlayerSlot(0), PlayerSlot(1), TreatyType::TradeAgreement, 1);
+        assert!(game.diplomacy.has_treaty(
+            PlayerSlot(0),
+            PlayerSlot(1),
+            TreatyType::TradeAgreement
+        ));
+
+        let score_before = game
+            .diplomacy
+            .get_relationship_score(PlayerSlot(0), PlayerSlot(1));
         game.diplomacy
-            .break_treaty(0, 1, TreatyType::TradeAgreement, 5);
+            .break_treaty(PlayerSlot(0), PlayerSlot(1), TreatyType::TradeAgreement, 5);
 
-        assert!(!game.diplomacy.has_treaty(0, 1, TreatyType::TradeAgreement));
+        assert!(!game.diplomacy.has_treaty(
+            PlayerSlot(0),
+            PlayerSlot(1),
+            TreatyType::TradeAgreement
+        ));
         assert_eq!(
-            game.diplomacy.get_relationship_score(0, 1),
+            game.diplomacy
+                .get_relationship_score(PlayerSlot(0), PlayerSlot(1)),
             score_before + TREATY_BREAK_SCORE_PENALTY
         );
     }
@@ -939,27 +1352,40 @@ mod tests {
     fn test_break_nonexistent_treaty() {
         let mut game = create_started_game();
 
-        let score_before = game.diplomacy.get_relationship_score(0, 1);
+        let score_before = game
+            .diplomacy
+            .get_relationship_score(PlayerSlot(0), PlayerSlot(1));
         game.diplomacy
-            .break_treaty(0, 1, TreatyType::TradeAgreement, 1);
+            .break_treaty(PlayerSlot(0), PlayerSlot(1), TreatyType::TradeAgreement, 1);
 
         // Score should not change if treaty didn't exist
-        assert_eq!(game.diplomacy.get_relationship_score(0, 1), score_before);
+        assert_eq!(
+            game.diplomacy
+                .get_relationship_score(PlayerSlot(0), PlayerSlot(1)),
+            score_before
+        );
     }
 
     #[test]
     fn test_has_treaty() {
         let mut game = create_started_game();
 
-        assert!(!game.diplomacy.has_treaty(0, 1, TreatyType::OpenBorders));
+        assert!(!game
+            .diplomacy
+            .has_treaty(PlayerSlot(0), PlayerSlot(1), TreatyType::OpenBorders));
 
-        game.diplomacy.modify_relationship_score(0, 1, 60);
         game.diplomacy
-            .propose_treaty(0, 1, TreatyType::OpenBorders, 1);
+            .modify_relationship_score(PlayerSlot(0), PlayerSlot(1), 60);
+        game.diplomacy
+            .propose_treaty(PlayerSlot(0), PlayerSlot(1), TreatyType::OpenBorders, 1);
 
-        assert!(game.diplomacy.has_treaty(0, 1, TreatyType::OpenBorders));
+        assert!(game
+            .diplomacy
+            .has_treaty(PlayerSlot(0), PlayerSlot(1), TreatyType::OpenBorders));
         // Order shouldn't matter
-        assert!(game.diplomacy.has_treaty(1, 0, TreatyType::OpenBorders));
+        assert!(game
+            .diplomacy
+            .has_treaty(PlayerSlot(1), PlayerSlot(0), TreatyType::OpenBorders));
     }
 
     #[test]
@@ -967,39 +1393,43 @@ mod tests {
         let mut game = create_started_game();
 
         // Same player - always true
-        assert!(game.diplomacy.can_units_pass(0, 0));
+        assert!(game.diplomacy.can_units_pass(PlayerSlot(0), PlayerSlot(0)));
 
         // Different players, no treaty - false
-        assert!(!game.diplomacy.can_units_pass(0, 1));
+        assert!(!game.diplomacy.can_units_pass(PlayerSlot(0), PlayerSlot(1)));
 
         // With open borders treaty
-        game.diplomacy.modify_relationship_score(0, 1, 60);
         game.diplomacy
-            .propose_treaty(0, 1, TreatyType::OpenBorders, 1);
-        assert!(game.diplomacy.can_units_pass(0, 1));
-        assert!(game.diplomacy.can_units_pass(1, 0));
+            .modify_relationship_score(PlayerSlot(0), PlayerSlot(1), 60);
+        game.diplomacy
+            .propose_treaty(PlayerSlot(0), PlayerSlot(1), TreatyType::OpenBorders, 1);
+        assert!(game.diplomacy.can_units_pass(PlayerSlot(0), PlayerSlot(1)));
+        assert!(game.diplomacy.can_units_pass(PlayerSlot(1), PlayerSlot(0)));
     }
 
     #[test]
     fn test_can_units_pass_allied() {
         let mut game = create_started_game();
 
-        game.diplomacy.get_mut(0, 1).unwrap().status = DiplomaticStatus::Allied;
-        assert!(game.diplomacy.can_units_pass(0, 1));
+        game.diplomacy
+            .get_mut(PlayerSlot(0), PlayerSlot(1))
+            .unwrap()
+            .status = DiplomaticStatus::Allied;
+        assert!(game.diplomacy.can_units_pass(PlayerSlot(0), PlayerSlot(1)));
     }
 
     #[test]
     fn test_update_turn_war_counters() {
         let mut game = create_started_game();
 
-        game.diplomacy.declare_war(0, 1, 1);
+        game.diplomacy.declare_war(PlayerSlot(0), PlayerSlot(1), 1);
 
         // Simulate several turns
         for turn in 2..=5 {
             game.diplomacy.update_turn(turn);
         }
 
-        let rel = game.diplomacy.get(0, 1).unwrap();
+        let rel = game.diplomacy.get(PlayerSlot(0), PlayerSlot(1)).unwrap();
         assert_eq!(rel.turns_at_war, 4);
         assert_eq!(rel.war_weariness, 4);
     }
@@ -1009,14 +1439,17 @@ mod tests {
         let mut game = create_started_game();
 
         // Set some initial war weariness
-        game.diplomacy.get_mut(0, 1).unwrap().war_weariness = 5;
+        game.diplomacy
+            .get_mut(PlayerSlot(0), PlayerSlot(1))
+            .unwrap()
+            .war_weariness = 5;
 
         // Simulate turns at peace
         for turn in 1..=3 {
             game.diplomacy.update_turn(turn);
         }
 
-        let rel = game.diplomacy.get(0, 1).unwrap();
+        let rel = game.diplomacy.get(PlayerSlot(0), PlayerSlot(1)).unwrap();
         assert_eq!(rel.turns_at_peace, 3);
         assert_eq!(rel.war_weariness, 2); // Decreased by 3
     }
@@ -1027,7 +1460,7 @@ mod tests {
 
         // Create a treaty that lasts 5 turns
         game.diplomacy
-            .get_mut(0, 1)
+            .get_mut(PlayerSlot(0), PlayerSlot(1))
             .unwrap()
             .add_treaty(ActiveTreaty {
                 treaty_type: TreatyType::Peace,
@@ -1035,24 +1468,31 @@ mod tests {
                 duration: Some(5),
             });
 
-        assert!(game.diplomacy.has_treaty(0, 1, TreatyType::Peace));
+        assert!(game
+            .diplomacy
+            .has_treaty(PlayerSlot(0), PlayerSlot(1), TreatyType::Peace));
 
         // Update to turn 6 - treaty should still exist (1 + 5 = 6, so > 5)
         game.diplomacy.update_turn(5);
-        assert!(game.diplomacy.has_treaty(0, 1, TreatyType::Peace));
+        assert!(game
+            .diplomacy
+            .has_treaty(PlayerSlot(0), PlayerSlot(1), TreatyType::Peace));
 
         // Update to turn 7 - treaty should expire (1 + 5 = 6, not > 6)
         game.diplomacy.update_turn(6);
-        assert!(!game.diplomacy.has_treaty(0, 1, TreatyType::Peace));
+        assert!(!game
+            .diplomacy
+            .has_treaty(PlayerSlot(0), PlayerSlot(1), TreatyType::Peace));
     }
 
     #[test]
     fn test_permanent_treaties_dont_expire() {
         let mut game = create_started_game();
 
-        game.diplomacy.modify_relationship_score(0, 1, 60);
         game.diplomacy
-            .propose_treaty(0, 1, TreatyType::OpenBorders, 1);
+            .modify_relationship_score(PlayerSlot(0), PlayerSlot(1), 60);
+        game.diplomacy
+            .propose_treaty(PlayerSlot(0), PlayerSlot(1), TreatyType::OpenBorders, 1);
 
         // Simulate many turns
         for turn in 2..=100 {
@@ -1060,20 +1500,36 @@ mod tests {
         }
 
         // Permanent treaty should still exist
-        assert!(game.diplomacy.has_treaty(0, 1, TreatyType::OpenBorders));
+        assert!(game
+            .diplomacy
+            .has_treaty(PlayerSlot(0), PlayerSlot(1), TreatyType::OpenBorders));
     }
 
     #[test]
     fn test_modify_relationship_score() {
         let mut game = create_started_game();
 
-        assert_eq!(game.diplomacy.get_relationship_score(0, 1), 0);
+        assert_eq!(
+            game.diplomacy
+                .get_relationship_score(PlayerSlot(0), PlayerSlot(1)),
+            0
+        );
 
-        game.diplomacy.modify_relationship_score(0, 1, 30);
-        assert_eq!(game.diplomacy.get_relationship_score(0, 1), 30);
+        game.diplomacy
+            .modify_relationship_score(PlayerSlot(0), PlayerSlot(1), 30);
+        assert_eq!(
+            game.diplomacy
+                .get_relationship_score(PlayerSlot(0), PlayerSlot(1)),
+            30
+        );
 
-        game.diplomacy.modify_relationship_score(0, 1, -50);
-        assert_eq!(game.diplomacy.get_relationship_score(0, 1), -20);
+        game.diplomacy
+            .modify_relationship_score(PlayerSlot(0), PlayerSlot(1), -50);
+        assert_eq!(
+            game.diplomacy
+                .get_relationship_score(PlayerSlot(0), PlayerSlot(1)),
+            -20
+        );
     }
 
     #[test]
@@ -1081,38 +1537,58 @@ mod tests {
         let mut game = create_started_game();
 
         // Test upper bound
-        game.diplomacy.modify_relationship_score(0, 1, 200);
-        assert_eq!(game.diplomacy.get_relationship_score(0, 1), 100);
+        game.diplomacy
+            .modify_relationship_score(PlayerSlot(0), PlayerSlot(1), 200);
+        assert_eq!(
+            game.diplomacy
+                .get_relationship_score(PlayerSlot(0), PlayerSlot(1)),
+            100
+        );
 
         // Test lower bound
-        game.diplomacy.modify_relationship_score(0, 1, -300);
-        assert_eq!(game.diplomacy.get_relationship_score(0, 1), -100);
+        game.diplomacy
+            .modify_relationship_score(PlayerSlot(0), PlayerSlot(1), -300);
+        assert_eq!(
+            game.diplomacy
+                .get_relationship_score(PlayerSlot(0), PlayerSlot(1)),
+            -100
+        );
     }
 
     #[test]
     fn test_is_war_likely() {
         let mut game = create_started_game();
 
-        assert!(!game.diplomacy.is_war_likely(0, 1)); // Score is 0
+        assert!(!game.diplomacy.is_war_likely(PlayerSlot(0), PlayerSlot(1))); // Score is 0
 
-        game.diplomacy.modify_relationship_score(0, 1, -49);
-        assert!(!game.diplomacy.is_war_likely(0, 1)); // Not below threshold
+        game.diplomacy
+            .modify_relationship_score(PlayerSlot(0), PlayerSlot(1), -49);
+        assert!(!game.diplomacy.is_war_likely(PlayerSlot(0), PlayerSlot(1))); // Not below threshold
 
-        game.diplomacy.modify_relationship_score(0, 1, -2);
-        assert!(game.diplomacy.is_war_likely(0, 1)); // Now at -51
+        game.diplomacy
+            .modify_relationship_score(PlayerSlot(0), PlayerSlot(1), -2);
+        assert!(game.diplomacy.is_war_likely(PlayerSlot(0), PlayerSlot(1))); // Now at -51
     }
 
     #[test]
     fn test_can_propose_friendly_treaty() {
         let mut game = create_started_game();
 
-        assert!(!game.diplomacy.can_propose_friendly_treaty(0, 1)); // Score is 0
+        assert!(!game
+            .diplomacy
+            .can_propose_friendly_treaty(PlayerSlot(0), PlayerSlot(1))); // Score is 0
 
-        game.diplomacy.modify_relationship_score(0, 1, 49);
-        assert!(!game.diplomacy.can_propose_friendly_treaty(0, 1)); // Not at threshold
+        game.diplomacy
+            .modify_relationship_score(PlayerSlot(0), PlayerSlot(1), 49);
+        assert!(!game
+            .diplomacy
+            .can_propose_friendly_treaty(PlayerSlot(0), PlayerSlot(1))); // Not at threshold
 
-        game.diplomacy.modify_relationship_score(0, 1, 1);
-        assert!(game.diplomacy.can_propose_friendly_treaty(0, 1)); // Now at 50
+        game.diplomacy
+            .modify_relationship_score(PlayerSlot(0), PlayerSlot(1), 1);
+        assert!(game
+            .diplomacy
+            .can_propose_friendly_treaty(PlayerSlot(0), PlayerSlot(1))); // Now at 50
     }
 
     #[test]
@@ -1199,22 +1675,32 @@ mod tests {
         let mut game = create_started_game();
 
         // Set up some diplomatic state
-        game.diplomacy.modify_relationship_score(0, 1, 60);
         game.diplomacy
-            .propose_treaty(0, 1, TreatyType::OpenBorders, 1);
+            .modify_relationship_score(PlayerSlot(0), PlayerSlot(1), 60);
+        game.diplomacy
+            .propose_treaty(PlayerSlot(0), PlayerSlot(1), TreatyType::OpenBorders, 1);
         game.diplomacy
-            .propose_treaty(0, 1, TreatyType::TradeAgreement, 2);
+            .propose_treaty(PlayerSlot(0), PlayerSlot(1), TreatyType::TradeAgreement, 2);
 
         let json = serde_json::to_string(&game).unwrap();
         let restored: GameState = serde_json::from_str(&json).unwrap();
 
-        assert!(restored.diplomacy.has_treaty(0, 1, TreatyType::OpenBorders));
-        assert!(restored
-            .diplomacy
-            .has_treaty(0, 1, TreatyType::TradeAgreement));
+        assert!(restored.diplomacy.has_treaty(
+            PlayerSlot(0),
+            PlayerSlot(1),
+            TreatyType::OpenBorders
+        ));
+        assert!(restored.diplomacy.has_treaty(
+            PlayerSlot(0),
+            PlayerSlot(1),
+            TreatyType::TradeAgreement
+        ));
         assert_eq!(
-            restored.diplomacy.get_relationship_score(0, 1),
-            game.diplomacy.get_relationship_score(0, 1)
+            restored
+                .diplomacy
+                .get_relationship_score(PlayerSlot(0), PlayerSlot(1)),
+            game.diplomacy
+                .get_relationship_score(PlayerSlot(0), PlayerSlot(1))
         );
     }
 
@@ -1223,28 +1709,54 @@ mod tests {
         let mut game = create_started_game();
 
         // Set up for friendly treaties
-        game.diplomacy.modify_relationship_score(0, 1, 60);
-        game.diplomacy.get_mut(0, 1).unwrap().status = DiplomaticStatus::Allied;
+        game.diplomacy
+            .modify_relationship_score(PlayerSlot(0), PlayerSlot(1), 60);
+        game.diplomacy
+            .get_mut(PlayerSlot(0), PlayerSlot(1))
+            .unwrap()
+            .status = DiplomaticStatus::Allied;
 
         // Test all treaty types except Peace (which needs war first)
+        assert!(game.diplomacy.propose_treaty(
+            PlayerSlot(0),
+            PlayerSlot(1),
+            TreatyType::OpenBorders,
+            1
+        ));
+        assert!(game.diplomacy.propose_treaty(
+            PlayerSlot(0),
+            PlayerSlot(1),
+            TreatyType::ResearchAgreement,
+            2
+        ));
+        assert!(game.diplomacy.propose_treaty(
+            PlayerSlot(0),
+            PlayerSlot(1),
+            TreatyType::TradeAgreement,
+            3
+        ));
+        assert!(game.diplomacy.propose_treaty(
+            PlayerSlot(0),
+            PlayerSlot(1),
+            TreatyType::DefensivePact,
+            4
+        ));
+
         assert!(game
             .diplomacy
-            .propose_treaty(0, 1, TreatyType::OpenBorders, 1));
-        assert!(game
-            .diplomacy
-            .propose_treaty(0, 1, TreatyType::ResearchAgreement, 2));
-        assert!(game
-            .diplomacy
-            .propose_treaty(0, 1, TreatyType::TradeAgreement, 3));
-        assert!(game
-            .diplomacy
-            .propose_treaty(0, 1, TreatyType::DefensivePact, 4));
-
-        assert!(game.diplomacy.has_treaty(0, 1, TreatyType::OpenBorders));
+            .has_treaty(PlayerSlot(0), PlayerSlot(1), TreatyType::OpenBorders));
+        assert!(game.diplomacy.has_treaty(
+            PlayerSlot(0),
+            PlayerSlot(1),
+            TreatyType::ResearchAgreement
+        ));
+        assert!(game.diplomacy.has_treaty(
+            PlayerSlot(0),
+            PlayerSlot(1),
+            TreatyType::TradeAgreement
+        ));
         assert!(game
             .diplomacy
-            .has_treaty(0, 1, TreatyType::ResearchAgreement));
-        assert!(game.diplomacy.has_treaty(0, 1, TreatyType::TradeAgreement));
-        assert!(game.diplomacy.has_treaty(0, 1, TreatyType::DefensivePact));
+            .has_treaty(PlayerSlot(0), PlayerSlot(1), TreatyType::DefensivePact));
     }
 }
@@ -0,0 +1,223 @@
+//! Reference vectors for cross-platform determinism checks.
+//!
+//! A [`GoldenVector`] is a canonical seed, settings and action script,
+//! together with the [`audit::state_hash`] expected at the end of every
+//! turn. Vectors are committed as JSON under `goldens/` in this crate and
+//! checked by the `golden_tests` integration test on every platform the
+//! engine ships to, so an x86, ARM or WASM build that drifts by a single
+//! bit fails before release.
+//!
+//! When a rules change is meant to alter results, rerun the test with
+//! `UPDATE_GOLDENS=1` to rewrite the expected hashes, and commit the new
+//! vectors together with the change.
+
+use crate::audit;
+use crate::events::GameAction;
+use crate::replay::GameEngine;
+use crate::settings::GameSettings;
+use crate::types::PlayerSlot;
+use serde::{Deserialize, Serialize};
+use std::fmt;
+
+/// One action of a golden script.
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct ScriptStep {
+    /// Player taking the action.
+    pub player: PlayerSlot,
+    /// The action.
+    pub action: GameAction,
+}
+
+/// State hash expected at the end of a turn.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct TurnHash {
+    /// Turn number.
+    pub turn: u32,
+    /// [`audit::state_hash`] once the turn's last action was applied, as
+    /// 16 hex digits.
+    #[serde(with = "hex_u64")]
+    pub hash: u64,
+}
+
+/// A canonical game and the state hashes it must produce.
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct GoldenVector {
+    /// Name of the vector, for reports.
+    pub name: String,
+    /// What the script exercises.
+    #[serde(default)]
+    pub description: String,
+    /// Game settings.
+    pub settings: GameSettings,
+    /// Game seed.
+    pub seed: [u8; 32],
+    /// Actions to apply, in order, after the game is created.
+    pub script: Vec<ScriptStep>,
+    /// Expected hash at the end of each turn the script reaches.
+    pub expected: Vec<TurnHash>,
+}
+
+/// Why a golden vector failed.
+#[derive(Debug)]
+pub enum GoldenError {
+    /// A script action was rejected.
+    Rejected { step: usize, reason: String },
+    /// The script reached different turns than expected.
+    TurnCount { expected: usize, actual: usize },
+    /// A turn ended with a different state.
+    HashMismatch {
+        turn: u32,
+        expected: u64,
+        actual: u64,
+    },
+}
+
+impl fmt::Display for GoldenError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            GoldenError::Rejected { step, reason } => {
+                write!(f, "Step {} was rejected: {}", step, reason)
+            }
+            GoldenError::TurnCount { expected, actual } => {
+                write!(f, "Script reached {} turns, expected {}", actual, expected)
+            }
+            GoldenError::HashMismatch {
+                turn,
+                expected,
+                actual,
+            } => write!(
+                f,
+                "Turn {} hash is {:016x}, expected {:016x}",
+                turn, actual, expected
+            ),
+        }
+    }
+}
+
+impl std::error::Error for GoldenError {}
+
+impl GoldenVector {
+    /// Play the script and record the hash at the end of every turn.
+    pub fn play(&self) -> Result<Vec<TurnHash>, GoldenError> {
+        let mut engine = GameEngine::new(self.settings.clone(), self.seed);
+        let mut hashes: Vec<TurnHash> = Vec::new();
+        for (step, ScriptStep { player, action }) in self.script.iter().enumerate() {
+            let reason = match engine.apply_action(*player, action) {
+                Ok(result) if result.success => None,
+                Ok(result) => Some(result.error.unwrap_or_default()),
+                Err(error) => Some(error.to_string()),
+            };
+            if let Some(reason) = reason {
+                return Err(GoldenError::Rejected { step, reason });
+            }
+            let hash = TurnHash {
+                turn: engine.state.turn,
+                hash: audit::state_hash(&engine.state),
+            };
+            match hashes.last_mut() {
+                Some(last) if last.turn == hash.turn => *last = hash,
+                _ => hashes.push(hash),
+            }
+        }
+        Ok(hashes)
+    }
+
+    /// Play the script and compare every turn against the expected hashes.
+    ///
+    /// Reports the first turn that differs.
+    pub fn check(&self) -> Result<(), GoldenError> {
+        let actual = self.play()?;
+        for (expected, actual) in self.expected.iter().zip(&actual) {
+            if expected != actual {
+                return Err(GoldenError::HashMismatch {
+                    turn: expected.turn,
+                    expected: expected.hash,
+                    actual: actual.hash,
+                });
+            }
+        }
+        if actual.len() != self.expected.len() {
+            return Err(GoldenError::TurnCount {
+                expected: self.expected.len(),
+                actual: actual.len(),
+            });
+        }
+        Ok(())
+    }
+}
+
+/// Serialize hashes as fixed-width hex so vectors diff cleanly and survive
+/// JSON readers without 64-bit integers.
+mod hex_u64 {
+    use serde::{Deserialize, Deserializer, Serializer};
+
+    pub fn serialize<S: Serializer>(value: &u64, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.serialize_str(&format!("{:016x}", value))
+    }
+
+    pub fn deserialize<'de, D: Deserializer<'de>>(deserializer: D) -> Result<u64, D::Error> {
+        let s = String::deserialize(deserializer)?;
+        u64::from_str_radix(&s, 16).map_err(serde::de::Error::custom)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn vector() -> GoldenVector {
+        GoldenVector {
+            name: "lobby".to_string(),
+            description: String::new(),
+            settings: GameSettings::new("Golden".to_string()),
+            seed: [3; 32],
+            script: vec![
+                ScriptStep {
+                    player: PlayerSlot(0),
+                    action: GameAction::JoinGame {
+                        player_name: "P0".to_string(),
+                        civilization_id: "rome".to_string(),
+                    },
+                },
+                ScriptStep {
+                    player: PlayerSlot(1),
+                    action: GameAction::JoinGame {
+                        player_name: "P1".to_string(),
+                        civilization_id: "egypt".to_string(),
+                    },
+                },
+            ],
+            expected: Vec::new(),
+        }
+    }
+
+    #[test]
+    fn test_check_reports_first_mismatch() {
+        let mut vector = vector();
+        vector.expected = vector.play().unwrap();
+        assert!(vector.check().is_ok());
+
+        vector.expected[0].hash ^= 1;
+        assert!(matches!(
+            vector.check(),
+            Err(GoldenError::HashMismatch { turn, .. }) if turn == vector.expected[0].turn
+        ));
+
+        vector.expected.clear();
+        assert!(matches!(
+            vector.check(),
+            Err(GoldenError::TurnCount { expected: 0, .. })
+        ));
+    }
+
+    #[test]
+    fn test_vector_json_round_trip() {
+        let mut vector = vector();
+        vector.expected = vector.play().unwrap();
+        let json = serde_json::to_string(&vector).unwrap();
+        assert!(json.contains(&format!("\"{:016x}\"", vector.expected[0].hash)));
+        let parsed: GoldenVector = serde_json::from_str(&json).unwrap();
+        assert_eq!(parsed.expected, vector.expected);
+        assert!(parsed.check().is_ok());
+    }
+}
@@ -2,8 +2,15 @@
 //!
 //! Uses offset "odd-q" coordinates where odd columns are shifted down.
 //! This is common for hex grids displayed with pointy-top hexagons.
+//!
+//! Geometry (rings, spirals, lines, rotation and reflection) is done in
+//! cube coordinates and converted back, and uses integer arithmetic only
+//! so every platform gets the same tiles.
 
+use crate::fixed::deterministic;
+use schemars::JsonSchema;
 use serde::{Deserialize, Serialize};
+use ts_rs::TS;
 
 /// Axial coordinates for hex grid (offset odd-q).
 ///
@@ -11,7 +18,9 @@ use serde::{Deserialize, Serialize};
 /// - `q` is the column (x-axis)
 /// - `r` is the row (y-axis)
 /// - Odd columns are shifted down by half a hex
-#[derive(Clone, Copy, PartialEq, Eq, Hash, Debug, Default, Serialize, Deserialize)]
+#[derive(
+    Clone, Copy, PartialEq, Eq, Hash, Debug, Default, Serialize, Deserialize, JsonSchema, TS,
+)]
 pub struct HexCoord {
     /// Column coordinate
     pub q: i32,
@@ -19,6 +28,22 @@ pub struct HexCoord {
     pub r: i32,
 }
 
+deterministic!(struct HexCoord { q, r });
+
+/// Cube offsets of the six neighbors, in the same clockwise order as
+/// [`HexCoord::neighbors`]: NE, E, SE, SW, W, NW.
+const CUBE_DIRECTIONS: [(i32, i32, i32); 6] = [
+    (1, 0, -1),
+    (1, -1, 0),
+    (0, -1, 1),
+    (-1, 0, 1),
+    (-1, 1, 0),
+    (0, 1, -1),
+];
+
+/// Scale of the nudge that keeps line points off hex edges.
+const LINE_SCALE: i64 = 1000;
+
 impl PartialOrd for HexCoord {
     fn partial_cmp(&self, other: &Self) -> Option<std::cmp::Ordering> {
         Some(self.cmp(other))
@@ -105,36 +130,175 @@ impl HexCoord {
 
     /// Get all hexes within a given radius (inclusive).
     ///
-    /// Returns a Vec of all hexes that are at most `radius` steps away.
+    /// Returns a Vec of all hexes that are at most `radius` steps away, in
+    /// [`spiral`](Self::spiral) order.
     pub fn hexes_in_radius(&self, radius: u32) -> Vec<HexCoord> {
-        let mut result = Vec::new();
-        let r = radius as i32;
-
-        for dq in -r..=r {
-            for dr in -r..=r {
-                let candidate = HexCoord::new(self.q + dq, self.r + dr);
-                if self.distance(&candidate) <= radius {
-                    result.push(candidate);
-                }
+        self.spiral(radius)
+    }
+
+    /// Get a ring of hexes at exactly the given distance.
+    pub fn hex_ring(&self, radius: u32) -> Vec<HexCoord> {
+        self.ring(radius)
+    }
+
+    /// Offset the hex by a cube vector scaled by `steps`.
+    fn cube_step(&self, (dx, dy, dz): (i32, i32, i32), steps: i32) -> HexCoord {
+        let (x, y, z) = self.to_cube();
+        HexCoord::from_cube(x + dx * steps, y + dy * steps, z + dz * steps)
+    }
+
+    /// Index of an adjacent hex in [`neighbors`](Self::neighbors), or `None`
+    /// if `other` isn't adjacent.
+    pub fn direction_to(&self, other: &HexCoord) -> Option<usize> {
+        self.neighbors().iter().position(|n| n == other)
+    }
+
+    /// Get the hexes at exactly `radius` steps, clockwise.
+    ///
+    /// The ring starts `radius` steps to the northeast, so `ring(1)` is the
+    /// same as [`neighbors`](Self::neighbors). A radius of zero is the hex
+    /// itself.
+    pub fn ring(&self, radius: u32) -> Vec<HexCoord> {
+        if radius == 0 {
+            return vec![*self];
+        }
+
+        let radius = radius as i32;
+        let mut result = Vec::with_capacity(6 * radius as usize);
+        let mut hex = self.cube_step(CUBE_DIRECTIONS[0], radius);
+        for side in 0..6 {
+            for _ in 0..radius {
+                result.push(hex);
+                hex = hex.cube_step(CUBE_DIRECTIONS[(side + 2) % 6], 1);
             }
         }
+        result
+    }
 
+    /// Get the hexes within `radius` steps, spiralling out: the hex itself,
+    /// then each ring from the inside out.
+    pub fn spiral(&self, radius: u32) -> Vec<HexCoord> {
+        let mut result = Vec::with_capacity(1 + 3 * radius as usize * (radius as usize + 1));
+        result.push(*self);
+        for r in 1..=radius {
+            result.extend(self.ring(r));
+        }
         result
     }
 
-    /// Get a ring of hexes at exactly the given distance.
-    pub fn hex_ring(&self, radius: u32) -> Vec<HexCoord> {
-        if radius == 0 {
+    /// Get the hexes on a straight line to `other`, both ends included.
+    ///
+    /// Each step moves to an adjacent hex. Points on the line are rounded to
+    /// the nearest hex; ties on a shared edge always go the same way.
+    pub fn line_to(&self, other: &HexCoord) -> Vec<HexCoord> {
+        let steps = self.distance(other) as i64;
+        if steps == 0 {
             return vec![*self];
         }
 
-        self.hexes_in_radius(radius)
+        let (x1, y1, z1) = self.to_cube();
+        let (x2, y2, z2) = other.to_cube();
+        let denominator = steps * LINE_SCALE;
+        (0..=steps)
+            .map(|i| {
+                // Linear interpolation as fractions of `denominator`, nudged
+                // off the edges (the nudges sum to zero)
+                let lerp = |a: i32, b: i32, nudge: i64| {
+                    (a as i64 * steps + (b - a) as i64 * i) * LINE_SCALE + nudge
+                };
+                cube_round(
+                    lerp(x1, x2, 1),
+                    lerp(y1, y2, 2),
+                    lerp(z1, z2, -3),
+                    denominator,
+                )
+            })
+            .collect()
+    }
+
+    /// Rotate the hex around `center` by `steps` sixths of a turn,
+    /// clockwise for positive steps.
+    pub fn rotate_around(&self, center: &HexCoord, steps: i32) -> HexCoord {
+        let (cx, cy, cz) = center.to_cube();
+        let (x, y, z) = self.to_cube();
+        let mut v = (x - cx, y - cy, z - cz);
+        for _ in 0..steps.rem_euclid(6) {
+            v = (-v.2, -v.0, -v.1);
+        }
+        HexCoord::from_cube(cx + v.0, cy + v.1, cz + v.2)
+    }
+
+    /// Mirror the hex through `center`, keeping its cube `x` offset and
+    /// swapping `y` and `z`.
+    pub fn reflect_x(&self, center: &HexCoord) -> HexCoord {
+        self.reflect(center, |(x, y, z)| (x, z, y))
+    }
+
+    /// Mirror the hex through `center`, keeping its cube `y` offset and
+    /// swapping `x` and `z`.
+    pub fn reflect_y(&self, center: &HexCoord) -> HexCoord {
+        self.reflect(center, |(x, y, z)| (z, y, x))
+    }
+
+    /// Mirror the hex through `center`, keeping its cube `z` offset and
+    /// swapping `x` and `y`.
+    pub fn reflect_z(&self, center: &HexCoord) -> HexCoord {
+        self.reflect(center, |(x, y, z)| (y, x, z))
+    }
+
+    fn reflect(
+        &self,
+        center: &HexCoord,
+        swap: impl Fn((i32, i32, i32)) -> (i32, i32, i32),
+    ) -> HexCoord {
+        let (cx, cy, cz) = center.to_cube();
+        let (x, y, z) = self.to_cube();
+        let (dx, dy, dz) = swap((x - cx, y - cy, z - cz));
+        HexCoord::from_cube(cx + dx, cy + dy, cz + dz)
+    }
+
+    /// Get the hexes within `radius` that can be seen from this one, in
+    /// spiral order.
+    ///
+    /// A hex is hidden when a hex strictly between it and the viewer on
+    /// [`line_to`](Self::line_to) blocks sight. Blocking hexes are
+    /// themselves visible.
+    pub fn field_of_view(
+        &self,
+        radius: u32,
+        blocks_sight: impl Fn(&HexCoord) -> bool,
+    ) -> Vec<HexCoord> {
+        self.spiral(radius)
             .into_iter()
-            .filter(|h| self.distance(h) == radius)
+            .filter(|target| {
+                let line = self.line_to(target);
+                line.len() <= 2 || !line[1..line.len() - 1].iter().any(&blocks_sight)
+            })
             .collect()
     }
 }
 
+/// Round a fractional cube coordinate, given as numerators over a common
+/// positive denominator, to the nearest hex.
+fn cube_round(x: i64, y: i64, z: i64, denominator: i64) -> HexCoord {
+    // Round half up: floor((2n + d) / 2d)
+    let round = |n: i64| (2 * n + denominator).div_euclid(2 * denominator);
+    let (mut rx, mut ry, mut rz) = (round(x), round(y), round(z));
+    let error = |r: i64, n: i64| (r * denominator - n).abs();
+    let (ex, ey, ez) = (error(rx, x), error(ry, y), error(rz, z));
+
+    // Recompute the component furthest from its rounded value so the three
+    // still sum to zero
+    if ex > ey && ex > ez {
+        rx = -ry - rz;
+    } else if ey > ez {
+        ry = -rx - rz;
+    } else {
+        rz = -rx - ry;
+    }
+    HexCoord::from_cube(rx as i32, ry as i32, rz as i32)
+}
+
 impl std::fmt::Display for HexCoord {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
         write!(f, "({}, {})", self.q, self.r)
@@ -204,4 +368,172 @@ mod tests {
         let coord = HexCoord::new(3, 7);
         assert_eq!(format!("{}", coord), "(3, 7)");
     }
+
+    /// Centers in both even and odd columns, including negative ones.
+    fn centers() -> Vec<HexCoord> {
+        let mut centers = Vec::new();
+        for q in -3..=4 {
+            for r in -2..=3 {
+                centers.push(HexCoord::new(q, r));
+            }
+        }
+        centers
+    }
+
+    /// Every hex within `radius` of `center`, by brute force.
+    fn brute_force_radius(center: &HexCoord, radius: u32) -> std::collections::BTreeSet<HexCoord> {
+        let r = radius as i32 * 2;
+        let mut result = std::collections::BTreeSet::new();
+        for q in center.q - r..=center.q + r {
+            for row in center.r - r..=center.r + r {
+                let candidate = HexCoord::new(q, row);
+                if center.distance(&candidate) <= radius {
+                    result.insert(candidate);
+                }
+            }
+        }
+        result
+    }
+
+    #[test]
+    fn test_ring() {
+        for center in centers() {
+            assert_eq!(center.ring(0), vec![center]);
+            assert_eq!(center.ring(1), center.neighbors().to_vec());
+            for radius in 1..=5 {
+                let ring = center.ring(radius);
+                assert_eq!(ring.len(), 6 * radius as usize);
+                assert!(ring.iter().all(|h| center.distance(h) == radius));
+                // Consecutive hexes are adjacent, all the way round
+                for (i, hex) in ring.iter().enumerate() {
+                    assert_eq!(hex.distance(&ring[(i + 1) % ring.len()]), 1);
+                }
+                let unique: std::collections::BTreeSet<_> = ring.iter().collect();
+                assert_eq!(unique.len(), ring.len());
+            }
+        }
+    }
+
+    #[test]
+    fn test_spiral() {
+        for center in centers() {
+            for radius in 0..=5 {
+                let spiral = center.spiral(radius);
+                let n = radius as usize;
+                assert_eq!(spiral.len(), 1 + 3 * n * (n + 1));
+                assert_eq!(spiral[0], center);
+                assert!(spiral
+                    .windows(2)
+                    .all(|w| center.distance(&w[0]) <= center.distance(&w[1])));
+                let set: std::collections::BTreeSet<_> = spiral.into_iter().collect();
+                assert_eq!(set, brute_force_radius(&center, radius));
+            }
+        }
+    }
+
+    #[test]
+    fn test_line_to() {
+        for start in centers() {
+            for end in start.spiral(6) {
+                let line = start.line_to(&end);
+                assert_eq!(line.len(), start.distance(&end) as usize + 1);
+                assert_eq!(line[0], start);
+                assert_eq!(*line.last().unwrap(), end);
+                for (i, w) in line.windows(2).enumerate() {
+                    assert_eq!(w[0].distance(&w[1]), 1, "{} to {} at {}", start, end, i);
+                }
+                for (i, hex) in line.iter().enumerate() {
+                    assert_eq!(start.distance(hex), i as u32);
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn test_line_along_axis_is_straight() {
+        let start = HexCoord::new(2, 2);
+        let end = start.cube_step(CUBE_DIRECTIONS[1], 4);
+        let expected: Vec<_> = (0..=4)
+            .map(|i| start.cube_step(CUBE_DIRECTIONS[1], i))
+            .collect();
+        assert_eq!(start.line_to(&end), expected);
+    }
+
+    #[test]
+    fn test_rotate_around() {
+        for center in centers() {
+            for hex in center.spiral(3) {
+                assert_eq!(hex.rotate_around(&center, 6), hex);
+                assert_eq!(
+                    hex.rotate_around(&center, -1),
+                    hex.rotate_around(&center, 5)
+                );
+                for steps in 0..6 {
+                    let rotated = hex.rotate_around(&center, steps);
+                    assert_eq!(center.distance(&rotated), center.distance(&hex));
+                    assert_eq!(rotated.rotate_around(&center, -steps), hex);
+                }
+            }
+            // One step clockwise moves each neighbor to the next one
+            let neighbors = center.neighbors();
+            for i in 0..6 {
+                assert_eq!(
+                    neighbors[i].rotate_around(&center, 1),
+                    neighbors[(i + 1) % 6]
+                );
+            }
+        }
+    }
+
+    #[test]
+    fn test_reflections() {
+        for center in centers() {
+            for hex in center.spiral(3) {
+                for reflected in [
+                    hex.reflect_x(&center),
+                    hex.reflect_y(&center),
+                    hex.reflect_z(&center),
+                ] {
+                    assert_eq!(center.distance(&reflected), center.distance(&hex));
+                }
+                assert_eq!(hex.reflect_x(&center).reflect_x(&center), hex);
+                assert_eq!(hex.reflect_y(&center).reflect_y(&center), hex);
+                assert_eq!(hex.reflect_z(&center).reflect_z(&center), hex);
+            }
+            assert_eq!(center.reflect_x(&center), center);
+            // Reflecting twice across different axes is a rotation
+            let hex = center.neighbors()[0];
+            assert_eq!(
+                hex.reflect_x(&center).reflect_y(&center),
+                hex.rotate_around(&center, 2)
+            );
+        }
+    }
+
+    #[test]
+    fn test_direction_to() {
+        let center = HexCoord::new(3, 3);
+        for (i, neighbor) in center.neighbors().iter().enumerate() {
+            assert_eq!(center.direction_to(neighbor), Some(i));
+        }
+        assert_eq!(center.direction_to(&center), None);
+        assert_eq!(center.direction_to(&HexCoord::new(6, 3)), None);
+    }
+
+    #[test]
+    fn test_field_of_view() {
+        let center = HexCoord::new(4, 4);
+        assert_eq!(center.field_of_view(3, |_| false), center.spiral(3));
+
+        // A wall to the east hides what's behind it but not itself
+        let wall = center.cube_step(CUBE_DIRECTIONS[1], 1);
+        let behind = center.cube_step(CUBE_DIRECTIONS[1], 2);
+        let view = center.field_of_view(3, |h| *h == wall);
+        assert!(view.contains(&wall));
+        assert!(!view.contains(&behind));
+        assert!(view.contains(&center.cube_step(CUBE_DIRECTIONS[4], 3)));
+
+        // Standing on a blocking hex doesn't blind the viewer
+        assert_eq!(center.field_of_view(2, |h| *h == center), center.spiral(2));
+    }
 }
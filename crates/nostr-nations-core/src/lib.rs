@@ -12,6 +12,7 @@
 //! - **Thoroughly tested**: Comprehensive test coverage
 
 // Core modules
+pub mod fixed;
 pub mod hex;
 pub mod map;
 pub mod terrain;
@@ -19,69 +20,176 @@ pub mod types;
 pub mod yields;
 
 // Game state modules
+pub mod advisor;
+pub mod ai;
+pub mod contact;
+pub mod demographics;
+pub mod eras;
 pub mod game_state;
 pub mod player;
 pub mod settings;
 
-// Map generation
+// Map generation and change
+pub mod continents;
 pub mod mapgen;
+pub mod terraform;
 
 // Units and combat
 pub mod combat;
+pub mod path_cache;
 pub mod pathfinding;
+pub mod siege;
 pub mod unit;
 
 // Cities and buildings
+pub mod borders;
 pub mod city;
+pub mod recommend;
+pub mod wonders;
 
 // Technology
+pub mod research;
 pub mod technology;
 
 // Trading system
+pub mod stockpile;
 pub mod trading;
+pub mod upkeep;
+
+// Road network
+pub mod roads;
 
 // Victory conditions
+pub mod concession;
+pub mod pause;
+pub mod scenario;
+pub mod substitution;
 pub mod victory;
+pub mod victory_proof;
 
 // Memory optimization utilities
+pub mod cow;
 pub mod memory;
+pub mod parallel;
+
+// Progress of long-running operations
+pub mod progress;
 
 // Nostr events and replay
+pub mod audit;
+pub mod diff;
+pub mod digest;
+pub mod event_kinds;
 pub mod events;
+pub mod game_log;
+pub mod goldens;
+pub mod merkle;
 pub mod replay;
+pub mod sandbox;
+pub mod schedule;
+pub mod schema;
+pub mod snapshot;
+pub mod turn;
+pub mod undo;
 
 // Visibility and fog of war
 pub mod visibility;
 
+// Localization
+pub mod locale;
+
 // Cashu randomness
 pub mod cashu;
 
 // Re-exports for convenience
+pub use advisor::{PendingItem, TurnAdvisor};
+pub use ai::{
+    assign_persona, choose_action, play_turn, AiBudget, AiError, AiPlanner, AiTick, Objective,
+    ObjectiveWeights, Persona, PersonaRuleset,
+};
+pub use audit::{AuditDivergence, AuditEntry, AuditInput, AuditLog, DivergenceKind, RngDraw};
 pub use cashu::{
     combat_random_from_proof, map_seed_from_proof, CashuConfig, DeterministicRandomness,
     RandomnessContext, RandomnessError, RandomnessManager, RandomnessProof, RandomnessProvider,
     RandomnessRequest,
 };
 pub use city::{BuildingType, City, ProductionItem, WonderType};
-pub use combat::{resolve_combat, CombatContext, CombatResult};
+pub use combat::{
+    resolve_combat, resolve_combat_with_difficulty, CombatContext, CombatPreview, CombatResult,
+    DamageRange,
+};
+pub use concession::{concede, concession_recipient, Concession};
+pub use continents::{label_continents, CIRCUMNAVIGATION_GOLD, MAX_LUXURY_CONTINENTS};
+pub use cow::Shared;
+pub use demographics::{Demographic, DemographicRow, Demographics, PlayerRank};
+pub use diff::{CityDiff, StateDiff, TileDiff, UnitDiff};
+pub use digest::{DigestBuilder, DigestItem, TurnDigest};
+pub use eras::{can_produce, era_from_techs, update_era, warmonger_percent};
+pub use event_kinds::{KindCategory, KindError, KindRegistry, KindSpec, NipClass};
 pub use events::{EventBuilder, EventChain, GameAction, GameEvent};
-pub use game_state::{DiplomacyState, DiplomaticStatus, GameError, GamePhase, GameState};
+pub use fixed::{Deterministic, Fixed};
+pub use game_log::{EntityRef, GameLog, LogCategory, LogEntry, LogFilter, DEFAULT_LOG_CAPACITY};
+pub use game_state::{
+    DiplomacyState, DiplomaticStatus, GameError, GamePhase, GameState, TreatyType,
+};
+pub use goldens::{GoldenError, GoldenVector, ScriptStep, TurnHash};
 pub use hex::HexCoord;
+pub use locale::{Catalog, CityNameRuleset, LocaleError, LocalizedMessage, Localizer};
 pub use map::{Map, Tile};
 pub use mapgen::{MapGenConfig, MapGenerator, SeededRng};
+pub use merkle::{prove_event, EventsRoot, MerkleProof};
+pub use path_cache::{PathCache, PathCacheStats};
 pub use pathfinding::{find_path, find_reachable, PathConfig, PathResult};
+pub use pause::{PauseOutcome, PauseState, DEFAULT_RESUME_COUNTDOWN_SECS};
 pub use player::{Civilization, Player, Score};
-pub use replay::{ActionEffect, ActionResult, GameEngine, ReplayConfig, ReplayError};
-pub use settings::{Difficulty, GameSettings, GameSpeed};
+pub use progress::{Progress, ProgressTracker};
+pub use recommend::{
+    production_options, rank_production, recommend_production, CityNeed, CityNeeds,
+    ProductionOption, Recommendation,
+};
+pub use replay::{
+    ActionEffect, ActionRejection, ActionResult, GameEngine, ReplayConfig, ReplayError,
+};
+pub use research::{progress_research, queue_research, research_cost};
+pub use roads::{RoadError, RoadWork};
+pub use sandbox::{fork_at, is_sandbox, sandbox_id, SANDBOX_PREFIX};
+pub use scenario::{
+    evaluate_rules, parse_rules, validate_rules, Condition, RuleOutcome, ScenarioError,
+    ScenarioProgress, ScenarioReport, ScenarioRule,
+};
+pub use schedule::{ScheduledTurn, TurnSchedule, TurnTimes, DEFAULT_TURN_SECS};
+pub use schema::{event_schemas, SchemaExport};
+pub use settings::{
+    BarbarianAggression, ConcessionPolicy, Difficulty, DifficultyModifiers, GameSettings,
+    GameSpeed, PausePolicy,
+};
+pub use siege::{capture_city, resolve_capture, CaptureChoice, CityCapture};
+pub use snapshot::{SnapshotError, StateSnapshot};
+pub use stockpile::{
+    collect_resources, unit_resource_cost, StockpileReport, DEPLETION_CHANCE_PERMILLE,
+    MAX_STOCKPILE, SOURCE_YIELD,
+};
+pub use substitution::{substitute, turns_absent, Substitution, DEFAULT_SUBSTITUTE_AFTER_TURNS};
 pub use technology::{TechTree, TechUnlocks, Technology};
+pub use terraform::{
+    feature_to_clear, progress_feature_work, update_climate, ClearError, FeatureWork,
+    GlobalWarming, CHOP_PRODUCTION,
+};
 pub use terrain::{Feature, Improvement, Resource, ResourceCategory, Road, Terrain};
 pub use trading::{
     calculate_trade_value, execute_trade, TradeError, TradeFairness, TradeItems, TradeManager,
     TradeOffer, TradeStatus,
 };
+pub use turn::{process_end_turn, TurnPhase};
 pub use types::*;
-pub use unit::{Promotion, Unit, UnitCategory, UnitStats, UnitType};
+pub use undo::{ActionBuffer, BufferedAction, CommittedAction};
+pub use unit::{
+    HealingSite, Promotion, PromotionError, Unit, UnitCategory, UnitStats, UnitTurnContext,
+    UnitTurnReport, UnitType,
+};
+pub use upkeep::{apply_upkeep, project_treasury, TreasuryProjection, UpkeepReport};
 pub use victory::{SpaceshipProgress, VictoryChecker};
+pub use victory_proof::{ProofPlayer, VictoryProof, VictoryProofError};
 pub use yields::Yields;
 
 // Memory optimization re-exports
@@ -92,3 +200,4 @@ pub use visibility::{
     redact_event_for_player, FilteredEvent, FilteredGameState, PlayerSummary, TileVisibility,
     VisibilityFilter,
 };
+pub use wonders::{BuiltWonder, WonderCompletion, WonderRefund};
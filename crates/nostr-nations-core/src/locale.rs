@@ -0,0 +1,503 @@
+//! Localization of player-facing strings.
+//!
+//! Core never hands finished English sentences to a frontend. Instead it
+//! produces [`LocalizedMessage`]s: a message key plus named parameters.
+//! Frontends look the key up in a [`Catalog`] for the player's language and
+//! substitute the parameters.
+//!
+//! Catalogs use a small Fluent-style syntax:
+//!
+//! ```text
+//! # Comment
+//! notify-your-turn = Turn { $turn } has begun. It's your move!
+//! ```
+//!
+//! Indented lines continue the previous message. The English catalog is
+//! built in and serves as the fallback for every other locale.
+//!
+//! Civilization-specific city names come from the city name ruleset
+//! ([`CityNameRuleset`]), which lists message keys per civilization.
+
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+use std::collections::{BTreeMap, HashMap};
+use std::fmt;
+use std::sync::OnceLock;
+use ts_rs::TS;
+
+/// Locale of the built-in catalog, used as the fallback for all others.
+pub const FALLBACK_LOCALE: &str = "en";
+
+const EN_CATALOG: &str = include_str!("../locales/en.ftl");
+const CITY_NAMES_RULESET: &str = include_str!("../rulesets/city_names.json");
+
+/// Message key helpers for game content.
+pub mod keys {
+    /// Display name of a civilization.
+    pub fn civ_name(civ_id: &str) -> String {
+        format!("civ-{}-name", civ_id)
+    }
+
+    /// Leader name of a civilization.
+    pub fn civ_leader(civ_id: &str) -> String {
+        format!("civ-{}-leader", civ_id)
+    }
+
+    /// Unique ability name of a civilization.
+    pub fn civ_ability(civ_id: &str) -> String {
+        format!("civ-{}-ability", civ_id)
+    }
+
+    /// Unique ability description of a civilization.
+    pub fn civ_ability_description(civ_id: &str) -> String {
+        format!("civ-{}-ability-description", civ_id)
+    }
+
+    /// Display name of a technology.
+    pub fn tech_name(tech_id: &str) -> String {
+        format!("tech-{}", tech_id)
+    }
+}
+
+/// Errors from loading localization data.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum LocaleError {
+    /// A catalog line could not be parsed.
+    Parse { line: usize, reason: String },
+    /// A message key was defined twice.
+    DuplicateKey(String),
+    /// A ruleset file was malformed.
+    InvalidRuleset(String),
+}
+
+impl fmt::Display for LocaleError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            LocaleError::Parse { line, reason } => {
+                write!(f, "Catalog parse error on line {}: {}", line, reason)
+            }
+            LocaleError::DuplicateKey(key) => write!(f, "Duplicate message key: {}", key),
+            LocaleError::InvalidRuleset(e) => write!(f, "Invalid ruleset: {}", e),
+        }
+    }
+}
+
+impl std::error::Error for LocaleError {}
+
+/// A message key plus the parameters to substitute into it.
+#[derive(Clone, Debug, Default, PartialEq, Eq, Serialize, Deserialize, JsonSchema, TS)]
+pub struct LocalizedMessage {
+    /// Catalog key.
+    pub key: String,
+    /// Named parameters.
+    #[serde(default, skip_serializing_if = "BTreeMap::is_empty")]
+    pub args: BTreeMap<String, String>,
+}
+
+impl LocalizedMessage {
+    /// Create a message with no parameters.
+    pub fn new(key: impl Into<String>) -> Self {
+        Self {
+            key: key.into(),
+            args: BTreeMap::new(),
+        }
+    }
+
+    /// Add a named parameter.
+    pub fn with_arg(mut self, name: impl Into<String>, value: impl ToString) -> Self {
+        self.args.insert(name.into(), value.to_string());
+        self
+    }
+
+    /// Render using the built-in English catalog.
+    pub fn to_english(&self) -> String {
+        Catalog::english().format(self)
+    }
+}
+
+/// Messages for a single locale.
+#[derive(Clone, Debug, Default)]
+pub struct Catalog {
+    locale: String,
+    messages: HashMap<String, String>,
+}
+
+impl Catalog {
+    /// Parse a catalog in Fluent-style `key = text` syntax.
+    pub fn parse(locale: impl Into<String>, source: &str) -> Result<Self, LocaleError> {
+        let mut messages: HashMap<String, String> = HashMap::new();
+        let mut current: Option<String> = None;
+
+        for (i, raw) in source.lines().enumerate() {
+            let line_no = i + 1;
+            if raw.trim().is_empty() || raw.trim_start().starts_with('#') {
+                current = None;
+                continue;
+            }
+
+            if raw.starts_with(char::is_whitespace) {
+                let key = current.as_ref().ok_or_else(|| LocaleError::Parse {
+                    line: line_no,
+                    reason: "continuation line without a message".to_string(),
+                })?;
+                if let Some(text) = messages.get_mut(key) {
+                    if !text.is_empty() {
+                        text.push('\n');
+                    }
+                    text.push_str(raw.trim());
+                }
+                continue;
+            }
+
+            let (key, value) = raw.split_once('=').ok_or_else(|| LocaleError::Parse {
+                line: line_no,
+                reason: "expected `key = value`".to_string(),
+            })?;
+            let key = key.trim();
+            if !is_valid_key(key) {
+                return Err(LocaleError::Parse {
+                    line: line_no,
+                    reason: format!("invalid message key `{}`", key),
+                });
+            }
+            if messages
+                .insert(key.to_string(), value.trim().to_string())
+                .is_some()
+            {
+                return Err(LocaleError::DuplicateKey(key.to_string()));
+            }
+            current = Some(key.to_string());
+        }
+
+        Ok(Self {
+            locale: locale.into(),
+            messages,
+        })
+    }
+
+    /// The built-in English catalog.
+    pub fn english() -> &'static Catalog {
+        static ENGLISH: OnceLock<Catalog> = OnceLock::new();
+        ENGLISH.get_or_init(|| {
+            Catalog::parse(FALLBACK_LOCALE, EN_CATALOG).expect("built-in catalog is valid")
+        })
+    }
+
+    /// Locale identifier (e.g. "en", "fr").
+    pub fn locale(&self) -> &str {
+        &self.locale
+    }
+
+    /// Number of messages.
+    pub fn len(&self) -> usize {
+        self.messages.len()
+    }
+
+    /// Whether the catalog has no messages.
+    pub fn is_empty(&self) -> bool {
+        self.messages.is_empty()
+    }
+
+    /// Whether the catalog defines `key`.
+    pub fn contains(&self, key: &str) -> bool {
+        self.messages.contains_key(key)
+    }
+
+    /// Raw message text for `key`, before substitution.
+    pub fn get(&self, key: &str) -> Option<&str> {
+        self.messages.get(key).map(String::as_str)
+    }
+
+    /// All messages, sorted by key (for shipping to a frontend).
+    pub fn messages(&self) -> BTreeMap<&str, &str> {
+        self.messages
+            .iter()
+            .map(|(k, v)| (k.as_str(), v.as_str()))
+            .collect()
+    }
+
+    /// Render a message, or `None` if the key is missing.
+    pub fn try_format(&self, message: &LocalizedMessage) -> Option<String> {
+        self.get(&message.key)
+            .map(|text| substitute(text, &message.args))
+    }
+
+    /// Render a message, falling back to the key itself if missing.
+    pub fn format(&self, message: &LocalizedMessage) -> String {
+        self.try_format(message)
+            .unwrap_or_else(|| message.key.clone())
+    }
+}
+
+/// Catalogs for all loaded locales, with English fallback.
+#[derive(Clone, Debug, Default)]
+pub struct Localizer {
+    catalogs: HashMap<String, Catalog>,
+}
+
+impl Localizer {
+    /// Create a localizer with only the built-in English catalog.
+    pub fn new() -> Self {
+        let mut localizer = Self::default();
+        localizer.add_catalog(Catalog::english().clone());
+        localizer
+    }
+
+    /// Add or replace the catalog for its locale.
+    pub fn add_catalog(&mut self, catalog: Catalog) {
+        self.catalogs.insert(catalog.locale.clone(), catalog);
+    }
+
+    /// Parse and add a catalog.
+    pub fn load(&mut self, locale: &str, source: &str) -> Result<(), LocaleError> {
+        self.add_catalog(Catalog::parse(locale, source)?);
+        Ok(())
+    }
+
+    /// Catalog for a locale, if loaded.
+    pub fn catalog(&self, locale: &str) -> Option<&Catalog> {
+        self.catalogs.get(locale)
+    }
+
+    /// Loaded locales, sorted.
+    pub fn locales(&self) -> Vec<&str> {
+        let mut locales: Vec<&str> = self.catalogs.keys().map(String::as_str).collect();
+        locales.sort_unstable();
+        locales
+    }
+
+    /// Render a message in `locale`.
+    ///
+    /// Tries the exact locale, then its language ("fr-CA" -> "fr"), then
+    /// English, then returns the key.
+    pub fn format(&self, locale: &str, message: &LocalizedMessage) -> String {
+        let language = locale.split(['-', '_']).next().unwrap_or(locale);
+        [locale, language, FALLBACK_LOCALE]
+            .iter()
+            .filter_map(|l| self.catalogs.get(*l))
+            .find_map(|c| c.try_format(message))
+            .unwrap_or_else(|| message.key.clone())
+    }
+}
+
+/// Civilization-specific city name lists.
+#[derive(Clone, Debug, Default, Serialize, Deserialize)]
+pub struct CityNameRuleset {
+    /// Message keys for city names, per civilization ID, in founding order.
+    pub city_names: BTreeMap<String, Vec<String>>,
+}
+
+impl CityNameRuleset {
+    /// Load a ruleset from JSON.
+    pub fn from_json(json: &str) -> Result<Self, LocaleError> {
+        serde_json::from_str(json).map_err(|e| LocaleError::InvalidRuleset(e.to_string()))
+    }
+
+    /// The built-in ruleset.
+    pub fn builtin() -> &'static CityNameRuleset {
+        static BUILTIN: OnceLock<CityNameRuleset> = OnceLock::new();
+        BUILTIN.get_or_init(|| {
+            CityNameRuleset::from_json(CITY_NAMES_RULESET).expect("built-in ruleset is valid")
+        })
+    }
+
+    /// City name keys for a civilization.
+    pub fn names_for(&self, civ_id: &str) -> &[String] {
+        self.city_names
+            .get(civ_id)
+            .map(Vec::as_slice)
+            .unwrap_or_default()
+    }
+
+    /// Suggest a name for a civilization's next city.
+    ///
+    /// `used` holds city names already taken, either as message keys or as
+    /// English text. Once the list is exhausted, names repeat with an
+    /// ordinal (`{ $name } { $ordinal }`, e.g. "Rome 2").
+    pub fn suggest(&self, civ_id: &str, used: &[String]) -> Option<LocalizedMessage> {
+        let names = self.names_for(civ_id);
+        let english = Catalog::english();
+        let taken = |key: &String| {
+            used.iter()
+                .any(|u| u == key || english.get(key) == Some(u.as_str()))
+        };
+
+        if let Some(key) = names.iter().find(|k| !taken(k)) {
+            return Some(LocalizedMessage::new(key.clone()));
+        }
+
+        let first = names.first()?;
+        let ordinal = used.len() / names.len() + 1;
+        Some(
+            LocalizedMessage::new("city-name-ordinal")
+                .with_arg("name", english.get(first).unwrap_or(first))
+                .with_arg("ordinal", ordinal),
+        )
+    }
+}
+
+/// Whether `key` is a valid message identifier.
+fn is_valid_key(key: &str) -> bool {
+    let mut chars = key.chars();
+    chars.next().is_some_and(|c| c.is_ascii_alphabetic())
+        && chars.all(|c| c.is_ascii_alphanumeric() || c == '-' || c == '_')
+}
+
+/// Replace `{ $name }` placeables with argument values.
+///
+/// Unknown parameters are left in place so missing arguments are visible.
+fn substitute(text: &str, args: &BTreeMap<String, String>) -> String {
+    let mut out = String::with_capacity(text.len());
+    let mut rest = text;
+    while let Some(start) = rest.find('{') {
+        out.push_str(&rest[..start]);
+        let Some(len) = rest[start..].find('}') else {
+            rest = &rest[start..];
+            break;
+        };
+        let placeable = &rest[start..start + len + 1];
+        let name = placeable[1..placeable.len() - 1].trim();
+        match name.strip_prefix('$').and_then(|n| args.get(n)) {
+            Some(value) => out.push_str(value),
+            None => out.push_str(placeable),
+        }
+        rest = &rest[start + len + 1..];
+    }
+    out.push_str(rest);
+    out
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::player::Civilization;
+    use crate::technology::TechTree;
+
+    // ==================== Catalog Tests ====================
+
+    #[test]
+    fn test_parse_and_format() {
+        let catalog = Catalog::parse(
+            "fr",
+            "# Commentaire\nnotify-your-turn = Tour { $turn } : à vous !\n",
+        )
+        .unwrap();
+        let msg = LocalizedMessage::new("notify-your-turn").with_arg("turn", 7);
+        assert_eq!(catalog.format(&msg), "Tour 7 : à vous !");
+        assert_eq!(catalog.locale(), "fr");
+        assert_eq!(catalog.len(), 1);
+    }
+
+    #[test]
+    fn test_continuation_lines() {
+        let catalog = Catalog::parse("en", "long =\n    first line\n    second line\n").unwrap();
+        assert_eq!(catalog.get("long"), Some("first line\nsecond line"));
+    }
+
+    #[test]
+    fn test_parse_errors() {
+        assert!(matches!(
+            Catalog::parse("en", "no equals sign"),
+            Err(LocaleError::Parse { line: 1, .. })
+        ));
+        assert!(matches!(
+            Catalog::parse("en", "1bad = x"),
+            Err(LocaleError::Parse { .. })
+        ));
+        assert_eq!(
+            Catalog::parse("en", "a = 1\na = 2").unwrap_err(),
+            LocaleError::DuplicateKey("a".to_string())
+        );
+    }
+
+    #[test]
+    fn test_missing_args_and_keys_stay_visible() {
+        let catalog = Catalog::parse("en", "greet = Hello { $name }!").unwrap();
+        assert_eq!(
+            catalog.format(&LocalizedMessage::new("greet")),
+            "Hello { $name }!"
+        );
+        assert_eq!(catalog.format(&LocalizedMessage::new("nope")), "nope");
+    }
+
+    // ==================== Localizer Tests ====================
+
+    #[test]
+    fn test_localizer_falls_back_to_language_then_english() {
+        let mut localizer = Localizer::new();
+        localizer
+            .load("fr", "notify-combat-title = Combat !")
+            .unwrap();
+
+        let combat = LocalizedMessage::new("notify-combat-title");
+        assert_eq!(localizer.format("fr-CA", &combat), "Combat !");
+
+        let turn = LocalizedMessage::new("notify-your-turn").with_arg("turn", 3);
+        assert_eq!(
+            localizer.format("fr", &turn),
+            "Turn 3 has begun. It's your move!"
+        );
+        assert_eq!(localizer.locales(), vec!["en", "fr"]);
+    }
+
+    // ==================== Built-in Content Tests ====================
+
+    #[test]
+    fn test_english_catalog_covers_game_content() {
+        let english = Catalog::english();
+        for civ in Civilization::all_civilizations() {
+            assert!(english.contains(&keys::civ_name(&civ.id)), "{}", civ.id);
+            assert!(english.contains(&keys::civ_leader(&civ.id)));
+            assert!(english.contains(&keys::civ_ability(&civ.id)));
+            assert!(english.contains(&keys::civ_ability_description(&civ.id)));
+            assert_eq!(
+                english.get(&keys::civ_name(&civ.id)),
+                Some(civ.name.as_str())
+            );
+        }
+        let tree = TechTree::new();
+        for tech in tree.all_ids().into_iter().filter_map(|id| tree.get(id)) {
+            assert_eq!(
+                english.get(&keys::tech_name(&tech.id)),
+                Some(tech.name.as_str()),
+                "{}",
+                tech.id
+            );
+        }
+    }
+
+    #[test]
+    fn test_city_ruleset_keys_exist() {
+        let english = Catalog::english();
+        let ruleset = CityNameRuleset::builtin();
+        for civ in Civilization::all_civilizations() {
+            let names = ruleset.names_for(&civ.id);
+            assert!(!names.is_empty(), "{}", civ.id);
+            for key in names {
+                assert!(english.contains(key), "{}", key);
+            }
+        }
+    }
+
+    #[test]
+    fn test_city_name_suggestions() {
+        let ruleset = CityNameRuleset::from_json(
+            r#"{"city_names": {"rome": ["city-rome-rome", "city-rome-antium"]}}"#,
+        )
+        .unwrap();
+
+        let first = ruleset.suggest("rome", &[]).unwrap();
+        assert_eq!(first.key, "city-rome-rome");
+
+        // English names count as taken too.
+        let second = ruleset.suggest("rome", &["Rome".to_string()]).unwrap();
+        assert_eq!(second.key, "city-rome-antium");
+
+        let used = vec!["city-rome-rome".to_string(), "Antium".to_string()];
+        let third = ruleset.suggest("rome", &used).unwrap();
+        assert_eq!(third.to_english(), "Rome 2");
+
+        assert!(ruleset.suggest("atlantis", &[]).is_none());
+        assert!(CityNameRuleset::from_json("not json").is_err());
+    }
+}
@@ -1,8 +1,10 @@
 //! Game map structure with tiles and spatial queries.
 
+use crate::cow::Shared;
+use crate::fixed::deterministic;
 use crate::hex::HexCoord;
 use crate::terrain::{Feature, Improvement, Resource, Road, Terrain};
-use crate::types::{CityId, PlayerId};
+use crate::types::{CityId, PlayerSlot};
 use crate::yields::Yields;
 use serde::{Deserialize, Serialize};
 use std::collections::HashMap;
@@ -15,7 +17,8 @@ pub struct Map {
     /// Map height in tiles.
     pub height: u32,
     /// All tiles indexed by coordinate.
-    pub tiles: HashMap<HexCoord, Tile>,
+    #[serde(with = "tile_list")]
+    pub tiles: Shared<HashMap<HexCoord, Tile>>,
     /// Does the map wrap horizontally?
     pub wrap_x: bool,
 }
@@ -26,7 +29,7 @@ impl Map {
         Self {
             width,
             height,
-            tiles: HashMap::new(),
+            tiles: Shared::default(),
             wrap_x,
         }
     }
@@ -104,6 +107,24 @@ impl Map {
             .collect()
     }
 
+    /// Get the continent a tile belongs to.
+    pub fn continent(&self, coord: &HexCoord) -> Option<u16> {
+        self.get(coord).and_then(|tile| tile.continent)
+    }
+
+    /// Check if two tiles are on the same landmass.
+    ///
+    /// On a map whose continents haven't been labeled, all land counts as
+    /// one landmass.
+    pub fn same_continent(&self, a: &HexCoord, b: &HexCoord) -> bool {
+        match (self.get(a), self.get(b)) {
+            (Some(a), Some(b)) => {
+                !a.terrain.is_water() && !b.terrain.is_water() && a.continent == b.continent
+            }
+            _ => false,
+        }
+    }
+
     /// Count total tiles in the map.
     pub fn tile_count(&self) -> usize {
         self.tiles.len()
@@ -142,13 +163,30 @@ pub struct Tile {
     /// Road/railroad on this tile.
     pub road: Option<Road>,
     /// Player who owns this tile (via city borders).
-    pub owner: Option<PlayerId>,
+    pub owner: Option<PlayerSlot>,
     /// City that owns this tile.
     pub city_id: Option<CityId>,
     /// Which edges have rivers (6 edges, clockwise from NE).
     pub river_edges: [bool; 6],
+    /// Landmass this tile belongs to, labeled when the map is generated.
+    /// Always `None` for water.
+    #[serde(default)]
+    pub continent: Option<u16>,
 }
 
+deterministic!(struct Tile {
+    coord,
+    terrain,
+    feature,
+    resource,
+    improvement,
+    road,
+    owner,
+    city_id,
+    river_edges,
+    continent,
+});
+
 impl Tile {
     /// Create a new tile with just terrain.
     pub fn new(coord: HexCoord, terrain: Terrain) -> Self {
@@ -162,6 +200,7 @@ impl Tile {
             owner: None,
             city_id: None,
             river_edges: [false; 6],
+            continent: None,
         }
     }
 
@@ -189,28 +228,26 @@ impl Tile {
         y.clamp_non_negative()
     }
 
-    /// Get the movement cost to enter this tile.
-    pub fn movement_cost(&self) -> u32 {
+    /// Get the movement cost to enter this tile, ignoring roads.
+    pub fn base_movement_cost(&self) -> u32 {
         // Feature cost takes precedence (impassable mountains, etc.)
-        if let Some(feature) = &self.feature {
-            let cost = feature.movement_cost();
-            if cost == u32::MAX {
-                return u32::MAX; // Impassable
-            }
-            // Road reduces cost
-            if let Some(road) = &self.road {
-                let multiplier = road.movement_multiplier();
-                return ((cost as f32) * multiplier).ceil() as u32;
-            }
-            return cost;
+        match &self.feature {
+            Some(feature) => feature.movement_cost(),
+            None => self.terrain.movement_cost(),
         }
+    }
 
-        let base = self.terrain.movement_cost();
+    /// Get the movement cost to enter this tile.
+    pub fn movement_cost(&self) -> u32 {
+        let base = self.base_movement_cost();
+        if base == u32::MAX {
+            return u32::MAX; // Impassable
+        }
 
         // Road reduces cost
         if let Some(road) = &self.road {
             let multiplier = road.movement_multiplier();
-            return ((base as f32) * multiplier).ceil() as u32;
+            return multiplier.mul_int(base as i64).ceil() as u32;
         }
 
         base
@@ -265,6 +302,35 @@ impl Default for Tile {
     }
 }
 
+/// Serialize tiles as a list sorted by coordinate, since JSON object keys
+/// must be strings.
+mod tile_list {
+    use super::*;
+    use serde::{Deserializer, Serializer};
+
+    pub fn serialize<S>(
+        tiles: &Shared<HashMap<HexCoord, Tile>>,
+        serializer: S,
+    ) -> Result<S::Ok, S::Error>
+    where
+        S: Serializer,
+    {
+        let mut sorted: Vec<&Tile> = tiles.values().collect();
+        sorted.sort_by_key(|t| (t.coord.q, t.coord.r));
+        serializer.collect_seq(sorted)
+    }
+
+    pub fn deserialize<'de, D>(deserializer: D) -> Result<Shared<HashMap<HexCoord, Tile>>, D::Error>
+    where
+        D: Deserializer<'de>,
+    {
+        let tiles: Vec<Tile> = Deserialize::deserialize(deserializer)?;
+        Ok(Shared::new(
+            tiles.into_iter().map(|t| (t.coord, t)).collect(),
+        ))
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -286,6 +352,20 @@ mod tests {
         assert_eq!(tile.terrain, Terrain::Grassland);
     }
 
+    #[test]
+    fn test_map_serialization() {
+        let mut map = Map::filled(4, 3, Terrain::Plains);
+        map.get_mut(&HexCoord::new(2, 1)).unwrap().terrain = Terrain::Desert;
+
+        let json = serde_json::to_string(&map).unwrap();
+        let restored: Map = serde_json::from_str(&json).unwrap();
+        assert_eq!(restored.tile_count(), 12);
+        assert_eq!(
+            restored.get(&HexCoord::new(2, 1)).unwrap().terrain,
+            Terrain::Desert
+        );
+    }
+
     #[test]
     fn test_tile_yields() {
         let mut tile = Tile::new(HexCoord::new(0, 0), Terrain::Grassland);
@@ -550,11 +630,11 @@ mod tests {
         assert!(tile.city_id.is_none());
 
         // Set owner
-        tile.owner = Some(0);
-        tile.city_id = Some(1);
+        tile.owner = Some(PlayerSlot(0));
+        tile.city_id = Some(CityId(1));
 
-        assert_eq!(tile.owner, Some(0));
-        assert_eq!(tile.city_id, Some(1));
+        assert_eq!(tile.owner, Some(PlayerSlot(0)));
+        assert_eq!(tile.city_id, Some(CityId(1)));
     }
 
     #[test]
@@ -4,10 +4,13 @@
 //! for the Nostr-based replay system - the same seed must always produce
 //! the same map.
 
+use crate::continents::{self, MAX_LUXURY_CONTINENTS};
 use crate::hex::HexCoord;
 use crate::map::{Map, Tile};
-use crate::terrain::{Feature, Resource, Terrain};
+use crate::progress::{ignore_progress, Progress};
+use crate::terrain::{Feature, Resource, ResourceCategory, Terrain};
 use crate::types::MapSize;
+use std::collections::{BTreeMap, BTreeSet};
 
 /// Configuration for map generation.
 #[derive(Clone, Debug)]
@@ -20,6 +23,19 @@ pub struct MapGenConfig {
     pub player_count: u8,
     /// Does the map wrap horizontally?
     pub wrap_x: bool,
+    /// Global temperature (0-100). 50 is Earth-like; higher pushes deserts
+    /// and jungles toward the poles, lower spreads tundra and snow.
+    pub temperature: u32,
+    /// Global rainfall (0-100). 50 is Earth-like; higher means more forest
+    /// and jungle, lower more desert.
+    pub rainfall: u32,
+    /// Terrain roughness (0-100). Higher breaks the land into more, smaller
+    /// landmasses with more hills.
+    pub roughness: u32,
+    /// How strictly climate follows latitude (0-100). At 100 terrain forms
+    /// clean bands from the equator to the poles; at 0 temperature is
+    /// pure noise.
+    pub climate_realism: u32,
 }
 
 impl Default for MapGenConfig {
@@ -29,6 +45,10 @@ impl Default for MapGenConfig {
             water_percentage: 30,
             player_count: 2,
             wrap_x: false,
+            temperature: 50,
+            rainfall: 50,
+            roughness: 50,
+            climate_realism: 80,
         }
     }
 }
@@ -92,6 +112,77 @@ impl SeededRng {
     }
 }
 
+/// Elevation, temperature and moisture of every tile, each in [0, 1].
+struct ClimateMap {
+    width: u32,
+    height: u32,
+    elevation: Vec<f32>,
+    temperature: Vec<f32>,
+    moisture: Vec<f32>,
+    /// Elevation below which tiles are water.
+    sea_level: f32,
+}
+
+impl ClimateMap {
+    fn index(&self, coord: &HexCoord) -> Option<usize> {
+        let in_map =
+            (0..self.width as i32).contains(&coord.q) && (0..self.height as i32).contains(&coord.r);
+        in_map.then(|| coord.r as usize * self.width as usize + coord.q as usize)
+    }
+
+    fn elevation_at(&self, coord: &HexCoord) -> f32 {
+        self.index(coord).map_or(0.0, |i| self.elevation[i])
+    }
+
+    fn temperature_at(&self, coord: &HexCoord) -> f32 {
+        self.index(coord).map_or(0.0, |i| self.temperature[i])
+    }
+
+    fn moisture_at(&self, coord: &HexCoord) -> f32 {
+        self.index(coord).map_or(0.0, |i| self.moisture[i])
+    }
+
+    /// Height above sea level, from 0 at the shore to 1 at the highest
+    /// peak.
+    fn altitude(&self, coord: &HexCoord) -> f32 {
+        if self.sea_level >= 1.0 {
+            return 0.0;
+        }
+        ((self.elevation_at(coord) - self.sea_level) / (1.0 - self.sea_level)).max(0.0)
+    }
+}
+
+/// Land terrain for a climate.
+fn land_terrain(temperature: f32, moisture: f32) -> Terrain {
+    if temperature < 0.12 {
+        Terrain::Snow
+    } else if temperature < 0.25 {
+        Terrain::Tundra
+    } else if moisture < 0.3 && temperature > 0.5 {
+        Terrain::Desert
+    } else if moisture < 0.5 {
+        Terrain::Plains
+    } else {
+        Terrain::Grassland
+    }
+}
+
+/// Distance of a row from the equator, from 0 in the middle of the map to
+/// 1 at the poles.
+fn latitude(row: u32, height: u32) -> f32 {
+    ((row as f32 + 0.5) / height as f32 * 2.0 - 1.0).abs()
+}
+
+/// A 0-100 knob as a fraction.
+fn percent(value: u32) -> f32 {
+    value.min(100) as f32 / 100.0
+}
+
+/// Ease interpolation so noise has no visible grid lines.
+fn smoothstep(t: f32) -> f32 {
+    t * t * (3.0 - 2.0 * t)
+}
+
 /// Generates game maps from a seed.
 pub struct MapGenerator {
     rng: SeededRng,
@@ -109,77 +200,174 @@ impl MapGenerator {
 
     /// Generate a complete map.
     pub fn generate(&mut self) -> Map {
+        self.generate_with_progress(&mut ignore_progress)
+    }
+
+    /// Generate a complete map, reporting progress after each phase.
+    pub fn generate_with_progress(&mut self, progress: &mut dyn FnMut(Progress)) -> Map {
         let (width, height) = self.config.size.dimensions();
         let mut map = Map::new(width, height, self.config.wrap_x);
 
-        // Phase 1: Generate base terrain using heightmap
-        self.generate_terrain(&mut map);
+        // Phase 1: Generate base terrain from climate, and label the
+        // landmasses it forms
+        let climate = self.generate_terrain(&mut map);
+        continents::label_continents(&mut map);
+        progress(Progress::new("terrain", 40));
 
         // Phase 2: Add features (hills, forests, etc.)
-        self.generate_features(&mut map);
+        self.generate_features(&mut map, &climate);
+        progress(Progress::new("features", 70));
 
         // Phase 3: Place resources
         self.place_resources(&mut map);
+        progress(Progress::new("resources", 85));
 
         // Phase 4: Add rivers
-        self.generate_rivers(&mut map);
+        self.generate_rivers(&mut map, &climate);
+        progress(Progress::new("rivers", 100));
 
         map
     }
 
-    /// Generate base terrain using a simple heightmap approach.
-    fn generate_terrain(&mut self, map: &mut Map) {
-        let (width, height) = (map.width, map.height);
-        let water_threshold = self.config.water_percentage as f32 / 100.0;
-
-        // Generate heightmap using multiple octaves of noise
-        let heightmap = self.generate_heightmap(width, height);
+    /// Generate base terrain from elevation, temperature and moisture
+    /// fields, returning the fields for the later phases.
+    fn generate_terrain(&mut self, map: &mut Map) -> ClimateMap {
+        let climate = self.generate_climate(map.width, map.height, map.wrap_x);
 
-        for q in 0..width as i32 {
-            for r in 0..height as i32 {
+        for q in 0..map.width as i32 {
+            for r in 0..map.height as i32 {
                 let coord = HexCoord::new(q, r);
-                let height = heightmap[r as usize * width as usize + q as usize];
-
-                let terrain = self.height_to_terrain(height, water_threshold, r, height as i32);
+                let i = climate.index(&coord).unwrap_or_default();
+                let terrain = if climate.elevation[i] < climate.sea_level {
+                    Terrain::Ocean
+                } else {
+                    land_terrain(climate.temperature[i], climate.moisture[i])
+                };
                 map.set(Tile::new(coord, terrain));
             }
         }
+
+        // Shallow water is whatever borders land
+        let mut coords: Vec<HexCoord> = map.tiles.keys().copied().collect();
+        coords.sort();
+        for coord in coords {
+            let borders_land = map
+                .neighbors(&coord)
+                .iter()
+                .any(|n| map.get(n).is_some_and(|t| !t.terrain.is_water()));
+            if let Some(tile) = map.get_mut(&coord) {
+                if tile.terrain == Terrain::Ocean && borders_land {
+                    tile.terrain = Terrain::Coast;
+                }
+            }
+        }
+
+        climate
     }
 
-    /// Generate a simple heightmap using value noise.
-    fn generate_heightmap(&mut self, width: u32, height: u32) -> Vec<f32> {
-        let size = (width * height) as usize;
-        let mut heightmap = vec![0.0f32; size];
+    /// Build the elevation, temperature and moisture fields.
+    ///
+    /// Elevation is pure noise, with sea level set so that
+    /// `water_percentage` of the map lies below it. Temperature falls off
+    /// from the equator to the poles and with height above the sea; the
+    /// `climate_realism` knob blends that in with noise. Moisture is noise,
+    /// dried out in the subtropics where deserts form.
+    fn generate_climate(&mut self, width: u32, height: u32, wrap_x: bool) -> ClimateMap {
+        let config = self.config.clone();
+        let persistence = 0.35 + percent(config.roughness) * 0.3;
+        let realism = percent(config.climate_realism);
+
+        let elevation =
+            self.noise_field(width, height, wrap_x, &[16.0, 8.0, 4.0, 2.0], persistence);
+        let temperature_noise = self.noise_field(width, height, wrap_x, &[16.0, 8.0], 0.5);
+        let moisture_noise = self.noise_field(width, height, wrap_x, &[12.0, 6.0, 3.0], 0.5);
+
+        let mut sorted = elevation.clone();
+        sorted.sort_by(|a, b| a.total_cmp(b));
+        let water_tiles = sorted.len() * config.water_percentage.min(100) as usize / 100;
+        let sea_level = match sorted.get(water_tiles) {
+            Some(level) => *level,
+            None => f32::INFINITY,
+        };
+
+        let mut temperature = vec![0.0f32; elevation.len()];
+        let mut moisture = vec![0.0f32; elevation.len()];
+        for r in 0..height {
+            let latitude = latitude(r, height);
+            // Subtropical dry belt, strongest at 30% of the way to the poles
+            let dryness = (1.0 - (latitude - 0.3).abs() / 0.15).max(0.0);
+
+            for q in 0..width {
+                let i = r as usize * width as usize + q as usize;
+                let altitude = if sea_level < 1.0 {
+                    ((elevation[i] - sea_level) / (1.0 - sea_level)).max(0.0)
+                } else {
+                    0.0
+                };
+
+                let warmth = (1.0 - latitude) * realism + temperature_noise[i] * (1.0 - realism);
+                let shift = (percent(config.temperature) - 0.5) * 0.4;
+                temperature[i] = (warmth + shift - altitude * 0.15).clamp(0.0, 1.0);
 
-        // Use multiple scales for more natural-looking terrain
-        let scales = [16.0, 8.0, 4.0, 2.0];
-        let weights = [0.5, 0.25, 0.15, 0.1];
+                let wetness = (percent(config.rainfall) - 0.5) * 0.5;
+                moisture[i] =
+                    (moisture_noise[i] + wetness - dryness * realism * 0.4).clamp(0.0, 1.0);
+            }
+        }
+
+        ClimateMap {
+            width,
+            height,
+            elevation,
+            temperature,
+            moisture,
+            sea_level,
+        }
+    }
 
-        for (scale, weight) in scales.iter().zip(weights.iter()) {
-            let grid_w = (width as f32 / scale).ceil() as usize + 1;
+    /// Fractal value noise over the map, normalized to [0, 1].
+    ///
+    /// Each octave interpolates a grid of random values spaced `scale`
+    /// tiles apart, and is weighted `persistence` times the one before. On
+    /// wrapping maps the grid wraps as well, so there is no seam.
+    fn noise_field(
+        &mut self,
+        width: u32,
+        height: u32,
+        wrap_x: bool,
+        scales: &[f32],
+        persistence: f32,
+    ) -> Vec<f32> {
+        let mut field = vec![0.0f32; (width * height) as usize];
+        let mut weight = 1.0;
+
+        for &scale in scales {
+            let cells_w = ((width as f32 / scale).round() as usize).max(1);
+            let grid_w = if wrap_x { cells_w } else { cells_w + 1 };
             let grid_h = (height as f32 / scale).ceil() as usize + 1;
 
-            // Generate random grid values
             let mut grid = vec![0.0f32; grid_w * grid_h];
             for val in grid.iter_mut() {
                 *val = self.rng.next_f32();
             }
 
-            // Interpolate to full resolution
             for r in 0..height {
                 for q in 0..width {
-                    let x = q as f32 / scale;
+                    let x = q as f32 * cells_w as f32 / width as f32;
                     let y = r as f32 / scale;
 
                     let x0 = x.floor() as usize;
                     let y0 = y.floor() as usize;
-                    let x1 = (x0 + 1).min(grid_w - 1);
+                    let x1 = if wrap_x {
+                        (x0 + 1) % grid_w
+                    } else {
+                        (x0 + 1).min(grid_w - 1)
+                    };
                     let y1 = (y0 + 1).min(grid_h - 1);
 
-                    let fx = x - x0 as f32;
-                    let fy = y - y0 as f32;
+                    let fx = smoothstep(x - x0 as f32);
+                    let fy = smoothstep(y - y0 as f32);
 
-                    // Bilinear interpolation
                     let v00 = grid[y0 * grid_w + x0];
                     let v10 = grid[y0 * grid_w + x1];
                     let v01 = grid[y1 * grid_w + x0];
@@ -187,64 +375,28 @@ impl MapGenerator {
 
                     let v0 = v00 * (1.0 - fx) + v10 * fx;
                     let v1 = v01 * (1.0 - fx) + v11 * fx;
-                    let value = v0 * (1.0 - fy) + v1 * fy;
-
                     let idx = r as usize * width as usize + q as usize;
-                    heightmap[idx] += value * weight;
+                    field[idx] += (v0 * (1.0 - fy) + v1 * fy) * weight;
                 }
             }
+            weight *= persistence;
         }
 
-        // Apply latitude-based temperature gradient
-        for r in 0..height {
-            let latitude_factor = 1.0 - (((r as f32 / height as f32) - 0.5).abs() * 2.0);
-            for q in 0..width {
-                let idx = r as usize * width as usize + q as usize;
-                // Blend height with latitude for temperature
-                heightmap[idx] = heightmap[idx] * 0.7 + latitude_factor * 0.3;
-            }
-        }
-
-        heightmap
-    }
-
-    /// Convert height value to terrain type.
-    fn height_to_terrain(
-        &self,
-        height: f32,
-        water_threshold: f32,
-        row: i32,
-        map_height: i32,
-    ) -> Terrain {
-        // Latitude affects temperature (polar regions get snow/tundra)
-        let latitude = (row as f32 / map_height as f32 - 0.5).abs() * 2.0;
-        let is_polar = latitude > 0.85;
-        let is_cold = latitude > 0.7;
-
-        if height < water_threshold * 0.7 {
-            Terrain::Ocean
-        } else if height < water_threshold {
-            Terrain::Coast
-        } else if is_polar {
-            Terrain::Snow
-        } else if is_cold {
-            Terrain::Tundra
-        } else if height > 0.8 {
-            // High altitude = desert or plains depending on moisture
-            if self.rng.clone().chance(0.4) {
-                Terrain::Desert
+        let min = field.iter().copied().fold(f32::INFINITY, f32::min);
+        let max = field.iter().copied().fold(f32::NEG_INFINITY, f32::max);
+        let range = max - min;
+        for value in field.iter_mut() {
+            *value = if range > f32::EPSILON {
+                (*value - min) / range
             } else {
-                Terrain::Plains
-            }
-        } else if height > 0.6 {
-            Terrain::Plains
-        } else {
-            Terrain::Grassland
+                0.5
+            };
         }
+        field
     }
 
     /// Add features like hills, forests, jungles, etc.
-    fn generate_features(&mut self, map: &mut Map) {
+    fn generate_features(&mut self, map: &mut Map, climate: &ClimateMap) {
         let mut coords: Vec<HexCoord> = map.tiles.keys().cloned().collect();
         coords.sort(); // Ensure deterministic iteration order
 
@@ -254,7 +406,7 @@ impl MapGenerator {
                     continue;
                 }
 
-                let feature = self.select_feature(&tile);
+                let feature = self.select_feature(&tile, climate);
                 if let Some(f) = feature {
                     if let Some(tile_mut) = map.get_mut(&coord) {
                         tile_mut.feature = Some(f);
@@ -264,56 +416,56 @@ impl MapGenerator {
         }
     }
 
-    /// Select a feature for a tile based on terrain and randomness.
-    fn select_feature(&mut self, tile: &Tile) -> Option<Feature> {
+    /// Select a feature for a tile from its climate and randomness.
+    fn select_feature(&mut self, tile: &Tile, climate: &ClimateMap) -> Option<Feature> {
+        let altitude = climate.altitude(&tile.coord);
+        let temperature = climate.temperature_at(&tile.coord);
+        let moisture = climate.moisture_at(&tile.coord);
+
+        // High ground first: peaks become mountains, uplands hills
+        if altitude > 0.85 && self.rng.chance(0.5) {
+            return Some(Feature::Mountains);
+        }
+        if altitude > 0.6 && self.rng.chance(0.5) {
+            return Some(Feature::Hills);
+        }
+        if self.rng.chance(0.08) {
+            return Some(Feature::Hills);
+        }
+
         match tile.terrain {
             Terrain::Grassland => {
-                if self.rng.chance(0.15) {
-                    Some(Feature::Hills)
-                } else if self.rng.chance(0.25) {
-                    Some(Feature::Forest)
-                } else if self.rng.chance(0.05) {
+                if temperature > 0.7 && moisture > 0.65 && self.rng.chance(0.6) {
+                    Some(Feature::Jungle)
+                } else if altitude < 0.1 && moisture > 0.7 && self.rng.chance(0.3) {
                     Some(Feature::Marsh)
+                } else if moisture > 0.55 && self.rng.chance(0.4) {
+                    Some(Feature::Forest)
                 } else {
                     None
                 }
             }
             Terrain::Plains => {
-                if self.rng.chance(0.20) {
-                    Some(Feature::Hills)
-                } else if self.rng.chance(0.15) {
+                if moisture > 0.4 && self.rng.chance(0.2) {
                     Some(Feature::Forest)
                 } else {
                     None
                 }
             }
             Terrain::Desert => {
-                if self.rng.chance(0.15) {
-                    Some(Feature::Hills)
-                } else if self.rng.chance(0.03) {
+                if self.rng.chance(0.03) {
                     Some(Feature::Oasis)
-                } else if self.rng.chance(0.08) {
-                    Some(Feature::FloodPlains)
                 } else {
                     None
                 }
             }
             Terrain::Tundra => {
-                if self.rng.chance(0.20) {
-                    Some(Feature::Hills)
-                } else if self.rng.chance(0.10) {
+                if moisture > 0.5 && self.rng.chance(0.25) {
                     Some(Feature::Forest)
                 } else {
                     None
                 }
             }
-            Terrain::Snow => {
-                if self.rng.chance(0.10) {
-                    Some(Feature::Hills)
-                } else {
-                    None
-                }
-            }
             _ => None,
         }
     }
@@ -323,9 +475,22 @@ impl MapGenerator {
         let mut coords: Vec<HexCoord> = map.tiles.keys().cloned().collect();
         coords.sort(); // Ensure deterministic iteration order
 
+        // Continents each luxury has been placed on
+        let mut luxury_continents: BTreeMap<Resource, BTreeSet<u16>> = BTreeMap::new();
+
         for coord in coords {
             if let Some(tile) = map.get(&coord).cloned() {
-                let resource = self.select_resource(&tile);
+                let mut resource = self.select_resource(&tile);
+                if let (Some(r), Some(continent)) = (resource, tile.continent) {
+                    if r.category() == ResourceCategory::Luxury {
+                        let placed = luxury_continents.entry(r).or_default();
+                        if placed.contains(&continent) || placed.len() < MAX_LUXURY_CONTINENTS {
+                            placed.insert(continent);
+                        } else {
+                            resource = None;
+                        }
+                    }
+                }
                 if let Some(r) = resource {
                     if let Some(tile_mut) = map.get_mut(&coord) {
                         tile_mut.resource = Some(r);
@@ -422,76 +587,78 @@ impl MapGenerator {
     }
 
     /// Generate rivers on the map.
-    fn generate_rivers(&mut self, map: &mut Map) {
+    fn generate_rivers(&mut self, map: &mut Map, climate: &ClimateMap) {
         let (width, height) = (map.width, map.height);
         let river_count = (width * height / 200) as usize;
 
         for _ in 0..river_count {
-            // Start rivers from hills/mountains
+            // Start rivers from high ground
             let start_q = self.rng.next_range(width) as i32;
             let start_r = self.rng.next_range(height) as i32;
             let start = HexCoord::new(start_q, start_r);
 
             if let Some(tile) = map.get(&start) {
-                if tile.feature == Some(Feature::Hills) && !tile.terrain.is_water() {
-                    self.trace_river(map, start);
+                let high = matches!(tile.feature, Some(Feature::Hills | Feature::Mountains));
+                if high && !tile.terrain.is_water() {
+                    self.trace_river(map, climate, start);
+                }
+            }
+        }
+
+        // Deserts flood along their rivers
+        let mut coords: Vec<HexCoord> = map.tiles.keys().copied().collect();
+        coords.sort();
+        for coord in coords {
+            if let Some(tile) = map.get_mut(&coord) {
+                if tile.terrain == Terrain::Desert && tile.feature.is_none() && tile.has_river() {
+                    tile.feature = Some(Feature::FloodPlains);
                 }
             }
         }
     }
 
-    /// Trace a river from a starting point to water.
-    fn trace_river(&mut self, map: &mut Map, start: HexCoord) {
+    /// Trace a river downhill from a starting point until it reaches water
+    /// or a basin.
+    fn trace_river(&mut self, map: &mut Map, climate: &ClimateMap, start: HexCoord) {
         let mut current = start;
         let max_length = 20;
 
         for _ in 0..max_length {
-            // Find neighbor closest to water or lowest elevation
             let neighbors = map.neighbors(&current);
-            let mut best_neighbor = None;
-            let mut found_water = false;
-
-            for neighbor in &neighbors {
-                if let Some(tile) = map.get(neighbor) {
-                    if tile.terrain.is_water() {
-                        found_water = true;
-                        best_neighbor = Some(*neighbor);
-                        break;
-                    }
-                    if best_neighbor.is_none() {
-                        best_neighbor = Some(*neighbor);
-                    }
-                }
-            }
-
-            if let Some(next) = best_neighbor {
-                // Add river edge between current and next
-                let edge = self.find_edge_index(&current, &next);
-                if let Some(tile) = map.get_mut(&current) {
-                    if let Some(idx) = edge {
-                        tile.river_edges[idx] = true;
-                    }
-                }
+            let water = neighbors
+                .iter()
+                .find(|n| map.get(n).is_some_and(|t| t.terrain.is_water()));
+            let next = match water {
+                Some(water) => Some(*water),
+                None => neighbors
+                    .iter()
+                    .filter(|n| climate.elevation_at(n) < climate.elevation_at(&current))
+                    .min_by(|a, b| {
+                        climate
+                            .elevation_at(a)
+                            .total_cmp(&climate.elevation_at(b))
+                            .then_with(|| (a.q, a.r).cmp(&(b.q, b.r)))
+                    })
+                    .copied(),
+            };
+
+            let Some(next) = next else {
+                break;
+            };
 
-                if found_water {
-                    break;
+            // Add river edge between current and next
+            let edge = current.direction_to(&next);
+            if let Some(tile) = map.get_mut(&current) {
+                if let Some(idx) = edge {
+                    tile.river_edges[idx] = true;
                 }
-                current = next;
-            } else {
-                break;
             }
-        }
-    }
 
-    /// Find which edge index connects two adjacent hexes.
-    fn find_edge_index(&self, from: &HexCoord, to: &HexCoord) -> Option<usize> {
-        let neighbors = from.neighbors();
-        for (i, n) in neighbors.iter().enumerate() {
-            if n == to {
-                return Some(i);
+            if water.is_some() {
+                break;
             }
+            current = next;
         }
-        None
     }
 
     /// Find suitable starting positions for players.
@@ -512,8 +679,12 @@ impl MapGenerator {
             }
         }
 
-        // Sort by score (best first)
-        candidates.sort_by(|a, b| b.1.cmp(&a.1));
+        // Sort by score (best first). Ties are broken by coordinate, since
+        // the map's iteration order differs between processes.
+        candidates.sort_by(|a, b| {
+            b.1.cmp(&a.1)
+                .then_with(|| (a.0.q, a.0.r).cmp(&(b.0.q, b.0.r)))
+        });
 
         // Select positions ensuring minimum distance
         for (coord, _score) in candidates {
@@ -548,7 +719,7 @@ impl MapGenerator {
         let mut score = 0u32;
 
         // Check tiles in radius 2
-        for neighbor_coord in coord.hexes_in_radius(2) {
+        for neighbor_coord in coord.spiral(2) {
             if let Some(tile) = map.get(&neighbor_coord) {
                 // Prefer land tiles
                 if !tile.terrain.is_water() {
@@ -606,6 +777,30 @@ mod tests {
         assert_ne!(rng1.next_u64(), rng2.next_u64());
     }
 
+    #[test]
+    fn test_map_generation_reports_progress() {
+        let config = MapGenConfig {
+            size: MapSize::Duel,
+            water_percentage: 30,
+            player_count: 2,
+            wrap_x: false,
+            ..MapGenConfig::default()
+        };
+        let mut reports = Vec::new();
+        let map = MapGenerator::new([5u8; 32], config.clone())
+            .generate_with_progress(&mut |p| reports.push(p));
+        let phases: Vec<&str> = reports.iter().map(|p| p.phase.as_str()).collect();
+        assert_eq!(phases, ["terrain", "features", "resources", "rivers"]);
+        assert!(reports.windows(2).all(|w| w[0].percent < w[1].percent));
+        assert_eq!(reports.last().unwrap().percent, 100);
+
+        // Reporting progress doesn't change the map
+        let plain = MapGenerator::new([5u8; 32], config).generate();
+        assert!(map
+            .iter()
+            .all(|(coord, tile)| plain.get(coord).unwrap().terrain == tile.terrain));
+    }
+
     #[test]
     fn test_map_generation_determinism() {
         let seed = [123u8; 32];
@@ -614,6 +809,7 @@ mod tests {
             water_percentage: 30,
             player_count: 2,
             wrap_x: false,
+            ..MapGenConfig::default()
         };
 
         let mut gen1 = MapGenerator::new(seed, config.clone());
@@ -641,6 +837,7 @@ mod tests {
             water_percentage: 30,
             player_count: 2,
             wrap_x: false,
+            ..MapGenConfig::default()
         };
 
         let mut gen = MapGenerator::new(seed, config);
@@ -653,6 +850,79 @@ mod tests {
         assert!(water_count > 0, "Map should have water");
     }
 
+    fn terrain_count(map: &Map, terrain: Terrain) -> usize {
+        map.iter().filter(|(_, t)| t.terrain == terrain).count()
+    }
+
+    #[test]
+    fn test_water_percentage() {
+        let config = MapGenConfig {
+            water_percentage: 45,
+            ..MapGenConfig::default()
+        };
+        let map = MapGenerator::new([8u8; 32], config).generate();
+
+        let water = map.iter().filter(|(_, t)| t.terrain.is_water()).count();
+        assert_eq!(water, map.tile_count() * 45 / 100);
+
+        // Coast is the water next to land
+        for (coord, tile) in map.iter() {
+            let borders_land = map
+                .neighbors(coord)
+                .iter()
+                .any(|n| !map.get(n).unwrap().terrain.is_water());
+            match tile.terrain {
+                Terrain::Coast => assert!(borders_land, "{}", coord),
+                Terrain::Ocean => assert!(!borders_land, "{}", coord),
+                _ => {}
+            }
+        }
+    }
+
+    #[test]
+    fn test_climate_bands() {
+        let map = MapGenerator::new([11u8; 32], MapGenConfig::default()).generate();
+        let rows = |from: i32, to: i32| {
+            map.iter()
+                .filter(move |(c, t)| (from..to).contains(&c.r) && !t.terrain.is_water())
+                .map(|(_, t)| t.terrain)
+        };
+
+        // The poles are frozen, the equator never is
+        let height = map.height as i32;
+        assert!(rows(0, 2)
+            .chain(rows(height - 2, height))
+            .all(|t| matches!(t, Terrain::Snow | Terrain::Tundra)));
+        assert!(rows(height / 2 - 3, height / 2 + 3)
+            .all(|t| !matches!(t, Terrain::Snow | Terrain::Tundra)));
+        assert!(terrain_count(&map, Terrain::Desert) > 0);
+    }
+
+    #[test]
+    fn test_climate_knobs() {
+        let generate = |temperature, rainfall| {
+            let config = MapGenConfig {
+                temperature,
+                rainfall,
+                ..MapGenConfig::default()
+            };
+            MapGenerator::new([12u8; 32], config).generate()
+        };
+
+        let cold = generate(20, 50);
+        let hot = generate(80, 50);
+        assert!(terrain_count(&cold, Terrain::Snow) > terrain_count(&hot, Terrain::Snow));
+
+        let dry = generate(50, 20);
+        let wet = generate(50, 80);
+        assert!(terrain_count(&dry, Terrain::Desert) > terrain_count(&wet, Terrain::Desert));
+
+        // The knobs change climate, not the lay of the land
+        assert!(cold
+            .iter()
+            .all(|(c, t)| t.terrain.is_water() == hot.get(c).unwrap().terrain.is_water()));
+    }
+
     #[test]
     fn test_starting_positions() {
         let seed = [99u8; 32];
@@ -661,6 +931,7 @@ mod tests {
             water_percentage: 30,
             player_count: 4,
             wrap_x: false,
+            ..MapGenConfig::default()
         };
 
         let mut gen = MapGenerator::new(seed, config.clone());
@@ -676,6 +947,29 @@ mod tests {
         }
     }
 
+    #[test]
+    fn test_starting_positions_deterministic() {
+        let config = MapGenConfig {
+            size: MapSize::Duel,
+            water_percentage: 30,
+            player_count: 2,
+            wrap_x: false,
+            ..MapGenConfig::default()
+        };
+
+        // Each generated map hashes its tiles differently, so this catches
+        // positions that depend on iteration order
+        let positions: Vec<Vec<HexCoord>> = (0..4)
+            .map(|_| {
+                let mut gen = MapGenerator::new([4u8; 32], config.clone());
+                let map = gen.generate();
+                gen.find_starting_positions(&map)
+            })
+            .collect();
+
+        assert!(positions.windows(2).all(|w| w[0] == w[1]));
+    }
+
     #[test]
     fn test_map_has_resources() {
         let seed = [7u8; 32];
@@ -688,6 +982,51 @@ mod tests {
         assert!(resource_count > 0, "Map should have resources");
     }
 
+    #[test]
+    fn test_generated_map_labels_continents() {
+        let mut gen = MapGenerator::new([7u8; 32], MapGenConfig::default());
+        let map = gen.generate();
+
+        for (coord, tile) in map.iter() {
+            assert_eq!(
+                tile.continent.is_some(),
+                !tile.terrain.is_water(),
+                "{}",
+                coord
+            );
+        }
+    }
+
+    #[test]
+    fn test_luxuries_limited_to_few_continents() {
+        // Desert hill islands in every third column
+        let mut map = Map::filled(30, 20, Terrain::Ocean);
+        for q in (0..30).step_by(3) {
+            for r in 0..20 {
+                let tile = map.get_mut(&HexCoord::new(q, r)).unwrap();
+                tile.terrain = Terrain::Desert;
+                tile.feature = Some(Feature::Hills);
+            }
+        }
+        assert_eq!(continents::label_continents(&mut map), 10);
+
+        let mut gen = MapGenerator::new([3u8; 32], MapGenConfig::default());
+        gen.place_resources(&mut map);
+
+        let mut luxuries: BTreeMap<Resource, BTreeSet<u16>> = BTreeMap::new();
+        for tile in map.tiles.values() {
+            if let (Some(r), Some(continent)) = (tile.resource, tile.continent) {
+                if r.category() == ResourceCategory::Luxury {
+                    luxuries.entry(r).or_default().insert(continent);
+                }
+            }
+        }
+        assert!(!luxuries.is_empty());
+        assert!(luxuries
+            .values()
+            .all(|continents| continents.len() <= MAX_LUXURY_CONTINENTS));
+    }
+
     #[test]
     fn test_map_has_features() {
         let seed = [13u8; 32];
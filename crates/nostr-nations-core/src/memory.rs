@@ -656,6 +656,7 @@ pub type CompactCityId = u16;
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::types::GameId;
 
     // ==================== ObjectPool Tests ====================
 
@@ -989,7 +990,7 @@ mod tests {
         use crate::settings::GameSettings;
 
         let settings = GameSettings::new("Test".to_string());
-        let game = GameState::new("test_game".to_string(), settings, [0u8; 32]);
+        let game = GameState::new(GameId::new("test_game"), settings, [0u8; 32]);
 
         let stats = MemoryStats::calculate(&game);
         assert!(stats.game_state_bytes > 0);
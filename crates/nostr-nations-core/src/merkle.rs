@@ -0,0 +1,392 @@
+//! Merkle commitments over event batches for light verification.
+//!
+//! Verifying a game by replaying its whole chain is too heavy for phones.
+//! Each [`StateSnapshot`] therefore carries an [`EventsRoot`]: the root of a
+//! Merkle tree over the actions executed since the previous snapshot. A
+//! light client that has a snapshot can check that a specific event belongs
+//! to the chain from the event and a short [`MerkleProof`], without
+//! downloading the rest of the batch.
+//!
+//! A leaf hashes the acting player and the action's content, so it can be
+//! computed before the event is signed. Leaves and inner nodes are hashed
+//! with different prefixes, and a node without a sibling moves up a level
+//! unchanged instead of being paired with itself.
+//!
+//! [`StateSnapshot`]: crate::snapshot::StateSnapshot
+
+use crate::events::{GameAction, GameEvent};
+use crate::types::PlayerSlot;
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+use ts_rs::TS;
+
+/// A leaf or node hash.
+pub type MerkleHash = [u8; 32];
+
+/// Prefix of leaf hashes.
+const LEAF_PREFIX: u8 = 0x00;
+
+/// Prefix of inner node hashes.
+const NODE_PREFIX: u8 = 0x01;
+
+/// Check if an action is committed to in its batch.
+///
+/// Game creation is implied by the first snapshot's state, snapshots close
+/// a batch rather than belong to one, and extension or unrecognized events
+/// are never executed.
+pub fn is_committed(action: &GameAction) -> bool {
+    !matches!(
+        action,
+        GameAction::CreateGame { .. }
+            | GameAction::Snapshot { .. }
+            | GameAction::Extension { .. }
+            | GameAction::Unrecognized { .. }
+    )
+}
+
+/// Leaf hash of an action taken by a player.
+pub fn action_leaf(player_id: PlayerSlot, action: &GameAction) -> MerkleHash {
+    let mut hasher = Sha256::new();
+    hasher.update([LEAF_PREFIX, player_id.0]);
+    hasher.update(action.content().as_bytes());
+    hasher.finalize().into()
+}
+
+/// Leaf hash of an event.
+pub fn event_leaf(event: &GameEvent) -> MerkleHash {
+    action_leaf(event.player_id, &event.action)
+}
+
+fn node_hash(left: &MerkleHash, right: &MerkleHash) -> MerkleHash {
+    let mut hasher = Sha256::new();
+    hasher.update([NODE_PREFIX]);
+    hasher.update(left);
+    hasher.update(right);
+    hasher.finalize().into()
+}
+
+/// Hash one level of the tree into the next.
+fn next_level(level: &[MerkleHash]) -> Vec<MerkleHash> {
+    level
+        .chunks(2)
+        .map(|pair| match pair {
+            [left, right] => node_hash(left, right),
+            [single] => *single,
+            _ => unreachable!("chunks of two"),
+        })
+        .collect()
+}
+
+/// Root of a tree over `leaves`. An empty batch has an all-zero root.
+pub fn merkle_root(leaves: &[MerkleHash]) -> MerkleHash {
+    if leaves.is_empty() {
+        return [0; 32];
+    }
+    let mut level = leaves.to_vec();
+    while level.len() > 1 {
+        level = next_level(&level);
+    }
+    level[0]
+}
+
+/// Root of the actions executed between two snapshots.
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize, JsonSchema, TS)]
+pub struct EventsRoot {
+    /// Number of actions in the batch.
+    pub count: u32,
+    /// Hex-encoded Merkle root.
+    pub root: String,
+}
+
+impl EventsRoot {
+    /// Commit to a batch of leaves.
+    pub fn from_leaves(leaves: &[MerkleHash]) -> Self {
+        Self {
+            count: leaves.len() as u32,
+            root: to_hex(&merkle_root(leaves)),
+        }
+    }
+
+    /// Check that an event is part of this batch.
+    pub fn contains(&self, event: &GameEvent, proof: &MerkleProof) -> bool {
+        proof.count == self.count
+            && proof
+                .root(&event_leaf(event))
+                .is_some_and(|root| to_hex(&root) == self.root)
+    }
+}
+
+/// Path from a leaf to the root of its batch.
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize, JsonSchema, TS)]
+pub struct MerkleProof {
+    /// Position of the leaf in the batch.
+    pub index: u32,
+    /// Number of leaves in the batch.
+    pub count: u32,
+    /// Hex-encoded sibling hashes from the leaf up. Levels where the node
+    /// has no sibling are skipped.
+    pub siblings: Vec<String>,
+}
+
+impl MerkleProof {
+    /// Build the proof for the leaf at `index`.
+    pub fn new(leaves: &[MerkleHash], index: usize) -> Option<Self> {
+        if index >= leaves.len() {
+            return None;
+        }
+        let mut siblings = Vec::new();
+        let mut level = leaves.to_vec();
+        let mut i = index;
+        while level.len() > 1 {
+            if let Some(sibling) = level.get(i ^ 1) {
+                siblings.push(to_hex(sibling));
+            }
+            level = next_level(&level);
+            i /= 2;
+        }
+        Some(Self {
+            index: index as u32,
+            count: leaves.len() as u32,
+            siblings,
+        })
+    }
+
+    /// Root implied by this proof for a leaf, or `None` if the proof is
+    /// malformed.
+    pub fn root(&self, leaf: &MerkleHash) -> Option<MerkleHash> {
+        if self.index >= self.count {
+            return None;
+        }
+        let mut siblings = self.siblings.iter();
+        let mut hash = *leaf;
+        let mut i = self.index as usize;
+        let mut width = self.count as usize;
+        while width > 1 {
+            if i ^ 1 < width {
+                let sibling = from_hex(siblings.next()?)?;
+                hash = if i.is_multiple_of(2) {
+                    node_hash(&hash, &sibling)
+                } else {
+                    node_hash(&sibling, &hash)
+                };
+            }
+            i /= 2;
+            width = width.div_ceil(2);
+        }
+        siblings.next().is_none().then_some(hash)
+    }
+}
+
+/// Leaves of the batch being built since the last snapshot.
+#[derive(Clone, Debug, Default)]
+pub struct EventBatch {
+    leaves: Vec<MerkleHash>,
+    /// Whether every action since the batch started was seen. An engine
+    /// loaded mid-batch can't commit to it.
+    complete: bool,
+}
+
+impl EventBatch {
+    /// Start a batch at the beginning of a game or right after a snapshot.
+    pub fn new() -> Self {
+        Self {
+            leaves: Vec::new(),
+            complete: true,
+        }
+    }
+
+    /// A batch joined partway through.
+    pub fn partial() -> Self {
+        Self::default()
+    }
+
+    /// Record an executed action.
+    pub fn push(&mut self, player_id: PlayerSlot, action: &GameAction) {
+        if is_committed(action) {
+            self.leaves.push(action_leaf(player_id, action));
+        }
+    }
+
+    /// Forget the most recent action, when it is undone.
+    pub fn pop(&mut self, action: &GameAction) {
+        if is_committed(action) {
+            self.leaves.pop();
+        }
+    }
+
+    /// Root of the batch so far, if it is complete.
+    pub fn root(&self) -> Option<EventsRoot> {
+        self.complete.then(|| EventsRoot::from_leaves(&self.leaves))
+    }
+
+    /// Close the batch and start the next one.
+    pub fn reset(&mut self) {
+        *self = Self::new();
+    }
+}
+
+/// Batches of an event chain, one per snapshot, as `(snapshot index,
+/// events in the batch)`.
+///
+/// Each batch runs from just after the previous snapshot (or the start of
+/// the chain) to the snapshot that commits to it. Events after the last
+/// snapshot aren't committed yet and aren't returned.
+pub fn batches(events: &[GameEvent]) -> Vec<(usize, Vec<&GameEvent>)> {
+    let mut batches = Vec::new();
+    let mut batch = Vec::new();
+    for (i, event) in events.iter().enumerate() {
+        if matches!(event.action, GameAction::Snapshot { .. }) {
+            batches.push((i, std::mem::take(&mut batch)));
+        } else if event.is_executable() && is_committed(&event.action) {
+            batch.push(event);
+        }
+    }
+    batches
+}
+
+/// Prove that the event with `event_id` is in the chain.
+///
+/// Returns the index of the snapshot that commits to it and the proof, or
+/// `None` if the event isn't in a committed batch.
+pub fn prove_event(events: &[GameEvent], event_id: &str) -> Option<(usize, MerkleProof)> {
+    batches(events).into_iter().find_map(|(snapshot, batch)| {
+        let index = batch.iter().position(|e| e.id == event_id)?;
+        let leaves: Vec<MerkleHash> = batch.iter().map(|e| event_leaf(e)).collect();
+        MerkleProof::new(&leaves, index).map(|proof| (snapshot, proof))
+    })
+}
+
+fn to_hex(bytes: &[u8]) -> String {
+    bytes.iter().map(|b| format!("{:02x}", b)).collect()
+}
+
+fn from_hex(s: &str) -> Option<MerkleHash> {
+    if s.len() != 64 {
+        return None;
+    }
+    let mut out = [0; 32];
+    for (i, byte) in out.iter_mut().enumerate() {
+        *byte = u8::from_str_radix(s.get(i * 2..i * 2 + 2)?, 16).ok()?;
+    }
+    Some(out)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::game_state::GameState;
+    use crate::settings::GameSettings;
+    use crate::snapshot::StateSnapshot;
+    use crate::types::{GameId, UnitId};
+
+    fn leaves(n: usize) -> Vec<MerkleHash> {
+        (0..n)
+            .map(|i| {
+                action_leaf(
+                    PlayerSlot(i as u8 % 2),
+                    &GameAction::FortifyUnit {
+                        unit_id: UnitId(i as u64),
+                    },
+                )
+            })
+            .collect()
+    }
+
+    #[test]
+    fn test_proofs_verify_for_every_leaf_and_size() {
+        for n in 1..=9 {
+            let leaves = leaves(n);
+            let root = merkle_root(&leaves);
+            for (i, leaf) in leaves.iter().enumerate() {
+                let proof = MerkleProof::new(&leaves, i).unwrap();
+                assert_eq!(proof.root(leaf), Some(root), "leaf {} of {}", i, n);
+            }
+            assert!(MerkleProof::new(&leaves, n).is_none());
+        }
+    }
+
+    #[test]
+    fn test_tampered_proof_fails() {
+        let leaves = leaves(5);
+        let root = merkle_root(&leaves);
+        let proof = MerkleProof::new(&leaves, 2).unwrap();
+
+        // Wrong leaf
+        assert_ne!(proof.root(&leaves[3]), Some(root));
+
+        // Wrong position
+        let mut moved = proof.clone();
+        moved.index = 3;
+        assert_ne!(moved.root(&leaves[2]), Some(root));
+
+        // Extra or missing siblings
+        let mut extra = proof.clone();
+        extra.siblings.push(extra.siblings[0].clone());
+        assert_eq!(extra.root(&leaves[2]), None);
+        let mut short = proof;
+        short.siblings.pop();
+        assert_eq!(short.root(&leaves[2]), None);
+    }
+
+    #[test]
+    fn test_prove_event_in_chain() {
+        let game_id = GameId::new("merkle");
+        let event = |i: u32, action: GameAction| {
+            let mut event = GameEvent::new(game_id.clone(), PlayerSlot(0), None, 1, i, action);
+            event.id = format!("evt{}", i);
+            event
+        };
+        let fortify = |i: u32| GameAction::FortifyUnit {
+            unit_id: UnitId(i as u64),
+        };
+
+        let mut events: Vec<GameEvent> = (0..3).map(|i| event(i, fortify(i))).collect();
+        let leaves: Vec<MerkleHash> = events.iter().map(event_leaf).collect();
+        let root = EventsRoot::from_leaves(&leaves);
+        events.push(event(
+            3,
+            GameAction::Snapshot {
+                snapshot: StateSnapshot {
+                    events_root: Some(root.clone()),
+                    ..StateSnapshot::capture(&GameState::new(
+                        game_id.clone(),
+                        GameSettings::new("Merkle".to_string()),
+                        [1; 32],
+                    ))
+                },
+            },
+        ));
+        events.push(event(4, fortify(4)));
+
+        let (snapshot, proof) = prove_event(&events, "evt1").unwrap();
+        assert_eq!(snapshot, 3);
+        assert!(root.contains(&events[1], &proof));
+        assert!(!root.contains(&events[2], &proof));
+
+        // Events after the last snapshot aren't committed yet
+        assert!(prove_event(&events, "evt4").is_none());
+    }
+
+    #[test]
+    fn test_batch_tracks_committed_actions() {
+        let mut batch = EventBatch::new();
+        batch.push(PlayerSlot(0), &GameAction::EndTurn);
+        batch.push(
+            PlayerSlot(0),
+            &GameAction::Extension {
+                kind: 31000,
+                content: String::new(),
+            },
+        );
+        batch.push(PlayerSlot(1), &GameAction::EndTurn);
+        assert_eq!(batch.root().unwrap().count, 2);
+
+        batch.pop(&GameAction::EndTurn);
+        assert_eq!(batch.root().unwrap().count, 1);
+
+        batch.reset();
+        assert_eq!(batch.root().unwrap(), EventsRoot::from_leaves(&[]));
+        assert!(EventBatch::partial().root().is_none());
+    }
+}
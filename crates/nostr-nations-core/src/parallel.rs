@@ -0,0 +1,72 @@
+//! Deterministic data parallelism for turn processing.
+//!
+//! [`par_map`] splits a slice into contiguous chunks, maps each chunk on
+//! its own scoped thread and concatenates the results in input order. The
+//! output is therefore identical to a sequential `map` no matter how many
+//! threads run, which keeps every peer's game state in sync.
+//!
+//! Small inputs and targets without threads (WASM) run sequentially.
+
+/// Inputs shorter than this are mapped on the calling thread.
+pub const PARALLEL_THRESHOLD: usize = 64;
+
+/// Number of worker threads to split work across.
+fn worker_count() -> usize {
+    if cfg!(target_arch = "wasm32") {
+        return 1;
+    }
+    std::thread::available_parallelism().map_or(1, |n| n.get())
+}
+
+/// Map every item, in parallel when worthwhile, preserving input order.
+pub fn par_map<T, R, F>(items: &[T], f: F) -> Vec<R>
+where
+    T: Sync,
+    R: Send,
+    F: Fn(&T) -> R + Sync,
+{
+    let workers = worker_count();
+    if items.len() < PARALLEL_THRESHOLD || workers < 2 {
+        return items.iter().map(f).collect();
+    }
+
+    let chunk_size = items.len().div_ceil(workers);
+    let f = &f;
+    std::thread::scope(|scope| {
+        let handles: Vec<_> = items
+            .chunks(chunk_size)
+            .map(|chunk| scope.spawn(move || chunk.iter().map(f).collect::<Vec<R>>()))
+            .collect();
+        handles
+            .into_iter()
+            .flat_map(|handle| match handle.join() {
+                Ok(results) => results,
+                Err(panic) => std::panic::resume_unwind(panic),
+            })
+            .collect()
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_par_map_small_input() {
+        let items = [1, 2, 3];
+        assert_eq!(par_map(&items, |x| x * 2), vec![2, 4, 6]);
+    }
+
+    #[test]
+    fn test_par_map_preserves_order() {
+        let items: Vec<u64> = (0..10_000).collect();
+        let sequential: Vec<u64> = items.iter().map(|x| x * x + 1).collect();
+        assert_eq!(par_map(&items, |x| x * x + 1), sequential);
+    }
+
+    #[test]
+    fn test_par_map_empty() {
+        let items: [u32; 0] = [];
+        assert!(par_map(&items, |x| *x).is_empty());
+    }
+}
@@ -0,0 +1,561 @@
+//! Hierarchical, cached pathfinding for large maps.
+//!
+//! The map is split into square regions of [`REGION_SIZE`] tiles. For each
+//! movement class a region graph records which regions connect and how
+//! expensive they are to cross, along with the connected components of
+//! passable tiles. A query first rejects goals in another component, then
+//! finds a route through the region graph and runs tile-level A* only
+//! inside that corridor. Results are cached until a tile they depend on
+//! changes.
+//!
+//! Corridor paths can be slightly longer than [`find_path`]'s optimum, but
+//! they depend only on the map, never on what was cached before.
+
+use crate::hex::HexCoord;
+use crate::map::Map;
+use crate::pathfinding::{find_path, find_path_within, step_cost, PathConfig, PathResult};
+use crate::unit::UnitCategory;
+use std::cmp::Reverse;
+use std::collections::{BTreeSet, BinaryHeap, HashMap, HashSet, VecDeque};
+
+/// Width and height of a region in tiles.
+pub const REGION_SIZE: i32 = 8;
+
+/// Default number of paths kept in the cache.
+pub const DEFAULT_PATH_CACHE_CAPACITY: usize = 1024;
+
+/// A region's position in the region grid.
+pub type RegionId = (i32, i32);
+
+/// Get the region containing a tile.
+pub fn region_of(coord: &HexCoord) -> RegionId {
+    (
+        coord.q.div_euclid(REGION_SIZE),
+        coord.r.div_euclid(REGION_SIZE),
+    )
+}
+
+/// Movement rules that share a region graph.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+enum MovementClass {
+    Land,
+    Embarked,
+    Naval,
+    Air,
+}
+
+impl MovementClass {
+    fn of(config: &PathConfig) -> Self {
+        match config.unit_category {
+            UnitCategory::Naval => MovementClass::Naval,
+            UnitCategory::Air => MovementClass::Air,
+            _ if config.embarked => MovementClass::Embarked,
+            _ => MovementClass::Land,
+        }
+    }
+
+    fn config(self) -> PathConfig {
+        let (unit_category, embarked) = match self {
+            MovementClass::Land => (UnitCategory::Melee, false),
+            MovementClass::Embarked => (UnitCategory::Melee, true),
+            MovementClass::Naval => (UnitCategory::Naval, false),
+            MovementClass::Air => (UnitCategory::Air, false),
+        };
+        PathConfig {
+            unit_category,
+            embarked,
+            ..PathConfig::default()
+        }
+    }
+}
+
+/// What a region looks like to the region graph.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+struct RegionSummary {
+    /// Average cost to enter a passable tile in the region (x10).
+    cost: u32,
+    /// Adjacent regions reachable in a single step.
+    neighbors: BTreeSet<RegionId>,
+}
+
+/// Region graph and tile components for one movement class.
+#[derive(Clone, Debug, Default)]
+struct RegionGraph {
+    regions: HashMap<RegionId, RegionSummary>,
+    /// Connected component of every passable tile.
+    components: HashMap<HexCoord, u32>,
+}
+
+impl RegionGraph {
+    fn build(map: &Map, config: &PathConfig) -> Self {
+        let region_ids: BTreeSet<RegionId> = map.tiles.keys().map(region_of).collect();
+        let regions = region_ids
+            .into_iter()
+            .map(|id| (id, summarize_region(map, config, id)))
+            .collect();
+        Self {
+            regions,
+            components: label_components(map, config),
+        }
+    }
+
+    /// Find the cheapest chain of regions between two regions.
+    fn route(&self, from: RegionId, to: RegionId) -> Option<Vec<RegionId>> {
+        let mut best: HashMap<RegionId, u32> = HashMap::from([(from, 0)]);
+        let mut came_from: HashMap<RegionId, RegionId> = HashMap::new();
+        let mut open = BinaryHeap::from([Reverse((0u32, from))]);
+
+        while let Some(Reverse((cost, region))) = open.pop() {
+            if region == to {
+                let mut route = vec![to];
+                let mut current = to;
+                while let Some(&prev) = came_from.get(&current) {
+                    route.push(prev);
+                    current = prev;
+                }
+                route.reverse();
+                return Some(route);
+            }
+            if cost > best[&region] {
+                continue;
+            }
+            let Some(summary) = self.regions.get(&region) else {
+                continue;
+            };
+            for next in &summary.neighbors {
+                let step = self.regions.get(next).map_or(u32::MAX, |s| s.cost);
+                let total = cost.saturating_add(step.saturating_mul(REGION_SIZE as u32));
+                if total < *best.get(next).unwrap_or(&u32::MAX) {
+                    best.insert(*next, total);
+                    came_from.insert(*next, region);
+                    open.push(Reverse((total, *next)));
+                }
+            }
+        }
+        None
+    }
+
+    /// Widen a region route by one region on every side.
+    fn corridor(&self, route: &[RegionId]) -> HashSet<RegionId> {
+        let mut corridor: HashSet<RegionId> = route.iter().copied().collect();
+        for region in route {
+            if let Some(summary) = self.regions.get(region) {
+                corridor.extend(summary.neighbors.iter().copied());
+            }
+        }
+        corridor
+    }
+}
+
+/// Get every tile coordinate of a region that exists on the map.
+fn region_tiles(map: &Map, region: RegionId) -> impl Iterator<Item = HexCoord> + '_ {
+    let (rq, rr) = region;
+    (0..REGION_SIZE).flat_map(move |dq| {
+        (0..REGION_SIZE).filter_map(move |dr| {
+            let coord = HexCoord::new(rq * REGION_SIZE + dq, rr * REGION_SIZE + dr);
+            map.tiles.contains_key(&coord).then_some(coord)
+        })
+    })
+}
+
+/// Check if a unit could stand on a tile.
+fn is_passable(map: &Map, coord: &HexCoord, config: &PathConfig) -> bool {
+    step_cost(map, coord, coord, config).is_some()
+}
+
+fn summarize_region(map: &Map, config: &PathConfig, region: RegionId) -> RegionSummary {
+    let mut total = 0u32;
+    let mut passable = 0u32;
+    let mut neighbors = BTreeSet::new();
+
+    for coord in region_tiles(map, region) {
+        let Some(cost) = step_cost(map, &coord, &coord, config) else {
+            continue;
+        };
+        total = total.saturating_add(cost);
+        passable += 1;
+
+        for neighbor in map.neighbors(&coord) {
+            let other = region_of(&neighbor);
+            if other != region && is_passable(map, &neighbor, config) {
+                neighbors.insert(other);
+            }
+        }
+    }
+
+    RegionSummary {
+        cost: total.checked_div(passable).unwrap_or(0),
+        neighbors,
+    }
+}
+
+/// Flood-fill passable tiles into connected components.
+///
+/// Tiles are visited in coordinate order so labels are stable.
+fn label_components(map: &Map, config: &PathConfig) -> HashMap<HexCoord, u32> {
+    let mut coords: Vec<HexCoord> = map.tiles.keys().copied().collect();
+    coords.sort_by_key(|c| (c.q, c.r));
+
+    let mut components = HashMap::new();
+    let mut next_label = 0;
+    for start in coords {
+        if components.contains_key(&start) || !is_passable(map, &start, config) {
+            continue;
+        }
+        components.insert(start, next_label);
+        let mut frontier = VecDeque::from([start]);
+        while let Some(coord) = frontier.pop_front() {
+            for neighbor in map.neighbors(&coord) {
+                if !components.contains_key(&neighbor) && is_passable(map, &neighbor, config) {
+                    components.insert(neighbor, next_label);
+                    frontier.push_back(neighbor);
+                }
+            }
+        }
+        next_label += 1;
+    }
+    components
+}
+
+/// Counters for cache effectiveness.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct PathCacheStats {
+    /// Queries answered from the cache.
+    pub hits: u64,
+    /// Queries that ran a search.
+    pub misses: u64,
+    /// Queries rejected because the goal is in another component.
+    pub unreachable: u64,
+    /// Cached paths dropped by invalidation or eviction.
+    pub evicted: u64,
+}
+
+/// A cached path and the regions its search looked at.
+#[derive(Clone, Debug)]
+struct CachedPath {
+    result: Option<PathResult>,
+    /// Regions the search could use; `None` means the whole map.
+    corridor: Option<HashSet<RegionId>>,
+}
+
+type PathKey = (HexCoord, HexCoord, MovementClass);
+
+/// Hierarchical pathfinder that caches results between queries.
+///
+/// Call [`PathCache::invalidate`] whenever a tile's terrain, feature, road
+/// or owner changes so dependent paths are recomputed.
+#[derive(Clone, Debug)]
+pub struct PathCache {
+    capacity: usize,
+    graphs: HashMap<MovementClass, RegionGraph>,
+    paths: HashMap<PathKey, CachedPath>,
+    /// Insertion order, for evicting the oldest paths first.
+    order: VecDeque<PathKey>,
+    stats: PathCacheStats,
+}
+
+impl Default for PathCache {
+    fn default() -> Self {
+        Self::new(DEFAULT_PATH_CACHE_CAPACITY)
+    }
+}
+
+impl PathCache {
+    /// Create a cache holding up to `capacity` paths.
+    pub fn new(capacity: usize) -> Self {
+        Self {
+            capacity: capacity.max(1),
+            graphs: HashMap::new(),
+            paths: HashMap::new(),
+            order: VecDeque::new(),
+            stats: PathCacheStats::default(),
+        }
+    }
+
+    /// Get cache counters.
+    pub fn stats(&self) -> PathCacheStats {
+        self.stats
+    }
+
+    /// Number of cached paths.
+    pub fn len(&self) -> usize {
+        self.paths.len()
+    }
+
+    /// Check if no paths are cached.
+    pub fn is_empty(&self) -> bool {
+        self.paths.is_empty()
+    }
+
+    /// Find a path, reusing a cached result when possible.
+    ///
+    /// `max_movement` in the config is ignored, as with [`find_path`].
+    /// Region graphs only know terrain, so configs with closed borders
+    /// are searched directly and not cached.
+    pub fn find_path(
+        &mut self,
+        map: &Map,
+        start: HexCoord,
+        goal: HexCoord,
+        config: &PathConfig,
+    ) -> Option<PathResult> {
+        if !config.closed_borders.is_empty() {
+            return find_path(map, start, goal, config);
+        }
+
+        let class = MovementClass::of(config);
+        let key = (start, goal, class);
+        if let Some(cached) = self.paths.get(&key) {
+            self.stats.hits += 1;
+            return cached.result.clone();
+        }
+        self.stats.misses += 1;
+
+        let class_config = class.config();
+        let graph = self
+            .graphs
+            .entry(class)
+            .or_insert_with(|| RegionGraph::build(map, &class_config));
+
+        let cached = search(graph, map, start, goal, &class_config);
+        if cached.result.is_none() && cached.corridor.is_some() {
+            self.stats.unreachable += 1;
+        }
+        let result = cached.result.clone();
+        self.insert(key, cached);
+        result
+    }
+
+    /// Drop everything that depends on a tile after it changes.
+    pub fn invalidate(&mut self, map: &Map, coord: HexCoord) {
+        let region = region_of(&coord);
+        let mut rebuilt = Vec::new();
+        for (class, graph) in &mut self.graphs {
+            let config = class.config();
+            let was_passable = graph.components.contains_key(&coord);
+            let summary = summarize_region(map, &config, region);
+            if was_passable != is_passable(map, &coord, &config)
+                || graph.regions.get(&region) != Some(&summary)
+            {
+                // The region graph itself changed, so any route may differ
+                *graph = RegionGraph::build(map, &config);
+                rebuilt.push(*class);
+            }
+        }
+
+        let stale: Vec<PathKey> = self
+            .paths
+            .iter()
+            .filter(|((_, _, class), cached)| {
+                rebuilt.contains(class)
+                    || cached
+                        .corridor
+                        .as_ref()
+                        .is_none_or(|corridor| corridor.contains(&region))
+            })
+            .map(|(key, _)| *key)
+            .collect();
+        for key in stale {
+            self.remove(&key);
+        }
+    }
+
+    /// Drop all cached paths and region graphs.
+    pub fn clear(&mut self) {
+        self.stats.evicted += self.paths.len() as u64;
+        self.graphs.clear();
+        self.paths.clear();
+        self.order.clear();
+    }
+
+    fn insert(&mut self, key: PathKey, cached: CachedPath) {
+        while self.paths.len() >= self.capacity {
+            let Some(oldest) = self.order.pop_front() else {
+                break;
+            };
+            if self.paths.remove(&oldest).is_some() {
+                self.stats.evicted += 1;
+            }
+        }
+        self.paths.insert(key, cached);
+        self.order.push_back(key);
+    }
+
+    fn remove(&mut self, key: &PathKey) {
+        if self.paths.remove(key).is_some() {
+            self.stats.evicted += 1;
+            self.order.retain(|k| k != key);
+        }
+    }
+}
+
+/// Run a hierarchical search against a region graph.
+fn search(
+    graph: &RegionGraph,
+    map: &Map,
+    start: HexCoord,
+    goal: HexCoord,
+    config: &PathConfig,
+) -> CachedPath {
+    let components = (graph.components.get(&start), graph.components.get(&goal));
+    let (Some(from), Some(to)) = components else {
+        // Odd starts (e.g. a unit stranded on impassable terrain) fall
+        // back to a plain search
+        return CachedPath {
+            result: find_path(map, start, goal, config),
+            corridor: None,
+        };
+    };
+    if from != to {
+        return CachedPath {
+            result: None,
+            corridor: Some(HashSet::from([region_of(&start), region_of(&goal)])),
+        };
+    }
+
+    if let Some(route) = graph.route(region_of(&start), region_of(&goal)) {
+        let corridor = graph.corridor(&route);
+        let result = find_path_within(map, start, goal, config, |coord| {
+            corridor.contains(&region_of(coord))
+        });
+        if result.is_some() {
+            return CachedPath {
+                result,
+                corridor: Some(corridor),
+            };
+        }
+    }
+
+    // The corridor was too narrow; search the whole map
+    CachedPath {
+        result: find_path(map, start, goal, config),
+        corridor: None,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::terrain::{Feature, Terrain};
+
+    fn big_map() -> Map {
+        Map::filled(40, 24, Terrain::Grassland)
+    }
+
+    // ==================== Region Tests ====================
+
+    #[test]
+    fn test_region_of_groups_tiles() {
+        assert_eq!(region_of(&HexCoord::new(0, 0)), (0, 0));
+        assert_eq!(region_of(&HexCoord::new(7, 7)), (0, 0));
+        assert_eq!(region_of(&HexCoord::new(8, 3)), (1, 0));
+        assert_eq!(region_of(&HexCoord::new(-1, 0)), (-1, 0));
+    }
+
+    #[test]
+    fn test_region_graph_links_neighbors() {
+        let map = big_map();
+        let graph = RegionGraph::build(&map, &PathConfig::default());
+        let origin = &graph.regions[&(0, 0)];
+        assert!(origin.neighbors.contains(&(1, 0)));
+        assert!(origin.neighbors.contains(&(0, 1)));
+        assert!(!origin.neighbors.contains(&(2, 0)));
+    }
+
+    // ==================== Query Tests ====================
+
+    #[test]
+    fn test_cached_path_matches_find_path_cost_on_open_map() {
+        let map = big_map();
+        let config = PathConfig::default();
+        let mut cache = PathCache::default();
+        let start = HexCoord::new(1, 1);
+        let goal = HexCoord::new(35, 20);
+
+        let direct = find_path(&map, start, goal, &config).unwrap();
+        let cached = cache.find_path(&map, start, goal, &config).unwrap();
+        assert_eq!(cached.total_cost, direct.total_cost);
+        assert_eq!(cached.path.first(), Some(&start));
+        assert_eq!(cached.path.last(), Some(&goal));
+    }
+
+    #[test]
+    fn test_second_query_hits_cache() {
+        let map = big_map();
+        let config = PathConfig::default();
+        let mut cache = PathCache::default();
+        let (start, goal) = (HexCoord::new(1, 1), HexCoord::new(30, 10));
+
+        let first = cache.find_path(&map, start, goal, &config);
+        let second = cache.find_path(&map, start, goal, &config);
+        assert_eq!(first.map(|p| p.path), second.map(|p| p.path),);
+        assert_eq!(cache.stats().hits, 1);
+        assert_eq!(cache.stats().misses, 1);
+    }
+
+    #[test]
+    fn test_other_island_rejected_without_search() {
+        let mut map = big_map();
+        // A wall of water splits the map in two
+        for r in 0..24 {
+            map.get_mut(&HexCoord::new(20, r)).unwrap().terrain = Terrain::Ocean;
+        }
+        let mut cache = PathCache::default();
+        let result = cache.find_path(
+            &map,
+            HexCoord::new(2, 2),
+            HexCoord::new(30, 2),
+            &PathConfig::default(),
+        );
+        assert!(result.is_none());
+        assert_eq!(cache.stats().unreachable, 1);
+    }
+
+    // ==================== Invalidation Tests ====================
+
+    #[test]
+    fn test_invalidate_recomputes_blocked_path() {
+        let mut map = big_map();
+        let config = PathConfig::default();
+        let mut cache = PathCache::default();
+        let (start, goal) = (HexCoord::new(2, 2), HexCoord::new(6, 2));
+
+        let before = cache.find_path(&map, start, goal, &config).unwrap();
+        let blocked = before.path[2];
+        map.get_mut(&blocked).unwrap().feature = Some(Feature::Mountains);
+        cache.invalidate(&map, blocked);
+
+        let after = cache.find_path(&map, start, goal, &config).unwrap();
+        assert!(!after.path.contains(&blocked));
+        assert_eq!(cache.stats().misses, 2);
+    }
+
+    #[test]
+    fn test_invalidate_keeps_unrelated_paths() {
+        let map = big_map();
+        let config = PathConfig::default();
+        let mut cache = PathCache::default();
+        let near = (HexCoord::new(1, 1), HexCoord::new(3, 1));
+        let far = (HexCoord::new(33, 20), HexCoord::new(35, 20));
+        cache.find_path(&map, near.0, near.1, &config);
+        cache.find_path(&map, far.0, far.1, &config);
+
+        // Nothing about the tile changed, so only paths through its region go
+        cache.invalidate(&map, HexCoord::new(2, 2));
+        assert_eq!(cache.len(), 1);
+        cache.find_path(&map, far.0, far.1, &config);
+        assert_eq!(cache.stats().hits, 1);
+    }
+
+    #[test]
+    fn test_capacity_evicts_oldest() {
+        let map = big_map();
+        let config = PathConfig::default();
+        let mut cache = PathCache::new(2);
+        for q in 1..4 {
+            cache.find_path(&map, HexCoord::new(0, 0), HexCoord::new(q, 0), &config);
+        }
+        assert_eq!(cache.len(), 2);
+        assert_eq!(cache.stats().evicted, 1);
+    }
+}
@@ -1,11 +1,14 @@
 //! A* pathfinding on hex grids.
 //!
 //! This module provides efficient pathfinding for units on the game map,
-//! taking into account terrain costs, unit type restrictions, and fog of war.
+//! taking into account terrain costs, roads, unit type restrictions, fog of
+//! war, and closed borders.
 
 use crate::hex::HexCoord;
 use crate::map::Map;
-use crate::unit::UnitCategory;
+use crate::terrain::Road;
+use crate::types::PlayerSlot;
+use crate::unit::{Unit, UnitCategory};
 use std::cmp::Ordering;
 use std::collections::{BinaryHeap, HashMap};
 
@@ -27,6 +30,20 @@ pub struct PathConfig {
     pub unit_category: UnitCategory,
     /// Is the unit embarked?
     pub embarked: bool,
+    /// Players whose territory the unit may not enter.
+    pub closed_borders: Vec<PlayerSlot>,
+}
+
+impl PathConfig {
+    /// Create a config for a unit's remaining movement.
+    pub fn for_unit(unit: &Unit) -> Self {
+        Self {
+            max_movement: unit.movement,
+            unit_category: unit.unit_type.stats().category,
+            embarked: unit.embarked,
+            closed_borders: Vec::new(),
+        }
+    }
 }
 
 impl Default for PathConfig {
@@ -35,6 +52,7 @@ impl Default for PathConfig {
             max_movement: 20, // 2 movement points * 10
             unit_category: UnitCategory::Melee,
             embarked: false,
+            closed_borders: Vec::new(),
         }
     }
 }
@@ -71,6 +89,19 @@ pub fn find_path(
     start: HexCoord,
     goal: HexCoord,
     config: &PathConfig,
+) -> Option<PathResult> {
+    find_path_within(map, start, goal, config, |_| true)
+}
+
+/// Find the shortest path using only tiles accepted by `allowed`.
+///
+/// The start tile is always allowed.
+pub(crate) fn find_path_within(
+    map: &Map,
+    start: HexCoord,
+    goal: HexCoord,
+    config: &PathConfig,
+    allowed: impl Fn(&HexCoord) -> bool,
 ) -> Option<PathResult> {
     if start == goal {
         return Some(PathResult {
@@ -82,12 +113,13 @@ pub fn find_path(
     let mut open_set = BinaryHeap::new();
     let mut came_from: HashMap<HexCoord, HexCoord> = HashMap::new();
     let mut g_scores: HashMap<HexCoord, u32> = HashMap::new();
+    let min_step = min_step_cost(map);
 
     g_scores.insert(start, 0);
     open_set.push(PathNode {
         coord: start,
         g_cost: 0,
-        f_cost: heuristic(&start, &goal),
+        f_cost: heuristic(&start, &goal, min_step),
     });
 
     while let Some(current) = open_set.pop() {
@@ -101,9 +133,16 @@ pub fn find_path(
         }
 
         let current_g = *g_scores.get(&current.coord).unwrap_or(&u32::MAX);
+        // Skip stale queue entries superseded by a cheaper route
+        if current.g_cost > current_g {
+            continue;
+        }
 
         for neighbor in map.neighbors(&current.coord) {
-            let move_cost = get_movement_cost(map, &neighbor, config);
+            if !allowed(&neighbor) {
+                continue;
+            }
+            let move_cost = get_movement_cost(map, &current.coord, &neighbor, config);
 
             // Skip impassable tiles
             if move_cost == u32::MAX {
@@ -120,7 +159,7 @@ pub fn find_path(
             came_from.insert(neighbor, current.coord);
             g_scores.insert(neighbor, tentative_g);
 
-            let f_cost = tentative_g + heuristic(&neighbor, &goal);
+            let f_cost = tentative_g + heuristic(&neighbor, &goal, min_step);
             open_set.push(PathNode {
                 coord: neighbor,
                 g_cost: tentative_g,
@@ -150,7 +189,7 @@ pub fn find_reachable(map: &Map, start: HexCoord, config: &PathConfig) -> HashMa
         let current_cost = *reachable.get(&current.coord).unwrap_or(&u32::MAX);
 
         for neighbor in map.neighbors(&current.coord) {
-            let move_cost = get_movement_cost(map, &neighbor, config);
+            let move_cost = get_movement_cost(map, &current.coord, &neighbor, config);
 
             if move_cost == u32::MAX {
                 continue;
@@ -201,13 +240,30 @@ pub fn find_attackable(
         .collect()
 }
 
-/// Get the movement cost to enter a tile.
-fn get_movement_cost(map: &Map, coord: &HexCoord, config: &PathConfig) -> u32 {
-    let tile = match map.get(coord) {
+/// Get the movement cost to step between two adjacent tiles.
+///
+/// Returns `None` if the destination is impassable for the unit.
+pub fn step_cost(map: &Map, from: &HexCoord, to: &HexCoord, config: &PathConfig) -> Option<u32> {
+    match get_movement_cost(map, from, to, config) {
+        u32::MAX => None,
+        cost => Some(cost),
+    }
+}
+
+/// Get the movement cost to step from one tile into an adjacent one.
+fn get_movement_cost(map: &Map, from: &HexCoord, to: &HexCoord, config: &PathConfig) -> u32 {
+    let tile = match map.get(to) {
         Some(t) => t,
         None => return u32::MAX,
     };
 
+    if tile
+        .owner
+        .is_some_and(|owner| config.closed_borders.contains(&owner))
+    {
+        return u32::MAX;
+    }
+
     // Check terrain passability based on unit type
     match config.unit_category {
         UnitCategory::Naval => {
@@ -228,17 +284,40 @@ fn get_movement_cost(map: &Map, coord: &HexCoord, config: &PathConfig) -> u32 {
     }
 
     // Get base movement cost (x10 for precision)
-    let cost = tile.movement_cost();
+    let cost = tile.base_movement_cost();
     if cost == u32::MAX {
         return u32::MAX;
     }
+    let cost = cost * 10;
+
+    // Roads only help when both tiles are connected
+    let from_road = map.get(from).and_then(|t| t.road);
+    match Road::between(from_road, tile.road) {
+        Some(road) if !config.embarked => road
+            .movement_multiplier()
+            .mul_int(cost as i64)
+            .ceil()
+            .max(1) as u32,
+        _ => cost,
+    }
+}
 
-    cost * 10
+/// Cheapest possible step on this map, so the heuristic never overestimates.
+fn min_step_cost(map: &Map) -> u32 {
+    let best = map
+        .tiles
+        .values()
+        .filter_map(|t| t.road)
+        .min_by_key(|r| r.movement_multiplier());
+    match best {
+        Some(road) => road.movement_multiplier().mul_int(10).ceil().max(1) as u32,
+        None => 10,
+    }
 }
 
-/// Heuristic for A* (hex distance * minimum cost).
-fn heuristic(a: &HexCoord, b: &HexCoord) -> u32 {
-    a.distance(b) * 10 // Minimum cost is 1 * 10
+/// Heuristic for A* (hex distance * minimum step cost).
+fn heuristic(a: &HexCoord, b: &HexCoord, min_step: u32) -> u32 {
+    a.distance(b) * min_step
 }
 
 /// Reconstruct the path from came_from map.
@@ -270,8 +349,8 @@ pub fn path_cost(map: &Map, path: &[HexCoord], config: &PathConfig) -> Option<u3
     }
 
     let mut total = 0u32;
-    for coord in path.iter().skip(1) {
-        let cost = get_movement_cost(map, coord, config);
+    for window in path.windows(2) {
+        let cost = get_movement_cost(map, &window[0], &window[1], config);
         if cost == u32::MAX {
             return None;
         }
@@ -297,7 +376,7 @@ pub fn is_valid_path(map: &Map, path: &[HexCoord], config: &PathConfig) -> bool
         }
 
         // Check destination is passable
-        if get_movement_cost(map, to, config) == u32::MAX {
+        if get_movement_cost(map, from, to, config) == u32::MAX {
             return false;
         }
     }
@@ -384,6 +463,33 @@ mod tests {
         }
     }
 
+    #[test]
+    fn test_closed_borders_block_paths() {
+        let mut map = create_test_map();
+        // Player 1 owns a wall of tiles across the map
+        for r in 0..10 {
+            map.get_mut(&HexCoord::new(5, r)).unwrap().owner = Some(PlayerSlot(1));
+        }
+        let start = HexCoord::new(2, 5);
+        let goal = HexCoord::new(8, 5);
+
+        let open = find_path(&map, start, goal, &PathConfig::default());
+        assert!(open.is_some());
+
+        let config = PathConfig {
+            closed_borders: vec![PlayerSlot(1)],
+            ..Default::default()
+        };
+        assert!(find_path(&map, start, goal, &config).is_none());
+
+        // Other players' borders don't matter
+        let config = PathConfig {
+            closed_borders: vec![PlayerSlot(2)],
+            ..Default::default()
+        };
+        assert!(find_path(&map, start, goal, &config).is_some());
+    }
+
     #[test]
     fn test_naval_unit_water_only() {
         let mut map = create_test_map();
@@ -470,4 +576,46 @@ mod tests {
         ];
         assert!(!is_valid_path(&map, &invalid, &config));
     }
+
+    #[test]
+    fn test_road_reduces_step_cost() {
+        let mut map = create_test_map();
+        let from = HexCoord::new(0, 0);
+        let to = HexCoord::new(1, 0);
+        let config = PathConfig::default();
+
+        // A road on only one end doesn't help
+        map.get_mut(&to).unwrap().road = Some(Road::Road);
+        assert_eq!(step_cost(&map, &from, &to, &config), Some(10));
+
+        map.get_mut(&from).unwrap().road = Some(Road::Road);
+        assert_eq!(step_cost(&map, &from, &to, &config), Some(5));
+
+        map.get_mut(&from).unwrap().road = Some(Road::Railroad);
+        map.get_mut(&to).unwrap().road = Some(Road::Railroad);
+        assert_eq!(step_cost(&map, &from, &to, &config), Some(1));
+    }
+
+    #[test]
+    fn test_find_path_follows_road() {
+        let mut map = create_test_map();
+        // Forest everywhere except a road detour
+        for tile in map.tiles.values_mut() {
+            tile.feature = Some(crate::terrain::Feature::Forest);
+        }
+        let road = [
+            HexCoord::new(2, 2),
+            HexCoord::new(1, 2),
+            HexCoord::new(1, 3),
+            HexCoord::new(2, 4),
+        ];
+        for coord in road {
+            map.get_mut(&coord).unwrap().road = Some(Road::Road);
+        }
+
+        // Three road steps beat two steps through the forest
+        let result = find_path(&map, road[0], road[3], &PathConfig::default()).unwrap();
+        assert_eq!(result.path, road.to_vec());
+        assert_eq!(result.total_cost, 30);
+    }
 }
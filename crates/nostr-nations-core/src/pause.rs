@@ -0,0 +1,243 @@
+//! Pausing and resuming synchronous games.
+//!
+//! Any player may request a pause. Depending on the game's [`PausePolicy`]
+//! the other players vote on it or the host decides. While the game is
+//! paused only pause, concession and extension actions are accepted, so
+//! turn timers stop. Resuming carries a countdown that every client shows
+//! before play continues.
+//!
+//! Requests, votes and resumes are ordinary game actions, so the pause
+//! history is part of the signed event chain.
+
+use crate::game_state::GameState;
+use crate::settings::PausePolicy;
+use crate::types::PlayerSlot;
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use ts_rs::TS;
+
+/// Default countdown before play continues after a resume, in seconds.
+pub const DEFAULT_RESUME_COUNTDOWN_SECS: u32 = 10;
+
+/// Whether the game is running, waiting on a pause vote, or paused.
+#[derive(Clone, Debug, Default, PartialEq, Eq, Serialize, Deserialize, JsonSchema, TS)]
+pub enum PauseState {
+    /// Play is running.
+    #[default]
+    Running,
+    /// A pause was requested and votes are being collected. Play continues
+    /// until the vote passes.
+    Requested {
+        requested_by: PlayerSlot,
+        reason: Option<String>,
+        votes: BTreeMap<PlayerSlot, bool>,
+    },
+    /// Play is paused.
+    Paused {
+        requested_by: PlayerSlot,
+        since_turn: u32,
+    },
+}
+
+impl PauseState {
+    /// Check if play is paused.
+    pub fn is_paused(&self) -> bool {
+        matches!(self, PauseState::Paused { .. })
+    }
+}
+
+/// Result of a pause request or vote.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum PauseOutcome {
+    /// Still waiting on votes.
+    Pending,
+    /// The game is now paused.
+    Paused,
+    /// The request was turned down.
+    Rejected,
+}
+
+/// Players who vote on pauses: remaining human players.
+fn voters(state: &GameState) -> Vec<PlayerSlot> {
+    state
+        .players
+        .iter()
+        .filter(|p| !p.eliminated && !p.is_ai)
+        .map(|p| p.id)
+        .collect()
+}
+
+/// Request a pause. The requester's vote counts in favour.
+pub fn request_pause(
+    state: &mut GameState,
+    player_id: PlayerSlot,
+    reason: Option<String>,
+) -> PauseOutcome {
+    state.pause = PauseState::Requested {
+        requested_by: player_id,
+        reason,
+        votes: BTreeMap::from([(player_id, true)]),
+    };
+    tally(state)
+}
+
+/// Vote on the pending pause request.
+pub fn vote_pause(state: &mut GameState, player_id: PlayerSlot, approve: bool) -> PauseOutcome {
+    if let PauseState::Requested { votes, .. } = &mut state.pause {
+        votes.insert(player_id, approve);
+    }
+    tally(state)
+}
+
+/// Resume play.
+pub fn resume(state: &mut GameState) {
+    state.pause = PauseState::Running;
+}
+
+/// Settle the pending request if the votes decide it.
+fn tally(state: &mut GameState) -> PauseOutcome {
+    let PauseState::Requested {
+        requested_by,
+        votes,
+        ..
+    } = &state.pause
+    else {
+        return PauseOutcome::Pending;
+    };
+
+    let outcome = match state.settings.pause_policy {
+        PausePolicy::HostDecides => match votes.get(&state.host()) {
+            Some(true) => PauseOutcome::Paused,
+            Some(false) => PauseOutcome::Rejected,
+            None => PauseOutcome::Pending,
+        },
+        PausePolicy::Vote => {
+            let voters = voters(state);
+            let count = |approve: bool| {
+                voters
+                    .iter()
+                    .filter(|id| votes.get(id) == Some(&approve))
+                    .count()
+            };
+            // A strict majority pauses; a tie is enough to refuse
+            if count(true) * 2 > voters.len() {
+                PauseOutcome::Paused
+            } else if count(false) * 2 >= voters.len() {
+                PauseOutcome::Rejected
+            } else {
+                PauseOutcome::Pending
+            }
+        }
+    };
+
+    let requested_by = *requested_by;
+    match outcome {
+        PauseOutcome::Paused => {
+            state.pause = PauseState::Paused {
+                requested_by,
+                since_turn: state.turn,
+            };
+        }
+        PauseOutcome::Rejected => state.pause = PauseState::Running,
+        PauseOutcome::Pending => {}
+    }
+    outcome
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::player::{Civilization, Player};
+    use crate::settings::GameSettings;
+    use crate::types::{GameId, Npub};
+
+    fn create_game(players: u8) -> GameState {
+        let settings = GameSettings::new("Test".to_string());
+        let mut state = GameState::new(GameId::new("game1"), settings, [0u8; 32]);
+        for id in 0..players {
+            state.players.push(Player::new(
+                PlayerSlot(id),
+                Npub(format!("npub{}", id)),
+                format!("Player {}", id),
+                Civilization::generic(),
+            ));
+        }
+        state
+    }
+
+    // ==================== Vote Tests ====================
+
+    #[test]
+    fn test_majority_pauses() {
+        let mut state = create_game(3);
+
+        assert_eq!(
+            request_pause(&mut state, PlayerSlot(1), None),
+            PauseOutcome::Pending
+        );
+        assert!(!state.pause.is_paused());
+        assert_eq!(
+            vote_pause(&mut state, PlayerSlot(2), true),
+            PauseOutcome::Paused
+        );
+        assert_eq!(
+            state.pause,
+            PauseState::Paused {
+                requested_by: PlayerSlot(1),
+                since_turn: 0
+            }
+        );
+
+        resume(&mut state);
+        assert_eq!(state.pause, PauseState::Running);
+    }
+
+    #[test]
+    fn test_tie_rejects() {
+        let mut state = create_game(2);
+
+        request_pause(&mut state, PlayerSlot(0), Some("dinner".to_string()));
+        assert_eq!(
+            vote_pause(&mut state, PlayerSlot(1), false),
+            PauseOutcome::Rejected
+        );
+        assert_eq!(state.pause, PauseState::Running);
+    }
+
+    #[test]
+    fn test_ai_and_eliminated_players_do_not_vote() {
+        let mut state = create_game(3);
+        state.players[1].is_ai = true;
+        state.players[2].eliminated = true;
+
+        assert_eq!(
+            request_pause(&mut state, PlayerSlot(0), None),
+            PauseOutcome::Paused
+        );
+    }
+
+    // ==================== Host Policy Tests ====================
+
+    #[test]
+    fn test_host_decides() {
+        let mut state = create_game(3);
+        state.settings.pause_policy = PausePolicy::HostDecides;
+        state.players[2].is_host = true;
+
+        request_pause(&mut state, PlayerSlot(0), None);
+        assert_eq!(
+            vote_pause(&mut state, PlayerSlot(1), true),
+            PauseOutcome::Pending
+        );
+        assert_eq!(
+            vote_pause(&mut state, PlayerSlot(2), false),
+            PauseOutcome::Rejected
+        );
+
+        assert_eq!(
+            request_pause(&mut state, PlayerSlot(2), None),
+            PauseOutcome::Paused
+        );
+    }
+}
@@ -1,18 +1,20 @@
 //! Player state and civilization data.
 
 use crate::hex::HexCoord;
-use crate::types::{CityId, Era, PlayerColor, PlayerId, TechId};
+use crate::locale::{keys, CityNameRuleset, LocalizedMessage};
+use crate::terrain::Resource;
+use crate::types::{CityId, Era, Npub, PlayerColor, PlayerSlot, TechId};
 use crate::victory::SpaceshipProgress;
 use serde::{Deserialize, Serialize};
-use std::collections::HashSet;
+use std::collections::{BTreeMap, HashSet};
 
 /// A player in the game.
 #[derive(Clone, Debug, Serialize, Deserialize)]
 pub struct Player {
     /// Player index (0-3 typically).
-    pub id: PlayerId,
+    pub id: PlayerSlot,
     /// Nostr public key (hex encoded).
-    pub pubkey: String,
+    pub pubkey: Npub,
     /// Display name.
     pub name: String,
     /// Chosen civilization.
@@ -31,12 +33,21 @@ pub struct Player {
     pub current_research: Option<TechId>,
     /// Accumulated research progress on current tech.
     pub research_progress: u32,
+    /// Technologies to research after the current one, in order.
+    #[serde(default)]
+    pub research_queue: Vec<TechId>,
     /// Set of completed technologies.
     pub technologies: HashSet<TechId>,
+    /// Technologies acquired by trade rather than research.
+    #[serde(default)]
+    pub traded_techs: HashSet<TechId>,
     /// ID of this player's capital city.
     pub capital: Option<CityId>,
     /// Whether this player has been eliminated.
     pub eliminated: bool,
+    /// Whether this player left the game by conceding.
+    #[serde(default)]
+    pub conceded: bool,
     /// Set of tiles this player has explored (can see terrain).
     pub explored_tiles: HashSet<HexCoord>,
     /// Player's current score breakdown.
@@ -45,11 +56,33 @@ pub struct Player {
     pub is_host: bool,
     /// Progress toward building the spaceship (for science victory).
     pub spaceship: SpaceshipProgress,
+    /// Whether this seat is controlled by the AI.
+    #[serde(default)]
+    pub is_ai: bool,
+    /// Empire-wide happiness.
+    #[serde(default)]
+    pub happiness: i32,
+    /// Last turn this player took an action.
+    #[serde(default)]
+    pub last_active_turn: u32,
+    /// Era the player has reached.
+    #[serde(default)]
+    pub era: Era,
+    /// Players this player has made contact with.
+    #[serde(default)]
+    pub met_players: HashSet<PlayerSlot>,
+    /// Keys that controlled this seat before substitutes took over, oldest
+    /// first.
+    #[serde(default)]
+    pub previous_pubkeys: Vec<Npub>,
+    /// Strategic resources on hand.
+    #[serde(default)]
+    pub stockpile: BTreeMap<Resource, u32>,
 }
 
 impl Player {
     /// Create a new player with default values.
-    pub fn new(id: PlayerId, pubkey: String, name: String, civilization: Civilization) -> Self {
+    pub fn new(id: PlayerSlot, pubkey: Npub, name: String, civilization: Civilization) -> Self {
         Self {
             id,
             pubkey,
@@ -62,16 +95,31 @@ impl Player {
             culture_per_turn: 0,
             current_research: None,
             research_progress: 0,
+            research_queue: Vec::new(),
             technologies: HashSet::new(),
+            traded_techs: HashSet::new(),
             capital: None,
             eliminated: false,
+            conceded: false,
             explored_tiles: HashSet::new(),
             score: Score::default(),
             is_host: false,
             spaceship: SpaceshipProgress::default(),
+            is_ai: false,
+            happiness: 0,
+            last_active_turn: 0,
+            era: Era::Ancient,
+            met_players: HashSet::new(),
+            previous_pubkeys: Vec::new(),
+            stockpile: BTreeMap::new(),
         }
     }
 
+    /// Amount of a strategic resource on hand.
+    pub fn stockpiled(&self, resource: Resource) -> u32 {
+        self.stockpile.get(&resource).copied().unwrap_or(0)
+    }
+
     /// Check if the player has researched a specific technology.
     pub fn has_tech(&self, tech_id: &TechId) -> bool {
         self.technologies.contains(tech_id)
@@ -253,6 +301,22 @@ impl Civilization {
         }
     }
 
+    /// Localization key for this civilization's name.
+    pub fn name_key(&self) -> String {
+        keys::civ_name(&self.id)
+    }
+
+    /// Localization key for this civilization's leader.
+    pub fn leader_key(&self) -> String {
+        keys::civ_leader(&self.id)
+    }
+
+    /// Suggest a name for this civilization's next city from the built-in
+    /// city name ruleset.
+    pub fn suggest_city_name(&self, used: &[String]) -> Option<LocalizedMessage> {
+        CityNameRuleset::builtin().suggest(&self.id, used)
+    }
+
     /// Get all predefined civilizations.
     pub fn all_civilizations() -> Vec<Civilization> {
         vec![
@@ -278,12 +342,12 @@ mod tests {
     #[test]
     fn test_player_creation() {
         let player = Player::new(
-            0,
-            "npub1abc123".to_string(),
+            PlayerSlot(0),
+            Npub::new("npub1abc123"),
             "TestPlayer".to_string(),
             Civilization::rome(),
         );
-        assert_eq!(player.id, 0);
+        assert_eq!(player.id, PlayerSlot(0));
         assert_eq!(player.name, "TestPlayer");
         assert!(!player.eliminated);
         assert_eq!(player.gold, 0);
@@ -292,8 +356,8 @@ mod tests {
     #[test]
     fn test_player_gold() {
         let mut player = Player::new(
-            0,
-            "npub".to_string(),
+            PlayerSlot(0),
+            Npub::new("npub"),
             "P1".to_string(),
             Civilization::generic(),
         );
@@ -309,8 +373,8 @@ mod tests {
     #[test]
     fn test_player_tech() {
         let mut player = Player::new(
-            0,
-            "npub".to_string(),
+            PlayerSlot(0),
+            Npub::new("npub"),
             "P1".to_string(),
             Civilization::generic(),
         );
@@ -323,8 +387,8 @@ mod tests {
     #[test]
     fn test_player_exploration() {
         let mut player = Player::new(
-            0,
-            "npub".to_string(),
+            PlayerSlot(0),
+            Npub::new("npub"),
             "P1".to_string(),
             Civilization::generic(),
         );
@@ -352,8 +416,8 @@ mod tests {
     #[test]
     fn test_player_serialization() {
         let player = Player::new(
-            0,
-            "npub1test".to_string(),
+            PlayerSlot(0),
+            Npub::new("npub1test"),
             "TestPlayer".to_string(),
             Civilization::rome(),
         );
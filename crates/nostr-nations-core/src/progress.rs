@@ -0,0 +1,104 @@
+//! Progress reporting for long-running operations.
+//!
+//! Operations that can take a noticeable amount of time, such as map
+//! generation or replaying an event chain, take a progress callback and
+//! call it as they work. Reports carry the phase the operation is in and
+//! how far through the whole operation it is; within one operation the
+//! percentage never goes backwards, and the last report is at 100%.
+
+use serde::{Deserialize, Serialize};
+
+/// How far a long-running operation has got.
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Progress {
+    /// What the operation is doing, e.g. "terrain" or "replay".
+    pub phase: String,
+    /// How much of the whole operation is done, from 0 to 100.
+    pub percent: u8,
+}
+
+impl Progress {
+    /// Create a progress report, capping the percentage at 100.
+    pub fn new(phase: impl Into<String>, percent: u8) -> Self {
+        Self {
+            phase: phase.into(),
+            percent: percent.min(100),
+        }
+    }
+
+    /// Progress through `done` of `total` steps. No steps counts as done.
+    pub fn of(phase: impl Into<String>, done: usize, total: usize) -> Self {
+        let percent = (done.min(total) * 100)
+            .checked_div(total)
+            .map_or(100, |percent| percent as u8);
+        Self::new(phase, percent)
+    }
+}
+
+/// A callback that ignores progress reports.
+pub fn ignore_progress(_: Progress) {}
+
+/// Reports progress through a counted number of steps, calling back only
+/// when the percentage changes.
+pub struct ProgressTracker<'a> {
+    callback: &'a mut dyn FnMut(Progress),
+    phase: String,
+    total: usize,
+    last: Option<u8>,
+}
+
+impl<'a> ProgressTracker<'a> {
+    /// Track `total` steps of a phase.
+    pub fn new(
+        callback: &'a mut dyn FnMut(Progress),
+        phase: impl Into<String>,
+        total: usize,
+    ) -> Self {
+        Self {
+            callback,
+            phase: phase.into(),
+            total,
+            last: None,
+        }
+    }
+
+    /// Record that `done` steps are complete.
+    pub fn set(&mut self, done: usize) {
+        let progress = Progress::of(self.phase.clone(), done, self.total);
+        if self.last.is_some_and(|last| last >= progress.percent) {
+            return;
+        }
+        self.last = Some(progress.percent);
+        (self.callback)(progress);
+    }
+
+    /// Record that every step is complete.
+    pub fn finish(&mut self) {
+        self.set(self.total);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_progress_of() {
+        assert_eq!(Progress::of("replay", 1, 4).percent, 25);
+        assert_eq!(Progress::of("replay", 9, 4).percent, 100);
+        assert_eq!(Progress::of("replay", 0, 0).percent, 100);
+        assert_eq!(Progress::new("sync", 250).percent, 100);
+    }
+
+    #[test]
+    fn test_tracker_reports_each_percent_once() {
+        let mut reports = Vec::new();
+        let mut callback = |p: Progress| reports.push(p.percent);
+        let mut tracker = ProgressTracker::new(&mut callback, "replay", 400);
+        for done in 0..=400 {
+            tracker.set(done);
+        }
+        tracker.finish();
+        assert_eq!(reports, (0..=100).collect::<Vec<u8>>());
+    }
+}
@@ -0,0 +1,353 @@
+//! City production recommendations.
+//!
+//! Ranks what a city could build against what it currently needs: more
+//! defense when enemy troops are close, food when it has stopped growing
+//! and science when its owner has fallen behind the rivals it has met.
+//!
+//! The AI chooses production from the same [`production_options`], scored
+//! with its persona's weights, so recommendations depend on nothing but the
+//! game state and come out the same on every peer.
+
+use crate::ai::{Objective, ObjectiveWeights, PersonaRuleset};
+use crate::city::{BuildingType, City, ProductionItem};
+use crate::eras;
+use crate::game_state::GameState;
+use crate::locale::LocalizedMessage;
+use crate::types::{CityId, PlayerSlot};
+use crate::unit::UnitType;
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+use ts_rs::TS;
+
+/// How close enemy military units must be to a city to threaten it.
+pub const THREAT_RADIUS: u32 = 3;
+
+/// Extra value an item gets towards its objective for meeting a need.
+pub const NEED_BONUS: i32 = 4;
+
+/// Something a city is short of.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash, Serialize, Deserialize, JsonSchema, TS)]
+pub enum CityNeed {
+    /// Enemy military units are near the city.
+    Defense,
+    /// The city has no food surplus and isn't growing.
+    Growth,
+    /// The owner produces less science than the rivals it has met.
+    Science,
+}
+
+impl CityNeed {
+    /// The objective that meeting this need serves.
+    pub fn objective(self) -> Objective {
+        match self {
+            CityNeed::Defense => Objective::Defense,
+            CityNeed::Growth => Objective::Growth,
+            CityNeed::Science => Objective::Science,
+        }
+    }
+}
+
+/// The measurements a city's needs are derived from.
+#[derive(Clone, Debug, Default, PartialEq, Eq, Serialize, Deserialize, JsonSchema, TS)]
+pub struct CityNeeds {
+    /// Enemy military units within [`THREAT_RADIUS`] of the city.
+    pub threats: u32,
+    /// Food left over each turn after feeding the citizens.
+    pub food_surplus: i32,
+    /// The owner's science per turn.
+    pub science: i32,
+    /// Average science per turn of the living rivals the owner has met,
+    /// or `None` if it hasn't met any.
+    pub rival_science: Option<i32>,
+}
+
+impl CityNeeds {
+    /// Measure a city's needs.
+    pub fn assess(state: &GameState, city: &City) -> Self {
+        let owner = city.owner;
+        let threats = state
+            .units
+            .values()
+            .filter(|u| u.is_military() && state.diplomacy.are_at_war(owner, u.owner))
+            .filter(|u| u.position.distance(&city.position) <= THREAT_RADIUS)
+            .count() as u32;
+
+        let food = state.city_yields(city.id).map_or(0, |y| y.food);
+        let food_surplus = food - city.population as i32 * 2;
+
+        let rivals: Vec<PlayerSlot> = state
+            .players
+            .iter()
+            .filter(|p| p.id != owner && !p.eliminated && state.has_met(owner, p.id))
+            .map(|p| p.id)
+            .collect();
+        let rival_science = (!rivals.is_empty()).then(|| {
+            let total: i32 = rivals
+                .iter()
+                .map(|&id| state.player_yields(id).science)
+                .sum();
+            total / rivals.len() as i32
+        });
+
+        Self {
+            threats,
+            food_surplus,
+            science: state.player_yields(owner).science,
+            rival_science,
+        }
+    }
+
+    /// Check if the city has a need.
+    pub fn has(&self, need: CityNeed) -> bool {
+        match need {
+            CityNeed::Defense => self.threats > 0,
+            CityNeed::Growth => self.food_surplus <= 0,
+            CityNeed::Science => self.rival_science.is_some_and(|s| self.science < s),
+        }
+    }
+
+    /// The city's current needs, most urgent first.
+    pub fn needs(&self) -> Vec<CityNeed> {
+        [CityNeed::Defense, CityNeed::Growth, CityNeed::Science]
+            .into_iter()
+            .filter(|&need| self.has(need))
+            .collect()
+    }
+}
+
+/// Something a city could build, valued against each objective.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct ProductionOption {
+    pub item: ProductionItem,
+    /// Value towards each objective, including [`NEED_BONUS`] for a need it
+    /// meets.
+    pub values: Vec<(Objective, i32)>,
+    /// The city's needs this item meets.
+    pub meets: Vec<CityNeed>,
+}
+
+/// An item, its value towards each objective and the need it meets.
+type OptionRow = (
+    ProductionItem,
+    &'static [(Objective, i32)],
+    Option<CityNeed>,
+);
+
+/// The items a city chooses between.
+const OPTIONS: [OptionRow; 7] = [
+    (
+        ProductionItem::Unit(UnitType::Settler),
+        &[(Objective::Expansion, 6)],
+        None,
+    ),
+    (
+        ProductionItem::Unit(UnitType::Warrior),
+        &[(Objective::Military, 4), (Objective::Defense, 1)],
+        None,
+    ),
+    (
+        ProductionItem::Unit(UnitType::Archer),
+        &[(Objective::Defense, 4), (Objective::Military, 1)],
+        Some(CityNeed::Defense),
+    ),
+    (
+        ProductionItem::Building(BuildingType::Walls),
+        &[(Objective::Defense, 5)],
+        Some(CityNeed::Defense),
+    ),
+    (
+        ProductionItem::Building(BuildingType::Library),
+        &[(Objective::Science, 5)],
+        Some(CityNeed::Science),
+    ),
+    (
+        ProductionItem::Building(BuildingType::Granary),
+        &[(Objective::Growth, 4)],
+        Some(CityNeed::Growth),
+    ),
+    (
+        ProductionItem::Building(BuildingType::Monument),
+        &[(Objective::Growth, 2), (Objective::Expansion, 1)],
+        None,
+    ),
+];
+
+/// List what a city could build, in a fixed order.
+///
+/// Buildings the city already has, or lacks the prerequisites for, are
+/// left out. Era and resource requirements aren't checked.
+pub fn production_options(state: &GameState, city: &City) -> Vec<ProductionOption> {
+    let needs = CityNeeds::assess(state, city);
+    OPTIONS
+        .into_iter()
+        .filter(|(item, _, _)| match item {
+            ProductionItem::Building(building) => city.can_build(*building),
+            _ => true,
+        })
+        .map(|(item, values, need)| {
+            let meets: Vec<CityNeed> = need.into_iter().filter(|&n| needs.has(n)).collect();
+            let mut values = values.to_vec();
+            values.extend(meets.iter().map(|n| (n.objective(), NEED_BONUS)));
+            ProductionOption {
+                item,
+                values,
+                meets,
+            }
+        })
+        .collect()
+}
+
+/// A ranked production choice.
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize, JsonSchema, TS)]
+pub struct Recommendation {
+    pub item: ProductionItem,
+    /// Weighted score; higher is better.
+    pub score: i32,
+    /// The city's needs this item meets.
+    pub meets: Vec<CityNeed>,
+}
+
+impl Recommendation {
+    /// Localized reason for the recommendation, for advisor tooltips.
+    pub fn message(&self) -> LocalizedMessage {
+        let key = match self.meets.first() {
+            Some(CityNeed::Defense) => "advisor-recommend-defense",
+            Some(CityNeed::Growth) => "advisor-recommend-growth",
+            Some(CityNeed::Science) => "advisor-recommend-science",
+            None => "advisor-recommend",
+        };
+        LocalizedMessage::new(key).with_arg("item", self.item.name())
+    }
+}
+
+/// Rank a city's production options with the given weights, best first.
+///
+/// Items the owner's era doesn't allow are left out. Ties keep the order
+/// of [`production_options`].
+pub fn rank_production(
+    state: &GameState,
+    city: &City,
+    weights: &ObjectiveWeights,
+) -> Vec<Recommendation> {
+    let player = state.get_player(city.owner);
+    let mut ranked: Vec<Recommendation> = production_options(state, city)
+        .into_iter()
+        .filter(|option| player.is_some_and(|p| eras::can_produce(p, &option.item)))
+        .map(|option| Recommendation {
+            score: weights.score(&option.values),
+            item: option.item,
+            meets: option.meets,
+        })
+        .collect();
+    ranked.sort_by_key(|r| std::cmp::Reverse(r.score));
+    ranked
+}
+
+/// Rank a city's production options for its owner, best first.
+///
+/// AI seats are ranked with their persona's weights and human seats with
+/// [`ObjectiveWeights::BALANCED`]. Returns nothing for unknown cities.
+pub fn recommend_production(state: &GameState, city_id: CityId) -> Vec<Recommendation> {
+    let Some(city) = state.cities.get(&city_id) else {
+        return Vec::new();
+    };
+    let weights = state
+        .ai_persona(city.owner)
+        .map_or(ObjectiveWeights::BALANCED, |persona| {
+            PersonaRuleset::builtin().weights(persona)
+        });
+    rank_production(state, city, &weights)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::hex::HexCoord;
+    use crate::player::{Civilization, Player};
+    use crate::settings::GameSettings;
+    use crate::types::{GameId, Npub, UnitId};
+    use crate::unit::Unit;
+
+    fn create_state() -> GameState {
+        let mut state = GameState::new(
+            GameId::new("recommend"),
+            GameSettings::new("Recommend".to_string()),
+            [1; 32],
+        );
+        for (id, name) in [(0, "Alice"), (1, "Bob")] {
+            state.players.push(Player::new(
+                PlayerSlot(id),
+                Npub::new(format!("npub{}", id).as_str()),
+                name.to_string(),
+                Civilization::generic(),
+            ));
+        }
+        state.diplomacy.initialize(&state.players);
+        let city = City::new(
+            CityId(1),
+            PlayerSlot(0),
+            "Rome".to_string(),
+            HexCoord::new(0, 0),
+            true,
+        );
+        state.cities.insert(CityId(1), city);
+        state
+    }
+
+    fn position(ranked: &[Recommendation], item: &ProductionItem) -> usize {
+        ranked.iter().position(|r| &r.item == item).unwrap()
+    }
+
+    #[test]
+    fn test_nearby_enemies_raise_defense() {
+        let mut state = create_state();
+        let walls = ProductionItem::Building(BuildingType::Walls);
+        let calm = recommend_production(&state, CityId(1));
+        assert!(calm.iter().all(|r| !r.meets.contains(&CityNeed::Defense)));
+
+        state.units.insert(
+            UnitId(1),
+            Unit::new(
+                UnitId(1),
+                PlayerSlot(1),
+                UnitType::Warrior,
+                HexCoord::new(2, 0),
+            ),
+        );
+        state.diplomacy.declare_war(PlayerSlot(0), PlayerSlot(1), 1);
+        let threatened = recommend_production(&state, CityId(1));
+        let needs = CityNeeds::assess(&state, &state.cities[&CityId(1)]);
+        assert_eq!(needs.threats, 1);
+        assert!(threatened[position(&threatened, &walls)]
+            .meets
+            .contains(&CityNeed::Defense));
+        assert!(position(&threatened, &walls) < position(&calm, &walls));
+        assert_eq!(threatened[0].message().key, "advisor-recommend-defense");
+    }
+
+    #[test]
+    fn test_stalled_growth_raises_granary() {
+        let mut state = create_state();
+        state.cities.get_mut(&CityId(1)).unwrap().population = 8;
+        let needs = CityNeeds::assess(&state, &state.cities[&CityId(1)]);
+        assert!(needs.has(CityNeed::Growth));
+
+        let ranked = recommend_production(&state, CityId(1));
+        let granary = &ranked[position(&ranked, &ProductionItem::Building(BuildingType::Granary))];
+        assert_eq!(granary.meets, vec![CityNeed::Growth]);
+        assert_eq!(granary.score, 4 + NEED_BONUS);
+    }
+
+    #[test]
+    fn test_ranking_is_deterministic_and_era_limited() {
+        let state = create_state();
+        let ranked = recommend_production(&state, CityId(1));
+        assert_eq!(ranked, recommend_production(&state, CityId(1)));
+        assert!(ranked.windows(2).all(|w| w[0].score >= w[1].score));
+        // Libraries need the Classical era
+        assert!(!ranked
+            .iter()
+            .any(|r| r.item == ProductionItem::Building(BuildingType::Library)));
+        assert!(recommend_production(&state, CityId(9)).is_empty());
+    }
+}
@@ -6,16 +6,54 @@
 //! - Verify determinism (same events = same final state)
 //! - Validate Cashu randomness proofs for fair play
 
+use crate::audit::{self, AuditLog};
+use crate::borders::{self, TileClaim};
 use crate::cashu::{DeterministicRandomness, RandomnessProof, RandomnessProvider};
-use crate::combat::{resolve_combat, CombatContext};
+use crate::city::{BuildingType, ProductionItem, WonderType};
+use crate::combat::{
+    city_ranged_strength, preview_with_difficulty, resolve_city_bombard, resolve_city_combat,
+    resolve_combat_with_difficulty, CityBombardContext, CityCombatContext, CombatContext,
+    CombatPreview, CITY_BOMBARD_RANGE,
+};
+use crate::concession;
+use crate::contact;
+use crate::continents;
+use crate::eras;
 use crate::events::{EventChain, GameAction, GameEvent};
-use crate::game_state::{GameError, GamePhase, GameState};
+use crate::fixed::Fixed;
+use crate::game_log::{GameLog, LogContext};
+use crate::game_state::{
+    GameError, GamePhase, GameState, TreatyType, WAR_DECLARATION_SCORE_PENALTY,
+};
 use crate::hex::HexCoord;
+use crate::map::Map;
 use crate::mapgen::{MapGenConfig, MapGenerator};
+use crate::merkle::EventBatch;
+use crate::pathfinding::{self, PathConfig};
+use crate::pause::{self, PauseOutcome, PauseState};
 use crate::player::{Civilization, Player};
+use crate::progress::{ignore_progress, Progress, ProgressTracker};
+use crate::research;
+use crate::roads::{self, RoadBuilt, RoadError, RoadWork};
+use crate::schedule::{TurnSchedule, TurnTimes};
 use crate::settings::GameSettings;
-use crate::types::PlayerId;
-use crate::unit::{Unit, UnitType};
+use crate::siege::{self, CaptureChoice};
+use crate::snapshot::{self, SnapshotError, StateSnapshot};
+use crate::stockpile;
+use crate::substitution;
+use crate::technology::TechTree;
+use crate::terraform::{self, ClearError, FeatureCleared, FeatureWork, TerrainChange};
+use crate::terrain::{Feature, Resource, Road, Terrain};
+use crate::trading::{execute_trade, TradeOffer, TradeStatus};
+use crate::turn;
+use crate::types::{CityId, Era, GameId, Npub, PlayerSlot, UnitId};
+use crate::undo::{ActionBuffer, CommittedAction};
+use crate::unit::{Promotion, PromotionError, Unit, UnitType};
+use crate::victory_proof::{VictoryProof, VictoryProofError};
+use crate::wonders;
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+use ts_rs::TS;
 
 /// Result of applying an action to game state.
 #[derive(Clone, Debug)]
@@ -47,51 +85,240 @@ impl ActionResult {
 }
 
 /// Side effects from an action (for UI/animation).
-#[derive(Clone, Debug)]
+#[derive(Clone, Debug, PartialEq)]
 pub enum ActionEffect {
     UnitMoved {
-        unit_id: u64,
+        unit_id: UnitId,
         from: HexCoord,
         to: HexCoord,
     },
     UnitDamaged {
-        unit_id: u64,
+        unit_id: UnitId,
         damage: u32,
         new_health: u32,
     },
     UnitDestroyed {
-        unit_id: u64,
+        unit_id: UnitId,
     },
     UnitCreated {
-        unit_id: u64,
+        unit_id: UnitId,
         unit_type: UnitType,
         position: HexCoord,
     },
     CityFounded {
-        city_id: u64,
+        city_id: CityId,
         name: String,
         position: HexCoord,
     },
     CityDamaged {
-        city_id: u64,
+        city_id: CityId,
         damage: u32,
     },
     CityGrew {
-        city_id: u64,
+        city_id: CityId,
         new_population: u32,
     },
+    TileClaimed {
+        city_id: CityId,
+        owner: PlayerSlot,
+        coord: HexCoord,
+    },
+    UnitHealed {
+        unit_id: UnitId,
+        amount: u32,
+        new_health: u32,
+    },
+    PromotionAvailable {
+        unit_id: UnitId,
+    },
+    UnitPromoted {
+        unit_id: UnitId,
+        promotion: Promotion,
+        new_health: u32,
+    },
+    RoadStarted {
+        unit_id: UnitId,
+        road: Road,
+        turns: u32,
+    },
+    RoadBuilt {
+        coord: HexCoord,
+        road: Road,
+    },
+    FeatureClearing {
+        unit_id: UnitId,
+        feature: Feature,
+        turns: u32,
+    },
+    /// A worker removed a feature; chopped forests give production.
+    FeatureCleared {
+        coord: HexCoord,
+        feature: Feature,
+        city_id: Option<CityId>,
+        production: u32,
+    },
+    /// A mine or oil well ran dry.
+    ResourceDepleted {
+        player_id: PlayerSlot,
+        coord: HexCoord,
+        resource: Resource,
+    },
+    /// The climate warmed and transformed a tile.
+    TerrainChanged {
+        coord: HexCoord,
+        terrain: Terrain,
+        feature: Option<Feature>,
+    },
     TechResearched {
-        player_id: PlayerId,
+        player_id: PlayerSlot,
         tech_id: String,
     },
     TurnStarted {
-        player_id: PlayerId,
+        player_id: PlayerSlot,
         turn: u32,
     },
     GameEnded {
-        winner_id: PlayerId,
+        winner_id: PlayerSlot,
         victory_type: String,
     },
+    PlayerConceded {
+        player_id: PlayerSlot,
+        to_player: Option<PlayerSlot>,
+    },
+    /// A scenario rule eliminated a player.
+    PlayerDefeated {
+        player_id: PlayerSlot,
+        rule_id: String,
+    },
+    CityTransferred {
+        city_id: CityId,
+        new_owner: PlayerSlot,
+    },
+    CityRazed {
+        city_id: CityId,
+    },
+    PauseRequested {
+        player_id: PlayerSlot,
+        reason: Option<String>,
+    },
+    PauseVoted {
+        player_id: PlayerSlot,
+        approve: bool,
+    },
+    GamePaused {
+        requested_by: PlayerSlot,
+    },
+    PauseRejected {
+        requested_by: PlayerSlot,
+    },
+    GameResumed {
+        player_id: PlayerSlot,
+        countdown_secs: u32,
+    },
+    PlayerSubstituted {
+        player_id: PlayerSlot,
+        pubkey: Npub,
+    },
+    MapRevealed {
+        player_id: PlayerSlot,
+    },
+    ResearchAgreementCompleted {
+        player_id: PlayerSlot,
+        partner: PlayerSlot,
+        science: u32,
+    },
+    ResearchAgreementBroken {
+        player_id: PlayerSlot,
+        partner: PlayerSlot,
+    },
+    CityBombarded {
+        city_id: CityId,
+        unit_id: UnitId,
+        damage: u32,
+    },
+    CityCaptured {
+        city_id: CityId,
+        player_id: PlayerSlot,
+        previous_owner: PlayerSlot,
+        population_lost: u32,
+        buildings_destroyed: Vec<BuildingType>,
+    },
+    CaptureResolved {
+        city_id: CityId,
+        choice: CaptureChoice,
+    },
+    UnitGifted {
+        unit_id: UnitId,
+        from: PlayerSlot,
+        to: PlayerSlot,
+    },
+    EraEntered {
+        player_id: PlayerSlot,
+        era: Era,
+    },
+    BuildingCompleted {
+        city_id: CityId,
+        building: BuildingType,
+    },
+    WonderCompleted {
+        city_id: CityId,
+        player_id: PlayerSlot,
+        wonder: WonderType,
+    },
+    WonderLost {
+        city_id: CityId,
+        player_id: PlayerSlot,
+        wonder: WonderType,
+        gold: i32,
+    },
+    FirstContact {
+        player_id: PlayerSlot,
+        met_player: PlayerSlot,
+    },
+    Circumnavigated {
+        player_id: PlayerSlot,
+        gold: i32,
+    },
+}
+
+impl From<TileClaim> for ActionEffect {
+    fn from(claim: TileClaim) -> Self {
+        ActionEffect::TileClaimed {
+            city_id: claim.city_id,
+            owner: claim.owner,
+            coord: claim.coord,
+        }
+    }
+}
+
+impl From<RoadBuilt> for ActionEffect {
+    fn from(built: RoadBuilt) -> Self {
+        ActionEffect::RoadBuilt {
+            coord: built.coord,
+            road: built.road,
+        }
+    }
+}
+
+impl From<FeatureCleared> for ActionEffect {
+    fn from(cleared: FeatureCleared) -> Self {
+        ActionEffect::FeatureCleared {
+            coord: cleared.coord,
+            feature: cleared.feature,
+            city_id: cleared.city_id,
+            production: cleared.production,
+        }
+    }
+}
+
+impl From<TerrainChange> for ActionEffect {
+    fn from(change: TerrainChange) -> Self {
+        ActionEffect::TerrainChanged {
+            coord: change.coord,
+            terrain: change.terrain,
+            feature: change.feature,
+        }
+    }
 }
 
 /// Configuration for replay validation.
@@ -101,6 +328,8 @@ pub struct ReplayConfig {
     pub strict_randomness_validation: bool,
     /// Whether to allow deterministic randomness (for offline games).
     pub allow_deterministic_randomness: bool,
+    /// Turns between state snapshots (0 disables snapshots).
+    pub snapshot_interval: u32,
 }
 
 impl Default for ReplayConfig {
@@ -108,6 +337,7 @@ impl Default for ReplayConfig {
         Self {
             strict_randomness_validation: false,
             allow_deterministic_randomness: true,
+            snapshot_interval: snapshot::DEFAULT_SNAPSHOT_INTERVAL,
         }
     }
 }
@@ -120,21 +350,39 @@ pub struct GameEngine {
     pub events: EventChain,
     /// Replay configuration.
     pub config: ReplayConfig,
+    /// Uncommitted local actions for undo/redo.
+    pub buffer: ActionBuffer,
     /// Fallback randomness provider for verification.
     fallback_rng: Option<DeterministicRandomness>,
+    /// Determinism audit log, recorded only when audit mode is enabled.
+    audit: Option<AuditLog>,
+    /// History of notable outcomes for the event log panel.
+    pub log: GameLog,
+    /// Whether a new turn has started that should be snapshotted.
+    snapshot_due: bool,
+    /// Actions executed since the last snapshot, for its events root.
+    batch: EventBatch,
+    /// Receives progress while starting the game generates the map.
+    progress: Option<Box<dyn FnMut(Progress) + Send + Sync>>,
 }
 
 impl GameEngine {
     /// Create a new game engine from a game creation event.
     pub fn new(settings: GameSettings, seed: [u8; 32]) -> Self {
-        let game_id = format!("game_{}", hex::encode(&seed[..8]));
+        let game_id = GameId(format!("game_{}", hex::encode(&seed[..8])));
         let state = GameState::new(game_id, settings, seed);
 
         Self {
             state,
             events: EventChain::new(),
             config: ReplayConfig::default(),
+            buffer: ActionBuffer::new(),
             fallback_rng: Some(DeterministicRandomness::new(seed)),
+            audit: None,
+            log: GameLog::default(),
+            snapshot_due: false,
+            batch: EventBatch::new(),
+            progress: None,
         }
     }
 
@@ -144,20 +392,32 @@ impl GameEngine {
             state,
             events: EventChain::new(),
             config: ReplayConfig::default(),
+            buffer: ActionBuffer::new(),
             fallback_rng: Some(DeterministicRandomness::new(seed)),
+            audit: None,
+            log: GameLog::default(),
+            snapshot_due: false,
+            batch: EventBatch::partial(),
+            progress: None,
         }
     }
 
     /// Create a new game engine with custom replay configuration.
     pub fn with_config(settings: GameSettings, seed: [u8; 32], config: ReplayConfig) -> Self {
-        let game_id = format!("game_{}", hex::encode(&seed[..8]));
+        let game_id = GameId(format!("game_{}", hex::encode(&seed[..8])));
         let state = GameState::new(game_id, settings, seed);
 
         Self {
             state,
             events: EventChain::new(),
             config,
+            buffer: ActionBuffer::new(),
             fallback_rng: Some(DeterministicRandomness::new(seed)),
+            audit: None,
+            log: GameLog::default(),
+            snapshot_due: false,
+            batch: EventBatch::new(),
+            progress: None,
         }
     }
 
@@ -170,11 +430,41 @@ impl GameEngine {
     pub fn from_events_with_config(
         events: &[GameEvent],
         config: ReplayConfig,
+    ) -> Result<Self, ReplayError> {
+        Self::from_events_with_progress(events, config, &mut ignore_progress)
+    }
+
+    /// Replay a game from an event chain, reporting progress through the
+    /// events replayed.
+    pub fn from_events_with_progress(
+        events: &[GameEvent],
+        config: ReplayConfig,
+        progress: &mut dyn FnMut(Progress),
     ) -> Result<Self, ReplayError> {
         if events.is_empty() {
             return Err(ReplayError::EmptyEventChain);
         }
 
+        // Start from the latest snapshot that restores cleanly
+        let mut end = events.len();
+        while let Some((index, snapshot)) = snapshot::latest_snapshot(&events[..end]) {
+            if let Ok(state) = snapshot.restore() {
+                let seed = state.seed;
+                let mut engine = Self::from_state(state, seed);
+                engine.config = config;
+                engine.batch = EventBatch::new();
+                let remaining = &events[index + 1..];
+                let mut tracker = ProgressTracker::new(progress, "replay", remaining.len());
+                for (i, event) in remaining.iter().enumerate() {
+                    tracker.set(i);
+                    engine.apply_event(event)?;
+                }
+                tracker.finish();
+                return Ok(engine);
+            }
+            end = index;
+        }
+
         // First event must be CreateGame
         let first = &events[0];
         let (settings, seed) = match &first.action {
@@ -192,20 +482,26 @@ impl GameEngine {
         let mut engine = Self::with_config(settings, seed, config);
 
         // Replay all events
-        for event in events {
+        let mut tracker = ProgressTracker::new(progress, "replay", events.len());
+        for (i, event) in events.iter().enumerate() {
+            tracker.set(i);
             engine.apply_event(event)?;
         }
+        tracker.finish();
 
         Ok(engine)
     }
 
     /// Apply an event to the game state.
     pub fn apply_event(&mut self, event: &GameEvent) -> Result<ActionResult, ReplayError> {
-        // Validate player turn (except for join events)
-        if !matches!(
-            event.action,
-            GameAction::CreateGame { .. } | GameAction::JoinGame { .. } | GameAction::StartGame
-        ) && self.state.phase == GamePhase::Playing
+        // Extension and unrecognized events are kept but never executed
+        if !event.is_executable() {
+            return Ok(ActionResult::ok(vec![]));
+        }
+
+        // Validate player turn (except for lobby actions and diplomatic responses)
+        if event.action.requires_turn()
+            && self.state.phase == GamePhase::Playing
             && event.player_id != self.state.current_player
         {
             return Err(ReplayError::NotPlayerTurn);
@@ -267,7 +563,9 @@ impl GameEngine {
             // Verify the random value matches the proof
             let expected_random = proof.to_f32();
             match &event.action {
-                GameAction::AttackUnit { random, .. } | GameAction::AttackCity { random, .. } => {
+                GameAction::AttackUnit { random, .. }
+                | GameAction::AttackCity { random, .. }
+                | GameAction::BombardUnit { random, .. } => {
                     // Allow small floating point tolerance
                     if (*random - expected_random).abs() > 0.001 {
                         return Err(ReplayError::InvalidRandomnessProof(
@@ -296,7 +594,7 @@ impl GameEngine {
     /// Apply a game action with access to the full event.
     fn apply_action_with_event(
         &mut self,
-        player_id: PlayerId,
+        player_id: PlayerSlot,
         action: &GameAction,
         _event: &GameEvent,
     ) -> Result<ActionResult, ReplayError> {
@@ -304,12 +602,107 @@ impl GameEngine {
         self.apply_action(player_id, action)
     }
 
+    /// Enable determinism audit mode, starting a fresh audit log.
+    pub fn enable_audit(&mut self) {
+        self.audit = Some(AuditLog::new());
+    }
+
+    /// Disable audit mode and return the recorded log.
+    pub fn disable_audit(&mut self) -> Option<AuditLog> {
+        self.audit.take()
+    }
+
+    /// Whether audit mode is enabled.
+    pub fn is_auditing(&self) -> bool {
+        self.audit.is_some()
+    }
+
+    /// The audit log recorded so far, if audit mode is enabled.
+    pub fn audit_log(&self) -> Option<&AuditLog> {
+        self.audit.as_ref()
+    }
+
     /// Apply a game action.
+    ///
+    /// In audit mode the action's inputs, random draws and resulting state
+    /// hash are appended to the audit log.
     pub fn apply_action(
         &mut self,
-        player_id: PlayerId,
+        player_id: PlayerSlot,
+        action: &GameAction,
+    ) -> Result<ActionResult, ReplayError> {
+        let context = LogContext::capture(&self.state, action);
+        let result = if self.audit.is_none() {
+            self.apply_action_unaudited(player_id, action)
+        } else {
+            let inputs = audit::capture_inputs(&self.state, player_id, action);
+            let result = self.apply_action_unaudited(player_id, action);
+            let accepted = matches!(&result, Ok(r) if r.success);
+            let hash = audit::state_hash(&self.state);
+            if let Some(log) = self.audit.as_mut() {
+                log.record(player_id, action, inputs, accepted, hash);
+            }
+            result
+        };
+        if let Ok(r) = &result {
+            if r.success {
+                self.log
+                    .record(&context, player_id, action, &self.state, &r.effects);
+                self.batch.push(player_id, action);
+            }
+        }
+        result
+    }
+
+    /// Take a snapshot of the current state if one is due.
+    ///
+    /// A snapshot becomes due when a turn divisible by the configured
+    /// snapshot interval starts. The caller publishes it in the event chain
+    /// as a [`GameAction::Snapshot`].
+    pub fn take_snapshot(&mut self) -> Option<StateSnapshot> {
+        if !std::mem::take(&mut self.snapshot_due) {
+            return None;
+        }
+        let snapshot = StateSnapshot::capture(&self.state).with_events_root(self.batch.root());
+        self.batch.reset();
+        Some(snapshot)
+    }
+
+    /// Preview the outcome of an action without changing the game.
+    ///
+    /// The action is applied to a copy-on-write snapshot of the current
+    /// state. Nothing is recorded in the event chain, undo buffer or audit
+    /// log. Returns the result and the hypothetical state.
+    pub fn preview(
+        &self,
+        player_id: PlayerSlot,
+        action: &GameAction,
+    ) -> Result<(ActionResult, GameState), ReplayError> {
+        let mut scratch = GameEngine::from_state(self.state.snapshot(), self.state.seed);
+        let result = scratch.apply_action_unaudited(player_id, action)?;
+        Ok((result, scratch.state))
+    }
+
+    fn apply_action_unaudited(
+        &mut self,
+        player_id: PlayerSlot,
         action: &GameAction,
     ) -> Result<ActionResult, ReplayError> {
+        if let Err(rejection) = self.validate_action(player_id, action) {
+            return rejection.into_action_result();
+        }
+
+        // Track activity so abandoned seats can be handed to substitutes
+        if !matches!(
+            action,
+            GameAction::Extension { .. } | GameAction::Unrecognized { .. }
+        ) {
+            let turn = self.state.turn;
+            if let Some(player) = self.state.get_player_mut(player_id) {
+                player.last_active_turn = turn;
+            }
+        }
+
         match action {
             GameAction::CreateGame { .. } => {
                 // Already handled in new()
@@ -325,12 +718,14 @@ impl GameEngine {
                     .find(|c| c.id == *civilization_id)
                     .unwrap_or_default();
 
-                let player = Player::new(
-                    self.state.players.len() as PlayerId,
-                    format!("npub_{}", player_id),
+                let id = PlayerSlot(self.state.players.len() as u8);
+                let mut player = Player::new(
+                    id,
+                    Npub(format!("npub_{}", player_id)),
                     player_name.clone(),
                     civ,
                 );
+                player.is_ai = self.state.is_ai_player(id);
 
                 self.state
                     .add_player(player)
@@ -348,25 +743,31 @@ impl GameEngine {
                     water_percentage: 30,
                     player_count: self.state.players.len() as u8,
                     wrap_x: self.state.settings.map_wraps,
+                    ..MapGenConfig::default()
                 };
                 let mut generator = MapGenerator::new(self.state.seed, config);
-                self.state.map = generator.generate();
+                self.state.map = match self.progress.as_mut() {
+                    Some(progress) => generator.generate_with_progress(progress),
+                    None => generator.generate(),
+                };
 
                 // Find starting positions and create settlers
                 let positions = generator.find_starting_positions(&self.state.map);
                 for (i, pos) in positions.into_iter().enumerate() {
                     if i < self.state.players.len() {
-                        let unit_id = self.state.allocate_unit_id();
-                        let settler = Unit::new(unit_id, i as PlayerId, UnitType::Settler, pos);
-                        self.state.units.insert(unit_id, settler);
-
-                        // Also give a warrior
-                        let unit_id = self.state.allocate_unit_id();
-                        let warrior = Unit::new(unit_id, i as PlayerId, UnitType::Warrior, pos);
-                        self.state.units.insert(unit_id, warrior);
+                        // Starting units and happiness depend on difficulty
+                        let owner = PlayerSlot(i as u8);
+                        let difficulty = self.state.settings.difficulty;
+                        let is_ai = self.state.is_ai_player(owner);
+                        for unit_type in difficulty.starting_units(is_ai) {
+                            let unit_id = self.state.allocate_unit_id();
+                            let unit = Unit::new(unit_id, owner, *unit_type, pos);
+                            self.state.units.insert(unit_id, unit);
+                        }
 
                         // Explore starting area
                         if let Some(player) = self.state.players.get_mut(i) {
+                            player.happiness = difficulty.starting_happiness(is_ai);
                             for coord in pos.hexes_in_radius(2) {
                                 player.explore_tile(coord);
                             }
@@ -375,60 +776,77 @@ impl GameEngine {
                 }
 
                 Ok(ActionResult::ok(vec![ActionEffect::TurnStarted {
-                    player_id: 0,
+                    player_id: PlayerSlot(0),
                     turn: 1,
                 }]))
             }
 
             GameAction::EndTurn => {
-                let _current = self.state.current_player;
-                self.state.next_turn().map_err(ReplayError::GameError)?;
+                let turn = self.state.turn;
+                let effects =
+                    turn::process_end_turn(&mut self.state).map_err(ReplayError::GameError)?;
+                if self.state.turn != turn
+                    && snapshot::snapshot_due(self.state.turn, self.config.snapshot_interval)
+                {
+                    self.snapshot_due = true;
+                }
+                Ok(ActionResult::ok(effects))
+            }
 
-                // Reset unit movement for next player
-                for unit in self.state.units.values_mut() {
-                    if unit.owner == self.state.current_player {
-                        unit.new_turn();
+            GameAction::Snapshot { snapshot } => {
+                // Peers replaying the full chain check snapshots as they pass
+                snapshot
+                    .verify(&self.state)
+                    .map_err(ReplayError::InvalidSnapshot)?;
+                if let (Some(expected), Some(actual)) = (&snapshot.events_root, self.batch.root()) {
+                    if *expected != actual {
+                        return Err(ReplayError::InvalidSnapshot(
+                            SnapshotError::EventsRootMismatch,
+                        ));
                     }
                 }
-
-                Ok(ActionResult::ok(vec![ActionEffect::TurnStarted {
-                    player_id: self.state.current_player,
-                    turn: self.state.turn,
-                }]))
+                self.batch.reset();
+                Ok(ActionResult::ok(vec![]))
             }
 
             GameAction::MoveUnit { unit_id, path } => {
+                let config = self
+                    .state
+                    .units
+                    .get(unit_id)
+                    .map(|unit| self.state.path_config(unit))
+                    .ok_or(ReplayError::UnitNotFound)?;
                 let unit = self
                     .state
                     .units
                     .get_mut(unit_id)
                     .ok_or(ReplayError::UnitNotFound)?;
 
-                if unit.owner != player_id {
-                    return Err(ReplayError::NotOwner);
-                }
-
-                if !unit.can_move() {
-                    return Ok(ActionResult::err("Unit cannot move"));
-                }
-
                 let from = unit.position;
+                let cost = path_cost(&self.state.map, unit, &config, path).unwrap_or(unit.movement);
                 if let Some(to) = path.last() {
                     unit.position = *to;
-                    unit.use_movement(path.len() as u32 * 10);
+                    unit.use_movement(cost);
 
                     // Explore tiles
-                    if let Some(player) = self.state.players.get_mut(player_id as usize) {
+                    if let Some(player) = self.state.players.get_mut(player_id.index()) {
                         for coord in to.hexes_in_radius(2) {
                             player.explore_tile(coord);
                         }
                     }
 
-                    Ok(ActionResult::ok(vec![ActionEffect::UnitMoved {
+                    let mut effects = vec![ActionEffect::UnitMoved {
                         unit_id: *unit_id,
                         from,
                         to: *to,
-                    }]))
+                    }];
+                    effects.extend(self.first_contacts(player_id));
+                    if let Some(gold) =
+                        continents::check_circumnavigation(&mut self.state, player_id)
+                    {
+                        effects.push(ActionEffect::Circumnavigated { player_id, gold });
+                    }
+                    Ok(ActionResult::ok(effects))
                 } else {
                     Ok(ActionResult::err("Empty path"))
                 }
@@ -452,10 +870,6 @@ impl GameEngine {
                     .ok_or(ReplayError::UnitNotFound)?
                     .clone();
 
-                if attacker.owner != player_id {
-                    return Err(ReplayError::NotOwner);
-                }
-
                 let attacker_tile = self
                     .state
                     .map
@@ -474,11 +888,15 @@ impl GameEngine {
                     defender: &defender,
                     attacker_tile: &attacker_tile,
                     defender_tile: &defender_tile,
-                    random: *random,
+                    random: Fixed::from_f32(*random),
                     is_ranged: attacker.is_ranged(),
                 };
 
-                let result = resolve_combat(&ctx);
+                let result = resolve_combat_with_difficulty(
+                    &ctx,
+                    self.state.difficulty_modifiers(attacker.owner).combat_bonus,
+                    self.state.difficulty_modifiers(defender.owner).combat_bonus,
+                );
 
                 let mut effects = Vec::new();
 
@@ -506,8 +924,14 @@ impl GameEngine {
                             new_health: atk.health,
                         });
                     }
+                    let could_promote = atk.can_promote();
                     atk.gain_experience(result.attacker_xp);
                     atk.mark_acted();
+                    if !could_promote && atk.can_promote() && !atk.is_dead() {
+                        effects.push(ActionEffect::PromotionAvailable {
+                            unit_id: *attacker_id,
+                        });
+                    }
                 }
 
                 // Remove dead units
@@ -521,37 +945,221 @@ impl GameEngine {
                 Ok(ActionResult::ok(effects))
             }
 
-            GameAction::FoundCity { settler_id, name } => {
-                let settler = self
+            GameAction::AttackCity {
+                attacker_id,
+                city_id,
+                random,
+            } => {
+                let attacker = self
                     .state
                     .units
-                    .get(settler_id)
-                    .ok_or(ReplayError::UnitNotFound)?;
+                    .get(attacker_id)
+                    .ok_or(ReplayError::UnitNotFound)?
+                    .clone();
+                let city = self
+                    .state
+                    .cities
+                    .get(city_id)
+                    .ok_or(ReplayError::CityNotFound)?;
+                let city_position = city.position;
+                let attacker_tile = self
+                    .state
+                    .map
+                    .get(&attacker.position)
+                    .cloned()
+                    .unwrap_or_default();
+
+                // Cities defend with the strength they bombard with
+                let result = resolve_city_combat(&CityCombatContext {
+                    attacker: &attacker,
+                    city_strength: city_ranged_strength(city, self.state.city_garrison(city)),
+                    city_health: city.health,
+                    attacker_tile: &attacker_tile,
+                    random: Fixed::from_f32(*random),
+                    is_ranged: attacker.is_ranged(),
+                });
 
-                if settler.owner != player_id {
-                    return Err(ReplayError::NotOwner);
+                let mut effects = Vec::new();
+                if let Some(city) = self.state.cities.get_mut(city_id) {
+                    city.take_damage(result.city_damage);
+                    effects.push(ActionEffect::CityDamaged {
+                        city_id: *city_id,
+                        damage: result.city_damage,
+                    });
                 }
-                if settler.unit_type != UnitType::Settler {
-                    return Ok(ActionResult::err("Only settlers can found cities"));
+
+                if let Some(atk) = self.state.units.get_mut(attacker_id) {
+                    if result.attacker_damage > 0 {
+                        atk.take_damage(result.attacker_damage);
+                        effects.push(ActionEffect::UnitDamaged {
+                            unit_id: *attacker_id,
+                            damage: result.attacker_damage,
+                            new_health: atk.health,
+                        });
+                    }
+                    let could_promote = atk.can_promote();
+                    atk.gain_experience(result.attacker_xp);
+                    atk.mark_acted();
+                    if !could_promote && atk.can_promote() && !atk.is_dead() {
+                        effects.push(ActionEffect::PromotionAvailable {
+                            unit_id: *attacker_id,
+                        });
+                    }
                 }
 
-                let pos = settler.position;
-                let tile = self
+                if result.attacker_destroyed {
+                    self.state.units.remove(attacker_id);
+                    effects.push(ActionEffect::UnitDestroyed {
+                        unit_id: *attacker_id,
+                    });
+                } else if result.city_captured {
+                    if let Some(capture) =
+                        siege::capture_city(&mut self.state, *city_id, attacker.owner)
+                    {
+                        effects.extend(
+                            capture
+                                .units_lost
+                                .iter()
+                                .map(|&unit_id| ActionEffect::UnitDestroyed { unit_id }),
+                        );
+                        if let Some(atk) = self.state.units.get_mut(attacker_id) {
+                            atk.position = city_position;
+                        }
+                        effects.push(ActionEffect::UnitMoved {
+                            unit_id: *attacker_id,
+                            from: attacker.position,
+                            to: city_position,
+                        });
+                        eras::apply_warmonger_penalty(&mut self.state, capture.new_owner);
+                        effects.push(ActionEffect::CityCaptured {
+                            city_id: *city_id,
+                            player_id: capture.new_owner,
+                            previous_owner: capture.previous_owner,
+                            population_lost: capture.population_lost,
+                            buildings_destroyed: capture.buildings_destroyed,
+                        });
+                    }
+                }
+
+                Ok(ActionResult::ok(effects))
+            }
+
+            GameAction::GiftUnit { unit_id, recipient } => {
+                let unit = self
+                    .state
+                    .units
+                    .get_mut(unit_id)
+                    .ok_or(ReplayError::UnitNotFound)?;
+                let from = unit.owner;
+                unit.owner = *recipient;
+                // The recipient can use the unit from their next turn
+                unit.movement = 0;
+                unit.mark_acted();
+                unit.fortified = false;
+                unit.fortify_turns = 0;
+                unit.sleeping = false;
+                unit.queued_path = None;
+                unit.road_work = None;
+                unit.feature_work = None;
+
+                Ok(ActionResult::ok(vec![ActionEffect::UnitGifted {
+                    unit_id: *unit_id,
+                    from,
+                    to: *recipient,
+                }]))
+            }
+
+            GameAction::SetProduction { city_id, item } => {
+                let city = self
+                    .state
+                    .cities
+                    .get_mut(city_id)
+                    .ok_or(ReplayError::CityNotFound)?;
+                city.set_production(item.clone());
+                Ok(ActionResult::ok(vec![]))
+            }
+
+            GameAction::ResolveCapture { city_id, choice } => {
+                if !siege::resolve_capture(&mut self.state, *city_id, *choice) {
+                    return Ok(ActionResult::err("City is not awaiting a capture choice"));
+                }
+                Ok(ActionResult::ok(vec![ActionEffect::CaptureResolved {
+                    city_id: *city_id,
+                    choice: *choice,
+                }]))
+            }
+
+            GameAction::BombardUnit {
+                city_id,
+                target_id,
+                random,
+            } => {
+                let city = self
+                    .state
+                    .cities
+                    .get(city_id)
+                    .ok_or(ReplayError::CityNotFound)?;
+                let target = self
+                    .state
+                    .units
+                    .get(target_id)
+                    .ok_or(ReplayError::UnitNotFound)?;
+                let target_tile = self
                     .state
                     .map
-                    .get(&pos)
-                    .ok_or(ReplayError::InvalidPosition)?;
+                    .get(&target.position)
+                    .cloned()
+                    .unwrap_or_default();
 
-                if !tile.can_found_city() {
-                    return Ok(ActionResult::err("Cannot found city here"));
+                let result = resolve_city_bombard(&CityBombardContext {
+                    city_strength: city_ranged_strength(city, self.state.city_garrison(city)),
+                    target,
+                    target_tile: &target_tile,
+                    random: Fixed::from_f32(*random),
+                });
+
+                let turn = self.state.turn;
+                if let Some(city) = self.state.cities.get_mut(city_id) {
+                    city.last_bombard_turn = Some(turn);
+                }
+
+                let mut effects = vec![ActionEffect::CityBombarded {
+                    city_id: *city_id,
+                    unit_id: *target_id,
+                    damage: result.damage,
+                }];
+                if let Some(target) = self.state.units.get_mut(target_id) {
+                    target.take_damage(result.damage);
+                    effects.push(ActionEffect::UnitDamaged {
+                        unit_id: *target_id,
+                        damage: result.damage,
+                        new_health: target.health,
+                    });
+                }
+                if result.target_destroyed {
+                    self.state.units.remove(target_id);
+                    effects.push(ActionEffect::UnitDestroyed {
+                        unit_id: *target_id,
+                    });
                 }
 
+                Ok(ActionResult::ok(effects))
+            }
+
+            GameAction::FoundCity { settler_id, name } => {
+                let settler = self
+                    .state
+                    .units
+                    .get(settler_id)
+                    .ok_or(ReplayError::UnitNotFound)?;
+                let pos = settler.position;
+
                 // Create city
                 let city_id = self.state.allocate_city_id();
                 let is_capital = self
                     .state
                     .players
-                    .get(player_id as usize)
+                    .get(player_id.index())
                     .map(|p| p.capital.is_none())
                     .unwrap_or(false);
 
@@ -560,11 +1168,16 @@ impl GameEngine {
 
                 // Update player capital
                 if is_capital {
-                    if let Some(player) = self.state.players.get_mut(player_id as usize) {
+                    if let Some(player) = self.state.players.get_mut(player_id.index()) {
                         player.capital = Some(city_id);
                     }
                 }
 
+                // Cities come with a road on their center tile
+                if let Some(tile) = self.state.map.get_mut(&pos) {
+                    tile.road = Some(Road::Road);
+                }
+
                 // Update map tiles
                 for coord in &city.territory {
                     if let Some(tile) = self.state.map.get_mut(coord) {
@@ -576,289 +1189,3659 @@ impl GameEngine {
                 self.state.cities.insert(city_id, city);
                 self.state.units.remove(settler_id);
 
-                Ok(ActionResult::ok(vec![ActionEffect::CityFounded {
+                let mut effects = vec![ActionEffect::CityFounded {
                     city_id,
                     name: name.clone(),
                     position: pos,
-                }]))
+                }];
+                effects.extend(self.first_contacts(player_id));
+                Ok(ActionResult::ok(effects))
             }
 
-            GameAction::FortifyUnit { unit_id } => {
+            GameAction::ChoosePromotion { unit_id, promotion } => {
                 let unit = self
                     .state
                     .units
                     .get_mut(unit_id)
                     .ok_or(ReplayError::UnitNotFound)?;
 
-                if unit.owner != player_id {
-                    return Err(ReplayError::NotOwner);
+                if let Err(e) = unit.promote(*promotion) {
+                    return Ok(ActionResult::err(&e.to_string()));
                 }
+                Ok(ActionResult::ok(vec![ActionEffect::UnitPromoted {
+                    unit_id: *unit_id,
+                    promotion: *promotion,
+                    new_health: unit.health,
+                }]))
+            }
+
+            GameAction::FortifyUnit { unit_id } => {
+                let unit = self
+                    .state
+                    .units
+                    .get_mut(unit_id)
+                    .ok_or(ReplayError::UnitNotFound)?;
 
                 unit.fortify();
                 Ok(ActionResult::ok(vec![]))
             }
 
+            GameAction::FortifyUntilHealed { unit_id } => {
+                let unit = self
+                    .state
+                    .units
+                    .get_mut(unit_id)
+                    .ok_or(ReplayError::UnitNotFound)?;
+
+                unit.fortify_until_healed();
+                Ok(ActionResult::ok(vec![]))
+            }
+
             GameAction::SetResearch { tech_id } => {
-                if let Some(player) = self.state.players.get_mut(player_id as usize) {
+                if let Some(player) = self.state.players.get_mut(player_id.index()) {
                     player.current_research = Some(tech_id.clone());
                     player.research_progress = 0;
                 }
                 Ok(ActionResult::ok(vec![]))
             }
 
-            GameAction::EndGame {
-                winner_id,
-                victory_type,
-            } => {
-                self.state.phase = GamePhase::Ended;
-                Ok(ActionResult::ok(vec![ActionEffect::GameEnded {
-                    winner_id: *winner_id,
-                    victory_type: victory_type.clone(),
-                }]))
+            GameAction::QueueResearch { tech_id } => {
+                if let Some(player) = self.state.players.get_mut(player_id.index()) {
+                    research::queue_research(player, &TechTree::new(), tech_id);
+                }
+                Ok(ActionResult::ok(vec![]))
             }
 
-            // Other actions - implement as needed
-            _ => Ok(ActionResult::ok(vec![])),
-        }
-    }
+            GameAction::SetResearchQueue { queue } => {
+                if let Some(player) = self.state.players.get_mut(player_id.index()) {
+                    player.research_queue = queue.clone();
+                }
+                Ok(ActionResult::ok(vec![]))
+            }
+
+            GameAction::DeclareWar { target_player } => {
+                let mut effects = Vec::new();
+                if research::break_research_agreement(&mut self.state, player_id, *target_player) {
+                    effects.push(ActionEffect::ResearchAgreementBroken {
+                        player_id,
+                        partner: *target_player,
+                    });
+                }
+                let era = self
+                    .state
+                    .get_player(player_id)
+                    .map_or(Era::Ancient, |p| p.era);
+                self.state.diplomacy.declare_war_with_penalty(
+                    player_id,
+                    *target_player,
+                    self.state.turn,
+                    eras::scale_penalty(WAR_DECLARATION_SCORE_PENALTY, era),
+                );
+                Ok(ActionResult::ok(effects))
+            }
+
+            GameAction::ProposePeace { target_player } => {
+                self.state
+                    .diplomacy
+                    .propose_peace(player_id, *target_player);
+                Ok(ActionResult::ok(vec![]))
+            }
+
+            GameAction::AcceptPeace { from_player } => {
+                self.state
+                    .diplomacy
+                    .accept_peace(*from_player, player_id, self.state.turn);
+                Ok(ActionResult::ok(vec![]))
+            }
+
+            GameAction::RejectPeace { from_player } => {
+                self.state
+                    .diplomacy
+                    .withdraw_peace_proposal(*from_player, player_id);
+                Ok(ActionResult::ok(vec![]))
+            }
+
+            GameAction::ProposeTreaty {
+                target_player,
+                treaty_type,
+            } => {
+                let signed = if *treaty_type == TreatyType::ResearchAgreement {
+                    research::sign_research_agreement(&mut self.state, player_id, *target_player)
+                } else {
+                    self.state.diplomacy.propose_treaty(
+                        player_id,
+                        *target_player,
+                        *treaty_type,
+                        self.state.turn,
+                    )
+                };
+                if !signed {
+                    return Ok(ActionResult::err("Treaty requirements not met"));
+                }
+                Ok(ActionResult::ok(vec![]))
+            }
+
+            GameAction::ProposeTrade {
+                to_player,
+                offer,
+                request,
+            } => {
+                self.state.trades.propose_trade(TradeOffer::new(
+                    0,
+                    player_id,
+                    *to_player,
+                    (**offer).clone(),
+                    (**request).clone(),
+                    self.state.turn,
+                    None,
+                ));
+                Ok(ActionResult::ok(vec![]))
+            }
+
+            GameAction::RespondTrade {
+                offer_id, accept, ..
+            } => {
+                if !*accept {
+                    self.state
+                        .trades
+                        .reject_trade(*offer_id)
+                        .map_err(|_| ReplayError::GameError(GameError::InvalidAction))?;
+                    return Ok(ActionResult::ok(vec![]));
+                }
+
+                let offer = self
+                    .state
+                    .trades
+                    .get_offer(*offer_id)
+                    .cloned()
+                    .ok_or(ReplayError::GameError(GameError::InvalidAction))?;
+                if let Err(e) = execute_trade(&mut self.state, &offer) {
+                    return Ok(ActionResult::err(&e.to_string()));
+                }
+                self.state
+                    .trades
+                    .accept_trade(*offer_id)
+                    .map_err(|_| ReplayError::GameError(GameError::InvalidAction))?;
+
+                // Traded techs can carry either side into a new era
+                let effects = [offer.from_player, offer.to_player]
+                    .into_iter()
+                    .filter_map(|player_id| {
+                        eras::update_era(&mut self.state, player_id)
+                            .map(|era| ActionEffect::EraEntered { player_id, era })
+                    })
+                    .collect();
+                Ok(ActionResult::ok(effects))
+            }
+
+            GameAction::BuildRoad { unit_id } => {
+                let unit = self
+                    .state
+                    .units
+                    .get(unit_id)
+                    .ok_or(ReplayError::UnitNotFound)?;
+                let road = match self.road_for(player_id, unit) {
+                    Ok(road) => road,
+                    Err(rejection) => return Ok(ActionResult::err(&rejection.to_string())),
+                };
+
+                let work = RoadWork::new(road);
+                if let Some(unit) = self.state.units.get_mut(unit_id) {
+                    unit.movement = 0;
+                    unit.road_work = Some(work);
+                    unit.feature_work = None;
+                }
+                Ok(ActionResult::ok(vec![ActionEffect::RoadStarted {
+                    unit_id: *unit_id,
+                    road,
+                    turns: work.turns_remaining,
+                }]))
+            }
+
+            GameAction::RemoveFeature { unit_id } => {
+                let unit = self
+                    .state
+                    .units
+                    .get(unit_id)
+                    .ok_or(ReplayError::UnitNotFound)?;
+                let work = match self.feature_for(player_id, unit) {
+                    Ok(feature) => FeatureWork::new(feature),
+                    Err(rejection) => return Ok(ActionResult::err(&rejection.to_string())),
+                };
+                let Some(work) = work else {
+                    return Ok(ActionResult::err(&ClearError::NothingToClear.to_string()));
+                };
+
+                if let Some(unit) = self.state.units.get_mut(unit_id) {
+                    unit.movement = 0;
+                    unit.feature_work = Some(work);
+                    unit.road_work = None;
+                }
+                Ok(ActionResult::ok(vec![ActionEffect::FeatureClearing {
+                    unit_id: *unit_id,
+                    feature: work.feature,
+                    turns: work.turns_remaining,
+                }]))
+            }
+
+            GameAction::BuyTile { city_id, tile } => {
+                let cost = self
+                    .state
+                    .cities
+                    .get(city_id)
+                    .map(|city| borders::tile_purchase_cost(city, *tile))
+                    .ok_or(ReplayError::CityNotFound)?;
+                if let Some(player) = self.state.players.get_mut(player_id.index()) {
+                    player.gold -= cost;
+                }
+
+                let claim = borders::claim_tile(&mut self.state, *city_id, *tile);
+                Ok(ActionResult::ok(
+                    claim.into_iter().map(ActionEffect::from).collect(),
+                ))
+            }
+
+            GameAction::EndGame {
+                winner_id,
+                victory_type,
+            } => {
+                self.state.phase = GamePhase::Ended;
+                Ok(ActionResult::ok(vec![ActionEffect::GameEnded {
+                    winner_id: *winner_id,
+                    victory_type: victory_type.clone(),
+                }]))
+            }
+
+            GameAction::Concede { to_player } => {
+                let was_current = self.state.current_player == player_id;
+                let concession = concession::concede(&mut self.state, player_id, *to_player);
+
+                let mut effects = vec![ActionEffect::PlayerConceded {
+                    player_id,
+                    to_player: concession.recipient,
+                }];
+                if let Some(new_owner) = concession.recipient {
+                    effects.extend(
+                        concession
+                            .transferred
+                            .iter()
+                            .map(|&city_id| ActionEffect::CityTransferred { city_id, new_owner }),
+                    );
+                }
+                effects.extend(
+                    concession
+                        .razed
+                        .iter()
+                        .map(|&city_id| ActionEffect::CityRazed { city_id }),
+                );
+                effects.extend(
+                    concession
+                        .disbanded
+                        .iter()
+                        .map(|&unit_id| ActionEffect::UnitDestroyed { unit_id }),
+                );
+
+                match concession.winner {
+                    Some((winner_id, victory_type)) => {
+                        effects.push(ActionEffect::GameEnded {
+                            winner_id,
+                            victory_type: format!("{:?}", victory_type),
+                        });
+                    }
+                    // Don't leave the game waiting on a player who has left
+                    None if was_current => {
+                        effects.extend(
+                            turn::process_end_turn(&mut self.state)
+                                .map_err(ReplayError::GameError)?,
+                        );
+                    }
+                    None => {}
+                }
+                Ok(ActionResult::ok(effects))
+            }
+
+            GameAction::RequestPause { reason } => {
+                let outcome = pause::request_pause(&mut self.state, player_id, reason.clone());
+                let mut effects = vec![ActionEffect::PauseRequested {
+                    player_id,
+                    reason: reason.clone(),
+                }];
+                effects.extend(pause_outcome_effect(player_id, outcome));
+                Ok(ActionResult::ok(effects))
+            }
+
+            GameAction::VotePause { approve } => {
+                let requested_by = match &self.state.pause {
+                    PauseState::Requested { requested_by, .. } => *requested_by,
+                    _ => player_id,
+                };
+                let outcome = pause::vote_pause(&mut self.state, player_id, *approve);
+                let mut effects = vec![ActionEffect::PauseVoted {
+                    player_id,
+                    approve: *approve,
+                }];
+                effects.extend(pause_outcome_effect(requested_by, outcome));
+                Ok(ActionResult::ok(effects))
+            }
+
+            GameAction::SubstitutePlayer {
+                player_slot,
+                new_pubkey,
+                new_name,
+            } => {
+                let effects = substitution::substitute(
+                    &mut self.state,
+                    *player_slot,
+                    new_pubkey.clone(),
+                    new_name.clone(),
+                )
+                .map(|sub| ActionEffect::PlayerSubstituted {
+                    player_id: sub.player_id,
+                    pubkey: sub.pubkey,
+                })
+                .into_iter()
+                .collect();
+                Ok(ActionResult::ok(effects))
+            }
+
+            GameAction::RevealMap => {
+                self.state.revealed = true;
+                Ok(ActionResult::ok(vec![ActionEffect::MapRevealed {
+                    player_id,
+                }]))
+            }
+
+            GameAction::ResumeGame { countdown_secs } => {
+                pause::resume(&mut self.state);
+                Ok(ActionResult::ok(vec![ActionEffect::GameResumed {
+                    player_id,
+                    countdown_secs: *countdown_secs,
+                }]))
+            }
+
+            // Other actions - implement as needed
+            _ => Ok(ActionResult::ok(vec![])),
+        }
+    }
+
+    /// Apply a local action through the pre-commit buffer.
+    ///
+    /// Successful actions are recorded so they can be undone until the turn
+    /// is committed, unless they revealed new tiles. Use `drain_committed` to collect actions that are ready
+    /// to be signed and broadcast.
+    pub fn submit_action(
+        &mut self,
+        player_id: PlayerSlot,
+        action: &GameAction,
+    ) -> Result<ActionResult, ReplayError> {
+        let before = self.state.clone();
+        let result = self.apply_action(player_id, action)?;
+        if result.success {
+            self.buffer.advance_sequence(self.events.len() as u32);
+            self.buffer
+                .record(player_id, action.clone(), before, &self.state);
+        }
+        Ok(result)
+    }
+
+    /// Undo the most recent uncommitted action.
+    ///
+    /// Returns the undone action, or `None` if there is nothing to undo.
+    pub fn undo(&mut self) -> Option<GameAction> {
+        let (action, before) = self.buffer.undo()?;
+        self.state = before;
+        self.batch.pop(&action);
+        self.log.undo_action();
+        Some(action)
+    }
+
+    /// Re-apply the most recently undone action.
+    pub fn redo(&mut self) -> Result<Option<ActionResult>, ReplayError> {
+        let Some((player_id, action)) = self.buffer.take_redo() else {
+            return Ok(None);
+        };
+
+        let before = self.state.clone();
+        match self.apply_action(player_id, &action) {
+            Ok(result) if result.success => {
+                self.buffer.advance_sequence(self.events.len() as u32);
+                self.buffer
+                    .record_redo(player_id, action, before, &self.state);
+                Ok(Some(result))
+            }
+            other => {
+                self.buffer.restore_redo(player_id, action);
+                other.map(Some)
+            }
+        }
+    }
+
+    /// Report map generation progress to a callback when the game starts,
+    /// or stop reporting with `None`.
+    pub fn set_progress_callback(
+        &mut self,
+        callback: Option<Box<dyn FnMut(Progress) + Send + Sync>>,
+    ) {
+        self.progress = callback;
+    }
+
+    /// Enable or disable action buffering (disable for simultaneous turns).
+    pub fn set_action_buffering(&mut self, enabled: bool) {
+        self.buffer.set_enabled(enabled);
+    }
+
+    /// Take committed actions that are ready to be signed and broadcast.
+    pub fn drain_committed(&mut self) -> Vec<CommittedAction> {
+        self.buffer.drain_committed()
+    }
 
     /// Check if an action is valid for the current state.
-    pub fn is_valid_action(&self, player_id: PlayerId, action: &GameAction) -> bool {
+    pub fn is_valid_action(&self, player_id: PlayerSlot, action: &GameAction) -> bool {
+        self.validate_action(player_id, action).is_ok()
+    }
+
+    /// Validate an action without applying it.
+    ///
+    /// This runs the same checks that `apply_action` performs before
+    /// executing, so a UI can preview whether an action would succeed.
+    pub fn validate_action(
+        &self,
+        player_id: PlayerSlot,
+        action: &GameAction,
+    ) -> Result<(), ActionRejection> {
+        if action.requires_turn()
+            && self.state.phase == GamePhase::Playing
+            && player_id != self.state.current_player
+        {
+            return Err(ActionRejection::NotPlayerTurn);
+        }
+
+        let allowed_while_paused = matches!(
+            action,
+            GameAction::RequestPause { .. }
+                | GameAction::VotePause { .. }
+                | GameAction::ResumeGame { .. }
+                | GameAction::Concede { .. }
+                | GameAction::Extension { .. }
+                | GameAction::Unrecognized { .. }
+                | GameAction::Snapshot { .. }
+        );
+        if self.state.pause.is_paused() && !allowed_while_paused {
+            return Err(ActionRejection::GamePaused);
+        }
+
         match action {
             GameAction::MoveUnit { unit_id, path } => {
-                if let Some(unit) = self.state.units.get(unit_id) {
-                    unit.owner == player_id && unit.can_move() && !path.is_empty()
-                } else {
-                    false
+                let unit = self.owned_unit(player_id, *unit_id)?;
+                let steps = path_steps(unit.position, path);
+                let to = match path.last() {
+                    Some(to) if steps > 0 => *to,
+                    _ => return Err(ActionRejection::EmptyPath),
+                };
+
+                if unit.has_acted {
+                    return Err(ActionRejection::UnitAlreadyActed);
+                }
+
+                let config = self.state.path_config(unit);
+                let mut prev = unit.position;
+                for step in &path[path.len() - steps..] {
+                    if prev.distance(step) != 1 {
+                        return Err(ActionRejection::PathNotContiguous);
+                    }
+                    let owner = self.state.map.get(step).and_then(|tile| tile.owner);
+                    if let Some(owner) = owner.filter(|o| config.closed_borders.contains(o)) {
+                        return Err(ActionRejection::ClosedBorders {
+                            position: *step,
+                            owner,
+                        });
+                    }
+                    prev = *step;
+                }
+
+                if self.state.map.get(&to).is_none() {
+                    return Err(ActionRejection::InvalidPosition);
+                }
+
+                let required = path_cost(&self.state.map, unit, &config, path)?;
+                if unit.movement == 0 || required > unit.movement {
+                    return Err(ActionRejection::NotEnoughMovement {
+                        required,
+                        available: unit.movement,
+                    });
+                }
+
+                let occupied = self.state.units.values().any(|other| {
+                    other.id != unit.id
+                        && other.position == to
+                        && (other.owner != player_id || other.is_military() == unit.is_military())
+                });
+                if occupied {
+                    return Err(ActionRejection::TileOccupied { position: to });
                 }
+
+                Ok(())
             }
+
             GameAction::AttackUnit {
                 attacker_id,
                 defender_id,
                 ..
             } => {
-                if let (Some(attacker), Some(defender)) = (
-                    self.state.units.get(attacker_id),
-                    self.state.units.get(defender_id),
-                ) {
-                    attacker.owner == player_id
-                        && defender.owner != player_id
-                        && attacker.can_attack()
+                let attacker = self.owned_unit(player_id, *attacker_id)?;
+                let defender = self
+                    .state
+                    .units
+                    .get(defender_id)
+                    .ok_or(ActionRejection::UnitNotFound)?;
+
+                if defender.owner == player_id {
+                    return Err(ActionRejection::CannotAttackOwnUnit);
+                }
+                if attacker.has_acted {
+                    return Err(ActionRejection::UnitAlreadyActed);
+                }
+                if !attacker.can_attack() {
+                    return Err(ActionRejection::NoCombatStrength);
+                }
+                if !self.state.diplomacy.are_at_war(player_id, defender.owner) {
+                    return Err(ActionRejection::WarRequired {
+                        target_player: defender.owner,
+                    });
+                }
+
+                let distance = attacker.position.distance(&defender.position);
+                let range = if attacker.is_ranged() {
+                    attacker.range()
                 } else {
-                    false
+                    1
+                };
+                if distance > range {
+                    return Err(ActionRejection::OutOfRange { distance, range });
                 }
+
+                Ok(())
             }
-            GameAction::FoundCity { settler_id, .. } => {
-                if let Some(unit) = self.state.units.get(settler_id) {
-                    unit.owner == player_id && unit.unit_type == UnitType::Settler
+
+            GameAction::AttackCity {
+                attacker_id,
+                city_id,
+                ..
+            } => {
+                let attacker = self.owned_unit(player_id, *attacker_id)?;
+                let city = self
+                    .state
+                    .cities
+                    .get(city_id)
+                    .ok_or(ActionRejection::CityNotFound)?;
+
+                if city.owner == player_id {
+                    return Err(ActionRejection::CannotAttackOwnCity);
+                }
+                if attacker.has_acted {
+                    return Err(ActionRejection::UnitAlreadyActed);
+                }
+                if !attacker.can_attack() {
+                    return Err(ActionRejection::NoCombatStrength);
+                }
+                if !self.state.diplomacy.are_at_war(player_id, city.owner) {
+                    return Err(ActionRejection::WarRequired {
+                        target_player: city.owner,
+                    });
+                }
+
+                let distance = attacker.position.distance(&city.position);
+                let range = if attacker.is_ranged() {
+                    attacker.range()
                 } else {
-                    false
+                    1
+                };
+                if distance > range {
+                    return Err(ActionRejection::OutOfRange { distance, range });
                 }
+
+                Ok(())
             }
-            GameAction::EndTurn => self.state.current_player == player_id,
-            _ => true,
-        }
+
+            GameAction::GiftUnit { unit_id, recipient } => {
+                self.owned_unit(player_id, *unit_id)?;
+                let recipient_player = self
+                    .state
+                    .get_player(*recipient)
+                    .ok_or(ActionRejection::InvalidTarget)?;
+                if *recipient == player_id {
+                    return Err(ActionRejection::InvalidTarget);
+                }
+                if recipient_player.eliminated {
+                    return Err(ActionRejection::PlayerEliminated);
+                }
+                if self.state.diplomacy.are_at_war(player_id, *recipient) {
+                    return Err(ActionRejection::AtWar);
+                }
+                Ok(())
+            }
+
+            GameAction::ResolveCapture { city_id, choice } => {
+                let city = self
+                    .state
+                    .cities
+                    .get(city_id)
+                    .ok_or(ActionRejection::CityNotFound)?;
+                if city.owner != player_id {
+                    return Err(ActionRejection::NotOwner);
+                }
+                if !city.awaiting_capture_choice {
+                    return Err(ActionRejection::NoCaptureChoice);
+                }
+                if *choice == CaptureChoice::Raze && city.is_capital {
+                    return Err(ActionRejection::CannotRazeCapital);
+                }
+                Ok(())
+            }
+
+            GameAction::SetProduction { city_id, item }
+            | GameAction::BuyItem { city_id, item, .. } => {
+                let city = self
+                    .state
+                    .cities
+                    .get(city_id)
+                    .ok_or(ActionRejection::CityNotFound)?;
+                if city.owner != player_id {
+                    return Err(ActionRejection::NotOwner);
+                }
+                if city.puppet {
+                    return Err(ActionRejection::PuppetCity);
+                }
+                if let ProductionItem::Wonder(wonder) = item {
+                    if wonders::is_built(&self.state, *wonder) {
+                        return Err(ActionRejection::WonderAlreadyBuilt { wonder: *wonder });
+                    }
+                }
+                if let Some(player) = self.state.get_player(player_id) {
+                    if !eras::can_produce(player, item) {
+                        return Err(ActionRejection::EraLocked {
+                            required: item.era(),
+                        });
+                    }
+                    if let Some((resource, required)) = stockpile::item_resource_cost(item) {
+                        let available = player.stockpiled(resource);
+                        if available < required {
+                            return Err(ActionRejection::NotEnoughResource {
+                                resource,
+                                required,
+                                available,
+                            });
+                        }
+                    }
+                }
+                Ok(())
+            }
+
+            GameAction::BombardUnit {
+                city_id, target_id, ..
+            } => {
+                let city = self
+                    .state
+                    .cities
+                    .get(city_id)
+                    .ok_or(ActionRejection::CityNotFound)?;
+                if city.owner != player_id {
+                    return Err(ActionRejection::NotOwner);
+                }
+                let target = self
+                    .state
+                    .units
+                    .get(target_id)
+                    .ok_or(ActionRejection::UnitNotFound)?;
+
+                if target.owner == player_id {
+                    return Err(ActionRejection::CannotAttackOwnUnit);
+                }
+                if city.last_bombard_turn == Some(self.state.turn) {
+                    return Err(ActionRejection::CityAlreadyBombarded);
+                }
+                if !self.state.diplomacy.are_at_war(player_id, target.owner) {
+                    return Err(ActionRejection::WarRequired {
+                        target_player: target.owner,
+                    });
+                }
+
+                let distance = city.position.distance(&target.position);
+                if distance > CITY_BOMBARD_RANGE {
+                    return Err(ActionRejection::OutOfRange {
+                        distance,
+                        range: CITY_BOMBARD_RANGE,
+                    });
+                }
+
+                Ok(())
+            }
+
+            GameAction::FoundCity { settler_id, .. } => {
+                let settler = self.owned_unit(player_id, *settler_id)?;
+                if settler.unit_type != UnitType::Settler {
+                    return Err(ActionRejection::NotASettler);
+                }
+
+                let tile = self
+                    .state
+                    .map
+                    .get(&settler.position)
+                    .ok_or(ActionRejection::InvalidPosition)?;
+                if !tile.can_found_city() {
+                    return Err(ActionRejection::CannotFoundCityHere);
+                }
+
+                Ok(())
+            }
+
+            GameAction::FortifyUnit { unit_id } => {
+                self.owned_unit(player_id, *unit_id)?;
+                Ok(())
+            }
+
+            GameAction::FortifyUntilHealed { unit_id } => {
+                let unit = self.owned_unit(player_id, *unit_id)?;
+                if unit.health >= 100 {
+                    return Err(ActionRejection::UnitNotDamaged);
+                }
+                Ok(())
+            }
+
+            GameAction::SetResearch { tech_id } => {
+                let tree = TechTree::new();
+                if tree.get(tech_id).is_none() {
+                    return Err(ActionRejection::UnknownTech);
+                }
+
+                if let Some(player) = self.state.get_player(player_id) {
+                    if player.has_tech(tech_id) {
+                        return Err(ActionRejection::TechAlreadyResearched);
+                    }
+                    if !tree.can_research(tech_id, &player.technologies) {
+                        return Err(ActionRejection::MissingPrerequisites);
+                    }
+                }
+
+                Ok(())
+            }
+
+            GameAction::QueueResearch { tech_id } => {
+                if TechTree::new().get(tech_id).is_none() {
+                    return Err(ActionRejection::UnknownTech);
+                }
+                if let Some(player) = self.state.get_player(player_id) {
+                    if player.has_tech(tech_id) {
+                        return Err(ActionRejection::TechAlreadyResearched);
+                    }
+                }
+                Ok(())
+            }
+
+            GameAction::SetResearchQueue { queue } => {
+                let tree = TechTree::new();
+                let Some(player) = self.state.get_player(player_id) else {
+                    return Ok(());
+                };
+
+                // Each queued tech must be researchable once the current
+                // research and the techs before it are done
+                let mut known = player.technologies.clone();
+                known.extend(player.current_research.iter().cloned());
+                for tech_id in queue {
+                    if tree.get(tech_id).is_none() {
+                        return Err(ActionRejection::UnknownTech);
+                    }
+                    if !known.insert(tech_id.clone()) {
+                        return Err(ActionRejection::TechAlreadyResearched);
+                    }
+                    if !tree.can_research(tech_id, &known) {
+                        return Err(ActionRejection::MissingPrerequisites);
+                    }
+                }
+                Ok(())
+            }
+
+            GameAction::DeclareWar { target_player } => {
+                self.check_met(player_id, *target_player)?;
+                if self.state.diplomacy.are_at_war(player_id, *target_player) {
+                    return Err(ActionRejection::AlreadyAtWar);
+                }
+                Ok(())
+            }
+
+            GameAction::ProposePeace { target_player } => {
+                self.check_target(player_id, *target_player)?;
+                if !self.state.diplomacy.are_at_war(player_id, *target_player) {
+                    return Err(ActionRejection::NotAtWar);
+                }
+                Ok(())
+            }
+
+            GameAction::AcceptPeace { from_player } | GameAction::RejectPeace { from_player } => {
+                if !self
+                    .state
+                    .diplomacy
+                    .has_peace_proposal(*from_player, player_id)
+                {
+                    return Err(ActionRejection::NoPendingProposal);
+                }
+                Ok(())
+            }
+
+            GameAction::ProposeTreaty {
+                target_player,
+                treaty_type,
+            } => {
+                self.check_met(player_id, *target_player)?;
+                if self.state.diplomacy.are_at_war(player_id, *target_player) {
+                    return Err(ActionRejection::AtWar);
+                }
+                if *treaty_type == TreatyType::ResearchAgreement {
+                    // Both parties pay, so the poorer one must afford it
+                    let required = research::research_agreement_cost(&self.state.settings);
+                    let available = [player_id, *target_player]
+                        .iter()
+                        .filter_map(|id| self.state.get_player(*id))
+                        .map(|player| player.gold)
+                        .min()
+                        .unwrap_or(0);
+                    if available < required {
+                        return Err(ActionRejection::NotEnoughGold {
+                            required,
+                            available,
+                        });
+                    }
+                }
+                Ok(())
+            }
+
+            GameAction::ProposeTrade {
+                to_player,
+                offer,
+                request,
+            } => {
+                self.check_met(player_id, *to_player)?;
+                if self.state.diplomacy.are_at_war(player_id, *to_player) {
+                    return Err(ActionRejection::AtWar);
+                }
+                if offer.is_empty() && request.is_empty() {
+                    return Err(ActionRejection::EmptyTrade);
+                }
+                Ok(())
+            }
+
+            GameAction::RespondTrade {
+                offer_id,
+                from_player,
+                ..
+            } => {
+                let offer = self
+                    .state
+                    .trades
+                    .get_offer(*offer_id)
+                    .ok_or(ActionRejection::NoPendingProposal)?;
+                if offer.to_player != player_id {
+                    return Err(ActionRejection::NotOwner);
+                }
+                if offer.from_player != *from_player || offer.status != TradeStatus::Pending {
+                    return Err(ActionRejection::NoPendingProposal);
+                }
+                Ok(())
+            }
+
+            GameAction::EndTurn => {
+                if self.state.current_player != player_id {
+                    return Err(ActionRejection::NotPlayerTurn);
+                }
+                Ok(())
+            }
+
+            GameAction::ChoosePromotion { unit_id, promotion } => {
+                let unit = self.owned_unit(player_id, *unit_id)?;
+                if !unit.can_promote() {
+                    return Err(ActionRejection::CannotPromote {
+                        reason: PromotionError::NotEnoughExperience {
+                            required: unit.next_promotion_threshold(),
+                            current: unit.experience,
+                        },
+                    });
+                }
+                unit.check_promotion(*promotion)
+                    .map_err(|reason| ActionRejection::CannotPromote { reason })
+            }
+
+            GameAction::BuildRoad { unit_id } => {
+                let unit = self.owned_unit(player_id, *unit_id)?;
+                if unit.unit_type != UnitType::Worker {
+                    return Err(ActionRejection::NotAWorker);
+                }
+                if unit.has_acted || unit.movement == 0 {
+                    return Err(ActionRejection::UnitAlreadyActed);
+                }
+                self.road_for(player_id, unit).map(|_| ())
+            }
+
+            GameAction::RemoveFeature { unit_id } => {
+                let unit = self.owned_unit(player_id, *unit_id)?;
+                if unit.unit_type != UnitType::Worker {
+                    return Err(ActionRejection::NotAWorker);
+                }
+                if unit.has_acted || unit.movement == 0 {
+                    return Err(ActionRejection::UnitAlreadyActed);
+                }
+                self.feature_for(player_id, unit).map(|_| ())
+            }
+
+            GameAction::BuyTile { city_id, tile } => {
+                let city = self
+                    .state
+                    .cities
+                    .get(city_id)
+                    .ok_or(ActionRejection::CityNotFound)?;
+                if city.owner != player_id {
+                    return Err(ActionRejection::NotOwner);
+                }
+                if !borders::can_claim(city, &self.state.map, *tile) {
+                    return Err(ActionRejection::TileNotClaimable { position: *tile });
+                }
+
+                let required = borders::tile_purchase_cost(city, *tile);
+                let available = self
+                    .state
+                    .get_player(player_id)
+                    .map_or(0, |player| player.gold);
+                if available < required {
+                    return Err(ActionRejection::NotEnoughGold {
+                        required,
+                        available,
+                    });
+                }
+                Ok(())
+            }
+
+            GameAction::Concede { to_player } => {
+                if self.state.phase != GamePhase::Playing {
+                    return Err(ActionRejection::GameNotInProgress);
+                }
+                let eliminated = |id| self.state.get_player(id).is_none_or(|p| p.eliminated);
+                if eliminated(player_id) {
+                    return Err(ActionRejection::PlayerEliminated);
+                }
+                if let Some(target) = to_player {
+                    self.check_target(player_id, *target)?;
+                    if eliminated(*target) {
+                        return Err(ActionRejection::PlayerEliminated);
+                    }
+                }
+                Ok(())
+            }
+
+            GameAction::RequestPause { .. } => {
+                self.check_active_player(player_id)?;
+                if self.state.pause != PauseState::Running {
+                    return Err(ActionRejection::PauseInProgress);
+                }
+                Ok(())
+            }
+
+            GameAction::VotePause { .. } => {
+                self.check_active_player(player_id)?;
+                match &self.state.pause {
+                    PauseState::Requested { votes, .. } if votes.contains_key(&player_id) => {
+                        Err(ActionRejection::AlreadyVoted)
+                    }
+                    PauseState::Requested { .. } => Ok(()),
+                    _ => Err(ActionRejection::NoPauseRequest),
+                }
+            }
+
+            GameAction::SubstitutePlayer {
+                player_slot,
+                new_pubkey,
+                ..
+            } => {
+                self.check_active_player(player_id)?;
+                if player_id != self.state.host() {
+                    return Err(ActionRejection::NotHost);
+                }
+                self.check_target(player_id, *player_slot)?;
+                let target = self
+                    .state
+                    .get_player(*player_slot)
+                    .ok_or(ActionRejection::InvalidTarget)?;
+                if target.eliminated {
+                    return Err(ActionRejection::PlayerEliminated);
+                }
+                if target.is_ai {
+                    return Err(ActionRejection::InvalidTarget);
+                }
+                if self.state.player_for_pubkey(new_pubkey.as_str()).is_some() {
+                    return Err(ActionRejection::PubkeyInUse);
+                }
+                let turns_absent =
+                    substitution::turns_absent(&self.state, *player_slot).unwrap_or(0);
+                let required = self.state.settings.substitute_after_turns;
+                if turns_absent < required {
+                    return Err(ActionRejection::PlayerNotAbsent {
+                        turns_absent,
+                        required,
+                    });
+                }
+                Ok(())
+            }
+
+            GameAction::ResumeGame { .. } => match self.state.pause {
+                PauseState::Paused { requested_by, .. } => {
+                    if player_id != requested_by && player_id != self.state.host() {
+                        return Err(ActionRejection::CannotResume);
+                    }
+                    Ok(())
+                }
+                _ => Err(ActionRejection::NotPaused),
+            },
+
+            GameAction::RevealMap => {
+                if self.state.phase != GamePhase::Ended {
+                    return Err(ActionRejection::GameNotEnded);
+                }
+                if player_id != self.state.host() {
+                    return Err(ActionRejection::NotHost);
+                }
+                if self.state.revealed {
+                    return Err(ActionRejection::AlreadyRevealed);
+                }
+                Ok(())
+            }
+
+            _ => Ok(()),
+        }
+    }
+
+    /// Get the road a worker would build on its current tile.
+    fn road_for(&self, player_id: PlayerSlot, unit: &Unit) -> Result<Road, ActionRejection> {
+        let tile = self
+            .state
+            .map
+            .get(&unit.position)
+            .ok_or(ActionRejection::InvalidPosition)?;
+        let player = self
+            .state
+            .get_player(player_id)
+            .ok_or(ActionRejection::NotOwner)?;
+        roads::road_to_build(tile, &player.technologies)
+            .map_err(|reason| ActionRejection::CannotBuildRoad { reason })
+    }
+
+    /// Get the feature a worker would clear from its current tile.
+    fn feature_for(&self, player_id: PlayerSlot, unit: &Unit) -> Result<Feature, ActionRejection> {
+        let tile = self
+            .state
+            .map
+            .get(&unit.position)
+            .ok_or(ActionRejection::InvalidPosition)?;
+        let player = self
+            .state
+            .get_player(player_id)
+            .ok_or(ActionRejection::NotOwner)?;
+        terraform::feature_to_clear(tile, &player.technologies)
+            .map_err(|reason| ActionRejection::CannotClearFeature { reason })
+    }
+
+    /// Check that the game is in progress and the player is still in it.
+    fn check_active_player(&self, player_id: PlayerSlot) -> Result<(), ActionRejection> {
+        if self.state.phase != GamePhase::Playing {
+            return Err(ActionRejection::GameNotInProgress);
+        }
+        if self
+            .state
+            .get_player(player_id)
+            .is_none_or(|p| p.eliminated)
+        {
+            return Err(ActionRejection::PlayerEliminated);
+        }
+        Ok(())
+    }
+
+    /// Meet everyone the player can now see.
+    fn first_contacts(&mut self, player_id: PlayerSlot) -> Vec<ActionEffect> {
+        let met = contact::update_contacts(&mut self.state, player_id);
+        turn::first_contact_effects(player_id, met)
+    }
+
+    /// Check that a diplomatic target is a player this player has met.
+    fn check_met(&self, player_id: PlayerSlot, target: PlayerSlot) -> Result<(), ActionRejection> {
+        self.check_target(player_id, target)?;
+        if !self.state.has_met(player_id, target) {
+            return Err(ActionRejection::NotMet {
+                target_player: target,
+            });
+        }
+        Ok(())
+    }
+
+    /// Check that a diplomatic target is another player in the game.
+    fn check_target(
+        &self,
+        player_id: PlayerSlot,
+        target: PlayerSlot,
+    ) -> Result<(), ActionRejection> {
+        if target == player_id || self.state.get_player(target).is_none() {
+            return Err(ActionRejection::InvalidTarget);
+        }
+        Ok(())
+    }
+
+    /// Look up a unit and check that the player owns it.
+    fn owned_unit(&self, player_id: PlayerSlot, unit_id: UnitId) -> Result<&Unit, ActionRejection> {
+        let unit = self
+            .state
+            .units
+            .get(&unit_id)
+            .ok_or(ActionRejection::UnitNotFound)?;
+        if unit.owner != player_id {
+            return Err(ActionRejection::NotOwner);
+        }
+        Ok(unit)
+    }
+
+    /// Preview an attack between two units without consuming randomness.
+    ///
+    /// Returns `None` if either unit doesn't exist.
+    pub fn preview_attack(
+        &self,
+        attacker_id: UnitId,
+        defender_id: UnitId,
+    ) -> Option<CombatPreview> {
+        let attacker = self.state.units.get(&attacker_id)?;
+        let defender = self.state.units.get(&defender_id)?;
+        let attacker_tile = self
+            .state
+            .map
+            .get(&attacker.position)
+            .cloned()
+            .unwrap_or_default();
+        let defender_tile = self
+            .state
+            .map
+            .get(&defender.position)
+            .cloned()
+            .unwrap_or_default();
+
+        let ctx = CombatContext {
+            attacker,
+            defender,
+            attacker_tile: &attacker_tile,
+            defender_tile: &defender_tile,
+            random: Fixed::HALF,
+            is_ranged: attacker.is_ranged(),
+        };
+        Some(preview_with_difficulty(
+            &ctx,
+            self.state.difficulty_modifiers(attacker.owner).combat_bonus,
+            self.state.difficulty_modifiers(defender.owner).combat_bonus,
+        ))
+    }
+
+    /// Build a proof of the game's outcome from the event chain.
+    pub fn victory_proof(&self) -> Result<VictoryProof, VictoryProofError> {
+        VictoryProof::build(&self.state, self.events.events())
+    }
+
+    /// Get the turn order and expected wait times at `now` (Unix seconds),
+    /// timed from the event chain.
+    pub fn turn_schedule(&self, now: u64) -> TurnSchedule {
+        let times = TurnTimes::from_events(self.events.events());
+        TurnSchedule::build(&self.state, &times, now)
+    }
+
+    /// Get the current turn number.
+    pub fn turn(&self) -> u32 {
+        self.state.turn
+    }
+
+    /// Check if the game has ended.
+    pub fn is_ended(&self) -> bool {
+        self.state.is_ended()
+    }
+
+    /// Get the number of events in the chain.
+    pub fn event_count(&self) -> usize {
+        self.events.len()
+    }
+
+    /// Get all randomness proofs from the event chain.
+    pub fn get_randomness_proofs(&self) -> Vec<&RandomnessProof> {
+        self.events.randomness_proofs()
+    }
+
+    /// Verify all randomness proofs in the event chain.
+    pub fn verify_all_proofs(&self) -> Result<(), ReplayError> {
+        for event in self.events.events() {
+            if event.action.requires_random() {
+                self.validate_randomness_proof(event)?;
+            }
+        }
+        Ok(())
+    }
+}
+
+/// Number of steps in a move path, excluding the unit's own tile if the
+/// path starts there.
+fn path_steps(from: HexCoord, path: &[HexCoord]) -> usize {
+    match path.first() {
+        Some(first) if *first == from => path.len() - 1,
+        _ => path.len(),
+    }
+}
+
+/// Movement a unit spends following a path, with road bonuses.
+fn path_cost(
+    map: &Map,
+    unit: &Unit,
+    config: &PathConfig,
+    path: &[HexCoord],
+) -> Result<u32, ActionRejection> {
+    let steps = path_steps(unit.position, path);
+    let mut prev = unit.position;
+    let mut total = 0u32;
+    for step in &path[path.len() - steps..] {
+        let cost = pathfinding::step_cost(map, &prev, step, config)
+            .ok_or(ActionRejection::ImpassableTerrain { position: *step })?;
+        total = total.saturating_add(cost);
+        prev = *step;
+    }
+    Ok(total)
+}
+
+/// Structured reason an action would be rejected.
+///
+/// Serialized with a snake_case `code` tag so the frontend can match on
+/// the reason and show details (e.g. required vs. available movement).
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize, JsonSchema, TS)]
+#[serde(tag = "code", rename_all = "snake_case")]
+pub enum ActionRejection {
+    NotPlayerTurn,
+    UnitNotFound,
+    CityNotFound,
+    NotOwner,
+    InvalidPosition,
+    EmptyPath,
+    PathNotContiguous,
+    UnitAlreadyActed,
+    CityAlreadyBombarded,
+    NotEnoughMovement {
+        required: u32,
+        available: u32,
+    },
+    TileOccupied {
+        position: HexCoord,
+    },
+    ClosedBorders {
+        position: HexCoord,
+        owner: PlayerSlot,
+    },
+    EraLocked {
+        required: Era,
+    },
+    NotMet {
+        target_player: PlayerSlot,
+    },
+    WonderAlreadyBuilt {
+        wonder: WonderType,
+    },
+    CannotAttackOwnUnit,
+    CannotAttackOwnCity,
+    NoCombatStrength,
+    WarRequired {
+        target_player: PlayerSlot,
+    },
+    OutOfRange {
+        distance: u32,
+        range: u32,
+    },
+    NotASettler,
+    CannotFoundCityHere,
+    UnknownTech,
+    TechAlreadyResearched,
+    MissingPrerequisites,
+    InvalidTarget,
+    AlreadyAtWar,
+    NotAtWar,
+    AtWar,
+    NoPendingProposal,
+    EmptyTrade,
+    TileNotClaimable {
+        position: HexCoord,
+    },
+    NotEnoughGold {
+        required: i32,
+        available: i32,
+    },
+    NotEnoughResource {
+        resource: Resource,
+        required: u32,
+        available: u32,
+    },
+    CannotPromote {
+        reason: PromotionError,
+    },
+    UnitNotDamaged,
+    ImpassableTerrain {
+        position: HexCoord,
+    },
+    NotAWorker,
+    CannotBuildRoad {
+        reason: RoadError,
+    },
+    CannotClearFeature {
+        reason: ClearError,
+    },
+    GameNotInProgress,
+    PlayerEliminated,
+    GamePaused,
+    PauseInProgress,
+    NoPauseRequest,
+    AlreadyVoted,
+    NotPaused,
+    CannotResume,
+    NotHost,
+    PubkeyInUse,
+    PlayerNotAbsent {
+        turns_absent: u32,
+        required: u32,
+    },
+    GameNotEnded,
+    AlreadyRevealed,
+    NoCaptureChoice,
+    CannotRazeCapital,
+    PuppetCity,
+}
+
+impl ActionRejection {
+    /// Convert a rejection into the result `apply_action` returns.
+    ///
+    /// Lookup and ownership failures are hard errors (as during replay);
+    /// rule violations produce a failed `ActionResult`.
+    fn into_action_result(self) -> Result<ActionResult, ReplayError> {
+        match self {
+            ActionRejection::NotPlayerTurn => Err(ReplayError::NotPlayerTurn),
+            ActionRejection::UnitNotFound => Err(ReplayError::UnitNotFound),
+            ActionRejection::CityNotFound => Err(ReplayError::CityNotFound),
+            ActionRejection::NotOwner => Err(ReplayError::NotOwner),
+            ActionRejection::InvalidPosition => Err(ReplayError::InvalidPosition),
+            other => Ok(ActionResult::err(&other.to_string())),
+        }
+    }
+}
+
+impl std::fmt::Display for ActionRejection {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            ActionRejection::NotPlayerTurn => write!(f, "Not this player's turn"),
+            ActionRejection::UnitNotFound => write!(f, "Unit not found"),
+            ActionRejection::CityNotFound => write!(f, "City not found"),
+            ActionRejection::NotOwner => write!(f, "Player doesn't own this"),
+            ActionRejection::InvalidPosition => write!(f, "Invalid position"),
+            ActionRejection::EmptyPath => write!(f, "Empty path"),
+            ActionRejection::PathNotContiguous => write!(f, "Path is not contiguous"),
+            ActionRejection::UnitAlreadyActed => write!(f, "Unit has already acted this turn"),
+            ActionRejection::CityAlreadyBombarded => {
+                write!(f, "City has already bombarded this turn")
+            }
+            ActionRejection::NotEnoughMovement {
+                required,
+                available,
+            } => write!(
+                f,
+                "Not enough movement (requires {}, has {})",
+                required, available
+            ),
+            ActionRejection::TileOccupied { position } => {
+                write!(f, "Tile ({}, {}) is occupied", position.q, position.r)
+            }
+            ActionRejection::ClosedBorders { position, owner } => write!(
+                f,
+                "Tile ({}, {}) is inside player {}'s closed borders",
+                position.q, position.r, owner
+            ),
+            ActionRejection::EraLocked { required } => {
+                write!(f, "Requires the {}", required)
+            }
+            ActionRejection::WonderAlreadyBuilt { wonder } => {
+                write!(f, "{:?} has already been built", wonder)
+            }
+            ActionRejection::NotMet { target_player } => {
+                write!(f, "You have not met player {}", target_player)
+            }
+            ActionRejection::CannotAttackOwnUnit => write!(f, "Cannot attack your own unit"),
+            ActionRejection::CannotAttackOwnCity => write!(f, "Cannot attack your own city"),
+            ActionRejection::NoCombatStrength => write!(f, "Unit cannot attack"),
+            ActionRejection::WarRequired { target_player } => {
+                write!(f, "Must be at war with player {}", target_player)
+            }
+            ActionRejection::OutOfRange { distance, range } => {
+                write!(f, "Target out of range ({} > {})", distance, range)
+            }
+            ActionRejection::NotASettler => write!(f, "Only settlers can found cities"),
+            ActionRejection::CannotFoundCityHere => write!(f, "Cannot found city here"),
+            ActionRejection::UnknownTech => write!(f, "Unknown technology"),
+            ActionRejection::TechAlreadyResearched => write!(f, "Technology already researched"),
+            ActionRejection::MissingPrerequisites => {
+                write!(f, "Technology prerequisites not met")
+            }
+            ActionRejection::InvalidTarget => write!(f, "Invalid target player"),
+            ActionRejection::AlreadyAtWar => write!(f, "Already at war"),
+            ActionRejection::NotAtWar => write!(f, "Not at war"),
+            ActionRejection::AtWar => write!(f, "Not allowed while at war"),
+            ActionRejection::NoPendingProposal => write!(f, "No pending proposal"),
+            ActionRejection::EmptyTrade => write!(f, "Trade offer is empty"),
+            ActionRejection::TileNotClaimable { position } => {
+                write!(f, "Tile ({}, {}) cannot be claimed", position.q, position.r)
+            }
+            ActionRejection::NotEnoughGold {
+                required,
+                available,
+            } => write!(
+                f,
+                "Not enough gold (requires {}, has {})",
+                required, available
+            ),
+            ActionRejection::NotEnoughResource {
+                resource,
+                required,
+                available,
+            } => write!(
+                f,
+                "Not enough {:?} (requires {}, has {})",
+                resource, required, available
+            ),
+            ActionRejection::CannotPromote { reason } => write!(f, "Cannot promote: {}", reason),
+            ActionRejection::UnitNotDamaged => write!(f, "Unit is already at full health"),
+            ActionRejection::ImpassableTerrain { position } => {
+                write!(f, "Tile ({}, {}) is impassable", position.q, position.r)
+            }
+            ActionRejection::NotAWorker => write!(f, "Only workers can improve tiles"),
+            ActionRejection::CannotBuildRoad { reason } => {
+                write!(f, "Cannot build road: {}", reason)
+            }
+            ActionRejection::CannotClearFeature { reason } => {
+                write!(f, "Cannot clear feature: {}", reason)
+            }
+            ActionRejection::GameNotInProgress => write!(f, "Game is not in progress"),
+            ActionRejection::PlayerEliminated => write!(f, "Player has been eliminated"),
+            ActionRejection::GamePaused => write!(f, "Game is paused"),
+            ActionRejection::PauseInProgress => write!(f, "A pause is already requested or active"),
+            ActionRejection::NoPauseRequest => write!(f, "No pause has been requested"),
+            ActionRejection::AlreadyVoted => write!(f, "Already voted on this pause"),
+            ActionRejection::NotPaused => write!(f, "Game is not paused"),
+            ActionRejection::CannotResume => {
+                write!(f, "Only the player who paused or the host can resume")
+            }
+            ActionRejection::NotHost => write!(f, "Only the host can do this"),
+            ActionRejection::PubkeyInUse => write!(f, "Key already controls a player"),
+            ActionRejection::GameNotEnded => write!(f, "Game has not ended"),
+            ActionRejection::AlreadyRevealed => write!(f, "Map is already revealed"),
+            ActionRejection::NoCaptureChoice => write!(f, "City is not awaiting a capture choice"),
+            ActionRejection::CannotRazeCapital => write!(f, "Original capitals cannot be razed"),
+            ActionRejection::PuppetCity => write!(f, "Puppet cities can't be directed"),
+            ActionRejection::PlayerNotAbsent {
+                turns_absent,
+                required,
+            } => write!(
+                f,
+                "Player has been inactive for {} turn(s), {} required",
+                turns_absent, required
+            ),
+        }
+    }
+}
+
+/// Errors from replay operations.
+#[derive(Clone, Debug)]
+pub enum ReplayError {
+    EmptyEventChain,
+    MissingCreateGame,
+    InvalidSettings,
+    InvalidEventChain,
+    NotPlayerTurn,
+    UnitNotFound,
+    CityNotFound,
+    NotOwner,
+    InvalidPosition,
+    GameError(GameError),
+    MissingRandomnessProof,
+    InvalidRandomnessProof(String),
+    InvalidSnapshot(SnapshotError),
+}
+
+impl std::fmt::Display for ReplayError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            ReplayError::EmptyEventChain => write!(f, "Event chain is empty"),
+            ReplayError::MissingCreateGame => write!(f, "First event must be CreateGame"),
+            ReplayError::InvalidSettings => write!(f, "Invalid game settings"),
+            ReplayError::InvalidEventChain => write!(f, "Invalid event chain"),
+            ReplayError::NotPlayerTurn => write!(f, "Not this player's turn"),
+            ReplayError::UnitNotFound => write!(f, "Unit not found"),
+            ReplayError::CityNotFound => write!(f, "City not found"),
+            ReplayError::NotOwner => write!(f, "Player doesn't own this"),
+            ReplayError::InvalidPosition => write!(f, "Invalid position"),
+            ReplayError::GameError(e) => write!(f, "Game error: {}", e),
+            ReplayError::MissingRandomnessProof => {
+                write!(f, "Missing randomness proof for action that requires it")
+            }
+            ReplayError::InvalidRandomnessProof(msg) => {
+                write!(f, "Invalid randomness proof: {}", msg)
+            }
+            ReplayError::InvalidSnapshot(e) => write!(f, "Invalid snapshot: {}", e),
+        }
+    }
+}
+
+impl std::error::Error for ReplayError {}
+
+/// Effect announcing how a pause vote was settled, if it was.
+fn pause_outcome_effect(requested_by: PlayerSlot, outcome: PauseOutcome) -> Option<ActionEffect> {
+    match outcome {
+        PauseOutcome::Paused => Some(ActionEffect::GamePaused { requested_by }),
+        PauseOutcome::Rejected => Some(ActionEffect::PauseRejected { requested_by }),
+        PauseOutcome::Pending => None,
+    }
+}
+
+// Helper for hex encoding
+mod hex {
+    pub fn encode(bytes: &[u8]) -> String {
+        bytes.iter().map(|b| format!("{:02x}", b)).collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::city::City;
+    use crate::cow::Shared;
+    use crate::game_state::TreatyType;
+    use crate::trading::TradeItems;
+    use crate::types::{TechId, VictoryType};
+
+    #[test]
+    fn test_engine_creation() {
+        let settings = GameSettings::new("Test Game".to_string());
+        let engine = GameEngine::new(settings, [0u8; 32]);
+
+        assert_eq!(engine.state.phase, GamePhase::Setup);
+    }
+
+    #[test]
+    fn test_player_join() {
+        let settings = GameSettings::new("Test".to_string());
+        let mut engine = GameEngine::new(settings, [0u8; 32]);
+
+        let result = engine.apply_action(
+            PlayerSlot(0),
+            &GameAction::JoinGame {
+                player_name: "Player1".to_string(),
+                civilization_id: "rome".to_string(),
+            },
+        );
+
+        assert!(result.is_ok());
+        assert_eq!(engine.state.players.len(), 1);
+    }
+
+    #[test]
+    fn test_game_start() {
+        let mut settings = GameSettings::new("Test".to_string());
+        settings.map_size = crate::types::MapSize::Duel;
+
+        let mut engine = GameEngine::new(settings, [42u8; 32]);
+
+        // Add players
+        engine
+            .apply_action(
+                PlayerSlot(0),
+                &GameAction::JoinGame {
+                    player_name: "P1".to_string(),
+                    civilization_id: "rome".to_string(),
+                },
+            )
+            .unwrap();
+        engine
+            .apply_action(
+                PlayerSlot(1),
+                &GameAction::JoinGame {
+                    player_name: "P2".to_string(),
+                    civilization_id: "egypt".to_string(),
+                },
+            )
+            .unwrap();
+
+        // Start game
+        let result = engine.apply_action(PlayerSlot(0), &GameAction::StartGame);
+        assert!(result.is_ok());
+
+        assert_eq!(engine.state.phase, GamePhase::Playing);
+        assert_eq!(engine.state.turn, 1);
+        assert!(!engine.state.units.is_empty()); // Should have starting units
+    }
+
+    #[test]
+    fn test_is_valid_action() {
+        let settings = GameSettings::new("Test".to_string());
+        let engine = GameEngine::new(settings, [0u8; 32]);
+
+        // EndTurn should be valid for current player
+        assert!(engine.is_valid_action(PlayerSlot(0), &GameAction::EndTurn));
+    }
+
+    // ==== Action Validation Tests ====
+
+    fn started_duel() -> GameEngine {
+        let mut settings = GameSettings::new("Test".to_string());
+        settings.map_size = crate::types::MapSize::Duel;
+        let mut engine = GameEngine::new(settings, [42u8; 32]);
+        for (id, civ) in [(0, "rome"), (1, "egypt")] {
+            engine
+                .apply_action(
+                    PlayerSlot(id),
+                    &GameAction::JoinGame {
+                        player_name: format!("P{}", id),
+                        civilization_id: civ.to_string(),
+                    },
+                )
+                .unwrap();
+        }
+        engine
+            .apply_action(PlayerSlot(0), &GameAction::StartGame)
+            .unwrap();
+        engine
+    }
+
+    /// A started duel whose players have already met.
+    fn met_duel() -> GameEngine {
+        let mut engine = started_duel();
+        contact::meet(&mut engine.state, PlayerSlot(0), PlayerSlot(1));
+        engine
+    }
+
+    fn unit_of(engine: &GameEngine, owner: PlayerSlot, unit_type: UnitType) -> Unit {
+        engine
+            .state
+            .units
+            .values()
+            .find(|u| u.owner == owner && u.unit_type == unit_type)
+            .cloned()
+            .unwrap()
+    }
+
+    fn open_neighbor(engine: &GameEngine, pos: HexCoord) -> HexCoord {
+        pos.neighbors()
+            .into_iter()
+            .find(|n| {
+                engine.state.map.get(n).is_some()
+                    && !engine.state.units.values().any(|u| u.position == *n)
+            })
+            .unwrap()
+    }
+
+    #[test]
+    fn test_validate_not_player_turn() {
+        let engine = started_duel();
+        let warrior = unit_of(&engine, PlayerSlot(1), UnitType::Warrior);
+
+        let action = GameAction::FortifyUnit {
+            unit_id: warrior.id,
+        };
+        assert_eq!(
+            engine.validate_action(PlayerSlot(1), &action),
+            Err(ActionRejection::NotPlayerTurn)
+        );
+    }
+
+    #[test]
+    fn test_validate_move_not_enough_movement() {
+        let mut engine = started_duel();
+        let warrior = unit_of(&engine, PlayerSlot(0), UnitType::Warrior);
+
+        let first = open_neighbor(&engine, warrior.position);
+        let second = open_neighbor(&engine, first);
+        let third = open_neighbor(&engine, second);
+        for coord in [first, second, third] {
+            engine.state.map.get_mut(&coord).unwrap().feature = None;
+        }
+        let action = GameAction::MoveUnit {
+            unit_id: warrior.id,
+            path: vec![warrior.position, first, second, third],
+        };
+
+        assert_eq!(
+            engine.validate_action(PlayerSlot(0), &action),
+            Err(ActionRejection::NotEnoughMovement {
+                required: 30,
+                available: warrior.movement,
+            })
+        );
+    }
+
+    #[test]
+    fn test_validate_move_tile_occupied() {
+        let mut engine = started_duel();
+        let warrior = unit_of(&engine, PlayerSlot(0), UnitType::Warrior);
+        let target = open_neighbor(&engine, warrior.position);
+        let id = engine.state.allocate_unit_id();
+        engine
+            .state
+            .units
+            .insert(id, Unit::new(id, PlayerSlot(0), UnitType::Warrior, target));
+
+        let action = GameAction::MoveUnit {
+            unit_id: warrior.id,
+            path: vec![target],
+        };
+        assert_eq!(
+            engine.validate_action(PlayerSlot(0), &action),
+            Err(ActionRejection::TileOccupied { position: target })
+        );
+
+        // Stacking a civilian with a military unit is allowed
+        let settler = unit_of(&engine, PlayerSlot(0), UnitType::Settler);
+        let action = GameAction::MoveUnit {
+            unit_id: settler.id,
+            path: vec![target],
+        };
+        assert!(engine.validate_action(PlayerSlot(0), &action).is_ok());
+    }
+
+    #[test]
+    fn test_validate_attack_requires_war() {
+        let mut engine = met_duel();
+        let warrior = unit_of(&engine, PlayerSlot(0), UnitType::Warrior);
+        let target = open_neighbor(&engine, warrior.position);
+        let enemy = unit_of(&engine, PlayerSlot(1), UnitType::Warrior);
+        engine.state.units.get_mut(&enemy.id).unwrap().position = target;
+
+        let attack = GameAction::AttackUnit {
+            attacker_id: warrior.id,
+            defender_id: enemy.id,
+            random: 0.5,
+        };
+        assert_eq!(
+            engine.validate_action(PlayerSlot(0), &attack),
+            Err(ActionRejection::WarRequired {
+                target_player: PlayerSlot(1)
+            })
+        );
+
+        let result = engine.apply_action(PlayerSlot(0), &attack).unwrap();
+        assert!(!result.success);
+
+        engine
+            .apply_action(
+                PlayerSlot(0),
+                &GameAction::DeclareWar {
+                    target_player: PlayerSlot(1),
+                },
+            )
+            .unwrap();
+        assert!(engine.validate_action(PlayerSlot(0), &attack).is_ok());
+    }
+
+    #[test]
+    fn test_city_bombards_once_per_turn() {
+        let mut engine = met_duel();
+        let settler = unit_of(&engine, PlayerSlot(0), UnitType::Settler);
+        let city = City::new(
+            CityId(1),
+            PlayerSlot(0),
+            "Rome".to_string(),
+            settler.position,
+            true,
+        );
+        engine.state.cities.insert(CityId(1), city);
+
+        // Park the enemy warrior two tiles from the city
+        let enemy = unit_of(&engine, PlayerSlot(1), UnitType::Warrior);
+        let target = settler
+            .position
+            .neighbors()
+            .into_iter()
+            .flat_map(|n| n.neighbors())
+            .find(|c| {
+                settler.position.distance(c) == CITY_BOMBARD_RANGE
+                    && engine.state.map.get(c).is_some()
+                    && !engine.state.units.values().any(|u| u.position == *c)
+            })
+            .unwrap();
+        engine.state.units.get_mut(&enemy.id).unwrap().position = target;
+
+        let bombard = GameAction::BombardUnit {
+            city_id: CityId(1),
+            target_id: enemy.id,
+            random: 0.5,
+        };
+        assert_eq!(
+            engine.validate_action(PlayerSlot(0), &bombard),
+            Err(ActionRejection::WarRequired {
+                target_player: PlayerSlot(1)
+            })
+        );
+        engine
+            .apply_action(
+                PlayerSlot(0),
+                &GameAction::DeclareWar {
+                    target_player: PlayerSlot(1),
+                },
+            )
+            .unwrap();
+
+        let result = engine.apply_action(PlayerSlot(0), &bombard).unwrap();
+        assert!(result.success);
+        let damage = match result.effects[0] {
+            ActionEffect::CityBombarded { damage, .. } => damage,
+            ref other => panic!("unexpected effect {:?}", other),
+        };
+        assert!(damage > 0);
+        assert_eq!(engine.state.units[&enemy.id].health, 100 - damage);
+        assert_eq!(
+            engine.validate_action(PlayerSlot(0), &bombard),
+            Err(ActionRejection::CityAlreadyBombarded)
+        );
+
+        // A new turn reloads the city, but the target must be in range
+        engine.state.turn += 1;
+        assert!(engine.validate_action(PlayerSlot(0), &bombard).is_ok());
+        let far = target
+            .neighbors()
+            .into_iter()
+            .find(|c| settler.position.distance(c) > CITY_BOMBARD_RANGE)
+            .unwrap();
+        engine.state.units.get_mut(&enemy.id).unwrap().position = far;
+        assert_eq!(
+            engine.validate_action(PlayerSlot(0), &bombard),
+            Err(ActionRejection::OutOfRange {
+                distance: CITY_BOMBARD_RANGE + 1,
+                range: CITY_BOMBARD_RANGE,
+            })
+        );
+    }
+
+    #[test]
+    fn test_capture_city_and_choose_puppet() {
+        let mut engine = met_duel();
+        let warrior = unit_of(&engine, PlayerSlot(0), UnitType::Warrior);
+        let target = open_neighbor(&engine, warrior.position);
+        let mut city = City::new(CityId(9), PlayerSlot(1), "Thebes".to_string(), target, true);
+        city.population = 4;
+        city.health = 1;
+        engine.state.cities.insert(CityId(9), city);
+
+        let attack = GameAction::AttackCity {
+            attacker_id: warrior.id,
+            city_id: CityId(9),
+            random: 0.5,
+        };
+        assert_eq!(
+            engine.validate_action(PlayerSlot(0), &attack),
+            Err(ActionRejection::WarRequired {
+                target_player: PlayerSlot(1)
+            })
+        );
+        engine
+            .apply_action(
+                PlayerSlot(0),
+                &GameAction::DeclareWar {
+                    target_player: PlayerSlot(1),
+                },
+            )
+            .unwrap();
+
+        let result = engine.apply_action(PlayerSlot(0), &attack).unwrap();
+        assert!(result.success);
+        assert!(result.effects.contains(&ActionEffect::CityCaptured {
+            city_id: CityId(9),
+            player_id: PlayerSlot(0),
+            previous_owner: PlayerSlot(1),
+            population_lost: 2,
+            buildings_destroyed: vec![],
+        }));
+        assert_eq!(engine.state.cities[&CityId(9)].owner, PlayerSlot(0));
+        assert_eq!(engine.state.units[&warrior.id].position, target);
+
+        // Original capitals can't be razed
+        let raze = GameAction::ResolveCapture {
+            city_id: CityId(9),
+            choice: CaptureChoice::Raze,
+        };
+        assert_eq!(
+            engine.validate_action(PlayerSlot(0), &raze),
+            Err(ActionRejection::CannotRazeCapital)
+        );
+
+        let puppet = GameAction::ResolveCapture {
+            city_id: CityId(9),
+            choice: CaptureChoice::Puppet,
+        };
+        let result = engine.apply_action(PlayerSlot(0), &puppet).unwrap();
+        assert_eq!(
+            result.effects,
+            [ActionEffect::CaptureResolved {
+                city_id: CityId(9),
+                choice: CaptureChoice::Puppet,
+            }]
+        );
+        assert_eq!(
+            engine.validate_action(PlayerSlot(0), &puppet),
+            Err(ActionRejection::NoCaptureChoice)
+        );
+        assert_eq!(
+            engine.validate_action(
+                PlayerSlot(0),
+                &GameAction::SetProduction {
+                    city_id: CityId(9),
+                    item: crate::city::ProductionItem::Building(BuildingType::Monument),
+                }
+            ),
+            Err(ActionRejection::PuppetCity)
+        );
+    }
+
+    #[test]
+    fn test_production_requires_era() {
+        let mut engine = started_duel();
+        let city = City::new(
+            CityId(9),
+            PlayerSlot(0),
+            "Roma".to_string(),
+            HexCoord::new(0, 0),
+            true,
+        );
+        engine.state.cities.insert(CityId(9), city);
+
+        let knight = GameAction::SetProduction {
+            city_id: CityId(9),
+            item: crate::city::ProductionItem::Unit(UnitType::Knight),
+        };
+        assert_eq!(
+            engine.validate_action(PlayerSlot(0), &knight),
+            Err(ActionRejection::EraLocked {
+                required: Era::Medieval
+            })
+        );
+
+        engine.state.players[0].era = Era::Medieval;
+        assert_eq!(
+            engine.validate_action(PlayerSlot(0), &knight),
+            Err(ActionRejection::NotEnoughResource {
+                resource: Resource::Horses,
+                required: 10,
+                available: 0,
+            })
+        );
+
+        engine.state.players[0]
+            .stockpile
+            .insert(Resource::Horses, 10);
+        assert_eq!(engine.validate_action(PlayerSlot(0), &knight), Ok(()));
+    }
+
+    #[test]
+    fn test_wonder_race() {
+        let mut engine = started_duel();
+        for (id, owner) in [(8, 1), (9, 0)] {
+            let position = HexCoord::new(id as i32 * 3, 1);
+            // Productive land, whatever the map generated there
+            let tile = engine.state.map.get_mut(&position).unwrap();
+            tile.terrain = Terrain::Plains;
+            tile.feature = None;
+            engine.state.cities.insert(
+                CityId(id),
+                City::new(
+                    CityId(id),
+                    PlayerSlot(owner),
+                    format!("C{}", id),
+                    position,
+                    false,
+                ),
+            );
+        }
+        let pyramids = |city_id| GameAction::SetProduction {
+            city_id,
+            item: ProductionItem::Wonder(WonderType::Pyramids),
+        };
+        engine
+            .apply_action(PlayerSlot(0), &pyramids(CityId(9)))
+            .unwrap();
+        engine
+            .state
+            .cities
+            .get_mut(&CityId(8))
+            .unwrap()
+            .set_production(ProductionItem::Wonder(WonderType::Pyramids));
+        engine
+            .state
+            .cities
+            .get_mut(&CityId(8))
+            .unwrap()
+            .production_progress = 40;
+        engine
+            .state
+            .cities
+            .get_mut(&CityId(9))
+            .unwrap()
+            .production_progress = WonderType::Pyramids.cost() - 1;
+
+        let gold = engine.state.players[1].gold;
+        let result = engine
+            .apply_action(PlayerSlot(0), &GameAction::EndTurn)
+            .unwrap();
+        assert!(result.effects.contains(&ActionEffect::WonderCompleted {
+            city_id: CityId(9),
+            player_id: PlayerSlot(0),
+            wonder: WonderType::Pyramids,
+        }));
+        assert!(result.effects.contains(&ActionEffect::WonderLost {
+            city_id: CityId(8),
+            player_id: PlayerSlot(1),
+            wonder: WonderType::Pyramids,
+            gold: 40,
+        }));
+        assert_eq!(engine.state.players[1].gold, gold + 40);
+        assert_eq!(engine.state.cities[&CityId(8)].production, None);
+        assert_eq!(
+            engine.validate_action(PlayerSlot(1), &pyramids(CityId(8))),
+            Err(ActionRejection::WonderAlreadyBuilt {
+                wonder: WonderType::Pyramids
+            })
+        );
+    }
+
+    #[test]
+    fn test_diplomacy_requires_first_contact() {
+        let mut engine = started_duel();
+        let war = GameAction::DeclareWar {
+            target_player: PlayerSlot(1),
+        };
+        assert_eq!(
+            engine.validate_action(PlayerSlot(0), &war),
+            Err(ActionRejection::NotMet {
+                target_player: PlayerSlot(1)
+            })
+        );
+
+        // Walk a warrior up to player 1's units
+        let enemy = unit_of(&engine, PlayerSlot(1), UnitType::Warrior);
+        let warrior = unit_of(&engine, PlayerSlot(0), UnitType::Warrior);
+        let target = open_neighbor(&engine, enemy.position);
+        engine.state.units.get_mut(&warrior.id).unwrap().position = open_neighbor(&engine, target);
+        let result = engine
+            .apply_action(
+                PlayerSlot(0),
+                &GameAction::MoveUnit {
+                    unit_id: warrior.id,
+                    path: vec![target],
+                },
+            )
+            .unwrap();
+        assert!(result.effects.contains(&ActionEffect::FirstContact {
+            player_id: PlayerSlot(0),
+            met_player: PlayerSlot(1),
+        }));
+        assert!(result.effects.contains(&ActionEffect::FirstContact {
+            player_id: PlayerSlot(1),
+            met_player: PlayerSlot(0),
+        }));
+        assert_eq!(engine.validate_action(PlayerSlot(0), &war), Ok(()));
+    }
+
+    #[test]
+    fn test_move_respects_closed_borders() {
+        let mut engine = started_duel();
+        let warrior = unit_of(&engine, PlayerSlot(0), UnitType::Warrior);
+        let target = open_neighbor(&engine, warrior.position);
+        engine.state.map.get_mut(&target).unwrap().owner = Some(PlayerSlot(1));
+
+        let action = GameAction::MoveUnit {
+            unit_id: warrior.id,
+            path: vec![target],
+        };
+        assert_eq!(
+            engine.validate_action(PlayerSlot(0), &action),
+            Err(ActionRejection::ClosedBorders {
+                position: target,
+                owner: PlayerSlot(1)
+            })
+        );
+
+        engine
+            .state
+            .diplomacy
+            .get_mut(PlayerSlot(0), PlayerSlot(1))
+            .unwrap()
+            .add_treaty(crate::game_state::ActiveTreaty {
+                treaty_type: crate::game_state::TreatyType::OpenBorders,
+                turn_signed: 1,
+                duration: None,
+            });
+        assert_eq!(engine.validate_action(PlayerSlot(0), &action), Ok(()));
+    }
+
+    #[test]
+    fn test_gift_unit() {
+        let mut engine = started_duel();
+        let warrior = unit_of(&engine, PlayerSlot(0), UnitType::Warrior);
+        let gift = GameAction::GiftUnit {
+            unit_id: warrior.id,
+            recipient: PlayerSlot(1),
+        };
+
+        let to_self = GameAction::GiftUnit {
+            unit_id: warrior.id,
+            recipient: PlayerSlot(0),
+        };
+        assert_eq!(
+            engine.validate_action(PlayerSlot(0), &to_self),
+            Err(ActionRejection::InvalidTarget)
+        );
+
+        let result = engine.apply_action(PlayerSlot(0), &gift).unwrap();
+        assert_eq!(
+            result.effects,
+            [ActionEffect::UnitGifted {
+                unit_id: warrior.id,
+                from: PlayerSlot(0),
+                to: PlayerSlot(1),
+            }]
+        );
+        let unit = &engine.state.units[&warrior.id];
+        assert_eq!(unit.owner, PlayerSlot(1));
+        assert_eq!(unit.movement, 0);
+        assert_eq!(
+            engine.validate_action(PlayerSlot(0), &gift),
+            Err(ActionRejection::NotOwner)
+        );
+    }
+
+    #[test]
+    fn test_validate_found_city_requires_settler() {
+        let engine = started_duel();
+        let warrior = unit_of(&engine, PlayerSlot(0), UnitType::Warrior);
+
+        let action = GameAction::FoundCity {
+            settler_id: warrior.id,
+            name: "Rome".to_string(),
+        };
+        assert_eq!(
+            engine.validate_action(PlayerSlot(0), &action),
+            Err(ActionRejection::NotASettler)
+        );
+    }
+
+    #[test]
+    fn test_validate_does_not_mutate_state() {
+        let engine = started_duel();
+        let warrior = unit_of(&engine, PlayerSlot(0), UnitType::Warrior);
+        let target = open_neighbor(&engine, warrior.position);
+
+        let action = GameAction::MoveUnit {
+            unit_id: warrior.id,
+            path: vec![warrior.position, target],
+        };
+        assert!(engine.validate_action(PlayerSlot(0), &action).is_ok());
+        assert_eq!(engine.state.units[&warrior.id].position, warrior.position);
+        assert_eq!(engine.state.units[&warrior.id].movement, warrior.movement);
+    }
+
+    #[test]
+    fn test_rejection_serializes_with_code() {
+        let json = serde_json::to_value(ActionRejection::NotEnoughMovement {
+            required: 30,
+            available: 20,
+        })
+        .unwrap();
+        assert_eq!(json["code"], "not_enough_movement");
+        assert_eq!(json["required"], 30);
+    }
+
+    // ==== Diplomacy Tests ====
+
+    #[test]
+    fn test_peace_proposal_flow() {
+        let mut engine = met_duel();
+
+        assert_eq!(
+            engine.validate_action(
+                PlayerSlot(0),
+                &GameAction::ProposePeace {
+                    target_player: PlayerSlot(1)
+                }
+            ),
+            Err(ActionRejection::NotAtWar)
+        );
+
+        engine
+            .apply_action(
+                PlayerSlot(0),
+                &GameAction::DeclareWar {
+                    target_player: PlayerSlot(1),
+                },
+            )
+            .unwrap();
+        engine
+            .apply_action(
+                PlayerSlot(0),
+                &GameAction::ProposePeace {
+                    target_player: PlayerSlot(1),
+                },
+            )
+            .unwrap();
+
+        // The recipient may answer outside their own turn
+        let result = engine
+            .apply_action(
+                PlayerSlot(1),
+                &GameAction::AcceptPeace {
+                    from_player: PlayerSlot(0),
+                },
+            )
+            .unwrap();
+        assert!(result.success);
+        assert!(!engine
+            .state
+            .diplomacy
+            .are_at_war(PlayerSlot(0), PlayerSlot(1)));
+
+        assert_eq!(
+            engine.validate_action(
+                PlayerSlot(1),
+                &GameAction::AcceptPeace {
+                    from_player: PlayerSlot(0)
+                }
+            ),
+            Err(ActionRejection::NoPendingProposal)
+        );
+    }
+
+    #[test]
+    fn test_propose_treaty_requirements() {
+        let mut engine = met_duel();
+        let action = GameAction::ProposeTreaty {
+            target_player: PlayerSlot(1),
+            treaty_type: TreatyType::OpenBorders,
+        };
+
+        let result = engine.apply_action(PlayerSlot(0), &action).unwrap();
+        assert!(!result.success);
+
+        engine
+            .state
+            .diplomacy
+            .modify_relationship_score(PlayerSlot(0), PlayerSlot(1), 60);
+        let result = engine.apply_action(PlayerSlot(0), &action).unwrap();
+        assert!(result.success);
+        assert!(engine.state.diplomacy.has_treaty(
+            PlayerSlot(0),
+            PlayerSlot(1),
+            TreatyType::OpenBorders
+        ));
+    }
+
+    #[test]
+    fn test_trade_offer_accept() {
+        let mut engine = met_duel();
+        engine.state.players[0].gold = 100;
+
+        engine
+            .apply_action(
+                PlayerSlot(0),
+                &GameAction::ProposeTrade {
+                    to_player: PlayerSlot(1),
+                    offer: Box::new(TradeItems::new().with_gold(50)),
+                    request: Box::new(TradeItems::new().with_open_borders()),
+                },
+            )
+            .unwrap();
+        let offer_id = engine.state.trades.get_offers_for_player(PlayerSlot(1))[0].id;
+
+        // Only the recipient can respond
+        let respond = GameAction::RespondTrade {
+            offer_id,
+            from_player: PlayerSlot(0),
+            accept: true,
+        };
+        assert_eq!(
+            engine.validate_action(PlayerSlot(0), &respond),
+            Err(ActionRejection::NotOwner)
+        );
+
+        let result = engine.apply_action(PlayerSlot(1), &respond).unwrap();
+        assert!(result.success);
+        assert_eq!(engine.state.players[0].gold, 50);
+        assert_eq!(engine.state.players[1].gold, 50);
+        assert_eq!(
+            engine.state.trades.get_offer(offer_id).unwrap().status,
+            TradeStatus::Accepted
+        );
+    }
+
+    #[test]
+    fn test_trade_offer_rejected_at_war() {
+        let mut engine = met_duel();
+        engine
+            .apply_action(
+                PlayerSlot(0),
+                &GameAction::DeclareWar {
+                    target_player: PlayerSlot(1),
+                },
+            )
+            .unwrap();
+
+        let action = GameAction::ProposeTrade {
+            to_player: PlayerSlot(1),
+            offer: Box::new(TradeItems::new().with_gold(10)),
+            request: Box::new(TradeItems::new()),
+        };
+        assert_eq!(
+            engine.validate_action(PlayerSlot(0), &action),
+            Err(ActionRejection::AtWar)
+        );
+    }
+
+    // ==== Action Buffer Tests ====
+
+    #[test]
+    fn test_undo_redo_move() {
+        let mut engine = started_duel();
+        // With the whole map explored the move reveals nothing
+        let coords: Vec<HexCoord> = engine.state.map.tiles.keys().copied().collect();
+        for coord in coords {
+            engine.state.players[0].explore_tile(coord);
+        }
+        let warrior = unit_of(&engine, PlayerSlot(0), UnitType::Warrior);
+        let target = open_neighbor(&engine, warrior.position);
+        let action = GameAction::MoveUnit {
+            unit_id: warrior.id,
+            path: vec![warrior.position, target],
+        };
+
+        assert!(
+            engine
+                .submit_action(PlayerSlot(0), &action)
+                .unwrap()
+                .success
+        );
+        assert_eq!(engine.state.units[&warrior.id].position, target);
+
+        assert!(engine.undo().is_some());
+        assert_eq!(engine.state.units[&warrior.id].position, warrior.position);
+        assert_eq!(engine.state.units[&warrior.id].movement, warrior.movement);
+
+        assert!(engine.redo().unwrap().is_some());
+        assert_eq!(engine.state.units[&warrior.id].position, target);
+        assert!(engine.drain_committed().is_empty());
+    }
+
+    #[test]
+    fn test_move_revealing_tiles_commits() {
+        let mut engine = started_duel();
+        let warrior = unit_of(&engine, PlayerSlot(0), UnitType::Warrior);
+        let target = open_neighbor(&engine, warrior.position);
+        let action = GameAction::MoveUnit {
+            unit_id: warrior.id,
+            path: vec![warrior.position, target],
+        };
+        let before = engine.state.clone();
+
+        engine.submit_action(PlayerSlot(0), &action).unwrap();
+        assert!(ActionBuffer::reveals_tiles(
+            PlayerSlot(0),
+            &before,
+            &engine.state
+        ));
+        assert!(engine.undo().is_none());
+        assert_eq!(engine.drain_committed().len(), 1);
+    }
+
+    #[test]
+    fn test_end_turn_commits_buffer() {
+        let mut engine = started_duel();
+        let warrior = unit_of(&engine, PlayerSlot(0), UnitType::Warrior);
+
+        let turn = engine.state.turn;
+        engine
+            .submit_action(
+                PlayerSlot(0),
+                &GameAction::FortifyUnit {
+                    unit_id: warrior.id,
+                },
+            )
+            .unwrap();
+        engine
+            .submit_action(PlayerSlot(0), &GameAction::EndTurn)
+            .unwrap();
+
+        assert!(engine.undo().is_none());
+        let committed = engine.drain_committed();
+        assert_eq!(committed.len(), 2);
+        assert!(matches!(committed[1].action, GameAction::EndTurn));
+
+        // Both are stamped with the turn they were taken in, in order
+        assert!(committed.iter().all(|c| c.turn == turn));
+        assert_eq!(committed[1].sequence, committed[0].sequence + 1);
+    }
+
+    #[test]
+    fn test_buffering_disabled() {
+        let mut engine = started_duel();
+        engine.set_action_buffering(false);
+        let warrior = unit_of(&engine, PlayerSlot(0), UnitType::Warrior);
+
+        engine
+            .submit_action(
+                PlayerSlot(0),
+                &GameAction::FortifyUnit {
+                    unit_id: warrior.id,
+                },
+            )
+            .unwrap();
+        assert!(engine.undo().is_none());
+        assert_eq!(engine.drain_committed().len(), 1);
+    }
+
+    #[test]
+    fn test_replay_config_strict_mode() {
+        let config = ReplayConfig {
+            strict_randomness_validation: true,
+            allow_deterministic_randomness: false,
+            ..ReplayConfig::default()
+        };
+
+        let settings = GameSettings::new("Test".to_string());
+        let engine = GameEngine::with_config(settings, [0u8; 32], config);
+
+        assert!(engine.config.strict_randomness_validation);
+        assert!(!engine.config.allow_deterministic_randomness);
+    }
+
+    #[test]
+    fn test_randomness_proof_validation() {
+        let settings = GameSettings::new("Test".to_string());
+        let engine = GameEngine::new(settings, [42u8; 32]);
+
+        // Create an event with a valid deterministic proof
+        let proof = RandomnessProof {
+            mint_keyset_id: "deterministic".to_string(),
+            blinded_message: vec![],
+            blinded_signature: vec![],
+            signature: [42u8; 32].to_vec(),
+            random_bytes: [0u8; 32],
+            context: "test".to_string(),
+            timestamp: 0,
+        };
+
+        let event = GameEvent::with_randomness(
+            GameId::new("game1"),
+            PlayerSlot(0),
+            None,
+            1,
+            1,
+            GameAction::AttackUnit {
+                attacker_id: UnitId(1),
+                defender_id: UnitId(2),
+                random: proof.to_f32(),
+            },
+            proof,
+        );
+
+        // Validation should pass (not strict mode)
+        assert!(engine.validate_randomness_proof(&event).is_ok());
+    }
+
+    // ==== Difficulty Tests ====
+
+    fn duel_with(
+        difficulty: crate::settings::Difficulty,
+        ai_players: Vec<PlayerSlot>,
+    ) -> GameEngine {
+        let mut settings = GameSettings::new("Test".to_string());
+        settings.map_size = crate::types::MapSize::Duel;
+        settings.difficulty = difficulty;
+        settings.ai_players = ai_players;
+        let mut engine = GameEngine::new(settings, [42u8; 32]);
+        for (id, civ) in [(0, "rome"), (1, "egypt")] {
+            engine
+                .apply_action(
+                    PlayerSlot(id),
+                    &GameAction::JoinGame {
+                        player_name: format!("P{}", id),
+                        civilization_id: civ.to_string(),
+                    },
+                )
+                .unwrap();
+        }
+        engine
+            .apply_action(PlayerSlot(0), &GameAction::StartGame)
+            .unwrap();
+        engine
+    }
+
+    fn unit_count(engine: &GameEngine, owner: PlayerSlot) -> usize {
+        engine
+            .state
+            .units
+            .values()
+            .filter(|u| u.owner == owner)
+            .count()
+    }
+
+    #[test]
+    fn test_difficulty_marks_ai_players() {
+        let engine = duel_with(crate::settings::Difficulty::Normal, vec![PlayerSlot(1)]);
+        assert!(!engine.state.players[0].is_ai);
+        assert!(engine.state.players[1].is_ai);
+    }
+
+    #[test]
+    fn test_difficulty_starting_units_and_happiness() {
+        use crate::settings::Difficulty;
+
+        let normal = duel_with(Difficulty::Normal, vec![PlayerSlot(1)]);
+        assert_eq!(unit_count(&normal, PlayerSlot(0)), 2);
+        assert_eq!(unit_count(&normal, PlayerSlot(1)), 2);
+
+        let deity = duel_with(Difficulty::Deity, vec![PlayerSlot(1)]);
+        assert_eq!(unit_count(&deity, PlayerSlot(0)), 2);
+        assert_eq!(
+            unit_count(&deity, PlayerSlot(1)),
+            Difficulty::Deity.starting_units(true).len()
+        );
+        assert_eq!(
+            deity.state.players[1].happiness,
+            Difficulty::Deity.starting_happiness(true)
+        );
+
+        let settler = duel_with(Difficulty::Settler, vec![]);
+        assert_eq!(unit_count(&settler, PlayerSlot(0)), 3);
+    }
+
+    #[test]
+    fn test_difficulty_recorded_for_replay() {
+        use crate::settings::Difficulty;
+
+        // CreateGame carries the settings as JSON, so the difficulty and AI
+        // seats survive a replay.
+        let engine = duel_with(Difficulty::Emperor, vec![PlayerSlot(1)]);
+        let json = serde_json::to_string(&engine.state.settings).unwrap();
+        let settings: GameSettings = serde_json::from_str(&json).unwrap();
+        assert_eq!(settings.difficulty, Difficulty::Emperor);
+        assert_eq!(settings.ai_players, vec![PlayerSlot(1)]);
+        assert_eq!(
+            crate::audit::state_hash(&duel_with(settings.difficulty, settings.ai_players).state),
+            crate::audit::state_hash(&engine.state)
+        );
+    }
+
+    // ==== Border Tests ====
+
+    fn duel_with_city() -> (GameEngine, CityId) {
+        let mut engine = started_duel();
+        let settler = unit_of(&engine, PlayerSlot(0), UnitType::Settler);
+        engine
+            .apply_action(
+                PlayerSlot(0),
+                &GameAction::FoundCity {
+                    settler_id: settler.id,
+                    name: "Rome".to_string(),
+                },
+            )
+            .unwrap();
+        let city_id = *engine.state.cities.keys().next().unwrap();
+        (engine, city_id)
+    }
+
+    #[test]
+    fn test_buy_tile_claims_tile_and_spends_gold() {
+        let (mut engine, city_id) = duel_with_city();
+        let city = &engine.state.cities[&city_id];
+        let tile = borders::next_border_tile(city, &engine.state.map).unwrap();
+        let cost = borders::tile_purchase_cost(city, tile);
+        let action = GameAction::BuyTile { city_id, tile };
+
+        assert_eq!(
+            engine.validate_action(PlayerSlot(0), &action),
+            Err(ActionRejection::NotEnoughGold {
+                required: cost,
+                available: 0,
+            })
+        );
+
+        engine.state.players[0].gold = cost + 5;
+        let result = engine.apply_action(PlayerSlot(0), &action).unwrap();
+        assert!(result.success);
+        assert_eq!(
+            result.effects,
+            vec![ActionEffect::TileClaimed {
+                city_id,
+                owner: PlayerSlot(0),
+                coord: tile,
+            }]
+        );
+        assert_eq!(engine.state.players[0].gold, 5);
+        assert_eq!(
+            engine.state.map.get(&tile).unwrap().owner,
+            Some(PlayerSlot(0))
+        );
+        assert!(engine.state.cities[&city_id].territory.contains(&tile));
+
+        // Already owned now
+        assert_eq!(
+            engine.validate_action(PlayerSlot(0), &action),
+            Err(ActionRejection::TileNotClaimable { position: tile })
+        );
+    }
+
+    #[test]
+    fn test_end_turn_grows_borders_from_culture() {
+        let (mut engine, city_id) = duel_with_city();
+        let needed = engine.state.cities[&city_id].culture_for_next_tile();
+        engine.state.cities.get_mut(&city_id).unwrap().culture = needed;
+        let expected = borders::next_border_tile(&engine.state.cities[&city_id], &engine.state.map);
+
+        let result = engine
+            .apply_action(PlayerSlot(0), &GameAction::EndTurn)
+            .unwrap();
+        assert!(result.effects.contains(&ActionEffect::TileClaimed {
+            city_id,
+            owner: PlayerSlot(0),
+            coord: expected.unwrap(),
+        }));
+        assert_eq!(engine.state.cities[&city_id].territory.len(), 8);
+    }
+
+    // ==== Promotion Tests ====
+
+    #[test]
+    fn test_choose_promotion_requires_experience() {
+        let mut engine = started_duel();
+        let warrior = unit_of(&engine, PlayerSlot(0), UnitType::Warrior);
+        let action = GameAction::ChoosePromotion {
+            unit_id: warrior.id,
+            promotion: Promotion::ShockI,
+        };
+
+        assert_eq!(
+            engine.validate_action(PlayerSlot(0), &action),
+            Err(ActionRejection::CannotPromote {
+                reason: PromotionError::NotEnoughExperience {
+                    required: 10,
+                    current: 0,
+                },
+            })
+        );
+        assert!(!engine.apply_action(PlayerSlot(0), &action).unwrap().success);
+    }
+
+    #[test]
+    fn test_choose_promotion_heals_and_applies() {
+        let mut engine = started_duel();
+        let warrior = unit_of(&engine, PlayerSlot(0), UnitType::Warrior);
+        {
+            let unit = engine.state.units.get_mut(&warrior.id).unwrap();
+            unit.gain_experience(10);
+            unit.take_damage(60);
+        }
+
+        let drill = GameAction::ChoosePromotion {
+            unit_id: warrior.id,
+            promotion: Promotion::DrillII,
+        };
+        assert!(matches!(
+            engine.validate_action(PlayerSlot(0), &drill),
+            Err(ActionRejection::CannotPromote {
+                reason: PromotionError::MissingPrerequisites
+            })
+        ));
+
+        let result = engine
+            .apply_action(
+                PlayerSlot(0),
+                &GameAction::ChoosePromotion {
+                    unit_id: warrior.id,
+                    promotion: Promotion::ShockI,
+                },
+            )
+            .unwrap();
+        assert_eq!(
+            result.effects,
+            vec![ActionEffect::UnitPromoted {
+                unit_id: warrior.id,
+                promotion: Promotion::ShockI,
+                new_health: 90,
+            }]
+        );
+        assert_eq!(
+            engine.state.units[&warrior.id].promotions,
+            vec![Promotion::ShockI]
+        );
+    }
+
+    #[test]
+    fn test_rejection_serializes_promotion_reason() {
+        let json = serde_json::to_value(ActionRejection::CannotPromote {
+            reason: PromotionError::AlreadyHas,
+        })
+        .unwrap();
+        assert_eq!(json["code"], "cannot_promote");
+        assert_eq!(json["reason"], "AlreadyHas");
+    }
+
+    // ==== Healing Tests ====
+
+    #[test]
+    fn test_end_turn_heals_and_trickles_garrison_xp() {
+        let (mut engine, city_id) = duel_with_city();
+        let city_pos = engine.state.cities[&city_id].position;
+        let warrior = unit_of(&engine, PlayerSlot(0), UnitType::Warrior);
+        {
+            let unit = engine.state.units.get_mut(&warrior.id).unwrap();
+            unit.position = city_pos;
+            unit.take_damage(50);
+        }
+        engine
+            .apply_action(
+                PlayerSlot(0),
+                &GameAction::FortifyUntilHealed {
+                    unit_id: warrior.id,
+                },
+            )
+            .unwrap();
+
+        engine
+            .apply_action(PlayerSlot(0), &GameAction::EndTurn)
+            .unwrap();
+        let result = engine
+            .apply_action(PlayerSlot(1), &GameAction::EndTurn)
+            .unwrap();
+
+        assert!(result.effects.contains(&ActionEffect::UnitHealed {
+            unit_id: warrior.id,
+            amount: 20,
+            new_health: 70,
+        }));
+        let unit = &engine.state.units[&warrior.id];
+        assert_eq!(unit.experience, 1);
+        assert!(unit.healing);
+    }
+
+    #[test]
+    fn test_no_healing_in_enemy_territory() {
+        let mut engine = started_duel();
+        let warrior = unit_of(&engine, PlayerSlot(0), UnitType::Warrior);
+        engine
+            .state
+            .units
+            .get_mut(&warrior.id)
+            .unwrap()
+            .take_damage(50);
+        engine.state.map.get_mut(&warrior.position).unwrap().owner = Some(PlayerSlot(1));
+
+        engine
+            .apply_action(PlayerSlot(0), &GameAction::EndTurn)
+            .unwrap();
+        engine
+            .apply_action(PlayerSlot(1), &GameAction::EndTurn)
+            .unwrap();
+        assert_eq!(engine.state.units[&warrior.id].health, 50);
+    }
+
+    #[test]
+    fn test_fortify_until_healed_requires_damage() {
+        let engine = started_duel();
+        let warrior = unit_of(&engine, PlayerSlot(0), UnitType::Warrior);
+        assert_eq!(
+            engine.validate_action(
+                PlayerSlot(0),
+                &GameAction::FortifyUntilHealed {
+                    unit_id: warrior.id
+                }
+            ),
+            Err(ActionRejection::UnitNotDamaged)
+        );
+    }
+
+    // ==== Road Tests ====
+
+    fn worker_with_wheel(engine: &mut GameEngine) -> UnitId {
+        let warrior = unit_of(engine, PlayerSlot(0), UnitType::Warrior);
+        let id = engine.state.allocate_unit_id();
+        let worker = Unit::new(id, PlayerSlot(0), UnitType::Worker, warrior.position);
+        engine.state.units.insert(id, worker);
+        engine.state.players[0]
+            .technologies
+            .insert("the_wheel".to_string());
+        id
+    }
+
+    #[test]
+    fn test_build_road_takes_several_turns() {
+        let mut engine = started_duel();
+        let worker = worker_with_wheel(&mut engine);
+        let position = engine.state.units[&worker].position;
+        engine.state.map.get_mut(&position).unwrap().feature = None;
+
+        let result = engine
+            .apply_action(PlayerSlot(0), &GameAction::BuildRoad { unit_id: worker })
+            .unwrap();
+        assert_eq!(
+            result.effects,
+            vec![ActionEffect::RoadStarted {
+                unit_id: worker,
+                road: Road::Road,
+                turns: Road::Road.build_turns(),
+            }]
+        );
+
+        for _ in 1..Road::Road.build_turns() {
+            engine
+                .apply_action(PlayerSlot(0), &GameAction::EndTurn)
+                .unwrap();
+            engine
+                .apply_action(PlayerSlot(1), &GameAction::EndTurn)
+                .unwrap();
+        }
+        assert!(engine.state.map.get(&position).unwrap().road.is_none());
+
+        let result = engine
+            .apply_action(PlayerSlot(0), &GameAction::EndTurn)
+            .unwrap();
+        assert!(result.effects.contains(&ActionEffect::RoadBuilt {
+            coord: position,
+            road: Road::Road,
+        }));
+        assert_eq!(
+            engine.state.map.get(&position).unwrap().road,
+            Some(Road::Road)
+        );
+        assert!(engine.state.units[&worker].road_work.is_none());
+    }
+
+    #[test]
+    fn test_build_road_requires_worker_and_tech() {
+        let mut engine = started_duel();
+        let warrior = unit_of(&engine, PlayerSlot(0), UnitType::Warrior);
+        assert_eq!(
+            engine.validate_action(
+                PlayerSlot(0),
+                &GameAction::BuildRoad {
+                    unit_id: warrior.id
+                }
+            ),
+            Err(ActionRejection::NotAWorker)
+        );
+
+        let worker = worker_with_wheel(&mut engine);
+        engine.state.players[0].technologies.clear();
+        assert_eq!(
+            engine.validate_action(PlayerSlot(0), &GameAction::BuildRoad { unit_id: worker }),
+            Err(ActionRejection::CannotBuildRoad {
+                reason: RoadError::MissingTechnology
+            })
+        );
+    }
+
+    #[test]
+    fn test_remove_feature_takes_several_turns() {
+        let mut engine = started_duel();
+        let worker = worker_with_wheel(&mut engine);
+        let position = engine.state.units[&worker].position;
+        engine.state.map.get_mut(&position).unwrap().feature = Some(Feature::Forest);
+        assert_eq!(
+            engine.validate_action(
+                PlayerSlot(0),
+                &GameAction::RemoveFeature { unit_id: worker }
+            ),
+            Err(ActionRejection::CannotClearFeature {
+                reason: ClearError::MissingTechnology
+            })
+        );
+
+        engine.state.players[0]
+            .technologies
+            .insert("bronze_working".to_string());
+        let result = engine
+            .apply_action(
+                PlayerSlot(0),
+                &GameAction::RemoveFeature { unit_id: worker },
+            )
+            .unwrap();
+        let turns = terraform::clear_turns(Feature::Forest).unwrap();
+        assert_eq!(
+            result.effects,
+            vec![ActionEffect::FeatureClearing {
+                unit_id: worker,
+                feature: Feature::Forest,
+                turns,
+            }]
+        );
+
+        for _ in 1..turns {
+            engine
+                .apply_action(PlayerSlot(0), &GameAction::EndTurn)
+                .unwrap();
+            engine
+                .apply_action(PlayerSlot(1), &GameAction::EndTurn)
+                .unwrap();
+        }
+        let result = engine
+            .apply_action(PlayerSlot(0), &GameAction::EndTurn)
+            .unwrap();
+        assert!(result.effects.contains(&ActionEffect::FeatureCleared {
+            coord: position,
+            feature: Feature::Forest,
+            city_id: None,
+            production: 0,
+        }));
+        assert_eq!(engine.state.map.get(&position).unwrap().feature, None);
+        assert!(engine.state.units[&worker].feature_work.is_none());
+    }
+
+    #[test]
+    fn test_moving_abandons_road_work() {
+        let mut engine = started_duel();
+        let worker = worker_with_wheel(&mut engine);
+        engine
+            .apply_action(PlayerSlot(0), &GameAction::BuildRoad { unit_id: worker })
+            .unwrap();
+        engine
+            .apply_action(PlayerSlot(0), &GameAction::EndTurn)
+            .unwrap();
+        engine
+            .apply_action(PlayerSlot(1), &GameAction::EndTurn)
+            .unwrap();
+
+        let position = engine.state.units[&worker].position;
+        let target = open_neighbor(&engine, position);
+        engine.state.map.get_mut(&target).unwrap().feature = None;
+        engine
+            .apply_action(
+                PlayerSlot(0),
+                &GameAction::MoveUnit {
+                    unit_id: worker,
+                    path: vec![position, target],
+                },
+            )
+            .unwrap();
+        assert!(engine.state.units[&worker].road_work.is_none());
+    }
+
+    #[test]
+    fn test_move_along_road_costs_less() {
+        let mut engine = started_duel();
+        let warrior = unit_of(&engine, PlayerSlot(0), UnitType::Warrior);
+        let first = open_neighbor(&engine, warrior.position);
+        let second = open_neighbor(&engine, first);
+        let third = open_neighbor(&engine, second);
+        for coord in [warrior.position, first, second, third] {
+            let tile = engine.state.map.get_mut(&coord).unwrap();
+            tile.feature = None;
+            tile.road = Some(Road::Road);
+        }
+
+        let result = engine
+            .apply_action(
+                PlayerSlot(0),
+                &GameAction::MoveUnit {
+                    unit_id: warrior.id,
+                    path: vec![warrior.position, first, second, third],
+                },
+            )
+            .unwrap();
+        assert!(result.success);
+        assert_eq!(
+            engine.state.units[&warrior.id].movement,
+            warrior.movement - 15
+        );
+    }
+
+    // ==== Upkeep Tests ====
+
+    #[test]
+    fn test_end_turn_disbands_unit_when_bankrupt() {
+        let mut engine = started_duel();
+        let warrior = unit_of(&engine, PlayerSlot(0), UnitType::Warrior);
+        for _ in 0..crate::upkeep::FREE_UNITS_BASE + 1 {
+            let id = engine.state.allocate_unit_id();
+            engine.state.units.insert(
+                id,
+                Unit::new(id, PlayerSlot(0), UnitType::Swordsman, warrior.position),
+            );
+        }
+        engine.state.players[0].gold = 0;
+
+        let result = engine
+            .apply_action(PlayerSlot(0), &GameAction::EndTurn)
+            .unwrap();
+        assert!(result.effects.contains(&ActionEffect::UnitDestroyed {
+            unit_id: warrior.id
+        }));
+        assert!(!engine.state.units.contains_key(&warrior.id));
+        assert_eq!(engine.state.players[0].gold, 0);
+    }
+
+    // ==== Pause Tests ====
+
+    #[test]
+    fn test_pause_vote_blocks_play_until_resumed() {
+        let mut engine = started_duel();
+
+        // Player 1 can ask for a pause while player 0 is moving
+        let result = engine
+            .apply_action(PlayerSlot(1), &GameAction::RequestPause { reason: None })
+            .unwrap();
+        assert_eq!(
+            result.effects,
+            vec![ActionEffect::PauseRequested {
+                player_id: PlayerSlot(1),
+                reason: None
+            }]
+        );
+        assert_eq!(
+            engine.validate_action(PlayerSlot(1), &GameAction::VotePause { approve: true }),
+            Err(ActionRejection::AlreadyVoted)
+        );
+
+        let result = engine
+            .apply_action(PlayerSlot(0), &GameAction::VotePause { approve: true })
+            .unwrap();
+        assert!(result.effects.contains(&ActionEffect::GamePaused {
+            requested_by: PlayerSlot(1)
+        }));
+        assert_eq!(
+            engine.validate_action(PlayerSlot(0), &GameAction::EndTurn),
+            Err(ActionRejection::GamePaused)
+        );
+
+        // Only the requester or the host may resume
+        engine.state.players[0].is_host = false;
+        engine.state.players[1].is_host = true;
+        let resume = GameAction::ResumeGame { countdown_secs: 5 };
+        assert_eq!(
+            engine.validate_action(PlayerSlot(0), &resume),
+            Err(ActionRejection::CannotResume)
+        );
+        let result = engine.apply_action(PlayerSlot(1), &resume).unwrap();
+        assert_eq!(
+            result.effects,
+            vec![ActionEffect::GameResumed {
+                player_id: PlayerSlot(1),
+                countdown_secs: 5
+            }]
+        );
+        assert!(engine.is_valid_action(PlayerSlot(0), &GameAction::EndTurn));
+        assert_eq!(
+            engine.validate_action(PlayerSlot(0), &resume),
+            Err(ActionRejection::NotPaused)
+        );
+    }
+
+    // ==== Substitution Tests ====
+
+    #[test]
+    fn test_host_substitutes_inactive_player() {
+        let mut engine = started_duel();
+        let substitute = GameAction::SubstitutePlayer {
+            player_slot: PlayerSlot(1),
+            new_pubkey: Npub::new("npub_sub"),
+            new_name: None,
+        };
+
+        // Player 1 acted this turn, so they can't be replaced yet
+        engine
+            .apply_action(PlayerSlot(1), &GameAction::RequestPause { reason: None })
+            .unwrap();
+        engine.state.pause = PauseState::Running;
+        assert_eq!(
+            engine.validate_action(PlayerSlot(0), &substitute),
+            Err(ActionRejection::PlayerNotAbsent {
+                turns_absent: 0,
+                required: engine.state.settings.substitute_after_turns,
+            })
+        );
+
+        engine.state.turn += engine.state.settings.substitute_after_turns;
+        assert_eq!(
+            engine.validate_action(PlayerSlot(1), &substitute),
+            Err(ActionRejection::NotHost)
+        );
+        let result = engine.apply_action(PlayerSlot(0), &substitute).unwrap();
+        assert_eq!(
+            result.effects,
+            vec![ActionEffect::PlayerSubstituted {
+                player_id: PlayerSlot(1),
+                pubkey: Npub::new("npub_sub")
+            }]
+        );
+        assert_eq!(
+            engine.state.player_for_pubkey("npub_sub"),
+            Some(PlayerSlot(1))
+        );
+        assert_eq!(
+            engine.validate_action(PlayerSlot(0), &substitute),
+            Err(ActionRejection::PubkeyInUse)
+        );
+    }
+
+    // ==== Research Queue Tests ====
+
+    #[test]
+    fn test_queue_research_and_edit_queue() {
+        let mut engine = started_duel();
+        let writing = "writing".to_string();
+        engine
+            .apply_action(
+                PlayerSlot(0),
+                &GameAction::QueueResearch {
+                    tech_id: writing.clone(),
+                },
+            )
+            .unwrap();
+
+        let player = &engine.state.players[0];
+        let mut planned: Vec<TechId> = player.current_research.iter().cloned().collect();
+        planned.extend(player.research_queue.iter().cloned());
+        assert_eq!(
+            planned,
+            TechTree::new().research_path(&writing, &player.technologies)
+        );
+
+        // Writing can't come before pottery
+        let out_of_order = GameAction::SetResearchQueue {
+            queue: vec![writing.clone(), "pottery".to_string()],
+        };
+        assert_eq!(
+            engine.validate_action(PlayerSlot(0), &out_of_order),
+            Err(ActionRejection::MissingPrerequisites)
+        );
+
+        let unknown = GameAction::SetResearchQueue {
+            queue: vec!["warp_drive".to_string()],
+        };
+        assert_eq!(
+            engine.validate_action(PlayerSlot(0), &unknown),
+            Err(ActionRejection::UnknownTech)
+        );
+
+        engine
+            .apply_action(
+                PlayerSlot(0),
+                &GameAction::SetResearchQueue { queue: vec![] },
+            )
+            .unwrap();
+        assert!(engine.state.players[0].research_queue.is_empty());
     }
 
-    /// Get the current turn number.
-    pub fn turn(&self) -> u32 {
-        self.state.turn
+    // ==== Map Reveal Tests ====
+
+    #[test]
+    fn test_host_reveals_map_after_game_ends() {
+        let mut engine = started_duel();
+        assert_eq!(
+            engine.validate_action(PlayerSlot(0), &GameAction::RevealMap),
+            Err(ActionRejection::GameNotEnded)
+        );
+
+        engine
+            .apply_action(PlayerSlot(1), &GameAction::Concede { to_player: None })
+            .unwrap();
+        assert_eq!(
+            engine.validate_action(PlayerSlot(1), &GameAction::RevealMap),
+            Err(ActionRejection::NotHost)
+        );
+
+        let result = engine
+            .apply_action(PlayerSlot(0), &GameAction::RevealMap)
+            .unwrap();
+        assert_eq!(
+            result.effects,
+            vec![ActionEffect::MapRevealed {
+                player_id: PlayerSlot(0)
+            }]
+        );
+        assert!(engine.state.revealed);
+        assert_eq!(
+            engine.validate_action(PlayerSlot(0), &GameAction::RevealMap),
+            Err(ActionRejection::AlreadyRevealed)
+        );
     }
 
-    /// Check if the game has ended.
-    pub fn is_ended(&self) -> bool {
-        self.state.is_ended()
+    // ==== Concession Tests ====
+
+    #[test]
+    fn test_concede_ends_duel() {
+        let mut engine = started_duel();
+        let warrior = unit_of(&engine, PlayerSlot(1), UnitType::Warrior);
+
+        // Conceding doesn't need to wait for the player's turn
+        let result = engine
+            .apply_action(PlayerSlot(1), &GameAction::Concede { to_player: None })
+            .unwrap();
+        assert!(result.success);
+        assert!(result.effects.contains(&ActionEffect::PlayerConceded {
+            player_id: PlayerSlot(1),
+            to_player: Some(PlayerSlot(0)),
+        }));
+        assert!(result.effects.contains(&ActionEffect::UnitDestroyed {
+            unit_id: warrior.id
+        }));
+        assert!(result.effects.contains(&ActionEffect::GameEnded {
+            winner_id: PlayerSlot(0),
+            victory_type: "Concession".to_string(),
+        }));
+        assert_eq!(
+            engine.state.winner,
+            Some((PlayerSlot(0), VictoryType::Concession))
+        );
+        assert_eq!(engine.state.phase, GamePhase::Ended);
+
+        assert_eq!(
+            engine.validate_action(PlayerSlot(0), &GameAction::Concede { to_player: None }),
+            Err(ActionRejection::GameNotInProgress)
+        );
     }
 
-    /// Get the number of events in the chain.
-    pub fn event_count(&self) -> usize {
-        self.events.len()
+    #[test]
+    fn test_concede_on_own_turn_passes_turn() {
+        let mut settings = GameSettings::new("Test".to_string());
+        settings.player_count = 3;
+        let mut engine = GameEngine::new(settings, [42u8; 32]);
+        for (id, civ) in [(0, "rome"), (1, "egypt"), (2, "greece")] {
+            engine
+                .apply_action(
+                    PlayerSlot(id),
+                    &GameAction::JoinGame {
+                        player_name: format!("P{}", id),
+                        civilization_id: civ.to_string(),
+                    },
+                )
+                .unwrap();
+        }
+        engine
+            .apply_action(PlayerSlot(0), &GameAction::StartGame)
+            .unwrap();
+
+        assert_eq!(
+            engine.validate_action(
+                PlayerSlot(0),
+                &GameAction::Concede {
+                    to_player: Some(PlayerSlot(0))
+                }
+            ),
+            Err(ActionRejection::InvalidTarget)
+        );
+
+        let result = engine
+            .apply_action(
+                PlayerSlot(0),
+                &GameAction::Concede {
+                    to_player: Some(PlayerSlot(2)),
+                },
+            )
+            .unwrap();
+        assert!(result.success);
+        assert_eq!(engine.state.phase, GamePhase::Playing);
+        assert_eq!(engine.state.current_player, PlayerSlot(1));
+        assert!(engine.state.players[0].conceded);
+
+        assert_eq!(
+            engine.validate_action(
+                PlayerSlot(1),
+                &GameAction::Concede {
+                    to_player: Some(PlayerSlot(0))
+                }
+            ),
+            Err(ActionRejection::PlayerEliminated)
+        );
     }
 
-    /// Get all randomness proofs from the event chain.
-    pub fn get_randomness_proofs(&self) -> Vec<&RandomnessProof> {
-        self.events.randomness_proofs()
+    // ==== Preview Tests ====
+
+    #[test]
+    fn test_preview_leaves_game_unchanged() {
+        let engine = started_duel();
+        let warrior = unit_of(&engine, PlayerSlot(0), UnitType::Warrior);
+        let target = open_neighbor(&engine, warrior.position);
+        let action = GameAction::MoveUnit {
+            unit_id: warrior.id,
+            path: vec![target],
+        };
+
+        let (result, preview) = engine.preview(PlayerSlot(0), &action).unwrap();
+        assert!(result.success);
+        assert_eq!(preview.units[&warrior.id].position, target);
+        assert_eq!(engine.state.units[&warrior.id].position, warrior.position);
+        assert!(Shared::ptr_eq(&preview.map.tiles, &engine.state.map.tiles));
+        assert!(Shared::ptr_eq(&preview.cities, &engine.state.cities));
+        assert_eq!(engine.event_count(), 0);
+        assert!(!engine.buffer.can_undo());
     }
 
-    /// Verify all randomness proofs in the event chain.
-    pub fn verify_all_proofs(&self) -> Result<(), ReplayError> {
-        for event in self.events.events() {
-            if event.action.requires_random() {
-                self.validate_randomness_proof(event)?;
-            }
-        }
-        Ok(())
+    #[test]
+    fn test_preview_rejects_out_of_turn_action() {
+        let engine = started_duel();
+        let warrior = unit_of(&engine, PlayerSlot(1), UnitType::Warrior);
+        let action = GameAction::FortifyUnit {
+            unit_id: warrior.id,
+        };
+        assert!(matches!(
+            engine.preview(PlayerSlot(1), &action),
+            Err(ReplayError::NotPlayerTurn)
+        ));
+        assert!(!engine.state.units[&warrior.id].fortified);
     }
-}
 
-/// Errors from replay operations.
-#[derive(Clone, Debug)]
-pub enum ReplayError {
-    EmptyEventChain,
-    MissingCreateGame,
-    InvalidSettings,
-    InvalidEventChain,
-    NotPlayerTurn,
-    UnitNotFound,
-    CityNotFound,
-    NotOwner,
-    InvalidPosition,
-    GameError(GameError),
-    MissingRandomnessProof,
-    InvalidRandomnessProof(String),
-}
+    // ==== Snapshot Tests ====
 
-impl std::fmt::Display for ReplayError {
-    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
-        match self {
-            ReplayError::EmptyEventChain => write!(f, "Event chain is empty"),
-            ReplayError::MissingCreateGame => write!(f, "First event must be CreateGame"),
-            ReplayError::InvalidSettings => write!(f, "Invalid game settings"),
-            ReplayError::InvalidEventChain => write!(f, "Invalid event chain"),
-            ReplayError::NotPlayerTurn => write!(f, "Not this player's turn"),
-            ReplayError::UnitNotFound => write!(f, "Unit not found"),
-            ReplayError::CityNotFound => write!(f, "City not found"),
-            ReplayError::NotOwner => write!(f, "Player doesn't own this"),
-            ReplayError::InvalidPosition => write!(f, "Invalid position"),
-            ReplayError::GameError(e) => write!(f, "Game error: {}", e),
-            ReplayError::MissingRandomnessProof => {
-                write!(f, "Missing randomness proof for action that requires it")
-            }
-            ReplayError::InvalidRandomnessProof(msg) => {
-                write!(f, "Invalid randomness proof: {}", msg)
+    /// Chain a duel with several rounds of end turns and a snapshot.
+    fn duel_events_with_snapshot() -> (Vec<GameEvent>, GameEngine) {
+        let settings = GameSettings::new("Test".to_string());
+        let seed = [42u8; 32];
+        let mut actions = vec![
+            (
+                0,
+                GameAction::CreateGame {
+                    settings_json: serde_json::to_string(&settings).unwrap(),
+                    seed,
+                },
+            ),
+            (
+                0,
+                GameAction::JoinGame {
+                    player_name: "P0".to_string(),
+                    civilization_id: "rome".to_string(),
+                },
+            ),
+            (
+                1,
+                GameAction::JoinGame {
+                    player_name: "P1".to_string(),
+                    civilization_id: "egypt".to_string(),
+                },
+            ),
+            (0, GameAction::StartGame),
+        ];
+
+        let mut engine = GameEngine::new(settings, seed);
+        engine.config.snapshot_interval = 2;
+        for (player, action) in &actions[1..] {
+            engine.apply_action(PlayerSlot(*player), action).unwrap();
+        }
+        for _ in 0..3 {
+            for player in 0..2 {
+                engine
+                    .apply_action(PlayerSlot(player), &GameAction::EndTurn)
+                    .unwrap();
+                actions.push((player, GameAction::EndTurn));
+                if let Some(snapshot) = engine.take_snapshot() {
+                    actions.push((player, GameAction::Snapshot { snapshot }));
+                }
             }
         }
+
+        let events = actions
+            .into_iter()
+            .enumerate()
+            .map(|(i, (player, action))| {
+                let prev = i.checked_sub(1).map(|p| format!("evt{}", p));
+                let mut event = GameEvent::new(
+                    GameId::new("game"),
+                    PlayerSlot(player),
+                    prev,
+                    0,
+                    i as u32,
+                    action,
+                );
+                event.id = format!("evt{}", i);
+                event
+            })
+            .collect();
+        (events, engine)
     }
-}
 
-impl std::error::Error for ReplayError {}
+    #[test]
+    fn test_snapshot_taken_when_interval_turn_starts() {
+        let mut engine = started_duel();
+        engine.config.snapshot_interval = 2;
+        engine
+            .apply_action(PlayerSlot(0), &GameAction::EndTurn)
+            .unwrap();
+        assert!(engine.take_snapshot().is_none());
 
-// Helper for hex encoding
-mod hex {
-    pub fn encode(bytes: &[u8]) -> String {
-        bytes.iter().map(|b| format!("{:02x}", b)).collect()
+        engine
+            .apply_action(PlayerSlot(1), &GameAction::EndTurn)
+            .unwrap();
+        let snapshot = engine.take_snapshot().unwrap();
+        assert_eq!(snapshot.turn, 2);
+        assert_eq!(snapshot.state_hash, audit::state_hash(&engine.state));
+        assert!(engine.take_snapshot().is_none());
     }
-}
 
-#[cfg(test)]
-mod tests {
-    use super::*;
+    #[test]
+    fn test_replay_starts_from_latest_snapshot() {
+        let (events, live) = duel_events_with_snapshot();
+        let expected = audit::state_hash(&live.state);
+
+        let full = GameEngine::from_events(&events).unwrap();
+        assert_eq!(audit::state_hash(&full.state), expected);
+
+        let compacted = snapshot::compact_events(&events);
+        assert!(compacted.len() < events.len());
+        assert!(matches!(compacted[0].action, GameAction::Snapshot { .. }));
+        let fast = GameEngine::from_events(compacted).unwrap();
+        assert_eq!(audit::state_hash(&fast.state), expected);
+    }
 
     #[test]
-    fn test_engine_creation() {
-        let settings = GameSettings::new("Test Game".to_string());
-        let engine = GameEngine::new(settings, [0u8; 32]);
+    fn test_replay_reports_progress() {
+        let (events, live) = duel_events_with_snapshot();
+        let mut reports = Vec::new();
+        let replayed = GameEngine::from_events_with_progress(
+            snapshot::compact_events(&events),
+            ReplayConfig::default(),
+            &mut |p| reports.push(p),
+        )
+        .unwrap();
+        assert_eq!(
+            audit::state_hash(&replayed.state),
+            audit::state_hash(&live.state)
+        );
+        assert!(reports.iter().all(|p| p.phase == "replay"));
+        assert!(reports.windows(2).all(|w| w[0].percent < w[1].percent));
+        assert_eq!(reports.last().map(|p| p.percent), Some(100));
+    }
 
-        assert_eq!(engine.state.phase, GamePhase::Setup);
+    #[test]
+    fn test_start_game_reports_map_progress() {
+        let mut engine = GameEngine::new(GameSettings::new("Test".to_string()), [42u8; 32]);
+        for id in 0..2 {
+            engine
+                .apply_action(
+                    PlayerSlot(id),
+                    &GameAction::JoinGame {
+                        player_name: format!("P{}", id),
+                        civilization_id: "rome".to_string(),
+                    },
+                )
+                .unwrap();
+        }
+        let reports = std::sync::Arc::new(std::sync::Mutex::new(Vec::new()));
+        let sink = reports.clone();
+        engine.set_progress_callback(Some(Box::new(move |p| sink.lock().unwrap().push(p))));
+        engine
+            .apply_action(PlayerSlot(0), &GameAction::StartGame)
+            .unwrap();
+        let reports = reports.lock().unwrap();
+        assert_eq!(reports.last().map(|p| p.percent), Some(100));
     }
 
     #[test]
-    fn test_player_join() {
-        let settings = GameSettings::new("Test".to_string());
-        let mut engine = GameEngine::new(settings, [0u8; 32]);
+    fn test_replay_rejects_mismatched_snapshot() {
+        let (mut events, _) = duel_events_with_snapshot();
+        let (index, _) = snapshot::latest_snapshot(&events).unwrap();
+        if let GameAction::Snapshot { snapshot } = &mut events[index].action {
+            snapshot.state_hash ^= 1;
+        }
 
-        let result = engine.apply_action(
-            0,
-            &GameAction::JoinGame {
-                player_name: "Player1".to_string(),
-                civilization_id: "rome".to_string(),
-            },
-        );
+        // The snapshot can't be restored, and full replay catches the bad hash
+        assert!(matches!(
+            GameEngine::from_events(&events),
+            Err(ReplayError::InvalidSnapshot(
+                SnapshotError::HashMismatch { .. }
+            ))
+        ));
+    }
 
-        assert!(result.is_ok());
-        assert_eq!(engine.state.players.len(), 1);
+    /// Replay every event from the start, checking each snapshot.
+    fn full_replay(events: &[GameEvent]) -> Result<GameEngine, ReplayError> {
+        let mut engine = GameEngine::from_events(&events[..1])?;
+        for event in &events[1..] {
+            engine.apply_event(event)?;
+        }
+        Ok(engine)
     }
 
     #[test]
-    fn test_game_start() {
+    fn test_snapshot_commits_to_events_since_previous() {
+        let (events, _) = duel_events_with_snapshot();
+        assert!(full_replay(&events).is_ok());
+
+        // A light client holding only the snapshot can check an event
+        let (index, snapshot) = snapshot::latest_snapshot(&events).unwrap();
+        let event = &events[index - 1];
+        let (proven_by, proof) = crate::merkle::prove_event(&events, &event.id).unwrap();
+        assert_eq!(proven_by, index);
+        assert!(snapshot.contains(event, &proof));
+        assert!(!snapshot.contains(&events[index - 2], &proof));
+    }
+
+    #[test]
+    fn test_replay_rejects_mismatched_events_root() {
+        let (mut events, _) = duel_events_with_snapshot();
+        let (index, _) = snapshot::latest_snapshot(&events).unwrap();
+        if let GameAction::Snapshot { snapshot } = &mut events[index].action {
+            snapshot.events_root.as_mut().unwrap().count += 1;
+        }
+
+        assert!(matches!(
+            full_replay(&events),
+            Err(ReplayError::InvalidSnapshot(
+                SnapshotError::EventsRootMismatch
+            ))
+        ));
+    }
+
+    // ==== Audit Tests ====
+
+    fn audited_duel() -> GameEngine {
         let mut settings = GameSettings::new("Test".to_string());
         settings.map_size = crate::types::MapSize::Duel;
-
         let mut engine = GameEngine::new(settings, [42u8; 32]);
-
-        // Add players
+        engine.enable_audit();
+        for (id, civ) in [(0, "rome"), (1, "egypt")] {
+            engine
+                .apply_action(
+                    PlayerSlot(id),
+                    &GameAction::JoinGame {
+                        player_name: format!("P{}", id),
+                        civilization_id: civ.to_string(),
+                    },
+                )
+                .unwrap();
+        }
         engine
-            .apply_action(
-                0,
-                &GameAction::JoinGame {
-                    player_name: "P1".to_string(),
-                    civilization_id: "rome".to_string(),
-                },
-            )
+            .apply_action(PlayerSlot(0), &GameAction::StartGame)
             .unwrap();
         engine
-            .apply_action(
-                1,
-                &GameAction::JoinGame {
-                    player_name: "P2".to_string(),
-                    civilization_id: "egypt".to_string(),
-                },
-            )
-            .unwrap();
-
-        // Start game
-        let result = engine.apply_action(0, &GameAction::StartGame);
-        assert!(result.is_ok());
-
-        assert_eq!(engine.state.phase, GamePhase::Playing);
-        assert_eq!(engine.state.turn, 1);
-        assert!(!engine.state.units.is_empty()); // Should have starting units
     }
 
     #[test]
-    fn test_is_valid_action() {
-        let settings = GameSettings::new("Test".to_string());
-        let engine = GameEngine::new(settings, [0u8; 32]);
-
-        // EndTurn should be valid for current player
-        assert!(engine.is_valid_action(0, &GameAction::EndTurn));
+    fn test_audit_disabled_by_default() {
+        let engine = started_duel();
+        assert!(!engine.is_auditing());
+        assert!(engine.audit_log().is_none());
     }
 
     #[test]
-    fn test_replay_config_strict_mode() {
-        let config = ReplayConfig {
-            strict_randomness_validation: true,
-            allow_deterministic_randomness: false,
-        };
+    fn test_audit_records_each_action() {
+        let mut engine = audited_duel();
+        engine
+            .apply_action(PlayerSlot(0), &GameAction::EndTurn)
+            .unwrap();
+        // Rejected actions are recorded too.
+        assert!(engine
+            .apply_action(PlayerSlot(0), &GameAction::EndTurn)
+            .is_err());
 
-        let settings = GameSettings::new("Test".to_string());
-        let engine = GameEngine::with_config(settings, [0u8; 32], config);
+        let log = engine.audit_log().unwrap();
+        assert_eq!(log.len(), 5);
+        assert!(log.entries()[3].accepted);
+        assert!(!log.entries()[4].accepted);
+        assert_eq!(
+            log.last_hash(),
+            Some(crate::audit::state_hash(&engine.state))
+        );
+    }
 
-        assert!(engine.config.strict_randomness_validation);
-        assert!(!engine.config.allow_deterministic_randomness);
+    #[test]
+    fn test_audit_logs_match_for_identical_replays() {
+        let mut a = audited_duel();
+        let mut b = audited_duel();
+        for engine in [&mut a, &mut b] {
+            engine
+                .apply_action(PlayerSlot(0), &GameAction::EndTurn)
+                .unwrap();
+            engine
+                .apply_action(PlayerSlot(1), &GameAction::EndTurn)
+                .unwrap();
+        }
+        let log_a = a.disable_audit().unwrap();
+        let log_b = b.disable_audit().unwrap();
+        assert!(log_a.compare(&log_b).is_none());
+        assert!(!a.is_auditing());
     }
 
     #[test]
-    fn test_randomness_proof_validation() {
-        let settings = GameSettings::new("Test".to_string());
-        let engine = GameEngine::new(settings, [42u8; 32]);
+    fn test_audit_pinpoints_desync_origin() {
+        let mut a = audited_duel();
+        let mut b = audited_duel();
+        a.apply_action(PlayerSlot(0), &GameAction::EndTurn).unwrap();
+        b.apply_action(PlayerSlot(0), &GameAction::EndTurn).unwrap();
 
-        // Create an event with a valid deterministic proof
-        let proof = RandomnessProof {
-            mint_keyset_id: "deterministic".to_string(),
-            blinded_message: vec![],
-            blinded_signature: vec![],
-            signature: [42u8; 32].to_vec(),
-            random_bytes: [0u8; 32],
-            context: "test".to_string(),
-            timestamp: 0,
-        };
+        // Corrupt one client's state outside the action stream.
+        let unit_id = *b
+            .state
+            .units
+            .iter()
+            .find(|(_, u)| u.owner == PlayerSlot(1))
+            .map(|(id, _)| id)
+            .unwrap();
+        b.state.units.get_mut(&unit_id).unwrap().health = 50;
 
-        let event = GameEvent::with_randomness(
-            "game1".to_string(),
-            0,
-            None,
-            1,
-            1,
-            GameAction::AttackUnit {
-                attacker_id: 1,
-                defender_id: 2,
-                random: proof.to_f32(),
-            },
-            proof,
-        );
+        let fortify = GameAction::FortifyUnit { unit_id };
+        a.apply_action(PlayerSlot(1), &fortify).unwrap();
+        b.apply_action(PlayerSlot(1), &fortify).unwrap();
 
-        // Validation should pass (not strict mode)
-        assert!(engine.validate_randomness_proof(&event).is_ok());
+        let divergence = a
+            .audit_log()
+            .unwrap()
+            .compare(b.audit_log().unwrap())
+            .unwrap();
+        assert_eq!(divergence.index, 4);
+        assert_eq!(
+            divergence.kind,
+            crate::audit::DivergenceKind::Input {
+                name: format!("unit.{}.health", unit_id)
+            }
+        );
     }
 }
@@ -0,0 +1,411 @@
+//! Research queues and per-turn research progress.
+//!
+//! Each player researches one technology at a time and may queue more
+//! behind it. Queuing a distant tech fills the queue with its missing
+//! prerequisites, in an order where each entry can be researched once the
+//! ones before it are done. When a player's turn ends their science is
+//! added to the current tech; once it is paid for, the next queued tech
+//! becomes current and any surplus science carries over.
+//!
+//! # Research Agreements
+//!
+//! Two players signing a research agreement each pay
+//! [`research_agreement_cost`] gold up front. After
+//! [`RESEARCH_AGREEMENT_TURNS`] turns of peace both receive
+//! [`research_agreement_science`] towards their current research. Declaring
+//! war on a partner cancels the agreement: the declaring player forfeits
+//! their gold and loses standing with the partner, who is refunded.
+
+use crate::game_state::{ActiveTreaty, GameState, TreatyType, TREATY_BREAK_SCORE_PENALTY};
+use crate::player::Player;
+use crate::settings::GameSettings;
+use crate::technology::TechTree;
+use crate::types::{PlayerSlot, TechId};
+
+/// Turns a research agreement runs before it pays off.
+pub const RESEARCH_AGREEMENT_TURNS: u32 = 20;
+
+/// Gold each party pays for a research agreement at normal speed.
+pub const RESEARCH_AGREEMENT_GOLD: i64 = 100;
+
+/// Science each party receives per gold paid into a research agreement.
+pub const RESEARCH_AGREEMENT_SCIENCE_PER_GOLD: u32 = 2;
+
+/// Gold each party pays to sign a research agreement at the game's speed.
+pub fn research_agreement_cost(settings: &GameSettings) -> i32 {
+    settings
+        .research_multiplier()
+        .mul_int(RESEARCH_AGREEMENT_GOLD)
+        .ceil() as i32
+}
+
+/// Science each party receives when a research agreement pays off.
+pub fn research_agreement_science(settings: &GameSettings) -> u32 {
+    research_agreement_cost(settings) as u32 * RESEARCH_AGREEMENT_SCIENCE_PER_GOLD
+}
+
+/// Sign a research agreement, taking the cost from both parties.
+///
+/// Returns false if the agreement can't be signed: either party can't
+/// afford it or their relationship doesn't allow it.
+pub fn sign_research_agreement(state: &mut GameState, a: PlayerSlot, b: PlayerSlot) -> bool {
+    let cost = research_agreement_cost(&state.settings);
+    let affordable = [a, b]
+        .iter()
+        .all(|id| state.get_player(*id).is_some_and(|p| p.gold >= cost));
+    if !affordable
+        || !state
+            .diplomacy
+            .propose_treaty(a, b, TreatyType::ResearchAgreement, state.turn)
+    {
+        return false;
+    }
+
+    // The agreement runs for a fixed term instead of until broken
+    if let Some(rel) = state.diplomacy.get_mut(a, b) {
+        rel.remove_treaty(TreatyType::ResearchAgreement);
+        rel.add_treaty(ActiveTreaty {
+            treaty_type: TreatyType::ResearchAgreement,
+            turn_signed: state.turn,
+            duration: Some(RESEARCH_AGREEMENT_TURNS),
+        });
+    }
+    for id in [a, b] {
+        if let Some(player) = state.get_player_mut(id) {
+            player.gold -= cost;
+        }
+    }
+    true
+}
+
+/// Cancel a research agreement because `declarer` declared war on
+/// `target`.
+///
+/// The target is refunded and the declarer loses standing with them.
+/// Returns false if the players had no agreement.
+pub fn break_research_agreement(
+    state: &mut GameState,
+    declarer: PlayerSlot,
+    target: PlayerSlot,
+) -> bool {
+    let cost = research_agreement_cost(&state.settings);
+    let Some(rel) = state.diplomacy.get_mut(declarer, target) else {
+        return false;
+    };
+    if !rel.remove_treaty(TreatyType::ResearchAgreement) {
+        return false;
+    }
+    rel.relationship_score = (rel.relationship_score + TREATY_BREAK_SCORE_PENALTY).clamp(-100, 100);
+    if let Some(player) = state.get_player_mut(target) {
+        player.gold += cost;
+    }
+    true
+}
+
+/// Pay off a player's research agreements that have run their term.
+///
+/// Both parties receive the science. Returns the partner of each completed
+/// agreement, in player order.
+pub fn complete_research_agreements(
+    state: &mut GameState,
+    player_id: PlayerSlot,
+) -> Vec<PlayerSlot> {
+    let turn = state.turn;
+    let mut partners: Vec<PlayerSlot> = state
+        .players
+        .iter()
+        .map(|p| p.id)
+        .filter(|&other| other != player_id)
+        .filter(|&other| {
+            state.diplomacy.get(player_id, other).is_some_and(|rel| {
+                rel.treaties.iter().any(|t| {
+                    t.treaty_type == TreatyType::ResearchAgreement
+                        && t.duration.is_some_and(|d| t.turn_signed + d <= turn)
+                })
+            })
+        })
+        .collect();
+    partners.sort_unstable();
+
+    let science = research_agreement_science(&state.settings);
+    for &other in &partners {
+        if let Some(rel) = state.diplomacy.get_mut(player_id, other) {
+            rel.remove_treaty(TreatyType::ResearchAgreement);
+        }
+        for id in [player_id, other] {
+            if let Some(player) = state.get_player_mut(id) {
+                player.research_progress += science;
+            }
+        }
+    }
+    partners
+}
+
+/// Research cost of a technology at the game's speed.
+pub fn research_cost(settings: &GameSettings, tree: &TechTree, tech_id: &TechId) -> Option<u32> {
+    let tech = tree.get(tech_id)?;
+    let cost = settings
+        .research_multiplier()
+        .mul_int(tech.cost as i64)
+        .ceil();
+    Some(cost.max(1) as u32)
+}
+
+/// Point a player's research at a target, queuing its prerequisites.
+///
+/// The current tech is kept, with its progress, if it lies on the path to
+/// the target; otherwise the first tech on the path replaces it.
+pub fn queue_research(player: &mut Player, tree: &TechTree, target: &TechId) {
+    let mut path = tree.research_path(target, &player.technologies);
+    if path.is_empty() {
+        return;
+    }
+
+    let current = player
+        .current_research
+        .as_ref()
+        .and_then(|current| path.iter().position(|tech_id| tech_id == current));
+    match current {
+        Some(index) => {
+            path.remove(index);
+        }
+        None => {
+            player.current_research = Some(path.remove(0));
+            player.research_progress = 0;
+        }
+    }
+    player.research_queue = path;
+}
+
+/// Add a player's science to their current research.
+///
+/// Returns the technologies completed this turn, in order.
+pub fn progress_research(state: &mut GameState, player_id: PlayerSlot) -> Vec<TechId> {
+    let tree = TechTree::new();
+    let science = state.player_yields(player_id).science.max(0);
+    let settings = &state.settings;
+    let Some(player) = state.players.get_mut(player_id.index()) else {
+        return Vec::new();
+    };
+    player.science_per_turn = science;
+    player.research_progress += science as u32;
+
+    let mut completed = Vec::new();
+    while let Some(tech_id) = next_research(player) {
+        let Some(cost) = research_cost(settings, &tree, &tech_id) else {
+            continue;
+        };
+        if player.research_progress < cost {
+            player.current_research = Some(tech_id);
+            return completed;
+        }
+        player.research_progress -= cost;
+        player.add_tech(tech_id.clone());
+        completed.push(tech_id);
+    }
+
+    // Science with nothing to research is lost
+    player.research_progress = 0;
+    completed
+}
+
+/// Take the tech to research next: the current one, or else the first
+/// queued one. Techs gained elsewhere, such as by trade, are skipped.
+fn next_research(player: &mut Player) -> Option<TechId> {
+    if let Some(tech_id) = player.current_research.take() {
+        if !player.has_tech(&tech_id) {
+            return Some(tech_id);
+        }
+    }
+    while !player.research_queue.is_empty() {
+        let tech_id = player.research_queue.remove(0);
+        if !player.has_tech(&tech_id) {
+            return Some(tech_id);
+        }
+    }
+    None
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::player::Civilization;
+    use crate::types::{GameId, Npub};
+
+    fn create_state() -> GameState {
+        let mut state = GameState::new(
+            GameId::new("test"),
+            GameSettings::new("Test".to_string()),
+            [0; 32],
+        );
+        for id in 0..2 {
+            state
+                .add_player(Player::new(
+                    PlayerSlot(id),
+                    Npub(format!("pk{}", id)),
+                    format!("P{}", id),
+                    Civilization::default(),
+                ))
+                .unwrap();
+        }
+        state.start().unwrap();
+        state
+    }
+
+    /// Two players on good enough terms to sign a research agreement.
+    fn friendly_state() -> GameState {
+        let mut state = create_state();
+        state.diplomacy.modify_relationship_score(
+            PlayerSlot(0),
+            PlayerSlot(1),
+            crate::game_state::FRIENDLY_TREATY_THRESHOLD,
+        );
+        for player in &mut state.players {
+            player.gold = 500;
+        }
+        state
+    }
+
+    fn cost(state: &GameState, tech_id: &str) -> u32 {
+        research_cost(&state.settings, &TechTree::new(), &tech_id.to_string()).unwrap()
+    }
+
+    // ==================== Queue Tests ====================
+
+    #[test]
+    fn test_queue_fills_with_prerequisites() {
+        let tree = TechTree::new();
+        let mut player = create_state().players.remove(0);
+
+        queue_research(&mut player, &tree, &"writing".to_string());
+        assert_eq!(player.current_research.as_deref(), Some("agriculture"));
+        assert_eq!(player.research_queue, ["pottery", "writing"]);
+    }
+
+    #[test]
+    fn test_queue_keeps_current_research_on_the_path() {
+        let tree = TechTree::new();
+        let mut player = create_state().players.remove(0);
+        player.add_tech("agriculture".to_string());
+        player.current_research = Some("pottery".to_string());
+        player.research_progress = 10;
+
+        queue_research(&mut player, &tree, &"writing".to_string());
+        assert_eq!(player.current_research.as_deref(), Some("pottery"));
+        assert_eq!(player.research_progress, 10);
+        assert_eq!(player.research_queue, ["writing"]);
+
+        // Beelining elsewhere switches research
+        queue_research(&mut player, &tree, &"mining".to_string());
+        assert_eq!(player.current_research.as_deref(), Some("mining"));
+        assert_eq!(player.research_progress, 0);
+        assert!(player.research_queue.is_empty());
+    }
+
+    // ==================== Progress Tests ====================
+
+    #[test]
+    fn test_progress_advances_queue_and_carries_surplus() {
+        let mut state = create_state();
+        let agriculture = cost(&state, "agriculture");
+        let pottery = cost(&state, "pottery");
+        let player = &mut state.players[0];
+        queue_research(player, &TechTree::new(), &"writing".to_string());
+        player.research_progress = agriculture + pottery - 1;
+
+        let completed = progress_research(&mut state, PlayerSlot(0));
+        assert_eq!(completed, ["agriculture"]);
+
+        let player = &state.players[0];
+        assert!(player.has_tech(&"agriculture".to_string()));
+        assert_eq!(player.current_research.as_deref(), Some("pottery"));
+        assert_eq!(player.research_progress, pottery - 1);
+        assert_eq!(player.research_queue, ["writing"]);
+    }
+
+    #[test]
+    fn test_progress_skips_techs_gained_elsewhere() {
+        let mut state = create_state();
+        let player = &mut state.players[0];
+        queue_research(player, &TechTree::new(), &"writing".to_string());
+        // Traded for the first two techs in the queue
+        player.add_tech("agriculture".to_string());
+        player.add_tech("pottery".to_string());
+
+        assert!(progress_research(&mut state, PlayerSlot(0)).is_empty());
+        let player = &state.players[0];
+        assert_eq!(player.current_research.as_deref(), Some("writing"));
+        assert!(player.research_queue.is_empty());
+    }
+
+    // ==================== Research Agreement Tests ====================
+
+    #[test]
+    fn test_research_agreement_pays_off_for_both() {
+        let mut state = friendly_state();
+        let cost = research_agreement_cost(&state.settings);
+        let science = research_agreement_science(&state.settings);
+
+        assert!(sign_research_agreement(
+            &mut state,
+            PlayerSlot(0),
+            PlayerSlot(1)
+        ));
+        assert_eq!(state.players[0].gold, 500 - cost);
+        assert_eq!(state.players[1].gold, 500 - cost);
+
+        // Nothing until the term runs out
+        assert!(complete_research_agreements(&mut state, PlayerSlot(0)).is_empty());
+        state.turn += RESEARCH_AGREEMENT_TURNS;
+        assert_eq!(
+            complete_research_agreements(&mut state, PlayerSlot(0)),
+            [PlayerSlot(1)]
+        );
+        assert_eq!(state.players[0].research_progress, science);
+        assert_eq!(state.players[1].research_progress, science);
+        assert!(!state.diplomacy.has_treaty(
+            PlayerSlot(0),
+            PlayerSlot(1),
+            TreatyType::ResearchAgreement
+        ));
+    }
+
+    #[test]
+    fn test_research_agreement_needs_gold_from_both() {
+        let mut state = friendly_state();
+        state.players[1].gold = 0;
+        assert!(!sign_research_agreement(
+            &mut state,
+            PlayerSlot(0),
+            PlayerSlot(1)
+        ));
+        assert_eq!(state.players[0].gold, 500);
+    }
+
+    #[test]
+    fn test_war_breaks_research_agreement() {
+        let mut state = friendly_state();
+        let cost = research_agreement_cost(&state.settings);
+        sign_research_agreement(&mut state, PlayerSlot(0), PlayerSlot(1));
+        let score = state
+            .diplomacy
+            .get_relationship_score(PlayerSlot(0), PlayerSlot(1));
+
+        assert!(break_research_agreement(
+            &mut state,
+            PlayerSlot(0),
+            PlayerSlot(1)
+        ));
+        assert_eq!(state.players[0].gold, 500 - cost);
+        assert_eq!(state.players[1].gold, 500);
+        assert_eq!(
+            state
+                .diplomacy
+                .get_relationship_score(PlayerSlot(0), PlayerSlot(1)),
+            score + TREATY_BREAK_SCORE_PENALTY
+        );
+
+        state.turn += RESEARCH_AGREEMENT_TURNS;
+        assert!(complete_research_agreements(&mut state, PlayerSlot(0)).is_empty());
+    }
+}
@@ -0,0 +1,327 @@
+//! Road construction and the road network.
+//!
+//! Workers build roads over several turns. Once a city is linked to its
+//! owner's capital by an unbroken chain of roads it earns a trade bonus.
+//! City centers always count as part of the network.
+
+use crate::city::City;
+use crate::fixed::deterministic;
+use crate::game_state::GameState;
+use crate::hex::HexCoord;
+use crate::map::Tile;
+use crate::technology::TechTree;
+use crate::terrain::Road;
+use crate::types::{CityId, PlayerSlot, TechId, UnitId};
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+use std::collections::{HashSet, VecDeque};
+use ts_rs::TS;
+
+/// Ability unlocked by technology that allows building roads.
+pub const ROADS_ABILITY: &str = "roads";
+
+/// Ability unlocked by technology that allows upgrading roads to railroads.
+pub const RAILROADS_ABILITY: &str = "railroads";
+
+/// Gold every city connected to its capital earns before population.
+pub const CONNECTION_BASE_GOLD: i32 = 1;
+
+/// Road construction in progress on a worker's tile.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct RoadWork {
+    /// Road being built.
+    pub road: Road,
+    /// Turns of work left.
+    pub turns_remaining: u32,
+}
+
+deterministic!(struct RoadWork {
+    road,
+    turns_remaining
+});
+
+impl RoadWork {
+    /// Start building a road.
+    pub fn new(road: Road) -> Self {
+        Self {
+            road,
+            turns_remaining: road.build_turns(),
+        }
+    }
+}
+
+/// Why a road can't be built on a tile.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize, Deserialize, JsonSchema, TS)]
+pub enum RoadError {
+    /// The tile is water or impassable.
+    InvalidTerrain,
+    /// The tile already has the best road available.
+    AlreadyBuilt,
+    /// The player lacks the technology for this road.
+    MissingTechnology,
+}
+
+impl std::fmt::Display for RoadError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            RoadError::InvalidTerrain => write!(f, "Roads cannot be built on this tile"),
+            RoadError::AlreadyBuilt => write!(f, "Tile already has a road"),
+            RoadError::MissingTechnology => write!(f, "Missing technology for road"),
+        }
+    }
+}
+
+impl std::error::Error for RoadError {}
+
+/// Pick the road a player can build next on a tile.
+///
+/// An empty tile gets a road; an existing road can be upgraded to a
+/// railroad once the player has the technology.
+pub fn road_to_build(tile: &Tile, researched: &HashSet<TechId>) -> Result<Road, RoadError> {
+    if tile.terrain.is_water() || !tile.is_passable_land() {
+        return Err(RoadError::InvalidTerrain);
+    }
+
+    let tree = TechTree::new();
+    let (road, ability) = match tile.road {
+        None => (Road::Road, ROADS_ABILITY),
+        Some(Road::Road) => (Road::Railroad, RAILROADS_ABILITY),
+        Some(Road::Railroad) => return Err(RoadError::AlreadyBuilt),
+    };
+    if !tree.has_ability(researched, ability) {
+        return Err(if tile.road.is_some() {
+            RoadError::AlreadyBuilt
+        } else {
+            RoadError::MissingTechnology
+        });
+    }
+    Ok(road)
+}
+
+/// A road finished by a worker.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct RoadBuilt {
+    pub unit_id: UnitId,
+    pub coord: HexCoord,
+    pub road: Road,
+}
+
+/// Advance every road a player's workers are building by one turn.
+///
+/// Workers are processed in ID order. Finished roads are placed on the map
+/// and the worker is freed for new orders.
+pub fn progress_road_work(state: &mut GameState, player_id: PlayerSlot) -> Vec<RoadBuilt> {
+    let mut unit_ids: Vec<UnitId> = state
+        .units
+        .values()
+        .filter(|u| u.owner == player_id && u.road_work.is_some())
+        .map(|u| u.id)
+        .collect();
+    unit_ids.sort_unstable();
+
+    let mut built = Vec::new();
+    for unit_id in unit_ids {
+        let Some(unit) = state.units.get_mut(&unit_id) else {
+            continue;
+        };
+        let Some(work) = unit.road_work.as_mut() else {
+            continue;
+        };
+        work.turns_remaining = work.turns_remaining.saturating_sub(1);
+        if work.turns_remaining > 0 {
+            continue;
+        }
+
+        let road = work.road;
+        let coord = unit.position;
+        unit.road_work = None;
+        if let Some(tile) = state.map.get_mut(&coord) {
+            tile.road = Some(road);
+        }
+        built.push(RoadBuilt {
+            unit_id,
+            coord,
+            road,
+        });
+    }
+    built
+}
+
+/// Get every tile reachable from a start tile along roads and cities.
+pub fn road_network(state: &GameState, start: HexCoord) -> HashSet<HexCoord> {
+    let cities: HashSet<HexCoord> = state.cities.values().map(|c| c.position).collect();
+    let on_network = |coord: &HexCoord| {
+        cities.contains(coord) || state.map.get(coord).is_some_and(|t| t.road.is_some())
+    };
+
+    let mut network = HashSet::new();
+    if !on_network(&start) {
+        return network;
+    }
+    let mut frontier = VecDeque::from([start]);
+    network.insert(start);
+    while let Some(coord) = frontier.pop_front() {
+        for neighbor in coord.neighbors() {
+            if !network.contains(&neighbor) && on_network(&neighbor) {
+                network.insert(neighbor);
+                frontier.push_back(neighbor);
+            }
+        }
+    }
+    network
+}
+
+/// Get a player's cities linked to their capital by road, sorted by ID.
+///
+/// The capital itself is not included.
+pub fn connected_cities(state: &GameState, player_id: PlayerSlot) -> Vec<CityId> {
+    let Some(capital) = state
+        .cities
+        .values()
+        .find(|c| c.owner == player_id && c.is_capital)
+    else {
+        return Vec::new();
+    };
+    let network = road_network(state, capital.position);
+
+    let mut connected: Vec<CityId> = state
+        .cities
+        .values()
+        .filter(|c| c.owner == player_id && !c.is_capital && network.contains(&c.position))
+        .map(|c| c.id)
+        .collect();
+    connected.sort_unstable();
+    connected
+}
+
+/// Check if a city is linked to its owner's capital by road.
+pub fn is_connected_to_capital(state: &GameState, city: &City) -> bool {
+    !city.is_capital && connected_cities(state, city.owner).contains(&city.id)
+}
+
+/// Gold a city earns from trade with its capital.
+pub fn connection_gold(city: &City) -> i32 {
+    CONNECTION_BASE_GOLD + city.population as i32 / 2
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::map::Map;
+    use crate::settings::GameSettings;
+    use crate::terrain::{Feature, Terrain};
+    use crate::types::GameId;
+
+    fn state_with_cities() -> GameState {
+        let mut state = GameState::new(
+            GameId::new("test"),
+            GameSettings::new("Test".to_string()),
+            [0u8; 32],
+        );
+        state.map = Map::filled(10, 10, Terrain::Plains);
+        state.cities.insert(
+            CityId(1),
+            City::new(
+                CityId(1),
+                PlayerSlot(0),
+                "Rome".to_string(),
+                HexCoord::new(2, 2),
+                true,
+            ),
+        );
+        state.cities.insert(
+            CityId(2),
+            City::new(
+                CityId(2),
+                PlayerSlot(0),
+                "Antium".to_string(),
+                HexCoord::new(5, 2),
+                false,
+            ),
+        );
+        state
+    }
+
+    fn researched(ids: &[&str]) -> HashSet<TechId> {
+        ids.iter().map(|id| id.to_string()).collect()
+    }
+
+    // ==================== Construction Tests ====================
+
+    #[test]
+    fn test_road_requires_technology() {
+        let tile = Tile::new(HexCoord::new(0, 0), Terrain::Plains);
+        assert_eq!(
+            road_to_build(&tile, &HashSet::new()),
+            Err(RoadError::MissingTechnology)
+        );
+        assert_eq!(
+            road_to_build(&tile, &researched(&["the_wheel"])),
+            Ok(Road::Road)
+        );
+    }
+
+    #[test]
+    fn test_road_upgrades_to_railroad() {
+        let mut tile = Tile::new(HexCoord::new(0, 0), Terrain::Plains);
+        tile.road = Some(Road::Road);
+        assert_eq!(
+            road_to_build(&tile, &researched(&["the_wheel"])),
+            Err(RoadError::AlreadyBuilt)
+        );
+        assert_eq!(
+            road_to_build(&tile, &researched(&["steam_power"])),
+            Ok(Road::Railroad)
+        );
+    }
+
+    #[test]
+    fn test_no_roads_on_water_or_mountains() {
+        let techs = researched(&["the_wheel"]);
+        let water = Tile::new(HexCoord::new(0, 0), Terrain::Ocean);
+        assert_eq!(
+            road_to_build(&water, &techs),
+            Err(RoadError::InvalidTerrain)
+        );
+
+        let mut mountain = Tile::new(HexCoord::new(0, 0), Terrain::Plains);
+        mountain.feature = Some(Feature::Mountains);
+        assert_eq!(
+            road_to_build(&mountain, &techs),
+            Err(RoadError::InvalidTerrain)
+        );
+    }
+
+    // ==================== Network Tests ====================
+
+    #[test]
+    fn test_city_connects_along_road() {
+        let mut state = state_with_cities();
+        assert!(connected_cities(&state, PlayerSlot(0)).is_empty());
+
+        for q in 3..5 {
+            state.map.get_mut(&HexCoord::new(q, 2)).unwrap().road = Some(Road::Road);
+        }
+        assert_eq!(connected_cities(&state, PlayerSlot(0)), vec![CityId(2)]);
+        assert!(is_connected_to_capital(&state, &state.cities[&CityId(2)]));
+        assert!(!is_connected_to_capital(&state, &state.cities[&CityId(1)]));
+    }
+
+    #[test]
+    fn test_connection_adds_gold() {
+        let mut state = state_with_cities();
+        let before = state.city_yields(CityId(2)).unwrap().gold;
+        for q in 3..5 {
+            state.map.get_mut(&HexCoord::new(q, 2)).unwrap().road = Some(Road::Road);
+        }
+        let after = state.city_yields(CityId(2)).unwrap().gold;
+        assert_eq!(after - before, connection_gold(&state.cities[&CityId(2)]));
+    }
+
+    #[test]
+    fn test_broken_road_disconnects() {
+        let mut state = state_with_cities();
+        state.map.get_mut(&HexCoord::new(3, 2)).unwrap().road = Some(Road::Road);
+        assert!(connected_cities(&state, PlayerSlot(0)).is_empty());
+    }
+}
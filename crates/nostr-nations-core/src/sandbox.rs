@@ -0,0 +1,161 @@
+//! Sandbox forks of a game for debugging and "what-if" play.
+//!
+//! [`fork_at`] replays a game's events up to the start of an earlier turn
+//! and gives the result a new game ID, so it can be played on locally
+//! without touching the original chain. Sandbox IDs start with
+//! [`SANDBOX_PREFIX`]; clients check [`is_sandbox`] to keep such games off
+//! the network.
+
+use crate::events::{GameAction, GameEvent};
+use crate::replay::{GameEngine, ReplayError};
+use crate::types::GameId;
+
+/// Prefix of every sandbox game ID.
+pub const SANDBOX_PREFIX: &str = "sandbox_";
+
+/// ID for the `n`th sandbox forked from `source` at `turn`.
+pub fn sandbox_id(source: &GameId, turn: u32, n: u32) -> GameId {
+    if n == 0 {
+        GameId::new(format!("{}{}_t{}", SANDBOX_PREFIX, source, turn))
+    } else {
+        GameId::new(format!("{}{}_t{}_{}", SANDBOX_PREFIX, source, turn, n))
+    }
+}
+
+/// Check if a game is a sandbox fork.
+pub fn is_sandbox(game_id: &GameId) -> bool {
+    game_id.as_str().starts_with(SANDBOX_PREFIX)
+}
+
+/// Replay `events` to the start of `turn` as a new game called `game_id`.
+///
+/// The events must be in chain order. Replay starts from the newest
+/// snapshot taken no later than `turn` and stops at the first event of
+/// that turn, so the fork is where the game stood when `turn` began.
+/// A turn the game never reached forks the latest state.
+pub fn fork_at(
+    events: &[GameEvent],
+    turn: u32,
+    game_id: GameId,
+) -> Result<GameEngine, ReplayError> {
+    if events.is_empty() {
+        return Err(ReplayError::EmptyEventChain);
+    }
+
+    let start = events
+        .iter()
+        .rposition(
+            |e| matches!(&e.action, GameAction::Snapshot { snapshot } if snapshot.turn <= turn),
+        )
+        .unwrap_or(0);
+    let mut engine = GameEngine::from_events(&events[..=start])?;
+    for event in &events[start + 1..] {
+        if engine.state.turn >= turn {
+            break;
+        }
+        engine.apply_event(event)?;
+    }
+
+    engine.state.id = game_id;
+    Ok(engine)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::audit;
+    use crate::settings::GameSettings;
+    use crate::types::PlayerSlot;
+
+    /// A duel played to turn 4, and the state hash at the start of each
+    /// turn.
+    fn duel_events() -> (Vec<GameEvent>, Vec<(u32, u64)>) {
+        let settings = GameSettings::new("Sandbox".to_string());
+        let seed = [9u8; 32];
+        let mut actions = vec![
+            (
+                0,
+                GameAction::CreateGame {
+                    settings_json: serde_json::to_string(&settings).unwrap(),
+                    seed,
+                },
+            ),
+            (
+                0,
+                GameAction::JoinGame {
+                    player_name: "P0".to_string(),
+                    civilization_id: "rome".to_string(),
+                },
+            ),
+            (
+                1,
+                GameAction::JoinGame {
+                    player_name: "P1".to_string(),
+                    civilization_id: "egypt".to_string(),
+                },
+            ),
+            (0, GameAction::StartGame),
+        ];
+        for _ in 1..4 {
+            actions.push((0, GameAction::EndTurn));
+            actions.push((1, GameAction::EndTurn));
+        }
+
+        let mut engine = GameEngine::new(settings, seed);
+        let mut hashes = Vec::new();
+        let mut events = Vec::new();
+        for (i, (player, action)) in actions.into_iter().enumerate() {
+            if !matches!(action, GameAction::CreateGame { .. }) {
+                engine.apply_action(PlayerSlot(player), &action).unwrap();
+            }
+            let mut event = GameEvent::new(
+                GameId::new("live"),
+                PlayerSlot(player),
+                i.checked_sub(1).map(|p| format!("evt{}", p)),
+                engine.state.turn,
+                i as u32,
+                action,
+            );
+            event.id = format!("evt{}", i);
+            events.push(event);
+            if hashes.last().map(|&(t, _)| t) != Some(engine.state.turn) {
+                hashes.push((engine.state.turn, audit::state_hash(&engine.state)));
+            }
+        }
+        (events, hashes)
+    }
+
+    #[test]
+    fn test_fork_matches_state_at_turn_start() {
+        let (events, hashes) = duel_events();
+        let (turn, expected) = hashes[hashes.len() - 2];
+        let fork_id = sandbox_id(&GameId::new("live"), turn, 0);
+
+        let mut fork = fork_at(&events, turn, fork_id.clone()).unwrap();
+        assert_eq!(fork.state.turn, turn);
+        assert_eq!(fork.state.id, fork_id);
+        fork.state.id = GameEngine::from_events(&events[..1]).unwrap().state.id;
+        assert_eq!(audit::state_hash(&fork.state), expected);
+    }
+
+    #[test]
+    fn test_fork_past_the_end_is_latest_state() {
+        let (events, _) = duel_events();
+        let live = GameEngine::from_events(&events).unwrap();
+        let fork = fork_at(&events, 99, sandbox_id(&GameId::new("live"), 99, 0)).unwrap();
+        assert_eq!(fork.state.turn, live.state.turn);
+        assert!(matches!(
+            fork_at(&[], 1, GameId::new("x")),
+            Err(ReplayError::EmptyEventChain)
+        ));
+    }
+
+    #[test]
+    fn test_sandbox_ids() {
+        let source = GameId::new("game_ab");
+        assert_eq!(sandbox_id(&source, 5, 0).as_str(), "sandbox_game_ab_t5");
+        assert_eq!(sandbox_id(&source, 5, 2).as_str(), "sandbox_game_ab_t5_2");
+        assert!(is_sandbox(&sandbox_id(&source, 5, 0)));
+        assert!(!is_sandbox(&source));
+    }
+}
@@ -0,0 +1,460 @@
+//! Scripted victory and defeat conditions for scenarios.
+//!
+//! The built-in victories ([`VictoryChecker`](crate::victory::VictoryChecker))
+//! cover ordinary games. Scenarios add their own rules, written as data in
+//! the game settings rather than code, so every peer evaluates exactly the
+//! same conditions:
+//!
+//! ```json
+//! {
+//!   "id": "hold-rome",
+//!   "description": "Hold Rome for 20 turns",
+//!   "outcome": "Victory",
+//!   "condition": { "type": "OwnsCity", "city": "Rome" },
+//!   "turns": 20
+//! }
+//! ```
+//!
+//! A [`Condition`] is checked for each player the rule applies to. When it
+//! has held for [`ScenarioRule::turns`] consecutive turns the rule fires:
+//! the player wins by [`VictoryType::Scenario`], or is eliminated.
+//!
+//! Rules are evaluated once per game turn, at the start of each new turn,
+//! in the order they are listed and for players in ID order. The first
+//! victory ends the game; a defeat that leaves one player standing makes
+//! them the winner.
+
+use crate::game_state::{GamePhase, GameState};
+use crate::types::{PlayerSlot, VictoryType};
+use serde::{Deserialize, Serialize};
+use std::collections::{BTreeMap, HashSet};
+
+/// Deepest nesting of `All`, `Any` and `Not` allowed in a condition.
+pub const MAX_CONDITION_DEPTH: usize = 8;
+
+/// A test against the game state, from one player's point of view.
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(tag = "type")]
+pub enum Condition {
+    /// The player owns the city with this name.
+    OwnsCity { city: String },
+    /// The player owns at least this many cities.
+    CitiesAtLeast { count: u32 },
+    /// The player's treasury holds at least this much gold.
+    GoldAtLeast { amount: i32 },
+    /// The player's score is at least this high.
+    ScoreAtLeast { score: u32 },
+    /// The player has researched this technology.
+    HasTech { tech_id: String },
+    /// The game has reached this turn.
+    TurnAtLeast { turn: u32 },
+    /// Another player has been eliminated.
+    PlayerEliminated { player_id: PlayerSlot },
+    /// Every condition holds.
+    All { conditions: Vec<Condition> },
+    /// At least one condition holds.
+    Any { conditions: Vec<Condition> },
+    /// The condition does not hold.
+    Not { condition: Box<Condition> },
+}
+
+impl Condition {
+    /// Whether the condition holds for `player_id`.
+    pub fn holds(&self, state: &GameState, player_id: PlayerSlot) -> bool {
+        let Some(player) = state.get_player(player_id) else {
+            return false;
+        };
+        match self {
+            Condition::OwnsCity { city } => state
+                .cities
+                .values()
+                .any(|c| c.owner == player_id && c.name == *city),
+            Condition::CitiesAtLeast { count } => {
+                state
+                    .cities
+                    .values()
+                    .filter(|c| c.owner == player_id)
+                    .count() as u32
+                    >= *count
+            }
+            Condition::GoldAtLeast { amount } => player.gold >= *amount,
+            Condition::ScoreAtLeast { score } => player.score.total >= *score,
+            Condition::HasTech { tech_id } => player.technologies.contains(tech_id),
+            Condition::TurnAtLeast { turn } => state.turn >= *turn,
+            Condition::PlayerEliminated { player_id } => {
+                state.get_player(*player_id).is_some_and(|p| p.eliminated)
+            }
+            Condition::All { conditions } => conditions.iter().all(|c| c.holds(state, player_id)),
+            Condition::Any { conditions } => conditions.iter().any(|c| c.holds(state, player_id)),
+            Condition::Not { condition } => !condition.holds(state, player_id),
+        }
+    }
+
+    /// Nesting depth, counting this condition.
+    fn depth(&self) -> usize {
+        match self {
+            Condition::All { conditions } | Condition::Any { conditions } => {
+                1 + conditions.iter().map(Condition::depth).max().unwrap_or(0)
+            }
+            Condition::Not { condition } => 1 + condition.depth(),
+            _ => 1,
+        }
+    }
+}
+
+/// What happens when a rule fires.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub enum RuleOutcome {
+    /// The player wins the game.
+    Victory,
+    /// The player is eliminated.
+    Defeat,
+}
+
+/// A scripted victory or defeat condition.
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ScenarioRule {
+    /// Unique identifier, used to track progress and in effects.
+    pub id: String,
+    /// Human-readable description for the scenario briefing.
+    #[serde(default)]
+    pub description: String,
+    /// What happens when the rule fires.
+    pub outcome: RuleOutcome,
+    /// Players the rule applies to (empty means everyone).
+    #[serde(default)]
+    pub players: Vec<PlayerSlot>,
+    /// The condition to check.
+    pub condition: Condition,
+    /// Consecutive turns the condition must hold before the rule fires.
+    #[serde(default = "default_rule_turns")]
+    pub turns: u32,
+}
+
+fn default_rule_turns() -> u32 {
+    1
+}
+
+impl ScenarioRule {
+    /// Whether the rule applies to a player.
+    pub fn applies_to(&self, player_id: PlayerSlot) -> bool {
+        self.players.is_empty() || self.players.contains(&player_id)
+    }
+}
+
+/// Errors in a set of scenario rules.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum ScenarioError {
+    /// The rules could not be parsed.
+    Parse(String),
+    /// A rule has an empty ID.
+    EmptyId,
+    /// Two rules share an ID.
+    DuplicateId(String),
+    /// A rule requires its condition to hold for zero turns.
+    ZeroTurns(String),
+    /// A rule's condition is nested too deeply.
+    TooDeep(String),
+}
+
+impl std::fmt::Display for ScenarioError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            ScenarioError::Parse(e) => write!(f, "Invalid scenario rules: {}", e),
+            ScenarioError::EmptyId => write!(f, "Scenario rule has an empty ID"),
+            ScenarioError::DuplicateId(id) => write!(f, "Duplicate scenario rule: {}", id),
+            ScenarioError::ZeroTurns(id) => {
+                write!(f, "Scenario rule {} must hold for at least one turn", id)
+            }
+            ScenarioError::TooDeep(id) => write!(
+                f,
+                "Scenario rule {} nests conditions more than {} deep",
+                id, MAX_CONDITION_DEPTH
+            ),
+        }
+    }
+}
+
+impl std::error::Error for ScenarioError {}
+
+/// Parse and validate a list of rules from JSON.
+pub fn parse_rules(json: &str) -> Result<Vec<ScenarioRule>, ScenarioError> {
+    let rules: Vec<ScenarioRule> =
+        serde_json::from_str(json).map_err(|e| ScenarioError::Parse(e.to_string()))?;
+    validate_rules(&rules)?;
+    Ok(rules)
+}
+
+/// Check a list of rules for mistakes that would make them ambiguous.
+pub fn validate_rules(rules: &[ScenarioRule]) -> Result<(), ScenarioError> {
+    let mut ids = HashSet::new();
+    for rule in rules {
+        if rule.id.is_empty() {
+            return Err(ScenarioError::EmptyId);
+        }
+        if !ids.insert(rule.id.as_str()) {
+            return Err(ScenarioError::DuplicateId(rule.id.clone()));
+        }
+        if rule.turns == 0 {
+            return Err(ScenarioError::ZeroTurns(rule.id.clone()));
+        }
+        if rule.condition.depth() > MAX_CONDITION_DEPTH {
+            return Err(ScenarioError::TooDeep(rule.id.clone()));
+        }
+    }
+    Ok(())
+}
+
+/// How long each rule's condition has held, per player.
+#[derive(Clone, Debug, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ScenarioProgress {
+    /// Last turn the rules were evaluated on.
+    pub evaluated_turn: u32,
+    /// Consecutive turns held, by rule ID and player.
+    pub held: BTreeMap<String, BTreeMap<PlayerSlot, u32>>,
+}
+
+impl ScenarioProgress {
+    /// Consecutive turns a rule's condition has held for a player.
+    pub fn turns_held(&self, rule_id: &str, player_id: PlayerSlot) -> u32 {
+        self.held
+            .get(rule_id)
+            .and_then(|players| players.get(&player_id))
+            .copied()
+            .unwrap_or(0)
+    }
+}
+
+/// Rules that fired during one evaluation.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct ScenarioReport {
+    /// Players eliminated, with the rule that defeated them.
+    pub defeated: Vec<(PlayerSlot, String)>,
+    /// The winner, if a rule ended the game.
+    pub winner: Option<(PlayerSlot, VictoryType)>,
+}
+
+/// Evaluate the game's scenario rules for the current turn.
+///
+/// Does nothing if the game has no rules, isn't being played, or the rules
+/// were already evaluated this turn.
+pub fn evaluate_rules(state: &mut GameState) -> ScenarioReport {
+    let mut report = ScenarioReport::default();
+    if state.settings.scenario_rules.is_empty()
+        || state.phase != GamePhase::Playing
+        || state.scenario.evaluated_turn >= state.turn
+    {
+        return report;
+    }
+    state.scenario.evaluated_turn = state.turn;
+
+    let rules = state.settings.scenario_rules.clone();
+    for rule in &rules {
+        let players: Vec<PlayerSlot> = state
+            .players
+            .iter()
+            .filter(|p| !p.eliminated && rule.applies_to(p.id))
+            .map(|p| p.id)
+            .collect();
+        for player_id in players {
+            // A defeat earlier in this pass may have removed the player
+            if state.get_player(player_id).is_none_or(|p| p.eliminated) {
+                continue;
+            }
+            let holds = rule.condition.holds(state, player_id);
+            let held = state.scenario.held.entry(rule.id.clone()).or_default();
+            if !holds {
+                held.remove(&player_id);
+                continue;
+            }
+            let turns = held.entry(player_id).or_insert(0);
+            *turns += 1;
+            if *turns < rule.turns {
+                continue;
+            }
+
+            match rule.outcome {
+                RuleOutcome::Victory => {
+                    end_game(state, &mut report, player_id);
+                    return report;
+                }
+                RuleOutcome::Defeat => {
+                    if let Some(player) = state.get_player_mut(player_id) {
+                        player.eliminate();
+                    }
+                    report.defeated.push((player_id, rule.id.clone()));
+                }
+            }
+        }
+    }
+
+    let mut remaining = state.players.iter().filter(|p| !p.eliminated);
+    if let (Some(winner), None, false) = (
+        remaining.next(),
+        remaining.next(),
+        report.defeated.is_empty(),
+    ) {
+        let winner_id = winner.id;
+        end_game(state, &mut report, winner_id);
+    }
+    report
+}
+
+fn end_game(state: &mut GameState, report: &mut ScenarioReport, winner_id: PlayerSlot) {
+    let winner = (winner_id, VictoryType::Scenario);
+    state.winner = Some(winner);
+    state.phase = GamePhase::Ended;
+    report.winner = Some(winner);
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::city::City;
+    use crate::hex::HexCoord;
+    use crate::player::{Civilization, Player};
+    use crate::settings::GameSettings;
+    use crate::types::{CityId, GameId, Npub};
+
+    fn scenario_game(rules: Vec<ScenarioRule>) -> GameState {
+        let mut settings = GameSettings::new("Scenario".to_string());
+        settings.player_count = 3;
+        settings.scenario_rules = rules;
+        let mut state = GameState::new(GameId::new("scenario"), settings, [0u8; 32]);
+        for id in 0..3 {
+            state.players.push(Player::new(
+                PlayerSlot(id),
+                Npub(format!("npub{}", id)),
+                format!("Player {}", id),
+                Civilization::generic(),
+            ));
+        }
+        state.phase = GamePhase::Playing;
+        state.turn = 1;
+        let rome = City::new(
+            CityId(1),
+            PlayerSlot(0),
+            "Rome".to_string(),
+            HexCoord::new(5, 5),
+            true,
+        );
+        state.cities.insert(CityId(1), rome);
+        state
+    }
+
+    fn rule(id: &str, outcome: RuleOutcome, condition: Condition, turns: u32) -> ScenarioRule {
+        ScenarioRule {
+            id: id.to_string(),
+            description: String::new(),
+            outcome,
+            players: Vec::new(),
+            condition,
+            turns,
+        }
+    }
+
+    #[test]
+    fn test_hold_city_for_turns() {
+        let hold_rome = Condition::OwnsCity {
+            city: "Rome".to_string(),
+        };
+        let mut state = scenario_game(vec![rule("hold", RuleOutcome::Victory, hold_rome, 3)]);
+
+        for turn in 1..=2 {
+            state.turn = turn;
+            assert_eq!(evaluate_rules(&mut state).winner, None);
+        }
+        assert_eq!(state.scenario.turns_held("hold", PlayerSlot(0)), 2);
+        // Evaluating the same turn twice doesn't count it twice
+        assert_eq!(evaluate_rules(&mut state), ScenarioReport::default());
+        assert_eq!(state.scenario.turns_held("hold", PlayerSlot(0)), 2);
+
+        // Losing the city resets the count
+        state.cities.get_mut(&CityId(1)).unwrap().owner = PlayerSlot(1);
+        state.turn = 3;
+        evaluate_rules(&mut state);
+        assert_eq!(state.scenario.turns_held("hold", PlayerSlot(0)), 0);
+        assert_eq!(state.scenario.turns_held("hold", PlayerSlot(1)), 1);
+
+        for turn in 4..=5 {
+            state.turn = turn;
+            evaluate_rules(&mut state);
+        }
+        assert_eq!(state.winner, Some((PlayerSlot(1), VictoryType::Scenario)));
+        assert_eq!(state.phase, GamePhase::Ended);
+    }
+
+    #[test]
+    fn test_defeat_rules_eliminate_players() {
+        let broke = Condition::Not {
+            condition: Box::new(Condition::GoldAtLeast { amount: 0 }),
+        };
+        let mut state = scenario_game(vec![rule("broke", RuleOutcome::Defeat, broke, 1)]);
+        state.players[1].gold = -10;
+
+        let report = evaluate_rules(&mut state);
+        assert_eq!(report.defeated, vec![(PlayerSlot(1), "broke".to_string())]);
+        assert!(state.players[1].eliminated);
+        assert_eq!(report.winner, None);
+
+        state.players[2].gold = -10;
+        state.turn = 2;
+        let report = evaluate_rules(&mut state);
+        assert_eq!(report.defeated, vec![(PlayerSlot(2), "broke".to_string())]);
+        assert_eq!(report.winner, Some((PlayerSlot(0), VictoryType::Scenario)));
+    }
+
+    #[test]
+    fn test_parse_and_validate_rules() {
+        let rules = parse_rules(
+            r#"[{
+                "id": "rome",
+                "outcome": "Victory",
+                "players": [0],
+                "condition": {"type": "All", "conditions": [
+                    {"type": "OwnsCity", "city": "Rome"},
+                    {"type": "TurnAtLeast", "turn": 50}
+                ]},
+                "turns": 20
+            }]"#,
+        )
+        .unwrap();
+        assert_eq!(rules[0].turns, 20);
+        assert!(rules[0].applies_to(PlayerSlot(0)));
+        assert!(!rules[0].applies_to(PlayerSlot(1)));
+
+        let duplicate = vec![
+            rule(
+                "a",
+                RuleOutcome::Victory,
+                Condition::TurnAtLeast { turn: 1 },
+                1,
+            ),
+            rule(
+                "a",
+                RuleOutcome::Defeat,
+                Condition::TurnAtLeast { turn: 1 },
+                1,
+            ),
+        ];
+        assert_eq!(
+            validate_rules(&duplicate),
+            Err(ScenarioError::DuplicateId("a".to_string()))
+        );
+
+        let mut deep = Condition::TurnAtLeast { turn: 1 };
+        for _ in 0..MAX_CONDITION_DEPTH {
+            deep = Condition::Not {
+                condition: Box::new(deep),
+            };
+        }
+        assert_eq!(
+            validate_rules(&[rule("deep", RuleOutcome::Victory, deep, 1)]),
+            Err(ScenarioError::TooDeep("deep".to_string()))
+        );
+        assert!(matches!(
+            parse_rules(r#"[{"id": "x"}]"#),
+            Err(ScenarioError::Parse(_))
+        ));
+    }
+}
@@ -0,0 +1,297 @@
+//! Turn order and expected wait times.
+//!
+//! [`TurnTimes`] collects how long each player takes per turn, either from
+//! the timestamps of `EndTurn` events in the chain or from a local clock.
+//! [`TurnSchedule`] combines those averages with the turn order to report
+//! who plays next and roughly when each player's turn comes up.
+
+use crate::events::{GameAction, GameEvent};
+use crate::game_state::GameState;
+use crate::types::PlayerSlot;
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use ts_rs::TS;
+
+/// Turn duration assumed before any turn has been timed, in seconds.
+pub const DEFAULT_TURN_SECS: u64 = 120;
+
+/// Per-player turn durations.
+#[derive(Clone, Debug, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct TurnTimes {
+    /// Total seconds and number of timed turns per player.
+    totals: BTreeMap<PlayerSlot, (u64, u32)>,
+    /// When the current turn started (Unix seconds), if known.
+    current_started: Option<u64>,
+}
+
+impl TurnTimes {
+    /// Create empty turn times.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Collect turn times from the `EndTurn` events of a chain.
+    ///
+    /// Events without a timestamp are skipped.
+    pub fn from_events(events: &[GameEvent]) -> Self {
+        let mut times = Self::new();
+        for event in events {
+            times.record(event);
+        }
+        times
+    }
+
+    /// Record an event, timing the turn if it ends one.
+    pub fn record(&mut self, event: &GameEvent) {
+        if event.timestamp == 0 {
+            return;
+        }
+        match event.action {
+            GameAction::EndTurn => self.end_turn(event.player_id, event.timestamp),
+            // The first timed event starts the clock
+            _ => {
+                self.current_started.get_or_insert(event.timestamp);
+            }
+        }
+    }
+
+    /// Start timing the current turn.
+    pub fn start_turn(&mut self, now: u64) {
+        self.current_started = Some(now);
+    }
+
+    /// Record that `player_id` ended their turn at `now`, and start timing
+    /// the next one.
+    pub fn end_turn(&mut self, player_id: PlayerSlot, now: u64) {
+        if let Some(started) = self.current_started {
+            let entry = self.totals.entry(player_id).or_insert((0, 0));
+            entry.0 += now.saturating_sub(started);
+            entry.1 += 1;
+        }
+        self.current_started = Some(now);
+    }
+
+    /// When the current turn started, if known.
+    pub fn current_started(&self) -> Option<u64> {
+        self.current_started
+    }
+
+    /// Average turn duration of a player, if any turn was timed.
+    pub fn average(&self, player_id: PlayerSlot) -> Option<u64> {
+        self.totals
+            .get(&player_id)
+            .filter(|(_, count)| *count > 0)
+            .map(|(total, count)| total / u64::from(*count))
+    }
+
+    /// Average turn duration across all players, if any turn was timed.
+    pub fn overall_average(&self) -> Option<u64> {
+        let (total, count) = self
+            .totals
+            .values()
+            .fold((0u64, 0u64), |(t, c), (total, count)| {
+                (t + total, c + u64::from(*count))
+            });
+        (count > 0).then(|| total / count)
+    }
+
+    /// Expected turn duration of a player, falling back to the overall
+    /// average and then to [`DEFAULT_TURN_SECS`].
+    pub fn expected(&self, player_id: PlayerSlot) -> u64 {
+        self.average(player_id)
+            .or_else(|| self.overall_average())
+            .unwrap_or(DEFAULT_TURN_SECS)
+    }
+}
+
+/// One upcoming turn in the schedule.
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize, JsonSchema, TS)]
+pub struct ScheduledTurn {
+    /// Player taking the turn.
+    pub player_id: PlayerSlot,
+    /// Player's average turn duration in seconds, if known.
+    pub average_secs: Option<u64>,
+    /// Estimated seconds until this player's turn starts (0 if playing now).
+    pub eta_secs: u64,
+}
+
+/// Turn order with expected wait times.
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct TurnSchedule {
+    /// Current turn number.
+    pub turn: u32,
+    /// Player whose turn it is.
+    pub current_player: PlayerSlot,
+    /// Seconds the current player has spent on this turn, if known.
+    pub elapsed_secs: Option<u64>,
+    /// Remaining players in turn order, starting with the current player.
+    pub queue: Vec<ScheduledTurn>,
+}
+
+impl TurnSchedule {
+    /// Build the schedule for a game at time `now` (Unix seconds).
+    pub fn build(state: &GameState, times: &TurnTimes, now: u64) -> Self {
+        let elapsed_secs = times
+            .current_started()
+            .map(|started| now.saturating_sub(started));
+
+        let count = state.players.len();
+        let order = (0..count)
+            .map(|offset| (state.current_player.index() + offset) % count.max(1))
+            .filter_map(|index| state.players.get(index))
+            .filter(|player| !player.eliminated)
+            .map(|player| player.id);
+
+        let mut queue = Vec::new();
+        let mut eta_secs = 0;
+        for player_id in order {
+            queue.push(ScheduledTurn {
+                player_id,
+                average_secs: times.average(player_id),
+                eta_secs,
+            });
+            let expected = times.expected(player_id);
+            eta_secs += if player_id == state.current_player {
+                expected.saturating_sub(elapsed_secs.unwrap_or(0))
+            } else {
+                expected
+            };
+        }
+
+        Self {
+            turn: state.turn,
+            current_player: state.current_player,
+            elapsed_secs,
+            queue,
+        }
+    }
+
+    /// Player who plays after the current one.
+    pub fn next_player(&self) -> Option<PlayerSlot> {
+        self.queue.get(1).map(|turn| turn.player_id)
+    }
+
+    /// Number of turns before `player_id` plays (0 if playing now).
+    pub fn position(&self, player_id: PlayerSlot) -> Option<usize> {
+        self.queue
+            .iter()
+            .position(|turn| turn.player_id == player_id)
+    }
+
+    /// Estimated seconds until `player_id`'s next turn starts.
+    pub fn eta(&self, player_id: PlayerSlot) -> Option<u64> {
+        self.queue
+            .iter()
+            .find(|turn| turn.player_id == player_id)
+            .map(|turn| turn.eta_secs)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::player::{Civilization, Player};
+    use crate::settings::GameSettings;
+    use crate::types::{GameId, Npub};
+
+    fn create_state(players: u8) -> GameState {
+        let mut state = GameState::new(
+            GameId::new("test"),
+            GameSettings::new("Test".to_string()),
+            [0; 32],
+        );
+        for id in 0..players {
+            state.players.push(Player::new(
+                PlayerSlot(id),
+                Npub(format!("npub{}", id)),
+                format!("Player {}", id),
+                Civilization::generic(),
+            ));
+        }
+        state
+    }
+
+    fn end_turn(player_id: PlayerSlot, timestamp: u64) -> GameEvent {
+        let mut event = GameEvent::new(
+            GameId::new("test"),
+            player_id,
+            None,
+            1,
+            0,
+            GameAction::EndTurn,
+        );
+        event.timestamp = timestamp;
+        event
+    }
+
+    // ==================== TurnTimes Tests ====================
+
+    #[test]
+    fn test_times_from_events() {
+        let events = vec![
+            end_turn(PlayerSlot(0), 1000),
+            end_turn(PlayerSlot(1), 1060),
+            end_turn(PlayerSlot(0), 1090),
+            end_turn(PlayerSlot(1), 1190),
+            end_turn(PlayerSlot(0), 1200),
+        ];
+        let times = TurnTimes::from_events(&events);
+
+        // The first EndTurn only starts the clock
+        assert_eq!(times.average(PlayerSlot(1)), Some(80));
+        assert_eq!(times.average(PlayerSlot(0)), Some(20));
+        assert_eq!(times.average(PlayerSlot(2)), None);
+        assert_eq!(times.overall_average(), Some(50));
+        assert_eq!(times.expected(PlayerSlot(2)), 50);
+        assert_eq!(times.current_started(), Some(1200));
+    }
+
+    #[test]
+    fn test_untimed_events_are_skipped() {
+        let times =
+            TurnTimes::from_events(&[end_turn(PlayerSlot(0), 0), end_turn(PlayerSlot(1), 0)]);
+        assert_eq!(times.overall_average(), None);
+        assert_eq!(times.expected(PlayerSlot(0)), DEFAULT_TURN_SECS);
+    }
+
+    // ==================== TurnSchedule Tests ====================
+
+    #[test]
+    fn test_schedule_order_and_eta() {
+        let mut state = create_state(4);
+        state.current_player = PlayerSlot(2);
+        state.players[3].eliminated = true;
+
+        let mut times = TurnTimes::new();
+        times.start_turn(0);
+        times.end_turn(PlayerSlot(2), 30);
+        times.end_turn(PlayerSlot(0), 90);
+        times.end_turn(PlayerSlot(1), 110);
+
+        let schedule = TurnSchedule::build(&state, &times, 120);
+        let order: Vec<PlayerSlot> = schedule.queue.iter().map(|t| t.player_id).collect();
+        assert_eq!(order, vec![PlayerSlot(2), PlayerSlot(0), PlayerSlot(1)]);
+        assert_eq!(schedule.next_player(), Some(PlayerSlot(0)));
+        assert_eq!(schedule.elapsed_secs, Some(10));
+
+        // Player 2 averages 30s and has used 10s, player 0 averages 60s
+        assert_eq!(schedule.eta(PlayerSlot(2)), Some(0));
+        assert_eq!(schedule.eta(PlayerSlot(0)), Some(20));
+        assert_eq!(schedule.eta(PlayerSlot(1)), Some(80));
+        assert_eq!(schedule.eta(PlayerSlot(3)), None);
+        assert_eq!(schedule.position(PlayerSlot(1)), Some(2));
+    }
+
+    #[test]
+    fn test_overdue_turn_does_not_go_negative() {
+        let state = create_state(2);
+        let mut times = TurnTimes::new();
+        times.start_turn(0);
+        times.end_turn(PlayerSlot(0), 10);
+        times.end_turn(PlayerSlot(1), 20);
+
+        let schedule = TurnSchedule::build(&state, &times, 500);
+        assert_eq!(schedule.eta(PlayerSlot(1)), Some(0));
+    }
+}
@@ -0,0 +1,304 @@
+//! JSON Schemas for game events.
+//!
+//! Frontends and third-party tools (replay viewers, bots, relay indexers)
+//! need the event format without reading the Rust source. The schemas here
+//! are derived from the types themselves with `schemars`, so they change
+//! whenever the types do and can't fall out of date.
+//!
+//! A [`SchemaExport`] collects any number of types into one JSON Schema
+//! document: every type, and everything it refers to, is a definition under
+//! `$defs`, and the document itself accepts any of the types that were
+//! added. [`SchemaExport::to_markdown`] renders the same definitions as
+//! reference documentation.
+
+use crate::cashu::RandomnessProof;
+use crate::events::{GameAction, GameEvent};
+use crate::snapshot::StateSnapshot;
+use schemars::generate::{SchemaGenerator, SchemaSettings};
+use schemars::JsonSchema;
+use serde_json::{json, Map, Value};
+use std::fmt::Write;
+
+/// JSON Schema dialect of exported documents.
+pub const SCHEMA_DIALECT: &str = "https://json-schema.org/draft/2020-12/schema";
+
+/// A set of types exported as one JSON Schema document.
+#[derive(Clone)]
+pub struct SchemaExport {
+    title: String,
+    generator: SchemaGenerator,
+    /// Definition names of the added types, in the order they were added.
+    roots: Vec<String>,
+}
+
+impl SchemaExport {
+    /// Start an empty export.
+    pub fn new(title: impl Into<String>) -> Self {
+        Self {
+            title: title.into(),
+            generator: SchemaGenerator::new(SchemaSettings::draft2020_12()),
+            roots: Vec::new(),
+        }
+    }
+
+    /// Add a type, along with every type it refers to.
+    pub fn add<T: JsonSchema>(&mut self) -> &mut Self {
+        let reference = self.generator.subschema_for::<T>();
+        // Types with the same name get a numbered definition, so take the
+        // name from the reference rather than from `T::schema_name`
+        let name = reference
+            .get("$ref")
+            .and_then(Value::as_str)
+            .and_then(|r| r.rsplit('/').next())
+            .map(str::to_string)
+            .unwrap_or_else(|| T::schema_name().into_owned());
+        if !self.roots.contains(&name) {
+            self.roots.push(name);
+        }
+        self
+    }
+
+    /// Definition names of the added types.
+    pub fn roots(&self) -> &[String] {
+        &self.roots
+    }
+
+    /// All definitions, including types only reachable from the added ones.
+    pub fn definitions(&self) -> Map<String, Value> {
+        self.generator.clone().take_definitions(true)
+    }
+
+    /// The schema of one added or referenced type, by definition name.
+    pub fn definition(&self, name: &str) -> Option<Value> {
+        self.definitions().remove(name)
+    }
+
+    /// The whole export as a JSON Schema document.
+    pub fn to_json(&self) -> Value {
+        let any_of: Vec<Value> = self
+            .roots
+            .iter()
+            .map(|name| json!({ "$ref": format!("#/$defs/{}", name) }))
+            .collect();
+        json!({
+            "$schema": SCHEMA_DIALECT,
+            "title": self.title,
+            "anyOf": any_of,
+            "$defs": self.definitions(),
+        })
+    }
+
+    /// Reference documentation for every definition, as Markdown.
+    ///
+    /// Each type gets a section with its description and a table of its
+    /// fields; tagged enums get a subsection per variant.
+    pub fn to_markdown(&self) -> String {
+        let definitions = self.definitions();
+        let mut out = format!("# {}\n", self.title);
+        let mut names: Vec<&String> = definitions.keys().collect();
+        // Added types first, then the types they use
+        names.sort_by_key(|name| (!self.roots.contains(name), name.as_str()));
+        for name in names {
+            let schema = &definitions[name];
+            let _ = write!(out, "\n## {}\n", name);
+            write_description(&mut out, schema);
+            write_body(&mut out, schema, "###");
+        }
+        out
+    }
+}
+
+impl std::fmt::Debug for SchemaExport {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("SchemaExport")
+            .field("title", &self.title)
+            .field("roots", &self.roots)
+            .finish()
+    }
+}
+
+/// Schemas for everything published as a game event: the event envelope,
+/// every action, and the payloads actions carry.
+pub fn event_schemas() -> SchemaExport {
+    let mut export = SchemaExport::new("Nostr Nations game events");
+    export
+        .add::<GameEvent>()
+        .add::<GameAction>()
+        .add::<StateSnapshot>()
+        .add::<RandomnessProof>();
+    export
+}
+
+fn write_description(out: &mut String, schema: &Value) {
+    if let Some(description) = schema.get("description").and_then(Value::as_str) {
+        let _ = write!(out, "\n{}\n", description);
+    }
+}
+
+fn write_body(out: &mut String, schema: &Value, heading: &str) {
+    if let Some(properties) = schema.get("properties").and_then(Value::as_object) {
+        write_fields(out, schema, properties);
+    }
+    if let Some(values) = schema.get("enum").and_then(Value::as_array) {
+        out.push_str("\nOne of: ");
+        let values: Vec<String> = values.iter().map(|v| format!("`{}`", v)).collect();
+        out.push_str(&values.join(", "));
+        out.push('\n');
+    }
+    let variants = schema
+        .get("oneOf")
+        .or_else(|| schema.get("anyOf"))
+        .and_then(Value::as_array);
+    for variant in variants.into_iter().flatten() {
+        let _ = write!(out, "\n{} {}\n", heading, variant_name(variant));
+        write_description(out, variant);
+        write_body(out, variant, "####");
+    }
+}
+
+fn write_fields(out: &mut String, schema: &Value, properties: &Map<String, Value>) {
+    let required: Vec<&str> = schema
+        .get("required")
+        .and_then(Value::as_array)
+        .map(|r| r.iter().filter_map(Value::as_str).collect())
+        .unwrap_or_default();
+    out.push_str("\n| Field | Type | Required | Description |\n|---|---|---|---|\n");
+    for (field, property) in properties {
+        let description = property
+            .get("description")
+            .and_then(Value::as_str)
+            .unwrap_or("")
+            .replace('\n', " ");
+        let _ = writeln!(
+            out,
+            "| `{}` | {} | {} | {} |",
+            field,
+            type_name(property),
+            if required.contains(&field.as_str()) {
+                "yes"
+            } else {
+                "no"
+            },
+            description
+        );
+    }
+}
+
+/// Name of a tagged enum variant: its tag value, or its title.
+fn variant_name(variant: &Value) -> String {
+    let tag = variant
+        .get("properties")
+        .and_then(Value::as_object)
+        .and_then(|p| p.values().find_map(|v| v.get("const")))
+        .or_else(|| variant.get("const"))
+        .or_else(|| variant.get("enum").and_then(|e| e.get(0)));
+    if let Some(tag) = tag {
+        return tag.as_str().map(str::to_string).unwrap_or(tag.to_string());
+    }
+    if let Some(name) = variant
+        .get("required")
+        .and_then(|r| r.get(0))
+        .and_then(Value::as_str)
+    {
+        return name.to_string();
+    }
+    variant
+        .get("title")
+        .and_then(Value::as_str)
+        .unwrap_or("variant")
+        .to_string()
+}
+
+/// Short human-readable type of a property schema.
+fn type_name(schema: &Value) -> String {
+    if let Some(reference) = schema.get("$ref").and_then(Value::as_str) {
+        let name = reference.rsplit('/').next().unwrap_or(reference);
+        return format!("[{}](#{})", name, name.to_lowercase());
+    }
+    if let Some(options) = schema
+        .get("anyOf")
+        .or_else(|| schema.get("oneOf"))
+        .and_then(Value::as_array)
+    {
+        let names: Vec<String> = options.iter().map(type_name).collect();
+        return names.join(" or ");
+    }
+    let types: Vec<&str> = match schema.get("type") {
+        Some(Value::String(t)) => vec![t.as_str()],
+        Some(Value::Array(ts)) => ts.iter().filter_map(Value::as_str).collect(),
+        _ => return "any".to_string(),
+    };
+    let names: Vec<String> = types
+        .into_iter()
+        .map(|t| match t {
+            "array" => match schema.get("items") {
+                Some(items) => format!("array of {}", type_name(items)),
+                None => "array".to_string(),
+            },
+            "object" => match schema.get("additionalProperties") {
+                Some(values) if values.is_object() => format!("map of {}", type_name(values)),
+                _ => "object".to_string(),
+            },
+            other => other.to_string(),
+        })
+        .collect();
+    names.join(" or ")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::hex::HexCoord;
+    use crate::types::UnitId;
+
+    #[test]
+    fn test_event_schemas_cover_every_action() {
+        let export = event_schemas();
+        assert_eq!(
+            export.roots(),
+            [
+                "GameEvent",
+                "GameAction",
+                "StateSnapshot",
+                "RandomnessProof"
+            ]
+        );
+
+        let action = export.definition("GameAction").unwrap();
+        let tags: Vec<String> = action["oneOf"]
+            .as_array()
+            .unwrap()
+            .iter()
+            .map(variant_name)
+            .collect();
+        for sample in [
+            GameAction::EndTurn,
+            GameAction::MoveUnit {
+                unit_id: UnitId(1),
+                path: vec![HexCoord::new(0, 0)],
+            },
+            GameAction::SetResearch {
+                tech_id: "pottery".to_string(),
+            },
+        ] {
+            let json = serde_json::to_value(&sample).unwrap();
+            assert!(tags.contains(&json["type"].as_str().unwrap().to_string()));
+        }
+        // Unrecognized actions are never serialized as such
+        assert!(!tags.contains(&"Unrecognized".to_string()));
+
+        let document = export.to_json();
+        assert_eq!(document["$schema"], SCHEMA_DIALECT);
+        assert!(document["$defs"]["HexCoord"].is_object());
+    }
+
+    #[test]
+    fn test_markdown_documents_fields() {
+        let markdown = event_schemas().to_markdown();
+        assert!(markdown.starts_with("# Nostr Nations game events\n"));
+        assert!(markdown.contains("\n## GameEvent\n"));
+        assert!(markdown.contains("\n### MoveUnit\n"));
+        assert!(markdown.contains("| `path` | array of [HexCoord](#hexcoord) | yes |"));
+        assert!(markdown.contains("| `expiration` | integer or null | no |"));
+    }
+}
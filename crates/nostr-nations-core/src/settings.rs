@@ -1,7 +1,14 @@
 //! Game settings and configuration.
 
-use crate::types::{Era, MapSize, VictoryConditions};
+use crate::ai::Persona;
+use crate::fixed::Fixed;
+use crate::scenario::{self, ScenarioError, ScenarioRule};
+use crate::substitution::{default_substitute_after_turns, DEFAULT_SUBSTITUTE_AFTER_TURNS};
+use crate::types::{Era, MapSize, PlayerSlot, VictoryConditions};
+use crate::unit::UnitType;
+use crate::yields::Yields;
 use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
 
 /// Configuration for a game session.
 #[derive(Clone, Debug, Serialize, Deserialize)]
@@ -20,6 +27,9 @@ pub struct GameSettings {
     pub max_turns: u32,
     /// Allow technology trading between players.
     pub tech_trading: bool,
+    /// Allow trading on technologies that were acquired by trade.
+    #[serde(default)]
+    pub tech_brokering: bool,
     /// Starting era for all players.
     pub starting_era: Era,
     /// Does the map wrap horizontally?
@@ -32,6 +42,29 @@ pub struct GameSettings {
     pub game_speed: GameSpeed,
     /// Difficulty level.
     pub difficulty: Difficulty,
+    /// Player seats controlled by the AI.
+    #[serde(default)]
+    pub ai_players: Vec<PlayerSlot>,
+    /// Personas chosen for AI seats. Seats without one are assigned a
+    /// persona from the game seed.
+    #[serde(default)]
+    pub ai_personas: BTreeMap<PlayerSlot, Persona>,
+    /// What happens to a conceding player's cities.
+    #[serde(default)]
+    pub concession_policy: ConcessionPolicy,
+    /// How pause requests are decided.
+    #[serde(default)]
+    pub pause_policy: PausePolicy,
+    /// Turns a player must be inactive before the host can hand their seat
+    /// to a substitute.
+    #[serde(default = "default_substitute_after_turns")]
+    pub substitute_after_turns: u32,
+    /// Whether mines and oil wells can run dry.
+    #[serde(default)]
+    pub resource_depletion: bool,
+    /// Scripted victory and defeat conditions for scenarios.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub scenario_rules: Vec<ScenarioRule>,
 }
 
 impl GameSettings {
@@ -45,12 +78,20 @@ impl GameSettings {
             turn_timer: 0,
             max_turns: 500,
             tech_trading: true,
+            tech_brokering: false,
             starting_era: Era::Ancient,
             map_wraps: false,
             fog_of_war: true,
             barbarians: false, // Disabled for now as we're focusing on multiplayer
             game_speed: GameSpeed::Normal,
             difficulty: Difficulty::Normal,
+            ai_players: Vec::new(),
+            ai_personas: BTreeMap::new(),
+            concession_policy: ConcessionPolicy::default(),
+            pause_policy: PausePolicy::default(),
+            substitute_after_turns: DEFAULT_SUBSTITUTE_AFTER_TURNS,
+            resource_depletion: false,
+            scenario_rules: Vec::new(),
         }
     }
 
@@ -64,12 +105,20 @@ impl GameSettings {
             turn_timer: 60,
             max_turns: 250,
             tech_trading: false,
+            tech_brokering: false,
             starting_era: Era::Ancient,
             map_wraps: false,
             fog_of_war: true,
             barbarians: false,
             game_speed: GameSpeed::Quick,
             difficulty: Difficulty::Normal,
+            ai_players: Vec::new(),
+            ai_personas: BTreeMap::new(),
+            concession_policy: ConcessionPolicy::default(),
+            pause_policy: PausePolicy::default(),
+            substitute_after_turns: DEFAULT_SUBSTITUTE_AFTER_TURNS,
+            resource_depletion: false,
+            scenario_rules: Vec::new(),
         }
     }
 
@@ -94,6 +143,7 @@ impl GameSettings {
         if tiles_per_player < min_tiles_per_player {
             return Err(SettingsError::MapTooSmallForPlayers);
         }
+        scenario::validate_rules(&self.scenario_rules).map_err(SettingsError::InvalidScenario)?;
         Ok(())
     }
 
@@ -103,12 +153,12 @@ impl GameSettings {
     }
 
     /// Calculate production multiplier based on game speed.
-    pub fn production_multiplier(&self) -> f32 {
+    pub fn production_multiplier(&self) -> Fixed {
         self.game_speed.production_multiplier()
     }
 
     /// Calculate research multiplier based on game speed.
-    pub fn research_multiplier(&self) -> f32 {
+    pub fn research_multiplier(&self) -> Fixed {
         self.game_speed.research_multiplier()
     }
 }
@@ -119,6 +169,27 @@ impl Default for GameSettings {
     }
 }
 
+/// What happens to a player's cities when they concede.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize, Deserialize, Default)]
+pub enum ConcessionPolicy {
+    /// Cities are handed to the player they surrender to, or to the
+    /// highest-scoring remaining player.
+    #[default]
+    Transfer,
+    /// Cities are destroyed and their territory released.
+    Raze,
+}
+
+/// How a pause request is decided.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize, Deserialize, Default)]
+pub enum PausePolicy {
+    /// A majority of the remaining human players must agree.
+    #[default]
+    Vote,
+    /// The host alone decides.
+    HostDecides,
+}
+
 /// Game speed affects how fast various game mechanics progress.
 #[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize, Deserialize, Default)]
 pub enum GameSpeed {
@@ -135,32 +206,32 @@ pub enum GameSpeed {
 
 impl GameSpeed {
     /// Get the production cost multiplier.
-    pub const fn production_multiplier(&self) -> f32 {
+    pub const fn production_multiplier(&self) -> Fixed {
         match self {
-            GameSpeed::Quick => 0.67,
-            GameSpeed::Normal => 1.0,
-            GameSpeed::Epic => 1.5,
-            GameSpeed::Marathon => 3.0,
+            GameSpeed::Quick => Fixed::from_percent(67),
+            GameSpeed::Normal => Fixed::ONE,
+            GameSpeed::Epic => Fixed::from_percent(150),
+            GameSpeed::Marathon => Fixed::from_int(3),
         }
     }
 
     /// Get the research cost multiplier.
-    pub const fn research_multiplier(&self) -> f32 {
+    pub const fn research_multiplier(&self) -> Fixed {
         match self {
-            GameSpeed::Quick => 0.67,
-            GameSpeed::Normal => 1.0,
-            GameSpeed::Epic => 1.5,
-            GameSpeed::Marathon => 3.0,
+            GameSpeed::Quick => Fixed::from_percent(67),
+            GameSpeed::Normal => Fixed::ONE,
+            GameSpeed::Epic => Fixed::from_percent(150),
+            GameSpeed::Marathon => Fixed::from_int(3),
         }
     }
 
     /// Get the growth rate multiplier.
-    pub const fn growth_multiplier(&self) -> f32 {
+    pub const fn growth_multiplier(&self) -> Fixed {
         match self {
-            GameSpeed::Quick => 0.67,
-            GameSpeed::Normal => 1.0,
-            GameSpeed::Epic => 1.5,
-            GameSpeed::Marathon => 3.0,
+            GameSpeed::Quick => Fixed::from_percent(67),
+            GameSpeed::Normal => Fixed::ONE,
+            GameSpeed::Epic => Fixed::from_percent(150),
+            GameSpeed::Marathon => Fixed::from_int(3),
         }
     }
 }
@@ -207,6 +278,108 @@ impl Difficulty {
             Difficulty::Deity => 20,
         }
     }
+
+    /// Get the starting happiness for a player.
+    ///
+    /// Humans get extra happiness on the easier levels; the AI gets more on
+    /// the harder ones.
+    pub const fn starting_happiness(&self, is_ai: bool) -> i32 {
+        match (self, is_ai) {
+            (Difficulty::Settler, false) => 15,
+            (Difficulty::Chieftain, false) => 12,
+            (_, false) => 9,
+            (Difficulty::Emperor, true) => 12,
+            (Difficulty::Deity, true) => 15,
+            (_, true) => 9,
+        }
+    }
+
+    /// Get the units a player starts the game with.
+    pub const fn starting_units(&self, is_ai: bool) -> &'static [UnitType] {
+        const BASE: &[UnitType] = &[UnitType::Settler, UnitType::Warrior];
+        match (self, is_ai) {
+            (Difficulty::Settler | Difficulty::Chieftain, false) => {
+                &[UnitType::Settler, UnitType::Warrior, UnitType::Worker]
+            }
+            (Difficulty::Emperor, true) => {
+                &[UnitType::Settler, UnitType::Warrior, UnitType::Warrior]
+            }
+            (Difficulty::Deity, true) => &[
+                UnitType::Settler,
+                UnitType::Settler,
+                UnitType::Warrior,
+                UnitType::Warrior,
+                UnitType::Worker,
+            ],
+            _ => BASE,
+        }
+    }
+
+    /// Get how aggressive barbarians are.
+    pub const fn barbarian_aggression(&self) -> BarbarianAggression {
+        match self {
+            Difficulty::Settler => BarbarianAggression::new(20, 1, false),
+            Difficulty::Chieftain => BarbarianAggression::new(15, 2, false),
+            Difficulty::Normal => BarbarianAggression::new(10, 2, true),
+            Difficulty::King => BarbarianAggression::new(8, 3, true),
+            Difficulty::Emperor => BarbarianAggression::new(6, 3, true),
+            Difficulty::Deity => BarbarianAggression::new(4, 4, true),
+        }
+    }
+
+    /// Get the modifiers that apply to a player at this difficulty.
+    ///
+    /// Difficulty bonuses apply to AI players only; humans play unmodified
+    /// apart from starting happiness and units.
+    pub const fn modifiers(&self, is_ai: bool) -> DifficultyModifiers {
+        DifficultyModifiers {
+            yield_bonus: if is_ai { self.ai_yield_bonus() } else { 0 },
+            combat_bonus: if is_ai { self.ai_combat_bonus() } else { 0 },
+            starting_happiness: self.starting_happiness(is_ai),
+        }
+    }
+}
+
+/// Per-player modifiers derived from the difficulty level.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct DifficultyModifiers {
+    /// Percentage bonus applied to all yields.
+    pub yield_bonus: i32,
+    /// Percentage bonus applied to combat strength.
+    pub combat_bonus: i32,
+    /// Happiness at the start of the game.
+    pub starting_happiness: i32,
+}
+
+impl DifficultyModifiers {
+    /// Apply the yield bonus.
+    pub fn apply_yields(&self, yields: Yields) -> Yields {
+        if self.yield_bonus == 0 {
+            return yields;
+        }
+        yields.multiply(Fixed::ONE + Fixed::from_percent(self.yield_bonus as i64))
+    }
+}
+
+/// Barbarian behavior derived from the difficulty level.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct BarbarianAggression {
+    /// Turns between new camp spawns.
+    pub spawn_interval: u32,
+    /// Units each camp may have in the field at once.
+    pub units_per_camp: u32,
+    /// Whether barbarians attack cities rather than only roaming units.
+    pub attacks_cities: bool,
+}
+
+impl BarbarianAggression {
+    const fn new(spawn_interval: u32, units_per_camp: u32, attacks_cities: bool) -> Self {
+        Self {
+            spawn_interval,
+            units_per_camp,
+            attacks_cities,
+        }
+    }
 }
 
 /// Errors from invalid game settings.
@@ -217,6 +390,7 @@ pub enum SettingsError {
     TooFewPlayers,
     TooManyPlayers,
     MapTooSmallForPlayers,
+    InvalidScenario(ScenarioError),
 }
 
 impl std::fmt::Display for SettingsError {
@@ -229,6 +403,7 @@ impl std::fmt::Display for SettingsError {
             SettingsError::MapTooSmallForPlayers => {
                 write!(f, "Map is too small for this many players")
             }
+            SettingsError::InvalidScenario(e) => write!(f, "{}", e),
         }
     }
 }
@@ -281,9 +456,15 @@ mod tests {
 
     #[test]
     fn test_game_speed_multipliers() {
-        assert_eq!(GameSpeed::Quick.production_multiplier(), 0.67);
-        assert_eq!(GameSpeed::Normal.production_multiplier(), 1.0);
-        assert_eq!(GameSpeed::Marathon.production_multiplier(), 3.0);
+        assert_eq!(
+            GameSpeed::Quick.production_multiplier(),
+            Fixed::from_percent(67)
+        );
+        assert_eq!(GameSpeed::Normal.production_multiplier(), Fixed::ONE);
+        assert_eq!(
+            GameSpeed::Marathon.production_multiplier(),
+            Fixed::from_int(3)
+        );
     }
 
     #[test]
@@ -300,4 +481,47 @@ mod tests {
         assert_eq!(restored.name, settings.name);
         assert_eq!(restored.map_size, settings.map_size);
     }
+
+    #[test]
+    fn test_difficulty_modifiers_apply_to_ai_only() {
+        let human = Difficulty::Deity.modifiers(false);
+        assert_eq!(human.yield_bonus, 0);
+        assert_eq!(human.combat_bonus, 0);
+
+        let ai = Difficulty::Deity.modifiers(true);
+        assert_eq!(ai.yield_bonus, 50);
+        assert_eq!(ai.combat_bonus, 20);
+        assert_eq!(
+            ai.apply_yields(Yields::new(2, 4, 0, 0, 0)),
+            Yields::new(3, 6, 0, 0, 0)
+        );
+    }
+
+    #[test]
+    fn test_difficulty_starting_conditions() {
+        assert_eq!(
+            Difficulty::Normal.starting_units(false),
+            &[UnitType::Settler, UnitType::Warrior]
+        );
+        assert!(Difficulty::Settler
+            .starting_units(false)
+            .contains(&UnitType::Worker));
+        assert!(
+            Difficulty::Deity.starting_units(true).len()
+                > Difficulty::Deity.starting_units(false).len()
+        );
+        assert!(
+            Difficulty::Settler.starting_happiness(false)
+                > Difficulty::Deity.starting_happiness(false)
+        );
+    }
+
+    #[test]
+    fn test_barbarians_scale_with_difficulty() {
+        let easy = Difficulty::Settler.barbarian_aggression();
+        let hard = Difficulty::Deity.barbarian_aggression();
+        assert!(easy.spawn_interval > hard.spawn_interval);
+        assert!(!easy.attacks_cities);
+        assert!(hard.attacks_cities);
+    }
 }
@@ -0,0 +1,319 @@
+//! City capture and what becomes of a conquered city.
+//!
+//! A melee unit that brings a city to zero health captures it. The city
+//! changes hands at once: it loses half its population, its defenses and
+//! some of its other buildings are destroyed, its production is lost and
+//! its territory passes to the captor. The captor then decides the city's
+//! fate with a [`CaptureChoice`]:
+//!
+//! - **Raze**: the city is destroyed and its territory released. Original
+//!   capitals can't be razed.
+//! - **Annex**: the city is kept as a normal city.
+//! - **Puppet**: the city is kept, but its owner can't direct production.
+//!
+//! Which buildings fall follows fixed rules rather than chance (see
+//! [`destroyed_buildings`]), so every peer ends up with the same city.
+
+use crate::city::{BuildingType, City};
+use crate::game_state::GameState;
+use crate::hex::HexCoord;
+use crate::types::{CityId, PlayerSlot, UnitId};
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+use ts_rs::TS;
+
+/// Health a captured city is left with, as a percentage of its maximum.
+pub const CAPTURED_CITY_HEALTH_PERCENT: u32 = 25;
+
+/// What the captor does with a captured city.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash, Serialize, Deserialize, JsonSchema, TS)]
+pub enum CaptureChoice {
+    /// Destroy the city and release its territory.
+    Raze,
+    /// Keep the city as a normal city.
+    Annex,
+    /// Keep the city without control over its production.
+    Puppet,
+}
+
+/// What capturing a city did to it.
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct CityCapture {
+    pub city_id: CityId,
+    pub previous_owner: PlayerSlot,
+    pub new_owner: PlayerSlot,
+    pub population_lost: u32,
+    /// Destroyed buildings, in [`destroyed_buildings`] order.
+    pub buildings_destroyed: Vec<BuildingType>,
+    /// Units of the previous owner lost with the city, by ID.
+    pub units_lost: Vec<UnitId>,
+    /// Tiles that passed to the new owner.
+    pub tiles_transferred: Vec<HexCoord>,
+}
+
+/// Buildings destroyed when a city is captured.
+///
+/// Walls and castles always fall. The other buildings are sorted by name
+/// and every other one is destroyed, starting with the first, so a city
+/// loses half of them rounded up.
+pub fn destroyed_buildings(city: &City) -> Vec<BuildingType> {
+    let is_defense = |b: &BuildingType| matches!(b, BuildingType::Walls | BuildingType::Castle);
+
+    let mut defenses: Vec<BuildingType> =
+        city.buildings.iter().copied().filter(is_defense).collect();
+    defenses.sort_by_key(|b| format!("{:?}", b));
+    let mut others: Vec<BuildingType> = city
+        .buildings
+        .iter()
+        .copied()
+        .filter(|b| !is_defense(b))
+        .collect();
+    others.sort_by_key(|b| format!("{:?}", b));
+
+    defenses.extend(others.into_iter().step_by(2));
+    defenses
+}
+
+/// Hand a city to the player who captured it.
+///
+/// The previous owner's units on the city tile are lost; the capturing
+/// unit is moved in by the caller. Returns `None` if the city doesn't
+/// exist or already belongs to the captor.
+pub fn capture_city(
+    state: &mut GameState,
+    city_id: CityId,
+    captor: PlayerSlot,
+) -> Option<CityCapture> {
+    let city = state.cities.get_mut(&city_id)?;
+    if city.owner == captor {
+        return None;
+    }
+    let previous_owner = city.owner;
+
+    let population_lost = city.population / 2;
+    city.population -= population_lost;
+    city.food_stored = 0;
+
+    let buildings_destroyed = destroyed_buildings(city);
+    for building in &buildings_destroyed {
+        city.remove_building(*building);
+    }
+    city.health = city.max_health * CAPTURED_CITY_HEALTH_PERCENT / 100;
+
+    city.owner = captor;
+    city.production = None;
+    city.production_progress = 0;
+    city.production_queue.clear();
+    city.specialists = Default::default();
+    city.worked_tiles.clear();
+    city.worked_tiles.insert(city.position);
+    city.puppet = false;
+    city.awaiting_capture_choice = true;
+
+    let position = city.position;
+    let mut tiles_transferred: Vec<HexCoord> = city
+        .territory
+        .iter()
+        .copied()
+        .filter(|coord| {
+            state
+                .map
+                .get(coord)
+                .is_some_and(|tile| tile.city_id == Some(city_id))
+        })
+        .collect();
+    tiles_transferred.sort_unstable_by_key(|c| (c.q, c.r));
+    for coord in &tiles_transferred {
+        if let Some(tile) = state.map.get_mut(coord) {
+            tile.owner = Some(captor);
+        }
+    }
+
+    let mut units_lost: Vec<UnitId> = state
+        .units
+        .values()
+        .filter(|u| u.owner == previous_owner && u.position == position)
+        .map(|u| u.id)
+        .collect();
+    units_lost.sort_unstable();
+    for unit_id in &units_lost {
+        state.units.remove(unit_id);
+    }
+
+    if let Some(player) = state.get_player_mut(previous_owner) {
+        if player.capital == Some(city_id) {
+            player.capital = None;
+        }
+    }
+
+    Some(CityCapture {
+        city_id,
+        previous_owner,
+        new_owner: captor,
+        population_lost,
+        buildings_destroyed,
+        units_lost,
+        tiles_transferred,
+    })
+}
+
+/// Apply the captor's choice for a captured city.
+///
+/// Returns `false` if the city doesn't exist or isn't awaiting a choice.
+pub fn resolve_capture(state: &mut GameState, city_id: CityId, choice: CaptureChoice) -> bool {
+    let Some(city) = state.cities.get_mut(&city_id) else {
+        return false;
+    };
+    if !city.awaiting_capture_choice {
+        return false;
+    }
+    city.awaiting_capture_choice = false;
+
+    match choice {
+        CaptureChoice::Annex => city.puppet = false,
+        CaptureChoice::Puppet => city.puppet = true,
+        CaptureChoice::Raze => {
+            let Some(city) = state.cities.remove(&city_id) else {
+                return false;
+            };
+            for coord in &city.territory {
+                if let Some(tile) = state.map.get_mut(coord) {
+                    if tile.city_id == Some(city_id) {
+                        tile.owner = None;
+                        tile.city_id = None;
+                    }
+                }
+            }
+        }
+    }
+    true
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::map::Map;
+    use crate::player::{Civilization, Player};
+    use crate::settings::GameSettings;
+    use crate::terrain::Terrain;
+    use crate::types::{GameId, Npub};
+    use crate::unit::{Unit, UnitType};
+
+    fn create_state() -> GameState {
+        let mut state = GameState::new(
+            GameId::new("test"),
+            GameSettings::new("Test".to_string()),
+            [0; 32],
+        );
+        for id in 0..2 {
+            state
+                .add_player(Player::new(
+                    PlayerSlot(id),
+                    Npub(format!("pk{}", id)),
+                    format!("P{}", id),
+                    Civilization::default(),
+                ))
+                .unwrap();
+        }
+        state.map = Map::filled(10, 10, Terrain::Grassland);
+
+        let mut city = City::new(
+            CityId(1),
+            PlayerSlot(1),
+            "Thebes".to_string(),
+            HexCoord::new(4, 4),
+            true,
+        );
+        city.population = 5;
+        for building in [
+            BuildingType::Walls,
+            BuildingType::Monument,
+            BuildingType::Granary,
+            BuildingType::Library,
+        ] {
+            city.add_building(building);
+        }
+        for coord in &city.territory {
+            let tile = state.map.get_mut(coord).unwrap();
+            tile.owner = Some(PlayerSlot(1));
+            tile.city_id = Some(CityId(1));
+        }
+        state.players[1].capital = Some(CityId(1));
+        state.cities.insert(CityId(1), city);
+        state.units.insert(
+            UnitId(7),
+            Unit::new(
+                UnitId(7),
+                PlayerSlot(1),
+                UnitType::Worker,
+                HexCoord::new(4, 4),
+            ),
+        );
+        state
+    }
+
+    #[test]
+    fn test_destroyed_buildings_are_deterministic() {
+        let state = create_state();
+        assert_eq!(
+            destroyed_buildings(&state.cities[&CityId(1)]),
+            [
+                BuildingType::Walls,
+                BuildingType::Granary,
+                BuildingType::Monument
+            ]
+        );
+    }
+
+    #[test]
+    fn test_capture_city() {
+        let mut state = create_state();
+        let capture = capture_city(&mut state, CityId(1), PlayerSlot(0)).unwrap();
+
+        assert_eq!(capture.previous_owner, PlayerSlot(1));
+        assert_eq!(capture.population_lost, 2);
+        assert_eq!(capture.units_lost, [UnitId(7)]);
+        assert_eq!(capture.tiles_transferred.len(), 7);
+
+        let city = &state.cities[&CityId(1)];
+        assert_eq!(city.owner, PlayerSlot(0));
+        assert_eq!(city.population, 3);
+        assert_eq!(city.buildings.len(), 1);
+        assert!(city.buildings.contains(&BuildingType::Library));
+        // Walls are gone, along with their health and strength
+        assert_eq!(city.max_health, 200);
+        assert_eq!(city.health, 50);
+        assert!(city.awaiting_capture_choice);
+        assert!(capture
+            .tiles_transferred
+            .iter()
+            .all(|c| state.map.get(c).unwrap().owner == Some(PlayerSlot(0))));
+        assert!(state.units.is_empty());
+        assert_eq!(state.players[1].capital, None);
+
+        // Capturing your own city does nothing
+        assert!(capture_city(&mut state, CityId(1), PlayerSlot(0)).is_none());
+    }
+
+    #[test]
+    fn test_resolve_capture() {
+        let mut state = create_state();
+        capture_city(&mut state, CityId(1), PlayerSlot(0)).unwrap();
+        assert!(resolve_capture(
+            &mut state,
+            CityId(1),
+            CaptureChoice::Puppet
+        ));
+        assert!(state.cities[&CityId(1)].puppet);
+        assert!(!state.cities[&CityId(1)].awaiting_capture_choice);
+        // The choice is made once
+        assert!(!resolve_capture(&mut state, CityId(1), CaptureChoice::Raze));
+
+        let mut state = create_state();
+        capture_city(&mut state, CityId(1), PlayerSlot(0)).unwrap();
+        assert!(resolve_capture(&mut state, CityId(1), CaptureChoice::Raze));
+        assert!(state.cities.is_empty());
+        let tile = state.map.get(&HexCoord::new(4, 4)).unwrap();
+        assert_eq!((tile.owner, tile.city_id), (None, None));
+    }
+}
@@ -0,0 +1,363 @@
+//! State snapshots for event chain compaction.
+//!
+//! Replaying a long game from its first event gets slower every turn. Every
+//! few turns the engine can capture a [`StateSnapshot`]: the full game state
+//! as compressed JSON plus its audit hash. Snapshots are published in the
+//! event chain like any other action. Peers replaying the full chain check
+//! each snapshot against their own state, and new peers can skip straight
+//! to the latest snapshot and replay only the events after it.
+//!
+//! A snapshot also commits to the actions since the previous one with a
+//! Merkle root (see [`crate::merkle`]), so light clients that only keep
+//! snapshots can still check that a given event is part of the game.
+
+use crate::audit;
+use crate::events::{GameAction, GameEvent};
+use crate::game_state::GameState;
+use crate::merkle::{EventsRoot, MerkleProof};
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+use ts_rs::TS;
+
+/// Turns between snapshots unless configured otherwise.
+pub const DEFAULT_SNAPSHOT_INTERVAL: u32 = 25;
+
+/// Shortest back-reference worth encoding.
+const MIN_MATCH: usize = 4;
+
+/// Longest back-reference a single token can encode.
+const MAX_MATCH: usize = MIN_MATCH + 0x7f;
+
+/// Longest run of literals a single token can encode.
+const MAX_LITERALS: usize = 0x80;
+
+/// How far back a match may start.
+const WINDOW: usize = u16::MAX as usize;
+
+/// Number of bits used to index the match finder's hash table.
+const HASH_BITS: u32 = 15;
+
+/// A compressed copy of the game state at the start of a turn.
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize, JsonSchema, TS)]
+pub struct StateSnapshot {
+    /// Turn the snapshot was taken on.
+    pub turn: u32,
+    /// Audit hash of the state (see [`audit::state_hash`]).
+    pub state_hash: u64,
+    /// Size of the uncompressed state JSON in bytes.
+    pub original_size: u32,
+    /// Hex-encoded compressed state JSON.
+    pub data: String,
+    /// Root of the actions executed since the previous snapshot, if the
+    /// snapshotting peer saw all of them.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub events_root: Option<EventsRoot>,
+}
+
+impl StateSnapshot {
+    /// Capture a snapshot of a game state.
+    pub fn capture(state: &GameState) -> Self {
+        let json = serde_json::to_vec(state).expect("game state serializes to JSON");
+        Self {
+            turn: state.turn,
+            state_hash: audit::state_hash(state),
+            original_size: json.len() as u32,
+            data: to_hex(&compress(&json)),
+            events_root: None,
+        }
+    }
+
+    /// Commit to the actions executed since the previous snapshot.
+    pub fn with_events_root(mut self, events_root: Option<EventsRoot>) -> Self {
+        self.events_root = events_root;
+        self
+    }
+
+    /// Check that an event is one of the actions this snapshot commits to.
+    pub fn contains(&self, event: &GameEvent, proof: &MerkleProof) -> bool {
+        self.events_root
+            .as_ref()
+            .is_some_and(|root| root.contains(event, proof))
+    }
+
+    /// Decode the snapshot and verify it against its hash.
+    pub fn restore(&self) -> Result<GameState, SnapshotError> {
+        let compressed = from_hex(&self.data).ok_or(SnapshotError::InvalidEncoding)?;
+        let json = decompress(&compressed).ok_or(SnapshotError::Corrupt)?;
+        if json.len() != self.original_size as usize {
+            return Err(SnapshotError::Corrupt);
+        }
+        let state: GameState = serde_json::from_slice(&json)
+            .map_err(|e| SnapshotError::InvalidState(e.to_string()))?;
+        self.verify(&state)?;
+        Ok(state)
+    }
+
+    /// Check that a state matches this snapshot.
+    pub fn verify(&self, state: &GameState) -> Result<(), SnapshotError> {
+        let actual = audit::state_hash(state);
+        if state.turn != self.turn || actual != self.state_hash {
+            return Err(SnapshotError::HashMismatch {
+                expected: self.state_hash,
+                actual,
+            });
+        }
+        Ok(())
+    }
+
+    /// Size of the encoded snapshot data in bytes.
+    pub fn encoded_size(&self) -> usize {
+        self.data.len()
+    }
+}
+
+/// Why a snapshot could not be used.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum SnapshotError {
+    /// The data is not valid hex.
+    InvalidEncoding,
+    /// The compressed data is truncated or malformed.
+    Corrupt,
+    /// The decompressed data is not a valid game state.
+    InvalidState(String),
+    /// The state does not match the snapshot's hash.
+    HashMismatch { expected: u64, actual: u64 },
+    /// The actions since the previous snapshot don't match its events root.
+    EventsRootMismatch,
+}
+
+impl std::fmt::Display for SnapshotError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            SnapshotError::InvalidEncoding => write!(f, "Snapshot data is not valid hex"),
+            SnapshotError::Corrupt => write!(f, "Snapshot data is corrupt"),
+            SnapshotError::InvalidState(msg) => write!(f, "Invalid snapshot state: {}", msg),
+            SnapshotError::HashMismatch { expected, actual } => write!(
+                f,
+                "Snapshot hash mismatch: expected {:016x}, got {:016x}",
+                expected, actual
+            ),
+            SnapshotError::EventsRootMismatch => {
+                write!(f, "Snapshot events root doesn't match the chain")
+            }
+        }
+    }
+}
+
+impl std::error::Error for SnapshotError {}
+
+/// Check if a snapshot should be taken at the start of a turn.
+///
+/// An interval of zero disables snapshots.
+pub fn snapshot_due(turn: u32, interval: u32) -> bool {
+    interval > 0 && turn > 0 && turn.is_multiple_of(interval)
+}
+
+/// Find the latest snapshot in an event list and its index.
+pub fn latest_snapshot(events: &[GameEvent]) -> Option<(usize, &StateSnapshot)> {
+    events
+        .iter()
+        .enumerate()
+        .rev()
+        .find_map(|(i, event)| match &event.action {
+            GameAction::Snapshot { snapshot } => Some((i, snapshot)),
+            _ => None,
+        })
+}
+
+/// Drop every event before the latest snapshot.
+///
+/// The result is all a new peer needs to rebuild the current state. Chains
+/// without a snapshot are returned unchanged.
+pub fn compact_events(events: &[GameEvent]) -> &[GameEvent] {
+    match latest_snapshot(events) {
+        Some((index, _)) => &events[index..],
+        None => events,
+    }
+}
+
+/// Compress bytes with a small LZ77 variant.
+///
+/// The output is a sequence of tokens. A control byte below `0x80` is
+/// followed by that many plus one literal bytes. A control byte of `0x80`
+/// or above is a match of `control - 0x80 + MIN_MATCH` bytes starting a
+/// little-endian `u16` offset back.
+fn compress(input: &[u8]) -> Vec<u8> {
+    let mut out = Vec::with_capacity(input.len() / 2);
+    let mut table = vec![usize::MAX; 1 << HASH_BITS];
+    let mut literals_start = 0;
+    let mut i = 0;
+
+    while i + MIN_MATCH <= input.len() {
+        let slot = hash(&input[i..i + MIN_MATCH]);
+        let candidate = table[slot];
+        table[slot] = i;
+
+        let matched = candidate != usize::MAX
+            && i - candidate <= WINDOW
+            && input[candidate..candidate + MIN_MATCH] == input[i..i + MIN_MATCH];
+        if !matched {
+            i += 1;
+            continue;
+        }
+
+        let mut length = MIN_MATCH;
+        while length < MAX_MATCH && i + length < input.len() {
+            if input[candidate + length] != input[i + length] {
+                break;
+            }
+            length += 1;
+        }
+
+        push_literals(&mut out, &input[literals_start..i]);
+        out.push(0x80 | (length - MIN_MATCH) as u8);
+        out.extend_from_slice(&((i - candidate) as u16).to_le_bytes());
+        i += length;
+        literals_start = i;
+    }
+
+    push_literals(&mut out, &input[literals_start..]);
+    out
+}
+
+fn push_literals(out: &mut Vec<u8>, literals: &[u8]) {
+    for chunk in literals.chunks(MAX_LITERALS) {
+        out.push((chunk.len() - 1) as u8);
+        out.extend_from_slice(chunk);
+    }
+}
+
+fn hash(bytes: &[u8]) -> usize {
+    let value = u32::from_le_bytes([bytes[0], bytes[1], bytes[2], bytes[3]]);
+    (value.wrapping_mul(2_654_435_761) >> (32 - HASH_BITS)) as usize
+}
+
+/// Reverse [`compress`], returning `None` for malformed input.
+fn decompress(input: &[u8]) -> Option<Vec<u8>> {
+    let mut out = Vec::with_capacity(input.len() * 4);
+    let mut i = 0;
+    while i < input.len() {
+        let control = input[i] as usize;
+        i += 1;
+        if control < 0x80 {
+            let end = i + control + 1;
+            out.extend_from_slice(input.get(i..end)?);
+            i = end;
+        } else {
+            let length = control - 0x80 + MIN_MATCH;
+            let offset = u16::from_le_bytes([*input.get(i)?, *input.get(i + 1)?]) as usize;
+            i += 2;
+            if offset == 0 || offset > out.len() {
+                return None;
+            }
+            let start = out.len() - offset;
+            for j in 0..length {
+                out.push(out[start + j]);
+            }
+        }
+    }
+    Some(out)
+}
+
+fn to_hex(bytes: &[u8]) -> String {
+    bytes.iter().map(|b| format!("{:02x}", b)).collect()
+}
+
+fn from_hex(s: &str) -> Option<Vec<u8>> {
+    if !s.len().is_multiple_of(2) {
+        return None;
+    }
+    (0..s.len())
+        .step_by(2)
+        .map(|i| u8::from_str_radix(s.get(i..i + 2)?, 16).ok())
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::map::Map;
+    use crate::settings::GameSettings;
+    use crate::terrain::Terrain;
+    use crate::types::GameId;
+
+    fn sample_state() -> GameState {
+        let mut state = GameState::new(
+            GameId::new("test"),
+            GameSettings::new("Test".to_string()),
+            [7u8; 32],
+        );
+        state.map = Map::filled(20, 20, Terrain::Grassland);
+        state.turn = 25;
+        state
+    }
+
+    // ==================== Compression Tests ====================
+
+    #[test]
+    fn test_compress_round_trip() {
+        let inputs: [&[u8]; 4] = [
+            b"",
+            b"abc",
+            b"aaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaa",
+            b"{\"terrain\":\"Grassland\"},{\"terrain\":\"Grassland\"},{\"terrain\":\"Plains\"}",
+        ];
+        for input in inputs {
+            assert_eq!(decompress(&compress(input)).unwrap(), input);
+        }
+
+        let noise: Vec<u8> = (0..5000u32).map(|i| (i * 7919 % 251) as u8).collect();
+        assert_eq!(decompress(&compress(&noise)).unwrap(), noise);
+    }
+
+    #[test]
+    fn test_decompress_rejects_bad_offset() {
+        assert!(decompress(&[0x80, 0x05, 0x00]).is_none());
+        assert!(decompress(&[0x03, b'a']).is_none());
+    }
+
+    #[test]
+    fn test_hex_round_trip() {
+        let bytes = [0u8, 1, 0x7f, 0xff];
+        assert_eq!(from_hex(&to_hex(&bytes)).unwrap(), bytes);
+        assert!(from_hex("abc").is_none());
+        assert!(from_hex("zz").is_none());
+    }
+
+    // ==================== Snapshot Tests ====================
+
+    #[test]
+    fn test_snapshot_restores_state() {
+        let state = sample_state();
+        let snapshot = StateSnapshot::capture(&state);
+        assert!(snapshot.encoded_size() < snapshot.original_size as usize);
+
+        let restored = snapshot.restore().unwrap();
+        assert_eq!(audit::state_hash(&restored), audit::state_hash(&state));
+        assert_eq!(restored.map.tiles.len(), state.map.tiles.len());
+    }
+
+    #[test]
+    fn test_snapshot_detects_tampering() {
+        let state = sample_state();
+        let mut snapshot = StateSnapshot::capture(&state);
+        snapshot.state_hash ^= 1;
+        assert!(matches!(
+            snapshot.restore(),
+            Err(SnapshotError::HashMismatch { .. })
+        ));
+
+        let mut snapshot = StateSnapshot::capture(&state);
+        snapshot.data.truncate(snapshot.data.len() - 2);
+        assert!(snapshot.restore().is_err());
+    }
+
+    #[test]
+    fn test_snapshot_due() {
+        assert!(!snapshot_due(0, 25));
+        assert!(!snapshot_due(24, 25));
+        assert!(snapshot_due(25, 25));
+        assert!(snapshot_due(50, 25));
+        assert!(!snapshot_due(25, 0));
+    }
+}
@@ -0,0 +1,293 @@
+//! Strategic resource stockpiles.
+//!
+//! Every turn, each strategic resource inside a player's borders that has
+//! the right improvement (a mine for iron, coal and uranium, a pasture for
+//! horses, an oil well for oil) adds [`SOURCE_YIELD`] to the player's
+//! stockpile, once they have the technology that reveals it. Units such as
+//! knights and tanks consume stockpiled resources when they are completed;
+//! a city that can't pay holds the finished unit until it can.
+//!
+//! With [`GameSettings::resource_depletion`](crate::settings::GameSettings)
+//! enabled, mines and oil wells can run dry. Each source has a
+//! [`DEPLETION_CHANCE_PERMILLE`] chance per turn, rolled by hashing the
+//! game seed with the turn and tile, so every peer depletes the same tiles.
+
+use crate::audit::{fnv1a, FNV_OFFSET};
+use crate::city::ProductionItem;
+use crate::game_state::GameState;
+use crate::hex::HexCoord;
+use crate::player::Player;
+use crate::technology::TechTree;
+use crate::terrain::{Improvement, Resource, ResourceCategory};
+use crate::types::PlayerSlot;
+use crate::unit::UnitType;
+use std::collections::BTreeMap;
+
+/// Resources added to a stockpile per improved source per turn.
+pub const SOURCE_YIELD: u32 = 2;
+
+/// Most of one resource a player can stockpile.
+pub const MAX_STOCKPILE: u32 = 60;
+
+/// Chance in a thousand that an improved mine or oil well runs dry each
+/// turn, when depletion is enabled.
+pub const DEPLETION_CHANCE_PERMILLE: u64 = 10;
+
+/// Strategic resources a unit consumes when it is completed.
+pub const fn unit_resource_cost(unit_type: UnitType) -> Option<(Resource, u32)> {
+    match unit_type {
+        UnitType::Chariot | UnitType::Horseman | UnitType::Knight => Some((Resource::Horses, 10)),
+        UnitType::Lancer | UnitType::Cavalry => Some((Resource::Horses, 15)),
+        UnitType::Swordsman | UnitType::Longswordsman | UnitType::Trebuchet => {
+            Some((Resource::Iron, 10))
+        }
+        UnitType::Frigate => Some((Resource::Iron, 15)),
+        UnitType::Ironclad => Some((Resource::Coal, 15)),
+        UnitType::Tank | UnitType::Battleship | UnitType::Fighter | UnitType::Bomber => {
+            Some((Resource::Oil, 20))
+        }
+        _ => None,
+    }
+}
+
+/// Strategic resources a production item consumes when it is completed.
+pub fn item_resource_cost(item: &ProductionItem) -> Option<(Resource, u32)> {
+    match item {
+        ProductionItem::Unit(unit_type) => unit_resource_cost(*unit_type),
+        _ => None,
+    }
+}
+
+/// Improvement that extracts a strategic resource.
+pub const fn source_improvement(resource: Resource) -> Option<Improvement> {
+    match resource {
+        Resource::Iron | Resource::Coal | Resource::Uranium => Some(Improvement::Mine),
+        Resource::Horses => Some(Improvement::Pasture),
+        Resource::Oil => Some(Improvement::OilWell),
+        _ => None,
+    }
+}
+
+/// Ability that reveals a strategic resource.
+pub const fn reveal_ability(resource: Resource) -> &'static str {
+    match resource {
+        Resource::Horses => "reveal_horses",
+        Resource::Coal => "reveal_coal",
+        Resource::Oil => "reveal_oil",
+        Resource::Uranium => "reveal_uranium",
+        _ => "reveal_iron",
+    }
+}
+
+/// Whether a player has enough stockpiled to complete an item.
+pub fn can_afford(player: &Player, item: &ProductionItem) -> bool {
+    item_resource_cost(item).is_none_or(|(resource, amount)| player.stockpiled(resource) >= amount)
+}
+
+/// Take the resources an item costs from a player's stockpile.
+///
+/// Returns `false`, taking nothing, if the player can't afford it.
+pub fn consume(player: &mut Player, item: &ProductionItem) -> bool {
+    let Some((resource, amount)) = item_resource_cost(item) else {
+        return true;
+    };
+    match player.stockpile.get_mut(&resource) {
+        Some(stock) if *stock >= amount => {
+            *stock -= amount;
+            true
+        }
+        _ => false,
+    }
+}
+
+/// A strategic resource that ran dry.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct Depletion {
+    pub coord: HexCoord,
+    pub resource: Resource,
+}
+
+/// Result of a player's resource turn.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct StockpileReport {
+    /// Resources added to the stockpile.
+    pub gained: BTreeMap<Resource, u32>,
+    /// Sources that ran dry this turn.
+    pub depleted: Vec<Depletion>,
+}
+
+/// Improved strategic sources inside a player's borders that they can
+/// see, in coordinate order.
+pub fn player_sources(state: &GameState, player_id: PlayerSlot) -> Vec<(HexCoord, Resource)> {
+    let Some(player) = state.get_player(player_id) else {
+        return Vec::new();
+    };
+    let tree = TechTree::new();
+    let mut sources: Vec<(HexCoord, Resource)> = state
+        .map
+        .tiles
+        .values()
+        .filter(|tile| tile.owner == Some(player_id))
+        .filter_map(|tile| {
+            let resource = tile.resource?;
+            let extracted = resource.category() == ResourceCategory::Strategic
+                && tile.improvement.is_some()
+                && tile.improvement == source_improvement(resource)
+                && tree.has_ability(&player.technologies, reveal_ability(resource));
+            extracted.then_some((tile.coord, resource))
+        })
+        .collect();
+    sources.sort_unstable_by_key(|(coord, _)| (coord.q, coord.r));
+    sources
+}
+
+/// Add a turn's output from a player's sources to their stockpile, then
+/// roll for depletion if the game has it enabled.
+pub fn collect_resources(state: &mut GameState, player_id: PlayerSlot) -> StockpileReport {
+    let mut report = StockpileReport::default();
+    let sources = player_sources(state, player_id);
+    let Some(player) = state.get_player_mut(player_id) else {
+        return report;
+    };
+    for &(_, resource) in &sources {
+        let stock = player.stockpile.entry(resource).or_insert(0);
+        let gained = SOURCE_YIELD.min(MAX_STOCKPILE.saturating_sub(*stock));
+        *stock += gained;
+        if gained > 0 {
+            *report.gained.entry(resource).or_insert(0) += gained;
+        }
+    }
+
+    if !state.settings.resource_depletion {
+        return report;
+    }
+    let mut turn_seed = fnv1a(FNV_OFFSET, &state.seed);
+    turn_seed = fnv1a(turn_seed, b"depletion");
+    turn_seed = fnv1a(turn_seed, &state.turn.to_le_bytes());
+    for (coord, resource) in sources {
+        // Pastures renew themselves; only mines and wells run dry
+        if source_improvement(resource) == Some(Improvement::Pasture) {
+            continue;
+        }
+        let mut roll = fnv1a(turn_seed, &coord.q.to_le_bytes());
+        roll = fnv1a(roll, &coord.r.to_le_bytes());
+        if roll % 1000 >= DEPLETION_CHANCE_PERMILLE {
+            continue;
+        }
+        if let Some(tile) = state.map.get_mut(&coord) {
+            tile.resource = None;
+        }
+        report.depleted.push(Depletion { coord, resource });
+    }
+    report
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::map::Map;
+    use crate::player::Civilization;
+    use crate::settings::GameSettings;
+    use crate::terrain::Terrain;
+    use crate::types::{GameId, Npub};
+
+    fn mining_game() -> GameState {
+        let settings = GameSettings::new("Test".to_string());
+        let mut state = GameState::new(GameId::new("test"), settings, [5u8; 32]);
+        state.map = Map::filled(40, 40, Terrain::Plains);
+        state
+            .add_player(Player::new(
+                PlayerSlot(0),
+                Npub::new("pk0"),
+                "P0".to_string(),
+                Civilization::default(),
+            ))
+            .unwrap();
+        state.players[0]
+            .technologies
+            .extend(["mining", "bronze_working", "iron_working"].map(str::to_string));
+        for q in 0..20 {
+            let tile = state.map.get_mut(&HexCoord::new(q, 0)).unwrap();
+            tile.owner = Some(PlayerSlot(0));
+            tile.resource = Some(Resource::Iron);
+            tile.improvement = Some(Improvement::Mine);
+        }
+        state.turn = 1;
+        state
+    }
+
+    #[test]
+    fn test_sources_fill_stockpile() {
+        let mut state = mining_game();
+        // Unimproved and foreign sources don't count
+        state.map.get_mut(&HexCoord::new(0, 0)).unwrap().improvement = None;
+        state.map.get_mut(&HexCoord::new(1, 0)).unwrap().owner = Some(PlayerSlot(1));
+
+        let report = collect_resources(&mut state, PlayerSlot(0));
+        assert_eq!(
+            report.gained.get(&Resource::Iron),
+            Some(&(18 * SOURCE_YIELD))
+        );
+        assert_eq!(
+            state.players[0].stockpiled(Resource::Iron),
+            18 * SOURCE_YIELD
+        );
+        assert!(report.depleted.is_empty());
+
+        collect_resources(&mut state, PlayerSlot(0));
+        assert_eq!(state.players[0].stockpiled(Resource::Iron), MAX_STOCKPILE);
+
+        // Hidden until the player can see it
+        state.players[0].technologies.remove("iron_working");
+        assert!(player_sources(&state, PlayerSlot(0)).is_empty());
+    }
+
+    #[test]
+    fn test_units_consume_stockpile() {
+        let mut player = Player::new(
+            PlayerSlot(0),
+            Npub::new("pk"),
+            "P".to_string(),
+            Civilization::default(),
+        );
+        let knight = ProductionItem::Unit(UnitType::Knight);
+        let warrior = ProductionItem::Unit(UnitType::Warrior);
+        assert!(can_afford(&player, &warrior));
+        assert!(!can_afford(&player, &knight));
+        assert!(!consume(&mut player, &knight));
+
+        player.stockpile.insert(Resource::Horses, 12);
+        assert!(consume(&mut player, &knight));
+        assert_eq!(player.stockpiled(Resource::Horses), 2);
+        assert!(consume(&mut player, &warrior));
+    }
+
+    #[test]
+    fn test_depletion_is_deterministic() {
+        let mut a = mining_game();
+        a.settings.resource_depletion = true;
+        let mut b = a.clone();
+
+        let mut depleted = Vec::new();
+        for turn in 1..=20 {
+            a.turn = turn;
+            b.turn = turn;
+            let report = collect_resources(&mut a, PlayerSlot(0));
+            assert_eq!(report, collect_resources(&mut b, PlayerSlot(0)));
+            depleted.extend(report.depleted);
+        }
+        // 20 mines over 20 turns at 1% should run at least one dry
+        assert!(!depleted.is_empty());
+        for depletion in depleted {
+            assert_eq!(a.map.get(&depletion.coord).unwrap().resource, None);
+        }
+
+        let mut off = mining_game();
+        for turn in 1..=20 {
+            off.turn = turn;
+            assert!(collect_resources(&mut off, PlayerSlot(0))
+                .depleted
+                .is_empty());
+        }
+    }
+}
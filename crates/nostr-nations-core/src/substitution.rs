@@ -0,0 +1,111 @@
+//! Substitute players taking over abandoned seats.
+//!
+//! When a player stops playing, the host can hand their civilization to a
+//! new key with a [`GameAction::SubstitutePlayer`] event. The substitute
+//! syncs the event chain like any joining peer, and from the substitution
+//! onwards only their key controls the seat. To stop a host from taking a
+//! seat away from someone who is merely slow, the original player must
+//! have been inactive for the game's `substitute_after_turns`.
+//!
+//! [`GameAction::SubstitutePlayer`]: crate::events::GameAction::SubstitutePlayer
+
+use crate::game_state::GameState;
+use crate::types::{Npub, PlayerSlot};
+
+/// Default number of turns a player must be inactive before a substitute
+/// can take over.
+pub const DEFAULT_SUBSTITUTE_AFTER_TURNS: u32 = 3;
+
+pub(crate) fn default_substitute_after_turns() -> u32 {
+    DEFAULT_SUBSTITUTE_AFTER_TURNS
+}
+
+/// Result of a substitution.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Substitution {
+    /// The seat that changed hands.
+    pub player_id: PlayerSlot,
+    /// Key that controlled the seat before.
+    pub previous_pubkey: Npub,
+    /// Key that controls the seat now.
+    pub pubkey: Npub,
+}
+
+/// Number of turns since a player last acted.
+pub fn turns_absent(state: &GameState, player_id: PlayerSlot) -> Option<u32> {
+    state
+        .get_player(player_id)
+        .map(|p| state.turn.saturating_sub(p.last_active_turn))
+}
+
+/// Hand a seat to a new key, optionally renaming it.
+///
+/// The caller is responsible for checking that the substitution is
+/// allowed.
+pub fn substitute(
+    state: &mut GameState,
+    player_id: PlayerSlot,
+    pubkey: Npub,
+    name: Option<String>,
+) -> Option<Substitution> {
+    let turn = state.turn;
+    let player = state.get_player_mut(player_id)?;
+    let previous_pubkey = std::mem::replace(&mut player.pubkey, pubkey.clone());
+    player.previous_pubkeys.push(previous_pubkey.clone());
+    if let Some(name) = name {
+        player.name = name;
+    }
+    // The substitute gets a full grace period before they can be replaced
+    player.last_active_turn = turn;
+
+    Some(Substitution {
+        player_id,
+        previous_pubkey,
+        pubkey,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::player::{Civilization, Player};
+    use crate::settings::GameSettings;
+    use crate::types::GameId;
+
+    fn create_game() -> GameState {
+        let settings = GameSettings::new("Test".to_string());
+        let mut state = GameState::new(GameId::new("game1"), settings, [0u8; 32]);
+        for id in 0..2 {
+            state.players.push(Player::new(
+                PlayerSlot(id),
+                Npub(format!("npub{}", id)),
+                format!("Player {}", id),
+                Civilization::generic(),
+            ));
+        }
+        state
+    }
+
+    #[test]
+    fn test_substitute_transfers_seat() {
+        let mut state = create_game();
+        state.turn = 7;
+        state.players[1].last_active_turn = 2;
+        assert_eq!(turns_absent(&state, PlayerSlot(1)), Some(5));
+
+        let substitution = substitute(
+            &mut state,
+            PlayerSlot(1),
+            Npub::new("npub_sub"),
+            Some("Sub".to_string()),
+        )
+        .unwrap();
+        assert_eq!(substitution.previous_pubkey, "npub1");
+        assert_eq!(state.players[1].name, "Sub");
+        assert_eq!(state.players[1].previous_pubkeys, vec!["npub1"]);
+        assert_eq!(turns_absent(&state, PlayerSlot(1)), Some(0));
+
+        assert_eq!(state.player_for_pubkey("npub_sub"), Some(PlayerSlot(1)));
+        assert_eq!(state.player_for_pubkey("npub1"), None);
+    }
+}
@@ -44,6 +44,11 @@ impl Technology {
     }
 
     /// Add prerequisites.
+    /// Localization key for this technology's name.
+    pub fn name_key(&self) -> String {
+        crate::locale::keys::tech_name(&self.id)
+    }
+
     pub fn with_prerequisites(mut self, prereqs: &[&str]) -> Self {
         self.prerequisites = prereqs.iter().map(|s| s.to_string()).collect();
         self
@@ -117,6 +122,8 @@ impl TechTree {
         tree.add_renaissance_techs();
         tree.add_industrial_techs();
         tree.add_modern_techs();
+        tree.add_atomic_techs();
+        tree.add_information_techs();
 
         tree
     }
@@ -168,6 +175,37 @@ impl TechTree {
             .collect()
     }
 
+    /// Technologies to research, in order, to reach a target.
+    ///
+    /// Missing prerequisites come before the techs that need them and the
+    /// target comes last. Researched techs are left out, so the path is
+    /// empty if the target is already known or doesn't exist.
+    pub fn research_path(&self, target: &TechId, researched: &HashSet<TechId>) -> Vec<TechId> {
+        let mut path = Vec::new();
+        let mut seen = HashSet::new();
+        self.visit_prerequisites(target, researched, &mut seen, &mut path);
+        path
+    }
+
+    fn visit_prerequisites(
+        &self,
+        tech_id: &TechId,
+        researched: &HashSet<TechId>,
+        seen: &mut HashSet<TechId>,
+        path: &mut Vec<TechId>,
+    ) {
+        if researched.contains(tech_id) || !seen.insert(tech_id.clone()) {
+            return;
+        }
+        let Some(tech) = self.techs.get(tech_id) else {
+            return;
+        };
+        for prereq in &tech.prerequisites {
+            self.visit_prerequisites(prereq, researched, seen, path);
+        }
+        path.push(tech_id.clone());
+    }
+
     /// Get what units are unlocked by a technology.
     pub fn units_unlocked_by(&self, tech_id: &TechId) -> Vec<UnitType> {
         self.techs
@@ -184,6 +222,15 @@ impl TechTree {
             .unwrap_or_default()
     }
 
+    /// Check if any researched technology unlocks an ability.
+    pub fn has_ability(&self, researched: &HashSet<TechId>, ability: &str) -> bool {
+        researched.iter().any(|id| {
+            self.techs
+                .get(id)
+                .is_some_and(|t| t.unlocks.abilities.iter().any(|a| a == ability))
+        })
+    }
+
     /// Add Ancient Era technologies.
     fn add_ancient_techs(&mut self) {
         // Starting tech - Agriculture
@@ -279,6 +326,7 @@ impl TechTree {
                 .with_prerequisites(&["mining"])
                 .unlocks_buildings(&[BuildingType::Walls])
                 .unlocks_improvements(&[Improvement::Quarry])
+                .unlocks_abilities(&["clear_marsh"])
                 .with_quote("A stone is hard, but time is harder."),
         );
 
@@ -342,7 +390,7 @@ impl TechTree {
             Technology::new("iron_working", "Iron Working", Era::Classical, 150)
                 .with_prerequisites(&["bronze_working"])
                 .unlocks_units(&[UnitType::Swordsman])
-                .unlocks_abilities(&["reveal_iron"])
+                .unlocks_abilities(&["reveal_iron", "clear_jungle"])
                 .with_quote("Iron rusts from disuse; water loses its purity from stagnation."),
         );
 
@@ -551,6 +599,7 @@ impl TechTree {
             Technology::new("steam_power", "Steam Power", Era::Industrial, 860)
                 .with_prerequisites(&["industrialization", "scientific_theory"])
                 .unlocks_units(&[UnitType::Ironclad])
+                .unlocks_abilities(&["railroads"])
                 .with_quote("Steam is a perfect servant but a terrible master."),
         );
 
@@ -630,10 +679,13 @@ impl TechTree {
                     "Electronics is really easy: all you have to do is connect things with wires.",
                 ),
         );
+    }
 
+    /// Add Atomic Era technologies.
+    fn add_atomic_techs(&mut self) {
         // Radar - requires Electronics
         self.add(
-            Technology::new("radar", "Radar", Era::Modern, 1250)
+            Technology::new("radar", "Radar", Era::Atomic, 1250)
                 .with_prerequisites(&["electronics"])
                 .unlocks_units(&[UnitType::Battleship, UnitType::Bomber])
                 .with_quote("Radar: the science of detection by reflection."),
@@ -641,7 +693,7 @@ impl TechTree {
 
         // Rocketry - requires Radar
         self.add(
-            Technology::new("rocketry", "Rocketry", Era::Modern, 1350)
+            Technology::new("rocketry", "Rocketry", Era::Atomic, 1350)
                 .with_prerequisites(&["radar"])
                 .unlocks_units(&[UnitType::RocketArtillery])
                 .with_quote(
@@ -651,15 +703,25 @@ impl TechTree {
 
         // Nuclear Fission - requires Radar
         self.add(
-            Technology::new("nuclear_fission", "Nuclear Fission", Era::Modern, 1400)
+            Technology::new("nuclear_fission", "Nuclear Fission", Era::Atomic, 1400)
                 .with_prerequisites(&["radar"])
                 .unlocks_abilities(&["reveal_uranium", "nuclear_weapons"])
                 .with_quote("I am become Death, the destroyer of worlds."),
         );
+    }
+
+    /// Add Information Era technologies.
+    fn add_information_techs(&mut self) {
+        // Computers - requires Electronics, Nuclear Fission
+        self.add(
+            Technology::new("computers", "Computers", Era::Information, 1450)
+                .with_prerequisites(&["electronics", "nuclear_fission"])
+                .with_quote("Computers are useless. They can only give you answers."),
+        );
 
         // Spaceflight - requires Rocketry
         self.add(
-            Technology::new("spaceflight", "Spaceflight", Era::Modern, 1500)
+            Technology::new("spaceflight", "Spaceflight", Era::Information, 1500)
                 .with_prerequisites(&["rocketry"])
                 .unlocks_abilities(&["spaceship_parts"])
                 .with_quote("That's one small step for man, one giant leap for mankind."),
@@ -742,6 +804,40 @@ mod tests {
         assert!(buildings.contains(&BuildingType::Granary));
     }
 
+    #[test]
+    fn test_research_path_expands_prerequisites() {
+        let tree = TechTree::new();
+        let target = "mathematics".to_string();
+
+        let path = tree.research_path(&target, &HashSet::new());
+        assert_eq!(
+            path,
+            [
+                "agriculture",
+                "pottery",
+                "writing",
+                "animal_husbandry",
+                "the_wheel",
+                "mathematics"
+            ]
+        );
+
+        let researched: HashSet<TechId> = ["agriculture", "pottery", "writing"]
+            .iter()
+            .map(|id| id.to_string())
+            .collect();
+        let path = tree.research_path(&target, &researched);
+        assert_eq!(path, ["animal_husbandry", "the_wheel", "mathematics"]);
+
+        // Every tech on the path is researchable once the ones before it are
+        let mut known = researched;
+        for tech_id in &path {
+            assert!(tree.can_research(tech_id, &known));
+            known.insert(tech_id.clone());
+        }
+        assert!(tree.research_path(&target, &known).is_empty());
+    }
+
     #[test]
     fn test_era_progression() {
         let tree = TechTree::new();
@@ -753,5 +849,7 @@ mod tests {
         assert!(!tree.get_era(Era::Renaissance).is_empty());
         assert!(!tree.get_era(Era::Industrial).is_empty());
         assert!(!tree.get_era(Era::Modern).is_empty());
+        assert!(!tree.get_era(Era::Atomic).is_empty());
+        assert!(!tree.get_era(Era::Information).is_empty());
     }
 }
@@ -0,0 +1,434 @@
+//! Terrain that changes over the course of a game.
+//!
+//! Workers clear forests, jungles and marshes over several turns, like
+//! building a road. Chopping a forest inside a player's borders puts
+//! [`CHOP_PRODUCTION`] toward the city that owns the tile.
+//!
+//! Late in the game, cities of industrialized players pollute. Pollution
+//! accumulates every game turn, and each time it passes
+//! [`WARMING_THRESHOLD`] the climate warms by a stage: [`WARMING_TILES`]
+//! coastal land tiles thaw or flood. The tiles are picked by hashing the
+//! game seed with the stage and each candidate's coordinate, so every peer
+//! transforms the same tiles without drawing randomness.
+
+use crate::audit::{fnv1a, FNV_OFFSET};
+use crate::fixed::deterministic;
+use crate::game_state::GameState;
+use crate::hex::HexCoord;
+use crate::map::Tile;
+use crate::technology::TechTree;
+use crate::terrain::{Feature, Terrain};
+use crate::types::{CityId, Era, PlayerSlot, TechId, UnitId};
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+use std::collections::HashSet;
+use ts_rs::TS;
+
+/// Production a chopped forest gives the city owning its tile.
+pub const CHOP_PRODUCTION: u32 = 20;
+
+/// Pollution that warms the climate by one stage.
+pub const WARMING_THRESHOLD: u32 = 200;
+
+/// Coastal tiles transformed each time the climate warms.
+pub const WARMING_TILES: usize = 6;
+
+/// Feature clearing in progress on a worker's tile.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct FeatureWork {
+    /// Feature being removed.
+    pub feature: Feature,
+    /// Turns of work left.
+    pub turns_remaining: u32,
+}
+
+deterministic!(struct FeatureWork {
+    feature,
+    turns_remaining
+});
+
+impl FeatureWork {
+    /// Start clearing a feature.
+    ///
+    /// Returns `None` if the feature can't be removed.
+    pub fn new(feature: Feature) -> Option<Self> {
+        Some(Self {
+            feature,
+            turns_remaining: clear_turns(feature)?,
+        })
+    }
+}
+
+/// Turns a worker needs to remove a feature, if it can be removed at all.
+pub const fn clear_turns(feature: Feature) -> Option<u32> {
+    match feature {
+        Feature::Forest => Some(3),
+        Feature::Jungle => Some(4),
+        Feature::Marsh => Some(5),
+        _ => None,
+    }
+}
+
+/// Ability needed to remove a feature.
+pub const fn clear_ability(feature: Feature) -> &'static str {
+    match feature {
+        Feature::Jungle => "clear_jungle",
+        Feature::Marsh => "clear_marsh",
+        _ => "clear_forest",
+    }
+}
+
+/// Why a feature can't be removed from a tile.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize, Deserialize, JsonSchema, TS)]
+pub enum ClearError {
+    /// The tile has no feature, or one that can't be removed.
+    NothingToClear,
+    /// The player lacks the technology to remove this feature.
+    MissingTechnology,
+}
+
+impl std::fmt::Display for ClearError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            ClearError::NothingToClear => write!(f, "Nothing to clear on this tile"),
+            ClearError::MissingTechnology => write!(f, "Missing technology to clear feature"),
+        }
+    }
+}
+
+impl std::error::Error for ClearError {}
+
+/// Pick the feature a player can remove from a tile.
+pub fn feature_to_clear(tile: &Tile, researched: &HashSet<TechId>) -> Result<Feature, ClearError> {
+    let feature = tile
+        .feature
+        .filter(|f| clear_turns(*f).is_some())
+        .ok_or(ClearError::NothingToClear)?;
+    if !TechTree::new().has_ability(researched, clear_ability(feature)) {
+        return Err(ClearError::MissingTechnology);
+    }
+    Ok(feature)
+}
+
+/// A feature removed by a worker.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct FeatureCleared {
+    pub unit_id: UnitId,
+    pub coord: HexCoord,
+    pub feature: Feature,
+    /// City that received production from a chopped forest.
+    pub city_id: Option<CityId>,
+    pub production: u32,
+}
+
+/// Advance every feature a player's workers are clearing by one turn.
+///
+/// Workers are processed in ID order. Cleared features are removed from
+/// the map and chopped forests pay out to the city owning the tile.
+pub fn progress_feature_work(state: &mut GameState, player_id: PlayerSlot) -> Vec<FeatureCleared> {
+    let mut unit_ids: Vec<UnitId> = state
+        .units
+        .values()
+        .filter(|u| u.owner == player_id && u.feature_work.is_some())
+        .map(|u| u.id)
+        .collect();
+    unit_ids.sort_unstable();
+
+    let mut cleared = Vec::new();
+    for unit_id in unit_ids {
+        let Some(unit) = state.units.get_mut(&unit_id) else {
+            continue;
+        };
+        let Some(work) = unit.feature_work.as_mut() else {
+            continue;
+        };
+        work.turns_remaining = work.turns_remaining.saturating_sub(1);
+        if work.turns_remaining > 0 {
+            continue;
+        }
+
+        let feature = work.feature;
+        let coord = unit.position;
+        unit.feature_work = None;
+        match state.map.get_mut(&coord) {
+            Some(tile) if tile.feature == Some(feature) => tile.feature = None,
+            // Something else changed the tile in the meantime
+            _ => continue,
+        }
+
+        let city_id = (feature == Feature::Forest)
+            .then(|| {
+                state
+                    .cities
+                    .values()
+                    .filter(|c| c.owner == player_id && c.territory.contains(&coord))
+                    .map(|c| c.id)
+                    .min()
+            })
+            .flatten();
+        let production = city_id.map_or(0, |_| CHOP_PRODUCTION);
+        if let Some(city) = city_id.and_then(|id| state.cities.get_mut(&id)) {
+            city.production_progress += production;
+        }
+        cleared.push(FeatureCleared {
+            unit_id,
+            coord,
+            feature,
+            city_id,
+            production,
+        });
+    }
+    cleared
+}
+
+/// Accumulated pollution and how far the climate has warmed.
+#[derive(Clone, Debug, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct GlobalWarming {
+    /// Last game turn pollution was added on.
+    pub evaluated_turn: u32,
+    /// Pollution toward the next stage.
+    pub pollution: u32,
+    /// Times the climate has warmed.
+    pub stage: u32,
+}
+
+/// Pollution each of a player's cities produces per turn in an era.
+pub const fn city_pollution(era: Era) -> u32 {
+    match era {
+        Era::Industrial => 1,
+        Era::Modern | Era::Information => 2,
+        Era::Atomic => 3,
+        _ => 0,
+    }
+}
+
+/// A tile transformed by a warming climate.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct TerrainChange {
+    pub coord: HexCoord,
+    pub terrain: Terrain,
+    pub feature: Option<Feature>,
+}
+
+/// What a tile becomes when the climate warms, if it changes.
+///
+/// Frozen land thaws; other flat coastal land floods into marsh and loses
+/// its improvement.
+fn warmed(tile: &Tile) -> Option<(Terrain, Option<Feature>)> {
+    match (tile.terrain, tile.feature) {
+        (_, Some(Feature::Hills | Feature::Mountains | Feature::Marsh)) => None,
+        (Terrain::Snow, _) => Some((Terrain::Tundra, None)),
+        (Terrain::Tundra, _) => Some((Terrain::Plains, None)),
+        (Terrain::Grassland | Terrain::Plains | Terrain::Desert, _) => {
+            Some((tile.terrain, Some(Feature::Marsh)))
+        }
+        _ => None,
+    }
+}
+
+/// Add this game turn's pollution and warm the climate if it passes the
+/// threshold.
+///
+/// Runs at most once per game turn; later calls in the same turn do
+/// nothing.
+pub fn update_climate(state: &mut GameState) -> Vec<TerrainChange> {
+    if state.warming.evaluated_turn >= state.turn {
+        return Vec::new();
+    }
+    state.warming.evaluated_turn = state.turn;
+
+    let pollution: u32 = state
+        .players
+        .iter()
+        .filter(|p| !p.eliminated)
+        .map(|p| {
+            let cities = state.cities.values().filter(|c| c.owner == p.id).count() as u32;
+            cities * city_pollution(p.era)
+        })
+        .sum();
+    state.warming.pollution += pollution;
+
+    let mut changes = Vec::new();
+    while state.warming.pollution >= WARMING_THRESHOLD {
+        state.warming.pollution -= WARMING_THRESHOLD;
+        state.warming.stage += 1;
+        changes.extend(warm_coast(state));
+    }
+    changes
+}
+
+/// Transform the coastal tiles picked for the current stage.
+fn warm_coast(state: &mut GameState) -> Vec<TerrainChange> {
+    let mut stage_seed = fnv1a(FNV_OFFSET, &state.seed);
+    stage_seed = fnv1a(stage_seed, &state.warming.stage.to_le_bytes());
+
+    let mut candidates: Vec<(u64, HexCoord)> = state
+        .map
+        .tiles
+        .values()
+        .filter(|tile| !tile.terrain.is_water() && tile.city_id.is_none())
+        .filter(|tile| warmed(tile).is_some())
+        .filter(|tile| {
+            state.map.neighbors(&tile.coord).iter().any(|n| {
+                state
+                    .map
+                    .get(n)
+                    .is_some_and(|neighbor| neighbor.terrain.is_water())
+            })
+        })
+        .map(|tile| {
+            let mut hash = fnv1a(stage_seed, &tile.coord.q.to_le_bytes());
+            hash = fnv1a(hash, &tile.coord.r.to_le_bytes());
+            (hash, tile.coord)
+        })
+        .collect();
+    candidates.sort_unstable_by_key(|&(hash, coord)| (hash, coord.q, coord.r));
+
+    let mut changes = Vec::new();
+    for (_, coord) in candidates.into_iter().take(WARMING_TILES) {
+        let Some(tile) = state.map.get_mut(&coord) else {
+            continue;
+        };
+        let Some((terrain, feature)) = warmed(tile) else {
+            continue;
+        };
+        tile.terrain = terrain;
+        if feature == Some(Feature::Marsh) {
+            tile.improvement = None;
+        }
+        tile.feature = feature;
+        changes.push(TerrainChange {
+            coord,
+            terrain,
+            feature,
+        });
+    }
+    changes
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::city::City;
+    use crate::map::Map;
+    use crate::player::{Civilization, Player};
+    use crate::settings::GameSettings;
+    use crate::types::{GameId, Npub};
+    use crate::unit::{Unit, UnitType};
+
+    fn island_game() -> GameState {
+        let settings = GameSettings::new("Test".to_string());
+        let mut state = GameState::new(GameId::new("test"), settings, [3u8; 32]);
+        let mut map = Map::filled(20, 20, Terrain::Ocean);
+        for q in 5..15 {
+            for r in 5..15 {
+                map.get_mut(&HexCoord::new(q, r)).unwrap().terrain = Terrain::Grassland;
+            }
+        }
+        state.map = map;
+        state
+            .add_player(Player::new(
+                PlayerSlot(0),
+                Npub::new("pk0"),
+                "P0".to_string(),
+                Civilization::default(),
+            ))
+            .unwrap();
+        let city = City::new(
+            CityId(1),
+            PlayerSlot(0),
+            "Capital".to_string(),
+            HexCoord::new(8, 8),
+            true,
+        );
+        state.cities.insert(CityId(1), city);
+        state.turn = 1;
+        state
+    }
+
+    #[test]
+    fn test_feature_requires_technology() {
+        let mut tile = Tile::new(HexCoord::new(0, 0), Terrain::Grassland);
+        let mut researched = HashSet::new();
+        assert_eq!(
+            feature_to_clear(&tile, &researched),
+            Err(ClearError::NothingToClear)
+        );
+        tile.feature = Some(Feature::Forest);
+        assert_eq!(
+            feature_to_clear(&tile, &researched),
+            Err(ClearError::MissingTechnology)
+        );
+        researched.insert("bronze_working".to_string());
+        assert_eq!(feature_to_clear(&tile, &researched), Ok(Feature::Forest));
+        tile.feature = Some(Feature::Hills);
+        assert_eq!(
+            feature_to_clear(&tile, &researched),
+            Err(ClearError::NothingToClear)
+        );
+    }
+
+    #[test]
+    fn test_chopped_forest_gives_production() {
+        let mut state = island_game();
+        let coord = HexCoord::new(9, 8);
+        state.map.get_mut(&coord).unwrap().feature = Some(Feature::Forest);
+        state
+            .cities
+            .get_mut(&CityId(1))
+            .unwrap()
+            .territory
+            .insert(coord);
+        let mut worker = Unit::new(UnitId(7), PlayerSlot(0), UnitType::Worker, coord);
+        worker.feature_work = FeatureWork::new(Feature::Forest);
+        state.units.insert(UnitId(7), worker);
+
+        assert!(progress_feature_work(&mut state, PlayerSlot(0)).is_empty());
+        assert!(progress_feature_work(&mut state, PlayerSlot(0)).is_empty());
+        let cleared = progress_feature_work(&mut state, PlayerSlot(0));
+        assert_eq!(
+            cleared,
+            vec![FeatureCleared {
+                unit_id: UnitId(7),
+                coord,
+                feature: Feature::Forest,
+                city_id: Some(CityId(1)),
+                production: CHOP_PRODUCTION,
+            }]
+        );
+        assert_eq!(state.map.get(&coord).unwrap().feature, None);
+        assert_eq!(
+            state.cities[&CityId(1)].production_progress,
+            CHOP_PRODUCTION
+        );
+        assert!(state.units[&UnitId(7)].feature_work.is_none());
+    }
+
+    #[test]
+    fn test_warming_floods_the_same_coastal_tiles() {
+        let mut a = island_game();
+        a.players[0].era = Era::Atomic;
+        a.warming.pollution = WARMING_THRESHOLD - 1;
+        let mut b = a.clone();
+
+        let changes = update_climate(&mut a);
+        assert_eq!(changes, update_climate(&mut b));
+        assert_eq!(changes.len(), WARMING_TILES);
+        assert_eq!(a.warming.stage, 1);
+        assert_eq!(a.warming.pollution, 2);
+        for change in &changes {
+            assert_eq!(change.feature, Some(Feature::Marsh));
+            let tile = a.map.get(&change.coord).unwrap();
+            assert_eq!(tile.feature, Some(Feature::Marsh));
+            assert!(a
+                .map
+                .neighbors(&change.coord)
+                .iter()
+                .any(|n| a.map.get(n).is_some_and(|t| t.terrain.is_water())));
+        }
+
+        // Only once per game turn
+        a.warming.pollution = WARMING_THRESHOLD;
+        assert!(update_climate(&mut a).is_empty());
+    }
+}
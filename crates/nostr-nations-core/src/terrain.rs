@@ -1,7 +1,10 @@
 //! Terrain types, features, and resources for the game map.
 
+use crate::fixed::{deterministic, Fixed};
 use crate::yields::Yields;
+use schemars::JsonSchema;
 use serde::{Deserialize, Serialize};
+use ts_rs::TS;
 
 /// Base terrain type for a tile.
 #[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Hash, Serialize, Deserialize)]
@@ -16,6 +19,8 @@ pub enum Terrain {
     Ocean,
 }
 
+deterministic!(enum Terrain);
+
 impl Terrain {
     /// Get the base yields for this terrain type.
     pub const fn base_yields(&self) -> Yields {
@@ -75,6 +80,8 @@ pub enum Feature {
     Ice,
 }
 
+deterministic!(enum Feature);
+
 impl Feature {
     /// Get the yield modifier for this feature.
     pub const fn yield_modifier(&self) -> Yields {
@@ -137,7 +144,9 @@ impl Feature {
 }
 
 /// Resources that can appear on tiles.
-#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[derive(
+    Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord, Hash, Serialize, Deserialize, JsonSchema, TS,
+)]
 pub enum Resource {
     // Strategic resources
     Iron,
@@ -173,6 +182,8 @@ pub enum Resource {
     Salt,
 }
 
+deterministic!(enum Resource);
+
 /// Category of a resource.
 #[derive(Clone, Copy, Debug, PartialEq, Eq, Hash, Serialize, Deserialize)]
 pub enum ResourceCategory {
@@ -286,7 +297,7 @@ impl Resource {
 }
 
 /// Tile improvements built by workers.
-#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash, Serialize, Deserialize, JsonSchema, TS)]
 pub enum Improvement {
     Farm,
     Mine,
@@ -305,6 +316,8 @@ pub enum Improvement {
     Landmark,
 }
 
+deterministic!(enum Improvement);
+
 impl Improvement {
     /// Get the yield bonus from this improvement.
     pub const fn yield_bonus(&self) -> Yields {
@@ -349,12 +362,34 @@ pub enum Road {
     Railroad,
 }
 
+deterministic!(enum Road);
+
 impl Road {
     /// Get the movement cost multiplier for this road type.
-    pub const fn movement_multiplier(&self) -> f32 {
+    pub const fn movement_multiplier(&self) -> Fixed {
         match self {
-            Road::Road => 0.5,
-            Road::Railroad => 0.1,
+            Road::Road => Fixed::HALF,
+            Road::Railroad => Fixed::from_percent(10),
+        }
+    }
+
+    /// Get the gold maintenance paid each turn.
+    pub const fn maintenance(&self) -> i32 {
+        match self {
+            Road::Road => 1,
+            Road::Railroad => 2,
+        }
+    }
+
+    /// Get the road linking two adjacent tiles.
+    ///
+    /// Movement only benefits from a road when both tiles have one, and a
+    /// railroad only when both tiles have railroads.
+    pub const fn between(from: Option<Road>, to: Option<Road>) -> Option<Road> {
+        match (from, to) {
+            (Some(Road::Railroad), Some(Road::Railroad)) => Some(Road::Railroad),
+            (Some(_), Some(_)) => Some(Road::Road),
+            _ => None,
         }
     }
 
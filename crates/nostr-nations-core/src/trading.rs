@@ -7,11 +7,14 @@
 //! - Technologies
 //! - Diplomatic agreements (open borders, defensive pacts)
 
+use crate::fixed::Fixed;
 use crate::game_state::GameState;
 use crate::terrain::{Resource, ResourceCategory};
-use crate::types::{CityId, PlayerId, TechId};
+use crate::types::{CityId, PlayerSlot, TechId};
+use schemars::JsonSchema;
 use serde::{Deserialize, Serialize};
 use std::collections::HashMap;
+use ts_rs::TS;
 
 /// A trade offer between two players.
 #[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
@@ -19,9 +22,9 @@ pub struct TradeOffer {
     /// Unique identifier for this trade offer.
     pub id: u64,
     /// Player proposing the trade.
-    pub from_player: PlayerId,
+    pub from_player: PlayerSlot,
     /// Player receiving the trade proposal.
-    pub to_player: PlayerId,
+    pub to_player: PlayerSlot,
     /// Items being offered by from_player.
     pub offer: TradeItems,
     /// Items requested from to_player.
@@ -38,8 +41,8 @@ impl TradeOffer {
     /// Create a new trade offer.
     pub fn new(
         id: u64,
-        from_player: PlayerId,
-        to_player: PlayerId,
+        from_player: PlayerSlot,
+        to_player: PlayerSlot,
         offer: TradeItems,
         request: TradeItems,
         turn_proposed: u32,
@@ -73,7 +76,7 @@ impl TradeOffer {
 }
 
 /// Items that can be traded between players.
-#[derive(Clone, Debug, Default, PartialEq, Serialize, Deserialize)]
+#[derive(Clone, Debug, Default, PartialEq, Serialize, Deserialize, JsonSchema, TS)]
 pub struct TradeItems {
     /// Lump sum of gold.
     pub gold: i32,
@@ -210,6 +213,10 @@ pub enum TradeError {
     SelfTrade,
     /// Trade offer has expired.
     Expired,
+    /// Technology trading is disabled for this game.
+    TechTradingDisabled,
+    /// Technology was itself acquired by trade and brokering is disabled.
+    TechBrokering,
 }
 
 impl std::fmt::Display for TradeError {
@@ -225,6 +232,10 @@ impl std::fmt::Display for TradeError {
             TradeError::AtWar => write!(f, "Cannot trade while at war"),
             TradeError::SelfTrade => write!(f, "Cannot trade with yourself"),
             TradeError::Expired => write!(f, "Trade offer has expired"),
+            TradeError::TechTradingDisabled => write!(f, "Technology trading is disabled"),
+            TradeError::TechBrokering => {
+                write!(f, "Cannot trade a technology acquired by trade")
+            }
         }
     }
 }
@@ -327,7 +338,7 @@ impl TradeManager {
     }
 
     /// Cancel a pending trade offer (by the proposing player).
-    pub fn cancel_trade(&mut self, offer_id: u64, player: PlayerId) -> Result<(), TradeError> {
+    pub fn cancel_trade(&mut self, offer_id: u64, player: PlayerSlot) -> Result<(), TradeError> {
         let offer = self
             .offers
             .get_mut(&offer_id)
@@ -351,7 +362,7 @@ impl TradeManager {
     }
 
     /// Get all pending offers sent to a player.
-    pub fn get_offers_for_player(&self, player: PlayerId) -> Vec<&TradeOffer> {
+    pub fn get_offers_for_player(&self, player: PlayerSlot) -> Vec<&TradeOffer> {
         self.offers
             .values()
             .filter(|o| o.to_player == player && o.status == TradeStatus::Pending)
@@ -359,7 +370,7 @@ impl TradeManager {
     }
 
     /// Get all pending offers sent by a player.
-    pub fn get_offers_from_player(&self, player: PlayerId) -> Vec<&TradeOffer> {
+    pub fn get_offers_from_player(&self, player: PlayerSlot) -> Vec<&TradeOffer> {
         self.offers
             .values()
             .filter(|o| o.from_player == player && o.status == TradeStatus::Pending)
@@ -367,7 +378,7 @@ impl TradeManager {
     }
 
     /// Get all offers involving a player (either direction).
-    pub fn get_all_offers_for_player(&self, player: PlayerId) -> Vec<&TradeOffer> {
+    pub fn get_all_offers_for_player(&self, player: PlayerSlot) -> Vec<&TradeOffer> {
         self.offers
             .values()
             .filter(|o| o.from_player == player || o.to_player == player)
@@ -385,7 +396,7 @@ impl TradeManager {
 
     /// Process active per-turn agreements for a new turn.
     /// Returns a list of (offer_id, from_player, to_player) for agreements that need gold transfers.
-    pub fn process_turn(&mut self) -> Vec<(u64, PlayerId, PlayerId, i32, i32)> {
+    pub fn process_turn(&mut self) -> Vec<(u64, PlayerSlot, PlayerSlot, i32, i32)> {
         let mut transfers = Vec::new();
         let mut expired = Vec::new();
 
@@ -440,11 +451,11 @@ impl TradeManager {
             return TradeFairness::OneWay;
         }
 
-        let ratio = higher as f32 / lower as f32;
+        let ratio = Fixed::from_ratio(higher as i64, lower as i64);
 
-        if ratio <= 1.2 {
+        if ratio <= Fixed::from_percent(120) {
             TradeFairness::Fair
-        } else if ratio <= 1.5 {
+        } else if ratio <= Fixed::from_percent(150) {
             TradeFairness::SlightlyUnfair
         } else {
             TradeFairness::VeryUnfair
@@ -453,7 +464,7 @@ impl TradeManager {
 }
 
 /// Calculate the estimated value of trade items from a player's perspective.
-pub fn calculate_trade_value(items: &TradeItems, game: &GameState, player: PlayerId) -> i32 {
+pub fn calculate_trade_value(items: &TradeItems, game: &GameState, player: PlayerSlot) -> i32 {
     let mut value = 0;
 
     // Gold is worth its face value
@@ -553,7 +564,7 @@ fn validate_trade(game: &GameState, offer: &TradeOffer) -> Result<(), TradeError
 /// Validate that a player can provide the specified trade items.
 fn validate_player_can_provide(
     game: &GameState,
-    player: PlayerId,
+    player: PlayerSlot,
     items: &TradeItems,
 ) -> Result<(), TradeError> {
     let player_data = game.get_player(player).ok_or(TradeError::NotAuthorized)?;
@@ -575,14 +586,27 @@ fn validate_player_can_provide(
     }
 
     // Check technologies
+    if !items.technologies.is_empty() && !game.settings.tech_trading {
+        return Err(TradeError::TechTradingDisabled);
+    }
     for tech_id in &items.technologies {
         if !player_data.has_tech(tech_id) {
             return Err(TradeError::InvalidTechnology);
         }
+        if !game.settings.tech_brokering && player_data.traded_techs.contains(tech_id) {
+            return Err(TradeError::TechBrokering);
+        }
     }
 
-    // Note: Resource validation would require a resource inventory system
-    // which isn't fully implemented in the current codebase
+    // Strategic resources come out of the stockpile; luxury and bonus
+    // resources aren't counted
+    for (&resource, &quantity) in &items.resources {
+        if resource.category() == ResourceCategory::Strategic
+            && player_data.stockpiled(resource) < quantity
+        {
+            return Err(TradeError::InsufficientResources);
+        }
+    }
 
     Ok(())
 }
@@ -590,8 +614,8 @@ fn validate_player_can_provide(
 /// Transfer trade items from one player to another.
 fn transfer_items(
     game: &mut GameState,
-    from: PlayerId,
-    to: PlayerId,
+    from: PlayerSlot,
+    to: PlayerSlot,
     items: &TradeItems,
 ) -> Result<(), TradeError> {
     // Transfer gold
@@ -615,11 +639,26 @@ fn transfer_items(
         }
     }
 
+    // Transfer stockpiled strategic resources
+    for (&resource, &quantity) in &items.resources {
+        if resource.category() != ResourceCategory::Strategic {
+            continue;
+        }
+        if let Some(from_player) = game.get_player_mut(from) {
+            let stock = from_player.stockpile.entry(resource).or_insert(0);
+            *stock = stock.saturating_sub(quantity);
+        }
+        if let Some(to_player) = game.get_player_mut(to) {
+            *to_player.stockpile.entry(resource).or_insert(0) += quantity;
+        }
+    }
+
     // Transfer technologies
     for tech_id in &items.technologies {
         if let Some(to_player) = game.get_player_mut(to) {
             if !to_player.has_tech(tech_id) {
                 to_player.add_tech(tech_id.clone());
+                to_player.traded_techs.insert(tech_id.clone());
             }
         }
     }
@@ -655,20 +694,21 @@ mod tests {
     use super::*;
     use crate::player::{Civilization, Player};
     use crate::settings::GameSettings;
+    use crate::types::{GameId, Npub};
 
     fn create_test_game() -> GameState {
         let settings = GameSettings::new("Test".to_string());
-        let mut game = GameState::new("game1".to_string(), settings, [0u8; 32]);
+        let mut game = GameState::new(GameId::new("game1"), settings, [0u8; 32]);
 
         let p1 = Player::new(
-            0,
-            "npub0".to_string(),
+            PlayerSlot(0),
+            Npub::new("npub0"),
             "Player1".to_string(),
             Civilization::generic(),
         );
         let p2 = Player::new(
-            1,
-            "npub1".to_string(),
+            PlayerSlot(1),
+            Npub::new("npub1"),
             "Player2".to_string(),
             Civilization::generic(),
         );
@@ -678,8 +718,8 @@ mod tests {
         game.start().unwrap();
 
         // Give players some gold
-        game.get_player_mut(0).unwrap().gold = 1000;
-        game.get_player_mut(1).unwrap().gold = 1000;
+        game.get_player_mut(PlayerSlot(0)).unwrap().gold = 1000;
+        game.get_player_mut(PlayerSlot(1)).unwrap().gold = 1000;
 
         game
     }
@@ -697,8 +737,8 @@ mod tests {
 
         let offer = TradeOffer::new(
             0,
-            0,
-            1,
+            PlayerSlot(0),
+            PlayerSlot(1),
             TradeItems::new().with_gold(100),
             TradeItems::new().with_gold(50),
             1,
@@ -720,8 +760,8 @@ mod tests {
 
         let offer = TradeOffer::new(
             0,
-            0,
-            1,
+            PlayerSlot(0),
+            PlayerSlot(1),
             TradeItems::new().with_gold(100),
             TradeItems::new().with_gold(50),
             1,
@@ -741,8 +781,8 @@ mod tests {
 
         let offer = TradeOffer::new(
             0,
-            0,
-            1,
+            PlayerSlot(0),
+            PlayerSlot(1),
             TradeItems::new().with_gold(100),
             TradeItems::new(),
             1,
@@ -761,8 +801,8 @@ mod tests {
 
         let offer = TradeOffer::new(
             0,
-            0,
-            1,
+            PlayerSlot(0),
+            PlayerSlot(1),
             TradeItems::new().with_gold(100),
             TradeItems::new(),
             1,
@@ -772,10 +812,13 @@ mod tests {
         let id = manager.propose_trade(offer);
 
         // Wrong player cannot cancel
-        assert_eq!(manager.cancel_trade(id, 1), Err(TradeError::NotAuthorized));
+        assert_eq!(
+            manager.cancel_trade(id, PlayerSlot(1)),
+            Err(TradeError::NotAuthorized)
+        );
 
         // Correct player can cancel
-        manager.cancel_trade(id, 0).unwrap();
+        manager.cancel_trade(id, PlayerSlot(0)).unwrap();
         assert_eq!(
             manager.get_offer(id).unwrap().status,
             TradeStatus::Cancelled
@@ -788,8 +831,8 @@ mod tests {
 
         let offer = TradeOffer::new(
             0,
-            0,
-            1,
+            PlayerSlot(0),
+            PlayerSlot(1),
             TradeItems::new().with_gold(100),
             TradeItems::new(),
             1,
@@ -807,21 +850,45 @@ mod tests {
         let mut manager = TradeManager::new();
 
         // Player 0 sends to player 1
-        let offer1 = TradeOffer::new(0, 0, 1, TradeItems::new(), TradeItems::new(), 1, None);
+        let offer1 = TradeOffer::new(
+            0,
+            PlayerSlot(0),
+            PlayerSlot(1),
+            TradeItems::new(),
+            TradeItems::new(),
+            1,
+            None,
+        );
         manager.propose_trade(offer1);
 
         // Player 2 sends to player 1
-        let offer2 = TradeOffer::new(0, 2, 1, TradeItems::new(), TradeItems::new(), 1, None);
+        let offer2 = TradeOffer::new(
+            0,
+            PlayerSlot(2),
+            PlayerSlot(1),
+            TradeItems::new(),
+            TradeItems::new(),
+            1,
+            None,
+        );
         manager.propose_trade(offer2);
 
         // Player 0 sends to player 2
-        let offer3 = TradeOffer::new(0, 0, 2, TradeItems::new(), TradeItems::new(), 1, None);
+        let offer3 = TradeOffer::new(
+            0,
+            PlayerSlot(0),
+            PlayerSlot(2),
+            TradeItems::new(),
+            TradeItems::new(),
+            1,
+            None,
+        );
         manager.propose_trade(offer3);
 
-        let offers_to_1 = manager.get_offers_for_player(1);
+        let offers_to_1 = manager.get_offers_for_player(PlayerSlot(1));
         assert_eq!(offers_to_1.len(), 2);
 
-        let offers_from_0 = manager.get_offers_from_player(0);
+        let offers_from_0 = manager.get_offers_from_player(PlayerSlot(0));
         assert_eq!(offers_from_0.len(), 2);
     }
 
@@ -831,8 +898,8 @@ mod tests {
 
         let offer = TradeOffer::new(
             0,
-            0,
-            1,
+            PlayerSlot(0),
+            PlayerSlot(1),
             TradeItems::new(),
             TradeItems::new(),
             1,
@@ -895,8 +962,8 @@ mod tests {
         // Fair trade: equal gold
         let fair_offer = TradeOffer::new(
             1,
-            0,
-            1,
+            PlayerSlot(0),
+            PlayerSlot(1),
             TradeItems::new().with_gold(100),
             TradeItems::new().with_gold(100),
             1,
@@ -910,8 +977,8 @@ mod tests {
         // One-way trade
         let one_way = TradeOffer::new(
             2,
-            0,
-            1,
+            PlayerSlot(0),
+            PlayerSlot(1),
             TradeItems::new().with_gold(100),
             TradeItems::new(),
             1,
@@ -929,30 +996,43 @@ mod tests {
 
         // Gold value
         let gold_items = TradeItems::new().with_gold(100);
-        assert_eq!(calculate_trade_value(&gold_items, &game, 0), 100);
+        assert_eq!(
+            calculate_trade_value(&gold_items, &game, PlayerSlot(0)),
+            100
+        );
 
         // Gold per turn value
         let gpt_items = TradeItems::new().with_gold_per_turn(10);
-        assert_eq!(calculate_trade_value(&gpt_items, &game, 0), 200); // 10 * 20
+        assert_eq!(calculate_trade_value(&gpt_items, &game, PlayerSlot(0)), 200); // 10 * 20
 
         // Strategic resource value
         let resource_items = TradeItems::new().with_resource(Resource::Iron, 2);
-        assert_eq!(calculate_trade_value(&resource_items, &game, 0), 100); // 50 * 2
+        assert_eq!(
+            calculate_trade_value(&resource_items, &game, PlayerSlot(0)),
+            100
+        ); // 50 * 2
 
         // Luxury resource value
         let luxury_items = TradeItems::new().with_resource(Resource::Gold, 1);
-        assert_eq!(calculate_trade_value(&luxury_items, &game, 0), 40);
+        assert_eq!(
+            calculate_trade_value(&luxury_items, &game, PlayerSlot(0)),
+            40
+        );
 
         // Open borders value
         let borders_items = TradeItems::new().with_open_borders();
-        assert_eq!(calculate_trade_value(&borders_items, &game, 0), 30);
+        assert_eq!(
+            calculate_trade_value(&borders_items, &game, PlayerSlot(0)),
+            30
+        );
 
         // Combined value
         let combined = TradeItems::new()
             .with_gold(100)
             .with_resource(Resource::Iron, 1)
             .with_open_borders();
-        assert_eq!(calculate_trade_value(&combined, &game, 0), 180); // 100 + 50 + 30
+        assert_eq!(calculate_trade_value(&combined, &game, PlayerSlot(0)), 180);
+        // 100 + 50 + 30
     }
 
     #[test]
@@ -961,8 +1041,8 @@ mod tests {
 
         let offer = TradeOffer::new(
             1,
-            0,
-            1,
+            PlayerSlot(0),
+            PlayerSlot(1),
             TradeItems::new().with_gold(100),
             TradeItems::new().with_gold(50),
             1,
@@ -972,20 +1052,20 @@ mod tests {
         execute_trade(&mut game, &offer).unwrap();
 
         // Player 0: started with 1000, gave 100, received 50 = 950
-        assert_eq!(game.get_player(0).unwrap().gold, 950);
+        assert_eq!(game.get_player(PlayerSlot(0)).unwrap().gold, 950);
         // Player 1: started with 1000, gave 50, received 100 = 1050
-        assert_eq!(game.get_player(1).unwrap().gold, 1050);
+        assert_eq!(game.get_player(PlayerSlot(1)).unwrap().gold, 1050);
     }
 
     #[test]
     fn test_execute_trade_insufficient_gold() {
         let mut game = create_test_game();
-        game.get_player_mut(0).unwrap().gold = 50;
+        game.get_player_mut(PlayerSlot(0)).unwrap().gold = 50;
 
         let offer = TradeOffer::new(
             1,
-            0,
-            1,
+            PlayerSlot(0),
+            PlayerSlot(1),
             TradeItems::new().with_gold(100),
             TradeItems::new(),
             1,
@@ -1001,12 +1081,12 @@ mod tests {
     #[test]
     fn test_execute_trade_at_war() {
         let mut game = create_test_game();
-        game.diplomacy.declare_war(0, 1, 1);
+        game.diplomacy.declare_war(PlayerSlot(0), PlayerSlot(1), 1);
 
         let offer = TradeOffer::new(
             1,
-            0,
-            1,
+            PlayerSlot(0),
+            PlayerSlot(1),
             TradeItems::new().with_gold(100),
             TradeItems::new(),
             1,
@@ -1022,8 +1102,8 @@ mod tests {
 
         let offer = TradeOffer::new(
             1,
-            0,
-            0, // Same player
+            PlayerSlot(0),
+            PlayerSlot(0), // Same player
             TradeItems::new().with_gold(100),
             TradeItems::new(),
             1,
@@ -1038,14 +1118,14 @@ mod tests {
         let mut game = create_test_game();
 
         // Give player 0 a technology
-        game.get_player_mut(0)
+        game.get_player_mut(PlayerSlot(0))
             .unwrap()
             .add_tech("writing".to_string());
 
         let offer = TradeOffer::new(
             1,
-            0,
-            1,
+            PlayerSlot(0),
+            PlayerSlot(1),
             TradeItems::new().with_technology("writing".to_string()),
             TradeItems::new().with_gold(100),
             1,
@@ -1055,7 +1135,60 @@ mod tests {
         execute_trade(&mut game, &offer).unwrap();
 
         // Player 1 should now have the technology
-        assert!(game.get_player(1).unwrap().has_tech(&"writing".to_string()));
+        assert!(game
+            .get_player(PlayerSlot(1))
+            .unwrap()
+            .has_tech(&"writing".to_string()));
+    }
+
+    #[test]
+    fn test_tech_trading_and_brokering_settings() {
+        let mut game = create_test_game();
+        game.get_player_mut(PlayerSlot(0))
+            .unwrap()
+            .add_tech("writing".to_string());
+        let writing = || TradeItems::new().with_technology("writing".to_string());
+
+        game.settings.tech_trading = false;
+        let offer = TradeOffer::new(
+            1,
+            PlayerSlot(0),
+            PlayerSlot(1),
+            writing(),
+            TradeItems::new(),
+            1,
+            None,
+        );
+        assert_eq!(
+            execute_trade(&mut game, &offer),
+            Err(TradeError::TechTradingDisabled)
+        );
+
+        game.settings.tech_trading = true;
+        execute_trade(&mut game, &offer).unwrap();
+        assert!(game
+            .get_player(PlayerSlot(1))
+            .unwrap()
+            .traded_techs
+            .contains("writing"));
+
+        // Player 1 can't pass on a tech they only traded for
+        let resale = TradeOffer::new(
+            2,
+            PlayerSlot(1),
+            PlayerSlot(0),
+            writing(),
+            TradeItems::new(),
+            1,
+            None,
+        );
+        assert_eq!(
+            execute_trade(&mut game, &resale),
+            Err(TradeError::TechBrokering)
+        );
+
+        game.settings.tech_brokering = true;
+        assert!(execute_trade(&mut game, &resale).is_ok());
     }
 
     #[test]
@@ -1065,8 +1198,8 @@ mod tests {
         // Player 0 does NOT have the technology
         let offer = TradeOffer::new(
             1,
-            0,
-            1,
+            PlayerSlot(0),
+            PlayerSlot(1),
             TradeItems::new().with_technology("writing".to_string()),
             TradeItems::new(),
             1,
@@ -1085,8 +1218,8 @@ mod tests {
 
         let offer = TradeOffer::new(
             0,
-            0,
-            1,
+            PlayerSlot(0),
+            PlayerSlot(1),
             TradeItems::new().with_gold_per_turn(10),
             TradeItems::new(),
             1,
@@ -1108,7 +1241,15 @@ mod tests {
 
     #[test]
     fn test_trade_offer_expiration() {
-        let offer = TradeOffer::new(1, 0, 1, TradeItems::new(), TradeItems::new(), 1, Some(5));
+        let offer = TradeOffer::new(
+            1,
+            PlayerSlot(0),
+            PlayerSlot(1),
+            TradeItems::new(),
+            TradeItems::new(),
+            1,
+            Some(5),
+        );
 
         assert!(!offer.is_expired(4));
         assert!(offer.is_expired(5));
@@ -1119,8 +1260,8 @@ mod tests {
     fn test_trade_offer_no_expiration() {
         let offer = TradeOffer::new(
             1,
-            0,
-            1,
+            PlayerSlot(0),
+            PlayerSlot(1),
             TradeItems::new(),
             TradeItems::new(),
             1,
@@ -1161,8 +1302,8 @@ mod tests {
 
         let offer = TradeOffer::new(
             0,
-            0,
-            1,
+            PlayerSlot(0),
+            PlayerSlot(1),
             TradeItems::new()
                 .with_gold(100)
                 .with_resource(Resource::Iron, 2),
@@ -0,0 +1,512 @@
+//! End-of-turn processing in ordered phases.
+//!
+//! Ending a turn runs each [`TurnPhase`] in order. Phases that mutate
+//! shared state (borders, roads, upkeep) run sequentially. Per-entity work
+//! that only reads state, such as city yields, unit healing context and
+//! player vision, is computed with [`par_map`] and applied in ID order, so
+//! every peer ends up with the same state regardless of thread count.
+
+use crate::borders;
+use crate::city::ProductionItem;
+use crate::contact;
+use crate::eras;
+use crate::game_state::{GameError, GameState};
+use crate::hex::HexCoord;
+use crate::parallel::par_map;
+use crate::replay::ActionEffect;
+use crate::research;
+use crate::roads;
+use crate::scenario;
+use crate::stockpile;
+use crate::terraform;
+use crate::types::{PlayerSlot, UnitId};
+use crate::unit::Unit;
+use crate::upkeep;
+use crate::visibility::VisibilityFilter;
+use crate::wonders;
+use std::collections::{BTreeSet, HashSet};
+
+/// Phases of end-of-turn processing, in the order they run.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum TurnPhase {
+    /// Cities of the ending player gain culture and claim tiles.
+    Borders,
+    /// Workers of the ending player advance roads and feature clearing.
+    Roads,
+    /// The ending player collects income and strategic resources, and pays
+    /// upkeep.
+    Upkeep,
+    /// The ending player's cities put production toward their current item.
+    Production,
+    /// The ending player's science goes into their research queue.
+    Research,
+    /// Play passes to the next player.
+    Advance,
+    /// Pollution warms the climate, once per game turn.
+    Climate,
+    /// Scenario rules are checked, once per game turn.
+    Scenario,
+    /// Units of the next player heal and reset movement.
+    Units,
+    /// Every player's vision is recomputed and explored tiles updated.
+    Visibility,
+}
+
+impl TurnPhase {
+    /// All phases in execution order.
+    pub const ORDER: [TurnPhase; 10] = [
+        TurnPhase::Borders,
+        TurnPhase::Roads,
+        TurnPhase::Upkeep,
+        TurnPhase::Production,
+        TurnPhase::Research,
+        TurnPhase::Advance,
+        TurnPhase::Climate,
+        TurnPhase::Scenario,
+        TurnPhase::Units,
+        TurnPhase::Visibility,
+    ];
+}
+
+/// End the current player's turn, running every phase in order.
+pub fn process_end_turn(state: &mut GameState) -> Result<Vec<ActionEffect>, GameError> {
+    let ending = state.current_player;
+    let mut effects = Vec::new();
+    for phase in TurnPhase::ORDER {
+        run_phase(state, phase, ending, &mut effects)?;
+    }
+    effects.push(ActionEffect::TurnStarted {
+        player_id: state.current_player,
+        turn: state.turn,
+    });
+    Ok(effects)
+}
+
+fn run_phase(
+    state: &mut GameState,
+    phase: TurnPhase,
+    ending: PlayerSlot,
+    effects: &mut Vec<ActionEffect>,
+) -> Result<(), GameError> {
+    match phase {
+        TurnPhase::Borders => {
+            let claims = borders::grow_borders(state, ending);
+            effects.extend(claims.into_iter().map(ActionEffect::from));
+        }
+        TurnPhase::Roads => {
+            // Workers finish roads before upkeep so new roads are paid for
+            // and connect cities straight away
+            let built = roads::progress_road_work(state, ending);
+            effects.extend(built.into_iter().map(ActionEffect::from));
+            // Chopped forests count toward this turn's production
+            let cleared = terraform::progress_feature_work(state, ending);
+            effects.extend(cleared.into_iter().map(ActionEffect::from));
+        }
+        TurnPhase::Upkeep => {
+            let resources = stockpile::collect_resources(state, ending);
+            effects.extend(resources.depleted.into_iter().map(|depletion| {
+                ActionEffect::ResourceDepleted {
+                    player_id: ending,
+                    coord: depletion.coord,
+                    resource: depletion.resource,
+                }
+            }));
+            // An empty treasury disbands units
+            let report = upkeep::apply_upkeep(state, ending);
+            effects.extend(
+                report
+                    .disbanded
+                    .into_iter()
+                    .map(|unit_id| ActionEffect::UnitDestroyed { unit_id }),
+            );
+        }
+        TurnPhase::Production => progress_production(state, ending, effects),
+        TurnPhase::Research => {
+            // Agreements pay off first so the science counts this turn
+            let science = research::research_agreement_science(&state.settings);
+            let partners = research::complete_research_agreements(state, ending);
+            effects.extend(partners.into_iter().flat_map(|partner| {
+                [(ending, partner), (partner, ending)].map(|(player_id, partner)| {
+                    ActionEffect::ResearchAgreementCompleted {
+                        player_id,
+                        partner,
+                        science,
+                    }
+                })
+            }));
+
+            let researched = research::progress_research(state, ending);
+            effects.extend(
+                researched
+                    .into_iter()
+                    .map(|tech_id| ActionEffect::TechResearched {
+                        player_id: ending,
+                        tech_id,
+                    }),
+            );
+            if let Some(era) = eras::update_era(state, ending) {
+                effects.push(ActionEffect::EraEntered {
+                    player_id: ending,
+                    era,
+                });
+            }
+        }
+        TurnPhase::Advance => state.next_turn()?,
+        TurnPhase::Climate => {
+            let changes = terraform::update_climate(state);
+            effects.extend(changes.into_iter().map(ActionEffect::from));
+        }
+        TurnPhase::Scenario => {
+            let report = scenario::evaluate_rules(state);
+            effects.extend(
+                report.defeated.into_iter().map(|(player_id, rule_id)| {
+                    ActionEffect::PlayerDefeated { player_id, rule_id }
+                }),
+            );
+            if let Some((winner_id, victory_type)) = report.winner {
+                effects.push(ActionEffect::GameEnded {
+                    winner_id,
+                    victory_type: format!("{:?}", victory_type),
+                });
+            } else if state.current_player().is_some_and(|p| p.eliminated) {
+                // Don't leave the game waiting on a defeated player
+                state.next_turn()?;
+            }
+        }
+        TurnPhase::Units => start_units(state, effects),
+        TurnPhase::Visibility => update_exploration(state, effects),
+    }
+    Ok(())
+}
+
+/// Put the ending player's production toward each city's current item and
+/// deliver whatever is completed.
+fn progress_production(state: &mut GameState, ending: PlayerSlot, effects: &mut Vec<ActionEffect>) {
+    for (city_id, yields) in state.city_yield_table(ending) {
+        let affordable = state
+            .cities
+            .get(&city_id)
+            .and_then(|c| c.production.as_ref())
+            .is_none_or(|item| {
+                state
+                    .get_player(ending)
+                    .is_some_and(|p| stockpile::can_afford(p, item))
+            });
+        let Some(city) = state.cities.get_mut(&city_id) else {
+            continue;
+        };
+        if !affordable {
+            // Hold the finished unit until the stockpile can pay for it
+            if let Some(cost) = city.production.as_ref().map(|item| item.cost()) {
+                city.production_progress = (city.production_progress
+                    + yields.production.max(0) as u32)
+                    .min(cost.saturating_sub(1));
+            }
+            continue;
+        }
+        let Some(item) = city.progress_production(yields.production) else {
+            continue;
+        };
+        let position = city.position;
+        match item {
+            ProductionItem::Building(building) => {
+                city.add_building(building);
+                effects.push(ActionEffect::BuildingCompleted { city_id, building });
+            }
+            ProductionItem::Unit(unit_type) => {
+                if let Some(player) = state.get_player_mut(ending) {
+                    stockpile::consume(player, &item);
+                }
+                let unit_id = state.allocate_unit_id();
+                state
+                    .units
+                    .insert(unit_id, Unit::new(unit_id, ending, unit_type, position));
+                effects.push(ActionEffect::UnitCreated {
+                    unit_id,
+                    unit_type,
+                    position,
+                });
+            }
+            ProductionItem::Wonder(wonder) => {
+                // Rivals are refunded as soon as a wonder is built, so it
+                // can only be missing from the registry here
+                let Some(completion) = wonders::complete_wonder(state, city_id, wonder) else {
+                    continue;
+                };
+                effects.push(ActionEffect::WonderCompleted {
+                    city_id,
+                    player_id: ending,
+                    wonder,
+                });
+                effects.extend(completion.refunds.into_iter().map(|refund| {
+                    ActionEffect::WonderLost {
+                        city_id: refund.city_id,
+                        player_id: refund.player_id,
+                        wonder,
+                        gold: refund.gold,
+                    }
+                }));
+            }
+            // Projects don't change the map
+            ProductionItem::Project(_) => {}
+        }
+    }
+}
+
+/// Heal and reset the next player's units based on where they rested.
+fn start_units(state: &mut GameState, effects: &mut Vec<ActionEffect>) {
+    let next = state.current_player;
+    let mut unit_ids: Vec<UnitId> = state
+        .units
+        .values()
+        .filter(|u| u.owner == next)
+        .map(|u| u.id)
+        .collect();
+    unit_ids.sort_unstable();
+
+    let contexts = {
+        let state = &*state;
+        par_map(&unit_ids, |id| state.unit_turn_context(&state.units[id]))
+    };
+
+    for (unit_id, ctx) in unit_ids.into_iter().zip(contexts) {
+        let Some(unit) = state.units.get_mut(&unit_id) else {
+            continue;
+        };
+        let could_promote = unit.can_promote();
+        let report = unit.start_turn(&ctx);
+        if report.healed > 0 {
+            effects.push(ActionEffect::UnitHealed {
+                unit_id,
+                amount: report.healed,
+                new_health: unit.health,
+            });
+        }
+        if !could_promote && unit.can_promote() {
+            effects.push(ActionEffect::PromotionAvailable { unit_id });
+        }
+    }
+}
+
+/// Mark every tile a player can currently see as explored.
+fn update_exploration(state: &mut GameState, effects: &mut Vec<ActionEffect>) {
+    let player_ids: Vec<PlayerSlot> = PlayerSlot::all(state.players.len()).collect();
+    let visible: Vec<(HashSet<HexCoord>, BTreeSet<PlayerSlot>)> = {
+        let state = &*state;
+        par_map(&player_ids, |id| {
+            let mut filter = VisibilityFilter::new(*id);
+            filter.update_from_game_state(state);
+            let sighted = contact::sighted_players(state, &filter);
+            (filter.visible_tiles().clone(), sighted)
+        })
+    };
+
+    for (player_id, (tiles, sighted)) in player_ids.into_iter().zip(visible) {
+        let player = &mut state.players[player_id.index()];
+        for coord in tiles {
+            player.explore_tile(coord);
+        }
+        let met = contact::record_sightings(state, player_id, sighted);
+        effects.extend(first_contact_effects(player_id, met));
+    }
+}
+
+/// Effects for a player's new contacts, one for each side of each contact.
+pub(crate) fn first_contact_effects(
+    player_id: PlayerSlot,
+    met: Vec<PlayerSlot>,
+) -> Vec<ActionEffect> {
+    met.into_iter()
+        .flat_map(|other| {
+            [(player_id, other), (other, player_id)].map(|(player_id, met_player)| {
+                ActionEffect::FirstContact {
+                    player_id,
+                    met_player,
+                }
+            })
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::city::City;
+    use crate::game_state::GamePhase;
+    use crate::map::Map;
+    use crate::player::{Civilization, Player};
+    use crate::settings::GameSettings;
+    use crate::terrain::{Resource, Terrain};
+    use crate::types::{GameId, Npub};
+    use crate::unit::{Unit, UnitType};
+
+    /// A large game with many cities and units per player.
+    fn busy_state(players: u8, per_player: i32) -> GameState {
+        let mut settings = GameSettings::new("Test".to_string());
+        settings.player_count = players;
+        let mut state = GameState::new(GameId::new("test"), settings, [0u8; 32]);
+        state.map = Map::filled(120, 75, Terrain::Grassland);
+        for id in 0..players {
+            state
+                .add_player(Player::new(
+                    PlayerSlot(id),
+                    Npub(format!("pk{}", id)),
+                    format!("P{}", id),
+                    Civilization::default(),
+                ))
+                .unwrap();
+            for i in 0..per_player {
+                let position = HexCoord::new(5 + i * 4 % 110, 3 + id as i32 * 9);
+                let city_id = state.allocate_city_id();
+                state.cities.insert(
+                    city_id,
+                    City::new(
+                        city_id,
+                        PlayerSlot(id),
+                        format!("C{}", city_id),
+                        position,
+                        i == 0,
+                    ),
+                );
+                for _ in 0..4 {
+                    let unit_id = state.allocate_unit_id();
+                    let mut unit = Unit::new(unit_id, PlayerSlot(id), UnitType::Warrior, position);
+                    unit.take_damage(30);
+                    state.units.insert(unit_id, unit);
+                }
+            }
+        }
+        state.phase = GamePhase::Playing;
+        state
+    }
+
+    #[test]
+    fn test_phases_run_in_order() {
+        assert_eq!(TurnPhase::ORDER[0], TurnPhase::Borders);
+        assert_eq!(TurnPhase::ORDER[3], TurnPhase::Production);
+        assert_eq!(TurnPhase::ORDER[4], TurnPhase::Research);
+        assert_eq!(TurnPhase::ORDER[5], TurnPhase::Advance);
+        assert_eq!(TurnPhase::ORDER[6], TurnPhase::Climate);
+        assert_eq!(TurnPhase::ORDER[7], TurnPhase::Scenario);
+        assert_eq!(TurnPhase::ORDER[9], TurnPhase::Visibility);
+    }
+
+    #[test]
+    fn test_end_turn_is_deterministic() {
+        let mut a = busy_state(4, 6);
+        let mut b = busy_state(4, 6);
+        for _ in 0..8 {
+            assert_eq!(
+                process_end_turn(&mut a).unwrap(),
+                process_end_turn(&mut b).unwrap()
+            );
+        }
+        assert_eq!(crate::audit::state_hash(&a), crate::audit::state_hash(&b));
+    }
+
+    #[test]
+    fn test_end_turn_heals_next_player_and_explores() {
+        let mut state = busy_state(2, 2);
+        let effects = process_end_turn(&mut state).unwrap();
+
+        assert_eq!(state.current_player, PlayerSlot(1));
+        assert!(effects
+            .iter()
+            .any(|e| matches!(e, ActionEffect::UnitHealed { .. })));
+        assert_eq!(
+            effects.last(),
+            Some(&ActionEffect::TurnStarted {
+                player_id: PlayerSlot(1),
+                turn: state.turn,
+            })
+        );
+        let city = state
+            .cities
+            .values()
+            .find(|c| c.owner == PlayerSlot(0))
+            .unwrap();
+        assert!(state.players[0].has_explored(&city.position));
+    }
+
+    #[test]
+    fn test_units_wait_for_strategic_resources() {
+        let mut state = busy_state(2, 1);
+        let knight = ProductionItem::Unit(UnitType::Knight);
+        let city_id = state
+            .cities
+            .values()
+            .find(|c| c.owner == PlayerSlot(0))
+            .unwrap()
+            .id;
+        let city = state.cities.get_mut(&city_id).unwrap();
+        city.set_production(knight.clone());
+        city.production_progress = knight.cost() - 1;
+        let center = city.position;
+        state.map.get_mut(&center).unwrap().terrain = Terrain::Plains;
+
+        let effects = process_end_turn(&mut state).unwrap();
+        assert!(!effects
+            .iter()
+            .any(|e| matches!(e, ActionEffect::UnitCreated { .. })));
+        assert_eq!(
+            state.cities[&city_id].production_progress,
+            knight.cost() - 1
+        );
+        process_end_turn(&mut state).unwrap();
+
+        state.players[0].stockpile.insert(Resource::Horses, 12);
+        let effects = process_end_turn(&mut state).unwrap();
+        assert!(effects.iter().any(|e| matches!(
+            e,
+            ActionEffect::UnitCreated {
+                unit_type: UnitType::Knight,
+                ..
+            }
+        )));
+        assert_eq!(state.players[0].stockpiled(Resource::Horses), 2);
+    }
+
+    #[test]
+    fn test_scenario_rules_checked_on_new_turn() {
+        let mut state = busy_state(2, 1);
+        state.turn = 1;
+        state.settings.scenario_rules = vec![crate::scenario::ScenarioRule {
+            id: "deadline".to_string(),
+            description: String::new(),
+            outcome: crate::scenario::RuleOutcome::Defeat,
+            players: vec![PlayerSlot(1)],
+            condition: crate::scenario::Condition::TurnAtLeast { turn: 2 },
+            turns: 1,
+        }];
+
+        // Rules are only checked once the round is over
+        let effects = process_end_turn(&mut state).unwrap();
+        assert!(!effects
+            .iter()
+            .any(|e| matches!(e, ActionEffect::PlayerDefeated { .. })));
+
+        let effects = process_end_turn(&mut state).unwrap();
+        assert!(effects.contains(&ActionEffect::PlayerDefeated {
+            player_id: PlayerSlot(1),
+            rule_id: "deadline".to_string(),
+        }));
+        assert!(effects.contains(&ActionEffect::GameEnded {
+            winner_id: PlayerSlot(0),
+            victory_type: "Scenario".to_string(),
+        }));
+        assert_eq!(state.phase, GamePhase::Ended);
+    }
+
+    #[test]
+    #[ignore] // Timing check; run with --release
+    fn bench_eight_player_huge_map_turn() {
+        let mut state = busy_state(8, 12);
+        let start = std::time::Instant::now();
+        for _ in 0..8 {
+            process_end_turn(&mut state).unwrap();
+        }
+        let per_turn = start.elapsed() / 8;
+        println!("8-player end turn: {:?}", per_turn);
+        assert!(per_turn < std::time::Duration::from_millis(100));
+    }
+}
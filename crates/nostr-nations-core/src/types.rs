@@ -1,18 +1,199 @@
 //! Core type aliases used throughout the crate.
 
+use crate::fixed::deterministic;
+use schemars::JsonSchema;
 use serde::{Deserialize, Serialize};
+use std::borrow::Borrow;
+use std::fmt;
+use ts_rs::TS;
+
+/// Declare a `Copy` integer identifier that serializes as its bare number.
+macro_rules! numeric_id {
+    ($(#[$meta:meta])* $name:ident($inner:ty)) => {
+        $(#[$meta])*
+        #[derive(
+            Clone,
+            Copy,
+            Debug,
+            Default,
+            PartialEq,
+            Eq,
+            PartialOrd,
+            Ord,
+            Hash,
+            Serialize,
+            Deserialize,
+            JsonSchema,
+            TS,
+        )]
+        #[serde(transparent)]
+        pub struct $name(pub $inner);
+
+        deterministic!(struct $name($inner));
+
+        impl $name {
+            /// The raw number.
+            pub const fn get(self) -> $inner {
+                self.0
+            }
+
+            /// The identifier allocated after this one.
+            pub const fn next(self) -> Self {
+                Self(self.0 + 1)
+            }
+        }
+
+        impl From<$inner> for $name {
+            fn from(value: $inner) -> Self {
+                Self(value)
+            }
+        }
+
+        impl From<$name> for $inner {
+            fn from(id: $name) -> Self {
+                id.0
+            }
+        }
+
+        impl fmt::Display for $name {
+            fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+                self.0.fmt(f)
+            }
+        }
+    };
+}
+
+/// Declare a string identifier that serializes as its bare string.
+macro_rules! string_id {
+    ($(#[$meta:meta])* $name:ident) => {
+        $(#[$meta])*
+        #[derive(
+            Clone,
+            Debug,
+            Default,
+            PartialEq,
+            Eq,
+            PartialOrd,
+            Ord,
+            Hash,
+            Serialize,
+            Deserialize,
+            JsonSchema,
+            TS,
+        )]
+        #[serde(transparent)]
+        pub struct $name(pub String);
+
+        deterministic!(struct $name(String));
+
+        impl $name {
+            /// Wrap a raw identifier.
+            pub fn new(id: impl Into<String>) -> Self {
+                Self(id.into())
+            }
+
+            /// The raw identifier.
+            pub fn as_str(&self) -> &str {
+                &self.0
+            }
+
+            /// Unwrap into the raw identifier.
+            pub fn into_string(self) -> String {
+                self.0
+            }
+        }
+
+        impl From<String> for $name {
+            fn from(id: String) -> Self {
+                Self(id)
+            }
+        }
+
+        impl From<&str> for $name {
+            fn from(id: &str) -> Self {
+                Self(id.to_string())
+            }
+        }
+
+        impl From<$name> for String {
+            fn from(id: $name) -> Self {
+                id.0
+            }
+        }
+
+        impl AsRef<str> for $name {
+            fn as_ref(&self) -> &str {
+                &self.0
+            }
+        }
+
+        impl Borrow<str> for $name {
+            fn borrow(&self) -> &str {
+                &self.0
+            }
+        }
+
+        impl PartialEq<str> for $name {
+            fn eq(&self, other: &str) -> bool {
+                self.0 == other
+            }
+        }
 
-/// Unique identifier for a game session.
-pub type GameId = String;
+        impl PartialEq<&str> for $name {
+            fn eq(&self, other: &&str) -> bool {
+                self.0 == *other
+            }
+        }
+
+        impl fmt::Display for $name {
+            fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+                f.write_str(&self.0)
+            }
+        }
+    };
+}
+
+string_id! {
+    /// Unique identifier for a game session.
+    GameId
+}
+
+string_id! {
+    /// A player's Nostr public key, as published in their join event.
+    Npub
+}
+
+numeric_id! {
+    /// Player slot in a game, `0..player_count`.
+    PlayerSlot(u8)
+}
+
+impl PlayerSlot {
+    /// Position of the player in [`GameState::players`](crate::game_state::GameState::players).
+    pub const fn index(self) -> usize {
+        self.0 as usize
+    }
 
-/// Player index (0-3 for 4 player games).
-pub type PlayerId = u8;
+    /// The slot that plays after this one, wrapping around `count` players.
+    pub const fn after(self, count: usize) -> Self {
+        Self(((self.0 as usize + 1) % count) as u8)
+    }
 
-/// Unique identifier for a unit.
-pub type UnitId = u64;
+    /// Every slot in a game of `count` players.
+    pub fn all(count: usize) -> impl Iterator<Item = Self> {
+        (0..count as u8).map(Self)
+    }
+}
 
-/// Unique identifier for a city.
-pub type CityId = u64;
+numeric_id! {
+    /// Unique identifier for a unit.
+    UnitId(u64)
+}
+
+numeric_id! {
+    /// Unique identifier for a city.
+    CityId(u64)
+}
 
 /// Nostr event ID for chain validation.
 pub type EventId = String;
@@ -21,7 +202,9 @@ pub type EventId = String;
 pub type TechId = String;
 
 /// Game era progression.
-#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash, Serialize, Deserialize, Default)]
+#[derive(
+    Clone, Copy, Debug, PartialEq, Eq, Hash, Serialize, Deserialize, JsonSchema, TS, Default,
+)]
 pub enum Era {
     #[default]
     Ancient,
@@ -30,6 +213,8 @@ pub enum Era {
     Renaissance,
     Industrial,
     Modern,
+    Atomic,
+    Information,
 }
 
 impl Era {
@@ -41,11 +226,13 @@ impl Era {
             Era::Medieval => Some(Era::Renaissance),
             Era::Renaissance => Some(Era::Industrial),
             Era::Industrial => Some(Era::Modern),
-            Era::Modern => None,
+            Era::Modern => Some(Era::Atomic),
+            Era::Atomic => Some(Era::Information),
+            Era::Information => None,
         }
     }
 
-    /// Get the era index (0-5).
+    /// Get the era index (0-7).
     pub const fn index(&self) -> usize {
         match self {
             Era::Ancient => 0,
@@ -54,6 +241,8 @@ impl Era {
             Era::Renaissance => 3,
             Era::Industrial => 4,
             Era::Modern => 5,
+            Era::Atomic => 6,
+            Era::Information => 7,
         }
     }
 
@@ -66,6 +255,8 @@ impl Era {
             Era::Renaissance,
             Era::Industrial,
             Era::Modern,
+            Era::Atomic,
+            Era::Information,
         ]
     }
 }
@@ -79,6 +270,8 @@ impl std::fmt::Display for Era {
             Era::Renaissance => write!(f, "Renaissance Era"),
             Era::Industrial => write!(f, "Industrial Era"),
             Era::Modern => write!(f, "Modern Era"),
+            Era::Atomic => write!(f, "Atomic Era"),
+            Era::Information => write!(f, "Information Era"),
         }
     }
 }
@@ -194,17 +387,21 @@ impl VictoryConditions {
 }
 
 /// Types of victory a player can achieve.
-#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash, Serialize, Deserialize, JsonSchema, TS)]
 pub enum VictoryType {
     Domination,
     Science,
     Economic,
     Diplomatic,
     Score,
+    /// Every other player conceded.
+    Concession,
+    /// A scenario rule declared the winner.
+    Scenario,
 }
 
 /// RGB color for player identification.
-#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize, Deserialize)]
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash, Serialize, Deserialize)]
 pub struct PlayerColor {
     pub r: u8,
     pub g: u8,
@@ -217,16 +414,16 @@ impl PlayerColor {
     }
 
     /// Default colors for players 0-7.
-    pub const fn default_for_player(player_id: PlayerId) -> Self {
+    pub const fn default_for_player(player_id: PlayerSlot) -> Self {
         match player_id {
-            0 => Self::new(255, 0, 0),     // Red
-            1 => Self::new(0, 0, 255),     // Blue
-            2 => Self::new(255, 255, 0),   // Yellow
-            3 => Self::new(0, 255, 0),     // Green
-            4 => Self::new(128, 0, 128),   // Purple
-            5 => Self::new(255, 165, 0),   // Orange
-            6 => Self::new(0, 255, 255),   // Cyan
-            _ => Self::new(255, 192, 203), // Pink
+            PlayerSlot(0) => Self::new(255, 0, 0),   // Red
+            PlayerSlot(1) => Self::new(0, 0, 255),   // Blue
+            PlayerSlot(2) => Self::new(255, 255, 0), // Yellow
+            PlayerSlot(3) => Self::new(0, 255, 0),   // Green
+            PlayerSlot(4) => Self::new(128, 0, 128), // Purple
+            PlayerSlot(5) => Self::new(255, 165, 0), // Orange
+            PlayerSlot(6) => Self::new(0, 255, 255), // Cyan
+            _ => Self::new(255, 192, 203),           // Pink
         }
     }
 
@@ -249,7 +446,8 @@ mod tests {
     #[test]
     fn test_era_progression() {
         assert_eq!(Era::Ancient.next(), Some(Era::Classical));
-        assert_eq!(Era::Modern.next(), None);
+        assert_eq!(Era::Modern.next(), Some(Era::Atomic));
+        assert_eq!(Era::Information.next(), None);
     }
 
     #[test]
@@ -266,7 +464,7 @@ mod tests {
 
     #[test]
     fn test_player_colors() {
-        let red = PlayerColor::default_for_player(0);
+        let red = PlayerColor::default_for_player(PlayerSlot(0));
         assert_eq!(red.r, 255);
         assert_eq!(red.g, 0);
         assert_eq!(red.b, 0);
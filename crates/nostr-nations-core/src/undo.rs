@@ -0,0 +1,388 @@
+//! Pre-commit action buffer for undo/redo within a turn.
+//!
+//! While buffering is enabled, the current player's actions are applied
+//! locally but held back from signing and broadcasting. They can be undone
+//! and redone freely until the turn is committed, which happens when the
+//! player ends their turn or takes an action that cannot be taken back
+//! (combat, diplomacy, anything that reveals randomness or involves
+//! another player). Moves and new cities can be undone unless they reveal
+//! tiles the player hadn't seen, since undoing would keep what was seen.
+//!
+//! Simultaneous-turn games should disable buffering so actions are
+//! committed as soon as they are applied.
+//!
+//! Each action is stamped with its turn and sequence number when it is
+//! recorded, so a batch committed by ending the turn still carries the turn
+//! its actions were taken in.
+
+use crate::events::GameAction;
+use crate::game_state::GameState;
+use crate::types::PlayerSlot;
+use crate::visibility::VisibilityFilter;
+
+/// An applied action that has not been committed yet.
+#[derive(Clone, Debug)]
+pub struct BufferedAction {
+    /// Player who took the action.
+    pub player_id: PlayerSlot,
+    /// The action that was applied.
+    pub action: GameAction,
+    /// Turn the action was taken in.
+    pub turn: u32,
+    /// Sequence number of the action's event.
+    pub sequence: u32,
+    /// Game state before the action was applied.
+    before: GameState,
+}
+
+/// A committed action, ready to be signed and broadcast.
+#[derive(Clone, Debug)]
+pub struct CommittedAction {
+    /// Player who took the action.
+    pub player_id: PlayerSlot,
+    /// The action that was applied.
+    pub action: GameAction,
+    /// Turn the action was taken in.
+    pub turn: u32,
+    /// Sequence number of the action's event.
+    pub sequence: u32,
+}
+
+impl From<BufferedAction> for CommittedAction {
+    fn from(entry: BufferedAction) -> Self {
+        Self {
+            player_id: entry.player_id,
+            action: entry.action,
+            turn: entry.turn,
+            sequence: entry.sequence,
+        }
+    }
+}
+
+/// Buffer of uncommitted actions with undo/redo support.
+#[derive(Clone, Debug)]
+pub struct ActionBuffer {
+    /// Whether actions are buffered before committing.
+    enabled: bool,
+    /// Applied but uncommitted actions, oldest first.
+    pending: Vec<BufferedAction>,
+    /// Undone actions that can be redone, most recently undone last.
+    redo: Vec<(PlayerSlot, GameAction)>,
+    /// Committed actions waiting to be signed and broadcast.
+    committed: Vec<CommittedAction>,
+    /// Sequence number for the next recorded action.
+    next_sequence: u32,
+}
+
+impl Default for ActionBuffer {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl ActionBuffer {
+    /// Create a new buffer with buffering enabled.
+    pub fn new() -> Self {
+        Self {
+            enabled: true,
+            pending: Vec::new(),
+            redo: Vec::new(),
+            committed: Vec::new(),
+            next_sequence: 0,
+        }
+    }
+
+    /// Check if buffering is enabled.
+    pub fn is_enabled(&self) -> bool {
+        self.enabled
+    }
+
+    /// Enable or disable buffering. Disabling commits any pending actions.
+    pub fn set_enabled(&mut self, enabled: bool) {
+        if !enabled {
+            self.commit();
+        }
+        self.enabled = enabled;
+    }
+
+    /// Check if an action can be held in the buffer and undone later.
+    pub fn is_undoable(action: &GameAction) -> bool {
+        matches!(
+            action,
+            GameAction::MoveUnit { .. }
+                | GameAction::FortifyUnit { .. }
+                | GameAction::FortifyUntilHealed { .. }
+                | GameAction::SleepUnit { .. }
+                | GameAction::WakeUnit { .. }
+                | GameAction::ChoosePromotion { .. }
+                | GameAction::FoundCity { .. }
+                | GameAction::BuildImprovement { .. }
+                | GameAction::BuildRoad { .. }
+                | GameAction::SetProduction { .. }
+                | GameAction::BuyItem { .. }
+                | GameAction::BuyTile { .. }
+                | GameAction::AssignCitizen { .. }
+                | GameAction::UnassignCitizen { .. }
+                | GameAction::SetResearch { .. }
+                | GameAction::QueueResearch { .. }
+                | GameAction::SetResearchQueue { .. }
+        )
+    }
+
+    /// Check whether a player can see tiles after an action that they
+    /// couldn't see before it and hadn't explored.
+    pub fn reveals_tiles(player_id: PlayerSlot, before: &GameState, after: &GameState) -> bool {
+        let Some(player) = before.get_player(player_id) else {
+            return false;
+        };
+        let mut seen = VisibilityFilter::new(player_id);
+        seen.update_from_game_state(before);
+        let mut visible = VisibilityFilter::new(player_id);
+        visible.update_from_game_state(after);
+        visible
+            .visible_tiles()
+            .iter()
+            .any(|coord| !seen.can_see_tile(coord) && !player.has_explored(coord))
+    }
+
+    /// Number recorded actions from at least `sequence`, e.g. to follow
+    /// events received from other players.
+    pub fn advance_sequence(&mut self, sequence: u32) {
+        self.next_sequence = self.next_sequence.max(sequence);
+    }
+
+    /// Record an applied action along with the states before and after it.
+    ///
+    /// The action takes the turn of `before` and the next sequence number.
+    /// Actions that cannot be undone, or that revealed new tiles, commit
+    /// the whole buffer.
+    pub fn record(
+        &mut self,
+        player_id: PlayerSlot,
+        action: GameAction,
+        before: GameState,
+        after: &GameState,
+    ) {
+        self.redo.clear();
+
+        let entry = BufferedAction {
+            player_id,
+            action,
+            turn: before.turn,
+            sequence: self.next_sequence,
+            before,
+        };
+        self.next_sequence += 1;
+        if self.enabled
+            && Self::is_undoable(&entry.action)
+            && !Self::reveals_tiles(player_id, &entry.before, after)
+        {
+            self.pending.push(entry);
+        } else {
+            self.commit();
+            self.committed.push(entry.into());
+        }
+    }
+
+    /// Commit an action that wasn't applied through the buffer, such as a
+    /// state snapshot, numbered after everything recorded so far.
+    pub fn commit_action(&mut self, player_id: PlayerSlot, action: GameAction, turn: u32) {
+        self.commit();
+        self.committed.push(CommittedAction {
+            player_id,
+            action,
+            turn,
+            sequence: self.next_sequence,
+        });
+        self.next_sequence += 1;
+    }
+
+    /// Pop the most recent pending action, returning it with the state to
+    /// restore.
+    pub fn undo(&mut self) -> Option<(GameAction, GameState)> {
+        let entry = self.pending.pop()?;
+        // The undone action was the latest recorded, so its number is free
+        self.next_sequence = entry.sequence;
+        self.redo.push((entry.player_id, entry.action.clone()));
+        Some((entry.action, entry.before))
+    }
+
+    /// Pop the most recently undone action so it can be applied again.
+    pub fn take_redo(&mut self) -> Option<(PlayerSlot, GameAction)> {
+        self.redo.pop()
+    }
+
+    /// Put an action back on the redo stack (e.g. if re-applying it failed).
+    pub fn restore_redo(&mut self, player_id: PlayerSlot, action: GameAction) {
+        self.redo.push((player_id, action));
+    }
+
+    /// Record a redone action without clearing the rest of the redo stack.
+    pub fn record_redo(
+        &mut self,
+        player_id: PlayerSlot,
+        action: GameAction,
+        before: GameState,
+        after: &GameState,
+    ) {
+        let redo = std::mem::take(&mut self.redo);
+        self.record(player_id, action, before, after);
+        self.redo = redo;
+    }
+
+    /// Check if there is anything to undo.
+    pub fn can_undo(&self) -> bool {
+        !self.pending.is_empty()
+    }
+
+    /// Check if there is anything to redo.
+    pub fn can_redo(&self) -> bool {
+        !self.redo.is_empty()
+    }
+
+    /// Get the pending (uncommitted) actions.
+    pub fn pending(&self) -> &[BufferedAction] {
+        &self.pending
+    }
+
+    /// Commit all pending actions, making them ready to broadcast.
+    pub fn commit(&mut self) {
+        self.committed
+            .extend(self.pending.drain(..).map(CommittedAction::from));
+        self.redo.clear();
+    }
+
+    /// Take the committed actions that are ready to be signed and broadcast.
+    pub fn drain_committed(&mut self) -> Vec<CommittedAction> {
+        std::mem::take(&mut self.committed)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::hex::HexCoord;
+    use crate::settings::GameSettings;
+    use crate::types::{GameId, UnitId};
+
+    fn test_state() -> GameState {
+        GameState::new(
+            GameId::new("game1"),
+            GameSettings::new("Test".to_string()),
+            [0u8; 32],
+        )
+    }
+
+    fn move_action(unit_id: UnitId) -> GameAction {
+        GameAction::MoveUnit {
+            unit_id,
+            path: vec![HexCoord::new(0, 0)],
+        }
+    }
+
+    /// Record an action that changed nothing but what it did.
+    fn record(buffer: &mut ActionBuffer, action: GameAction, before: GameState) {
+        let after = before.clone();
+        buffer.record(PlayerSlot(0), action, before, &after);
+    }
+
+    #[test]
+    fn test_undoable_actions_are_buffered() {
+        let mut buffer = ActionBuffer::new();
+        record(&mut buffer, move_action(UnitId(1)), test_state());
+
+        assert!(buffer.can_undo());
+        assert!(buffer.drain_committed().is_empty());
+    }
+
+    #[test]
+    fn test_undo_and_redo() {
+        let mut buffer = ActionBuffer::new();
+        record(&mut buffer, move_action(UnitId(1)), test_state());
+        record(&mut buffer, move_action(UnitId(2)), test_state());
+
+        let (action, _) = buffer.undo().unwrap();
+        assert!(matches!(
+            action,
+            GameAction::MoveUnit {
+                unit_id: UnitId(2),
+                ..
+            }
+        ));
+        assert!(buffer.can_redo());
+
+        let (player, action) = buffer.take_redo().unwrap();
+        assert_eq!(player, PlayerSlot(0));
+        assert!(matches!(
+            action,
+            GameAction::MoveUnit {
+                unit_id: UnitId(2),
+                ..
+            }
+        ));
+    }
+
+    #[test]
+    fn test_new_action_clears_redo() {
+        let mut buffer = ActionBuffer::new();
+        record(&mut buffer, move_action(UnitId(1)), test_state());
+        buffer.undo();
+        assert!(buffer.can_redo());
+
+        record(&mut buffer, move_action(UnitId(3)), test_state());
+        assert!(!buffer.can_redo());
+    }
+
+    #[test]
+    fn test_irreversible_action_commits_buffer() {
+        let mut buffer = ActionBuffer::new();
+        record(&mut buffer, move_action(UnitId(1)), test_state());
+        record(&mut buffer, GameAction::EndTurn, test_state());
+
+        assert!(!buffer.can_undo());
+        let committed = buffer.drain_committed();
+        assert_eq!(committed.len(), 2);
+        assert!(matches!(
+            committed[0].action,
+            GameAction::MoveUnit {
+                unit_id: UnitId(1),
+                ..
+            }
+        ));
+        assert!(matches!(committed[1].action, GameAction::EndTurn));
+    }
+
+    #[test]
+    fn test_turn_and_sequence_stamped_at_record_time() {
+        let mut buffer = ActionBuffer::new();
+        buffer.advance_sequence(5);
+        let mut state = test_state();
+        state.turn = 3;
+
+        record(&mut buffer, move_action(UnitId(1)), state.clone());
+        record(&mut buffer, move_action(UnitId(2)), state.clone());
+        buffer.undo();
+        record(&mut buffer, move_action(UnitId(3)), state.clone());
+        record(&mut buffer, GameAction::EndTurn, state);
+        buffer.commit_action(PlayerSlot(0), GameAction::EndTurn, 4);
+
+        let stamps: Vec<(u32, u32)> = buffer
+            .drain_committed()
+            .iter()
+            .map(|c| (c.turn, c.sequence))
+            .collect();
+        assert_eq!(stamps, vec![(3, 5), (3, 6), (3, 7), (4, 8)]);
+    }
+
+    #[test]
+    fn test_disabled_buffer_commits_immediately() {
+        let mut buffer = ActionBuffer::new();
+        record(&mut buffer, move_action(UnitId(1)), test_state());
+        buffer.set_enabled(false);
+        assert_eq!(buffer.drain_committed().len(), 1);
+
+        record(&mut buffer, move_action(UnitId(2)), test_state());
+        assert!(!buffer.can_undo());
+        assert_eq!(buffer.drain_committed().len(), 1);
+    }
+}
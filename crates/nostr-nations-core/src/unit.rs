@@ -1,8 +1,13 @@
 //! Unit system - military and civilian units.
 
+use crate::fixed::deterministic;
 use crate::hex::HexCoord;
-use crate::types::{Era, PlayerId, UnitId};
+use crate::roads::RoadWork;
+use crate::terraform::FeatureWork;
+use crate::types::{Era, PlayerSlot, UnitId};
+use schemars::JsonSchema;
 use serde::{Deserialize, Serialize};
+use ts_rs::TS;
 
 /// A unit on the game map.
 #[derive(Clone, Debug, Serialize, Deserialize)]
@@ -10,7 +15,7 @@ pub struct Unit {
     /// Unique identifier.
     pub id: UnitId,
     /// Owning player.
-    pub owner: PlayerId,
+    pub owner: PlayerSlot,
     /// Type of unit.
     pub unit_type: UnitType,
     /// Current position on the map.
@@ -35,11 +40,40 @@ pub struct Unit {
     pub sleeping: bool,
     /// Queued orders/path.
     pub queued_path: Option<Vec<HexCoord>>,
+    /// Is the unit fortified until fully healed?
+    #[serde(default)]
+    pub healing: bool,
+    /// Road this worker is building on its tile.
+    #[serde(default)]
+    pub road_work: Option<RoadWork>,
+    /// Feature this worker is clearing from its tile.
+    #[serde(default)]
+    pub feature_work: Option<FeatureWork>,
 }
 
+deterministic!(struct Unit {
+    id,
+    owner,
+    unit_type,
+    position,
+    health,
+    movement,
+    experience,
+    promotions,
+    fortified,
+    fortify_turns,
+    embarked,
+    has_acted,
+    sleeping,
+    queued_path,
+    healing,
+    road_work,
+    feature_work,
+});
+
 impl Unit {
     /// Create a new unit.
-    pub fn new(id: UnitId, owner: PlayerId, unit_type: UnitType, position: HexCoord) -> Self {
+    pub fn new(id: UnitId, owner: PlayerSlot, unit_type: UnitType, position: HexCoord) -> Self {
         let stats = unit_type.stats();
         Self {
             id,
@@ -56,6 +90,9 @@ impl Unit {
             has_acted: false,
             sleeping: false,
             queued_path: None,
+            healing: false,
+            road_work: None,
+            feature_work: None,
         }
     }
 
@@ -113,10 +150,12 @@ impl Unit {
     /// Use movement points.
     pub fn use_movement(&mut self, cost: u32) {
         self.movement = self.movement.saturating_sub(cost);
-        // Unfortify when moving
+        // Unfortify and abandon tile work when moving
         if cost > 0 {
             self.fortified = false;
             self.fortify_turns = 0;
+            self.road_work = None;
+            self.feature_work = None;
         }
     }
 
@@ -152,7 +191,7 @@ impl Unit {
     }
 
     /// Get XP needed for next promotion.
-    fn next_promotion_threshold(&self) -> u32 {
+    pub fn next_promotion_threshold(&self) -> u32 {
         // Each promotion requires more XP
         let promo_count = self.promotions.len() as u32;
         10 + promo_count * 10
@@ -163,6 +202,58 @@ impl Unit {
         self.promotions.push(promotion);
     }
 
+    /// Check whether this unit could take a promotion, ignoring XP.
+    pub fn check_promotion(&self, promotion: Promotion) -> Result<(), PromotionError> {
+        if self.promotions.contains(&promotion) {
+            return Err(PromotionError::AlreadyHas);
+        }
+        if !promotion.is_available_to(self.unit_type.stats().category) {
+            return Err(PromotionError::WrongUnitType);
+        }
+        if let Some(conflict) = self
+            .promotions
+            .iter()
+            .find(|p| p.excludes().contains(&promotion) || promotion.excludes().contains(p))
+        {
+            return Err(PromotionError::Excluded { by: *conflict });
+        }
+        if !promotion
+            .prerequisites()
+            .iter()
+            .all(|p| self.promotions.contains(p))
+        {
+            return Err(PromotionError::MissingPrerequisites);
+        }
+        Ok(())
+    }
+
+    /// Get the promotions this unit could take next.
+    pub fn available_promotions(&self) -> Vec<Promotion> {
+        Promotion::all()
+            .iter()
+            .copied()
+            .filter(|p| self.check_promotion(*p).is_ok())
+            .collect()
+    }
+
+    /// Take a promotion the player chose after leveling up.
+    ///
+    /// Promoting heals the unit and uses up its turn.
+    pub fn promote(&mut self, promotion: Promotion) -> Result<(), PromotionError> {
+        if !self.can_promote() {
+            return Err(PromotionError::NotEnoughExperience {
+                required: self.next_promotion_threshold(),
+                current: self.experience,
+            });
+        }
+        self.check_promotion(promotion)?;
+
+        self.promotions.push(promotion);
+        self.heal(PROMOTION_HEAL);
+        self.has_acted = true;
+        Ok(())
+    }
+
     /// Fortify the unit.
     pub fn fortify(&mut self) {
         self.fortified = true;
@@ -179,8 +270,57 @@ impl Unit {
         (self.fortify_turns.min(2) * 25) as i32
     }
 
+    /// Fortify until fully healed, then wake for new orders.
+    pub fn fortify_until_healed(&mut self) {
+        self.fortify();
+        self.healing = true;
+    }
+
+    /// Check if the unit spent its last turn resting.
+    ///
+    /// Fortified units count as resting even though fortifying uses their
+    /// action.
+    pub fn is_resting(&self) -> bool {
+        self.fortified || (!self.has_acted && self.movement == self.effective_stats().movement * 10)
+    }
+
+    /// Get how much the unit heals at the start of its turn.
+    pub fn heal_rate(&self, ctx: &UnitTurnContext) -> u32 {
+        // March lets a unit heal even after moving or fighting
+        if !self.is_resting() && !self.promotions.contains(&Promotion::March) {
+            return 0;
+        }
+        match ctx.site {
+            // Enemy territory only heals with a Medic nearby
+            HealingSite::Enemy if ctx.medic_nearby => MEDIC_HEAL_BONUS * 2,
+            site if ctx.medic_nearby => site.base_heal() + MEDIC_HEAL_BONUS,
+            site => site.base_heal(),
+        }
+    }
+
     /// Reset for new turn.
     pub fn new_turn(&mut self) {
+        self.start_turn(&UnitTurnContext::default());
+    }
+
+    /// Reset for a new turn, healing and earning garrison XP.
+    pub fn start_turn(&mut self, ctx: &UnitTurnContext) -> UnitTurnReport {
+        let mut report = UnitTurnReport::default();
+
+        // Healing and garrison XP depend on what the unit did last turn
+        let before = self.health;
+        self.heal(self.heal_rate(ctx));
+        report.healed = self.health - before;
+
+        if ctx.garrisoned
+            && self.is_military()
+            && self.is_resting()
+            && self.experience < GARRISON_XP_CAP
+        {
+            self.gain_experience(GARRISON_XP_PER_TURN);
+            report.experience = GARRISON_XP_PER_TURN;
+        }
+
         let stats = self.effective_stats();
         self.movement = stats.movement * 10;
         self.has_acted = false;
@@ -190,10 +330,15 @@ impl Unit {
             self.fortify_turns += 1;
         }
 
-        // Heal if fortified or in friendly territory
-        if self.fortified {
-            self.heal(10);
+        // Units healing in place wake up once at full health
+        if self.healing && self.health >= 100 {
+            self.healing = false;
+            self.fortified = false;
+            self.fortify_turns = 0;
+            report.finished_healing = true;
         }
+
+        report
     }
 
     /// Check if this is a civilian unit.
@@ -208,7 +353,7 @@ impl Unit {
 }
 
 /// Types of units available.
-#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash, Serialize, Deserialize, JsonSchema, TS)]
 pub enum UnitType {
     // Civilian
     Settler,
@@ -267,6 +412,8 @@ pub enum UnitType {
     Bomber,
 }
 
+deterministic!(enum UnitType);
+
 impl UnitType {
     /// Get the stats for this unit type.
     pub const fn stats(&self) -> UnitStats {
@@ -369,15 +516,14 @@ impl UnitType {
             UnitType::Infantry
             | UnitType::MachineGun
             | UnitType::Tank
-            | UnitType::Battleship
-            | UnitType::RocketArtillery
             | UnitType::Fighter
-            | UnitType::Bomber
             | UnitType::GreatScientist
             | UnitType::GreatEngineer
             | UnitType::GreatMerchant
             | UnitType::GreatArtist
             | UnitType::GreatGeneral => Era::Modern,
+
+            UnitType::Battleship | UnitType::RocketArtillery | UnitType::Bomber => Era::Atomic,
         }
     }
 
@@ -518,8 +664,68 @@ pub enum UnitCategory {
     Air,
 }
 
+/// Extra healing per turn from a nearby Medic.
+pub const MEDIC_HEAL_BONUS: u32 = 5;
+
+/// Experience a garrisoned unit earns each turn.
+pub const GARRISON_XP_PER_TURN: u32 = 1;
+
+/// Garrisoned units stop earning experience past this total.
+pub const GARRISON_XP_CAP: u32 = 30;
+
+/// Where a unit starts its turn, for healing purposes.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub enum HealingSite {
+    /// In one of the owner's cities.
+    City,
+    /// In the owner's territory.
+    Friendly,
+    /// In unowned territory.
+    #[default]
+    Neutral,
+    /// In another player's territory.
+    Enemy,
+}
+
+impl HealingSite {
+    /// Health restored per turn while resting here.
+    pub const fn base_heal(&self) -> u32 {
+        match self {
+            HealingSite::City => 20,
+            HealingSite::Friendly => 15,
+            HealingSite::Neutral => 10,
+            HealingSite::Enemy => 0,
+        }
+    }
+}
+
+/// Surroundings that affect a unit at the start of its turn.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct UnitTurnContext {
+    /// Where the unit is.
+    pub site: HealingSite,
+    /// Whether the unit or an adjacent friendly unit has Medic.
+    pub medic_nearby: bool,
+    /// Whether the unit is stationed in one of its owner's cities.
+    pub garrisoned: bool,
+}
+
+/// What happened to a unit at the start of its turn.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct UnitTurnReport {
+    /// Health restored.
+    pub healed: u32,
+    /// Garrison experience earned.
+    pub experience: u32,
+    /// The unit finished fortify-until-healed and needs orders.
+    pub finished_healing: bool,
+}
+
+/// Health restored when a unit is promoted.
+pub const PROMOTION_HEAL: u32 = 50;
+
 /// Unit promotions (upgrades earned through combat).
-#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash, Serialize, Deserialize, JsonSchema, TS)]
 pub enum Promotion {
     // Melee promotions
     ShockI,
@@ -552,6 +758,8 @@ pub enum Promotion {
     CoverII,
 }
 
+deterministic!(enum Promotion);
+
 impl Promotion {
     /// Apply this promotion to unit stats.
     pub const fn apply(&self, mut stats: UnitStats) -> UnitStats {
@@ -587,6 +795,77 @@ impl Promotion {
         }
     }
 
+    /// Get every promotion in tree order.
+    pub const fn all() -> &'static [Promotion] {
+        &[
+            Promotion::ShockI,
+            Promotion::ShockII,
+            Promotion::ShockIII,
+            Promotion::DrillI,
+            Promotion::DrillII,
+            Promotion::DrillIII,
+            Promotion::AccuracyI,
+            Promotion::AccuracyII,
+            Promotion::AccuracyIII,
+            Promotion::BarrageI,
+            Promotion::BarrageII,
+            Promotion::BarrageIII,
+            Promotion::Medic,
+            Promotion::March,
+            Promotion::Blitz,
+            Promotion::Logistics,
+            Promotion::Mobility,
+            Promotion::Sentry,
+            Promotion::CoverI,
+            Promotion::CoverII,
+        ]
+    }
+
+    /// Check whether units of a category can take this promotion.
+    pub const fn is_available_to(&self, category: UnitCategory) -> bool {
+        match self {
+            Promotion::ShockI
+            | Promotion::ShockII
+            | Promotion::ShockIII
+            | Promotion::DrillI
+            | Promotion::DrillII
+            | Promotion::DrillIII
+            | Promotion::Blitz => matches!(category, UnitCategory::Melee | UnitCategory::Cavalry),
+            Promotion::AccuracyI
+            | Promotion::AccuracyII
+            | Promotion::AccuracyIII
+            | Promotion::BarrageI
+            | Promotion::BarrageII
+            | Promotion::BarrageIII
+            | Promotion::Logistics => matches!(
+                category,
+                UnitCategory::Ranged | UnitCategory::Siege | UnitCategory::Naval
+            ),
+            Promotion::Medic
+            | Promotion::March
+            | Promotion::Mobility
+            | Promotion::Sentry
+            | Promotion::CoverI
+            | Promotion::CoverII => !matches!(category, UnitCategory::Civilian),
+        }
+    }
+
+    /// Get the promotions this one cannot be combined with.
+    ///
+    /// Branches are exclusive at their first tier: a unit that starts down
+    /// Shock can never take Drill, and so on.
+    pub const fn excludes(&self) -> &'static [Promotion] {
+        match self {
+            Promotion::ShockI => &[Promotion::DrillI],
+            Promotion::DrillI => &[Promotion::ShockI],
+            Promotion::AccuracyI => &[Promotion::BarrageI],
+            Promotion::BarrageI => &[Promotion::AccuracyI],
+            Promotion::Blitz => &[Promotion::Logistics],
+            Promotion::Logistics => &[Promotion::Blitz],
+            _ => &[],
+        }
+    }
+
     /// Get the prerequisite promotions for this one.
     pub const fn prerequisites(&self) -> &'static [Promotion] {
         match self {
@@ -605,15 +884,53 @@ impl Promotion {
     }
 }
 
+/// Reasons a unit cannot take a promotion.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize, Deserialize, JsonSchema, TS)]
+pub enum PromotionError {
+    /// The unit has not earned enough experience.
+    NotEnoughExperience { required: u32, current: u32 },
+    /// The unit already has this promotion.
+    AlreadyHas,
+    /// This unit type cannot take the promotion.
+    WrongUnitType,
+    /// An earlier promotion closed off this branch.
+    Excluded { by: Promotion },
+    /// Prerequisite promotions are missing.
+    MissingPrerequisites,
+}
+
+impl std::fmt::Display for PromotionError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            PromotionError::NotEnoughExperience { required, current } => write!(
+                f,
+                "Not enough experience (requires {}, has {})",
+                required, current
+            ),
+            PromotionError::AlreadyHas => write!(f, "Unit already has this promotion"),
+            PromotionError::WrongUnitType => write!(f, "Unit type cannot take this promotion"),
+            PromotionError::Excluded { by } => write!(f, "Excluded by {:?}", by),
+            PromotionError::MissingPrerequisites => write!(f, "Promotion prerequisites not met"),
+        }
+    }
+}
+
+impl std::error::Error for PromotionError {}
+
 #[cfg(test)]
 mod tests {
     use super::*;
 
     #[test]
     fn test_unit_creation() {
-        let unit = Unit::new(1, 0, UnitType::Warrior, HexCoord::new(5, 5));
-        assert_eq!(unit.id, 1);
-        assert_eq!(unit.owner, 0);
+        let unit = Unit::new(
+            UnitId(1),
+            PlayerSlot(0),
+            UnitType::Warrior,
+            HexCoord::new(5, 5),
+        );
+        assert_eq!(unit.id, UnitId(1));
+        assert_eq!(unit.owner, PlayerSlot(0));
         assert_eq!(unit.health, 100);
         assert_eq!(unit.movement, 20); // 2 * 10
     }
@@ -633,7 +950,12 @@ mod tests {
 
     #[test]
     fn test_effective_combat_strength() {
-        let mut unit = Unit::new(1, 0, UnitType::Warrior, HexCoord::new(0, 0));
+        let mut unit = Unit::new(
+            UnitId(1),
+            PlayerSlot(0),
+            UnitType::Warrior,
+            HexCoord::new(0, 0),
+        );
         assert_eq!(unit.effective_combat_strength(), 8);
 
         unit.health = 50;
@@ -642,7 +964,12 @@ mod tests {
 
     #[test]
     fn test_unit_movement() {
-        let mut unit = Unit::new(1, 0, UnitType::Warrior, HexCoord::new(0, 0));
+        let mut unit = Unit::new(
+            UnitId(1),
+            PlayerSlot(0),
+            UnitType::Warrior,
+            HexCoord::new(0, 0),
+        );
         assert!(unit.can_move());
 
         unit.use_movement(10);
@@ -656,7 +983,12 @@ mod tests {
 
     #[test]
     fn test_unit_damage_and_heal() {
-        let mut unit = Unit::new(1, 0, UnitType::Warrior, HexCoord::new(0, 0));
+        let mut unit = Unit::new(
+            UnitId(1),
+            PlayerSlot(0),
+            UnitType::Warrior,
+            HexCoord::new(0, 0),
+        );
 
         unit.take_damage(30);
         assert_eq!(unit.health, 70);
@@ -671,14 +1003,24 @@ mod tests {
 
     #[test]
     fn test_unit_death() {
-        let mut unit = Unit::new(1, 0, UnitType::Warrior, HexCoord::new(0, 0));
+        let mut unit = Unit::new(
+            UnitId(1),
+            PlayerSlot(0),
+            UnitType::Warrior,
+            HexCoord::new(0, 0),
+        );
         unit.take_damage(100);
         assert!(unit.is_dead());
     }
 
     #[test]
     fn test_unit_fortification() {
-        let mut unit = Unit::new(1, 0, UnitType::Warrior, HexCoord::new(0, 0));
+        let mut unit = Unit::new(
+            UnitId(1),
+            PlayerSlot(0),
+            UnitType::Warrior,
+            HexCoord::new(0, 0),
+        );
         assert_eq!(unit.fortification_bonus(), 0);
 
         unit.fortify();
@@ -697,7 +1039,12 @@ mod tests {
 
     #[test]
     fn test_unit_experience() {
-        let mut unit = Unit::new(1, 0, UnitType::Warrior, HexCoord::new(0, 0));
+        let mut unit = Unit::new(
+            UnitId(1),
+            PlayerSlot(0),
+            UnitType::Warrior,
+            HexCoord::new(0, 0),
+        );
         assert!(!unit.can_promote());
 
         unit.gain_experience(10);
@@ -709,7 +1056,12 @@ mod tests {
 
     #[test]
     fn test_civilian_unit() {
-        let settler = Unit::new(1, 0, UnitType::Settler, HexCoord::new(0, 0));
+        let settler = Unit::new(
+            UnitId(1),
+            PlayerSlot(0),
+            UnitType::Settler,
+            HexCoord::new(0, 0),
+        );
         assert!(settler.is_civilian());
         assert!(!settler.is_military());
         assert_eq!(settler.effective_combat_strength(), 0);
@@ -717,7 +1069,12 @@ mod tests {
 
     #[test]
     fn test_ranged_unit() {
-        let archer = Unit::new(1, 0, UnitType::Archer, HexCoord::new(0, 0));
+        let archer = Unit::new(
+            UnitId(1),
+            PlayerSlot(0),
+            UnitType::Archer,
+            HexCoord::new(0, 0),
+        );
         assert!(archer.is_ranged());
         assert_eq!(archer.range(), 2);
         assert_eq!(archer.effective_ranged_strength(), 7);
@@ -725,7 +1082,12 @@ mod tests {
 
     #[test]
     fn test_unit_serialization() {
-        let unit = Unit::new(1, 0, UnitType::Knight, HexCoord::new(3, 7));
+        let unit = Unit::new(
+            UnitId(1),
+            PlayerSlot(0),
+            UnitType::Knight,
+            HexCoord::new(3, 7),
+        );
         let json = serde_json::to_string(&unit).unwrap();
         let restored: Unit = serde_json::from_str(&json).unwrap();
 
@@ -740,4 +1102,178 @@ mod tests {
         assert_eq!(Promotion::ShockII.prerequisites(), &[Promotion::ShockI]);
         assert_eq!(Promotion::ShockIII.prerequisites(), &[Promotion::ShockII]);
     }
+
+    #[test]
+    fn test_promotion_branches_are_exclusive() {
+        let mut unit = Unit::new(
+            UnitId(1),
+            PlayerSlot(0),
+            UnitType::Warrior,
+            HexCoord::new(0, 0),
+        );
+        unit.gain_experience(10);
+        unit.promote(Promotion::ShockI).unwrap();
+
+        assert_eq!(
+            unit.check_promotion(Promotion::DrillI),
+            Err(PromotionError::Excluded {
+                by: Promotion::ShockI
+            })
+        );
+        assert!(unit.available_promotions().contains(&Promotion::ShockII));
+        assert!(!unit.available_promotions().contains(&Promotion::DrillI));
+    }
+
+    #[test]
+    fn test_promotion_requires_prereqs_and_category() {
+        let unit = Unit::new(
+            UnitId(1),
+            PlayerSlot(0),
+            UnitType::Warrior,
+            HexCoord::new(0, 0),
+        );
+        assert_eq!(
+            unit.check_promotion(Promotion::ShockII),
+            Err(PromotionError::MissingPrerequisites)
+        );
+        assert_eq!(
+            unit.check_promotion(Promotion::AccuracyI),
+            Err(PromotionError::WrongUnitType)
+        );
+
+        let archer = Unit::new(
+            UnitId(2),
+            PlayerSlot(0),
+            UnitType::Archer,
+            HexCoord::new(0, 0),
+        );
+        assert!(archer
+            .available_promotions()
+            .contains(&Promotion::AccuracyI));
+        assert!(!archer.available_promotions().contains(&Promotion::ShockI));
+    }
+
+    #[test]
+    fn test_promote_heals_and_ends_turn() {
+        let mut unit = Unit::new(
+            UnitId(1),
+            PlayerSlot(0),
+            UnitType::Warrior,
+            HexCoord::new(0, 0),
+        );
+        assert_eq!(
+            unit.promote(Promotion::ShockI),
+            Err(PromotionError::NotEnoughExperience {
+                required: 10,
+                current: 0
+            })
+        );
+
+        unit.take_damage(70);
+        unit.gain_experience(10);
+        unit.promote(Promotion::ShockI).unwrap();
+
+        assert_eq!(unit.health, 80);
+        assert!(unit.has_acted);
+        assert_eq!(unit.promotions, vec![Promotion::ShockI]);
+        assert!(!unit.can_promote());
+    }
+
+    #[test]
+    fn test_heal_rate_by_site() {
+        let mut unit = Unit::new(
+            UnitId(1),
+            PlayerSlot(0),
+            UnitType::Warrior,
+            HexCoord::new(0, 0),
+        );
+        unit.take_damage(50);
+        let at = |site, medic_nearby| UnitTurnContext {
+            site,
+            medic_nearby,
+            garrisoned: false,
+        };
+
+        assert_eq!(unit.heal_rate(&at(HealingSite::City, false)), 20);
+        assert_eq!(unit.heal_rate(&at(HealingSite::Friendly, false)), 15);
+        assert_eq!(unit.heal_rate(&at(HealingSite::Neutral, false)), 10);
+        assert_eq!(unit.heal_rate(&at(HealingSite::Enemy, false)), 0);
+        assert_eq!(unit.heal_rate(&at(HealingSite::Enemy, true)), 10);
+        assert_eq!(unit.heal_rate(&at(HealingSite::Friendly, true)), 20);
+    }
+
+    #[test]
+    fn test_no_healing_after_acting_without_march() {
+        let mut unit = Unit::new(
+            UnitId(1),
+            PlayerSlot(0),
+            UnitType::Warrior,
+            HexCoord::new(0, 0),
+        );
+        unit.take_damage(50);
+        unit.use_movement(10);
+        let ctx = UnitTurnContext {
+            site: HealingSite::City,
+            ..Default::default()
+        };
+        assert_eq!(unit.heal_rate(&ctx), 0);
+
+        unit.promotions.push(Promotion::March);
+        assert_eq!(unit.heal_rate(&ctx), 20);
+    }
+
+    #[test]
+    fn test_fortify_until_healed_wakes_at_full_health() {
+        let mut unit = Unit::new(
+            UnitId(1),
+            PlayerSlot(0),
+            UnitType::Warrior,
+            HexCoord::new(0, 0),
+        );
+        unit.take_damage(30);
+        unit.fortify_until_healed();
+        let ctx = UnitTurnContext {
+            site: HealingSite::City,
+            ..Default::default()
+        };
+
+        let report = unit.start_turn(&ctx);
+        assert_eq!(report.healed, 20);
+        assert!(!report.finished_healing);
+        assert!(unit.fortified && unit.healing);
+
+        let report = unit.start_turn(&ctx);
+        assert_eq!(report.healed, 10);
+        assert!(report.finished_healing);
+        assert!(!unit.fortified && !unit.healing);
+    }
+
+    #[test]
+    fn test_garrison_xp_trickle() {
+        let mut unit = Unit::new(
+            UnitId(1),
+            PlayerSlot(0),
+            UnitType::Warrior,
+            HexCoord::new(0, 0),
+        );
+        let ctx = UnitTurnContext {
+            site: HealingSite::City,
+            garrisoned: true,
+            ..Default::default()
+        };
+
+        assert_eq!(unit.start_turn(&ctx).experience, GARRISON_XP_PER_TURN);
+        assert_eq!(unit.experience, 1);
+
+        unit.experience = GARRISON_XP_CAP;
+        assert_eq!(unit.start_turn(&ctx).experience, 0);
+
+        let mut worker = Unit::new(
+            UnitId(2),
+            PlayerSlot(0),
+            UnitType::Worker,
+            HexCoord::new(0, 0),
+        );
+        assert_eq!(worker.start_turn(&ctx).experience, 0);
+    }
 }
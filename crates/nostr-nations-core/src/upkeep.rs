@@ -0,0 +1,290 @@
+//! Per-turn maintenance costs for units, buildings, and roads.
+//!
+//! Each player pays upkeep when their turn ends. Military units beyond a
+//! free allowance cost gold, as do most buildings and every road inside the
+//! player's borders. If the treasury goes negative the cheapest military
+//! unit is disbanded and the treasury is reset to zero.
+
+use crate::game_state::GameState;
+use crate::types::{PlayerSlot, UnitId};
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+use ts_rs::TS;
+
+/// Military units every player supports for free.
+pub const FREE_UNITS_BASE: u32 = 3;
+
+/// Additional free military units per city.
+pub const FREE_UNITS_PER_CITY: u32 = 1;
+
+/// Gold paid per military unit beyond the free allowance.
+pub const UNIT_UPKEEP: i32 = 1;
+
+/// A player's expected gold flow for the next turn.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Serialize, Deserialize, JsonSchema, TS)]
+pub struct TreasuryProjection {
+    /// Gold currently in the treasury.
+    pub gold: i32,
+    /// Gold earned from cities.
+    pub income: i32,
+    /// Gold paid for military units.
+    pub unit_upkeep: i32,
+    /// Gold paid for buildings.
+    pub building_upkeep: i32,
+    /// Gold paid for roads.
+    pub road_upkeep: i32,
+    /// Income minus all upkeep.
+    pub net: i32,
+    /// Turns until the treasury goes negative, if it is shrinking.
+    pub turns_until_bankrupt: Option<u32>,
+}
+
+impl TreasuryProjection {
+    /// Total upkeep across all categories.
+    pub fn total_upkeep(&self) -> i32 {
+        self.unit_upkeep + self.building_upkeep + self.road_upkeep
+    }
+}
+
+/// Result of paying upkeep at the end of a turn.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct UpkeepReport {
+    /// Gold gained (or lost) this turn.
+    pub net: i32,
+    /// Units disbanded because the treasury went negative.
+    pub disbanded: Vec<UnitId>,
+}
+
+/// Count a player's military units.
+fn military_units(state: &GameState, player_id: PlayerSlot) -> u32 {
+    state
+        .units
+        .values()
+        .filter(|u| u.owner == player_id && u.is_military())
+        .count() as u32
+}
+
+/// Gold a player pays for military units beyond the free allowance.
+pub fn unit_upkeep(state: &GameState, player_id: PlayerSlot) -> i32 {
+    let cities = state
+        .cities
+        .values()
+        .filter(|c| c.owner == player_id)
+        .count() as u32;
+    let free = FREE_UNITS_BASE + cities * FREE_UNITS_PER_CITY;
+    military_units(state, player_id).saturating_sub(free) as i32 * UNIT_UPKEEP
+}
+
+/// Gold a player pays for buildings in their cities.
+pub fn building_upkeep(state: &GameState, player_id: PlayerSlot) -> i32 {
+    state
+        .cities
+        .values()
+        .filter(|c| c.owner == player_id)
+        .flat_map(|c| c.buildings.iter())
+        .map(|b| b.maintenance())
+        .sum()
+}
+
+/// Gold a player pays for roads in their territory.
+///
+/// Cities provide their own road, so city centers are free.
+pub fn road_upkeep(state: &GameState, player_id: PlayerSlot) -> i32 {
+    state
+        .map
+        .tiles
+        .values()
+        .filter(|t| t.owner == Some(player_id))
+        .filter(|t| !state.cities.values().any(|c| c.position == t.coord))
+        .filter_map(|t| t.road.map(|r| r.maintenance()))
+        .sum()
+}
+
+/// Project a player's treasury for the next turn.
+pub fn project_treasury(state: &GameState, player_id: PlayerSlot) -> TreasuryProjection {
+    let gold = state.get_player(player_id).map_or(0, |p| p.gold);
+    let income = state.player_yields(player_id).gold;
+    let unit_upkeep = unit_upkeep(state, player_id);
+    let building_upkeep = building_upkeep(state, player_id);
+    let road_upkeep = road_upkeep(state, player_id);
+    let net = income - unit_upkeep - building_upkeep - road_upkeep;
+
+    let turns_until_bankrupt = (net < 0).then(|| (gold.max(0) / -net) as u32 + 1);
+
+    TreasuryProjection {
+        gold,
+        income,
+        unit_upkeep,
+        building_upkeep,
+        road_upkeep,
+        net,
+        turns_until_bankrupt,
+    }
+}
+
+/// Pick the military unit to disband first: lowest production cost, then
+/// lowest ID.
+pub fn disband_candidate(state: &GameState, player_id: PlayerSlot) -> Option<UnitId> {
+    state
+        .units
+        .values()
+        .filter(|u| u.owner == player_id && u.is_military())
+        .min_by_key(|u| (u.unit_type.stats().cost, u.id))
+        .map(|u| u.id)
+}
+
+/// Collect income and pay upkeep for a player.
+///
+/// If the treasury ends up negative, one unit is disbanded and the
+/// treasury is reset to zero.
+pub fn apply_upkeep(state: &mut GameState, player_id: PlayerSlot) -> UpkeepReport {
+    let projection = project_treasury(state, player_id);
+    let mut report = UpkeepReport {
+        net: projection.net,
+        disbanded: Vec::new(),
+    };
+
+    let Some(player) = state.get_player_mut(player_id) else {
+        return report;
+    };
+    player.gold += projection.net;
+    player.gold_per_turn = projection.net;
+    if player.gold >= 0 {
+        return report;
+    }
+    player.gold = 0;
+
+    if let Some(unit_id) = disband_candidate(state, player_id) {
+        state.units.remove(&unit_id);
+        report.disbanded.push(unit_id);
+    }
+    report
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::city::{BuildingType, City};
+    use crate::hex::HexCoord;
+    use crate::map::Map;
+    use crate::player::{Civilization, Player};
+    use crate::settings::GameSettings;
+    use crate::terrain::{Road, Terrain};
+    use crate::types::{CityId, GameId, Npub};
+    use crate::unit::{Unit, UnitType};
+
+    fn test_state() -> GameState {
+        let mut state = GameState::new(
+            GameId::new("test"),
+            GameSettings::new("Test".to_string()),
+            [0u8; 32],
+        );
+        state.map = Map::filled(10, 10, Terrain::Plains);
+        state
+            .add_player(Player::new(
+                PlayerSlot(0),
+                Npub::new("pk0"),
+                "Alice".to_string(),
+                Civilization::default(),
+            ))
+            .unwrap();
+        state
+    }
+
+    fn add_unit(state: &mut GameState, unit_type: UnitType) -> UnitId {
+        let id = state.allocate_unit_id();
+        state.units.insert(
+            id,
+            Unit::new(id, PlayerSlot(0), unit_type, HexCoord::new(1, 1)),
+        );
+        id
+    }
+
+    // ==================== Projection Tests ====================
+
+    #[test]
+    fn test_free_units_cost_nothing() {
+        let mut state = test_state();
+        for _ in 0..FREE_UNITS_BASE {
+            add_unit(&mut state, UnitType::Warrior);
+        }
+        add_unit(&mut state, UnitType::Settler);
+        assert_eq!(unit_upkeep(&state, PlayerSlot(0)), 0);
+
+        add_unit(&mut state, UnitType::Warrior);
+        assert_eq!(unit_upkeep(&state, PlayerSlot(0)), UNIT_UPKEEP);
+    }
+
+    #[test]
+    fn test_projection_includes_buildings_and_roads() {
+        let mut state = test_state();
+        let mut city = City::new(
+            CityId(1),
+            PlayerSlot(0),
+            "Rome".to_string(),
+            HexCoord::new(5, 5),
+            true,
+        );
+        city.buildings.insert(BuildingType::Monument);
+        city.buildings.insert(BuildingType::Courthouse);
+        for coord in city.territory.clone() {
+            let tile = state.map.get_mut(&coord).unwrap();
+            tile.owner = Some(PlayerSlot(0));
+            tile.road = Some(Road::Road);
+        }
+        state.cities.insert(CityId(1), city);
+
+        let projection = project_treasury(&state, PlayerSlot(0));
+        assert_eq!(projection.building_upkeep, 5);
+        // Six roads around the city; the city center is free
+        assert_eq!(projection.road_upkeep, 6);
+        assert_eq!(projection.total_upkeep(), 11);
+        assert_eq!(projection.net, projection.income - 11);
+    }
+
+    #[test]
+    fn test_projection_turns_until_bankrupt() {
+        let mut state = test_state();
+        for _ in 0..FREE_UNITS_BASE + 2 {
+            add_unit(&mut state, UnitType::Warrior);
+        }
+        state.players[0].gold = 5;
+
+        let projection = project_treasury(&state, PlayerSlot(0));
+        assert_eq!(projection.net, -2);
+        assert_eq!(projection.turns_until_bankrupt, Some(3));
+    }
+
+    // ==================== Disband Tests ====================
+
+    #[test]
+    fn test_apply_upkeep_pays_from_treasury() {
+        let mut state = test_state();
+        for _ in 0..FREE_UNITS_BASE + 1 {
+            add_unit(&mut state, UnitType::Warrior);
+        }
+        state.players[0].gold = 10;
+
+        let report = apply_upkeep(&mut state, PlayerSlot(0));
+        assert_eq!(report.net, -1);
+        assert!(report.disbanded.is_empty());
+        assert_eq!(state.players[0].gold, 9);
+        assert_eq!(state.players[0].gold_per_turn, -1);
+    }
+
+    #[test]
+    fn test_negative_treasury_disbands_lowest_value_unit() {
+        let mut state = test_state();
+        let swordsman = add_unit(&mut state, UnitType::Swordsman);
+        let warrior = add_unit(&mut state, UnitType::Warrior);
+        for _ in 0..FREE_UNITS_BASE {
+            add_unit(&mut state, UnitType::Swordsman);
+        }
+
+        let report = apply_upkeep(&mut state, PlayerSlot(0));
+        assert_eq!(report.disbanded, vec![warrior]);
+        assert_eq!(state.players[0].gold, 0);
+        assert!(state.units.contains_key(&swordsman));
+        assert!(!state.units.contains_key(&warrior));
+    }
+}
@@ -1,7 +1,7 @@
 //! Victory condition checking for all victory types.
 
 use crate::game_state::GameState;
-use crate::types::{PlayerId, VictoryType};
+use crate::types::{PlayerSlot, VictoryType};
 use serde::{Deserialize, Serialize};
 use std::collections::HashMap;
 
@@ -163,7 +163,7 @@ impl VictoryChecker {
     ///
     /// Returns the player ID if they control all capitals that were originally
     /// assigned to players at the start of the game.
-    pub fn check_domination(game: &GameState) -> Option<PlayerId> {
+    pub fn check_domination(game: &GameState) -> Option<PlayerSlot> {
         // Get all original capitals (cities that are marked as capitals)
         let original_capitals: Vec<_> = game.cities.values().filter(|c| c.is_capital).collect();
 
@@ -173,7 +173,7 @@ impl VictoryChecker {
         }
 
         // Check if any single player owns all capitals
-        let active_players: Vec<PlayerId> = game
+        let active_players: Vec<PlayerSlot> = game
             .players
             .iter()
             .filter(|p| !p.eliminated)
@@ -191,7 +191,7 @@ impl VictoryChecker {
     }
 
     /// Check for science victory: a player has completed all spaceship parts.
-    pub fn check_science(game: &GameState) -> Option<PlayerId> {
+    pub fn check_science(game: &GameState) -> Option<PlayerSlot> {
         for player in &game.players {
             if !player.eliminated && player.spaceship.is_complete() {
                 return Some(player.id);
@@ -201,7 +201,7 @@ impl VictoryChecker {
     }
 
     /// Check for economic victory: a player has accumulated enough gold.
-    pub fn check_economic(game: &GameState, threshold: i32) -> Option<PlayerId> {
+    pub fn check_economic(game: &GameState, threshold: i32) -> Option<PlayerSlot> {
         for player in &game.players {
             if !player.eliminated && player.gold >= threshold {
                 return Some(player.id);
@@ -214,13 +214,13 @@ impl VictoryChecker {
     ///
     /// The votes map contains voter -> candidate mappings.
     /// A player wins if they receive more than half of the total votes.
-    pub fn check_diplomatic_votes(votes: &HashMap<PlayerId, PlayerId>) -> Option<PlayerId> {
+    pub fn check_diplomatic_votes(votes: &HashMap<PlayerSlot, PlayerSlot>) -> Option<PlayerSlot> {
         if votes.is_empty() {
             return None;
         }
 
         // Count votes for each candidate
-        let mut vote_counts: HashMap<PlayerId, u32> = HashMap::new();
+        let mut vote_counts: HashMap<PlayerSlot, u32> = HashMap::new();
         for &candidate in votes.values() {
             *vote_counts.entry(candidate).or_insert(0) += 1;
         }
@@ -241,7 +241,7 @@ impl VictoryChecker {
     /// Check for score victory: highest score when turn limit is reached.
     ///
     /// Returns the player with the highest score among non-eliminated players.
-    pub fn check_score_victory(game: &GameState) -> Option<PlayerId> {
+    pub fn check_score_victory(game: &GameState) -> Option<PlayerSlot> {
         game.players
             .iter()
             .filter(|p| !p.eliminated)
@@ -259,7 +259,7 @@ impl VictoryChecker {
     ///
     /// Note: Diplomatic victory requires external vote data and should be
     /// checked separately using `check_diplomatic_votes`.
-    pub fn check_all(&self, game: &GameState) -> Option<(PlayerId, VictoryType)> {
+    pub fn check_all(&self, game: &GameState) -> Option<(PlayerSlot, VictoryType)> {
         // Check domination victory
         if self.domination_enabled {
             if let Some(winner) = Self::check_domination(game) {
@@ -301,8 +301,8 @@ impl VictoryChecker {
     /// an external source (player voting).
     pub fn check_diplomatic(
         &self,
-        votes: &HashMap<PlayerId, PlayerId>,
-    ) -> Option<(PlayerId, VictoryType)> {
+        votes: &HashMap<PlayerSlot, PlayerSlot>,
+    ) -> Option<(PlayerSlot, VictoryType)> {
         if !self.diplomatic_enabled {
             return None;
         }
@@ -318,16 +318,17 @@ mod tests {
     use crate::hex::HexCoord;
     use crate::player::{Civilization, Player};
     use crate::settings::GameSettings;
+    use crate::types::{CityId, GameId, Npub};
 
     fn create_test_game() -> GameState {
         let settings = GameSettings::new("Test".to_string());
-        GameState::new("game1".to_string(), settings, [0u8; 32])
+        GameState::new(GameId::new("game1"), settings, [0u8; 32])
     }
 
-    fn create_test_player(id: PlayerId, name: &str) -> Player {
+    fn create_test_player(id: PlayerSlot, name: &str) -> Player {
         Player::new(
             id,
-            format!("npub{}", id),
+            Npub(format!("npub{}", id)),
             name.to_string(),
             Civilization::generic(),
         )
@@ -444,37 +445,61 @@ mod tests {
     #[test]
     fn test_domination_single_player_owns_all() {
         let mut game = create_test_game();
-        let mut p1 = create_test_player(0, "P1");
-        let mut p2 = create_test_player(1, "P2");
-        p1.capital = Some(1);
-        p2.capital = Some(2);
+        let mut p1 = create_test_player(PlayerSlot(0), "P1");
+        let mut p2 = create_test_player(PlayerSlot(1), "P2");
+        p1.capital = Some(CityId(1));
+        p2.capital = Some(CityId(2));
         game.players.push(p1);
         game.players.push(p2);
 
         // Player 0 owns both capitals
-        let capital1 = City::new(1, 0, "Capital1".to_string(), HexCoord::new(0, 0), true);
-        let capital2 = City::new(2, 0, "Capital2".to_string(), HexCoord::new(5, 5), true);
-        game.cities.insert(1, capital1);
-        game.cities.insert(2, capital2);
+        let capital1 = City::new(
+            CityId(1),
+            PlayerSlot(0),
+            "Capital1".to_string(),
+            HexCoord::new(0, 0),
+            true,
+        );
+        let capital2 = City::new(
+            CityId(2),
+            PlayerSlot(0),
+            "Capital2".to_string(),
+            HexCoord::new(5, 5),
+            true,
+        );
+        game.cities.insert(CityId(1), capital1);
+        game.cities.insert(CityId(2), capital2);
 
-        assert_eq!(VictoryChecker::check_domination(&game), Some(0));
+        assert_eq!(VictoryChecker::check_domination(&game), Some(PlayerSlot(0)));
     }
 
     #[test]
     fn test_domination_no_winner_capitals_split() {
         let mut game = create_test_game();
-        let mut p1 = create_test_player(0, "P1");
-        let mut p2 = create_test_player(1, "P2");
-        p1.capital = Some(1);
-        p2.capital = Some(2);
+        let mut p1 = create_test_player(PlayerSlot(0), "P1");
+        let mut p2 = create_test_player(PlayerSlot(1), "P2");
+        p1.capital = Some(CityId(1));
+        p2.capital = Some(CityId(2));
         game.players.push(p1);
         game.players.push(p2);
 
         // Each player owns their own capital
-        let capital1 = City::new(1, 0, "Capital1".to_string(), HexCoord::new(0, 0), true);
-        let capital2 = City::new(2, 1, "Capital2".to_string(), HexCoord::new(5, 5), true);
-        game.cities.insert(1, capital1);
-        game.cities.insert(2, capital2);
+        let capital1 = City::new(
+            CityId(1),
+            PlayerSlot(0),
+            "Capital1".to_string(),
+            HexCoord::new(0, 0),
+            true,
+        );
+        let capital2 = City::new(
+            CityId(2),
+            PlayerSlot(1),
+            "Capital2".to_string(),
+            HexCoord::new(5, 5),
+            true,
+        );
+        game.cities.insert(CityId(1), capital1);
+        game.cities.insert(CityId(2), capital2);
 
         assert!(VictoryChecker::check_domination(&game).is_none());
     }
@@ -482,21 +507,33 @@ mod tests {
     #[test]
     fn test_domination_eliminated_player_ignored() {
         let mut game = create_test_game();
-        let mut p1 = create_test_player(0, "P1");
-        let mut p2 = create_test_player(1, "P2");
-        p1.capital = Some(1);
-        p2.capital = Some(2);
+        let mut p1 = create_test_player(PlayerSlot(0), "P1");
+        let mut p2 = create_test_player(PlayerSlot(1), "P2");
+        p1.capital = Some(CityId(1));
+        p2.capital = Some(CityId(2));
         p2.eliminated = true;
         game.players.push(p1);
         game.players.push(p2);
 
         // Player 0 owns both capitals (P2 is eliminated)
-        let capital1 = City::new(1, 0, "Capital1".to_string(), HexCoord::new(0, 0), true);
-        let capital2 = City::new(2, 0, "Capital2".to_string(), HexCoord::new(5, 5), true);
-        game.cities.insert(1, capital1);
-        game.cities.insert(2, capital2);
+        let capital1 = City::new(
+            CityId(1),
+            PlayerSlot(0),
+            "Capital1".to_string(),
+            HexCoord::new(0, 0),
+            true,
+        );
+        let capital2 = City::new(
+            CityId(2),
+            PlayerSlot(0),
+            "Capital2".to_string(),
+            HexCoord::new(5, 5),
+            true,
+        );
+        game.cities.insert(CityId(1), capital1);
+        game.cities.insert(CityId(2), capital2);
 
-        assert_eq!(VictoryChecker::check_domination(&game), Some(0));
+        assert_eq!(VictoryChecker::check_domination(&game), Some(PlayerSlot(0)));
     }
 
     // ==================== Science Victory Tests ====================
@@ -504,8 +541,8 @@ mod tests {
     #[test]
     fn test_science_no_winner() {
         let mut game = create_test_game();
-        game.players.push(create_test_player(0, "P1"));
-        game.players.push(create_test_player(1, "P2"));
+        game.players.push(create_test_player(PlayerSlot(0), "P1"));
+        game.players.push(create_test_player(PlayerSlot(1), "P2"));
 
         assert!(VictoryChecker::check_science(&game).is_none());
     }
@@ -513,7 +550,7 @@ mod tests {
     #[test]
     fn test_science_winner() {
         let mut game = create_test_game();
-        let mut p1 = create_test_player(0, "P1");
+        let mut p1 = create_test_player(PlayerSlot(0), "P1");
         p1.spaceship = SpaceshipProgress {
             cockpit: true,
             fuel_tanks: true,
@@ -522,15 +559,15 @@ mod tests {
             stasis_chamber: true,
         };
         game.players.push(p1);
-        game.players.push(create_test_player(1, "P2"));
+        game.players.push(create_test_player(PlayerSlot(1), "P2"));
 
-        assert_eq!(VictoryChecker::check_science(&game), Some(0));
+        assert_eq!(VictoryChecker::check_science(&game), Some(PlayerSlot(0)));
     }
 
     #[test]
     fn test_science_partial_progress_no_winner() {
         let mut game = create_test_game();
-        let mut p1 = create_test_player(0, "P1");
+        let mut p1 = create_test_player(PlayerSlot(0), "P1");
         p1.spaceship = SpaceshipProgress {
             cockpit: true,
             fuel_tanks: true,
@@ -546,7 +583,7 @@ mod tests {
     #[test]
     fn test_science_eliminated_player_ignored() {
         let mut game = create_test_game();
-        let mut p1 = create_test_player(0, "P1");
+        let mut p1 = create_test_player(PlayerSlot(0), "P1");
         p1.eliminated = true;
         p1.spaceship = SpaceshipProgress {
             cockpit: true,
@@ -556,7 +593,7 @@ mod tests {
             stasis_chamber: true,
         };
         game.players.push(p1);
-        game.players.push(create_test_player(1, "P2"));
+        game.players.push(create_test_player(PlayerSlot(1), "P2"));
 
         assert!(VictoryChecker::check_science(&game).is_none());
     }
@@ -566,7 +603,7 @@ mod tests {
     #[test]
     fn test_economic_no_winner() {
         let mut game = create_test_game();
-        let mut p1 = create_test_player(0, "P1");
+        let mut p1 = create_test_player(PlayerSlot(0), "P1");
         p1.gold = 5000;
         game.players.push(p1);
 
@@ -576,32 +613,38 @@ mod tests {
     #[test]
     fn test_economic_winner() {
         let mut game = create_test_game();
-        let mut p1 = create_test_player(0, "P1");
+        let mut p1 = create_test_player(PlayerSlot(0), "P1");
         p1.gold = 25_000;
         game.players.push(p1);
-        game.players.push(create_test_player(1, "P2"));
+        game.players.push(create_test_player(PlayerSlot(1), "P2"));
 
-        assert_eq!(VictoryChecker::check_economic(&game, 20_000), Some(0));
+        assert_eq!(
+            VictoryChecker::check_economic(&game, 20_000),
+            Some(PlayerSlot(0))
+        );
     }
 
     #[test]
     fn test_economic_exact_threshold() {
         let mut game = create_test_game();
-        let mut p1 = create_test_player(0, "P1");
+        let mut p1 = create_test_player(PlayerSlot(0), "P1");
         p1.gold = 20_000;
         game.players.push(p1);
 
-        assert_eq!(VictoryChecker::check_economic(&game, 20_000), Some(0));
+        assert_eq!(
+            VictoryChecker::check_economic(&game, 20_000),
+            Some(PlayerSlot(0))
+        );
     }
 
     #[test]
     fn test_economic_eliminated_player_ignored() {
         let mut game = create_test_game();
-        let mut p1 = create_test_player(0, "P1");
+        let mut p1 = create_test_player(PlayerSlot(0), "P1");
         p1.gold = 30_000;
         p1.eliminated = true;
         game.players.push(p1);
-        game.players.push(create_test_player(1, "P2"));
+        game.players.push(create_test_player(PlayerSlot(1), "P2"));
 
         assert!(VictoryChecker::check_economic(&game, 20_000).is_none());
     }
@@ -610,27 +653,30 @@ mod tests {
 
     #[test]
     fn test_diplomatic_no_votes() {
-        let votes: HashMap<PlayerId, PlayerId> = HashMap::new();
+        let votes: HashMap<PlayerSlot, PlayerSlot> = HashMap::new();
         assert!(VictoryChecker::check_diplomatic_votes(&votes).is_none());
     }
 
     #[test]
     fn test_diplomatic_majority_winner() {
-        let mut votes: HashMap<PlayerId, PlayerId> = HashMap::new();
-        votes.insert(0, 1); // Player 0 votes for Player 1
-        votes.insert(1, 1); // Player 1 votes for Player 1
-        votes.insert(2, 0); // Player 2 votes for Player 0
+        let mut votes: HashMap<PlayerSlot, PlayerSlot> = HashMap::new();
+        votes.insert(PlayerSlot(0), PlayerSlot(1)); // Player 0 votes for Player 1
+        votes.insert(PlayerSlot(1), PlayerSlot(1)); // Player 1 votes for Player 1
+        votes.insert(PlayerSlot(2), PlayerSlot(0)); // Player 2 votes for Player 0
 
         // 2 out of 3 votes for Player 1 (majority)
-        assert_eq!(VictoryChecker::check_diplomatic_votes(&votes), Some(1));
+        assert_eq!(
+            VictoryChecker::check_diplomatic_votes(&votes),
+            Some(PlayerSlot(1))
+        );
     }
 
     #[test]
     fn test_diplomatic_no_majority() {
-        let mut votes: HashMap<PlayerId, PlayerId> = HashMap::new();
-        votes.insert(0, 0); // Player 0 votes for themselves
-        votes.insert(1, 1); // Player 1 votes for themselves
-        votes.insert(2, 2); // Player 2 votes for themselves
+        let mut votes: HashMap<PlayerSlot, PlayerSlot> = HashMap::new();
+        votes.insert(PlayerSlot(0), PlayerSlot(0)); // Player 0 votes for themselves
+        votes.insert(PlayerSlot(1), PlayerSlot(1)); // Player 1 votes for themselves
+        votes.insert(PlayerSlot(2), PlayerSlot(2)); // Player 2 votes for themselves
 
         // No majority
         assert!(VictoryChecker::check_diplomatic_votes(&votes).is_none());
@@ -638,29 +684,35 @@ mod tests {
 
     #[test]
     fn test_diplomatic_two_player_majority() {
-        let mut votes: HashMap<PlayerId, PlayerId> = HashMap::new();
-        votes.insert(0, 1);
-        votes.insert(1, 1);
+        let mut votes: HashMap<PlayerSlot, PlayerSlot> = HashMap::new();
+        votes.insert(PlayerSlot(0), PlayerSlot(1));
+        votes.insert(PlayerSlot(1), PlayerSlot(1));
 
         // 2 out of 2 votes for Player 1
-        assert_eq!(VictoryChecker::check_diplomatic_votes(&votes), Some(1));
+        assert_eq!(
+            VictoryChecker::check_diplomatic_votes(&votes),
+            Some(PlayerSlot(1))
+        );
     }
 
     #[test]
     fn test_diplomatic_four_player_needs_three() {
-        let mut votes: HashMap<PlayerId, PlayerId> = HashMap::new();
-        votes.insert(0, 1);
-        votes.insert(1, 1);
-        votes.insert(2, 0);
-        votes.insert(3, 0);
+        let mut votes: HashMap<PlayerSlot, PlayerSlot> = HashMap::new();
+        votes.insert(PlayerSlot(0), PlayerSlot(1));
+        votes.insert(PlayerSlot(1), PlayerSlot(1));
+        votes.insert(PlayerSlot(2), PlayerSlot(0));
+        votes.insert(PlayerSlot(3), PlayerSlot(0));
 
         // 2 vs 2, no majority (need 3)
         assert!(VictoryChecker::check_diplomatic_votes(&votes).is_none());
 
-        votes.insert(3, 1); // Change vote to create majority
+        votes.insert(PlayerSlot(3), PlayerSlot(1)); // Change vote to create majority
 
         // 3 out of 4 for Player 1
-        assert_eq!(VictoryChecker::check_diplomatic_votes(&votes), Some(1));
+        assert_eq!(
+            VictoryChecker::check_diplomatic_votes(&votes),
+            Some(PlayerSlot(1))
+        );
     }
 
     // ==================== Score Victory Tests ====================
@@ -668,19 +720,22 @@ mod tests {
     #[test]
     fn test_score_single_player() {
         let mut game = create_test_game();
-        let mut p1 = create_test_player(0, "P1");
+        let mut p1 = create_test_player(PlayerSlot(0), "P1");
         p1.score.total = 100;
         game.players.push(p1);
 
-        assert_eq!(VictoryChecker::check_score_victory(&game), Some(0));
+        assert_eq!(
+            VictoryChecker::check_score_victory(&game),
+            Some(PlayerSlot(0))
+        );
     }
 
     #[test]
     fn test_score_highest_wins() {
         let mut game = create_test_game();
-        let mut p1 = create_test_player(0, "P1");
-        let mut p2 = create_test_player(1, "P2");
-        let mut p3 = create_test_player(2, "P3");
+        let mut p1 = create_test_player(PlayerSlot(0), "P1");
+        let mut p2 = create_test_player(PlayerSlot(1), "P2");
+        let mut p3 = create_test_player(PlayerSlot(2), "P3");
         p1.score.total = 100;
         p2.score.total = 250;
         p3.score.total = 150;
@@ -688,21 +743,27 @@ mod tests {
         game.players.push(p2);
         game.players.push(p3);
 
-        assert_eq!(VictoryChecker::check_score_victory(&game), Some(1));
+        assert_eq!(
+            VictoryChecker::check_score_victory(&game),
+            Some(PlayerSlot(1))
+        );
     }
 
     #[test]
     fn test_score_eliminated_ignored() {
         let mut game = create_test_game();
-        let mut p1 = create_test_player(0, "P1");
-        let mut p2 = create_test_player(1, "P2");
+        let mut p1 = create_test_player(PlayerSlot(0), "P1");
+        let mut p2 = create_test_player(PlayerSlot(1), "P2");
         p1.score.total = 500;
         p1.eliminated = true;
         p2.score.total = 100;
         game.players.push(p1);
         game.players.push(p2);
 
-        assert_eq!(VictoryChecker::check_score_victory(&game), Some(1));
+        assert_eq!(
+            VictoryChecker::check_score_victory(&game),
+            Some(PlayerSlot(1))
+        );
     }
 
     // ==================== Check All Victory Tests ====================
@@ -710,8 +771,8 @@ mod tests {
     #[test]
     fn test_check_all_no_winner() {
         let mut game = create_test_game();
-        game.players.push(create_test_player(0, "P1"));
-        game.players.push(create_test_player(1, "P2"));
+        game.players.push(create_test_player(PlayerSlot(0), "P1"));
+        game.players.push(create_test_player(PlayerSlot(1), "P2"));
         game.turn = 1;
 
         let checker = VictoryChecker::default();
@@ -721,7 +782,7 @@ mod tests {
     #[test]
     fn test_check_all_domination_priority() {
         let mut game = create_test_game();
-        let mut p1 = create_test_player(0, "P1");
+        let mut p1 = create_test_player(PlayerSlot(0), "P1");
         p1.gold = 30_000; // Also has economic victory
         p1.spaceship = SpaceshipProgress {
             cockpit: true,
@@ -730,30 +791,42 @@ mod tests {
             life_support: true,
             stasis_chamber: true,
         }; // Also has science victory
-        p1.capital = Some(1);
+        p1.capital = Some(CityId(1));
         game.players.push(p1);
 
-        let mut p2 = create_test_player(1, "P2");
-        p2.capital = Some(2);
+        let mut p2 = create_test_player(PlayerSlot(1), "P2");
+        p2.capital = Some(CityId(2));
         game.players.push(p2);
 
         // P1 owns both capitals
-        let capital1 = City::new(1, 0, "Capital1".to_string(), HexCoord::new(0, 0), true);
-        let capital2 = City::new(2, 0, "Capital2".to_string(), HexCoord::new(5, 5), true);
-        game.cities.insert(1, capital1);
-        game.cities.insert(2, capital2);
+        let capital1 = City::new(
+            CityId(1),
+            PlayerSlot(0),
+            "Capital1".to_string(),
+            HexCoord::new(0, 0),
+            true,
+        );
+        let capital2 = City::new(
+            CityId(2),
+            PlayerSlot(0),
+            "Capital2".to_string(),
+            HexCoord::new(5, 5),
+            true,
+        );
+        game.cities.insert(CityId(1), capital1);
+        game.cities.insert(CityId(2), capital2);
 
         let checker = VictoryChecker::default();
         let result = checker.check_all(&game);
 
         // Domination should be checked first
-        assert_eq!(result, Some((0, VictoryType::Domination)));
+        assert_eq!(result, Some((PlayerSlot(0), VictoryType::Domination)));
     }
 
     #[test]
     fn test_check_all_science_without_domination() {
         let mut game = create_test_game();
-        let mut p1 = create_test_player(0, "P1");
+        let mut p1 = create_test_player(PlayerSlot(0), "P1");
         p1.spaceship = SpaceshipProgress {
             cockpit: true,
             fuel_tanks: true,
@@ -762,19 +835,19 @@ mod tests {
             stasis_chamber: true,
         };
         game.players.push(p1);
-        game.players.push(create_test_player(1, "P2"));
+        game.players.push(create_test_player(PlayerSlot(1), "P2"));
 
         let checker = VictoryChecker::default();
         let result = checker.check_all(&game);
 
-        assert_eq!(result, Some((0, VictoryType::Science)));
+        assert_eq!(result, Some((PlayerSlot(0), VictoryType::Science)));
     }
 
     #[test]
     fn test_check_all_score_at_turn_limit() {
         let mut game = create_test_game();
-        let mut p1 = create_test_player(0, "P1");
-        let mut p2 = create_test_player(1, "P2");
+        let mut p1 = create_test_player(PlayerSlot(0), "P1");
+        let mut p2 = create_test_player(PlayerSlot(1), "P2");
         p1.score.total = 100;
         p2.score.total = 200;
         game.players.push(p1);
@@ -784,14 +857,14 @@ mod tests {
         let checker = VictoryChecker::default();
         let result = checker.check_all(&game);
 
-        assert_eq!(result, Some((1, VictoryType::Score)));
+        assert_eq!(result, Some((PlayerSlot(1), VictoryType::Score)));
     }
 
     #[test]
     fn test_check_all_score_before_turn_limit() {
         let mut game = create_test_game();
-        let mut p1 = create_test_player(0, "P1");
-        let mut p2 = create_test_player(1, "P2");
+        let mut p1 = create_test_player(PlayerSlot(0), "P1");
+        let mut p2 = create_test_player(PlayerSlot(1), "P2");
         p1.score.total = 100;
         p2.score.total = 200;
         game.players.push(p1);
@@ -808,10 +881,10 @@ mod tests {
     #[test]
     fn test_check_all_disabled_victories() {
         let mut game = create_test_game();
-        let mut p1 = create_test_player(0, "P1");
+        let mut p1 = create_test_player(PlayerSlot(0), "P1");
         p1.gold = 30_000;
         game.players.push(p1);
-        game.players.push(create_test_player(1, "P2"));
+        game.players.push(create_test_player(PlayerSlot(1), "P2"));
 
         let checker = VictoryChecker::with_settings(
             true,
@@ -831,13 +904,13 @@ mod tests {
     #[test]
     fn test_check_diplomatic_method() {
         let checker = VictoryChecker::default();
-        let mut votes: HashMap<PlayerId, PlayerId> = HashMap::new();
-        votes.insert(0, 1);
-        votes.insert(1, 1);
-        votes.insert(2, 0);
+        let mut votes: HashMap<PlayerSlot, PlayerSlot> = HashMap::new();
+        votes.insert(PlayerSlot(0), PlayerSlot(1));
+        votes.insert(PlayerSlot(1), PlayerSlot(1));
+        votes.insert(PlayerSlot(2), PlayerSlot(0));
 
         let result = checker.check_diplomatic(&votes);
-        assert_eq!(result, Some((1, VictoryType::Diplomatic)));
+        assert_eq!(result, Some((PlayerSlot(1), VictoryType::Diplomatic)));
     }
 
     #[test]
@@ -851,9 +924,9 @@ mod tests {
             20_000,
             Some(500),
         );
-        let mut votes: HashMap<PlayerId, PlayerId> = HashMap::new();
-        votes.insert(0, 1);
-        votes.insert(1, 1);
+        let mut votes: HashMap<PlayerSlot, PlayerSlot> = HashMap::new();
+        votes.insert(PlayerSlot(0), PlayerSlot(1));
+        votes.insert(PlayerSlot(1), PlayerSlot(1));
 
         let result = checker.check_diplomatic(&votes);
         assert!(result.is_none());
@@ -0,0 +1,455 @@
+//! Compact proofs of a game's outcome.
+//!
+//! A [`VictoryProof`] lets a third party (a ladder, a wager escrow) check who
+//! won a game without replaying its whole event chain. It carries the final
+//! state hash, the winner claim, each player's final standing, and only the
+//! events that establish the victory condition:
+//!
+//! - **Domination**: attacks on the capitals the winner now holds, and any
+//!   concessions
+//! - **Concession**: the `Concede` event signed by every losing player
+//! - **Other victories**: the `EndGame` event, if one was published
+//!
+//! The last event of the chain is always included so the proof can be tied
+//! to a chain tip. The events keep their Nostr IDs, so their signatures can
+//! be checked against the relays independently of this crate.
+
+use crate::audit;
+use crate::events::{GameAction, GameEvent};
+use crate::game_state::{GamePhase, GameState};
+use crate::types::{EventId, GameId, Npub, PlayerSlot, VictoryType};
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+use std::collections::HashSet;
+use ts_rs::TS;
+
+/// A player's standing when the game ended.
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize, JsonSchema, TS)]
+pub struct ProofPlayer {
+    /// Player ID.
+    pub id: PlayerSlot,
+    /// Player's Nostr public key.
+    pub pubkey: Npub,
+    /// Final score.
+    pub score: u32,
+    /// Whether the player was eliminated.
+    pub eliminated: bool,
+    /// Whether the player conceded.
+    pub conceded: bool,
+}
+
+/// Evidence that a game ended with a particular winner.
+#[derive(Clone, Debug, Serialize, Deserialize, JsonSchema, TS)]
+pub struct VictoryProof {
+    /// Game the proof is for.
+    pub game_id: GameId,
+    /// Winning player.
+    pub winner: PlayerSlot,
+    /// How the game was won.
+    pub victory_type: VictoryType,
+    /// Turn the game ended on.
+    pub turn: u32,
+    /// Audit hash of the final state (see [`audit::state_hash`]).
+    pub state_hash: u64,
+    /// ID of the last event in the chain.
+    pub final_event_id: Option<EventId>,
+    /// Every player's final standing.
+    pub players: Vec<ProofPlayer>,
+    /// Events establishing the victory, in chain order.
+    pub evidence: Vec<GameEvent>,
+}
+
+impl VictoryProof {
+    /// Build a proof from a finished game and its event chain.
+    pub fn build(state: &GameState, events: &[GameEvent]) -> Result<Self, VictoryProofError> {
+        if state.phase != GamePhase::Ended {
+            return Err(VictoryProofError::GameNotEnded);
+        }
+        let (winner, victory_type) = state
+            .winner
+            .or_else(|| declared_winner(events))
+            .ok_or(VictoryProofError::NoWinner)?;
+
+        let capitals: HashSet<_> = state
+            .cities
+            .values()
+            .filter(|c| c.is_capital && c.owner == winner)
+            .map(|c| c.id)
+            .collect();
+
+        let last_index = events.len().checked_sub(1);
+        let evidence = events
+            .iter()
+            .enumerate()
+            .filter(|(index, event)| {
+                Some(*index) == last_index
+                    || matches!(event.action, GameAction::EndGame { .. })
+                    || match (&event.action, victory_type) {
+                        (GameAction::Concede { .. }, VictoryType::Domination)
+                        | (GameAction::Concede { .. }, VictoryType::Concession) => true,
+                        (GameAction::AttackCity { city_id, .. }, VictoryType::Domination) => {
+                            event.player_id == winner && capitals.contains(city_id)
+                        }
+                        _ => false,
+                    }
+            })
+            .map(|(_, event)| event.clone())
+            .collect();
+
+        Ok(Self {
+            game_id: state.id.clone(),
+            winner,
+            victory_type,
+            turn: state.turn,
+            state_hash: audit::state_hash(state),
+            final_event_id: events.last().map(|e| e.id.clone()),
+            players: state
+                .players
+                .iter()
+                .map(|p| ProofPlayer {
+                    id: p.id,
+                    pubkey: p.pubkey.clone(),
+                    score: p.score.total,
+                    eliminated: p.eliminated,
+                    conceded: p.conceded,
+                })
+                .collect(),
+            evidence,
+        })
+    }
+
+    /// Get the winner's public key.
+    pub fn winner_pubkey(&self) -> Option<&str> {
+        self.players
+            .iter()
+            .find(|p| p.id == self.winner)
+            .map(|p| p.pubkey.as_str())
+    }
+
+    /// Check that the proof is consistent and its evidence supports the
+    /// claimed victory.
+    ///
+    /// This does not check event signatures; verify those against the
+    /// original Nostr events.
+    pub fn verify(&self) -> Result<(), VictoryProofError> {
+        let winner = self
+            .players
+            .iter()
+            .find(|p| p.id == self.winner)
+            .ok_or(VictoryProofError::UnknownWinner(self.winner))?;
+        if winner.eliminated {
+            return Err(VictoryProofError::WinnerEliminated);
+        }
+        if let Some(event) = self.evidence.iter().find(|e| e.game_id != self.game_id) {
+            return Err(VictoryProofError::WrongGame(event.id.clone()));
+        }
+        if let Some(ref id) = self.final_event_id {
+            if self.evidence.last().map(|e| &e.id) != Some(id) {
+                return Err(VictoryProofError::MissingFinalEvent);
+            }
+        }
+
+        let conceded: HashSet<PlayerSlot> = self
+            .evidence
+            .iter()
+            .filter(|e| matches!(e.action, GameAction::Concede { .. }))
+            .map(|e| e.player_id)
+            .collect();
+        if let Some(player) = self
+            .players
+            .iter()
+            .find(|p| p.conceded && !conceded.contains(&p.id))
+        {
+            return Err(VictoryProofError::MissingEvidence(player.id));
+        }
+
+        match self.victory_type {
+            VictoryType::Concession => {
+                if let Some(player) = self
+                    .players
+                    .iter()
+                    .find(|p| p.id != self.winner && !p.conceded)
+                {
+                    return Err(VictoryProofError::MissingEvidence(player.id));
+                }
+            }
+            VictoryType::Domination => {
+                // Either everyone else is out, or the winner took capitals
+                let captured = self.evidence.iter().any(|e| {
+                    e.player_id == self.winner && matches!(e.action, GameAction::AttackCity { .. })
+                });
+                if let Some(player) = self
+                    .players
+                    .iter()
+                    .find(|p| p.id != self.winner && !p.eliminated)
+                {
+                    if !captured {
+                        return Err(VictoryProofError::PlayerStillActive(player.id));
+                    }
+                }
+            }
+            VictoryType::Score => {
+                let best = self
+                    .players
+                    .iter()
+                    .filter(|p| !p.eliminated)
+                    .map(|p| p.score)
+                    .max();
+                if best != Some(winner.score) {
+                    return Err(VictoryProofError::ScoreNotHighest);
+                }
+            }
+            VictoryType::Science
+            | VictoryType::Economic
+            | VictoryType::Diplomatic
+            | VictoryType::Scenario => {}
+        }
+
+        Ok(())
+    }
+
+    /// Check the proof against a state the verifier replayed or restored
+    /// (e.g. from a snapshot).
+    pub fn verify_state(&self, state: &GameState) -> Result<(), VictoryProofError> {
+        let actual = audit::state_hash(state);
+        if actual != self.state_hash {
+            return Err(VictoryProofError::StateMismatch {
+                expected: self.state_hash,
+                actual,
+            });
+        }
+        self.verify()
+    }
+}
+
+/// Find the winner declared by the last `EndGame` event.
+fn declared_winner(events: &[GameEvent]) -> Option<(PlayerSlot, VictoryType)> {
+    events.iter().rev().find_map(|event| match &event.action {
+        GameAction::EndGame {
+            winner_id,
+            victory_type,
+        } => parse_victory_type(victory_type).map(|v| (*winner_id, v)),
+        _ => None,
+    })
+}
+
+/// Parse a victory type as written in an `EndGame` event.
+fn parse_victory_type(name: &str) -> Option<VictoryType> {
+    match name.to_ascii_lowercase().as_str() {
+        "domination" => Some(VictoryType::Domination),
+        "science" => Some(VictoryType::Science),
+        "economic" => Some(VictoryType::Economic),
+        "diplomatic" => Some(VictoryType::Diplomatic),
+        "score" => Some(VictoryType::Score),
+        "concession" => Some(VictoryType::Concession),
+        "scenario" => Some(VictoryType::Scenario),
+        _ => None,
+    }
+}
+
+/// Why a victory proof could not be built or verified.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum VictoryProofError {
+    /// The game has not ended.
+    GameNotEnded,
+    /// The game ended without a winner.
+    NoWinner,
+    /// The winner is not one of the listed players.
+    UnknownWinner(PlayerSlot),
+    /// The winner is marked as eliminated.
+    WinnerEliminated,
+    /// An evidence event belongs to another game.
+    WrongGame(EventId),
+    /// The final event is not the last piece of evidence.
+    MissingFinalEvent,
+    /// A player's concession is claimed but not backed by an event.
+    MissingEvidence(PlayerSlot),
+    /// A domination win is claimed while another player is still in and
+    /// no capital attacks are offered as evidence.
+    PlayerStillActive(PlayerSlot),
+    /// A score win is claimed by a player without the highest score.
+    ScoreNotHighest,
+    /// The state does not match the proof's hash.
+    StateMismatch { expected: u64, actual: u64 },
+}
+
+impl std::fmt::Display for VictoryProofError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            VictoryProofError::GameNotEnded => write!(f, "Game has not ended"),
+            VictoryProofError::NoWinner => write!(f, "Game ended without a winner"),
+            VictoryProofError::UnknownWinner(id) => write!(f, "Unknown winner {}", id),
+            VictoryProofError::WinnerEliminated => write!(f, "Winner was eliminated"),
+            VictoryProofError::WrongGame(id) => {
+                write!(f, "Event {} belongs to another game", id)
+            }
+            VictoryProofError::MissingFinalEvent => write!(f, "Final event missing from proof"),
+            VictoryProofError::MissingEvidence(id) => {
+                write!(f, "No concession event from player {}", id)
+            }
+            VictoryProofError::PlayerStillActive(id) => {
+                write!(f, "Player {} was not eliminated", id)
+            }
+            VictoryProofError::ScoreNotHighest => write!(f, "Winner does not have the top score"),
+            VictoryProofError::StateMismatch { expected, actual } => write!(
+                f,
+                "State hash mismatch: expected {:016x}, got {:016x}",
+                expected, actual
+            ),
+        }
+    }
+}
+
+impl std::error::Error for VictoryProofError {}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::concession;
+    use crate::events::EventBuilder;
+    use crate::player::{Civilization, Player};
+    use crate::settings::GameSettings;
+
+    fn create_test_game() -> GameState {
+        let mut settings = GameSettings::new("Proof".to_string());
+        settings.player_count = 3;
+        let mut state = GameState::new(GameId::new("g"), settings, [0u8; 32]);
+        for id in 0..3 {
+            let player = Player::new(
+                PlayerSlot(id),
+                Npub(format!("npub{}", id)),
+                format!("P{}", id),
+                Civilization::default(),
+            );
+            state.add_player(player).unwrap();
+        }
+        state.start().unwrap();
+        state
+    }
+
+    fn event(builder: &mut EventBuilder, id: &str, action: GameAction) -> GameEvent {
+        let mut event = builder.build(action);
+        event.id = id.to_string();
+        builder.set_last_event(event.id.clone());
+        event
+    }
+
+    fn conceded_game() -> (GameState, Vec<GameEvent>) {
+        let mut state = create_test_game();
+        let mut events = Vec::new();
+        let mut b1 = EventBuilder::new(GameId::new("g"), PlayerSlot(1));
+        events.push(event(&mut b1, "e1", GameAction::EndTurn));
+        events.push(event(
+            &mut b1,
+            "e2",
+            GameAction::Concede { to_player: None },
+        ));
+        concession::concede(&mut state, PlayerSlot(1), None);
+        let mut b2 = EventBuilder::new(GameId::new("g"), PlayerSlot(2));
+        b2.set_last_event("e2".to_string());
+        events.push(event(
+            &mut b2,
+            "e3",
+            GameAction::Concede { to_player: None },
+        ));
+        concession::concede(&mut state, PlayerSlot(2), None);
+        (state, events)
+    }
+
+    // ==================== Build Tests ====================
+
+    #[test]
+    fn test_build_requires_ended_game() {
+        let state = create_test_game();
+        assert!(matches!(
+            VictoryProof::build(&state, &[]),
+            Err(VictoryProofError::GameNotEnded)
+        ));
+    }
+
+    #[test]
+    fn test_concession_proof_keeps_only_concessions() {
+        let (state, events) = conceded_game();
+        let proof = VictoryProof::build(&state, &events).unwrap();
+
+        assert_eq!(proof.winner, PlayerSlot(0));
+        assert_eq!(proof.victory_type, VictoryType::Concession);
+        assert_eq!(proof.winner_pubkey(), Some("npub0"));
+        assert_eq!(proof.final_event_id, Some("e3".to_string()));
+        let ids: Vec<&str> = proof.evidence.iter().map(|e| e.id.as_str()).collect();
+        assert_eq!(ids, vec!["e2", "e3"]);
+
+        assert_eq!(proof.verify(), Ok(()));
+        assert_eq!(proof.verify_state(&state), Ok(()));
+    }
+
+    #[test]
+    fn test_winner_from_end_game_event() {
+        let mut state = create_test_game();
+        state.phase = GamePhase::Ended;
+        let mut builder = EventBuilder::new(GameId::new("g"), PlayerSlot(0));
+        let events = vec![event(
+            &mut builder,
+            "end",
+            GameAction::EndGame {
+                winner_id: PlayerSlot(2),
+                victory_type: "Science".to_string(),
+            },
+        )];
+
+        let proof = VictoryProof::build(&state, &events).unwrap();
+        assert_eq!(proof.winner, PlayerSlot(2));
+        assert_eq!(proof.victory_type, VictoryType::Science);
+        assert_eq!(proof.verify(), Ok(()));
+    }
+
+    // ==================== Verify Tests ====================
+
+    #[test]
+    fn test_verify_rejects_missing_concession() {
+        let (state, events) = conceded_game();
+        let mut proof = VictoryProof::build(&state, &events).unwrap();
+        proof.evidence.remove(0);
+
+        assert_eq!(
+            proof.verify(),
+            Err(VictoryProofError::MissingEvidence(PlayerSlot(1)))
+        );
+    }
+
+    #[test]
+    fn test_verify_rejects_forged_winner() {
+        let (state, events) = conceded_game();
+        let mut proof = VictoryProof::build(&state, &events).unwrap();
+        proof.winner = PlayerSlot(1);
+
+        assert_eq!(proof.verify(), Err(VictoryProofError::WinnerEliminated));
+    }
+
+    #[test]
+    fn test_verify_state_detects_mismatch() {
+        let (mut state, events) = conceded_game();
+        let proof = VictoryProof::build(&state, &events).unwrap();
+        state.turn += 1;
+
+        assert!(matches!(
+            proof.verify_state(&state),
+            Err(VictoryProofError::StateMismatch { .. })
+        ));
+    }
+
+    #[test]
+    fn test_score_proof_requires_top_score() {
+        let mut state = create_test_game();
+        state.players[0].score.total = 10;
+        state.players[1].score.total = 30;
+        state.winner = Some((PlayerSlot(1), VictoryType::Score));
+        state.phase = GamePhase::Ended;
+
+        let mut proof = VictoryProof::build(&state, &[]).unwrap();
+        assert_eq!(proof.verify(), Ok(()));
+
+        proof.winner = PlayerSlot(0);
+        assert_eq!(proof.verify(), Err(VictoryProofError::ScoreNotHighest));
+    }
+}
@@ -12,14 +12,16 @@
 //! - Enemy units are only visible if within vision range of own units/cities
 //! - Explored tiles show last known state (fog of war)
 //! - Unit health is hidden for enemies unless in combat
+//! - Once the host reveals the map at game end, nothing is hidden
 
 use crate::city::City;
+use crate::demographics::Demographics;
 use crate::events::{GameAction, GameEvent};
 use crate::game_state::{DiplomaticStatus, GameState, TreatyType};
 use crate::hex::HexCoord;
 use crate::map::Tile;
 use crate::player::Player;
-use crate::types::{CityId, PlayerId, UnitId};
+use crate::types::{CityId, PlayerSlot, UnitId};
 use crate::unit::Unit;
 use serde::{Deserialize, Serialize};
 use std::collections::{HashMap, HashSet};
@@ -40,7 +42,7 @@ const HILLS_VISION_BONUS: u32 = 1;
 #[derive(Clone, Debug)]
 pub struct VisibilityFilter {
     /// The player this filter applies to.
-    player_id: PlayerId,
+    player_id: PlayerSlot,
     /// Set of tiles currently visible to the player.
     visible_tiles: HashSet<HexCoord>,
     /// Set of unit IDs currently visible to the player.
@@ -48,18 +50,21 @@ pub struct VisibilityFilter {
     /// Set of city IDs currently visible to the player.
     visible_cities: HashSet<CityId>,
     /// Allied players whose units/cities are always visible.
-    allied_players: HashSet<PlayerId>,
+    allied_players: HashSet<PlayerSlot>,
+    /// Whether the map has been revealed at game end.
+    revealed: bool,
 }
 
 impl VisibilityFilter {
     /// Create a new visibility filter for a player.
-    pub fn new(player_id: PlayerId) -> Self {
+    pub fn new(player_id: PlayerSlot) -> Self {
         Self {
             player_id,
             visible_tiles: HashSet::new(),
             visible_units: HashSet::new(),
             visible_cities: HashSet::new(),
             allied_players: HashSet::new(),
+            revealed: false,
         }
     }
 
@@ -73,10 +78,19 @@ impl VisibilityFilter {
         self.visible_units.clear();
         self.visible_cities.clear();
         self.allied_players.clear();
+        self.revealed = game.revealed;
+
+        // After the end-of-game reveal everything is visible
+        if self.revealed {
+            self.visible_tiles.extend(game.map.tiles.keys().copied());
+            self.visible_units.extend(game.units.keys().copied());
+            self.visible_cities.extend(game.cities.keys().copied());
+            return;
+        }
 
         // Find allied players (those with open borders or Allied status)
         for (i, _player) in game.players.iter().enumerate() {
-            let other_id = i as PlayerId;
+            let other_id = PlayerSlot(i as u8);
             if other_id == self.player_id {
                 continue;
             }
@@ -120,7 +134,7 @@ impl VisibilityFilter {
 
     /// Add visible tiles within range of a position.
     fn add_visible_tiles_in_range(&mut self, center: &HexCoord, range: u32, game: &GameState) {
-        for coord in center.hexes_in_radius(range) {
+        for coord in center.spiral(range) {
             if game.map.in_bounds(&coord) {
                 self.visible_tiles.insert(coord);
             }
@@ -194,10 +208,15 @@ impl VisibilityFilter {
     }
 
     /// Get the player ID this filter is for.
-    pub fn player_id(&self) -> PlayerId {
+    pub fn player_id(&self) -> PlayerSlot {
         self.player_id
     }
 
+    /// Check if the map has been revealed at game end.
+    pub fn is_revealed(&self) -> bool {
+        self.revealed
+    }
+
     /// Get all currently visible tiles.
     pub fn visible_tiles(&self) -> &HashSet<HexCoord> {
         &self.visible_tiles
@@ -218,8 +237,9 @@ impl VisibilityFilter {
     /// Returns a `FilteredEvent` indicating whether the event is fully visible,
     /// partially visible (with some information redacted), or completely hidden.
     pub fn filter_event(&self, event: &GameEvent) -> FilteredEvent {
-        // Events from the player themselves are always fully visible
-        if event.player_id == self.player_id {
+        // Events from the player themselves, and every event once the map
+        // is revealed, are fully visible
+        if event.player_id == self.player_id || self.revealed {
             return FilteredEvent::FullyVisible(event.clone());
         }
 
@@ -228,17 +248,30 @@ impl VisibilityFilter {
             GameAction::CreateGame { .. }
             | GameAction::JoinGame { .. }
             | GameAction::StartGame
-            | GameAction::EndGame { .. } => FilteredEvent::FullyVisible(event.clone()),
+            | GameAction::EndGame { .. }
+            | GameAction::Concede { .. }
+            | GameAction::RequestPause { .. }
+            | GameAction::VotePause { .. }
+            | GameAction::ResumeGame { .. }
+            | GameAction::SubstitutePlayer { .. }
+            | GameAction::RevealMap => FilteredEvent::FullyVisible(event.clone()),
 
             // End turn is visible (turn order is public)
             GameAction::EndTurn => FilteredEvent::FullyVisible(event.clone()),
 
             // Research changes are hidden (tech is secret)
-            GameAction::SetResearch { .. } => FilteredEvent::Hidden,
+            GameAction::SetResearch { .. }
+            | GameAction::QueueResearch { .. }
+            | GameAction::SetResearchQueue { .. } => FilteredEvent::Hidden,
 
             // Diplomacy events between this player and another are visible
             GameAction::DeclareWar { target_player }
-            | GameAction::ProposePeace { target_player } => {
+            | GameAction::ProposePeace { target_player }
+            | GameAction::ProposeTreaty { target_player, .. }
+            | GameAction::ProposeTrade {
+                to_player: target_player,
+                ..
+            } => {
                 if *target_player == self.player_id || event.player_id == self.player_id {
                     FilteredEvent::FullyVisible(event.clone())
                 } else {
@@ -246,7 +279,9 @@ impl VisibilityFilter {
                 }
             }
 
-            GameAction::AcceptPeace { from_player } | GameAction::RejectPeace { from_player } => {
+            GameAction::AcceptPeace { from_player }
+            | GameAction::RejectPeace { from_player }
+            | GameAction::RespondTrade { from_player, .. } => {
                 if *from_player == self.player_id || event.player_id == self.player_id {
                     FilteredEvent::FullyVisible(event.clone())
                 } else {
@@ -298,6 +333,32 @@ impl VisibilityFilter {
                 }
             }
 
+            GameAction::ResolveCapture { city_id, .. } => {
+                if self.visible_cities.contains(city_id) {
+                    FilteredEvent::FullyVisible(event.clone())
+                } else {
+                    FilteredEvent::Hidden
+                }
+            }
+
+            GameAction::GiftUnit { unit_id, .. } => {
+                if self.visible_units.contains(unit_id) {
+                    FilteredEvent::FullyVisible(event.clone())
+                } else {
+                    FilteredEvent::Hidden
+                }
+            }
+
+            GameAction::BombardUnit {
+                city_id, target_id, ..
+            } => {
+                if self.visible_cities.contains(city_id) || self.visible_units.contains(target_id) {
+                    FilteredEvent::FullyVisible(event.clone())
+                } else {
+                    FilteredEvent::Hidden
+                }
+            }
+
             // City founding - visible if we can see the tile
             GameAction::FoundCity { settler_id, .. } => {
                 if self.visible_units.contains(settler_id) {
@@ -309,10 +370,12 @@ impl VisibilityFilter {
 
             // Unit state changes - visible if we can see the unit
             GameAction::FortifyUnit { unit_id }
+            | GameAction::FortifyUntilHealed { unit_id }
             | GameAction::SleepUnit { unit_id }
             | GameAction::WakeUnit { unit_id }
             | GameAction::DeleteUnit { unit_id }
-            | GameAction::UpgradeUnit { unit_id, .. } => {
+            | GameAction::UpgradeUnit { unit_id, .. }
+            | GameAction::ChoosePromotion { unit_id, .. } => {
                 if self.visible_units.contains(unit_id) {
                     FilteredEvent::FullyVisible(event.clone())
                 } else {
@@ -336,7 +399,8 @@ impl VisibilityFilter {
             | GameAction::BuyItem { city_id, .. }
             | GameAction::AssignCitizen { city_id, .. }
             | GameAction::UnassignCitizen { city_id, .. }
-            | GameAction::SellBuilding { city_id, .. } => {
+            | GameAction::SellBuilding { city_id, .. }
+            | GameAction::BuyTile { city_id, .. } => {
                 // Only visible for own cities, not just visible cities
                 if let Some(player) = self.get_city_owner_from_id(*city_id) {
                     if player == self.player_id {
@@ -350,17 +414,31 @@ impl VisibilityFilter {
             GameAction::RequestRandom { .. } | GameAction::ProvideRandom { .. } => {
                 FilteredEvent::Hidden
             }
+
+            // Snapshots contain the full state, including what players can't see
+            GameAction::Snapshot { .. } => FilteredEvent::Hidden,
+
+            // We can't tell what extension and unrecognized events reveal
+            GameAction::Extension { .. } | GameAction::Unrecognized { .. } => FilteredEvent::Hidden,
         }
     }
 
     /// Helper to track city ownership (would need game state in real implementation)
-    fn get_city_owner_from_id(&self, _city_id: CityId) -> Option<PlayerId> {
+    fn get_city_owner_from_id(&self, _city_id: CityId) -> Option<PlayerSlot> {
         // In a real implementation, this would look up the city
         // For now, we check if it's in our visible cities
         // The actual owner check happens in filter_game_state
         None
     }
 
+    /// Build the player's demographics report.
+    ///
+    /// Only the player's own values are exact; other players appear by
+    /// rank alone.
+    pub fn filter_demographics(&self, game: &GameState) -> Demographics {
+        Demographics::for_player(game, self.player_id)
+    }
+
     /// Filter the complete game state, applying fog of war.
     ///
     /// Returns a `FilteredGameState` containing only information the player
@@ -372,7 +450,11 @@ impl VisibilityFilter {
             .expect("Player should exist");
 
         // Get explored tiles from the player's record
-        let explored_tiles = own_player.explored_tiles.clone();
+        let explored_tiles = if self.revealed {
+            game.map.tiles.keys().copied().collect()
+        } else {
+            own_player.explored_tiles.clone()
+        };
 
         // Build visible tiles map
         let mut visible_tiles_map = HashMap::new();
@@ -386,14 +468,16 @@ impl VisibilityFilter {
         let mut visible_units_map = HashMap::new();
         for unit_id in &self.visible_units {
             if let Some(unit) = game.units.get(unit_id) {
-                let filtered_unit =
-                    if unit.owner == self.player_id || self.allied_players.contains(&unit.owner) {
-                        // Full info for own and allied units
-                        unit.clone()
-                    } else {
-                        // Redact health for enemy units not in combat
-                        redact_enemy_unit(unit)
-                    };
+                let filtered_unit = if self.revealed
+                    || unit.owner == self.player_id
+                    || self.allied_players.contains(&unit.owner)
+                {
+                    // Full info for own and allied units, or all units once revealed
+                    unit.clone()
+                } else {
+                    // Redact health for enemy units not in combat
+                    redact_enemy_unit(unit)
+                };
                 visible_units_map.insert(*unit_id, filtered_unit);
             }
         }
@@ -402,14 +486,16 @@ impl VisibilityFilter {
         let mut visible_cities_map = HashMap::new();
         for city_id in &self.visible_cities {
             if let Some(city) = game.cities.get(city_id) {
-                let filtered_city =
-                    if city.owner == self.player_id || self.allied_players.contains(&city.owner) {
-                        // Full info for own and allied cities
-                        city.clone()
-                    } else {
-                        // Limited info for enemy cities
-                        redact_enemy_city(city)
-                    };
+                let filtered_city = if self.revealed
+                    || city.owner == self.player_id
+                    || self.allied_players.contains(&city.owner)
+                {
+                    // Full info for own and allied cities, or all cities once revealed
+                    city.clone()
+                } else {
+                    // Limited info for enemy cities
+                    redact_enemy_city(city)
+                };
                 visible_cities_map.insert(*city_id, filtered_city);
             }
         }
@@ -418,7 +504,7 @@ impl VisibilityFilter {
         let other_players: Vec<PlayerSummary> = game
             .players
             .iter()
-            .filter(|p| p.id != self.player_id)
+            .filter(|p| p.id != self.player_id && game.has_met(self.player_id, p.id))
             .map(|p| {
                 let relationship = game
                     .diplomacy
@@ -436,6 +522,13 @@ impl VisibilityFilter {
             })
             .collect();
 
+        // Full stats of every player once the map is revealed
+        let revealed_players = if self.revealed {
+            game.players.clone()
+        } else {
+            Vec::new()
+        };
+
         FilteredGameState {
             visible_tiles: visible_tiles_map,
             explored_tiles,
@@ -443,6 +536,7 @@ impl VisibilityFilter {
             visible_cities: visible_cities_map,
             own_player,
             other_players,
+            revealed_players,
             turn: game.turn,
             current_player: game.current_player,
         }
@@ -495,10 +589,14 @@ pub struct FilteredGameState {
     pub own_player: Player,
     /// Limited information about other players.
     pub other_players: Vec<PlayerSummary>,
+    /// Full information about every player, filled in once the map is
+    /// revealed at game end.
+    #[serde(default)]
+    pub revealed_players: Vec<Player>,
     /// Current turn number.
     pub turn: u32,
     /// Current player whose turn it is.
-    pub current_player: PlayerId,
+    pub current_player: PlayerSlot,
 }
 
 impl FilteredGameState {
@@ -554,7 +652,7 @@ pub enum TileVisibility {
 #[derive(Clone, Debug, Serialize, Deserialize)]
 pub struct PlayerSummary {
     /// Player's ID.
-    pub id: PlayerId,
+    pub id: PlayerSlot,
     /// Player's display name.
     pub name: String,
     /// Player's civilization name.
@@ -605,34 +703,33 @@ fn redact_enemy_city(city: &City) -> City {
 /// that the viewing player shouldn't have access to.
 pub fn redact_event_for_player(
     event: &GameEvent,
-    viewer: PlayerId,
+    viewer: PlayerSlot,
     filter: &VisibilityFilter,
 ) -> GameEvent {
     let mut redacted = event.clone();
 
-    // If the event is from the viewer, no redaction needed
-    if event.player_id == viewer {
+    // If the event is from the viewer or the map is revealed, no redaction needed
+    if event.player_id == viewer || filter.is_revealed() {
         return redacted;
     }
 
     // Redact based on action type
     match &redacted.action {
         GameAction::MoveUnit { unit_id, path } => {
-            // If we can see the unit but not all tiles in path, redact path
-            if filter.can_see_unit(*unit_id) {
-                let visible_path: Vec<HexCoord> = path
-                    .iter()
-                    .filter(|c| filter.can_see_tile(c))
-                    .copied()
-                    .collect();
-
-                if visible_path.len() < path.len() {
-                    // Create redacted path with only visible portions
-                    redacted.action = GameAction::MoveUnit {
-                        unit_id: *unit_id,
-                        path: visible_path,
-                    };
-                }
+            // Keep only the tiles the viewer can see, whether or not they can
+            // see the unit itself
+            let visible_path: Vec<HexCoord> = path
+                .iter()
+                .filter(|c| filter.can_see_tile(c))
+                .copied()
+                .collect();
+
+            if visible_path.len() < path.len() {
+                // Create redacted path with only visible portions
+                redacted.action = GameAction::MoveUnit {
+                    unit_id: *unit_id,
+                    path: visible_path,
+                };
             }
         }
         GameAction::AttackUnit {
@@ -659,6 +756,17 @@ pub fn redact_event_for_player(
                 random: 0.0,
             };
         }
+        GameAction::BombardUnit {
+            city_id,
+            target_id,
+            random: _,
+        } => {
+            redacted.action = GameAction::BombardUnit {
+                city_id: *city_id,
+                target_id: *target_id,
+                random: 0.0,
+            };
+        }
         _ => {}
     }
 
@@ -675,22 +783,23 @@ mod tests {
     use crate::events::GameAction;
     use crate::player::Civilization;
     use crate::settings::GameSettings;
+    use crate::types::{GameId, Npub};
     use crate::unit::{Unit, UnitType};
 
     fn create_test_game() -> GameState {
         let settings = GameSettings::new("Test".to_string());
-        let mut game = GameState::new("test_game".to_string(), settings, [0u8; 32]);
+        let mut game = GameState::new(GameId::new("test_game"), settings, [0u8; 32]);
 
         // Add two players
         let p1 = Player::new(
-            0,
-            "npub1".to_string(),
+            PlayerSlot(0),
+            Npub::new("npub1"),
             "Player1".to_string(),
             Civilization::generic(),
         );
         let p2 = Player::new(
-            1,
-            "npub2".to_string(),
+            PlayerSlot(1),
+            Npub::new("npub2"),
             "Player2".to_string(),
             Civilization::generic(),
         );
@@ -704,14 +813,14 @@ mod tests {
         game
     }
 
-    fn add_unit(game: &mut GameState, owner: PlayerId, position: HexCoord) -> UnitId {
+    fn add_unit(game: &mut GameState, owner: PlayerSlot, position: HexCoord) -> UnitId {
         let id = game.allocate_unit_id();
         let unit = Unit::new(id, owner, UnitType::Warrior, position);
         game.units.insert(id, unit);
         id
     }
 
-    fn add_city(game: &mut GameState, owner: PlayerId, position: HexCoord, name: &str) -> CityId {
+    fn add_city(game: &mut GameState, owner: PlayerSlot, position: HexCoord, name: &str) -> CityId {
         let id = game.allocate_city_id();
         let city = City::new(id, owner, name.to_string(), position, false);
         game.cities.insert(id, city);
@@ -722,8 +831,8 @@ mod tests {
 
     #[test]
     fn test_visibility_filter_creation() {
-        let filter = VisibilityFilter::new(0);
-        assert_eq!(filter.player_id(), 0);
+        let filter = VisibilityFilter::new(PlayerSlot(0));
+        assert_eq!(filter.player_id(), PlayerSlot(0));
         assert!(filter.visible_tiles().is_empty());
         assert!(filter.visible_units().is_empty());
         assert!(filter.visible_cities().is_empty());
@@ -733,9 +842,9 @@ mod tests {
     fn test_visibility_from_own_unit() {
         let mut game = create_test_game();
         let unit_pos = HexCoord::new(10, 10);
-        add_unit(&mut game, 0, unit_pos);
+        add_unit(&mut game, PlayerSlot(0), unit_pos);
 
-        let mut filter = VisibilityFilter::new(0);
+        let mut filter = VisibilityFilter::new(PlayerSlot(0));
         filter.update_from_game_state(&game);
 
         // Should see tiles in radius of unit
@@ -751,9 +860,9 @@ mod tests {
     fn test_visibility_from_own_city() {
         let mut game = create_test_game();
         let city_pos = HexCoord::new(10, 10);
-        add_city(&mut game, 0, city_pos, "TestCity");
+        add_city(&mut game, PlayerSlot(0), city_pos, "TestCity");
 
-        let mut filter = VisibilityFilter::new(0);
+        let mut filter = VisibilityFilter::new(PlayerSlot(0));
         filter.update_from_game_state(&game);
 
         // Should see tiles around city
@@ -766,9 +875,9 @@ mod tests {
     #[test]
     fn test_own_units_always_visible() {
         let mut game = create_test_game();
-        let unit_id = add_unit(&mut game, 0, HexCoord::new(10, 10));
+        let unit_id = add_unit(&mut game, PlayerSlot(0), HexCoord::new(10, 10));
 
-        let mut filter = VisibilityFilter::new(0);
+        let mut filter = VisibilityFilter::new(PlayerSlot(0));
         filter.update_from_game_state(&game);
 
         assert!(filter.can_see_unit(unit_id));
@@ -778,11 +887,11 @@ mod tests {
     fn test_enemy_unit_visible_in_range() {
         let mut game = create_test_game();
         // Player 0's unit at (10, 10)
-        add_unit(&mut game, 0, HexCoord::new(10, 10));
+        add_unit(&mut game, PlayerSlot(0), HexCoord::new(10, 10));
         // Player 1's unit nearby at (11, 10)
-        let enemy_id = add_unit(&mut game, 1, HexCoord::new(11, 10));
+        let enemy_id = add_unit(&mut game, PlayerSlot(1), HexCoord::new(11, 10));
 
-        let mut filter = VisibilityFilter::new(0);
+        let mut filter = VisibilityFilter::new(PlayerSlot(0));
         filter.update_from_game_state(&game);
 
         assert!(filter.can_see_unit(enemy_id));
@@ -792,11 +901,11 @@ mod tests {
     fn test_enemy_unit_hidden_out_of_range() {
         let mut game = create_test_game();
         // Player 0's unit at (10, 10)
-        add_unit(&mut game, 0, HexCoord::new(10, 10));
+        add_unit(&mut game, PlayerSlot(0), HexCoord::new(10, 10));
         // Player 1's unit far away at (0, 0)
-        let enemy_id = add_unit(&mut game, 1, HexCoord::new(0, 0));
+        let enemy_id = add_unit(&mut game, PlayerSlot(1), HexCoord::new(0, 0));
 
-        let mut filter = VisibilityFilter::new(0);
+        let mut filter = VisibilityFilter::new(PlayerSlot(0));
         filter.update_from_game_state(&game);
 
         assert!(!filter.can_see_unit(enemy_id));
@@ -806,11 +915,11 @@ mod tests {
     fn test_city_visibility() {
         let mut game = create_test_game();
         // Player 0's unit near player 1's city
-        add_unit(&mut game, 0, HexCoord::new(10, 10));
+        add_unit(&mut game, PlayerSlot(0), HexCoord::new(10, 10));
         // Player 1's city nearby
-        let city_id = add_city(&mut game, 1, HexCoord::new(11, 10), "EnemyCity");
+        let city_id = add_city(&mut game, PlayerSlot(1), HexCoord::new(11, 10), "EnemyCity");
 
-        let mut filter = VisibilityFilter::new(0);
+        let mut filter = VisibilityFilter::new(PlayerSlot(0));
         filter.update_from_game_state(&game);
 
         assert!(filter.can_see_city(city_id));
@@ -821,15 +930,16 @@ mod tests {
         let mut game = create_test_game();
 
         // Set up alliance (open borders)
-        game.diplomacy.modify_relationship_score(0, 1, 60);
         game.diplomacy
-            .propose_treaty(0, 1, TreatyType::OpenBorders, 1);
+            .modify_relationship_score(PlayerSlot(0), PlayerSlot(1), 60);
+        game.diplomacy
+            .propose_treaty(PlayerSlot(0), PlayerSlot(1), TreatyType::OpenBorders, 1);
 
         // Add units
-        add_unit(&mut game, 0, HexCoord::new(10, 10));
-        let allied_unit = add_unit(&mut game, 1, HexCoord::new(0, 0)); // Far away
+        add_unit(&mut game, PlayerSlot(0), HexCoord::new(10, 10));
+        let allied_unit = add_unit(&mut game, PlayerSlot(1), HexCoord::new(0, 0)); // Far away
 
-        let mut filter = VisibilityFilter::new(0);
+        let mut filter = VisibilityFilter::new(PlayerSlot(0));
         filter.update_from_game_state(&game);
 
         // Allied unit should be visible even if not in range
@@ -841,14 +951,14 @@ mod tests {
     #[test]
     fn test_own_events_fully_visible() {
         let mut game = create_test_game();
-        add_unit(&mut game, 0, HexCoord::new(10, 10));
+        add_unit(&mut game, PlayerSlot(0), HexCoord::new(10, 10));
 
-        let mut filter = VisibilityFilter::new(0);
+        let mut filter = VisibilityFilter::new(PlayerSlot(0));
         filter.update_from_game_state(&game);
 
         let event = GameEvent::new(
-            "test".to_string(),
-            0, // Player 0's event
+            GameId::new("test"),
+            PlayerSlot(0), // Player 0's event
             None,
             1,
             1,
@@ -862,14 +972,14 @@ mod tests {
     #[test]
     fn test_game_lifecycle_events_visible() {
         let mut game = create_test_game();
-        add_unit(&mut game, 0, HexCoord::new(10, 10));
+        add_unit(&mut game, PlayerSlot(0), HexCoord::new(10, 10));
 
-        let mut filter = VisibilityFilter::new(0);
+        let mut filter = VisibilityFilter::new(PlayerSlot(0));
         filter.update_from_game_state(&game);
 
         let event = GameEvent::new(
-            "test".to_string(),
-            1, // Player 1's event
+            GameId::new("test"),
+            PlayerSlot(1), // Player 1's event
             None,
             1,
             1,
@@ -883,14 +993,14 @@ mod tests {
     #[test]
     fn test_research_events_hidden() {
         let mut game = create_test_game();
-        add_unit(&mut game, 0, HexCoord::new(10, 10));
+        add_unit(&mut game, PlayerSlot(0), HexCoord::new(10, 10));
 
-        let mut filter = VisibilityFilter::new(0);
+        let mut filter = VisibilityFilter::new(PlayerSlot(0));
         filter.update_from_game_state(&game);
 
         let event = GameEvent::new(
-            "test".to_string(),
-            1, // Enemy player
+            GameId::new("test"),
+            PlayerSlot(1), // Enemy player
             None,
             1,
             1,
@@ -906,19 +1016,21 @@ mod tests {
     #[test]
     fn test_diplomacy_event_visibility() {
         let mut game = create_test_game();
-        add_unit(&mut game, 0, HexCoord::new(10, 10));
+        add_unit(&mut game, PlayerSlot(0), HexCoord::new(10, 10));
 
-        let mut filter = VisibilityFilter::new(0);
+        let mut filter = VisibilityFilter::new(PlayerSlot(0));
         filter.update_from_game_state(&game);
 
         // War declaration targeting us is visible
         let war_event = GameEvent::new(
-            "test".to_string(),
-            1,
+            GameId::new("test"),
+            PlayerSlot(1),
             None,
             1,
             1,
-            GameAction::DeclareWar { target_player: 0 },
+            GameAction::DeclareWar {
+                target_player: PlayerSlot(0),
+            },
         );
 
         let filtered = filter.filter_event(&war_event);
@@ -928,15 +1040,15 @@ mod tests {
     #[test]
     fn test_combat_event_visibility() {
         let mut game = create_test_game();
-        let our_unit = add_unit(&mut game, 0, HexCoord::new(10, 10));
-        let enemy_unit = add_unit(&mut game, 1, HexCoord::new(11, 10));
+        let our_unit = add_unit(&mut game, PlayerSlot(0), HexCoord::new(10, 10));
+        let enemy_unit = add_unit(&mut game, PlayerSlot(1), HexCoord::new(11, 10));
 
-        let mut filter = VisibilityFilter::new(0);
+        let mut filter = VisibilityFilter::new(PlayerSlot(0));
         filter.update_from_game_state(&game);
 
         let attack_event = GameEvent::new(
-            "test".to_string(),
-            1,
+            GameId::new("test"),
+            PlayerSlot(1),
             None,
             1,
             1,
@@ -956,23 +1068,23 @@ mod tests {
     #[test]
     fn test_filtered_game_state_contains_own_player() {
         let mut game = create_test_game();
-        add_unit(&mut game, 0, HexCoord::new(10, 10));
+        add_unit(&mut game, PlayerSlot(0), HexCoord::new(10, 10));
 
-        let mut filter = VisibilityFilter::new(0);
+        let mut filter = VisibilityFilter::new(PlayerSlot(0));
         filter.update_from_game_state(&game);
 
         let filtered_state = filter.filter_game_state(&game);
 
-        assert_eq!(filtered_state.own_player.id, 0);
+        assert_eq!(filtered_state.own_player.id, PlayerSlot(0));
         assert_eq!(filtered_state.own_player.name, "Player1");
     }
 
     #[test]
     fn test_filtered_game_state_visible_tiles() {
         let mut game = create_test_game();
-        add_unit(&mut game, 0, HexCoord::new(10, 10));
+        add_unit(&mut game, PlayerSlot(0), HexCoord::new(10, 10));
 
-        let mut filter = VisibilityFilter::new(0);
+        let mut filter = VisibilityFilter::new(PlayerSlot(0));
         filter.update_from_game_state(&game);
 
         let filtered_state = filter.filter_game_state(&game);
@@ -984,13 +1096,13 @@ mod tests {
     #[test]
     fn test_filtered_game_state_enemy_unit_redaction() {
         let mut game = create_test_game();
-        add_unit(&mut game, 0, HexCoord::new(10, 10));
-        let enemy_id = add_unit(&mut game, 1, HexCoord::new(11, 10));
+        add_unit(&mut game, PlayerSlot(0), HexCoord::new(10, 10));
+        let enemy_id = add_unit(&mut game, PlayerSlot(1), HexCoord::new(11, 10));
 
         // Damage the enemy unit
         game.units.get_mut(&enemy_id).unwrap().health = 50;
 
-        let mut filter = VisibilityFilter::new(0);
+        let mut filter = VisibilityFilter::new(PlayerSlot(0));
         filter.update_from_game_state(&game);
 
         let filtered_state = filter.filter_game_state(&game);
@@ -1003,12 +1115,12 @@ mod tests {
     #[test]
     fn test_filtered_game_state_own_unit_not_redacted() {
         let mut game = create_test_game();
-        let our_id = add_unit(&mut game, 0, HexCoord::new(10, 10));
+        let our_id = add_unit(&mut game, PlayerSlot(0), HexCoord::new(10, 10));
 
         // Damage our unit
         game.units.get_mut(&our_id).unwrap().health = 50;
 
-        let mut filter = VisibilityFilter::new(0);
+        let mut filter = VisibilityFilter::new(PlayerSlot(0));
         filter.update_from_game_state(&game);
 
         let filtered_state = filter.filter_game_state(&game);
@@ -1021,14 +1133,14 @@ mod tests {
     #[test]
     fn test_filtered_game_state_enemy_city_redaction() {
         let mut game = create_test_game();
-        add_unit(&mut game, 0, HexCoord::new(10, 10));
-        let city_id = add_city(&mut game, 1, HexCoord::new(11, 10), "EnemyCity");
+        add_unit(&mut game, PlayerSlot(0), HexCoord::new(10, 10));
+        let city_id = add_city(&mut game, PlayerSlot(1), HexCoord::new(11, 10), "EnemyCity");
 
         // Set production on enemy city
         game.cities.get_mut(&city_id).unwrap().production =
             Some(crate::city::ProductionItem::Unit(UnitType::Warrior));
 
-        let mut filter = VisibilityFilter::new(0);
+        let mut filter = VisibilityFilter::new(PlayerSlot(0));
         filter.update_from_game_state(&game);
 
         let filtered_state = filter.filter_game_state(&game);
@@ -1041,16 +1153,17 @@ mod tests {
     #[test]
     fn test_player_summary_contains_basic_info() {
         let mut game = create_test_game();
-        add_unit(&mut game, 0, HexCoord::new(10, 10));
+        add_unit(&mut game, PlayerSlot(0), HexCoord::new(10, 10));
+        crate::contact::meet(&mut game, PlayerSlot(0), PlayerSlot(1));
 
-        let mut filter = VisibilityFilter::new(0);
+        let mut filter = VisibilityFilter::new(PlayerSlot(0));
         filter.update_from_game_state(&game);
 
         let filtered_state = filter.filter_game_state(&game);
 
         assert_eq!(filtered_state.other_players.len(), 1);
         let summary = &filtered_state.other_players[0];
-        assert_eq!(summary.id, 1);
+        assert_eq!(summary.id, PlayerSlot(1));
         assert_eq!(summary.name, "Player2");
         assert!(summary.is_alive);
     }
@@ -1060,12 +1173,12 @@ mod tests {
     #[test]
     fn test_tile_visibility_states() {
         let mut game = create_test_game();
-        add_unit(&mut game, 0, HexCoord::new(10, 10));
+        add_unit(&mut game, PlayerSlot(0), HexCoord::new(10, 10));
 
         // Mark some tiles as explored
         game.players[0].explored_tiles.insert(HexCoord::new(0, 0));
 
-        let mut filter = VisibilityFilter::new(0);
+        let mut filter = VisibilityFilter::new(PlayerSlot(0));
         filter.update_from_game_state(&game);
 
         let filtered_state = filter.filter_game_state(&game);
@@ -1089,20 +1202,61 @@ mod tests {
         );
     }
 
+    // ========== Map Reveal Tests ==========
+
+    #[test]
+    fn test_revealed_map_hides_nothing() {
+        let mut game = create_test_game();
+        add_unit(&mut game, PlayerSlot(0), HexCoord::new(2, 2));
+        let enemy_unit = add_unit(&mut game, PlayerSlot(1), HexCoord::new(18, 18));
+        let enemy_city = add_city(&mut game, PlayerSlot(1), HexCoord::new(17, 17), "Far");
+        game.units.get_mut(&enemy_unit).unwrap().health = 40;
+        game.cities
+            .get_mut(&enemy_city)
+            .unwrap()
+            .production_progress = 15;
+        game.revealed = true;
+
+        let mut filter = VisibilityFilter::new(PlayerSlot(0));
+        filter.update_from_game_state(&game);
+        assert!(filter.is_revealed());
+        assert!(filter.can_see_tile(&HexCoord::new(19, 19)));
+        assert!(filter.can_see_unit(enemy_unit));
+        assert!(filter.can_see_city(enemy_city));
+
+        let research = GameEvent::new(
+            GameId::new("test"),
+            PlayerSlot(1),
+            None,
+            1,
+            1,
+            GameAction::SetResearch {
+                tech_id: "writing".to_string(),
+            },
+        );
+        assert!(filter.filter_event(&research).is_fully_visible());
+
+        let state = filter.filter_game_state(&game);
+        assert_eq!(state.visible_units[&enemy_unit].health, 40);
+        assert_eq!(state.visible_cities[&enemy_city].production_progress, 15);
+        assert_eq!(state.explored_tiles.len(), game.map.tiles.len());
+        assert_eq!(state.revealed_players.len(), 2);
+    }
+
     // ========== Event Redaction Tests ==========
 
     #[test]
     fn test_redact_event_removes_random() {
         let mut game = create_test_game();
-        let attacker = add_unit(&mut game, 1, HexCoord::new(11, 10));
-        let defender = add_unit(&mut game, 0, HexCoord::new(10, 10));
+        let attacker = add_unit(&mut game, PlayerSlot(1), HexCoord::new(11, 10));
+        let defender = add_unit(&mut game, PlayerSlot(0), HexCoord::new(10, 10));
 
-        let mut filter = VisibilityFilter::new(0);
+        let mut filter = VisibilityFilter::new(PlayerSlot(0));
         filter.update_from_game_state(&game);
 
         let event = GameEvent::new(
-            "test".to_string(),
-            1,
+            GameId::new("test"),
+            PlayerSlot(1),
             None,
             1,
             1,
@@ -1113,7 +1267,7 @@ mod tests {
             },
         );
 
-        let redacted = redact_event_for_player(&event, 0, &filter);
+        let redacted = redact_event_for_player(&event, PlayerSlot(0), &filter);
 
         if let GameAction::AttackUnit { random, .. } = redacted.action {
             assert_eq!(random, 0.0);
@@ -1125,12 +1279,19 @@ mod tests {
     #[test]
     fn test_redact_event_removes_randomness_proof() {
         let mut game = create_test_game();
-        add_unit(&mut game, 0, HexCoord::new(10, 10));
+        add_unit(&mut game, PlayerSlot(0), HexCoord::new(10, 10));
 
-        let mut filter = VisibilityFilter::new(0);
+        let mut filter = VisibilityFilter::new(PlayerSlot(0));
         filter.update_from_game_state(&game);
 
-        let mut event = GameEvent::new("test".to_string(), 1, None, 1, 1, GameAction::EndTurn);
+        let mut event = GameEvent::new(
+            GameId::new("test"),
+            PlayerSlot(1),
+            None,
+            1,
+            1,
+            GameAction::EndTurn,
+        );
         event.randomness_proof = Some(crate::cashu::RandomnessProof {
             mint_keyset_id: "test".to_string(),
             blinded_message: vec![1, 2, 3],
@@ -1141,21 +1302,21 @@ mod tests {
             timestamp: 12345,
         });
 
-        let redacted = redact_event_for_player(&event, 0, &filter);
+        let redacted = redact_event_for_player(&event, PlayerSlot(0), &filter);
         assert!(redacted.randomness_proof.is_none());
     }
 
     #[test]
     fn test_own_events_not_redacted() {
         let mut game = create_test_game();
-        add_unit(&mut game, 0, HexCoord::new(10, 10));
+        add_unit(&mut game, PlayerSlot(0), HexCoord::new(10, 10));
 
-        let mut filter = VisibilityFilter::new(0);
+        let mut filter = VisibilityFilter::new(PlayerSlot(0));
         filter.update_from_game_state(&game);
 
         let event = GameEvent::new(
-            "test".to_string(),
-            0, // Own event
+            GameId::new("test"),
+            PlayerSlot(0), // Own event
             None,
             1,
             1,
@@ -1164,7 +1325,7 @@ mod tests {
             },
         );
 
-        let redacted = redact_event_for_player(&event, 0, &filter);
+        let redacted = redact_event_for_player(&event, PlayerSlot(0), &filter);
 
         // Should be unchanged
         if let GameAction::SetResearch { tech_id } = &redacted.action {
@@ -1180,7 +1341,7 @@ mod tests {
     fn test_visibility_with_no_units() {
         let game = create_test_game();
 
-        let mut filter = VisibilityFilter::new(0);
+        let mut filter = VisibilityFilter::new(PlayerSlot(0));
         filter.update_from_game_state(&game);
 
         // Should have no visibility
@@ -1192,10 +1353,10 @@ mod tests {
     #[test]
     fn test_multiple_units_combine_visibility() {
         let mut game = create_test_game();
-        add_unit(&mut game, 0, HexCoord::new(5, 5));
-        add_unit(&mut game, 0, HexCoord::new(15, 15));
+        add_unit(&mut game, PlayerSlot(0), HexCoord::new(5, 5));
+        add_unit(&mut game, PlayerSlot(0), HexCoord::new(15, 15));
 
-        let mut filter = VisibilityFilter::new(0);
+        let mut filter = VisibilityFilter::new(PlayerSlot(0));
         filter.update_from_game_state(&game);
 
         // Should see tiles around both units
@@ -1212,9 +1373,9 @@ mod tests {
         if let Some(tile) = game.map.get_mut(&unit_pos) {
             tile.feature = Some(crate::terrain::Feature::Hills);
         }
-        add_unit(&mut game, 0, unit_pos);
+        add_unit(&mut game, PlayerSlot(0), unit_pos);
 
-        let mut filter = VisibilityFilter::new(0);
+        let mut filter = VisibilityFilter::new(PlayerSlot(0));
         filter.update_from_game_state(&game);
 
         // Should have extended vision (default 2 + 1 for hills = 3)
@@ -1225,7 +1386,14 @@ mod tests {
 
     #[test]
     fn test_filtered_event_is_visible() {
-        let event = GameEvent::new("test".to_string(), 0, None, 1, 1, GameAction::EndTurn);
+        let event = GameEvent::new(
+            GameId::new("test"),
+            PlayerSlot(0),
+            None,
+            1,
+            1,
+            GameAction::EndTurn,
+        );
 
         let fully = FilteredEvent::FullyVisible(event.clone());
         let partial = FilteredEvent::PartiallyVisible(event.clone());
@@ -1241,7 +1409,14 @@ mod tests {
 
     #[test]
     fn test_filtered_event_get_event() {
-        let event = GameEvent::new("test".to_string(), 0, None, 1, 1, GameAction::EndTurn);
+        let event = GameEvent::new(
+            GameId::new("test"),
+            PlayerSlot(0),
+            None,
+            1,
+            1,
+            GameAction::EndTurn,
+        );
 
         let fully = FilteredEvent::FullyVisible(event.clone());
         let hidden = FilteredEvent::Hidden;
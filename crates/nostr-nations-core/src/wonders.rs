@@ -0,0 +1,215 @@
+//! World wonders and the race to build them.
+//!
+//! Each wonder can be built once per world. The game keeps a registry of
+//! built wonders in [`GameState::wonders`]; a wonder that is already in the
+//! registry can no longer be chosen for production. When a city completes
+//! a wonder, every other city still working on it loses the race: the
+//! wonder is dropped from its production and queue, and the production
+//! already spent on it is refunded to its owner as gold.
+//!
+//! Completion is announced to the builder and to every player who has met
+//! them (see [`GameState::has_met`]).
+
+use crate::city::{ProductionItem, WonderType};
+use crate::game_state::GameState;
+use crate::types::{CityId, PlayerSlot};
+use serde::{Deserialize, Serialize};
+
+/// A wonder in the world registry.
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct BuiltWonder {
+    pub wonder: WonderType,
+    pub city_id: CityId,
+    pub player_id: PlayerSlot,
+    /// Turn the wonder was completed.
+    pub turn: u32,
+}
+
+/// Production refunded to a city that lost a wonder race.
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct WonderRefund {
+    pub city_id: CityId,
+    pub player_id: PlayerSlot,
+    /// Gold paid out, one per point of production spent.
+    pub gold: i32,
+}
+
+/// The outcome of completing a wonder.
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct WonderCompletion {
+    pub built: BuiltWonder,
+    /// Cities that were also building the wonder, by city ID.
+    pub refunds: Vec<WonderRefund>,
+}
+
+/// Check whether a wonder has been built anywhere in the world.
+pub fn is_built(state: &GameState, wonder: WonderType) -> bool {
+    state.wonders.contains_key(&wonder)
+}
+
+/// Record a wonder as built in a city and refund every other city
+/// building it.
+///
+/// Returns `None` if the city doesn't exist or the wonder was already
+/// built elsewhere.
+pub fn complete_wonder(
+    state: &mut GameState,
+    city_id: CityId,
+    wonder: WonderType,
+) -> Option<WonderCompletion> {
+    if is_built(state, wonder) {
+        return None;
+    }
+    let turn = state.turn;
+    let city = state.cities.get_mut(&city_id)?;
+    city.wonders.insert(wonder);
+    let built = BuiltWonder {
+        wonder,
+        city_id,
+        player_id: city.owner,
+        turn,
+    };
+    state.wonders.insert(wonder, built.clone());
+    if let Some(player) = state.get_player_mut(built.player_id) {
+        player.score.wonders += 1;
+        player.score.recalculate();
+    }
+
+    let mut racing: Vec<CityId> = state
+        .cities
+        .values()
+        .filter(|c| c.id != city_id)
+        .filter(|c| {
+            c.production == Some(ProductionItem::Wonder(wonder))
+                || c.production_queue.contains(&ProductionItem::Wonder(wonder))
+        })
+        .map(|c| c.id)
+        .collect();
+    racing.sort_unstable();
+
+    let mut refunds = Vec::new();
+    for id in racing {
+        let Some(city) = state.cities.get_mut(&id) else {
+            continue;
+        };
+        city.production_queue
+            .retain(|item| *item != ProductionItem::Wonder(wonder));
+        if city.production != Some(ProductionItem::Wonder(wonder)) {
+            continue;
+        }
+        let gold = city.production_progress as i32;
+        let owner = city.owner;
+        city.production = city.production_queue.pop();
+        city.production_progress = 0;
+        if let Some(player) = state.get_player_mut(owner) {
+            player.gold += gold;
+        }
+        refunds.push(WonderRefund {
+            city_id: id,
+            player_id: owner,
+            gold,
+        });
+    }
+
+    Some(WonderCompletion { built, refunds })
+}
+
+/// Whether a player hears about a wonder being completed.
+pub fn is_announced_to(state: &GameState, built: &BuiltWonder, viewer: PlayerSlot) -> bool {
+    built.player_id == viewer || state.has_met(viewer, built.player_id)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::city::City;
+    use crate::hex::HexCoord;
+    use crate::player::{Civilization, Player};
+    use crate::settings::GameSettings;
+    use crate::types::{GameId, Npub};
+
+    fn create_state() -> GameState {
+        let mut settings = GameSettings::new("Test".to_string());
+        settings.player_count = 3;
+        let mut state = GameState::new(GameId::new("test"), settings, [0; 32]);
+        for id in 0..3 {
+            state
+                .add_player(Player::new(
+                    PlayerSlot(id),
+                    Npub(format!("pk{}", id)),
+                    format!("P{}", id),
+                    Civilization::default(),
+                ))
+                .unwrap();
+        }
+        for id in 0..3u64 {
+            let mut city = City::new(
+                CityId(id + 1),
+                PlayerSlot(id as u8),
+                format!("C{}", id),
+                HexCoord::new(id as i32 * 6, 0),
+                true,
+            );
+            city.set_production(ProductionItem::Wonder(WonderType::Pyramids));
+            city.production_progress = 50;
+            state.cities.insert(CityId(id + 1), city);
+        }
+        state
+    }
+
+    #[test]
+    fn test_complete_wonder_refunds_rivals() {
+        let mut state = create_state();
+        state
+            .cities
+            .get_mut(&CityId(3))
+            .unwrap()
+            .queue_production(ProductionItem::Building(crate::city::BuildingType::Granary));
+
+        let completion = complete_wonder(&mut state, CityId(1), WonderType::Pyramids).unwrap();
+        assert_eq!(completion.built.player_id, PlayerSlot(0));
+        assert!(is_built(&state, WonderType::Pyramids));
+        assert!(state.cities[&CityId(1)]
+            .wonders
+            .contains(&WonderType::Pyramids));
+        assert_eq!(state.players[0].score.wonders, 1);
+
+        assert_eq!(
+            completion.refunds,
+            [
+                WonderRefund {
+                    city_id: CityId(2),
+                    player_id: PlayerSlot(1),
+                    gold: 50
+                },
+                WonderRefund {
+                    city_id: CityId(3),
+                    player_id: PlayerSlot(2),
+                    gold: 50
+                }
+            ]
+        );
+        assert_eq!(state.players[1].gold, 50);
+        assert_eq!(state.cities[&CityId(2)].production, None);
+        // Rivals move on to the next item in their queue
+        assert_eq!(
+            state.cities[&CityId(3)].production,
+            Some(ProductionItem::Building(crate::city::BuildingType::Granary))
+        );
+
+        // A wonder is built once per world
+        assert!(complete_wonder(&mut state, CityId(2), WonderType::Pyramids).is_none());
+    }
+
+    #[test]
+    fn test_wonder_announced_to_met_players() {
+        let mut state = create_state();
+        let completion = complete_wonder(&mut state, CityId(1), WonderType::Pyramids).unwrap();
+        assert!(is_announced_to(&state, &completion.built, PlayerSlot(0)));
+        assert!(!is_announced_to(&state, &completion.built, PlayerSlot(1)));
+
+        crate::contact::meet(&mut state, PlayerSlot(0), PlayerSlot(1));
+        assert!(is_announced_to(&state, &completion.built, PlayerSlot(1)));
+        assert!(!is_announced_to(&state, &completion.built, PlayerSlot(2)));
+    }
+}
@@ -1,5 +1,6 @@
 //! Resource yields from tiles, buildings, and other game elements.
 
+use crate::fixed::{deterministic, Fixed};
 use serde::{Deserialize, Serialize};
 use std::ops::{Add, AddAssign, Sub, SubAssign};
 
@@ -20,6 +21,14 @@ pub struct Yields {
     pub culture: i32,
 }
 
+deterministic!(struct Yields {
+    food,
+    production,
+    gold,
+    science,
+    culture,
+});
+
 impl Yields {
     /// Create yields with all values set to zero.
     pub const fn zero() -> Self {
@@ -98,13 +107,14 @@ impl Yields {
     }
 
     /// Apply a multiplier to all yields.
-    pub fn multiply(&self, factor: f32) -> Self {
+    pub fn multiply(&self, factor: Fixed) -> Self {
+        let scale = |value: i32| factor.mul_int(value as i64).round() as i32;
         Self {
-            food: (self.food as f32 * factor).round() as i32,
-            production: (self.production as f32 * factor).round() as i32,
-            gold: (self.gold as f32 * factor).round() as i32,
-            science: (self.science as f32 * factor).round() as i32,
-            culture: (self.culture as f32 * factor).round() as i32,
+            food: scale(self.food),
+            production: scale(self.production),
+            gold: scale(self.gold),
+            science: scale(self.science),
+            culture: scale(self.culture),
         }
     }
 
@@ -237,7 +247,7 @@ mod tests {
     #[test]
     fn test_multiply() {
         let y = Yields::new(2, 4, 3, 1, 0);
-        let doubled = y.multiply(2.0);
+        let doubled = y.multiply(Fixed::from_int(2));
         assert_eq!(doubled.food, 4);
         assert_eq!(doubled.production, 8);
     }
@@ -19,7 +19,7 @@ use nostr_nations_core::{
     player::{Civilization, Player},
     settings::GameSettings,
     terrain::{Feature, Terrain},
-    types::{MapSize, PlayerId},
+    types::{CityId, GameId, MapSize, Npub, PlayerSlot, UnitId},
     unit::{Promotion, Unit, UnitType},
 };
 use std::collections::HashSet;
@@ -34,12 +34,12 @@ fn create_game_with_players(player_count: u8) -> GameState {
     settings.player_count = player_count;
     settings.map_size = MapSize::Duel;
 
-    let mut game = GameState::new("fog-test-game".to_string(), settings, [42u8; 32]);
+    let mut game = GameState::new(GameId::new("fog-test-game"), settings, [42u8; 32]);
 
     for i in 0..player_count {
         let player = Player::new(
-            i,
-            format!("npub_player_{}", i),
+            PlayerSlot(i),
+            Npub(format!("npub_player_{}", i)),
             format!("Player {}", i + 1),
             Civilization::generic(),
         );
@@ -55,10 +55,10 @@ fn create_test_map(width: u32, height: u32) -> Map {
 }
 
 /// Create a test player
-fn create_test_player(id: PlayerId, name: &str) -> Player {
+fn create_test_player(id: PlayerSlot, name: &str) -> Player {
     Player::new(
         id,
-        format!("npub_{}", id),
+        Npub(format!("npub_{}", id)),
         name.to_string(),
         Civilization::generic(),
     )
@@ -181,7 +181,7 @@ mod initial_visibility {
 
     #[test]
     fn test_new_player_starts_with_no_visibility() {
-        let player = create_test_player(0, "NewPlayer");
+        let player = create_test_player(PlayerSlot(0), "NewPlayer");
 
         // New player should have no explored tiles
         assert!(player.explored_tiles.is_empty());
@@ -195,7 +195,7 @@ mod initial_visibility {
 
     #[test]
     fn test_unit_reveals_tiles_around_it() {
-        let mut player = create_test_player(0, "UnitPlayer");
+        let mut player = create_test_player(PlayerSlot(0), "UnitPlayer");
         let map = create_test_map(20, 20);
 
         // Place a warrior at (10, 10)
@@ -242,7 +242,7 @@ mod initial_visibility {
 
     #[test]
     fn test_city_reveals_tiles_in_radius() {
-        let mut player = create_test_player(0, "CityPlayer");
+        let mut player = create_test_player(PlayerSlot(0), "CityPlayer");
         let map = create_test_map(20, 20);
 
         // City position
@@ -298,7 +298,7 @@ mod initial_visibility {
 
     #[test]
     fn test_multiple_units_combine_visibility() {
-        let mut player = create_test_player(0, "MultiUnitPlayer");
+        let mut player = create_test_player(PlayerSlot(0), "MultiUnitPlayer");
         let map = create_test_map(30, 30);
 
         // Place two warriors at different positions
@@ -388,7 +388,7 @@ mod vision_radius {
 
     #[test]
     fn test_unit_on_hills_sees_further() {
-        let mut player = create_test_player(0, "HillsPlayer");
+        let mut player = create_test_player(PlayerSlot(0), "HillsPlayer");
         let mut map = create_test_map(20, 20);
 
         // Place hills at unit position
@@ -461,7 +461,12 @@ mod vision_radius {
     #[test]
     fn test_sentry_promotion_increases_vision() {
         // The Sentry promotion gives +1 vision
-        let mut unit = Unit::new(1, 0, UnitType::Warrior, HexCoord::new(5, 5));
+        let mut unit = Unit::new(
+            UnitId(1),
+            PlayerSlot(0),
+            UnitType::Warrior,
+            HexCoord::new(5, 5),
+        );
 
         let base_vision = base_vision_radius(unit.unit_type);
 
@@ -514,7 +519,7 @@ mod exploration {
 
     #[test]
     fn test_explored_tiles_stay_explored() {
-        let mut player = create_test_player(0, "Explorer");
+        let mut player = create_test_player(PlayerSlot(0), "Explorer");
         let map = create_test_map(20, 20);
 
         let unit_pos = HexCoord::new(10, 10);
@@ -616,7 +621,7 @@ mod exploration {
 
     #[test]
     fn test_exploration_accumulates_over_time() {
-        let mut player = create_test_player(0, "Explorer");
+        let mut player = create_test_player(PlayerSlot(0), "Explorer");
         let map = create_test_map(40, 40);
 
         // Move unit along a path
@@ -649,7 +654,7 @@ mod exploration {
 
     #[test]
     fn test_exploration_score_contribution() {
-        let mut player = create_test_player(0, "ScoreExplorer");
+        let mut player = create_test_player(PlayerSlot(0), "ScoreExplorer");
 
         let initial_land_score = player.score.land;
 
@@ -676,7 +681,7 @@ mod unit_movement {
 
     #[test]
     fn test_moving_unit_reveals_new_tiles() {
-        let mut player = create_test_player(0, "Mover");
+        let mut player = create_test_player(PlayerSlot(0), "Mover");
         let map = create_test_map(30, 30);
 
         let start_pos = HexCoord::new(10, 10);
@@ -749,7 +754,7 @@ mod unit_movement {
 
     #[test]
     fn test_unit_path_reveals_all_tiles_along_route() {
-        let mut player = create_test_player(0, "PathMover");
+        let mut player = create_test_player(PlayerSlot(0), "PathMover");
         let map = create_test_map(30, 30);
 
         // Unit moves along a path
@@ -798,7 +803,7 @@ mod unit_movement {
 
     #[test]
     fn test_fast_units_reveal_more_tiles_per_turn() {
-        let _player = create_test_player(0, "FastMover");
+        let _player = create_test_player(PlayerSlot(0), "FastMover");
         let map = create_test_map(40, 40);
 
         // Warrior moves 2 tiles per turn
@@ -844,7 +849,7 @@ mod unit_movement {
 
     #[test]
     fn test_movement_through_forest_still_reveals() {
-        let mut player = create_test_player(0, "ForestMover");
+        let mut player = create_test_player(PlayerSlot(0), "ForestMover");
         let mut map = create_test_map(20, 20);
 
         // Add forests along path
@@ -981,7 +986,12 @@ mod combat_visibility {
 
     #[test]
     fn test_ranged_attack_within_range() {
-        let archer = Unit::new(1, 0, UnitType::Archer, HexCoord::new(5, 5));
+        let archer = Unit::new(
+            UnitId(1),
+            PlayerSlot(0),
+            UnitType::Archer,
+            HexCoord::new(5, 5),
+        );
         let target_pos = HexCoord::new(7, 5);
 
         let range = archer.range();
@@ -998,7 +1008,12 @@ mod combat_visibility {
 
     #[test]
     fn test_ranged_attack_out_of_range() {
-        let archer = Unit::new(1, 0, UnitType::Archer, HexCoord::new(5, 5));
+        let archer = Unit::new(
+            UnitId(1),
+            PlayerSlot(0),
+            UnitType::Archer,
+            HexCoord::new(5, 5),
+        );
         let target_pos = HexCoord::new(10, 5);
 
         let range = archer.range();
@@ -1045,7 +1060,13 @@ mod city_visibility {
     fn test_cities_have_fixed_vision_radius() {
         let base_city_vision = 2u32;
 
-        let city = City::new(1, 0, "TestCity".to_string(), HexCoord::new(10, 10), true);
+        let city = City::new(
+            CityId(1),
+            PlayerSlot(0),
+            "TestCity".to_string(),
+            HexCoord::new(10, 10),
+            true,
+        );
 
         // City should provide consistent vision
         let visible_tiles = city.position.hexes_in_radius(base_city_vision);
@@ -1060,7 +1081,13 @@ mod city_visibility {
         let base_city_vision = 2u32;
         let wall_vision_bonus = 1u32;
 
-        let mut city = City::new(1, 0, "WalledCity".to_string(), HexCoord::new(10, 10), true);
+        let mut city = City::new(
+            CityId(1),
+            PlayerSlot(0),
+            "WalledCity".to_string(),
+            HexCoord::new(10, 10),
+            true,
+        );
 
         // Add walls
         city.add_building(BuildingType::Walls);
@@ -1082,8 +1109,8 @@ mod city_visibility {
         let castle_bonus = 1u32;
 
         let mut city = City::new(
-            1,
-            0,
+            CityId(1),
+            PlayerSlot(0),
             "FortifiedCity".to_string(),
             HexCoord::new(10, 10),
             true,
@@ -1142,16 +1169,16 @@ mod city_visibility {
     #[test]
     fn test_capital_vs_regular_city_vision() {
         let capital = City::new(
-            1,
-            0,
+            CityId(1),
+            PlayerSlot(0),
             "Capital".to_string(),
             HexCoord::new(5, 5),
             true, // is_capital
         );
 
         let regular_city = City::new(
-            2,
-            0,
+            CityId(2),
+            PlayerSlot(0),
             "Village".to_string(),
             HexCoord::new(15, 15),
             false, // not capital
@@ -1171,8 +1198,8 @@ mod city_visibility {
     #[test]
     fn test_city_territory_visibility() {
         let city = City::new(
-            1,
-            0,
+            CityId(1),
+            PlayerSlot(0),
             "TerritoryCity".to_string(),
             HexCoord::new(10, 10),
             true,
@@ -1206,12 +1233,12 @@ mod shared_visibility {
         game.start().unwrap();
 
         // Set players as allies
-        if let Some(rel) = game.diplomacy.get_mut(0, 1) {
+        if let Some(rel) = game.diplomacy.get_mut(PlayerSlot(0), PlayerSlot(1)) {
             rel.status = DiplomaticStatus::Allied;
         }
 
         // Verify alliance
-        let rel = game.diplomacy.get(0, 1).unwrap();
+        let rel = game.diplomacy.get(PlayerSlot(0), PlayerSlot(1)).unwrap();
         assert_eq!(rel.status, DiplomaticStatus::Allied);
 
         // Player 0 explores some tiles
@@ -1230,18 +1257,24 @@ mod shared_visibility {
         game.start().unwrap();
 
         // Establish open borders
-        game.diplomacy.modify_relationship_score(0, 1, 60);
-        assert!(game
-            .diplomacy
-            .propose_treaty(0, 1, TreatyType::OpenBorders, 1));
+        game.diplomacy
+            .modify_relationship_score(PlayerSlot(0), PlayerSlot(1), 60);
+        assert!(game.diplomacy.propose_treaty(
+            PlayerSlot(0),
+            PlayerSlot(1),
+            TreatyType::OpenBorders,
+            1
+        ));
 
         // Verify treaty
-        assert!(game.diplomacy.has_treaty(0, 1, TreatyType::OpenBorders));
+        assert!(game
+            .diplomacy
+            .has_treaty(PlayerSlot(0), PlayerSlot(1), TreatyType::OpenBorders));
 
         // With open borders, visibility might be shared (depending on implementation)
         // At minimum, units can pass through each other's territory
-        assert!(game.diplomacy.can_units_pass(0, 1));
-        assert!(game.diplomacy.can_units_pass(1, 0));
+        assert!(game.diplomacy.can_units_pass(PlayerSlot(0), PlayerSlot(1)));
+        assert!(game.diplomacy.can_units_pass(PlayerSlot(1), PlayerSlot(0)));
     }
 
     #[test]
@@ -1251,23 +1284,28 @@ mod shared_visibility {
         game.start().unwrap();
 
         // Set up alliance with shared visibility
-        if let Some(rel) = game.diplomacy.get_mut(0, 1) {
+        if let Some(rel) = game.diplomacy.get_mut(PlayerSlot(0), PlayerSlot(1)) {
             rel.status = DiplomaticStatus::Allied;
         }
-        game.diplomacy.modify_relationship_score(0, 1, 60);
         game.diplomacy
-            .propose_treaty(0, 1, TreatyType::OpenBorders, 1);
+            .modify_relationship_score(PlayerSlot(0), PlayerSlot(1), 60);
+        game.diplomacy
+            .propose_treaty(PlayerSlot(0), PlayerSlot(1), TreatyType::OpenBorders, 1);
 
         // Verify initial state
-        assert!(game.diplomacy.has_treaty(0, 1, TreatyType::OpenBorders));
+        assert!(game
+            .diplomacy
+            .has_treaty(PlayerSlot(0), PlayerSlot(1), TreatyType::OpenBorders));
 
         // Declare war
-        game.diplomacy.declare_war(0, 1, 2);
+        game.diplomacy.declare_war(PlayerSlot(0), PlayerSlot(1), 2);
 
         // War removes treaties and shared visibility
-        assert!(game.diplomacy.are_at_war(0, 1));
-        assert!(!game.diplomacy.has_treaty(0, 1, TreatyType::OpenBorders));
-        assert!(!game.diplomacy.can_units_pass(0, 1));
+        assert!(game.diplomacy.are_at_war(PlayerSlot(0), PlayerSlot(1)));
+        assert!(!game
+            .diplomacy
+            .has_treaty(PlayerSlot(0), PlayerSlot(1), TreatyType::OpenBorders));
+        assert!(!game.diplomacy.can_units_pass(PlayerSlot(0), PlayerSlot(1)));
     }
 
     #[test]
@@ -1277,16 +1315,21 @@ mod shared_visibility {
         game.start().unwrap();
 
         // Establish defensive pact (requires allied status)
-        if let Some(rel) = game.diplomacy.get_mut(0, 1) {
+        if let Some(rel) = game.diplomacy.get_mut(PlayerSlot(0), PlayerSlot(1)) {
             rel.status = DiplomaticStatus::Allied;
         }
 
-        assert!(game
-            .diplomacy
-            .propose_treaty(0, 1, TreatyType::DefensivePact, 1));
+        assert!(game.diplomacy.propose_treaty(
+            PlayerSlot(0),
+            PlayerSlot(1),
+            TreatyType::DefensivePact,
+            1
+        ));
 
         // Defensive pact means close military cooperation
-        assert!(game.diplomacy.has_treaty(0, 1, TreatyType::DefensivePact));
+        assert!(game
+            .diplomacy
+            .has_treaty(PlayerSlot(0), PlayerSlot(1), TreatyType::DefensivePact));
 
         // With defensive pact, visibility would be shared more extensively
     }
@@ -1298,11 +1341,11 @@ mod shared_visibility {
         game.start().unwrap();
 
         // Default neutral relationship
-        let rel = game.diplomacy.get(0, 1).unwrap();
+        let rel = game.diplomacy.get(PlayerSlot(0), PlayerSlot(1)).unwrap();
         assert_eq!(rel.status, DiplomaticStatus::Neutral);
 
         // Units cannot pass without treaty
-        assert!(!game.diplomacy.can_units_pass(0, 1));
+        assert!(!game.diplomacy.can_units_pass(PlayerSlot(0), PlayerSlot(1)));
 
         // Player 0 explores
         game.players[0].explore_tile(HexCoord::new(10, 10));
@@ -1318,17 +1361,17 @@ mod shared_visibility {
         game.start().unwrap();
 
         // Player 0 allied with Player 1
-        if let Some(rel) = game.diplomacy.get_mut(0, 1) {
+        if let Some(rel) = game.diplomacy.get_mut(PlayerSlot(0), PlayerSlot(1)) {
             rel.status = DiplomaticStatus::Allied;
         }
 
         // Player 1 allied with Player 2
-        if let Some(rel) = game.diplomacy.get_mut(1, 2) {
+        if let Some(rel) = game.diplomacy.get_mut(PlayerSlot(1), PlayerSlot(2)) {
             rel.status = DiplomaticStatus::Allied;
         }
 
         // Player 0 and Player 2 are not directly allied
-        let rel_0_2 = game.diplomacy.get(0, 2).unwrap();
+        let rel_0_2 = game.diplomacy.get(PlayerSlot(0), PlayerSlot(2)).unwrap();
         assert_ne!(rel_0_2.status, DiplomaticStatus::Allied);
 
         // Visibility sharing should not chain through allies
@@ -1345,7 +1388,7 @@ mod edge_cases {
 
     #[test]
     fn test_map_edges_visibility() {
-        let mut player = create_test_player(0, "EdgePlayer");
+        let mut player = create_test_player(PlayerSlot(0), "EdgePlayer");
         let map = create_test_map(20, 20);
 
         // Unit at corner of map
@@ -1407,7 +1450,12 @@ mod edge_cases {
 
     #[test]
     fn test_units_on_transport_visibility() {
-        let mut unit = Unit::new(1, 0, UnitType::Warrior, HexCoord::new(5, 5));
+        let mut unit = Unit::new(
+            UnitId(1),
+            PlayerSlot(0),
+            UnitType::Warrior,
+            HexCoord::new(5, 5),
+        );
 
         // Unit embarks
         unit.embarked = true;
@@ -1450,7 +1498,7 @@ mod edge_cases {
     #[test]
     fn test_zero_vision_edge_case() {
         // Hypothetical unit with zero vision
-        let mut player = create_test_player(0, "BlindPlayer");
+        let mut player = create_test_player(PlayerSlot(0), "BlindPlayer");
         let map = create_test_map(20, 20);
 
         let pos = HexCoord::new(10, 10);
@@ -1472,7 +1520,7 @@ mod edge_cases {
     fn test_visibility_at_max_map_size() {
         // Test with a larger map
         let map = create_test_map(100, 100);
-        let mut player = create_test_player(0, "LargeMapPlayer");
+        let mut player = create_test_player(PlayerSlot(0), "LargeMapPlayer");
 
         let center = HexCoord::new(50, 50);
         let large_vision = 5u32;
@@ -1583,8 +1631,18 @@ mod integration {
         let unit1_id = game.allocate_unit_id();
         let unit2_id = game.allocate_unit_id();
 
-        let unit1 = Unit::new(unit1_id, 0, UnitType::Warrior, HexCoord::new(5, 5));
-        let unit2 = Unit::new(unit2_id, 1, UnitType::Warrior, HexCoord::new(35, 20));
+        let unit1 = Unit::new(
+            unit1_id,
+            PlayerSlot(0),
+            UnitType::Warrior,
+            HexCoord::new(5, 5),
+        );
+        let unit2 = Unit::new(
+            unit2_id,
+            PlayerSlot(1),
+            UnitType::Warrior,
+            HexCoord::new(35, 20),
+        );
 
         game.units.insert(unit1_id, unit1.clone());
         game.units.insert(unit2_id, unit2.clone());
@@ -1623,7 +1681,7 @@ mod integration {
         let city_id = game.allocate_city_id();
         let city = City::new(
             city_id,
-            0,
+            PlayerSlot(0),
             "TestCity".to_string(),
             HexCoord::new(10, 10),
             true,
@@ -1632,7 +1690,12 @@ mod integration {
 
         // Create unit for player 0
         let unit_id = game.allocate_unit_id();
-        let unit = Unit::new(unit_id, 0, UnitType::Warrior, HexCoord::new(15, 10));
+        let unit = Unit::new(
+            unit_id,
+            PlayerSlot(0),
+            UnitType::Warrior,
+            HexCoord::new(15, 10),
+        );
         game.units.insert(unit_id, unit.clone());
 
         // Reveal from city
@@ -1666,19 +1729,29 @@ mod integration {
         game.start().unwrap();
 
         // Initial peaceful state
-        assert!(!game.diplomacy.are_at_war(0, 1));
+        assert!(!game.diplomacy.are_at_war(PlayerSlot(0), PlayerSlot(1)));
 
         // Units can see each other when in range
-        let p0_unit = Unit::new(1, 0, UnitType::Warrior, HexCoord::new(10, 10));
-        let p1_unit = Unit::new(2, 1, UnitType::Warrior, HexCoord::new(11, 10));
+        let p0_unit = Unit::new(
+            UnitId(1),
+            PlayerSlot(0),
+            UnitType::Warrior,
+            HexCoord::new(10, 10),
+        );
+        let p1_unit = Unit::new(
+            UnitId(2),
+            PlayerSlot(1),
+            UnitType::Warrior,
+            HexCoord::new(11, 10),
+        );
 
-        game.units.insert(1, p0_unit);
-        game.units.insert(2, p1_unit);
+        game.units.insert(UnitId(1), p0_unit);
+        game.units.insert(UnitId(2), p1_unit);
 
         // War declared
-        game.diplomacy.declare_war(0, 1, 1);
+        game.diplomacy.declare_war(PlayerSlot(0), PlayerSlot(1), 1);
 
-        assert!(game.diplomacy.are_at_war(0, 1));
+        assert!(game.diplomacy.are_at_war(PlayerSlot(0), PlayerSlot(1)));
 
         // Enemy units in visible range should be seen as hostile
         // (actual hostility handling would be in game logic)
@@ -0,0 +1,74 @@
+//! Determinism cross-check against the reference vectors in `goldens/`.
+//!
+//! Every vector is replayed and its per-turn state hashes compared with
+//! the committed ones. CI runs this on x86, ARM and WASM; any difference
+//! means the engine isn't producing byte-identical games across targets.
+//!
+//! The vectors are embedded at compile time so the test also runs where
+//! there is no file system. To accept an intentional change in results,
+//! run `UPDATE_GOLDENS=1 cargo test -p nostr-nations-core --test golden_tests`
+//! on a native target and commit the rewritten files.
+
+use nostr_nations_core::goldens::GoldenVector;
+
+/// Committed vectors: (file name, contents).
+const VECTORS: &[(&str, &str)] = &[
+    (
+        "duel_opening.json",
+        include_str!("../goldens/duel_opening.json"),
+    ),
+    (
+        "duel_barbarians.json",
+        include_str!("../goldens/duel_barbarians.json"),
+    ),
+];
+
+fn parse(file: &str, json: &str) -> GoldenVector {
+    serde_json::from_str(json).unwrap_or_else(|e| panic!("{} is not a golden vector: {}", file, e))
+}
+
+/// Rewrite a vector's expected hashes from this build.
+#[cfg(not(target_arch = "wasm32"))]
+fn update(file: &str, mut vector: GoldenVector) {
+    vector.expected = vector.play().unwrap_or_else(|e| panic!("{}: {}", file, e));
+    let path = std::path::Path::new(env!("CARGO_MANIFEST_DIR"))
+        .join("goldens")
+        .join(file);
+    let json = serde_json::to_string_pretty(&vector).unwrap() + "\n";
+    std::fs::write(&path, json).unwrap();
+}
+
+#[test]
+fn test_golden_vectors() {
+    #[cfg(not(target_arch = "wasm32"))]
+    if std::env::var_os("UPDATE_GOLDENS").is_some() {
+        for (file, json) in VECTORS {
+            update(file, parse(file, json));
+        }
+        return;
+    }
+
+    let failures: Vec<String> = VECTORS
+        .iter()
+        .filter_map(|(file, json)| {
+            let vector = parse(file, json);
+            vector
+                .check()
+                .err()
+                .map(|e| format!("{} ({}): {}", vector.name, file, e))
+        })
+        .collect();
+    assert!(failures.is_empty(), "{}", failures.join("\n"));
+}
+
+#[test]
+fn test_golden_vectors_cover_many_turns() {
+    for (file, json) in VECTORS {
+        let vector = parse(file, json);
+        assert!(
+            vector.expected.len() >= 10,
+            "{} should check at least 10 turns",
+            file
+        );
+    }
+}
@@ -12,13 +12,14 @@
 use nostr_nations_core::{
     city::{BuildingType, City, ProductionItem},
     combat::{resolve_combat, CombatContext},
+    fixed::Fixed,
     game_state::{DiplomaticStatus, GamePhase, GameState, TreatyType},
     hex::HexCoord,
     map::{Map, Tile},
     player::{Civilization, Player},
     settings::GameSettings,
     terrain::{Feature, Terrain},
-    types::{MapSize, PlayerId, VictoryType},
+    types::{CityId, GameId, MapSize, Npub, PlayerSlot, UnitId, VictoryType},
     unit::{Unit, UnitType},
     victory::{SpaceshipProgress, VictoryChecker},
     yields::Yields,
@@ -34,12 +35,12 @@ fn create_game_with_players(player_count: u8) -> GameState {
     settings.player_count = player_count;
     settings.map_size = MapSize::Duel;
 
-    let mut game = GameState::new("test-game-1".to_string(), settings, [42u8; 32]);
+    let mut game = GameState::new(GameId::new("test-game-1"), settings, [42u8; 32]);
 
     for i in 0..player_count {
         let player = Player::new(
-            i,
-            format!("npub_player_{}", i),
+            PlayerSlot(i),
+            Npub(format!("npub_player_{}", i)),
             format!("Player {}", i + 1),
             Civilization::generic(),
         );
@@ -55,10 +56,10 @@ fn create_test_map(width: u32, height: u32) -> Map {
 }
 
 /// Create a simple test player
-fn create_test_player(id: PlayerId, name: &str) -> Player {
+fn create_test_player(id: PlayerSlot, name: &str) -> Player {
     Player::new(
         id,
-        format!("npub_{}", id),
+        Npub(format!("npub_{}", id)),
         name.to_string(),
         Civilization::generic(),
     )
@@ -70,12 +71,13 @@ fn create_test_player(id: PlayerId, name: &str) -> Player {
 
 mod game_setup_flow {
     use super::*;
+    use nostr_nations_core::types::{GameId, Npub};
 
     #[test]
     fn test_complete_game_setup_2_players() {
         // Step 1: Create game with settings
         let settings = GameSettings::new("Test Game".to_string());
-        let mut game = GameState::new("game-setup-test".to_string(), settings, [0u8; 32]);
+        let mut game = GameState::new(GameId::new("game-setup-test"), settings, [0u8; 32]);
 
         // Verify initial state
         assert_eq!(game.phase, GamePhase::Setup);
@@ -83,8 +85,8 @@ mod game_setup_flow {
         assert!(game.players.is_empty());
 
         // Step 2: Add players
-        let p1 = create_test_player(0, "Alice");
-        let p2 = create_test_player(1, "Bob");
+        let p1 = create_test_player(PlayerSlot(0), "Alice");
+        let p2 = create_test_player(PlayerSlot(1), "Bob");
 
         game.add_player(p1).expect("Should add player 1");
         game.add_player(p2).expect("Should add player 2");
@@ -103,10 +105,10 @@ mod game_setup_flow {
         // Step 5: Verify initial state
         assert_eq!(game.phase, GamePhase::Playing);
         assert_eq!(game.turn, 1);
-        assert_eq!(game.current_player, 0);
+        assert_eq!(game.current_player, PlayerSlot(0));
 
         // Verify diplomacy was initialized
-        let rel = game.diplomacy.get(0, 1);
+        let rel = game.diplomacy.get(PlayerSlot(0), PlayerSlot(1));
         assert!(rel.is_some());
         assert_eq!(rel.unwrap().status, DiplomaticStatus::Neutral);
     }
@@ -116,11 +118,11 @@ mod game_setup_flow {
         let mut settings = GameSettings::new("4 Player Game".to_string());
         settings.player_count = 4;
 
-        let mut game = GameState::new("game-4p".to_string(), settings, [1u8; 32]);
+        let mut game = GameState::new(GameId::new("game-4p"), settings, [1u8; 32]);
 
         // Add 4 players
         for i in 0..4 {
-            let player = create_test_player(i, &format!("Player{}", i + 1));
+            let player = create_test_player(PlayerSlot(i), &format!("Player{}", i + 1));
             game.add_player(player).expect("Should add player");
         }
 
@@ -133,7 +135,7 @@ mod game_setup_flow {
         // Verify all player pairs have diplomacy initialized
         for i in 0..4 {
             for j in (i + 1)..4 {
-                let rel = game.diplomacy.get(i, j);
+                let rel = game.diplomacy.get(PlayerSlot(i), PlayerSlot(j));
                 assert!(
                     rel.is_some(),
                     "Diplomacy should exist between {} and {}",
@@ -147,9 +149,10 @@ mod game_setup_flow {
     #[test]
     fn test_cannot_start_without_enough_players() {
         let settings = GameSettings::new("Test".to_string());
-        let mut game = GameState::new("game-1p".to_string(), settings, [0u8; 32]);
+        let mut game = GameState::new(GameId::new("game-1p"), settings, [0u8; 32]);
 
-        game.add_player(create_test_player(0, "Solo")).unwrap();
+        game.add_player(create_test_player(PlayerSlot(0), "Solo"))
+            .unwrap();
 
         let result = game.start();
         assert!(result.is_err());
@@ -160,17 +163,17 @@ mod game_setup_flow {
     fn test_cannot_add_duplicate_player() {
         let mut settings = GameSettings::new("Duplicate Test".to_string());
         settings.player_count = 4; // Allow more players
-        let mut game = GameState::new("dup-test".to_string(), settings, [0u8; 32]);
+        let mut game = GameState::new(GameId::new("dup-test"), settings, [0u8; 32]);
 
         let p1 = Player::new(
-            0,
-            "npub_same".to_string(),
+            PlayerSlot(0),
+            Npub::new("npub_same"),
             "First".to_string(),
             Civilization::generic(),
         );
         let p2 = Player::new(
-            1,
-            "npub_same".to_string(),
+            PlayerSlot(1),
+            Npub::new("npub_same"),
             "Second".to_string(),
             Civilization::generic(),
         );
@@ -192,20 +195,30 @@ mod game_setup_flow {
         let settler2_id = game.allocate_unit_id();
         let warrior2_id = game.allocate_unit_id();
 
-        assert_eq!(settler1_id, 1);
-        assert_eq!(warrior1_id, 2);
-        assert_eq!(settler2_id, 3);
-        assert_eq!(warrior2_id, 4);
+        assert_eq!(settler1_id, UnitId(1));
+        assert_eq!(warrior1_id, UnitId(2));
+        assert_eq!(settler2_id, UnitId(3));
+        assert_eq!(warrior2_id, UnitId(4));
 
         // Create starting units
-        let settler1 = Unit::new(settler1_id, 0, UnitType::Settler, HexCoord::new(5, 5));
-        let warrior1 = Unit::new(warrior1_id, 0, UnitType::Warrior, HexCoord::new(5, 6));
+        let settler1 = Unit::new(
+            settler1_id,
+            PlayerSlot(0),
+            UnitType::Settler,
+            HexCoord::new(5, 5),
+        );
+        let warrior1 = Unit::new(
+            warrior1_id,
+            PlayerSlot(0),
+            UnitType::Warrior,
+            HexCoord::new(5, 6),
+        );
 
         game.units.insert(settler1_id, settler1);
         game.units.insert(warrior1_id, warrior1);
 
         assert_eq!(game.units.len(), 2);
-        assert_eq!(game.units.get(&settler1_id).unwrap().owner, 0);
+        assert_eq!(game.units.get(&settler1_id).unwrap().owner, PlayerSlot(0));
     }
 }
 
@@ -224,23 +237,23 @@ mod turn_flow {
 
         // Initial state
         assert_eq!(game.turn, 1);
-        assert_eq!(game.current_player, 0);
-        assert!(game.is_player_turn(0));
-        assert!(!game.is_player_turn(1));
+        assert_eq!(game.current_player, PlayerSlot(0));
+        assert!(game.is_player_turn(PlayerSlot(0)));
+        assert!(!game.is_player_turn(PlayerSlot(1)));
 
         // Player 0 ends turn
         game.next_turn().unwrap();
-        assert_eq!(game.current_player, 1);
+        assert_eq!(game.current_player, PlayerSlot(1));
         assert_eq!(game.turn, 1); // Still turn 1
 
         // Player 1 ends turn
         game.next_turn().unwrap();
-        assert_eq!(game.current_player, 2);
+        assert_eq!(game.current_player, PlayerSlot(2));
         assert_eq!(game.turn, 1);
 
         // Player 2 ends turn - new turn cycle
         game.next_turn().unwrap();
-        assert_eq!(game.current_player, 0);
+        assert_eq!(game.current_player, PlayerSlot(0));
         assert_eq!(game.turn, 2);
     }
 
@@ -252,7 +265,12 @@ mod turn_flow {
 
         // Create a warrior for player 0
         let unit_id = game.allocate_unit_id();
-        let mut warrior = Unit::new(unit_id, 0, UnitType::Warrior, HexCoord::new(5, 5));
+        let mut warrior = Unit::new(
+            unit_id,
+            PlayerSlot(0),
+            UnitType::Warrior,
+            HexCoord::new(5, 5),
+        );
 
         // Warrior has 2 movement (stored as 20 for precision)
         assert_eq!(warrior.movement, 20);
@@ -286,7 +304,12 @@ mod turn_flow {
 
         // Create a settler for player 0
         let settler_id = game.allocate_unit_id();
-        let settler = Unit::new(settler_id, 0, UnitType::Settler, HexCoord::new(10, 10));
+        let settler = Unit::new(
+            settler_id,
+            PlayerSlot(0),
+            UnitType::Settler,
+            HexCoord::new(10, 10),
+        );
         game.units.insert(settler_id, settler);
 
         // Verify the tile can have a city founded
@@ -295,7 +318,13 @@ mod turn_flow {
 
         // Found city
         let city_id = game.allocate_city_id();
-        let city = City::new(city_id, 0, "Rome".to_string(), HexCoord::new(10, 10), true);
+        let city = City::new(
+            city_id,
+            PlayerSlot(0),
+            "Rome".to_string(),
+            HexCoord::new(10, 10),
+            true,
+        );
         game.cities.insert(city_id, city);
 
         // Remove settler (consumed)
@@ -321,7 +350,7 @@ mod turn_flow {
         let city_id = game.allocate_city_id();
         let mut city = City::new(
             city_id,
-            0,
+            PlayerSlot(0),
             "TestCity".to_string(),
             HexCoord::new(10, 10),
             true,
@@ -363,15 +392,15 @@ mod turn_flow {
         // Eliminate player 1
         game.players[1].eliminated = true;
 
-        assert_eq!(game.current_player, 0);
+        assert_eq!(game.current_player, PlayerSlot(0));
         game.next_turn().unwrap();
 
         // Should skip player 1 and go to player 2
-        assert_eq!(game.current_player, 2);
+        assert_eq!(game.current_player, PlayerSlot(2));
 
         game.next_turn().unwrap();
         // Should go back to player 0, incrementing turn
-        assert_eq!(game.current_player, 0);
+        assert_eq!(game.current_player, PlayerSlot(0));
         assert_eq!(game.turn, 2);
     }
 }
@@ -395,8 +424,18 @@ mod combat_flow {
 
     #[test]
     fn test_basic_melee_combat() {
-        let attacker = Unit::new(1, 0, UnitType::Warrior, HexCoord::new(0, 0));
-        let defender = Unit::new(2, 1, UnitType::Warrior, HexCoord::new(1, 0));
+        let attacker = Unit::new(
+            UnitId(1),
+            PlayerSlot(0),
+            UnitType::Warrior,
+            HexCoord::new(0, 0),
+        );
+        let defender = Unit::new(
+            UnitId(2),
+            PlayerSlot(1),
+            UnitType::Warrior,
+            HexCoord::new(1, 0),
+        );
         let tile = create_test_tile();
 
         let ctx = CombatContext {
@@ -404,7 +443,7 @@ mod combat_flow {
             defender: &defender,
             attacker_tile: &tile,
             defender_tile: &tile,
-            random: 0.5,
+            random: Fixed::HALF,
             is_ranged: false,
         };
 
@@ -428,8 +467,18 @@ mod combat_flow {
 
     #[test]
     fn test_ranged_combat_no_counter() {
-        let attacker = Unit::new(1, 0, UnitType::Archer, HexCoord::new(0, 0));
-        let defender = Unit::new(2, 1, UnitType::Warrior, HexCoord::new(2, 0));
+        let attacker = Unit::new(
+            UnitId(1),
+            PlayerSlot(0),
+            UnitType::Archer,
+            HexCoord::new(0, 0),
+        );
+        let defender = Unit::new(
+            UnitId(2),
+            PlayerSlot(1),
+            UnitType::Warrior,
+            HexCoord::new(2, 0),
+        );
         let tile = create_test_tile();
 
         let ctx = CombatContext {
@@ -437,7 +486,7 @@ mod combat_flow {
             defender: &defender,
             attacker_tile: &tile,
             defender_tile: &tile,
-            random: 0.5,
+            random: Fixed::HALF,
             is_ranged: true,
         };
 
@@ -453,8 +502,18 @@ mod combat_flow {
 
     #[test]
     fn test_stronger_unit_advantage() {
-        let attacker = Unit::new(1, 0, UnitType::Swordsman, HexCoord::new(0, 0)); // 14 strength
-        let defender = Unit::new(2, 1, UnitType::Warrior, HexCoord::new(1, 0)); // 8 strength
+        let attacker = Unit::new(
+            UnitId(1),
+            PlayerSlot(0),
+            UnitType::Swordsman,
+            HexCoord::new(0, 0),
+        ); // 14 strength
+        let defender = Unit::new(
+            UnitId(2),
+            PlayerSlot(1),
+            UnitType::Warrior,
+            HexCoord::new(1, 0),
+        ); // 8 strength
         let tile = create_test_tile();
 
         let ctx = CombatContext {
@@ -462,7 +521,7 @@ mod combat_flow {
             defender: &defender,
             attacker_tile: &tile,
             defender_tile: &tile,
-            random: 0.5,
+            random: Fixed::HALF,
             is_ranged: false,
         };
 
@@ -474,8 +533,18 @@ mod combat_flow {
 
     #[test]
     fn test_terrain_defense_bonus() {
-        let attacker = Unit::new(1, 0, UnitType::Warrior, HexCoord::new(0, 0));
-        let defender = Unit::new(2, 1, UnitType::Warrior, HexCoord::new(1, 0));
+        let attacker = Unit::new(
+            UnitId(1),
+            PlayerSlot(0),
+            UnitType::Warrior,
+            HexCoord::new(0, 0),
+        );
+        let defender = Unit::new(
+            UnitId(2),
+            PlayerSlot(1),
+            UnitType::Warrior,
+            HexCoord::new(1, 0),
+        );
         let flat_tile = create_test_tile();
         let hill_tile = create_hill_tile();
 
@@ -485,7 +554,7 @@ mod combat_flow {
             defender: &defender,
             attacker_tile: &flat_tile,
             defender_tile: &flat_tile,
-            random: 0.5,
+            random: Fixed::HALF,
             is_ranged: false,
         };
         let result_flat = resolve_combat(&ctx_flat);
@@ -496,7 +565,7 @@ mod combat_flow {
             defender: &defender,
             attacker_tile: &flat_tile,
             defender_tile: &hill_tile,
-            random: 0.5,
+            random: Fixed::HALF,
             is_ranged: false,
         };
         let result_hills = resolve_combat(&ctx_hills);
@@ -508,8 +577,18 @@ mod combat_flow {
     #[test]
     fn test_unit_destruction() {
         // Create a heavily wounded defender
-        let attacker = Unit::new(1, 0, UnitType::Swordsman, HexCoord::new(0, 0));
-        let mut defender = Unit::new(2, 1, UnitType::Warrior, HexCoord::new(1, 0));
+        let attacker = Unit::new(
+            UnitId(1),
+            PlayerSlot(0),
+            UnitType::Swordsman,
+            HexCoord::new(0, 0),
+        );
+        let mut defender = Unit::new(
+            UnitId(2),
+            PlayerSlot(1),
+            UnitType::Warrior,
+            HexCoord::new(1, 0),
+        );
         defender.health = 10; // Nearly dead
 
         let tile = create_test_tile();
@@ -519,7 +598,7 @@ mod combat_flow {
             defender: &defender,
             attacker_tile: &tile,
             defender_tile: &tile,
-            random: 0.9, // High random favors attacker
+            random: Fixed::from_percent(90), // High random favors attacker
             is_ranged: false,
         };
 
@@ -540,14 +619,24 @@ mod combat_flow {
         game.start().unwrap();
 
         // Declare war so combat is allowed
-        game.diplomacy.declare_war(0, 1, 1);
+        game.diplomacy.declare_war(PlayerSlot(0), PlayerSlot(1), 1);
 
         // Create units
         let attacker_id = game.allocate_unit_id();
         let defender_id = game.allocate_unit_id();
 
-        let attacker = Unit::new(attacker_id, 0, UnitType::Warrior, HexCoord::new(5, 5));
-        let defender = Unit::new(defender_id, 1, UnitType::Warrior, HexCoord::new(5, 6));
+        let attacker = Unit::new(
+            attacker_id,
+            PlayerSlot(0),
+            UnitType::Warrior,
+            HexCoord::new(5, 5),
+        );
+        let defender = Unit::new(
+            defender_id,
+            PlayerSlot(1),
+            UnitType::Warrior,
+            HexCoord::new(5, 6),
+        );
 
         game.units.insert(attacker_id, attacker.clone());
         game.units.insert(defender_id, defender.clone());
@@ -562,7 +651,7 @@ mod combat_flow {
             defender: &defender,
             attacker_tile: &attacker_tile,
             defender_tile: &defender_tile,
-            random: 0.5,
+            random: Fixed::HALF,
             is_ranged: false,
         };
 
@@ -604,8 +693,18 @@ mod combat_flow {
 
     #[test]
     fn test_fortification_defense_bonus() {
-        let attacker = Unit::new(1, 0, UnitType::Warrior, HexCoord::new(0, 0));
-        let mut defender = Unit::new(2, 1, UnitType::Warrior, HexCoord::new(1, 0));
+        let attacker = Unit::new(
+            UnitId(1),
+            PlayerSlot(0),
+            UnitType::Warrior,
+            HexCoord::new(0, 0),
+        );
+        let mut defender = Unit::new(
+            UnitId(2),
+            PlayerSlot(1),
+            UnitType::Warrior,
+            HexCoord::new(1, 0),
+        );
         let tile = create_test_tile();
 
         // Combat without fortification
@@ -614,7 +713,7 @@ mod combat_flow {
             defender: &defender,
             attacker_tile: &tile,
             defender_tile: &tile,
-            random: 0.5,
+            random: Fixed::HALF,
             is_ranged: false,
         };
         let result1 = resolve_combat(&ctx1);
@@ -629,7 +728,7 @@ mod combat_flow {
             defender: &defender,
             attacker_tile: &tile,
             defender_tile: &tile,
-            random: 0.5,
+            random: Fixed::HALF,
             is_ranged: false,
         };
         let result2 = resolve_combat(&ctx2);
@@ -654,7 +753,12 @@ mod city_management_flow {
 
         // Create settler
         let settler_id = game.allocate_unit_id();
-        let settler = Unit::new(settler_id, 0, UnitType::Settler, HexCoord::new(15, 15));
+        let settler = Unit::new(
+            settler_id,
+            PlayerSlot(0),
+            UnitType::Settler,
+            HexCoord::new(15, 15),
+        );
         game.units.insert(settler_id, settler);
 
         // Verify settler is civilian
@@ -664,7 +768,7 @@ mod city_management_flow {
         let city_id = game.allocate_city_id();
         let city = City::new(
             city_id,
-            0,
+            PlayerSlot(0),
             "New Rome".to_string(),
             HexCoord::new(15, 15),
             true,
@@ -695,7 +799,7 @@ mod city_management_flow {
         let city_id = game.allocate_city_id();
         let mut city = City::new(
             city_id,
-            0,
+            PlayerSlot(0),
             "Production City".to_string(),
             HexCoord::new(10, 10),
             true,
@@ -734,7 +838,12 @@ mod city_management_flow {
 
         // Create the actual unit
         let unit_id = game.allocate_unit_id();
-        let warrior = Unit::new(unit_id, 0, UnitType::Warrior, HexCoord::new(10, 10));
+        let warrior = Unit::new(
+            unit_id,
+            PlayerSlot(0),
+            UnitType::Warrior,
+            HexCoord::new(10, 10),
+        );
         game.units.insert(unit_id, warrior);
 
         assert_eq!(game.units.len(), 1);
@@ -753,7 +862,7 @@ mod city_management_flow {
         let city_id = game.allocate_city_id();
         let mut city = City::new(
             city_id,
-            0,
+            PlayerSlot(0),
             "Building City".to_string(),
             HexCoord::new(10, 10),
             true,
@@ -790,8 +899,8 @@ mod city_management_flow {
     fn test_city_growth() {
         let city_id = 1;
         let mut city = City::new(
-            city_id,
-            0,
+            CityId(city_id),
+            PlayerSlot(0),
             "Growing City".to_string(),
             HexCoord::new(10, 10),
             true,
@@ -821,8 +930,8 @@ mod city_management_flow {
     fn test_city_starvation() {
         let city_id = 1;
         let mut city = City::new(
-            city_id,
-            0,
+            CityId(city_id),
+            PlayerSlot(0),
             "Starving City".to_string(),
             HexCoord::new(10, 10),
             true,
@@ -851,8 +960,8 @@ mod city_management_flow {
     #[test]
     fn test_building_prerequisites() {
         let mut city = City::new(
-            1,
-            0,
+            CityId(1),
+            PlayerSlot(0),
             "Building Test".to_string(),
             HexCoord::new(10, 10),
             true,
@@ -878,8 +987,8 @@ mod city_management_flow {
     #[test]
     fn test_walls_increase_city_defense() {
         let mut city = City::new(
-            1,
-            0,
+            CityId(1),
+            PlayerSlot(0),
             "Fortified City".to_string(),
             HexCoord::new(10, 10),
             true,
@@ -915,26 +1024,38 @@ mod victory_conditions {
         game.start().unwrap();
 
         // Create capitals for both players
-        let capital1 = City::new(1, 0, "Capital1".to_string(), HexCoord::new(5, 5), true);
-        let capital2 = City::new(2, 1, "Capital2".to_string(), HexCoord::new(30, 20), true);
+        let capital1 = City::new(
+            CityId(1),
+            PlayerSlot(0),
+            "Capital1".to_string(),
+            HexCoord::new(5, 5),
+            true,
+        );
+        let capital2 = City::new(
+            CityId(2),
+            PlayerSlot(1),
+            "Capital2".to_string(),
+            HexCoord::new(30, 20),
+            true,
+        );
 
-        game.cities.insert(1, capital1);
-        game.cities.insert(2, capital2);
-        game.players[0].capital = Some(1);
-        game.players[1].capital = Some(2);
+        game.cities.insert(CityId(1), capital1);
+        game.cities.insert(CityId(2), capital2);
+        game.players[0].capital = Some(CityId(1));
+        game.players[1].capital = Some(CityId(2));
 
         // Initially no winner
         let checker = VictoryChecker::new();
         assert!(checker.check_all(&game).is_none());
 
         // Player 0 captures player 1's capital
-        game.cities.get_mut(&2).unwrap().owner = 0;
+        game.cities.get_mut(&CityId(2)).unwrap().owner = PlayerSlot(0);
 
         // Now player 0 owns all capitals
         let result = checker.check_all(&game);
         assert!(result.is_some());
         let (winner, victory_type) = result.unwrap();
-        assert_eq!(winner, 0);
+        assert_eq!(winner, PlayerSlot(0));
         assert_eq!(victory_type, VictoryType::Domination);
     }
 
@@ -963,7 +1084,7 @@ mod victory_conditions {
         let result = checker.check_all(&game);
         assert!(result.is_some());
         let (winner, victory_type) = result.unwrap();
-        assert_eq!(winner, 0);
+        assert_eq!(winner, PlayerSlot(0));
         assert_eq!(victory_type, VictoryType::Science);
     }
 
@@ -983,7 +1104,7 @@ mod victory_conditions {
 
         let result = VictoryChecker::check_economic(&game, 20_000);
         assert!(result.is_some());
-        assert_eq!(result.unwrap(), 1);
+        assert_eq!(result.unwrap(), PlayerSlot(1));
     }
 
     #[test]
@@ -1007,7 +1128,7 @@ mod victory_conditions {
         let result = checker.check_all(&game);
         assert!(result.is_some());
         let (winner, victory_type) = result.unwrap();
-        assert_eq!(winner, 1); // Player 1 has higher score
+        assert_eq!(winner, PlayerSlot(1)); // Player 1 has higher score
         assert_eq!(victory_type, VictoryType::Score);
     }
 
@@ -1027,7 +1148,7 @@ mod victory_conditions {
         assert_eq!(game.phase, GamePhase::Ended);
         assert!(game.winner.is_some());
         let (winner, victory_type) = game.winner.unwrap();
-        assert_eq!(winner, 0);
+        assert_eq!(winner, PlayerSlot(0));
         assert_eq!(victory_type, VictoryType::Domination);
     }
 
@@ -1038,10 +1159,22 @@ mod victory_conditions {
         game.start().unwrap();
 
         // Create capitals - both owned by player 0
-        let capital1 = City::new(1, 0, "Capital1".to_string(), HexCoord::new(5, 5), true);
-        let capital2 = City::new(2, 0, "Capital2".to_string(), HexCoord::new(30, 20), true);
-        game.cities.insert(1, capital1);
-        game.cities.insert(2, capital2);
+        let capital1 = City::new(
+            CityId(1),
+            PlayerSlot(0),
+            "Capital1".to_string(),
+            HexCoord::new(5, 5),
+            true,
+        );
+        let capital2 = City::new(
+            CityId(2),
+            PlayerSlot(0),
+            "Capital2".to_string(),
+            HexCoord::new(30, 20),
+            true,
+        );
+        game.cities.insert(CityId(1), capital1);
+        game.cities.insert(CityId(2), capital2);
 
         // Player 0 also has complete spaceship
         game.players[0].spaceship = SpaceshipProgress {
@@ -1075,13 +1208,14 @@ mod diplomacy_flow {
         game.start().unwrap();
 
         // Initially at peace
-        assert!(!game.diplomacy.are_at_war(0, 1));
+        assert!(!game.diplomacy.are_at_war(PlayerSlot(0), PlayerSlot(1)));
 
         // Declare war
-        game.diplomacy.declare_war(0, 1, game.turn);
+        game.diplomacy
+            .declare_war(PlayerSlot(0), PlayerSlot(1), game.turn);
 
-        assert!(game.diplomacy.are_at_war(0, 1));
-        let rel = game.diplomacy.get(0, 1).unwrap();
+        assert!(game.diplomacy.are_at_war(PlayerSlot(0), PlayerSlot(1)));
+        let rel = game.diplomacy.get(PlayerSlot(0), PlayerSlot(1)).unwrap();
         assert_eq!(rel.status, DiplomaticStatus::War);
     }
 
@@ -1092,17 +1226,25 @@ mod diplomacy_flow {
         game.start().unwrap();
 
         // Establish friendly relationship and treaty
-        game.diplomacy.modify_relationship_score(0, 1, 60);
+        game.diplomacy
+            .modify_relationship_score(PlayerSlot(0), PlayerSlot(1), 60);
+        assert!(game.diplomacy.propose_treaty(
+            PlayerSlot(0),
+            PlayerSlot(1),
+            TreatyType::OpenBorders,
+            1
+        ));
         assert!(game
             .diplomacy
-            .propose_treaty(0, 1, TreatyType::OpenBorders, 1));
-        assert!(game.diplomacy.has_treaty(0, 1, TreatyType::OpenBorders));
+            .has_treaty(PlayerSlot(0), PlayerSlot(1), TreatyType::OpenBorders));
 
         // Declare war - should break treaty
-        game.diplomacy.declare_war(0, 1, 2);
+        game.diplomacy.declare_war(PlayerSlot(0), PlayerSlot(1), 2);
 
-        assert!(game.diplomacy.are_at_war(0, 1));
-        assert!(!game.diplomacy.has_treaty(0, 1, TreatyType::OpenBorders));
+        assert!(game.diplomacy.are_at_war(PlayerSlot(0), PlayerSlot(1)));
+        assert!(!game
+            .diplomacy
+            .has_treaty(PlayerSlot(0), PlayerSlot(1), TreatyType::OpenBorders));
     }
 
     #[test]
@@ -1112,14 +1254,14 @@ mod diplomacy_flow {
         game.start().unwrap();
 
         // Go to war
-        game.diplomacy.declare_war(0, 1, 1);
-        assert!(game.diplomacy.are_at_war(0, 1));
+        game.diplomacy.declare_war(PlayerSlot(0), PlayerSlot(1), 1);
+        assert!(game.diplomacy.are_at_war(PlayerSlot(0), PlayerSlot(1)));
 
         // Make peace
-        game.diplomacy.make_peace(0, 1, 10);
+        game.diplomacy.make_peace(PlayerSlot(0), PlayerSlot(1), 10);
 
-        assert!(!game.diplomacy.are_at_war(0, 1));
-        let rel = game.diplomacy.get(0, 1).unwrap();
+        assert!(!game.diplomacy.are_at_war(PlayerSlot(0), PlayerSlot(1)));
+        let rel = game.diplomacy.get(PlayerSlot(0), PlayerSlot(1)).unwrap();
         assert_eq!(rel.status, DiplomaticStatus::Neutral);
         assert!(rel.has_treaty(TreatyType::Peace));
     }
@@ -1131,18 +1273,27 @@ mod diplomacy_flow {
         game.start().unwrap();
 
         // Default relationship score is 0, too low for friendly treaties
-        assert!(!game
-            .diplomacy
-            .propose_treaty(0, 1, TreatyType::OpenBorders, 1));
+        assert!(!game.diplomacy.propose_treaty(
+            PlayerSlot(0),
+            PlayerSlot(1),
+            TreatyType::OpenBorders,
+            1
+        ));
 
         // Increase relationship
-        game.diplomacy.modify_relationship_score(0, 1, 50);
+        game.diplomacy
+            .modify_relationship_score(PlayerSlot(0), PlayerSlot(1), 50);
 
         // Now should work
+        assert!(game.diplomacy.propose_treaty(
+            PlayerSlot(0),
+            PlayerSlot(1),
+            TreatyType::OpenBorders,
+            1
+        ));
         assert!(game
             .diplomacy
-            .propose_treaty(0, 1, TreatyType::OpenBorders, 1));
-        assert!(game.diplomacy.has_treaty(0, 1, TreatyType::OpenBorders));
+            .has_treaty(PlayerSlot(0), PlayerSlot(1), TreatyType::OpenBorders));
     }
 
     #[test]
@@ -1151,13 +1302,17 @@ mod diplomacy_flow {
         game.map = create_test_map(40, 25);
         game.start().unwrap();
 
-        game.diplomacy.modify_relationship_score(0, 1, 60);
-        game.diplomacy.declare_war(0, 1, 1);
+        game.diplomacy
+            .modify_relationship_score(PlayerSlot(0), PlayerSlot(1), 60);
+        game.diplomacy.declare_war(PlayerSlot(0), PlayerSlot(1), 1);
 
         // Cannot propose treaties while at war
-        assert!(!game
-            .diplomacy
-            .propose_treaty(0, 1, TreatyType::OpenBorders, 2));
+        assert!(!game.diplomacy.propose_treaty(
+            PlayerSlot(0),
+            PlayerSlot(1),
+            TreatyType::OpenBorders,
+            2
+        ));
     }
 
     #[test]
@@ -1167,16 +1322,17 @@ mod diplomacy_flow {
         game.start().unwrap();
 
         // Without treaty, can't pass
-        assert!(!game.diplomacy.can_units_pass(0, 1));
+        assert!(!game.diplomacy.can_units_pass(PlayerSlot(0), PlayerSlot(1)));
 
         // Establish open borders
-        game.diplomacy.modify_relationship_score(0, 1, 60);
         game.diplomacy
-            .propose_treaty(0, 1, TreatyType::OpenBorders, 1);
+            .modify_relationship_score(PlayerSlot(0), PlayerSlot(1), 60);
+        game.diplomacy
+            .propose_treaty(PlayerSlot(0), PlayerSlot(1), TreatyType::OpenBorders, 1);
 
         // Now can pass
-        assert!(game.diplomacy.can_units_pass(0, 1));
-        assert!(game.diplomacy.can_units_pass(1, 0)); // Bidirectional
+        assert!(game.diplomacy.can_units_pass(PlayerSlot(0), PlayerSlot(1)));
+        assert!(game.diplomacy.can_units_pass(PlayerSlot(1), PlayerSlot(0))); // Bidirectional
     }
 
     #[test]
@@ -1185,18 +1341,32 @@ mod diplomacy_flow {
         game.map = create_test_map(40, 25);
         game.start().unwrap();
 
-        game.diplomacy.declare_war(0, 1, 1);
+        game.diplomacy.declare_war(PlayerSlot(0), PlayerSlot(1), 1);
 
-        let initial_weariness = game.diplomacy.get(0, 1).unwrap().war_weariness;
+        let initial_weariness = game
+            .diplomacy
+            .get(PlayerSlot(0), PlayerSlot(1))
+            .unwrap()
+            .war_weariness;
 
         // Simulate turns at war
         for turn in 2..=10 {
             game.diplomacy.update_turn(turn);
         }
 
-        let final_weariness = game.diplomacy.get(0, 1).unwrap().war_weariness;
+        let final_weariness = game
+            .diplomacy
+            .get(PlayerSlot(0), PlayerSlot(1))
+            .unwrap()
+            .war_weariness;
         assert!(final_weariness > initial_weariness);
-        assert_eq!(game.diplomacy.get(0, 1).unwrap().turns_at_war, 9);
+        assert_eq!(
+            game.diplomacy
+                .get(PlayerSlot(0), PlayerSlot(1))
+                .unwrap()
+                .turns_at_war,
+            9
+        );
     }
 
     #[test]
@@ -1205,11 +1375,13 @@ mod diplomacy_flow {
         game.map = create_test_map(40, 25);
         game.start().unwrap();
 
-        game.diplomacy.declare_war(0, 1, 1);
-        game.diplomacy.make_peace(0, 1, 5);
+        game.diplomacy.declare_war(PlayerSlot(0), PlayerSlot(1), 1);
+        game.diplomacy.make_peace(PlayerSlot(0), PlayerSlot(1), 5);
 
         // Peace treaty has 10 turn duration
-        assert!(game.diplomacy.has_treaty(0, 1, TreatyType::Peace));
+        assert!(game
+            .diplomacy
+            .has_treaty(PlayerSlot(0), PlayerSlot(1), TreatyType::Peace));
 
         // Advance 10 turns
         for turn in 6..=16 {
@@ -1217,7 +1389,9 @@ mod diplomacy_flow {
         }
 
         // Peace treaty should have expired
-        assert!(!game.diplomacy.has_treaty(0, 1, TreatyType::Peace));
+        assert!(!game
+            .diplomacy
+            .has_treaty(PlayerSlot(0), PlayerSlot(1), TreatyType::Peace));
     }
 
     #[test]
@@ -1227,16 +1401,17 @@ mod diplomacy_flow {
         game.start().unwrap();
 
         // Players 0 and 1 build friendship
-        game.diplomacy.modify_relationship_score(0, 1, 60);
         game.diplomacy
-            .propose_treaty(0, 1, TreatyType::OpenBorders, 1);
+            .modify_relationship_score(PlayerSlot(0), PlayerSlot(1), 60);
+        game.diplomacy
+            .propose_treaty(PlayerSlot(0), PlayerSlot(1), TreatyType::OpenBorders, 1);
         game.diplomacy
-            .propose_treaty(0, 1, TreatyType::TradeAgreement, 2);
+            .propose_treaty(PlayerSlot(0), PlayerSlot(1), TreatyType::TradeAgreement, 2);
 
         // Player 2 declares war on player 0
-        game.diplomacy.declare_war(2, 0, 3);
-        assert!(game.diplomacy.are_at_war(0, 2));
-        assert!(!game.diplomacy.are_at_war(1, 2)); // Player 1 not involved
+        game.diplomacy.declare_war(PlayerSlot(2), PlayerSlot(0), 3);
+        assert!(game.diplomacy.are_at_war(PlayerSlot(0), PlayerSlot(2)));
+        assert!(!game.diplomacy.are_at_war(PlayerSlot(1), PlayerSlot(2))); // Player 1 not involved
 
         // War progresses
         for turn in 4..=15 {
@@ -1244,12 +1419,18 @@ mod diplomacy_flow {
         }
 
         // Make peace
-        game.diplomacy.make_peace(0, 2, 15);
-        assert!(!game.diplomacy.are_at_war(0, 2));
+        game.diplomacy.make_peace(PlayerSlot(0), PlayerSlot(2), 15);
+        assert!(!game.diplomacy.are_at_war(PlayerSlot(0), PlayerSlot(2)));
 
         // Verify player 0 and 1 treaties are still intact
-        assert!(game.diplomacy.has_treaty(0, 1, TreatyType::OpenBorders));
-        assert!(game.diplomacy.has_treaty(0, 1, TreatyType::TradeAgreement));
+        assert!(game
+            .diplomacy
+            .has_treaty(PlayerSlot(0), PlayerSlot(1), TreatyType::OpenBorders));
+        assert!(game.diplomacy.has_treaty(
+            PlayerSlot(0),
+            PlayerSlot(1),
+            TreatyType::TradeAgreement
+        ));
     }
 }
 
@@ -1259,6 +1440,7 @@ mod diplomacy_flow {
 
 mod save_load_flow {
     use super::*;
+    use nostr_nations_core::types::Npub;
 
     /// Create an empty map for serialization tests
     /// Note: Maps with tiles cannot be serialized to JSON due to HexCoord keys
@@ -1275,13 +1457,18 @@ mod save_load_flow {
 
         // Add some game state
         let unit_id = game.allocate_unit_id();
-        let warrior = Unit::new(unit_id, 0, UnitType::Warrior, HexCoord::new(5, 5));
+        let warrior = Unit::new(
+            unit_id,
+            PlayerSlot(0),
+            UnitType::Warrior,
+            HexCoord::new(5, 5),
+        );
         game.units.insert(unit_id, warrior);
 
         let city_id = game.allocate_city_id();
         let city = City::new(
             city_id,
-            0,
+            PlayerSlot(0),
             "TestCity".to_string(),
             HexCoord::new(10, 10),
             true,
@@ -1311,17 +1498,18 @@ mod save_load_flow {
         game.start().unwrap();
 
         // Set up complex state
-        game.diplomacy.declare_war(0, 1, 1);
-        game.diplomacy.modify_relationship_score(0, 2, 60);
+        game.diplomacy.declare_war(PlayerSlot(0), PlayerSlot(1), 1);
+        game.diplomacy
+            .modify_relationship_score(PlayerSlot(0), PlayerSlot(2), 60);
         game.diplomacy
-            .propose_treaty(0, 2, TreatyType::OpenBorders, 1);
+            .propose_treaty(PlayerSlot(0), PlayerSlot(2), TreatyType::OpenBorders, 1);
 
         // Add units
         for i in 0..3 {
             let unit_id = game.allocate_unit_id();
             let unit = Unit::new(
                 unit_id,
-                i,
+                PlayerSlot(i),
                 UnitType::Warrior,
                 HexCoord::new(i as i32 * 5, 5),
             );
@@ -1333,7 +1521,7 @@ mod save_load_flow {
             let city_id = game.allocate_city_id();
             let mut city = City::new(
                 city_id,
-                i,
+                PlayerSlot(i),
                 format!("City{}", i),
                 HexCoord::new(i as i32 * 10 + 2, 10),
                 true,
@@ -1366,8 +1554,12 @@ mod save_load_flow {
         }
 
         // Verify diplomacy
-        assert!(restored.diplomacy.are_at_war(0, 1));
-        assert!(restored.diplomacy.has_treaty(0, 2, TreatyType::OpenBorders));
+        assert!(restored.diplomacy.are_at_war(PlayerSlot(0), PlayerSlot(1)));
+        assert!(restored.diplomacy.has_treaty(
+            PlayerSlot(0),
+            PlayerSlot(2),
+            TreatyType::OpenBorders
+        ));
 
         // Verify units
         assert_eq!(restored.units.len(), game.units.len());
@@ -1391,8 +1583,8 @@ mod save_load_flow {
     #[test]
     fn test_player_serialization() {
         let mut player = Player::new(
-            0,
-            "npub_test123".to_string(),
+            PlayerSlot(0),
+            Npub::new("npub_test123"),
             "TestPlayer".to_string(),
             Civilization::rome(),
         );
@@ -1419,7 +1611,12 @@ mod save_load_flow {
     fn test_unit_serialization_with_promotions() {
         use nostr_nations_core::unit::Promotion;
 
-        let mut unit = Unit::new(42, 1, UnitType::Knight, HexCoord::new(15, 20));
+        let mut unit = Unit::new(
+            UnitId(42),
+            PlayerSlot(1),
+            UnitType::Knight,
+            HexCoord::new(15, 20),
+        );
         unit.health = 75;
         unit.experience = 25;
         unit.add_promotion(Promotion::ShockI);
@@ -1442,8 +1639,8 @@ mod save_load_flow {
     #[test]
     fn test_city_serialization_full() {
         let mut city = City::new(
-            5,
-            2,
+            CityId(5),
+            PlayerSlot(2),
             "TestMetropolis".to_string(),
             HexCoord::new(25, 30),
             true,
@@ -1494,7 +1691,7 @@ mod save_load_flow {
         // Test that individual tiles serialize correctly
         let mut tile = Tile::new(HexCoord::new(5, 5), Terrain::Grassland);
         tile.feature = Some(Feature::Hills);
-        tile.owner = Some(0);
+        tile.owner = Some(PlayerSlot(0));
 
         let json = serde_json::to_string(&tile).expect("Serialize tile");
         let restored: Tile = serde_json::from_str(&json).expect("Deserialize tile");
@@ -1502,7 +1699,7 @@ mod save_load_flow {
         assert_eq!(restored.coord, tile.coord);
         assert_eq!(restored.terrain, tile.terrain);
         assert_eq!(restored.feature, Some(Feature::Hills));
-        assert_eq!(restored.owner, Some(0));
+        assert_eq!(restored.owner, Some(PlayerSlot(0)));
     }
 
     #[test]
@@ -1514,10 +1711,15 @@ mod save_load_flow {
         game.start().unwrap();
 
         // Play some turns
-        game.diplomacy.declare_war(0, 1, 1);
+        game.diplomacy.declare_war(PlayerSlot(0), PlayerSlot(1), 1);
 
         let unit_id = game.allocate_unit_id();
-        let mut warrior = Unit::new(unit_id, 0, UnitType::Warrior, HexCoord::new(5, 5));
+        let mut warrior = Unit::new(
+            unit_id,
+            PlayerSlot(0),
+            UnitType::Warrior,
+            HexCoord::new(5, 5),
+        );
         warrior.gain_experience(15);
         game.units.insert(unit_id, warrior);
 
@@ -1536,7 +1738,7 @@ mod save_load_flow {
         // Verify game state matches saved point
         assert_eq!(restored.turn, saved_turn);
         assert_eq!(restored.current_player, saved_current_player);
-        assert!(restored.diplomacy.are_at_war(0, 1));
+        assert!(restored.diplomacy.are_at_war(PlayerSlot(0), PlayerSlot(1)));
 
         // Continue playing - advance multiple times to ensure turn increments
         let pre_turn = restored.turn;
@@ -1552,8 +1754,10 @@ mod save_load_flow {
         );
 
         // Can make peace now
-        restored.diplomacy.make_peace(0, 1, restored.turn);
-        assert!(!restored.diplomacy.are_at_war(0, 1));
+        restored
+            .diplomacy
+            .make_peace(PlayerSlot(0), PlayerSlot(1), restored.turn);
+        assert!(!restored.diplomacy.are_at_war(PlayerSlot(0), PlayerSlot(1)));
     }
 }
 
@@ -1572,12 +1776,22 @@ mod full_game_scenarios {
         game.start().unwrap();
 
         // Turn 1: Player 0 - Create starting units
-        assert!(game.is_player_turn(0));
+        assert!(game.is_player_turn(PlayerSlot(0)));
 
         let settler_id = game.allocate_unit_id();
         let warrior_id = game.allocate_unit_id();
-        let settler = Unit::new(settler_id, 0, UnitType::Settler, HexCoord::new(10, 10));
-        let warrior = Unit::new(warrior_id, 0, UnitType::Warrior, HexCoord::new(10, 11));
+        let settler = Unit::new(
+            settler_id,
+            PlayerSlot(0),
+            UnitType::Settler,
+            HexCoord::new(10, 10),
+        );
+        let warrior = Unit::new(
+            warrior_id,
+            PlayerSlot(0),
+            UnitType::Warrior,
+            HexCoord::new(10, 11),
+        );
         game.units.insert(settler_id, settler);
         game.units.insert(warrior_id, warrior);
 
@@ -1585,7 +1799,7 @@ mod full_game_scenarios {
         let city_id = game.allocate_city_id();
         let mut city = City::new(
             city_id,
-            0,
+            PlayerSlot(0),
             "Capital".to_string(),
             HexCoord::new(10, 10),
             true,
@@ -1599,19 +1813,29 @@ mod full_game_scenarios {
         game.next_turn().unwrap();
 
         // Turn 1: Player 1 - Same setup
-        assert!(game.is_player_turn(1));
+        assert!(game.is_player_turn(PlayerSlot(1)));
 
         let settler2_id = game.allocate_unit_id();
         let warrior2_id = game.allocate_unit_id();
-        let settler2 = Unit::new(settler2_id, 1, UnitType::Settler, HexCoord::new(30, 15));
-        let warrior2 = Unit::new(warrior2_id, 1, UnitType::Warrior, HexCoord::new(30, 16));
+        let settler2 = Unit::new(
+            settler2_id,
+            PlayerSlot(1),
+            UnitType::Settler,
+            HexCoord::new(30, 15),
+        );
+        let warrior2 = Unit::new(
+            warrior2_id,
+            PlayerSlot(1),
+            UnitType::Warrior,
+            HexCoord::new(30, 16),
+        );
         game.units.insert(settler2_id, settler2);
         game.units.insert(warrior2_id, warrior2);
 
         let city2_id = game.allocate_city_id();
         let mut city2 = City::new(
             city2_id,
-            1,
+            PlayerSlot(1),
             "Enemy Capital".to_string(),
             HexCoord::new(30, 15),
             true,
@@ -1625,7 +1849,7 @@ mod full_game_scenarios {
 
         // Turn 2 begins
         assert_eq!(game.turn, 2);
-        assert!(game.is_player_turn(0));
+        assert!(game.is_player_turn(PlayerSlot(0)));
 
         // Simulate several turns of production
         // With 2 players, each full turn requires 2 next_turn calls
@@ -1663,7 +1887,7 @@ mod full_game_scenarios {
         let city1_id = game.allocate_city_id();
         let city1 = City::new(
             city1_id,
-            0,
+            PlayerSlot(0),
             "Player0 Capital".to_string(),
             HexCoord::new(5, 5),
             true,
@@ -1674,7 +1898,7 @@ mod full_game_scenarios {
         let city2_id = game.allocate_city_id();
         let mut city2 = City::new(
             city2_id,
-            1,
+            PlayerSlot(1),
             "Player1 Capital".to_string(),
             HexCoord::new(10, 10),
             true,
@@ -1684,11 +1908,16 @@ mod full_game_scenarios {
         game.players[1].capital = Some(city2_id);
 
         // Declare war
-        game.diplomacy.declare_war(0, 1, 1);
+        game.diplomacy.declare_war(PlayerSlot(0), PlayerSlot(1), 1);
 
         // Create attacking unit adjacent to enemy city
         let attacker_id = game.allocate_unit_id();
-        let attacker = Unit::new(attacker_id, 0, UnitType::Swordsman, HexCoord::new(10, 9));
+        let attacker = Unit::new(
+            attacker_id,
+            PlayerSlot(0),
+            UnitType::Swordsman,
+            HexCoord::new(10, 9),
+        );
         game.units.insert(attacker_id, attacker);
 
         // Attack city until captured
@@ -1699,7 +1928,7 @@ mod full_game_scenarios {
             let city = game.cities.get(&city2_id).unwrap();
             if city.can_be_captured() {
                 // Capture city
-                game.cities.get_mut(&city2_id).unwrap().owner = 0;
+                game.cities.get_mut(&city2_id).unwrap().owner = PlayerSlot(0);
                 break;
             }
 
@@ -1708,13 +1937,13 @@ mod full_game_scenarios {
         }
 
         // Verify conquest
-        assert_eq!(game.cities.get(&city2_id).unwrap().owner, 0);
+        assert_eq!(game.cities.get(&city2_id).unwrap().owner, PlayerSlot(0));
 
         // Check for domination victory
         let checker = VictoryChecker::new();
         let result = checker.check_all(&game);
         assert!(result.is_some());
-        assert_eq!(result.unwrap(), (0, VictoryType::Domination));
+        assert_eq!(result.unwrap(), (PlayerSlot(0), VictoryType::Domination));
     }
 
     #[test]
@@ -1727,7 +1956,7 @@ mod full_game_scenarios {
         let city1_id = game.allocate_city_id();
         let city1 = City::new(
             city1_id,
-            0,
+            PlayerSlot(0),
             "Capital".to_string(),
             HexCoord::new(5, 5),
             true,
@@ -1738,7 +1967,7 @@ mod full_game_scenarios {
         let city2_id = game.allocate_city_id();
         let city2 = City::new(
             city2_id,
-            1,
+            PlayerSlot(1),
             "Enemy Capital".to_string(),
             HexCoord::new(35, 20),
             true,
@@ -1747,11 +1976,16 @@ mod full_game_scenarios {
         game.players[1].capital = Some(city2_id);
 
         // Establish peace and friendly relations
-        game.diplomacy.modify_relationship_score(0, 1, 60);
         game.diplomacy
-            .propose_treaty(0, 1, TreatyType::OpenBorders, 1);
+            .modify_relationship_score(PlayerSlot(0), PlayerSlot(1), 60);
         game.diplomacy
-            .propose_treaty(0, 1, TreatyType::ResearchAgreement, 1);
+            .propose_treaty(PlayerSlot(0), PlayerSlot(1), TreatyType::OpenBorders, 1);
+        game.diplomacy.propose_treaty(
+            PlayerSlot(0),
+            PlayerSlot(1),
+            TreatyType::ResearchAgreement,
+            1,
+        );
 
         // Player 0 builds spaceship over time
         game.players[0].add_spaceship_part("cockpit");
@@ -1771,6 +2005,6 @@ mod full_game_scenarios {
         let checker = VictoryChecker::new();
         let result = checker.check_all(&game);
         assert!(result.is_some());
-        assert_eq!(result.unwrap(), (0, VictoryType::Science));
+        assert_eq!(result.unwrap(), (PlayerSlot(0), VictoryType::Science));
     }
 }
@@ -0,0 +1,191 @@
+//! Benchmarks comparing cached hierarchical pathfinding with `find_path`.
+//!
+//! These run on a 128x80 map with forests, hills, mountain ridges and
+//! lakes. They are marked `#[ignore]` because they are slow in debug builds.
+//!
+//! Run with: `cargo test --release --test pathfinding_bench -- --ignored --nocapture`
+
+use nostr_nations_core::{
+    find_path,
+    hex::HexCoord,
+    map::Map,
+    terrain::{Feature, Terrain},
+    PathCache, PathConfig,
+};
+use std::time::{Duration, Instant};
+
+// =============================================================================
+// Test Helpers
+// =============================================================================
+
+const WIDTH: u32 = 128;
+const HEIGHT: u32 = 80;
+
+/// Build a varied 128x80 map from a fixed pattern.
+fn bench_map() -> Map {
+    let mut map = Map::filled(WIDTH, HEIGHT, Terrain::Grassland);
+    let mut state = 0x2545_f491_u32;
+    let mut next = move || {
+        state ^= state << 13;
+        state ^= state >> 17;
+        state ^= state << 5;
+        state
+    };
+
+    let mut coords: Vec<HexCoord> = map.tiles.keys().copied().collect();
+    coords.sort_by_key(|c| (c.q, c.r));
+    for coord in coords {
+        let tile = map.get_mut(&coord).unwrap();
+        tile.feature = match next() % 10 {
+            0..=2 => Some(Feature::Forest),
+            3 => Some(Feature::Hills),
+            _ => None,
+        };
+        // Mountain ridges every 20 columns with a pass every 16 rows
+        if coord.q % 20 == 10 && coord.r % 16 != 8 {
+            tile.feature = Some(Feature::Mountains);
+        }
+        // A lake in the middle of the map
+        if (coord.q - 64).abs() < 6 && (coord.r - 40).abs() < 6 {
+            tile.feature = None;
+            tile.terrain = Terrain::Coast;
+        }
+    }
+    map
+}
+
+/// Pick query endpoints spread over the map.
+fn queries(count: usize) -> Vec<(HexCoord, HexCoord)> {
+    (0..count as i32)
+        .map(|i| {
+            let start = HexCoord::new((i * 7) % 20, (i * 11) % HEIGHT as i32);
+            let goal = HexCoord::new(
+                WIDTH as i32 - 1 - (i * 5) % 20,
+                (i * 13 + 17) % HEIGHT as i32,
+            );
+            (start, goal)
+        })
+        .collect()
+}
+
+fn report(name: &str, elapsed: Duration, count: usize) {
+    println!(
+        "  {:<24} {:>10.2?} total, {:>10.2?} per path",
+        name,
+        elapsed,
+        elapsed / count.max(1) as u32
+    );
+}
+
+// =============================================================================
+// Benchmarks
+// =============================================================================
+
+/// Compare a cold hierarchical search and a warm cache with plain A*.
+#[test]
+#[ignore]
+fn bench_cached_vs_find_path_128x80() {
+    let map = bench_map();
+    let config = PathConfig::default();
+    let queries = queries(50);
+    println!(
+        "\n>>> Pathfinding benchmark ({}x{}, {} paths)",
+        WIDTH,
+        HEIGHT,
+        queries.len()
+    );
+
+    let start = Instant::now();
+    let direct: Vec<_> = queries
+        .iter()
+        .map(|(from, to)| find_path(&map, *from, *to, &config))
+        .collect();
+    let direct_time = start.elapsed();
+    report("find_path", direct_time, queries.len());
+
+    let mut cache = PathCache::default();
+    let start = Instant::now();
+    let cold: Vec<_> = queries
+        .iter()
+        .map(|(from, to)| cache.find_path(&map, *from, *to, &config))
+        .collect();
+    let cold_time = start.elapsed();
+    report("PathCache (cold)", cold_time, queries.len());
+
+    let start = Instant::now();
+    for (from, to) in &queries {
+        cache.find_path(&map, *from, *to, &config);
+    }
+    let warm_time = start.elapsed();
+    report("PathCache (warm)", warm_time, queries.len());
+
+    // Both must agree on reachability; corridor paths may cost a bit more
+    let mut extra = 0u64;
+    let mut optimal = 0u64;
+    for (a, b) in direct.iter().zip(&cold) {
+        assert_eq!(a.is_some(), b.is_some());
+        if let (Some(a), Some(b)) = (a, b) {
+            assert!(b.total_cost >= a.total_cost);
+            extra += (b.total_cost - a.total_cost) as u64;
+            optimal += a.total_cost as u64;
+        }
+    }
+    println!(
+        "  Path cost overhead: {:.2}%",
+        extra as f64 * 100.0 / optimal.max(1) as f64
+    );
+    println!("  Cache stats: {:?}", cache.stats());
+
+    assert!(warm_time < direct_time, "Warm cache should beat find_path");
+}
+
+/// Measure how quickly unreachable goals are rejected.
+#[test]
+#[ignore]
+fn bench_unreachable_goal_128x80() {
+    let mut map = bench_map();
+    // Seal off the right edge of the map with water
+    for r in 0..HEIGHT as i32 {
+        map.get_mut(&HexCoord::new(120, r)).unwrap().terrain = Terrain::Ocean;
+    }
+    let config = PathConfig::default();
+    let (from, to) = (HexCoord::new(0, 0), HexCoord::new(125, 40));
+    println!("\n>>> Unreachable goal benchmark");
+
+    let start = Instant::now();
+    assert!(find_path(&map, from, to, &config).is_none());
+    report("find_path", start.elapsed(), 1);
+
+    let mut cache = PathCache::default();
+    cache.find_path(&map, HexCoord::new(1, 1), HexCoord::new(2, 2), &config);
+    let start = Instant::now();
+    assert!(cache.find_path(&map, from, to, &config).is_none());
+    report("PathCache", start.elapsed(), 1);
+}
+
+/// Measure the cost of re-querying after a road is built mid-route.
+#[test]
+#[ignore]
+fn bench_invalidation_128x80() {
+    let mut map = bench_map();
+    let config = PathConfig::default();
+    let queries = queries(50);
+    let mut cache = PathCache::default();
+    for (from, to) in &queries {
+        cache.find_path(&map, *from, *to, &config);
+    }
+    println!("\n>>> Invalidation benchmark");
+
+    let changed = HexCoord::new(64, 10);
+    map.get_mut(&changed).unwrap().road = Some(nostr_nations_core::Road::Road);
+    let start = Instant::now();
+    cache.invalidate(&map, changed);
+    report("invalidate", start.elapsed(), 1);
+
+    let start = Instant::now();
+    for (from, to) in &queries {
+        cache.find_path(&map, *from, *to, &config);
+    }
+    report("re-query", start.elapsed(), queries.len());
+    println!("  Cache stats: {:?}", cache.stats());
+}
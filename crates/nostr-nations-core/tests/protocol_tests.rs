@@ -17,7 +17,7 @@ use nostr_nations_core::{
     replay::{GameEngine, ReplayConfig, ReplayError},
     settings::GameSettings,
     terrain::Improvement,
-    types::{MapSize, PlayerId},
+    types::{CityId, GameId, MapSize, PlayerSlot, UnitId},
     unit::UnitType,
 };
 
@@ -29,7 +29,7 @@ use nostr_nations_core::{
 fn create_event(
     id: &str,
     game_id: &str,
-    player_id: PlayerId,
+    player_id: PlayerSlot,
     prev_event_id: Option<&str>,
     turn: u32,
     sequence: u32,
@@ -37,7 +37,7 @@ fn create_event(
     timestamp: u64,
 ) -> GameEvent {
     let mut event = GameEvent::new(
-        game_id.to_string(),
+        GameId::new(game_id),
         player_id,
         prev_event_id.map(|s| s.to_string()),
         turn,
@@ -53,7 +53,7 @@ fn create_event(
 fn create_event_with_proof(
     id: &str,
     game_id: &str,
-    player_id: PlayerId,
+    player_id: PlayerSlot,
     prev_event_id: Option<&str>,
     turn: u32,
     sequence: u32,
@@ -62,7 +62,7 @@ fn create_event_with_proof(
     proof: RandomnessProof,
 ) -> GameEvent {
     let mut event = GameEvent::with_randomness(
-        game_id.to_string(),
+        GameId::new(game_id),
         player_id,
         prev_event_id.map(|s| s.to_string()),
         turn,
@@ -111,7 +111,7 @@ mod game_event_serialization {
         let event = create_event(
             "evt_001",
             "game_123",
-            0,
+            PlayerSlot(0),
             None,
             1,
             1,
@@ -133,7 +133,7 @@ mod game_event_serialization {
         let original = create_event(
             "evt_002",
             "game_456",
-            1,
+            PlayerSlot(1),
             Some("evt_001"),
             5,
             3,
@@ -208,7 +208,10 @@ mod game_event_serialization {
             HexCoord::new(6, 6),
         ];
 
-        let action = GameAction::MoveUnit { unit_id: 42, path };
+        let action = GameAction::MoveUnit {
+            unit_id: UnitId(42),
+            path,
+        };
 
         let json = serde_json::to_string(&action).unwrap();
         let restored: GameAction = serde_json::from_str(&json).unwrap();
@@ -218,7 +221,7 @@ mod game_event_serialization {
                 unit_id,
                 path: restored_path,
             } => {
-                assert_eq!(unit_id, 42);
+                assert_eq!(unit_id, UnitId(42));
                 assert_eq!(restored_path.len(), 3);
                 assert_eq!(restored_path[0], HexCoord::new(5, 5));
             }
@@ -229,8 +232,8 @@ mod game_event_serialization {
     #[test]
     fn test_attack_unit_action_serialization() {
         let action = GameAction::AttackUnit {
-            attacker_id: 1,
-            defender_id: 2,
+            attacker_id: UnitId(1),
+            defender_id: UnitId(2),
             random: 0.5,
         };
 
@@ -243,8 +246,8 @@ mod game_event_serialization {
                 defender_id,
                 random,
             } => {
-                assert_eq!(attacker_id, 1);
-                assert_eq!(defender_id, 2);
+                assert_eq!(attacker_id, UnitId(1));
+                assert_eq!(defender_id, UnitId(2));
                 assert!((random - 0.5).abs() < 0.001);
             }
             _ => panic!("Expected AttackUnit action"),
@@ -257,13 +260,13 @@ mod game_event_serialization {
         let event = create_event_with_proof(
             "evt_rng",
             "game_rng",
-            0,
+            PlayerSlot(0),
             None,
             1,
             1,
             GameAction::AttackUnit {
-                attacker_id: 1,
-                defender_id: 2,
+                attacker_id: UnitId(1),
+                defender_id: UnitId(2),
                 random: 0.75,
             },
             1700000000,
@@ -291,69 +294,77 @@ mod game_event_serialization {
             GameAction::StartGame,
             GameAction::EndTurn,
             GameAction::EndGame {
-                winner_id: 0,
+                winner_id: PlayerSlot(0),
                 victory_type: "domination".to_string(),
             },
             GameAction::MoveUnit {
-                unit_id: 1,
+                unit_id: UnitId(1),
                 path: vec![HexCoord::new(0, 0)],
             },
             GameAction::AttackUnit {
-                attacker_id: 1,
-                defender_id: 2,
+                attacker_id: UnitId(1),
+                defender_id: UnitId(2),
                 random: 0.5,
             },
             GameAction::AttackCity {
-                attacker_id: 1,
-                city_id: 1,
+                attacker_id: UnitId(1),
+                city_id: CityId(1),
                 random: 0.5,
             },
             GameAction::FoundCity {
-                settler_id: 1,
+                settler_id: UnitId(1),
                 name: "Rome".to_string(),
             },
-            GameAction::FortifyUnit { unit_id: 1 },
-            GameAction::SleepUnit { unit_id: 1 },
-            GameAction::WakeUnit { unit_id: 1 },
-            GameAction::DeleteUnit { unit_id: 1 },
+            GameAction::FortifyUnit { unit_id: UnitId(1) },
+            GameAction::SleepUnit { unit_id: UnitId(1) },
+            GameAction::WakeUnit { unit_id: UnitId(1) },
+            GameAction::DeleteUnit { unit_id: UnitId(1) },
             GameAction::UpgradeUnit {
-                unit_id: 1,
+                unit_id: UnitId(1),
                 gold_cost: 100,
             },
             GameAction::BuildImprovement {
-                unit_id: 1,
+                unit_id: UnitId(1),
                 improvement: Improvement::Farm,
             },
-            GameAction::BuildRoad { unit_id: 1 },
-            GameAction::RemoveFeature { unit_id: 1 },
+            GameAction::BuildRoad { unit_id: UnitId(1) },
+            GameAction::RemoveFeature { unit_id: UnitId(1) },
             GameAction::SetProduction {
-                city_id: 1,
+                city_id: CityId(1),
                 item: ProductionItem::Unit(UnitType::Warrior),
             },
             GameAction::BuyItem {
-                city_id: 1,
+                city_id: CityId(1),
                 item: ProductionItem::Unit(UnitType::Warrior),
                 gold_cost: 200,
             },
             GameAction::AssignCitizen {
-                city_id: 1,
+                city_id: CityId(1),
                 tile: HexCoord::new(5, 5),
             },
             GameAction::UnassignCitizen {
-                city_id: 1,
+                city_id: CityId(1),
                 tile: HexCoord::new(5, 5),
             },
             GameAction::SellBuilding {
-                city_id: 1,
+                city_id: CityId(1),
                 building: "granary".to_string(),
             },
             GameAction::SetResearch {
                 tech_id: "writing".to_string(),
             },
-            GameAction::DeclareWar { target_player: 1 },
-            GameAction::ProposePeace { target_player: 1 },
-            GameAction::AcceptPeace { from_player: 1 },
-            GameAction::RejectPeace { from_player: 1 },
+            GameAction::DeclareWar {
+                target_player: PlayerSlot(1),
+            },
+            GameAction::ProposePeace {
+                target_player: PlayerSlot(1),
+            },
+            GameAction::AcceptPeace {
+                from_player: PlayerSlot(1),
+            },
+            GameAction::RejectPeace {
+                from_player: PlayerSlot(1),
+            },
             GameAction::RequestRandom {
                 purpose: "combat".to_string(),
                 blinded_message: "msg".to_string(),
@@ -396,13 +407,22 @@ mod event_chain_validation {
     fn test_events_link_to_previous_correctly() {
         let mut chain = EventChain::new();
 
-        let event1 = create_event("evt1", "game1", 0, None, 1, 1, GameAction::EndTurn, 1000);
+        let event1 = create_event(
+            "evt1",
+            "game1",
+            PlayerSlot(0),
+            None,
+            1,
+            1,
+            GameAction::EndTurn,
+            1000,
+        );
         assert!(chain.add(event1).is_ok());
 
         let event2 = create_event(
             "evt2",
             "game1",
-            0,
+            PlayerSlot(0),
             Some("evt1"),
             1,
             2,
@@ -414,7 +434,7 @@ mod event_chain_validation {
         let event3 = create_event(
             "evt3",
             "game1",
-            0,
+            PlayerSlot(0),
             Some("evt2"),
             1,
             3,
@@ -436,7 +456,7 @@ mod event_chain_validation {
             .add(create_event(
                 "a",
                 "g",
-                0,
+                PlayerSlot(0),
                 None,
                 1,
                 1,
@@ -448,7 +468,7 @@ mod event_chain_validation {
             .add(create_event(
                 "b",
                 "g",
-                0,
+                PlayerSlot(0),
                 Some("a"),
                 1,
                 2,
@@ -460,7 +480,7 @@ mod event_chain_validation {
             .add(create_event(
                 "c",
                 "g",
-                0,
+                PlayerSlot(0),
                 Some("b"),
                 1,
                 3,
@@ -488,7 +508,7 @@ mod event_chain_validation {
             .add(create_event(
                 "evt1",
                 "game1",
-                0,
+                PlayerSlot(0),
                 None,
                 1,
                 1,
@@ -501,7 +521,7 @@ mod event_chain_validation {
         let result = chain.add(create_event(
             "evt2",
             "game1",
-            0,
+            PlayerSlot(0),
             Some("wrong_id"),
             1,
             2,
@@ -520,7 +540,7 @@ mod event_chain_validation {
             .add(create_event(
                 "evt1",
                 "game1",
-                0,
+                PlayerSlot(0),
                 None,
                 1,
                 1,
@@ -533,7 +553,7 @@ mod event_chain_validation {
         let result = chain.add(create_event(
             "evt2",
             "game1",
-            0,
+            PlayerSlot(0),
             None,
             1,
             2,
@@ -555,7 +575,7 @@ mod event_chain_validation {
             .add(create_event(
                 "first",
                 "g",
-                0,
+                PlayerSlot(0),
                 None,
                 1,
                 1,
@@ -569,7 +589,7 @@ mod event_chain_validation {
             .add(create_event(
                 "second",
                 "g",
-                0,
+                PlayerSlot(0),
                 Some("first"),
                 1,
                 2,
@@ -588,7 +608,7 @@ mod event_chain_validation {
             .add(create_event(
                 "t1_1",
                 "g",
-                0,
+                PlayerSlot(0),
                 None,
                 1,
                 1,
@@ -600,7 +620,7 @@ mod event_chain_validation {
             .add(create_event(
                 "t1_2",
                 "g",
-                0,
+                PlayerSlot(0),
                 Some("t1_1"),
                 1,
                 2,
@@ -612,7 +632,7 @@ mod event_chain_validation {
             .add(create_event(
                 "t2_1",
                 "g",
-                0,
+                PlayerSlot(0),
                 Some("t1_2"),
                 2,
                 1,
@@ -624,7 +644,7 @@ mod event_chain_validation {
             .add(create_event(
                 "t2_2",
                 "g",
-                0,
+                PlayerSlot(0),
                 Some("t2_1"),
                 2,
                 2,
@@ -655,9 +675,36 @@ mod event_ordering {
     fn test_events_sorted_by_timestamp() {
         // Create events with different timestamps
         let events = vec![
-            create_event("evt3", "g", 0, None, 1, 1, GameAction::EndTurn, 3000),
-            create_event("evt1", "g", 0, None, 1, 1, GameAction::EndTurn, 1000),
-            create_event("evt2", "g", 0, None, 1, 1, GameAction::EndTurn, 2000),
+            create_event(
+                "evt3",
+                "g",
+                PlayerSlot(0),
+                None,
+                1,
+                1,
+                GameAction::EndTurn,
+                3000,
+            ),
+            create_event(
+                "evt1",
+                "g",
+                PlayerSlot(0),
+                None,
+                1,
+                1,
+                GameAction::EndTurn,
+                1000,
+            ),
+            create_event(
+                "evt2",
+                "g",
+                PlayerSlot(0),
+                None,
+                1,
+                1,
+                GameAction::EndTurn,
+                2000,
+            ),
         ];
 
         // Sort by timestamp
@@ -672,9 +719,36 @@ mod event_ordering {
     #[test]
     fn test_same_timestamp_ordered_by_id() {
         let events = vec![
-            create_event("evt_c", "g", 0, None, 1, 1, GameAction::EndTurn, 1000),
-            create_event("evt_a", "g", 0, None, 1, 1, GameAction::EndTurn, 1000),
-            create_event("evt_b", "g", 0, None, 1, 1, GameAction::EndTurn, 1000),
+            create_event(
+                "evt_c",
+                "g",
+                PlayerSlot(0),
+                None,
+                1,
+                1,
+                GameAction::EndTurn,
+                1000,
+            ),
+            create_event(
+                "evt_a",
+                "g",
+                PlayerSlot(0),
+                None,
+                1,
+                1,
+                GameAction::EndTurn,
+                1000,
+            ),
+            create_event(
+                "evt_b",
+                "g",
+                PlayerSlot(0),
+                None,
+                1,
+                1,
+                GameAction::EndTurn,
+                1000,
+            ),
         ];
 
         // Sort by timestamp first, then by ID
@@ -695,12 +769,12 @@ mod event_ordering {
             .add(create_event(
                 "t1_s1",
                 "g",
-                0,
+                PlayerSlot(0),
                 None,
                 1,
                 1,
                 GameAction::MoveUnit {
-                    unit_id: 1,
+                    unit_id: UnitId(1),
                     path: vec![HexCoord::new(0, 0)],
                 },
                 100,
@@ -711,12 +785,12 @@ mod event_ordering {
             .add(create_event(
                 "t1_s2",
                 "g",
-                0,
+                PlayerSlot(0),
                 Some("t1_s1"),
                 1,
                 2,
                 GameAction::MoveUnit {
-                    unit_id: 2,
+                    unit_id: UnitId(2),
                     path: vec![HexCoord::new(1, 1)],
                 },
                 101,
@@ -727,7 +801,7 @@ mod event_ordering {
             .add(create_event(
                 "t1_s3",
                 "g",
-                0,
+                PlayerSlot(0),
                 Some("t1_s2"),
                 1,
                 3,
@@ -751,7 +825,7 @@ mod event_ordering {
             .add(create_event(
                 "evt1",
                 "g",
-                0,
+                PlayerSlot(0),
                 None,
                 1,
                 1,
@@ -764,7 +838,7 @@ mod event_ordering {
         let result = chain.add(create_event(
             "evt2",
             "g",
-            0,
+            PlayerSlot(0),
             Some("evt1"),
             1,
             1, // Same sequence - invalid
@@ -783,7 +857,7 @@ mod event_ordering {
             .add(create_event(
                 "evt1",
                 "g",
-                0,
+                PlayerSlot(0),
                 None,
                 5,
                 1,
@@ -796,7 +870,7 @@ mod event_ordering {
         let result = chain.add(create_event(
             "evt2",
             "g",
-            0,
+            PlayerSlot(0),
             Some("evt1"),
             4, // Earlier turn - invalid
             1,
@@ -815,7 +889,7 @@ mod event_ordering {
             .add(create_event(
                 "t1_s5",
                 "g",
-                0,
+                PlayerSlot(0),
                 None,
                 1,
                 5,
@@ -828,7 +902,7 @@ mod event_ordering {
         let result = chain.add(create_event(
             "t2_s1",
             "g",
-            0,
+            PlayerSlot(0),
             Some("t1_s5"),
             2, // New turn
             1, // Sequence resets
@@ -847,6 +921,7 @@ mod event_ordering {
 mod event_types {
     use super::*;
     use nostr_nations_core::city::BuildingType;
+    use nostr_nations_core::types::GameId;
 
     #[test]
     fn test_create_game_with_settings() {
@@ -903,7 +978,7 @@ mod event_types {
         ];
 
         let action = GameAction::MoveUnit {
-            unit_id: 42,
+            unit_id: UnitId(42),
             path: path.clone(),
         };
 
@@ -912,7 +987,7 @@ mod event_types {
             path: moved_path,
         } = action
         {
-            assert_eq!(unit_id, 42);
+            assert_eq!(unit_id, UnitId(42));
             assert_eq!(moved_path, path);
         }
     }
@@ -920,8 +995,8 @@ mod event_types {
     #[test]
     fn test_attack_unit_action() {
         let action = GameAction::AttackUnit {
-            attacker_id: 10,
-            defender_id: 20,
+            attacker_id: UnitId(10),
+            defender_id: UnitId(20),
             random: 0.7,
         };
 
@@ -933,8 +1008,8 @@ mod event_types {
             random,
         } = action
         {
-            assert_eq!(attacker_id, 10);
-            assert_eq!(defender_id, 20);
+            assert_eq!(attacker_id, UnitId(10));
+            assert_eq!(defender_id, UnitId(20));
             assert!((random - 0.7).abs() < 0.001);
         }
     }
@@ -942,8 +1017,8 @@ mod event_types {
     #[test]
     fn test_attack_city_action() {
         let action = GameAction::AttackCity {
-            attacker_id: 5,
-            city_id: 1,
+            attacker_id: UnitId(5),
+            city_id: CityId(1),
             random: 0.3,
         };
 
@@ -955,8 +1030,8 @@ mod event_types {
             random,
         } = action
         {
-            assert_eq!(attacker_id, 5);
-            assert_eq!(city_id, 1);
+            assert_eq!(attacker_id, UnitId(5));
+            assert_eq!(city_id, CityId(1));
             assert!((random - 0.3).abs() < 0.001);
         }
     }
@@ -964,12 +1039,12 @@ mod event_types {
     #[test]
     fn test_found_city_action() {
         let action = GameAction::FoundCity {
-            settler_id: 1,
+            settler_id: UnitId(1),
             name: "Constantinople".to_string(),
         };
 
         if let GameAction::FoundCity { settler_id, name } = action {
-            assert_eq!(settler_id, 1);
+            assert_eq!(settler_id, UnitId(1));
             assert_eq!(name, "Constantinople");
         }
     }
@@ -977,12 +1052,12 @@ mod event_types {
     #[test]
     fn test_set_production_action() {
         let action = GameAction::SetProduction {
-            city_id: 1,
+            city_id: CityId(1),
             item: ProductionItem::Unit(UnitType::Archer),
         };
 
         if let GameAction::SetProduction { city_id, item } = action {
-            assert_eq!(city_id, 1);
+            assert_eq!(city_id, CityId(1));
             assert_eq!(item, ProductionItem::Unit(UnitType::Archer));
         }
     }
@@ -990,12 +1065,12 @@ mod event_types {
     #[test]
     fn test_set_production_building() {
         let action = GameAction::SetProduction {
-            city_id: 2,
+            city_id: CityId(2),
             item: ProductionItem::Building(BuildingType::Library),
         };
 
         if let GameAction::SetProduction { city_id, item } = action {
-            assert_eq!(city_id, 2);
+            assert_eq!(city_id, CityId(2));
             assert_eq!(item, ProductionItem::Building(BuildingType::Library));
         }
     }
@@ -1020,35 +1095,41 @@ mod event_types {
 
     #[test]
     fn test_declare_war_action() {
-        let action = GameAction::DeclareWar { target_player: 2 };
+        let action = GameAction::DeclareWar {
+            target_player: PlayerSlot(2),
+        };
 
         if let GameAction::DeclareWar { target_player } = action {
-            assert_eq!(target_player, 2);
+            assert_eq!(target_player, PlayerSlot(2));
         }
     }
 
     #[test]
     fn test_propose_peace_action() {
-        let action = GameAction::ProposePeace { target_player: 1 };
+        let action = GameAction::ProposePeace {
+            target_player: PlayerSlot(1),
+        };
 
         if let GameAction::ProposePeace { target_player } = action {
-            assert_eq!(target_player, 1);
+            assert_eq!(target_player, PlayerSlot(1));
         }
     }
 
     #[test]
     fn test_accept_peace_action() {
-        let action = GameAction::AcceptPeace { from_player: 3 };
+        let action = GameAction::AcceptPeace {
+            from_player: PlayerSlot(3),
+        };
 
         if let GameAction::AcceptPeace { from_player } = action {
-            assert_eq!(from_player, 3);
+            assert_eq!(from_player, PlayerSlot(3));
         }
     }
 
     #[test]
     fn test_end_game_with_victory_type() {
         let action = GameAction::EndGame {
-            winner_id: 0,
+            winner_id: PlayerSlot(0),
             victory_type: "science".to_string(),
         };
 
@@ -1057,7 +1138,7 @@ mod event_types {
             victory_type,
         } = action
         {
-            assert_eq!(winner_id, 0);
+            assert_eq!(winner_id, PlayerSlot(0));
             assert_eq!(victory_type, "science");
         }
     }
@@ -1067,8 +1148,8 @@ mod event_types {
         use nostr_nations_core::events::kinds;
 
         let create_event = GameEvent::new(
-            "g".to_string(),
-            0,
+            GameId::new("g"),
+            PlayerSlot(0),
             None,
             0,
             1,
@@ -1080,8 +1161,8 @@ mod event_types {
         assert_eq!(create_event.kind(), kinds::GAME_CREATE);
 
         let join_event = GameEvent::new(
-            "g".to_string(),
-            0,
+            GameId::new("g"),
+            PlayerSlot(0),
             None,
             0,
             1,
@@ -1092,33 +1173,47 @@ mod event_types {
         );
         assert_eq!(join_event.kind(), kinds::PLAYER_JOIN);
 
-        let start_event = GameEvent::new("g".to_string(), 0, None, 0, 1, GameAction::StartGame);
+        let start_event = GameEvent::new(
+            GameId::new("g"),
+            PlayerSlot(0),
+            None,
+            0,
+            1,
+            GameAction::StartGame,
+        );
         assert_eq!(start_event.kind(), kinds::GAME_START);
 
         let action_event = GameEvent::new(
-            "g".to_string(),
-            0,
+            GameId::new("g"),
+            PlayerSlot(0),
             None,
             1,
             1,
             GameAction::MoveUnit {
-                unit_id: 1,
+                unit_id: UnitId(1),
                 path: vec![],
             },
         );
         assert_eq!(action_event.kind(), kinds::GAME_ACTION);
 
-        let end_turn_event = GameEvent::new("g".to_string(), 0, None, 1, 1, GameAction::EndTurn);
+        let end_turn_event = GameEvent::new(
+            GameId::new("g"),
+            PlayerSlot(0),
+            None,
+            1,
+            1,
+            GameAction::EndTurn,
+        );
         assert_eq!(end_turn_event.kind(), kinds::TURN_END);
 
         let end_game_event = GameEvent::new(
-            "g".to_string(),
-            0,
+            GameId::new("g"),
+            PlayerSlot(0),
             None,
             1,
             1,
             GameAction::EndGame {
-                winner_id: 0,
+                winner_id: PlayerSlot(0),
                 victory_type: "test".to_string(),
             },
         );
@@ -1132,6 +1227,7 @@ mod event_types {
 
 mod event_validation {
     use super::*;
+    use nostr_nations_core::types::GameId;
 
     fn setup_game_engine() -> GameEngine {
         let mut settings = GameSettings::new("Validation Test".to_string());
@@ -1146,7 +1242,7 @@ mod event_validation {
         // Add two valid players
         engine
             .apply_action(
-                0,
+                PlayerSlot(0),
                 &GameAction::JoinGame {
                     player_name: "P1".to_string(),
                     civilization_id: "rome".to_string(),
@@ -1156,7 +1252,7 @@ mod event_validation {
 
         engine
             .apply_action(
-                1,
+                PlayerSlot(1),
                 &GameAction::JoinGame {
                     player_name: "P2".to_string(),
                     civilization_id: "egypt".to_string(),
@@ -1164,13 +1260,15 @@ mod event_validation {
             )
             .unwrap();
 
-        engine.apply_action(0, &GameAction::StartGame).unwrap();
+        engine
+            .apply_action(PlayerSlot(0), &GameAction::StartGame)
+            .unwrap();
 
         // Player 99 doesn't exist - attacking with non-existent unit
         let result = engine.apply_action(
-            0,
+            PlayerSlot(0),
             &GameAction::MoveUnit {
-                unit_id: 999, // Non-existent unit
+                unit_id: UnitId(999), // Non-existent unit
                 path: vec![HexCoord::new(0, 0)],
             },
         );
@@ -1186,7 +1284,7 @@ mod event_validation {
         // Add players and start
         engine
             .apply_action(
-                0,
+                PlayerSlot(0),
                 &GameAction::JoinGame {
                     player_name: "P1".to_string(),
                     civilization_id: "rome".to_string(),
@@ -1195,23 +1293,25 @@ mod event_validation {
             .unwrap();
         engine
             .apply_action(
-                1,
+                PlayerSlot(1),
                 &GameAction::JoinGame {
                     player_name: "P2".to_string(),
                     civilization_id: "egypt".to_string(),
                 },
             )
             .unwrap();
-        engine.apply_action(0, &GameAction::StartGame).unwrap();
+        engine
+            .apply_action(PlayerSlot(0), &GameAction::StartGame)
+            .unwrap();
 
         // It's player 0's turn, not player 1's
-        assert_eq!(engine.state.current_player, 0);
+        assert_eq!(engine.state.current_player, PlayerSlot(0));
 
         // Create an event from player 1 (wrong turn)
         let event = create_event(
             "bad_evt",
-            &engine.state.id,
-            1, // Player 1 trying to act
+            engine.state.id.as_str(),
+            PlayerSlot(1), // Player 1 trying to act
             None,
             1,
             1,
@@ -1230,7 +1330,7 @@ mod event_validation {
         let mut engine = setup_game_engine();
 
         // Try to start game without any players
-        let result = engine.apply_action(0, &GameAction::StartGame);
+        let result = engine.apply_action(PlayerSlot(0), &GameAction::StartGame);
         assert!(result.is_err());
     }
 
@@ -1242,13 +1342,13 @@ mod event_validation {
         let attack_event = create_event(
             "attack",
             "game",
-            0,
+            PlayerSlot(0),
             None,
             1,
             1,
             GameAction::AttackUnit {
-                attacker_id: 1,
-                defender_id: 2,
+                attacker_id: UnitId(1),
+                defender_id: UnitId(2),
                 random: 0.5,
             },
             1000,
@@ -1267,13 +1367,13 @@ mod event_validation {
         let attack_event = create_event_with_proof(
             "attack",
             "game",
-            0,
+            PlayerSlot(0),
             None,
             1,
             1,
             GameAction::AttackUnit {
-                attacker_id: 1,
-                defender_id: 2,
+                attacker_id: UnitId(1),
+                defender_id: UnitId(2),
                 random: 0.5,
             },
             1000,
@@ -1299,12 +1399,12 @@ mod event_validation {
         assert!(!GameAction::EndTurn.requires_random());
         assert!(!GameAction::StartGame.requires_random());
         assert!(!GameAction::MoveUnit {
-            unit_id: 1,
+            unit_id: UnitId(1),
             path: vec![]
         }
         .requires_random());
         assert!(!GameAction::FoundCity {
-            settler_id: 1,
+            settler_id: UnitId(1),
             name: "Test".to_string()
         }
         .requires_random());
@@ -1316,7 +1416,7 @@ mod event_validation {
 
     #[test]
     fn test_event_builder_sequences_correctly() {
-        let mut builder = EventBuilder::new("game1".to_string(), 0);
+        let mut builder = EventBuilder::new(GameId::new("game1"), PlayerSlot(0));
         builder.set_turn(1);
 
         let event1 = builder.build(GameAction::EndTurn);
@@ -1340,6 +1440,7 @@ mod event_validation {
 
 mod replay_system {
     use super::*;
+    use nostr_nations_core::types::GameId;
 
     fn create_game_events() -> Vec<GameEvent> {
         let settings = GameSettings::new("Replay Test".to_string());
@@ -1361,7 +1462,7 @@ mod replay_system {
             create_event_with_proof(
                 "evt_create",
                 "game_replay",
-                0,
+                PlayerSlot(0),
                 None,
                 0,
                 1,
@@ -1375,7 +1476,7 @@ mod replay_system {
             create_event(
                 "evt_join1",
                 "game_replay",
-                0,
+                PlayerSlot(0),
                 Some("evt_create"),
                 0,
                 2,
@@ -1388,7 +1489,7 @@ mod replay_system {
             create_event(
                 "evt_join2",
                 "game_replay",
-                1,
+                PlayerSlot(1),
                 Some("evt_join1"),
                 0,
                 3,
@@ -1401,7 +1502,7 @@ mod replay_system {
             create_event(
                 "evt_start",
                 "game_replay",
-                0,
+                PlayerSlot(0),
                 Some("evt_join2"),
                 0,
                 4,
@@ -1477,7 +1578,7 @@ mod replay_system {
         let events = vec![create_event(
             "evt_join",
             "game",
-            0,
+            PlayerSlot(0),
             None,
             0,
             1,
@@ -1497,6 +1598,7 @@ mod replay_system {
         let config = ReplayConfig {
             strict_randomness_validation: true,
             allow_deterministic_randomness: false,
+            ..ReplayConfig::default()
         };
 
         let settings = GameSettings::new("Strict Test".to_string());
@@ -1514,17 +1616,17 @@ mod replay_system {
         let mut rng2 = DeterministicRandomness::new(seed);
 
         let context1 = RandomnessContext::Combat {
-            game_id: "test".to_string(),
+            game_id: GameId::new("test"),
             turn: 1,
-            attacker_id: 1,
-            defender_id: 2,
+            attacker_id: UnitId(1),
+            defender_id: UnitId(2),
         };
 
         let context2 = RandomnessContext::Combat {
-            game_id: "test".to_string(),
+            game_id: GameId::new("test"),
             turn: 1,
-            attacker_id: 1,
-            defender_id: 2,
+            attacker_id: UnitId(1),
+            defender_id: UnitId(2),
         };
 
         let proof1 = rng1.request_randomness(context1).unwrap();
@@ -1540,12 +1642,12 @@ mod replay_system {
         let event = create_event(
             "evt",
             "game",
-            0,
+            PlayerSlot(0),
             None,
             1,
             1,
             GameAction::MoveUnit {
-                unit_id: 42,
+                unit_id: UnitId(42),
                 path: vec![HexCoord::new(5, 5), HexCoord::new(5, 6)],
             },
             1000,
@@ -1563,7 +1665,7 @@ mod replay_system {
         let event = create_event(
             "evt_test",
             "game_abc",
-            1,
+            PlayerSlot(1),
             Some("prev_evt"),
             5,
             3,
@@ -1591,6 +1693,7 @@ mod replay_system {
         let config = ReplayConfig {
             strict_randomness_validation: false, // Allow deterministic
             allow_deterministic_randomness: true,
+            ..ReplayConfig::default()
         };
 
         let result = GameEngine::from_events_with_config(&events, config);
@@ -1603,7 +1706,7 @@ mod replay_system {
         let engine = GameEngine::new(settings, [0u8; 32]);
 
         // EndTurn should be valid for player 0 (default current player)
-        assert!(engine.is_valid_action(0, &GameAction::EndTurn));
+        assert!(engine.is_valid_action(PlayerSlot(0), &GameAction::EndTurn));
     }
 
     #[test]
@@ -1614,7 +1717,7 @@ mod replay_system {
         events.push(create_event(
             "evt_end_turn",
             "game_replay",
-            0,
+            PlayerSlot(0),
             Some("evt_start"),
             1,
             1,
@@ -1635,6 +1738,7 @@ mod replay_system {
 
 mod edge_cases {
     use super::*;
+    use nostr_nations_core::types::GameId;
 
     #[test]
     fn test_action_description() {
@@ -1643,13 +1747,15 @@ mod edge_cases {
             (GameAction::StartGame, "Game started"),
             (
                 GameAction::FoundCity {
-                    settler_id: 1,
+                    settler_id: UnitId(1),
                     name: "Rome".to_string(),
                 },
                 "Founded city Rome",
             ),
             (
-                GameAction::DeclareWar { target_player: 2 },
+                GameAction::DeclareWar {
+                    target_player: PlayerSlot(2),
+                },
                 "Declared war on player 2",
             ),
         ];
@@ -1669,7 +1775,7 @@ mod edge_cases {
     fn test_hex_coord_in_events() {
         let coord = HexCoord::new(100, -50);
         let action = GameAction::AssignCitizen {
-            city_id: 1,
+            city_id: CityId(1),
             tile: coord,
         };
 
@@ -1691,7 +1797,10 @@ mod edge_cases {
             path.push(HexCoord::new(i, i * 2));
         }
 
-        let action = GameAction::MoveUnit { unit_id: 1, path };
+        let action = GameAction::MoveUnit {
+            unit_id: UnitId(1),
+            path,
+        };
 
         let json = serde_json::to_string(&action).unwrap();
         let restored: GameAction = serde_json::from_str(&json).unwrap();
@@ -1723,7 +1832,7 @@ mod edge_cases {
     #[test]
     fn test_unicode_in_city_name() {
         let action = GameAction::FoundCity {
-            settler_id: 1,
+            settler_id: UnitId(1),
             name: "北京".to_string(), // Beijing in Chinese
         };
 
@@ -1739,14 +1848,14 @@ mod edge_cases {
     fn test_randomness_proof_in_tags() {
         let proof = create_test_proof(0.5);
         let mut event = GameEvent::with_randomness(
-            "game".to_string(),
-            0,
+            GameId::new("game"),
+            PlayerSlot(0),
             None,
             1,
             1,
             GameAction::AttackUnit {
-                attacker_id: 1,
-                defender_id: 2,
+                attacker_id: UnitId(1),
+                defender_id: UnitId(2),
                 random: 0.5,
             },
             proof,
@@ -1771,13 +1880,13 @@ mod edge_cases {
         let event = create_event_with_proof(
             "evt1",
             "game",
-            0,
+            PlayerSlot(0),
             None,
             1,
             1,
             GameAction::AttackUnit {
-                attacker_id: 1,
-                defender_id: 2,
+                attacker_id: UnitId(1),
+                defender_id: UnitId(2),
                 random: 0.75,
             },
             1000,
@@ -0,0 +1,282 @@
+//! Encryption at rest for saves and relay storage.
+//!
+//! Saved games and the relay database hold private game information, such
+//! as other players' units seen through fog of war and sealed diplomacy.
+//! An [`AtRestKey`] encrypts such data with XChaCha20-Poly1305 before it is
+//! written to disk. The key is derived either from the player's identity
+//! secret key ([`AtRestKey::from_identity_key`]) or from a passphrase
+//! ([`PassphraseKeyFile`]).
+//!
+//! Encrypted data starts with a fixed header, so it can be told apart from
+//! plaintext written before encryption was turned on. [`open`] decrypts
+//! encrypted data and passes plaintext through unchanged, which lets
+//! existing saves keep loading; they are encrypted the next time they are
+//! written.
+
+use argon2::{Algorithm, Argon2, Params, Version};
+use chacha20poly1305::aead::rand_core::RngCore;
+use chacha20poly1305::aead::{Aead, AeadCore, KeyInit, OsRng, Payload};
+use chacha20poly1305::{XChaCha20Poly1305, XNonce};
+use hkdf::Hkdf;
+use serde::{Deserialize, Serialize};
+use sha2::Sha256;
+
+/// Header that starts every encrypted blob; the last byte is the format
+/// version.
+pub const ENCRYPTED_HEADER: &[u8; 6] = b"NNENC\x01";
+
+/// Length of an XChaCha20 nonce.
+const NONCE_LEN: usize = 24;
+
+/// Length of a passphrase salt.
+pub const SALT_LEN: usize = 16;
+
+/// Plaintext encrypted into a key file to check the passphrase.
+const KEY_CHECK: &[u8] = b"nostr-nations at-rest key check";
+
+/// Errors from encrypting or decrypting data at rest.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum AtRestError {
+    /// The data is encrypted but no key was given.
+    KeyRequired,
+    /// The data could not be decrypted with this key, or was tampered with.
+    WrongKey,
+    /// The data is too short to be encrypted data.
+    Malformed,
+    /// The key could not be derived.
+    KeyDerivation(String),
+    /// The data could not be encrypted.
+    Encryption(String),
+}
+
+impl std::fmt::Display for AtRestError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            AtRestError::KeyRequired => write!(f, "Data is encrypted and no key was given"),
+            AtRestError::WrongKey => write!(f, "Wrong key or corrupted data"),
+            AtRestError::Malformed => write!(f, "Malformed encrypted data"),
+            AtRestError::KeyDerivation(msg) => write!(f, "Key derivation failed: {}", msg),
+            AtRestError::Encryption(msg) => write!(f, "Encryption failed: {}", msg),
+        }
+    }
+}
+
+impl std::error::Error for AtRestError {}
+
+/// A key for encrypting data at rest.
+#[derive(Clone, PartialEq, Eq)]
+pub struct AtRestKey {
+    key: [u8; 32],
+}
+
+impl AtRestKey {
+    /// Derive a key from the player's identity secret key.
+    ///
+    /// The identity key itself is never used for encryption; HKDF gives an
+    /// independent key for this purpose.
+    pub fn from_identity_key(secret_key: &[u8; 32]) -> Self {
+        let hkdf = Hkdf::<Sha256>::new(Some(b"nostr-nations/at-rest"), secret_key);
+        let mut key = [0u8; 32];
+        hkdf.expand(b"v1", &mut key)
+            .expect("32 bytes is a valid HKDF-SHA256 output length");
+        Self { key }
+    }
+
+    /// Derive a key from a passphrase with Argon2id.
+    pub fn from_passphrase(
+        passphrase: &str,
+        salt: &[u8; SALT_LEN],
+        params: KdfParams,
+    ) -> Result<Self, AtRestError> {
+        let params = Params::new(params.memory_kib, params.iterations, 1, Some(32))
+            .map_err(|e| AtRestError::KeyDerivation(e.to_string()))?;
+        let mut key = [0u8; 32];
+        Argon2::new(Algorithm::Argon2id, Version::V0x13, params)
+            .hash_password_into(passphrase.as_bytes(), salt, &mut key)
+            .map_err(|e| AtRestError::KeyDerivation(e.to_string()))?;
+        Ok(Self { key })
+    }
+
+    /// Encrypt data with a fresh random nonce.
+    pub fn encrypt(&self, plaintext: &[u8]) -> Result<Vec<u8>, AtRestError> {
+        let cipher = XChaCha20Poly1305::new(&self.key.into());
+        let nonce = XChaCha20Poly1305::generate_nonce(&mut OsRng);
+        let ciphertext = cipher
+            .encrypt(
+                &nonce,
+                Payload {
+                    msg: plaintext,
+                    aad: ENCRYPTED_HEADER,
+                },
+            )
+            .map_err(|e| AtRestError::Encryption(e.to_string()))?;
+
+        let mut out = Vec::with_capacity(ENCRYPTED_HEADER.len() + NONCE_LEN + ciphertext.len());
+        out.extend_from_slice(ENCRYPTED_HEADER);
+        out.extend_from_slice(&nonce);
+        out.extend_from_slice(&ciphertext);
+        Ok(out)
+    }
+
+    /// Decrypt data produced by [`encrypt`](Self::encrypt).
+    pub fn decrypt(&self, data: &[u8]) -> Result<Vec<u8>, AtRestError> {
+        let body = data
+            .strip_prefix(ENCRYPTED_HEADER.as_slice())
+            .ok_or(AtRestError::Malformed)?;
+        if body.len() < NONCE_LEN {
+            return Err(AtRestError::Malformed);
+        }
+        let (nonce, ciphertext) = body.split_at(NONCE_LEN);
+        XChaCha20Poly1305::new(&self.key.into())
+            .decrypt(
+                XNonce::from_slice(nonce),
+                Payload {
+                    msg: ciphertext,
+                    aad: ENCRYPTED_HEADER,
+                },
+            )
+            .map_err(|_| AtRestError::WrongKey)
+    }
+}
+
+impl std::fmt::Debug for AtRestKey {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str("AtRestKey(..)")
+    }
+}
+
+/// Check whether data was written by [`AtRestKey::encrypt`].
+pub fn is_encrypted(data: &[u8]) -> bool {
+    data.starts_with(ENCRYPTED_HEADER)
+}
+
+/// Read data that may or may not be encrypted.
+///
+/// Encrypted data is decrypted with `key`; plaintext is returned as is.
+pub fn open(key: Option<&AtRestKey>, data: Vec<u8>) -> Result<Vec<u8>, AtRestError> {
+    if !is_encrypted(&data) {
+        return Ok(data);
+    }
+    key.ok_or(AtRestError::KeyRequired)?.decrypt(&data)
+}
+
+/// Prepare data for writing: encrypted if there is a key, otherwise as is.
+pub fn seal(key: Option<&AtRestKey>, plaintext: Vec<u8>) -> Result<Vec<u8>, AtRestError> {
+    match key {
+        Some(key) => key.encrypt(&plaintext),
+        None => Ok(plaintext),
+    }
+}
+
+/// Argon2id cost parameters for passphrase keys.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct KdfParams {
+    /// Memory cost in KiB.
+    pub memory_kib: u32,
+    /// Number of passes.
+    pub iterations: u32,
+}
+
+impl Default for KdfParams {
+    fn default() -> Self {
+        Self {
+            memory_kib: 19 * 1024,
+            iterations: 2,
+        }
+    }
+}
+
+/// Everything needed to turn a passphrase back into its key, stored next
+/// to the encrypted data. Contains no secrets.
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct PassphraseKeyFile {
+    /// Random salt for the key derivation.
+    pub salt: [u8; SALT_LEN],
+    /// Key derivation cost.
+    pub kdf: KdfParams,
+    /// A known value encrypted with the key, to detect a wrong passphrase.
+    check: Vec<u8>,
+}
+
+impl PassphraseKeyFile {
+    /// Set up a passphrase with a fresh salt, returning the key file to
+    /// store and the derived key.
+    pub fn create(passphrase: &str, kdf: KdfParams) -> Result<(Self, AtRestKey), AtRestError> {
+        let mut salt = [0u8; SALT_LEN];
+        OsRng
+            .try_fill_bytes(&mut salt)
+            .map_err(|e| AtRestError::KeyDerivation(e.to_string()))?;
+        let key = AtRestKey::from_passphrase(passphrase, &salt, kdf)?;
+        let check = key.encrypt(KEY_CHECK)?;
+        Ok((Self { salt, kdf, check }, key))
+    }
+
+    /// Derive the key for a passphrase, failing with
+    /// [`AtRestError::WrongKey`] if it isn't the one the file was created
+    /// with.
+    pub fn unlock(&self, passphrase: &str) -> Result<AtRestKey, AtRestError> {
+        let key = AtRestKey::from_passphrase(passphrase, &self.salt, self.kdf)?;
+        if key.decrypt(&self.check)? != KEY_CHECK {
+            return Err(AtRestError::WrongKey);
+        }
+        Ok(key)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Cheap parameters so tests don't spend seconds hashing.
+    const TEST_KDF: KdfParams = KdfParams {
+        memory_kib: 64,
+        iterations: 1,
+    };
+
+    #[test]
+    fn test_encrypt_round_trip() {
+        let key = AtRestKey::from_identity_key(&[7; 32]);
+        let sealed = key.encrypt(b"secret plans").unwrap();
+        assert!(is_encrypted(&sealed));
+        assert!(!sealed.windows(6).any(|w| w == b"secret"));
+        assert_eq!(key.decrypt(&sealed).unwrap(), b"secret plans");
+
+        // Fresh nonce every time
+        assert_ne!(key.encrypt(b"secret plans").unwrap(), sealed);
+
+        let other = AtRestKey::from_identity_key(&[8; 32]);
+        assert_eq!(other.decrypt(&sealed), Err(AtRestError::WrongKey));
+
+        let mut tampered = sealed.clone();
+        *tampered.last_mut().unwrap() ^= 1;
+        assert_eq!(key.decrypt(&tampered), Err(AtRestError::WrongKey));
+        assert_eq!(key.decrypt(ENCRYPTED_HEADER), Err(AtRestError::Malformed));
+    }
+
+    #[test]
+    fn test_open_passes_plaintext_through() {
+        let key = AtRestKey::from_identity_key(&[1; 32]);
+        let plain = br#"{"turn":3}"#.to_vec();
+        assert_eq!(open(Some(&key), plain.clone()).unwrap(), plain);
+        assert_eq!(open(None, plain.clone()).unwrap(), plain);
+
+        let sealed = seal(Some(&key), plain.clone()).unwrap();
+        assert_eq!(open(None, sealed.clone()), Err(AtRestError::KeyRequired));
+        assert_eq!(open(Some(&key), sealed).unwrap(), plain);
+        assert_eq!(seal(None, plain.clone()).unwrap(), plain);
+    }
+
+    #[test]
+    fn test_passphrase_key_file() {
+        let (file, key) = PassphraseKeyFile::create("correct horse", TEST_KDF).unwrap();
+        let json = serde_json::to_string(&file).unwrap();
+        let file: PassphraseKeyFile = serde_json::from_str(&json).unwrap();
+
+        assert_eq!(file.unlock("correct horse").unwrap(), key);
+        assert_eq!(
+            file.unlock("battery staple").unwrap_err(),
+            AtRestError::WrongKey
+        );
+        assert_eq!(format!("{:?}", key), "AtRestKey(..)");
+    }
+}
@@ -2,11 +2,16 @@
 //!
 //! This module provides functionality to batch multiple small events into
 //! single messages to reduce network round trips and improve throughput.
+//!
+//! Flushing is game-aware: events that other players must see right away
+//! (turn ends, combat, war declarations) flush the batch immediately, while
+//! unit moves and other free-move actions are batched for longer.
 
+use crate::priority::{event_priority, EventPriority, EventPriorityQueue};
 use nostr_nations_core::events::GameEvent;
 use serde::{Deserialize, Serialize};
 use std::collections::VecDeque;
-use std::time::{Duration, Instant};
+use web_time::{Duration, Instant};
 
 /// Configuration for event batching.
 #[derive(Clone, Debug)]
@@ -19,6 +24,11 @@ pub struct BatchConfig {
     pub compression_threshold: usize,
     /// Whether compression is enabled.
     pub compression_enabled: bool,
+    /// Flush based on event priority instead of only size and time.
+    pub adaptive_flush: bool,
+    /// Time to wait before sending a batch of free-move actions (adaptive
+    /// flush only).
+    pub free_move_timeout: Duration,
 }
 
 impl Default for BatchConfig {
@@ -28,6 +38,8 @@ impl Default for BatchConfig {
             max_batch_timeout: Duration::from_millis(100),
             compression_threshold: 1024,
             compression_enabled: true,
+            adaptive_flush: true,
+            free_move_timeout: Duration::from_millis(250),
         }
     }
 }
@@ -55,8 +67,8 @@ impl EventBatch {
             events,
             compressed: false,
             original_size: 0,
-            created_at: std::time::SystemTime::now()
-                .duration_since(std::time::UNIX_EPOCH)
+            created_at: web_time::SystemTime::now()
+                .duration_since(web_time::UNIX_EPOCH)
                 .map(|d| d.as_millis() as u64)
                 .unwrap_or(0),
         }
@@ -93,6 +105,8 @@ pub struct EventBatcher {
     batch_start: Option<Instant>,
     /// Next batch ID.
     next_batch_id: u64,
+    /// Whether an urgent event is pending or waiting elsewhere.
+    urgent: bool,
     /// Statistics.
     stats: BatchStats,
 }
@@ -110,6 +124,8 @@ pub struct BatchStats {
     pub bytes_after_compression: u64,
     /// Average batch size.
     pub avg_batch_size: f64,
+    /// Batches flushed early because of an urgent event.
+    pub urgent_flushes: u64,
 }
 
 impl EventBatcher {
@@ -120,6 +136,7 @@ impl EventBatcher {
             pending: VecDeque::new(),
             batch_start: None,
             next_batch_id: 1,
+            urgent: false,
             stats: BatchStats::default(),
         }
     }
@@ -130,38 +147,90 @@ impl EventBatcher {
     }
 
     /// Add an event to the pending batch.
+    ///
+    /// The event's priority decides how soon the batch is sent.
     pub fn add_event(&mut self, event: GameEvent) {
+        let priority = event_priority(&event);
+        self.add_event_with_priority(event, priority);
+    }
+
+    /// Add an event with an explicit priority.
+    pub fn add_event_with_priority(&mut self, event: GameEvent, priority: EventPriority) {
         if self.batch_start.is_none() {
             self.batch_start = Some(Instant::now());
         }
         self.pending.push_back(event);
+        self.flush_hint(priority);
+    }
+
+    /// Tell the batcher an event of the given priority is waiting to be sent.
+    ///
+    /// High and critical priorities mark the pending batch for immediate
+    /// flushing, so urgent events are not stuck behind batched moves. Hints
+    /// with nothing pending are ignored.
+    pub fn flush_hint(&mut self, priority: EventPriority) {
+        if self.config.adaptive_flush
+            && !self.pending.is_empty()
+            && !EventPriority::High.is_higher_than(&priority)
+        {
+            self.urgent = true;
+        }
+    }
+
+    /// Move every event waiting in a priority queue into the pending batch.
+    ///
+    /// Events are taken in the queue's dequeue order. Returns the number of
+    /// events moved.
+    pub fn add_from_queue(&mut self, queue: &mut EventPriorityQueue) -> usize {
+        let mut moved = 0;
+        while let Some(event) = queue.dequeue() {
+            self.add_event(event);
+            moved += 1;
+        }
+        moved
     }
 
     /// Check if a batch is ready to be sent.
     pub fn is_batch_ready(&self) -> bool {
+        if self.pending.is_empty() {
+            return false;
+        }
+
         // Batch is ready if we have max events
         if self.pending.len() >= self.config.max_batch_size {
             return true;
         }
 
-        // Or if timeout has elapsed
-        if let Some(start) = self.batch_start {
-            if start.elapsed() >= self.config.max_batch_timeout {
-                return !self.pending.is_empty();
-            }
+        // Or if an urgent event is pending
+        if self.urgent {
+            return true;
         }
 
-        false
+        // Or if timeout has elapsed
+        self.batch_start
+            .is_some_and(|start| start.elapsed() >= self.current_timeout())
+    }
+
+    /// Time the current batch may wait before it is sent.
+    fn current_timeout(&self) -> Duration {
+        if self.config.adaptive_flush {
+            self.config.free_move_timeout
+        } else {
+            self.config.max_batch_timeout
+        }
     }
 
     /// Flush the current batch (regardless of size/timeout).
+    #[tracing::instrument(name = "batch.flush", skip_all, fields(game_id = tracing::field::Empty))]
     pub fn flush(&mut self) -> Option<EventBatch> {
-        if self.pending.is_empty() {
-            return None;
-        }
+        let first = self.pending.front()?;
+        tracing::Span::current().record(crate::debug::GAME_ID, first.game_id.as_str());
 
         let events: Vec<GameEvent> = self.pending.drain(..).collect();
         self.batch_start = None;
+        if std::mem::take(&mut self.urgent) {
+            self.stats.urgent_flushes += 1;
+        }
 
         let batch_id = self.next_batch_id;
         self.next_batch_id += 1;
@@ -238,7 +307,12 @@ impl EventUnbatcher {
 
     /// Process a batch and extract events.
     /// Returns None if the batch was already seen (duplicate).
+    #[tracing::instrument(name = "batch.process_batch", skip_all, fields(game_id = tracing::field::Empty))]
     pub fn process_batch(&mut self, batch: EventBatch) -> Option<Vec<GameEvent>> {
+        if let Some(first) = batch.events.first() {
+            tracing::Span::current().record(crate::debug::GAME_ID, first.game_id.as_str());
+        }
+
         // Check for duplicate
         if self.seen_batches.contains(&batch.batch_id) {
             self.stats.duplicates_ignored += 1;
@@ -289,9 +363,14 @@ impl Default for EventUnbatcher {
 mod tests {
     use super::*;
     use nostr_nations_core::events::GameAction;
+    use nostr_nations_core::types::{GameId, PlayerSlot, UnitId};
 
     fn create_test_event(id: &str) -> GameEvent {
-        let mut event = GameEvent::new("test_game".to_string(), 0, None, 1, 1, GameAction::EndTurn);
+        let action = GameAction::MoveUnit {
+            unit_id: UnitId(1),
+            path: vec![],
+        };
+        let mut event = GameEvent::new(GameId::new("test_game"), PlayerSlot(0), None, 1, 1, action);
         event.id = id.to_string();
         event
     }
@@ -314,9 +393,12 @@ mod tests {
             max_batch_timeout: Duration::from_millis(200),
             compression_threshold: 2048,
             compression_enabled: false,
+            adaptive_flush: false,
+            free_move_timeout: Duration::from_millis(500),
         };
         assert_eq!(config.max_batch_size, 100);
         assert!(!config.compression_enabled);
+        assert!(!config.adaptive_flush);
     }
 
     // ==================== EventBatch Tests ====================
@@ -461,6 +543,100 @@ mod tests {
         assert_eq!(batch2.batch_id, 2);
     }
 
+    // ==================== Adaptive Flush Tests ====================
+
+    fn event_with(id: &str, action: GameAction) -> GameEvent {
+        let mut event = GameEvent::new(GameId::new("test_game"), PlayerSlot(0), None, 1, 1, action);
+        event.id = id.to_string();
+        event
+    }
+
+    #[test]
+    fn test_moves_wait_for_free_move_timeout() {
+        let mut batcher = EventBatcher::with_defaults();
+        batcher.add_event(create_test_event("e1"));
+        batcher.add_event(create_test_event("e2"));
+        assert!(!batcher.is_batch_ready());
+
+        let mut batcher = EventBatcher::new(BatchConfig {
+            free_move_timeout: Duration::ZERO,
+            ..Default::default()
+        });
+        batcher.add_event(create_test_event("e1"));
+        assert!(batcher.is_batch_ready());
+    }
+
+    #[test]
+    fn test_end_turn_flushes_immediately() {
+        let mut batcher = EventBatcher::with_defaults();
+        batcher.add_event(create_test_event("e1"));
+        batcher.add_event(event_with("e2", GameAction::EndTurn));
+
+        let batch = batcher.take_batch().unwrap();
+        assert_eq!(batch.len(), 2);
+        assert_eq!(batcher.stats().urgent_flushes, 1);
+
+        // The next batch starts relaxed again
+        batcher.add_event(create_test_event("e3"));
+        assert!(!batcher.is_batch_ready());
+    }
+
+    #[test]
+    fn test_combat_flushes_immediately() {
+        let mut batcher = EventBatcher::with_defaults();
+        batcher.add_event(event_with(
+            "e1",
+            GameAction::AttackUnit {
+                attacker_id: UnitId(1),
+                defender_id: UnitId(2),
+                random: 0.5,
+            },
+        ));
+        assert!(batcher.is_batch_ready());
+    }
+
+    #[test]
+    fn test_flush_hint_from_priority_queue() {
+        let mut batcher = EventBatcher::with_defaults();
+        let mut queue = EventPriorityQueue::with_defaults();
+        batcher.add_event(create_test_event("e1"));
+
+        queue.enqueue(create_test_event("q1")).unwrap();
+        batcher.flush_hint(queue.highest_priority().unwrap());
+        assert!(!batcher.is_batch_ready());
+
+        queue
+            .enqueue(event_with("q2", GameAction::EndTurn))
+            .unwrap();
+        assert_eq!(queue.highest_priority(), Some(EventPriority::High));
+        batcher.flush_hint(queue.highest_priority().unwrap());
+        assert!(batcher.is_batch_ready());
+
+        assert_eq!(batcher.add_from_queue(&mut queue), 2);
+        assert!(queue.is_empty());
+        assert_eq!(batcher.flush().unwrap().len(), 3);
+    }
+
+    #[test]
+    fn test_non_adaptive_ignores_priority() {
+        let mut batcher = EventBatcher::new(BatchConfig {
+            adaptive_flush: false,
+            ..Default::default()
+        });
+        batcher.add_event(event_with("e1", GameAction::EndTurn));
+        assert!(!batcher.is_batch_ready());
+    }
+
+    #[test]
+    fn test_flush_hint_without_pending_events() {
+        let mut batcher = EventBatcher::with_defaults();
+        batcher.flush_hint(EventPriority::Critical);
+        assert!(batcher.take_batch().is_none());
+
+        batcher.add_event(create_test_event("e1"));
+        assert!(!batcher.is_batch_ready());
+    }
+
     // ==================== EventUnbatcher Tests ====================
 
     #[test]
@@ -2,10 +2,23 @@
 //!
 //! This module provides LRU caching for recently synced events
 //! and deduplication of incoming events.
+//!
+//! With the `sqlite` feature, both can be persisted to a [`CacheStore`] so
+//! hot events and dedup state survive restarts. Entries past their TTL are
+//! dropped when loading.
 
+#[cfg(feature = "sqlite")]
+use crate::relay::StorageError;
 use nostr_nations_core::events::GameEvent;
+use nostr_nations_core::types::{GameId, PlayerSlot};
+#[cfg(feature = "sqlite")]
+use rusqlite::{params, Connection};
 use std::collections::{HashMap, HashSet, VecDeque};
-use std::time::{Duration, Instant};
+#[cfg(feature = "sqlite")]
+use std::path::Path;
+#[cfg(feature = "sqlite")]
+use std::sync::{Arc, Mutex};
+use web_time::{Duration, Instant, SystemTime, UNIX_EPOCH};
 
 /// Configuration for the event cache.
 #[derive(Clone, Debug)]
@@ -270,6 +283,70 @@ impl EventCache {
             .collect()
     }
 
+    /// Persist cached events and dedup state, replacing any previously
+    /// saved state.
+    #[cfg(feature = "sqlite")]
+    pub fn save_to(&self, store: &CacheStore) -> Result<(), StorageError> {
+        let now = now_ms();
+        let events: Vec<(&CachedEvent, u64)> = self
+            .lru_order
+            .iter()
+            .filter_map(|id| self.events.get(id))
+            .map(|cached| {
+                let cached_at = now.saturating_sub(cached.age().as_millis() as u64);
+                (cached, cached_at)
+            })
+            .collect();
+        store.save_events(&events)?;
+
+        let ids: Vec<(&str, u64)> = self
+            .seen_order
+            .iter()
+            .map(|id| (id.as_str(), now))
+            .collect();
+        store.save_seen_ids(CACHE_SCOPE, &ids)
+    }
+
+    /// Load a cache from a store (warm start).
+    ///
+    /// Events older than `config.max_age` are dropped, and the remaining
+    /// events keep their age so they expire on schedule.
+    #[cfg(feature = "sqlite")]
+    pub fn load_from(config: CacheConfig, store: &CacheStore) -> Result<Self, StorageError> {
+        let now = now_ms();
+        let mut cache = Self::new(config);
+
+        if cache.config.enable_dedup {
+            for (id, _) in store.load_seen_ids(CACHE_SCOPE)?.into_iter().rev() {
+                cache.track_seen(id);
+            }
+        }
+
+        // Stored most recent first; insert oldest first to rebuild LRU order
+        for stored in store.load_events()?.into_iter().rev() {
+            let age = Duration::from_millis(now.saturating_sub(stored.cached_at));
+            if age > cache.config.max_age {
+                cache.stats.expirations += 1;
+                continue;
+            }
+            while cache.events.len() >= cache.config.max_events {
+                cache.evict_lru();
+            }
+
+            let id = stored.event.id.clone();
+            let mut cached = CachedEvent::new(stored.event);
+            cached.cached_at = Instant::now().checked_sub(age).unwrap_or(cached.cached_at);
+            cached.access_count = stored.access_count;
+            cache.events.insert(id.clone(), cached);
+            cache.lru_order.push_front(id.clone());
+            if cache.config.enable_dedup && !cache.seen_ids.contains(&id) {
+                cache.track_seen(id);
+            }
+        }
+
+        Ok(cache)
+    }
+
     /// Evict the least recently used event.
     fn evict_lru(&mut self) {
         if let Some(id) = self.lru_order.pop_back() {
@@ -304,6 +381,8 @@ pub struct EventDeduplicator {
     seen: HashSet<String>,
     /// Order for LRU eviction.
     order: VecDeque<String>,
+    /// When each ID was first seen (ms since epoch).
+    seen_at: HashMap<String, u64>,
     /// Maximum IDs to track.
     max_ids: usize,
     /// Statistics.
@@ -338,6 +417,7 @@ impl EventDeduplicator {
         Self {
             seen: HashSet::new(),
             order: VecDeque::new(),
+            seen_at: HashMap::new(),
             max_ids,
             stats: DedupStats::default(),
         }
@@ -354,17 +434,24 @@ impl EventDeduplicator {
         }
 
         // Track the new ID
+        self.track(id.to_string(), now_ms());
+        self.stats.unique += 1;
+
+        false
+    }
+
+    /// Track an ID, evicting the oldest if at capacity.
+    fn track(&mut self, id: String, seen_at: u64) {
         if self.seen.len() >= self.max_ids {
             if let Some(old_id) = self.order.pop_back() {
                 self.seen.remove(&old_id);
+                self.seen_at.remove(&old_id);
             }
         }
 
-        self.seen.insert(id.to_string());
-        self.order.push_front(id.to_string());
-        self.stats.unique += 1;
-
-        false
+        self.seen.insert(id.clone());
+        self.seen_at.insert(id.clone(), seen_at);
+        self.order.push_front(id);
     }
 
     /// Check and return the event if not duplicate.
@@ -385,6 +472,7 @@ impl EventDeduplicator {
     pub fn clear(&mut self) {
         self.seen.clear();
         self.order.clear();
+        self.seen_at.clear();
     }
 
     /// Get statistics.
@@ -401,14 +489,224 @@ impl EventDeduplicator {
     pub fn is_empty(&self) -> bool {
         self.seen.is_empty()
     }
+
+    /// Persist tracked IDs, replacing any previously saved state.
+    #[cfg(feature = "sqlite")]
+    pub fn save_to(&self, store: &CacheStore) -> Result<(), StorageError> {
+        let ids: Vec<(&str, u64)> = self
+            .order
+            .iter()
+            .map(|id| (id.as_str(), self.seen_at.get(id).copied().unwrap_or(0)))
+            .collect();
+        store.save_seen_ids(DEDUP_SCOPE, &ids)
+    }
+
+    /// Load a deduplicator from a store, dropping IDs older than `ttl`.
+    #[cfg(feature = "sqlite")]
+    pub fn load_from(
+        store: &CacheStore,
+        max_ids: usize,
+        ttl: Duration,
+    ) -> Result<Self, StorageError> {
+        let cutoff = now_ms().saturating_sub(ttl.as_millis() as u64);
+        let mut dedup = Self::new(max_ids);
+
+        // Stored most recent first; replay oldest first to rebuild LRU order
+        for (id, seen_at) in store.load_seen_ids(DEDUP_SCOPE)?.into_iter().rev() {
+            if seen_at >= cutoff {
+                dedup.track(id, seen_at);
+            }
+        }
+
+        Ok(dedup)
+    }
+}
+
+// ==================== Persistence ====================
+
+/// Scope for dedup IDs saved by `EventCache`.
+#[cfg(feature = "sqlite")]
+const CACHE_SCOPE: &str = "cache";
+/// Scope for dedup IDs saved by `EventDeduplicator`.
+#[cfg(feature = "sqlite")]
+const DEDUP_SCOPE: &str = "dedup";
+
+/// Current time in milliseconds since the Unix epoch.
+fn now_ms() -> u64 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map(|d| d.as_millis() as u64)
+        .unwrap_or(0)
+}
+
+/// A cached event loaded from a store.
+#[cfg(feature = "sqlite")]
+struct StoredEvent {
+    event: GameEvent,
+    cached_at: u64,
+    access_count: u64,
+}
+
+/// SQLite-backed persistence for [`EventCache`] and [`EventDeduplicator`].
+#[cfg(feature = "sqlite")]
+#[derive(Clone)]
+pub struct CacheStore {
+    conn: Arc<Mutex<Connection>>,
+}
+
+#[cfg(feature = "sqlite")]
+impl CacheStore {
+    /// Open an in-memory store.
+    pub fn new_in_memory() -> Result<Self, StorageError> {
+        Self::from_connection(Connection::open_in_memory()?)
+    }
+
+    /// Open a file-based store.
+    pub fn new<P: AsRef<Path>>(path: P) -> Result<Self, StorageError> {
+        Self::from_connection(Connection::open(path)?)
+    }
+
+    fn from_connection(conn: Connection) -> Result<Self, StorageError> {
+        conn.execute(
+            "CREATE TABLE IF NOT EXISTS cached_events (
+                id TEXT PRIMARY KEY,
+                raw_event TEXT NOT NULL,
+                cached_at INTEGER NOT NULL,
+                access_count INTEGER NOT NULL,
+                lru_rank INTEGER NOT NULL
+            )",
+            [],
+        )?;
+        conn.execute(
+            "CREATE TABLE IF NOT EXISTS seen_ids (
+                scope TEXT NOT NULL,
+                id TEXT NOT NULL,
+                seen_at INTEGER NOT NULL,
+                lru_rank INTEGER NOT NULL,
+                PRIMARY KEY (scope, id)
+            )",
+            [],
+        )?;
+
+        Ok(Self {
+            conn: Arc::new(Mutex::new(conn)),
+        })
+    }
+
+    /// Replace stored events. Events are given most recently used first.
+    fn save_events(&self, events: &[(&CachedEvent, u64)]) -> Result<(), StorageError> {
+        let mut conn = self
+            .conn
+            .lock()
+            .map_err(|e| StorageError::LockError(e.to_string()))?;
+        let tx = conn.transaction()?;
+
+        tx.execute("DELETE FROM cached_events", [])?;
+        for (rank, (cached, cached_at)) in events.iter().enumerate() {
+            let raw_event = serde_json::to_string(&cached.event)
+                .map_err(|e| StorageError::Serialization(e.to_string()))?;
+            tx.execute(
+                "INSERT OR REPLACE INTO cached_events (id, raw_event, cached_at, access_count, lru_rank)
+                 VALUES (?1, ?2, ?3, ?4, ?5)",
+                params![
+                    cached.event.id,
+                    raw_event,
+                    *cached_at as i64,
+                    cached.access_count as i64,
+                    rank as i64
+                ],
+            )?;
+        }
+
+        tx.commit()?;
+        Ok(())
+    }
+
+    /// Load stored events, most recently used first.
+    fn load_events(&self) -> Result<Vec<StoredEvent>, StorageError> {
+        let conn = self
+            .conn
+            .lock()
+            .map_err(|e| StorageError::LockError(e.to_string()))?;
+        let mut stmt = conn.prepare(
+            "SELECT raw_event, cached_at, access_count FROM cached_events ORDER BY lru_rank",
+        )?;
+        let rows = stmt.query_map([], |row| {
+            Ok((
+                row.get::<_, String>(0)?,
+                row.get::<_, i64>(1)?,
+                row.get::<_, i64>(2)?,
+            ))
+        })?;
+
+        let mut events = Vec::new();
+        for row in rows {
+            let (raw_event, cached_at, access_count) = row?;
+            let event: GameEvent = serde_json::from_str(&raw_event)
+                .map_err(|e| StorageError::Serialization(e.to_string()))?;
+            events.push(StoredEvent {
+                event,
+                cached_at: cached_at as u64,
+                access_count: access_count as u64,
+            });
+        }
+        Ok(events)
+    }
+
+    /// Replace stored IDs for a scope. IDs are given most recent first.
+    fn save_seen_ids(&self, scope: &str, ids: &[(&str, u64)]) -> Result<(), StorageError> {
+        let mut conn = self
+            .conn
+            .lock()
+            .map_err(|e| StorageError::LockError(e.to_string()))?;
+        let tx = conn.transaction()?;
+
+        tx.execute("DELETE FROM seen_ids WHERE scope = ?1", params![scope])?;
+        for (rank, (id, seen_at)) in ids.iter().enumerate() {
+            tx.execute(
+                "INSERT OR REPLACE INTO seen_ids (scope, id, seen_at, lru_rank)
+                 VALUES (?1, ?2, ?3, ?4)",
+                params![scope, id, *seen_at as i64, rank as i64],
+            )?;
+        }
+
+        tx.commit()?;
+        Ok(())
+    }
+
+    /// Load stored IDs for a scope, most recent first.
+    fn load_seen_ids(&self, scope: &str) -> Result<Vec<(String, u64)>, StorageError> {
+        let conn = self
+            .conn
+            .lock()
+            .map_err(|e| StorageError::LockError(e.to_string()))?;
+        let mut stmt =
+            conn.prepare("SELECT id, seen_at FROM seen_ids WHERE scope = ?1 ORDER BY lru_rank")?;
+        let rows = stmt.query_map(params![scope], |row| {
+            Ok((row.get::<_, String>(0)?, row.get::<_, i64>(1)? as u64))
+        })?;
+
+        rows.collect::<Result<_, _>>().map_err(StorageError::from)
+    }
+
+    /// Remove all persisted state.
+    pub fn clear(&self) -> Result<(), StorageError> {
+        let conn = self
+            .conn
+            .lock()
+            .map_err(|e| StorageError::LockError(e.to_string()))?;
+        conn.execute("DELETE FROM cached_events", [])?;
+        conn.execute("DELETE FROM seen_ids", [])?;
+        Ok(())
+    }
 }
 
 /// Index for fast event lookup by various criteria.
 pub struct EventIndex {
     /// Events by game ID.
-    by_game: HashMap<String, HashSet<String>>,
+    by_game: HashMap<GameId, HashSet<String>>,
     /// Events by player ID.
-    by_player: HashMap<u8, HashSet<String>>,
+    by_player: HashMap<PlayerSlot, HashSet<String>>,
     /// Events by turn.
     by_turn: HashMap<u32, HashSet<String>>,
 }
@@ -460,7 +758,7 @@ impl EventIndex {
     }
 
     /// Get event IDs for a player.
-    pub fn by_player(&self, player_id: u8) -> impl Iterator<Item = &String> {
+    pub fn by_player(&self, player_id: PlayerSlot) -> impl Iterator<Item = &String> {
         self.by_player.get(&player_id).into_iter().flatten()
     }
 
@@ -488,9 +786,9 @@ mod tests {
     use super::*;
     use nostr_nations_core::events::GameAction;
 
-    fn create_event(id: &str, game_id: &str, player_id: u8, turn: u32) -> GameEvent {
+    fn create_event(id: &str, game_id: &str, player_id: PlayerSlot, turn: u32) -> GameEvent {
         let mut event = GameEvent::new(
-            game_id.to_string(),
+            GameId::new(game_id),
             player_id,
             None,
             turn,
@@ -514,7 +812,7 @@ mod tests {
 
     #[test]
     fn test_cached_event_new() {
-        let event = create_event("e1", "g1", 0, 1);
+        let event = create_event("e1", "g1", PlayerSlot(0), 1);
         let cached = CachedEvent::new(event);
 
         assert_eq!(cached.access_count, 0);
@@ -523,7 +821,7 @@ mod tests {
 
     #[test]
     fn test_cached_event_record_access() {
-        let event = create_event("e1", "g1", 0, 1);
+        let event = create_event("e1", "g1", PlayerSlot(0), 1);
         let mut cached = CachedEvent::new(event);
 
         cached.record_access();
@@ -545,7 +843,7 @@ mod tests {
     #[test]
     fn test_event_cache_insert() {
         let mut cache = EventCache::with_defaults();
-        let event = create_event("e1", "g1", 0, 1);
+        let event = create_event("e1", "g1", PlayerSlot(0), 1);
 
         let is_new = cache.insert(event);
         assert!(is_new);
@@ -555,8 +853,8 @@ mod tests {
     #[test]
     fn test_event_cache_insert_duplicate() {
         let mut cache = EventCache::with_defaults();
-        let event1 = create_event("e1", "g1", 0, 1);
-        let event2 = create_event("e1", "g1", 0, 1); // Same ID
+        let event1 = create_event("e1", "g1", PlayerSlot(0), 1);
+        let event2 = create_event("e1", "g1", PlayerSlot(0), 1); // Same ID
 
         assert!(cache.insert(event1));
         assert!(!cache.insert(event2));
@@ -566,7 +864,7 @@ mod tests {
     #[test]
     fn test_event_cache_get() {
         let mut cache = EventCache::with_defaults();
-        let event = create_event("e1", "g1", 0, 1);
+        let event = create_event("e1", "g1", PlayerSlot(0), 1);
 
         cache.insert(event);
 
@@ -588,7 +886,7 @@ mod tests {
     #[test]
     fn test_event_cache_contains() {
         let mut cache = EventCache::with_defaults();
-        let event = create_event("e1", "g1", 0, 1);
+        let event = create_event("e1", "g1", PlayerSlot(0), 1);
 
         cache.insert(event);
 
@@ -599,7 +897,7 @@ mod tests {
     #[test]
     fn test_event_cache_remove() {
         let mut cache = EventCache::with_defaults();
-        let event = create_event("e1", "g1", 0, 1);
+        let event = create_event("e1", "g1", PlayerSlot(0), 1);
 
         cache.insert(event);
         let removed = cache.remove("e1");
@@ -616,15 +914,15 @@ mod tests {
         };
         let mut cache = EventCache::new(config);
 
-        cache.insert(create_event("e1", "g1", 0, 1));
-        cache.insert(create_event("e2", "g1", 0, 1));
-        cache.insert(create_event("e3", "g1", 0, 1));
+        cache.insert(create_event("e1", "g1", PlayerSlot(0), 1));
+        cache.insert(create_event("e2", "g1", PlayerSlot(0), 1));
+        cache.insert(create_event("e3", "g1", PlayerSlot(0), 1));
 
         // Access e1 to make it recently used
         cache.get("e1");
 
         // Insert e4, should evict e2 (oldest unused)
-        cache.insert(create_event("e4", "g1", 0, 1));
+        cache.insert(create_event("e4", "g1", PlayerSlot(0), 1));
 
         assert!(cache.contains("e1"));
         assert!(!cache.contains("e2"));
@@ -635,8 +933,8 @@ mod tests {
     #[test]
     fn test_event_cache_clear() {
         let mut cache = EventCache::with_defaults();
-        cache.insert(create_event("e1", "g1", 0, 1));
-        cache.insert(create_event("e2", "g1", 0, 1));
+        cache.insert(create_event("e1", "g1", PlayerSlot(0), 1));
+        cache.insert(create_event("e2", "g1", PlayerSlot(0), 1));
 
         cache.clear();
         assert!(cache.is_empty());
@@ -645,9 +943,9 @@ mod tests {
     #[test]
     fn test_event_cache_events_for_game() {
         let mut cache = EventCache::with_defaults();
-        cache.insert(create_event("e1", "game1", 0, 1));
-        cache.insert(create_event("e2", "game1", 0, 1));
-        cache.insert(create_event("e3", "game2", 0, 1));
+        cache.insert(create_event("e1", "game1", PlayerSlot(0), 1));
+        cache.insert(create_event("e2", "game1", PlayerSlot(0), 1));
+        cache.insert(create_event("e3", "game2", PlayerSlot(0), 1));
 
         let game1_events = cache.events_for_game("game1");
         assert_eq!(game1_events.len(), 2);
@@ -660,7 +958,7 @@ mod tests {
     fn test_event_cache_stats() {
         let mut cache = EventCache::with_defaults();
 
-        cache.insert(create_event("e1", "g1", 0, 1));
+        cache.insert(create_event("e1", "g1", PlayerSlot(0), 1));
         cache.get("e1");
         cache.get("e2"); // Miss
 
@@ -691,8 +989,8 @@ mod tests {
     #[test]
     fn test_deduplicator_filter() {
         let mut dedup = EventDeduplicator::new(100);
-        let event1 = create_event("e1", "g1", 0, 1);
-        let event2 = create_event("e1", "g1", 0, 1); // Same ID
+        let event1 = create_event("e1", "g1", PlayerSlot(0), 1);
+        let event2 = create_event("e1", "g1", PlayerSlot(0), 1); // Same ID
 
         assert!(dedup.filter(event1).is_some());
         assert!(dedup.filter(event2).is_none());
@@ -702,10 +1000,10 @@ mod tests {
     fn test_deduplicator_filter_batch() {
         let mut dedup = EventDeduplicator::new(100);
         let events = vec![
-            create_event("e1", "g1", 0, 1),
-            create_event("e2", "g1", 0, 1),
-            create_event("e1", "g1", 0, 1), // Duplicate
-            create_event("e3", "g1", 0, 1),
+            create_event("e1", "g1", PlayerSlot(0), 1),
+            create_event("e2", "g1", PlayerSlot(0), 1),
+            create_event("e1", "g1", PlayerSlot(0), 1), // Duplicate
+            create_event("e3", "g1", PlayerSlot(0), 1),
         ];
 
         let filtered = dedup.filter_batch(events);
@@ -739,6 +1037,136 @@ mod tests {
         assert_eq!(stats.unique, 2);
     }
 
+    // ==================== Persistence Tests ====================
+
+    #[cfg(feature = "sqlite")]
+    #[test]
+    fn test_cache_warm_start_roundtrip() {
+        let store = CacheStore::new_in_memory().unwrap();
+        let mut cache = EventCache::with_defaults();
+        cache.insert(create_event("e1", "g1", PlayerSlot(0), 1));
+        cache.insert(create_event("e2", "g1", PlayerSlot(0), 2));
+        cache.get("e1");
+        cache.save_to(&store).unwrap();
+
+        let mut restored = EventCache::load_from(CacheConfig::default(), &store).unwrap();
+        assert_eq!(restored.len(), 2);
+        assert!(restored.is_duplicate("e1"));
+        assert!(!restored.insert(create_event("e2", "g1", PlayerSlot(0), 2)));
+        assert_eq!(restored.get("e1").unwrap().turn, 1);
+
+        // LRU order survives: e2 is least recently used
+        restored.config.max_events = 2;
+        restored.insert(create_event("e3", "g1", PlayerSlot(0), 3));
+        assert!(!restored.contains("e2"));
+        assert!(restored.contains("e1"));
+    }
+
+    #[cfg(feature = "sqlite")]
+    #[test]
+    fn test_cache_load_enforces_ttl() {
+        let store = CacheStore::new_in_memory().unwrap();
+        let fresh = CachedEvent::new(create_event("fresh", "g1", PlayerSlot(0), 1));
+        let stale = CachedEvent::new(create_event("stale", "g1", PlayerSlot(0), 1));
+        let now = now_ms();
+        store
+            .save_events(&[(&fresh, now - 1_000), (&stale, now - 600_000)])
+            .unwrap();
+
+        let cache = EventCache::load_from(CacheConfig::default(), &store).unwrap();
+        assert!(cache.contains("fresh"));
+        assert!(!cache.contains("stale"));
+        assert_eq!(cache.stats().expirations, 1);
+
+        // Restored events keep their age
+        assert!(cache.events["fresh"].age() >= Duration::from_millis(1_000));
+    }
+
+    #[cfg(feature = "sqlite")]
+    #[test]
+    fn test_cache_load_respects_capacity() {
+        let store = CacheStore::new_in_memory().unwrap();
+        let mut cache = EventCache::with_defaults();
+        for i in 0..5 {
+            cache.insert(create_event(&format!("e{}", i), "g1", PlayerSlot(0), i));
+        }
+        cache.save_to(&store).unwrap();
+
+        let config = CacheConfig {
+            max_events: 2,
+            ..Default::default()
+        };
+        let restored = EventCache::load_from(config, &store).unwrap();
+        assert_eq!(restored.len(), 2);
+        assert!(restored.contains("e4"));
+        assert!(restored.contains("e3"));
+    }
+
+    #[cfg(feature = "sqlite")]
+    #[test]
+    fn test_dedup_warm_start_roundtrip() {
+        let store = CacheStore::new_in_memory().unwrap();
+        let mut dedup = EventDeduplicator::new(100);
+        dedup.is_duplicate("a");
+        dedup.is_duplicate("b");
+        dedup.save_to(&store).unwrap();
+
+        let mut restored =
+            EventDeduplicator::load_from(&store, 100, Duration::from_secs(60)).unwrap();
+        assert_eq!(restored.len(), 2);
+        assert!(restored.is_duplicate("a"));
+        assert!(!restored.is_duplicate("c"));
+    }
+
+    #[cfg(feature = "sqlite")]
+    #[test]
+    fn test_dedup_load_enforces_ttl() {
+        let store = CacheStore::new_in_memory().unwrap();
+        let now = now_ms();
+        store
+            .save_seen_ids(DEDUP_SCOPE, &[("new", now), ("old", now - 120_000)])
+            .unwrap();
+
+        let mut restored =
+            EventDeduplicator::load_from(&store, 100, Duration::from_secs(60)).unwrap();
+        assert_eq!(restored.len(), 1);
+        assert!(restored.is_duplicate("new"));
+        assert!(!restored.is_duplicate("old"));
+    }
+
+    #[cfg(feature = "sqlite")]
+    #[test]
+    fn test_cache_store_scopes_are_separate() {
+        let store = CacheStore::new_in_memory().unwrap();
+        let mut dedup = EventDeduplicator::new(100);
+        dedup.is_duplicate("dedup_only");
+        dedup.save_to(&store).unwrap();
+        EventCache::with_defaults().save_to(&store).unwrap();
+
+        let restored = EventDeduplicator::load_from(&store, 100, Duration::from_secs(60)).unwrap();
+        assert_eq!(restored.len(), 1);
+
+        store.clear().unwrap();
+        let cleared = EventDeduplicator::load_from(&store, 100, Duration::from_secs(60)).unwrap();
+        assert!(cleared.is_empty());
+    }
+
+    #[cfg(feature = "sqlite")]
+    #[test]
+    fn test_cache_store_file_persists() {
+        let dir = tempfile::TempDir::new().unwrap();
+        let path = dir.path().join("cache.db");
+
+        let mut cache = EventCache::with_defaults();
+        cache.insert(create_event("e1", "g1", PlayerSlot(0), 1));
+        cache.save_to(&CacheStore::new(&path).unwrap()).unwrap();
+
+        let restored =
+            EventCache::load_from(CacheConfig::default(), &CacheStore::new(&path).unwrap())
+                .unwrap();
+        assert!(restored.contains("e1"));
+    }
+
     // ==================== EventIndex Tests ====================
 
     #[test]
@@ -750,19 +1178,19 @@ mod tests {
     #[test]
     fn test_event_index_add() {
         let mut index = EventIndex::new();
-        let event = create_event("e1", "game1", 0, 5);
+        let event = create_event("e1", "game1", PlayerSlot(0), 5);
 
         index.add(&event);
 
         assert_eq!(index.by_game("game1").count(), 1);
-        assert_eq!(index.by_player(0).count(), 1);
+        assert_eq!(index.by_player(PlayerSlot(0)).count(), 1);
         assert_eq!(index.by_turn(5).count(), 1);
     }
 
     #[test]
     fn test_event_index_remove() {
         let mut index = EventIndex::new();
-        let event = create_event("e1", "game1", 0, 5);
+        let event = create_event("e1", "game1", PlayerSlot(0), 5);
 
         index.add(&event);
         index.remove(&event);
@@ -774,15 +1202,15 @@ mod tests {
     fn test_event_index_multiple_events() {
         let mut index = EventIndex::new();
 
-        index.add(&create_event("e1", "game1", 0, 1));
-        index.add(&create_event("e2", "game1", 0, 1));
-        index.add(&create_event("e3", "game1", 1, 2));
-        index.add(&create_event("e4", "game2", 0, 1));
+        index.add(&create_event("e1", "game1", PlayerSlot(0), 1));
+        index.add(&create_event("e2", "game1", PlayerSlot(0), 1));
+        index.add(&create_event("e3", "game1", PlayerSlot(1), 2));
+        index.add(&create_event("e4", "game2", PlayerSlot(0), 1));
 
         assert_eq!(index.by_game("game1").count(), 3);
         assert_eq!(index.by_game("game2").count(), 1);
-        assert_eq!(index.by_player(0).count(), 3);
-        assert_eq!(index.by_player(1).count(), 1);
+        assert_eq!(index.by_player(PlayerSlot(0)).count(), 3);
+        assert_eq!(index.by_player(PlayerSlot(1)).count(), 1);
         assert_eq!(index.by_turn(1).count(), 3);
         assert_eq!(index.by_turn(2).count(), 1);
     }
@@ -790,7 +1218,7 @@ mod tests {
     #[test]
     fn test_event_index_clear() {
         let mut index = EventIndex::new();
-        index.add(&create_event("e1", "game1", 0, 1));
+        index.add(&create_event("e1", "game1", PlayerSlot(0), 1));
 
         index.clear();
         assert_eq!(index.by_game("game1").count(), 0);
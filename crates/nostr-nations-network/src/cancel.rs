@@ -0,0 +1,139 @@
+//! Cancellation of long-running network operations.
+//!
+//! Syncing a game, querying relays in bulk and publishing across the pool
+//! can all outlive the reason they were started: the player navigates
+//! away, or the game is dropped. Such operations take a
+//! [`CancellationToken`] and check it as they go, stopping cleanly once it
+//! is cancelled instead of running to completion in the background.
+//!
+//! Tokens are cheap to clone; every clone shares the same state. A child
+//! token is cancelled along with its parent but can also be cancelled on
+//! its own, so a game can hand each of its operations a child of one
+//! game-wide token.
+
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::{Arc, Mutex};
+use tokio::sync::Notify;
+
+#[derive(Debug, Default)]
+struct TokenState {
+    cancelled: AtomicBool,
+    notify: Notify,
+    children: Mutex<Vec<CancellationToken>>,
+}
+
+/// A shared flag that tells an operation to stop.
+#[derive(Clone, Debug, Default)]
+pub struct CancellationToken {
+    state: Arc<TokenState>,
+}
+
+impl CancellationToken {
+    /// Create a token that hasn't been cancelled.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Create a token that is cancelled when this one is.
+    pub fn child_token(&self) -> CancellationToken {
+        let child = CancellationToken::new();
+        if self.is_cancelled() {
+            child.cancel();
+        } else if let Ok(mut children) = self.state.children.lock() {
+            children.retain(|c| !c.is_cancelled());
+            children.push(child.clone());
+        }
+        child
+    }
+
+    /// Cancel the token and all of its children.
+    pub fn cancel(&self) {
+        if self.state.cancelled.swap(true, Ordering::SeqCst) {
+            return;
+        }
+        self.state.notify.notify_waiters();
+        let children = self
+            .state
+            .children
+            .lock()
+            .map(|mut c| std::mem::take(&mut *c))
+            .unwrap_or_default();
+        for child in children {
+            child.cancel();
+        }
+    }
+
+    /// Whether the token has been cancelled.
+    pub fn is_cancelled(&self) -> bool {
+        self.state.cancelled.load(Ordering::SeqCst)
+    }
+
+    /// Wait until the token is cancelled.
+    pub async fn cancelled(&self) {
+        loop {
+            let notified = self.state.notify.notified();
+            if self.is_cancelled() {
+                return;
+            }
+            notified.await;
+        }
+    }
+
+    /// Return `Err(Cancelled)` if the token has been cancelled.
+    pub fn check(&self) -> Result<(), Cancelled> {
+        if self.is_cancelled() {
+            Err(Cancelled)
+        } else {
+            Ok(())
+        }
+    }
+}
+
+/// An operation was cancelled before it finished.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct Cancelled;
+
+impl std::fmt::Display for Cancelled {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "Operation cancelled")
+    }
+}
+
+impl std::error::Error for Cancelled {}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_cancel_reaches_children() {
+        let parent = CancellationToken::new();
+        let child = parent.child_token();
+        let grandchild = child.child_token();
+        let sibling = parent.child_token();
+
+        child.cancel();
+        assert!(child.is_cancelled() && grandchild.is_cancelled());
+        assert!(!parent.is_cancelled() && !sibling.is_cancelled());
+
+        parent.cancel();
+        assert!(sibling.is_cancelled());
+        assert_eq!(parent.check(), Err(Cancelled));
+        // Children of a cancelled token start out cancelled
+        assert!(parent.child_token().is_cancelled());
+    }
+
+    #[tokio::test]
+    async fn test_cancelled_wakes_waiters() {
+        let token = CancellationToken::new();
+        let waiter = {
+            let token = token.clone();
+            tokio::spawn(async move { token.cancelled().await })
+        };
+        tokio::task::yield_now().await;
+        token.cancel();
+        waiter.await.unwrap();
+        // Already-cancelled tokens return at once
+        token.cancelled().await;
+    }
+}
@@ -14,12 +14,27 @@
 //! - `HostPriority`: Host's events always take precedence
 //! - `Merge`: Attempt to merge compatible changes
 //! - `Reject`: Reject the conflicting event
+//!
+//! # Game Policies
+//!
+//! [`GameConflictPolicy`] handles actions that are individually valid but
+//! cannot all succeed when applied together: units from different players
+//! entering the same tile, settlers founding overlapping cities, and several
+//! attacks on one defender. Ties are broken by event hash (the event ID), so
+//! every peer reaches the same outcome regardless of arrival order.
 
 use crate::delta::EntityType;
-use nostr_nations_core::events::GameEvent;
-use nostr_nations_core::types::PlayerId;
+use nostr_nations_core::events::{GameAction, GameEvent};
+use nostr_nations_core::game_state::GameState;
+use nostr_nations_core::hex::HexCoord;
+use nostr_nations_core::types::{PlayerSlot, UnitId};
 use std::collections::HashMap;
 
+/// Default minimum distance between cities founded in the same batch.
+///
+/// New cities claim their adjacent tiles, so closer cities would overlap.
+pub const DEFAULT_MIN_CITY_DISTANCE: u32 = 3;
+
 /// Types of conflicts that can occur during synchronization.
 #[derive(Clone, Debug, PartialEq, Eq)]
 pub enum ConflictType {
@@ -27,8 +42,8 @@ pub enum ConflictType {
     ConcurrentModification {
         entity_type: EntityType,
         entity_id: u64,
-        player_a: PlayerId,
-        player_b: PlayerId,
+        player_a: PlayerSlot,
+        player_b: PlayerSlot,
     },
     /// Events with the same timestamp but different content.
     TimestampCollision {
@@ -96,7 +111,7 @@ pub struct ConflictDetector {
     /// Previously seen events by ID.
     seen_events: HashMap<String, GameEvent>,
     /// Entity version tracking: (EntityType, entity_id) -> Vec<(player_id, timestamp)>.
-    entity_versions: HashMap<(EntityType, u64), Vec<(u8, u64)>>,
+    entity_versions: HashMap<(EntityType, u64), Vec<(PlayerSlot, u64)>>,
     /// Events by timestamp for collision detection.
     events_by_timestamp: HashMap<u64, Vec<String>>,
 }
@@ -219,8 +234,6 @@ impl ConflictDetector {
 
     /// Validate state transitions for an event.
     fn validate_transition(&self, event: &GameEvent) -> Option<String> {
-        use nostr_nations_core::events::GameAction;
-
         match &event.action {
             GameAction::EndTurn => {
                 // EndTurn should only be valid on the player's turn
@@ -367,7 +380,7 @@ pub struct ConflictResolver {
     /// The resolution strategy to use.
     strategy: ResolutionStrategy,
     /// The host player ID (for HostPriority strategy).
-    host_player: Option<u8>,
+    host_player: Option<PlayerSlot>,
 }
 
 impl ConflictResolver {
@@ -380,7 +393,7 @@ impl ConflictResolver {
     }
 
     /// Set the host player for HostPriority resolution.
-    pub fn with_host(mut self, host: u8) -> Self {
+    pub fn with_host(mut self, host: PlayerSlot) -> Self {
         self.host_player = Some(host);
         self
     }
@@ -391,7 +404,7 @@ impl ConflictResolver {
     }
 
     /// Get the host player if set.
-    pub fn host_player(&self) -> Option<u8> {
+    pub fn host_player(&self) -> Option<PlayerSlot> {
         self.host_player
     }
 
@@ -423,8 +436,8 @@ impl ConflictResolver {
     /// Resolve a concurrent modification conflict.
     fn resolve_concurrent_modification(
         &self,
-        _player_a: PlayerId,
-        _player_b: PlayerId,
+        _player_a: PlayerSlot,
+        _player_b: PlayerSlot,
         events: &[GameEvent],
     ) -> Resolution {
         match self.strategy {
@@ -564,60 +577,250 @@ pub fn auto_resolve_conflicts(
         .collect()
 }
 
+/// A game-specific conflict between events.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum GameConflict {
+    /// Units from different players moving onto the same tile.
+    SameDestination { tile: HexCoord, winner_id: String },
+    /// Cities founded too close to each other.
+    OverlappingCities {
+        position: HexCoord,
+        winner_id: String,
+    },
+}
+
+impl std::fmt::Display for GameConflict {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            GameConflict::SameDestination { tile, winner_id } => {
+                write!(
+                    f,
+                    "Tile ({}, {}) already claimed by event {}",
+                    tile.q, tile.r, winner_id
+                )
+            }
+            GameConflict::OverlappingCities {
+                position,
+                winner_id,
+            } => {
+                write!(
+                    f,
+                    "City at ({}, {}) overlaps city founded by event {}",
+                    position.q, position.r, winner_id
+                )
+            }
+        }
+    }
+}
+
+/// Outcome of applying game policies to a batch of events.
+#[derive(Clone, Debug, Default)]
+pub struct GamePolicyOutcome {
+    /// Surviving events in the order they should be applied.
+    pub ordered: Vec<GameEvent>,
+    /// Events that lost a conflict, with the reason.
+    pub rejected: Vec<(GameEvent, GameConflict)>,
+}
+
+impl GamePolicyOutcome {
+    /// Check if an event was rejected.
+    pub fn is_rejected(&self, event_id: &str) -> bool {
+        self.rejected.iter().any(|(e, _)| e.id == event_id)
+    }
+}
+
+/// Resolves conflicts between concurrent gameplay actions.
+#[derive(Clone, Debug)]
+pub struct GameConflictPolicy {
+    /// Minimum hex distance between cities founded concurrently.
+    min_city_distance: u32,
+}
+
+impl Default for GameConflictPolicy {
+    fn default() -> Self {
+        Self::new(DEFAULT_MIN_CITY_DISTANCE)
+    }
+}
+
+impl GameConflictPolicy {
+    /// Create a policy with the given minimum city distance.
+    pub fn new(min_city_distance: u32) -> Self {
+        Self { min_city_distance }
+    }
+
+    /// Get the minimum city distance.
+    pub fn min_city_distance(&self) -> u32 {
+        self.min_city_distance
+    }
+
+    /// Resolve a batch of concurrent events against the state they apply to.
+    ///
+    /// - **Same destination**: the move with the lowest event hash keeps the
+    ///   tile; other players' moves ending there are rejected.
+    /// - **Overlapping cities**: the founding with the lowest event hash
+    ///   wins; foundings within `min_city_distance` of it are rejected.
+    /// - **Simultaneous attacks**: all attacks on a defender are kept, but
+    ///   reordered by event hash so every peer resolves combat in the same
+    ///   order. Later attacks fail naturally if the defender is gone.
+    ///
+    /// Other events keep their original relative order.
+    pub fn resolve(&self, events: &[GameEvent], state: &GameState) -> GamePolicyOutcome {
+        let mut by_hash: Vec<usize> = (0..events.len()).collect();
+        by_hash.sort_by(|&a, &b| events[a].id.cmp(&events[b].id));
+
+        let mut rejected: HashMap<usize, GameConflict> = HashMap::new();
+        let mut claimed_tiles: HashMap<HexCoord, (PlayerSlot, usize)> = HashMap::new();
+        let mut founded: Vec<(HexCoord, usize)> = Vec::new();
+
+        for &i in &by_hash {
+            let event = &events[i];
+            match &event.action {
+                GameAction::MoveUnit { path, .. } => {
+                    let Some(tile) = path.last() else { continue };
+                    match claimed_tiles.get(tile) {
+                        Some(&(owner, winner)) if owner != event.player_id => {
+                            rejected.insert(
+                                i,
+                                GameConflict::SameDestination {
+                                    tile: *tile,
+                                    winner_id: events[winner].id.clone(),
+                                },
+                            );
+                        }
+                        Some(_) => {}
+                        None => {
+                            claimed_tiles.insert(*tile, (event.player_id, i));
+                        }
+                    }
+                }
+                GameAction::FoundCity { settler_id, .. } => {
+                    let Some(position) = settler_position(state, *settler_id) else {
+                        continue;
+                    };
+                    let clash = founded
+                        .iter()
+                        .find(|(other, _)| position.distance(other) < self.min_city_distance);
+                    match clash {
+                        Some(&(_, winner)) => {
+                            rejected.insert(
+                                i,
+                                GameConflict::OverlappingCities {
+                                    position,
+                                    winner_id: events[winner].id.clone(),
+                                },
+                            );
+                        }
+                        None => founded.push((position, i)),
+                    }
+                }
+                _ => {}
+            }
+        }
+
+        // Attacks on the same defender take their slots in hash order
+        let mut ordered: Vec<usize> = (0..events.len())
+            .filter(|i| !rejected.contains_key(i))
+            .collect();
+        let mut attacks: HashMap<UnitId, Vec<usize>> = HashMap::new();
+        for (slot, &i) in ordered.iter().enumerate() {
+            if let GameAction::AttackUnit { defender_id, .. } = &events[i].action {
+                attacks.entry(*defender_id).or_default().push(slot);
+            }
+        }
+        for slots in attacks.values() {
+            let mut indices: Vec<usize> = slots.iter().map(|&slot| ordered[slot]).collect();
+            indices.sort_by(|&a, &b| events[a].id.cmp(&events[b].id));
+            for (&slot, i) in slots.iter().zip(indices) {
+                ordered[slot] = i;
+            }
+        }
+
+        let mut rejected: Vec<(usize, GameConflict)> = rejected.into_iter().collect();
+        rejected.sort_by_key(|(i, _)| *i);
+
+        GamePolicyOutcome {
+            ordered: ordered.into_iter().map(|i| events[i].clone()).collect(),
+            rejected: rejected
+                .into_iter()
+                .map(|(i, conflict)| (events[i].clone(), conflict))
+                .collect(),
+        }
+    }
+}
+
+/// Get the position of a settler from the state.
+fn settler_position(state: &GameState, settler_id: UnitId) -> Option<HexCoord> {
+    state.units.get(&settler_id).map(|u| u.position)
+}
+
 /// Extract entity IDs affected by an event.
 fn extract_entity_ids(event: &GameEvent) -> Vec<(EntityType, u64)> {
-    use nostr_nations_core::events::GameAction;
-
     let mut entities = Vec::new();
 
     // Add player as always affected
-    entities.push((EntityType::Player, event.player_id as u64));
+    entities.push((EntityType::Player, event.player_id.get() as u64));
 
     match &event.action {
         GameAction::FoundCity { settler_id, .. } => {
-            entities.push((EntityType::Unit, *settler_id));
+            entities.push((EntityType::Unit, settler_id.get()));
         }
         GameAction::MoveUnit { unit_id, .. }
         | GameAction::FortifyUnit { unit_id }
+        | GameAction::FortifyUntilHealed { unit_id }
         | GameAction::SleepUnit { unit_id }
         | GameAction::WakeUnit { unit_id }
         | GameAction::DeleteUnit { unit_id }
         | GameAction::UpgradeUnit { unit_id, .. }
+        | GameAction::ChoosePromotion { unit_id, .. }
         | GameAction::BuildImprovement { unit_id, .. }
         | GameAction::BuildRoad { unit_id }
         | GameAction::RemoveFeature { unit_id } => {
-            entities.push((EntityType::Unit, *unit_id));
+            entities.push((EntityType::Unit, unit_id.get()));
         }
         GameAction::AttackUnit {
             attacker_id,
             defender_id,
             ..
         } => {
-            entities.push((EntityType::Unit, *attacker_id));
-            entities.push((EntityType::Unit, *defender_id));
+            entities.push((EntityType::Unit, attacker_id.get()));
+            entities.push((EntityType::Unit, defender_id.get()));
         }
         GameAction::AttackCity {
             attacker_id,
             city_id,
             ..
         } => {
-            entities.push((EntityType::Unit, *attacker_id));
-            entities.push((EntityType::City, *city_id));
+            entities.push((EntityType::Unit, attacker_id.get()));
+            entities.push((EntityType::City, city_id.get()));
         }
         GameAction::SetProduction { city_id, .. }
         | GameAction::BuyItem { city_id, .. }
         | GameAction::AssignCitizen { city_id, .. }
         | GameAction::UnassignCitizen { city_id, .. }
         | GameAction::SellBuilding { city_id, .. } => {
-            entities.push((EntityType::City, *city_id));
+            entities.push((EntityType::City, city_id.get()));
         }
-        GameAction::DeclareWar { target_player } | GameAction::ProposePeace { target_player } => {
-            entities.push((EntityType::Player, *target_player as u64));
-            entities.push((EntityType::Diplomacy, event.player_id as u64));
+        GameAction::BuyTile { city_id, tile } => {
+            // Two cities buying the same tile conflict on the territory entity
+            let tile_id = ((tile.q as u32 as u64) << 32) | tile.r as u32 as u64;
+            entities.push((EntityType::City, city_id.get()));
+            entities.push((EntityType::Territory, tile_id));
         }
-        GameAction::AcceptPeace { from_player } | GameAction::RejectPeace { from_player } => {
-            entities.push((EntityType::Player, *from_player as u64));
-            entities.push((EntityType::Diplomacy, event.player_id as u64));
+        GameAction::DeclareWar { target_player }
+        | GameAction::ProposePeace { target_player }
+        | GameAction::ProposeTreaty { target_player, .. }
+        | GameAction::ProposeTrade {
+            to_player: target_player,
+            ..
+        } => {
+            entities.push((EntityType::Player, target_player.get() as u64));
+            entities.push((EntityType::Diplomacy, event.player_id.get() as u64));
+        }
+        GameAction::AcceptPeace { from_player }
+        | GameAction::RejectPeace { from_player }
+        | GameAction::RespondTrade { from_player, .. } => {
+            entities.push((EntityType::Player, from_player.get() as u64));
+            entities.push((EntityType::Diplomacy, event.player_id.get() as u64));
         }
         _ => {}
     }
@@ -629,11 +832,12 @@ fn extract_entity_ids(event: &GameEvent) -> Vec<(EntityType, u64)> {
 mod tests {
     use super::*;
     use nostr_nations_core::events::GameAction;
+    use nostr_nations_core::types::{CityId, GameId};
 
     fn create_test_event(id: &str, player_id: u8, timestamp: u64, prev: Option<&str>) -> GameEvent {
         let mut event = GameEvent::new(
-            "test_game".to_string(),
-            player_id,
+            GameId::new("test_game"),
+            PlayerSlot(player_id),
             prev.map(|s| s.to_string()),
             1,
             1,
@@ -646,13 +850,13 @@ mod tests {
 
     fn create_unit_move_event(id: &str, player_id: u8, unit_id: u64, timestamp: u64) -> GameEvent {
         let mut event = GameEvent::new(
-            "test_game".to_string(),
-            player_id,
+            GameId::new("test_game"),
+            PlayerSlot(player_id),
             None,
             1,
             1,
             GameAction::MoveUnit {
-                unit_id,
+                unit_id: UnitId(unit_id),
                 path: vec![],
             },
         );
@@ -668,8 +872,8 @@ mod tests {
         let conflict = ConflictType::ConcurrentModification {
             entity_type: EntityType::Unit,
             entity_id: 42,
-            player_a: 1,
-            player_b: 2,
+            player_a: PlayerSlot(1),
+            player_b: PlayerSlot(2),
         };
         let display = format!("{}", conflict);
         assert!(display.contains("Unit"));
@@ -709,20 +913,20 @@ mod tests {
         let c1 = ConflictType::ConcurrentModification {
             entity_type: EntityType::Unit,
             entity_id: 1,
-            player_a: 1,
-            player_b: 2,
+            player_a: PlayerSlot(1),
+            player_b: PlayerSlot(2),
         };
         let c2 = ConflictType::ConcurrentModification {
             entity_type: EntityType::Unit,
             entity_id: 1,
-            player_a: 1,
-            player_b: 2,
+            player_a: PlayerSlot(1),
+            player_b: PlayerSlot(2),
         };
         let c3 = ConflictType::ConcurrentModification {
             entity_type: EntityType::City,
             entity_id: 1,
-            player_a: 1,
-            player_b: 2,
+            player_a: PlayerSlot(1),
+            player_b: PlayerSlot(2),
         };
 
         assert_eq!(c1, c2);
@@ -809,12 +1013,14 @@ mod tests {
 
         // Check event with same timestamp but different action (different description)
         let mut evt2 = GameEvent::new(
-            "test_game".to_string(),
-            1,
+            GameId::new("test_game"),
+            PlayerSlot(1),
             None,
             1,
             1,
-            GameAction::FortifyUnit { unit_id: 42 },
+            GameAction::FortifyUnit {
+                unit_id: UnitId(42),
+            },
         );
         evt2.id = "evt2".to_string();
         evt2.timestamp = 1000;
@@ -873,8 +1079,8 @@ mod tests {
 
         // Add game start event
         let mut evt1 = GameEvent::new(
-            "test_game".to_string(),
-            0,
+            GameId::new("test_game"),
+            PlayerSlot(0),
             None,
             0,
             1,
@@ -886,8 +1092,8 @@ mod tests {
 
         // Try to start again
         let mut evt2 = GameEvent::new(
-            "test_game".to_string(),
-            0,
+            GameId::new("test_game"),
+            PlayerSlot(0),
             Some("evt1".to_string()),
             0,
             2,
@@ -910,8 +1116,8 @@ mod tests {
 
         // Add game start event
         let mut evt1 = GameEvent::new(
-            "test_game".to_string(),
-            0,
+            GameId::new("test_game"),
+            PlayerSlot(0),
             None,
             0,
             1,
@@ -923,8 +1129,8 @@ mod tests {
 
         // Try to join after start
         let mut evt2 = GameEvent::new(
-            "test_game".to_string(),
-            1,
+            GameId::new("test_game"),
+            PlayerSlot(1),
             Some("evt1".to_string()),
             0,
             2,
@@ -950,12 +1156,14 @@ mod tests {
 
         // Delete unit 42
         let mut evt1 = GameEvent::new(
-            "test_game".to_string(),
-            0,
+            GameId::new("test_game"),
+            PlayerSlot(0),
             None,
             1,
             1,
-            GameAction::DeleteUnit { unit_id: 42 },
+            GameAction::DeleteUnit {
+                unit_id: UnitId(42),
+            },
         );
         evt1.id = "evt1".to_string();
         evt1.timestamp = 1000;
@@ -963,13 +1171,13 @@ mod tests {
 
         // Try to move deleted unit
         let mut evt2 = GameEvent::new(
-            "test_game".to_string(),
-            0,
+            GameId::new("test_game"),
+            PlayerSlot(0),
             Some("evt1".to_string()),
             1,
             2,
             GameAction::MoveUnit {
-                unit_id: 42,
+                unit_id: UnitId(42),
                 path: vec![],
             },
         );
@@ -1024,9 +1232,10 @@ mod tests {
 
     #[test]
     fn test_conflict_resolver_with_host() {
-        let resolver = ConflictResolver::new(ResolutionStrategy::HostPriority).with_host(42);
+        let resolver =
+            ConflictResolver::new(ResolutionStrategy::HostPriority).with_host(PlayerSlot(42));
         assert_eq!(resolver.strategy(), ResolutionStrategy::HostPriority);
-        assert_eq!(resolver.host_player(), Some(42));
+        assert_eq!(resolver.host_player(), Some(PlayerSlot(42)));
     }
 
     #[test]
@@ -1046,8 +1255,8 @@ mod tests {
         let conflict = ConflictType::ConcurrentModification {
             entity_type: EntityType::Unit,
             entity_id: 42,
-            player_a: 1,
-            player_b: 2,
+            player_a: PlayerSlot(1),
+            player_b: PlayerSlot(2),
         };
 
         let resolution = resolver.resolve(&conflict, &events);
@@ -1065,8 +1274,8 @@ mod tests {
         let conflict = ConflictType::ConcurrentModification {
             entity_type: EntityType::Unit,
             entity_id: 42,
-            player_a: 1,
-            player_b: 2,
+            player_a: PlayerSlot(1),
+            player_b: PlayerSlot(2),
         };
 
         let resolution = resolver.resolve(&conflict, &events);
@@ -1075,7 +1284,8 @@ mod tests {
 
     #[test]
     fn test_resolve_concurrent_modification_host_priority() {
-        let resolver = ConflictResolver::new(ResolutionStrategy::HostPriority).with_host(2);
+        let resolver =
+            ConflictResolver::new(ResolutionStrategy::HostPriority).with_host(PlayerSlot(2));
 
         let evt1 = create_test_event("evt_a", 1, 1000, None); // Not host
         let evt2 = create_test_event("evt_b", 2, 2000, None); // Host
@@ -1084,8 +1294,8 @@ mod tests {
         let conflict = ConflictType::ConcurrentModification {
             entity_type: EntityType::Unit,
             entity_id: 42,
-            player_a: 1,
-            player_b: 2,
+            player_a: PlayerSlot(1),
+            player_b: PlayerSlot(2),
         };
 
         let resolution = resolver.resolve(&conflict, &events);
@@ -1094,7 +1304,8 @@ mod tests {
 
     #[test]
     fn test_resolve_concurrent_modification_host_priority_no_host_event() {
-        let resolver = ConflictResolver::new(ResolutionStrategy::HostPriority).with_host(99);
+        let resolver =
+            ConflictResolver::new(ResolutionStrategy::HostPriority).with_host(PlayerSlot(99));
 
         let evt1 = create_test_event("evt_a", 1, 1000, None);
         let evt2 = create_test_event("evt_b", 2, 2000, None);
@@ -1103,8 +1314,8 @@ mod tests {
         let conflict = ConflictType::ConcurrentModification {
             entity_type: EntityType::Unit,
             entity_id: 42,
-            player_a: 1,
-            player_b: 2,
+            player_a: PlayerSlot(1),
+            player_b: PlayerSlot(2),
         };
 
         // Falls back to first wins
@@ -1123,8 +1334,8 @@ mod tests {
         let conflict = ConflictType::ConcurrentModification {
             entity_type: EntityType::Unit,
             entity_id: 42,
-            player_a: 1,
-            player_b: 2,
+            player_a: PlayerSlot(1),
+            player_b: PlayerSlot(2),
         };
 
         // Rejects the later event
@@ -1210,8 +1421,8 @@ mod tests {
             ConflictType::ConcurrentModification {
                 entity_type: EntityType::Unit,
                 entity_id: 42,
-                player_a: 1,
-                player_b: 2,
+                player_a: PlayerSlot(1),
+                player_b: PlayerSlot(2),
             },
             ConflictType::MissingPredecessor {
                 event_id: "evt3".to_string(),
@@ -1247,14 +1458,14 @@ mod tests {
     #[test]
     fn test_extract_entity_ids_attack_unit() {
         let mut event = GameEvent::new(
-            "test_game".to_string(),
-            1,
+            GameId::new("test_game"),
+            PlayerSlot(1),
             None,
             1,
             1,
             GameAction::AttackUnit {
-                attacker_id: 10,
-                defender_id: 20,
+                attacker_id: UnitId(10),
+                defender_id: UnitId(20),
                 random: 0.5,
             },
         );
@@ -1273,13 +1484,13 @@ mod tests {
         use nostr_nations_core::unit::UnitType;
 
         let mut event = GameEvent::new(
-            "test_game".to_string(),
-            1,
+            GameId::new("test_game"),
+            PlayerSlot(1),
             None,
             1,
             1,
             GameAction::SetProduction {
-                city_id: 5,
+                city_id: CityId(5),
                 item: ProductionItem::Unit(UnitType::Warrior),
             },
         );
@@ -1294,12 +1505,14 @@ mod tests {
     #[test]
     fn test_extract_entity_ids_diplomacy() {
         let mut event = GameEvent::new(
-            "test_game".to_string(),
-            1,
+            GameId::new("test_game"),
+            PlayerSlot(1),
             None,
             1,
             1,
-            GameAction::DeclareWar { target_player: 2 },
+            GameAction::DeclareWar {
+                target_player: PlayerSlot(2),
+            },
         );
         event.id = "evt1".to_string();
 
@@ -1347,12 +1560,14 @@ mod tests {
         // Create an event with both missing predecessor and timestamp collision
         // Use a different action to trigger timestamp collision (different description)
         let mut evt2 = GameEvent::new(
-            "test_game".to_string(),
-            1,
+            GameId::new("test_game"),
+            PlayerSlot(1),
             Some("missing".to_string()),
             1,
             1,
-            GameAction::FortifyUnit { unit_id: 42 },
+            GameAction::FortifyUnit {
+                unit_id: UnitId(42),
+            },
         );
         evt2.id = "evt2".to_string();
         evt2.timestamp = 1000;
@@ -1368,4 +1583,218 @@ mod tests {
             .iter()
             .any(|c| matches!(c, ConflictType::TimestampCollision { .. })));
     }
+
+    // ==================== GameConflictPolicy Tests ====================
+
+    fn create_action_event(id: &str, player_id: PlayerSlot, action: GameAction) -> GameEvent {
+        let mut event = GameEvent::new(GameId::new("test_game"), player_id, None, 1, 1, action);
+        event.id = id.to_string();
+        event
+    }
+
+    fn move_to(id: &str, player_id: PlayerSlot, unit_id: UnitId, q: i32, r: i32) -> GameEvent {
+        create_action_event(
+            id,
+            player_id,
+            GameAction::MoveUnit {
+                unit_id,
+                path: vec![HexCoord::new(q, r)],
+            },
+        )
+    }
+
+    fn attack(
+        id: &str,
+        player_id: PlayerSlot,
+        attacker_id: UnitId,
+        defender_id: UnitId,
+    ) -> GameEvent {
+        create_action_event(
+            id,
+            player_id,
+            GameAction::AttackUnit {
+                attacker_id,
+                defender_id,
+                random: 0.5,
+            },
+        )
+    }
+
+    fn found_city(id: &str, player_id: PlayerSlot, settler_id: UnitId) -> GameEvent {
+        create_action_event(
+            id,
+            player_id,
+            GameAction::FoundCity {
+                settler_id,
+                name: format!("City {}", settler_id),
+            },
+        )
+    }
+
+    fn state_with_settlers(settlers: &[(UnitId, PlayerSlot, HexCoord)]) -> GameState {
+        use nostr_nations_core::settings::GameSettings;
+        use nostr_nations_core::unit::{Unit, UnitType};
+
+        let mut state = GameState::new(
+            GameId::new("test_game"),
+            GameSettings::new("Test".to_string()),
+            [0u8; 32],
+        );
+        for &(id, owner, position) in settlers {
+            state
+                .units
+                .insert(id, Unit::new(id, owner, UnitType::Settler, position));
+        }
+        state
+    }
+
+    fn ids(events: &[GameEvent]) -> Vec<&str> {
+        events.iter().map(|e| e.id.as_str()).collect()
+    }
+
+    #[test]
+    fn test_policy_same_destination_lowest_hash_wins() {
+        let state = state_with_settlers(&[]);
+        let events = vec![
+            move_to("bbb", PlayerSlot(0), UnitId(1), 2, 2),
+            move_to("aaa", PlayerSlot(1), UnitId(2), 2, 2),
+        ];
+
+        let outcome = GameConflictPolicy::default().resolve(&events, &state);
+
+        assert_eq!(ids(&outcome.ordered), vec!["aaa"]);
+        assert_eq!(outcome.rejected.len(), 1);
+        assert_eq!(outcome.rejected[0].0.id, "bbb");
+        assert_eq!(
+            outcome.rejected[0].1,
+            GameConflict::SameDestination {
+                tile: HexCoord::new(2, 2),
+                winner_id: "aaa".to_string(),
+            }
+        );
+    }
+
+    #[test]
+    fn test_policy_same_destination_independent_of_arrival_order() {
+        let state = state_with_settlers(&[]);
+        let forward = vec![
+            move_to("aaa", PlayerSlot(0), UnitId(1), 2, 2),
+            move_to("bbb", PlayerSlot(1), UnitId(2), 2, 2),
+        ];
+        let reverse = vec![
+            move_to("bbb", PlayerSlot(1), UnitId(2), 2, 2),
+            move_to("aaa", PlayerSlot(0), UnitId(1), 2, 2),
+        ];
+
+        let policy = GameConflictPolicy::default();
+        assert_eq!(ids(&policy.resolve(&forward, &state).ordered), vec!["aaa"]);
+        assert_eq!(ids(&policy.resolve(&reverse, &state).ordered), vec!["aaa"]);
+    }
+
+    #[test]
+    fn test_policy_same_player_can_share_destination() {
+        let state = state_with_settlers(&[]);
+        let events = vec![
+            move_to("aaa", PlayerSlot(0), UnitId(1), 2, 2),
+            move_to("bbb", PlayerSlot(0), UnitId(2), 2, 2),
+        ];
+
+        let outcome = GameConflictPolicy::default().resolve(&events, &state);
+        assert!(outcome.rejected.is_empty());
+        assert_eq!(ids(&outcome.ordered), vec!["aaa", "bbb"]);
+    }
+
+    #[test]
+    fn test_policy_different_destinations_no_conflict() {
+        let state = state_with_settlers(&[]);
+        let events = vec![
+            move_to("aaa", PlayerSlot(0), UnitId(1), 2, 2),
+            move_to("bbb", PlayerSlot(1), UnitId(2), 3, 2),
+        ];
+
+        let outcome = GameConflictPolicy::default().resolve(&events, &state);
+        assert!(outcome.rejected.is_empty());
+    }
+
+    #[test]
+    fn test_policy_overlapping_cities() {
+        let state = state_with_settlers(&[
+            (UnitId(10), PlayerSlot(0), HexCoord::new(0, 0)),
+            (UnitId(20), PlayerSlot(1), HexCoord::new(2, 0)),
+        ]);
+        let events = vec![
+            found_city("ccc", PlayerSlot(0), UnitId(10)),
+            found_city("abc", PlayerSlot(1), UnitId(20)),
+        ];
+
+        let outcome = GameConflictPolicy::default().resolve(&events, &state);
+
+        assert_eq!(ids(&outcome.ordered), vec!["abc"]);
+        assert!(outcome.is_rejected("ccc"));
+        assert!(matches!(
+            &outcome.rejected[0].1,
+            GameConflict::OverlappingCities { winner_id, .. } if winner_id == "abc"
+        ));
+    }
+
+    #[test]
+    fn test_policy_distant_cities_both_founded() {
+        let state = state_with_settlers(&[
+            (UnitId(10), PlayerSlot(0), HexCoord::new(0, 0)),
+            (UnitId(20), PlayerSlot(1), HexCoord::new(3, 0)),
+        ]);
+        let events = vec![
+            found_city("ccc", PlayerSlot(0), UnitId(10)),
+            found_city("abc", PlayerSlot(1), UnitId(20)),
+        ];
+
+        let outcome = GameConflictPolicy::default().resolve(&events, &state);
+        assert!(outcome.rejected.is_empty());
+        assert_eq!(ids(&outcome.ordered), vec!["ccc", "abc"]);
+    }
+
+    #[test]
+    fn test_policy_custom_city_distance() {
+        let state = state_with_settlers(&[
+            (UnitId(10), PlayerSlot(0), HexCoord::new(0, 0)),
+            (UnitId(20), PlayerSlot(1), HexCoord::new(3, 0)),
+        ]);
+        let events = vec![
+            found_city("ccc", PlayerSlot(0), UnitId(10)),
+            found_city("abc", PlayerSlot(1), UnitId(20)),
+        ];
+
+        let policy = GameConflictPolicy::new(4);
+        assert_eq!(policy.min_city_distance(), 4);
+        assert!(policy.resolve(&events, &state).is_rejected("ccc"));
+    }
+
+    #[test]
+    fn test_policy_simultaneous_attacks_ordered_by_hash() {
+        let state = state_with_settlers(&[]);
+        let events = vec![
+            attack("ccc", PlayerSlot(0), UnitId(1), UnitId(99)),
+            move_to("mmm", PlayerSlot(2), UnitId(5), 7, 7),
+            attack("aaa", PlayerSlot(1), UnitId(2), UnitId(99)),
+            attack("bbb", PlayerSlot(0), UnitId(3), UnitId(42)),
+        ];
+
+        let outcome = GameConflictPolicy::default().resolve(&events, &state);
+
+        // Attacks on 99 swap slots; other events keep their positions
+        assert!(outcome.rejected.is_empty());
+        assert_eq!(ids(&outcome.ordered), vec!["aaa", "mmm", "ccc", "bbb"]);
+    }
+
+    #[test]
+    fn test_game_conflict_display() {
+        let conflict = GameConflict::SameDestination {
+            tile: HexCoord::new(1, 2),
+            winner_id: "abc".to_string(),
+        };
+        assert_eq!(
+            conflict.to_string(),
+            "Tile (1, 2) already claimed by event abc"
+        );
+    }
 }
@@ -0,0 +1,526 @@
+//! Network diagnostics for the in-game debug overlay.
+//!
+//! The network modules are instrumented with `tracing` spans that share a
+//! small set of field names ([`GAME_ID`], [`PEER_ID`], [`EVENT_ID`]).
+//! A [`DebugRecorder`] collects closed spans from this crate through a
+//! [`DebugLayer`], keeps the most recent ones in a ring buffer along with
+//! per-span timing summaries and ad-hoc metrics, and produces a
+//! [`NetworkDebugReport`] that the Bevy and Tauri layers can render as an
+//! overlay.
+//!
+//! ```ignore
+//! let recorder = DebugRecorder::new(DEFAULT_SPAN_CAPACITY);
+//! recorder.install_global()?;
+//!
+//! // ... later, when the overlay is open
+//! let report = recorder.report();
+//! for line in report.overlay_lines(10) {
+//!     println!("{}", line);
+//! }
+//! ```
+
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+use std::collections::{BTreeMap, HashMap, VecDeque};
+use std::fmt;
+use std::sync::{Arc, Mutex};
+use tracing::field::{Field, Visit};
+use tracing::span::{Attributes, Id, Record};
+use tracing::Subscriber;
+use tracing_subscriber::layer::{Context, Layer};
+use tracing_subscriber::prelude::*;
+use ts_rs::TS;
+use web_time::Instant;
+
+/// Span field carrying the game ID.
+pub const GAME_ID: &str = "game_id";
+/// Span field carrying the remote peer ID.
+pub const PEER_ID: &str = "peer_id";
+/// Span field carrying the event ID.
+pub const EVENT_ID: &str = "event_id";
+
+/// Default number of recent spans kept by a recorder.
+pub const DEFAULT_SPAN_CAPACITY: usize = 256;
+
+/// Only spans from this crate are recorded.
+const TARGET_PREFIX: &str = "nostr_nations_network";
+
+/// A closed span captured by the recorder.
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize, JsonSchema, TS)]
+pub struct SpanRecord {
+    /// Span name (e.g. "peer.handle_message").
+    pub name: String,
+    /// Module path the span was created in.
+    pub target: String,
+    /// Game ID field, if recorded.
+    pub game_id: Option<String>,
+    /// Peer ID field, if recorded.
+    pub peer_id: Option<String>,
+    /// Event ID field, if recorded.
+    pub event_id: Option<String>,
+    /// Time from creation to close, in microseconds.
+    pub duration_us: u64,
+    /// Unix timestamp (ms) when the span closed.
+    pub closed_at: u64,
+}
+
+/// Aggregated timings for all spans with the same name.
+#[derive(Clone, Debug, Default, PartialEq, Serialize, Deserialize, JsonSchema, TS)]
+pub struct SpanSummary {
+    /// Span name.
+    pub name: String,
+    /// Number of spans closed.
+    pub count: u64,
+    /// Total duration in microseconds.
+    pub total_us: u64,
+    /// Longest duration in microseconds.
+    pub max_us: u64,
+}
+
+impl SpanSummary {
+    /// Average duration in microseconds.
+    pub fn average_us(&self) -> u64 {
+        self.total_us.checked_div(self.count).unwrap_or(0)
+    }
+
+    fn record(&mut self, duration_us: u64) {
+        self.count += 1;
+        self.total_us += duration_us;
+        self.max_us = self.max_us.max(duration_us);
+    }
+}
+
+/// Snapshot of recent network activity for the debug overlay.
+#[derive(Clone, Debug, Default, Serialize, Deserialize, JsonSchema, TS)]
+pub struct NetworkDebugReport {
+    /// Unix timestamp (ms) the report was generated.
+    pub generated_at: u64,
+    /// Recent spans, newest first.
+    pub recent_spans: Vec<SpanRecord>,
+    /// Timing summaries, sorted by span name.
+    pub summaries: Vec<SpanSummary>,
+    /// Named metrics (queue depths, peer counts, ...).
+    pub metrics: BTreeMap<String, f64>,
+}
+
+impl NetworkDebugReport {
+    /// Get recent spans recorded for a game.
+    pub fn spans_for_game<'a>(&'a self, game_id: &'a str) -> impl Iterator<Item = &'a SpanRecord> {
+        self.recent_spans
+            .iter()
+            .filter(move |s| s.game_id.as_deref() == Some(game_id))
+    }
+
+    /// Get recent spans recorded for a peer.
+    pub fn spans_for_peer<'a>(&'a self, peer_id: &'a str) -> impl Iterator<Item = &'a SpanRecord> {
+        self.recent_spans
+            .iter()
+            .filter(move |s| s.peer_id.as_deref() == Some(peer_id))
+    }
+
+    /// Render the report as plain text lines for an overlay.
+    ///
+    /// Metrics come first, then span summaries, then up to `max_spans`
+    /// of the most recent spans.
+    pub fn overlay_lines(&self, max_spans: usize) -> Vec<String> {
+        let mut lines = Vec::new();
+
+        for (name, value) in &self.metrics {
+            lines.push(format!("{}: {}", name, value));
+        }
+
+        for summary in &self.summaries {
+            lines.push(format!(
+                "{} x{} avg {}us max {}us",
+                summary.name,
+                summary.count,
+                summary.average_us(),
+                summary.max_us
+            ));
+        }
+
+        for span in self.recent_spans.iter().take(max_spans) {
+            let mut line = format!("{} {}us", span.name, span.duration_us);
+            for (field, value) in [(PEER_ID, &span.peer_id), (EVENT_ID, &span.event_id)] {
+                if let Some(value) = value {
+                    line.push_str(&format!(" {}={}", field, value));
+                }
+            }
+            lines.push(line);
+        }
+
+        lines
+    }
+}
+
+#[derive(Debug, Default)]
+struct RecorderState {
+    spans: VecDeque<SpanRecord>,
+    summaries: HashMap<String, SpanSummary>,
+    metrics: BTreeMap<String, f64>,
+}
+
+/// Collects spans and metrics for [`NetworkDebugReport`]s.
+///
+/// Cloning a recorder shares the underlying buffer.
+#[derive(Clone, Debug)]
+pub struct DebugRecorder {
+    state: Arc<Mutex<RecorderState>>,
+    capacity: usize,
+}
+
+impl DebugRecorder {
+    /// Create a recorder keeping up to `capacity` recent spans.
+    pub fn new(capacity: usize) -> Self {
+        Self {
+            state: Arc::new(Mutex::new(RecorderState::default())),
+            capacity: capacity.max(1),
+        }
+    }
+
+    /// Create a `tracing` layer that feeds this recorder.
+    pub fn layer(&self) -> DebugLayer {
+        DebugLayer {
+            recorder: self.clone(),
+            open: Mutex::new(HashMap::new()),
+        }
+    }
+
+    /// Install this recorder as the global `tracing` subscriber.
+    pub fn install_global(&self) -> Result<(), tracing::subscriber::SetGlobalDefaultError> {
+        tracing::subscriber::set_global_default(tracing_subscriber::registry().with(self.layer()))
+    }
+
+    /// Record a closed span.
+    pub fn record_span(&self, span: SpanRecord) {
+        let Ok(mut state) = self.state.lock() else {
+            return;
+        };
+        state
+            .summaries
+            .entry(span.name.clone())
+            .or_insert_with(|| SpanSummary {
+                name: span.name.clone(),
+                ..Default::default()
+            })
+            .record(span.duration_us);
+        if state.spans.len() >= self.capacity {
+            state.spans.pop_front();
+        }
+        state.spans.push_back(span);
+    }
+
+    /// Set a named metric shown in the report.
+    pub fn set_metric(&self, name: impl Into<String>, value: f64) {
+        if let Ok(mut state) = self.state.lock() {
+            state.metrics.insert(name.into(), value);
+        }
+    }
+
+    /// Number of spans currently buffered.
+    pub fn span_count(&self) -> usize {
+        self.state.lock().map(|s| s.spans.len()).unwrap_or(0)
+    }
+
+    /// Drop all buffered spans, summaries and metrics.
+    pub fn clear(&self) {
+        if let Ok(mut state) = self.state.lock() {
+            *state = RecorderState::default();
+        }
+    }
+
+    /// Build a report from the current buffer.
+    pub fn report(&self) -> NetworkDebugReport {
+        let Ok(state) = self.state.lock() else {
+            return NetworkDebugReport::default();
+        };
+
+        let mut summaries: Vec<SpanSummary> = state.summaries.values().cloned().collect();
+        summaries.sort_by(|a, b| a.name.cmp(&b.name));
+
+        NetworkDebugReport {
+            generated_at: now_ms(),
+            recent_spans: state.spans.iter().rev().cloned().collect(),
+            summaries,
+            metrics: state.metrics.clone(),
+        }
+    }
+}
+
+impl Default for DebugRecorder {
+    fn default() -> Self {
+        Self::new(DEFAULT_SPAN_CAPACITY)
+    }
+}
+
+/// A span that has been created but not yet closed.
+struct OpenSpan {
+    record: SpanRecord,
+    started: Instant,
+}
+
+impl Visit for OpenSpan {
+    fn record_str(&mut self, field: &Field, value: &str) {
+        self.set(field, value.to_string());
+    }
+
+    fn record_debug(&mut self, field: &Field, value: &dyn fmt::Debug) {
+        self.set(field, format!("{:?}", value));
+    }
+}
+
+impl OpenSpan {
+    fn set(&mut self, field: &Field, value: String) {
+        match field.name() {
+            GAME_ID => self.record.game_id = Some(value),
+            PEER_ID => self.record.peer_id = Some(value),
+            EVENT_ID => self.record.event_id = Some(value),
+            _ => {}
+        }
+    }
+}
+
+/// `tracing` layer that forwards this crate's spans to a [`DebugRecorder`].
+pub struct DebugLayer {
+    recorder: DebugRecorder,
+    open: Mutex<HashMap<Id, OpenSpan>>,
+}
+
+impl<S: Subscriber> Layer<S> for DebugLayer {
+    fn on_new_span(&self, attrs: &Attributes<'_>, id: &Id, _ctx: Context<'_, S>) {
+        let metadata = attrs.metadata();
+        if !metadata.target().starts_with(TARGET_PREFIX) {
+            return;
+        }
+
+        let mut span = OpenSpan {
+            record: SpanRecord {
+                name: metadata.name().to_string(),
+                target: metadata.target().to_string(),
+                game_id: None,
+                peer_id: None,
+                event_id: None,
+                duration_us: 0,
+                closed_at: 0,
+            },
+            started: Instant::now(),
+        };
+        attrs.record(&mut span);
+
+        if let Ok(mut open) = self.open.lock() {
+            open.insert(id.clone(), span);
+        }
+    }
+
+    fn on_record(&self, id: &Id, values: &Record<'_>, _ctx: Context<'_, S>) {
+        if let Ok(mut open) = self.open.lock() {
+            if let Some(span) = open.get_mut(id) {
+                values.record(span);
+            }
+        }
+    }
+
+    fn on_close(&self, id: Id, _ctx: Context<'_, S>) {
+        let span = match self.open.lock() {
+            Ok(mut open) => open.remove(&id),
+            Err(_) => None,
+        };
+        if let Some(mut span) = span {
+            span.record.duration_us = span.started.elapsed().as_micros() as u64;
+            span.record.closed_at = now_ms();
+            self.recorder.record_span(span.record);
+        }
+    }
+}
+
+fn now_ms() -> u64 {
+    web_time::SystemTime::now()
+        .duration_since(web_time::UNIX_EPOCH)
+        .map(|d| d.as_millis() as u64)
+        .unwrap_or(0)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::batch::EventBatcher;
+    use crate::peer::{PeerId, PeerManager, PeerMessage};
+    use crate::sync::{SyncManager, SyncResponse};
+    use nostr_nations_core::events::{GameAction, GameEvent};
+    use nostr_nations_core::types::{GameId, PlayerSlot};
+
+    fn record(name: &str, duration_us: u64) -> SpanRecord {
+        SpanRecord {
+            name: name.to_string(),
+            target: TARGET_PREFIX.to_string(),
+            game_id: Some("game1".to_string()),
+            peer_id: None,
+            event_id: None,
+            duration_us,
+            closed_at: 0,
+        }
+    }
+
+    fn subscriber(recorder: &DebugRecorder) -> impl Subscriber {
+        tracing_subscriber::registry().with(recorder.layer())
+    }
+
+    // ==================== DebugRecorder Tests ====================
+
+    #[test]
+    fn test_recorder_ring_buffer() {
+        let recorder = DebugRecorder::new(2);
+        recorder.record_span(record("a", 10));
+        recorder.record_span(record("b", 20));
+        recorder.record_span(record("c", 30));
+
+        assert_eq!(recorder.span_count(), 2);
+        let report = recorder.report();
+        assert_eq!(report.recent_spans[0].name, "c");
+        assert_eq!(report.recent_spans[1].name, "b");
+        // Summaries outlive the ring buffer
+        assert_eq!(report.summaries.len(), 3);
+    }
+
+    #[test]
+    fn test_recorder_summaries() {
+        let recorder = DebugRecorder::default();
+        recorder.record_span(record("sync", 10));
+        recorder.record_span(record("sync", 30));
+
+        let report = recorder.report();
+        let summary = &report.summaries[0];
+        assert_eq!(summary.count, 2);
+        assert_eq!(summary.average_us(), 20);
+        assert_eq!(summary.max_us, 30);
+    }
+
+    #[test]
+    fn test_recorder_metrics_and_clear() {
+        let recorder = DebugRecorder::default();
+        recorder.set_metric("peers", 3.0);
+        recorder.record_span(record("sync", 10));
+
+        assert_eq!(recorder.report().metrics.get("peers"), Some(&3.0));
+
+        recorder.clear();
+        let report = recorder.report();
+        assert!(report.metrics.is_empty());
+        assert!(report.recent_spans.is_empty());
+    }
+
+    // ==================== DebugLayer Tests ====================
+
+    #[test]
+    fn test_layer_captures_span_fields() {
+        let recorder = DebugRecorder::default();
+
+        tracing::subscriber::with_default(subscriber(&recorder), || {
+            let span = tracing::info_span!(
+                "test.span",
+                game_id = "game1",
+                peer_id = tracing::field::Empty,
+                event_id = tracing::field::Empty
+            );
+            span.record("peer_id", "peer1");
+            let _enter = span.enter();
+        });
+
+        let report = recorder.report();
+        assert_eq!(report.recent_spans.len(), 1);
+        let span = &report.recent_spans[0];
+        assert_eq!(span.name, "test.span");
+        assert_eq!(span.game_id.as_deref(), Some("game1"));
+        assert_eq!(span.peer_id.as_deref(), Some("peer1"));
+        assert!(span.event_id.is_none());
+    }
+
+    #[test]
+    fn test_sync_and_batch_are_instrumented() {
+        let recorder = DebugRecorder::default();
+
+        tracing::subscriber::with_default(subscriber(&recorder), || {
+            let mut sync = SyncManager::new(GameId::new("game1"), 0);
+            sync.create_request();
+            sync.handle_response(SyncResponse {
+                game_id: GameId::new("game1"),
+                has_more: false,
+                events: Vec::new(),
+                current_turn: 1,
+                chain_hash: None,
+            });
+
+            let mut batcher = EventBatcher::with_defaults();
+            batcher.add_event(GameEvent::new(
+                GameId::new("game1"),
+                PlayerSlot(0),
+                None,
+                1,
+                1,
+                GameAction::EndTurn,
+            ));
+            batcher.flush();
+        });
+
+        let report = recorder.report();
+        assert_eq!(report.spans_for_game("game1").count(), 2);
+        assert!(report
+            .summaries
+            .iter()
+            .any(|s| s.name == "sync.handle_response"));
+        assert!(report.summaries.iter().any(|s| s.name == "batch.flush"));
+    }
+
+    #[tokio::test]
+    async fn test_peer_messages_are_instrumented() {
+        let recorder = DebugRecorder::default();
+        let _guard = tracing::subscriber::set_default(subscriber(&recorder));
+
+        let manager = PeerManager::new(PeerId::from("node"), GameId::new("game1"), true);
+        manager
+            .handle_message("peer1", PeerMessage::Ping { timestamp: 1 })
+            .await;
+
+        let report = recorder.report();
+        let span = report.spans_for_peer("peer1").next().expect("peer span");
+        assert_eq!(span.name, "peer.handle_message");
+        assert_eq!(span.game_id.as_deref(), Some("game1"));
+    }
+
+    #[test]
+    fn test_layer_ignores_foreign_targets() {
+        let recorder = DebugRecorder::default();
+
+        tracing::subscriber::with_default(subscriber(&recorder), || {
+            let _span = tracing::info_span!(target: "bevy_render", "frame").entered();
+        });
+
+        assert_eq!(recorder.span_count(), 0);
+    }
+
+    // ==================== NetworkDebugReport Tests ====================
+
+    #[test]
+    fn test_overlay_lines() {
+        let recorder = DebugRecorder::default();
+        recorder.set_metric("pending_events", 4.0);
+        let mut span = record("peer.handle_message", 12);
+        span.peer_id = Some("peer1".to_string());
+        recorder.record_span(span);
+
+        let lines = recorder.report().overlay_lines(5);
+        assert_eq!(lines[0], "pending_events: 4");
+        assert!(lines[1].starts_with("peer.handle_message x1"));
+        assert_eq!(lines[2], "peer.handle_message 12us peer_id=peer1");
+    }
+
+    #[test]
+    fn test_report_serialization() {
+        let recorder = DebugRecorder::default();
+        recorder.record_span(record("sync", 10));
+
+        let json = serde_json::to_string(&recorder.report()).unwrap();
+        let restored: NetworkDebugReport = serde_json::from_str(&json).unwrap();
+        assert_eq!(restored.recent_spans.len(), 1);
+    }
+}
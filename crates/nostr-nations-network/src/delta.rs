@@ -2,10 +2,34 @@
 //!
 //! This module provides functionality to track changes and sync only
 //! modified data instead of full state transfers.
+//!
+//! # Keyframes
+//!
+//! Deltas recorded with [`DeltaSyncManager::record_delta`] are kept in a
+//! history log. Every `keyframe_interval` deltas the log is folded into a
+//! [`Keyframe`] holding the latest change for every live entity, and the
+//! deltas it covers are dropped. When a peer asks to sync, the manager
+//! negotiates a [`SyncPlan`]: peers within `keyframe_interval` deltas get a
+//! single compacted delta, peers further behind get the latest keyframe plus
+//! whatever happened after it.
+//!
+//! # Tile Runs
+//!
+//! Border growth and conquest change the owner of many neighbouring tiles
+//! at once. Instead of one record per tile, deltas and keyframes carry
+//! territory as [`TileRun`]s: contiguous tiles along a row that share an
+//! owner. [`encode_tile_runs`] builds them and [`TileRun::coords`] expands
+//! them again.
 
 use nostr_nations_core::events::GameEvent;
+use nostr_nations_core::hex::HexCoord;
+use nostr_nations_core::replay::ActionEffect;
+use nostr_nations_core::types::PlayerSlot;
 use serde::{Deserialize, Serialize};
-use std::collections::{HashMap, HashSet};
+use std::collections::{BTreeMap, HashMap, HashSet};
+
+/// Default number of recorded deltas between keyframes.
+pub const DEFAULT_KEYFRAME_INTERVAL: usize = 32;
 
 /// Entity types that can be tracked for delta sync.
 #[derive(Clone, Copy, Debug, Hash, PartialEq, Eq, Serialize, Deserialize)]
@@ -147,6 +171,62 @@ impl DirtyTracker {
     }
 }
 
+/// Contiguous tiles along a row that changed to the same owner.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct TileRun {
+    /// First tile of the run.
+    pub start: HexCoord,
+    /// Number of tiles, counting along the row from `start`.
+    pub len: u32,
+    /// New owner (None if the tiles became unowned).
+    pub owner: Option<PlayerSlot>,
+}
+
+impl TileRun {
+    /// Tiles covered by the run.
+    pub fn coords(&self) -> impl Iterator<Item = HexCoord> + '_ {
+        (0..self.len as i32).map(|i| HexCoord::new(self.start.q + i, self.start.r))
+    }
+}
+
+/// Encode tile owner changes as runs.
+///
+/// If a tile appears more than once, its last owner wins. Runs are ordered
+/// by row, then column.
+pub fn encode_tile_runs(
+    changes: impl IntoIterator<Item = (HexCoord, Option<PlayerSlot>)>,
+) -> Vec<TileRun> {
+    let tiles: BTreeMap<(i32, i32), Option<PlayerSlot>> = changes
+        .into_iter()
+        .map(|(coord, owner)| ((coord.r, coord.q), owner))
+        .collect();
+
+    let mut runs: Vec<TileRun> = Vec::new();
+    for ((r, q), owner) in tiles {
+        match runs.last_mut() {
+            Some(run)
+                if run.start.r == r && run.start.q + run.len as i32 == q && run.owner == owner =>
+            {
+                run.len += 1;
+            }
+            _ => runs.push(TileRun {
+                start: HexCoord::new(q, r),
+                len: 1,
+                owner,
+            }),
+        }
+    }
+    runs
+}
+
+/// Re-encode runs so later runs override earlier ones where they overlap.
+fn merge_tile_runs<'a>(runs: impl IntoIterator<Item = &'a TileRun>) -> Vec<TileRun> {
+    encode_tile_runs(
+        runs.into_iter()
+            .flat_map(|run| run.coords().map(move |coord| (coord, run.owner))),
+    )
+}
+
 /// A delta representing changes to sync.
 #[derive(Clone, Debug, Serialize, Deserialize)]
 pub struct StateDelta {
@@ -158,6 +238,9 @@ pub struct StateDelta {
     pub changes: Vec<EntityChange>,
     /// Deleted entities.
     pub deletions: Vec<EntityId>,
+    /// Territory changes, encoded as runs.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub tile_runs: Vec<TileRun>,
     /// Timestamp of the delta.
     pub timestamp: u64,
 }
@@ -170,8 +253,9 @@ impl StateDelta {
             target_version,
             changes: Vec::new(),
             deletions: Vec::new(),
-            timestamp: std::time::SystemTime::now()
-                .duration_since(std::time::UNIX_EPOCH)
+            tile_runs: Vec::new(),
+            timestamp: web_time::SystemTime::now()
+                .duration_since(web_time::UNIX_EPOCH)
                 .map(|d| d.as_millis() as u64)
                 .unwrap_or(0),
         }
@@ -187,14 +271,23 @@ impl StateDelta {
         self.deletions.push(entity_id);
     }
 
+    /// Add tile owner changes, merging them into the delta's runs.
+    pub fn add_tile_changes(
+        &mut self,
+        changes: impl IntoIterator<Item = (HexCoord, Option<PlayerSlot>)>,
+    ) {
+        let added = encode_tile_runs(changes);
+        self.tile_runs = merge_tile_runs(self.tile_runs.iter().chain(&added));
+    }
+
     /// Check if the delta is empty.
     pub fn is_empty(&self) -> bool {
-        self.changes.is_empty() && self.deletions.is_empty()
+        self.changes.is_empty() && self.deletions.is_empty() && self.tile_runs.is_empty()
     }
 
-    /// Get the number of changes.
+    /// Get the number of changes (each tile run counts once).
     pub fn change_count(&self) -> usize {
-        self.changes.len() + self.deletions.len()
+        self.changes.len() + self.deletions.len() + self.tile_runs.len()
     }
 }
 
@@ -222,14 +315,179 @@ pub enum ChangeType {
     Delete,
 }
 
+/// Full snapshot of every live entity at a given version.
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct Keyframe {
+    /// Version captured by this keyframe.
+    pub version: u64,
+    /// Latest change for each live entity.
+    pub entities: Vec<EntityChange>,
+    /// Owner of every tile that changed hands, encoded as runs.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub tile_runs: Vec<TileRun>,
+    /// Timestamp of the keyframe.
+    pub timestamp: u64,
+}
+
+impl Keyframe {
+    /// Build a keyframe by folding deltas on top of an optional base keyframe.
+    pub fn fold<'a>(
+        base: Option<&Keyframe>,
+        deltas: impl IntoIterator<Item = &'a StateDelta>,
+    ) -> Self {
+        let mut entities: Vec<Option<EntityChange>> = Vec::new();
+        let mut index: HashMap<EntityId, usize> = HashMap::new();
+        let mut version = base.map(|k| k.version).unwrap_or(0);
+        let mut timestamp = base.map(|k| k.timestamp).unwrap_or(0);
+        let mut tile_runs: Vec<&TileRun> = base
+            .map(|k| k.tile_runs.iter().collect())
+            .unwrap_or_default();
+
+        if let Some(base) = base {
+            for change in &base.entities {
+                index.insert(change.entity_id.clone(), entities.len());
+                entities.push(Some(change.clone()));
+            }
+        }
+
+        for delta in deltas {
+            for change in &delta.changes {
+                let slot = if change.change_type == ChangeType::Delete {
+                    None
+                } else {
+                    Some(change.clone())
+                };
+                match index.get(&change.entity_id) {
+                    Some(&i) => entities[i] = slot,
+                    None => {
+                        index.insert(change.entity_id.clone(), entities.len());
+                        entities.push(slot);
+                    }
+                }
+            }
+            for entity_id in &delta.deletions {
+                if let Some(&i) = index.get(entity_id) {
+                    entities[i] = None;
+                }
+            }
+            tile_runs.extend(&delta.tile_runs);
+            version = version.max(delta.target_version);
+            timestamp = timestamp.max(delta.timestamp);
+        }
+
+        Self {
+            version,
+            entities: entities.into_iter().flatten().collect(),
+            tile_runs: merge_tile_runs(tile_runs),
+            timestamp,
+        }
+    }
+
+    /// Get the snapshot of a single entity.
+    pub fn get(&self, entity_id: &EntityId) -> Option<&EntityChange> {
+        self.entities.iter().find(|c| &c.entity_id == entity_id)
+    }
+
+    /// Get the number of entities in the keyframe.
+    pub fn entity_count(&self) -> usize {
+        self.entities.len()
+    }
+}
+
+/// How a peer should be brought up to date.
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub enum SyncPlan {
+    /// Peer already has the current version.
+    UpToDate,
+    /// Peer can catch up with a single compacted delta.
+    Deltas(StateDelta),
+    /// Peer is too far behind; send the latest keyframe and anything after it.
+    Keyframe {
+        keyframe: Keyframe,
+        delta: Option<StateDelta>,
+    },
+    /// Nothing retained covers the peer; a full state transfer is needed.
+    FullSync,
+}
+
+/// Squash consecutive deltas into one, keeping only the latest change per entity.
+///
+/// Entities created and deleted within the window disappear entirely.
+/// Returns None if `deltas` is empty.
+pub fn compact_deltas(deltas: &[StateDelta]) -> Option<StateDelta> {
+    let first = deltas.first()?;
+    let last = deltas.last()?;
+
+    // (latest change or None if deleted, created within the window)
+    let mut order: Vec<EntityId> = Vec::new();
+    let mut entries: HashMap<EntityId, (Option<EntityChange>, bool)> = HashMap::new();
+
+    for delta in deltas {
+        let deleted = delta
+            .changes
+            .iter()
+            .filter(|c| c.change_type == ChangeType::Delete)
+            .map(|c| &c.entity_id)
+            .chain(delta.deletions.iter());
+
+        for change in &delta.changes {
+            if change.change_type == ChangeType::Delete {
+                continue;
+            }
+            let entry = entries.entry(change.entity_id.clone()).or_insert_with(|| {
+                order.push(change.entity_id.clone());
+                (None, change.change_type == ChangeType::Create)
+            });
+            let mut change = change.clone();
+            if entry.1 {
+                change.change_type = ChangeType::Create;
+            }
+            entry.0 = Some(change);
+        }
+
+        for entity_id in deleted {
+            let entry = entries.entry(entity_id.clone()).or_insert_with(|| {
+                order.push(entity_id.clone());
+                (None, false)
+            });
+            entry.0 = None;
+        }
+    }
+
+    let mut compacted = StateDelta::new(first.base_version, last.target_version);
+    compacted.timestamp = last.timestamp;
+    compacted.tile_runs = merge_tile_runs(deltas.iter().flat_map(|delta| &delta.tile_runs));
+    for entity_id in order {
+        match entries.remove(&entity_id) {
+            Some((Some(change), _)) => compacted.add_change(change),
+            Some((None, false)) => compacted.add_deletion(entity_id),
+            _ => {}
+        }
+    }
+    Some(compacted)
+}
+
+/// A recorded delta and how many original deltas were squashed into it.
+#[derive(Clone, Debug)]
+struct LoggedDelta {
+    delta: StateDelta,
+    merged: usize,
+}
+
 /// Manages delta synchronization between peers.
 pub struct DeltaSyncManager {
     /// Local dirty tracker.
     tracker: DirtyTracker,
     /// Last synced version per peer.
     peer_versions: HashMap<String, u64>,
-    /// Pending deltas waiting to be sent.
-    pending_deltas: Vec<StateDelta>,
+    /// Recorded deltas since the latest keyframe.
+    history: Vec<LoggedDelta>,
+    /// Latest keyframe.
+    keyframe: Option<Keyframe>,
+    /// Number of deltas between keyframes.
+    keyframe_interval: usize,
+    /// Deltas recorded since the latest keyframe.
+    deltas_since_keyframe: usize,
     /// Statistics.
     stats: DeltaSyncStats,
 }
@@ -247,19 +505,40 @@ pub struct DeltaSyncStats {
     pub delta_syncs: u64,
     /// Sync operations that required full state.
     pub full_syncs: u64,
+    /// Sync operations that fell back to a keyframe.
+    pub keyframe_syncs: u64,
+    /// Keyframes generated locally.
+    pub keyframes_created: u64,
+    /// Keyframes received from peers.
+    pub keyframes_applied: u64,
+    /// Deltas removed by compaction.
+    pub deltas_compacted: u64,
 }
 
 impl DeltaSyncManager {
     /// Create a new delta sync manager.
     pub fn new() -> Self {
+        Self::with_keyframe_interval(DEFAULT_KEYFRAME_INTERVAL)
+    }
+
+    /// Create a manager that generates a keyframe every `interval` deltas.
+    pub fn with_keyframe_interval(interval: usize) -> Self {
         Self {
             tracker: DirtyTracker::new(),
             peer_versions: HashMap::new(),
-            pending_deltas: Vec::new(),
+            history: Vec::new(),
+            keyframe: None,
+            keyframe_interval: interval.max(1),
+            deltas_since_keyframe: 0,
             stats: DeltaSyncStats::default(),
         }
     }
 
+    /// Get the number of deltas between keyframes.
+    pub fn keyframe_interval(&self) -> usize {
+        self.keyframe_interval
+    }
+
     /// Get the dirty tracker.
     pub fn tracker(&self) -> &DirtyTracker {
         &self.tracker
@@ -345,6 +624,176 @@ impl DeltaSyncManager {
     pub fn stats(&self) -> &DeltaSyncStats {
         &self.stats
     }
+
+    /// Record a locally produced delta in the history log.
+    ///
+    /// Deltas must chain: each base version has to match the previous
+    /// target version. A keyframe is generated every `keyframe_interval`
+    /// deltas.
+    pub fn record_delta(&mut self, delta: StateDelta) -> Result<(), DeltaSyncError> {
+        if delta.target_version < delta.base_version {
+            return Err(DeltaSyncError::InvalidDelta(format!(
+                "target version {} is before base version {}",
+                delta.target_version, delta.base_version
+            )));
+        }
+        let head = self.head_version();
+        if (!self.history.is_empty() || self.keyframe.is_some()) && delta.base_version != head {
+            return Err(DeltaSyncError::VersionMismatch {
+                expected: head,
+                received: delta.base_version,
+            });
+        }
+
+        self.history.push(LoggedDelta { delta, merged: 1 });
+        self.deltas_since_keyframe += 1;
+        self.stats.deltas_created += 1;
+
+        if self.deltas_since_keyframe >= self.keyframe_interval {
+            self.generate_keyframe();
+        }
+        Ok(())
+    }
+
+    /// Fold the history into a new keyframe and drop the deltas it covers.
+    pub fn generate_keyframe(&mut self) -> Option<&Keyframe> {
+        if !self.history.is_empty() {
+            let keyframe = Keyframe::fold(
+                self.keyframe.as_ref(),
+                self.history.iter().map(|logged| &logged.delta),
+            );
+            self.history.clear();
+            self.keyframe = Some(keyframe);
+            self.deltas_since_keyframe = 0;
+            self.stats.keyframes_created += 1;
+        }
+        self.keyframe.as_ref()
+    }
+
+    /// Get the latest keyframe.
+    pub fn latest_keyframe(&self) -> Option<&Keyframe> {
+        self.keyframe.as_ref()
+    }
+
+    /// Get the number of entries in the delta history.
+    pub fn history_len(&self) -> usize {
+        self.history.len()
+    }
+
+    /// Get the version reached by the latest recorded delta or keyframe.
+    pub fn head_version(&self) -> u64 {
+        self.history
+            .last()
+            .map(|logged| logged.delta.target_version)
+            .or_else(|| self.keyframe.as_ref().map(|k| k.version))
+            .unwrap_or(0)
+    }
+
+    /// Squash all but the most recent `keep_recent` history entries into one.
+    ///
+    /// Returns the number of history entries removed.
+    pub fn compact(&mut self, keep_recent: usize) -> usize {
+        let split = self.history.len().saturating_sub(keep_recent);
+        if split < 2 {
+            return 0;
+        }
+
+        let older: Vec<LoggedDelta> = self.history.drain(..split).collect();
+        let merged = older.iter().map(|logged| logged.merged).sum();
+        let deltas: Vec<StateDelta> = older.into_iter().map(|logged| logged.delta).collect();
+        if let Some(delta) = compact_deltas(&deltas) {
+            self.history.insert(0, LoggedDelta { delta, merged });
+        }
+
+        let removed = split - 1;
+        self.stats.deltas_compacted += removed as u64;
+        removed
+    }
+
+    /// Count how many recorded deltas a peer at `version` is missing.
+    pub fn deltas_behind(&self, version: u64) -> usize {
+        self.history
+            .iter()
+            .filter(|logged| logged.delta.target_version > version)
+            .map(|logged| logged.merged)
+            .sum()
+    }
+
+    /// Negotiate a sync with a peer that reports its current version.
+    pub fn handle_sync_request(&mut self, peer_id: &str, version: u64) -> SyncPlan {
+        self.register_peer_version(peer_id, version);
+        self.plan_sync(peer_id)
+    }
+
+    /// Decide how to bring a peer up to date.
+    ///
+    /// Peers more than `keyframe_interval` deltas behind, or older than the
+    /// retained history, fall back to a keyframe transfer.
+    pub fn plan_sync(&mut self, peer_id: &str) -> SyncPlan {
+        let head = self.head_version();
+        let peer_version = self.peer_versions.get(peer_id).copied();
+
+        if let Some(version) = peer_version {
+            if version >= head {
+                return SyncPlan::UpToDate;
+            }
+
+            let covered = self
+                .history
+                .first()
+                .is_some_and(|logged| logged.delta.base_version <= version);
+            if covered && self.deltas_behind(version) <= self.keyframe_interval {
+                let pending: Vec<StateDelta> = self
+                    .history
+                    .iter()
+                    .filter(|logged| logged.delta.target_version > version)
+                    .map(|logged| logged.delta.clone())
+                    .collect();
+                if let Some(delta) = compact_deltas(&pending) {
+                    self.stats.delta_syncs += 1;
+                    return SyncPlan::Deltas(delta);
+                }
+            }
+        }
+
+        match &self.keyframe {
+            Some(keyframe) => {
+                let tail: Vec<StateDelta> = self
+                    .history
+                    .iter()
+                    .map(|logged| logged.delta.clone())
+                    .collect();
+                self.stats.keyframe_syncs += 1;
+                SyncPlan::Keyframe {
+                    keyframe: keyframe.clone(),
+                    delta: compact_deltas(&tail),
+                }
+            }
+            None => {
+                self.stats.full_syncs += 1;
+                SyncPlan::FullSync
+            }
+        }
+    }
+
+    /// Replace local history with a keyframe received from a peer.
+    pub fn apply_keyframe(&mut self, keyframe: Keyframe) -> Result<(), DeltaSyncError> {
+        if keyframe.version < self.head_version() {
+            return Err(DeltaSyncError::VersionMismatch {
+                expected: self.head_version(),
+                received: keyframe.version,
+            });
+        }
+
+        self.tracker.global_version = self.tracker.global_version.max(keyframe.version);
+        self.tracker.clear_all_dirty();
+        self.stats.keyframes_applied += 1;
+        self.stats.entities_synced += (keyframe.entity_count() + keyframe.tile_runs.len()) as u64;
+        self.history.clear();
+        self.deltas_since_keyframe = 0;
+        self.keyframe = Some(keyframe);
+        Ok(())
+    }
 }
 
 impl Default for DeltaSyncManager {
@@ -421,6 +870,24 @@ pub fn extract_entities_from_event(event: &GameEvent) -> Vec<EntityId> {
         GameAction::EndGame { .. } => {
             entities.push(EntityId::new(EntityType::GameSettings, "game_end"));
         }
+        GameAction::RequestPause { .. }
+        | GameAction::VotePause { .. }
+        | GameAction::ResumeGame { .. } => {
+            entities.push(EntityId::new(EntityType::GameSettings, "pause"));
+        }
+        GameAction::SubstitutePlayer { player_slot, .. } => {
+            entities.push(EntityId::player(player_slot.to_string()));
+        }
+        GameAction::RevealMap => {
+            entities.push(EntityId::new(EntityType::GameSettings, "game_end"));
+        }
+        GameAction::Concede { to_player } => {
+            // The conceding player's cities and territory change hands
+            if let Some(to) = to_player {
+                entities.push(EntityId::player(to.to_string()));
+            }
+            entities.push(EntityId::new(EntityType::Territory, "concession"));
+        }
         GameAction::FoundCity { settler_id, name } => {
             entities.push(EntityId::unit(settler_id.to_string()));
             entities.push(EntityId::city(name.clone()));
@@ -450,11 +917,25 @@ pub fn extract_entities_from_event(event: &GameEvent) -> Vec<EntityId> {
             entities.push(EntityId::unit(attacker_id.to_string()));
             entities.push(EntityId::city(city_id.to_string()));
         }
+        GameAction::BombardUnit {
+            city_id, target_id, ..
+        } => {
+            entities.push(EntityId::city(city_id.to_string()));
+            entities.push(EntityId::unit(target_id.to_string()));
+        }
+        GameAction::ResolveCapture { city_id, .. } => {
+            entities.push(EntityId::city(city_id.to_string()));
+        }
+        GameAction::GiftUnit { unit_id, .. } => {
+            entities.push(EntityId::unit(unit_id.to_string()));
+        }
         GameAction::FortifyUnit { unit_id }
+        | GameAction::FortifyUntilHealed { unit_id }
         | GameAction::SleepUnit { unit_id }
         | GameAction::WakeUnit { unit_id }
         | GameAction::DeleteUnit { unit_id }
-        | GameAction::UpgradeUnit { unit_id, .. } => {
+        | GameAction::UpgradeUnit { unit_id, .. }
+        | GameAction::ChoosePromotion { unit_id, .. } => {
             entities.push(EntityId::unit(unit_id.to_string()));
         }
         GameAction::BuildImprovement { unit_id, .. }
@@ -467,22 +948,38 @@ pub fn extract_entities_from_event(event: &GameEvent) -> Vec<EntityId> {
         | GameAction::SellBuilding { city_id, .. } => {
             entities.push(EntityId::city(city_id.to_string()));
         }
-        GameAction::SetResearch { tech_id } => {
+        GameAction::BuyTile { city_id, tile } => {
+            entities.push(EntityId::city(city_id.to_string()));
+            entities.push(EntityId::territory(format!("{},{}", tile.q, tile.r)));
+        }
+        GameAction::SetResearch { tech_id } | GameAction::QueueResearch { tech_id } => {
             entities.push(EntityId::new(EntityType::Technology, tech_id.clone()));
         }
+        GameAction::SetResearchQueue { queue } => {
+            for tech_id in queue {
+                entities.push(EntityId::new(EntityType::Technology, tech_id.clone()));
+            }
+        }
         GameAction::DeclareWar { target_player } => {
             entities.push(EntityId::new(
                 EntityType::Diplomacy,
                 format!("{}_{}", event.player_id, target_player),
             ));
         }
-        GameAction::ProposePeace { target_player } => {
+        GameAction::ProposePeace { target_player }
+        | GameAction::ProposeTreaty { target_player, .. }
+        | GameAction::ProposeTrade {
+            to_player: target_player,
+            ..
+        } => {
             entities.push(EntityId::new(
                 EntityType::Diplomacy,
                 format!("{}_{}", event.player_id, target_player),
             ));
         }
-        GameAction::AcceptPeace { from_player } | GameAction::RejectPeace { from_player } => {
+        GameAction::AcceptPeace { from_player }
+        | GameAction::RejectPeace { from_player }
+        | GameAction::RespondTrade { from_player, .. } => {
             entities.push(EntityId::new(
                 EntityType::Diplomacy,
                 format!("{}_{}", event.player_id, from_player),
@@ -491,15 +988,46 @@ pub fn extract_entities_from_event(event: &GameEvent) -> Vec<EntityId> {
         GameAction::RequestRandom { .. } | GameAction::ProvideRandom { .. } => {
             // Randomness actions don't affect game state entities directly
         }
+        GameAction::Snapshot { .. } => {
+            // Snapshots record state without changing it
+        }
+        GameAction::Extension { .. } | GameAction::Unrecognized { .. } => {
+            // Never executed, so no game state changes
+        }
     }
 
     entities
 }
 
+/// Extract tile-owner changes from applied action effects.
+///
+/// Border growth happens during turn processing rather than in a dedicated
+/// action, so peers track claimed tiles through these territory entities.
+pub fn extract_territory_from_effects(effects: &[ActionEffect]) -> Vec<EntityId> {
+    effects
+        .iter()
+        .filter_map(|effect| match effect {
+            ActionEffect::TileClaimed { coord, .. } => {
+                Some(EntityId::territory(format!("{},{}", coord.q, coord.r)))
+            }
+            _ => None,
+        })
+        .collect()
+}
+
+/// Extract tile-owner changes from applied action effects as runs.
+pub fn extract_tile_runs_from_effects(effects: &[ActionEffect]) -> Vec<TileRun> {
+    encode_tile_runs(effects.iter().filter_map(|effect| match effect {
+        ActionEffect::TileClaimed { coord, owner, .. } => Some((*coord, Some(*owner))),
+        _ => None,
+    }))
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
     use nostr_nations_core::events::GameAction;
+    use nostr_nations_core::types::{CityId, GameId, UnitId};
 
     // ==================== EntityId Tests ====================
 
@@ -751,11 +1279,377 @@ mod tests {
         assert!(!manager.peer_needs_sync("peer1"));
     }
 
+    // ==================== Keyframe Tests ====================
+
+    fn change(id: &str, version: u64, data: &str, change_type: ChangeType) -> EntityChange {
+        EntityChange {
+            entity_id: EntityId::unit(id),
+            version,
+            data: data.to_string(),
+            change_type,
+        }
+    }
+
+    fn delta(base: u64, changes: Vec<EntityChange>, deletions: Vec<&str>) -> StateDelta {
+        let mut delta = StateDelta::new(base, base + 1);
+        for c in changes {
+            delta.add_change(c);
+        }
+        for id in deletions {
+            delta.add_deletion(EntityId::unit(id));
+        }
+        delta
+    }
+
+    #[test]
+    fn test_keyframe_fold_applies_changes_and_deletions() {
+        let deltas = vec![
+            delta(0, vec![change("u1", 1, "a", ChangeType::Create)], vec![]),
+            delta(1, vec![change("u2", 1, "b", ChangeType::Create)], vec![]),
+            delta(
+                2,
+                vec![change("u1", 2, "a2", ChangeType::Update)],
+                vec!["u2"],
+            ),
+        ];
+
+        let keyframe = Keyframe::fold(None, &deltas);
+
+        assert_eq!(keyframe.version, 3);
+        assert_eq!(keyframe.entity_count(), 1);
+        assert_eq!(keyframe.get(&EntityId::unit("u1")).unwrap().data, "a2");
+        assert!(keyframe.get(&EntityId::unit("u2")).is_none());
+    }
+
+    #[test]
+    fn test_keyframe_generated_every_interval() {
+        let mut manager = DeltaSyncManager::with_keyframe_interval(3);
+        for i in 0..3 {
+            let c = change("u1", i + 1, &format!("v{}", i), ChangeType::Update);
+            manager.record_delta(delta(i, vec![c], vec![])).unwrap();
+        }
+
+        let keyframe = manager.latest_keyframe().expect("keyframe generated");
+        assert_eq!(keyframe.version, 3);
+        assert_eq!(keyframe.get(&EntityId::unit("u1")).unwrap().data, "v2");
+        assert_eq!(manager.history_len(), 0);
+        assert_eq!(manager.stats().keyframes_created, 1);
+        assert_eq!(manager.head_version(), 3);
+    }
+
+    #[test]
+    fn test_record_delta_rejects_gap() {
+        let mut manager = DeltaSyncManager::new();
+        manager.record_delta(delta(0, vec![], vec![])).unwrap();
+
+        let result = manager.record_delta(delta(5, vec![], vec![]));
+        assert!(matches!(
+            result,
+            Err(DeltaSyncError::VersionMismatch {
+                expected: 1,
+                received: 5
+            })
+        ));
+    }
+
+    // ==================== Compaction Tests ====================
+
+    #[test]
+    fn test_compact_deltas_keeps_latest_change_per_entity() {
+        let deltas = vec![
+            delta(0, vec![change("u1", 1, "a", ChangeType::Update)], vec![]),
+            delta(1, vec![change("u1", 2, "b", ChangeType::Update)], vec![]),
+            delta(2, vec![change("u2", 1, "c", ChangeType::Update)], vec![]),
+        ];
+
+        let compacted = compact_deltas(&deltas).unwrap();
+
+        assert_eq!(compacted.base_version, 0);
+        assert_eq!(compacted.target_version, 3);
+        assert_eq!(compacted.changes.len(), 2);
+        assert_eq!(compacted.changes[0].data, "b");
+        assert_eq!(compacted.changes[1].data, "c");
+    }
+
+    #[test]
+    fn test_compact_deltas_drops_created_then_deleted() {
+        let deltas = vec![
+            delta(0, vec![change("u1", 1, "a", ChangeType::Create)], vec![]),
+            delta(1, vec![change("u1", 2, "b", ChangeType::Update)], vec![]),
+            delta(2, vec![], vec!["u1", "u2"]),
+        ];
+
+        let compacted = compact_deltas(&deltas).unwrap();
+
+        assert!(compacted.changes.is_empty());
+        assert_eq!(compacted.deletions, vec![EntityId::unit("u2")]);
+    }
+
+    #[test]
+    fn test_compact_deltas_preserves_create() {
+        let deltas = vec![
+            delta(0, vec![change("u1", 1, "a", ChangeType::Create)], vec![]),
+            delta(1, vec![change("u1", 2, "b", ChangeType::Update)], vec![]),
+        ];
+
+        let compacted = compact_deltas(&deltas).unwrap();
+        assert_eq!(compacted.changes[0].change_type, ChangeType::Create);
+        assert_eq!(compacted.changes[0].data, "b");
+        assert!(compact_deltas(&[]).is_none());
+    }
+
+    #[test]
+    fn test_manager_compact_history() {
+        let mut manager = DeltaSyncManager::with_keyframe_interval(10);
+        for i in 0..5 {
+            let c = change("u1", i + 1, &format!("v{}", i), ChangeType::Update);
+            manager.record_delta(delta(i, vec![c], vec![])).unwrap();
+        }
+
+        assert_eq!(manager.compact(2), 2);
+        assert_eq!(manager.history_len(), 3);
+        assert_eq!(manager.deltas_behind(0), 5);
+        assert_eq!(manager.stats().deltas_compacted, 2);
+        assert_eq!(manager.compact(2), 0);
+    }
+
+    // ==================== Sync Negotiation Tests ====================
+
+    #[test]
+    fn test_plan_sync_up_to_date() {
+        let mut manager = DeltaSyncManager::with_keyframe_interval(4);
+        manager.record_delta(delta(0, vec![], vec![])).unwrap();
+
+        assert!(matches!(
+            manager.handle_sync_request("peer1", 1),
+            SyncPlan::UpToDate
+        ));
+    }
+
+    #[test]
+    fn test_plan_sync_sends_compacted_delta_when_close() {
+        let mut manager = DeltaSyncManager::with_keyframe_interval(4);
+        for i in 0..3 {
+            let c = change("u1", i + 1, &format!("v{}", i), ChangeType::Update);
+            manager.record_delta(delta(i, vec![c], vec![])).unwrap();
+        }
+
+        match manager.handle_sync_request("peer1", 1) {
+            SyncPlan::Deltas(delta) => {
+                assert_eq!(delta.base_version, 1);
+                assert_eq!(delta.target_version, 3);
+                assert_eq!(delta.changes.len(), 1);
+                assert_eq!(delta.changes[0].data, "v2");
+            }
+            other => panic!("expected deltas, got {:?}", other),
+        }
+        assert_eq!(manager.stats().delta_syncs, 1);
+    }
+
+    #[test]
+    fn test_plan_sync_falls_back_to_keyframe() {
+        let mut manager = DeltaSyncManager::with_keyframe_interval(2);
+        for i in 0..5 {
+            let c = change(&format!("u{}", i), 1, "x", ChangeType::Create);
+            manager.record_delta(delta(i, vec![c], vec![])).unwrap();
+        }
+
+        // Peer at version 1 predates the latest keyframe (version 4).
+        match manager.handle_sync_request("peer1", 1) {
+            SyncPlan::Keyframe { keyframe, delta } => {
+                assert_eq!(keyframe.version, 4);
+                assert_eq!(keyframe.entity_count(), 4);
+                let delta = delta.expect("tail delta after keyframe");
+                assert_eq!(delta.base_version, 4);
+                assert_eq!(delta.target_version, 5);
+            }
+            other => panic!("expected keyframe, got {:?}", other),
+        }
+        assert_eq!(manager.stats().keyframe_syncs, 1);
+    }
+
+    #[test]
+    fn test_plan_sync_negotiates_per_peer() {
+        let mut manager = DeltaSyncManager::with_keyframe_interval(3);
+        for i in 0..5 {
+            manager.record_delta(delta(i, vec![], vec![])).unwrap();
+        }
+
+        // Keyframe at 3, history covers 3..5.
+        assert!(matches!(
+            manager.handle_sync_request("peer1", 1),
+            SyncPlan::Keyframe { .. }
+        ));
+        assert!(matches!(
+            manager.handle_sync_request("peer2", 3),
+            SyncPlan::Deltas(_)
+        ));
+        assert!(matches!(
+            manager.handle_sync_request("peer3", 5),
+            SyncPlan::UpToDate
+        ));
+    }
+
+    #[test]
+    fn test_plan_sync_full_sync_without_keyframe() {
+        let mut manager = DeltaSyncManager::with_keyframe_interval(10);
+        manager.record_delta(delta(0, vec![], vec![])).unwrap();
+
+        assert!(matches!(manager.plan_sync("unknown"), SyncPlan::FullSync));
+        assert_eq!(manager.stats().full_syncs, 1);
+    }
+
+    #[test]
+    fn test_apply_keyframe() {
+        let mut sender = DeltaSyncManager::with_keyframe_interval(2);
+        sender
+            .record_delta(delta(
+                0,
+                vec![change("u1", 1, "a", ChangeType::Create)],
+                vec![],
+            ))
+            .unwrap();
+        sender
+            .record_delta(delta(
+                1,
+                vec![change("u2", 1, "b", ChangeType::Create)],
+                vec![],
+            ))
+            .unwrap();
+        let keyframe = sender.latest_keyframe().unwrap().clone();
+
+        let mut receiver = DeltaSyncManager::new();
+        receiver.mark_modified(EntityId::unit("u9"));
+        receiver.apply_keyframe(keyframe.clone()).unwrap();
+
+        assert_eq!(receiver.tracker().global_version(), 2);
+        assert!(!receiver.tracker().has_dirty());
+        assert_eq!(receiver.stats().keyframes_applied, 1);
+        assert_eq!(receiver.head_version(), 2);
+
+        let mut stale = keyframe;
+        stale.version = 1;
+        assert!(receiver.apply_keyframe(stale).is_err());
+    }
+
+    #[test]
+    fn test_sync_plan_serialization() {
+        let keyframe = Keyframe::fold(
+            None,
+            &[delta(
+                0,
+                vec![change("u1", 1, "a", ChangeType::Create)],
+                vec![],
+            )],
+        );
+        let plan = SyncPlan::Keyframe {
+            keyframe,
+            delta: None,
+        };
+
+        let json = serde_json::to_string(&plan).unwrap();
+        let restored: SyncPlan = serde_json::from_str(&json).unwrap();
+        assert!(matches!(restored, SyncPlan::Keyframe { .. }));
+    }
+
+    // ==================== TileRun Tests ====================
+
+    #[test]
+    fn test_encode_tile_runs_groups_rows() {
+        // A 6x5 block of conquered territory becomes one run per row
+        let block =
+            (0..5).flat_map(|r| (10..16).map(move |q| (HexCoord::new(q, r), Some(PlayerSlot(1)))));
+        let runs = encode_tile_runs(block);
+        assert_eq!(runs.len(), 5);
+        assert!(runs.iter().all(|run| run.len == 6 && run.start.q == 10));
+
+        let coords: HashSet<HexCoord> = runs.iter().flat_map(|run| run.coords()).collect();
+        assert_eq!(coords.len(), 30);
+        assert!(coords.contains(&HexCoord::new(15, 4)));
+    }
+
+    #[test]
+    fn test_encode_tile_runs_splits_gaps_and_owners() {
+        let runs = encode_tile_runs([
+            (HexCoord::new(0, 0), Some(PlayerSlot(0))),
+            (HexCoord::new(1, 0), Some(PlayerSlot(0))),
+            (HexCoord::new(2, 0), Some(PlayerSlot(1))),
+            (HexCoord::new(4, 0), Some(PlayerSlot(1))),
+            (HexCoord::new(2, 0), Some(PlayerSlot(0))),
+            (HexCoord::new(5, 0), None),
+        ]);
+        assert_eq!(
+            runs,
+            vec![
+                TileRun {
+                    start: HexCoord::new(0, 0),
+                    len: 3,
+                    owner: Some(PlayerSlot(0))
+                },
+                TileRun {
+                    start: HexCoord::new(4, 0),
+                    len: 1,
+                    owner: Some(PlayerSlot(1))
+                },
+                TileRun {
+                    start: HexCoord::new(5, 0),
+                    len: 1,
+                    owner: None
+                },
+            ]
+        );
+    }
+
+    #[test]
+    fn test_tile_runs_survive_compaction_and_keyframes() {
+        let row = |owner| (0..4).map(move |q| (HexCoord::new(q, 2), Some(PlayerSlot(owner))));
+        let mut first = StateDelta::new(0, 1);
+        first.add_tile_changes(row(0));
+        let mut second = StateDelta::new(1, 2);
+        second.add_tile_changes([(HexCoord::new(3, 2), Some(PlayerSlot(1)))]);
+        assert_eq!(first.change_count(), 1);
+
+        let compacted = compact_deltas(&[first.clone(), second.clone()]).unwrap();
+        assert_eq!(compacted.tile_runs.len(), 2);
+        assert_eq!(compacted.tile_runs[0].len, 3);
+        assert_eq!(compacted.tile_runs[1].owner, Some(PlayerSlot(1)));
+
+        let keyframe = Keyframe::fold(None, [&first, &second]);
+        assert_eq!(keyframe.tile_runs, compacted.tile_runs);
+    }
+
+    #[test]
+    fn test_extract_tile_runs_from_effects() {
+        let effects: Vec<ActionEffect> = (0..3)
+            .map(|q| ActionEffect::TileClaimed {
+                city_id: CityId(1),
+                owner: PlayerSlot(2),
+                coord: HexCoord::new(q, 7),
+            })
+            .collect();
+
+        assert_eq!(
+            extract_tile_runs_from_effects(&effects),
+            vec![TileRun {
+                start: HexCoord::new(0, 7),
+                len: 3,
+                owner: Some(PlayerSlot(2))
+            }]
+        );
+    }
+
     // ==================== extract_entities_from_event Tests ====================
 
     #[test]
     fn test_extract_entities_end_turn() {
-        let event = GameEvent::new("game1".to_string(), 0, None, 1, 1, GameAction::EndTurn);
+        let event = GameEvent::new(
+            GameId::new("game1"),
+            PlayerSlot(0),
+            None,
+            1,
+            1,
+            GameAction::EndTurn,
+        );
 
         let entities = extract_entities_from_event(&event);
         assert!(entities.iter().any(|e| e.entity_type == EntityType::Player));
@@ -764,13 +1658,13 @@ mod tests {
     #[test]
     fn test_extract_entities_found_city() {
         let event = GameEvent::new(
-            "game1".to_string(),
-            0,
+            GameId::new("game1"),
+            PlayerSlot(0),
             None,
             1,
             1,
             GameAction::FoundCity {
-                settler_id: 1,
+                settler_id: UnitId(1),
                 name: "Rome".to_string(),
             },
         );
@@ -783,14 +1677,14 @@ mod tests {
     #[test]
     fn test_extract_entities_attack() {
         let event = GameEvent::new(
-            "game1".to_string(),
-            0,
+            GameId::new("game1"),
+            PlayerSlot(0),
             None,
             1,
             1,
             GameAction::AttackUnit {
-                attacker_id: 1,
-                defender_id: 2,
+                attacker_id: UnitId(1),
+                defender_id: UnitId(2),
                 random: 0.5,
             },
         );
@@ -803,6 +1697,45 @@ mod tests {
         assert_eq!(unit_entities.len(), 2);
     }
 
+    #[test]
+    fn test_extract_entities_buy_tile() {
+        let event = GameEvent::new(
+            GameId::new("game1"),
+            PlayerSlot(0),
+            None,
+            1,
+            1,
+            GameAction::BuyTile {
+                city_id: CityId(1),
+                tile: nostr_nations_core::HexCoord::new(3, 4),
+            },
+        );
+
+        let entities = extract_entities_from_event(&event);
+        assert!(entities.contains(&EntityId::territory("3,4")));
+        assert!(entities.contains(&EntityId::city("1")));
+    }
+
+    #[test]
+    fn test_extract_territory_from_effects() {
+        let effects = vec![
+            ActionEffect::TileClaimed {
+                city_id: CityId(1),
+                owner: PlayerSlot(0),
+                coord: nostr_nations_core::HexCoord::new(2, -1),
+            },
+            ActionEffect::TurnStarted {
+                player_id: PlayerSlot(1),
+                turn: 2,
+            },
+        ];
+
+        assert_eq!(
+            extract_territory_from_effects(&effects),
+            vec![EntityId::territory("2,-1")]
+        );
+    }
+
     // ==================== Error Tests ====================
 
     #[test]
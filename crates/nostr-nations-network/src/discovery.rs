@@ -11,6 +11,7 @@
 //! as QR codes for easy mobile scanning.
 
 use crate::peer::ConnectionTicket;
+use nostr_nations_core::types::GameId;
 use serde::{Deserialize, Serialize};
 
 /// QR code data for connection.
@@ -240,7 +241,7 @@ fn simple_hash(data: &str) -> u64 {
 #[derive(Default)]
 pub struct DiscoveryService {
     /// Known hosts (game_id -> ticket).
-    known_hosts: std::collections::HashMap<String, ConnectionTicket>,
+    known_hosts: std::collections::HashMap<GameId, ConnectionTicket>,
 }
 
 impl DiscoveryService {
@@ -285,15 +286,16 @@ impl DiscoveryService {
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::peer::{Capabilities, PeerId};
 
     // ==================== QrCodeData Tests ====================
 
     #[test]
     fn test_qr_code_data_roundtrip() {
         let ticket = ConnectionTicket::new(
-            "node123".to_string(),
+            PeerId::from("node123"),
             vec!["192.168.1.1:4433".to_string()],
-            "game456".to_string(),
+            GameId::new("game456"),
             3600,
         );
 
@@ -308,7 +310,8 @@ mod tests {
 
     #[test]
     fn test_qr_code_data_version() {
-        let ticket = ConnectionTicket::new("node1".to_string(), vec![], "game1".to_string(), 3600);
+        let ticket =
+            ConnectionTicket::new(PeerId::from("node1"), vec![], GameId::new("game1"), 3600);
 
         let qr_data = QrCodeData::new(ticket);
         assert_eq!(qr_data.version, 1);
@@ -318,9 +321,9 @@ mod tests {
     fn test_qr_code_data_preserves_ticket_fields() {
         let addresses = vec!["192.168.1.1:4433".to_string(), "10.0.0.1:4433".to_string()];
         let ticket = ConnectionTicket::new(
-            "node_abc".to_string(),
+            PeerId::from("node_abc"),
             addresses.clone(),
-            "game_xyz".to_string(),
+            GameId::new("game_xyz"),
             7200,
         );
 
@@ -337,11 +340,12 @@ mod tests {
     fn test_qr_code_expired() {
         // Create a ticket that is already expired (expires_at = 0 is Unix epoch, always in the past)
         let ticket = ConnectionTicket {
-            node_id: "node123".to_string(),
+            node_id: PeerId::from("node123"),
             addresses: vec![],
             alpn: "nostr-nations/1".to_string(),
-            game_id: "game456".to_string(),
+            game_id: GameId::new("game456"),
             expires_at: 0, // Unix epoch - always in the past
+            capabilities: Capabilities::default(),
         };
 
         let qr_data = QrCodeData::new(ticket);
@@ -655,7 +659,8 @@ mod tests {
     fn test_discovery_service() {
         let mut service = DiscoveryService::new();
 
-        let ticket = ConnectionTicket::new("node1".to_string(), vec![], "game1".to_string(), 3600);
+        let ticket =
+            ConnectionTicket::new(PeerId::from("node1"), vec![], GameId::new("game1"), 3600);
 
         service.register_host(ticket);
         assert!(service.get_ticket("game1").is_some());
@@ -669,9 +674,12 @@ mod tests {
     fn test_discovery_service_register_multiple() {
         let mut service = DiscoveryService::new();
 
-        let ticket1 = ConnectionTicket::new("node1".to_string(), vec![], "game1".to_string(), 3600);
-        let ticket2 = ConnectionTicket::new("node2".to_string(), vec![], "game2".to_string(), 3600);
-        let ticket3 = ConnectionTicket::new("node3".to_string(), vec![], "game3".to_string(), 3600);
+        let ticket1 =
+            ConnectionTicket::new(PeerId::from("node1"), vec![], GameId::new("game1"), 3600);
+        let ticket2 =
+            ConnectionTicket::new(PeerId::from("node2"), vec![], GameId::new("game2"), 3600);
+        let ticket3 =
+            ConnectionTicket::new(PeerId::from("node3"), vec![], GameId::new("game3"), 3600);
 
         service.register_host(ticket1);
         service.register_host(ticket2);
@@ -689,15 +697,15 @@ mod tests {
         let mut service = DiscoveryService::new();
 
         let ticket1 = ConnectionTicket::new(
-            "node1".to_string(),
+            PeerId::from("node1"),
             vec!["addr1".to_string()],
-            "game1".to_string(),
+            GameId::new("game1"),
             3600,
         );
         let ticket2 = ConnectionTicket::new(
-            "node2".to_string(),
+            PeerId::from("node2"),
             vec!["addr2".to_string()],
-            "game1".to_string(), // Same game ID
+            GameId::new("game1"), // Same game ID
             7200,
         );
 
@@ -733,9 +741,9 @@ mod tests {
         let mut service = DiscoveryService::new();
 
         let ticket = ConnectionTicket::new(
-            "discovered_node".to_string(),
+            PeerId::from("discovered_node"),
             vec!["192.168.1.100:4433".to_string()],
-            "discovered_game".to_string(),
+            GameId::new("discovered_game"),
             3600,
         );
 
@@ -752,11 +760,12 @@ mod tests {
 
         // Create a ticket that is already expired (expires_at = 0 is Unix epoch, always in the past)
         let ticket = ConnectionTicket {
-            node_id: "expired_node".to_string(),
+            node_id: PeerId::from("expired_node"),
             addresses: vec![],
             alpn: "nostr-nations/1".to_string(),
-            game_id: "expired_game".to_string(),
+            game_id: GameId::new("expired_game"),
             expires_at: 0, // Unix epoch - always in the past
+            capabilities: Capabilities::default(),
         };
 
         service.add_discovered(ticket);
@@ -770,18 +779,19 @@ mod tests {
         let mut service = DiscoveryService::new();
 
         let valid_ticket = ConnectionTicket::new(
-            "valid_node".to_string(),
+            PeerId::from("valid_node"),
             vec![],
-            "valid_game".to_string(),
+            GameId::new("valid_game"),
             3600,
         );
         // Create a ticket that is already expired (expires_at = 0 is Unix epoch, always in the past)
         let expired_ticket = ConnectionTicket {
-            node_id: "expired_node".to_string(),
+            node_id: PeerId::from("expired_node"),
             addresses: vec![],
             alpn: "nostr-nations/1".to_string(),
-            game_id: "expired_game".to_string(),
+            game_id: GameId::new("expired_game"),
             expires_at: 0, // Unix epoch - always in the past
+            capabilities: Capabilities::default(),
         };
 
         service.register_host(valid_ticket);
@@ -801,18 +811,20 @@ mod tests {
 
         // Create tickets that are already expired (expires_at = 0 is Unix epoch, always in the past)
         let ticket1 = ConnectionTicket {
-            node_id: "n1".to_string(),
+            node_id: PeerId::from("n1"),
             addresses: vec![],
             alpn: "nostr-nations/1".to_string(),
-            game_id: "g1".to_string(),
+            game_id: GameId::new("g1"),
             expires_at: 0,
+            capabilities: Capabilities::default(),
         };
         let ticket2 = ConnectionTicket {
-            node_id: "n2".to_string(),
+            node_id: PeerId::from("n2"),
             addresses: vec![],
             alpn: "nostr-nations/1".to_string(),
-            game_id: "g2".to_string(),
+            game_id: GameId::new("g2"),
             expires_at: 0,
+            capabilities: Capabilities::default(),
         };
 
         service.register_host(ticket1);
@@ -827,8 +839,8 @@ mod tests {
     fn test_discovery_service_cleanup_expired_none() {
         let mut service = DiscoveryService::new();
 
-        let ticket1 = ConnectionTicket::new("n1".to_string(), vec![], "g1".to_string(), 3600);
-        let ticket2 = ConnectionTicket::new("n2".to_string(), vec![], "g2".to_string(), 3600);
+        let ticket1 = ConnectionTicket::new(PeerId::from("n1"), vec![], GameId::new("g1"), 3600);
+        let ticket2 = ConnectionTicket::new(PeerId::from("n2"), vec![], GameId::new("g2"), 3600);
 
         service.register_host(ticket1);
         service.register_host(ticket2);
@@ -847,9 +859,9 @@ mod tests {
 
         // Host creates a ticket
         let ticket = ConnectionTicket::new(
-            "host_node".to_string(),
+            PeerId::from("host_node"),
             vec!["192.168.1.1:4433".to_string()],
-            "multiplayer_game".to_string(),
+            GameId::new("multiplayer_game"),
             3600,
         );
 
@@ -10,10 +10,14 @@
 //! dependencies like `x25519-dalek` and `chacha20poly1305`.
 
 use nostr_nations_core::events::GameEvent;
-use nostr_nations_core::types::PlayerId;
+use nostr_nations_core::types::{GameId, PlayerSlot};
 use serde::{Deserialize, Serialize};
 use std::collections::HashMap;
 
+/// Version of the payload encryption scheme, exchanged in the peer
+/// handshake.
+pub const ENCRYPTION_VERSION: u8 = 1;
+
 /// Manages key pairs and encryption for a player.
 ///
 /// Handles:
@@ -27,7 +31,7 @@ pub struct EncryptionManager {
     /// Our public key (32 bytes).
     public_key: Option<[u8; 32]>,
     /// Public keys of known peers, indexed by player ID.
-    peer_keys: HashMap<PlayerId, [u8; 32]>,
+    peer_keys: HashMap<PlayerSlot, [u8; 32]>,
 }
 
 impl EncryptionManager {
@@ -52,8 +56,8 @@ impl EncryptionManager {
 
         // Placeholder: generate deterministic "random" keys
         // Real implementation would use: x25519_dalek::StaticSecret::random()
-        let timestamp = std::time::SystemTime::now()
-            .duration_since(std::time::UNIX_EPOCH)
+        let timestamp = web_time::SystemTime::now()
+            .duration_since(web_time::UNIX_EPOCH)
             .map(|d| d.as_nanos() as u64)
             .unwrap_or(0);
 
@@ -92,22 +96,22 @@ impl EncryptionManager {
     /// Add a peer's public key.
     ///
     /// Required before encrypting messages to that peer.
-    pub fn add_peer_key(&mut self, player: PlayerId, public_key: [u8; 32]) {
+    pub fn add_peer_key(&mut self, player: PlayerSlot, public_key: [u8; 32]) {
         self.peer_keys.insert(player, public_key);
     }
 
     /// Remove a peer's public key.
-    pub fn remove_peer_key(&mut self, player: PlayerId) {
+    pub fn remove_peer_key(&mut self, player: PlayerSlot) {
         self.peer_keys.remove(&player);
     }
 
     /// Check if we have a peer's public key.
-    pub fn has_peer_key(&self, player: PlayerId) -> bool {
+    pub fn has_peer_key(&self, player: PlayerSlot) -> bool {
         self.peer_keys.contains_key(&player)
     }
 
     /// Get a peer's public key if known.
-    pub fn get_peer_key(&self, player: PlayerId) -> Option<&[u8; 32]> {
+    pub fn get_peer_key(&self, player: PlayerSlot) -> Option<&[u8; 32]> {
         self.peer_keys.get(&player)
     }
 
@@ -127,7 +131,7 @@ impl EncryptionManager {
     }
 
     /// Get all known peer IDs.
-    pub fn peer_ids(&self) -> Vec<PlayerId> {
+    pub fn peer_ids(&self) -> Vec<PlayerSlot> {
         self.peer_keys.keys().copied().collect()
     }
 
@@ -185,7 +189,7 @@ impl EncryptedPayload {
 #[derive(Clone, Debug, Serialize, Deserialize)]
 pub struct EncryptedGameEvent {
     /// Game this event belongs to.
-    pub game_id: String,
+    pub game_id: GameId,
     /// The encrypted event payload.
     pub encrypted_payload: EncryptedPayload,
     /// Turn number (unencrypted for ordering).
@@ -197,7 +201,7 @@ pub struct EncryptedGameEvent {
 impl EncryptedGameEvent {
     /// Create a new encrypted game event.
     pub fn new(
-        game_id: String,
+        game_id: GameId,
         encrypted_payload: EncryptedPayload,
         turn: u32,
         sequence: u32,
@@ -217,7 +221,7 @@ pub enum EncryptionError {
     /// No private key available for decryption or signing.
     NoPrivateKey,
     /// No public key available for the specified peer.
-    NoPeerKey(PlayerId),
+    NoPeerKey(PlayerSlot),
     /// Encryption operation failed.
     EncryptionFailed(String),
     /// Decryption operation failed.
@@ -251,7 +255,7 @@ impl std::error::Error for EncryptionError {}
 /// Real implementation would use X25519 key exchange + XChaCha20-Poly1305.
 pub fn encrypt_for_player(
     manager: &EncryptionManager,
-    recipient: PlayerId,
+    recipient: PlayerSlot,
     plaintext: &[u8],
 ) -> Result<EncryptedPayload, EncryptionError> {
     // Check we have our private key
@@ -296,7 +300,7 @@ pub fn encrypt_for_player(
 /// Real implementation would use X25519 key exchange + XChaCha20-Poly1305.
 pub fn decrypt_from_player(
     manager: &EncryptionManager,
-    sender: PlayerId,
+    sender: PlayerSlot,
     encrypted: &EncryptedPayload,
 ) -> Result<Vec<u8>, EncryptionError> {
     // Check we have our private key
@@ -335,7 +339,7 @@ pub fn decrypt_from_player(
 pub fn encrypt_event(
     event: &GameEvent,
     manager: &EncryptionManager,
-    recipient: PlayerId,
+    recipient: PlayerSlot,
 ) -> Result<EncryptedGameEvent, EncryptionError> {
     // Serialize the event to JSON
     let plaintext = serde_json::to_vec(event)
@@ -356,7 +360,7 @@ pub fn encrypt_event(
 pub fn decrypt_event(
     encrypted: &EncryptedGameEvent,
     manager: &EncryptionManager,
-    sender: PlayerId,
+    sender: PlayerSlot,
 ) -> Result<GameEvent, EncryptionError> {
     // Decrypt the payload
     let plaintext = decrypt_from_player(manager, sender, &encrypted.encrypted_payload)?;
@@ -384,6 +388,7 @@ pub fn compute_shared_secret(private_key: &[u8; 32], peer_public_key: &[u8; 32])
 mod tests {
     use super::*;
     use nostr_nations_core::events::GameAction;
+    use nostr_nations_core::types::UnitId;
 
     // ==================== EncryptionManager Tests ====================
 
@@ -429,10 +434,10 @@ mod tests {
         let mut manager = EncryptionManager::new();
         let peer_key = [3u8; 32];
 
-        assert!(!manager.has_peer_key(0));
-        manager.add_peer_key(0, peer_key);
-        assert!(manager.has_peer_key(0));
-        assert_eq!(manager.get_peer_key(0), Some(&peer_key));
+        assert!(!manager.has_peer_key(PlayerSlot(0)));
+        manager.add_peer_key(PlayerSlot(0), peer_key);
+        assert!(manager.has_peer_key(PlayerSlot(0)));
+        assert_eq!(manager.get_peer_key(PlayerSlot(0)), Some(&peer_key));
         assert_eq!(manager.peer_count(), 1);
     }
 
@@ -441,31 +446,31 @@ mod tests {
         let mut manager = EncryptionManager::new();
         let peer_key = [3u8; 32];
 
-        manager.add_peer_key(0, peer_key);
-        assert!(manager.has_peer_key(0));
+        manager.add_peer_key(PlayerSlot(0), peer_key);
+        assert!(manager.has_peer_key(PlayerSlot(0)));
 
-        manager.remove_peer_key(0);
-        assert!(!manager.has_peer_key(0));
+        manager.remove_peer_key(PlayerSlot(0));
+        assert!(!manager.has_peer_key(PlayerSlot(0)));
     }
 
     #[test]
     fn test_peer_ids() {
         let mut manager = EncryptionManager::new();
-        manager.add_peer_key(0, [1u8; 32]);
-        manager.add_peer_key(2, [2u8; 32]);
-        manager.add_peer_key(5, [3u8; 32]);
+        manager.add_peer_key(PlayerSlot(0), [1u8; 32]);
+        manager.add_peer_key(PlayerSlot(2), [2u8; 32]);
+        manager.add_peer_key(PlayerSlot(5), [3u8; 32]);
 
         let mut ids = manager.peer_ids();
         ids.sort();
-        assert_eq!(ids, vec![0, 2, 5]);
+        assert_eq!(ids, vec![PlayerSlot(0), PlayerSlot(2), PlayerSlot(5)]);
     }
 
     #[test]
     fn test_clear() {
         let mut manager = EncryptionManager::new();
         manager.generate_keypair();
-        manager.add_peer_key(0, [1u8; 32]);
-        manager.add_peer_key(1, [2u8; 32]);
+        manager.add_peer_key(PlayerSlot(0), [1u8; 32]);
+        manager.add_peer_key(PlayerSlot(1), [2u8; 32]);
 
         assert!(manager.has_private_key());
         assert_eq!(manager.peer_count(), 2);
@@ -512,7 +517,7 @@ mod tests {
     #[test]
     fn test_encrypted_game_event_new() {
         let payload = EncryptedPayload::new(vec![1, 2, 3], [0u8; 24], [0u8; 32]);
-        let event = EncryptedGameEvent::new("game123".to_string(), payload, 5, 10);
+        let event = EncryptedGameEvent::new(GameId::new("game123"), payload, 5, 10);
 
         assert_eq!(event.game_id, "game123");
         assert_eq!(event.turn, 5);
@@ -522,7 +527,7 @@ mod tests {
     #[test]
     fn test_encrypted_game_event_serialize() {
         let payload = EncryptedPayload::new(vec![1, 2, 3], [0u8; 24], [0u8; 32]);
-        let event = EncryptedGameEvent::new("game456".to_string(), payload, 1, 2);
+        let event = EncryptedGameEvent::new(GameId::new("game456"), payload, 1, 2);
 
         let json = serde_json::to_string(&event).unwrap();
         let restored: EncryptedGameEvent = serde_json::from_str(&json).unwrap();
@@ -541,7 +546,7 @@ mod tests {
             "No private key available"
         );
         assert_eq!(
-            format!("{}", EncryptionError::NoPeerKey(5)),
+            format!("{}", EncryptionError::NoPeerKey(PlayerSlot(5))),
             "No public key for player 5"
         );
         assert_eq!(
@@ -568,8 +573,14 @@ mod tests {
     #[test]
     fn test_encryption_error_equality() {
         assert_eq!(EncryptionError::NoPrivateKey, EncryptionError::NoPrivateKey);
-        assert_eq!(EncryptionError::NoPeerKey(1), EncryptionError::NoPeerKey(1));
-        assert_ne!(EncryptionError::NoPeerKey(1), EncryptionError::NoPeerKey(2));
+        assert_eq!(
+            EncryptionError::NoPeerKey(PlayerSlot(1)),
+            EncryptionError::NoPeerKey(PlayerSlot(1))
+        );
+        assert_ne!(
+            EncryptionError::NoPeerKey(PlayerSlot(1)),
+            EncryptionError::NoPeerKey(PlayerSlot(2))
+        );
     }
 
     #[test]
@@ -584,7 +595,7 @@ mod tests {
     #[test]
     fn test_encrypt_no_private_key() {
         let manager = EncryptionManager::new();
-        let result = encrypt_for_player(&manager, 0, b"hello");
+        let result = encrypt_for_player(&manager, PlayerSlot(0), b"hello");
 
         assert!(matches!(result, Err(EncryptionError::NoPrivateKey)));
     }
@@ -594,9 +605,12 @@ mod tests {
         let mut manager = EncryptionManager::new();
         manager.generate_keypair();
 
-        let result = encrypt_for_player(&manager, 0, b"hello");
+        let result = encrypt_for_player(&manager, PlayerSlot(0), b"hello");
 
-        assert!(matches!(result, Err(EncryptionError::NoPeerKey(0))));
+        assert!(matches!(
+            result,
+            Err(EncryptionError::NoPeerKey(PlayerSlot(0)))
+        ));
     }
 
     #[test]
@@ -610,15 +624,15 @@ mod tests {
         let recipient_pubkey = recipient.generate_keypair();
 
         // Exchange keys
-        sender.add_peer_key(1, recipient_pubkey); // recipient is player 1
-        recipient.add_peer_key(0, sender_pubkey); // sender is player 0
+        sender.add_peer_key(PlayerSlot(1), recipient_pubkey); // recipient is player 1
+        recipient.add_peer_key(PlayerSlot(0), sender_pubkey); // sender is player 0
 
         // Encrypt
         let plaintext = b"Hello, secret world!";
-        let encrypted = encrypt_for_player(&sender, 1, plaintext).unwrap();
+        let encrypted = encrypt_for_player(&sender, PlayerSlot(1), plaintext).unwrap();
 
         // Decrypt
-        let decrypted = decrypt_from_player(&recipient, 0, &encrypted).unwrap();
+        let decrypted = decrypt_from_player(&recipient, PlayerSlot(0), &encrypted).unwrap();
 
         assert_eq!(decrypted, plaintext.to_vec());
     }
@@ -636,18 +650,18 @@ mod tests {
         let pubkey2 = player2.generate_keypair();
 
         // Player 0 and 1 exchange keys
-        player0.add_peer_key(1, pubkey1);
-        player1.add_peer_key(0, pubkey0);
+        player0.add_peer_key(PlayerSlot(1), pubkey1);
+        player1.add_peer_key(PlayerSlot(0), pubkey0);
 
         // Player 1 and 2 exchange keys (but 0 doesn't know 2)
-        player1.add_peer_key(2, pubkey2);
-        player2.add_peer_key(1, pubkey1);
+        player1.add_peer_key(PlayerSlot(2), pubkey2);
+        player2.add_peer_key(PlayerSlot(1), pubkey1);
 
         // Player 0 encrypts for player 1
-        let encrypted = encrypt_for_player(&player0, 1, b"secret").unwrap();
+        let encrypted = encrypt_for_player(&player0, PlayerSlot(1), b"secret").unwrap();
 
         // Player 1 tries to decrypt but thinks it's from player 2 (wrong keys)
-        let result = decrypt_from_player(&player1, 2, &encrypted);
+        let result = decrypt_from_player(&player1, PlayerSlot(2), &encrypted);
 
         assert!(matches!(result, Err(EncryptionError::DecryptionFailed(_))));
     }
@@ -660,13 +674,13 @@ mod tests {
         let mut recipient = EncryptionManager::new();
         let recipient_pubkey = recipient.generate_keypair();
 
-        sender.add_peer_key(1, recipient_pubkey);
-        recipient.add_peer_key(0, sender_pubkey);
+        sender.add_peer_key(PlayerSlot(1), recipient_pubkey);
+        recipient.add_peer_key(PlayerSlot(0), sender_pubkey);
 
-        let encrypted = encrypt_for_player(&sender, 1, &[]).unwrap();
+        let encrypted = encrypt_for_player(&sender, PlayerSlot(1), &[]).unwrap();
         assert!(encrypted.ciphertext.is_empty());
 
-        let decrypted = decrypt_from_player(&recipient, 0, &encrypted).unwrap();
+        let decrypted = decrypt_from_player(&recipient, PlayerSlot(0), &encrypted).unwrap();
         assert!(decrypted.is_empty());
     }
 
@@ -678,14 +692,14 @@ mod tests {
         let mut recipient = EncryptionManager::new();
         let recipient_pubkey = recipient.generate_keypair();
 
-        sender.add_peer_key(1, recipient_pubkey);
-        recipient.add_peer_key(0, sender_pubkey);
+        sender.add_peer_key(PlayerSlot(1), recipient_pubkey);
+        recipient.add_peer_key(PlayerSlot(0), sender_pubkey);
 
         // Large plaintext (1KB)
         let plaintext: Vec<u8> = (0..1024).map(|i| (i % 256) as u8).collect();
 
-        let encrypted = encrypt_for_player(&sender, 1, &plaintext).unwrap();
-        let decrypted = decrypt_from_player(&recipient, 0, &encrypted).unwrap();
+        let encrypted = encrypt_for_player(&sender, PlayerSlot(1), &plaintext).unwrap();
+        let decrypted = decrypt_from_player(&recipient, PlayerSlot(0), &encrypted).unwrap();
 
         assert_eq!(decrypted, plaintext);
     }
@@ -695,9 +709,16 @@ mod tests {
     #[test]
     fn test_encrypt_event_no_keys() {
         let manager = EncryptionManager::new();
-        let event = GameEvent::new("game1".to_string(), 0, None, 1, 1, GameAction::EndTurn);
+        let event = GameEvent::new(
+            GameId::new("game1"),
+            PlayerSlot(0),
+            None,
+            1,
+            1,
+            GameAction::EndTurn,
+        );
 
-        let result = encrypt_event(&event, &manager, 1);
+        let result = encrypt_event(&event, &manager, PlayerSlot(1));
         assert!(matches!(result, Err(EncryptionError::NoPrivateKey)));
     }
 
@@ -710,21 +731,28 @@ mod tests {
         let mut recipient = EncryptionManager::new();
         let recipient_pubkey = recipient.generate_keypair();
 
-        sender.add_peer_key(1, recipient_pubkey);
-        recipient.add_peer_key(0, sender_pubkey);
+        sender.add_peer_key(PlayerSlot(1), recipient_pubkey);
+        recipient.add_peer_key(PlayerSlot(0), sender_pubkey);
 
         // Create a game event
-        let event = GameEvent::new("game123".to_string(), 0, None, 5, 3, GameAction::EndTurn);
+        let event = GameEvent::new(
+            GameId::new("game123"),
+            PlayerSlot(0),
+            None,
+            5,
+            3,
+            GameAction::EndTurn,
+        );
 
         // Encrypt the event
-        let encrypted = encrypt_event(&event, &sender, 1).unwrap();
+        let encrypted = encrypt_event(&event, &sender, PlayerSlot(1)).unwrap();
 
         assert_eq!(encrypted.game_id, "game123");
         assert_eq!(encrypted.turn, 5);
         assert_eq!(encrypted.sequence, 3);
 
         // Decrypt the event
-        let decrypted = decrypt_event(&encrypted, &recipient, 0).unwrap();
+        let decrypted = decrypt_event(&encrypted, &recipient, PlayerSlot(0)).unwrap();
 
         assert_eq!(decrypted.game_id, event.game_id);
         assert_eq!(decrypted.turn, event.turn);
@@ -739,18 +767,18 @@ mod tests {
         let mut recipient = EncryptionManager::new();
         let recipient_pubkey = recipient.generate_keypair();
 
-        sender.add_peer_key(1, recipient_pubkey);
-        recipient.add_peer_key(0, sender_pubkey);
+        sender.add_peer_key(PlayerSlot(1), recipient_pubkey);
+        recipient.add_peer_key(PlayerSlot(0), sender_pubkey);
 
         // Create a more complex event
         let event = GameEvent::new(
-            "game456".to_string(),
-            0,
+            GameId::new("game456"),
+            PlayerSlot(0),
             Some("prev_event_id".to_string()),
             10,
             5,
             GameAction::MoveUnit {
-                unit_id: 42,
+                unit_id: UnitId(42),
                 path: vec![
                     nostr_nations_core::hex::HexCoord::new(0, 0),
                     nostr_nations_core::hex::HexCoord::new(1, 0),
@@ -759,15 +787,15 @@ mod tests {
             },
         );
 
-        let encrypted = encrypt_event(&event, &sender, 1).unwrap();
-        let decrypted = decrypt_event(&encrypted, &recipient, 0).unwrap();
+        let encrypted = encrypt_event(&event, &sender, PlayerSlot(1)).unwrap();
+        let decrypted = decrypt_event(&encrypted, &recipient, PlayerSlot(0)).unwrap();
 
         assert_eq!(decrypted.game_id, event.game_id);
         assert_eq!(decrypted.prev_event_id, event.prev_event_id);
 
         match decrypted.action {
             GameAction::MoveUnit { unit_id, path } => {
-                assert_eq!(unit_id, 42);
+                assert_eq!(unit_id, UnitId(42));
                 assert_eq!(path.len(), 3);
             }
             _ => panic!("Wrong action type"),
@@ -821,14 +849,14 @@ mod tests {
 
         // Add multiple peer keys
         for i in 0..5u8 {
-            manager.add_peer_key(i, [i; 32]);
+            manager.add_peer_key(PlayerSlot(i), [i; 32]);
         }
 
         assert_eq!(manager.peer_count(), 5);
 
         for i in 0..5u8 {
-            assert!(manager.has_peer_key(i));
-            assert_eq!(manager.get_peer_key(i), Some(&[i; 32]));
+            assert!(manager.has_peer_key(PlayerSlot(i)));
+            assert_eq!(manager.get_peer_key(PlayerSlot(i)), Some(&[i; 32]));
         }
     }
 
@@ -848,23 +876,24 @@ mod tests {
 
         // Sender adds all recipient keys
         for (i, r) in recipients.iter().enumerate() {
-            sender.add_peer_key(i as PlayerId, r.public_key().unwrap());
+            sender.add_peer_key(PlayerSlot(i as u8), r.public_key().unwrap());
         }
 
         // Each recipient adds sender's key
         let sender_pubkey = sender.public_key().unwrap();
         for r in &mut recipients {
-            r.add_peer_key(255, sender_pubkey); // Sender is player 255
+            r.add_peer_key(PlayerSlot(255), sender_pubkey); // Sender is player 255
         }
 
         let plaintext = b"Message for everyone";
 
         // Encrypt for each recipient separately
         for i in 0..3u8 {
-            let encrypted = encrypt_for_player(&sender, i, plaintext).unwrap();
+            let encrypted = encrypt_for_player(&sender, PlayerSlot(i), plaintext).unwrap();
 
             // Corresponding recipient can decrypt
-            let decrypted = decrypt_from_player(&recipients[i as usize], 255, &encrypted).unwrap();
+            let decrypted =
+                decrypt_from_player(&recipients[i as usize], PlayerSlot(255), &encrypted).unwrap();
             assert_eq!(decrypted, plaintext.to_vec());
         }
     }
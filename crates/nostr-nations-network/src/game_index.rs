@@ -0,0 +1,350 @@
+//! Index of an identity's games, built from relay history.
+//!
+//! A [`GameIndex`] groups game events by game ID and keeps a
+//! [`GameSummary`] of each game: its name, players, current turn, last
+//! activity and newest snapshot. The "continue game" browser lists these
+//! summaries and resumes a game with [`resume_events`].
+//!
+//! Events come from two kinds of sources. [`GameIndex::scan_relay`] reads
+//! the identity's own [`LocalRelay`], which only holds games that identity
+//! played. Remote relays are queried with [`remote_filter`]; there events
+//! are signed by the player's key, so filtering on the author finds the
+//! games they posted in. Whatever the remote relay returns is passed to
+//! [`GameIndex::ingest`] under the relay's URL.
+//!
+//! ```rust,ignore
+//! let mut index = GameIndex::new();
+//! index.scan_relay(&local_relay)?;
+//! let sub_id = client.subscribe(vec![remote_filter(&npub)]);
+//! // ... for each event the relay returns
+//! index.ingest(&event, client.url());
+//!
+//! for game in index.games() {
+//!     println!("{} - turn {}", game.name, game.turn);
+//! }
+//! ```
+
+use crate::relay::{Filter, LocalRelay, StorageError};
+use nostr_nations_core::events::{GameAction, GameEvent};
+use nostr_nations_core::settings::GameSettings;
+use nostr_nations_core::snapshot;
+use nostr_nations_core::types::GameId;
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+use std::collections::{BTreeMap, HashMap, HashSet};
+use ts_rs::TS;
+
+/// Source name for events read from the local relay.
+pub const LOCAL_SOURCE: &str = "local";
+
+/// What the game browser shows about one game.
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize, JsonSchema, TS)]
+pub struct GameSummary {
+    /// Game ID.
+    pub game_id: GameId,
+    /// Game name from its settings, or the game ID if the creation event
+    /// hasn't been seen.
+    pub name: String,
+    /// Names of the players who joined, in join order.
+    pub players: Vec<String>,
+    /// Latest turn seen.
+    pub turn: u32,
+    /// Timestamp of the newest event (Unix seconds).
+    pub last_activity: u64,
+    /// Number of distinct events seen.
+    pub event_count: usize,
+    /// Turn of the newest state snapshot, if any.
+    pub snapshot_turn: Option<u32>,
+    /// Whether the game has ended.
+    pub finished: bool,
+    /// Where events for this game were found: [`LOCAL_SOURCE`] or relay
+    /// URLs.
+    pub sources: Vec<String>,
+}
+
+/// An indexed game and the events already counted for it.
+struct IndexedGame {
+    summary: GameSummary,
+    seen: HashSet<String>,
+    /// Joined players keyed by event order.
+    players: BTreeMap<(u64, u32, u32), String>,
+}
+
+impl IndexedGame {
+    fn new(game_id: &GameId) -> Self {
+        Self {
+            summary: GameSummary {
+                game_id: game_id.clone(),
+                name: game_id.to_string(),
+                players: Vec::new(),
+                turn: 0,
+                last_activity: 0,
+                event_count: 0,
+                snapshot_turn: None,
+                finished: false,
+                sources: Vec::new(),
+            },
+            seen: HashSet::new(),
+            players: BTreeMap::new(),
+        }
+    }
+}
+
+/// Games grouped from relay events.
+#[derive(Default)]
+pub struct GameIndex {
+    games: HashMap<GameId, IndexedGame>,
+}
+
+impl GameIndex {
+    /// Create an empty index.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Add an event found at `source`.
+    ///
+    /// Returns `false` if the event was already indexed; the source is
+    /// still recorded.
+    pub fn ingest(&mut self, event: &GameEvent, source: &str) -> bool {
+        let game = self
+            .games
+            .entry(event.game_id.clone())
+            .or_insert_with(|| IndexedGame::new(&event.game_id));
+        let summary = &mut game.summary;
+        if !summary.sources.iter().any(|s| s == source) {
+            summary.sources.push(source.to_string());
+        }
+        if !game.seen.insert(event.id.clone()) {
+            return false;
+        }
+
+        summary.event_count += 1;
+        summary.turn = summary.turn.max(event.turn);
+        summary.last_activity = summary.last_activity.max(event.timestamp);
+        match &event.action {
+            GameAction::CreateGame { settings_json, .. } => {
+                if let Ok(settings) = serde_json::from_str::<GameSettings>(settings_json) {
+                    summary.name = settings.name;
+                }
+            }
+            GameAction::JoinGame { player_name, .. } => {
+                game.players.insert(
+                    (event.timestamp, event.turn, event.sequence),
+                    player_name.clone(),
+                );
+                summary.players = game.players.values().cloned().collect();
+            }
+            GameAction::EndGame { .. } => summary.finished = true,
+            GameAction::Snapshot { .. } => {
+                summary.snapshot_turn = Some(summary.snapshot_turn.unwrap_or(0).max(event.turn));
+            }
+            _ => {}
+        }
+        true
+    }
+
+    /// Index every event in a local relay. Returns the number of new
+    /// events.
+    pub fn scan_relay(&mut self, relay: &LocalRelay) -> Result<usize, StorageError> {
+        let events = relay.query(&Filter::new())?;
+        Ok(events
+            .iter()
+            .filter(|event| self.ingest(event, LOCAL_SOURCE))
+            .count())
+    }
+
+    /// Get the summary of one game.
+    pub fn get(&self, game_id: &GameId) -> Option<&GameSummary> {
+        self.games.get(game_id).map(|g| &g.summary)
+    }
+
+    /// Summaries of every game, most recently active first.
+    pub fn games(&self) -> Vec<GameSummary> {
+        let mut games: Vec<GameSummary> = self.games.values().map(|g| g.summary.clone()).collect();
+        games.sort_by(|a, b| {
+            b.last_activity
+                .cmp(&a.last_activity)
+                .then_with(|| a.game_id.cmp(&b.game_id))
+        });
+        games
+    }
+
+    /// Number of games indexed.
+    pub fn len(&self) -> usize {
+        self.games.len()
+    }
+
+    /// Check if no games are indexed.
+    pub fn is_empty(&self) -> bool {
+        self.games.is_empty()
+    }
+}
+
+/// Filter for finding an identity's games on a remote relay.
+pub fn remote_filter(npub: &str) -> Filter {
+    Filter::authors(vec![npub.to_string()])
+}
+
+/// Every event of a game in a local relay, in chain order.
+pub fn game_events(relay: &LocalRelay, game_id: &GameId) -> Result<Vec<GameEvent>, StorageError> {
+    let mut events = relay.query(&Filter::game(game_id.clone()))?;
+    events.sort_by_key(|e| (e.timestamp, e.turn, e.sequence));
+    Ok(events)
+}
+
+/// Events needed to resume a game from a local relay, oldest first.
+///
+/// Starts at the newest state snapshot, so a long game resumes without
+/// replaying from its first event. Returns an empty list for an unknown
+/// game.
+pub fn resume_events(relay: &LocalRelay, game_id: &GameId) -> Result<Vec<GameEvent>, StorageError> {
+    let mut events = game_events(relay, game_id)?;
+    let start = events.len() - snapshot::compact_events(&events).len();
+    Ok(events.split_off(start))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use nostr_nations_core::game_state::GameState;
+    use nostr_nations_core::snapshot::StateSnapshot;
+    use nostr_nations_core::types::PlayerSlot;
+
+    fn create_event(
+        game_id: &str,
+        id: &str,
+        turn: u32,
+        timestamp: u64,
+        action: GameAction,
+    ) -> GameEvent {
+        let mut event = GameEvent::new(GameId::new(game_id), PlayerSlot(0), None, turn, 0, action);
+        event.id = id.to_string();
+        event.timestamp = timestamp;
+        event
+    }
+
+    fn create_game(game_id: &str, name: &str) -> GameEvent {
+        create_event(
+            game_id,
+            &format!("{}-create", game_id),
+            0,
+            100,
+            GameAction::CreateGame {
+                settings_json: serde_json::to_string(&GameSettings::new(name.to_string())).unwrap(),
+                seed: [1; 32],
+            },
+        )
+    }
+
+    fn join(game_id: &str, name: &str, timestamp: u64) -> GameEvent {
+        create_event(
+            game_id,
+            &format!("{}-join-{}", game_id, name),
+            0,
+            timestamp,
+            GameAction::JoinGame {
+                player_name: name.to_string(),
+                civilization_id: "rome".to_string(),
+            },
+        )
+    }
+
+    // ==================== Index Tests ====================
+
+    #[test]
+    fn test_groups_events_by_game() {
+        let mut index = GameIndex::new();
+        for event in [
+            create_game("g1", "First"),
+            join("g1", "Alice", 110),
+            join("g1", "Bob", 120),
+            create_event("g1", "g1-end", 3, 300, GameAction::EndTurn),
+            create_game("g2", "Second"),
+        ] {
+            assert!(index.ingest(&event, LOCAL_SOURCE));
+        }
+
+        assert_eq!(index.len(), 2);
+        let first = index.get(&GameId::new("g1")).unwrap();
+        assert_eq!(first.name, "First");
+        assert_eq!(first.players, vec!["Alice", "Bob"]);
+        assert_eq!(first.turn, 3);
+        assert_eq!(first.last_activity, 300);
+        assert_eq!(first.event_count, 4);
+        assert!(!first.finished);
+
+        let ids: Vec<GameId> = index.games().into_iter().map(|g| g.game_id).collect();
+        assert_eq!(ids, vec![GameId::new("g1"), GameId::new("g2")]);
+    }
+
+    #[test]
+    fn test_same_event_from_two_sources_counts_once() {
+        let mut index = GameIndex::new();
+        let event = create_game("g1", "Shared");
+        assert!(index.ingest(&event, LOCAL_SOURCE));
+        assert!(!index.ingest(&event, "wss://relay.example.com"));
+
+        let summary = index.get(&GameId::new("g1")).unwrap();
+        assert_eq!(summary.event_count, 1);
+        assert_eq!(
+            summary.sources,
+            vec![LOCAL_SOURCE, "wss://relay.example.com"]
+        );
+    }
+
+    #[test]
+    fn test_players_ordered_by_join_even_when_ingested_out_of_order() {
+        let mut index = GameIndex::new();
+        index.ingest(&join("g1", "Bob", 120), "wss://a");
+        index.ingest(&join("g1", "Alice", 110), "wss://b");
+
+        let summary = index.get(&GameId::new("g1")).unwrap();
+        assert_eq!(summary.players, vec!["Alice", "Bob"]);
+        assert_eq!(summary.name, "g1");
+    }
+
+    #[test]
+    fn test_scan_relay_and_resume_from_snapshot() {
+        let relay = LocalRelay::new_in_memory().unwrap();
+        let snapshot = StateSnapshot::capture(&GameState::new(
+            GameId::new("g1"),
+            GameSettings::new("Long".to_string()),
+            [1; 32],
+        ));
+        relay.publish(&create_game("g1", "Long")).unwrap();
+        relay
+            .publish(&create_event("g1", "g1-a", 1, 200, GameAction::EndTurn))
+            .unwrap();
+        relay
+            .publish(&create_event(
+                "g1",
+                "g1-snap",
+                5,
+                300,
+                GameAction::Snapshot { snapshot },
+            ))
+            .unwrap();
+        relay
+            .publish(&create_event("g1", "g1-b", 5, 400, GameAction::EndTurn))
+            .unwrap();
+
+        let mut index = GameIndex::new();
+        assert_eq!(index.scan_relay(&relay).unwrap(), 4);
+        assert_eq!(index.scan_relay(&relay).unwrap(), 0);
+        assert_eq!(
+            index.get(&GameId::new("g1")).unwrap().snapshot_turn,
+            Some(5)
+        );
+
+        let ids: Vec<String> = resume_events(&relay, &GameId::new("g1"))
+            .unwrap()
+            .into_iter()
+            .map(|e| e.id)
+            .collect();
+        assert_eq!(ids, vec!["g1-snap", "g1-b"]);
+        assert!(resume_events(&relay, &GameId::new("missing"))
+            .unwrap()
+            .is_empty());
+    }
+}
@@ -0,0 +1,580 @@
+//! In-process multiplayer test harness.
+//!
+//! Enabled with the `test-support` feature. [`TwoClientHarness`] runs two
+//! complete client stacks in one process. Each [`TestClient`] has its own
+//! [`GameEngine`], [`LocalRelay`] and [`PeerManager`], and the two talk
+//! through a [`PeerTransport`] carrying serialized [`PeerMessage`]s, by
+//! default a [`LoopbackTransport`]. Tests script a game through the harness and check
+//! that both clients converge to the same state hash, which makes it the
+//! place to regression-test sync, conflict handling and encryption.
+//! [`TwoClientHarness::with_netem`] runs the same game over a link with
+//! simulated latency, jitter, loss and reordering.
+//!
+//! Everything except ACKs and resume requests is sent sequenced, so the
+//! clients see each other's messages in order. Gaps are filled by asking
+//! the peer to resume, and messages whose ACK never came are resent once
+//! the link goes quiet.
+//!
+//! # Example
+//!
+//! ```rust,ignore
+//! let mut harness = TwoClientHarness::new(settings, [7u8; 32]).await?;
+//! harness.act(0, GameAction::EndTurn).await?;
+//! harness.assert_converged();
+//! ```
+
+use crate::netem::{NetemConfig, NetemTransport};
+use crate::peer::{PeerEvent, PeerManager, PeerMessage};
+use crate::redaction::RedactionError;
+use crate::relay::{Filter, LocalRelay, StorageError};
+use crate::transport::{LoopbackNetwork, LoopbackTransport, PeerTransport, TransportError};
+use nostr_nations_core::audit;
+use nostr_nations_core::events::{GameAction, GameEvent};
+use nostr_nations_core::replay::{ActionResult, GameEngine, ReplayError};
+use nostr_nations_core::settings::GameSettings;
+use nostr_nations_core::types::{GameId, PlayerSlot};
+
+/// Maximum rounds of message pumping before a sync is considered stuck.
+const MAX_SYNC_ROUNDS: usize = 64;
+
+/// How long a sync waits between polls while frames are in flight.
+const IN_FLIGHT_POLL: std::time::Duration = std::time::Duration::from_millis(1);
+
+/// Errors from driving the harness.
+#[derive(Debug)]
+pub enum HarnessError {
+    /// The local relay failed.
+    Relay(StorageError),
+    /// The peer transport failed.
+    Transport(TransportError),
+    /// A client's engine failed to apply or replay an event.
+    Replay(ReplayError),
+    /// A message or event could not be (de)serialized.
+    Serialization(String),
+    /// An outgoing message could not be redacted for the peer.
+    Redaction(RedactionError),
+    /// A client rejected a scripted action.
+    Rejected(String),
+    /// A client has no game yet.
+    NoGame,
+    /// The clients kept exchanging messages without settling.
+    SyncStalled,
+}
+
+impl std::fmt::Display for HarnessError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            HarnessError::Relay(e) => write!(f, "Relay error: {}", e),
+            HarnessError::Transport(e) => write!(f, "Transport error: {}", e),
+            HarnessError::Replay(e) => write!(f, "Replay error: {}", e),
+            HarnessError::Serialization(msg) => write!(f, "Serialization error: {}", msg),
+            HarnessError::Redaction(e) => write!(f, "Redaction error: {}", e),
+            HarnessError::Rejected(msg) => write!(f, "Action rejected: {}", msg),
+            HarnessError::NoGame => write!(f, "Client has no game"),
+            HarnessError::SyncStalled => write!(f, "Clients did not settle"),
+        }
+    }
+}
+
+impl std::error::Error for HarnessError {}
+
+impl From<RedactionError> for HarnessError {
+    fn from(e: RedactionError) -> Self {
+        HarnessError::Redaction(e)
+    }
+}
+
+impl From<StorageError> for HarnessError {
+    fn from(e: StorageError) -> Self {
+        HarnessError::Relay(e)
+    }
+}
+
+impl From<TransportError> for HarnessError {
+    fn from(e: TransportError) -> Self {
+        HarnessError::Transport(e)
+    }
+}
+
+impl From<ReplayError> for HarnessError {
+    fn from(e: ReplayError) -> Self {
+        HarnessError::Replay(e)
+    }
+}
+
+impl From<serde_json::Error> for HarnessError {
+    fn from(e: serde_json::Error) -> Self {
+        HarnessError::Serialization(e.to_string())
+    }
+}
+
+/// A full client stack: engine, local relay and peer manager.
+pub struct TestClient<T: PeerTransport = LoopbackTransport> {
+    /// Player this client controls.
+    pub player_id: PlayerSlot,
+    /// Game engine, once the game has been created or synced.
+    pub engine: Option<GameEngine>,
+    /// Local relay holding every event this client has seen.
+    pub relay: LocalRelay,
+    /// Peer connection manager.
+    pub peers: PeerManager,
+    transport: T,
+    /// ID of the peer this client talks to.
+    remote_id: String,
+    game_id: GameId,
+    /// Lamport clock used as the event timestamp.
+    clock: u64,
+    last_event_id: Option<String>,
+}
+
+impl<T: PeerTransport> TestClient<T> {
+    /// Create a client with an in-memory relay that talks to `remote_id`.
+    pub fn new(
+        transport: T,
+        remote_id: &str,
+        player_id: PlayerSlot,
+        game_id: &GameId,
+        is_host: bool,
+    ) -> Result<Self, HarnessError> {
+        Ok(Self {
+            player_id,
+            engine: None,
+            relay: LocalRelay::new_in_memory()?,
+            // Both clients belong to the test, which checks they hold the
+            // same full state, so nothing is redacted between them
+            peers: PeerManager::new(transport.local_id().clone(), game_id.clone(), is_host)
+                .without_redaction(),
+            transport,
+            remote_id: remote_id.to_string(),
+            game_id: game_id.clone(),
+            clock: 0,
+            last_event_id: None,
+        })
+    }
+
+    /// Get the game engine.
+    pub fn engine(&self) -> Result<&GameEngine, HarnessError> {
+        self.engine.as_ref().ok_or(HarnessError::NoGame)
+    }
+
+    /// Audit hash of this client's game state.
+    pub fn state_hash(&self) -> Option<u64> {
+        self.engine.as_ref().map(|e| audit::state_hash(&e.state))
+    }
+
+    /// Every event in this client's relay, oldest first.
+    pub fn events(&self) -> Result<Vec<GameEvent>, HarnessError> {
+        let mut events = self.relay.query(&Filter::game(self.game_id.clone()))?;
+        events.sort_by_key(|e| e.timestamp);
+        Ok(events)
+    }
+
+    /// Create a new game as host.
+    pub fn create_game(
+        &mut self,
+        settings: &GameSettings,
+        seed: [u8; 32],
+    ) -> Result<(), HarnessError> {
+        let action = GameAction::CreateGame {
+            settings_json: serde_json::to_string(settings)?,
+            seed,
+        };
+        self.engine = Some(GameEngine::new(settings.clone(), seed));
+        self.record(action)?;
+        Ok(())
+    }
+
+    /// Apply an action locally, then publish and send it to the peer.
+    pub async fn perform(&mut self, action: GameAction) -> Result<ActionResult, HarnessError> {
+        let player_id = self.player_id;
+        let engine = self.engine.as_mut().ok_or(HarnessError::NoGame)?;
+        let result = engine.apply_action(player_id, &action)?;
+        if !result.success {
+            return Err(HarnessError::Rejected(
+                result.error.clone().unwrap_or_default(),
+            ));
+        }
+
+        let event = self.record(action)?;
+        self.send_event(&event).await?;
+        Ok(result)
+    }
+
+    /// Get the peer transport.
+    pub fn transport(&self) -> &T {
+        &self.transport
+    }
+
+    /// Send a message to the peer as it is.
+    pub async fn send(&self, message: &PeerMessage) -> Result<(), HarnessError> {
+        let frame = message.to_bytes()?;
+        self.transport.send(&self.remote_id, frame).await?;
+        Ok(())
+    }
+
+    /// Send a message to the peer with the next sequence number, so it is
+    /// resent until acknowledged.
+    pub async fn send_sequenced(&self, message: PeerMessage) -> Result<(), HarnessError> {
+        let sequenced = self
+            .peers
+            .sequence_message(&self.remote_id, message)
+            .await?;
+        match sequenced {
+            Some(sequenced) => self.send(&sequenced).await,
+            None => Ok(()),
+        }
+    }
+
+    /// Resend every message the peer hasn't acknowledged. Returns how many
+    /// were sent.
+    pub async fn retransmit(&self) -> Result<usize, HarnessError> {
+        let unacked = self.peers.unacked(&self.remote_id).await;
+        for message in &unacked {
+            self.send(message).await?;
+        }
+        Ok(unacked.len())
+    }
+
+    /// Ask the peer for every event it has.
+    pub async fn request_sync(&self) -> Result<(), HarnessError> {
+        self.send_sequenced(PeerMessage::SyncRequest {
+            from_turn: 0,
+            from_sequence: 0,
+        })
+        .await
+    }
+
+    /// Process every waiting message. Returns the number handled.
+    pub async fn pump(&mut self) -> Result<usize, HarnessError> {
+        let remote = self.remote_id.clone();
+        let mut handled = 0;
+        while let Some((_, frame)) = self.transport.try_recv()? {
+            let message = PeerMessage::from_bytes(&frame)?;
+            handled += 1;
+            for message in self.peers.deliver(&remote, message).await {
+                match message {
+                    PeerMessage::Hello { ref peer_id, .. } => {
+                        let peer_id = peer_id.clone();
+                        self.peers.add_peer(peer_id.clone()).await;
+                        self.peers.handle_message(peer_id.as_str(), message).await;
+                    }
+                    PeerMessage::SyncResponse { events_json } => self.apply_sync(&events_json)?,
+                    other => self.peers.handle_message(&remote, other).await,
+                }
+            }
+        }
+
+        while let Some(event) = self.peers.try_recv_event() {
+            match event {
+                PeerEvent::GameEventReceived { event_json, .. } => {
+                    let event: GameEvent = serde_json::from_str(&event_json)?;
+                    self.receive(event)?;
+                }
+                PeerEvent::SyncRequested { .. } => {
+                    let events_json = self
+                        .events()?
+                        .iter()
+                        .map(serde_json::to_string)
+                        .collect::<Result<_, _>>()?;
+                    self.send_sequenced(PeerMessage::SyncResponse { events_json })
+                        .await?;
+                }
+                PeerEvent::ReplayNeeded {
+                    last_received_seq, ..
+                } => {
+                    self.send(&PeerMessage::Resume { last_received_seq })
+                        .await?;
+                }
+                PeerEvent::ResumeRequested {
+                    last_received_seq, ..
+                } => {
+                    let replay = self.peers.replay_since(&remote, last_received_seq).await;
+                    for message in replay.unwrap_or_default() {
+                        self.send(&message).await?;
+                    }
+                }
+                _ => {}
+            }
+        }
+
+        if let Some(ack) = self.peers.pending_ack(&remote).await {
+            self.send(&ack).await?;
+        }
+        if let Some(resume) = self.peers.pending_resume(&remote).await {
+            self.send(&resume).await?;
+        }
+        Ok(handled)
+    }
+
+    /// Build the next event in the chain and store it in the local relay.
+    fn record(&mut self, action: GameAction) -> Result<GameEvent, HarnessError> {
+        let engine = self.engine.as_ref().ok_or(HarnessError::NoGame)?;
+        self.clock += 1;
+        let mut event = GameEvent::new(
+            self.game_id.clone(),
+            self.player_id,
+            self.last_event_id.clone(),
+            engine.state.turn,
+            self.clock as u32,
+            action,
+        );
+        event.id = format!("{}:{}", self.peers.node_id(), self.clock);
+        event.timestamp = self.clock;
+        self.relay.publish(&event)?;
+        self.last_event_id = Some(event.id.clone());
+        Ok(event)
+    }
+
+    async fn send_event(&self, event: &GameEvent) -> Result<(), HarnessError> {
+        let message = PeerMessage::GameEvent {
+            event_json: serde_json::to_string(event)?,
+        };
+        self.send_sequenced(message).await
+    }
+
+    /// Apply an event received from the peer.
+    fn receive(&mut self, event: GameEvent) -> Result<(), HarnessError> {
+        if self.relay.get_event(&event.id).is_ok() {
+            return Ok(());
+        }
+        self.relay.publish(&event)?;
+        self.clock = self.clock.max(event.timestamp);
+        self.last_event_id = Some(event.id.clone());
+        if let Some(engine) = self.engine.as_mut() {
+            engine.apply_event(&event)?;
+        }
+        Ok(())
+    }
+
+    /// Rebuild the game from a full sync response.
+    fn apply_sync(&mut self, events_json: &[String]) -> Result<(), HarnessError> {
+        let mut events = events_json
+            .iter()
+            .map(|json| serde_json::from_str::<GameEvent>(json))
+            .collect::<Result<Vec<_>, _>>()?;
+        events.sort_by_key(|e| e.timestamp);
+        for event in &events {
+            self.relay.publish(event)?;
+        }
+        if let Some(last) = events.last() {
+            self.clock = self.clock.max(last.timestamp);
+            self.last_event_id = Some(last.id.clone());
+        }
+        self.engine = Some(GameEngine::from_events(&events)?);
+        Ok(())
+    }
+}
+
+/// Two connected clients playing the same game.
+pub struct TwoClientHarness<T: PeerTransport = LoopbackTransport> {
+    /// The hosting client (player 0).
+    pub host: TestClient<T>,
+    /// The joining client (player 1).
+    pub guest: TestClient<T>,
+}
+
+impl TwoClientHarness {
+    /// Connect two clients, create a game, join both players and start it.
+    ///
+    /// Both clients bind to a fresh [`LoopbackNetwork`]. The host creates
+    /// and joins the game, the guest connects, syncs the host's events and
+    /// joins, then the host starts the game.
+    pub async fn new(settings: GameSettings, seed: [u8; 32]) -> Result<Self, HarnessError> {
+        let network = LoopbackNetwork::new();
+        Self::start(
+            network.bind("host")?,
+            network.bind("guest")?,
+            settings,
+            seed,
+        )
+        .await
+    }
+}
+
+impl TwoClientHarness<NetemTransport<LoopbackTransport>> {
+    /// Like [`TwoClientHarness::new`], but both clients receive through a
+    /// [`NetemTransport`] with the given impairments.
+    ///
+    /// Each direction gets its own seed derived from `netem.seed`, so the
+    /// two links don't make identical decisions.
+    pub async fn with_netem(
+        settings: GameSettings,
+        seed: [u8; 32],
+        netem: NetemConfig,
+    ) -> Result<Self, HarnessError> {
+        let network = LoopbackNetwork::new();
+        let guest_netem = NetemConfig {
+            seed: netem.seed.wrapping_add(1),
+            ..netem.clone()
+        };
+        let host_transport = NetemTransport::new(network.bind("host")?, netem);
+        let guest_transport = NetemTransport::new(network.bind("guest")?, guest_netem);
+        Self::start(host_transport, guest_transport, settings, seed).await
+    }
+}
+
+impl<T: PeerTransport> TwoClientHarness<T> {
+    async fn start(
+        host_transport: T,
+        guest_transport: T,
+        settings: GameSettings,
+        seed: [u8; 32],
+    ) -> Result<Self, HarnessError> {
+        let game_id = GameId(format!("harness_{:02x}{:02x}", seed[0], seed[1]));
+        let host_id = host_transport.local_id().clone();
+        let guest_id = guest_transport.local_id().clone();
+        guest_transport.connect(host_id.as_str()).await?;
+        host_transport.accept().await?;
+
+        let mut harness = Self {
+            host: TestClient::new(
+                host_transport,
+                guest_id.as_str(),
+                PlayerSlot(0),
+                &game_id,
+                true,
+            )?,
+            guest: TestClient::new(
+                guest_transport,
+                host_id.as_str(),
+                PlayerSlot(1),
+                &game_id,
+                false,
+            )?,
+        };
+
+        harness.host.create_game(&settings, seed)?;
+        harness.host.perform(join_action("Host", "rome")).await?;
+
+        for (client, name) in [(&harness.host, "Host"), (&harness.guest, "Guest")] {
+            client
+                .send_sequenced(client.peers.hello(name.to_string()))
+                .await?;
+        }
+        harness.guest.request_sync().await?;
+        harness.sync().await?;
+
+        harness.guest.perform(join_action("Guest", "egypt")).await?;
+        harness.sync().await?;
+        harness.host.perform(GameAction::StartGame).await?;
+        harness.sync().await?;
+        Ok(harness)
+    }
+
+    /// Get the client controlling a player.
+    pub fn client(&self, player_id: PlayerSlot) -> &TestClient<T> {
+        if player_id == self.host.player_id {
+            &self.host
+        } else {
+            &self.guest
+        }
+    }
+
+    fn client_mut(&mut self, player_id: PlayerSlot) -> &mut TestClient<T> {
+        if player_id == self.host.player_id {
+            &mut self.host
+        } else {
+            &mut self.guest
+        }
+    }
+
+    /// Perform an action as a player and deliver it to the other client.
+    pub async fn act(
+        &mut self,
+        player_id: PlayerSlot,
+        action: GameAction,
+    ) -> Result<ActionResult, HarnessError> {
+        let result = self.client_mut(player_id).perform(action).await?;
+        self.sync().await?;
+        Ok(result)
+    }
+
+    /// Exchange messages until neither client has anything left to handle
+    /// or acknowledge.
+    ///
+    /// Once nothing is in flight, unacknowledged messages are taken as lost
+    /// and resent. Waiting for frames still in flight doesn't count as a
+    /// round.
+    pub async fn sync(&mut self) -> Result<(), HarnessError> {
+        let mut rounds = 0;
+        while rounds < MAX_SYNC_ROUNDS {
+            let handled = self.host.pump().await? + self.guest.pump().await?;
+            if handled > 0 {
+                rounds += 1;
+                continue;
+            }
+            if self.host.transport.in_flight() + self.guest.transport.in_flight() > 0 {
+                tokio::time::sleep(IN_FLIGHT_POLL).await;
+                continue;
+            }
+            let resent = self.host.retransmit().await? + self.guest.retransmit().await?;
+            if resent == 0 {
+                return Ok(());
+            }
+            rounds += 1;
+        }
+        Err(HarnessError::SyncStalled)
+    }
+
+    /// Check both clients have identical game state.
+    pub fn converged(&self) -> bool {
+        let host = self.host.state_hash();
+        host.is_some() && host == self.guest.state_hash()
+    }
+
+    /// Panic unless both clients have identical game state.
+    pub fn assert_converged(&self) {
+        assert!(
+            self.converged(),
+            "clients diverged: host {:?}, guest {:?}",
+            self.host.state_hash(),
+            self.guest.state_hash()
+        );
+    }
+}
+
+fn join_action(name: &str, civilization_id: &str) -> GameAction {
+    GameAction::JoinGame {
+        player_name: name.to_string(),
+        civilization_id: civilization_id.to_string(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // ==================== Harness Tests ====================
+
+    #[tokio::test]
+    async fn test_harness_starts_converged_game() {
+        let harness = TwoClientHarness::new(GameSettings::new("Harness".to_string()), [3u8; 32])
+            .await
+            .unwrap();
+        harness.assert_converged();
+
+        let engine = harness.guest.engine().unwrap();
+        assert_eq!(engine.state.players.len(), 2);
+        assert_eq!(
+            harness.host.events().unwrap().len(),
+            harness.guest.events().unwrap().len()
+        );
+    }
+
+    #[tokio::test]
+    async fn test_harness_converges_over_slow_link() {
+        let netem = NetemConfig {
+            latency_ms: 5,
+            jitter_ms: 2,
+            seed: 9,
+            ..Default::default()
+        };
+        let harness =
+            TwoClientHarness::with_netem(GameSettings::new("Laggy".to_string()), [4u8; 32], netem)
+                .await
+                .unwrap();
+        harness.assert_converged();
+        assert!(harness.host.transport().stats().delivered > 0);
+        assert_eq!(harness.host.transport().in_flight(), 0);
+    }
+}
@@ -11,6 +11,13 @@
 //! 1. **Full Client**: Local relay + P2P + optional remote relays
 //! 2. **Light Client**: Connects to full client or remote relays only
 //!
+//! # Browser Builds
+//!
+//! The crate compiles to `wasm32-unknown-unknown` with
+//! `--no-default-features`. Without the `sqlite` feature the local relay
+//! stores events in IndexedDB, and remote relays are reached over browser
+//! websockets with [`relay::RelayClient`]. There is no P2P in the browser.
+//!
 //! Game events are signed Nostr events that can be:
 //! - Broadcast to relays for persistence
 //! - Sent directly via Iroh for real-time sync
@@ -19,7 +26,10 @@
 //! # Modules
 //!
 //! - [`peer`]: Peer connection management and messaging
+//! - [`transport`]: Peer transport trait and in-memory loopback transport
+//! - `netem`: Latency, jitter, loss and reordering for testing on bad networks
 //! - [`sync`]: Game state synchronization protocol
+//! - [`cancel`]: Cancellation tokens for sync, relay queries and publishes
 //! - [`discovery`]: Peer discovery and QR code generation
 //! - [`batch`]: Event batching for reduced network overhead
 //! - [`compression`]: Optional payload compression
@@ -29,21 +39,54 @@
 //! - [`cache`]: Event caching and deduplication
 //! - [`conflict`]: Conflict detection and resolution for multiplayer sync
 //! - [`encryption`]: NIP-04 style encryption for secure peer communication
+//! - [`signing`]: Schnorr signatures by players' Nostr keys
+//! - [`nip44`]: NIP-44 encryption of direct messages between Nostr keys
+//! - [`at_rest`]: Encryption of saves and relay storage on disk
+//! - [`redaction`]: Per-recipient redaction of everything a full client sends
 //! - [`offline`]: Offline scenario handling, event queuing, and reconnection
 //! - [`randomness`]: Verifiable randomness protocol for fair multiplayer games
+//! - [`tournament`]: Single-elimination tournament brackets and match lobbies
+//! - [`lobby`]: Join policies (allowlist, NIP-05, proof of work, ecash bond) for public lobbies
+//! - [`reputation`]: Per-player reputation, block lists and signed abandon reports
+//! - [`game_index`]: Index of an identity's games for the "continue game" browser
+//! - [`pitboss`]: Asynchronous play-by-relay games with turn notifications
+//! - [`notifier`]: Push notification bridge (encrypted DM / webhook) for turn alerts
+//! - [`debug`]: Tracing span capture and network debug reports for the overlay
+//! - `metrics`: Prometheus metrics endpoint for dedicated hosts (feature `metrics`)
+//! - `harness`: In-process two-client test harness (feature `test-support`)
 
 // Re-export core types
 pub use nostr_nations_core;
 
 // Networking modules
 pub mod peer;
+pub mod transport;
+#[cfg(not(target_arch = "wasm32"))]
+pub mod netem;
 pub mod sync;
+pub mod cancel;
 pub mod discovery;
 pub mod relay;
 pub mod conflict;
 pub mod encryption;
+pub mod signing;
+pub mod nip44;
+pub mod at_rest;
+pub mod redaction;
 pub mod offline;
 pub mod randomness;
+pub mod tournament;
+pub mod lobby;
+pub mod reputation;
+pub mod game_index;
+pub mod pitboss;
+pub mod notifier;
+pub mod presence;
+pub mod debug;
+#[cfg(feature = "metrics")]
+pub mod metrics;
+#[cfg(feature = "test-support")]
+pub mod harness;
 
 // Optimization modules
 pub mod batch;
@@ -56,20 +99,35 @@ pub mod cache;
 // Re-exports for convenience
 pub use peer::{
     ConnectionTicket, PeerManager, PeerMessage, PeerEvent, PeerInfo,
-    ConnectionState, PeerId, TicketError,
+    ConnectionState, PeerId, TicketError, DEFAULT_REPLAY_CAPACITY, DEFAULT_REPLAY_RETRY,
+    Capabilities, NegotiatedSession, HandshakeError, PROTOCOL_VERSION, MIN_PROTOCOL_VERSION,
 };
+pub use transport::{Frame, LoopbackNetwork, LoopbackTransport, PeerTransport, TransportError};
+#[cfg(not(target_arch = "wasm32"))]
+pub use netem::{NetemConfig, NetemStats, NetemTransport};
 pub use sync::{
     SyncManager, SyncResponder, SyncRequest, SyncResponse, SyncResult,
-    SyncState, PeerSyncTracker,
+    SyncState, PeerSyncTracker, StateQuery, StateQueryError, StateQueryResponse, InclusionProof,
+    STATE_QUERY_MAX_AGE_SECS, MAX_PENDING_STATE_NONCES,
 };
+pub use cancel::{CancellationToken, Cancelled};
 pub use discovery::{
     QrCodeData, QrCodeMatrix, QrGenerator, QrParseError,
     DiscoveryService, ErrorCorrection,
 };
 pub use relay::{
-    Filter, LocalRelay, RelayStorage, StorageError,
+    Filter, LocalRelay, RelayStorage, StorageError, MemoryStorage,
     Subscription, SubscriptionBuilder, SubscriptionManager,
+    ClientMessage, RelayClient, RelayClientError, RelayMessage, RelaySubscription,
+    RelayList, RelayListEntry, RelaySelector, RELAY_LIST_KIND,
+    PolicyViolation, RateLimit, RateLimiter, RelayGuard, RelayPolicy,
 };
+#[cfg(not(target_arch = "wasm32"))]
+pub use relay::{ExpirationPruner, DEFAULT_PRUNE_INTERVAL};
+#[cfg(not(target_arch = "wasm32"))]
+pub use relay::{BatchWriter, BatchWriterHandle, BatchWriterStats};
+#[cfg(feature = "sqlite")]
+pub use relay::StorageOptions;
 
 // Optimization re-exports
 pub use batch::{
@@ -83,44 +141,92 @@ pub use compression::{
 pub use delta::{
     EntityType, EntityId, DirtyTracker, StateDelta, EntityChange,
     ChangeType, DeltaSyncManager, DeltaSyncStats, DeltaSyncError,
+    Keyframe, SyncPlan, compact_deltas, DEFAULT_KEYFRAME_INTERVAL,
+    TileRun, encode_tile_runs, extract_tile_runs_from_effects,
 };
 pub use pool::{
     PooledConnectionState, ConnectionHealth, PoolConfig, BackoffConfig,
     BackoffState, PooledConnection, ConnectionPool, PoolStats, PoolStatus, PoolError,
+    ConnectionRole, PublishResult,
 };
 pub use priority::{
     EventPriority, PrioritizedEvent, PriorityQueueConfig,
-    EventPriorityQueue, PriorityQueueStats, QueueError, event_priority,
+    EventPriorityQueue, PriorityQueueStats, QueueError, event_priority, WaitTimeStats,
 };
 pub use cache::{
     CacheConfig, CachedEvent, EventCache, CacheStats,
     EventDeduplicator, DedupStats, EventIndex,
 };
+#[cfg(feature = "sqlite")]
+pub use cache::CacheStore;
 pub use conflict::{
     ConflictType, ConflictDetector, ResolutionStrategy, Resolution,
-    ConflictResolver, auto_resolve_conflicts,
+    ConflictResolver, auto_resolve_conflicts, GameConflict, GameConflictPolicy,
+    GamePolicyOutcome, DEFAULT_MIN_CITY_DISTANCE,
 };
 pub use encryption::{
     EncryptionManager, EncryptedPayload, EncryptedGameEvent, EncryptionError,
     encrypt_for_player, decrypt_from_player, encrypt_event, decrypt_event,
-    compute_shared_secret,
+    compute_shared_secret, ENCRYPTION_VERSION,
 };
+pub use signing::{verify_signature, SignatureError, SigningKey};
+pub use nip44::Nip44Error;
+pub use at_rest::{AtRestError, AtRestKey, KdfParams, PassphraseKeyFile};
+pub use redaction::{RedactionError, RedactionGate};
 pub use offline::{
     OfflineManager, OfflineStorage, OfflineSyncStrategy, ConnectionMonitor,
-    StorageError as OfflineStorageError,
+    StorageError as OfflineStorageError, Reconciliation, StorageLayout, LifecycleConfig,
+    ResumePlan,
 };
 pub use randomness::{
     RandomnessRequest, RandomnessResponse, RandomnessProof, RandomnessPurpose,
     RandomnessProvider, RandomnessClient, RandomnessError, RandomnessMessage,
     PlayerId as RandomnessPlayerId,
 };
+pub use tournament::{
+    Tournament, TournamentStatus, TournamentError, TournamentEvent, Participant,
+    BracketMatch, MatchStatus, MatchLobby, MatchResult, ResultSignature,
+};
+pub use lobby::{
+    check_join, pow_bits, BondRequirement, EcashBond, JoinApplication, JoinEvent, JoinPolicy,
+    JoinRejection, JoinVerifier, Lobby, Nip05Requirement,
+};
+pub use reputation::{
+    AbandonReport, BlockList, ReportError, ReportEvent, ReportKind, ReputationRecord,
+    ReputationStore,
+};
+pub use game_index::{
+    GameIndex, GameSummary, LOCAL_SOURCE, game_events, remote_filter, resume_events,
+};
+pub use pitboss::{
+    PitbossHost, PitbossConfig, PitbossError, TurnNotification, DirectMessage,
+    OfflineTurnQueue, QueuedTurn,
+};
+pub use notifier::{
+    Notifier, NotifierConfig, NotifierError, NotifyOutcome, WebhookPayload, WebhookTransport,
+    HttpWebhookTransport,
+};
+pub use presence::{
+    PresenceChange, PresenceEntry, PresenceMap, PresenceStatus, PresenceUpdate,
+    DEFAULT_IDLE_AFTER_MS, DEFAULT_PRESENCE_INTERVAL_MS, DEFAULT_PRESENCE_TIMEOUT_MS,
+};
+pub use debug::{
+    DebugLayer, DebugRecorder, NetworkDebugReport, SpanRecord, SpanSummary,
+    DEFAULT_SPAN_CAPACITY,
+};
+#[cfg(feature = "metrics")]
+pub use metrics::{
+    Counter, Gauge, Histogram, MetricKind, MetricsRegistry, MetricsServer, NetworkMetrics,
+};
+#[cfg(feature = "test-support")]
+pub use harness::{HarnessError, TestClient, TwoClientHarness};
 
 /// Network configuration
 #[derive(Debug, Clone)]
 pub struct NetworkConfig {
     /// Enable Iroh P2P networking
     pub enable_p2p: bool,
-    /// Nostr relay URLs to connect to
+    /// Default Nostr relay URLs, used for players without a NIP-65 relay list
     pub relay_urls: Vec<String>,
     /// Enable local embedded relay
     pub enable_local_relay: bool,
@@ -130,6 +236,9 @@ pub struct NetworkConfig {
     pub ticket_ttl_secs: u64,
     /// Maximum peers to connect to
     pub max_peers: usize,
+    /// Simulated network impairments, honored in debug builds only
+    #[cfg(not(target_arch = "wasm32"))]
+    pub netem: Option<NetemConfig>,
 }
 
 impl Default for NetworkConfig {
@@ -141,6 +250,23 @@ impl Default for NetworkConfig {
             p2p_port: 0,
             ticket_ttl_secs: 3600, // 1 hour
             max_peers: 8,
+            #[cfg(not(target_arch = "wasm32"))]
+            netem: None,
+        }
+    }
+}
+
+#[cfg(not(target_arch = "wasm32"))]
+impl NetworkConfig {
+    /// Get the simulated network impairments to apply.
+    ///
+    /// Always `None` in release builds, so a stray setting can't degrade
+    /// real games.
+    pub fn netem(&self) -> Option<&NetemConfig> {
+        if cfg!(debug_assertions) {
+            self.netem.as_ref()
+        } else {
+            None
         }
     }
 }
@@ -193,6 +319,7 @@ pub fn init(config: &NetworkConfig) -> Result<NetworkHandle, NetworkError> {
             NetworkMode::Light
         },
         stats: NetworkStats::default(),
+        relays: RelaySelector::new(config.relay_urls.clone()),
     })
 }
 
@@ -205,6 +332,8 @@ pub struct NetworkHandle {
     pub mode: NetworkMode,
     /// Statistics.
     pub stats: NetworkStats,
+    /// Per-player relay selection.
+    pub relays: RelaySelector,
 }
 
 impl NetworkHandle {
@@ -223,6 +352,16 @@ impl NetworkHandle {
         self.config.enable_p2p
     }
 
+    /// Get the relay selector.
+    pub fn relays(&self) -> &RelaySelector {
+        &self.relays
+    }
+
+    /// Get the relay selector for updating relay lists.
+    pub fn relays_mut(&mut self) -> &mut RelaySelector {
+        &mut self.relays
+    }
+
     /// Check if local relay is enabled.
     pub fn is_local_relay_enabled(&self) -> bool {
         self.config.enable_local_relay
@@ -261,6 +400,7 @@ impl std::error::Error for NetworkError {}
 #[cfg(test)]
 mod tests {
     use super::*;
+    use nostr_nations_core::types::GameId;
 
     // ==================== NetworkConfig Tests ====================
 
@@ -273,6 +413,7 @@ mod tests {
         assert_eq!(config.max_peers, 8);
         assert_eq!(config.ticket_ttl_secs, 3600);
         assert!(config.relay_urls.is_empty());
+        assert!(config.netem.is_none());
     }
 
     #[test]
@@ -284,6 +425,7 @@ mod tests {
             p2p_port: 4433,
             ticket_ttl_secs: 7200,
             max_peers: 16,
+            netem: None,
         };
 
         let cloned = config.clone();
@@ -434,6 +576,7 @@ mod tests {
             p2p_port: 12345,
             ticket_ttl_secs: 1800,
             max_peers: 4,
+            netem: None,
         };
 
         let handle = init(&config).unwrap();
@@ -509,6 +652,26 @@ mod tests {
         assert_eq!(handle.config.max_peers, cloned.config.max_peers);
     }
 
+    #[test]
+    fn test_network_handle_relays_default_to_config() {
+        let config = NetworkConfig {
+            relay_urls: vec!["wss://relay.example.com".to_string()],
+            ..Default::default()
+        };
+        let mut handle = init(&config).unwrap();
+        assert_eq!(
+            handle.relays().publish_relays(&["alice"]),
+            vec!["wss://relay.example.com"]
+        );
+
+        let list = RelayList::new("alice".to_string(), 1).with_relay("wss://alice.example", true, true);
+        handle.relays_mut().update(list);
+        assert_eq!(
+            handle.relays().publish_relays(&["alice"]),
+            vec!["wss://alice.example"]
+        );
+    }
+
     // ==================== NetworkError Tests ====================
 
     #[test]
@@ -650,6 +813,7 @@ mod tests {
             p2p_port: 0, // Random port
             ticket_ttl_secs: 3600,
             max_peers: 8,
+            netem: None,
         };
 
         let handle = init(&config).unwrap();
@@ -679,6 +843,7 @@ mod tests {
             p2p_port: 0,
             ticket_ttl_secs: 1800,
             max_peers: 4,
+            netem: None,
         };
 
         let handle = init(&config).unwrap();
@@ -698,6 +863,7 @@ mod tests {
             p2p_port: 0,
             ticket_ttl_secs: 3600,
             max_peers: 2,
+            netem: None,
         };
 
         let handle = init(&config).unwrap();
@@ -713,21 +879,21 @@ mod tests {
     fn test_peer_module_reexports() {
         // Verify that peer module types are properly re-exported
         let ticket = ConnectionTicket::new(
-            "node".to_string(),
+            PeerId::from("node"),
             vec![],
-            "game".to_string(),
+            GameId::new("game"),
             3600,
         );
         assert!(!ticket.is_expired());
 
-        let info = PeerInfo::new("peer".to_string());
+        let info = PeerInfo::new(PeerId::from("peer"));
         assert_eq!(info.state, ConnectionState::Connecting);
     }
 
     #[test]
     fn test_sync_module_reexports() {
         // Verify that sync module types are properly re-exported
-        let manager = SyncManager::new("game".to_string(), 0);
+        let manager = SyncManager::new(GameId::new("game"), 0);
         assert_eq!(*manager.state(), SyncState::Idle);
 
         let tracker = PeerSyncTracker::new();
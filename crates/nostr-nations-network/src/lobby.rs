@@ -0,0 +1,809 @@
+//! Join policies for public lobbies.
+//!
+//! A host can require joining players to clear one or more hurdles before
+//! they take a seat, to keep spam joins out of public lobbies:
+//!
+//! - **Allowlist**: only listed npubs may join
+//! - **NIP-05**: the player's NIP-05 identifier must resolve to their npub,
+//!   optionally on one of a set of domains
+//! - **Proof of work**: the join event ID must have a minimum number of
+//!   leading zero bits, committed to in its `nonce` tag (NIP-13)
+//! - **Ecash bond**: the player locks a small Cashu token with the host,
+//!   refunded when the game ends
+//!
+//! Every application carries the player's signed join event. Its ID and
+//! signature are checked first, and every other check runs against the
+//! pubkey that signed it, so a player can't borrow someone else's npub or
+//! someone else's work. Npubs on the host's [`BlockList`] are refused
+//! before any of the policy checks run.
+//!
+//! Checks that need the outside world (resolving NIP-05 identifiers,
+//! checking a token is unspent at its mint) go through a [`JoinVerifier`]
+//! supplied by the host.
+
+use crate::reputation::BlockList;
+use crate::signing::{decode_pubkey, encode_npub, verify_signature, SigningKey};
+use nostr_nations_core::events::kinds;
+use nostr_nations_core::types::{GameId, Npub};
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+use std::collections::BTreeMap;
+use std::fmt;
+
+/// NIP-05 requirement for joining players.
+#[derive(Clone, Debug, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Nip05Requirement {
+    /// Domains the identifier must be on. Empty accepts any domain.
+    #[serde(default)]
+    pub domains: Vec<String>,
+}
+
+/// Ecash bond required to join.
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct BondRequirement {
+    /// Smallest bond accepted, in sats.
+    pub amount_sats: u64,
+    /// Mints the host accepts tokens from. Empty accepts any mint.
+    #[serde(default)]
+    pub mints: Vec<String>,
+}
+
+/// What a player must show to join a lobby. Every requirement that is set
+/// must be met.
+#[derive(Clone, Debug, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct JoinPolicy {
+    /// Npubs allowed to join, or `None` to allow anyone.
+    #[serde(default)]
+    pub allowlist: Option<Vec<Npub>>,
+    /// Verified NIP-05 identifier, if required.
+    #[serde(default)]
+    pub nip05: Option<Nip05Requirement>,
+    /// Leading zero bits required on the join event ID. Zero disables the
+    /// check.
+    #[serde(default)]
+    pub min_pow_bits: u32,
+    /// Ecash bond, if required.
+    #[serde(default)]
+    pub bond: Option<BondRequirement>,
+}
+
+impl JoinPolicy {
+    /// A policy that lets anyone join.
+    pub fn open() -> Self {
+        Self::default()
+    }
+
+    /// Only allow the given npubs.
+    pub fn with_allowlist(mut self, npubs: impl IntoIterator<Item = Npub>) -> Self {
+        self.allowlist = Some(npubs.into_iter().collect());
+        self
+    }
+
+    /// Require a NIP-05 identifier on one of `domains`, or any domain if
+    /// empty.
+    pub fn with_nip05(mut self, domains: impl IntoIterator<Item = String>) -> Self {
+        self.nip05 = Some(Nip05Requirement {
+            domains: domains.into_iter().collect(),
+        });
+        self
+    }
+
+    /// Require proof of work on the join event.
+    pub fn with_pow(mut self, bits: u32) -> Self {
+        self.min_pow_bits = bits;
+        self
+    }
+
+    /// Require an ecash bond of at least `amount_sats` from one of `mints`,
+    /// or any mint if empty.
+    pub fn with_bond(mut self, amount_sats: u64, mints: impl IntoIterator<Item = String>) -> Self {
+        self.bond = Some(BondRequirement {
+            amount_sats,
+            mints: mints.into_iter().collect(),
+        });
+        self
+    }
+
+    /// Check if the policy lets anyone join.
+    pub fn is_open(&self) -> bool {
+        *self == Self::open()
+    }
+}
+
+/// A Cashu token locked with the host as a join bond.
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct EcashBond {
+    /// URL of the mint that issued the token.
+    pub mint: String,
+    /// Token value, in sats.
+    pub amount_sats: u64,
+    /// Serialized Cashu token.
+    pub token: String,
+}
+
+/// A player's signed join event (NIP-01), of kind [`kinds::PLAYER_JOIN`].
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct JoinEvent {
+    /// Event ID, as a hex string.
+    pub id: String,
+    /// Author's x-only public key, as a hex string.
+    pub pubkey: String,
+    /// Unix timestamp.
+    pub created_at: u64,
+    /// Nostr event kind.
+    pub kind: u32,
+    /// Nostr tags.
+    pub tags: Vec<Vec<String>>,
+    /// Event content.
+    pub content: String,
+    /// BIP-340 signature over the ID, as a hex string.
+    pub sig: String,
+}
+
+impl JoinEvent {
+    /// Sign a join event for a game, mining a `nonce` tag until the ID has
+    /// at least `target_bits` leading zero bits (NIP-13).
+    pub fn signed(key: &SigningKey, game_id: &GameId, created_at: u64, target_bits: u32) -> Self {
+        let mut event = Self {
+            id: String::new(),
+            pubkey: to_hex(&key.public_key()),
+            created_at,
+            kind: kinds::PLAYER_JOIN,
+            tags: Vec::new(),
+            content: String::new(),
+            sig: String::new(),
+        };
+        let mut nonce = 0u64;
+        loop {
+            event.tags = vec![vec!["g".to_string(), game_id.to_string()]];
+            if target_bits > 0 {
+                event.tags.push(vec![
+                    "nonce".to_string(),
+                    nonce.to_string(),
+                    target_bits.to_string(),
+                ]);
+            }
+            event.id = to_hex(&event.compute_id());
+            if pow_bits(&event.id) >= target_bits {
+                break;
+            }
+            nonce += 1;
+        }
+        event.sig = to_hex(&key.sign(&event.compute_id()));
+        event
+    }
+
+    /// The ID as NIP-01 defines it: the SHA-256 of the serialized
+    /// `[0, pubkey, created_at, kind, tags, content]` array.
+    pub fn compute_id(&self) -> [u8; 32] {
+        let serialized = serde_json::json!([
+            0,
+            self.pubkey,
+            self.created_at,
+            self.kind,
+            self.tags,
+            self.content
+        ]);
+        Sha256::digest(serialized.to_string().as_bytes()).into()
+    }
+
+    /// Check the event is a join event whose ID matches its contents and
+    /// whose signature is by its pubkey. Returns the verified pubkey.
+    pub fn verify(&self) -> Result<[u8; 32], JoinRejection> {
+        let id = self.compute_id();
+        if self.kind != kinds::PLAYER_JOIN || !self.id.eq_ignore_ascii_case(&to_hex(&id)) {
+            return Err(JoinRejection::InvalidJoinEvent);
+        }
+        let pubkey = decode_pubkey(&self.pubkey).map_err(|_| JoinRejection::InvalidJoinEvent)?;
+        let signature = from_hex(&self.sig).ok_or(JoinRejection::InvalidJoinEvent)?;
+        if !verify_signature(&self.pubkey, &id, &signature) {
+            return Err(JoinRejection::InvalidJoinEvent);
+        }
+        Ok(pubkey)
+    }
+
+    /// Game ID from the event's `g` tag.
+    pub fn game_id(&self) -> Option<&str> {
+        self.tag("g")
+    }
+
+    /// Proof of work the event carries: the leading zero bits of its ID,
+    /// capped at the target committed to in its `nonce` tag. Work that
+    /// wasn't committed to, such as a lucky ID, doesn't count.
+    pub fn committed_work(&self) -> u32 {
+        let target = self
+            .tags
+            .iter()
+            .find(|tag| tag.first().map(String::as_str) == Some("nonce"))
+            .and_then(|tag| tag.get(2))
+            .and_then(|target| target.parse().ok())
+            .unwrap_or(0);
+        pow_bits(&self.id).min(target)
+    }
+
+    fn tag(&self, name: &str) -> Option<&str> {
+        self.tags
+            .iter()
+            .find(|tag| tag.first().map(String::as_str) == Some(name))
+            .and_then(|tag| tag.get(1))
+            .map(String::as_str)
+    }
+}
+
+/// What a joining player presents to the lobby.
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct JoinApplication {
+    /// Player's signed join event.
+    pub event: JoinEvent,
+    /// Player's NIP-05 identifier (`name@domain`).
+    #[serde(default)]
+    pub nip05: Option<String>,
+    /// Bond offered.
+    #[serde(default)]
+    pub bond: Option<EcashBond>,
+}
+
+impl JoinApplication {
+    /// Create an application with no NIP-05 identifier or bond.
+    pub fn new(event: JoinEvent) -> Self {
+        Self {
+            event,
+            nip05: None,
+            bond: None,
+        }
+    }
+}
+
+/// Checks that need the outside world.
+pub trait JoinVerifier {
+    /// The pubkey a NIP-05 identifier resolves to, if any.
+    fn resolve_nip05(&self, identifier: &str) -> Option<Npub>;
+
+    /// Check a bond token is genuine and unspent, e.g. by swapping it at
+    /// the mint.
+    fn verify_bond(&self, bond: &EcashBond) -> bool;
+}
+
+/// Reasons a join is refused.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum JoinRejection {
+    /// The join event's ID or signature doesn't check out.
+    InvalidJoinEvent,
+    /// The join event is for another game.
+    WrongGame,
+    /// The npub isn't on the allowlist.
+    NotAllowed,
+    /// No NIP-05 identifier was given.
+    Nip05Missing,
+    /// The NIP-05 identifier doesn't resolve to the player's npub.
+    Nip05Unverified(String),
+    /// The NIP-05 identifier is on a domain the host doesn't accept.
+    Nip05Domain(String),
+    /// The join event doesn't carry enough proof of work.
+    InsufficientWork { required: u32, actual: u32 },
+    /// No bond was offered.
+    BondMissing,
+    /// The bond is worth less than required.
+    BondTooSmall { required: u64, offered: u64 },
+    /// The bond comes from a mint the host doesn't accept.
+    UntrustedMint(String),
+    /// The bond token is invalid or already spent.
+    BondInvalid,
+    /// The player is already seated.
+    AlreadyJoined,
+    /// The host has blocked the npub.
+    Blocked,
+}
+
+impl fmt::Display for JoinRejection {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            JoinRejection::InvalidJoinEvent => write!(f, "Join event is not validly signed"),
+            JoinRejection::WrongGame => write!(f, "Join event is for another game"),
+            JoinRejection::NotAllowed => write!(f, "Not on the lobby allowlist"),
+            JoinRejection::Nip05Missing => write!(f, "A NIP-05 identifier is required"),
+            JoinRejection::Nip05Unverified(id) => write!(f, "Could not verify NIP-05 {}", id),
+            JoinRejection::Nip05Domain(id) => write!(f, "NIP-05 domain not accepted: {}", id),
+            JoinRejection::InsufficientWork { required, actual } => write!(
+                f,
+                "Join event has {} bits of work, {} required",
+                actual, required
+            ),
+            JoinRejection::BondMissing => write!(f, "An ecash bond is required"),
+            JoinRejection::BondTooSmall { required, offered } => write!(
+                f,
+                "Bond of {} sats is less than the required {}",
+                offered, required
+            ),
+            JoinRejection::UntrustedMint(mint) => write!(f, "Mint not accepted: {}", mint),
+            JoinRejection::BondInvalid => write!(f, "Bond token is invalid or spent"),
+            JoinRejection::AlreadyJoined => write!(f, "Already joined"),
+            JoinRejection::Blocked => write!(f, "Blocked by the host"),
+        }
+    }
+}
+
+impl std::error::Error for JoinRejection {}
+
+/// Number of leading zero bits in a hex event ID (NIP-13 difficulty).
+///
+/// Counting stops at the first character that isn't a hex digit.
+pub fn pow_bits(event_id: &str) -> u32 {
+    let mut bits = 0;
+    for c in event_id.chars() {
+        let Some(nibble) = c.to_digit(16) else {
+            break;
+        };
+        if nibble == 0 {
+            bits += 4;
+        } else {
+            bits += nibble.leading_zeros() - 28;
+            break;
+        }
+    }
+    bits
+}
+
+/// Check an application against a policy, without seating the player.
+/// Returns the npub that signed the join event.
+pub fn check_join(
+    policy: &JoinPolicy,
+    application: &JoinApplication,
+    verifier: &dyn JoinVerifier,
+) -> Result<Npub, JoinRejection> {
+    let pubkey = application.event.verify()?;
+    check_policy(policy, &pubkey, application, verifier)?;
+    Ok(encode_npub(&pubkey))
+}
+
+/// Check an application whose join event was signed by `pubkey`.
+fn check_policy(
+    policy: &JoinPolicy,
+    pubkey: &[u8; 32],
+    application: &JoinApplication,
+    verifier: &dyn JoinVerifier,
+) -> Result<(), JoinRejection> {
+    let is_player = |npub: &Npub| decode_pubkey(npub.as_str()).as_ref() == Ok(pubkey);
+
+    if let Some(ref allowlist) = policy.allowlist {
+        if !allowlist.iter().any(is_player) {
+            return Err(JoinRejection::NotAllowed);
+        }
+    }
+
+    if policy.min_pow_bits > 0 {
+        let actual = application.event.committed_work();
+        if actual < policy.min_pow_bits {
+            return Err(JoinRejection::InsufficientWork {
+                required: policy.min_pow_bits,
+                actual,
+            });
+        }
+    }
+
+    if let Some(ref requirement) = policy.nip05 {
+        let identifier = application
+            .nip05
+            .as_ref()
+            .ok_or(JoinRejection::Nip05Missing)?;
+        let domain = identifier.rsplit_once('@').map_or("", |(_, d)| d);
+        if !requirement.domains.is_empty()
+            && !requirement
+                .domains
+                .iter()
+                .any(|d| d.eq_ignore_ascii_case(domain))
+        {
+            return Err(JoinRejection::Nip05Domain(identifier.clone()));
+        }
+        if !verifier
+            .resolve_nip05(identifier)
+            .is_some_and(|npub| is_player(&npub))
+        {
+            return Err(JoinRejection::Nip05Unverified(identifier.clone()));
+        }
+    }
+
+    // Bonds are checked last so the mint is only contacted for otherwise
+    // acceptable players
+    if let Some(ref requirement) = policy.bond {
+        let bond = application
+            .bond
+            .as_ref()
+            .ok_or(JoinRejection::BondMissing)?;
+        if bond.amount_sats < requirement.amount_sats {
+            return Err(JoinRejection::BondTooSmall {
+                required: requirement.amount_sats,
+                offered: bond.amount_sats,
+            });
+        }
+        if !requirement.mints.is_empty() && !requirement.mints.contains(&bond.mint) {
+            return Err(JoinRejection::UntrustedMint(bond.mint.clone()));
+        }
+        if !verifier.verify_bond(bond) {
+            return Err(JoinRejection::BondInvalid);
+        }
+    }
+
+    Ok(())
+}
+
+/// A lobby's seated players and the bonds it holds for them.
+#[derive(Clone, Debug)]
+pub struct Lobby {
+    game_id: GameId,
+    policy: JoinPolicy,
+    /// Npubs refused whatever the policy says.
+    blocked: BlockList,
+    /// Admitted players, with the bond each one locked.
+    players: BTreeMap<Npub, Option<EcashBond>>,
+}
+
+impl Lobby {
+    /// Create a lobby with a join policy.
+    pub fn new(game_id: GameId, policy: JoinPolicy) -> Self {
+        Self {
+            game_id,
+            policy,
+            blocked: BlockList::new(),
+            players: BTreeMap::new(),
+        }
+    }
+
+    /// Game ID of the lobby.
+    pub fn game_id(&self) -> &GameId {
+        &self.game_id
+    }
+
+    /// The lobby's join policy.
+    pub fn policy(&self) -> &JoinPolicy {
+        &self.policy
+    }
+
+    /// Change the join policy. Players already admitted keep their seats.
+    pub fn set_policy(&mut self, policy: JoinPolicy) {
+        self.policy = policy;
+    }
+
+    /// Replace the block list. Players already admitted keep their seats.
+    pub fn set_block_list(&mut self, blocked: BlockList) {
+        self.blocked = blocked;
+    }
+
+    /// Check an application and seat the player, holding their bond.
+    /// Returns the npub the player was seated as.
+    pub fn admit(
+        &mut self,
+        application: JoinApplication,
+        verifier: &dyn JoinVerifier,
+    ) -> Result<Npub, JoinRejection> {
+        let pubkey = application.event.verify()?;
+        if application.event.game_id() != Some(self.game_id.as_str()) {
+            return Err(JoinRejection::WrongGame);
+        }
+        let npub = encode_npub(&pubkey);
+        if self.blocked.is_blocked(&npub) {
+            return Err(JoinRejection::Blocked);
+        }
+        if self.players.contains_key(&npub) {
+            return Err(JoinRejection::AlreadyJoined);
+        }
+        check_policy(&self.policy, &pubkey, &application, verifier)?;
+        let bond = self.policy.bond.as_ref().and(application.bond);
+        self.players.insert(npub.clone(), bond);
+        Ok(npub)
+    }
+
+    /// Check if a player has been admitted.
+    pub fn is_admitted(&self, npub: &Npub) -> bool {
+        self.players.contains_key(npub)
+    }
+
+    /// Number of admitted players.
+    pub fn player_count(&self) -> usize {
+        self.players.len()
+    }
+
+    /// Remove a player who left before the game started, returning their
+    /// bond for refund.
+    pub fn leave(&mut self, npub: &Npub) -> Option<EcashBond> {
+        self.players.remove(npub).flatten()
+    }
+
+    /// Bonds currently held, by player.
+    pub fn held_bonds(&self) -> impl Iterator<Item = (&Npub, &EcashBond)> {
+        self.players
+            .iter()
+            .filter_map(|(npub, bond)| bond.as_ref().map(|b| (npub, b)))
+    }
+
+    /// Release every bond at game end, for refunding to its player.
+    pub fn refund_bonds(&mut self) -> Vec<(Npub, EcashBond)> {
+        self.players
+            .iter_mut()
+            .filter_map(|(npub, bond)| bond.take().map(|b| (npub.clone(), b)))
+            .collect()
+    }
+}
+
+fn to_hex(bytes: &[u8]) -> String {
+    bytes.iter().map(|b| format!("{:02x}", b)).collect()
+}
+
+fn from_hex(hex: &str) -> Option<Vec<u8>> {
+    if !hex.len().is_multiple_of(2) || !hex.is_ascii() {
+        return None;
+    }
+    (0..hex.len())
+        .step_by(2)
+        .map(|i| u8::from_str_radix(&hex[i..i + 2], 16).ok())
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::collections::HashMap;
+
+    const MINT: &str = "https://mint.example";
+
+    fn key(seed: u8) -> SigningKey {
+        SigningKey::from_secret_bytes(&[seed; 32]).unwrap()
+    }
+
+    fn npub(seed: u8) -> Npub {
+        key(seed).npub()
+    }
+
+    /// An application from player `seed` to `game1` with `bits` of work.
+    fn apply(seed: u8, bits: u32) -> JoinApplication {
+        JoinApplication::new(JoinEvent::signed(
+            &key(seed),
+            &GameId::new("game1"),
+            1_700_000_000,
+            bits,
+        ))
+    }
+
+    #[derive(Default)]
+    struct TestVerifier {
+        nip05: HashMap<String, Npub>,
+        spent: Vec<String>,
+    }
+
+    impl JoinVerifier for TestVerifier {
+        fn resolve_nip05(&self, identifier: &str) -> Option<Npub> {
+            self.nip05.get(identifier).cloned()
+        }
+
+        fn verify_bond(&self, bond: &EcashBond) -> bool {
+            !self.spent.contains(&bond.token)
+        }
+    }
+
+    fn bond(amount_sats: u64, token: &str) -> EcashBond {
+        EcashBond {
+            mint: MINT.to_string(),
+            amount_sats,
+            token: token.to_string(),
+        }
+    }
+
+    #[test]
+    fn test_pow_bits() {
+        assert_eq!(pow_bits("ffff"), 0);
+        assert_eq!(pow_bits("7fff"), 1);
+        assert_eq!(pow_bits("0fff"), 4);
+        assert_eq!(pow_bits("002f"), 10);
+        assert_eq!(pow_bits("0000"), 16);
+        assert_eq!(pow_bits(""), 0);
+    }
+
+    #[test]
+    fn test_join_event_id_matches_nip01() {
+        let event = apply(1, 0).event;
+        assert_eq!(event.kind, kinds::PLAYER_JOIN);
+        assert_eq!(event.game_id(), Some("game1"));
+        assert_eq!(event.verify(), Ok(key(1).public_key()));
+
+        let serialized = format!(
+            r#"[0,"{}",1700000000,{},[["g","game1"]],""]"#,
+            event.pubkey,
+            kinds::PLAYER_JOIN
+        );
+        assert_eq!(event.id, to_hex(&Sha256::digest(serialized.as_bytes())));
+    }
+
+    #[test]
+    fn test_open_policy_admits_anyone_once() {
+        let mut lobby = Lobby::new(GameId::new("game1"), JoinPolicy::open());
+        assert!(lobby.policy().is_open());
+        let application = apply(1, 0);
+        let verifier = TestVerifier::default();
+        assert_eq!(lobby.admit(application.clone(), &verifier), Ok(npub(1)));
+        assert!(lobby.is_admitted(&npub(1)));
+        assert_eq!(
+            lobby.admit(application, &verifier),
+            Err(JoinRejection::AlreadyJoined)
+        );
+    }
+
+    #[test]
+    fn test_allowlist_and_pow() {
+        let policy = JoinPolicy::open().with_allowlist([npub(1)]).with_pow(8);
+        let verifier = TestVerifier::default();
+
+        let stranger = apply(2, 8);
+        assert_eq!(
+            check_join(&policy, &stranger, &verifier),
+            Err(JoinRejection::NotAllowed)
+        );
+        let lazy = apply(1, 4);
+        assert_eq!(
+            check_join(&policy, &lazy, &verifier),
+            Err(JoinRejection::InsufficientWork {
+                required: 8,
+                actual: 4
+            })
+        );
+        let worked = apply(1, 8);
+        assert_eq!(check_join(&policy, &worked, &verifier), Ok(npub(1)));
+
+        // Hex keys on the allowlist match too
+        let policy = policy.with_allowlist([Npub::from(worked.event.pubkey.as_str())]);
+        assert_eq!(check_join(&policy, &worked, &verifier), Ok(npub(1)));
+    }
+
+    #[test]
+    fn test_forged_join_events_refused() {
+        let policy = JoinPolicy::open().with_allowlist([npub(1)]).with_pow(8);
+        let verifier = TestVerifier::default();
+
+        // Claiming an allowed npub without its key
+        let mut impostor = apply(2, 8);
+        impostor.event.pubkey = apply(1, 0).event.pubkey;
+        assert_eq!(
+            check_join(&policy, &impostor, &verifier),
+            Err(JoinRejection::InvalidJoinEvent)
+        );
+        impostor.event.id = to_hex(&impostor.event.compute_id());
+        assert_eq!(
+            check_join(&policy, &impostor, &verifier),
+            Err(JoinRejection::InvalidJoinEvent)
+        );
+
+        // Claiming work the ID doesn't have
+        let mut boaster = apply(1, 0);
+        boaster.event.id = format!("0000{}", &boaster.event.id[4..]);
+        assert_eq!(
+            check_join(&policy, &boaster, &verifier),
+            Err(JoinRejection::InvalidJoinEvent)
+        );
+
+        // A lucky ID without a committed target has no work
+        let mut lucky = apply(1, 8);
+        lucky.event.tags.truncate(1);
+        assert_eq!(lucky.event.committed_work(), 0);
+
+        // Tampering with a signed event
+        let mut edited = apply(1, 8);
+        edited.event.content = "edited".to_string();
+        assert_eq!(
+            check_join(&policy, &edited, &verifier),
+            Err(JoinRejection::InvalidJoinEvent)
+        );
+        let mut unsigned = apply(1, 8);
+        unsigned.event.sig.clear();
+        assert_eq!(
+            check_join(&policy, &unsigned, &verifier),
+            Err(JoinRejection::InvalidJoinEvent)
+        );
+    }
+
+    #[test]
+    fn test_join_event_for_another_game_refused() {
+        let mut lobby = Lobby::new(GameId::new("game2"), JoinPolicy::open());
+        assert_eq!(
+            lobby.admit(apply(1, 0), &TestVerifier::default()),
+            Err(JoinRejection::WrongGame)
+        );
+        assert!(!lobby.is_admitted(&npub(1)));
+    }
+
+    #[test]
+    fn test_nip05_must_resolve_to_player() {
+        let policy = JoinPolicy::open().with_nip05(["example.com".to_string()]);
+        let mut verifier = TestVerifier::default();
+        verifier
+            .nip05
+            .insert("alice@example.com".to_string(), npub(1));
+
+        let mut application = apply(1, 0);
+        assert_eq!(
+            check_join(&policy, &application, &verifier),
+            Err(JoinRejection::Nip05Missing)
+        );
+        application.nip05 = Some("alice@other.com".to_string());
+        assert_eq!(
+            check_join(&policy, &application, &verifier),
+            Err(JoinRejection::Nip05Domain("alice@other.com".to_string()))
+        );
+        application.nip05 = Some("alice@example.com".to_string());
+        assert_eq!(check_join(&policy, &application, &verifier), Ok(npub(1)));
+
+        // Someone else's identifier doesn't count
+        application.event = apply(2, 0).event;
+        assert_eq!(
+            check_join(&policy, &application, &verifier),
+            Err(JoinRejection::Nip05Unverified(
+                "alice@example.com".to_string()
+            ))
+        );
+    }
+
+    #[test]
+    fn test_bonds_held_and_refunded() {
+        let policy = JoinPolicy::open().with_bond(100, [MINT.to_string()]);
+        let mut lobby = Lobby::new(GameId::new("game1"), policy);
+        let verifier = TestVerifier {
+            spent: vec!["spent".to_string()],
+            ..TestVerifier::default()
+        };
+
+        let mut application = apply(1, 0);
+        assert_eq!(
+            lobby.admit(application.clone(), &verifier),
+            Err(JoinRejection::BondMissing)
+        );
+        application.bond = Some(bond(50, "small"));
+        assert_eq!(
+            lobby.admit(application.clone(), &verifier),
+            Err(JoinRejection::BondTooSmall {
+                required: 100,
+                offered: 50
+            })
+        );
+        application.bond = Some(EcashBond {
+            mint: "https://other.mint".to_string(),
+            ..bond(100, "elsewhere")
+        });
+        assert_eq!(
+            lobby.admit(application.clone(), &verifier),
+            Err(JoinRejection::UntrustedMint(
+                "https://other.mint".to_string()
+            ))
+        );
+        application.bond = Some(bond(100, "spent"));
+        assert_eq!(
+            lobby.admit(application.clone(), &verifier),
+            Err(JoinRejection::BondInvalid)
+        );
+
+        application.bond = Some(bond(100, "good"));
+        lobby.admit(application, &verifier).unwrap();
+        let mut second = apply(2, 0);
+        second.bond = Some(bond(150, "leaver"));
+        lobby.admit(second, &verifier).unwrap();
+        assert_eq!(lobby.held_bonds().count(), 2);
+
+        assert_eq!(lobby.leave(&npub(2)), Some(bond(150, "leaver")));
+        let refunds = lobby.refund_bonds();
+        assert_eq!(refunds, vec![(npub(1), bond(100, "good"))]);
+        assert_eq!(lobby.held_bonds().count(), 0);
+        assert!(lobby.is_admitted(&npub(1)));
+    }
+
+    #[test]
+    fn test_blocked_npub_refused_even_when_open() {
+        let mut lobby = Lobby::new(GameId::new("game1"), JoinPolicy::open());
+        let mut blocked = BlockList::new();
+        blocked.block(npub(1), "abandoned three games");
+        lobby.set_block_list(blocked);
+
+        let verifier = TestVerifier::default();
+        assert_eq!(
+            lobby.admit(apply(1, 0), &verifier),
+            Err(JoinRejection::Blocked)
+        );
+        lobby.admit(apply(2, 0), &verifier).unwrap();
+    }
+}
@@ -0,0 +1,753 @@
+//! Prometheus metrics for dedicated hosts.
+//!
+//! Enabled with the `metrics` feature. A [`MetricsRegistry`] holds
+//! counters, gauges and histograms and renders them in the Prometheus text
+//! exposition format. [`NetworkMetrics`] pre-registers the metrics a
+//! headless relay/host cares about, and [`MetricsServer`] serves the
+//! registry over plain HTTP at `/metrics` for scraping.
+//!
+//! ```ignore
+//! let registry = MetricsRegistry::new();
+//! let metrics = NetworkMetrics::new(&registry);
+//! let _server = MetricsServer::start("0.0.0.0:9464", registry)?;
+//!
+//! relay.publish(&event)?;
+//! metrics.record_event_stored();
+//! metrics.record_publish();
+//! ```
+
+use std::collections::VecDeque;
+use std::io::{self, Read, Write};
+use std::net::{SocketAddr, TcpListener, TcpStream, ToSocketAddrs};
+use std::sync::atomic::{AtomicBool, AtomicU64, Ordering};
+use std::sync::{Arc, Mutex};
+use std::thread::JoinHandle;
+use std::time::{Duration, Instant};
+
+/// Prefix applied to every metric registered by [`NetworkMetrics`].
+pub const METRIC_PREFIX: &str = "nostr_nations";
+
+/// Window used to compute the publish rate.
+pub const PUBLISH_RATE_WINDOW: Duration = Duration::from_secs(10);
+
+/// Histogram buckets for peer round-trip times, in milliseconds.
+pub const RTT_BUCKETS_MS: &[f64] = &[5.0, 10.0, 25.0, 50.0, 100.0, 250.0, 500.0, 1000.0, 2500.0];
+
+/// Histogram buckets for sync durations, in seconds.
+pub const SYNC_BUCKETS_SECS: &[f64] = &[0.01, 0.05, 0.1, 0.25, 0.5, 1.0, 2.5, 5.0, 10.0, 30.0];
+
+/// Content type of the Prometheus text format.
+const CONTENT_TYPE: &str = "text/plain; version=0.0.4";
+
+/// A monotonically increasing counter.
+#[derive(Clone, Debug, Default)]
+pub struct Counter(Arc<AtomicU64>);
+
+impl Counter {
+    /// Increment by one.
+    pub fn inc(&self) {
+        self.inc_by(1);
+    }
+
+    /// Increment by `n`.
+    pub fn inc_by(&self, n: u64) {
+        self.0.fetch_add(n, Ordering::Relaxed);
+    }
+
+    /// Get the current value.
+    pub fn get(&self) -> u64 {
+        self.0.load(Ordering::Relaxed)
+    }
+}
+
+/// A value that can go up and down.
+#[derive(Clone, Debug, Default)]
+pub struct Gauge(Arc<AtomicU64>);
+
+impl Gauge {
+    /// Set the value.
+    pub fn set(&self, value: f64) {
+        self.0.store(value.to_bits(), Ordering::Relaxed);
+    }
+
+    /// Get the current value.
+    pub fn get(&self) -> f64 {
+        f64::from_bits(self.0.load(Ordering::Relaxed))
+    }
+}
+
+#[derive(Debug)]
+struct HistogramData {
+    bounds: Vec<f64>,
+    counts: Vec<u64>,
+    sum: f64,
+    count: u64,
+}
+
+/// Samples observations into fixed buckets.
+#[derive(Clone, Debug)]
+pub struct Histogram(Arc<Mutex<HistogramData>>);
+
+impl Histogram {
+    /// Create a histogram with the given upper bucket bounds.
+    pub fn new(buckets: &[f64]) -> Self {
+        let mut bounds: Vec<f64> = buckets.iter().copied().filter(|b| b.is_finite()).collect();
+        bounds.sort_by(f64::total_cmp);
+        bounds.dedup();
+        let counts = vec![0; bounds.len()];
+        Self(Arc::new(Mutex::new(HistogramData {
+            bounds,
+            counts,
+            sum: 0.0,
+            count: 0,
+        })))
+    }
+
+    /// Record an observation.
+    pub fn observe(&self, value: f64) {
+        let Ok(mut data) = self.0.lock() else {
+            return;
+        };
+        if let Some(i) = data.bounds.iter().position(|&b| value <= b) {
+            data.counts[i] += 1;
+        }
+        data.sum += value;
+        data.count += 1;
+    }
+
+    /// Get the number of observations.
+    pub fn count(&self) -> u64 {
+        self.0.lock().map(|d| d.count).unwrap_or(0)
+    }
+
+    /// Get the sum of all observations.
+    pub fn sum(&self) -> f64 {
+        self.0.lock().map(|d| d.sum).unwrap_or(0.0)
+    }
+}
+
+/// Kind of a metric family.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum MetricKind {
+    /// Monotonically increasing count.
+    Counter,
+    /// Point-in-time value.
+    Gauge,
+    /// Bucketed observations.
+    Histogram,
+}
+
+impl MetricKind {
+    fn as_str(&self) -> &'static str {
+        match self {
+            MetricKind::Counter => "counter",
+            MetricKind::Gauge => "gauge",
+            MetricKind::Histogram => "histogram",
+        }
+    }
+}
+
+#[derive(Clone, Debug)]
+enum Series {
+    Counter(Counter),
+    Gauge(Gauge),
+    Histogram(Histogram),
+}
+
+type Labels = Vec<(String, String)>;
+
+#[derive(Debug)]
+struct Family {
+    name: String,
+    help: String,
+    kind: MetricKind,
+    series: Vec<(Labels, Series)>,
+}
+
+/// Registry of metrics rendered together in one scrape.
+///
+/// Cloning a registry shares the underlying metrics.
+#[derive(Clone, Debug, Default)]
+pub struct MetricsRegistry {
+    families: Arc<Mutex<Vec<Family>>>,
+}
+
+impl MetricsRegistry {
+    /// Create an empty registry.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Register (or look up) a counter.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `name` is already registered with a different kind.
+    pub fn counter(&self, name: &str, help: &str) -> Counter {
+        match self.series(name, help, MetricKind::Counter, &[], || {
+            Series::Counter(Counter::default())
+        }) {
+            Series::Counter(counter) => counter,
+            _ => unreachable!(),
+        }
+    }
+
+    /// Register (or look up) a gauge.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `name` is already registered with a different kind.
+    pub fn gauge(&self, name: &str, help: &str) -> Gauge {
+        self.gauge_with_labels(name, help, &[])
+    }
+
+    /// Register (or look up) one labelled series of a gauge.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `name` is already registered with a different kind.
+    pub fn gauge_with_labels(&self, name: &str, help: &str, labels: &[(&str, &str)]) -> Gauge {
+        match self.series(name, help, MetricKind::Gauge, labels, || {
+            Series::Gauge(Gauge::default())
+        }) {
+            Series::Gauge(gauge) => gauge,
+            _ => unreachable!(),
+        }
+    }
+
+    /// Register (or look up) a histogram with the given bucket bounds.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `name` is already registered with a different kind.
+    pub fn histogram(&self, name: &str, help: &str, buckets: &[f64]) -> Histogram {
+        match self.series(name, help, MetricKind::Histogram, &[], || {
+            Series::Histogram(Histogram::new(buckets))
+        }) {
+            Series::Histogram(histogram) => histogram,
+            _ => unreachable!(),
+        }
+    }
+
+    fn series(
+        &self,
+        name: &str,
+        help: &str,
+        kind: MetricKind,
+        labels: &[(&str, &str)],
+        create: impl FnOnce() -> Series,
+    ) -> Series {
+        let labels: Labels = labels
+            .iter()
+            .map(|(k, v)| (k.to_string(), v.to_string()))
+            .collect();
+        let mut families = self.families.lock().unwrap_or_else(|e| e.into_inner());
+
+        let index = match families.iter().position(|f| f.name == name) {
+            Some(i) => i,
+            None => {
+                families.push(Family {
+                    name: name.to_string(),
+                    help: help.to_string(),
+                    kind,
+                    series: Vec::new(),
+                });
+                families.len() - 1
+            }
+        };
+        let family = &mut families[index];
+        assert_eq!(
+            family.kind,
+            kind,
+            "metric {} already registered as a {}",
+            name,
+            family.kind.as_str()
+        );
+
+        if let Some((_, series)) = family.series.iter().find(|(l, _)| *l == labels) {
+            return series.clone();
+        }
+        let series = create();
+        family.series.push((labels, series.clone()));
+        series
+    }
+
+    /// Render all metrics in the Prometheus text exposition format.
+    pub fn render(&self) -> String {
+        let families = self.families.lock().unwrap_or_else(|e| e.into_inner());
+        let mut out = String::new();
+
+        for family in families.iter() {
+            out.push_str(&format!(
+                "# HELP {} {}\n",
+                family.name,
+                escape_help(&family.help)
+            ));
+            out.push_str(&format!(
+                "# TYPE {} {}\n",
+                family.name,
+                family.kind.as_str()
+            ));
+
+            for (labels, series) in &family.series {
+                match series {
+                    Series::Counter(counter) => {
+                        out.push_str(&format!(
+                            "{}{} {}\n",
+                            family.name,
+                            format_labels(labels, None),
+                            counter.get()
+                        ));
+                    }
+                    Series::Gauge(gauge) => {
+                        out.push_str(&format!(
+                            "{}{} {}\n",
+                            family.name,
+                            format_labels(labels, None),
+                            format_value(gauge.get())
+                        ));
+                    }
+                    Series::Histogram(histogram) => {
+                        render_histogram(&mut out, &family.name, labels, histogram);
+                    }
+                }
+            }
+        }
+
+        out
+    }
+}
+
+fn render_histogram(out: &mut String, name: &str, labels: &Labels, histogram: &Histogram) {
+    let Ok(data) = histogram.0.lock() else {
+        return;
+    };
+
+    let mut cumulative = 0;
+    for (bound, count) in data.bounds.iter().zip(&data.counts) {
+        cumulative += count;
+        out.push_str(&format!(
+            "{}_bucket{} {}\n",
+            name,
+            format_labels(labels, Some(&format_value(*bound))),
+            cumulative
+        ));
+    }
+    out.push_str(&format!(
+        "{}_bucket{} {}\n",
+        name,
+        format_labels(labels, Some("+Inf")),
+        data.count
+    ));
+    out.push_str(&format!(
+        "{}_sum{} {}\n",
+        name,
+        format_labels(labels, None),
+        format_value(data.sum)
+    ));
+    out.push_str(&format!(
+        "{}_count{} {}\n",
+        name,
+        format_labels(labels, None),
+        data.count
+    ));
+}
+
+fn format_labels(labels: &Labels, le: Option<&str>) -> String {
+    let mut parts: Vec<String> = labels
+        .iter()
+        .map(|(k, v)| format!("{}=\"{}\"", k, escape_label(v)))
+        .collect();
+    if let Some(le) = le {
+        parts.push(format!("le=\"{}\"", le));
+    }
+    if parts.is_empty() {
+        String::new()
+    } else {
+        format!("{{{}}}", parts.join(","))
+    }
+}
+
+fn format_value(value: f64) -> String {
+    if value.is_nan() {
+        "NaN".to_string()
+    } else if value == f64::INFINITY {
+        "+Inf".to_string()
+    } else if value == f64::NEG_INFINITY {
+        "-Inf".to_string()
+    } else {
+        value.to_string()
+    }
+}
+
+fn escape_help(help: &str) -> String {
+    help.replace('\\', "\\\\").replace('\n', "\\n")
+}
+
+fn escape_label(value: &str) -> String {
+    value
+        .replace('\\', "\\\\")
+        .replace('"', "\\\"")
+        .replace('\n', "\\n")
+}
+
+/// Standard metrics for a headless relay/host.
+#[derive(Clone, Debug)]
+pub struct NetworkMetrics {
+    registry: MetricsRegistry,
+    events_stored: Counter,
+    publishes: Counter,
+    publish_rate: Gauge,
+    peer_rtt: Histogram,
+    sync_duration: Histogram,
+    recent_publishes: Arc<Mutex<VecDeque<Instant>>>,
+}
+
+impl NetworkMetrics {
+    /// Register the standard metrics in `registry`.
+    pub fn new(registry: &MetricsRegistry) -> Self {
+        Self {
+            registry: registry.clone(),
+            events_stored: registry.counter(
+                &metric_name("events_stored_total"),
+                "Events stored by the local relay",
+            ),
+            publishes: registry.counter(
+                &metric_name("publishes_total"),
+                "Events published to peers and relays",
+            ),
+            publish_rate: registry.gauge(
+                &metric_name("publishes_per_second"),
+                "Publishes per second over the last 10 seconds",
+            ),
+            peer_rtt: registry.histogram(
+                &metric_name("peer_rtt_milliseconds"),
+                "Peer ping round-trip time",
+                RTT_BUCKETS_MS,
+            ),
+            sync_duration: registry.histogram(
+                &metric_name("sync_duration_seconds"),
+                "Time taken to complete a state sync",
+                SYNC_BUCKETS_SECS,
+            ),
+            recent_publishes: Arc::new(Mutex::new(VecDeque::new())),
+        }
+    }
+
+    /// Get the underlying registry.
+    pub fn registry(&self) -> &MetricsRegistry {
+        &self.registry
+    }
+
+    /// Record an event stored by the relay.
+    pub fn record_event_stored(&self) {
+        self.events_stored.inc();
+    }
+
+    /// Record a publish and update the publish rate.
+    pub fn record_publish(&self) {
+        self.record_publish_at(Instant::now());
+    }
+
+    fn record_publish_at(&self, now: Instant) {
+        self.publishes.inc();
+
+        let mut recent = self
+            .recent_publishes
+            .lock()
+            .unwrap_or_else(|e| e.into_inner());
+        recent.push_back(now);
+        while recent
+            .front()
+            .is_some_and(|t| now.duration_since(*t) > PUBLISH_RATE_WINDOW)
+        {
+            recent.pop_front();
+        }
+        self.publish_rate
+            .set(recent.len() as f64 / PUBLISH_RATE_WINDOW.as_secs_f64());
+    }
+
+    /// Record a peer ping round-trip time.
+    pub fn observe_peer_rtt(&self, rtt_ms: u32) {
+        self.peer_rtt.observe(rtt_ms as f64);
+    }
+
+    /// Set the depth of a named queue (e.g. "priority", "batch", "offline").
+    pub fn set_queue_depth(&self, queue: &str, depth: usize) {
+        self.registry
+            .gauge_with_labels(
+                &metric_name("queue_depth"),
+                "Events waiting in a queue",
+                &[("queue", queue)],
+            )
+            .set(depth as f64);
+    }
+
+    /// Record how long a sync took.
+    pub fn observe_sync_duration(&self, duration: Duration) {
+        self.sync_duration.observe(duration.as_secs_f64());
+    }
+}
+
+fn metric_name(suffix: &str) -> String {
+    format!("{}_{}", METRIC_PREFIX, suffix)
+}
+
+/// Minimal HTTP server exposing a registry at `/metrics`.
+///
+/// Runs on a background thread until dropped or [`stop`](Self::stop)ped.
+pub struct MetricsServer {
+    addr: SocketAddr,
+    shutdown: Arc<AtomicBool>,
+    handle: Option<JoinHandle<()>>,
+}
+
+impl MetricsServer {
+    /// Bind to `addr` and start serving `registry`.
+    pub fn start(addr: impl ToSocketAddrs, registry: MetricsRegistry) -> io::Result<Self> {
+        let listener = TcpListener::bind(addr)?;
+        let addr = listener.local_addr()?;
+        let shutdown = Arc::new(AtomicBool::new(false));
+
+        let stop = shutdown.clone();
+        let handle = std::thread::Builder::new()
+            .name("metrics-server".to_string())
+            .spawn(move || {
+                for stream in listener.incoming() {
+                    if stop.load(Ordering::Relaxed) {
+                        break;
+                    }
+                    if let Ok(stream) = stream {
+                        let _ = handle_connection(stream, &registry);
+                    }
+                }
+            })?;
+
+        Ok(Self {
+            addr,
+            shutdown,
+            handle: Some(handle),
+        })
+    }
+
+    /// Get the address the server is listening on.
+    pub fn local_addr(&self) -> SocketAddr {
+        self.addr
+    }
+
+    /// Stop the server and wait for its thread to exit.
+    pub fn stop(&mut self) {
+        let Some(handle) = self.handle.take() else {
+            return;
+        };
+        self.shutdown.store(true, Ordering::Relaxed);
+        // Wake the accept loop so it sees the shutdown flag
+        let _ = TcpStream::connect(self.addr);
+        let _ = handle.join();
+    }
+}
+
+impl Drop for MetricsServer {
+    fn drop(&mut self) {
+        self.stop();
+    }
+}
+
+fn handle_connection(mut stream: TcpStream, registry: &MetricsRegistry) -> io::Result<()> {
+    stream.set_read_timeout(Some(Duration::from_secs(5)))?;
+    stream.set_write_timeout(Some(Duration::from_secs(5)))?;
+
+    let mut request = Vec::new();
+    let mut buf = [0u8; 1024];
+    while !request.windows(4).any(|w| w == b"\r\n\r\n") && request.len() < 8192 {
+        let n = stream.read(&mut buf)?;
+        if n == 0 {
+            break;
+        }
+        request.extend_from_slice(&buf[..n]);
+    }
+
+    let request = String::from_utf8_lossy(&request);
+    let mut parts = request.lines().next().unwrap_or("").split_whitespace();
+    let method = parts.next().unwrap_or("");
+    let path = parts.next().unwrap_or("");
+
+    let (status, content_type, body) = match (method, path) {
+        ("GET", "/metrics") => ("200 OK", CONTENT_TYPE, registry.render()),
+        ("GET", _) => ("404 Not Found", "text/plain", "Not Found\n".to_string()),
+        _ => (
+            "405 Method Not Allowed",
+            "text/plain",
+            "Method Not Allowed\n".to_string(),
+        ),
+    };
+
+    write!(
+        stream,
+        "HTTP/1.1 {}\r\nContent-Type: {}\r\nContent-Length: {}\r\nConnection: close\r\n\r\n{}",
+        status,
+        content_type,
+        body.len(),
+        body
+    )?;
+    stream.flush()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // ==================== Metric Type Tests ====================
+
+    #[test]
+    fn test_counter_and_gauge() {
+        let counter = Counter::default();
+        counter.inc();
+        counter.inc_by(4);
+        assert_eq!(counter.get(), 5);
+
+        let gauge = Gauge::default();
+        gauge.set(2.5);
+        assert_eq!(gauge.get(), 2.5);
+    }
+
+    #[test]
+    fn test_histogram_observe() {
+        let histogram = Histogram::new(&[10.0, 1.0, 5.0]);
+        histogram.observe(0.5);
+        histogram.observe(7.0);
+        histogram.observe(100.0);
+
+        assert_eq!(histogram.count(), 3);
+        assert_eq!(histogram.sum(), 107.5);
+    }
+
+    // ==================== MetricsRegistry Tests ====================
+
+    #[test]
+    fn test_registry_render_counter_and_gauge() {
+        let registry = MetricsRegistry::new();
+        registry.counter("events_total", "Events seen").inc_by(3);
+        registry.gauge("peers", "Connected peers").set(2.0);
+
+        let text = registry.render();
+        assert!(text.contains("# HELP events_total Events seen\n"));
+        assert!(text.contains("# TYPE events_total counter\n"));
+        assert!(text.contains("events_total 3\n"));
+        assert!(text.contains("# TYPE peers gauge\n"));
+        assert!(text.contains("peers 2\n"));
+    }
+
+    #[test]
+    fn test_registry_render_histogram_is_cumulative() {
+        let registry = MetricsRegistry::new();
+        let histogram = registry.histogram("rtt", "Round trip", &[10.0, 50.0]);
+        histogram.observe(5.0);
+        histogram.observe(20.0);
+        histogram.observe(200.0);
+
+        let text = registry.render();
+        assert!(text.contains("rtt_bucket{le=\"10\"} 1\n"));
+        assert!(text.contains("rtt_bucket{le=\"50\"} 2\n"));
+        assert!(text.contains("rtt_bucket{le=\"+Inf\"} 3\n"));
+        assert!(text.contains("rtt_sum 225\n"));
+        assert!(text.contains("rtt_count 3\n"));
+    }
+
+    #[test]
+    fn test_registry_returns_existing_series() {
+        let registry = MetricsRegistry::new();
+        registry.counter("c", "help").inc();
+        registry.counter("c", "help").inc();
+
+        assert_eq!(registry.counter("c", "help").get(), 2);
+        assert_eq!(registry.render().matches("# TYPE c").count(), 1);
+    }
+
+    #[test]
+    fn test_registry_labelled_gauges_share_family() {
+        let registry = MetricsRegistry::new();
+        registry
+            .gauge_with_labels("depth", "Queue depth", &[("queue", "batch")])
+            .set(4.0);
+        registry
+            .gauge_with_labels("depth", "Queue depth", &[("queue", "say \"hi\"")])
+            .set(1.0);
+
+        let text = registry.render();
+        assert_eq!(text.matches("# TYPE depth gauge").count(), 1);
+        assert!(text.contains("depth{queue=\"batch\"} 4\n"));
+        assert!(text.contains("depth{queue=\"say \\\"hi\\\"\"} 1\n"));
+    }
+
+    #[test]
+    #[should_panic(expected = "already registered")]
+    fn test_registry_kind_mismatch_panics() {
+        let registry = MetricsRegistry::new();
+        registry.counter("m", "help");
+        registry.gauge("m", "help");
+    }
+
+    // ==================== NetworkMetrics Tests ====================
+
+    #[test]
+    fn test_network_metrics_render() {
+        let registry = MetricsRegistry::new();
+        let metrics = NetworkMetrics::new(&registry);
+
+        metrics.record_event_stored();
+        metrics.observe_peer_rtt(42);
+        metrics.set_queue_depth("priority", 7);
+        metrics.observe_sync_duration(Duration::from_millis(300));
+
+        let text = registry.render();
+        assert!(text.contains("nostr_nations_events_stored_total 1\n"));
+        assert!(text.contains("nostr_nations_peer_rtt_milliseconds_count 1\n"));
+        assert!(text.contains("nostr_nations_queue_depth{queue=\"priority\"} 7\n"));
+        assert!(text.contains("nostr_nations_sync_duration_seconds_bucket{le=\"0.5\"} 1\n"));
+    }
+
+    #[test]
+    fn test_network_metrics_publish_rate_window() {
+        let registry = MetricsRegistry::new();
+        let metrics = NetworkMetrics::new(&registry);
+        let start = Instant::now();
+
+        for _ in 0..20 {
+            metrics.record_publish_at(start);
+        }
+        assert_eq!(metrics.publish_rate.get(), 2.0);
+
+        // Earlier publishes fall out of the window
+        metrics.record_publish_at(start + PUBLISH_RATE_WINDOW + Duration::from_secs(1));
+        assert_eq!(metrics.publish_rate.get(), 0.1);
+        assert_eq!(metrics.publishes.get(), 21);
+    }
+
+    // ==================== MetricsServer Tests ====================
+
+    fn http_get(addr: SocketAddr, path: &str) -> String {
+        let mut stream = TcpStream::connect(addr).unwrap();
+        write!(stream, "GET {} HTTP/1.1\r\nHost: localhost\r\n\r\n", path).unwrap();
+        let mut response = String::new();
+        stream.read_to_string(&mut response).unwrap();
+        response
+    }
+
+    #[test]
+    fn test_metrics_server_serves_registry() {
+        let registry = MetricsRegistry::new();
+        registry.counter("scraped_total", "Test counter").inc();
+        let mut server = MetricsServer::start("127.0.0.1:0", registry).unwrap();
+
+        let response = http_get(server.local_addr(), "/metrics");
+        assert!(response.starts_with("HTTP/1.1 200 OK"));
+        assert!(response.contains("Content-Type: text/plain; version=0.0.4"));
+        assert!(response.contains("scraped_total 1\n"));
+
+        let response = http_get(server.local_addr(), "/other");
+        assert!(response.starts_with("HTTP/1.1 404"));
+
+        server.stop();
+    }
+}
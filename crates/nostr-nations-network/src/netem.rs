@@ -0,0 +1,436 @@
+//! Simulated bad networks for testing.
+//!
+//! [`NetemTransport`] wraps any [`PeerTransport`] and impairs the frames
+//! arriving at it, in the spirit of Linux `netem`: each frame is delayed by
+//! a fixed latency plus random jitter, a fraction is dropped, and a
+//! fraction is reordered by delivering it straight away, ahead of frames
+//! still in flight. Wrap both ends of a link to impair both directions.
+//!
+//! Impairments are drawn from a seeded generator, so a given
+//! [`NetemConfig`] makes the same decisions for the same sequence of
+//! frames. Jitter larger than the gap between frames also reorders them,
+//! as it does on a real network.
+//!
+//! Apps enable it through [`NetworkConfig::netem`], which is ignored in
+//! release builds.
+//!
+//! # Example
+//!
+//! ```rust,ignore
+//! let config = NetemConfig {
+//!     latency_ms: 80,
+//!     jitter_ms: 20,
+//!     loss: 0.05,
+//!     ..Default::default()
+//! };
+//! let host = NetemTransport::new(network.bind("host")?, config);
+//! ```
+
+use crate::peer::PeerId;
+use crate::transport::{Frame, PeerTransport, TransportError};
+use crate::NetworkConfig;
+use nostr_nations_core::SeededRng;
+use serde::{Deserialize, Serialize};
+use std::cmp::{Ordering, Reverse};
+use std::collections::BinaryHeap;
+use std::future::Future;
+use std::sync::{Mutex, MutexGuard};
+use std::time::Duration;
+use tokio::time::Instant;
+
+/// Impairments applied to incoming frames.
+///
+/// The default config impairs nothing.
+#[derive(Clone, Debug, Default, PartialEq, Serialize, Deserialize)]
+pub struct NetemConfig {
+    /// Delay added to every frame, in milliseconds.
+    pub latency_ms: u64,
+    /// Random variation on the delay, in milliseconds either way.
+    pub jitter_ms: u64,
+    /// Probability that a frame is dropped (0.0 - 1.0).
+    pub loss: f32,
+    /// Probability that a frame skips the delay and overtakes frames in
+    /// flight (0.0 - 1.0). Has no effect without latency.
+    pub reorder: f32,
+    /// Seed for the impairment decisions.
+    pub seed: u64,
+}
+
+impl NetemConfig {
+    /// Check if this config leaves frames untouched.
+    pub fn is_passthrough(&self) -> bool {
+        self.latency_ms == 0 && self.jitter_ms == 0 && self.loss <= 0.0
+    }
+}
+
+/// Counters for what a [`NetemTransport`] did to its frames.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct NetemStats {
+    /// Frames handed to the receiver.
+    pub delivered: u64,
+    /// Frames dropped.
+    pub dropped: u64,
+    /// Frames sent ahead of frames in flight.
+    pub reordered: u64,
+}
+
+/// A frame waiting out its delay.
+struct InFlight {
+    due: Instant,
+    /// Arrival order, to keep equal deadlines first-in first-out.
+    order: u64,
+    frame: Frame,
+}
+
+impl PartialEq for InFlight {
+    fn eq(&self, other: &Self) -> bool {
+        self.cmp(other) == Ordering::Equal
+    }
+}
+
+impl Eq for InFlight {}
+
+impl PartialOrd for InFlight {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl Ord for InFlight {
+    fn cmp(&self, other: &Self) -> Ordering {
+        (self.due, self.order).cmp(&(other.due, other.order))
+    }
+}
+
+/// Mutable impairment state.
+struct Link {
+    rng: SeededRng,
+    next_order: u64,
+    in_flight: BinaryHeap<Reverse<InFlight>>,
+    stats: NetemStats,
+}
+
+/// A [`PeerTransport`] that delays, drops and reorders incoming frames.
+///
+/// Connections and outgoing frames go straight to the wrapped transport.
+pub struct NetemTransport<T: PeerTransport> {
+    inner: T,
+    config: NetemConfig,
+    link: Mutex<Link>,
+}
+
+impl<T: PeerTransport> NetemTransport<T> {
+    /// Wrap a transport with the given impairments.
+    pub fn new(inner: T, config: NetemConfig) -> Self {
+        let mut seed = [0u8; 32];
+        seed[..8].copy_from_slice(&config.seed.to_le_bytes());
+        Self {
+            inner,
+            link: Mutex::new(Link {
+                rng: SeededRng::from_seed(&seed),
+                next_order: 0,
+                in_flight: BinaryHeap::new(),
+                stats: NetemStats::default(),
+            }),
+            config,
+        }
+    }
+
+    /// Wrap a transport with the impairments from a network config.
+    ///
+    /// Frames pass through untouched when the config has none, or in
+    /// release builds.
+    pub fn from_config(inner: T, config: &NetworkConfig) -> Self {
+        Self::new(inner, config.netem().cloned().unwrap_or_default())
+    }
+
+    /// Get the impairments in effect.
+    pub fn config(&self) -> &NetemConfig {
+        &self.config
+    }
+
+    /// Get the wrapped transport.
+    pub fn inner(&self) -> &T {
+        &self.inner
+    }
+
+    /// Get the impairment counters.
+    pub fn stats(&self) -> NetemStats {
+        self.lock().stats
+    }
+
+    /// Decide the fate of a frame that just arrived.
+    fn admit(&self, frame: Frame) {
+        let now = Instant::now();
+        let mut link = self.lock();
+
+        if self.config.loss > 0.0 && link.rng.chance(self.config.loss) {
+            link.stats.dropped += 1;
+            return;
+        }
+
+        let mut delay_ms = self.config.latency_ms;
+        if self.config.jitter_ms > 0 {
+            let spread = link.rng.next_range(self.config.jitter_ms as u32 * 2 + 1) as u64;
+            delay_ms = (delay_ms + spread).saturating_sub(self.config.jitter_ms);
+        }
+        if delay_ms > 0 && self.config.reorder > 0.0 && link.rng.chance(self.config.reorder) {
+            if !link.in_flight.is_empty() {
+                link.stats.reordered += 1;
+            }
+            delay_ms = 0;
+        }
+
+        let order = link.next_order;
+        link.next_order += 1;
+        link.in_flight.push(Reverse(InFlight {
+            due: now + Duration::from_millis(delay_ms),
+            order,
+            frame,
+        }));
+    }
+
+    /// Take the next frame whose delay has passed.
+    fn pop_due(&self) -> Option<Frame> {
+        let mut link = self.lock();
+        let Reverse(next) = link.in_flight.peek()?;
+        if next.due > Instant::now() {
+            return None;
+        }
+        let Reverse(next) = link.in_flight.pop()?;
+        link.stats.delivered += 1;
+        Some(next.frame)
+    }
+
+    /// When the next frame in flight becomes deliverable.
+    fn next_due(&self) -> Option<Instant> {
+        self.lock().in_flight.peek().map(|Reverse(f)| f.due)
+    }
+
+    fn lock(&self) -> MutexGuard<'_, Link> {
+        self.link
+            .lock()
+            .unwrap_or_else(|poisoned| poisoned.into_inner())
+    }
+}
+
+impl<T: PeerTransport> PeerTransport for NetemTransport<T> {
+    fn local_id(&self) -> &PeerId {
+        self.inner.local_id()
+    }
+
+    fn connect(&self, peer_id: &str) -> impl Future<Output = Result<(), TransportError>> + Send {
+        self.inner.connect(peer_id)
+    }
+
+    fn accept(&self) -> impl Future<Output = Result<PeerId, TransportError>> + Send {
+        self.inner.accept()
+    }
+
+    fn send(
+        &self,
+        peer_id: &str,
+        frame: Vec<u8>,
+    ) -> impl Future<Output = Result<(), TransportError>> + Send {
+        self.inner.send(peer_id, frame)
+    }
+
+    async fn recv(&self) -> Result<Frame, TransportError> {
+        loop {
+            if let Some(frame) = self.pop_due() {
+                return Ok(frame);
+            }
+            match self.next_due() {
+                Some(due) => {
+                    tokio::select! {
+                        frame = self.inner.recv() => self.admit(frame?),
+                        _ = tokio::time::sleep_until(due) => {}
+                    }
+                }
+                None => self.admit(self.inner.recv().await?),
+            }
+        }
+    }
+
+    fn try_recv(&self) -> Result<Option<Frame>, TransportError> {
+        while let Some(frame) = self.inner.try_recv()? {
+            self.admit(frame);
+        }
+        Ok(self.pop_due())
+    }
+
+    fn in_flight(&self) -> usize {
+        self.lock().in_flight.len() + self.inner.in_flight()
+    }
+
+    fn disconnect(&self, peer_id: &str) {
+        self.inner.disconnect(peer_id)
+    }
+
+    fn is_connected(&self, peer_id: &str) -> bool {
+        self.inner.is_connected(peer_id)
+    }
+}
+
+impl<T: PeerTransport + std::fmt::Debug> std::fmt::Debug for NetemTransport<T> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("NetemTransport")
+            .field("inner", &self.inner)
+            .field("config", &self.config)
+            .finish()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::transport::{LoopbackNetwork, LoopbackTransport};
+
+    async fn impaired_pair(
+        network: &LoopbackNetwork,
+        config: NetemConfig,
+    ) -> (NetemTransport<LoopbackTransport>, LoopbackTransport) {
+        let host = NetemTransport::new(network.bind("host").unwrap(), config);
+        let guest = network.bind("guest").unwrap();
+        guest.connect("host").await.unwrap();
+        host.accept().await.unwrap();
+        (host, guest)
+    }
+
+    /// Receive every frame sent so far, in delivery order.
+    async fn receive_all(host: &NetemTransport<LoopbackTransport>) -> Vec<u8> {
+        let mut received = Vec::new();
+        while let Some((_, frame)) = host.try_recv().unwrap() {
+            received.push(frame[0]);
+        }
+        while host.in_flight() > 0 {
+            received.push(host.recv().await.unwrap().1[0]);
+        }
+        received
+    }
+
+    // ==================== Impairment Tests ====================
+
+    #[tokio::test]
+    async fn test_passthrough_delivers_immediately() {
+        let network = LoopbackNetwork::new();
+        let (host, guest) = impaired_pair(&network, NetemConfig::default()).await;
+        assert!(host.config().is_passthrough());
+
+        guest.send("host", vec![1]).await.unwrap();
+        assert_eq!(
+            host.try_recv().unwrap(),
+            Some((PeerId::from("guest"), vec![1]))
+        );
+        assert_eq!(host.stats().delivered, 1);
+    }
+
+    #[tokio::test]
+    async fn test_latency_holds_frames_back() {
+        let network = LoopbackNetwork::new();
+        let config = NetemConfig {
+            latency_ms: 30,
+            ..Default::default()
+        };
+        let (host, guest) = impaired_pair(&network, config).await;
+
+        let start = Instant::now();
+        guest.send("host", vec![1]).await.unwrap();
+        assert_eq!(host.try_recv().unwrap(), None);
+        assert_eq!(host.in_flight(), 1);
+
+        assert_eq!(host.recv().await.unwrap().1, vec![1]);
+        assert!(start.elapsed() >= Duration::from_millis(30));
+        assert_eq!(host.in_flight(), 0);
+    }
+
+    #[tokio::test]
+    async fn test_loss_drops_a_share_of_frames() {
+        let network = LoopbackNetwork::new();
+        let config = NetemConfig {
+            loss: 0.3,
+            seed: 7,
+            ..Default::default()
+        };
+        let (host, guest) = impaired_pair(&network, config).await;
+
+        for i in 0..200u8 {
+            guest.send("host", vec![i]).await.unwrap();
+        }
+        let mut received = 0;
+        while host.try_recv().unwrap().is_some() {
+            received += 1;
+        }
+
+        let stats = host.stats();
+        assert_eq!(stats.delivered + stats.dropped, 200);
+        assert_eq!(stats.delivered, received);
+        assert!(
+            (30..90).contains(&stats.dropped),
+            "dropped {}",
+            stats.dropped
+        );
+    }
+
+    #[tokio::test]
+    async fn test_reorder_lets_frames_overtake() {
+        let network = LoopbackNetwork::new();
+        let config = NetemConfig {
+            latency_ms: 20,
+            reorder: 0.5,
+            seed: 3,
+            ..Default::default()
+        };
+        let (host, guest) = impaired_pair(&network, config).await;
+
+        for i in 0..20u8 {
+            guest.send("host", vec![i]).await.unwrap();
+        }
+        let mut received = receive_all(&host).await;
+
+        assert!(host.stats().reordered > 0);
+        assert_ne!(received, (0..20u8).collect::<Vec<_>>());
+        received.sort();
+        assert_eq!(received, (0..20u8).collect::<Vec<_>>());
+    }
+
+    #[tokio::test]
+    async fn test_same_seed_makes_same_decisions() {
+        let config = NetemConfig {
+            latency_ms: 10,
+            jitter_ms: 10,
+            loss: 0.2,
+            reorder: 0.2,
+            seed: 11,
+        };
+
+        let mut runs = Vec::new();
+        for _ in 0..2 {
+            let network = LoopbackNetwork::new();
+            let (host, guest) = impaired_pair(&network, config.clone()).await;
+            for i in 0..30u8 {
+                guest.send("host", vec![i]).await.unwrap();
+            }
+            runs.push((receive_all(&host).await, host.stats()));
+        }
+        assert_eq!(runs[0], runs[1]);
+    }
+
+    #[test]
+    fn test_network_config_enables_netem_in_debug_builds() {
+        let config = NetworkConfig {
+            netem: Some(NetemConfig {
+                latency_ms: 50,
+                ..Default::default()
+            }),
+            ..Default::default()
+        };
+        let network = LoopbackNetwork::new();
+        let transport = NetemTransport::from_config(network.bind("host").unwrap(), &config);
+
+        assert_eq!(
+            transport.config().latency_ms,
+            if cfg!(debug_assertions) { 50 } else { 0 }
+        );
+    }
+}
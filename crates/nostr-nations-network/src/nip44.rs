@@ -0,0 +1,328 @@
+//! NIP-44 (version 2) encryption for direct messages.
+//!
+//! Turn notifications are sent to players' phones as encrypted direct
+//! messages, which have to be readable by ordinary Nostr clients. NIP-44
+//! derives a conversation key from an ECDH shared secret between the
+//! sender's and recipient's Nostr keys, then encrypts each message with
+//! ChaCha20 under keys expanded from a random nonce, authenticated with
+//! HMAC-SHA256:
+//!
+//! ```text
+//! payload = base64(0x02 || nonce[32] || ciphertext || mac[32])
+//! ```
+//!
+//! Plaintext is padded to hide its exact length. Only the players' Nostr
+//! keys are used; game traffic encryption lives in [`crate::encryption`].
+
+use crate::signing::{decode_pubkey, SigningKey};
+use base64::engine::general_purpose::STANDARD as BASE64;
+use base64::Engine;
+use chacha20::cipher::{KeyIvInit, StreamCipher};
+use chacha20::ChaCha20;
+use chacha20poly1305::aead::rand_core::RngCore;
+use chacha20poly1305::aead::OsRng;
+use hkdf::Hkdf;
+use hmac::{Hmac, Mac};
+use sha2::Sha256;
+
+/// Version byte that starts every payload.
+pub const VERSION: u8 = 2;
+
+/// Longest plaintext that can be encrypted, in bytes.
+pub const MAX_PLAINTEXT_LEN: usize = 65535;
+
+/// Salt for deriving conversation keys.
+const CONVERSATION_SALT: &[u8] = b"nip44-v2";
+
+/// Length of the per-message nonce.
+const NONCE_LEN: usize = 32;
+
+/// Length of the HMAC tag.
+const MAC_LEN: usize = 32;
+
+/// Errors from encrypting or decrypting a NIP-44 payload.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum Nip44Error {
+    /// The other party's key is not a valid npub or hex key.
+    InvalidPubkey(String),
+    /// The plaintext is empty or longer than [`MAX_PLAINTEXT_LEN`].
+    InvalidLength(usize),
+    /// The payload uses a version other than [`VERSION`].
+    UnsupportedVersion,
+    /// The payload is not valid base64 or has the wrong size or padding.
+    Malformed,
+    /// The MAC doesn't match: wrong key or tampered payload.
+    InvalidMac,
+}
+
+impl std::fmt::Display for Nip44Error {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Nip44Error::InvalidPubkey(key) => write!(f, "Invalid public key: {}", key),
+            Nip44Error::InvalidLength(len) => write!(
+                f,
+                "Plaintext must be 1 to {} bytes, got {}",
+                MAX_PLAINTEXT_LEN, len
+            ),
+            Nip44Error::UnsupportedVersion => write!(f, "Unsupported NIP-44 version"),
+            Nip44Error::Malformed => write!(f, "Malformed NIP-44 payload"),
+            Nip44Error::InvalidMac => write!(f, "Wrong key or tampered NIP-44 payload"),
+        }
+    }
+}
+
+impl std::error::Error for Nip44Error {}
+
+/// Derive the conversation key shared by `key` and `pubkey` (an npub or
+/// hex key). Both parties derive the same key.
+pub fn conversation_key(key: &SigningKey, pubkey: &str) -> Result<[u8; 32], Nip44Error> {
+    let invalid = || Nip44Error::InvalidPubkey(pubkey.to_string());
+    let pubkey = decode_pubkey(pubkey).map_err(|_| invalid())?;
+    let shared_x = key.shared_secret(&pubkey).map_err(|_| invalid())?;
+    let (conversation_key, _) = Hkdf::<Sha256>::extract(Some(CONVERSATION_SALT), &shared_x);
+    Ok(conversation_key.into())
+}
+
+/// Encrypt a message from `key` to `recipient_pubkey` with a fresh random
+/// nonce.
+pub fn encrypt(
+    key: &SigningKey,
+    recipient_pubkey: &str,
+    plaintext: &str,
+) -> Result<String, Nip44Error> {
+    let mut nonce = [0u8; NONCE_LEN];
+    OsRng.fill_bytes(&mut nonce);
+    encrypt_with_nonce(&conversation_key(key, recipient_pubkey)?, &nonce, plaintext)
+}
+
+/// Decrypt a message to `key` from `sender_pubkey`.
+pub fn decrypt(key: &SigningKey, sender_pubkey: &str, payload: &str) -> Result<String, Nip44Error> {
+    decrypt_with_key(&conversation_key(key, sender_pubkey)?, payload)
+}
+
+/// Encrypt with a given conversation key and nonce.
+///
+/// The nonce must never be reused with the same conversation key; use
+/// [`encrypt`] unless reproducing a known payload.
+pub fn encrypt_with_nonce(
+    conversation_key: &[u8; 32],
+    nonce: &[u8; NONCE_LEN],
+    plaintext: &str,
+) -> Result<String, Nip44Error> {
+    let (chacha_key, chacha_nonce, hmac_key) = message_keys(conversation_key, nonce);
+    let mut padded = pad(plaintext.as_bytes())?;
+    ChaCha20::new(&chacha_key.into(), &chacha_nonce.into()).apply_keystream(&mut padded);
+    let mac = message_mac(&hmac_key, nonce, &padded)
+        .finalize()
+        .into_bytes();
+
+    let mut payload = Vec::with_capacity(1 + NONCE_LEN + padded.len() + MAC_LEN);
+    payload.push(VERSION);
+    payload.extend_from_slice(nonce);
+    payload.extend_from_slice(&padded);
+    payload.extend_from_slice(&mac);
+    Ok(BASE64.encode(payload))
+}
+
+/// Decrypt with a given conversation key.
+pub fn decrypt_with_key(conversation_key: &[u8; 32], payload: &str) -> Result<String, Nip44Error> {
+    // A leading '#' marks a payload from a future, non-base64 version
+    if payload.starts_with('#') {
+        return Err(Nip44Error::UnsupportedVersion);
+    }
+    let payload = BASE64.decode(payload).map_err(|_| Nip44Error::Malformed)?;
+    // Version, nonce, at least the smallest padded message, and the MAC
+    if payload.len() < 1 + NONCE_LEN + 2 + 32 + MAC_LEN {
+        return Err(Nip44Error::Malformed);
+    }
+    if payload[0] != VERSION {
+        return Err(Nip44Error::UnsupportedVersion);
+    }
+
+    let nonce: [u8; NONCE_LEN] = payload[1..1 + NONCE_LEN]
+        .try_into()
+        .expect("slice is NONCE_LEN long");
+    let (ciphertext, mac) =
+        payload[1 + NONCE_LEN..].split_at(payload.len() - 1 - NONCE_LEN - MAC_LEN);
+    let (chacha_key, chacha_nonce, hmac_key) = message_keys(conversation_key, &nonce);
+    message_mac(&hmac_key, &nonce, ciphertext)
+        .verify_slice(mac)
+        .map_err(|_| Nip44Error::InvalidMac)?;
+
+    let mut padded = ciphertext.to_vec();
+    ChaCha20::new(&chacha_key.into(), &chacha_nonce.into()).apply_keystream(&mut padded);
+    unpad(&padded)
+}
+
+/// Expand the per-message ChaCha20 key, ChaCha20 nonce and HMAC key.
+fn message_keys(
+    conversation_key: &[u8; 32],
+    nonce: &[u8; NONCE_LEN],
+) -> ([u8; 32], [u8; 12], [u8; 32]) {
+    let hkdf =
+        Hkdf::<Sha256>::from_prk(conversation_key).expect("32 bytes is a valid HKDF-SHA256 key");
+    let mut keys = [0u8; 76];
+    hkdf.expand(nonce, &mut keys)
+        .expect("76 bytes is a valid HKDF-SHA256 output length");
+
+    let mut chacha_key = [0u8; 32];
+    let mut chacha_nonce = [0u8; 12];
+    let mut hmac_key = [0u8; 32];
+    chacha_key.copy_from_slice(&keys[..32]);
+    chacha_nonce.copy_from_slice(&keys[32..44]);
+    hmac_key.copy_from_slice(&keys[44..]);
+    (chacha_key, chacha_nonce, hmac_key)
+}
+
+/// HMAC over the nonce and ciphertext.
+fn message_mac(hmac_key: &[u8; 32], nonce: &[u8; NONCE_LEN], ciphertext: &[u8]) -> Hmac<Sha256> {
+    let mut mac =
+        <Hmac<Sha256> as Mac>::new_from_slice(hmac_key).expect("HMAC accepts keys of any length");
+    mac.update(nonce);
+    mac.update(ciphertext);
+    mac
+}
+
+/// Padded length of a plaintext: at least 32 bytes, then rounded up in
+/// chunks of an eighth of the next power of two.
+fn padded_len(len: usize) -> usize {
+    if len <= 32 {
+        return 32;
+    }
+    let next_power = 1usize << (usize::BITS - (len - 1).leading_zeros());
+    let chunk = if next_power <= 256 {
+        32
+    } else {
+        next_power / 8
+    };
+    chunk * ((len - 1) / chunk + 1)
+}
+
+/// Prefix the plaintext with its big-endian length and zero-pad it.
+fn pad(plaintext: &[u8]) -> Result<Vec<u8>, Nip44Error> {
+    let len = plaintext.len();
+    if len == 0 || len > MAX_PLAINTEXT_LEN {
+        return Err(Nip44Error::InvalidLength(len));
+    }
+    let mut padded = Vec::with_capacity(2 + padded_len(len));
+    padded.extend_from_slice(&(len as u16).to_be_bytes());
+    padded.extend_from_slice(plaintext);
+    padded.resize(2 + padded_len(len), 0);
+    Ok(padded)
+}
+
+/// Strip the length prefix and padding, checking both.
+fn unpad(padded: &[u8]) -> Result<String, Nip44Error> {
+    let len = u16::from_be_bytes([padded[0], padded[1]]) as usize;
+    if len == 0 || padded.len() != 2 + padded_len(len) {
+        return Err(Nip44Error::Malformed);
+    }
+    String::from_utf8(padded[2..2 + len].to_vec()).map_err(|_| Nip44Error::Malformed)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn key(seed: u8) -> SigningKey {
+        let mut secret = [0u8; 32];
+        secret[31] = seed;
+        SigningKey::from_secret_bytes(&secret).unwrap()
+    }
+
+    fn hex(bytes: &[u8]) -> String {
+        bytes.iter().map(|b| format!("{:02x}", b)).collect()
+    }
+
+    #[test]
+    fn test_conversation_key_vector() {
+        // From the NIP-44 v2 test vectors
+        let conversation_key = conversation_key(&key(1), &hex(&key(2).public_key())).unwrap();
+        assert_eq!(
+            hex(&conversation_key),
+            "c41c775356fd92eadc63ff5a0dc1da211b268cbea22316767095b2871ea1412d"
+        );
+        assert_eq!(
+            conversation_key,
+            super::conversation_key(&key(2), key(1).npub().as_str()).unwrap()
+        );
+    }
+
+    #[test]
+    fn test_encrypt_vector() {
+        // From the NIP-44 v2 test vectors
+        let conversation_key = conversation_key(&key(1), &hex(&key(2).public_key())).unwrap();
+        let mut nonce = [0u8; NONCE_LEN];
+        nonce[31] = 1;
+        let payload = encrypt_with_nonce(&conversation_key, &nonce, "a").unwrap();
+        assert_eq!(
+            payload,
+            "AgAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAABee0G5VSK0/9YypIObAtDKfYEAjD35uVkHyB0F4DwrcNaCXlCWZKaArsGrY6M9wnuTMxWfp1RTN9Xga8no+kF5Vsb"
+        );
+        assert_eq!(decrypt_with_key(&conversation_key, &payload).unwrap(), "a");
+    }
+
+    #[test]
+    fn test_round_trip_between_players() {
+        let alice = key(1);
+        let bob = key(2);
+        let message = "It's your turn (4) in game game1".repeat(20);
+
+        let payload = encrypt(&alice, bob.npub().as_str(), &message).unwrap();
+        assert_eq!(
+            decrypt(&bob, alice.npub().as_str(), &payload).unwrap(),
+            message
+        );
+        assert_eq!(
+            decrypt(&key(3), alice.npub().as_str(), &payload),
+            Err(Nip44Error::InvalidMac)
+        );
+
+        // Fresh nonces make every payload different
+        assert_ne!(
+            encrypt(&alice, bob.npub().as_str(), &message).unwrap(),
+            payload
+        );
+    }
+
+    #[test]
+    fn test_tampered_payload_rejected() {
+        let conversation_key = conversation_key(&key(1), key(2).npub().as_str()).unwrap();
+        let payload = encrypt_with_nonce(&conversation_key, &[7; NONCE_LEN], "hello").unwrap();
+        let mut raw = BASE64.decode(&payload).unwrap();
+        raw[40] ^= 1;
+        assert_eq!(
+            decrypt_with_key(&conversation_key, &BASE64.encode(&raw)),
+            Err(Nip44Error::InvalidMac)
+        );
+        raw[40] ^= 1;
+        raw[0] = 1;
+        assert_eq!(
+            decrypt_with_key(&conversation_key, &BASE64.encode(&raw)),
+            Err(Nip44Error::UnsupportedVersion)
+        );
+        assert_eq!(
+            decrypt_with_key(&conversation_key, "#future"),
+            Err(Nip44Error::UnsupportedVersion)
+        );
+        assert_eq!(
+            decrypt_with_key(&conversation_key, "AgAA"),
+            Err(Nip44Error::Malformed)
+        );
+    }
+
+    #[test]
+    fn test_padding() {
+        assert_eq!(padded_len(1), 32);
+        assert_eq!(padded_len(32), 32);
+        assert_eq!(padded_len(33), 64);
+        assert_eq!(padded_len(257), 320);
+        assert_eq!(padded_len(1000), 1024);
+        assert_eq!(padded_len(65535), 65536);
+        assert_eq!(pad(b""), Err(Nip44Error::InvalidLength(0)));
+        assert_eq!(
+            pad(&vec![b'a'; MAX_PLAINTEXT_LEN + 1]),
+            Err(Nip44Error::InvalidLength(MAX_PLAINTEXT_LEN + 1))
+        );
+    }
+}
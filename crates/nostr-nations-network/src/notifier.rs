@@ -0,0 +1,550 @@
+//! Push notification bridge for turn alerts.
+//!
+//! Forwards pitboss [`TurnNotification`]s to a player's own infrastructure
+//! so they can get phone notifications without keeping the game open:
+//!
+//! - **Encrypted DM**: published to a configured npub, encrypted with
+//!   NIP-44 from the player's own Nostr key so a Nostr mobile client
+//!   logged in as the recipient can read it
+//! - **Webhook**: JSON POST to a configured URL (e.g. ntfy, Gotify, or a
+//!   home automation server)
+//!
+//! Both targets are optional and configured per player.
+//!
+//! The built-in [`HttpWebhookTransport`] speaks plain HTTP only: there is
+//! no TLS client in this crate. [`Notifier::new`] therefore refuses
+//! `https://` webhook URLs up front with
+//! [`NotifierError::HttpsUnsupported`] rather than failing on every turn.
+//! For HTTPS endpoints such as hosted ntfy, supply a custom
+//! [`WebhookTransport`] or route through a local HTTP proxy.
+//!
+//! A failed webhook doesn't stop the DM: the error is recorded in the
+//! [`NotifyOutcome`] alongside it.
+
+use crate::nip44::Nip44Error;
+use crate::pitboss::{DirectMessage, PitbossError, TurnNotification};
+use crate::signing::{decode_pubkey, SigningKey};
+use nostr_nations_core::types::{GameId, PlayerSlot};
+use serde::Serialize;
+use std::io::{Read, Write};
+use std::net::{TcpStream, ToSocketAddrs};
+use std::time::Duration;
+
+/// Default timeout for webhook requests.
+pub const DEFAULT_WEBHOOK_TIMEOUT_MS: u64 = 5000;
+
+/// Where to deliver turn alerts.
+#[derive(Clone, Debug, Default)]
+pub struct NotifierConfig {
+    /// npub (or 64-character hex pubkey) to send encrypted DMs to.
+    pub dm_recipient: Option<String>,
+    /// URL to POST a JSON payload to. Must be `http://` unless a custom
+    /// [`WebhookTransport`] handles other schemes.
+    pub webhook_url: Option<String>,
+}
+
+impl NotifierConfig {
+    /// Check if any delivery target is configured.
+    pub fn is_enabled(&self) -> bool {
+        self.dm_recipient.is_some() || self.webhook_url.is_some()
+    }
+}
+
+/// JSON body sent to webhooks.
+#[derive(Clone, Debug, Serialize)]
+pub struct WebhookPayload {
+    pub title: String,
+    pub message: String,
+    pub game_id: GameId,
+    pub player_id: PlayerSlot,
+    pub turn: u32,
+    pub deadline: Option<u64>,
+}
+
+impl From<&TurnNotification> for WebhookPayload {
+    fn from(notification: &TurnNotification) -> Self {
+        Self {
+            title: "Nostr Nations".to_string(),
+            message: notification.message(),
+            game_id: notification.game_id.clone(),
+            player_id: notification.player_id,
+            turn: notification.turn,
+            deadline: notification.deadline,
+        }
+    }
+}
+
+/// Transport used to deliver webhook requests.
+pub trait WebhookTransport: Send + Sync {
+    /// POST a JSON body to the given URL.
+    fn post_json(&self, url: &str, body: &str) -> Result<(), NotifierError>;
+
+    /// Check that the transport can reach a URL, before any notification is
+    /// sent.
+    fn check_url(&self, _url: &str) -> Result<(), NotifierError> {
+        Ok(())
+    }
+}
+
+/// Minimal blocking HTTP/1.1 transport for `http://` URLs.
+///
+/// Has no TLS support: [`check_url`](WebhookTransport::check_url) rejects
+/// `https://` URLs with [`NotifierError::HttpsUnsupported`].
+#[derive(Clone, Debug)]
+pub struct HttpWebhookTransport {
+    timeout: Duration,
+}
+
+impl Default for HttpWebhookTransport {
+    fn default() -> Self {
+        Self::new(Duration::from_millis(DEFAULT_WEBHOOK_TIMEOUT_MS))
+    }
+}
+
+impl HttpWebhookTransport {
+    /// Create a transport with the given connect/read/write timeout.
+    pub fn new(timeout: Duration) -> Self {
+        Self { timeout }
+    }
+}
+
+impl WebhookTransport for HttpWebhookTransport {
+    fn post_json(&self, url: &str, body: &str) -> Result<(), NotifierError> {
+        let (host, port, path) = parse_http_url(url)?;
+
+        let addr = (host.as_str(), port)
+            .to_socket_addrs()
+            .map_err(|e| NotifierError::Webhook(e.to_string()))?
+            .next()
+            .ok_or_else(|| NotifierError::Webhook(format!("Cannot resolve {}", host)))?;
+        let mut stream = TcpStream::connect_timeout(&addr, self.timeout)
+            .map_err(|e| NotifierError::Webhook(e.to_string()))?;
+        stream
+            .set_read_timeout(Some(self.timeout))
+            .and_then(|_| stream.set_write_timeout(Some(self.timeout)))
+            .map_err(|e| NotifierError::Webhook(e.to_string()))?;
+
+        let request = format!(
+            "POST {} HTTP/1.1\r\nHost: {}\r\nContent-Type: application/json\r\n\
+             Content-Length: {}\r\nConnection: close\r\n\r\n{}",
+            path,
+            host,
+            body.len(),
+            body
+        );
+        stream
+            .write_all(request.as_bytes())
+            .map_err(|e| NotifierError::Webhook(e.to_string()))?;
+
+        let mut response = String::new();
+        stream
+            .read_to_string(&mut response)
+            .map_err(|e| NotifierError::Webhook(e.to_string()))?;
+
+        let status = response
+            .lines()
+            .next()
+            .and_then(|line| line.split_whitespace().nth(1))
+            .and_then(|code| code.parse::<u16>().ok())
+            .ok_or_else(|| NotifierError::Webhook("Malformed HTTP response".to_string()))?;
+        if !(200..300).contains(&status) {
+            return Err(NotifierError::WebhookStatus(status));
+        }
+
+        Ok(())
+    }
+
+    fn check_url(&self, url: &str) -> Result<(), NotifierError> {
+        parse_http_url(url).map(|_| ())
+    }
+}
+
+/// Result of delivering a notification.
+#[derive(Clone, Debug, Default)]
+pub struct NotifyOutcome {
+    /// Encrypted DM to publish to relays, if a DM recipient is configured.
+    pub direct_message: Option<DirectMessage>,
+    /// Whether the webhook was called successfully.
+    pub webhook_sent: bool,
+    /// Why the webhook failed, if it did.
+    pub webhook_error: Option<NotifierError>,
+}
+
+/// Delivers turn alerts to a player's configured targets.
+pub struct Notifier {
+    config: NotifierConfig,
+    /// Sender's Nostr key, used to encrypt DMs. Kept apart from any game's
+    /// encryption keys.
+    key: SigningKey,
+    /// Decoded DM recipient key.
+    dm_key: Option<[u8; 32]>,
+    transport: Box<dyn WebhookTransport>,
+}
+
+impl Notifier {
+    /// Create a notifier using the built-in HTTP transport.
+    ///
+    /// Fails with [`NotifierError::HttpsUnsupported`] for an `https://`
+    /// webhook URL.
+    pub fn new(config: NotifierConfig, key: SigningKey) -> Result<Self, NotifierError> {
+        Self::with_transport(config, key, Box::new(HttpWebhookTransport::default()))
+    }
+
+    /// Create a notifier with a custom webhook transport.
+    pub fn with_transport(
+        config: NotifierConfig,
+        key: SigningKey,
+        transport: Box<dyn WebhookTransport>,
+    ) -> Result<Self, NotifierError> {
+        let dm_key = config
+            .dm_recipient
+            .as_deref()
+            .map(|key| {
+                decode_pubkey(key).map_err(|_| NotifierError::InvalidPubkey(key.to_string()))
+            })
+            .transpose()?;
+        if let Some(url) = config.webhook_url.as_deref() {
+            transport.check_url(url)?;
+        }
+
+        Ok(Self {
+            config,
+            key,
+            dm_key,
+            transport,
+        })
+    }
+
+    /// Get the notifier configuration.
+    pub fn config(&self) -> &NotifierConfig {
+        &self.config
+    }
+
+    /// Deliver a notification to every configured target.
+    ///
+    /// The DM is returned rather than published so the caller can send it
+    /// through whichever relays it is connected to. A webhook failure is
+    /// recorded in the outcome and doesn't prevent the DM.
+    pub fn notify(
+        &self,
+        notification: &TurnNotification,
+        created_at: u64,
+    ) -> Result<NotifyOutcome, NotifierError> {
+        let mut outcome = NotifyOutcome::default();
+
+        if let Some(key) = self.dm_key {
+            // Nostr `p` tags carry hex pubkeys, even when configured as an npub
+            let recipient: String = key.iter().map(|b| format!("{:02x}", b)).collect();
+            let dm = notification
+                .to_direct_message(&self.key, &recipient, created_at)
+                .map_err(|e| match e {
+                    PitbossError::Encryption(e) => NotifierError::Encryption(e),
+                    other => NotifierError::Webhook(other.to_string()),
+                })?;
+            outcome.direct_message = Some(dm);
+        }
+
+        if let Some(url) = self.config.webhook_url.as_deref() {
+            let sent = serde_json::to_string(&WebhookPayload::from(notification))
+                .map_err(|e| NotifierError::Webhook(e.to_string()))
+                .and_then(|body| self.transport.post_json(url, &body));
+            match sent {
+                Ok(()) => outcome.webhook_sent = true,
+                Err(e) => outcome.webhook_error = Some(e),
+            }
+        }
+
+        Ok(outcome)
+    }
+}
+
+/// Split an `http://host[:port]/path` URL into its parts.
+fn parse_http_url(url: &str) -> Result<(String, u16, String), NotifierError> {
+    if url.starts_with("https://") {
+        return Err(NotifierError::HttpsUnsupported(url.to_string()));
+    }
+    let rest = url
+        .strip_prefix("http://")
+        .ok_or_else(|| NotifierError::UnsupportedUrl(url.to_string()))?;
+    let (authority, path) = match rest.find('/') {
+        Some(i) => (&rest[..i], &rest[i..]),
+        None => (rest, "/"),
+    };
+    if authority.is_empty() {
+        return Err(NotifierError::UnsupportedUrl(url.to_string()));
+    }
+
+    let (host, port) = match authority.rsplit_once(':') {
+        Some((host, port)) => {
+            let port = port
+                .parse()
+                .map_err(|_| NotifierError::UnsupportedUrl(url.to_string()))?;
+            (host, port)
+        }
+        None => (authority, 80),
+    };
+
+    Ok((host.to_string(), port, path.to_string()))
+}
+
+/// Errors that can occur when delivering notifications.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum NotifierError {
+    /// The configured DM recipient is not a valid npub or hex key.
+    InvalidPubkey(String),
+    /// The webhook URL is not a supported `http://` URL.
+    UnsupportedUrl(String),
+    /// The webhook URL is `https://`, which the built-in transport can't
+    /// speak.
+    HttpsUnsupported(String),
+    /// The webhook request failed.
+    Webhook(String),
+    /// The webhook returned a non-2xx status.
+    WebhookStatus(u16),
+    /// DM encryption failed.
+    Encryption(Nip44Error),
+}
+
+impl std::fmt::Display for NotifierError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            NotifierError::InvalidPubkey(key) => write!(f, "Invalid public key: {}", key),
+            NotifierError::UnsupportedUrl(url) => write!(f, "Unsupported webhook URL: {}", url),
+            NotifierError::HttpsUnsupported(url) => write!(
+                f,
+                "HTTPS webhooks need a custom transport or a local HTTP proxy: {}",
+                url
+            ),
+            NotifierError::Webhook(msg) => write!(f, "Webhook failed: {}", msg),
+            NotifierError::WebhookStatus(code) => write!(f, "Webhook returned status {}", code),
+            NotifierError::Encryption(e) => write!(f, "Encryption error: {}", e),
+        }
+    }
+}
+
+impl std::error::Error for NotifierError {}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::net::TcpListener;
+    use std::sync::{Arc, Mutex};
+    use std::thread;
+
+    fn notification() -> TurnNotification {
+        TurnNotification {
+            game_id: GameId::new("game1"),
+            player_id: PlayerSlot(1),
+            turn: 4,
+            deadline: None,
+            is_reminder: false,
+        }
+    }
+
+    fn sender_key() -> SigningKey {
+        SigningKey::from_secret_bytes(&[7; 32]).unwrap()
+    }
+
+    fn recipient_key() -> SigningKey {
+        SigningKey::from_secret_bytes(&[0x22; 32]).unwrap()
+    }
+
+    #[derive(Default)]
+    struct RecordingTransport {
+        posts: Arc<Mutex<Vec<(String, String)>>>,
+    }
+
+    impl WebhookTransport for RecordingTransport {
+        fn post_json(&self, url: &str, body: &str) -> Result<(), NotifierError> {
+            self.posts
+                .lock()
+                .unwrap()
+                .push((url.to_string(), body.to_string()));
+            Ok(())
+        }
+    }
+
+    #[test]
+    fn test_parse_http_url() {
+        assert_eq!(
+            parse_http_url("http://localhost:8080/topic").unwrap(),
+            ("localhost".to_string(), 8080, "/topic".to_string())
+        );
+        assert_eq!(
+            parse_http_url("http://example.com").unwrap(),
+            ("example.com".to_string(), 80, "/".to_string())
+        );
+        assert!(matches!(
+            parse_http_url("https://example.com"),
+            Err(NotifierError::HttpsUnsupported(_))
+        ));
+        assert!(matches!(
+            parse_http_url("ftp://example.com"),
+            Err(NotifierError::UnsupportedUrl(_))
+        ));
+    }
+
+    // ==== Notifier Tests ====
+
+    #[test]
+    fn test_notify_dm_and_webhook() {
+        let transport = RecordingTransport::default();
+        let posts = transport.posts.clone();
+        let config = NotifierConfig {
+            dm_recipient: Some(recipient_key().npub().as_str().to_string()),
+            webhook_url: Some("http://localhost/hook".to_string()),
+        };
+        let notifier = Notifier::with_transport(config, sender_key(), Box::new(transport)).unwrap();
+
+        let outcome = notifier.notify(&notification(), 100).unwrap();
+        let dm = outcome.direct_message.unwrap();
+        let hex: String = recipient_key()
+            .public_key()
+            .iter()
+            .map(|b| format!("{:02x}", b))
+            .collect();
+        assert_eq!(dm.recipient_pubkey, hex);
+        assert!(outcome.webhook_sent);
+
+        let posts = posts.lock().unwrap();
+        assert_eq!(posts[0].0, "http://localhost/hook");
+        assert!(posts[0].1.contains("\"turn\":4"));
+    }
+
+    #[test]
+    fn test_dm_readable_only_by_recipient() {
+        let recipient = recipient_key();
+        let config = NotifierConfig {
+            dm_recipient: Some(recipient.npub().as_str().to_string()),
+            webhook_url: None,
+        };
+        let notifier = Notifier::new(config, sender_key()).unwrap();
+
+        let dm = notifier
+            .notify(&notification(), 100)
+            .unwrap()
+            .direct_message
+            .unwrap();
+        assert_eq!(dm.decrypt_notification(&recipient).unwrap(), notification());
+        let outsider = SigningKey::from_secret_bytes(&[0x33; 32]).unwrap();
+        assert!(dm.decrypt_notification(&outsider).is_err());
+    }
+
+    #[test]
+    fn test_webhook_failure_keeps_dm() {
+        struct FailingTransport;
+
+        impl WebhookTransport for FailingTransport {
+            fn post_json(&self, _url: &str, _body: &str) -> Result<(), NotifierError> {
+                Err(NotifierError::WebhookStatus(503))
+            }
+        }
+
+        let config = NotifierConfig {
+            dm_recipient: Some(recipient_key().npub().as_str().to_string()),
+            webhook_url: Some("http://localhost/hook".to_string()),
+        };
+        let notifier =
+            Notifier::with_transport(config, sender_key(), Box::new(FailingTransport)).unwrap();
+
+        let outcome = notifier.notify(&notification(), 100).unwrap();
+        assert!(outcome.direct_message.is_some());
+        assert!(!outcome.webhook_sent);
+        assert_eq!(
+            outcome.webhook_error,
+            Some(NotifierError::WebhookStatus(503))
+        );
+    }
+
+    #[test]
+    fn test_https_webhook_rejected_at_config_time() {
+        let config = NotifierConfig {
+            dm_recipient: None,
+            webhook_url: Some("https://ntfy.sh/alerts".to_string()),
+        };
+        assert_eq!(
+            Notifier::new(config.clone(), sender_key()).err(),
+            Some(NotifierError::HttpsUnsupported(
+                "https://ntfy.sh/alerts".to_string()
+            ))
+        );
+
+        // The built-in transport never sends to an HTTPS URL in the clear
+        assert_eq!(
+            HttpWebhookTransport::default().post_json("https://ntfy.sh/alerts", "{}"),
+            Err(NotifierError::HttpsUnsupported(
+                "https://ntfy.sh/alerts".to_string()
+            ))
+        );
+
+        // A custom transport can take HTTPS URLs
+        let transport = RecordingTransport::default();
+        assert!(Notifier::with_transport(config, sender_key(), Box::new(transport)).is_ok());
+    }
+
+    #[test]
+    fn test_notify_without_targets() {
+        let config = NotifierConfig::default();
+        assert!(!config.is_enabled());
+
+        let notifier = Notifier::new(config, sender_key()).unwrap();
+        let outcome = notifier.notify(&notification(), 100).unwrap();
+        assert!(outcome.direct_message.is_none());
+        assert!(!outcome.webhook_sent);
+    }
+
+    #[test]
+    fn test_invalid_dm_recipient() {
+        let config = NotifierConfig {
+            dm_recipient: Some("not-a-key".to_string()),
+            webhook_url: None,
+        };
+        assert!(matches!(
+            Notifier::new(config, sender_key()),
+            Err(NotifierError::InvalidPubkey(_))
+        ));
+    }
+
+    #[test]
+    fn test_http_transport_posts_json() {
+        let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+        let port = listener.local_addr().unwrap().port();
+        let server = thread::spawn(move || {
+            let (mut stream, _) = listener.accept().unwrap();
+            let mut buf = [0u8; 1024];
+            let n = stream.read(&mut buf).unwrap();
+            stream
+                .write_all(b"HTTP/1.1 204 No Content\r\nContent-Length: 0\r\n\r\n")
+                .unwrap();
+            String::from_utf8_lossy(&buf[..n]).to_string()
+        });
+
+        let transport = HttpWebhookTransport::default();
+        transport
+            .post_json(&format!("http://127.0.0.1:{}/alerts", port), "{}")
+            .unwrap();
+
+        let request = server.join().unwrap();
+        assert!(request.starts_with("POST /alerts HTTP/1.1"));
+        assert!(request.contains("Content-Type: application/json"));
+    }
+
+    #[test]
+    fn test_http_transport_error_status() {
+        let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+        let port = listener.local_addr().unwrap().port();
+        let server = thread::spawn(move || {
+            let (mut stream, _) = listener.accept().unwrap();
+            let mut buf = [0u8; 1024];
+            let _ = stream.read(&mut buf).unwrap();
+            stream
+                .write_all(b"HTTP/1.1 500 Internal Server Error\r\nContent-Length: 0\r\n\r\n")
+                .unwrap();
+        });
+
+        let result =
+            HttpWebhookTransport::default().post_json(&format!("http://127.0.0.1:{}/", port), "{}");
+        server.join().unwrap();
+        assert_eq!(result.unwrap_err(), NotifierError::WebhookStatus(500));
+    }
+}
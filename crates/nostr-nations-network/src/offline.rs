@@ -5,6 +5,9 @@
 //! - **OfflineStorage**: Persists events and game state locally
 //! - **OfflineSyncStrategy**: Defines how to handle reconnection
 //! - **ConnectionMonitor**: Monitors connection health and triggers offline mode
+//! - **Reconciliation**: Conflict-checks queued actions against remote events
+//! - **StorageLayout**: On-device storage paths inside the app sandbox
+//! - **ResumePlan**: What to re-check when a backgrounded app comes back
 //!
 //! # Usage
 //!
@@ -27,13 +30,39 @@
 //! // When connection restored
 //! let pending = manager.go_online();
 //! // Send pending events to network
+//!
+//! // Or, to check them against what happened meanwhile
+//! let outcome = manager.reconcile(&remote_events, &ConflictResolver::default());
+//! // Send outcome.accepted, resync if outcome.needs_resync
+//! ```
+//!
+//! # Mobile Lifecycle
+//!
+//! iOS and Android may suspend or kill a backgrounded app without warning,
+//! so queued events are flushed to disk when the app moves to the
+//! background, and connections are re-validated when it comes back:
+//!
+//! ```rust,ignore
+//! let layout = StorageLayout::new(app_data_dir, app_cache_dir);
+//! layout.prepare()?;
+//! let storage = layout.offline_storage();
+//!
+//! // App moved to the background
+//! manager.suspend(&storage, Some(&game_state), now)?;
+//!
+//! // App back in the foreground
+//! let plan = manager.resume(&storage, &mut monitor, &LifecycleConfig::default(), now)?;
+//! if plan.revalidate_connections {
+//!     // Ping peers and relays before sending anything
+//! }
 //! ```
 
+use crate::conflict::{ConflictDetector, ConflictResolver, ConflictType, Resolution};
 use nostr_nations_core::events::GameEvent;
 use nostr_nations_core::game_state::GameState;
 use std::fs;
 use std::io;
-use std::path::PathBuf;
+use std::path::{Path, PathBuf};
 
 /// Error types for offline storage operations.
 #[derive(Debug)]
@@ -46,6 +75,10 @@ pub enum StorageError {
     PathNotFound(PathBuf),
     /// Failed to create storage directory.
     DirectoryCreationFailed(PathBuf),
+    /// Storage directory exists but cannot be written to.
+    NotWritable(PathBuf),
+    /// Owner pubkey can't be used to name a profile directory.
+    InvalidOwner(String),
 }
 
 impl std::fmt::Display for StorageError {
@@ -57,6 +90,8 @@ impl std::fmt::Display for StorageError {
             StorageError::DirectoryCreationFailed(path) => {
                 write!(f, "Failed to create directory: {:?}", path)
             }
+            StorageError::NotWritable(path) => write!(f, "Directory not writable: {:?}", path),
+            StorageError::InvalidOwner(owner) => write!(f, "Invalid owner pubkey: {:?}", owner),
         }
     }
 }
@@ -135,6 +170,8 @@ pub struct OfflineManager {
     max_offline_turns: u32,
     /// The sync strategy to use.
     sync_strategy: OfflineSyncStrategy,
+    /// When the app was moved to the background, if it is suspended.
+    suspended_at: Option<u64>,
 }
 
 impl Default for OfflineManager {
@@ -154,6 +191,7 @@ impl OfflineManager {
             connection_attempts: 0,
             max_offline_turns: 10,
             sync_strategy: OfflineSyncStrategy::default(),
+            suspended_at: None,
         }
     }
 
@@ -167,6 +205,7 @@ impl OfflineManager {
             connection_attempts: 0,
             max_offline_turns,
             sync_strategy,
+            suspended_at: None,
         }
     }
 
@@ -232,6 +271,37 @@ impl OfflineManager {
         &self.pending_events
     }
 
+    /// Route a locally produced event.
+    ///
+    /// Returns the event for immediate broadcast when online. When offline,
+    /// the event is queued instead, with a provisional ID and timestamp if it
+    /// has not been signed yet so it can be conflict-checked on reconnect.
+    pub fn submit_event(&mut self, mut event: GameEvent) -> Option<GameEvent> {
+        if self.is_online {
+            return Some(event);
+        }
+
+        if event.timestamp == 0 {
+            event.timestamp = web_time::SystemTime::now()
+                .duration_since(web_time::UNIX_EPOCH)
+                .unwrap_or_default()
+                .as_millis() as u64;
+        }
+        if event.id.is_empty() {
+            event.id = format!(
+                "offline:{}:{}:{}",
+                event.game_id, event.turn, event.sequence
+            );
+        }
+        self.queue_event(event);
+        None
+    }
+
+    /// Check if local actions can be taken right now.
+    pub fn allows_local_actions(&self) -> bool {
+        self.is_online || self.sync_strategy.allows_local_actions()
+    }
+
     /// Clear all pending events.
     ///
     /// Use this if events need to be discarded (e.g., due to conflict resolution
@@ -257,8 +327,8 @@ impl OfflineManager {
     /// Record a successful sync at the given turn.
     pub fn record_sync(&mut self, turn: u32) {
         self.last_sync_turn = turn;
-        self.last_sync_timestamp = std::time::SystemTime::now()
-            .duration_since(std::time::UNIX_EPOCH)
+        self.last_sync_timestamp = web_time::SystemTime::now()
+            .duration_since(web_time::UNIX_EPOCH)
             .unwrap_or_default()
             .as_secs();
     }
@@ -316,6 +386,167 @@ impl OfflineManager {
     pub fn connection_attempts(&self) -> u32 {
         self.connection_attempts
     }
+
+    // ==================== App Lifecycle ====================
+
+    /// Flush queued events (and the game state, if given) to disk before
+    /// the app is backgrounded.
+    ///
+    /// Mobile platforms may kill a suspended app without notice, so
+    /// anything not on disk by now can be lost. `now` is a Unix timestamp
+    /// in seconds.
+    pub fn suspend(
+        &mut self,
+        storage: &OfflineStorage,
+        state: Option<&GameState>,
+        now: u64,
+    ) -> Result<(), StorageError> {
+        if self.pending_events.is_empty() {
+            storage.clear_pending_events()?;
+        } else {
+            storage.save_pending_events(&self.pending_events)?;
+        }
+        if let Some(state) = state {
+            storage.save_game_state(state)?;
+        }
+        self.suspended_at = Some(now);
+        Ok(())
+    }
+
+    /// Bring the manager back after the app returns to the foreground.
+    ///
+    /// If the process was killed while suspended, the queue is empty and
+    /// is restored from `storage`. Connections are marked for
+    /// re-validation on `monitor` once the app has been away longer than
+    /// the configured threshold.
+    pub fn resume(
+        &mut self,
+        storage: &OfflineStorage,
+        monitor: &mut ConnectionMonitor,
+        config: &LifecycleConfig,
+        now: u64,
+    ) -> Result<ResumePlan, StorageError> {
+        let mut restored_events = 0;
+        if self.pending_events.is_empty() {
+            self.pending_events = storage.load_pending_events()?;
+            restored_events = self.pending_events.len();
+        }
+
+        // A fresh process has no suspend time; treat it as a long absence
+        let suspended_for = match self.suspended_at.take() {
+            Some(at) => now.saturating_sub(at),
+            None => u64::MAX,
+        };
+        let revalidate_connections = suspended_for >= config.revalidate_after_secs;
+        if revalidate_connections {
+            monitor.invalidate();
+        }
+
+        Ok(ResumePlan {
+            suspended_for_secs: suspended_for,
+            revalidate_connections,
+            needs_resync: suspended_for >= config.resync_after_secs,
+            restored_events,
+        })
+    }
+
+    /// Check if the app is currently suspended.
+    pub fn is_suspended(&self) -> bool {
+        self.suspended_at.is_some()
+    }
+
+    // ==================== Reconciliation ====================
+
+    /// Transition to online state, checking queued events against remote
+    /// events that happened while offline.
+    ///
+    /// Queued events are checked in order. Each one that survives conflict
+    /// resolution is relinked onto the end of the remote chain so it can be
+    /// signed and sent. If any queued event is dropped, local state already
+    /// reflects it and must be rebuilt from the canonical chain.
+    pub fn reconcile(
+        &mut self,
+        remote_events: &[GameEvent],
+        resolver: &ConflictResolver,
+    ) -> Reconciliation {
+        let pending = self.go_online();
+        let mut outcome = Reconciliation::default();
+
+        let mut detector = ConflictDetector::new();
+        for event in remote_events {
+            detector.add_event(event.clone());
+        }
+        let mut prev_id = remote_events.last().map(|e| e.id.clone());
+
+        for mut event in pending {
+            // The local chain is relinked below, so a stale predecessor is expected
+            let conflicts: Vec<ConflictType> = detector
+                .check_event(&event)
+                .into_iter()
+                .filter(|c| !matches!(c, ConflictType::MissingPredecessor { .. }))
+                .collect();
+
+            let mut keep = true;
+            for conflict in &conflicts {
+                let mut involved = vec![event.clone()];
+                involved.extend(conflicting_events(conflict, remote_events));
+
+                match resolver.resolve(conflict, &involved) {
+                    Resolution::Accept(id) if id == event.id => {}
+                    Resolution::Merge(_) => {}
+                    Resolution::RequestResync => {
+                        outcome.needs_resync = true;
+                        keep = false;
+                    }
+                    Resolution::Reject(id) if id != event.id => {}
+                    _ => keep = false,
+                }
+            }
+
+            if keep {
+                if remote_events.is_empty() {
+                    prev_id = Some(event.id.clone());
+                } else {
+                    event.prev_event_id = prev_id.replace(event.id.clone());
+                }
+                detector.add_event(event.clone());
+                outcome.accepted.push(event);
+            } else {
+                outcome.needs_resync = true;
+                outcome.rejected.push((event, conflicts));
+            }
+        }
+
+        outcome
+    }
+}
+
+/// Remote events on the other side of a conflict.
+fn conflicting_events(conflict: &ConflictType, remote_events: &[GameEvent]) -> Vec<GameEvent> {
+    match conflict {
+        ConflictType::ConcurrentModification { player_a, .. } => remote_events
+            .iter()
+            .filter(|e| e.player_id == *player_a)
+            .cloned()
+            .collect(),
+        ConflictType::TimestampCollision { event_a_id, .. } => remote_events
+            .iter()
+            .filter(|e| &e.id == event_a_id)
+            .cloned()
+            .collect(),
+        _ => Vec::new(),
+    }
+}
+
+/// Result of reconciling queued events after reconnecting.
+#[derive(Clone, Debug, Default)]
+pub struct Reconciliation {
+    /// Queued events to send, in their original order.
+    pub accepted: Vec<GameEvent>,
+    /// Queued events dropped by conflict resolution, with their conflicts.
+    pub rejected: Vec<(GameEvent, Vec<ConflictType>)>,
+    /// Whether local state must be rebuilt from the canonical chain.
+    pub needs_resync: bool,
 }
 
 // ==================== OfflineStorage ====================
@@ -370,8 +601,7 @@ impl OfflineStorage {
         self.ensure_directory()?;
         let json = serde_json::to_string_pretty(events)
             .map_err(|e| StorageError::Serialization(e.to_string()))?;
-        fs::write(self.pending_events_path(), json)?;
-        Ok(())
+        write_atomic(&self.pending_events_path(), json.as_bytes())
     }
 
     /// Load pending events from disk.
@@ -391,8 +621,7 @@ impl OfflineStorage {
         self.ensure_directory()?;
         let json = serde_json::to_string_pretty(game)
             .map_err(|e| StorageError::Serialization(e.to_string()))?;
-        fs::write(self.game_state_path(), json)?;
-        Ok(())
+        write_atomic(&self.game_state_path(), json.as_bytes())
     }
 
     /// Load game state from disk.
@@ -448,6 +677,186 @@ impl OfflineStorage {
     }
 }
 
+/// Write a file by writing a sibling temp file and renaming it over the
+/// target, so a process killed mid-write never leaves a truncated file.
+fn write_atomic(path: &Path, contents: &[u8]) -> Result<(), StorageError> {
+    let tmp = path.with_extension("tmp");
+    fs::write(&tmp, contents)?;
+    fs::rename(&tmp, path)?;
+    Ok(())
+}
+
+// ==================== StorageLayout ====================
+
+/// Directory under the data and cache directories holding one directory
+/// per identity.
+const PROFILES_DIR: &str = "profiles";
+
+/// Longest owner pubkey accepted as a profile directory name.
+const MAX_OWNER_LEN: usize = 128;
+
+/// On-device storage locations for the app.
+///
+/// Mobile apps may only write inside their sandbox, so every path is
+/// derived from the data and cache directories the platform hands out
+/// (Tauri's `app_data_dir` and `app_cache_dir`). Data that must survive
+/// goes under the data directory; the event cache goes under the cache
+/// directory, which the OS may clear when storage runs low.
+///
+/// Several identities can share a device. [`for_owner`](Self::for_owner)
+/// gives each owner pubkey its own directories under `profiles/`, so their
+/// relay databases, offline queues and saves never mix.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct StorageLayout {
+    /// Directory for persistent data.
+    data_dir: PathBuf,
+    /// Directory for rebuildable data.
+    cache_dir: PathBuf,
+    /// Pubkey whose data this layout holds, if it is a profile layout.
+    owner: Option<String>,
+}
+
+impl StorageLayout {
+    /// Create a layout from the platform's data and cache directories.
+    pub fn new(data_dir: impl Into<PathBuf>, cache_dir: impl Into<PathBuf>) -> Self {
+        Self {
+            data_dir: data_dir.into(),
+            cache_dir: cache_dir.into(),
+            owner: None,
+        }
+    }
+
+    /// The layout for one identity's data, nested under this (device-wide)
+    /// layout.
+    ///
+    /// `owner` must be a pubkey in hex or bech32 (`npub1...`) form; anything
+    /// else could escape the profile directory and is refused.
+    pub fn for_owner(&self, owner: &str) -> Result<StorageLayout, StorageError> {
+        let valid = !owner.is_empty()
+            && owner.len() <= MAX_OWNER_LEN
+            && owner.chars().all(|c| c.is_ascii_alphanumeric());
+        if !valid {
+            return Err(StorageError::InvalidOwner(owner.to_string()));
+        }
+        Ok(Self {
+            data_dir: self.data_dir.join(PROFILES_DIR).join(owner),
+            cache_dir: self.cache_dir.join(PROFILES_DIR).join(owner),
+            owner: Some(owner.to_string()),
+        })
+    }
+
+    /// Pubkey whose data this layout holds, or `None` for the device-wide
+    /// layout.
+    pub fn owner(&self) -> Option<&str> {
+        self.owner.as_deref()
+    }
+
+    /// Create a layout with everything under one root directory.
+    pub fn with_root(root: impl Into<PathBuf>) -> Self {
+        let root = root.into();
+        let cache_dir = root.join("cache");
+        Self::new(root, cache_dir)
+    }
+
+    /// Get the persistent data directory.
+    pub fn data_dir(&self) -> &Path {
+        &self.data_dir
+    }
+
+    /// Get the cache directory.
+    pub fn cache_dir(&self) -> &Path {
+        &self.cache_dir
+    }
+
+    /// Directory for the offline queue and game state snapshot.
+    pub fn offline_dir(&self) -> PathBuf {
+        self.data_dir.join("offline")
+    }
+
+    /// Path of the local relay's event database.
+    pub fn relay_db_path(&self) -> PathBuf {
+        self.data_dir.join("relay.db")
+    }
+
+    /// Directory for saved games.
+    pub fn saves_dir(&self) -> PathBuf {
+        self.data_dir.join("saves")
+    }
+
+    /// Directory for map screenshots and timelapse frames.
+    pub fn captures_dir(&self) -> PathBuf {
+        self.data_dir.join("captures")
+    }
+
+    /// Path of the passphrase key file for encryption at rest.
+    pub fn at_rest_key_path(&self) -> PathBuf {
+        self.data_dir.join("at_rest_key.json")
+    }
+
+    /// Path of the event cache database.
+    pub fn cache_db_path(&self) -> PathBuf {
+        self.cache_dir.join("event_cache.db")
+    }
+
+    /// Offline storage rooted at [`Self::offline_dir`].
+    pub fn offline_storage(&self) -> OfflineStorage {
+        OfflineStorage::new(self.offline_dir())
+    }
+
+    /// Create every directory and check that each can be written to.
+    ///
+    /// Call once at startup. A sandbox directory that exists but rejects
+    /// writes (for example after an OS restore) fails here rather than on
+    /// the first save.
+    pub fn prepare(&self) -> Result<(), StorageError> {
+        for dir in [
+            self.data_dir.clone(),
+            self.cache_dir.clone(),
+            self.offline_dir(),
+        ] {
+            fs::create_dir_all(&dir)
+                .map_err(|_| StorageError::DirectoryCreationFailed(dir.clone()))?;
+            let probe = dir.join(".write_probe");
+            fs::write(&probe, b"ok").map_err(|_| StorageError::NotWritable(dir.clone()))?;
+            let _ = fs::remove_file(&probe);
+        }
+        Ok(())
+    }
+}
+
+// ==================== Lifecycle ====================
+
+/// Thresholds applied when the app returns to the foreground.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct LifecycleConfig {
+    /// Re-validate connections after being backgrounded this long.
+    pub revalidate_after_secs: u64,
+    /// Request a full resync after being backgrounded this long.
+    pub resync_after_secs: u64,
+}
+
+impl Default for LifecycleConfig {
+    fn default() -> Self {
+        Self {
+            revalidate_after_secs: 30,
+            resync_after_secs: 300,
+        }
+    }
+}
+
+/// What the app should do after resuming.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct ResumePlan {
+    /// Seconds spent in the background (`u64::MAX` after a cold start).
+    pub suspended_for_secs: u64,
+    /// Peers and relays should be pinged before sending anything.
+    pub revalidate_connections: bool,
+    /// The game should be resynced from peers.
+    pub needs_resync: bool,
+    /// Number of queued events restored from disk.
+    pub restored_events: usize,
+}
+
 // ==================== ConnectionMonitor ====================
 
 /// Monitors connection health and determines when to go offline.
@@ -471,6 +880,8 @@ pub struct ConnectionMonitor {
     total_failures: u64,
     /// Timestamp of last successful connection.
     last_success_timestamp: u64,
+    /// Whether connections must be re-checked before being trusted.
+    needs_revalidation: bool,
 }
 
 impl Default for ConnectionMonitor {
@@ -495,6 +906,7 @@ impl ConnectionMonitor {
             total_successes: 0,
             total_failures: 0,
             last_success_timestamp: 0,
+            needs_revalidation: false,
         }
     }
 
@@ -508,15 +920,17 @@ impl ConnectionMonitor {
             total_successes: 0,
             total_failures: 0,
             last_success_timestamp: 0,
+            needs_revalidation: false,
         }
     }
 
     /// Record a successful connection/ping.
     pub fn record_success(&mut self) {
         self.consecutive_failures = 0;
+        self.needs_revalidation = false;
         self.total_successes += 1;
-        self.last_success_timestamp = std::time::SystemTime::now()
-            .duration_since(std::time::UNIX_EPOCH)
+        self.last_success_timestamp = web_time::SystemTime::now()
+            .duration_since(web_time::UNIX_EPOCH)
             .unwrap_or_default()
             .as_secs();
     }
@@ -529,9 +943,23 @@ impl ConnectionMonitor {
 
     /// Check if the connection is currently considered healthy.
     ///
-    /// The connection is healthy if consecutive failures are below the threshold.
+    /// The connection is healthy if consecutive failures are below the
+    /// threshold and it does not need re-validation.
     pub fn is_connection_healthy(&self) -> bool {
-        self.consecutive_failures < self.failure_threshold
+        !self.needs_revalidation && self.consecutive_failures < self.failure_threshold
+    }
+
+    /// Mark connections as stale until the next successful ping.
+    ///
+    /// Used after the app resumes from the background, when sockets may
+    /// have been closed by the OS without an error being reported.
+    pub fn invalidate(&mut self) {
+        self.needs_revalidation = true;
+    }
+
+    /// Check if connections must be re-checked before being trusted.
+    pub fn needs_revalidation(&self) -> bool {
+        self.needs_revalidation
     }
 
     /// Check if the client should transition to offline mode.
@@ -597,6 +1025,7 @@ impl ConnectionMonitor {
         self.total_successes = 0;
         self.total_failures = 0;
         self.last_success_timestamp = 0;
+        self.needs_revalidation = false;
     }
 
     /// Calculate the success rate as a percentage.
@@ -619,11 +1048,19 @@ mod tests {
     use super::*;
     use nostr_nations_core::events::GameAction;
     use nostr_nations_core::settings::GameSettings;
+    use nostr_nations_core::types::{GameId, PlayerSlot, UnitId};
     use tempfile::TempDir;
 
     // Helper to create a test event
     fn create_test_event(id: &str, turn: u32) -> GameEvent {
-        let mut event = GameEvent::new("game1".to_string(), 0, None, turn, 1, GameAction::EndTurn);
+        let mut event = GameEvent::new(
+            GameId::new("game1"),
+            PlayerSlot(0),
+            None,
+            turn,
+            1,
+            GameAction::EndTurn,
+        );
         event.id = id.to_string();
         event.timestamp = 1000 + turn as u64;
         event
@@ -632,7 +1069,7 @@ mod tests {
     // Helper to create a test game state
     fn create_test_game_state() -> GameState {
         let settings = GameSettings::default();
-        GameState::new("game1".to_string(), settings, [0u8; 32])
+        GameState::new(GameId::new("game1"), settings, [0u8; 32])
     }
 
     // ==================== OfflineSyncStrategy Tests ====================
@@ -1141,6 +1578,313 @@ mod tests {
         assert!(matches!(storage_err, StorageError::Io(_)));
     }
 
+    // ==================== Reconciliation Tests ====================
+
+    fn move_event(id: &str, player: u8, unit_id: u64, timestamp: u64) -> GameEvent {
+        let mut event = GameEvent::new(
+            GameId::new("game1"),
+            PlayerSlot(player),
+            None,
+            1,
+            1,
+            GameAction::MoveUnit {
+                unit_id: UnitId(unit_id),
+                path: vec![],
+            },
+        );
+        event.id = id.to_string();
+        event.timestamp = timestamp;
+        event
+    }
+
+    #[test]
+    fn test_submit_event_online_passes_through() {
+        let mut manager = OfflineManager::new();
+        let event = create_test_event("e1", 1);
+
+        assert_eq!(manager.submit_event(event).unwrap().id, "e1");
+        assert!(!manager.has_pending_events());
+    }
+
+    #[test]
+    fn test_submit_event_offline_queues_with_provisional_id() {
+        let mut manager = OfflineManager::new();
+        manager.go_offline();
+
+        let event = GameEvent::new(
+            GameId::new("game1"),
+            PlayerSlot(0),
+            None,
+            3,
+            7,
+            GameAction::EndTurn,
+        );
+        assert!(manager.submit_event(event).is_none());
+
+        let pending = manager.pending_events();
+        assert_eq!(pending[0].id, "offline:game1:3:7");
+        assert!(pending[0].timestamp > 0);
+    }
+
+    #[test]
+    fn test_allows_local_actions() {
+        let mut manager = OfflineManager::with_config(10, OfflineSyncStrategy::PauseUntilOnline);
+        assert!(manager.allows_local_actions());
+
+        manager.go_offline();
+        assert!(!manager.allows_local_actions());
+
+        manager.set_sync_strategy(OfflineSyncStrategy::QueueAndSync);
+        assert!(manager.allows_local_actions());
+    }
+
+    #[test]
+    fn test_reconcile_without_conflicts_relinks_chain() {
+        let mut manager = OfflineManager::new();
+        manager.go_offline();
+        manager.queue_event(move_event("local1", 0, 1, 5000));
+        manager.queue_event(move_event("local2", 0, 2, 5100));
+
+        let remote = vec![move_event("remote1", 1, 9, 5050)];
+        let outcome = manager.reconcile(&remote, &ConflictResolver::default());
+
+        assert!(manager.is_online());
+        assert!(!manager.has_pending_events());
+        assert!(!outcome.needs_resync);
+        assert_eq!(outcome.accepted.len(), 2);
+        assert_eq!(
+            outcome.accepted[0].prev_event_id.as_deref(),
+            Some("remote1")
+        );
+        assert_eq!(outcome.accepted[1].prev_event_id.as_deref(), Some("local1"));
+    }
+
+    #[test]
+    fn test_reconcile_rejects_losing_concurrent_modification() {
+        let mut manager = OfflineManager::new();
+        manager.go_offline();
+        manager.queue_event(move_event("local1", 0, 1, 5500));
+        manager.queue_event(move_event("local2", 0, 2, 9000));
+
+        // Remote player touched unit 1 first
+        let remote = vec![move_event("remote1", 1, 1, 5000)];
+        let outcome = manager.reconcile(&remote, &ConflictResolver::default());
+
+        assert!(outcome.needs_resync);
+        assert_eq!(outcome.rejected.len(), 1);
+        assert_eq!(outcome.rejected[0].0.id, "local1");
+        assert!(matches!(
+            outcome.rejected[0].1[0],
+            ConflictType::ConcurrentModification { entity_id: 1, .. }
+        ));
+        assert_eq!(outcome.accepted.len(), 1);
+        assert_eq!(outcome.accepted[0].id, "local2");
+    }
+
+    #[test]
+    fn test_reconcile_rejects_invalid_transition() {
+        let mut manager = OfflineManager::new();
+        manager.go_offline();
+        manager.queue_event(move_event("local1", 0, 4, 9000));
+
+        let mut delete = move_event("remote1", 1, 4, 1000);
+        delete.action = GameAction::DeleteUnit { unit_id: UnitId(4) };
+        let outcome = manager.reconcile(&[delete], &ConflictResolver::default());
+
+        assert!(outcome.accepted.is_empty());
+        assert_eq!(outcome.rejected[0].0.id, "local1");
+    }
+
+    // ==================== StorageLayout Tests ====================
+
+    #[test]
+    fn test_storage_layout_paths() {
+        let layout = StorageLayout::new("/data", "/cache");
+        assert_eq!(layout.offline_dir(), PathBuf::from("/data/offline"));
+        assert_eq!(layout.relay_db_path(), PathBuf::from("/data/relay.db"));
+        assert_eq!(
+            layout.at_rest_key_path(),
+            PathBuf::from("/data/at_rest_key.json")
+        );
+        assert_eq!(
+            layout.cache_db_path(),
+            PathBuf::from("/cache/event_cache.db")
+        );
+        assert_eq!(
+            layout.offline_storage().storage_path(),
+            &PathBuf::from("/data/offline")
+        );
+
+        let single = StorageLayout::with_root("/root");
+        assert_eq!(single.cache_dir(), Path::new("/root/cache"));
+    }
+
+    #[test]
+    fn test_storage_layout_profiles_are_isolated() {
+        let layout = StorageLayout::new("/data", "/cache");
+        assert_eq!(layout.owner(), None);
+
+        let alice = layout.for_owner("npub1alice").unwrap();
+        let bob = layout.for_owner("npub1bob").unwrap();
+        assert_eq!(alice.owner(), Some("npub1alice"));
+        assert_eq!(
+            alice.relay_db_path(),
+            PathBuf::from("/data/profiles/npub1alice/relay.db")
+        );
+        assert_eq!(
+            alice.cache_db_path(),
+            PathBuf::from("/cache/profiles/npub1alice/event_cache.db")
+        );
+        assert_ne!(alice.offline_dir(), bob.offline_dir());
+        assert_ne!(alice.saves_dir(), bob.saves_dir());
+
+        for bad in ["", "../npub1bob", "npub1/x", &"a".repeat(200)] {
+            assert!(matches!(
+                layout.for_owner(bad),
+                Err(StorageError::InvalidOwner(_))
+            ));
+        }
+    }
+
+    #[test]
+    fn test_storage_layout_prepare_creates_directories() {
+        let temp_dir = TempDir::new().unwrap();
+        let layout =
+            StorageLayout::new(temp_dir.path().join("data"), temp_dir.path().join("cache"));
+
+        layout.prepare().unwrap();
+        assert!(layout.offline_dir().is_dir());
+        assert!(layout.cache_dir().is_dir());
+        assert!(!layout.offline_dir().join(".write_probe").exists());
+    }
+
+    #[test]
+    fn test_storage_layout_prepare_fails_on_file() {
+        let temp_dir = TempDir::new().unwrap();
+        let blocker = temp_dir.path().join("data");
+        fs::write(&blocker, b"not a directory").unwrap();
+
+        let layout = StorageLayout::with_root(&blocker);
+        assert!(matches!(
+            layout.prepare(),
+            Err(StorageError::DirectoryCreationFailed(_))
+        ));
+    }
+
+    #[test]
+    fn test_save_leaves_no_temp_file() {
+        let temp_dir = TempDir::new().unwrap();
+        let storage = OfflineStorage::new(temp_dir.path());
+        storage
+            .save_pending_events(&[create_test_event("e1", 1)])
+            .unwrap();
+
+        let files: Vec<_> = fs::read_dir(temp_dir.path())
+            .unwrap()
+            .map(|entry| entry.unwrap().file_name())
+            .collect();
+        assert_eq!(files, vec![std::ffi::OsString::from("pending_events.json")]);
+    }
+
+    // ==================== Lifecycle Tests ====================
+
+    #[test]
+    fn test_suspend_flushes_queue_and_state() {
+        let temp_dir = TempDir::new().unwrap();
+        let storage = OfflineStorage::new(temp_dir.path());
+        let mut manager = OfflineManager::new();
+        manager.go_offline();
+        manager.queue_event(create_test_event("e1", 1));
+
+        manager
+            .suspend(&storage, Some(&create_test_game_state()), 1000)
+            .unwrap();
+
+        assert!(manager.is_suspended());
+        assert_eq!(storage.load_pending_events().unwrap().len(), 1);
+        assert!(storage.has_game_state());
+    }
+
+    #[test]
+    fn test_suspend_with_empty_queue_clears_stale_file() {
+        let temp_dir = TempDir::new().unwrap();
+        let storage = OfflineStorage::new(temp_dir.path());
+        storage
+            .save_pending_events(&[create_test_event("old", 1)])
+            .unwrap();
+
+        let mut manager = OfflineManager::new();
+        manager.suspend(&storage, None, 1000).unwrap();
+
+        assert!(!storage.has_pending_events());
+        assert!(!storage.has_game_state());
+    }
+
+    #[test]
+    fn test_short_resume_keeps_connections() {
+        let temp_dir = TempDir::new().unwrap();
+        let storage = OfflineStorage::new(temp_dir.path());
+        let mut manager = OfflineManager::new();
+        let mut monitor = ConnectionMonitor::default();
+        let config = LifecycleConfig::default();
+
+        manager.suspend(&storage, None, 1000).unwrap();
+        let plan = manager
+            .resume(&storage, &mut monitor, &config, 1010)
+            .unwrap();
+
+        assert_eq!(plan.suspended_for_secs, 10);
+        assert!(!plan.revalidate_connections);
+        assert!(!plan.needs_resync);
+        assert!(!manager.is_suspended());
+        assert!(monitor.is_connection_healthy());
+    }
+
+    #[test]
+    fn test_long_resume_revalidates_and_resyncs() {
+        let temp_dir = TempDir::new().unwrap();
+        let storage = OfflineStorage::new(temp_dir.path());
+        let mut manager = OfflineManager::new();
+        let mut monitor = ConnectionMonitor::default();
+        let config = LifecycleConfig::default();
+
+        manager.suspend(&storage, None, 1000).unwrap();
+        let plan = manager
+            .resume(&storage, &mut monitor, &config, 2000)
+            .unwrap();
+
+        assert!(plan.revalidate_connections);
+        assert!(plan.needs_resync);
+        assert!(monitor.needs_revalidation());
+        assert!(!monitor.is_connection_healthy());
+
+        monitor.record_success();
+        assert!(monitor.is_connection_healthy());
+    }
+
+    #[test]
+    fn test_cold_resume_restores_queue() {
+        let temp_dir = TempDir::new().unwrap();
+        let storage = OfflineStorage::new(temp_dir.path());
+
+        let mut before = OfflineManager::new();
+        before.go_offline();
+        before.queue_event(create_test_event("e1", 1));
+        before.queue_event(create_test_event("e2", 2));
+        before.suspend(&storage, None, 1000).unwrap();
+
+        // Process killed while suspended; a new manager starts from disk
+        let mut after = OfflineManager::new();
+        let mut monitor = ConnectionMonitor::default();
+        let plan = after
+            .resume(&storage, &mut monitor, &LifecycleConfig::default(), 1005)
+            .unwrap();
+
+        assert_eq!(plan.restored_events, 2);
+        assert!(plan.revalidate_connections);
+        assert_eq!(after.pending_count(), 2);
+    }
+
     // ==================== Integration Tests ====================
 
     #[test]
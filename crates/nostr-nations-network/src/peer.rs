@@ -12,14 +12,295 @@
 //! 3. Client scans QR code and extracts ticket
 //! 4. Client uses ticket to connect to host
 //! 5. Both peers can now exchange game events
-
+//!
+//! # Session Resume
+//!
+//! Messages sent with [`PeerManager::sequence_message`] carry a per-peer
+//! sequence number and are kept in a bounded replay buffer until the peer
+//! acknowledges them with a cumulative [`PeerMessage::Ack`]. A peer that
+//! reconnects sends [`PeerMessage::Resume`] with the last sequence it
+//! received, and only the messages after that are replayed. If the buffer
+//! no longer covers the gap, the peer falls back to a full sync.
+//!
+//! Received messages are delivered strictly in sequence. Anything that
+//! arrives after a gap is held until the missing messages come in, and
+//! [`PeerEvent::ReplayNeeded`] asks the caller to request them with a
+//! Resume. The cumulative ACK never moves past a gap. If the Resume or its
+//! replay is lost, or held messages overflowed, [`PeerManager::pending_resume`]
+//! repeats the request once [`DEFAULT_REPLAY_RETRY`] passes without the gap
+//! closing.
+//!
+//! # Redaction
+//!
+//! A full client sets a [`RedactionGate`] with
+//! [`PeerManager::update_redaction`]. From then on every message is
+//! redacted for the recipient's npub before it is sequenced, so game
+//! events and sync responses never carry more than the peer's player may
+//! see, and replays resend the redacted copy. Until a gate is set, messages
+//! carrying game state are refused, unless the manager was built
+//! [`PeerManager::without_redaction`] for peers that may see everything.
+//!
+//! # Handshake
+//!
+//! Each side opens with [`PeerMessage::Hello`] carrying its
+//! [`Capabilities`]: protocol version range, compression algorithms,
+//! encryption versions, batching support and ruleset hash. The receiver
+//! negotiates a [`NegotiatedSession`] from both sets, or reports a
+//! [`HandshakeError`] and drops the peer when they can't play together.
+//! A Hello may also name the sender's npub; peers whose npub is on the
+//! manager's [`BlockList`] are refused. The npub is only trusted, and used
+//! for redaction, once the peer answers the challenge in our Hello with a
+//! [`PeerMessage::NpubProof`] signed by that key; see
+//! [`PeerManager::pending_npub_proof`].
+
+use crate::compression::CompressionAlgorithm;
+use crate::encryption::ENCRYPTION_VERSION;
+use crate::presence::{PresenceChange, PresenceMap, PresenceUpdate};
+use crate::redaction::{RedactionError, RedactionGate};
+use crate::reputation::BlockList;
+use crate::signing::{verify_signature, SigningKey};
+use crate::sync::{StateQuery, StateQueryResponse};
+use chacha20poly1305::aead::rand_core::RngCore;
+use chacha20poly1305::aead::OsRng;
+use nostr_nations_core::game_state::GameState;
+use nostr_nations_core::types::{GameId, Npub, PlayerSlot};
 use serde::{Deserialize, Serialize};
-use std::collections::HashMap;
+use sha2::{Digest, Sha256};
+use std::borrow::Borrow;
+use std::collections::{BTreeMap, HashMap, VecDeque};
+use std::fmt;
 use std::sync::Arc;
+use std::time::Duration;
 use tokio::sync::{mpsc, RwLock};
+use web_time::Instant;
 
 /// Unique identifier for a peer (derived from their Iroh node ID).
-pub type PeerId = String;
+#[derive(Clone, Debug, Default, PartialEq, Eq, PartialOrd, Ord, Hash, Serialize, Deserialize)]
+#[serde(transparent)]
+pub struct PeerId(pub String);
+
+impl PeerId {
+    /// Wrap a raw node ID.
+    pub fn new(id: impl Into<String>) -> Self {
+        Self(id.into())
+    }
+
+    /// The raw node ID.
+    pub fn as_str(&self) -> &str {
+        &self.0
+    }
+}
+
+impl From<String> for PeerId {
+    fn from(id: String) -> Self {
+        Self(id)
+    }
+}
+
+impl From<&str> for PeerId {
+    fn from(id: &str) -> Self {
+        Self(id.to_string())
+    }
+}
+
+impl Borrow<str> for PeerId {
+    fn borrow(&self) -> &str {
+        &self.0
+    }
+}
+
+impl PartialEq<str> for PeerId {
+    fn eq(&self, other: &str) -> bool {
+        self.0 == other
+    }
+}
+
+impl PartialEq<&str> for PeerId {
+    fn eq(&self, other: &&str) -> bool {
+        self.0 == *other
+    }
+}
+
+impl fmt::Display for PeerId {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(&self.0)
+    }
+}
+
+/// Default number of unacknowledged messages kept per peer for resume.
+pub const DEFAULT_REPLAY_CAPACITY: usize = 256;
+
+/// Default time to wait for a requested replay before asking again.
+pub const DEFAULT_REPLAY_RETRY: Duration = Duration::from_secs(5);
+
+/// Peer protocol version spoken by this build.
+pub const PROTOCOL_VERSION: u32 = 2;
+
+/// Oldest peer protocol version this build can talk to.
+///
+/// Version 1 peers predate the capability exchange and send a bare
+/// [`PeerMessage::Hello`].
+pub const MIN_PROTOCOL_VERSION: u32 = 1;
+
+/// Features a peer supports, sent in its [`PeerMessage::Hello`].
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Capabilities {
+    /// Protocol version the peer speaks.
+    pub protocol_version: u32,
+    /// Oldest protocol version the peer accepts.
+    pub min_protocol_version: u32,
+    /// Supported compression algorithms, most preferred first.
+    pub compression: Vec<CompressionAlgorithm>,
+    /// Supported encryption scheme versions.
+    pub encryption_versions: Vec<u8>,
+    /// Whether the peer accepts batched events.
+    pub batching: bool,
+    /// Hash of the game rules, see `nostr_nations_core::audit::ruleset_hash`
+    /// (0 if unknown).
+    pub ruleset_hash: u64,
+}
+
+impl Default for Capabilities {
+    fn default() -> Self {
+        Self {
+            protocol_version: PROTOCOL_VERSION,
+            min_protocol_version: MIN_PROTOCOL_VERSION,
+            compression: vec![
+                CompressionAlgorithm::Lz,
+                CompressionAlgorithm::Rle,
+                CompressionAlgorithm::None,
+            ],
+            encryption_versions: vec![ENCRYPTION_VERSION],
+            batching: true,
+            ruleset_hash: 0,
+        }
+    }
+}
+
+impl Capabilities {
+    /// Capabilities assumed for a version 1 peer that sent none.
+    pub fn legacy() -> Self {
+        Self {
+            protocol_version: 1,
+            min_protocol_version: 1,
+            compression: vec![CompressionAlgorithm::Rle, CompressionAlgorithm::None],
+            encryption_versions: vec![ENCRYPTION_VERSION],
+            batching: true,
+            ruleset_hash: 0,
+        }
+    }
+
+    /// Set the ruleset hash.
+    pub fn with_ruleset_hash(mut self, ruleset_hash: u64) -> Self {
+        self.ruleset_hash = ruleset_hash;
+        self
+    }
+
+    /// Negotiate session parameters with a remote peer.
+    ///
+    /// Uses the highest protocol version both sides accept, the first of
+    /// our compression algorithms the remote also supports, and the
+    /// highest common encryption version. Ruleset hashes are only compared
+    /// when both sides know theirs.
+    pub fn negotiate(&self, remote: &Capabilities) -> Result<NegotiatedSession, HandshakeError> {
+        let version = self.protocol_version.min(remote.protocol_version);
+        if version < self.min_protocol_version || version < remote.min_protocol_version {
+            return Err(HandshakeError::IncompatibleVersion {
+                local: self.protocol_version,
+                remote: remote.protocol_version,
+            });
+        }
+
+        if self.ruleset_hash != 0
+            && remote.ruleset_hash != 0
+            && self.ruleset_hash != remote.ruleset_hash
+        {
+            return Err(HandshakeError::RulesetMismatch {
+                local: self.ruleset_hash,
+                remote: remote.ruleset_hash,
+            });
+        }
+
+        let encryption_version = self
+            .encryption_versions
+            .iter()
+            .filter(|v| remote.encryption_versions.contains(v))
+            .max()
+            .copied()
+            .ok_or(HandshakeError::NoCommonEncryption)?;
+
+        let compression = self
+            .compression
+            .iter()
+            .find(|a| remote.compression.contains(a))
+            .copied()
+            .unwrap_or(CompressionAlgorithm::None);
+
+        Ok(NegotiatedSession {
+            protocol_version: version,
+            compression,
+            encryption_version,
+            batching: self.batching && remote.batching,
+        })
+    }
+}
+
+/// Parameters agreed with a peer during the handshake.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct NegotiatedSession {
+    /// Protocol version used with the peer.
+    pub protocol_version: u32,
+    /// Compression algorithm for payloads.
+    pub compression: CompressionAlgorithm,
+    /// Encryption scheme version.
+    pub encryption_version: u8,
+    /// Whether events may be batched.
+    pub batching: bool,
+}
+
+/// Why a handshake failed.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum HandshakeError {
+    /// The peer's protocol version is outside the range we accept.
+    IncompatibleVersion { local: u32, remote: u32 },
+    /// The peer plays under different rules.
+    RulesetMismatch { local: u64, remote: u64 },
+    /// No encryption version is supported by both sides.
+    NoCommonEncryption,
+    /// The peer is in a different game.
+    GameMismatch { expected: GameId, actual: GameId },
+    /// The peer's npub is on the block list.
+    Blocked(Npub),
+    /// The peer didn't prove it holds the key of the npub it named.
+    BadNpubProof,
+}
+
+impl std::fmt::Display for HandshakeError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            HandshakeError::IncompatibleVersion { local, remote } => write!(
+                f,
+                "Incompatible protocol version: we speak {}, peer speaks {}",
+                local, remote
+            ),
+            HandshakeError::RulesetMismatch { local, remote } => write!(
+                f,
+                "Ruleset mismatch: ours is {:016x}, peer's is {:016x}",
+                local, remote
+            ),
+            HandshakeError::NoCommonEncryption => {
+                write!(f, "No common encryption version")
+            }
+            HandshakeError::GameMismatch { expected, actual } => {
+                write!(f, "Peer is in game {}, expected {}", actual, expected)
+            }
+            HandshakeError::Blocked(npub) => write!(f, "{} is blocked", npub),
+            HandshakeError::BadNpubProof => write!(f, "Peer could not prove its npub"),
+        }
+    }
+}
+
+impl std::error::Error for HandshakeError {}
 
 /// Connection ticket for peer discovery.
 ///
@@ -28,22 +309,25 @@ pub type PeerId = String;
 #[derive(Clone, Debug, Serialize, Deserialize)]
 pub struct ConnectionTicket {
     /// The host's node ID.
-    pub node_id: String,
+    pub node_id: PeerId,
     /// Direct addresses for connection.
     pub addresses: Vec<String>,
     /// ALPN protocol identifier.
     pub alpn: String,
     /// Game ID this ticket is for.
-    pub game_id: String,
+    pub game_id: GameId,
     /// Expiration timestamp (Unix seconds).
     pub expires_at: u64,
+    /// Host capabilities, so joiners can reject incompatible hosts early.
+    #[serde(default = "Capabilities::legacy")]
+    pub capabilities: Capabilities,
 }
 
 impl ConnectionTicket {
     /// Create a new connection ticket.
-    pub fn new(node_id: String, addresses: Vec<String>, game_id: String, ttl_secs: u64) -> Self {
-        let expires_at = std::time::SystemTime::now()
-            .duration_since(std::time::UNIX_EPOCH)
+    pub fn new(node_id: PeerId, addresses: Vec<String>, game_id: GameId, ttl_secs: u64) -> Self {
+        let expires_at = web_time::SystemTime::now()
+            .duration_since(web_time::UNIX_EPOCH)
             .map(|d| d.as_secs() + ttl_secs)
             .unwrap_or(0);
 
@@ -53,9 +337,31 @@ impl ConnectionTicket {
             alpn: "nostr-nations/1".to_string(),
             game_id,
             expires_at,
+            capabilities: Capabilities::default(),
         }
     }
 
+    /// Set the advertised host capabilities.
+    pub fn with_capabilities(mut self, capabilities: Capabilities) -> Self {
+        self.capabilities = capabilities;
+        self
+    }
+
+    /// Check the host can be joined for `game_id` with our capabilities.
+    pub fn negotiate(
+        &self,
+        game_id: &GameId,
+        local: &Capabilities,
+    ) -> Result<NegotiatedSession, HandshakeError> {
+        if self.game_id != *game_id {
+            return Err(HandshakeError::GameMismatch {
+                expected: game_id.clone(),
+                actual: self.game_id.clone(),
+            });
+        }
+        local.negotiate(&self.capabilities)
+    }
+
     /// Serialize ticket to a string (for QR codes).
     pub fn to_string(&self) -> Result<String, serde_json::Error> {
         // Use base64-encoded JSON for compact representation
@@ -72,8 +378,8 @@ impl ConnectionTicket {
 
     /// Check if the ticket has expired.
     pub fn is_expired(&self) -> bool {
-        let now = std::time::SystemTime::now()
-            .duration_since(std::time::UNIX_EPOCH)
+        let now = web_time::SystemTime::now()
+            .duration_since(web_time::UNIX_EPOCH)
             .map(|d| d.as_secs())
             .unwrap_or(0);
         now > self.expires_at
@@ -107,9 +413,18 @@ pub enum PeerMessage {
     /// Handshake message with game info.
     Hello {
         peer_id: PeerId,
-        game_id: String,
+        game_id: GameId,
         player_name: String,
+        #[serde(default)]
+        npub: Option<Npub>,
+        #[serde(default = "Capabilities::legacy")]
+        capabilities: Capabilities,
+        /// Nonce the receiver signs to prove its own npub.
+        #[serde(default)]
+        challenge: Option<String>,
     },
+    /// Signature over the receiver's Hello challenge by the sender's npub.
+    NpubProof { signature: Vec<u8> },
     /// Request to join the game.
     JoinRequest {
         player_name: String,
@@ -118,7 +433,7 @@ pub enum PeerMessage {
     /// Response to join request.
     JoinResponse {
         accepted: bool,
-        player_id: Option<u32>,
+        player_id: Option<PlayerSlot>,
         reason: Option<String>,
     },
     /// Game event from Nostr.
@@ -130,6 +445,14 @@ pub enum PeerMessage {
     },
     /// Response with game events.
     SyncResponse { events_json: Vec<String> },
+    /// Light client request for a nonce to sign into its next state query.
+    StateNonceRequest,
+    /// Nonce issued by the host for a state query.
+    StateNonce { nonce: String },
+    /// Light client request for its player's view of the game.
+    StateQuery { query: StateQuery },
+    /// The querying player's view of the game.
+    StateResponse { response: Box<StateQueryResponse> },
     /// Request randomness (Cashu).
     RandomnessRequest {
         request_id: String,
@@ -147,9 +470,35 @@ pub enum PeerMessage {
     Pong { timestamp: u64 },
     /// Graceful disconnect.
     Goodbye { reason: String },
+    /// A message carrying a per-peer sequence number.
+    Sequenced {
+        seq: u64,
+        message: Box<PeerMessage>,
+    },
+    /// Cumulative acknowledgement of all sequenced messages up to `seq`.
+    Ack { seq: u64 },
+    /// Resume a session after reconnecting.
+    Resume { last_received_seq: u64 },
+    /// Presence announcement for a player.
+    Presence {
+        player_id: PlayerSlot,
+        update: PresenceUpdate,
+    },
 }
 
 impl PeerMessage {
+    /// Check whether the message carries game state that must be redacted
+    /// per recipient.
+    pub fn carries_state(&self) -> bool {
+        match self {
+            PeerMessage::GameEvent { .. }
+            | PeerMessage::SyncResponse { .. }
+            | PeerMessage::StateResponse { .. } => true,
+            PeerMessage::Sequenced { message, .. } => message.carries_state(),
+            _ => false,
+        }
+    }
+
     /// Serialize to bytes for network transmission.
     pub fn to_bytes(&self) -> Result<Vec<u8>, serde_json::Error> {
         serde_json::to_vec(self)
@@ -185,12 +534,18 @@ pub struct PeerInfo {
     pub state: ConnectionState,
     /// Player name (if joined).
     pub player_name: Option<String>,
+    /// Npub the peer proved it holds the key for, if any.
+    pub npub: Option<Npub>,
+    /// Npub named in the peer's Hello, not yet proven.
+    pub claimed_npub: Option<Npub>,
     /// Player ID in the game (if joined).
-    pub player_id: Option<u32>,
+    pub player_id: Option<PlayerSlot>,
     /// Last ping timestamp.
     pub last_ping: u64,
     /// Round-trip time in milliseconds.
     pub rtt_ms: Option<u32>,
+    /// Parameters agreed in the handshake (if completed).
+    pub session: Option<NegotiatedSession>,
 }
 
 impl PeerInfo {
@@ -200,9 +555,12 @@ impl PeerInfo {
             peer_id,
             state: ConnectionState::Connecting,
             player_name: None,
+            npub: None,
+            claimed_npub: None,
             player_id: None,
             last_ping: 0,
             rtt_ms: None,
+            session: None,
         }
     }
 }
@@ -231,6 +589,12 @@ pub enum PeerEvent {
         from_turn: u32,
         from_sequence: u32,
     },
+    /// A light client asked for a state query nonce (host only).
+    StateNonceRequested { peer_id: PeerId },
+    /// Received a nonce to sign into a state query.
+    StateNonceReceived { peer_id: PeerId, nonce: String },
+    /// Received a signed state query from a light client.
+    StateQueried { peer_id: PeerId, query: StateQuery },
     /// Received a randomness request (host only).
     RandomnessRequested {
         peer_id: PeerId,
@@ -238,14 +602,88 @@ pub enum PeerEvent {
         context: String,
         blinded_message: Vec<u8>,
     },
+    /// A reconnecting peer asked to resume its session.
+    ResumeRequested {
+        peer_id: PeerId,
+        last_received_seq: u64,
+    },
+    /// Messages from a peer arrived after a gap. Send the peer a
+    /// [`PeerMessage::Resume`] from `last_received_seq` to fill it.
+    ReplayNeeded {
+        peer_id: PeerId,
+        last_received_seq: u64,
+    },
+    /// Handshake with a peer succeeded.
+    HandshakeCompleted {
+        peer_id: PeerId,
+        session: NegotiatedSession,
+    },
+    /// A peer proved the npub it named in its Hello.
+    NpubVerified { peer_id: PeerId, npub: Npub },
+    /// Handshake with a peer failed; the peer has been dropped.
+    HandshakeFailed {
+        peer_id: PeerId,
+        error: HandshakeError,
+    },
+    /// A player's presence changed.
+    PresenceChanged(PresenceChange),
+}
+
+/// Sequencing state for one peer, kept across reconnects.
+#[derive(Clone, Debug, Default)]
+struct PeerSession {
+    /// Sequence number of the last message we sent (0 if none).
+    last_sent: u64,
+    /// Sent messages not yet acknowledged, oldest first.
+    replay: VecDeque<(u64, PeerMessage)>,
+    /// Sequence number of the last message delivered in order.
+    last_received: u64,
+    /// Messages received ahead of a gap, held until it is filled.
+    held: BTreeMap<u64, PeerMessage>,
+    /// Highest sequence number received, delivered or not.
+    highest_seen: u64,
+    /// When we last asked the peer to replay, while a gap is open.
+    replay_requested_at: Option<Instant>,
+    /// Last cumulative ACK we sent to the peer.
+    last_ack_sent: u64,
+}
+
+impl PeerSession {
+    /// Drop buffered messages up to and including `seq`.
+    fn acknowledge(&mut self, seq: u64) {
+        while self.replay.front().is_some_and(|(s, _)| *s <= seq) {
+            self.replay.pop_front();
+        }
+    }
+}
+
+/// Digest a peer signs to prove its npub: the verifier's challenge, bound
+/// to the game and the prover's node ID.
+fn npub_proof_digest(game_id: &GameId, challenge: &str, prover: &str) -> [u8; 32] {
+    let mut hasher = Sha256::new();
+    hasher.update(b"nostr-nations npub proof");
+    hasher.update([0]);
+    hasher.update(game_id.as_str().as_bytes());
+    hasher.update([0]);
+    hasher.update(challenge.as_bytes());
+    hasher.update([0]);
+    hasher.update(prover.as_bytes());
+    hasher.finalize().into()
+}
+
+/// A fresh random nonce, hex encoded.
+fn random_nonce() -> String {
+    let mut bytes = [0u8; 16];
+    OsRng.fill_bytes(&mut bytes);
+    bytes.iter().map(|b| format!("{:02x}", b)).collect()
 }
 
 /// Manages peer connections for a game session.
 pub struct PeerManager {
     /// Our node ID.
-    node_id: String,
+    node_id: PeerId,
     /// Game ID we're managing connections for.
-    game_id: String,
+    game_id: GameId,
     /// Whether we're the host.
     is_host: bool,
     /// Connected peers.
@@ -254,11 +692,35 @@ pub struct PeerManager {
     event_tx: mpsc::Sender<PeerEvent>,
     /// Channel for receiving events.
     event_rx: mpsc::Receiver<PeerEvent>,
+    /// Per-peer sequencing state for session resume.
+    sessions: Arc<RwLock<HashMap<PeerId, PeerSession>>>,
+    /// Maximum unacknowledged messages kept per peer.
+    replay_capacity: usize,
+    /// How long to wait for a requested replay before asking again.
+    replay_retry: Duration,
+    /// Capabilities we advertise in the handshake.
+    capabilities: Capabilities,
+    /// Latest presence of each player.
+    presence: Arc<RwLock<PresenceMap>>,
+    /// Our npub, announced in the handshake.
+    npub: Option<Npub>,
+    /// Key our npub is proven with.
+    signing_key: Option<SigningKey>,
+    /// Nonce peers sign to prove their npub to us.
+    challenge: String,
+    /// Challenges from peers' Hellos we still owe a proof for.
+    owed_proofs: Arc<RwLock<HashMap<PeerId, String>>>,
+    /// Npubs whose handshakes are refused.
+    blocked: Arc<RwLock<BlockList>>,
+    /// Per-recipient redaction of outgoing messages, on a full client.
+    redaction: Arc<RwLock<Option<RedactionGate>>>,
+    /// Whether game state may be sent without a redaction gate.
+    unredacted: bool,
 }
 
 impl PeerManager {
     /// Create a new peer manager.
-    pub fn new(node_id: String, game_id: String, is_host: bool) -> Self {
+    pub fn new(node_id: PeerId, game_id: GameId, is_host: bool) -> Self {
         let (event_tx, event_rx) = mpsc::channel(100);
 
         Self {
@@ -268,16 +730,127 @@ impl PeerManager {
             peers: Arc::new(RwLock::new(HashMap::new())),
             event_tx,
             event_rx,
+            sessions: Arc::new(RwLock::new(HashMap::new())),
+            replay_capacity: DEFAULT_REPLAY_CAPACITY,
+            replay_retry: DEFAULT_REPLAY_RETRY,
+            capabilities: Capabilities::default(),
+            presence: Arc::new(RwLock::new(PresenceMap::default())),
+            npub: None,
+            signing_key: None,
+            challenge: random_nonce(),
+            owed_proofs: Arc::new(RwLock::new(HashMap::new())),
+            blocked: Arc::new(RwLock::new(BlockList::new())),
+            redaction: Arc::new(RwLock::new(None)),
+            unredacted: false,
         }
     }
 
+    /// Create a peer manager with a custom replay buffer size.
+    pub fn with_replay_capacity(
+        node_id: PeerId,
+        game_id: GameId,
+        is_host: bool,
+        replay_capacity: usize,
+    ) -> Self {
+        Self {
+            replay_capacity,
+            ..Self::new(node_id, game_id, is_host)
+        }
+    }
+
+    /// Set the capabilities advertised in the handshake.
+    pub fn with_capabilities(mut self, capabilities: Capabilities) -> Self {
+        self.capabilities = capabilities;
+        self
+    }
+
+    /// Set how long to wait for a requested replay before asking again.
+    pub fn with_replay_retry(mut self, replay_retry: Duration) -> Self {
+        self.replay_retry = replay_retry;
+        self
+    }
+
+    /// Set the npub announced in the handshake.
+    ///
+    /// Peers only trust it if it is proven; use
+    /// [`PeerManager::with_signing_key`] to be able to.
+    pub fn with_npub(mut self, npub: Npub) -> Self {
+        self.npub = Some(npub);
+        self
+    }
+
+    /// Announce the npub of `key` in the handshake and prove it to peers.
+    pub fn with_signing_key(mut self, key: SigningKey) -> Self {
+        self.npub = Some(key.npub());
+        self.signing_key = Some(key);
+        self
+    }
+
+    /// Send game state to peers without a redaction gate.
+    ///
+    /// Only for peers that may see the whole game, such as test clients
+    /// and hot-seat games; anything else should set a gate with
+    /// [`PeerManager::update_redaction`].
+    pub fn without_redaction(mut self) -> Self {
+        self.unredacted = true;
+        self
+    }
+
+    /// Replace the block list. Connected peers aren't dropped; their next
+    /// handshake is checked against the new list.
+    pub async fn set_block_list(&self, blocked: BlockList) {
+        *self.blocked.write().await = blocked;
+    }
+
+    /// Redact everything sent to peers for the players of `game`.
+    ///
+    /// Call again whenever the authoritative state changes so each peer's
+    /// visibility stays current.
+    pub async fn update_redaction(&self, game: &GameState) {
+        let mut redaction = self.redaction.write().await;
+        match redaction.as_mut() {
+            Some(gate) => gate.update(game),
+            None => *redaction = Some(RedactionGate::from_game_state(game)),
+        }
+    }
+
+    /// Get the capabilities advertised in the handshake.
+    pub fn capabilities(&self) -> &Capabilities {
+        &self.capabilities
+    }
+
+    /// Build the handshake message to open a connection with.
+    pub fn hello(&self, player_name: String) -> PeerMessage {
+        PeerMessage::Hello {
+            peer_id: self.node_id.clone(),
+            game_id: self.game_id.clone(),
+            player_name,
+            npub: self.npub.clone(),
+            capabilities: self.capabilities.clone(),
+            challenge: Some(self.challenge.clone()),
+        }
+    }
+
+    /// Take the proof of our npub owed to a peer whose Hello challenged us.
+    ///
+    /// Send it after the peer's Hello has been handled. Returns `None` if
+    /// nothing is owed or we have no signing key.
+    pub async fn pending_npub_proof(&self, peer_id: &str) -> Option<PeerMessage> {
+        let key = self.signing_key.as_ref()?;
+        let challenge = self.owed_proofs.write().await.remove(peer_id)?;
+        let digest = npub_proof_digest(&self.game_id, &challenge, self.node_id.as_str());
+        Some(PeerMessage::NpubProof {
+            signature: key.sign(&digest),
+        })
+    }
+
     /// Get our node ID.
-    pub fn node_id(&self) -> &str {
+    pub fn node_id(&self) -> &PeerId {
         &self.node_id
     }
 
     /// Get the game ID.
-    pub fn game_id(&self) -> &str {
+    pub fn game_id(&self) -> &GameId {
         &self.game_id
     }
 
@@ -288,7 +861,13 @@ impl PeerManager {
 
     /// Create a connection ticket for this game.
     pub fn create_ticket(&self, addresses: Vec<String>, ttl_secs: u64) -> ConnectionTicket {
-        ConnectionTicket::new(self.node_id.clone(), addresses, self.game_id.clone(), ttl_secs)
+        ConnectionTicket::new(
+            self.node_id.clone(),
+            addresses,
+            self.game_id.clone(),
+            ttl_secs,
+        )
+        .with_capabilities(self.capabilities.clone())
     }
 
     /// Get the number of connected peers.
@@ -315,21 +894,32 @@ impl PeerManager {
     }
 
     /// Remove a peer connection.
+    #[tracing::instrument(name = "peer.remove_peer", skip_all, fields(game_id = %self.game_id, peer_id = %peer_id))]
     pub async fn remove_peer(&self, peer_id: &str, reason: String) {
         let mut peers = self.peers.write().await;
-        peers.remove(peer_id);
+        let removed = peers.remove(peer_id);
+
+        // The player behind a dropped peer is no longer at the keyboard
+        let player_id = removed
+            .and_then(|peer| peer.player_id);
+        if let Some(player_id) = player_id {
+            let change = self.presence.write().await.disconnect(player_id);
+            if let Some(change) = change {
+                let _ = self.event_tx.send(PeerEvent::PresenceChanged(change)).await;
+            }
+        }
 
         let _ = self
             .event_tx
             .send(PeerEvent::PeerDisconnected {
-                peer_id: peer_id.to_string(),
+                peer_id: PeerId::from(peer_id),
                 reason,
             })
             .await;
     }
 
     /// Update peer state after successful join.
-    pub async fn peer_joined(&self, peer_id: &str, player_name: String, player_id: u32) {
+    pub async fn peer_joined(&self, peer_id: &str, player_name: String, player_id: PlayerSlot) {
         let mut peers = self.peers.write().await;
         if let Some(peer) = peers.get_mut(peer_id) {
             peer.state = ConnectionState::Joined;
@@ -338,9 +928,271 @@ impl PeerManager {
         }
     }
 
+    /// Redact an outgoing message for a peer and assign it the next
+    /// sequence number.
+    ///
+    /// With a redaction gate set, the message is first redacted for the
+    /// peer's proven npub; `None` means nothing in it may be sent to them,
+    /// and no sequence number is used. A peer without a proven npub gets an
+    /// error rather than unredacted data, and so does a message carrying
+    /// game state when no gate is set.
+    ///
+    /// The message is kept in the replay buffer until acknowledged. When the
+    /// buffer is full, the oldest message is dropped and a resume past it
+    /// will require a full sync.
+    #[tracing::instrument(name = "peer.sequence_message", skip_all, fields(game_id = %self.game_id, peer_id = %peer_id))]
+    pub async fn sequence_message(
+        &self,
+        peer_id: &str,
+        message: PeerMessage,
+    ) -> Result<Option<PeerMessage>, RedactionError> {
+        let message = match self.redaction.read().await.as_ref() {
+            Some(gate) => {
+                let npub = self
+                    .peers
+                    .read()
+                    .await
+                    .get(peer_id)
+                    .and_then(|p| p.npub.clone());
+                let recipient = npub.as_ref().map_or(peer_id, |npub| npub.as_str());
+                match gate.redact_message(recipient, &message)? {
+                    Some(message) => message,
+                    None => return Ok(None),
+                }
+            }
+            None if message.carries_state() && !self.unredacted => {
+                return Err(RedactionError::NoGate);
+            }
+            None => message,
+        };
+
+        let mut sessions = self.sessions.write().await;
+        let session = sessions.entry(PeerId::from(peer_id)).or_default();
+
+        session.last_sent += 1;
+        let seq = session.last_sent;
+        session.replay.push_back((seq, message.clone()));
+        while session.replay.len() > self.replay_capacity {
+            session.replay.pop_front();
+        }
+
+        Ok(Some(PeerMessage::Sequenced {
+            seq,
+            message: Box::new(message),
+        }))
+    }
+
+    /// Get the sequence number of the last message received in order from
+    /// a peer.
+    ///
+    /// Send this in a [`PeerMessage::Resume`] after reconnecting.
+    pub async fn last_received_seq(&self, peer_id: &str) -> u64 {
+        self.sessions
+            .read()
+            .await
+            .get(peer_id)
+            .map(|s| s.last_received)
+            .unwrap_or(0)
+    }
+
+    /// Get the number of unacknowledged messages buffered for a peer.
+    pub async fn unacked_count(&self, peer_id: &str) -> usize {
+        self.sessions
+            .read()
+            .await
+            .get(peer_id)
+            .map(|s| s.replay.len())
+            .unwrap_or(0)
+    }
+
+    /// Take a cumulative ACK for a peer if new messages arrived since the
+    /// last one.
+    pub async fn pending_ack(&self, peer_id: &str) -> Option<PeerMessage> {
+        let mut sessions = self.sessions.write().await;
+        let session = sessions.get_mut(peer_id)?;
+        if session.last_received <= session.last_ack_sent {
+            return None;
+        }
+        session.last_ack_sent = session.last_received;
+        Some(PeerMessage::Ack {
+            seq: session.last_received,
+        })
+    }
+
+    /// Take a repeated [`PeerMessage::Resume`] for a peer if a gap has been
+    /// open longer than the replay retry without being filled.
+    ///
+    /// Poll this alongside [`PeerManager::pending_ack`]; it covers a lost
+    /// Resume or replay and messages dropped because too many were held.
+    pub async fn pending_resume(&self, peer_id: &str) -> Option<PeerMessage> {
+        let mut sessions = self.sessions.write().await;
+        let session = sessions.get_mut(peer_id)?;
+        let requested_at = session.replay_requested_at?;
+        if requested_at.elapsed() < self.replay_retry {
+            return None;
+        }
+        session.replay_requested_at = Some(Instant::now());
+        Some(PeerMessage::Resume {
+            last_received_seq: session.last_received,
+        })
+    }
+
+    /// Get the messages to replay for a peer resuming after
+    /// `last_received_seq`.
+    ///
+    /// Returns `None` if the replay buffer no longer covers the gap, in
+    /// which case the peer needs a full sync.
+    #[tracing::instrument(name = "peer.replay_since", skip_all, fields(game_id = %self.game_id, peer_id = %peer_id))]
+    pub async fn replay_since(
+        &self,
+        peer_id: &str,
+        last_received_seq: u64,
+    ) -> Option<Vec<PeerMessage>> {
+        let mut sessions = self.sessions.write().await;
+        let session = sessions.entry(PeerId::from(peer_id)).or_default();
+        session.acknowledge(last_received_seq);
+
+        if last_received_seq > session.last_sent {
+            return None;
+        }
+        let first_needed = last_received_seq + 1;
+        match session.replay.front() {
+            Some((first, _)) if *first > first_needed => return None,
+            None if first_needed <= session.last_sent => return None,
+            _ => {}
+        }
+
+        Some(
+            session
+                .replay
+                .iter()
+                .map(|(seq, message)| PeerMessage::Sequenced {
+                    seq: *seq,
+                    message: Box::new(message.clone()),
+                })
+                .collect(),
+        )
+    }
+
+    /// Get every message a peer hasn't acknowledged yet, to resend when
+    /// its ACK is overdue.
+    pub async fn unacked(&self, peer_id: &str) -> Vec<PeerMessage> {
+        self.sessions
+            .read()
+            .await
+            .get(peer_id)
+            .map(|session| {
+                session
+                    .replay
+                    .iter()
+                    .map(|(seq, message)| PeerMessage::Sequenced {
+                        seq: *seq,
+                        message: Box::new(message.clone()),
+                    })
+                    .collect()
+            })
+            .unwrap_or_default()
+    }
+
+    /// Forget a peer's session so the next connection starts fresh.
+    pub async fn clear_session(&self, peer_id: &str) {
+        self.sessions.write().await.remove(peer_id);
+    }
+
+    /// Put a message from a peer in sequence order.
+    ///
+    /// Returns the messages now ready to handle, oldest first. A sequenced
+    /// message is only delivered once every message before it has been,
+    /// together with any held messages it unblocks. Messages after a gap
+    /// are held, and opening a gap emits [`PeerEvent::ReplayNeeded`]; see
+    /// [`PeerManager::pending_resume`] for when that request goes missing.
+    /// Duplicates are dropped and re-arm [`PeerManager::pending_ack`],
+    /// since the peer resending them means it missed our ACK. Unsequenced
+    /// messages are returned as they are.
+    #[tracing::instrument(name = "peer.deliver", skip_all, fields(game_id = %self.game_id, peer_id = %peer_id))]
+    pub async fn deliver(&self, peer_id: &str, message: PeerMessage) -> Vec<PeerMessage> {
+        let PeerMessage::Sequenced { seq, message } = message else {
+            return vec![message];
+        };
+
+        let gap_from = {
+            let mut sessions = self.sessions.write().await;
+            let session = sessions.entry(PeerId::from(peer_id)).or_default();
+            if seq <= session.last_received || session.held.contains_key(&seq) {
+                // Duplicate from a replay, already handled or held
+                session.last_ack_sent = 0;
+                return Vec::new();
+            }
+            session.highest_seen = session.highest_seen.max(seq);
+            if seq > session.last_received + 1 {
+                if session.held.len() < self.replay_capacity {
+                    session.held.insert(seq, *message);
+                }
+                let opens_gap = session.replay_requested_at.is_none();
+                if opens_gap {
+                    session.replay_requested_at = Some(Instant::now());
+                }
+                opens_gap.then_some(session.last_received)
+            } else {
+                let mut ready = vec![*message];
+                session.last_received = seq;
+                while let Some(next) = session.held.remove(&(session.last_received + 1)) {
+                    ready.push(next);
+                    session.last_received += 1;
+                }
+                // Messages dropped while held keep the gap open
+                if session.last_received >= session.highest_seen {
+                    session.replay_requested_at = None;
+                }
+                return ready;
+            }
+        };
+
+        if let Some(last_received_seq) = gap_from {
+            let _ = self
+                .event_tx
+                .send(PeerEvent::ReplayNeeded {
+                    peer_id: PeerId::from(peer_id),
+                    last_received_seq,
+                })
+                .await;
+        }
+        Vec::new()
+    }
+
     /// Handle an incoming message from a peer.
+    ///
+    /// Sequenced messages go through [`PeerManager::deliver`] first.
+    #[tracing::instrument(name = "peer.handle_message", skip_all, fields(game_id = %self.game_id, peer_id = %peer_id))]
     pub async fn handle_message(&self, peer_id: &str, message: PeerMessage) {
+        for message in self.deliver(peer_id, message).await {
+            self.dispatch(peer_id, message).await;
+        }
+    }
+
+    /// Handle a message that is ready for delivery.
+    async fn dispatch(&self, peer_id: &str, message: PeerMessage) {
         match message {
+            PeerMessage::Hello {
+                game_id,
+                npub,
+                capabilities,
+                challenge,
+                ..
+            } => {
+                self.handshake(peer_id, game_id, npub, &capabilities).await;
+                if let Some(challenge) = challenge {
+                    if self.signing_key.is_some() && self.peers.read().await.contains_key(peer_id) {
+                        self.owed_proofs
+                            .write()
+                            .await
+                            .insert(PeerId::from(peer_id), challenge);
+                    }
+                }
+            }
+            PeerMessage::NpubProof { signature } => {
+                self.verify_npub_proof(peer_id, &signature).await;
+            }
             PeerMessage::JoinRequest {
                 player_name,
                 civilization_id,
@@ -348,7 +1200,7 @@ impl PeerManager {
                 let _ = self
                     .event_tx
                     .send(PeerEvent::JoinRequest {
-                        peer_id: peer_id.to_string(),
+                        peer_id: PeerId::from(peer_id),
                         player_name,
                         civilization_id,
                     })
@@ -358,7 +1210,7 @@ impl PeerManager {
                 let _ = self
                     .event_tx
                     .send(PeerEvent::GameEventReceived {
-                        peer_id: peer_id.to_string(),
+                        peer_id: PeerId::from(peer_id),
                         event_json,
                     })
                     .await;
@@ -370,12 +1222,38 @@ impl PeerManager {
                 let _ = self
                     .event_tx
                     .send(PeerEvent::SyncRequested {
-                        peer_id: peer_id.to_string(),
+                        peer_id: PeerId::from(peer_id),
                         from_turn,
                         from_sequence,
                     })
                     .await;
             }
+            PeerMessage::StateNonceRequest => {
+                let _ = self
+                    .event_tx
+                    .send(PeerEvent::StateNonceRequested {
+                        peer_id: PeerId::from(peer_id),
+                    })
+                    .await;
+            }
+            PeerMessage::StateNonce { nonce } => {
+                let _ = self
+                    .event_tx
+                    .send(PeerEvent::StateNonceReceived {
+                        peer_id: PeerId::from(peer_id),
+                        nonce,
+                    })
+                    .await;
+            }
+            PeerMessage::StateQuery { query } => {
+                let _ = self
+                    .event_tx
+                    .send(PeerEvent::StateQueried {
+                        peer_id: PeerId::from(peer_id),
+                        query,
+                    })
+                    .await;
+            }
             PeerMessage::RandomnessRequest {
                 request_id,
                 context,
@@ -384,7 +1262,7 @@ impl PeerManager {
                 let _ = self
                     .event_tx
                     .send(PeerEvent::RandomnessRequested {
-                        peer_id: peer_id.to_string(),
+                        peer_id: PeerId::from(peer_id),
                         request_id,
                         context,
                         blinded_message,
@@ -400,8 +1278,8 @@ impl PeerManager {
             }
             PeerMessage::Pong { timestamp } => {
                 // Calculate RTT
-                let now = std::time::SystemTime::now()
-                    .duration_since(std::time::UNIX_EPOCH)
+                let now = web_time::SystemTime::now()
+                    .duration_since(web_time::UNIX_EPOCH)
                     .map(|d| d.as_millis() as u64)
                     .unwrap_or(0);
                 let rtt = (now.saturating_sub(timestamp)) as u32;
@@ -414,10 +1292,164 @@ impl PeerManager {
             PeerMessage::Goodbye { reason } => {
                 self.remove_peer(peer_id, reason).await;
             }
+            PeerMessage::Ack { seq } => {
+                if let Some(session) = self.sessions.write().await.get_mut(peer_id) {
+                    session.acknowledge(seq);
+                }
+            }
+            PeerMessage::Presence { player_id, update } => {
+                let now = web_time::SystemTime::now()
+                    .duration_since(web_time::UNIX_EPOCH)
+                    .map(|d| d.as_millis() as u64)
+                    .unwrap_or(0);
+                self.update_presence(player_id, &update, now).await;
+            }
+            PeerMessage::Resume { last_received_seq } => {
+                let _ = self
+                    .event_tx
+                    .send(PeerEvent::ResumeRequested {
+                        peer_id: PeerId::from(peer_id),
+                        last_received_seq,
+                    })
+                    .await;
+            }
             _ => {} // Other messages handled elsewhere
         }
     }
 
+    /// Negotiate with a peer that sent [`PeerMessage::Hello`].
+    ///
+    /// On failure the peer is told why and removed.
+    async fn handshake(
+        &self,
+        peer_id: &str,
+        game_id: GameId,
+        npub: Option<Npub>,
+        remote: &Capabilities,
+    ) {
+        let blocked = {
+            let list = self.blocked.read().await;
+            npub.as_ref().filter(|npub| list.is_blocked(npub)).cloned()
+        };
+        let result = if game_id != self.game_id {
+            Err(HandshakeError::GameMismatch {
+                expected: self.game_id.clone(),
+                actual: game_id,
+            })
+        } else if let Some(npub) = blocked {
+            Err(HandshakeError::Blocked(npub))
+        } else {
+            self.capabilities.negotiate(remote)
+        };
+
+        match result {
+            Ok(session) => {
+                if let Some(peer) = self.peers.write().await.get_mut(peer_id) {
+                    peer.session = Some(session);
+                    peer.claimed_npub = npub;
+                    if peer.state == ConnectionState::Connecting {
+                        peer.state = ConnectionState::Connected;
+                    }
+                }
+                let _ = self
+                    .event_tx
+                    .send(PeerEvent::HandshakeCompleted {
+                        peer_id: PeerId::from(peer_id),
+                        session,
+                    })
+                    .await;
+            }
+            Err(error) => {
+                tracing::warn!(%error, "peer handshake failed");
+                let reason = error.to_string();
+                let _ = self
+                    .event_tx
+                    .send(PeerEvent::HandshakeFailed {
+                        peer_id: PeerId::from(peer_id),
+                        error,
+                    })
+                    .await;
+                self.remove_peer(peer_id, reason).await;
+            }
+        }
+    }
+
+    /// Trust a peer's claimed npub if `signature` proves it, or drop the
+    /// peer.
+    async fn verify_npub_proof(&self, peer_id: &str, signature: &[u8]) {
+        let digest = npub_proof_digest(&self.game_id, &self.challenge, peer_id);
+        let verified = {
+            let mut peers = self.peers.write().await;
+            peers.get_mut(peer_id).and_then(|peer| {
+                let npub = peer.claimed_npub.clone()?;
+                verify_signature(npub.as_str(), &digest, signature).then(|| {
+                    peer.npub = Some(npub.clone());
+                    npub
+                })
+            })
+        };
+
+        match verified {
+            Some(npub) => {
+                let _ = self
+                    .event_tx
+                    .send(PeerEvent::NpubVerified {
+                        peer_id: PeerId::from(peer_id),
+                        npub,
+                    })
+                    .await;
+            }
+            None => {
+                let error = HandshakeError::BadNpubProof;
+                tracing::warn!(%error, "peer handshake failed");
+                let reason = error.to_string();
+                let _ = self
+                    .event_tx
+                    .send(PeerEvent::HandshakeFailed {
+                        peer_id: PeerId::from(peer_id),
+                        error,
+                    })
+                    .await;
+                self.remove_peer(peer_id, reason).await;
+            }
+        }
+    }
+
+    /// Record a player's presence, emitting [`PeerEvent::PresenceChanged`]
+    /// if it changed.
+    pub async fn update_presence(
+        &self,
+        player_id: PlayerSlot,
+        update: &PresenceUpdate,
+        now_ms: u64,
+    ) {
+        let change = self
+            .presence
+            .write()
+            .await
+            .update(player_id, update, now_ms);
+        if let Some(change) = change {
+            let _ = self.event_tx.send(PeerEvent::PresenceChanged(change)).await;
+        }
+    }
+
+    /// Mark players who stopped announcing presence as disconnected.
+    pub async fn expire_presence(&self, now_ms: u64) -> Vec<PresenceChange> {
+        let changes = self.presence.write().await.expire(now_ms);
+        for change in &changes {
+            let _ = self
+                .event_tx
+                .send(PeerEvent::PresenceChanged(change.clone()))
+                .await;
+        }
+        changes
+    }
+
+    /// Get the latest presence of every player.
+    pub async fn presence(&self) -> PresenceMap {
+        self.presence.read().await.clone()
+    }
+
     /// Receive the next peer event.
     pub async fn recv_event(&mut self) -> Option<PeerEvent> {
         self.event_rx.recv().await
@@ -524,9 +1556,9 @@ mod tests {
     #[test]
     fn test_connection_ticket_roundtrip() {
         let ticket = ConnectionTicket::new(
-            "node123".to_string(),
+            PeerId::from("node123"),
             vec!["192.168.1.1:4433".to_string()],
-            "game456".to_string(),
+            GameId::new("game456"),
             3600,
         );
 
@@ -546,9 +1578,9 @@ mod tests {
             "[::1]:4433".to_string(),
         ];
         let ticket = ConnectionTicket::new(
-            "node_multi".to_string(),
+            PeerId::from("node_multi"),
             addresses.clone(),
-            "game_multi".to_string(),
+            GameId::new("game_multi"),
             7200,
         );
 
@@ -562,9 +1594,9 @@ mod tests {
     #[test]
     fn test_connection_ticket_empty_addresses() {
         let ticket = ConnectionTicket::new(
-            "node_empty".to_string(),
+            PeerId::from("node_empty"),
             vec![],
-            "game_empty".to_string(),
+            GameId::new("game_empty"),
             3600,
         );
 
@@ -577,9 +1609,9 @@ mod tests {
     #[test]
     fn test_connection_ticket_alpn_protocol() {
         let ticket = ConnectionTicket::new(
-            "node1".to_string(),
+            PeerId::from("node1"),
             vec![],
-            "game1".to_string(),
+            GameId::new("game1"),
             3600,
         );
 
@@ -590,11 +1622,12 @@ mod tests {
     fn test_ticket_expiration() {
         // Create a ticket that is already expired (expires_at = 0 is Unix epoch, always in the past)
         let ticket = ConnectionTicket {
-            node_id: "node123".to_string(),
+            node_id: PeerId::from("node123"),
             addresses: vec![],
             alpn: "nostr-nations/1".to_string(),
-            game_id: "game456".to_string(),
+            game_id: GameId::new("game456"),
             expires_at: 0, // Unix epoch - always in the past
+            capabilities: Capabilities::default(),
         };
 
         assert!(ticket.is_expired());
@@ -603,9 +1636,9 @@ mod tests {
     #[test]
     fn test_ticket_not_expired() {
         let ticket = ConnectionTicket::new(
-            "node123".to_string(),
+            PeerId::from("node123"),
             vec![],
-            "game456".to_string(),
+            GameId::new("game456"),
             3600, // 1 hour TTL
         );
 
@@ -652,9 +1685,12 @@ mod tests {
     #[test]
     fn test_peer_message_roundtrip() {
         let msg = PeerMessage::Hello {
-            peer_id: "peer123".to_string(),
-            game_id: "game456".to_string(),
+            peer_id: PeerId::from("peer123"),
+            game_id: GameId::new("game456"),
             player_name: "Alice".to_string(),
+            npub: None,
+            capabilities: Capabilities::default(),
+            challenge: None,
         };
 
         let bytes = msg.to_bytes().unwrap();
@@ -665,10 +1701,13 @@ mod tests {
                 peer_id,
                 game_id,
                 player_name,
+                capabilities,
+                ..
             } => {
                 assert_eq!(peer_id, "peer123");
                 assert_eq!(game_id, "game456");
                 assert_eq!(player_name, "Alice");
+                assert_eq!(capabilities, Capabilities::default());
             }
             _ => panic!("Wrong message type"),
         }
@@ -697,7 +1736,7 @@ mod tests {
     fn test_peer_message_join_response_accepted() {
         let msg = PeerMessage::JoinResponse {
             accepted: true,
-            player_id: Some(42),
+            player_id: Some(PlayerSlot(42)),
             reason: None,
         };
 
@@ -707,7 +1746,7 @@ mod tests {
         match decoded {
             PeerMessage::JoinResponse { accepted, player_id, reason } => {
                 assert!(accepted);
-                assert_eq!(player_id, Some(42));
+                assert_eq!(player_id, Some(PlayerSlot(42)));
                 assert!(reason.is_none());
             }
             _ => panic!("Wrong message type"),
@@ -902,7 +1941,7 @@ mod tests {
 
     #[test]
     fn test_peer_info_new() {
-        let info = PeerInfo::new("peer123".to_string());
+        let info = PeerInfo::new(PeerId::from("peer123"));
 
         assert_eq!(info.peer_id, "peer123");
         assert_eq!(info.state, ConnectionState::Connecting);
@@ -914,9 +1953,9 @@ mod tests {
 
     #[test]
     fn test_peer_info_clone() {
-        let mut info = PeerInfo::new("peer1".to_string());
+        let mut info = PeerInfo::new(PeerId::from("peer1"));
         info.player_name = Some("Alice".to_string());
-        info.player_id = Some(1);
+        info.player_id = Some(PlayerSlot(1));
         info.state = ConnectionState::Joined;
 
         let cloned = info.clone();
@@ -984,12 +2023,12 @@ mod tests {
 
     #[tokio::test]
     async fn test_peer_manager() {
-        let manager = PeerManager::new("node1".to_string(), "game1".to_string(), true);
+        let manager = PeerManager::new(PeerId::from("node1"), GameId::new("game1"), true);
 
         assert_eq!(manager.peer_count().await, 0);
         assert!(manager.is_host());
 
-        manager.add_peer("peer1".to_string()).await;
+        manager.add_peer(PeerId::from("peer1")).await;
         assert_eq!(manager.peer_count().await, 1);
 
         let peer = manager.get_peer("peer1").await.unwrap();
@@ -1001,7 +2040,7 @@ mod tests {
 
     #[tokio::test]
     async fn test_peer_manager_node_id_and_game_id() {
-        let manager = PeerManager::new("my_node".to_string(), "my_game".to_string(), false);
+        let manager = PeerManager::new(PeerId::from("my_node"), GameId::new("my_game"), false);
 
         assert_eq!(manager.node_id(), "my_node");
         assert_eq!(manager.game_id(), "my_game");
@@ -1010,7 +2049,7 @@ mod tests {
 
     #[tokio::test]
     async fn test_peer_manager_create_ticket() {
-        let manager = PeerManager::new("node123".to_string(), "game456".to_string(), true);
+        let manager = PeerManager::new(PeerId::from("node123"), GameId::new("game456"), true);
 
         let addresses = vec!["192.168.1.1:4433".to_string()];
         let ticket = manager.create_ticket(addresses.clone(), 3600);
@@ -1023,11 +2062,11 @@ mod tests {
 
     #[tokio::test]
     async fn test_peer_manager_multiple_peers() {
-        let manager = PeerManager::new("node1".to_string(), "game1".to_string(), true);
+        let manager = PeerManager::new(PeerId::from("node1"), GameId::new("game1"), true);
 
-        manager.add_peer("peer1".to_string()).await;
-        manager.add_peer("peer2".to_string()).await;
-        manager.add_peer("peer3".to_string()).await;
+        manager.add_peer(PeerId::from("peer1")).await;
+        manager.add_peer(PeerId::from("peer2")).await;
+        manager.add_peer(PeerId::from("peer3")).await;
 
         assert_eq!(manager.peer_count().await, 3);
 
@@ -1043,7 +2082,7 @@ mod tests {
 
     #[tokio::test]
     async fn test_peer_manager_get_nonexistent_peer() {
-        let manager = PeerManager::new("node1".to_string(), "game1".to_string(), true);
+        let manager = PeerManager::new(PeerId::from("node1"), GameId::new("game1"), true);
 
         let peer = manager.get_peer("nonexistent").await;
         assert!(peer.is_none());
@@ -1051,32 +2090,32 @@ mod tests {
 
     #[tokio::test]
     async fn test_peer_manager_peer_joined() {
-        let manager = PeerManager::new("node1".to_string(), "game1".to_string(), true);
+        let manager = PeerManager::new(PeerId::from("node1"), GameId::new("game1"), true);
 
-        manager.add_peer("peer1".to_string()).await;
-        manager.peer_joined("peer1", "Alice".to_string(), 42).await;
+        manager.add_peer(PeerId::from("peer1")).await;
+        manager.peer_joined("peer1", "Alice".to_string(), PlayerSlot(42)).await;
 
         let peer = manager.get_peer("peer1").await.unwrap();
         assert_eq!(peer.state, ConnectionState::Joined);
         assert_eq!(peer.player_name, Some("Alice".to_string()));
-        assert_eq!(peer.player_id, Some(42));
+        assert_eq!(peer.player_id, Some(PlayerSlot(42)));
     }
 
     #[tokio::test]
     async fn test_peer_manager_peer_joined_nonexistent() {
-        let manager = PeerManager::new("node1".to_string(), "game1".to_string(), true);
+        let manager = PeerManager::new(PeerId::from("node1"), GameId::new("game1"), true);
 
         // Should not panic when peer doesn't exist
-        manager.peer_joined("nonexistent", "Alice".to_string(), 42).await;
+        manager.peer_joined("nonexistent", "Alice".to_string(), PlayerSlot(42)).await;
 
         assert!(manager.get_peer("nonexistent").await.is_none());
     }
 
     #[tokio::test]
     async fn test_peer_manager_handle_ping() {
-        let manager = PeerManager::new("node1".to_string(), "game1".to_string(), true);
+        let manager = PeerManager::new(PeerId::from("node1"), GameId::new("game1"), true);
 
-        manager.add_peer("peer1".to_string()).await;
+        manager.add_peer(PeerId::from("peer1")).await;
 
         let ping = PeerMessage::Ping { timestamp: 1234567890 };
         manager.handle_message("peer1", ping).await;
@@ -1087,9 +2126,9 @@ mod tests {
 
     #[tokio::test]
     async fn test_peer_manager_handle_goodbye() {
-        let manager = PeerManager::new("node1".to_string(), "game1".to_string(), true);
+        let manager = PeerManager::new(PeerId::from("node1"), GameId::new("game1"), true);
 
-        manager.add_peer("peer1".to_string()).await;
+        manager.add_peer(PeerId::from("peer1")).await;
         assert_eq!(manager.peer_count().await, 1);
 
         let goodbye = PeerMessage::Goodbye { reason: "User disconnected".to_string() };
@@ -1100,12 +2139,739 @@ mod tests {
 
     #[tokio::test]
     async fn test_peer_manager_remove_peer_twice() {
-        let manager = PeerManager::new("node1".to_string(), "game1".to_string(), true);
+        let manager = PeerManager::new(PeerId::from("node1"), GameId::new("game1"), true);
 
-        manager.add_peer("peer1".to_string()).await;
+        manager.add_peer(PeerId::from("peer1")).await;
         manager.remove_peer("peer1", "first removal".to_string()).await;
         manager.remove_peer("peer1", "second removal".to_string()).await;
 
         assert_eq!(manager.peer_count().await, 0);
     }
+
+    // ==================== Session Resume Tests ====================
+
+    fn game_event(n: u32) -> PeerMessage {
+        PeerMessage::GameEvent {
+            event_json: format!("{{\"n\":{}}}", n),
+        }
+    }
+
+    async fn sequenced(manager: &PeerManager, peer_id: &str, message: PeerMessage) -> PeerMessage {
+        manager
+            .sequence_message(peer_id, message)
+            .await
+            .unwrap()
+            .unwrap()
+    }
+
+    #[tokio::test]
+    async fn test_sequence_message_numbers_per_peer() {
+        let manager =
+            PeerManager::new(PeerId::from("node1"), GameId::new("game1"), true).without_redaction();
+
+        let first = sequenced(&manager, "peer1", game_event(1)).await;
+        let second = sequenced(&manager, "peer1", game_event(2)).await;
+        let other = sequenced(&manager, "peer2", game_event(1)).await;
+
+        assert!(matches!(first, PeerMessage::Sequenced { seq: 1, .. }));
+        assert!(matches!(second, PeerMessage::Sequenced { seq: 2, .. }));
+        assert!(matches!(other, PeerMessage::Sequenced { seq: 1, .. }));
+        assert_eq!(manager.unacked_count("peer1").await, 2);
+    }
+
+    #[tokio::test]
+    async fn test_ack_trims_replay_buffer() {
+        let manager =
+            PeerManager::new(PeerId::from("node1"), GameId::new("game1"), true).without_redaction();
+        for n in 0..5 {
+            sequenced(&manager, "peer1", game_event(n)).await;
+        }
+
+        manager.handle_message("peer1", PeerMessage::Ack { seq: 3 }).await;
+        assert_eq!(manager.unacked_count("peer1").await, 2);
+    }
+
+    #[tokio::test]
+    async fn test_receive_sequenced_dedupes_and_acks() {
+        let mut manager = PeerManager::new(PeerId::from("node1"), GameId::new("game1"), false);
+        let sender =
+            PeerManager::new(PeerId::from("host"), GameId::new("game1"), true).without_redaction();
+
+        let msg = sequenced(&sender, "node1", game_event(1)).await;
+        manager.handle_message("host", msg.clone()).await;
+        manager.handle_message("host", msg).await;
+
+        assert!(matches!(
+            manager.try_recv_event(),
+            Some(PeerEvent::GameEventReceived { .. })
+        ));
+        assert!(manager.try_recv_event().is_none());
+        assert_eq!(manager.last_received_seq("host").await, 1);
+
+        assert!(matches!(
+            manager.pending_ack("host").await,
+            Some(PeerMessage::Ack { seq: 1 })
+        ));
+        assert!(manager.pending_ack("host").await.is_none());
+    }
+
+    #[tokio::test]
+    async fn test_receive_sequenced_in_order_after_gap() {
+        let mut manager = PeerManager::new(PeerId::from("node1"), GameId::new("game1"), false);
+        let sender =
+            PeerManager::new(PeerId::from("host"), GameId::new("game1"), true).without_redaction();
+        let mut sent = Vec::new();
+        for n in 1..=3 {
+            sent.push(sequenced(&sender, "node1", game_event(n)).await);
+        }
+
+        // Message 3 arrives before 2: it is held and a replay is requested
+        manager.handle_message("host", sent[0].clone()).await;
+        manager.handle_message("host", sent[2].clone()).await;
+        assert_eq!(manager.last_received_seq("host").await, 1);
+        assert!(matches!(
+            manager.pending_ack("host").await,
+            Some(PeerMessage::Ack { seq: 1 })
+        ));
+
+        manager.handle_message("host", sent[1].clone()).await;
+        assert_eq!(manager.last_received_seq("host").await, 3);
+
+        let mut received = Vec::new();
+        let mut replay_requests = Vec::new();
+        while let Some(event) = manager.try_recv_event() {
+            match event {
+                PeerEvent::GameEventReceived { event_json, .. } => received.push(event_json),
+                PeerEvent::ReplayNeeded {
+                    last_received_seq, ..
+                } => replay_requests.push(last_received_seq),
+                other => panic!("Unexpected event {:?}", other),
+            }
+        }
+        assert_eq!(received, ["{\"n\":1}", "{\"n\":2}", "{\"n\":3}"]);
+        assert_eq!(replay_requests, [1]);
+    }
+
+    #[tokio::test]
+    async fn test_lost_replay_request_is_repeated() {
+        let mut manager = PeerManager::new(PeerId::from("node1"), GameId::new("game1"), false)
+            .with_replay_retry(Duration::ZERO);
+        let sender =
+            PeerManager::new(PeerId::from("host"), GameId::new("game1"), true).without_redaction();
+        let mut sent = Vec::new();
+        for n in 1..=3 {
+            sent.push(sequenced(&sender, "node1", game_event(n)).await);
+        }
+        assert!(manager.pending_resume("host").await.is_none());
+
+        // Message 2 is lost, and so is the first replay request
+        manager.handle_message("host", sent[0].clone()).await;
+        manager.handle_message("host", sent[2].clone()).await;
+        assert!(matches!(
+            manager.try_recv_event(),
+            Some(PeerEvent::GameEventReceived { .. })
+        ));
+        assert!(matches!(
+            manager.try_recv_event(),
+            Some(PeerEvent::ReplayNeeded {
+                last_received_seq: 1,
+                ..
+            })
+        ));
+
+        let Some(PeerMessage::Resume { last_received_seq }) = manager.pending_resume("host").await
+        else {
+            panic!("Expected the replay to be requested again");
+        };
+        assert_eq!(last_received_seq, 1);
+
+        let replay = sender.replay_since("node1", last_received_seq).await;
+        for message in replay.unwrap() {
+            manager.handle_message("host", message).await;
+        }
+        assert_eq!(manager.last_received_seq("host").await, 3);
+        assert!(manager.pending_resume("host").await.is_none());
+    }
+
+    #[tokio::test]
+    async fn test_replay_retry_waits_for_timeout() {
+        let manager = PeerManager::new(PeerId::from("node1"), GameId::new("game1"), false);
+        let sender =
+            PeerManager::new(PeerId::from("host"), GameId::new("game1"), true).without_redaction();
+        sequenced(&sender, "node1", game_event(1)).await;
+        let second = sequenced(&sender, "node1", game_event(2)).await;
+
+        manager.handle_message("host", second).await;
+        assert!(manager.pending_resume("host").await.is_none());
+    }
+
+    #[tokio::test]
+    async fn test_overflowed_hold_keeps_requesting_replay() {
+        let manager = PeerManager::with_replay_capacity(
+            PeerId::from("node1"),
+            GameId::new("game1"),
+            false,
+            1,
+        )
+        .with_replay_retry(Duration::ZERO);
+        let sender =
+            PeerManager::new(PeerId::from("host"), GameId::new("game1"), true).without_redaction();
+        let mut sent = Vec::new();
+        for n in 1..=3 {
+            sent.push(sequenced(&sender, "node1", game_event(n)).await);
+        }
+
+        // Only message 2 fits in the hold; 3 is dropped
+        manager.handle_message("host", sent[1].clone()).await;
+        manager.handle_message("host", sent[2].clone()).await;
+        manager.handle_message("host", sent[0].clone()).await;
+        assert_eq!(manager.last_received_seq("host").await, 2);
+
+        // The gap to message 3 is still open, so the replay is requested
+        assert!(matches!(
+            manager.pending_resume("host").await,
+            Some(PeerMessage::Resume {
+                last_received_seq: 2
+            })
+        ));
+    }
+
+    #[tokio::test]
+    async fn test_duplicate_rearms_ack() {
+        let manager = PeerManager::new(PeerId::from("node1"), GameId::new("game1"), false);
+        let sender =
+            PeerManager::new(PeerId::from("host"), GameId::new("game1"), true).without_redaction();
+        let msg = sequenced(&sender, "node1", game_event(1)).await;
+
+        manager.handle_message("host", msg.clone()).await;
+        assert!(manager.pending_ack("host").await.is_some());
+        assert!(manager.pending_ack("host").await.is_none());
+
+        // The sender resending means our ACK was lost
+        manager.handle_message("host", msg).await;
+        assert!(matches!(
+            manager.pending_ack("host").await,
+            Some(PeerMessage::Ack { seq: 1 })
+        ));
+    }
+
+    #[tokio::test]
+    async fn test_resume_replays_only_unreceived() {
+        let mut manager =
+            PeerManager::new(PeerId::from("host"), GameId::new("game1"), true).without_redaction();
+        for n in 0..5 {
+            sequenced(&manager, "peer1", game_event(n)).await;
+        }
+
+        manager
+            .handle_message("peer1", PeerMessage::Resume { last_received_seq: 3 })
+            .await;
+        assert!(matches!(
+            manager.try_recv_event(),
+            Some(PeerEvent::ResumeRequested {
+                last_received_seq: 3,
+                ..
+            })
+        ));
+
+        let replay = manager.replay_since("peer1", 3).await.unwrap();
+        let seqs: Vec<u64> = replay
+            .iter()
+            .map(|m| match m {
+                PeerMessage::Sequenced { seq, .. } => *seq,
+                _ => panic!("Expected sequenced message"),
+            })
+            .collect();
+        assert_eq!(seqs, vec![4, 5]);
+
+        // Resume point is also an implicit ACK
+        assert_eq!(manager.unacked_count("peer1").await, 2);
+    }
+
+    #[tokio::test]
+    async fn test_resume_up_to_date() {
+        let manager =
+            PeerManager::new(PeerId::from("host"), GameId::new("game1"), true).without_redaction();
+        sequenced(&manager, "peer1", game_event(1)).await;
+
+        assert!(manager.replay_since("peer1", 1).await.unwrap().is_empty());
+        assert!(manager.replay_since("new_peer", 0).await.unwrap().is_empty());
+    }
+
+    #[tokio::test]
+    async fn test_resume_past_buffer_needs_full_sync() {
+        let manager =
+            PeerManager::with_replay_capacity(PeerId::from("host"), GameId::new("game1"), true, 2)
+                .without_redaction();
+        for n in 0..5 {
+            sequenced(&manager, "peer1", game_event(n)).await;
+        }
+
+        assert_eq!(manager.unacked_count("peer1").await, 2);
+        assert!(manager.replay_since("peer1", 1).await.is_none());
+        assert_eq!(manager.replay_since("peer1", 3).await.unwrap().len(), 2);
+        // Claims to have received more than we sent
+        assert!(manager.replay_since("peer1", 10).await.is_none());
+    }
+
+    #[tokio::test]
+    async fn test_session_survives_reconnect() {
+        let manager =
+            PeerManager::new(PeerId::from("host"), GameId::new("game1"), true).without_redaction();
+        manager.add_peer(PeerId::from("peer1")).await;
+        sequenced(&manager, "peer1", game_event(1)).await;
+        manager.remove_peer("peer1", "dropped".to_string()).await;
+        manager.add_peer(PeerId::from("peer1")).await;
+
+        assert_eq!(manager.replay_since("peer1", 0).await.unwrap().len(), 1);
+
+        manager.clear_session("peer1").await;
+        assert_eq!(manager.unacked_count("peer1").await, 0);
+    }
+
+    // ==================== Handshake Tests ====================
+
+    #[test]
+    fn test_negotiate_picks_common_parameters() {
+        let local = Capabilities::default();
+        let remote = Capabilities {
+            compression: vec![CompressionAlgorithm::Rle],
+            batching: false,
+            ..Capabilities::default()
+        };
+
+        let session = local.negotiate(&remote).unwrap();
+        assert_eq!(session.protocol_version, PROTOCOL_VERSION);
+        assert_eq!(session.compression, CompressionAlgorithm::Rle);
+        assert_eq!(session.encryption_version, ENCRYPTION_VERSION);
+        assert!(!session.batching);
+    }
+
+    #[test]
+    fn test_negotiate_accepts_legacy_peer() {
+        let session = Capabilities::default()
+            .negotiate(&Capabilities::legacy())
+            .unwrap();
+        assert_eq!(session.protocol_version, 1);
+    }
+
+    #[test]
+    fn test_negotiate_rejects_incompatible_version() {
+        let remote = Capabilities {
+            protocol_version: 9,
+            min_protocol_version: 9,
+            ..Capabilities::default()
+        };
+        assert_eq!(
+            Capabilities::default().negotiate(&remote),
+            Err(HandshakeError::IncompatibleVersion {
+                local: PROTOCOL_VERSION,
+                remote: 9
+            })
+        );
+    }
+
+    #[test]
+    fn test_negotiate_rejects_ruleset_and_encryption_mismatch() {
+        let local = Capabilities::default().with_ruleset_hash(1);
+        let remote = Capabilities::default().with_ruleset_hash(2);
+        assert!(matches!(
+            local.negotiate(&remote),
+            Err(HandshakeError::RulesetMismatch { .. })
+        ));
+
+        // Unknown hashes are not compared
+        assert!(local.negotiate(&Capabilities::default()).is_ok());
+
+        let remote = Capabilities {
+            encryption_versions: vec![],
+            ..Capabilities::default()
+        };
+        assert_eq!(
+            local.negotiate(&remote),
+            Err(HandshakeError::NoCommonEncryption)
+        );
+    }
+
+    #[test]
+    fn test_ticket_negotiate_checks_game_and_version() {
+        let manager = PeerManager::new(PeerId::from("host"), GameId::new("game1"), true);
+        let ticket = manager.create_ticket(vec![], 3600);
+        let local = Capabilities::default();
+
+        assert!(ticket.negotiate(&GameId::new("game1"), &local).is_ok());
+        assert!(matches!(
+            ticket.negotiate(&GameId::new("game2"), &local),
+            Err(HandshakeError::GameMismatch { .. })
+        ));
+
+        let newer = ticket.with_capabilities(Capabilities {
+            protocol_version: 9,
+            min_protocol_version: 9,
+            ..Capabilities::default()
+        });
+        assert!(matches!(
+            newer.negotiate(&GameId::new("game1"), &local),
+            Err(HandshakeError::IncompatibleVersion { .. })
+        ));
+    }
+
+    #[test]
+    fn test_hello_without_capabilities_is_legacy() {
+        let json = r#"{"type":"Hello","peer_id":"p","game_id":"g","player_name":"A"}"#;
+        match PeerMessage::from_bytes(json.as_bytes()).unwrap() {
+            PeerMessage::Hello { capabilities, .. } => {
+                assert_eq!(capabilities, Capabilities::legacy());
+            }
+            _ => panic!("Wrong message type"),
+        }
+    }
+
+    #[tokio::test]
+    async fn test_hello_completes_handshake() {
+        let mut host = PeerManager::new(PeerId::from("host"), GameId::new("game1"), true);
+        let guest = PeerManager::new(PeerId::from("guest"), GameId::new("game1"), false);
+        host.add_peer(PeerId::from("guest")).await;
+
+        host.handle_message("guest", guest.hello("Bob".to_string()))
+            .await;
+
+        let peer = host.get_peer("guest").await.unwrap();
+        assert_eq!(peer.state, ConnectionState::Connected);
+        assert!(peer.session.is_some());
+        assert!(matches!(
+            host.try_recv_event(),
+            Some(PeerEvent::PeerConnected { .. })
+        ));
+        assert!(matches!(
+            host.try_recv_event(),
+            Some(PeerEvent::HandshakeCompleted { .. })
+        ));
+    }
+
+    #[tokio::test]
+    async fn test_failed_handshake_drops_peer() {
+        let mut host = PeerManager::new(PeerId::from("host"), GameId::new("game1"), true);
+        let guest = PeerManager::new(PeerId::from("guest"), GameId::new("game2"), false);
+        host.add_peer(PeerId::from("guest")).await;
+        host.try_recv_event();
+
+        host.handle_message("guest", guest.hello("Bob".to_string()))
+            .await;
+
+        assert!(host.get_peer("guest").await.is_none());
+        match host.try_recv_event() {
+            Some(PeerEvent::HandshakeFailed { error, .. }) => {
+                assert!(matches!(error, HandshakeError::GameMismatch { .. }));
+            }
+            other => panic!("Unexpected event: {:?}", other),
+        }
+        assert!(matches!(
+            host.try_recv_event(),
+            Some(PeerEvent::PeerDisconnected { .. })
+        ));
+    }
+
+    #[tokio::test]
+    async fn test_blocked_npub_fails_handshake() {
+        let mut host = PeerManager::new(PeerId::from("host"), GameId::new("game1"), true);
+        let mut blocked = BlockList::new();
+        blocked.block(Npub::from("npub_mallory"), "cheating");
+        host.set_block_list(blocked).await;
+
+        let friend = PeerManager::new(PeerId::from("friend"), GameId::new("game1"), false)
+            .with_npub(Npub::from("npub_bob"));
+        host.add_peer(PeerId::from("friend")).await;
+        host.handle_message("friend", friend.hello("Bob".to_string()))
+            .await;
+        // The npub is only claimed until the friend proves it
+        let peer = host.get_peer("friend").await.unwrap();
+        assert_eq!(peer.claimed_npub, Some(Npub::from("npub_bob")));
+        assert!(peer.npub.is_none());
+
+        let guest = PeerManager::new(PeerId::from("guest"), GameId::new("game1"), false)
+            .with_npub(Npub::from("npub_mallory"));
+        host.add_peer(PeerId::from("guest")).await;
+        while host.try_recv_event().is_some() {}
+        host.handle_message("guest", guest.hello("Mallory".to_string()))
+            .await;
+
+        assert!(host.get_peer("guest").await.is_none());
+        match host.try_recv_event() {
+            Some(PeerEvent::HandshakeFailed { error, .. }) => {
+                assert_eq!(error, HandshakeError::Blocked(Npub::from("npub_mallory")));
+            }
+            other => panic!("Unexpected event: {:?}", other),
+        }
+    }
+
+    // ==================== Npub Proof Tests ====================
+
+    fn key(n: u8) -> SigningKey {
+        SigningKey::from_secret_bytes(&[n; 32]).unwrap()
+    }
+
+    /// Exchange Hellos between `host` and `guest`, then prove the guest's
+    /// npub to the host.
+    async fn handshake_with_proof(host: &PeerManager, guest: &PeerManager) {
+        let guest_id = guest.node_id().clone();
+        host.add_peer(guest_id.clone()).await;
+        host.handle_message(guest_id.as_str(), guest.hello("Guest".to_string()))
+            .await;
+        guest.add_peer(host.node_id().clone()).await;
+        guest
+            .handle_message(host.node_id().as_str(), host.hello("Host".to_string()))
+            .await;
+        let proof = guest.pending_npub_proof(host.node_id().as_str()).await;
+        host.handle_message(guest_id.as_str(), proof.unwrap()).await;
+    }
+
+    #[tokio::test]
+    async fn test_npub_trusted_once_proven() {
+        let mut host = PeerManager::new(PeerId::from("host"), GameId::new("game1"), true);
+        let guest = PeerManager::new(PeerId::from("guest"), GameId::new("game1"), false)
+            .with_signing_key(key(1));
+
+        handshake_with_proof(&host, &guest).await;
+        let peer = host.get_peer("guest").await.unwrap();
+        assert_eq!(peer.npub, Some(key(1).npub()));
+        assert!(std::iter::from_fn(|| host.try_recv_event())
+            .any(|e| matches!(e, PeerEvent::NpubVerified { .. })));
+
+        // A proof is owed once per Hello
+        assert!(guest.pending_npub_proof("host").await.is_none());
+    }
+
+    #[tokio::test]
+    async fn test_npub_claimed_without_its_key_drops_peer() {
+        let mut host = PeerManager::new(PeerId::from("host"), GameId::new("game1"), true);
+
+        // Mallory names Alice's npub but can only sign with her own key
+        let mallory = PeerManager::new(PeerId::from("mallory"), GameId::new("game1"), false)
+            .with_signing_key(key(2))
+            .with_npub(key(1).npub());
+        handshake_with_proof(&host, &mallory).await;
+        assert!(host.get_peer("mallory").await.is_none());
+        assert!(
+            std::iter::from_fn(|| host.try_recv_event()).any(|e| matches!(
+                e,
+                PeerEvent::HandshakeFailed {
+                    error: HandshakeError::BadNpubProof,
+                    ..
+                }
+            ))
+        );
+
+        // Alice's real proof doesn't work when replayed by another peer
+        let alice = PeerManager::new(PeerId::from("alice"), GameId::new("game1"), false)
+            .with_signing_key(key(1));
+        alice.add_peer(PeerId::from("host")).await;
+        alice
+            .handle_message("host", host.hello("Host".to_string()))
+            .await;
+        let proof = alice.pending_npub_proof("host").await.unwrap();
+        host.add_peer(PeerId::from("mallory")).await;
+        host.handle_message("mallory", alice.hello("Alice".to_string()))
+            .await;
+        host.handle_message("mallory", proof).await;
+        assert!(host.get_peer("mallory").await.is_none());
+    }
+
+    #[tokio::test]
+    async fn test_game_state_refused_without_gate() {
+        let host = PeerManager::new(PeerId::from("host"), GameId::new("game1"), true);
+        let event = PeerMessage::GameEvent {
+            event_json: "{}".to_string(),
+        };
+        assert_eq!(
+            host.sequence_message("peer1", event.clone())
+                .await
+                .unwrap_err(),
+            RedactionError::NoGate
+        );
+        assert_eq!(host.unacked_count("peer1").await, 0);
+
+        // Messages without game state still go out
+        assert!(host
+            .sequence_message("peer1", PeerMessage::Ping { timestamp: 1 })
+            .await
+            .unwrap()
+            .is_some());
+
+        let trusting =
+            PeerManager::new(PeerId::from("host"), GameId::new("game1"), true).without_redaction();
+        assert!(trusting
+            .sequence_message("peer1", event)
+            .await
+            .unwrap()
+            .is_some());
+    }
+
+    // ==================== Redaction Tests ====================
+
+    #[tokio::test]
+    async fn test_sends_redacted_for_peer_npub() {
+        use nostr_nations_core::events::{GameAction, GameEvent};
+        use nostr_nations_core::hex::HexCoord;
+        use nostr_nations_core::map::Map;
+        use nostr_nations_core::player::{Civilization, Player};
+        use nostr_nations_core::settings::GameSettings;
+        use nostr_nations_core::terrain::Terrain;
+        use nostr_nations_core::unit::{Unit, UnitType};
+
+        let mut game = GameState::new(
+            GameId::new("game1"),
+            GameSettings::new("Test".to_string()),
+            [0u8; 32],
+        );
+        let keys = [key(1), key(2)];
+        for (id, key) in keys.iter().enumerate() {
+            game.add_player(Player::new(
+                PlayerSlot(id as u8),
+                key.npub(),
+                format!("Player {}", id),
+                Civilization::generic(),
+            ))
+            .unwrap();
+        }
+        game.start().unwrap();
+        game.map = Map::filled(20, 20, Terrain::Grassland);
+        for (owner, position) in [(0, HexCoord::new(2, 2)), (1, HexCoord::new(15, 15))] {
+            let id = game.allocate_unit_id();
+            let unit = Unit::new(id, PlayerSlot(owner), UnitType::Warrior, position);
+            game.units.insert(id, unit);
+        }
+        let hidden = game
+            .units
+            .values()
+            .find(|u| u.owner == PlayerSlot(1))
+            .unwrap()
+            .id;
+
+        let host = PeerManager::new(PeerId::from("host"), GameId::new("game1"), true);
+        host.update_redaction(&game).await;
+        for (peer_id, key) in ["viewer", "enemy"].into_iter().zip(keys) {
+            let guest = PeerManager::new(PeerId::from(peer_id), GameId::new("game1"), false)
+                .with_signing_key(key);
+            handshake_with_proof(&host, &guest).await;
+        }
+
+        let secret = HexCoord::new(16, 15);
+        let event = GameEvent::new(
+            GameId::new("game1"),
+            PlayerSlot(1),
+            None,
+            1,
+            1,
+            GameAction::MoveUnit {
+                unit_id: hidden,
+                path: vec![secret],
+            },
+        );
+        let event_json = serde_json::to_string(&event).unwrap();
+        let secret_json = serde_json::to_string(&secret).unwrap();
+        let carries_secret = |message: &PeerMessage| {
+            String::from_utf8_lossy(&message.to_bytes().unwrap())
+                .replace('\\', "")
+                .contains(&secret_json)
+        };
+
+        // The hidden move is never sequenced for the viewer, only the owner
+        let move_message = PeerMessage::GameEvent {
+            event_json: event_json.clone(),
+        };
+        assert!(host
+            .sequence_message("viewer", move_message.clone())
+            .await
+            .unwrap()
+            .is_none());
+        assert_eq!(host.unacked_count("viewer").await, 0);
+        let to_enemy = sequenced(&host, "enemy", move_message).await;
+        assert!(carries_secret(&to_enemy));
+
+        // Sync responses are filtered the same way, and so are their replays
+        let sync = PeerMessage::SyncResponse {
+            events_json: vec![event_json],
+        };
+        let to_viewer = sequenced(&host, "viewer", sync).await;
+        assert!(!carries_secret(&to_viewer));
+        assert!(host
+            .unacked("viewer")
+            .await
+            .iter()
+            .all(|m| !carries_secret(m)));
+
+        // A peer whose npub we don't know gets nothing
+        host.add_peer(PeerId::from("stranger")).await;
+        assert_eq!(
+            host.sequence_message("stranger", PeerMessage::Ping { timestamp: 1 })
+                .await
+                .unwrap_err(),
+            RedactionError::UnknownRecipient("stranger".to_string())
+        );
+    }
+
+    // ==================== Presence Tests ====================
+
+    #[tokio::test]
+    async fn test_presence_messages_update_map() {
+        use crate::presence::PresenceStatus;
+
+        let mut host = PeerManager::new(PeerId::from("host"), GameId::new("game1"), true);
+        let update = PresenceUpdate::new(PresenceStatus::InTurn, false);
+
+        host.handle_message(
+            "guest",
+            PeerMessage::Presence {
+                player_id: PlayerSlot(1),
+                update: update.clone(),
+            },
+        )
+        .await;
+        host.handle_message(
+            "guest",
+            PeerMessage::Presence {
+                player_id: PlayerSlot(1),
+                update,
+            },
+        )
+        .await;
+
+        assert_eq!(host.presence().await.status(PlayerSlot(1)), PresenceStatus::InTurn);
+        assert!(matches!(
+            host.try_recv_event(),
+            Some(PeerEvent::PresenceChanged(PresenceChange {
+                player_id: PlayerSlot(1),
+                status: PresenceStatus::InTurn,
+                ..
+            }))
+        ));
+        assert!(host.try_recv_event().is_none());
+    }
+
+    #[tokio::test]
+    async fn test_removed_peer_is_disconnected() {
+        use crate::presence::PresenceStatus;
+
+        let mut host = PeerManager::new(PeerId::from("host"), GameId::new("game1"), true);
+        host.add_peer(PeerId::from("guest")).await;
+        host.peer_joined("guest", "Bob".to_string(), PlayerSlot(1)).await;
+        host.update_presence(PlayerSlot(1), &PresenceUpdate::new(PresenceStatus::Online, false), 0)
+            .await;
+        while host.try_recv_event().is_some() {}
+
+        host.remove_peer("guest", "left".to_string()).await;
+
+        assert_eq!(
+            host.presence().await.status(PlayerSlot(1)),
+            PresenceStatus::Disconnected
+        );
+        assert!(matches!(
+            host.try_recv_event(),
+            Some(PeerEvent::PresenceChanged(PresenceChange {
+                status: PresenceStatus::Disconnected,
+                ..
+            }))
+        ));
+    }
 }
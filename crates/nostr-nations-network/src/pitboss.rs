@@ -0,0 +1,661 @@
+//! Pitboss (play-by-relay) asynchronous game mode.
+//!
+//! In a pitboss game the host relay holds the authoritative game state.
+//! Players connect whenever they like, take their turn, and disconnect:
+//!
+//! - **PitbossHost**: Validates and applies submitted turns, persists the
+//!   accepted events, and decides who to notify next
+//! - **TurnNotification**: "It's your turn" message, delivered as an
+//!   direct message to the player's pubkey, encrypted with NIP-44
+//! - **OfflineTurnQueue**: Turns played while disconnected, submitted in
+//!   order once a connection to the host is available
+//!
+//! # Usage
+//!
+//! ```rust,ignore
+//! use nostr_nations_network::pitboss::{PitbossConfig, PitbossHost};
+//!
+//! let mut host = PitbossHost::new(engine, relay, PitbossConfig::default(), now);
+//! host.register_player(0, alice_pubkey);
+//! host.register_player(1, bob_pubkey);
+//!
+//! // Alice submits her whole turn, ending with EndTurn
+//! if let Some(notification) = host.submit_turn(0, &events, now)? {
+//!     let dm = notification.to_direct_message(&host_key, host.player_pubkey(1).unwrap(), now)?;
+//!     // Publish dm to Bob's relays
+//! }
+//! ```
+
+use crate::nip44::{self, Nip44Error};
+use crate::relay::{Filter, LocalRelay};
+use crate::signing::SigningKey;
+use nostr_nations_core::events::{GameAction, GameEvent};
+use nostr_nations_core::replay::GameEngine;
+use nostr_nations_core::types::{GameId, Npub, PlayerSlot};
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+use std::collections::{HashMap, VecDeque};
+use ts_rs::TS;
+
+/// Nostr event kinds used by pitboss games.
+pub mod kinds {
+    /// Encrypted direct message. Pitboss sends NIP-44 payloads, which
+    /// clients tell apart from NIP-04 ones by their format.
+    pub const ENCRYPTED_DM: u32 = 4;
+}
+
+/// Default interval between "still your turn" reminders (12 hours).
+pub const DEFAULT_REMINDER_INTERVAL_SECS: u64 = 12 * 60 * 60;
+
+/// Configuration for a pitboss game.
+#[derive(Clone, Debug)]
+pub struct PitbossConfig {
+    /// Time a player has to finish their turn (None = unlimited).
+    pub turn_timeout_secs: Option<u64>,
+    /// Interval between reminder notifications for the current player.
+    pub reminder_interval_secs: u64,
+}
+
+impl Default for PitbossConfig {
+    fn default() -> Self {
+        Self {
+            turn_timeout_secs: None,
+            reminder_interval_secs: DEFAULT_REMINDER_INTERVAL_SECS,
+        }
+    }
+}
+
+/// Notification telling a player it is their turn.
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize, JsonSchema, TS)]
+pub struct TurnNotification {
+    /// Game the notification is for.
+    pub game_id: GameId,
+    /// Player whose turn it is.
+    pub player_id: PlayerSlot,
+    /// Current turn number.
+    pub turn: u32,
+    /// Unix timestamp by which the turn must be completed.
+    pub deadline: Option<u64>,
+    /// Whether this is a repeat reminder rather than the first notice.
+    pub is_reminder: bool,
+}
+
+impl TurnNotification {
+    /// Human-readable notification text.
+    pub fn message(&self) -> String {
+        if self.is_reminder {
+            format!(
+                "Reminder: it's still your turn ({}) in game {}",
+                self.turn, self.game_id
+            )
+        } else {
+            format!("It's your turn ({}) in game {}", self.turn, self.game_id)
+        }
+    }
+
+    /// Encrypt this notification as a direct message to the player,
+    /// from the sender's Nostr key.
+    pub fn to_direct_message(
+        &self,
+        sender: &SigningKey,
+        recipient_pubkey: &str,
+        created_at: u64,
+    ) -> Result<DirectMessage, PitbossError> {
+        let plaintext =
+            serde_json::to_string(self).map_err(|e| PitbossError::Serialization(e.to_string()))?;
+        let content = nip44::encrypt(sender, recipient_pubkey, &plaintext)?;
+
+        Ok(DirectMessage {
+            kind: kinds::ENCRYPTED_DM,
+            recipient: self.player_id,
+            recipient_pubkey: recipient_pubkey.to_string(),
+            pubkey: sender
+                .public_key()
+                .iter()
+                .map(|b| format!("{:02x}", b))
+                .collect(),
+            content,
+            tags: vec![
+                vec!["p".to_string(), recipient_pubkey.to_string()],
+                vec!["g".to_string(), self.game_id.to_string()],
+            ],
+            created_at,
+        })
+    }
+}
+
+/// An encrypted direct message addressed to a single player.
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct DirectMessage {
+    /// Nostr event kind.
+    pub kind: u32,
+    /// Recipient player ID.
+    pub recipient: PlayerSlot,
+    /// Recipient's Nostr public key.
+    pub recipient_pubkey: String,
+    /// Sender's Nostr public key, as a hex string.
+    pub pubkey: String,
+    /// NIP-44 encrypted message body.
+    pub content: String,
+    /// Event tags.
+    pub tags: Vec<Vec<String>>,
+    /// Unix timestamp.
+    pub created_at: u64,
+}
+
+impl DirectMessage {
+    /// Decrypt a turn notification with the recipient's Nostr key.
+    pub fn decrypt_notification(
+        &self,
+        recipient: &SigningKey,
+    ) -> Result<TurnNotification, PitbossError> {
+        let plaintext = nip44::decrypt(recipient, &self.pubkey, &self.content)?;
+        serde_json::from_str(&plaintext).map_err(|e| PitbossError::Serialization(e.to_string()))
+    }
+}
+
+/// Host side of a pitboss game.
+///
+/// Holds the authoritative engine and persists accepted events to the
+/// host relay so players can catch up whenever they reconnect.
+pub struct PitbossHost {
+    config: PitbossConfig,
+    engine: GameEngine,
+    relay: LocalRelay,
+    /// Nostr public keys of registered players.
+    players: HashMap<PlayerSlot, Npub>,
+    /// When the current player's turn started.
+    turn_started_at: u64,
+    /// When the current player was last notified.
+    last_notified_at: Option<u64>,
+}
+
+impl PitbossHost {
+    /// Create a host for a game that has already started.
+    pub fn new(engine: GameEngine, relay: LocalRelay, config: PitbossConfig, now: u64) -> Self {
+        Self {
+            config,
+            engine,
+            relay,
+            players: HashMap::new(),
+            turn_started_at: now,
+            last_notified_at: None,
+        }
+    }
+
+    /// Register a player's Nostr public key for notifications.
+    pub fn register_player(&mut self, player_id: PlayerSlot, pubkey: Npub) {
+        self.players.insert(player_id, pubkey);
+    }
+
+    /// Get a registered player's public key.
+    pub fn player_pubkey(&self, player_id: PlayerSlot) -> Option<&str> {
+        self.players.get(&player_id).map(Npub::as_str)
+    }
+
+    /// Get the game ID.
+    pub fn game_id(&self) -> &GameId {
+        &self.engine.state.id
+    }
+
+    /// Get the authoritative game engine.
+    pub fn engine(&self) -> &GameEngine {
+        &self.engine
+    }
+
+    /// Get the player whose turn it is.
+    pub fn current_player(&self) -> PlayerSlot {
+        self.engine.state.current_player
+    }
+
+    /// Deadline for the current turn, if turns are timed.
+    pub fn deadline(&self) -> Option<u64> {
+        self.config
+            .turn_timeout_secs
+            .map(|timeout| self.turn_started_at + timeout)
+    }
+
+    /// Check whether the current player has run out of time.
+    pub fn is_overdue(&self, now: u64) -> bool {
+        self.deadline().is_some_and(|deadline| now >= deadline)
+    }
+
+    /// Notification for the current player, marking them as notified.
+    pub fn notify_current(&mut self, now: u64) -> Option<TurnNotification> {
+        if self.engine.is_ended() {
+            return None;
+        }
+
+        let is_reminder = self.last_notified_at.is_some();
+        self.last_notified_at = Some(now);
+        Some(TurnNotification {
+            game_id: self.game_id().clone(),
+            player_id: self.current_player(),
+            turn: self.engine.turn(),
+            deadline: self.deadline(),
+            is_reminder,
+        })
+    }
+
+    /// Reminder for the current player if the reminder interval has elapsed.
+    pub fn due_reminder(&mut self, now: u64) -> Option<TurnNotification> {
+        let last = self.last_notified_at?;
+        if now.saturating_sub(last) < self.config.reminder_interval_secs {
+            return None;
+        }
+        self.notify_current(now)
+    }
+
+    /// Validate and apply a player's complete turn.
+    ///
+    /// The events must all belong to the current player and end with
+    /// `EndTurn`. The turn is applied atomically: if any event is rejected
+    /// the game state is left unchanged. Returns the notification for the
+    /// next player, if the game continues.
+    pub fn submit_turn(
+        &mut self,
+        player_id: PlayerSlot,
+        events: &[GameEvent],
+        now: u64,
+    ) -> Result<Option<TurnNotification>, PitbossError> {
+        if self.engine.is_ended() {
+            return Err(PitbossError::GameEnded);
+        }
+        if player_id != self.current_player() {
+            return Err(PitbossError::NotPlayerTurn);
+        }
+        let last = events.last().ok_or(PitbossError::EmptyTurn)?;
+        if !matches!(last.action, GameAction::EndTurn) {
+            return Err(PitbossError::IncompleteTurn);
+        }
+        for event in events {
+            if event.game_id != self.engine.state.id {
+                return Err(PitbossError::WrongGame);
+            }
+            if event.player_id != player_id {
+                return Err(PitbossError::NotPlayerTurn);
+            }
+        }
+
+        let snapshot = self.engine.state.clone();
+        for event in events {
+            let rejection = match self.engine.apply_event(event) {
+                Ok(result) if result.success => None,
+                Ok(result) => Some(result.error.unwrap_or_default()),
+                Err(e) => Some(e.to_string()),
+            };
+            if let Some(reason) = rejection {
+                self.engine.state = snapshot;
+                return Err(PitbossError::Rejected(reason));
+            }
+        }
+
+        for event in events {
+            self.relay
+                .publish(event)
+                .map_err(|e| PitbossError::Storage(e.to_string()))?;
+        }
+
+        self.turn_started_at = now;
+        self.last_notified_at = None;
+        Ok(self.notify_current(now))
+    }
+
+    /// Get accepted events since a timestamp, for catching up on reconnect.
+    pub fn events_since(&self, since: u64) -> Result<Vec<GameEvent>, PitbossError> {
+        let filter = Filter::game(self.game_id().clone()).since(since);
+        self.relay
+            .query(&filter)
+            .map_err(|e| PitbossError::Storage(e.to_string()))
+    }
+}
+
+/// A turn played while disconnected from the host.
+#[derive(Clone, Debug, Serialize, Deserialize, JsonSchema, TS)]
+pub struct QueuedTurn {
+    /// Game the turn belongs to.
+    pub game_id: GameId,
+    /// Player who played the turn.
+    pub player_id: PlayerSlot,
+    /// Turn number.
+    pub turn: u32,
+    /// Events making up the turn, ending with `EndTurn`.
+    pub events: Vec<GameEvent>,
+}
+
+/// Queue of completed turns waiting to be submitted to the host.
+#[derive(Clone, Debug, Default, Serialize, Deserialize)]
+pub struct OfflineTurnQueue {
+    turns: VecDeque<QueuedTurn>,
+}
+
+impl OfflineTurnQueue {
+    /// Create an empty queue.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Queue a completed turn.
+    pub fn queue(&mut self, turn: QueuedTurn) {
+        self.turns.push_back(turn);
+    }
+
+    /// Number of queued turns.
+    pub fn len(&self) -> usize {
+        self.turns.len()
+    }
+
+    /// Check if the queue is empty.
+    pub fn is_empty(&self) -> bool {
+        self.turns.is_empty()
+    }
+
+    /// Get the next turn to submit.
+    pub fn peek(&self) -> Option<&QueuedTurn> {
+        self.turns.front()
+    }
+
+    /// Submit queued turns in order.
+    ///
+    /// Stops at the first failure, leaving that turn and any after it in
+    /// the queue. Returns the number of turns submitted.
+    pub fn flush<F>(&mut self, mut submit: F) -> Result<usize, PitbossError>
+    where
+        F: FnMut(&QueuedTurn) -> Result<(), PitbossError>,
+    {
+        let mut submitted = 0;
+        while let Some(turn) = self.turns.front() {
+            submit(turn)?;
+            self.turns.pop_front();
+            submitted += 1;
+        }
+        Ok(submitted)
+    }
+}
+
+/// Errors that can occur in pitboss games.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum PitbossError {
+    /// It is not this player's turn.
+    NotPlayerTurn,
+    /// Event belongs to a different game.
+    WrongGame,
+    /// Submitted turn contained no events.
+    EmptyTurn,
+    /// Submitted turn did not end with `EndTurn`.
+    IncompleteTurn,
+    /// The game has already ended.
+    GameEnded,
+    /// An event in the turn was rejected by the engine.
+    Rejected(String),
+    /// Notification encryption failed.
+    Encryption(Nip44Error),
+    /// Relay storage failed.
+    Storage(String),
+    /// Serialization failed.
+    Serialization(String),
+}
+
+impl std::fmt::Display for PitbossError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            PitbossError::NotPlayerTurn => write!(f, "Not this player's turn"),
+            PitbossError::WrongGame => write!(f, "Event belongs to a different game"),
+            PitbossError::EmptyTurn => write!(f, "Turn contains no events"),
+            PitbossError::IncompleteTurn => write!(f, "Turn must end with EndTurn"),
+            PitbossError::GameEnded => write!(f, "Game has ended"),
+            PitbossError::Rejected(reason) => write!(f, "Turn rejected: {}", reason),
+            PitbossError::Encryption(e) => write!(f, "Encryption error: {}", e),
+            PitbossError::Storage(msg) => write!(f, "Storage error: {}", msg),
+            PitbossError::Serialization(msg) => write!(f, "Serialization error: {}", msg),
+        }
+    }
+}
+
+impl std::error::Error for PitbossError {}
+
+impl From<Nip44Error> for PitbossError {
+    fn from(e: Nip44Error) -> Self {
+        PitbossError::Encryption(e)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use nostr_nations_core::settings::GameSettings;
+    use nostr_nations_core::types::{MapSize, UnitId};
+
+    fn started_engine() -> GameEngine {
+        let mut settings = GameSettings::new("Pitboss".to_string());
+        settings.map_size = MapSize::Duel;
+        let mut engine = GameEngine::new(settings, [7u8; 32]);
+        for (id, civ) in [(0, "rome"), (1, "egypt")] {
+            engine
+                .apply_action(
+                    PlayerSlot(id),
+                    &GameAction::JoinGame {
+                        player_name: format!("P{}", id),
+                        civilization_id: civ.to_string(),
+                    },
+                )
+                .unwrap();
+        }
+        engine
+            .apply_action(PlayerSlot(0), &GameAction::StartGame)
+            .unwrap();
+        engine
+    }
+
+    fn create_host() -> PitbossHost {
+        let relay = LocalRelay::new_in_memory().unwrap();
+        let mut host = PitbossHost::new(started_engine(), relay, PitbossConfig::default(), 1000);
+        host.register_player(PlayerSlot(0), Npub::new("alice"));
+        host.register_player(PlayerSlot(1), Npub::new("bob"));
+        host
+    }
+
+    fn end_turn_event(host: &PitbossHost, player_id: PlayerSlot, id: &str) -> GameEvent {
+        let mut event = GameEvent::new(
+            GameId(host.game_id().to_string()),
+            player_id,
+            None,
+            host.engine().turn(),
+            0,
+            GameAction::EndTurn,
+        );
+        event.id = id.to_string();
+        event.timestamp = 2000;
+        event
+    }
+
+    // ==== Host Tests ====
+
+    #[test]
+    fn test_submit_turn_notifies_next_player() {
+        let mut host = create_host();
+        let event = end_turn_event(&host, PlayerSlot(0), "e1");
+
+        let notification = host
+            .submit_turn(PlayerSlot(0), &[event], 2000)
+            .unwrap()
+            .unwrap();
+        assert_eq!(notification.player_id, PlayerSlot(1));
+        assert!(!notification.is_reminder);
+        assert_eq!(host.current_player(), PlayerSlot(1));
+        assert_eq!(host.events_since(0).unwrap().len(), 1);
+    }
+
+    #[test]
+    fn test_submit_turn_out_of_order() {
+        let mut host = create_host();
+        let event = end_turn_event(&host, PlayerSlot(1), "e1");
+
+        assert_eq!(
+            host.submit_turn(PlayerSlot(1), &[event], 2000).unwrap_err(),
+            PitbossError::NotPlayerTurn
+        );
+    }
+
+    #[test]
+    fn test_submit_turn_requires_end_turn() {
+        let mut host = create_host();
+        assert_eq!(
+            host.submit_turn(PlayerSlot(0), &[], 2000).unwrap_err(),
+            PitbossError::EmptyTurn
+        );
+
+        let mut event = end_turn_event(&host, PlayerSlot(0), "e1");
+        event.action = GameAction::FortifyUnit { unit_id: UnitId(1) };
+        assert_eq!(
+            host.submit_turn(PlayerSlot(0), &[event], 2000).unwrap_err(),
+            PitbossError::IncompleteTurn
+        );
+    }
+
+    #[test]
+    fn test_rejected_turn_leaves_state_unchanged() {
+        let mut host = create_host();
+        let mut bad = end_turn_event(&host, PlayerSlot(0), "e1");
+        bad.action = GameAction::FortifyUnit {
+            unit_id: UnitId(9999),
+        };
+        let end = end_turn_event(&host, PlayerSlot(0), "e2");
+
+        let err = host
+            .submit_turn(PlayerSlot(0), &[bad, end], 2000)
+            .unwrap_err();
+        assert!(matches!(err, PitbossError::Rejected(_)));
+        assert_eq!(host.current_player(), PlayerSlot(0));
+        assert!(host.events_since(0).unwrap().is_empty());
+    }
+
+    #[test]
+    fn test_reminders_and_deadline() {
+        let relay = LocalRelay::new_in_memory().unwrap();
+        let config = PitbossConfig {
+            turn_timeout_secs: Some(3600),
+            reminder_interval_secs: 600,
+        };
+        let mut host = PitbossHost::new(started_engine(), relay, config, 1000);
+
+        assert!(host.due_reminder(5000).is_none()); // Never notified yet
+        let first = host.notify_current(1000).unwrap();
+        assert_eq!(first.deadline, Some(4600));
+        assert!(host.due_reminder(1500).is_none());
+
+        let reminder = host.due_reminder(1600).unwrap();
+        assert!(reminder.is_reminder);
+        assert!(!host.is_overdue(4599));
+        assert!(host.is_overdue(4600));
+    }
+
+    // ==== Notification Tests ====
+
+    fn key(seed: u8) -> SigningKey {
+        SigningKey::from_secret_bytes(&[seed; 32]).unwrap()
+    }
+
+    #[test]
+    fn test_notification_direct_message_roundtrip() {
+        let host_key = key(1);
+        let bob = key(2);
+
+        let notification = TurnNotification {
+            game_id: GameId::new("game1"),
+            player_id: PlayerSlot(1),
+            turn: 3,
+            deadline: None,
+            is_reminder: false,
+        };
+
+        let dm = notification
+            .to_direct_message(&host_key, bob.npub().as_str(), 1234)
+            .unwrap();
+        assert_eq!(dm.kind, kinds::ENCRYPTED_DM);
+        assert_eq!(
+            dm.tags[0],
+            vec!["p".to_string(), bob.npub().as_str().to_string()]
+        );
+        assert!(!dm.content.contains("game1"));
+
+        let decrypted = dm.decrypt_notification(&bob).unwrap();
+        assert_eq!(decrypted, notification);
+
+        // Nobody else can read it
+        assert_eq!(
+            dm.decrypt_notification(&key(3)).unwrap_err(),
+            PitbossError::Encryption(Nip44Error::InvalidMac)
+        );
+    }
+
+    #[test]
+    fn test_direct_message_requires_valid_recipient_key() {
+        let notification = TurnNotification {
+            game_id: GameId::new("game1"),
+            player_id: PlayerSlot(1),
+            turn: 1,
+            deadline: None,
+            is_reminder: false,
+        };
+
+        assert_eq!(
+            notification
+                .to_direct_message(&key(1), "bob", 0)
+                .unwrap_err(),
+            PitbossError::Encryption(Nip44Error::InvalidPubkey("bob".to_string()))
+        );
+    }
+
+    // ==== Offline Queue Tests ====
+
+    #[test]
+    fn test_offline_queue_flush_in_order() {
+        let host = create_host();
+        let mut queue = OfflineTurnQueue::new();
+        for turn in 1..=3 {
+            queue.queue(QueuedTurn {
+                game_id: GameId(host.game_id().to_string()),
+                player_id: PlayerSlot(0),
+                turn,
+                events: vec![end_turn_event(&host, PlayerSlot(0), &format!("e{}", turn))],
+            });
+        }
+
+        let mut seen = Vec::new();
+        let submitted = queue
+            .flush(|turn| {
+                seen.push(turn.turn);
+                Ok(())
+            })
+            .unwrap();
+        assert_eq!(submitted, 3);
+        assert_eq!(seen, vec![1, 2, 3]);
+        assert!(queue.is_empty());
+    }
+
+    #[test]
+    fn test_offline_queue_stops_on_failure() {
+        let host = create_host();
+        let mut queue = OfflineTurnQueue::new();
+        for turn in 1..=2 {
+            queue.queue(QueuedTurn {
+                game_id: GameId(host.game_id().to_string()),
+                player_id: PlayerSlot(0),
+                turn,
+                events: vec![end_turn_event(&host, PlayerSlot(0), &format!("e{}", turn))],
+            });
+        }
+
+        let result = queue.flush(|turn| {
+            if turn.turn == 2 {
+                Err(PitbossError::NotPlayerTurn)
+            } else {
+                Ok(())
+            }
+        });
+        assert_eq!(result.unwrap_err(), PitbossError::NotPlayerTurn);
+        assert_eq!(queue.len(), 1);
+        assert_eq!(queue.peek().unwrap().turn, 2);
+    }
+}
@@ -2,11 +2,23 @@
 //!
 //! This module provides connection management with automatic reconnection,
 //! health monitoring, and exponential backoff for failed connections.
-
+//!
+//! # Failover
+//!
+//! Connections are either primaries, which carry publishes, or warm standbys
+//! kept connected to backup relays. Each connection is scored from its
+//! observed latency and uptime; [`ConnectionPool::publish`] walks relays in
+//! score order and fails over to the next-best standby whenever a primary
+//! rejects a publish, aggregating the result as "succeeded on k of n".
+//! [`ConnectionPool::publish_cancellable`] stops trying relays, and abandons
+//! the send in flight, once its [`CancellationToken`] is cancelled.
+
+use crate::cancel::CancellationToken;
 use serde::{Deserialize, Serialize};
 use std::collections::HashMap;
+use std::future::Future;
 use std::sync::Arc;
-use std::time::{Duration, Instant};
+use web_time::{Duration, Instant};
 use tokio::sync::RwLock;
 
 /// Connection state.
@@ -24,6 +36,21 @@ pub enum PooledConnectionState {
     Closed,
 }
 
+/// Role of a connection within the pool.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub enum ConnectionRole {
+    /// Carries publishes.
+    Primary,
+    /// Kept connected to a backup relay, used on failover.
+    Standby,
+}
+
+/// Weight of the newest sample in the smoothed round-trip time.
+const RTT_SMOOTHING: f64 = 0.2;
+
+/// Round-trip time at which the latency factor of a score halves.
+const RTT_HALF_SCORE_MS: f64 = 250.0;
+
 /// Health status of a connection.
 #[derive(Clone, Debug)]
 pub struct ConnectionHealth {
@@ -33,6 +60,8 @@ pub struct ConnectionHealth {
     pub last_ping: Option<Instant>,
     /// Last ping round-trip time in milliseconds.
     pub last_rtt_ms: Option<u32>,
+    /// Smoothed round-trip time in milliseconds.
+    pub avg_rtt_ms: Option<f64>,
     /// Number of consecutive failures.
     pub consecutive_failures: u32,
     /// Last error message.
@@ -51,6 +80,7 @@ impl Default for ConnectionHealth {
             state: PooledConnectionState::Disconnected,
             last_ping: None,
             last_rtt_ms: None,
+            avg_rtt_ms: None,
             consecutive_failures: 0,
             last_error: None,
             last_state_change: Instant::now(),
@@ -99,9 +129,26 @@ impl ConnectionHealth {
     pub fn record_ping(&mut self, rtt_ms: u32) {
         self.last_ping = Some(Instant::now());
         self.last_rtt_ms = Some(rtt_ms);
+        self.avg_rtt_ms = Some(match self.avg_rtt_ms {
+            Some(avg) => avg + RTT_SMOOTHING * (rtt_ms as f64 - avg),
+            None => rtt_ms as f64,
+        });
         self.record_success();
     }
 
+    /// Score the connection from observed uptime and latency (0.0 to 1.0).
+    ///
+    /// Uptime is the success rate; latency halves the score at 250ms.
+    /// Connections without a latency sample get a neutral latency factor.
+    pub fn score(&self) -> f64 {
+        let uptime = self.success_rate() / 100.0;
+        let latency = match self.avg_rtt_ms {
+            Some(rtt) => RTT_HALF_SCORE_MS / (RTT_HALF_SCORE_MS + rtt),
+            None => 0.5,
+        };
+        uptime * latency
+    }
+
     /// Update the connection state.
     pub fn set_state(&mut self, state: PooledConnectionState) {
         if self.state != state {
@@ -128,6 +175,8 @@ pub struct PoolConfig {
     pub max_consecutive_failures: u32,
     /// Enable automatic reconnection.
     pub auto_reconnect: bool,
+    /// Number of standby connections to keep warm.
+    pub warm_standby_count: usize,
 }
 
 impl Default for PoolConfig {
@@ -140,6 +189,7 @@ impl Default for PoolConfig {
             health_check_interval: Duration::from_secs(30),
             max_consecutive_failures: 3,
             auto_reconnect: true,
+            warm_standby_count: 1,
         }
     }
 }
@@ -247,7 +297,7 @@ impl BackoffState {
 
 /// Simple pseudo-random factor for jitter (0.0 to 1.0).
 fn rand_factor() -> f64 {
-    use std::time::SystemTime;
+    use web_time::SystemTime;
     let nanos = SystemTime::now()
         .duration_since(SystemTime::UNIX_EPOCH)
         .map(|d| d.subsec_nanos())
@@ -262,6 +312,8 @@ pub struct PooledConnection {
     pub id: String,
     /// Target address/endpoint.
     pub endpoint: String,
+    /// Primary or standby.
+    pub role: ConnectionRole,
     /// Health information.
     pub health: ConnectionHealth,
     /// Backoff state for reconnection.
@@ -278,6 +330,7 @@ impl PooledConnection {
         Self {
             id,
             endpoint,
+            role: ConnectionRole::Primary,
             health: ConnectionHealth::new(),
             backoff: BackoffState::new(backoff_config),
             last_activity: Instant::now(),
@@ -328,6 +381,8 @@ pub struct PoolStats {
     pub idle_timeouts: u64,
     /// Health checks performed.
     pub health_checks: u64,
+    /// Publishes moved from a failed primary to a standby.
+    pub failovers: u64,
 }
 
 impl ConnectionPool {
@@ -373,6 +428,22 @@ impl ConnectionPool {
 
     /// Add a new connection to the pool.
     pub async fn add_connection(&self, id: String, endpoint: String) -> Result<(), PoolError> {
+        self.add_with_role(id, endpoint, ConnectionRole::Primary)
+            .await
+    }
+
+    /// Add a standby connection to a backup relay.
+    pub async fn add_standby(&self, id: String, endpoint: String) -> Result<(), PoolError> {
+        self.add_with_role(id, endpoint, ConnectionRole::Standby)
+            .await
+    }
+
+    async fn add_with_role(
+        &self,
+        id: String,
+        endpoint: String,
+        role: ConnectionRole,
+    ) -> Result<(), PoolError> {
         let mut connections = self.connections.write().await;
 
         // Check pool capacity
@@ -385,7 +456,9 @@ impl ConnectionPool {
             return Err(PoolError::DuplicateConnection(id));
         }
 
-        let connection = PooledConnection::new(id.clone(), endpoint, self.backoff_config.clone());
+        let mut connection =
+            PooledConnection::new(id.clone(), endpoint, self.backoff_config.clone());
+        connection.role = role;
         connections.insert(id, connection);
 
         // Update stats
@@ -532,6 +605,183 @@ impl ConnectionPool {
             .collect()
     }
 
+    /// Record a ping round-trip time on a connection.
+    pub async fn record_ping(&self, id: &str, rtt_ms: u32) {
+        let mut connections = self.connections.write().await;
+        if let Some(conn) = connections.get_mut(id) {
+            conn.health.record_ping(rtt_ms);
+            conn.backoff.reset();
+            conn.touch();
+        }
+    }
+
+    /// Get connection IDs ordered by score, best first.
+    ///
+    /// Connected relays always rank ahead of disconnected ones; ties are
+    /// broken by ID so the order is stable.
+    pub async fn ranked(&self, role: Option<ConnectionRole>) -> Vec<String> {
+        let connections = self.connections.read().await;
+        let mut ranked: Vec<&PooledConnection> = connections
+            .values()
+            .filter(|c| role.is_none_or(|r| c.role == r))
+            .collect();
+        ranked.sort_by(|a, b| {
+            let a_up = a.health.state == PooledConnectionState::Connected;
+            let b_up = b.health.state == PooledConnectionState::Connected;
+            b_up.cmp(&a_up)
+                .then_with(|| b.health.score().total_cmp(&a.health.score()))
+                .then_with(|| a.id.cmp(&b.id))
+        });
+        ranked.into_iter().map(|c| c.id.clone()).collect()
+    }
+
+    /// Get the order in which relays are tried for a publish.
+    ///
+    /// Primaries come first, followed by standbys, each group ordered by score.
+    pub async fn failover_order(&self) -> Vec<String> {
+        let mut order = self.ranked(Some(ConnectionRole::Primary)).await;
+        order.extend(self.ranked(Some(ConnectionRole::Standby)).await);
+        order
+    }
+
+    /// Get standby connections that should be (re)connected to keep
+    /// `warm_standby_count` standbys warm, best-scored first.
+    pub async fn standbys_to_warm(&self) -> Vec<String> {
+        let ranked = self.ranked(Some(ConnectionRole::Standby)).await;
+        let connections = self.connections.read().await;
+        let warm = ranked
+            .iter()
+            .filter_map(|id| connections.get(id))
+            .filter(|c| c.health.state == PooledConnectionState::Connected)
+            .count();
+        let needed = self.config.warm_standby_count.saturating_sub(warm);
+
+        ranked
+            .into_iter()
+            .filter(|id| {
+                connections.get(id).is_some_and(|c| {
+                    c.health.state == PooledConnectionState::Disconnected
+                        && c.backoff.should_retry()
+                        && !c.backoff.max_reached()
+                })
+            })
+            .take(needed)
+            .collect()
+    }
+
+    /// Demote a failed primary and promote the best connected standby.
+    ///
+    /// Returns the promoted standby, if any.
+    pub async fn failover(&self, failed_id: &str) -> Option<String> {
+        let promoted = self
+            .ranked(Some(ConnectionRole::Standby))
+            .await
+            .into_iter()
+            .next()?;
+
+        let mut connections = self.connections.write().await;
+        if connections.get(&promoted)?.health.state != PooledConnectionState::Connected {
+            return None;
+        }
+        if let Some(failed) = connections.get_mut(failed_id) {
+            failed.role = ConnectionRole::Standby;
+        }
+        if let Some(conn) = connections.get_mut(&promoted) {
+            conn.role = ConnectionRole::Primary;
+        }
+        drop(connections);
+
+        self.stats.write().await.failovers += 1;
+        Some(promoted)
+    }
+
+    /// Publish to `target` relays, failing over to standbys on errors.
+    ///
+    /// Relays are tried in [`failover_order`](Self::failover_order) until
+    /// `target` succeed or every connected relay has been tried. Outcomes are
+    /// recorded against each connection's health.
+    pub async fn publish<F, Fut>(&self, target: usize, send: F) -> PublishResult
+    where
+        F: FnMut(String) -> Fut,
+        Fut: Future<Output = Result<(), String>>,
+    {
+        self.publish_cancellable(target, &CancellationToken::new(), send)
+            .await
+    }
+
+    /// Publish like [`publish`](Self::publish) until `cancel` is cancelled.
+    ///
+    /// A cancelled publish tries no further relays and drops the send in
+    /// flight without recording it against the relay's health; the result
+    /// is marked [`cancelled`](PublishResult::cancelled).
+    pub async fn publish_cancellable<F, Fut>(
+        &self,
+        target: usize,
+        cancel: &CancellationToken,
+        mut send: F,
+    ) -> PublishResult
+    where
+        F: FnMut(String) -> Fut,
+        Fut: Future<Output = Result<(), String>>,
+    {
+        let mut result = PublishResult::new(target);
+
+        for id in self.failover_order().await {
+            if result.succeeded.len() >= target {
+                break;
+            }
+            if cancel.is_cancelled() {
+                result.cancelled = true;
+                break;
+            }
+            let connected = self
+                .connections
+                .read()
+                .await
+                .get(&id)
+                .is_some_and(|c| c.health.state == PooledConnectionState::Connected);
+            if !connected {
+                continue;
+            }
+
+            let outcome = tokio::select! {
+                biased;
+                _ = cancel.cancelled() => None,
+                outcome = send(id.clone()) => Some(outcome),
+            };
+            let Some(outcome) = outcome else {
+                result.cancelled = true;
+                break;
+            };
+            match outcome {
+                Ok(()) => {
+                    self.record_success(&id).await;
+                    result.succeeded.push(id);
+                }
+                Err(error) => {
+                    self.record_failure(&id, error.clone()).await;
+                    result.failed.push((id, error));
+                }
+            }
+        }
+
+        if !result.failed.is_empty() && !result.succeeded.is_empty() {
+            let standby_used = {
+                let connections = self.connections.read().await;
+                result.succeeded.iter().any(|id| {
+                    connections
+                        .get(id)
+                        .is_some_and(|c| c.role == ConnectionRole::Standby)
+                })
+            };
+            if standby_used {
+                self.stats.write().await.failovers += 1;
+            }
+        }
+
+        result
+    }
+
     /// Get pool statistics.
     pub async fn stats(&self) -> PoolStats {
         self.stats.read().await.clone()
@@ -562,6 +812,60 @@ impl ConnectionPool {
     }
 }
 
+/// Aggregated outcome of a publish across relays.
+#[derive(Clone, Debug, Default)]
+pub struct PublishResult {
+    /// Number of relays the publish aimed for.
+    pub target: usize,
+    /// Relays that accepted the event.
+    pub succeeded: Vec<String>,
+    /// Relays that rejected the event, with the error.
+    pub failed: Vec<(String, String)>,
+    /// Whether the publish was cancelled before it finished.
+    pub cancelled: bool,
+}
+
+impl PublishResult {
+    /// Create an empty result aiming for `target` relays.
+    pub fn new(target: usize) -> Self {
+        Self {
+            target,
+            ..Default::default()
+        }
+    }
+
+    /// Number of relays that accepted the event (k).
+    pub fn success_count(&self) -> usize {
+        self.succeeded.len()
+    }
+
+    /// Number of relays attempted (n).
+    pub fn attempted(&self) -> usize {
+        self.succeeded.len() + self.failed.len()
+    }
+
+    /// Check if the publish reached its target.
+    pub fn is_complete(&self) -> bool {
+        self.succeeded.len() >= self.target
+    }
+
+    /// Check if at least `k` relays accepted the event.
+    pub fn succeeded_on(&self, k: usize) -> bool {
+        self.succeeded.len() >= k
+    }
+}
+
+impl std::fmt::Display for PublishResult {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f,
+            "succeeded on {} of {} relays",
+            self.success_count(),
+            self.attempted()
+        )
+    }
+}
+
 /// Summary of pool status.
 #[derive(Clone, Debug)]
 pub struct PoolStatus {
@@ -952,6 +1256,216 @@ mod tests {
         assert_eq!(stats.connections_closed, 1);
     }
 
+    // ==================== Failover Tests ====================
+
+    #[test]
+    fn test_connection_health_score_prefers_low_latency() {
+        let mut fast = ConnectionHealth::new();
+        fast.record_ping(50);
+        let mut slow = ConnectionHealth::new();
+        slow.record_ping(800);
+
+        assert!(fast.score() > slow.score());
+
+        slow.record_ping(800);
+        slow.record_failure("timeout".to_string());
+        let before = slow.score();
+        slow.record_failure("timeout".to_string());
+        assert!(slow.score() < before);
+    }
+
+    #[test]
+    fn test_connection_health_rtt_smoothing() {
+        let mut health = ConnectionHealth::new();
+        health.record_ping(100);
+        health.record_ping(200);
+
+        assert_eq!(health.last_rtt_ms, Some(200));
+        assert!((health.avg_rtt_ms.unwrap() - 120.0).abs() < f64::EPSILON);
+    }
+
+    async fn failover_pool() -> ConnectionPool {
+        let pool = ConnectionPool::new(PoolConfig::default());
+        pool.add_connection("primary".to_string(), "wss://a".to_string())
+            .await
+            .unwrap();
+        pool.add_standby("backup_fast".to_string(), "wss://b".to_string())
+            .await
+            .unwrap();
+        pool.add_standby("backup_slow".to_string(), "wss://c".to_string())
+            .await
+            .unwrap();
+        for id in ["primary", "backup_fast", "backup_slow"] {
+            pool.mark_connected(id).await;
+        }
+        pool.record_ping("backup_fast", 40).await;
+        pool.record_ping("backup_slow", 600).await;
+        pool
+    }
+
+    #[tokio::test]
+    async fn test_failover_order_primaries_then_ranked_standbys() {
+        let pool = failover_pool().await;
+
+        assert_eq!(
+            pool.failover_order().await,
+            vec!["primary", "backup_fast", "backup_slow"]
+        );
+
+        pool.mark_disconnected("backup_fast").await;
+        assert_eq!(
+            pool.ranked(Some(ConnectionRole::Standby)).await,
+            vec!["backup_slow", "backup_fast"]
+        );
+    }
+
+    #[tokio::test]
+    async fn test_standbys_to_warm() {
+        let config = PoolConfig {
+            warm_standby_count: 2,
+            ..Default::default()
+        };
+        let pool = ConnectionPool::new(config);
+        pool.add_standby("s1".to_string(), "wss://a".to_string())
+            .await
+            .unwrap();
+        pool.add_standby("s2".to_string(), "wss://b".to_string())
+            .await
+            .unwrap();
+        pool.add_connection("p1".to_string(), "wss://c".to_string())
+            .await
+            .unwrap();
+
+        let mut to_warm = pool.standbys_to_warm().await;
+        to_warm.sort();
+        assert_eq!(to_warm, vec!["s1", "s2"]);
+
+        pool.mark_connected("s1").await;
+        assert_eq!(pool.standbys_to_warm().await, vec!["s2"]);
+
+        pool.mark_connected("s2").await;
+        assert!(pool.standbys_to_warm().await.is_empty());
+    }
+
+    #[tokio::test]
+    async fn test_failover_promotes_best_standby() {
+        let pool = failover_pool().await;
+
+        let promoted = pool.failover("primary").await;
+        assert_eq!(promoted.as_deref(), Some("backup_fast"));
+
+        let primary = pool.get_connection("primary").await.unwrap();
+        assert_eq!(primary.role, ConnectionRole::Standby);
+        let backup = pool.get_connection("backup_fast").await.unwrap();
+        assert_eq!(backup.role, ConnectionRole::Primary);
+        assert_eq!(pool.stats().await.failovers, 1);
+    }
+
+    #[tokio::test]
+    async fn test_failover_without_connected_standby() {
+        let pool = ConnectionPool::new(PoolConfig::default());
+        pool.add_connection("primary".to_string(), "wss://a".to_string())
+            .await
+            .unwrap();
+        pool.add_standby("backup".to_string(), "wss://b".to_string())
+            .await
+            .unwrap();
+
+        assert!(pool.failover("primary").await.is_none());
+    }
+
+    #[tokio::test]
+    async fn test_publish_fails_over_to_standby() {
+        let pool = failover_pool().await;
+
+        let result = pool
+            .publish(1, |id| async move {
+                if id == "primary" {
+                    Err("rate limited".to_string())
+                } else {
+                    Ok(())
+                }
+            })
+            .await;
+
+        assert!(result.is_complete());
+        assert_eq!(result.succeeded, vec!["backup_fast"]);
+        assert_eq!(result.failed.len(), 1);
+        assert_eq!(result.to_string(), "succeeded on 1 of 2 relays");
+        assert_eq!(pool.stats().await.failovers, 1);
+
+        let primary = pool.get_connection("primary").await.unwrap();
+        assert_eq!(primary.health.consecutive_failures, 1);
+    }
+
+    #[tokio::test]
+    async fn test_publish_aggregates_k_of_n() {
+        let pool = failover_pool().await;
+
+        let result = pool
+            .publish(3, |id| async move {
+                if id == "backup_slow" {
+                    Err("offline".to_string())
+                } else {
+                    Ok(())
+                }
+            })
+            .await;
+
+        assert_eq!(result.success_count(), 2);
+        assert_eq!(result.attempted(), 3);
+        assert!(result.succeeded_on(2));
+        assert!(!result.is_complete());
+    }
+
+    #[tokio::test]
+    async fn test_publish_stops_when_cancelled() {
+        let pool = failover_pool().await;
+        let cancel = CancellationToken::new();
+
+        // The primary never answers; cancelling abandons it
+        let result = pool
+            .publish_cancellable(1, &cancel, |id| {
+                let cancel = cancel.clone();
+                async move {
+                    assert_eq!(id, "primary");
+                    cancel.cancel();
+                    std::future::pending::<Result<(), String>>().await
+                }
+            })
+            .await;
+
+        assert!(result.cancelled);
+        assert_eq!(result.attempted(), 0);
+        let primary = pool.get_connection("primary").await.unwrap();
+        assert_eq!(primary.health.consecutive_failures, 0);
+
+        // An already-cancelled publish tries nothing
+        let result = pool
+            .publish_cancellable(1, &cancel, |_| async { Ok(()) })
+            .await;
+        assert!(result.cancelled);
+        assert!(!result.is_complete());
+    }
+
+    #[tokio::test]
+    async fn test_publish_skips_disconnected_relays() {
+        let pool = failover_pool().await;
+        pool.mark_disconnected("primary").await;
+
+        let mut tried = Vec::new();
+        let result = pool
+            .publish(1, |id| {
+                tried.push(id);
+                async { Ok(()) }
+            })
+            .await;
+
+        assert_eq!(tried, vec!["backup_fast"]);
+        assert!(result.is_complete());
+        assert_eq!(pool.stats().await.failovers, 0);
+    }
+
     // ==================== PoolError Tests ====================
 
     #[test]
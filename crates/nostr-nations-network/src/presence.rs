@@ -0,0 +1,334 @@
+//! Player presence and activity indicators.
+//!
+//! Each client periodically announces whether its player is online, taking
+//! their turn, idle, or gone, and whether they are typing. Announcements
+//! travel as ephemeral events of kind [`kinds::PRESENCE`] (or as
+//! [`PeerMessage::Presence`](crate::peer::PeerMessage::Presence) over P2P),
+//! so relays never store them.
+//!
+//! A [`PresenceMap`] collects the latest announcement per player and marks
+//! players as disconnected when they stop announcing.
+
+use nostr_nations_core::events::{kinds, GameAction, GameEvent};
+use nostr_nations_core::types::{GameId, PlayerSlot};
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use ts_rs::TS;
+
+/// Default time between presence announcements.
+pub const DEFAULT_PRESENCE_INTERVAL_MS: u64 = 15_000;
+
+/// Default time without input before a player counts as idle.
+pub const DEFAULT_IDLE_AFTER_MS: u64 = 120_000;
+
+/// Default time without an announcement before a player counts as
+/// disconnected (three missed announcements).
+pub const DEFAULT_PRESENCE_TIMEOUT_MS: u64 = 3 * DEFAULT_PRESENCE_INTERVAL_MS;
+
+/// What a player is doing.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash, Serialize, Deserialize, JsonSchema, TS)]
+#[serde(rename_all = "snake_case")]
+pub enum PresenceStatus {
+    /// At the keyboard, waiting for their turn.
+    Online,
+    /// At the keyboard and taking their turn.
+    InTurn,
+    /// No recent input.
+    Idle,
+    /// Not announcing presence.
+    Disconnected,
+}
+
+impl PresenceStatus {
+    /// Work out the local player's status.
+    pub fn local(is_turn: bool, last_input_ms: u64, now_ms: u64, idle_after_ms: u64) -> Self {
+        if now_ms.saturating_sub(last_input_ms) >= idle_after_ms {
+            PresenceStatus::Idle
+        } else if is_turn {
+            PresenceStatus::InTurn
+        } else {
+            PresenceStatus::Online
+        }
+    }
+}
+
+/// One presence announcement.
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct PresenceUpdate {
+    /// Player's status.
+    pub status: PresenceStatus,
+    /// Whether the player is typing a chat message.
+    #[serde(default)]
+    pub typing: bool,
+}
+
+impl PresenceUpdate {
+    /// Create an update.
+    pub fn new(status: PresenceStatus, typing: bool) -> Self {
+        Self { status, typing }
+    }
+
+    /// Wrap the update in an ephemeral presence event.
+    pub fn to_event(&self, game_id: GameId, player_id: PlayerSlot, turn: u32) -> GameEvent {
+        let content = serde_json::to_string(self).unwrap_or_default();
+        GameEvent::new(
+            game_id,
+            player_id,
+            None,
+            turn,
+            0,
+            GameAction::Extension {
+                kind: kinds::PRESENCE,
+                content,
+            },
+        )
+    }
+
+    /// Read an update from a presence event.
+    pub fn from_event(event: &GameEvent) -> Option<Self> {
+        match &event.action {
+            GameAction::Extension { kind, content } if *kind == kinds::PRESENCE => {
+                serde_json::from_str(content).ok()
+            }
+            _ => None,
+        }
+    }
+}
+
+/// Latest known presence of a player.
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize, JsonSchema, TS)]
+pub struct PresenceEntry {
+    /// Player ID.
+    pub player_id: PlayerSlot,
+    /// Player's status.
+    pub status: PresenceStatus,
+    /// Whether the player is typing.
+    pub typing: bool,
+    /// When the last announcement arrived (milliseconds since the epoch).
+    pub last_seen_ms: u64,
+}
+
+/// A player's presence changed.
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct PresenceChange {
+    /// Player ID.
+    pub player_id: PlayerSlot,
+    /// Status before the change (`None` if first seen).
+    pub previous: Option<PresenceStatus>,
+    /// Status after the change.
+    pub status: PresenceStatus,
+    /// Whether the player is typing.
+    pub typing: bool,
+}
+
+/// Latest presence of every player.
+#[derive(Clone, Debug)]
+pub struct PresenceMap {
+    entries: BTreeMap<PlayerSlot, PresenceEntry>,
+    timeout_ms: u64,
+}
+
+impl Default for PresenceMap {
+    fn default() -> Self {
+        Self::new(DEFAULT_PRESENCE_TIMEOUT_MS)
+    }
+}
+
+impl PresenceMap {
+    /// Create a map that marks players disconnected after `timeout_ms`
+    /// without an announcement.
+    pub fn new(timeout_ms: u64) -> Self {
+        Self {
+            entries: BTreeMap::new(),
+            timeout_ms,
+        }
+    }
+
+    /// Record an announcement. Returns the change, if the status or typing
+    /// indicator changed.
+    pub fn update(
+        &mut self,
+        player_id: PlayerSlot,
+        update: &PresenceUpdate,
+        now_ms: u64,
+    ) -> Option<PresenceChange> {
+        let previous = self
+            .entries
+            .get(&player_id)
+            .map(|entry| (entry.status, entry.typing));
+        self.entries.insert(
+            player_id,
+            PresenceEntry {
+                player_id,
+                status: update.status,
+                typing: update.typing,
+                last_seen_ms: now_ms,
+            },
+        );
+
+        if previous == Some((update.status, update.typing)) {
+            return None;
+        }
+        Some(PresenceChange {
+            player_id,
+            previous: previous.map(|(status, _)| status),
+            status: update.status,
+            typing: update.typing,
+        })
+    }
+
+    /// Mark a player as disconnected. Returns the change, if any.
+    pub fn disconnect(&mut self, player_id: PlayerSlot) -> Option<PresenceChange> {
+        let entry = self.entries.get_mut(&player_id)?;
+        if entry.status == PresenceStatus::Disconnected {
+            return None;
+        }
+        let previous = entry.status;
+        entry.status = PresenceStatus::Disconnected;
+        entry.typing = false;
+        Some(PresenceChange {
+            player_id,
+            previous: Some(previous),
+            status: PresenceStatus::Disconnected,
+            typing: false,
+        })
+    }
+
+    /// Mark players who stopped announcing as disconnected.
+    pub fn expire(&mut self, now_ms: u64) -> Vec<PresenceChange> {
+        let stale: Vec<PlayerSlot> = self
+            .entries
+            .values()
+            .filter(|e| {
+                e.status != PresenceStatus::Disconnected
+                    && now_ms.saturating_sub(e.last_seen_ms) >= self.timeout_ms
+            })
+            .map(|e| e.player_id)
+            .collect();
+        stale
+            .into_iter()
+            .filter_map(|player_id| self.disconnect(player_id))
+            .collect()
+    }
+
+    /// Get a player's presence.
+    pub fn get(&self, player_id: PlayerSlot) -> Option<&PresenceEntry> {
+        self.entries.get(&player_id)
+    }
+
+    /// Get a player's status, `Disconnected` if never seen.
+    pub fn status(&self, player_id: PlayerSlot) -> PresenceStatus {
+        self.get(player_id)
+            .map(|e| e.status)
+            .unwrap_or(PresenceStatus::Disconnected)
+    }
+
+    /// Iterate over all players, in player order.
+    pub fn iter(&self) -> impl Iterator<Item = &PresenceEntry> {
+        self.entries.values()
+    }
+
+    /// Number of players tracked.
+    pub fn len(&self) -> usize {
+        self.entries.len()
+    }
+
+    /// Check if no players are tracked.
+    pub fn is_empty(&self) -> bool {
+        self.entries.is_empty()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // ==================== Status Tests ====================
+
+    #[test]
+    fn test_local_status() {
+        assert_eq!(
+            PresenceStatus::local(true, 1000, 2000, 5000),
+            PresenceStatus::InTurn
+        );
+        assert_eq!(
+            PresenceStatus::local(false, 1000, 2000, 5000),
+            PresenceStatus::Online
+        );
+        assert_eq!(
+            PresenceStatus::local(true, 1000, 6000, 5000),
+            PresenceStatus::Idle
+        );
+    }
+
+    #[test]
+    fn test_update_event_roundtrip() {
+        let update = PresenceUpdate::new(PresenceStatus::InTurn, true);
+        let event = update.to_event(GameId::new("game1"), PlayerSlot(2), 7);
+
+        assert!(event.is_ephemeral());
+        assert!(!event.is_executable());
+        assert_eq!(PresenceUpdate::from_event(&event), Some(update));
+
+        let other = GameEvent::new(
+            GameId::new("game1"),
+            PlayerSlot(2),
+            None,
+            7,
+            0,
+            GameAction::EndTurn,
+        );
+        assert_eq!(PresenceUpdate::from_event(&other), None);
+    }
+
+    // ==================== PresenceMap Tests ====================
+
+    #[test]
+    fn test_update_reports_changes_only() {
+        let mut map = PresenceMap::default();
+        let online = PresenceUpdate::new(PresenceStatus::Online, false);
+
+        let change = map.update(PlayerSlot(1), &online, 100).unwrap();
+        assert_eq!(change.previous, None);
+        assert_eq!(change.status, PresenceStatus::Online);
+
+        // Repeated announcements only refresh the entry
+        assert_eq!(map.update(PlayerSlot(1), &online, 200), None);
+        assert_eq!(map.get(PlayerSlot(1)).unwrap().last_seen_ms, 200);
+
+        let typing = PresenceUpdate::new(PresenceStatus::Online, true);
+        let change = map.update(PlayerSlot(1), &typing, 300).unwrap();
+        assert_eq!(change.previous, Some(PresenceStatus::Online));
+        assert!(change.typing);
+    }
+
+    #[test]
+    fn test_expire_marks_silent_players_disconnected() {
+        let mut map = PresenceMap::new(1000);
+        map.update(
+            PlayerSlot(0),
+            &PresenceUpdate::new(PresenceStatus::Online, false),
+            0,
+        );
+        map.update(
+            PlayerSlot(1),
+            &PresenceUpdate::new(PresenceStatus::InTurn, true),
+            500,
+        );
+
+        let changes = map.expire(1200);
+        assert_eq!(changes.len(), 1);
+        assert_eq!(changes[0].player_id, PlayerSlot(0));
+        assert_eq!(changes[0].status, PresenceStatus::Disconnected);
+        assert_eq!(map.status(PlayerSlot(0)), PresenceStatus::Disconnected);
+        assert_eq!(map.status(PlayerSlot(1)), PresenceStatus::InTurn);
+        assert_eq!(map.status(PlayerSlot(9)), PresenceStatus::Disconnected);
+
+        // Already disconnected players are not reported again
+        assert!(map
+            .expire(5000)
+            .iter()
+            .all(|c| c.player_id == PlayerSlot(1)));
+    }
+}
@@ -2,6 +2,13 @@
 //!
 //! This module provides priority-based event scheduling to ensure
 //! critical events (combat, turn end) are processed before cosmetic updates.
+//!
+//! Under sustained load, lower priorities are protected from starvation in
+//! two ways:
+//! - **Aging**: events waiting longer than `age_promotion_threshold` are
+//!   promoted one level, up to `EventPriority::High`
+//! - **Quotas**: with fair scheduling, each priority may only be served a
+//!   limited number of times in a row before yielding to others
 
 use nostr_nations_core::events::{GameAction, GameEvent};
 use serde::{Deserialize, Serialize};
@@ -30,6 +37,17 @@ impl EventPriority {
         *self as u8
     }
 
+    /// Get the next priority level up, if any.
+    pub fn promoted(&self) -> Option<EventPriority> {
+        match self {
+            EventPriority::Critical => None,
+            EventPriority::High => Some(EventPriority::Critical),
+            EventPriority::Normal => Some(EventPriority::High),
+            EventPriority::Low => Some(EventPriority::Normal),
+            EventPriority::Cosmetic => Some(EventPriority::Low),
+        }
+    }
+
     /// Check if this priority is higher than another.
     pub fn is_higher_than(&self, other: &EventPriority) -> bool {
         self.value() < other.value()
@@ -55,9 +73,18 @@ pub fn event_priority(event: &GameEvent) -> EventPriority {
         // High priority - game state changes and combat
         GameAction::EndTurn => EventPriority::High,
         GameAction::EndGame { .. } => EventPriority::Critical,
+        GameAction::Concede { .. } => EventPriority::Critical,
+        GameAction::RequestPause { .. } => EventPriority::Critical,
+        GameAction::VotePause { .. } => EventPriority::Critical,
+        GameAction::ResumeGame { .. } => EventPriority::Critical,
+        GameAction::SubstitutePlayer { .. } => EventPriority::Critical,
+        GameAction::RevealMap => EventPriority::Critical,
         GameAction::StartGame => EventPriority::High,
         GameAction::AttackUnit { .. } => EventPriority::High,
         GameAction::AttackCity { .. } => EventPriority::High,
+        GameAction::BombardUnit { .. } => EventPriority::High,
+        GameAction::ResolveCapture { .. } => EventPriority::High,
+        GameAction::GiftUnit { .. } => EventPriority::High,
 
         // Normal priority - standard game actions
         GameAction::CreateGame { .. } => EventPriority::Normal,
@@ -67,13 +94,17 @@ pub fn event_priority(event: &GameEvent) -> EventPriority {
         GameAction::SetProduction { .. } => EventPriority::Normal,
         GameAction::BuyItem { .. } => EventPriority::Normal,
         GameAction::SetResearch { .. } => EventPriority::Normal,
+        GameAction::QueueResearch { .. } => EventPriority::Normal,
+        GameAction::SetResearchQueue { .. } => EventPriority::Normal,
 
         // Low priority - unit state changes
         GameAction::FortifyUnit { .. } => EventPriority::Low,
+        GameAction::FortifyUntilHealed { .. } => EventPriority::Low,
         GameAction::SleepUnit { .. } => EventPriority::Low,
         GameAction::WakeUnit { .. } => EventPriority::Low,
         GameAction::DeleteUnit { .. } => EventPriority::Normal,
         GameAction::UpgradeUnit { .. } => EventPriority::Normal,
+        GameAction::ChoosePromotion { .. } => EventPriority::Normal,
 
         // Worker actions
         GameAction::BuildImprovement { .. } => EventPriority::Normal,
@@ -84,16 +115,28 @@ pub fn event_priority(event: &GameEvent) -> EventPriority {
         GameAction::AssignCitizen { .. } => EventPriority::Low,
         GameAction::UnassignCitizen { .. } => EventPriority::Low,
         GameAction::SellBuilding { .. } => EventPriority::Normal,
+        GameAction::BuyTile { .. } => EventPriority::Normal,
 
         // Diplomacy
         GameAction::DeclareWar { .. } => EventPriority::High,
         GameAction::ProposePeace { .. } => EventPriority::Normal,
         GameAction::AcceptPeace { .. } => EventPriority::Normal,
         GameAction::RejectPeace { .. } => EventPriority::Normal,
+        GameAction::ProposeTreaty { .. } => EventPriority::Normal,
+
+        // Trading
+        GameAction::ProposeTrade { .. } => EventPriority::Normal,
+        GameAction::RespondTrade { .. } => EventPriority::Normal,
 
         // Randomness
         GameAction::RequestRandom { .. } => EventPriority::Normal,
         GameAction::ProvideRandom { .. } => EventPriority::Normal,
+
+        // Snapshots only speed up replay, so they can wait
+        GameAction::Snapshot { .. } => EventPriority::Low,
+
+        // Not executed, so nothing waits on them
+        GameAction::Extension { .. } | GameAction::Unrecognized { .. } => EventPriority::Low,
     }
 }
 
@@ -108,19 +151,23 @@ pub struct PrioritizedEvent {
     pub sequence: u64,
     /// Time the event was enqueued.
     pub enqueued_at: u64,
+    /// Priority the event was enqueued with, before any aging.
+    pub original_priority: EventPriority,
+    /// Time the event last changed priority (enqueue or promotion).
+    pub aged_at: u64,
 }
 
 impl PrioritizedEvent {
     /// Create a new prioritized event.
     pub fn new(event: GameEvent, priority: EventPriority, sequence: u64) -> Self {
+        let enqueued_at = now_ms();
         Self {
             event,
             priority,
             sequence,
-            enqueued_at: std::time::SystemTime::now()
-                .duration_since(std::time::UNIX_EPOCH)
-                .map(|d| d.as_millis() as u64)
-                .unwrap_or(0),
+            enqueued_at,
+            original_priority: priority,
+            aged_at: enqueued_at,
         }
     }
 
@@ -167,8 +214,26 @@ pub struct PriorityQueueConfig {
     pub fair_scheduling: bool,
     /// Maximum consecutive events from same priority before yielding.
     pub max_consecutive: usize,
-    /// Age-based promotion threshold (ms).
+    /// Age-based promotion threshold (ms). Zero disables aging.
     pub age_promotion_threshold: u64,
+    /// Per-priority overrides for `max_consecutive`.
+    pub priority_quotas: HashMap<EventPriority, usize>,
+}
+
+impl PriorityQueueConfig {
+    /// Get the consecutive-dequeue quota for a priority.
+    pub fn quota_for(&self, priority: EventPriority) -> usize {
+        self.priority_quotas
+            .get(&priority)
+            .copied()
+            .unwrap_or(self.max_consecutive)
+    }
+
+    /// Set the consecutive-dequeue quota for a priority.
+    pub fn with_quota(mut self, priority: EventPriority, quota: usize) -> Self {
+        self.priority_quotas.insert(priority, quota);
+        self
+    }
 }
 
 impl Default for PriorityQueueConfig {
@@ -178,6 +243,7 @@ impl Default for PriorityQueueConfig {
             fair_scheduling: true,
             max_consecutive: 10,
             age_promotion_threshold: 5000, // 5 seconds
+            priority_quotas: HashMap::new(),
         }
     }
 }
@@ -213,6 +279,33 @@ pub struct PriorityQueueStats {
     pub by_priority: HashMap<EventPriority, u64>,
     /// Priority promotions (due to aging).
     pub promotions: u64,
+    /// Observed wait times by original priority.
+    pub wait_times: HashMap<EventPriority, WaitTimeStats>,
+}
+
+/// Observed queue wait times for one priority level.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct WaitTimeStats {
+    /// Number of events dequeued.
+    pub count: u64,
+    /// Sum of wait times (ms).
+    pub total_ms: u64,
+    /// Longest wait time (ms).
+    pub max_ms: u64,
+}
+
+impl WaitTimeStats {
+    /// Record a wait time.
+    pub fn record(&mut self, wait_ms: u64) {
+        self.count += 1;
+        self.total_ms += wait_ms;
+        self.max_ms = self.max_ms.max(wait_ms);
+    }
+
+    /// Get the average wait time (ms).
+    pub fn average_ms(&self) -> Option<u64> {
+        self.total_ms.checked_div(self.count)
+    }
 }
 
 impl EventPriorityQueue {
@@ -282,24 +375,108 @@ impl EventPriorityQueue {
 
     /// Dequeue the next event based on priority and fair scheduling.
     pub fn dequeue(&mut self) -> Option<GameEvent> {
-        if self.config.fair_scheduling {
+        self.dequeue_at(now_ms())
+    }
+
+    /// Dequeue the next event as of the given time (ms since epoch).
+    ///
+    /// Events that have waited past the aging threshold are promoted first.
+    pub fn dequeue_at(&mut self, now: u64) -> Option<GameEvent> {
+        self.promote_aged(now);
+
+        let prioritized = if self.config.fair_scheduling {
             self.dequeue_fair()
         } else {
             self.dequeue_strict()
+        }?;
+
+        self.stats.events_dequeued += 1;
+        self.stats
+            .wait_times
+            .entry(prioritized.original_priority)
+            .or_default()
+            .record(now.saturating_sub(prioritized.enqueued_at));
+        Some(prioritized.event)
+    }
+
+    /// Promote events that have waited past the aging threshold.
+    ///
+    /// Events are promoted one level per threshold elapsed, but never into
+    /// `Critical`, which is reserved for system events. Returns the number
+    /// of promotions.
+    pub fn promote_aged(&mut self, now: u64) -> usize {
+        let threshold = self.config.age_promotion_threshold;
+        if threshold == 0 {
+            return 0;
+        }
+
+        let is_due = |p: &PrioritizedEvent| {
+            p.priority.value() > EventPriority::High.value()
+                && now.saturating_sub(p.aged_at) >= threshold
+        };
+        let promote = |mut p: PrioritizedEvent| {
+            if let Some(priority) = p.priority.promoted() {
+                p.priority = priority;
+            }
+            p.aged_at = now;
+            p
+        };
+
+        let mut promoted = 0;
+        if self.config.fair_scheduling {
+            // Queues are ordered by `aged_at`, so only the fronts need checking.
+            // Walk from the highest eligible level down so an event moves at
+            // most one level per call.
+            for priority in [
+                EventPriority::Normal,
+                EventPriority::Low,
+                EventPriority::Cosmetic,
+            ] {
+                let mut moved = Vec::new();
+                if let Some(queue) = self.priority_queues.get_mut(&priority) {
+                    while queue.front().is_some_and(is_due) {
+                        if let Some(p) = queue.pop_front() {
+                            moved.push(promote(p));
+                        }
+                    }
+                }
+                if let Some(target) = priority.promoted() {
+                    if let Some(queue) = self.priority_queues.get_mut(&target) {
+                        promoted += moved.len();
+                        queue.extend(moved);
+                    }
+                }
+            }
+        } else if self.heap.iter().any(is_due) {
+            let events = std::mem::take(&mut self.heap).into_vec();
+            self.heap = events
+                .into_iter()
+                .map(|p| {
+                    if is_due(&p) {
+                        promoted += 1;
+                        promote(p)
+                    } else {
+                        p
+                    }
+                })
+                .collect();
         }
+
+        self.stats.promotions += promoted as u64;
+        promoted
     }
 
     /// Strict priority dequeue (always highest priority first).
-    fn dequeue_strict(&mut self) -> Option<GameEvent> {
-        let prioritized = self.heap.pop()?;
-        self.stats.events_dequeued += 1;
-        Some(prioritized.event)
+    fn dequeue_strict(&mut self) -> Option<PrioritizedEvent> {
+        self.heap.pop()
     }
 
     /// Fair scheduling dequeue (prevents starvation).
-    fn dequeue_fair(&mut self) -> Option<GameEvent> {
+    fn dequeue_fair(&mut self) -> Option<PrioritizedEvent> {
         // Check if we should yield to lower priorities
-        let should_yield = self.consecutive_count >= self.config.max_consecutive;
+        let should_yield = self
+            .last_priority
+            .is_some_and(|p| self.consecutive_count >= self.config.quota_for(p));
 
         // Try priorities in order
         let priorities = [
@@ -329,8 +506,7 @@ impl EventPriorityQueue {
                         self.last_priority = Some(priority);
                     }
 
-                    self.stats.events_dequeued += 1;
-                    return Some(prioritized.event);
+                    return Some(prioritized);
                 }
             }
         }
@@ -367,6 +543,22 @@ impl EventPriorityQueue {
         }
     }
 
+    /// Get the most urgent priority of any waiting event.
+    ///
+    /// Pass it to `EventBatcher::flush_hint` so a pending batch is sent
+    /// before an urgent event waits behind it.
+    pub fn highest_priority(&self) -> Option<EventPriority> {
+        if self.config.fair_scheduling {
+            self.priority_queues
+                .iter()
+                .filter(|(_, queue)| !queue.is_empty())
+                .map(|(priority, _)| *priority)
+                .max()
+        } else {
+            self.heap.peek().map(|p| p.priority)
+        }
+    }
+
     /// Get the number of events in the queue.
     pub fn len(&self) -> usize {
         if self.config.fair_scheduling {
@@ -429,6 +621,14 @@ impl EventPriorityQueue {
     }
 }
 
+/// Current time in milliseconds since the Unix epoch.
+fn now_ms() -> u64 {
+    web_time::SystemTime::now()
+        .duration_since(web_time::UNIX_EPOCH)
+        .map(|d| d.as_millis() as u64)
+        .unwrap_or(0)
+}
+
 /// Queue errors.
 #[derive(Clone, Debug)]
 pub enum QueueError {
@@ -452,9 +652,10 @@ impl std::error::Error for QueueError {}
 #[cfg(test)]
 mod tests {
     use super::*;
+    use nostr_nations_core::types::{GameId, PlayerSlot, UnitId};
 
     fn create_event(id: &str, action: GameAction) -> GameEvent {
-        let mut event = GameEvent::new("test_game".to_string(), 0, None, 1, 1, action);
+        let mut event = GameEvent::new(GameId::new("test_game"), PlayerSlot(0), None, 1, 1, action);
         event.id = id.to_string();
         event
     }
@@ -487,8 +688,8 @@ mod tests {
         let attack = create_event(
             "e1",
             GameAction::AttackUnit {
-                attacker_id: 1,
-                defender_id: 2,
+                attacker_id: UnitId(1),
+                defender_id: UnitId(2),
                 random: 0.5,
             },
         );
@@ -500,7 +701,7 @@ mod tests {
         let move_unit = create_event(
             "e3",
             GameAction::MoveUnit {
-                unit_id: 1,
+                unit_id: UnitId(1),
                 path: vec![],
             },
         );
@@ -547,8 +748,8 @@ mod tests {
         let attack = create_event(
             "e1",
             GameAction::AttackUnit {
-                attacker_id: 1,
-                defender_id: 2,
+                attacker_id: UnitId(1),
+                defender_id: UnitId(2),
                 random: 0.5,
             },
         );
@@ -755,6 +956,169 @@ mod tests {
         assert_eq!(stats.by_priority.get(&EventPriority::High), Some(&1));
     }
 
+    // ==================== Aging Tests ====================
+
+    fn aging_queue(fair_scheduling: bool) -> EventPriorityQueue {
+        EventPriorityQueue::new(PriorityQueueConfig {
+            fair_scheduling,
+            age_promotion_threshold: 1000,
+            ..Default::default()
+        })
+    }
+
+    #[test]
+    fn test_priority_promoted() {
+        assert_eq!(EventPriority::Cosmetic.promoted(), Some(EventPriority::Low));
+        assert_eq!(EventPriority::Normal.promoted(), Some(EventPriority::High));
+        assert_eq!(EventPriority::Critical.promoted(), None);
+    }
+
+    #[test]
+    fn test_aging_promotes_waiting_events() {
+        let mut queue = aging_queue(true);
+        queue
+            .enqueue_with_priority(create_event("low", GameAction::EndTurn), EventPriority::Low)
+            .unwrap();
+        let now = now_ms();
+
+        assert_eq!(queue.promote_aged(now), 0);
+        assert_eq!(queue.promote_aged(now + 1000), 1);
+        assert_eq!(queue.count_by_priority(EventPriority::Normal), 1);
+
+        // One level per threshold, never into Critical
+        assert_eq!(queue.promote_aged(now + 2000), 1);
+        assert_eq!(queue.count_by_priority(EventPriority::High), 1);
+        assert_eq!(queue.promote_aged(now + 10_000), 0);
+        assert_eq!(queue.stats().promotions, 2);
+    }
+
+    fn assert_aged_event_beats_fresh_events(fair_scheduling: bool) {
+        let mut queue = aging_queue(fair_scheduling);
+        queue
+            .enqueue_with_priority(
+                create_event("old", GameAction::EndTurn),
+                EventPriority::Cosmetic,
+            )
+            .unwrap();
+
+        // Cosmetic -> Low -> Normal -> High over three thresholds
+        let now = now_ms();
+        for step in 1..=3 {
+            assert_eq!(queue.promote_aged(now + step * 1000), 1);
+        }
+
+        for i in 0..5 {
+            queue
+                .enqueue_with_priority(
+                    create_event(&format!("fresh{}", i), GameAction::EndTurn),
+                    EventPriority::Normal,
+                )
+                .unwrap();
+        }
+
+        assert_eq!(queue.dequeue_at(now).unwrap().id, "old");
+        assert_eq!(queue.stats().promotions, 3);
+    }
+
+    #[test]
+    fn test_aging_prevents_starvation_fair() {
+        assert_aged_event_beats_fresh_events(true);
+    }
+
+    #[test]
+    fn test_aging_prevents_starvation_strict() {
+        assert_aged_event_beats_fresh_events(false);
+    }
+
+    #[test]
+    fn test_aging_disabled() {
+        let mut queue = EventPriorityQueue::new(PriorityQueueConfig {
+            age_promotion_threshold: 0,
+            ..Default::default()
+        });
+        queue
+            .enqueue_with_priority(create_event("low", GameAction::EndTurn), EventPriority::Low)
+            .unwrap();
+
+        assert_eq!(queue.promote_aged(u64::MAX), 0);
+        assert_eq!(queue.count_by_priority(EventPriority::Low), 1);
+    }
+
+    // ==================== Quota Tests ====================
+
+    #[test]
+    fn test_quota_for_defaults_to_max_consecutive() {
+        let config = PriorityQueueConfig {
+            max_consecutive: 7,
+            ..Default::default()
+        }
+        .with_quota(EventPriority::High, 2);
+
+        assert_eq!(config.quota_for(EventPriority::High), 2);
+        assert_eq!(config.quota_for(EventPriority::Normal), 7);
+    }
+
+    #[test]
+    fn test_per_priority_quota() {
+        let config = PriorityQueueConfig {
+            max_consecutive: 10,
+            age_promotion_threshold: 0,
+            ..Default::default()
+        }
+        .with_quota(EventPriority::High, 1);
+        let mut queue = EventPriorityQueue::new(config);
+
+        for i in 0..3 {
+            queue
+                .enqueue_with_priority(
+                    create_event(&format!("high{}", i), GameAction::EndTurn),
+                    EventPriority::High,
+                )
+                .unwrap();
+        }
+        queue
+            .enqueue_with_priority(create_event("low", GameAction::EndTurn), EventPriority::Low)
+            .unwrap();
+
+        // High may only be served once before yielding
+        assert_eq!(queue.dequeue().unwrap().id, "high0");
+        assert_eq!(queue.dequeue().unwrap().id, "low");
+        assert_eq!(queue.dequeue().unwrap().id, "high1");
+    }
+
+    // ==================== WaitTimeStats Tests ====================
+
+    #[test]
+    fn test_wait_time_stats_record() {
+        let mut stats = WaitTimeStats::default();
+        assert_eq!(stats.average_ms(), None);
+
+        stats.record(100);
+        stats.record(300);
+        assert_eq!(stats.count, 2);
+        assert_eq!(stats.max_ms, 300);
+        assert_eq!(stats.average_ms(), Some(200));
+    }
+
+    #[test]
+    fn test_queue_tracks_wait_times_by_original_priority() {
+        let mut queue = aging_queue(true);
+        queue
+            .enqueue_with_priority(create_event("low", GameAction::EndTurn), EventPriority::Low)
+            .unwrap();
+
+        let event = queue.dequeue_at(now_ms() + 1500).unwrap();
+        assert_eq!(event.id, "low");
+
+        let stats = queue.stats().wait_times[&EventPriority::Low];
+        assert_eq!(stats.count, 1);
+        assert!(stats.max_ms >= 1500);
+        assert!(!queue
+            .stats()
+            .wait_times
+            .contains_key(&EventPriority::Normal));
+    }
+
     // ==================== QueueError Tests ====================
 
     #[test]
@@ -135,8 +135,8 @@ impl RandomnessProvider {
         // Generate random value with proof
         let (random_value, proof) = self.generate_random();
 
-        let timestamp = std::time::SystemTime::now()
-            .duration_since(std::time::UNIX_EPOCH)
+        let timestamp = web_time::SystemTime::now()
+            .duration_since(web_time::UNIX_EPOCH)
             .map(|d| d.as_secs())
             .unwrap_or(0);
 
@@ -144,7 +144,7 @@ impl RandomnessProvider {
             request_id,
             random_value,
             proof: Some(proof),
-            provider: "host".to_string(), // Provider ID would be set by caller
+            provider: PeerId::from("host"), // Provider ID would be set by caller
             timestamp,
         }
     }
@@ -259,7 +259,7 @@ impl RandomnessClient {
             pending_requests: HashMap::new(),
             received_responses: HashMap::new(),
             request_counter: 0,
-            player_id: "player".to_string(),
+            player_id: PeerId::from("player"),
         }
     }
 
@@ -284,8 +284,8 @@ impl RandomnessClient {
         self.request_counter += 1;
         let request_id = format!("{}-{}-{}-{}", game_id, turn, sequence, self.request_counter);
 
-        let timestamp = std::time::SystemTime::now()
-            .duration_since(std::time::UNIX_EPOCH)
+        let timestamp = web_time::SystemTime::now()
+            .duration_since(web_time::UNIX_EPOCH)
             .map(|d| d.as_secs())
             .unwrap_or(0);
 
@@ -476,7 +476,7 @@ mod tests {
             game_id: "game-456".to_string(),
             turn: 5,
             sequence: 10,
-            requester: "player1".to_string(),
+            requester: PeerId::from("player1"),
             purpose: RandomnessPurpose::Combat {
                 attacker_id: 100,
                 defender_id: 200,
@@ -500,7 +500,7 @@ mod tests {
             game_id: "game-1".to_string(),
             turn: 1,
             sequence: 1,
-            requester: "player".to_string(),
+            requester: PeerId::from("player"),
             purpose: RandomnessPurpose::BarbarianSpawn,
             timestamp: 0,
         };
@@ -582,7 +582,7 @@ mod tests {
             request_id: "req-123".to_string(),
             random_value: 42,
             proof: None,
-            provider: "host".to_string(),
+            provider: PeerId::from("host"),
             timestamp: 1234567890,
         };
 
@@ -605,7 +605,7 @@ mod tests {
             request_id: "req-456".to_string(),
             random_value: 12345,
             proof: Some(proof),
-            provider: "host".to_string(),
+            provider: PeerId::from("host"),
             timestamp: 0,
         };
 
@@ -689,7 +689,7 @@ mod tests {
             game_id: "game1".to_string(),
             turn: 1,
             sequence: 1,
-            requester: "player1".to_string(),
+            requester: PeerId::from("player1"),
             purpose: RandomnessPurpose::BarbarianSpawn,
             timestamp: 0,
         };
@@ -760,7 +760,7 @@ mod tests {
             game_id: "game1".to_string(),
             turn: 1,
             sequence: 1,
-            requester: "player1".to_string(),
+            requester: PeerId::from("player1"),
             purpose: RandomnessPurpose::BarbarianSpawn,
             timestamp: 0,
         };
@@ -781,7 +781,7 @@ mod tests {
             game_id: "game1".to_string(),
             turn: 1,
             sequence: 1,
-            requester: "player1".to_string(),
+            requester: PeerId::from("player1"),
             purpose: RandomnessPurpose::BarbarianSpawn,
             timestamp: 0,
         };
@@ -819,7 +819,8 @@ mod tests {
 
     #[test]
     fn test_client_with_player_id() {
-        let mut client = RandomnessClient::with_player_id("custom-player".to_string());
+        let mut client =
+            RandomnessClient::with_player_id(crate::peer::PeerId::from("custom-player"));
 
         let request = client.create_request("game", 1, 1, RandomnessPurpose::BarbarianSpawn);
 
@@ -884,7 +885,7 @@ mod tests {
             request_id: "unknown-request".to_string(),
             random_value: 42,
             proof: None,
-            provider: "host".to_string(),
+            provider: PeerId::from("host"),
             timestamp: 0,
         };
 
@@ -909,7 +910,7 @@ mod tests {
             request_id: request.request_id.clone(),
             random_value: 42,
             proof: Some(bad_proof),
-            provider: "host".to_string(),
+            provider: PeerId::from("host"),
             timestamp: 0,
         };
 
@@ -1047,7 +1048,7 @@ mod tests {
             game_id: "game1".to_string(),
             turn: 1,
             sequence: 1,
-            requester: "player1".to_string(),
+            requester: PeerId::from("player1"),
             purpose: RandomnessPurpose::BarbarianSpawn,
             timestamp: 0,
         };
@@ -1070,7 +1071,7 @@ mod tests {
             request_id: "msg-resp".to_string(),
             random_value: 42,
             proof: None,
-            provider: "host".to_string(),
+            provider: PeerId::from("host"),
             timestamp: 0,
         };
 
@@ -1115,7 +1116,7 @@ mod tests {
     fn test_full_randomness_flow() {
         let seed = [42u8; 32];
         let mut provider = RandomnessProvider::new(seed);
-        let mut client = RandomnessClient::with_player_id("player1".to_string());
+        let mut client = RandomnessClient::with_player_id(PeerId::from("player1"));
 
         // Client creates request
         let request = client.create_request(
@@ -0,0 +1,478 @@
+//! Per-recipient redaction at the network boundary.
+//!
+//! A full client holds the complete game, including units and cities other
+//! players must not see. Rather than trusting every send site to call
+//! [`redact_event_for_player`], outgoing game events and sync responses go
+//! through a [`RedactionGate`]. The gate keeps a [`VisibilityFilter`] per
+//! recipient, keyed by npub. It drops events the recipient can't see and
+//! redacts the rest, so the bytes a peer receives never carry more than
+//! their player may know. A recipient the gate doesn't know gets an error
+//! rather than unfiltered data. State snapshots answering a light client's
+//! query are already filtered for one player, so the gate only lets them
+//! through to that player. Recipients are matched by key, so a player
+//! listed by hex key is found under their npub and the other way round.
+//! Before the game starts nothing is hidden, so the gate lets everything
+//! through to anyone, including players still joining.
+//!
+//! [`PeerManager`](crate::peer::PeerManager) owns a gate and runs every
+//! outgoing message through it before sequencing, once
+//! [`PeerManager::update_redaction`](crate::peer::PeerManager::update_redaction)
+//! has been called, and refuses to send game state before then. Refresh
+//! the gate whenever the authoritative state changes. Already sequenced
+//! messages are refused.
+
+use crate::peer::PeerMessage;
+use crate::signing::decode_pubkey;
+use crate::sync::SyncResponse;
+use nostr_nations_core::events::GameEvent;
+use nostr_nations_core::game_state::{GamePhase, GameState};
+use nostr_nations_core::types::Npub;
+use nostr_nations_core::visibility::{redact_event_for_player, FilteredEvent, VisibilityFilter};
+use std::collections::HashMap;
+
+/// Errors from redacting outgoing data.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum RedactionError {
+    /// The recipient's npub doesn't belong to a player in the game.
+    UnknownRecipient(String),
+    /// A state snapshot was addressed to someone other than the player it
+    /// was filtered for.
+    MisaddressedState(String),
+    /// The message was sequenced before it reached the gate.
+    AlreadySequenced,
+    /// Game state was sent before a redaction gate was set.
+    NoGate,
+    /// An event could not be (de)serialized.
+    Serialization(String),
+}
+
+impl std::fmt::Display for RedactionError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            RedactionError::UnknownRecipient(npub) => write!(f, "Unknown recipient: {}", npub),
+            RedactionError::MisaddressedState(npub) => {
+                write!(f, "State snapshot is not for {}", npub)
+            }
+            RedactionError::AlreadySequenced => {
+                write!(f, "Message was sequenced before redaction")
+            }
+            RedactionError::NoGate => write!(f, "No redaction gate set for game state"),
+            RedactionError::Serialization(msg) => write!(f, "Serialization error: {}", msg),
+        }
+    }
+}
+
+impl std::error::Error for RedactionError {}
+
+impl From<serde_json::Error> for RedactionError {
+    fn from(e: serde_json::Error) -> Self {
+        RedactionError::Serialization(e.to_string())
+    }
+}
+
+/// Redacts everything a full client sends, per recipient.
+#[derive(Clone, Debug, Default)]
+pub struct RedactionGate {
+    /// Visibility of each recipient, keyed by npub.
+    filters: HashMap<Npub, VisibilityFilter>,
+    /// Whether the game is still being set up, with nothing to hide.
+    setup: bool,
+}
+
+impl RedactionGate {
+    /// Create a gate that knows no recipients yet.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Create a gate for every player in a game.
+    pub fn from_game_state(game: &GameState) -> Self {
+        let mut gate = Self::new();
+        gate.update(game);
+        gate
+    }
+
+    /// Recompute every player's visibility from the authoritative state.
+    pub fn update(&mut self, game: &GameState) {
+        self.setup = game.phase == GamePhase::Setup;
+        self.filters.clear();
+        for player in &game.players {
+            let mut filter = VisibilityFilter::new(player.id);
+            filter.update_from_game_state(game);
+            self.filters.insert(player.pubkey.clone(), filter);
+        }
+    }
+
+    /// Visibility filter of a recipient.
+    pub fn filter(&self, recipient: &str) -> Option<&VisibilityFilter> {
+        self.filters.get(recipient).or_else(|| {
+            self.filters
+                .iter()
+                .find(|(npub, _)| same_pubkey(npub.as_str(), recipient))
+                .map(|(_, filter)| filter)
+        })
+    }
+
+    /// Number of recipients the gate knows.
+    pub fn recipient_count(&self) -> usize {
+        self.filters.len()
+    }
+
+    fn filter_for(&self, recipient: &str) -> Result<&VisibilityFilter, RedactionError> {
+        self.filter(recipient)
+            .ok_or_else(|| RedactionError::UnknownRecipient(recipient.to_string()))
+    }
+
+    /// Redact an event for a recipient.
+    ///
+    /// Returns `None` if the event is hidden from them.
+    pub fn redact_event(
+        &self,
+        recipient: &str,
+        event: &GameEvent,
+    ) -> Result<Option<GameEvent>, RedactionError> {
+        let filter = self.filter_for(recipient)?;
+        Ok(match filter.filter_event(event) {
+            FilteredEvent::FullyVisible(event) | FilteredEvent::PartiallyVisible(event) => {
+                Some(redact_event_for_player(&event, filter.player_id(), filter))
+            }
+            FilteredEvent::Hidden => None,
+        })
+    }
+
+    /// Redact the events of a sync response for a recipient.
+    pub fn redact_sync_response(
+        &self,
+        recipient: &str,
+        response: &SyncResponse,
+    ) -> Result<SyncResponse, RedactionError> {
+        let mut events = Vec::with_capacity(response.events.len());
+        for event in &response.events {
+            events.extend(self.redact_event(recipient, event)?);
+        }
+        Ok(SyncResponse {
+            events,
+            ..response.clone()
+        })
+    }
+
+    /// Redact a peer message for a recipient.
+    ///
+    /// Game events and sync responses are redacted, and state responses
+    /// are only sent to the player they were filtered for; other messages
+    /// carry no game state and pass unchanged. Returns `None` if nothing in the
+    /// message may be sent.
+    pub fn redact_message(
+        &self,
+        recipient: &str,
+        message: &PeerMessage,
+    ) -> Result<Option<PeerMessage>, RedactionError> {
+        if self.setup && !matches!(message, PeerMessage::Sequenced { .. }) {
+            return Ok(Some(message.clone()));
+        }
+        match message {
+            PeerMessage::GameEvent { event_json } => {
+                let event: GameEvent = serde_json::from_str(event_json)?;
+                match self.redact_event(recipient, &event)? {
+                    Some(event) => Ok(Some(PeerMessage::GameEvent {
+                        event_json: serde_json::to_string(&event)?,
+                    })),
+                    None => Ok(None),
+                }
+            }
+            PeerMessage::SyncResponse { events_json } => {
+                let mut redacted = Vec::with_capacity(events_json.len());
+                for event_json in events_json {
+                    let event: GameEvent = serde_json::from_str(event_json)?;
+                    if let Some(event) = self.redact_event(recipient, &event)? {
+                        redacted.push(serde_json::to_string(&event)?);
+                    }
+                }
+                Ok(Some(PeerMessage::SyncResponse {
+                    events_json: redacted,
+                }))
+            }
+            PeerMessage::StateResponse { response } => {
+                self.filter_for(recipient)?;
+                if !same_pubkey(response.pubkey.as_str(), recipient) {
+                    return Err(RedactionError::MisaddressedState(recipient.to_string()));
+                }
+                Ok(Some(message.clone()))
+            }
+            // Redacting inside a sequenced message would leave gaps in the
+            // peer's sequence numbers
+            PeerMessage::Sequenced { .. } => Err(RedactionError::AlreadySequenced),
+            other => {
+                // Make sure the recipient is known even for messages without
+                // game state
+                self.filter_for(recipient)?;
+                Ok(Some(other.clone()))
+            }
+        }
+    }
+
+    /// Redact a peer message and serialize it for the wire.
+    pub fn encode(
+        &self,
+        recipient: &str,
+        message: &PeerMessage,
+    ) -> Result<Option<Vec<u8>>, RedactionError> {
+        match self.redact_message(recipient, message)? {
+            Some(message) => Ok(Some(message.to_bytes()?)),
+            None => Ok(None),
+        }
+    }
+}
+
+/// Check whether two keys are the same, whether written as hex or npub.
+fn same_pubkey(a: &str, b: &str) -> bool {
+    a == b
+        || matches!(
+            (decode_pubkey(a), decode_pubkey(b)),
+            (Ok(a), Ok(b)) if a == b
+        )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::signing::SigningKey;
+    use crate::sync::{StateQuery, SyncResponder};
+    use nostr_nations_core::events::GameAction;
+    use nostr_nations_core::hex::HexCoord;
+    use nostr_nations_core::map::Map;
+    use nostr_nations_core::player::{Civilization, Player};
+    use nostr_nations_core::settings::GameSettings;
+    use nostr_nations_core::terrain::Terrain;
+    use nostr_nations_core::types::{GameId, PlayerSlot, UnitId};
+    use nostr_nations_core::unit::{Unit, UnitType};
+
+    const VIEWER: &str = "npub_viewer";
+    const ENEMY: &str = "npub_enemy";
+
+    fn create_game() -> GameState {
+        let settings = GameSettings::new("Test".to_string());
+        let mut game = GameState::new(GameId::new("game1"), settings, [0u8; 32]);
+        for (id, pubkey) in [(0, VIEWER), (1, ENEMY)] {
+            game.add_player(Player::new(
+                PlayerSlot(id),
+                Npub(pubkey.to_string()),
+                format!("Player {}", id),
+                Civilization::generic(),
+            ))
+            .unwrap();
+        }
+        game.start().unwrap();
+        game.map = Map::filled(20, 20, Terrain::Grassland);
+        game
+    }
+
+    fn add_unit(game: &mut GameState, owner: PlayerSlot, position: HexCoord) -> UnitId {
+        let id = game.allocate_unit_id();
+        game.units
+            .insert(id, Unit::new(id, owner, UnitType::Warrior, position));
+        id
+    }
+
+    fn move_event(unit_id: UnitId, path: Vec<HexCoord>) -> GameEvent {
+        GameEvent::new(
+            GameId::new("game1"),
+            PlayerSlot(1),
+            None,
+            1,
+            1,
+            GameAction::MoveUnit { unit_id, path },
+        )
+    }
+
+    fn coord_json(coord: HexCoord) -> String {
+        serde_json::to_string(&coord).unwrap()
+    }
+
+    /// Check the wire bytes for a JSON fragment, ignoring the escaping of
+    /// event JSON nested in a message.
+    fn contains(bytes: &[u8], needle: &str) -> bool {
+        String::from_utf8_lossy(bytes)
+            .replace('\\', "")
+            .contains(needle)
+    }
+
+    // ==================== Event Tests ====================
+
+    #[test]
+    fn test_hidden_unit_positions_never_sent() {
+        let mut game = create_game();
+        add_unit(&mut game, PlayerSlot(0), HexCoord::new(2, 2));
+        let hidden = add_unit(&mut game, PlayerSlot(1), HexCoord::new(15, 15));
+        let gate = RedactionGate::from_game_state(&game);
+
+        let secret = [HexCoord::new(16, 15), HexCoord::new(17, 15)];
+        let message = PeerMessage::GameEvent {
+            event_json: serde_json::to_string(&move_event(hidden, secret.to_vec())).unwrap(),
+        };
+
+        // The owner gets the move, the viewer gets nothing
+        let to_enemy = gate.encode(ENEMY, &message).unwrap().unwrap();
+        assert!(contains(&to_enemy, &coord_json(secret[1])));
+        assert_eq!(gate.encode(VIEWER, &message).unwrap(), None);
+    }
+
+    #[test]
+    fn test_partially_visible_move_keeps_only_visible_tiles() {
+        let mut game = create_game();
+        add_unit(&mut game, PlayerSlot(0), HexCoord::new(10, 10));
+        let hidden = add_unit(&mut game, PlayerSlot(1), HexCoord::new(15, 10));
+        let gate = RedactionGate::from_game_state(&game);
+
+        let path = vec![
+            HexCoord::new(14, 10),
+            HexCoord::new(13, 10),
+            HexCoord::new(12, 10),
+        ];
+        let message = PeerMessage::GameEvent {
+            event_json: serde_json::to_string(&move_event(hidden, path.clone())).unwrap(),
+        };
+
+        let bytes = gate.encode(VIEWER, &message).unwrap().unwrap();
+        assert!(contains(&bytes, &coord_json(path[2])));
+        assert!(!contains(&bytes, &coord_json(path[0])));
+        assert!(!contains(&bytes, &coord_json(path[1])));
+    }
+
+    // ==================== Sync Tests ====================
+
+    #[test]
+    fn test_sync_response_drops_hidden_events() {
+        let mut game = create_game();
+        add_unit(&mut game, PlayerSlot(0), HexCoord::new(2, 2));
+        let hidden = add_unit(&mut game, PlayerSlot(1), HexCoord::new(15, 15));
+        let gate = RedactionGate::from_game_state(&game);
+
+        let secret = HexCoord::new(16, 16);
+        let end_turn = GameEvent::new(
+            GameId::new("game1"),
+            PlayerSlot(1),
+            None,
+            1,
+            2,
+            GameAction::EndTurn,
+        );
+        let events = vec![move_event(hidden, vec![secret]), end_turn];
+
+        let response = SyncResponse {
+            game_id: GameId::new("game1"),
+            has_more: false,
+            events: events.clone(),
+            current_turn: 1,
+            chain_hash: None,
+        };
+        let redacted = gate.redact_sync_response(VIEWER, &response).unwrap();
+        assert_eq!(redacted.events.len(), 1);
+        assert!(matches!(redacted.events[0].action, GameAction::EndTurn));
+
+        let message = PeerMessage::SyncResponse {
+            events_json: events
+                .iter()
+                .map(|e| serde_json::to_string(e).unwrap())
+                .collect(),
+        };
+        let bytes = gate.encode(VIEWER, &message).unwrap().unwrap();
+        assert!(!contains(&bytes, &coord_json(secret)));
+        assert!(contains(
+            &gate.encode(ENEMY, &message).unwrap().unwrap(),
+            &coord_json(secret)
+        ));
+    }
+
+    #[test]
+    fn test_state_response_only_sent_to_its_player() {
+        let mut game = create_game();
+        let viewer = SigningKey::from_secret_bytes(&[1u8; 32]).unwrap();
+        game.players[0].pubkey = viewer.npub();
+        let gate = RedactionGate::from_game_state(&game);
+        let mut responder = SyncResponder::new(GameId::new("game1"));
+        let nonce = responder.issue_state_nonce();
+        let query = StateQuery::signed(GameId::new("game1"), &viewer, nonce, 100);
+        let response = responder.respond_state(&query, &game, 100).unwrap();
+        let message = PeerMessage::StateResponse {
+            response: Box::new(response),
+        };
+
+        assert!(gate
+            .encode(viewer.npub().as_str(), &message)
+            .unwrap()
+            .is_some());
+        // The same player, addressed by hex key
+        let hex: String = viewer
+            .public_key()
+            .iter()
+            .map(|b| format!("{:02x}", b))
+            .collect();
+        assert!(gate.encode(&hex, &message).unwrap().is_some());
+        assert_eq!(
+            gate.encode(ENEMY, &message),
+            Err(RedactionError::MisaddressedState(ENEMY.to_string()))
+        );
+    }
+
+    // ==================== Recipient Tests ====================
+
+    #[test]
+    fn test_unknown_recipient_and_sequenced_messages_refused() {
+        let game = create_game();
+        let gate = RedactionGate::from_game_state(&game);
+        assert_eq!(gate.recipient_count(), 2);
+
+        let ping = PeerMessage::Ping { timestamp: 1 };
+        assert_eq!(
+            gate.encode("npub_stranger", &ping),
+            Err(RedactionError::UnknownRecipient(
+                "npub_stranger".to_string()
+            ))
+        );
+        assert!(gate.encode(VIEWER, &ping).unwrap().is_some());
+
+        let sequenced = PeerMessage::Sequenced {
+            seq: 1,
+            message: Box::new(ping),
+        };
+        assert_eq!(
+            gate.encode(VIEWER, &sequenced),
+            Err(RedactionError::AlreadySequenced)
+        );
+    }
+
+    #[test]
+    fn test_nothing_hidden_before_the_game_starts() {
+        let settings = GameSettings::new("Test".to_string());
+        let mut game = GameState::new(GameId::new("game1"), settings, [0u8; 32]);
+        game.add_player(Player::new(
+            PlayerSlot(0),
+            Npub(VIEWER.to_string()),
+            "Host".to_string(),
+            Civilization::generic(),
+        ))
+        .unwrap();
+        let mut gate = RedactionGate::from_game_state(&game);
+
+        // A player still joining can sync the lobby
+        let sync = PeerMessage::SyncResponse {
+            events_json: vec![serde_json::to_string(&move_event(UnitId(1), Vec::new())).unwrap()],
+        };
+        assert!(gate.encode("npub_joining", &sync).unwrap().is_some());
+
+        game.add_player(Player::new(
+            PlayerSlot(1),
+            Npub(ENEMY.to_string()),
+            "Guest".to_string(),
+            Civilization::generic(),
+        ))
+        .unwrap();
+        game.start().unwrap();
+        gate.update(&game);
+        assert_eq!(
+            gate.encode("npub_joining", &sync),
+            Err(RedactionError::UnknownRecipient("npub_joining".to_string()))
+        );
+    }
+}
@@ -0,0 +1,488 @@
+//! Websocket relay client protocol.
+//!
+//! A light client has no P2P connections; it talks to remote relays over
+//! websockets using NIP-01 messages. [`RelayClient`] is the protocol state
+//! without any I/O: it queues outgoing frames, tracks subscriptions and
+//! parses incoming frames. The socket itself is supplied by the platform,
+//! e.g. [`BrowserRelaySocket`](super::websocket::BrowserRelaySocket) in a
+//! wasm32 build.
+//!
+//! Bulk queries can be tied to a [`CancellationToken`] with
+//! [`RelayClient::subscribe_with_cancel`]. Once the token is cancelled the
+//! subscription is closed on the relay and any further events for it are
+//! dropped.
+//!
+//! # Example
+//!
+//! ```rust,ignore
+//! let mut client = RelayClient::new("wss://relay.example.com");
+//! let sub_id = client.subscribe(vec![Filter::game(game_id)]);
+//! for frame in client.take_outgoing() {
+//!     socket.send(&frame);
+//! }
+//! while let Some(frame) = socket.recv() {
+//!     if let Some(RelayMessage::Event { event, .. }) = client.handle_frame(&frame)? {
+//!         local_relay.publish(&event)?;
+//!     }
+//! }
+//! ```
+
+use crate::cancel::CancellationToken;
+use crate::relay::filter::Filter;
+use nostr_nations_core::events::GameEvent;
+use serde_json::Value;
+use std::collections::{HashMap, VecDeque};
+
+/// A message from a client to a relay.
+#[derive(Clone, Debug)]
+pub enum ClientMessage {
+    /// Publish an event: `["EVENT", <event>]`.
+    Event(Box<GameEvent>),
+    /// Open a subscription: `["REQ", <sub_id>, <filter>...]`.
+    Req {
+        sub_id: String,
+        filters: Vec<Filter>,
+    },
+    /// Close a subscription: `["CLOSE", <sub_id>]`.
+    Close(String),
+}
+
+impl ClientMessage {
+    /// Encode as a NIP-01 JSON array.
+    pub fn to_json(&self) -> Result<String, RelayClientError> {
+        let value = match self {
+            ClientMessage::Event(event) => Value::Array(vec![
+                "EVENT".into(),
+                serde_json::to_value(event).map_err(RelayClientError::from)?,
+            ]),
+            ClientMessage::Req { sub_id, filters } => {
+                let mut items = vec!["REQ".into(), sub_id.as_str().into()];
+                for filter in filters {
+                    items.push(serde_json::to_value(filter).map_err(RelayClientError::from)?);
+                }
+                Value::Array(items)
+            }
+            ClientMessage::Close(sub_id) => {
+                Value::Array(vec!["CLOSE".into(), sub_id.as_str().into()])
+            }
+        };
+        Ok(value.to_string())
+    }
+}
+
+/// A message from a relay to a client.
+#[derive(Clone, Debug)]
+pub enum RelayMessage {
+    /// An event matching a subscription.
+    Event {
+        sub_id: String,
+        event: Box<GameEvent>,
+    },
+    /// Result of publishing an event.
+    Ok {
+        event_id: String,
+        accepted: bool,
+        message: String,
+    },
+    /// End of stored events for a subscription.
+    Eose(String),
+    /// The relay closed a subscription.
+    Closed { sub_id: String, message: String },
+    /// Human-readable notice.
+    Notice(String),
+}
+
+impl RelayMessage {
+    /// Parse a NIP-01 JSON array.
+    pub fn from_json(json: &str) -> Result<Self, RelayClientError> {
+        let value: Value = serde_json::from_str(json)?;
+        let items = value
+            .as_array()
+            .ok_or_else(|| RelayClientError::Malformed("expected a JSON array".to_string()))?;
+        let tag = str_at(items, 0)?;
+
+        match tag {
+            "EVENT" => Ok(RelayMessage::Event {
+                sub_id: str_at(items, 1)?.to_string(),
+                event: Box::new(serde_json::from_value(
+                    items.get(2).cloned().unwrap_or(Value::Null),
+                )?),
+            }),
+            "OK" => Ok(RelayMessage::Ok {
+                event_id: str_at(items, 1)?.to_string(),
+                accepted: items.get(2).and_then(Value::as_bool).unwrap_or(false),
+                message: str_at(items, 3).unwrap_or_default().to_string(),
+            }),
+            "EOSE" => Ok(RelayMessage::Eose(str_at(items, 1)?.to_string())),
+            "CLOSED" => Ok(RelayMessage::Closed {
+                sub_id: str_at(items, 1)?.to_string(),
+                message: str_at(items, 2).unwrap_or_default().to_string(),
+            }),
+            "NOTICE" => Ok(RelayMessage::Notice(str_at(items, 1)?.to_string())),
+            other => Err(RelayClientError::Malformed(format!(
+                "unknown message type {}",
+                other
+            ))),
+        }
+    }
+}
+
+fn str_at(items: &[Value], index: usize) -> Result<&str, RelayClientError> {
+    items
+        .get(index)
+        .and_then(Value::as_str)
+        .ok_or_else(|| RelayClientError::Malformed(format!("missing string at {}", index)))
+}
+
+/// Relay client errors.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum RelayClientError {
+    /// The frame is not a valid NIP-01 message.
+    Malformed(String),
+    /// An event could not be (de)serialized.
+    Serialization(String),
+    /// The websocket failed or is closed.
+    Socket(String),
+}
+
+impl std::fmt::Display for RelayClientError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            RelayClientError::Malformed(msg) => write!(f, "Malformed relay message: {}", msg),
+            RelayClientError::Serialization(msg) => write!(f, "Serialization error: {}", msg),
+            RelayClientError::Socket(msg) => write!(f, "Websocket error: {}", msg),
+        }
+    }
+}
+
+impl std::error::Error for RelayClientError {}
+
+impl From<serde_json::Error> for RelayClientError {
+    fn from(e: serde_json::Error) -> Self {
+        RelayClientError::Serialization(e.to_string())
+    }
+}
+
+/// State of one subscription.
+#[derive(Clone, Debug)]
+pub struct RelaySubscription {
+    /// Filters sent with the REQ.
+    pub filters: Vec<Filter>,
+    /// Whether the relay has sent all stored events.
+    pub eose: bool,
+    /// Events received so far.
+    pub received: usize,
+    /// Closes the subscription when cancelled.
+    pub cancel: Option<CancellationToken>,
+}
+
+/// NIP-01 protocol state for one remote relay.
+#[derive(Debug)]
+pub struct RelayClient {
+    url: String,
+    outgoing: VecDeque<String>,
+    subscriptions: HashMap<String, RelaySubscription>,
+    /// Events published but not yet acknowledged, by event ID.
+    pending_ok: HashMap<String, GameEvent>,
+    next_sub: u64,
+}
+
+impl RelayClient {
+    /// Create a client for a relay URL.
+    pub fn new(url: &str) -> Self {
+        Self {
+            url: url.to_string(),
+            outgoing: VecDeque::new(),
+            subscriptions: HashMap::new(),
+            pending_ok: HashMap::new(),
+            next_sub: 0,
+        }
+    }
+
+    /// Relay URL.
+    pub fn url(&self) -> &str {
+        &self.url
+    }
+
+    /// Queue a REQ and return the new subscription ID.
+    pub fn subscribe(&mut self, filters: Vec<Filter>) -> String {
+        self.open_subscription(filters, None)
+    }
+
+    /// Queue a REQ that is closed once `cancel` is cancelled, and return
+    /// the new subscription ID.
+    pub fn subscribe_with_cancel(
+        &mut self,
+        filters: Vec<Filter>,
+        cancel: CancellationToken,
+    ) -> String {
+        self.open_subscription(filters, Some(cancel))
+    }
+
+    fn open_subscription(
+        &mut self,
+        filters: Vec<Filter>,
+        cancel: Option<CancellationToken>,
+    ) -> String {
+        self.next_sub += 1;
+        let sub_id = format!("nn{}", self.next_sub);
+        self.queue(ClientMessage::Req {
+            sub_id: sub_id.clone(),
+            filters: filters.clone(),
+        });
+        self.subscriptions.insert(
+            sub_id.clone(),
+            RelaySubscription {
+                filters,
+                eose: false,
+                received: 0,
+                cancel,
+            },
+        );
+        sub_id
+    }
+
+    /// Queue a CLOSE for a subscription.
+    pub fn unsubscribe(&mut self, sub_id: &str) -> bool {
+        if self.subscriptions.remove(sub_id).is_none() {
+            return false;
+        }
+        self.queue(ClientMessage::Close(sub_id.to_string()));
+        true
+    }
+
+    /// Queue an event for publishing.
+    pub fn publish(&mut self, event: &GameEvent) {
+        self.pending_ok.insert(event.id.clone(), event.clone());
+        self.queue(ClientMessage::Event(Box::new(event.clone())));
+    }
+
+    /// Queue the REQs for every open subscription again, e.g. after the
+    /// socket reconnects. Unacknowledged events are re-sent too.
+    pub fn resubscribe(&mut self) {
+        self.close_cancelled();
+        let mut subs: Vec<(String, Vec<Filter>)> = self
+            .subscriptions
+            .iter_mut()
+            .map(|(id, sub)| {
+                sub.eose = false;
+                (id.clone(), sub.filters.clone())
+            })
+            .collect();
+        subs.sort_by(|a, b| a.0.cmp(&b.0));
+        for (sub_id, filters) in subs {
+            self.queue(ClientMessage::Req { sub_id, filters });
+        }
+
+        let mut pending: Vec<GameEvent> = self.pending_ok.values().cloned().collect();
+        pending.sort_by_key(|e| e.timestamp);
+        for event in pending {
+            self.queue(ClientMessage::Event(Box::new(event)));
+        }
+    }
+
+    fn queue(&mut self, message: ClientMessage) {
+        match message.to_json() {
+            Ok(frame) => self.outgoing.push_back(frame),
+            Err(e) => tracing::warn!(url = %self.url, error = %e, "dropping unencodable message"),
+        }
+    }
+
+    /// Queue a CLOSE for every subscription whose token was cancelled.
+    fn close_cancelled(&mut self) {
+        let mut cancelled: Vec<String> = self
+            .subscriptions
+            .iter()
+            .filter(|(_, sub)| sub.cancel.as_ref().is_some_and(|c| c.is_cancelled()))
+            .map(|(id, _)| id.clone())
+            .collect();
+        cancelled.sort();
+        for sub_id in cancelled {
+            self.unsubscribe(&sub_id);
+        }
+    }
+
+    /// Take every frame waiting to be sent.
+    ///
+    /// Subscriptions cancelled since the last call are closed first.
+    pub fn take_outgoing(&mut self) -> Vec<String> {
+        self.close_cancelled();
+        self.outgoing.drain(..).collect()
+    }
+
+    /// Number of frames waiting to be sent.
+    pub fn outgoing_len(&self) -> usize {
+        self.outgoing.len()
+    }
+
+    /// Parse an incoming frame and update subscription state.
+    ///
+    /// Returns `None` for events on subscriptions that are no longer open,
+    /// including ones that have been cancelled.
+    pub fn handle_frame(&mut self, frame: &str) -> Result<Option<RelayMessage>, RelayClientError> {
+        self.close_cancelled();
+        let message = RelayMessage::from_json(frame)?;
+        match &message {
+            RelayMessage::Event { sub_id, .. } => match self.subscriptions.get_mut(sub_id) {
+                Some(sub) => sub.received += 1,
+                None => return Ok(None),
+            },
+            RelayMessage::Eose(sub_id) => {
+                if let Some(sub) = self.subscriptions.get_mut(sub_id) {
+                    sub.eose = true;
+                }
+            }
+            RelayMessage::Closed { sub_id, .. } => {
+                self.subscriptions.remove(sub_id);
+            }
+            RelayMessage::Ok { event_id, .. } => {
+                self.pending_ok.remove(event_id);
+            }
+            RelayMessage::Notice(_) => {}
+        }
+        Ok(Some(message))
+    }
+
+    /// Get a subscription's state.
+    pub fn subscription(&self, sub_id: &str) -> Option<&RelaySubscription> {
+        self.subscriptions.get(sub_id)
+    }
+
+    /// Number of published events not yet acknowledged.
+    pub fn pending_count(&self) -> usize {
+        self.pending_ok.len()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use nostr_nations_core::events::GameAction;
+    use nostr_nations_core::types::{GameId, PlayerSlot};
+
+    fn test_event(id: &str) -> GameEvent {
+        let mut event = GameEvent::new(
+            GameId::new("g1"),
+            PlayerSlot(0),
+            None,
+            1,
+            1,
+            GameAction::EndTurn,
+        );
+        event.id = id.to_string();
+        event.timestamp = 10;
+        event
+    }
+
+    // ==================== Message Encoding Tests ====================
+
+    #[test]
+    fn test_client_message_encoding() {
+        let req = ClientMessage::Req {
+            sub_id: "s1".to_string(),
+            filters: vec![Filter::kinds(vec![1])],
+        };
+        assert_eq!(req.to_json().unwrap(), r#"["REQ","s1",{"kinds":[1]}]"#);
+        assert_eq!(
+            ClientMessage::Close("s1".to_string()).to_json().unwrap(),
+            r#"["CLOSE","s1"]"#
+        );
+
+        let json = ClientMessage::Event(Box::new(test_event("e1")))
+            .to_json()
+            .unwrap();
+        assert!(json.starts_with(r#"["EVENT",{"#));
+    }
+
+    #[test]
+    fn test_relay_message_parsing() {
+        let event_json = serde_json::to_string(&test_event("e1")).unwrap();
+        let frame = format!(r#"["EVENT","s1",{}]"#, event_json);
+        match RelayMessage::from_json(&frame).unwrap() {
+            RelayMessage::Event { sub_id, event } => {
+                assert_eq!(sub_id, "s1");
+                assert_eq!(event.id, "e1");
+            }
+            other => panic!("unexpected {:?}", other),
+        }
+
+        assert!(matches!(
+            RelayMessage::from_json(r#"["OK","e1",true,""]"#).unwrap(),
+            RelayMessage::Ok { accepted: true, .. }
+        ));
+        assert!(matches!(
+            RelayMessage::from_json(r#"["NOTICE","slow down"]"#).unwrap(),
+            RelayMessage::Notice(_)
+        ));
+        assert!(RelayMessage::from_json(r#"["AUTH","x"]"#).is_err());
+        assert!(RelayMessage::from_json(r#"{"not":"array"}"#).is_err());
+    }
+
+    // ==================== RelayClient Tests ====================
+
+    #[test]
+    fn test_subscription_lifecycle() {
+        let mut client = RelayClient::new("wss://relay.example.com");
+        let sub_id = client.subscribe(vec![Filter::game(GameId::new("g1"))]);
+        assert_eq!(client.take_outgoing().len(), 1);
+
+        let event_json = serde_json::to_string(&test_event("e1")).unwrap();
+        let frame = format!(r#"["EVENT","{}",{}]"#, sub_id, event_json);
+        assert!(client.handle_frame(&frame).unwrap().is_some());
+        client
+            .handle_frame(&format!(r#"["EOSE","{}"]"#, sub_id))
+            .unwrap();
+
+        let sub = client.subscription(&sub_id).unwrap();
+        assert!(sub.eose);
+        assert_eq!(sub.received, 1);
+
+        assert!(client.unsubscribe(&sub_id));
+        assert_eq!(
+            client.take_outgoing(),
+            vec![format!(r#"["CLOSE","{}"]"#, sub_id)]
+        );
+        assert!(client.handle_frame(&frame).unwrap().is_none());
+    }
+
+    #[test]
+    fn test_cancelled_subscription_is_closed() {
+        let mut client = RelayClient::new("wss://relay.example.com");
+        let cancel = CancellationToken::new();
+        let sub_id =
+            client.subscribe_with_cancel(vec![Filter::game(GameId::new("g1"))], cancel.clone());
+        let other = client.subscribe(vec![Filter::game(GameId::new("g2"))]);
+        client.take_outgoing();
+
+        cancel.cancel();
+        let event_json = serde_json::to_string(&test_event("e1")).unwrap();
+        let frame = format!(r#"["EVENT","{}",{}]"#, sub_id, event_json);
+        assert!(client.handle_frame(&frame).unwrap().is_none());
+        assert!(client.subscription(&sub_id).is_none());
+        assert!(client.subscription(&other).is_some());
+        assert_eq!(
+            client.take_outgoing(),
+            vec![format!(r#"["CLOSE","{}"]"#, sub_id)]
+        );
+
+        // Reconnecting doesn't reopen it
+        client.resubscribe();
+        assert_eq!(client.take_outgoing().len(), 1);
+    }
+
+    #[test]
+    fn test_publish_until_acknowledged() {
+        let mut client = RelayClient::new("wss://relay.example.com");
+        client.subscribe(vec![Filter::new()]);
+        client.publish(&test_event("e1"));
+        client.take_outgoing();
+        assert_eq!(client.pending_count(), 1);
+
+        // Reconnect: the REQ and the unacknowledged event are sent again
+        client.resubscribe();
+        assert_eq!(client.outgoing_len(), 2);
+
+        client.handle_frame(r#"["OK","e1",true,""]"#).unwrap();
+        assert_eq!(client.pending_count(), 0);
+    }
+}
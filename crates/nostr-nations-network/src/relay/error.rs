@@ -0,0 +1,74 @@
+//! Errors shared by the relay storage backends.
+
+use crate::at_rest::AtRestError;
+use crate::relay::policy::PolicyViolation;
+
+/// Storage error types.
+#[derive(Debug)]
+pub enum StorageError {
+    /// SQLite error.
+    #[cfg(feature = "sqlite")]
+    Sqlite(rusqlite::Error),
+    /// Storage backend error (IndexedDB, failed integrity check).
+    Backend(String),
+    /// Event not found.
+    NotFound(String),
+    /// Serialization error.
+    Serialization(String),
+    /// Lock error.
+    LockError(String),
+    /// Event refused by the relay policy.
+    Rejected(PolicyViolation),
+    /// An encrypted event could not be decrypted, or no key was set.
+    Encryption(AtRestError),
+    /// Reading or writing an event archive failed.
+    Io(std::io::Error),
+}
+
+impl std::fmt::Display for StorageError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            #[cfg(feature = "sqlite")]
+            StorageError::Sqlite(e) => write!(f, "SQLite error: {}", e),
+            StorageError::Backend(msg) => write!(f, "Storage backend error: {}", msg),
+            StorageError::NotFound(id) => write!(f, "Event not found: {}", id),
+            StorageError::Serialization(msg) => write!(f, "Serialization error: {}", msg),
+            StorageError::LockError(msg) => write!(f, "Lock error: {}", msg),
+            StorageError::Rejected(violation) => write!(f, "Event rejected: {}", violation),
+            StorageError::Encryption(e) => write!(f, "Encryption error: {}", e),
+            StorageError::Io(e) => write!(f, "IO error: {}", e),
+        }
+    }
+}
+
+impl std::error::Error for StorageError {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            #[cfg(feature = "sqlite")]
+            StorageError::Sqlite(e) => Some(e),
+            StorageError::Rejected(violation) => Some(violation),
+            StorageError::Encryption(e) => Some(e),
+            StorageError::Io(e) => Some(e),
+            _ => None,
+        }
+    }
+}
+
+impl From<std::io::Error> for StorageError {
+    fn from(err: std::io::Error) -> Self {
+        StorageError::Io(err)
+    }
+}
+
+impl From<AtRestError> for StorageError {
+    fn from(err: AtRestError) -> Self {
+        StorageError::Encryption(err)
+    }
+}
+
+#[cfg(feature = "sqlite")]
+impl From<rusqlite::Error> for StorageError {
+    fn from(err: rusqlite::Error) -> Self {
+        StorageError::Sqlite(err)
+    }
+}
@@ -0,0 +1,105 @@
+//! Background pruning of expired events (NIP-40).
+
+use crate::relay::LocalRelay;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::Arc;
+use std::thread::JoinHandle;
+use std::time::Duration;
+
+/// Default time between pruning passes.
+pub const DEFAULT_PRUNE_INTERVAL: Duration = Duration::from_secs(60);
+
+/// Deletes expired events from a relay on a background thread.
+///
+/// Runs until dropped or [`stop`](Self::stop)ped.
+pub struct ExpirationPruner {
+    shutdown: Arc<AtomicBool>,
+    handle: Option<JoinHandle<()>>,
+}
+
+impl ExpirationPruner {
+    /// Start pruning `relay` every `interval`.
+    pub fn start(relay: LocalRelay, interval: Duration) -> std::io::Result<Self> {
+        let shutdown = Arc::new(AtomicBool::new(false));
+
+        let stop = shutdown.clone();
+        let handle = std::thread::Builder::new()
+            .name("relay-expiration".to_string())
+            .spawn(move || {
+                while !stop.load(Ordering::Relaxed) {
+                    match relay.prune_expired() {
+                        Ok(0) => {}
+                        Ok(pruned) => tracing::debug!(pruned, "pruned expired events"),
+                        Err(error) => tracing::warn!(%error, "failed to prune expired events"),
+                    }
+                    std::thread::park_timeout(interval);
+                }
+            })?;
+
+        Ok(Self {
+            shutdown,
+            handle: Some(handle),
+        })
+    }
+
+    /// Stop pruning and wait for the thread to exit.
+    pub fn stop(&mut self) {
+        let Some(handle) = self.handle.take() else {
+            return;
+        };
+        self.shutdown.store(true, Ordering::Relaxed);
+        handle.thread().unpark();
+        let _ = handle.join();
+    }
+}
+
+impl Drop for ExpirationPruner {
+    fn drop(&mut self) {
+        self.stop();
+    }
+}
+
+impl std::fmt::Debug for ExpirationPruner {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("ExpirationPruner")
+            .field("running", &self.handle.is_some())
+            .finish()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use nostr_nations_core::events::{GameAction, GameEvent};
+    use nostr_nations_core::types::{GameId, PlayerSlot};
+
+    #[test]
+    fn test_pruner_removes_expired_events() {
+        let relay = LocalRelay::new_in_memory().unwrap();
+        let mut event = GameEvent::new(
+            GameId::new("game1"),
+            PlayerSlot(0),
+            None,
+            1,
+            1,
+            GameAction::EndTurn,
+        );
+        event.id = "lobby_ad".to_string();
+        relay
+            .storage
+            .store_event(&event.with_expiration(1))
+            .unwrap();
+        assert_eq!(relay.event_count().unwrap(), 1);
+
+        let mut pruner = ExpirationPruner::start(relay.clone(), Duration::from_millis(10)).unwrap();
+        for _ in 0..100 {
+            if relay.event_count().unwrap() == 0 {
+                break;
+            }
+            std::thread::sleep(Duration::from_millis(10));
+        }
+        pruner.stop();
+
+        assert_eq!(relay.event_count().unwrap(), 0);
+    }
+}
@@ -4,6 +4,7 @@
 //! See: https://github.com/nostr-protocol/nips/blob/master/01.md
 
 use nostr_nations_core::events::GameEvent;
+use nostr_nations_core::types::GameId;
 use serde::{Deserialize, Serialize};
 use std::collections::HashSet;
 
@@ -44,7 +45,7 @@ pub struct Filter {
 
     /// Game ID filter (custom extension for Nostr Nations).
     #[serde(skip_serializing_if = "Option::is_none")]
-    pub game_id: Option<String>,
+    pub game_id: Option<GameId>,
 }
 
 impl Filter {
@@ -78,7 +79,7 @@ impl Filter {
     }
 
     /// Create a filter for a specific game.
-    pub fn game(game_id: String) -> Self {
+    pub fn game(game_id: GameId) -> Self {
         Self {
             game_id: Some(game_id),
             ..Default::default()
@@ -129,7 +130,7 @@ impl Filter {
     }
 
     /// Add game ID filter.
-    pub fn with_game_id(mut self, game_id: String) -> Self {
+    pub fn with_game_id(mut self, game_id: GameId) -> Self {
         self.game_id = Some(game_id);
         self
     }
@@ -174,7 +175,7 @@ impl Filter {
 
         // Check game ID filter
         if let Some(ref game_id) = self.game_id {
-            if &event.game_id != game_id {
+            if event.game_id != *game_id {
                 return false;
             }
         }
@@ -290,6 +291,7 @@ impl Filter {
 mod tests {
     use super::*;
     use nostr_nations_core::events::{GameAction, GameEvent};
+    use nostr_nations_core::types::PlayerSlot;
 
     fn create_test_event(
         id: &str,
@@ -312,7 +314,14 @@ mod tests {
             _ => GameAction::EndTurn,
         };
 
-        let mut event = GameEvent::new(game_id.to_string(), player_id, None, 1, 1, action);
+        let mut event = GameEvent::new(
+            GameId::new(game_id),
+            PlayerSlot(player_id),
+            None,
+            1,
+            1,
+            action,
+        );
         event.id = id.to_string();
         event.timestamp = timestamp;
         event
@@ -404,7 +413,7 @@ mod tests {
 
     #[test]
     fn test_filter_game_id() {
-        let filter = Filter::game("game1".to_string());
+        let filter = Filter::game(GameId::new("game1"));
 
         let event1 = create_test_event("event1", 0, 30104, 1000, "game1");
         let event2 = create_test_event("event2", 0, 30104, 1000, "game2");
@@ -417,7 +426,7 @@ mod tests {
     fn test_filter_combined() {
         let filter = Filter::new()
             .with_kinds(vec![30104])
-            .with_game_id("game1".to_string())
+            .with_game_id(GameId::new("game1"))
             .since(1000)
             .until(2000);
 
@@ -0,0 +1,252 @@
+//! IndexedDB storage backend for the browser light client.
+//!
+//! IndexedDB is asynchronous while the relay storage API is synchronous, so
+//! this backend keeps every event in a [`MemoryStorage`] and writes changes
+//! through to IndexedDB in the background. [`IndexedDbStorage::open`] loads
+//! the stored events once at startup.
+//!
+//! Only compiled for `wasm32` targets.
+
+use crate::relay::error::StorageError;
+use crate::relay::filter::Filter;
+use crate::relay::memory::MemoryStorage;
+use crate::relay::ndjson;
+use nostr_nations_core::events::GameEvent;
+use nostr_nations_core::types::GameId;
+use std::io::{BufRead, Write};
+use wasm_bindgen::{closure::Closure, JsCast, JsValue};
+use wasm_bindgen_futures::JsFuture;
+use web_sys::{IdbDatabase, IdbObjectStore, IdbRequest, IdbTransactionMode};
+
+/// Schema version of the IndexedDB database.
+const DB_VERSION: u32 = 1;
+
+/// Object store holding serialized events keyed by event ID.
+const EVENT_STORE: &str = "events";
+
+/// Event storage persisted to IndexedDB.
+#[derive(Clone)]
+pub struct IndexedDbStorage {
+    memory: MemoryStorage,
+    db: Option<IdbDatabase>,
+}
+
+fn backend_error(value: JsValue) -> StorageError {
+    StorageError::Backend(format!("{:?}", value))
+}
+
+/// Wait for an IndexedDB request to finish and return its result.
+async fn await_request(request: &IdbRequest) -> Result<JsValue, StorageError> {
+    let promise = js_sys::Promise::new(&mut |resolve, reject| {
+        request.set_onsuccess(Some(&resolve));
+        request.set_onerror(Some(&reject));
+    });
+    JsFuture::from(promise).await.map_err(backend_error)?;
+    request.result().map_err(backend_error)
+}
+
+impl IndexedDbStorage {
+    /// Create a store that is not persisted.
+    pub fn new_in_memory() -> Result<Self, StorageError> {
+        Ok(Self {
+            memory: MemoryStorage::new_in_memory()?,
+            db: None,
+        })
+    }
+
+    /// Open (or create) a database and load its events.
+    pub async fn open(name: &str) -> Result<Self, StorageError> {
+        let factory = web_sys::window()
+            .ok_or_else(|| StorageError::Backend("No window".to_string()))?
+            .indexed_db()
+            .map_err(backend_error)?
+            .ok_or_else(|| StorageError::Backend("IndexedDB unavailable".to_string()))?;
+        let request = factory
+            .open_with_u32(name, DB_VERSION)
+            .map_err(backend_error)?;
+
+        let upgrade_request = request.clone();
+        let on_upgrade = Closure::once(move |_: JsValue| {
+            if let Ok(result) = upgrade_request.result() {
+                let db: IdbDatabase = result.unchecked_into();
+                if !db.object_store_names().contains(EVENT_STORE) {
+                    let _ = db.create_object_store(EVENT_STORE);
+                }
+            }
+        });
+        request.set_onupgradeneeded(Some(on_upgrade.as_ref().unchecked_ref()));
+        let db: IdbDatabase = await_request(&request).await?.unchecked_into();
+        request.set_onupgradeneeded(None);
+
+        let storage = Self {
+            memory: MemoryStorage::new_in_memory()?,
+            db: Some(db),
+        };
+        storage.load().await?;
+        Ok(storage)
+    }
+
+    /// Load every persisted event into memory.
+    async fn load(&self) -> Result<(), StorageError> {
+        let Some(store) = self.object_store(IdbTransactionMode::Readonly)? else {
+            return Ok(());
+        };
+        let request = store.get_all().map_err(backend_error)?;
+        let values = js_sys::Array::from(&await_request(&request).await?);
+        for value in values.iter() {
+            let Some(json) = value.as_string() else {
+                continue;
+            };
+            match serde_json::from_str::<GameEvent>(&json) {
+                Ok(event) => self.memory.store_event(&event)?,
+                Err(e) => tracing::warn!(error = %e, "skipping unreadable stored event"),
+            }
+        }
+        Ok(())
+    }
+
+    fn object_store(
+        &self,
+        mode: IdbTransactionMode,
+    ) -> Result<Option<IdbObjectStore>, StorageError> {
+        let Some(db) = &self.db else {
+            return Ok(None);
+        };
+        let transaction = db
+            .transaction_with_str_and_mode(EVENT_STORE, mode)
+            .map_err(backend_error)?;
+        transaction
+            .object_store(EVENT_STORE)
+            .map(Some)
+            .map_err(backend_error)
+    }
+
+    /// Store an event.
+    pub fn store_event(&self, event: &GameEvent) -> Result<(), StorageError> {
+        self.memory.store_event(event)?;
+        if let Some(store) = self.object_store(IdbTransactionMode::Readwrite)? {
+            let json = serde_json::to_string(event)
+                .map_err(|e| StorageError::Serialization(e.to_string()))?;
+            store
+                .put_with_key(&JsValue::from_str(&json), &JsValue::from_str(&event.id))
+                .map_err(backend_error)?;
+        }
+        Ok(())
+    }
+
+    /// Store a batch of events in one IndexedDB transaction. Returns the
+    /// number stored.
+    pub fn store_events(&self, events: &[GameEvent]) -> Result<usize, StorageError> {
+        self.memory.store_events(events)?;
+        if let Some(store) = self.object_store(IdbTransactionMode::Readwrite)? {
+            for event in events {
+                let json = serde_json::to_string(event)
+                    .map_err(|e| StorageError::Serialization(e.to_string()))?;
+                store
+                    .put_with_key(&JsValue::from_str(&json), &JsValue::from_str(&event.id))
+                    .map_err(backend_error)?;
+            }
+        }
+        Ok(events.len())
+    }
+
+    /// Retrieve an event by ID.
+    pub fn get_event(&self, id: &str) -> Result<GameEvent, StorageError> {
+        self.memory.get_event(id)
+    }
+
+    /// Query events using a NIP-01 filter, newest first.
+    pub fn query_events(&self, filter: &Filter) -> Result<Vec<GameEvent>, StorageError> {
+        self.memory.query_events(filter)
+    }
+
+    /// Write the events matching a filter to `writer` as NDJSON, oldest
+    /// first. Returns the number of events written.
+    pub fn export_ndjson<W: Write>(
+        &self,
+        filter: &Filter,
+        writer: W,
+    ) -> Result<usize, StorageError> {
+        self.memory.export_ndjson(filter, writer)
+    }
+
+    /// Store the events in an NDJSON archive, writing each batch through to
+    /// IndexedDB. Returns the number stored.
+    pub fn import_ndjson<R: BufRead>(&self, reader: R) -> Result<usize, StorageError> {
+        ndjson::read_events(reader, |events| self.store_events(events))
+    }
+
+    /// Delete an event by ID.
+    pub fn delete_event(&self, id: &str) -> Result<bool, StorageError> {
+        let deleted = self.memory.delete_event(id)?;
+        if let Some(store) = self.object_store(IdbTransactionMode::Readwrite)? {
+            store
+                .delete(&JsValue::from_str(id))
+                .map_err(backend_error)?;
+        }
+        Ok(deleted)
+    }
+
+    /// Delete events that expired at or before `now` (Unix seconds).
+    pub fn prune_expired(&self, now: u64) -> Result<usize, StorageError> {
+        let expired = self.memory.expired_ids(now)?;
+        for id in &expired {
+            self.delete_event(id)?;
+        }
+        Ok(expired.len())
+    }
+
+    /// Get the number of stored events.
+    pub fn event_count(&self) -> Result<usize, StorageError> {
+        self.memory.event_count()
+    }
+
+    /// Get all events for a specific game.
+    pub fn get_game_events(&self, game_id: &GameId) -> Result<Vec<GameEvent>, StorageError> {
+        self.memory.get_game_events(game_id)
+    }
+
+    /// Delete all events for a specific game.
+    pub fn delete_game_events(&self, game_id: &str) -> Result<usize, StorageError> {
+        let events = self.memory.get_game_events(game_id)?;
+        for event in &events {
+            self.delete_event(&event.id)?;
+        }
+        Ok(events.len())
+    }
+
+    /// Clear all events.
+    pub fn clear(&self) -> Result<(), StorageError> {
+        self.memory.clear()?;
+        if let Some(store) = self.object_store(IdbTransactionMode::Readwrite)? {
+            store.clear().map_err(backend_error)?;
+        }
+        Ok(())
+    }
+
+    /// Store a subscription filter (kept in memory only).
+    pub fn store_subscription(&self, sub_id: &str, filter: &Filter) -> Result<(), StorageError> {
+        self.memory.store_subscription(sub_id, filter)
+    }
+
+    /// Get a stored subscription filter.
+    pub fn get_subscription(&self, sub_id: &str) -> Result<Filter, StorageError> {
+        self.memory.get_subscription(sub_id)
+    }
+
+    /// Delete a subscription.
+    pub fn delete_subscription(&self, sub_id: &str) -> Result<bool, StorageError> {
+        self.memory.delete_subscription(sub_id)
+    }
+
+    /// Writes are handed to IndexedDB as they happen, so there is nothing
+    /// left to flush.
+    pub fn flush(&self) -> Result<(), StorageError> {
+        Ok(())
+    }
+
+    /// Check the in-memory copy; IndexedDB has no integrity check.
+    pub fn check_integrity(&self) -> Result<bool, StorageError> {
+        self.memory.check_integrity()
+    }
+}
@@ -0,0 +1,318 @@
+//! In-memory storage backend for the local Nostr relay.
+//!
+//! Used in place of SQLite when the `sqlite` feature is disabled, and as
+//! the read cache behind the browser's IndexedDB storage. It has the same
+//! API as the SQLite backend, and filters match with [`Filter::matches`].
+
+use crate::relay::error::StorageError;
+use crate::relay::filter::Filter;
+use crate::relay::ndjson;
+use crate::relay::unix_now;
+use nostr_nations_core::events::GameEvent;
+use nostr_nations_core::types::GameId;
+use std::collections::HashMap;
+use std::io::{BufRead, Write};
+use std::sync::{Arc, Mutex, MutexGuard};
+
+#[derive(Default)]
+struct Tables {
+    events: HashMap<String, GameEvent>,
+    subscriptions: HashMap<String, Filter>,
+}
+
+/// Thread-safe in-memory event storage.
+#[derive(Clone, Default)]
+pub struct MemoryStorage {
+    tables: Arc<Mutex<Tables>>,
+}
+
+impl MemoryStorage {
+    /// Create an empty store.
+    pub fn new_in_memory() -> Result<Self, StorageError> {
+        Ok(Self::default())
+    }
+
+    fn lock(&self) -> Result<MutexGuard<'_, Tables>, StorageError> {
+        self.tables
+            .lock()
+            .map_err(|e| StorageError::LockError(e.to_string()))
+    }
+
+    /// Store an event, replacing any event with the same ID.
+    pub fn store_event(&self, event: &GameEvent) -> Result<(), StorageError> {
+        self.lock()?.events.insert(event.id.clone(), event.clone());
+        Ok(())
+    }
+
+    /// Store a batch of events under one lock. Returns the number stored.
+    pub fn store_events(&self, events: &[GameEvent]) -> Result<usize, StorageError> {
+        let mut tables = self.lock()?;
+        for event in events {
+            tables.events.insert(event.id.clone(), event.clone());
+        }
+        Ok(events.len())
+    }
+
+    /// Retrieve an event by ID.
+    pub fn get_event(&self, id: &str) -> Result<GameEvent, StorageError> {
+        self.lock()?
+            .events
+            .get(id)
+            .cloned()
+            .ok_or_else(|| StorageError::NotFound(id.to_string()))
+    }
+
+    /// Query events using a NIP-01 filter, newest first.
+    ///
+    /// Expired events are left out.
+    pub fn query_events(&self, filter: &Filter) -> Result<Vec<GameEvent>, StorageError> {
+        let now = unix_now();
+        let tables = self.lock()?;
+        let mut events: Vec<GameEvent> = tables
+            .events
+            .values()
+            .filter(|event| !event.is_expired(now) && filter.matches(event))
+            .cloned()
+            .collect();
+        events.sort_by(|a, b| b.timestamp.cmp(&a.timestamp).then_with(|| a.id.cmp(&b.id)));
+        if let Some(limit) = filter.limit {
+            events.truncate(limit);
+        }
+        Ok(events)
+    }
+
+    /// Write the events matching a filter to `writer` as NDJSON, oldest
+    /// first. Returns the number of events written.
+    pub fn export_ndjson<W: Write>(
+        &self,
+        filter: &Filter,
+        mut writer: W,
+    ) -> Result<usize, StorageError> {
+        let events = self.query_events(filter)?;
+        for event in events.iter().rev() {
+            ndjson::write_event(&mut writer, event)?;
+        }
+        writer.flush()?;
+        Ok(events.len())
+    }
+
+    /// Store the events in an NDJSON archive. Returns the number stored.
+    pub fn import_ndjson<R: BufRead>(&self, reader: R) -> Result<usize, StorageError> {
+        ndjson::read_events(reader, |events| self.store_events(events))
+    }
+
+    /// Delete an event by ID.
+    pub fn delete_event(&self, id: &str) -> Result<bool, StorageError> {
+        Ok(self.lock()?.events.remove(id).is_some())
+    }
+
+    /// IDs of events that expired at or before `now` (Unix seconds).
+    pub fn expired_ids(&self, now: u64) -> Result<Vec<String>, StorageError> {
+        Ok(self
+            .lock()?
+            .events
+            .values()
+            .filter(|event| event.is_expired(now))
+            .map(|event| event.id.clone())
+            .collect())
+    }
+
+    /// Delete events that expired at or before `now` (Unix seconds).
+    pub fn prune_expired(&self, now: u64) -> Result<usize, StorageError> {
+        let mut tables = self.lock()?;
+        let before = tables.events.len();
+        tables.events.retain(|_, event| !event.is_expired(now));
+        Ok(before - tables.events.len())
+    }
+
+    /// Get the number of stored events.
+    pub fn event_count(&self) -> Result<usize, StorageError> {
+        Ok(self.lock()?.events.len())
+    }
+
+    /// Get all events for a specific game.
+    pub fn get_game_events(&self, game_id: &GameId) -> Result<Vec<GameEvent>, StorageError> {
+        self.query_events(&Filter::game(game_id.clone()))
+    }
+
+    /// Delete all events for a specific game.
+    pub fn delete_game_events(&self, game_id: &str) -> Result<usize, StorageError> {
+        let mut tables = self.lock()?;
+        let before = tables.events.len();
+        tables.events.retain(|_, event| event.game_id != game_id);
+        Ok(before - tables.events.len())
+    }
+
+    /// Clear all events.
+    pub fn clear(&self) -> Result<(), StorageError> {
+        self.lock()?.events.clear();
+        Ok(())
+    }
+
+    /// Store a subscription filter.
+    pub fn store_subscription(&self, sub_id: &str, filter: &Filter) -> Result<(), StorageError> {
+        self.lock()?
+            .subscriptions
+            .insert(sub_id.to_string(), filter.clone());
+        Ok(())
+    }
+
+    /// Get a stored subscription filter.
+    pub fn get_subscription(&self, sub_id: &str) -> Result<Filter, StorageError> {
+        self.lock()?
+            .subscriptions
+            .get(sub_id)
+            .cloned()
+            .ok_or_else(|| StorageError::NotFound(sub_id.to_string()))
+    }
+
+    /// Delete a subscription.
+    pub fn delete_subscription(&self, sub_id: &str) -> Result<bool, StorageError> {
+        Ok(self.lock()?.subscriptions.remove(sub_id).is_some())
+    }
+
+    /// Nothing to flush; present for parity with the SQLite backend.
+    pub fn flush(&self) -> Result<(), StorageError> {
+        Ok(())
+    }
+
+    /// An in-memory store cannot be damaged; always true unless the lock
+    /// is poisoned.
+    pub fn check_integrity(&self) -> Result<bool, StorageError> {
+        self.lock().map(|_| true)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use nostr_nations_core::events::GameAction;
+    use nostr_nations_core::types::PlayerSlot;
+
+    fn create_test_event(id: &str, game_id: &str, timestamp: u64) -> GameEvent {
+        let mut event = GameEvent::new(
+            GameId::new(game_id),
+            PlayerSlot(0),
+            None,
+            1,
+            1,
+            GameAction::EndTurn,
+        );
+        event.id = id.to_string();
+        event.timestamp = timestamp;
+        event
+    }
+
+    #[test]
+    fn test_store_get_and_delete() {
+        let storage = MemoryStorage::new_in_memory().unwrap();
+        storage
+            .store_event(&create_test_event("e1", "g1", 100))
+            .unwrap();
+
+        assert_eq!(storage.get_event("e1").unwrap().timestamp, 100);
+        assert!(matches!(
+            storage.get_event("missing"),
+            Err(StorageError::NotFound(_))
+        ));
+        assert!(storage.delete_event("e1").unwrap());
+        assert!(!storage.delete_event("e1").unwrap());
+        assert_eq!(storage.event_count().unwrap(), 0);
+    }
+
+    #[test]
+    fn test_query_newest_first_with_limit() {
+        let storage = MemoryStorage::new_in_memory().unwrap();
+        for (id, ts) in [("a", 100), ("b", 300), ("c", 200)] {
+            storage
+                .store_event(&create_test_event(id, "g1", ts))
+                .unwrap();
+        }
+        storage
+            .store_event(&create_test_event("other", "g2", 400))
+            .unwrap();
+
+        let events = storage
+            .query_events(&Filter::game(GameId::new("g1")).limit(2))
+            .unwrap();
+        let ids: Vec<&str> = events.iter().map(|e| e.id.as_str()).collect();
+        assert_eq!(ids, vec!["b", "c"]);
+    }
+
+    #[test]
+    fn test_ndjson_round_trip() {
+        let storage = MemoryStorage::new_in_memory().unwrap();
+        for (id, ts) in [("a", 100), ("b", 300), ("c", 200)] {
+            storage
+                .store_event(&create_test_event(id, "g1", ts))
+                .unwrap();
+        }
+
+        let mut archive = Vec::new();
+        assert_eq!(
+            storage.export_ndjson(&Filter::new(), &mut archive).unwrap(),
+            3
+        );
+
+        let restored = MemoryStorage::new_in_memory().unwrap();
+        assert_eq!(restored.import_ndjson(archive.as_slice()).unwrap(), 3);
+        let ids: Vec<String> = restored
+            .query_events(&Filter::new())
+            .unwrap()
+            .into_iter()
+            .map(|e| e.id)
+            .collect();
+        assert_eq!(ids, vec!["b", "c", "a"]);
+    }
+
+    #[test]
+    fn test_expired_events_hidden_and_pruned() {
+        let storage = MemoryStorage::new_in_memory().unwrap();
+        storage
+            .store_event(&create_test_event("a", "g1", 1))
+            .unwrap();
+        storage
+            .store_event(&create_test_event("b", "g1", 2).with_expiration(10))
+            .unwrap();
+
+        let events = storage.query_events(&Filter::new()).unwrap();
+        assert_eq!(events.len(), 1);
+        assert_eq!(events[0].id, "a");
+
+        assert_eq!(storage.expired_ids(9).unwrap().len(), 0);
+        assert_eq!(storage.prune_expired(10).unwrap(), 1);
+        assert_eq!(storage.event_count().unwrap(), 1);
+    }
+
+    #[test]
+    fn test_delete_game_events() {
+        let storage = MemoryStorage::new_in_memory().unwrap();
+        storage
+            .store_event(&create_test_event("a", "g1", 1))
+            .unwrap();
+        storage
+            .store_event(&create_test_event("b", "g1", 2))
+            .unwrap();
+        storage
+            .store_event(&create_test_event("c", "g2", 3))
+            .unwrap();
+
+        assert_eq!(storage.delete_game_events("g1").unwrap(), 2);
+        assert_eq!(
+            storage.get_game_events(&GameId::new("g2")).unwrap().len(),
+            1
+        );
+    }
+
+    #[test]
+    fn test_subscription_storage() {
+        let storage = MemoryStorage::new_in_memory().unwrap();
+        let filter = Filter::game(GameId::new("g1"));
+        storage.store_subscription("sub1", &filter).unwrap();
+
+        let loaded = storage.get_subscription("sub1").unwrap();
+        assert_eq!(loaded.game_id, Some(GameId::new("g1")));
+        assert!(storage.delete_subscription("sub1").unwrap());
+        assert!(storage.get_subscription("sub1").is_err());
+    }
+}
@@ -7,9 +7,38 @@
 //!
 //! The relay consists of three main components:
 //!
-//! - **Storage** ([`RelayStorage`]): SQLite-backed persistent storage for events
+//! - **Storage** ([`RelayStorage`]): Persistent storage for events
 //! - **Subscriptions** ([`SubscriptionManager`]): Real-time event notifications
 //! - **Filters** ([`Filter`]): NIP-01 compliant event filtering
+//! - **Policy** ([`RelayPolicy`]): rate limits and spam protection for
+//!   events from remote clients
+//!
+//! Events with a NIP-40 `expiration` tag are hidden from queries once they
+//! expire and deleted by [`LocalRelay::prune_expired`], which an
+//! [`ExpirationPruner`] can run periodically. Events of ephemeral kinds
+//! (pings, presence) reach subscribers but are never stored.
+//!
+//! Bulk loads go through [`LocalRelay::publish_batch`], which stores a whole
+//! batch in one write. Events mirrored into storage from subscription
+//! callbacks can be queued on a [`BatchWriter`], which writes them in
+//! batches from a background thread.
+//!
+//! Event histories can be backed up, moved between devices or handed to
+//! other nostr tooling as NDJSON archives with `export_ndjson` and
+//! `import_ndjson` on every storage backend (see [`ndjson`]).
+//!
+//! Remote relays are reached through [`RelayClient`], the NIP-01 websocket
+//! protocol used by light clients.
+//! Which remote relays to use for each player comes from their NIP-65 relay
+//! lists, via [`RelaySelector`].
+//!
+//! # Storage Backends
+//!
+//! [`RelayStorage`] is chosen at compile time:
+//!
+//! - `sqlite` feature (default): SQLite database
+//! - wasm32 without `sqlite`: in-memory cache persisted to IndexedDB
+//! - otherwise: in-memory only ([`MemoryStorage`])
 //!
 //! # Example
 //!
@@ -48,13 +77,61 @@
 //! - Tag filters (`#e`, `#p`, etc.)
 //! - Result limiting (`limit`)
 
+pub mod client;
+pub mod error;
+#[cfg(not(target_arch = "wasm32"))]
+pub mod expiration;
 pub mod filter;
+#[cfg(target_arch = "wasm32")]
+pub mod indexeddb;
+pub mod memory;
+pub mod ndjson;
+pub mod policy;
+pub mod relay_list;
+#[cfg(feature = "sqlite")]
 pub mod storage;
 pub mod subscription;
-
+#[cfg(not(target_arch = "wasm32"))]
+pub mod writer;
+#[cfg(target_arch = "wasm32")]
+pub mod websocket;
+
+pub use client::{ClientMessage, RelayClient, RelayClientError, RelayMessage, RelaySubscription};
+pub use error::StorageError;
+#[cfg(not(target_arch = "wasm32"))]
+pub use expiration::{ExpirationPruner, DEFAULT_PRUNE_INTERVAL};
 pub use filter::Filter;
-pub use storage::{RelayStorage, StorageError};
+pub use memory::MemoryStorage;
+pub use ndjson::IMPORT_BATCH_SIZE;
+pub use policy::{PolicyViolation, RateLimit, RateLimiter, RelayGuard, RelayPolicy};
+pub use relay_list::{RelayList, RelayListEntry, RelaySelector, RELAY_LIST_KIND};
+#[cfg(feature = "sqlite")]
+pub use storage::{RelayStorage, StorageOptions};
 pub use subscription::{Subscription, SubscriptionBuilder, SubscriptionCallback, SubscriptionManager};
+#[cfg(not(target_arch = "wasm32"))]
+pub use writer::{
+    BatchWriter, BatchWriterHandle, BatchWriterStats, DEFAULT_FLUSH_INTERVAL, DEFAULT_MAX_BATCH,
+};
+#[cfg(target_arch = "wasm32")]
+pub use indexeddb::IndexedDbStorage;
+#[cfg(target_arch = "wasm32")]
+pub use websocket::BrowserRelaySocket;
+
+/// Event storage backend (IndexedDB).
+#[cfg(all(target_arch = "wasm32", not(feature = "sqlite")))]
+pub type RelayStorage = IndexedDbStorage;
+
+/// Event storage backend (in-memory).
+#[cfg(all(not(target_arch = "wasm32"), not(feature = "sqlite")))]
+pub type RelayStorage = MemoryStorage;
+
+/// Current time in seconds since the Unix epoch.
+pub(crate) fn unix_now() -> u64 {
+    web_time::SystemTime::now()
+        .duration_since(web_time::UNIX_EPOCH)
+        .map(|d| d.as_secs())
+        .unwrap_or(0)
+}
 
 /// Local relay combining storage and subscription management.
 ///
@@ -67,6 +144,10 @@ pub struct LocalRelay {
     pub storage: RelayStorage,
     /// Subscription manager for real-time notifications.
     pub subscriptions: SubscriptionManager,
+    /// Policy checks for events from remote clients.
+    pub guard: RelayGuard,
+    /// Identity whose events this relay stores, if it was opened for one.
+    owner: Option<String>,
 }
 
 impl LocalRelay {
@@ -75,23 +156,153 @@ impl LocalRelay {
         Ok(Self {
             storage: RelayStorage::new_in_memory()?,
             subscriptions: SubscriptionManager::new(),
+            guard: RelayGuard::default(),
+            owner: None,
         })
     }
 
     /// Create a new local relay with file-based storage.
+    #[cfg(feature = "sqlite")]
     pub fn new<P: AsRef<std::path::Path>>(path: P) -> Result<Self, StorageError> {
         Ok(Self {
             storage: RelayStorage::new(path)?,
             subscriptions: SubscriptionManager::new(),
+            guard: RelayGuard::default(),
+            owner: None,
+        })
+    }
+
+    /// Open the local relay of one identity on a shared device.
+    ///
+    /// Each owner pubkey gets its own database under `layout`'s profile
+    /// directory (see [`StorageLayout::for_owner`](crate::StorageLayout::for_owner)),
+    /// so games of different identities never share storage.
+    #[cfg(feature = "sqlite")]
+    pub fn for_identity(layout: &crate::StorageLayout, owner: &str) -> Result<Self, StorageError> {
+        let profile = layout
+            .for_owner(owner)
+            .map_err(|e| StorageError::Backend(e.to_string()))?;
+        let path = profile.relay_db_path();
+        if let Some(dir) = path.parent() {
+            std::fs::create_dir_all(dir).map_err(|e| StorageError::Backend(e.to_string()))?;
+        }
+        let mut relay = Self::new(path)?;
+        relay.owner = Some(owner.to_string());
+        Ok(relay)
+    }
+
+    /// Identity whose events this relay stores, or `None` for a relay not
+    /// opened with [`for_identity`](Self::for_identity).
+    pub fn owner(&self) -> Option<&str> {
+        self.owner.as_deref()
+    }
+
+    /// Create a new local relay with file-based storage opened with the
+    /// given options.
+    #[cfg(feature = "sqlite")]
+    pub fn open_with_options<P: AsRef<std::path::Path>>(
+        path: P,
+        options: StorageOptions,
+    ) -> Result<Self, StorageError> {
+        Ok(Self {
+            storage: RelayStorage::open_with_options(path, options)?,
+            subscriptions: SubscriptionManager::new(),
+            guard: RelayGuard::default(),
+            owner: None,
         })
     }
 
+    /// Encrypt events stored from now on; see [`RelayStorage::with_encryption`].
+    #[cfg(feature = "sqlite")]
+    pub fn with_encryption(mut self, key: crate::at_rest::AtRestKey) -> Self {
+        self.storage = self.storage.with_encryption(key);
+        self
+    }
+
+    /// Open a local relay persisted to an IndexedDB database.
+    #[cfg(all(target_arch = "wasm32", not(feature = "sqlite")))]
+    pub async fn open_indexed_db(name: &str) -> Result<Self, StorageError> {
+        Ok(Self {
+            storage: IndexedDbStorage::open(name).await?,
+            subscriptions: SubscriptionManager::new(),
+            guard: RelayGuard::default(),
+            owner: None,
+        })
+    }
+
+    /// Apply a policy to events from remote clients.
+    pub fn with_policy(mut self, policy: RelayPolicy) -> Self {
+        self.guard = RelayGuard::new(policy);
+        self
+    }
+
+    /// Store an event from a remote client if the policy allows it.
+    ///
+    /// `pubkey` is the event's author and `connection_id` identifies the
+    /// client connection it arrived on. Refused events fail with
+    /// [`StorageError::Rejected`].
+    #[tracing::instrument(name = "relay.publish_remote", skip_all, fields(game_id = %event.game_id, event_id = %event.id))]
+    pub fn publish_remote(
+        &self,
+        connection_id: &str,
+        pubkey: &str,
+        event: &nostr_nations_core::events::GameEvent,
+    ) -> Result<usize, StorageError> {
+        let now = web_time::Instant::now();
+        if let Err(violation) = self.guard.admit(connection_id, pubkey, event, now) {
+            tracing::debug!(%violation, "event rejected");
+            return Err(StorageError::Rejected(violation));
+        }
+        self.publish(event)
+    }
+
+    /// Forget rate limit state for a closed client connection.
+    pub fn disconnect(&self, connection_id: &str) {
+        self.guard.disconnect(connection_id);
+    }
+
     /// Store an event and notify matching subscribers.
+    ///
+    /// Ephemeral events are only passed to subscribers, and events that
+    /// have already expired are dropped.
+    #[tracing::instrument(name = "relay.publish", skip_all, fields(game_id = %event.game_id, event_id = %event.id))]
     pub fn publish(&self, event: &nostr_nations_core::events::GameEvent) -> Result<usize, StorageError> {
-        self.storage.store_event(event)?;
+        if event.is_expired(unix_now()) {
+            return Ok(0);
+        }
+        if !event.is_ephemeral() {
+            self.storage.store_event(event)?;
+        }
         Ok(self.subscriptions.notify_subscribers(event))
     }
 
+    /// Store a batch of events in one write, then notify subscribers of
+    /// each.
+    ///
+    /// Ephemeral and expired events are handled as in [`publish`](Self::publish).
+    /// Returns the total number of notifications sent.
+    #[tracing::instrument(name = "relay.publish_batch", skip_all, fields(events = events.len()))]
+    pub fn publish_batch(&self, events: &[nostr_nations_core::events::GameEvent]) -> Result<usize, StorageError> {
+        let now = unix_now();
+        let live: Vec<_> = events.iter().filter(|e| !e.is_expired(now)).collect();
+        let stored: Vec<_> = live
+            .iter()
+            .filter(|e| !e.is_ephemeral())
+            .map(|e| (*e).clone())
+            .collect();
+        self.storage.store_events(&stored)?;
+        Ok(live
+            .into_iter()
+            .map(|event| self.subscriptions.notify_subscribers(event))
+            .sum())
+    }
+
+    /// Delete every expired event. Returns the number deleted.
+    #[tracing::instrument(name = "relay.prune_expired", skip_all)]
+    pub fn prune_expired(&self) -> Result<usize, StorageError> {
+        self.storage.prune_expired(unix_now())
+    }
+
     /// Subscribe to events matching the given filter.
     pub fn subscribe<F>(&self, filter: Filter, callback: F) -> String
     where
@@ -106,6 +317,7 @@ impl LocalRelay {
     }
 
     /// Query events from storage.
+    #[tracing::instrument(name = "relay.query", skip_all)]
     pub fn query(&self, filter: &Filter) -> Result<Vec<nostr_nations_core::events::GameEvent>, StorageError> {
         self.storage.query_events(filter)
     }
@@ -129,11 +341,29 @@ impl LocalRelay {
     pub fn subscription_count(&self) -> usize {
         self.subscriptions.subscription_count()
     }
+
+    /// Flush storage to disk before the app is backgrounded.
+    pub fn suspend(&self) -> Result<(), StorageError> {
+        self.storage.flush()
+    }
+
+    /// Check storage after the app returns to the foreground.
+    ///
+    /// Fails with [`StorageError::Backend`] if the store was damaged while
+    /// the app was suspended.
+    pub fn resume(&self) -> Result<(), StorageError> {
+        if self.storage.check_integrity()? {
+            Ok(())
+        } else {
+            Err(StorageError::Backend("relay storage failed integrity check".to_string()))
+        }
+    }
 }
 
 impl std::fmt::Debug for LocalRelay {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
         f.debug_struct("LocalRelay")
+            .field("owner", &self.owner)
             .field("event_count", &self.storage.event_count().unwrap_or(0))
             .field("subscription_count", &self.subscriptions.subscription_count())
             .finish()
@@ -146,11 +376,12 @@ mod tests {
     use nostr_nations_core::events::{GameAction, GameEvent};
     use std::sync::atomic::{AtomicUsize, Ordering};
     use std::sync::Arc;
+    use nostr_nations_core::types::{GameId, PlayerSlot};
 
     fn create_test_event(id: &str, game_id: &str, timestamp: u64) -> GameEvent {
         let mut event = GameEvent::new(
-            game_id.to_string(),
-            0,
+            GameId::new(game_id),
+            PlayerSlot(0),
             None,
             1,
             1,
@@ -208,10 +439,98 @@ mod tests {
         relay.publish(&create_test_event("event2", "game1", 2000)).unwrap();
         relay.publish(&create_test_event("event3", "game2", 3000)).unwrap();
 
-        let events = relay.query(&Filter::game("game1".to_string())).unwrap();
+        let events = relay.query(&Filter::game(GameId::new("game1"))).unwrap();
         assert_eq!(events.len(), 2);
     }
 
+    #[test]
+    fn test_local_relay_publish_remote_applies_policy() {
+        let relay = LocalRelay::new_in_memory()
+            .unwrap()
+            .with_policy(RelayPolicy::new().with_pubkey_rate(RateLimit::per_minute(1)));
+
+        let first = create_test_event("event1", "game1", 1000);
+        let second = create_test_event("event2", "game1", 2000);
+        relay.publish_remote("conn1", "alice", &first).unwrap();
+        let result = relay.publish_remote("conn2", "alice", &second);
+
+        assert!(matches!(
+            result,
+            Err(StorageError::Rejected(PolicyViolation::PubkeyRateLimited(_)))
+        ));
+        assert_eq!(relay.event_count().unwrap(), 1);
+
+        // Local publishing is not limited
+        relay.publish(&create_test_event("event3", "game1", 3000)).unwrap();
+        assert_eq!(relay.event_count().unwrap(), 2);
+    }
+
+    #[test]
+    fn test_local_relay_skips_ephemeral_and_expired_events() {
+        let relay = LocalRelay::new_in_memory().unwrap();
+        let count = Arc::new(AtomicUsize::new(0));
+        let count_clone = count.clone();
+        relay.subscribe(Filter::new(), move |_| {
+            count_clone.fetch_add(1, Ordering::SeqCst);
+        });
+
+        let mut ping = create_test_event("ping", "game1", 1000);
+        ping.action = GameAction::Extension {
+            kind: nostr_nations_core::events::kinds::PING,
+            content: String::new(),
+        };
+        assert_eq!(relay.publish(&ping).unwrap(), 1);
+
+        let expired = create_test_event("old", "game1", 1000).with_expiration(1);
+        assert_eq!(relay.publish(&expired).unwrap(), 0);
+
+        assert_eq!(count.load(Ordering::SeqCst), 1);
+        assert_eq!(relay.event_count().unwrap(), 0);
+    }
+
+    #[test]
+    fn test_local_relay_publish_batch() {
+        let relay = LocalRelay::new_in_memory().unwrap();
+        let count = Arc::new(AtomicUsize::new(0));
+        let count_clone = count.clone();
+        relay.subscribe(Filter::game(GameId::new("game1")), move |_| {
+            count_clone.fetch_add(1, Ordering::SeqCst);
+        });
+
+        let mut ping = create_test_event("ping", "game1", 1000);
+        ping.action = GameAction::Extension {
+            kind: nostr_nations_core::events::kinds::PING,
+            content: String::new(),
+        };
+        let events = vec![
+            create_test_event("e1", "game1", 1000),
+            create_test_event("e2", "game1", 1001),
+            create_test_event("e3", "game2", 1002),
+            create_test_event("old", "game1", 1000).with_expiration(1),
+            ping,
+        ];
+
+        assert_eq!(relay.publish_batch(&events).unwrap(), 3);
+        assert_eq!(count.load(Ordering::SeqCst), 3);
+        assert_eq!(relay.event_count().unwrap(), 3);
+    }
+
+    #[test]
+    fn test_local_relay_prune_expired() {
+        let relay = LocalRelay::new_in_memory().unwrap();
+        let expires_soon = unix_now() + 1;
+        relay.publish(&create_test_event("keep", "game1", 1000)).unwrap();
+        relay
+            .publish(&create_test_event("lobby_ad", "game1", 1000).with_expiration(expires_soon))
+            .unwrap();
+        assert_eq!(relay.query(&Filter::new()).unwrap().len(), 2);
+
+        // Nothing has expired yet
+        assert_eq!(relay.prune_expired().unwrap(), 0);
+        assert_eq!(relay.storage.prune_expired(expires_soon).unwrap(), 1);
+        assert_eq!(relay.event_count().unwrap(), 1);
+    }
+
     #[test]
     fn test_local_relay_get_event() {
         let relay = LocalRelay::new_in_memory().unwrap();
@@ -244,11 +563,11 @@ mod tests {
         let game2_count = Arc::new(AtomicUsize::new(0));
         let game2_clone = game2_count.clone();
 
-        relay.subscribe(Filter::game("game1".to_string()), move |_| {
+        relay.subscribe(Filter::game(GameId::new("game1")), move |_| {
             game1_clone.fetch_add(1, Ordering::SeqCst);
         });
 
-        relay.subscribe(Filter::game("game2".to_string()), move |_| {
+        relay.subscribe(Filter::game(GameId::new("game2")), move |_| {
             game2_clone.fetch_add(1, Ordering::SeqCst);
         });
 
@@ -259,4 +578,33 @@ mod tests {
         assert_eq!(game1_count.load(Ordering::SeqCst), 2);
         assert_eq!(game2_count.load(Ordering::SeqCst), 1);
     }
+
+    #[test]
+    fn test_local_relay_suspend_and_resume() {
+        let relay = LocalRelay::new_in_memory().unwrap();
+        relay.publish(&create_test_event("e1", "game1", 1000)).unwrap();
+
+        relay.suspend().unwrap();
+        relay.resume().unwrap();
+        assert_eq!(relay.event_count().unwrap(), 1);
+    }
+
+    #[cfg(feature = "sqlite")]
+    #[test]
+    fn test_local_relay_for_identity_is_isolated() {
+        let dir = tempfile::tempdir().unwrap();
+        let layout = crate::StorageLayout::new(dir.path().join("data"), dir.path().join("cache"));
+
+        let alice = LocalRelay::for_identity(&layout, "npubalice").unwrap();
+        alice.publish(&create_test_event("e1", "game1", 1000)).unwrap();
+        assert_eq!(alice.owner(), Some("npubalice"));
+
+        let bob = LocalRelay::for_identity(&layout, "npubbob").unwrap();
+        assert_eq!(bob.event_count().unwrap(), 0);
+        drop(alice);
+
+        let alice = LocalRelay::for_identity(&layout, "npubalice").unwrap();
+        assert_eq!(alice.event_count().unwrap(), 1);
+        assert!(LocalRelay::for_identity(&layout, "../bob").is_err());
+    }
 }
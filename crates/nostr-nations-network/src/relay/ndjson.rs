@@ -0,0 +1,148 @@
+//! NDJSON import and export of relay events.
+//!
+//! An archive holds one event per line. Each line is the stored
+//! [`GameEvent`] with the NIP-01 `kind`, `created_at`, `tags` and `content`
+//! fields alongside, so nostr tooling and `jq` can read it without knowing
+//! the game's types. Import reads the game event fields and ignores the
+//! rest.
+//!
+//! Both directions stream: export writes events as the backend yields them,
+//! and import stores events in batches of [`IMPORT_BATCH_SIZE`], so archives
+//! of hundreds of megabytes never have to fit in memory.
+
+use crate::relay::error::StorageError;
+use nostr_nations_core::events::GameEvent;
+use serde::Serialize;
+use std::io::{BufRead, Write};
+
+/// Number of events stored per write during an import.
+pub const IMPORT_BATCH_SIZE: usize = 500;
+
+/// An archive line: the event plus its NIP-01 fields.
+#[derive(Serialize)]
+struct ArchiveLine<'a> {
+    #[serde(flatten)]
+    event: &'a GameEvent,
+    kind: u32,
+    created_at: u64,
+    tags: Vec<Vec<String>>,
+    content: String,
+}
+
+/// Write one event as an archive line.
+pub(crate) fn write_event<W: Write>(writer: &mut W, event: &GameEvent) -> Result<(), StorageError> {
+    let line = ArchiveLine {
+        event,
+        kind: event.kind(),
+        created_at: event.timestamp,
+        tags: event.tags(),
+        content: event.content(),
+    };
+    serde_json::to_writer(&mut *writer, &line)
+        .map_err(|e| StorageError::Serialization(e.to_string()))?;
+    writer.write_all(b"\n")?;
+    Ok(())
+}
+
+/// Read archive lines and hand them to `store` in batches.
+///
+/// Blank lines are skipped. A line that isn't an event stops the import
+/// with an error naming the line; batches stored before it are kept.
+/// Returns the number of events stored.
+pub(crate) fn read_events<R, F>(reader: R, mut store: F) -> Result<usize, StorageError>
+where
+    R: BufRead,
+    F: FnMut(&[GameEvent]) -> Result<usize, StorageError>,
+{
+    let mut batch = Vec::with_capacity(IMPORT_BATCH_SIZE);
+    let mut stored = 0;
+    for (index, line) in reader.lines().enumerate() {
+        let line = line?;
+        if line.trim().is_empty() {
+            continue;
+        }
+        let event: GameEvent = serde_json::from_str(&line)
+            .map_err(|e| StorageError::Serialization(format!("line {}: {}", index + 1, e)))?;
+        batch.push(event);
+        if batch.len() == IMPORT_BATCH_SIZE {
+            stored += store(&batch)?;
+            batch.clear();
+        }
+    }
+    if !batch.is_empty() {
+        stored += store(&batch)?;
+    }
+    Ok(stored)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use nostr_nations_core::events::GameAction;
+    use nostr_nations_core::types::{GameId, PlayerSlot};
+
+    fn create_test_event(id: &str, timestamp: u64) -> GameEvent {
+        let mut event = GameEvent::new(
+            GameId::new("game1"),
+            PlayerSlot(0),
+            None,
+            1,
+            1,
+            GameAction::EndTurn,
+        );
+        event.id = id.to_string();
+        event.timestamp = timestamp;
+        event
+    }
+
+    #[test]
+    fn test_line_has_nip01_fields() {
+        let mut out = Vec::new();
+        write_event(&mut out, &create_test_event("e1", 1000)).unwrap();
+
+        let text = String::from_utf8(out).unwrap();
+        assert!(text.ends_with('\n'));
+        let value: serde_json::Value = serde_json::from_str(text.trim_end()).unwrap();
+        assert_eq!(value["id"], "e1");
+        assert_eq!(value["created_at"], 1000);
+        assert_eq!(value["kind"], create_test_event("e1", 1000).kind());
+        assert!(value["tags"].is_array());
+        assert!(value["content"].is_string());
+    }
+
+    #[test]
+    fn test_read_events_in_batches() {
+        let mut archive = Vec::new();
+        for i in 0..(IMPORT_BATCH_SIZE + 3) {
+            write_event(
+                &mut archive,
+                &create_test_event(&format!("e{}", i), i as u64),
+            )
+            .unwrap();
+        }
+        archive.extend_from_slice(b"\n");
+
+        let mut batches = Vec::new();
+        let stored = read_events(archive.as_slice(), |events| {
+            batches.push(events.len());
+            Ok(events.len())
+        })
+        .unwrap();
+
+        assert_eq!(stored, IMPORT_BATCH_SIZE + 3);
+        assert_eq!(batches, vec![IMPORT_BATCH_SIZE, 3]);
+    }
+
+    #[test]
+    fn test_malformed_line_reports_line_number() {
+        let mut archive = Vec::new();
+        write_event(&mut archive, &create_test_event("e1", 1)).unwrap();
+        archive.extend_from_slice(b"{\"not\": \"an event\"}\n");
+
+        let result = read_events(archive.as_slice(), |events| Ok(events.len()));
+        match result {
+            Err(StorageError::Serialization(msg)) => assert!(msg.starts_with("line 2:")),
+            other => panic!("expected a serialization error, got {:?}", other),
+        }
+    }
+}
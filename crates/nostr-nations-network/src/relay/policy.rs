@@ -0,0 +1,426 @@
+//! Spam protection for a [`LocalRelay`](super::LocalRelay) exposed to other
+//! devices.
+//!
+//! A [`RelayPolicy`] sets what the relay accepts from remote clients:
+//!
+//! - Per-pubkey and per-connection rate limits
+//! - A maximum serialized event size
+//! - Allowed event kinds per game
+//! - A minimum NIP-13 proof-of-work difficulty
+//!
+//! The default policy accepts everything, matching a relay only used by the
+//! local client. [`RelayGuard`] applies a policy and keeps the rate limit
+//! counters.
+
+use nostr_nations_core::events::GameEvent;
+use nostr_nations_core::types::GameId;
+use std::collections::{HashMap, HashSet, VecDeque};
+use std::sync::{Arc, Mutex};
+use web_time::{Duration, Instant};
+
+/// Maximum number of events allowed in a sliding time window.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct RateLimit {
+    /// Events allowed per window.
+    pub max_events: u32,
+    /// Window length.
+    pub window: Duration,
+}
+
+impl RateLimit {
+    /// Allow `max_events` per second.
+    pub fn per_second(max_events: u32) -> Self {
+        Self {
+            max_events,
+            window: Duration::from_secs(1),
+        }
+    }
+
+    /// Allow `max_events` per minute.
+    pub fn per_minute(max_events: u32) -> Self {
+        Self {
+            max_events,
+            window: Duration::from_secs(60),
+        }
+    }
+}
+
+/// What a relay accepts from remote clients.
+#[derive(Clone, Debug, Default)]
+pub struct RelayPolicy {
+    /// Maximum serialized event size in bytes.
+    pub max_event_bytes: Option<usize>,
+    /// Rate limit per event author.
+    pub pubkey_rate: Option<RateLimit>,
+    /// Rate limit per client connection.
+    pub connection_rate: Option<RateLimit>,
+    /// Allowed kinds per game ID. Games not listed accept any kind.
+    pub allowed_kinds: HashMap<GameId, HashSet<u32>>,
+    /// Minimum NIP-13 difficulty (leading zero bits of the event ID).
+    pub min_pow_difficulty: u32,
+}
+
+impl RelayPolicy {
+    /// Create a policy that accepts everything.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Policy suited to a relay shared over the local network.
+    pub fn lan() -> Self {
+        Self::new()
+            .with_max_event_bytes(64 * 1024)
+            .with_pubkey_rate(RateLimit::per_second(20))
+            .with_connection_rate(RateLimit::per_second(50))
+    }
+
+    /// Set the maximum serialized event size.
+    pub fn with_max_event_bytes(mut self, max_event_bytes: usize) -> Self {
+        self.max_event_bytes = Some(max_event_bytes);
+        self
+    }
+
+    /// Set the per-pubkey rate limit.
+    pub fn with_pubkey_rate(mut self, limit: RateLimit) -> Self {
+        self.pubkey_rate = Some(limit);
+        self
+    }
+
+    /// Set the per-connection rate limit.
+    pub fn with_connection_rate(mut self, limit: RateLimit) -> Self {
+        self.connection_rate = Some(limit);
+        self
+    }
+
+    /// Only accept the given kinds for a game.
+    pub fn allow_kinds(mut self, game_id: impl Into<GameId>, kinds: &[u32]) -> Self {
+        self.allowed_kinds
+            .entry(game_id.into())
+            .or_default()
+            .extend(kinds.iter().copied());
+        self
+    }
+
+    /// Require NIP-13 proof of work of at least `difficulty` bits.
+    pub fn with_min_pow(mut self, difficulty: u32) -> Self {
+        self.min_pow_difficulty = difficulty;
+        self
+    }
+
+    /// Check an event's size, kind and proof of work.
+    pub fn check_event(&self, event: &GameEvent) -> Result<(), PolicyViolation> {
+        if let Some(max) = self.max_event_bytes {
+            let size = serde_json::to_vec(event)
+                .map(|b| b.len())
+                .unwrap_or(usize::MAX);
+            if size > max {
+                return Err(PolicyViolation::TooLarge { size, max });
+            }
+        }
+
+        if let Some(kinds) = self.allowed_kinds.get(&event.game_id) {
+            let kind = event.kind();
+            if !kinds.contains(&kind) {
+                return Err(PolicyViolation::KindNotAllowed {
+                    kind,
+                    game_id: event.game_id.clone(),
+                });
+            }
+        }
+
+        if self.min_pow_difficulty > 0 {
+            let actual = pow_difficulty(&event.id);
+            if actual < self.min_pow_difficulty {
+                return Err(PolicyViolation::InsufficientPow {
+                    required: self.min_pow_difficulty,
+                    actual,
+                });
+            }
+        }
+
+        Ok(())
+    }
+}
+
+/// Count the leading zero bits of a hex event ID (NIP-13 difficulty).
+///
+/// Stops at the first character that isn't a hex digit.
+pub fn pow_difficulty(id: &str) -> u32 {
+    let mut bits = 0;
+    for c in id.chars() {
+        let Some(nibble) = c.to_digit(16) else {
+            break;
+        };
+        if nibble == 0 {
+            bits += 4;
+        } else {
+            bits += nibble.leading_zeros() - 28;
+            break;
+        }
+    }
+    bits
+}
+
+/// Why a relay refused an event.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum PolicyViolation {
+    /// The author sent too many events.
+    PubkeyRateLimited(String),
+    /// The connection sent too many events.
+    ConnectionRateLimited(String),
+    /// The serialized event is too large.
+    TooLarge { size: usize, max: usize },
+    /// The kind is not allowed in the event's game.
+    KindNotAllowed { kind: u32, game_id: GameId },
+    /// The event ID does not carry enough proof of work.
+    InsufficientPow { required: u32, actual: u32 },
+}
+
+impl std::fmt::Display for PolicyViolation {
+    // Messages use the NIP-01 machine-readable prefixes for OK responses
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            PolicyViolation::PubkeyRateLimited(pubkey) => {
+                write!(f, "rate-limited: too many events from {}", pubkey)
+            }
+            PolicyViolation::ConnectionRateLimited(_) => {
+                write!(f, "rate-limited: too many events on this connection")
+            }
+            PolicyViolation::TooLarge { size, max } => {
+                write!(f, "invalid: event is {} bytes, limit is {}", size, max)
+            }
+            PolicyViolation::KindNotAllowed { kind, game_id } => {
+                write!(f, "blocked: kind {} not allowed in game {}", kind, game_id)
+            }
+            PolicyViolation::InsufficientPow { required, actual } => {
+                write!(f, "pow: difficulty {} is less than {}", actual, required)
+            }
+        }
+    }
+}
+
+impl std::error::Error for PolicyViolation {}
+
+/// Sliding-window event counters keyed by pubkey or connection.
+#[derive(Clone, Debug, Default)]
+pub struct RateLimiter {
+    hits: HashMap<String, VecDeque<Instant>>,
+}
+
+impl RateLimiter {
+    /// Create an empty rate limiter.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Record an event for `key` at `now`. Returns false if over the limit,
+    /// in which case the event is not counted.
+    pub fn check(&mut self, key: &str, limit: RateLimit, now: Instant) -> bool {
+        let hits = self.hits.entry(key.to_string()).or_default();
+        while hits
+            .front()
+            .is_some_and(|t| now.saturating_duration_since(*t) >= limit.window)
+        {
+            hits.pop_front();
+        }
+        if hits.len() >= limit.max_events as usize {
+            return false;
+        }
+        hits.push_back(now);
+        true
+    }
+
+    /// Drop the counters for a key.
+    pub fn forget(&mut self, key: &str) {
+        self.hits.remove(key);
+    }
+
+    /// Number of keys being tracked.
+    pub fn tracked(&self) -> usize {
+        self.hits.len()
+    }
+}
+
+/// Applies a [`RelayPolicy`] to incoming events.
+///
+/// Clones share their rate limit counters.
+#[derive(Clone, Debug, Default)]
+pub struct RelayGuard {
+    policy: RelayPolicy,
+    pubkeys: Arc<Mutex<RateLimiter>>,
+    connections: Arc<Mutex<RateLimiter>>,
+}
+
+impl RelayGuard {
+    /// Create a guard for a policy.
+    pub fn new(policy: RelayPolicy) -> Self {
+        Self {
+            policy,
+            ..Self::default()
+        }
+    }
+
+    /// Get the policy.
+    pub fn policy(&self) -> &RelayPolicy {
+        &self.policy
+    }
+
+    /// Check whether to accept an event from `pubkey` on `connection_id`.
+    ///
+    /// Every attempt counts against the connection, but only events that
+    /// pass the other checks count against the pubkey.
+    pub fn admit(
+        &self,
+        connection_id: &str,
+        pubkey: &str,
+        event: &GameEvent,
+        now: Instant,
+    ) -> Result<(), PolicyViolation> {
+        if let Some(limit) = self.policy.connection_rate {
+            let mut connections = self.connections.lock().unwrap_or_else(|e| e.into_inner());
+            if !connections.check(connection_id, limit, now) {
+                return Err(PolicyViolation::ConnectionRateLimited(
+                    connection_id.to_string(),
+                ));
+            }
+        }
+
+        self.policy.check_event(event)?;
+
+        if let Some(limit) = self.policy.pubkey_rate {
+            let mut pubkeys = self.pubkeys.lock().unwrap_or_else(|e| e.into_inner());
+            if !pubkeys.check(pubkey, limit, now) {
+                return Err(PolicyViolation::PubkeyRateLimited(pubkey.to_string()));
+            }
+        }
+
+        Ok(())
+    }
+
+    /// Forget a closed connection's counters.
+    pub fn disconnect(&self, connection_id: &str) {
+        self.connections
+            .lock()
+            .unwrap_or_else(|e| e.into_inner())
+            .forget(connection_id);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use nostr_nations_core::events::{kinds, GameAction};
+    use nostr_nations_core::types::PlayerSlot;
+
+    fn event(id: &str) -> GameEvent {
+        let mut event = GameEvent::new(
+            GameId::new("game1"),
+            PlayerSlot(0),
+            None,
+            1,
+            1,
+            GameAction::EndTurn,
+        );
+        event.id = id.to_string();
+        event
+    }
+
+    // ==================== Event Check Tests ====================
+
+    #[test]
+    fn test_default_policy_accepts_everything() {
+        let guard = RelayGuard::default();
+        let now = Instant::now();
+        for _ in 0..100 {
+            assert!(guard.admit("conn", "alice", &event("ff"), now).is_ok());
+        }
+    }
+
+    #[test]
+    fn test_size_cap() {
+        let policy = RelayPolicy::new().with_max_event_bytes(10);
+        assert!(matches!(
+            policy.check_event(&event("ff")),
+            Err(PolicyViolation::TooLarge { max: 10, .. })
+        ));
+    }
+
+    #[test]
+    fn test_kind_allowlist_per_game() {
+        let policy = RelayPolicy::new().allow_kinds("game1", &[kinds::GAME_ACTION]);
+        assert_eq!(
+            policy.check_event(&event("ff")),
+            Err(PolicyViolation::KindNotAllowed {
+                kind: kinds::TURN_END,
+                game_id: GameId::new("game1")
+            })
+        );
+
+        let policy = policy.allow_kinds("game1", &[kinds::TURN_END]);
+        assert!(policy.check_event(&event("ff")).is_ok());
+
+        let mut other = event("ff");
+        other.game_id = GameId::new("game2");
+        assert!(RelayPolicy::new()
+            .allow_kinds("game1", &[])
+            .check_event(&other)
+            .is_ok());
+    }
+
+    #[test]
+    fn test_pow_difficulty() {
+        assert_eq!(pow_difficulty("ff"), 0);
+        assert_eq!(pow_difficulty("7f"), 1);
+        assert_eq!(pow_difficulty("000f"), 12);
+        assert_eq!(pow_difficulty("0001"), 15);
+        assert_eq!(pow_difficulty(""), 0);
+
+        let policy = RelayPolicy::new().with_min_pow(8);
+        assert!(policy.check_event(&event("00ff")).is_ok());
+        assert_eq!(
+            policy.check_event(&event("0fff")),
+            Err(PolicyViolation::InsufficientPow {
+                required: 8,
+                actual: 4
+            })
+        );
+    }
+
+    // ==================== Rate Limit Tests ====================
+
+    #[test]
+    fn test_pubkey_rate_limit_window() {
+        let guard = RelayGuard::new(RelayPolicy::new().with_pubkey_rate(RateLimit::per_second(2)));
+        let start = Instant::now();
+
+        assert!(guard.admit("c1", "alice", &event("ff"), start).is_ok());
+        assert!(guard.admit("c2", "alice", &event("ff"), start).is_ok());
+        assert_eq!(
+            guard.admit("c3", "alice", &event("ff"), start),
+            Err(PolicyViolation::PubkeyRateLimited("alice".to_string()))
+        );
+        assert!(guard.admit("c1", "bob", &event("ff"), start).is_ok());
+
+        let later = start + Duration::from_secs(1);
+        assert!(guard.admit("c1", "alice", &event("ff"), later).is_ok());
+    }
+
+    #[test]
+    fn test_connection_rate_counts_rejected_events() {
+        let policy = RelayPolicy::new()
+            .with_connection_rate(RateLimit::per_minute(2))
+            .with_min_pow(8);
+        let guard = RelayGuard::new(policy);
+        let now = Instant::now();
+
+        assert!(guard.admit("conn", "alice", &event("ff"), now).is_err());
+        assert!(guard.admit("conn", "bob", &event("00"), now).is_ok());
+        assert_eq!(
+            guard.admit("conn", "carol", &event("00"), now),
+            Err(PolicyViolation::ConnectionRateLimited("conn".to_string()))
+        );
+
+        guard.disconnect("conn");
+        assert!(guard.admit("conn", "carol", &event("00"), now).is_ok());
+    }
+}
@@ -0,0 +1,341 @@
+//! Relay selection from NIP-65 relay lists.
+//!
+//! Players publish a kind 10002 event whose `r` tags name the relays they
+//! read from and write to. [`RelaySelector`] keeps the latest list for each
+//! player and picks relays per player: events destined for a player are
+//! published to their write relays, and lobby discovery reads from their
+//! read relays. Players without a list (or without relays of the needed
+//! kind) fall back to the configured default relays.
+
+use crate::relay::filter::Filter;
+use std::collections::HashMap;
+
+/// Event kind of a NIP-65 relay list.
+pub const RELAY_LIST_KIND: u32 = 10002;
+
+/// One relay in a relay list.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct RelayListEntry {
+    /// Relay URL.
+    pub url: String,
+    /// Whether the player reads from this relay.
+    pub read: bool,
+    /// Whether the player writes to this relay.
+    pub write: bool,
+}
+
+/// A player's NIP-65 relay list.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct RelayList {
+    /// Public key of the player.
+    pub pubkey: String,
+    /// Creation time of the list event (Unix seconds).
+    pub created_at: u64,
+    /// Declared relays, in tag order.
+    pub relays: Vec<RelayListEntry>,
+}
+
+impl RelayList {
+    /// Create an empty relay list.
+    pub fn new(pubkey: String, created_at: u64) -> Self {
+        Self {
+            pubkey,
+            created_at,
+            relays: Vec::new(),
+        }
+    }
+
+    /// Add a relay. Invalid URLs and duplicates are ignored.
+    pub fn with_relay(mut self, url: &str, read: bool, write: bool) -> Self {
+        self.add_relay(url, read, write);
+        self
+    }
+
+    /// Parse a relay list from the tags of a kind 10002 event.
+    ///
+    /// `["r", url]` marks a read and write relay, `["r", url, "read"]` and
+    /// `["r", url, "write"]` mark one direction only. Other tags, unknown
+    /// markers and non-websocket URLs are skipped.
+    pub fn from_tags(pubkey: String, created_at: u64, tags: &[Vec<String>]) -> Self {
+        let mut list = Self::new(pubkey, created_at);
+        for tag in tags {
+            if tag.first().map(String::as_str) != Some("r") {
+                continue;
+            }
+            let Some(url) = tag.get(1) else {
+                continue;
+            };
+            let (read, write) = match tag.get(2).map(String::as_str) {
+                None | Some("") => (true, true),
+                Some("read") => (true, false),
+                Some("write") => (false, true),
+                Some(_) => continue,
+            };
+            list.add_relay(url, read, write);
+        }
+        list
+    }
+
+    /// Generate the `r` tags for publishing this list.
+    pub fn to_tags(&self) -> Vec<Vec<String>> {
+        self.relays
+            .iter()
+            .filter(|entry| entry.read || entry.write)
+            .map(|entry| {
+                let mut tag = vec!["r".to_string(), entry.url.clone()];
+                match (entry.read, entry.write) {
+                    (true, false) => tag.push("read".to_string()),
+                    (false, true) => tag.push("write".to_string()),
+                    _ => {}
+                }
+                tag
+            })
+            .collect()
+    }
+
+    /// Relays the player reads from.
+    pub fn read_relays(&self) -> Vec<String> {
+        self.relays
+            .iter()
+            .filter(|entry| entry.read)
+            .map(|entry| entry.url.clone())
+            .collect()
+    }
+
+    /// Relays the player writes to.
+    pub fn write_relays(&self) -> Vec<String> {
+        self.relays
+            .iter()
+            .filter(|entry| entry.write)
+            .map(|entry| entry.url.clone())
+            .collect()
+    }
+
+    fn add_relay(&mut self, url: &str, read: bool, write: bool) {
+        let Some(url) = normalize_url(url) else {
+            return;
+        };
+        match self.relays.iter_mut().find(|entry| entry.url == url) {
+            // Repeated tags for one relay combine their markers
+            Some(entry) => {
+                entry.read |= read;
+                entry.write |= write;
+            }
+            None => self.relays.push(RelayListEntry { url, read, write }),
+        }
+    }
+}
+
+/// Picks relays for players from their relay lists.
+#[derive(Clone, Debug, Default)]
+pub struct RelaySelector {
+    /// Relays used when a player has no usable list.
+    defaults: Vec<String>,
+    /// Latest relay list per player public key.
+    lists: HashMap<String, RelayList>,
+}
+
+impl RelaySelector {
+    /// Create a selector falling back to the given relays.
+    pub fn new(defaults: Vec<String>) -> Self {
+        let mut unique = Vec::new();
+        for url in defaults.iter().filter_map(|url| normalize_url(url)) {
+            if !unique.contains(&url) {
+                unique.push(url);
+            }
+        }
+        Self {
+            defaults: unique,
+            lists: HashMap::new(),
+        }
+    }
+
+    /// Get the default relays.
+    pub fn defaults(&self) -> &[String] {
+        &self.defaults
+    }
+
+    /// Store a player's relay list.
+    ///
+    /// Relay lists are replaceable, so an older list than the one held is
+    /// ignored. Returns whether the list was stored.
+    pub fn update(&mut self, list: RelayList) -> bool {
+        if self
+            .lists
+            .get(&list.pubkey)
+            .is_some_and(|existing| existing.created_at >= list.created_at)
+        {
+            return false;
+        }
+        self.lists.insert(list.pubkey.clone(), list);
+        true
+    }
+
+    /// Get a player's relay list.
+    pub fn get(&self, pubkey: &str) -> Option<&RelayList> {
+        self.lists.get(pubkey)
+    }
+
+    /// Forget a player's relay list.
+    pub fn remove(&mut self, pubkey: &str) -> Option<RelayList> {
+        self.lists.remove(pubkey)
+    }
+
+    /// Relays to publish events destined for these players to.
+    pub fn publish_relays(&self, pubkeys: &[&str]) -> Vec<String> {
+        self.select(pubkeys, RelayList::write_relays)
+    }
+
+    /// Relays to read these players' lobbies from.
+    pub fn discovery_relays(&self, pubkeys: &[&str]) -> Vec<String> {
+        self.select(pubkeys, RelayList::read_relays)
+    }
+
+    /// Filter fetching the relay lists of these players.
+    pub fn relay_list_filter(pubkeys: &[&str]) -> Filter {
+        Filter::kinds(vec![RELAY_LIST_KIND])
+            .with_authors(pubkeys.iter().map(|p| p.to_string()).collect())
+    }
+
+    /// Union of each player's relays, in order, without duplicates.
+    fn select(&self, pubkeys: &[&str], relays: fn(&RelayList) -> Vec<String>) -> Vec<String> {
+        let mut selected: Vec<String> = Vec::new();
+        let mut use_defaults = pubkeys.is_empty();
+
+        for pubkey in pubkeys {
+            let declared = self.lists.get(*pubkey).map(relays).unwrap_or_default();
+            if declared.is_empty() {
+                use_defaults = true;
+            }
+            for url in declared {
+                if !selected.contains(&url) {
+                    selected.push(url);
+                }
+            }
+        }
+
+        if use_defaults {
+            for url in &self.defaults {
+                if !selected.contains(url) {
+                    selected.push(url.clone());
+                }
+            }
+        }
+        selected
+    }
+}
+
+/// Trim a relay URL and drop its trailing slash. Returns `None` unless it is
+/// a websocket URL.
+fn normalize_url(url: &str) -> Option<String> {
+    let url = url.trim().trim_end_matches('/');
+    let host = url
+        .strip_prefix("wss://")
+        .or_else(|| url.strip_prefix("ws://"))?;
+    if host.is_empty() {
+        return None;
+    }
+    Some(url.to_string())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn tag(parts: &[&str]) -> Vec<String> {
+        parts.iter().map(|p| p.to_string()).collect()
+    }
+
+    // ==================== RelayList Tests ====================
+
+    #[test]
+    fn test_from_tags_parses_markers() {
+        let tags = vec![
+            tag(&["r", "wss://both.example/"]),
+            tag(&["r", "wss://in.example", "read"]),
+            tag(&["r", "wss://out.example", "write"]),
+            tag(&["r", "https://not-a-relay.example"]),
+            tag(&["r", "wss://odd.example", "sometimes"]),
+            tag(&["p", "wss://ignored.example"]),
+        ];
+        let list = RelayList::from_tags("alice".to_string(), 10, &tags);
+
+        assert_eq!(
+            list.read_relays(),
+            vec!["wss://both.example", "wss://in.example"]
+        );
+        assert_eq!(
+            list.write_relays(),
+            vec!["wss://both.example", "wss://out.example"]
+        );
+    }
+
+    #[test]
+    fn test_tags_roundtrip() {
+        let list = RelayList::new("alice".to_string(), 10)
+            .with_relay("wss://both.example", true, true)
+            .with_relay("wss://in.example", true, false)
+            .with_relay("wss://out.example", false, true);
+
+        let parsed = RelayList::from_tags("alice".to_string(), 10, &list.to_tags());
+        assert_eq!(parsed, list);
+    }
+
+    // ==================== RelaySelector Tests ====================
+
+    #[test]
+    fn test_selects_declared_relays() {
+        let mut selector = RelaySelector::new(vec!["wss://default.example".to_string()]);
+        selector.update(
+            RelayList::new("alice".to_string(), 10)
+                .with_relay("wss://in.example", true, false)
+                .with_relay("wss://out.example", false, true),
+        );
+
+        assert_eq!(
+            selector.publish_relays(&["alice"]),
+            vec!["wss://out.example"]
+        );
+        assert_eq!(
+            selector.discovery_relays(&["alice"]),
+            vec!["wss://in.example"]
+        );
+    }
+
+    #[test]
+    fn test_falls_back_to_defaults() {
+        let mut selector = RelaySelector::new(vec!["wss://default.example".to_string()]);
+        selector.update(RelayList::new("alice".to_string(), 10).with_relay(
+            "wss://in.example",
+            true,
+            false,
+        ));
+
+        // Alice declares no write relays, Bob has no list at all
+        assert_eq!(
+            selector.publish_relays(&["alice"]),
+            vec!["wss://default.example"]
+        );
+        assert_eq!(
+            selector.discovery_relays(&["alice", "bob"]),
+            vec!["wss://in.example", "wss://default.example"]
+        );
+        assert_eq!(selector.publish_relays(&[]), vec!["wss://default.example"]);
+    }
+
+    #[test]
+    fn test_update_keeps_newest_list() {
+        let mut selector = RelaySelector::default();
+        let newer =
+            RelayList::new("alice".to_string(), 20).with_relay("wss://new.example", true, true);
+        let older =
+            RelayList::new("alice".to_string(), 10).with_relay("wss://old.example", true, true);
+
+        assert!(selector.update(newer));
+        assert!(!selector.update(older));
+        assert_eq!(
+            selector.publish_relays(&["alice"]),
+            vec!["wss://new.example"]
+        );
+    }
+}
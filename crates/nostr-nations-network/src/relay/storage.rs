@@ -1,13 +1,53 @@
 //! SQLite storage backend for the local Nostr relay.
 //!
 //! Provides persistent storage for game events with NIP-01 compliant querying.
-
+//!
+//! File databases run in WAL mode by default (see [`StorageOptions`]), so
+//! readers aren't blocked while events are written. Bulk loads such as an
+//! initial sync should use [`RelayStorage::store_events`], which writes a
+//! whole batch in one transaction instead of one per event.
+//!
+//! With [`RelayStorage::with_encryption`], each event's full JSON is stored
+//! encrypted and its content column left empty. The columns used for
+//! filtering (ID, author, kind, timestamp, game ID and tags) stay readable
+//! so queries still work. Rows written before encryption was turned on are
+//! read as before; [`RelayStorage::encrypt_existing`] rewrites them.
+//! [`RelayStorage::export_ndjson`] writes decrypted events, so archives of
+//! an encrypted database need protecting separately.
+
+use crate::at_rest::{self, AtRestError, AtRestKey};
+use crate::relay::error::StorageError;
 use crate::relay::filter::Filter;
+use crate::relay::ndjson;
+use crate::relay::unix_now;
+use crate::reputation::ReputationRecord;
 use nostr_nations_core::events::GameEvent;
+use nostr_nations_core::types::{GameId, Npub};
+use rusqlite::types::Value;
 use rusqlite::{params, Connection};
+use std::io::{BufRead, Write};
 use std::path::Path;
 use std::sync::{Arc, Mutex};
 
+/// How a file database is opened.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct StorageOptions {
+    /// Use write-ahead logging with `synchronous = NORMAL` instead of a
+    /// rollback journal.
+    pub wal: bool,
+    /// Checkpoint the WAL into the database once it reaches this many pages.
+    pub wal_autocheckpoint: u32,
+}
+
+impl Default for StorageOptions {
+    fn default() -> Self {
+        Self {
+            wal: true,
+            wal_autocheckpoint: 1000,
+        }
+    }
+}
+
 /// SQLite-based storage for Nostr events.
 ///
 /// Thread-safe wrapper around SQLite connection with methods
@@ -15,45 +55,7 @@ use std::sync::{Arc, Mutex};
 #[derive(Clone)]
 pub struct RelayStorage {
     conn: Arc<Mutex<Connection>>,
-}
-
-/// Storage error types.
-#[derive(Debug)]
-pub enum StorageError {
-    /// SQLite error.
-    Sqlite(rusqlite::Error),
-    /// Event not found.
-    NotFound(String),
-    /// Serialization error.
-    Serialization(String),
-    /// Lock error.
-    LockError(String),
-}
-
-impl std::fmt::Display for StorageError {
-    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
-        match self {
-            StorageError::Sqlite(e) => write!(f, "SQLite error: {}", e),
-            StorageError::NotFound(id) => write!(f, "Event not found: {}", id),
-            StorageError::Serialization(msg) => write!(f, "Serialization error: {}", msg),
-            StorageError::LockError(msg) => write!(f, "Lock error: {}", msg),
-        }
-    }
-}
-
-impl std::error::Error for StorageError {
-    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
-        match self {
-            StorageError::Sqlite(e) => Some(e),
-            _ => None,
-        }
-    }
-}
-
-impl From<rusqlite::Error> for StorageError {
-    fn from(err: rusqlite::Error) -> Self {
-        StorageError::Sqlite(err)
-    }
+    cipher: Option<AtRestKey>,
 }
 
 impl RelayStorage {
@@ -62,6 +64,7 @@ impl RelayStorage {
         let conn = Connection::open_in_memory()?;
         let storage = Self {
             conn: Arc::new(Mutex::new(conn)),
+            cipher: None,
         };
         storage.init_db()?;
         Ok(storage)
@@ -69,9 +72,28 @@ impl RelayStorage {
 
     /// Create a new storage instance with a file-based database.
     pub fn new<P: AsRef<Path>>(path: P) -> Result<Self, StorageError> {
+        Self::open_with_options(path, StorageOptions::default())
+    }
+
+    /// Create a new storage instance with a file-based database opened
+    /// with the given options.
+    pub fn open_with_options<P: AsRef<Path>>(
+        path: P,
+        options: StorageOptions,
+    ) -> Result<Self, StorageError> {
         let conn = Connection::open(path)?;
+        if options.wal {
+            conn.query_row("PRAGMA journal_mode = WAL", [], |_| Ok(()))?;
+            conn.execute_batch("PRAGMA synchronous = NORMAL")?;
+            conn.query_row(
+                &format!("PRAGMA wal_autocheckpoint = {}", options.wal_autocheckpoint),
+                [],
+                |_| Ok(()),
+            )?;
+        }
         let storage = Self {
             conn: Arc::new(Mutex::new(conn)),
+            cipher: None,
         };
         storage.init_db()?;
         Ok(storage)
@@ -94,11 +116,20 @@ impl RelayStorage {
                 content TEXT NOT NULL,
                 sig TEXT,
                 game_id TEXT,
-                raw_event TEXT NOT NULL
+                raw_event TEXT NOT NULL,
+                expires_at INTEGER
             )",
             [],
         )?;
 
+        // Databases created before NIP-40 support lack the expiration column
+        let has_expires_at = conn
+            .prepare("SELECT 1 FROM pragma_table_info('events') WHERE name = 'expires_at'")?
+            .exists([])?;
+        if !has_expires_at {
+            conn.execute("ALTER TABLE events ADD COLUMN expires_at INTEGER", [])?;
+        }
+
         // Tags table - for efficient tag-based queries
         conn.execute(
             "CREATE TABLE IF NOT EXISTS tags (
@@ -120,6 +151,26 @@ impl RelayStorage {
             [],
         )?;
 
+        // Reputation tables - per-player history and the block list, kept
+        // across games
+        conn.execute(
+            "CREATE TABLE IF NOT EXISTS reputation (
+                npub TEXT PRIMARY KEY,
+                games_completed INTEGER NOT NULL,
+                abandons INTEGER NOT NULL,
+                desync_reports INTEGER NOT NULL,
+                cheat_reports INTEGER NOT NULL
+            )",
+            [],
+        )?;
+        conn.execute(
+            "CREATE TABLE IF NOT EXISTS blocked (
+                npub TEXT PRIMARY KEY,
+                reason TEXT NOT NULL
+            )",
+            [],
+        )?;
+
         // Create indexes for common queries
         conn.execute(
             "CREATE INDEX IF NOT EXISTS idx_events_kind ON events(kind)",
@@ -137,6 +188,10 @@ impl RelayStorage {
             "CREATE INDEX IF NOT EXISTS idx_events_game_id ON events(game_id)",
             [],
         )?;
+        conn.execute(
+            "CREATE INDEX IF NOT EXISTS idx_events_expires_at ON events(expires_at)",
+            [],
+        )?;
         conn.execute(
             "CREATE INDEX IF NOT EXISTS idx_tags_event_id ON tags(event_id)",
             [],
@@ -149,6 +204,28 @@ impl RelayStorage {
         Ok(())
     }
 
+    /// Encrypt events written from now on with `key`, and decrypt
+    /// encrypted events on read.
+    pub fn with_encryption(mut self, key: AtRestKey) -> Self {
+        self.cipher = Some(key);
+        self
+    }
+
+    /// Whether new events are written encrypted.
+    pub fn is_encrypted(&self) -> bool {
+        self.cipher.is_some()
+    }
+
+    /// The database's journal mode, e.g. "wal" or "memory".
+    pub fn journal_mode(&self) -> Result<String, StorageError> {
+        let conn = self
+            .conn
+            .lock()
+            .map_err(|e| StorageError::LockError(e.to_string()))?;
+
+        Ok(conn.query_row("PRAGMA journal_mode", [], |row| row.get(0))?)
+    }
+
     /// Store an event in the database.
     pub fn store_event(&self, event: &GameEvent) -> Result<(), StorageError> {
         let conn = self
@@ -156,39 +233,66 @@ impl RelayStorage {
             .lock()
             .map_err(|e| StorageError::LockError(e.to_string()))?;
 
-        let raw_event =
+        self.insert_event(&conn, event)
+    }
+
+    /// Store a batch of events in a single transaction.
+    ///
+    /// Either every event is stored or, on error, none are. Returns the
+    /// number of events stored.
+    pub fn store_events(&self, events: &[GameEvent]) -> Result<usize, StorageError> {
+        let mut conn = self
+            .conn
+            .lock()
+            .map_err(|e| StorageError::LockError(e.to_string()))?;
+
+        let tx = conn.transaction()?;
+        for event in events {
+            self.insert_event(&tx, event)?;
+        }
+        tx.commit()?;
+        Ok(events.len())
+    }
+
+    fn insert_event(&self, conn: &Connection, event: &GameEvent) -> Result<(), StorageError> {
+        let json =
             serde_json::to_string(event).map_err(|e| StorageError::Serialization(e.to_string()))?;
+        let (raw_event, content) = match &self.cipher {
+            Some(key) => (Value::Blob(key.encrypt(json.as_bytes())?), String::new()),
+            None => (Value::Text(json), event.content()),
+        };
 
-        let content = event.content();
         let kind = event.kind();
         let pubkey = event.player_id.to_string();
 
         // Insert the event
-        conn.execute(
-            "INSERT OR REPLACE INTO events (id, pubkey, kind, created_at, content, game_id, raw_event)
-             VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7)",
-            params![
+        conn.prepare_cached(
+            "INSERT OR REPLACE INTO events (id, pubkey, kind, created_at, content, game_id, raw_event, expires_at)
+             VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8)",
+        )?
+        .execute(params![
                 event.id,
                 pubkey,
                 kind,
                 event.timestamp,
                 content,
-                event.game_id,
-                raw_event
-            ],
-        )?;
+                event.game_id.as_str(),
+                raw_event,
+                event.expiration
+            ])?;
 
         // Delete old tags for this event (in case of update)
-        conn.execute("DELETE FROM tags WHERE event_id = ?1", params![event.id])?;
+        conn.prepare_cached("DELETE FROM tags WHERE event_id = ?1")?
+            .execute(params![event.id])?;
 
         // Insert tags
         let tags = event.tags();
+        let mut insert_tag = conn.prepare_cached(
+            "INSERT INTO tags (event_id, tag_name, tag_value) VALUES (?1, ?2, ?3)",
+        )?;
         for tag in &tags {
             if tag.len() >= 2 {
-                conn.execute(
-                    "INSERT INTO tags (event_id, tag_name, tag_value) VALUES (?1, ?2, ?3)",
-                    params![event.id, tag[0], tag[1]],
-                )?;
+                insert_tag.execute(params![event.id, tag[0], tag[1]])?;
             }
         }
 
@@ -202,7 +306,7 @@ impl RelayStorage {
             .lock()
             .map_err(|e| StorageError::LockError(e.to_string()))?;
 
-        let raw_event: String = conn
+        let raw_event: Value = conn
             .query_row(
                 "SELECT raw_event FROM events WHERE id = ?1",
                 params![id],
@@ -213,17 +317,153 @@ impl RelayStorage {
                 _ => StorageError::Sqlite(e),
             })?;
 
-        serde_json::from_str(&raw_event).map_err(|e| StorageError::Serialization(e.to_string()))
+        self.decode_event(raw_event)
+    }
+
+    /// Parse a stored `raw_event`, decrypting it if it was encrypted.
+    fn decode_event(&self, raw_event: Value) -> Result<GameEvent, StorageError> {
+        let json = match raw_event {
+            Value::Text(json) => json.into_bytes(),
+            Value::Blob(data) => at_rest::open(self.cipher.as_ref(), data)?,
+            other => {
+                return Err(StorageError::Serialization(format!(
+                    "unexpected raw_event type {:?}",
+                    other.data_type()
+                )))
+            }
+        };
+        serde_json::from_slice(&json).map_err(|e| StorageError::Serialization(e.to_string()))
+    }
+
+    /// Encrypt every event stored in plaintext, e.g. after turning
+    /// encryption on for an existing database. Returns the number of events
+    /// rewritten.
+    pub fn encrypt_existing(&self) -> Result<usize, StorageError> {
+        let key = self.cipher.as_ref().ok_or(AtRestError::KeyRequired)?;
+        let mut conn = self
+            .conn
+            .lock()
+            .map_err(|e| StorageError::LockError(e.to_string()))?;
+
+        let tx = conn.transaction()?;
+        let plaintext: Vec<(String, String)> = tx
+            .prepare("SELECT id, raw_event FROM events WHERE typeof(raw_event) = 'text'")?
+            .query_map([], |row| Ok((row.get(0)?, row.get(1)?)))?
+            .collect::<Result<_, _>>()?;
+        {
+            let mut update =
+                tx.prepare("UPDATE events SET raw_event = ?1, content = '' WHERE id = ?2")?;
+            for (id, json) in &plaintext {
+                update.execute(params![key.encrypt(json.as_bytes())?, id])?;
+            }
+        }
+        tx.commit()?;
+        Ok(plaintext.len())
     }
 
     /// Query events using a NIP-01 filter.
+    ///
+    /// Expired events are left out.
     pub fn query_events(&self, filter: &Filter) -> Result<Vec<GameEvent>, StorageError> {
         let conn = self
             .conn
             .lock()
             .map_err(|e| StorageError::LockError(e.to_string()))?;
 
-        let mut sql = String::from("SELECT DISTINCT e.raw_event FROM events e");
+        let (mut sql, params_vec) = Self::filter_query(filter);
+
+        // Order by created_at descending (newest first)
+        sql.push_str(" ORDER BY e.created_at DESC");
+
+        // Apply limit
+        if let Some(limit) = filter.limit {
+            sql.push_str(&format!(" LIMIT {}", limit));
+        }
+
+        // Execute query
+        let params_refs: Vec<&dyn rusqlite::ToSql> =
+            params_vec.iter().map(|p| p.as_ref()).collect();
+        let mut stmt = conn.prepare(&sql)?;
+        let rows = stmt.query_map(params_refs.as_slice(), |row| {
+            let raw_event: Value = row.get(0)?;
+            Ok(raw_event)
+        })?;
+
+        let mut events = Vec::new();
+        for row in rows {
+            match self.decode_event(row?) {
+                Ok(event) => events.push(event),
+                Err(StorageError::Serialization(_)) => {}
+                Err(e) => return Err(e),
+            }
+        }
+
+        Ok(events)
+    }
+
+    /// Write the events matching a filter to `writer` as NDJSON, oldest
+    /// first. Returns the number of events written.
+    ///
+    /// Rows are streamed from the database, so the export never holds more
+    /// than one event in memory. With a `limit`, the newest events are
+    /// exported. Expired events and events that fail to decode are left
+    /// out. The database is locked for the duration of the export.
+    pub fn export_ndjson<W: Write>(
+        &self,
+        filter: &Filter,
+        mut writer: W,
+    ) -> Result<usize, StorageError> {
+        let conn = self
+            .conn
+            .lock()
+            .map_err(|e| StorageError::LockError(e.to_string()))?;
+
+        let (base, params_vec) = Self::filter_query(filter);
+        let sql = match filter.limit {
+            Some(limit) => format!(
+                "SELECT raw_event FROM ({} ORDER BY e.created_at DESC LIMIT {}) ORDER BY created_at ASC, id ASC",
+                base, limit
+            ),
+            None => format!("{} ORDER BY e.created_at ASC, e.id ASC", base),
+        };
+
+        let params_refs: Vec<&dyn rusqlite::ToSql> =
+            params_vec.iter().map(|p| p.as_ref()).collect();
+        let mut stmt = conn.prepare(&sql)?;
+        let mut rows = stmt.query(params_refs.as_slice())?;
+
+        let mut written = 0;
+        while let Some(row) = rows.next()? {
+            match self.decode_event(row.get(0)?) {
+                Ok(event) => {
+                    ndjson::write_event(&mut writer, &event)?;
+                    written += 1;
+                }
+                Err(StorageError::Serialization(_)) => {}
+                Err(e) => return Err(e),
+            }
+        }
+        writer.flush()?;
+        Ok(written)
+    }
+
+    /// Store the events in an NDJSON archive, such as one written by
+    /// [`export_ndjson`](Self::export_ndjson). Returns the number of events
+    /// stored.
+    ///
+    /// Events are stored in transactions of
+    /// [`IMPORT_BATCH_SIZE`](ndjson::IMPORT_BATCH_SIZE); events already
+    /// present are replaced. A malformed line stops the import, keeping the
+    /// batches stored before it.
+    pub fn import_ndjson<R: BufRead>(&self, reader: R) -> Result<usize, StorageError> {
+        ndjson::read_events(reader, |events| self.store_events(events))
+    }
+
+    /// Build the `SELECT` for a filter, without ordering or limit.
+    ///
+    /// Selects `raw_event`, `created_at` and `id`.
+    fn filter_query(filter: &Filter) -> (String, Vec<Box<dyn rusqlite::ToSql>>) {
+        let mut sql = String::from("SELECT DISTINCT e.raw_event, e.created_at, e.id FROM events e");
         let mut conditions: Vec<String> = Vec::new();
         let mut params_vec: Vec<Box<dyn rusqlite::ToSql>> = Vec::new();
 
@@ -299,40 +539,18 @@ impl RelayStorage {
 
         if let Some(ref game_id) = filter.game_id {
             conditions.push("e.game_id = ?".to_string());
-            params_vec.push(Box::new(game_id.clone()));
+            params_vec.push(Box::new(game_id.to_string()));
         }
 
+        conditions.push("(e.expires_at IS NULL OR e.expires_at > ?)".to_string());
+        params_vec.push(Box::new(unix_now() as i64));
+
         if !conditions.is_empty() {
             sql.push_str(" WHERE ");
             sql.push_str(&conditions.join(" AND "));
         }
 
-        // Order by created_at descending (newest first)
-        sql.push_str(" ORDER BY e.created_at DESC");
-
-        // Apply limit
-        if let Some(limit) = filter.limit {
-            sql.push_str(&format!(" LIMIT {}", limit));
-        }
-
-        // Execute query
-        let params_refs: Vec<&dyn rusqlite::ToSql> =
-            params_vec.iter().map(|p| p.as_ref()).collect();
-        let mut stmt = conn.prepare(&sql)?;
-        let rows = stmt.query_map(params_refs.as_slice(), |row| {
-            let raw_event: String = row.get(0)?;
-            Ok(raw_event)
-        })?;
-
-        let mut events = Vec::new();
-        for row in rows {
-            let raw_event = row?;
-            if let Ok(event) = serde_json::from_str::<GameEvent>(&raw_event) {
-                events.push(event);
-            }
-        }
-
-        Ok(events)
+        (sql, params_vec)
     }
 
     /// Delete an event by ID.
@@ -348,6 +566,28 @@ impl RelayStorage {
         Ok(rows_affected > 0)
     }
 
+    /// Delete events that expired at or before `now` (Unix seconds).
+    ///
+    /// Returns the number of events deleted.
+    pub fn prune_expired(&self, now: u64) -> Result<usize, StorageError> {
+        let conn = self
+            .conn
+            .lock()
+            .map_err(|e| StorageError::LockError(e.to_string()))?;
+
+        conn.execute(
+            "DELETE FROM tags WHERE event_id IN
+             (SELECT id FROM events WHERE expires_at IS NOT NULL AND expires_at <= ?1)",
+            params![now as i64],
+        )?;
+        let rows_affected = conn.execute(
+            "DELETE FROM events WHERE expires_at IS NOT NULL AND expires_at <= ?1",
+            params![now as i64],
+        )?;
+
+        Ok(rows_affected)
+    }
+
     /// Get the number of stored events.
     pub fn event_count(&self) -> Result<usize, StorageError> {
         let conn = self
@@ -361,8 +601,8 @@ impl RelayStorage {
     }
 
     /// Get all events for a specific game.
-    pub fn get_game_events(&self, game_id: &str) -> Result<Vec<GameEvent>, StorageError> {
-        self.query_events(&Filter::game(game_id.to_string()))
+    pub fn get_game_events(&self, game_id: &GameId) -> Result<Vec<GameEvent>, StorageError> {
+        self.query_events(&Filter::game(game_id.clone()))
     }
 
     /// Delete all events for a specific game.
@@ -401,8 +641,8 @@ impl RelayStorage {
         let filter_json = serde_json::to_string(filter)
             .map_err(|e| StorageError::Serialization(e.to_string()))?;
 
-        let now = std::time::SystemTime::now()
-            .duration_since(std::time::UNIX_EPOCH)
+        let now = web_time::SystemTime::now()
+            .duration_since(web_time::UNIX_EPOCH)
             .unwrap()
             .as_secs();
 
@@ -447,17 +687,129 @@ impl RelayStorage {
 
         Ok(rows_affected > 0)
     }
+
+    /// Replace the stored reputation records and block list.
+    pub fn save_reputation(
+        &self,
+        records: &[&ReputationRecord],
+        blocks: &[(&Npub, &str)],
+    ) -> Result<(), StorageError> {
+        let mut conn = self
+            .conn
+            .lock()
+            .map_err(|e| StorageError::LockError(e.to_string()))?;
+
+        let tx = conn.transaction()?;
+        tx.execute("DELETE FROM reputation", [])?;
+        tx.execute("DELETE FROM blocked", [])?;
+        for record in records {
+            tx.execute(
+                "INSERT INTO reputation (npub, games_completed, abandons, desync_reports, cheat_reports)
+                 VALUES (?1, ?2, ?3, ?4, ?5)",
+                params![
+                    record.npub.as_str(),
+                    record.games_completed,
+                    record.abandons,
+                    record.desync_reports,
+                    record.cheat_reports
+                ],
+            )?;
+        }
+        for (npub, reason) in blocks {
+            tx.execute(
+                "INSERT INTO blocked (npub, reason) VALUES (?1, ?2)",
+                params![npub.as_str(), reason],
+            )?;
+        }
+        tx.commit()?;
+        Ok(())
+    }
+
+    /// Load the stored reputation records, in npub order.
+    pub fn load_reputation(&self) -> Result<Vec<ReputationRecord>, StorageError> {
+        let conn = self
+            .conn
+            .lock()
+            .map_err(|e| StorageError::LockError(e.to_string()))?;
+
+        let mut stmt = conn.prepare(
+            "SELECT npub, games_completed, abandons, desync_reports, cheat_reports
+             FROM reputation ORDER BY npub",
+        )?;
+        let records = stmt
+            .query_map([], |row| {
+                Ok(ReputationRecord {
+                    npub: Npub::new(row.get::<_, String>(0)?),
+                    games_completed: row.get(1)?,
+                    abandons: row.get(2)?,
+                    desync_reports: row.get(3)?,
+                    cheat_reports: row.get(4)?,
+                })
+            })?
+            .collect::<Result<Vec<_>, _>>()?;
+        Ok(records)
+    }
+
+    /// Load the stored block list as (npub, reason) pairs, in npub order.
+    pub fn load_blocks(&self) -> Result<Vec<(Npub, String)>, StorageError> {
+        let conn = self
+            .conn
+            .lock()
+            .map_err(|e| StorageError::LockError(e.to_string()))?;
+
+        let mut stmt = conn.prepare("SELECT npub, reason FROM blocked ORDER BY npub")?;
+        let blocks = stmt
+            .query_map([], |row| {
+                Ok((Npub::new(row.get::<_, String>(0)?), row.get(1)?))
+            })?
+            .collect::<Result<Vec<_>, _>>()?;
+        Ok(blocks)
+    }
+
+    /// Write any dirty pages to disk, and checkpoint the WAL if there is
+    /// one.
+    ///
+    /// Called before the app is backgrounded so a process killed while
+    /// suspended loses nothing.
+    pub fn flush(&self) -> Result<(), StorageError> {
+        let conn = self
+            .conn
+            .lock()
+            .map_err(|e| StorageError::LockError(e.to_string()))?;
+
+        conn.cache_flush()?;
+        let journal_mode: String = conn.query_row("PRAGMA journal_mode", [], |row| row.get(0))?;
+        if journal_mode == "wal" {
+            conn.query_row("PRAGMA wal_checkpoint(PASSIVE)", [], |_| Ok(()))?;
+        }
+        Ok(())
+    }
+
+    /// Run SQLite's quick integrity check.
+    ///
+    /// Returns false if the database file is damaged, e.g. after the OS
+    /// killed the app mid-write.
+    pub fn check_integrity(&self) -> Result<bool, StorageError> {
+        let conn = self
+            .conn
+            .lock()
+            .map_err(|e| StorageError::LockError(e.to_string()))?;
+
+        let result: String = conn.query_row("PRAGMA quick_check", [], |row| row.get(0))?;
+        Ok(result == "ok")
+    }
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
     use nostr_nations_core::events::GameAction;
+    use nostr_nations_core::types::PlayerSlot;
 
     fn create_test_event(id: &str, player_id: u8, game_id: &str, timestamp: u64) -> GameEvent {
         let mut event = GameEvent::new(
-            game_id.to_string(),
-            player_id,
+            GameId::new(game_id),
+            PlayerSlot(player_id),
             None,
             1,
             1,
@@ -515,6 +867,90 @@ mod tests {
         assert!(!deleted);
     }
 
+    #[test]
+    fn test_expired_events_hidden_and_pruned() {
+        let storage = RelayStorage::new_in_memory().unwrap();
+        storage
+            .store_event(&create_test_event("keep", 0, "game1", 1000))
+            .unwrap();
+        storage
+            .store_event(&create_test_event("old", 0, "game1", 1001).with_expiration(10))
+            .unwrap();
+
+        let events = storage.query_events(&Filter::new()).unwrap();
+        assert_eq!(events.len(), 1);
+        assert_eq!(events[0].id, "keep");
+
+        assert_eq!(storage.prune_expired(10).unwrap(), 1);
+        assert_eq!(storage.event_count().unwrap(), 1);
+    }
+
+    #[test]
+    fn test_init_db_adds_expiration_column() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("relay.db");
+        {
+            let conn = Connection::open(&path).unwrap();
+            conn.execute(
+                "CREATE TABLE events (
+                    id TEXT PRIMARY KEY,
+                    pubkey TEXT NOT NULL,
+                    kind INTEGER NOT NULL,
+                    created_at INTEGER NOT NULL,
+                    content TEXT NOT NULL,
+                    sig TEXT,
+                    game_id TEXT,
+                    raw_event TEXT NOT NULL
+                )",
+                [],
+            )
+            .unwrap();
+        }
+
+        let storage = RelayStorage::new(&path).unwrap();
+        storage
+            .store_event(&create_test_event("old", 0, "game1", 1000).with_expiration(10))
+            .unwrap();
+        assert_eq!(storage.prune_expired(10).unwrap(), 1);
+    }
+
+    #[test]
+    fn test_store_events_in_one_transaction() {
+        let storage = RelayStorage::new_in_memory().unwrap();
+        let events: Vec<GameEvent> = (0..50)
+            .map(|i| create_test_event(&format!("event{}", i), 0, "game1", 1000 + i))
+            .collect();
+
+        assert_eq!(storage.store_events(&events).unwrap(), 50);
+        assert_eq!(storage.event_count().unwrap(), 50);
+        let filter = Filter::new().with_game_id(GameId::new("game1"));
+        assert_eq!(storage.query_events(&filter).unwrap().len(), 50);
+
+        // Re-storing replaces rather than duplicates
+        assert_eq!(storage.store_events(&events[..10]).unwrap(), 10);
+        assert_eq!(storage.event_count().unwrap(), 50);
+        assert_eq!(storage.store_events(&[]).unwrap(), 0);
+    }
+
+    #[test]
+    fn test_file_storage_uses_wal() {
+        let dir = tempfile::tempdir().unwrap();
+        let storage = RelayStorage::new(dir.path().join("relay.db")).unwrap();
+        assert_eq!(storage.journal_mode().unwrap(), "wal");
+        storage
+            .store_event(&create_test_event("event1", 0, "game1", 1000))
+            .unwrap();
+        storage.flush().unwrap();
+
+        let options = StorageOptions {
+            wal: false,
+            ..Default::default()
+        };
+        let storage =
+            RelayStorage::open_with_options(dir.path().join("rollback.db"), options).unwrap();
+        assert_eq!(storage.journal_mode().unwrap(), "delete");
+    }
+
     #[test]
     fn test_query_events_by_ids() {
         let storage = RelayStorage::new_in_memory().unwrap();
@@ -593,7 +1029,7 @@ mod tests {
             .store_event(&create_test_event("event3", 0, "game1", 1002))
             .unwrap();
 
-        let filter = Filter::game("game1".to_string());
+        let filter = Filter::game(GameId::new("game1"));
         let events = storage.query_events(&filter).unwrap();
 
         assert_eq!(events.len(), 2);
@@ -655,7 +1091,7 @@ mod tests {
             .store_event(&create_test_event("event2", 0, "game2", 1001))
             .unwrap();
 
-        let events = storage.get_game_events("game1").unwrap();
+        let events = storage.get_game_events(&GameId::new("game1")).unwrap();
 
         assert_eq!(events.len(), 1);
         assert_eq!(events[0].game_id, "game1");
@@ -705,13 +1141,13 @@ mod tests {
 
         let filter = Filter::new()
             .with_kinds(vec![30100, 30101])
-            .with_game_id("game1".to_string());
+            .with_game_id(GameId::new("game1"));
 
         storage.store_subscription("sub1", &filter).unwrap();
 
         let retrieved = storage.get_subscription("sub1").unwrap();
         assert_eq!(retrieved.kinds, Some(vec![30100, 30101]));
-        assert_eq!(retrieved.game_id, Some("game1".to_string()));
+        assert_eq!(retrieved.game_id, Some(GameId::new("game1")));
     }
 
     #[test]
@@ -776,4 +1212,138 @@ mod tests {
         assert_eq!(storage1.event_count().unwrap(), 1);
         assert_eq!(storage2.event_count().unwrap(), 1);
     }
+
+    #[test]
+    fn test_encrypted_storage() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("relay.db");
+        let key = AtRestKey::from_identity_key(&[5; 32]);
+        let storage = RelayStorage::new(&path)
+            .unwrap()
+            .with_encryption(key.clone());
+
+        let event = create_test_event("event1", 0, "game1", 1000);
+        storage.store_event(&event).unwrap();
+        assert_eq!(storage.get_event("event1").unwrap().id, "event1");
+        let filter = Filter::new().with_game_id(GameId::new("game1"));
+        assert_eq!(storage.query_events(&filter).unwrap().len(), 1);
+
+        // Without the key the event can't be read
+        let locked = RelayStorage::new(&path).unwrap();
+        assert!(matches!(
+            locked.get_event("event1"),
+            Err(StorageError::Encryption(AtRestError::KeyRequired))
+        ));
+        let wrong = RelayStorage::new(&path)
+            .unwrap()
+            .with_encryption(AtRestKey::from_identity_key(&[6; 32]));
+        assert!(matches!(
+            wrong.query_events(&filter),
+            Err(StorageError::Encryption(AtRestError::WrongKey))
+        ));
+    }
+
+    #[test]
+    fn test_ndjson_round_trip() {
+        let storage = RelayStorage::new_in_memory().unwrap();
+        for (id, ts) in [("a", 100), ("b", 300), ("c", 200)] {
+            storage
+                .store_event(&create_test_event(id, 0, "game1", ts))
+                .unwrap();
+        }
+        storage
+            .store_event(&create_test_event("other", 1, "game2", 400))
+            .unwrap();
+
+        let mut archive = Vec::new();
+        let filter = Filter::new().with_game_id(GameId::new("game1"));
+        assert_eq!(storage.export_ndjson(&filter, &mut archive).unwrap(), 3);
+
+        let lines: Vec<serde_json::Value> = archive
+            .split(|b| *b == b'\n')
+            .filter(|line| !line.is_empty())
+            .map(|line| serde_json::from_slice(line).unwrap())
+            .collect();
+        let ids: Vec<&str> = lines.iter().map(|l| l["id"].as_str().unwrap()).collect();
+        assert_eq!(ids, vec!["a", "c", "b"]);
+
+        let restored = RelayStorage::new_in_memory().unwrap();
+        assert_eq!(restored.import_ndjson(archive.as_slice()).unwrap(), 3);
+        assert_eq!(restored.event_count().unwrap(), 3);
+        assert_eq!(restored.get_event("c").unwrap().timestamp, 200);
+    }
+
+    #[test]
+    fn test_ndjson_export_limit_keeps_newest() {
+        let storage = RelayStorage::new_in_memory().unwrap();
+        for (id, ts) in [("a", 100), ("b", 300), ("c", 200)] {
+            storage
+                .store_event(&create_test_event(id, 0, "game1", ts))
+                .unwrap();
+        }
+
+        let mut archive = Vec::new();
+        storage
+            .export_ndjson(&Filter::new().limit(2), &mut archive)
+            .unwrap();
+
+        let restored = RelayStorage::new_in_memory().unwrap();
+        restored.import_ndjson(archive.as_slice()).unwrap();
+        assert!(restored.get_event("a").is_err());
+        let first_line = archive.split(|b| *b == b'\n').next().unwrap();
+        let first: GameEvent = serde_json::from_slice(first_line).unwrap();
+        assert_eq!(first.id, "c");
+    }
+
+    #[test]
+    fn test_ndjson_export_decrypts_events() {
+        let storage = RelayStorage::new_in_memory()
+            .unwrap()
+            .with_encryption(AtRestKey::from_identity_key(&[5; 32]));
+        storage
+            .store_event(&create_test_event("event1", 0, "game1", 1000))
+            .unwrap();
+
+        let mut archive = Vec::new();
+        assert_eq!(
+            storage.export_ndjson(&Filter::new(), &mut archive).unwrap(),
+            1
+        );
+
+        let plain = RelayStorage::new_in_memory().unwrap();
+        assert_eq!(plain.import_ndjson(archive.as_slice()).unwrap(), 1);
+        assert_eq!(plain.get_event("event1").unwrap().timestamp, 1000);
+    }
+
+    #[test]
+    fn test_encrypt_existing_migrates_plaintext() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("relay.db");
+        let plain = RelayStorage::new(&path).unwrap();
+        plain
+            .store_events(&[
+                create_test_event("old1", 0, "game1", 1000),
+                create_test_event("old2", 0, "game1", 1001),
+            ])
+            .unwrap();
+
+        let key = AtRestKey::from_identity_key(&[5; 32]);
+        let storage = RelayStorage::new(&path).unwrap().with_encryption(key);
+        // Plaintext rows stay readable before and after migrating
+        assert_eq!(storage.get_event("old1").unwrap().id, "old1");
+        storage
+            .store_event(&create_test_event("new", 0, "game1", 1002))
+            .unwrap();
+        assert_eq!(storage.encrypt_existing().unwrap(), 2);
+        assert_eq!(storage.encrypt_existing().unwrap(), 0);
+        assert_eq!(storage.event_count().unwrap(), 3);
+        let filter = Filter::new().with_game_id(GameId::new("game1"));
+        assert_eq!(storage.query_events(&filter).unwrap().len(), 3);
+
+        assert!(matches!(
+            plain.get_event("old1"),
+            Err(StorageError::Encryption(AtRestError::KeyRequired))
+        ));
+        assert!(plain.encrypt_existing().is_err());
+    }
 }
@@ -4,6 +4,7 @@
 
 use crate::relay::filter::Filter;
 use nostr_nations_core::events::GameEvent;
+use nostr_nations_core::types::GameId;
 use std::collections::HashMap;
 use std::sync::{Arc, Mutex, RwLock};
 
@@ -274,7 +275,7 @@ impl SubscriptionBuilder {
     }
 
     /// Filter by game ID.
-    pub fn game(mut self, game_id: String) -> Self {
+    pub fn game(mut self, game_id: GameId) -> Self {
         self.filter = self.filter.with_game_id(game_id);
         self
     }
@@ -309,12 +310,13 @@ impl Default for SubscriptionBuilder {
 mod tests {
     use super::*;
     use nostr_nations_core::events::GameAction;
+    use nostr_nations_core::types::PlayerSlot;
     use std::sync::atomic::{AtomicUsize, Ordering};
 
     fn create_test_event(id: &str, player_id: u8, game_id: &str, timestamp: u64) -> GameEvent {
         let mut event = GameEvent::new(
-            game_id.to_string(),
-            player_id,
+            GameId::new(game_id),
+            PlayerSlot(player_id),
             None,
             1,
             1,
@@ -342,7 +344,7 @@ mod tests {
     fn test_subscription_matches() {
         let sub = Subscription::new(
             "sub1".to_string(),
-            Filter::game("game1".to_string()),
+            Filter::game(GameId::new("game1")),
             |_| {},
         );
 
@@ -445,12 +447,12 @@ mod tests {
         let count2_clone = count2.clone();
 
         // Subscribe to game1 events
-        manager.subscribe(Filter::game("game1".to_string()), move |_| {
+        manager.subscribe(Filter::game(GameId::new("game1")), move |_| {
             count1_clone.fetch_add(1, Ordering::SeqCst);
         });
 
         // Subscribe to game2 events
-        manager.subscribe(Filter::game("game2".to_string()), move |_| {
+        manager.subscribe(Filter::game(GameId::new("game2")), move |_| {
             count2_clone.fetch_add(1, Ordering::SeqCst);
         });
 
@@ -487,11 +489,11 @@ mod tests {
     fn test_manager_get_filter() {
         let manager = SubscriptionManager::new();
 
-        let filter = Filter::game("game1".to_string()).with_kinds(vec![30100]);
+        let filter = Filter::game(GameId::new("game1")).with_kinds(vec![30100]);
         manager.subscribe_with_id("sub1".to_string(), filter, |_| {});
 
         let retrieved = manager.get_filter("sub1").unwrap();
-        assert_eq!(retrieved.game_id, Some("game1".to_string()));
+        assert_eq!(retrieved.game_id, Some(GameId::new("game1")));
         assert_eq!(retrieved.kinds, Some(vec![30100]));
 
         // Non-existent subscription
@@ -562,12 +564,12 @@ mod tests {
 
         manager.subscribe_with_id(
             "sub1".to_string(),
-            Filter::game("game1".to_string()),
+            Filter::game(GameId::new("game1")),
             |_| {},
         );
         manager.subscribe_with_id(
             "sub2".to_string(),
-            Filter::game("game2".to_string()),
+            Filter::game(GameId::new("game2")),
             |_| {},
         );
         manager.subscribe_with_id("sub3".to_string(), Filter::new(), |_| {});
@@ -606,7 +608,7 @@ mod tests {
 
         let sub_id = SubscriptionBuilder::new()
             .with_id("custom_sub".to_string())
-            .game("game1".to_string())
+            .game(GameId::new("game1"))
             .kinds(vec![30100, 30101])
             .since(1000)
             .subscribe(&manager, move |_| {
@@ -617,7 +619,7 @@ mod tests {
         assert!(manager.has_subscription("custom_sub"));
 
         let filter = manager.get_filter("custom_sub").unwrap();
-        assert_eq!(filter.game_id, Some("game1".to_string()));
+        assert_eq!(filter.game_id, Some(GameId::new("game1")));
         assert_eq!(filter.kinds, Some(vec![30100, 30101]));
         assert_eq!(filter.since, Some(1000));
     }
@@ -627,7 +629,7 @@ mod tests {
         let manager = SubscriptionManager::new();
 
         let sub_id = SubscriptionBuilder::new()
-            .game("game1".to_string())
+            .game(GameId::new("game1"))
             .subscribe(&manager, |_| {});
 
         assert!(sub_id.starts_with("sub_"));
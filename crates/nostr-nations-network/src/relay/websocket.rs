@@ -0,0 +1,93 @@
+//! Browser websocket connection to a remote relay.
+//!
+//! Wraps a `web_sys::WebSocket` and moves frames between it and a
+//! [`RelayClient`]. Incoming frames are buffered by the socket's message
+//! handler and handed to the client on [`BrowserRelaySocket::poll`].
+//!
+//! Only compiled for `wasm32` targets.
+
+use crate::relay::client::{RelayClient, RelayClientError, RelayMessage};
+use std::cell::RefCell;
+use std::collections::VecDeque;
+use std::rc::Rc;
+use wasm_bindgen::{closure::Closure, JsCast};
+use web_sys::{MessageEvent, WebSocket};
+
+/// A websocket connection to one relay.
+pub struct BrowserRelaySocket {
+    socket: WebSocket,
+    inbox: Rc<RefCell<VecDeque<String>>>,
+    // Kept alive for as long as the socket may call it
+    _on_message: Closure<dyn FnMut(MessageEvent)>,
+}
+
+impl BrowserRelaySocket {
+    /// Open a websocket to the client's relay URL.
+    pub fn connect(client: &RelayClient) -> Result<Self, RelayClientError> {
+        let socket = WebSocket::new(client.url())
+            .map_err(|e| RelayClientError::Socket(format!("{:?}", e)))?;
+        let inbox = Rc::new(RefCell::new(VecDeque::new()));
+
+        let queue = inbox.clone();
+        let on_message = Closure::<dyn FnMut(MessageEvent)>::new(move |event: MessageEvent| {
+            if let Some(text) = event.data().as_string() {
+                queue.borrow_mut().push_back(text);
+            }
+        });
+        socket.set_onmessage(Some(on_message.as_ref().unchecked_ref()));
+
+        Ok(Self {
+            socket,
+            inbox,
+            _on_message: on_message,
+        })
+    }
+
+    /// Check if the socket is open.
+    pub fn is_open(&self) -> bool {
+        self.socket.ready_state() == WebSocket::OPEN
+    }
+
+    /// Send every frame the client has queued. Frames stay queued until the
+    /// socket is open.
+    pub fn flush(&self, client: &mut RelayClient) -> Result<usize, RelayClientError> {
+        if !self.is_open() {
+            return Ok(0);
+        }
+        let frames = client.take_outgoing();
+        for frame in &frames {
+            self.socket
+                .send_with_str(frame)
+                .map_err(|e| RelayClientError::Socket(format!("{:?}", e)))?;
+        }
+        Ok(frames.len())
+    }
+
+    /// Hand every received frame to the client and return the parsed
+    /// messages. Malformed frames are logged and skipped.
+    pub fn poll(&self, client: &mut RelayClient) -> Vec<RelayMessage> {
+        let frames: Vec<String> = self.inbox.borrow_mut().drain(..).collect();
+        frames
+            .iter()
+            .filter_map(|frame| match client.handle_frame(frame) {
+                Ok(message) => message,
+                Err(e) => {
+                    tracing::warn!(url = %client.url(), error = %e, "ignoring relay frame");
+                    None
+                }
+            })
+            .collect()
+    }
+
+    /// Close the socket.
+    pub fn close(&self) {
+        let _ = self.socket.close();
+    }
+}
+
+impl Drop for BrowserRelaySocket {
+    fn drop(&mut self) {
+        self.socket.set_onmessage(None);
+        self.close();
+    }
+}
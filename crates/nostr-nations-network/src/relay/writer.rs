@@ -0,0 +1,287 @@
+//! Background batching of event writes.
+//!
+//! Subscription callbacks run on whatever thread published the event, and
+//! writing each event to storage from there costs a transaction apiece.
+//! A [`BatchWriter`] instead queues events and writes them from a
+//! background thread with [`RelayStorage::store_events`], either once
+//! enough have queued up or after a short interval, whichever comes first.
+
+use crate::relay::error::StorageError;
+use crate::relay::RelayStorage;
+use nostr_nations_core::events::GameEvent;
+use std::sync::atomic::{AtomicBool, AtomicUsize, Ordering};
+use std::sync::{Arc, Mutex};
+use std::thread::{JoinHandle, Thread};
+use std::time::Duration;
+
+/// Default longest time an event waits in the queue.
+pub const DEFAULT_FLUSH_INTERVAL: Duration = Duration::from_millis(50);
+
+/// Default number of queued events that triggers an immediate flush.
+pub const DEFAULT_MAX_BATCH: usize = 256;
+
+/// Counters for a batch writer.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct BatchWriterStats {
+    /// Events written to storage.
+    pub events_written: usize,
+    /// Transactions used to write them.
+    pub batches: usize,
+}
+
+struct Shared {
+    storage: RelayStorage,
+    queue: Mutex<Vec<GameEvent>>,
+    events_written: AtomicUsize,
+    batches: AtomicUsize,
+}
+
+impl Shared {
+    /// Write everything queued. Events are put back if the write fails.
+    fn flush(&self) -> Result<usize, StorageError> {
+        let batch = {
+            let mut queue = self
+                .queue
+                .lock()
+                .map_err(|e| StorageError::LockError(e.to_string()))?;
+            std::mem::take(&mut *queue)
+        };
+        if batch.is_empty() {
+            return Ok(0);
+        }
+        match self.storage.store_events(&batch) {
+            Ok(written) => {
+                self.events_written.fetch_add(written, Ordering::Relaxed);
+                self.batches.fetch_add(1, Ordering::Relaxed);
+                Ok(written)
+            }
+            Err(error) => {
+                if let Ok(mut queue) = self.queue.lock() {
+                    queue.splice(0..0, batch);
+                }
+                Err(error)
+            }
+        }
+    }
+}
+
+/// Queues events for a [`BatchWriter`]; cheap to clone into callbacks.
+#[derive(Clone)]
+pub struct BatchWriterHandle {
+    shared: Arc<Shared>,
+    thread: Thread,
+    max_batch: usize,
+}
+
+impl BatchWriterHandle {
+    /// Queue an event to be written.
+    pub fn write(&self, event: &GameEvent) {
+        let queued = match self.shared.queue.lock() {
+            Ok(mut queue) => {
+                queue.push(event.clone());
+                queue.len()
+            }
+            Err(error) => {
+                tracing::warn!(%error, event_id = %event.id, "dropping queued write");
+                return;
+            }
+        };
+        if queued >= self.max_batch {
+            self.thread.unpark();
+        }
+    }
+
+    /// Number of events waiting to be written.
+    pub fn queued(&self) -> usize {
+        self.shared.queue.lock().map(|q| q.len()).unwrap_or(0)
+    }
+}
+
+/// Writes queued events to storage in batches on a background thread.
+///
+/// Runs until dropped or [`stop`](Self::stop)ped; stopping writes whatever
+/// is still queued.
+pub struct BatchWriter {
+    handle: BatchWriterHandle,
+    shutdown: Arc<AtomicBool>,
+    thread: Option<JoinHandle<()>>,
+}
+
+impl BatchWriter {
+    /// Start writing to `storage` with the default interval and batch size.
+    pub fn start(storage: RelayStorage) -> std::io::Result<Self> {
+        Self::start_with(storage, DEFAULT_FLUSH_INTERVAL, DEFAULT_MAX_BATCH)
+    }
+
+    /// Start writing to `storage` every `interval`, or as soon as
+    /// `max_batch` events are queued.
+    pub fn start_with(
+        storage: RelayStorage,
+        interval: Duration,
+        max_batch: usize,
+    ) -> std::io::Result<Self> {
+        let shared = Arc::new(Shared {
+            storage,
+            queue: Mutex::new(Vec::new()),
+            events_written: AtomicUsize::new(0),
+            batches: AtomicUsize::new(0),
+        });
+        let shutdown = Arc::new(AtomicBool::new(false));
+
+        let stop = shutdown.clone();
+        let writer = shared.clone();
+        let thread = std::thread::Builder::new()
+            .name("relay-writer".to_string())
+            .spawn(move || loop {
+                std::thread::park_timeout(interval);
+                let stopping = stop.load(Ordering::Relaxed);
+                if let Err(error) = writer.flush() {
+                    tracing::warn!(%error, "failed to write queued events");
+                }
+                if stopping {
+                    break;
+                }
+            })?;
+
+        Ok(Self {
+            handle: BatchWriterHandle {
+                shared,
+                thread: thread.thread().clone(),
+                max_batch: max_batch.max(1),
+            },
+            shutdown,
+            thread: Some(thread),
+        })
+    }
+
+    /// A handle for queueing events.
+    pub fn handle(&self) -> BatchWriterHandle {
+        self.handle.clone()
+    }
+
+    /// Queue an event to be written.
+    pub fn write(&self, event: &GameEvent) {
+        self.handle.write(event);
+    }
+
+    /// A subscription callback that queues every event it is notified of.
+    pub fn subscriber(&self) -> impl Fn(&GameEvent) + Send + Sync + 'static {
+        let handle = self.handle();
+        move |event| handle.write(event)
+    }
+
+    /// Write everything queued now, on the calling thread.
+    pub fn flush(&self) -> Result<usize, StorageError> {
+        self.handle.shared.flush()
+    }
+
+    /// Events written and transactions used so far.
+    pub fn stats(&self) -> BatchWriterStats {
+        BatchWriterStats {
+            events_written: self.handle.shared.events_written.load(Ordering::Relaxed),
+            batches: self.handle.shared.batches.load(Ordering::Relaxed),
+        }
+    }
+
+    /// Write what is queued, then stop the thread and wait for it to exit.
+    pub fn stop(&mut self) {
+        let Some(thread) = self.thread.take() else {
+            return;
+        };
+        self.shutdown.store(true, Ordering::Relaxed);
+        thread.thread().unpark();
+        let _ = thread.join();
+    }
+}
+
+impl Drop for BatchWriter {
+    fn drop(&mut self) {
+        self.stop();
+    }
+}
+
+impl std::fmt::Debug for BatchWriter {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("BatchWriter")
+            .field("running", &self.thread.is_some())
+            .field("queued", &self.handle.queued())
+            .finish()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::relay::{Filter, LocalRelay};
+    use nostr_nations_core::events::GameAction;
+    use nostr_nations_core::types::{GameId, PlayerSlot};
+
+    fn create_test_event(id: &str) -> GameEvent {
+        let mut event = GameEvent::new(
+            GameId::new("game1"),
+            PlayerSlot(0),
+            None,
+            1,
+            1,
+            GameAction::EndTurn,
+        );
+        event.id = id.to_string();
+        event
+    }
+
+    #[test]
+    fn test_writer_batches_subscription_writes() {
+        let source = LocalRelay::new_in_memory().unwrap();
+        let mirror = RelayStorage::new_in_memory().unwrap();
+        let mut writer =
+            BatchWriter::start_with(mirror.clone(), Duration::from_secs(60), 10).unwrap();
+        source.subscribe(Filter::new(), writer.subscriber());
+
+        for i in 0..10 {
+            source
+                .publish(&create_test_event(&format!("e{}", i)))
+                .unwrap();
+        }
+        // Reaching the batch size wakes the writer
+        for _ in 0..100 {
+            if mirror.event_count().unwrap() == 10 {
+                break;
+            }
+            std::thread::sleep(Duration::from_millis(10));
+        }
+        assert_eq!(mirror.event_count().unwrap(), 10);
+        assert_eq!(writer.stats().batches, 1);
+
+        // Stopping writes the rest
+        for i in 10..15 {
+            source
+                .publish(&create_test_event(&format!("e{}", i)))
+                .unwrap();
+        }
+        writer.stop();
+        assert_eq!(mirror.event_count().unwrap(), 15);
+        assert_eq!(
+            writer.stats(),
+            BatchWriterStats {
+                events_written: 15,
+                batches: 2
+            }
+        );
+    }
+
+    #[test]
+    fn test_writer_flushes_on_interval() {
+        let storage = RelayStorage::new_in_memory().unwrap();
+        let writer =
+            BatchWriter::start_with(storage.clone(), Duration::from_millis(10), 1000).unwrap();
+        writer.write(&create_test_event("e1"));
+        for _ in 0..100 {
+            if storage.event_count().unwrap() == 1 {
+                break;
+            }
+            std::thread::sleep(Duration::from_millis(10));
+        }
+        assert_eq!(storage.event_count().unwrap(), 1);
+        assert_eq!(writer.flush().unwrap(), 0);
+    }
+}
@@ -0,0 +1,493 @@
+//! Player reputation and block lists shared across games.
+//!
+//! A [`ReputationStore`] keeps a local record for every npub this client
+//! has played with: games completed, games abandoned, and reported desyncs
+//! or cheating. It also holds the [`BlockList`] of npubs the player never
+//! wants to meet again; lobbies refuse their join applications and peer
+//! managers refuse their handshakes.
+//!
+//! With the `sqlite` feature the store is kept in the relay database next
+//! to the game events, so it survives restarts and outlives any one game.
+//!
+//! Abandons can optionally be published as signed [`AbandonReport`]
+//! events. Collecting other players' reports and passing them to
+//! [`BlockList::from_reports`] builds a community block list.
+
+#[cfg(feature = "sqlite")]
+use crate::relay::{RelayStorage, StorageError};
+use crate::signing::{verify_signature, SigningKey};
+use nostr_nations_core::event_kinds::ABANDON_REPORT;
+use nostr_nations_core::types::{GameId, Npub};
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+use std::collections::{BTreeMap, BTreeSet};
+use std::fmt;
+use ts_rs::TS;
+
+/// Local record of one player's history.
+#[derive(Clone, Debug, Default, PartialEq, Eq, Serialize, Deserialize, JsonSchema, TS)]
+pub struct ReputationRecord {
+    /// The player.
+    pub npub: Npub,
+    /// Games the player stayed in until the end.
+    pub games_completed: u32,
+    /// Games the player left before the end.
+    pub abandons: u32,
+    /// Desyncs reported against the player.
+    pub desync_reports: u32,
+    /// Cheating reported against the player.
+    pub cheat_reports: u32,
+}
+
+impl ReputationRecord {
+    /// Create an empty record.
+    pub fn new(npub: Npub) -> Self {
+        Self {
+            npub,
+            ..Self::default()
+        }
+    }
+
+    /// Fraction of finished games the player abandoned, or `None` if they
+    /// haven't finished one.
+    pub fn abandon_rate(&self) -> Option<f32> {
+        let total = self.games_completed + self.abandons;
+        (total > 0).then(|| self.abandons as f32 / total as f32)
+    }
+}
+
+/// Something reported against a player.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash, Serialize, Deserialize, JsonSchema, TS)]
+pub enum ReportKind {
+    /// The player left a game before it ended.
+    Abandon,
+    /// The player's game state diverged from everyone else's.
+    Desync,
+    /// The player was caught cheating.
+    Cheat,
+}
+
+/// Npubs refused by lobbies and peer managers, with the reason each was
+/// blocked.
+#[derive(Clone, Debug, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct BlockList {
+    entries: BTreeMap<Npub, String>,
+}
+
+impl BlockList {
+    /// Create an empty block list.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Block an npub. Returns `false` if it was already blocked; the
+    /// reason is updated either way.
+    pub fn block(&mut self, npub: Npub, reason: impl Into<String>) -> bool {
+        self.entries.insert(npub, reason.into()).is_none()
+    }
+
+    /// Unblock an npub. Returns `false` if it wasn't blocked.
+    pub fn unblock(&mut self, npub: &Npub) -> bool {
+        self.entries.remove(npub).is_some()
+    }
+
+    /// Check if an npub is blocked.
+    pub fn is_blocked(&self, npub: &Npub) -> bool {
+        self.entries.contains_key(npub)
+    }
+
+    /// Why an npub was blocked.
+    pub fn reason(&self, npub: &Npub) -> Option<&str> {
+        self.entries.get(npub).map(String::as_str)
+    }
+
+    /// Blocked npubs and their reasons, in npub order.
+    pub fn iter(&self) -> impl Iterator<Item = (&Npub, &str)> {
+        self.entries
+            .iter()
+            .map(|(npub, reason)| (npub, reason.as_str()))
+    }
+
+    /// Add every entry of another list, keeping our own reasons.
+    pub fn merge(&mut self, other: &BlockList) {
+        for (npub, reason) in &other.entries {
+            self.entries
+                .entry(npub.clone())
+                .or_insert_with(|| reason.clone());
+        }
+    }
+
+    /// Number of blocked npubs.
+    pub fn len(&self) -> usize {
+        self.entries.len()
+    }
+
+    /// Check if no npubs are blocked.
+    pub fn is_empty(&self) -> bool {
+        self.entries.is_empty()
+    }
+
+    /// Build a block list from published abandon reports.
+    ///
+    /// An npub is blocked once `threshold` different reporters have
+    /// reported it. Unsigned reports and self-reports are ignored, and a
+    /// reporter counts once per subject however many games they report.
+    pub fn from_reports<'a>(
+        reports: impl IntoIterator<Item = &'a AbandonReport>,
+        threshold: usize,
+    ) -> Self {
+        let mut reporters: BTreeMap<&Npub, BTreeSet<&Npub>> = BTreeMap::new();
+        for report in reports {
+            if report.is_signed() && report.reporter != report.subject {
+                reporters
+                    .entry(&report.subject)
+                    .or_default()
+                    .insert(&report.reporter);
+            }
+        }
+
+        let mut list = Self::new();
+        for (subject, by) in reporters {
+            if by.len() >= threshold.max(1) {
+                list.block(
+                    subject.clone(),
+                    format!("Abandons reported by {} players", by.len()),
+                );
+            }
+        }
+        list
+    }
+}
+
+/// Reputation of every player met, and the local block list.
+#[derive(Clone, Debug, Default)]
+pub struct ReputationStore {
+    records: BTreeMap<Npub, ReputationRecord>,
+    blocks: BlockList,
+}
+
+impl ReputationStore {
+    /// Create an empty store.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    fn record_mut(&mut self, npub: &Npub) -> &mut ReputationRecord {
+        self.records
+            .entry(npub.clone())
+            .or_insert_with(|| ReputationRecord::new(npub.clone()))
+    }
+
+    /// Count a game the player finished.
+    pub fn record_completed(&mut self, npub: &Npub) {
+        self.record_mut(npub).games_completed += 1;
+    }
+
+    /// Count a report against a player.
+    pub fn record_report(&mut self, npub: &Npub, kind: ReportKind) {
+        let record = self.record_mut(npub);
+        match kind {
+            ReportKind::Abandon => record.abandons += 1,
+            ReportKind::Desync => record.desync_reports += 1,
+            ReportKind::Cheat => record.cheat_reports += 1,
+        }
+    }
+
+    /// Count an abandon and return a report of it signed by `reporter`,
+    /// for publishing if the player chooses to share it.
+    pub fn report_abandon(
+        &mut self,
+        reporter: &SigningKey,
+        subject: Npub,
+        game_id: GameId,
+        turn: u32,
+        created_at: u64,
+    ) -> AbandonReport {
+        self.record_report(&subject, ReportKind::Abandon);
+        AbandonReport::signed(game_id, reporter, subject, turn, created_at)
+    }
+
+    /// Get a player's record.
+    pub fn get(&self, npub: &Npub) -> Option<&ReputationRecord> {
+        self.records.get(npub)
+    }
+
+    /// Every record, in npub order.
+    pub fn records(&self) -> impl Iterator<Item = &ReputationRecord> {
+        self.records.values()
+    }
+
+    /// The local block list.
+    pub fn block_list(&self) -> &BlockList {
+        &self.blocks
+    }
+
+    /// Block an npub. Returns `false` if it was already blocked.
+    pub fn block(&mut self, npub: Npub, reason: impl Into<String>) -> bool {
+        self.blocks.block(npub, reason)
+    }
+
+    /// Unblock an npub. Returns `false` if it wasn't blocked.
+    pub fn unblock(&mut self, npub: &Npub) -> bool {
+        self.blocks.unblock(npub)
+    }
+
+    /// Check if an npub is blocked.
+    pub fn is_blocked(&self, npub: &Npub) -> bool {
+        self.blocks.is_blocked(npub)
+    }
+
+    /// Load the store from the relay database.
+    #[cfg(feature = "sqlite")]
+    pub fn load_from(storage: &RelayStorage) -> Result<Self, StorageError> {
+        let records = storage
+            .load_reputation()?
+            .into_iter()
+            .map(|record| (record.npub.clone(), record))
+            .collect();
+        let mut blocks = BlockList::new();
+        for (npub, reason) in storage.load_blocks()? {
+            blocks.block(npub, reason);
+        }
+        Ok(Self { records, blocks })
+    }
+
+    /// Save the whole store to the relay database, replacing what was
+    /// there.
+    #[cfg(feature = "sqlite")]
+    pub fn save_to(&self, storage: &RelayStorage) -> Result<(), StorageError> {
+        let records: Vec<&ReputationRecord> = self.records.values().collect();
+        let blocks: Vec<(&Npub, &str)> = self.blocks.iter().collect();
+        storage.save_reputation(&records, &blocks)
+    }
+}
+
+/// A signed report that a player abandoned a game.
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct AbandonReport {
+    /// Game that was abandoned.
+    pub game_id: GameId,
+    /// Player making the report.
+    pub reporter: Npub,
+    /// Player who left.
+    pub subject: Npub,
+    /// Turn the player left on.
+    pub turn: u32,
+    /// When the report was signed, in Unix seconds.
+    pub created_at: u64,
+    /// Reporter's signature over the report digest.
+    pub signature: Vec<u8>,
+}
+
+impl AbandonReport {
+    /// Create a report signed by `reporter`.
+    pub fn signed(
+        game_id: GameId,
+        reporter: &SigningKey,
+        subject: Npub,
+        turn: u32,
+        created_at: u64,
+    ) -> Self {
+        let mut report = Self {
+            game_id,
+            reporter: reporter.npub(),
+            subject,
+            turn,
+            created_at,
+            signature: Vec::new(),
+        };
+        report.signature = reporter.sign(&report.digest());
+        report
+    }
+
+    /// Digest of the report fields covered by the signature.
+    pub fn digest(&self) -> [u8; 32] {
+        let mut hasher = Sha256::new();
+        hasher.update(self.game_id.as_str().as_bytes());
+        hasher.update([0]);
+        hasher.update(self.reporter.as_str().as_bytes());
+        hasher.update([0]);
+        hasher.update(self.subject.as_str().as_bytes());
+        hasher.update([0]);
+        hasher.update(self.turn.to_le_bytes());
+        hasher.update(self.created_at.to_le_bytes());
+        hasher.finalize().into()
+    }
+
+    /// Check whether the signature was made by the reporter over these
+    /// fields.
+    pub fn is_signed(&self) -> bool {
+        verify_signature(self.reporter.as_str(), &self.digest(), &self.signature)
+    }
+
+    /// Build the event to publish to relays.
+    pub fn to_event(&self) -> ReportEvent {
+        ReportEvent {
+            kind: ABANDON_REPORT,
+            pubkey: self.reporter.to_string(),
+            content: serde_json::to_string(self).unwrap_or_default(),
+            tags: vec![
+                vec![
+                    "d".to_string(),
+                    format!("{}:{}", self.game_id, self.subject),
+                ],
+                vec!["g".to_string(), self.game_id.to_string()],
+                vec!["p".to_string(), self.subject.to_string()],
+            ],
+            created_at: self.created_at,
+        }
+    }
+
+    /// Read a report from a published event.
+    ///
+    /// Fails if the event isn't an abandon report, or its author didn't
+    /// sign the report it carries.
+    pub fn from_event(event: &ReportEvent) -> Result<Self, ReportError> {
+        if event.kind != ABANDON_REPORT {
+            return Err(ReportError::WrongKind(event.kind));
+        }
+        let report: Self = serde_json::from_str(&event.content)
+            .map_err(|e| ReportError::Malformed(e.to_string()))?;
+        if report.reporter.as_str() != event.pubkey || !report.is_signed() {
+            return Err(ReportError::BadSignature);
+        }
+        Ok(report)
+    }
+}
+
+/// A reputation report ready to be published to relays.
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct ReportEvent {
+    /// Nostr event kind.
+    pub kind: u32,
+    /// Author pubkey.
+    pub pubkey: String,
+    /// JSON content.
+    pub content: String,
+    /// Nostr tags.
+    pub tags: Vec<Vec<String>>,
+    /// Unix timestamp.
+    pub created_at: u64,
+}
+
+/// Reasons a published report is refused.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum ReportError {
+    /// The event is of another kind.
+    WrongKind(u32),
+    /// The content isn't a report.
+    Malformed(String),
+    /// The report isn't signed by the event's author.
+    BadSignature,
+}
+
+impl fmt::Display for ReportError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ReportError::WrongKind(kind) => write!(f, "Not a report event: kind {}", kind),
+            ReportError::Malformed(msg) => write!(f, "Malformed report: {}", msg),
+            ReportError::BadSignature => write!(f, "Report signature is invalid"),
+        }
+    }
+}
+
+impl std::error::Error for ReportError {}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn key(name: &str) -> SigningKey {
+        SigningKey::from_secret_bytes(&Sha256::digest(name.as_bytes()).into()).unwrap()
+    }
+
+    fn npub(name: &str) -> Npub {
+        key(name).npub()
+    }
+
+    #[test]
+    fn test_records_count_games_and_reports() {
+        let mut store = ReputationStore::new();
+        store.record_completed(&npub("alice"));
+        store.record_completed(&npub("alice"));
+        store.record_report(&npub("alice"), ReportKind::Desync);
+        let report = store.report_abandon(&key("bob"), npub("alice"), GameId::new("g1"), 12, 100);
+
+        let alice = store.get(&npub("alice")).unwrap();
+        assert_eq!(alice.games_completed, 2);
+        assert_eq!(alice.abandons, 1);
+        assert_eq!(alice.desync_reports, 1);
+        assert_eq!(alice.cheat_reports, 0);
+        assert!((alice.abandon_rate().unwrap() - 1.0 / 3.0).abs() < 1e-6);
+        assert!(store.get(&npub("bob")).is_none());
+        assert!(report.is_signed());
+    }
+
+    #[test]
+    fn test_report_event_round_trip_and_forgery() {
+        let report = AbandonReport::signed(GameId::new("g1"), &key("bob"), npub("alice"), 5, 100);
+        let event = report.to_event();
+        assert_eq!(event.kind, ABANDON_REPORT);
+        assert_eq!(AbandonReport::from_event(&event).unwrap(), report);
+
+        let mut forged = event.clone();
+        forged.pubkey = "mallory".to_string();
+        assert_eq!(
+            AbandonReport::from_event(&forged),
+            Err(ReportError::BadSignature)
+        );
+
+        let mut tampered = report.clone();
+        tampered.turn = 1;
+        assert!(!tampered.is_signed());
+
+        // Mallory can't file a report in Bob's name
+        let mut impersonated = report.clone();
+        impersonated.signature = key("mallory").sign(&impersonated.digest());
+        assert!(!impersonated.is_signed());
+    }
+
+    #[test]
+    fn test_shared_block_list_needs_distinct_reporters() {
+        let reports = vec![
+            AbandonReport::signed(GameId::new("g1"), &key("bob"), npub("alice"), 5, 100),
+            AbandonReport::signed(GameId::new("g2"), &key("bob"), npub("alice"), 7, 200),
+            AbandonReport::signed(GameId::new("g3"), &key("carol"), npub("alice"), 2, 300),
+            AbandonReport::signed(GameId::new("g3"), &key("carol"), npub("dave"), 2, 300),
+            AbandonReport::signed(GameId::new("g4"), &key("dave"), npub("dave"), 2, 300),
+        ];
+
+        let list = BlockList::from_reports(&reports, 2);
+        assert!(list.is_blocked(&npub("alice")));
+        assert!(!list.is_blocked(&npub("dave")));
+        assert_eq!(list.len(), 1);
+
+        let mut local = BlockList::new();
+        local.block(npub("alice"), "rage quit");
+        local.merge(&list);
+        assert_eq!(local.reason(&npub("alice")), Some("rage quit"));
+    }
+
+    #[cfg(feature = "sqlite")]
+    #[test]
+    fn test_store_persists_in_relay_db() {
+        let storage = RelayStorage::new_in_memory().unwrap();
+        let mut store = ReputationStore::new();
+        store.record_completed(&npub("alice"));
+        store.record_report(&npub("bob"), ReportKind::Cheat);
+        store.block(npub("bob"), "cheating");
+        store.save_to(&storage).unwrap();
+
+        store.unblock(&npub("bob"));
+        store.save_to(&storage).unwrap();
+        store.block(npub("carol"), "spam");
+        store.save_to(&storage).unwrap();
+
+        let loaded = ReputationStore::load_from(&storage).unwrap();
+        assert_eq!(loaded.get(&npub("alice")).unwrap().games_completed, 1);
+        assert_eq!(loaded.get(&npub("bob")).unwrap().cheat_reports, 1);
+        assert!(!loaded.is_blocked(&npub("bob")));
+        assert_eq!(loaded.block_list().reason(&npub("carol")), Some("spam"));
+    }
+}
@@ -0,0 +1,247 @@
+//! Schnorr signatures by players' Nostr keys.
+//!
+//! Anything a player vouches for outside a signed game event (state
+//! queries, match results, abandon reports) is signed with the same
+//! secp256k1 key as their Nostr identity, using BIP-340 Schnorr signatures
+//! as in NIP-01. Callers hash their own fields into a 32-byte digest;
+//! this module only signs and verifies digests.
+//!
+//! The same key also agrees shared secrets with other players (ECDH), for
+//! the NIP-44 direct messages in [`crate::nip44`].
+
+use bech32::{Bech32, Hrp};
+use nostr_nations_core::types::Npub;
+use secp256k1::{ecdh, schnorr, Keypair, Message, Parity, XOnlyPublicKey, SECP256K1};
+use std::fmt;
+
+/// A player's Nostr secret key, used to sign digests.
+#[derive(Clone)]
+pub struct SigningKey {
+    keypair: Keypair,
+}
+
+impl SigningKey {
+    /// Load a key from its 32 secret bytes.
+    pub fn from_secret_bytes(secret: &[u8; 32]) -> Result<Self, SignatureError> {
+        let keypair = Keypair::from_seckey_slice(SECP256K1, secret)
+            .map_err(|_| SignatureError::InvalidSecretKey)?;
+        Ok(Self { keypair })
+    }
+
+    /// The x-only public key, as used in Nostr events.
+    pub fn public_key(&self) -> [u8; 32] {
+        self.keypair.x_only_public_key().0.serialize()
+    }
+
+    /// The public key as a bech32 `npub1...` string (NIP-19).
+    pub fn npub(&self) -> Npub {
+        encode_npub(&self.public_key())
+    }
+
+    /// Sign a digest.
+    pub fn sign(&self, digest: &[u8; 32]) -> Vec<u8> {
+        let message = Message::from_digest(*digest);
+        SECP256K1
+            .sign_schnorr_no_aux_rand(&message, &self.keypair)
+            .serialize()
+            .to_vec()
+    }
+
+    /// The x coordinate of the ECDH point shared with `pubkey`, taking the
+    /// x-only key to have an even y coordinate as in BIP-340.
+    pub fn shared_secret(&self, pubkey: &[u8; 32]) -> Result<[u8; 32], SignatureError> {
+        let key = XOnlyPublicKey::from_slice(pubkey).map_err(|_| {
+            SignatureError::InvalidPubkey(pubkey.iter().map(|b| format!("{:02x}", b)).collect())
+        })?;
+        let point =
+            ecdh::shared_secret_point(&key.public_key(Parity::Even), &self.keypair.secret_key());
+        let mut x = [0u8; 32];
+        x.copy_from_slice(&point[..32]);
+        Ok(x)
+    }
+}
+
+impl fmt::Debug for SigningKey {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        // Never print the secret
+        f.debug_struct("SigningKey")
+            .field("npub", &self.npub())
+            .finish()
+    }
+}
+
+/// Check that `signature` is a signature over `digest` by `pubkey`.
+///
+/// `pubkey` may be an npub or a 64-character hex key. Malformed keys and
+/// signatures fail verification.
+pub fn verify_signature(pubkey: &str, digest: &[u8; 32], signature: &[u8]) -> bool {
+    let Ok(key) = decode_pubkey(pubkey) else {
+        return false;
+    };
+    let Ok(key) = XOnlyPublicKey::from_slice(&key) else {
+        return false;
+    };
+    let Ok(signature) = schnorr::Signature::from_slice(signature) else {
+        return false;
+    };
+    SECP256K1
+        .verify_schnorr(&signature, &Message::from_digest(*digest), &key)
+        .is_ok()
+}
+
+/// Decode an npub or 64-character hex public key.
+pub fn decode_pubkey(key: &str) -> Result<[u8; 32], SignatureError> {
+    let invalid = || SignatureError::InvalidPubkey(key.to_string());
+    if key.len() == 64 && key.chars().all(|c| c.is_ascii_hexdigit()) {
+        let mut out = [0u8; 32];
+        for (i, byte) in out.iter_mut().enumerate() {
+            *byte = u8::from_str_radix(&key[i * 2..i * 2 + 2], 16).map_err(|_| invalid())?;
+        }
+        return Ok(out);
+    }
+    decode_npub(key)
+}
+
+/// Encode a 32-byte public key as a bech32 `npub1...` string (NIP-19).
+pub fn encode_npub(pubkey: &[u8; 32]) -> Npub {
+    let npub = bech32::encode::<Bech32>(Hrp::parse_unchecked("npub"), pubkey)
+        .expect("32 bytes always fit in a bech32 string");
+    Npub::from(npub.as_str())
+}
+
+/// Decode a bech32 `npub1...` string (NIP-19) into a 32-byte public key.
+pub fn decode_npub(npub: &str) -> Result<[u8; 32], SignatureError> {
+    let invalid = || SignatureError::InvalidPubkey(npub.to_string());
+    let (hrp, bytes) = bech32::decode(npub).map_err(|_| invalid())?;
+    if hrp.as_str() != "npub" {
+        return Err(invalid());
+    }
+    bytes.try_into().map_err(|_| invalid())
+}
+
+/// Errors that can occur when loading keys.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum SignatureError {
+    /// The secret key is zero or not below the curve order.
+    InvalidSecretKey,
+    /// The public key is not a valid npub or hex key.
+    InvalidPubkey(String),
+}
+
+impl fmt::Display for SignatureError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            SignatureError::InvalidSecretKey => write!(f, "Invalid secret key"),
+            SignatureError::InvalidPubkey(key) => write!(f, "Invalid public key: {}", key),
+        }
+    }
+}
+
+impl std::error::Error for SignatureError {}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // NIP-19 encoding of the all-0x11 test key
+    const TEST_NPUB: &str = "npub1zyg3zyg3zyg3zyg3zyg3zyg3zyg3zyg3zyg3zyg3zyg3zyg3zygse4sl3h";
+
+    fn key(seed: u8) -> SigningKey {
+        SigningKey::from_secret_bytes(&[seed; 32]).unwrap()
+    }
+
+    #[test]
+    fn test_sign_and_verify() {
+        let alice = key(1);
+        let digest = [7u8; 32];
+        let signature = alice.sign(&digest);
+
+        assert_eq!(signature.len(), 64);
+        assert!(verify_signature(alice.npub().as_str(), &digest, &signature));
+
+        // Hex keys verify the same signature
+        let hex: String = alice
+            .public_key()
+            .iter()
+            .map(|b| format!("{:02x}", b))
+            .collect();
+        assert!(verify_signature(&hex, &digest, &signature));
+    }
+
+    #[test]
+    fn test_verify_rejects_wrong_key_digest_or_signature() {
+        let alice = key(1);
+        let bob = key(2);
+        let digest = [7u8; 32];
+        let signature = alice.sign(&digest);
+
+        assert!(!verify_signature(bob.npub().as_str(), &digest, &signature));
+        assert!(!verify_signature(
+            alice.npub().as_str(),
+            &[8u8; 32],
+            &signature
+        ));
+        assert!(!verify_signature(
+            alice.npub().as_str(),
+            &digest,
+            &signature[..63]
+        ));
+        assert!(!verify_signature("npub_alice", &digest, &signature));
+    }
+
+    #[test]
+    fn test_invalid_secret_key() {
+        assert_eq!(
+            SigningKey::from_secret_bytes(&[0u8; 32]).unwrap_err(),
+            SignatureError::InvalidSecretKey
+        );
+    }
+
+    #[test]
+    fn test_shared_secret_agrees() {
+        let alice = key(1);
+        let bob = key(2);
+        assert_eq!(
+            alice.shared_secret(&bob.public_key()).unwrap(),
+            bob.shared_secret(&alice.public_key()).unwrap()
+        );
+        assert_ne!(
+            alice.shared_secret(&bob.public_key()).unwrap(),
+            alice.shared_secret(&key(3).public_key()).unwrap()
+        );
+        assert!(alice.shared_secret(&[0xff; 32]).is_err());
+    }
+
+    #[test]
+    fn test_npub_round_trip() {
+        let alice = key(1);
+        assert!(alice.npub().as_str().starts_with("npub1"));
+        assert_eq!(
+            decode_npub(alice.npub().as_str()).unwrap(),
+            alice.public_key()
+        );
+    }
+
+    #[test]
+    fn test_decode_npub() {
+        assert_eq!(decode_npub(TEST_NPUB).unwrap(), [0x11u8; 32]);
+        assert_eq!(encode_npub(&[0x11u8; 32]).as_str(), TEST_NPUB);
+    }
+
+    #[test]
+    fn test_decode_npub_bad_checksum() {
+        let mut bad = TEST_NPUB.to_string();
+        bad.pop();
+        bad.push('q');
+        assert_eq!(
+            decode_npub(&bad),
+            Err(SignatureError::InvalidPubkey(bad.clone()))
+        );
+    }
+
+    #[test]
+    fn test_decode_pubkey_hex() {
+        assert_eq!(decode_pubkey(&"11".repeat(32)).unwrap(), [0x11u8; 32]);
+        assert!(decode_pubkey("nsec1abc").is_err());
+    }
+}
@@ -11,10 +11,49 @@
 //! 2. Host sends all events after that point
 //! 3. Client applies events and confirms sync
 //! 4. During gameplay, events are broadcast to all peers
-
+//!
+//! A sync can be abandoned at any point through the manager's
+//! [`CancellationToken`], e.g. when the player leaves the game.
+//!
+//! # State Queries
+//!
+//! Light clients that don't replay the event chain ask the full client for
+//! a snapshot instead, with a signed [`StateQuery`]. The responder works out
+//! the player from the signing pubkey, never from anything else in the
+//! query, and answers with that player's [`FilteredGameState`]. A light
+//! client can't fetch another player's view without their key.
+//!
+//! # Inclusion Proofs
+//!
+//! A light client that keeps only state snapshots can still check that a
+//! particular event is part of the game: the full client answers with an
+//! [`InclusionProof`] against the events root of the snapshot that closes
+//! the event's batch.
+
+use crate::cancel::CancellationToken;
+use crate::signing::{decode_pubkey, verify_signature, SigningKey};
+use chacha20poly1305::aead::rand_core::RngCore;
+use chacha20poly1305::aead::OsRng;
 use nostr_nations_core::events::{EventChain, GameEvent};
+use nostr_nations_core::game_state::GameState;
+use nostr_nations_core::merkle::{self, MerkleProof};
+use nostr_nations_core::progress::{Progress, ProgressTracker};
+use nostr_nations_core::replay::GameEngine;
+use nostr_nations_core::snapshot::StateSnapshot;
+use nostr_nations_core::types::{GameId, Npub, PlayerSlot};
+use nostr_nations_core::visibility::{FilteredGameState, VisibilityFilter};
 use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
 use std::collections::VecDeque;
+use std::fmt;
+
+/// How far a state query's timestamp may be from the responder's clock, in
+/// seconds, before it is refused as stale.
+pub const STATE_QUERY_MAX_AGE_SECS: u64 = 300;
+
+/// How many unanswered state query nonces a responder remembers. Older
+/// nonces are forgotten and queries signed over them are refused.
+pub const MAX_PENDING_STATE_NONCES: usize = 64;
 
 /// State of synchronization.
 #[derive(Clone, Debug, PartialEq, Eq)]
@@ -31,13 +70,15 @@ pub enum SyncState {
     Synced,
     /// Sync failed.
     Failed(String),
+    /// Sync was cancelled.
+    Cancelled,
 }
 
 /// Request for game state sync.
 #[derive(Clone, Debug, Serialize, Deserialize)]
 pub struct SyncRequest {
     /// Game ID.
-    pub game_id: String,
+    pub game_id: GameId,
     /// Player making the request.
     pub player_id: u32,
     /// Last turn we have events for.
@@ -52,7 +93,7 @@ pub struct SyncRequest {
 #[derive(Clone, Debug, Serialize, Deserialize)]
 pub struct SyncResponse {
     /// Game ID.
-    pub game_id: String,
+    pub game_id: GameId,
     /// Whether more events are available.
     pub has_more: bool,
     /// Events in this response.
@@ -81,7 +122,7 @@ pub struct SyncManager {
     /// Current sync state.
     state: SyncState,
     /// Game ID.
-    game_id: String,
+    game_id: GameId,
     /// Our player ID.
     player_id: u32,
     /// Pending events to apply.
@@ -92,11 +133,13 @@ pub struct SyncManager {
     confirmed_sequence: u32,
     /// Last confirmed event ID.
     confirmed_event_id: Option<String>,
+    /// Cancels the sync in progress.
+    cancel: CancellationToken,
 }
 
 impl SyncManager {
     /// Create a new sync manager.
-    pub fn new(game_id: String, player_id: u32) -> Self {
+    pub fn new(game_id: GameId, player_id: u32) -> Self {
         Self {
             state: SyncState::Idle,
             game_id,
@@ -105,6 +148,43 @@ impl SyncManager {
             confirmed_turn: 0,
             confirmed_sequence: 0,
             confirmed_event_id: None,
+            cancel: CancellationToken::new(),
+        }
+    }
+
+    /// Create a sync manager whose sync is cancelled along with `parent`.
+    pub fn with_cancellation(game_id: GameId, player_id: u32, parent: &CancellationToken) -> Self {
+        Self {
+            cancel: parent.child_token(),
+            ..Self::new(game_id, player_id)
+        }
+    }
+
+    /// Token that cancels the sync in progress.
+    pub fn cancellation_token(&self) -> &CancellationToken {
+        &self.cancel
+    }
+
+    /// Cancel the sync in progress, dropping any pending events.
+    pub fn cancel(&mut self) {
+        self.cancel.cancel();
+        self.mark_cancelled();
+    }
+
+    /// Record a cancellation seen through the token.
+    fn mark_cancelled(&mut self) {
+        self.state = SyncState::Cancelled;
+        self.pending_events.clear();
+    }
+
+    /// Result of an operation that stopped because the sync was cancelled.
+    fn cancelled_result(&mut self, events_applied: usize) -> SyncResult {
+        self.mark_cancelled();
+        SyncResult {
+            events_received: 0,
+            events_applied,
+            state: self.state.clone(),
+            errors: vec!["Sync cancelled".to_string()],
         }
     }
 
@@ -132,7 +212,11 @@ impl SyncManager {
     }
 
     /// Process a sync response from the host.
+    #[tracing::instrument(name = "sync.handle_response", skip_all, fields(game_id = %self.game_id))]
     pub fn handle_response(&mut self, response: SyncResponse) -> SyncResult {
+        if self.cancel.is_cancelled() {
+            return self.cancelled_result(0);
+        }
         if response.game_id != self.game_id {
             self.state = SyncState::Failed("Wrong game ID".to_string());
             return SyncResult {
@@ -172,6 +256,7 @@ impl SyncManager {
     }
 
     /// Confirm an event was successfully applied.
+    #[tracing::instrument(name = "sync.confirm_event", skip_all, fields(game_id = %self.game_id, event_id = %event.id))]
     pub fn confirm_event(&mut self, event: &GameEvent) {
         self.confirmed_turn = event.turn;
         self.confirmed_sequence = event.sequence;
@@ -182,6 +267,45 @@ impl SyncManager {
         }
     }
 
+    /// Apply every pending event to an engine, reporting progress through
+    /// the events applied.
+    ///
+    /// Stops at the first event the engine rejects and marks the sync as
+    /// failed. If the sync is cancelled, stops before the next event.
+    pub fn apply_pending(
+        &mut self,
+        engine: &mut GameEngine,
+        progress: &mut dyn FnMut(Progress),
+    ) -> SyncResult {
+        let mut tracker = ProgressTracker::new(progress, "sync", self.pending_events.len());
+        let mut errors = Vec::new();
+        let mut events_applied = 0;
+        while let Some(event) = self.next_event() {
+            if self.cancel.is_cancelled() {
+                return self.cancelled_result(events_applied);
+            }
+            tracker.set(events_applied);
+            if let Err(e) = engine.apply_event(&event) {
+                errors.push(e.to_string());
+                self.report_failure(e.to_string());
+                break;
+            }
+            self.confirm_event(&event);
+            events_applied += 1;
+        }
+        if errors.is_empty() {
+            self.state = SyncState::Synced;
+            tracker.finish();
+        }
+
+        SyncResult {
+            events_received: 0,
+            events_applied,
+            state: self.state.clone(),
+            errors,
+        }
+    }
+
     /// Report a failed event application.
     pub fn report_failure(&mut self, error: String) {
         self.state = SyncState::Failed(error);
@@ -197,23 +321,141 @@ impl SyncManager {
     pub fn reset(&mut self) {
         self.state = SyncState::Idle;
         self.pending_events.clear();
+        if self.cancel.is_cancelled() {
+            self.cancel = CancellationToken::new();
+        }
+    }
+}
+
+/// A light client's signed request for its player's view of the game.
+///
+/// The query signs a nonce issued by the responder with
+/// [`SyncResponder::issue_state_nonce`], so each signed query is answered
+/// at most once and can't be replayed by whoever sees it.
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct StateQuery {
+    /// Game ID.
+    pub game_id: GameId,
+    /// Signer's public key. The response is scoped to this player.
+    pub pubkey: Npub,
+    /// Nonce issued by the responder for this query.
+    pub nonce: String,
+    /// When the query was signed, in Unix seconds.
+    pub created_at: u64,
+    /// Schnorr signature over the query digest by `pubkey`.
+    pub signature: Vec<u8>,
+}
+
+impl StateQuery {
+    /// Create a query signed by `key`.
+    pub fn signed(game_id: GameId, key: &SigningKey, nonce: String, created_at: u64) -> Self {
+        let mut query = Self {
+            game_id,
+            pubkey: key.npub(),
+            nonce,
+            created_at,
+            signature: Vec::new(),
+        };
+        query.signature = key.sign(&query.digest());
+        query
+    }
+
+    /// Digest of the query fields covered by the signature.
+    pub fn digest(&self) -> [u8; 32] {
+        let mut hasher = Sha256::new();
+        hasher.update(self.game_id.as_str().as_bytes());
+        hasher.update([0]);
+        hasher.update(self.pubkey.as_str().as_bytes());
+        hasher.update([0]);
+        hasher.update(self.nonce.as_bytes());
+        hasher.update([0]);
+        hasher.update(self.created_at.to_le_bytes());
+        hasher.finalize().into()
+    }
+
+    /// Check whether the signature was made by `pubkey` over these fields.
+    pub fn is_signed(&self) -> bool {
+        verify_signature(self.pubkey.as_str(), &self.digest(), &self.signature)
+    }
+}
+
+/// Reasons a state query is refused.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum StateQueryError {
+    /// The query is for a different game.
+    WrongGame,
+    /// The signature doesn't match the query.
+    BadSignature,
+    /// The query was signed too long ago, or too far in the future.
+    Stale,
+    /// The nonce wasn't issued by this responder, or was already used.
+    UnknownNonce,
+    /// The signer isn't a player in the game.
+    NotAPlayer(Npub),
+}
+
+impl fmt::Display for StateQueryError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            StateQueryError::WrongGame => write!(f, "Query is for a different game"),
+            StateQueryError::BadSignature => write!(f, "Query signature is invalid"),
+            StateQueryError::Stale => write!(f, "Query is too old"),
+            StateQueryError::UnknownNonce => write!(f, "Query nonce is unknown or already used"),
+            StateQueryError::NotAPlayer(npub) => write!(f, "{} is not a player", npub),
+        }
+    }
+}
+
+impl std::error::Error for StateQueryError {}
+
+/// The game as seen by the player who signed a [`StateQuery`].
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct StateQueryResponse {
+    /// Game ID.
+    pub game_id: GameId,
+    /// Public key of the player the state is scoped to.
+    pub pubkey: Npub,
+    /// That player's slot.
+    pub player_id: PlayerSlot,
+    /// The player's view of the game.
+    pub state: FilteredGameState,
+}
+
+/// Proof that an event is part of a game, for light clients.
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct InclusionProof {
+    /// The proven event.
+    pub event: GameEvent,
+    /// ID of the snapshot event whose events root covers it.
+    pub snapshot_id: String,
+    /// Path from the event to that root.
+    pub proof: MerkleProof,
+}
+
+impl InclusionProof {
+    /// Check the proof against the snapshot the light client holds.
+    pub fn verify(&self, snapshot: &StateSnapshot) -> bool {
+        snapshot.contains(&self.event, &self.proof)
     }
 }
 
 /// Creates sync responses for host.
 pub struct SyncResponder {
     /// Game ID.
-    game_id: String,
+    game_id: GameId,
     /// Maximum events per response.
     max_events_per_response: usize,
+    /// Issued state query nonces not yet used, oldest first.
+    state_nonces: VecDeque<String>,
 }
 
 impl SyncResponder {
     /// Create a new sync responder.
-    pub fn new(game_id: String) -> Self {
+    pub fn new(game_id: GameId) -> Self {
         Self {
             game_id,
             max_events_per_response: 100,
+            state_nonces: VecDeque::new(),
         }
     }
 
@@ -224,6 +466,7 @@ impl SyncResponder {
     }
 
     /// Create a sync response for a request.
+    #[tracing::instrument(name = "sync.respond", skip_all, fields(game_id = %self.game_id))]
     pub fn respond(&self, request: &SyncRequest, chain: &EventChain) -> SyncResponse {
         let mut events = Vec::new();
         let mut found_start = request.last_event_id.is_none();
@@ -260,6 +503,82 @@ impl SyncResponder {
             chain_hash: None, // Could compute hash for validation
         }
     }
+
+    /// Prove to a light client that an event is part of the game.
+    ///
+    /// Returns `None` if the event isn't in the chain or no snapshot covers
+    /// it yet.
+    pub fn prove_event(&self, chain: &EventChain, event_id: &str) -> Option<InclusionProof> {
+        let events = chain.events();
+        let (snapshot, proof) = merkle::prove_event(events, event_id)?;
+        let event = chain.get(event_id)?.clone();
+        Some(InclusionProof {
+            event,
+            snapshot_id: events[snapshot].id.clone(),
+            proof,
+        })
+    }
+
+    /// Issue a fresh nonce for a light client to sign into its next
+    /// [`StateQuery`].
+    pub fn issue_state_nonce(&mut self) -> String {
+        let mut bytes = [0u8; 16];
+        OsRng.fill_bytes(&mut bytes);
+        let nonce: String = bytes.iter().map(|b| format!("{:02x}", b)).collect();
+        if self.state_nonces.len() == MAX_PENDING_STATE_NONCES {
+            self.state_nonces.pop_front();
+        }
+        self.state_nonces.push_back(nonce.clone());
+        nonce
+    }
+
+    /// Answer a light client's state query with the signer's view of the
+    /// game.
+    ///
+    /// The query's nonce is used up once its signature checks out, so the
+    /// same query is never answered twice. `now` is the current time in
+    /// Unix seconds.
+    #[tracing::instrument(name = "sync.respond_state", skip_all, fields(game_id = %self.game_id))]
+    pub fn respond_state(
+        &mut self,
+        query: &StateQuery,
+        game: &GameState,
+        now: u64,
+    ) -> Result<StateQueryResponse, StateQueryError> {
+        if query.game_id != self.game_id || game.id != self.game_id {
+            return Err(StateQueryError::WrongGame);
+        }
+        if !query.is_signed() {
+            return Err(StateQueryError::BadSignature);
+        }
+        if now.abs_diff(query.created_at) > STATE_QUERY_MAX_AGE_SECS {
+            return Err(StateQueryError::Stale);
+        }
+        let nonce = self
+            .state_nonces
+            .iter()
+            .position(|n| *n == query.nonce)
+            .ok_or(StateQueryError::UnknownNonce)?;
+        self.state_nonces.remove(nonce);
+
+        // Players may be listed by hex key or npub
+        let signer =
+            decode_pubkey(query.pubkey.as_str()).map_err(|_| StateQueryError::BadSignature)?;
+        let player = game
+            .players
+            .iter()
+            .find(|p| decode_pubkey(p.pubkey.as_str()).is_ok_and(|key| key == signer))
+            .ok_or_else(|| StateQueryError::NotAPlayer(query.pubkey.clone()))?;
+
+        let mut filter = VisibilityFilter::new(player.id);
+        filter.update_from_game_state(game);
+        Ok(StateQueryResponse {
+            game_id: self.game_id.clone(),
+            pubkey: player.pubkey.clone(),
+            player_id: player.id,
+            state: filter.filter_game_state(game),
+        })
+    }
 }
 
 /// Tracks which events peers have confirmed.
@@ -308,11 +627,14 @@ impl PeerSyncTracker {
 mod tests {
     use super::*;
     use nostr_nations_core::events::GameAction;
+    use nostr_nations_core::player::{Civilization, Player};
+    use nostr_nations_core::progress::ignore_progress;
+    use nostr_nations_core::settings::GameSettings;
 
     fn create_test_event(id: &str, turn: u32, seq: u32) -> GameEvent {
         let mut event = GameEvent::new(
-            "test_game".to_string(),
-            0,
+            GameId::new("test_game"),
+            PlayerSlot(0),
             None,
             turn,
             seq,
@@ -324,8 +646,8 @@ mod tests {
 
     fn create_test_event_with_prev(id: &str, prev: Option<&str>, turn: u32, seq: u32) -> GameEvent {
         let mut event = GameEvent::new(
-            "test_game".to_string(),
-            0,
+            GameId::new("test_game"),
+            PlayerSlot(0),
             prev.map(|s| s.to_string()),
             turn,
             seq,
@@ -335,6 +657,104 @@ mod tests {
         event
     }
 
+    #[test]
+    fn test_apply_pending_reports_progress() {
+        let settings = GameSettings::new("Test".to_string());
+        let seed = [3u8; 32];
+        let mut events = vec![GameEvent::new(
+            GameId::new("test_game"),
+            PlayerSlot(0),
+            None,
+            0,
+            0,
+            GameAction::CreateGame {
+                settings_json: serde_json::to_string(&settings).unwrap(),
+                seed,
+            },
+        )];
+        for id in 0..2 {
+            events.push(GameEvent::new(
+                GameId::new("test_game"),
+                PlayerSlot(id),
+                None,
+                0,
+                id as u32 + 1,
+                GameAction::JoinGame {
+                    player_name: format!("P{}", id),
+                    civilization_id: "rome".to_string(),
+                },
+            ));
+        }
+
+        let mut manager = SyncManager::new(GameId::new("test_game"), 1);
+        manager.create_request();
+        manager.handle_response(SyncResponse {
+            game_id: GameId::new("test_game"),
+            has_more: false,
+            events,
+            current_turn: 0,
+            chain_hash: None,
+        });
+
+        let mut engine = GameEngine::new(settings, seed);
+        let mut reports = Vec::new();
+        let result = manager.apply_pending(&mut engine, &mut |p| reports.push(p));
+        assert_eq!(result.events_applied, 3);
+        assert!(result.errors.is_empty());
+        assert!(manager.is_synced());
+        assert_eq!(engine.state.players.len(), 2);
+        assert_eq!(
+            reports.iter().map(|p| p.percent).collect::<Vec<_>>(),
+            [0, 33, 66, 100]
+        );
+        assert!(reports.iter().all(|p| p.phase == "sync"));
+    }
+
+    #[test]
+    fn test_sync_manager_cancel() {
+        let game = CancellationToken::new();
+        let mut manager = SyncManager::with_cancellation(GameId::new("test_game"), 1, &game);
+        manager.create_request();
+        let response = SyncResponse {
+            game_id: GameId::new("test_game"),
+            has_more: true,
+            events: vec![create_test_event("e1", 1, 1), create_test_event("e2", 1, 2)],
+            current_turn: 1,
+            chain_hash: None,
+        };
+        manager.handle_response(response.clone());
+        assert_eq!(manager.pending_count(), 2);
+
+        // Dropping the game cancels the sync and its queued events
+        game.cancel();
+        let mut engine = GameEngine::new(GameSettings::new("Test".to_string()), [0; 32]);
+        let result = manager.apply_pending(&mut engine, &mut ignore_progress);
+        assert_eq!(result.events_applied, 0);
+        assert_eq!(result.state, SyncState::Cancelled);
+        assert_eq!(manager.pending_count(), 0);
+
+        // Later responses are ignored
+        let result = manager.handle_response(response);
+        assert_eq!(result.events_received, 0);
+        assert_eq!(manager.pending_count(), 0);
+
+        // Resetting allows a fresh sync
+        manager.reset();
+        assert!(!manager.cancellation_token().is_cancelled());
+        assert_eq!(
+            manager
+                .handle_response(SyncResponse {
+                    game_id: GameId::new("test_game"),
+                    has_more: false,
+                    events: vec![create_test_event("e1", 1, 1)],
+                    current_turn: 1,
+                    chain_hash: None,
+                })
+                .events_received,
+            1
+        );
+    }
+
     // ==================== SyncState Tests ====================
 
     #[test]
@@ -370,7 +790,7 @@ mod tests {
     #[test]
     fn test_sync_request_serialization() {
         let request = SyncRequest {
-            game_id: "game123".to_string(),
+            game_id: GameId::new("game123"),
             player_id: 42,
             last_turn: 5,
             last_sequence: 10,
@@ -390,7 +810,7 @@ mod tests {
     #[test]
     fn test_sync_request_serialization_no_last_event() {
         let request = SyncRequest {
-            game_id: "game123".to_string(),
+            game_id: GameId::new("game123"),
             player_id: 0,
             last_turn: 0,
             last_sequence: 0,
@@ -408,7 +828,7 @@ mod tests {
     #[test]
     fn test_sync_response_serialization() {
         let response = SyncResponse {
-            game_id: "game123".to_string(),
+            game_id: GameId::new("game123"),
             has_more: true,
             events: vec![create_test_event("evt1", 1, 1)],
             current_turn: 5,
@@ -428,7 +848,7 @@ mod tests {
     #[test]
     fn test_sync_response_serialization_empty_events() {
         let response = SyncResponse {
-            game_id: "game123".to_string(),
+            game_id: GameId::new("game123"),
             has_more: false,
             events: vec![],
             current_turn: 0,
@@ -445,7 +865,7 @@ mod tests {
     #[test]
     fn test_sync_response_serialization_multiple_events() {
         let response = SyncResponse {
-            game_id: "game123".to_string(),
+            game_id: GameId::new("game123"),
             has_more: false,
             events: vec![
                 create_test_event("evt1", 1, 1),
@@ -469,7 +889,7 @@ mod tests {
 
     #[test]
     fn test_sync_manager_new() {
-        let manager = SyncManager::new("game1".to_string(), 42);
+        let manager = SyncManager::new(GameId::new("game1"), 42);
 
         assert_eq!(*manager.state(), SyncState::Idle);
         assert!(!manager.is_synced());
@@ -478,7 +898,7 @@ mod tests {
 
     #[test]
     fn test_sync_manager_request() {
-        let mut manager = SyncManager::new("game1".to_string(), 0);
+        let mut manager = SyncManager::new(GameId::new("game1"), 0);
         let request = manager.create_request();
 
         assert_eq!(request.game_id, "game1");
@@ -489,11 +909,11 @@ mod tests {
 
     #[test]
     fn test_sync_manager_request_with_progress() {
-        let mut manager = SyncManager::new("game1".to_string(), 1);
+        let mut manager = SyncManager::new(GameId::new("game1"), 1);
 
         // Simulate having confirmed some events
         let response = SyncResponse {
-            game_id: "game1".to_string(),
+            game_id: GameId::new("game1"),
             has_more: false,
             events: vec![create_test_event("evt1", 3, 5)],
             current_turn: 3,
@@ -515,11 +935,11 @@ mod tests {
 
     #[test]
     fn test_sync_manager_response() {
-        let mut manager = SyncManager::new("game1".to_string(), 0);
+        let mut manager = SyncManager::new(GameId::new("game1"), 0);
         manager.create_request();
 
         let response = SyncResponse {
-            game_id: "game1".to_string(),
+            game_id: GameId::new("game1"),
             has_more: false,
             events: vec![create_test_event("evt1", 1, 1)],
             current_turn: 1,
@@ -533,11 +953,11 @@ mod tests {
 
     #[test]
     fn test_sync_manager_response_wrong_game() {
-        let mut manager = SyncManager::new("game1".to_string(), 0);
+        let mut manager = SyncManager::new(GameId::new("game1"), 0);
         manager.create_request();
 
         let response = SyncResponse {
-            game_id: "wrong_game".to_string(),
+            game_id: GameId::new("wrong_game"),
             has_more: false,
             events: vec![],
             current_turn: 0,
@@ -552,11 +972,11 @@ mod tests {
 
     #[test]
     fn test_sync_manager_response_has_more() {
-        let mut manager = SyncManager::new("game1".to_string(), 0);
+        let mut manager = SyncManager::new(GameId::new("game1"), 0);
         manager.create_request();
 
         let response = SyncResponse {
-            game_id: "game1".to_string(),
+            game_id: GameId::new("game1"),
             has_more: true,
             events: vec![create_test_event("evt1", 1, 1)],
             current_turn: 1,
@@ -570,7 +990,7 @@ mod tests {
 
     #[test]
     fn test_sync_manager_apply_events() {
-        let mut manager = SyncManager::new("game1".to_string(), 0);
+        let mut manager = SyncManager::new(GameId::new("game1"), 0);
         manager.create_request();
 
         let events = vec![
@@ -579,7 +999,7 @@ mod tests {
         ];
 
         let response = SyncResponse {
-            game_id: "game1".to_string(),
+            game_id: GameId::new("game1"),
             has_more: false,
             events,
             current_turn: 1,
@@ -605,18 +1025,18 @@ mod tests {
 
     #[test]
     fn test_sync_manager_next_event_empty() {
-        let mut manager = SyncManager::new("game1".to_string(), 0);
+        let mut manager = SyncManager::new(GameId::new("game1"), 0);
 
         assert!(manager.next_event().is_none());
     }
 
     #[test]
     fn test_sync_manager_report_failure() {
-        let mut manager = SyncManager::new("game1".to_string(), 0);
+        let mut manager = SyncManager::new(GameId::new("game1"), 0);
         manager.create_request();
 
         let response = SyncResponse {
-            game_id: "game1".to_string(),
+            game_id: GameId::new("game1"),
             has_more: false,
             events: vec![create_test_event("evt1", 1, 1)],
             current_turn: 1,
@@ -637,11 +1057,11 @@ mod tests {
 
     #[test]
     fn test_sync_manager_reset() {
-        let mut manager = SyncManager::new("game1".to_string(), 0);
+        let mut manager = SyncManager::new(GameId::new("game1"), 0);
         manager.create_request();
 
         let response = SyncResponse {
-            game_id: "game1".to_string(),
+            game_id: GameId::new("game1"),
             has_more: false,
             events: vec![create_test_event("evt1", 1, 1)],
             current_turn: 1,
@@ -659,11 +1079,11 @@ mod tests {
 
     #[test]
     fn test_sync_manager_is_synced_with_pending() {
-        let mut manager = SyncManager::new("game1".to_string(), 0);
+        let mut manager = SyncManager::new(GameId::new("game1"), 0);
         manager.create_request();
 
         let response = SyncResponse {
-            game_id: "game1".to_string(),
+            game_id: GameId::new("game1"),
             has_more: false,
             events: vec![create_test_event("evt1", 1, 1)],
             current_turn: 1,
@@ -680,12 +1100,12 @@ mod tests {
 
     #[test]
     fn test_sync_responder_new() {
-        let responder = SyncResponder::new("game1".to_string());
+        let responder = SyncResponder::new(GameId::new("game1"));
 
         // Verify it's created (we can't easily test internals)
         let chain = EventChain::new();
         let request = SyncRequest {
-            game_id: "game1".to_string(),
+            game_id: GameId::new("game1"),
             player_id: 0,
             last_turn: 0,
             last_sequence: 0,
@@ -698,7 +1118,7 @@ mod tests {
 
     #[test]
     fn test_sync_responder_with_max_events() {
-        let responder = SyncResponder::new("game1".to_string()).with_max_events(2);
+        let responder = SyncResponder::new(GameId::new("game1")).with_max_events(2);
 
         let mut chain = EventChain::new();
         chain
@@ -715,7 +1135,7 @@ mod tests {
             .unwrap();
 
         let request = SyncRequest {
-            game_id: "game1".to_string(),
+            game_id: GameId::new("game1"),
             player_id: 0,
             last_turn: 0,
             last_sequence: 0,
@@ -729,11 +1149,11 @@ mod tests {
 
     #[test]
     fn test_sync_responder_respond_empty_chain() {
-        let responder = SyncResponder::new("game1".to_string());
+        let responder = SyncResponder::new(GameId::new("game1"));
 
         let chain = EventChain::new();
         let request = SyncRequest {
-            game_id: "game1".to_string(),
+            game_id: GameId::new("game1"),
             player_id: 0,
             last_turn: 0,
             last_sequence: 0,
@@ -748,7 +1168,7 @@ mod tests {
 
     #[test]
     fn test_sync_responder_respond_from_beginning() {
-        let responder = SyncResponder::new("game1".to_string());
+        let responder = SyncResponder::new(GameId::new("game1"));
 
         let mut chain = EventChain::new();
         chain
@@ -759,7 +1179,7 @@ mod tests {
             .unwrap();
 
         let request = SyncRequest {
-            game_id: "game1".to_string(),
+            game_id: GameId::new("game1"),
             player_id: 0,
             last_turn: 0,
             last_sequence: 0,
@@ -775,7 +1195,7 @@ mod tests {
 
     #[test]
     fn test_sync_responder_respond_from_midpoint() {
-        let responder = SyncResponder::new("game1".to_string());
+        let responder = SyncResponder::new(GameId::new("game1"));
 
         let mut chain = EventChain::new();
         chain
@@ -789,7 +1209,7 @@ mod tests {
             .unwrap();
 
         let request = SyncRequest {
-            game_id: "game1".to_string(),
+            game_id: GameId::new("game1"),
             player_id: 0,
             last_turn: 1,
             last_sequence: 1,
@@ -804,7 +1224,7 @@ mod tests {
 
     #[test]
     fn test_sync_responder_respond_fully_synced() {
-        let responder = SyncResponder::new("game1".to_string());
+        let responder = SyncResponder::new(GameId::new("game1"));
 
         let mut chain = EventChain::new();
         chain
@@ -815,7 +1235,7 @@ mod tests {
             .unwrap();
 
         let request = SyncRequest {
-            game_id: "game1".to_string(),
+            game_id: GameId::new("game1"),
             player_id: 0,
             last_turn: 1,
             last_sequence: 2,
@@ -827,6 +1247,176 @@ mod tests {
         assert!(!response.has_more);
     }
 
+    // ==================== State Query Tests ====================
+
+    fn alice() -> SigningKey {
+        SigningKey::from_secret_bytes(&[1u8; 32]).unwrap()
+    }
+
+    fn bob() -> SigningKey {
+        SigningKey::from_secret_bytes(&[2u8; 32]).unwrap()
+    }
+
+    fn create_game() -> GameState {
+        let mut game = GameState::new(
+            GameId::new("game1"),
+            GameSettings::new("Test".to_string()),
+            [0u8; 32],
+        );
+        for (id, key) in [(0, alice()), (1, bob())] {
+            game.add_player(Player::new(
+                PlayerSlot(id),
+                key.npub(),
+                format!("P{}", id),
+                Civilization::generic(),
+            ))
+            .unwrap();
+        }
+        game.start().unwrap();
+        game
+    }
+
+    #[test]
+    fn test_state_query_scoped_to_signer() {
+        let game = create_game();
+        let mut responder = SyncResponder::new(GameId::new("game1"));
+
+        let nonce = responder.issue_state_nonce();
+        let query = StateQuery::signed(GameId::new("game1"), &bob(), nonce, 1_000);
+        assert!(query.is_signed());
+        let response = responder.respond_state(&query, &game, 1_000).unwrap();
+        assert_eq!(response.player_id, PlayerSlot(1));
+        assert_eq!(response.state.own_player.id, PlayerSlot(1));
+        assert!(response
+            .state
+            .other_players
+            .iter()
+            .all(|p| p.id != PlayerSlot(1)));
+    }
+
+    #[test]
+    fn test_state_query_rejects_forged_and_stale_queries() {
+        let game = create_game();
+        let mut responder = SyncResponder::new(GameId::new("game1"));
+        let nonce = responder.issue_state_nonce();
+        let query = StateQuery::signed(GameId::new("game1"), &bob(), nonce.clone(), 1_000);
+
+        // Swapping in another player's key invalidates the signature
+        let forged = StateQuery {
+            pubkey: alice().npub(),
+            ..query.clone()
+        };
+        assert_eq!(
+            responder.respond_state(&forged, &game, 1_000).unwrap_err(),
+            StateQueryError::BadSignature
+        );
+
+        let late = 1_000 + STATE_QUERY_MAX_AGE_SECS + 1;
+        assert_eq!(
+            responder.respond_state(&query, &game, late).unwrap_err(),
+            StateQueryError::Stale
+        );
+
+        let other_game = StateQuery::signed(GameId::new("game2"), &bob(), nonce.clone(), 1_000);
+        assert_eq!(
+            responder
+                .respond_state(&other_game, &game, 1_000)
+                .unwrap_err(),
+            StateQueryError::WrongGame
+        );
+
+        let eve = SigningKey::from_secret_bytes(&[3u8; 32]).unwrap();
+        let stranger = StateQuery::signed(GameId::new("game1"), &eve, nonce, 1_000);
+        assert_eq!(
+            responder
+                .respond_state(&stranger, &game, 1_000)
+                .unwrap_err(),
+            StateQueryError::NotAPlayer(eve.npub())
+        );
+    }
+
+    #[test]
+    fn test_state_query_forged_for_another_player_rejected() {
+        let game = create_game();
+        let mut responder = SyncResponder::new(GameId::new("game1"));
+
+        // Eve knows Bob's npub and signs a query for it with her own key,
+        // the way the old hash-of-pubkey "signature" could be recomputed
+        let eve = SigningKey::from_secret_bytes(&[3u8; 32]).unwrap();
+        let mut forged = StateQuery {
+            game_id: GameId::new("game1"),
+            pubkey: bob().npub(),
+            nonce: responder.issue_state_nonce(),
+            created_at: 1_000,
+            signature: Vec::new(),
+        };
+        forged.signature = eve.sign(&forged.digest());
+        assert!(!forged.is_signed());
+        assert_eq!(
+            responder.respond_state(&forged, &game, 1_000).unwrap_err(),
+            StateQueryError::BadSignature
+        );
+
+        // Anything short of a real signature is refused too
+        let mut hashed = forged.clone();
+        hashed.signature = Sha256::digest(forged.digest()).to_vec();
+        assert_eq!(
+            responder.respond_state(&hashed, &game, 1_000).unwrap_err(),
+            StateQueryError::BadSignature
+        );
+    }
+
+    #[test]
+    fn test_state_query_cannot_be_replayed() {
+        let game = create_game();
+        let mut responder = SyncResponder::new(GameId::new("game1"));
+        let nonce = responder.issue_state_nonce();
+        let query = StateQuery::signed(GameId::new("game1"), &bob(), nonce, 1_000);
+
+        assert!(responder.respond_state(&query, &game, 1_000).is_ok());
+        // A captured query is refused within the freshness window
+        assert_eq!(
+            responder.respond_state(&query, &game, 1_001).unwrap_err(),
+            StateQueryError::UnknownNonce
+        );
+
+        // Nonces the responder never issued are refused too
+        let made_up = StateQuery::signed(GameId::new("game1"), &bob(), "00".repeat(16), 1_000);
+        assert_eq!(
+            responder.respond_state(&made_up, &game, 1_000).unwrap_err(),
+            StateQueryError::UnknownNonce
+        );
+
+        // Only the most recent nonces are kept
+        let oldest = responder.issue_state_nonce();
+        for _ in 0..MAX_PENDING_STATE_NONCES {
+            responder.issue_state_nonce();
+        }
+        let expired = StateQuery::signed(GameId::new("game1"), &bob(), oldest, 1_000);
+        assert_eq!(
+            responder.respond_state(&expired, &game, 1_000).unwrap_err(),
+            StateQueryError::UnknownNonce
+        );
+    }
+
+    #[test]
+    fn test_state_query_matches_hex_and_npub_keys() {
+        let mut game = create_game();
+        // Bob is listed by hex key but signs with his npub
+        let hex: String = bob()
+            .public_key()
+            .iter()
+            .map(|b| format!("{:02x}", b))
+            .collect();
+        game.players[1].pubkey = Npub::new(hex);
+        let mut responder = SyncResponder::new(GameId::new("game1"));
+        let nonce = responder.issue_state_nonce();
+        let query = StateQuery::signed(GameId::new("game1"), &bob(), nonce, 1_000);
+
+        let response = responder.respond_state(&query, &game, 1_000).unwrap();
+        assert_eq!(response.player_id, PlayerSlot(1));
+    }
+
     // ==================== PeerSyncTracker Tests ====================
 
     #[test]
@@ -972,8 +1562,8 @@ mod tests {
     #[test]
     fn test_full_sync_flow() {
         // Simulate a complete sync flow between manager and responder
-        let mut manager = SyncManager::new("game1".to_string(), 0);
-        let responder = SyncResponder::new("game1".to_string());
+        let mut manager = SyncManager::new(GameId::new("game1"), 0);
+        let responder = SyncResponder::new(GameId::new("game1"));
 
         // Build an event chain on the host
         let mut chain = EventChain::new();
@@ -1011,8 +1601,8 @@ mod tests {
     #[test]
     fn test_incremental_sync_flow() {
         // Simulate incremental sync (client already has some events)
-        let mut manager = SyncManager::new("game1".to_string(), 0);
-        let responder = SyncResponder::new("game1".to_string());
+        let mut manager = SyncManager::new(GameId::new("game1"), 0);
+        let responder = SyncResponder::new(GameId::new("game1"));
 
         // Initial sync
         let mut chain = EventChain::new();
@@ -1059,11 +1649,11 @@ mod tests {
     #[test]
     fn test_conflict_detection_via_wrong_game() {
         // A simple conflict scenario: response for wrong game
-        let mut manager = SyncManager::new("game1".to_string(), 0);
+        let mut manager = SyncManager::new(GameId::new("game1"), 0);
         manager.create_request();
 
         let response = SyncResponse {
-            game_id: "game2".to_string(), // Wrong game!
+            game_id: GameId::new("game2"), // Wrong game!
             has_more: false,
             events: vec![create_test_event("evt1", 1, 1)],
             current_turn: 1,
@@ -1075,4 +1665,46 @@ mod tests {
         assert!(matches!(manager.state(), SyncState::Failed(_)));
         assert!(!result.errors.is_empty());
     }
+
+    #[test]
+    fn test_prove_event_against_snapshot() {
+        let mut events: Vec<GameEvent> = (0..3)
+            .map(|i| {
+                let mut event = create_test_event(&format!("evt{}", i), 1, i + 1);
+                event.player_id = PlayerSlot(i as u8 % 2);
+                event
+            })
+            .collect();
+        let leaves: Vec<_> = events.iter().map(merkle::event_leaf).collect();
+        let game = GameState::new(
+            GameId::new("test_game"),
+            GameSettings::new("Test".to_string()),
+            [0u8; 32],
+        );
+        let snapshot = StateSnapshot::capture(&game)
+            .with_events_root(Some(merkle::EventsRoot::from_leaves(&leaves)));
+        events.push(create_test_event("snap", 1, 4));
+        events[3].action = GameAction::Snapshot {
+            snapshot: snapshot.clone(),
+        };
+
+        let mut chain = EventChain::new();
+        for (i, mut event) in events.into_iter().enumerate() {
+            event.prev_event_id = i.checked_sub(1).map(|p| format!("evt{}", p));
+            chain.add(event).unwrap();
+        }
+
+        let responder = SyncResponder::new(GameId::new("test_game"));
+        let proof = responder.prove_event(&chain, "evt1").unwrap();
+        assert_eq!(proof.snapshot_id, "snap");
+        assert!(proof.verify(&snapshot));
+
+        // A light client spots an event that was altered in transit
+        let mut forged = proof.clone();
+        forged.event.player_id = PlayerSlot(0);
+        assert!(!forged.verify(&snapshot));
+
+        assert!(responder.prove_event(&chain, "snap").is_none());
+        assert!(responder.prove_event(&chain, "missing").is_none());
+    }
 }
@@ -0,0 +1,1038 @@
+//! Tournament bracket orchestration.
+//!
+//! This module runs single-elimination tournaments on top of the normal
+//! game lobby flow:
+//! - A bracket event is published to relays when the tournament starts
+//! - Each ready pairing gets an auto-generated lobby (a `CreateGame` event)
+//! - Both players sign the match result, which is published to relays
+//! - Verified results advance the winner into the next round
+//!
+//! # Bracket Layout
+//!
+//! The bracket is padded to the next power of two. Participants are placed
+//! by seed so the top seeds meet as late as possible, and empty slots are
+//! treated as byes that advance the opponent automatically.
+
+use crate::signing::{verify_signature, SigningKey};
+use nostr_nations_core::events::{GameAction, GameEvent};
+use nostr_nations_core::settings::GameSettings;
+use nostr_nations_core::types::{GameId, PlayerSlot};
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+use ts_rs::TS;
+
+/// Nostr event kinds used for tournament events.
+pub mod kinds {
+    pub use nostr_nations_core::event_kinds::{MATCH_RESULT, TOURNAMENT_BRACKET};
+}
+
+/// Minimum number of participants needed to start a tournament.
+pub const MIN_PARTICIPANTS: usize = 2;
+
+/// A registered tournament participant.
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Participant {
+    /// Participant's Nostr public key.
+    pub pubkey: String,
+    /// Display name.
+    pub name: String,
+    /// Seed (1 = top seed). Assigned in join order.
+    pub seed: u32,
+}
+
+/// Lifecycle of a tournament.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub enum TournamentStatus {
+    /// Accepting participants.
+    #[default]
+    Registration,
+    /// Bracket generated, matches being played.
+    InProgress,
+    /// Champion decided.
+    Completed,
+}
+
+/// State of a single bracket match.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub enum MatchStatus {
+    /// Waiting for one or both players from the previous round.
+    #[default]
+    Pending,
+    /// Both players known, lobby generated.
+    Ready,
+    /// Winner decided (by result or bye).
+    Completed,
+}
+
+/// Auto-generated lobby for a bracket pairing.
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct MatchLobby {
+    /// Game ID of the lobby.
+    pub game_id: GameId,
+    /// Settings for the match game.
+    pub settings: GameSettings,
+    /// Seed for the match game (derived from the tournament seed).
+    pub seed: [u8; 32],
+    /// Pubkeys of the two players, in slot order.
+    pub players: [String; 2],
+}
+
+impl MatchLobby {
+    /// Build the `CreateGame` event that opens this lobby on relays.
+    pub fn create_game_event(&self) -> GameEvent {
+        let settings_json = serde_json::to_string(&self.settings).unwrap_or_default();
+        let mut event = GameEvent::new(
+            self.game_id.clone(),
+            PlayerSlot(0),
+            None,
+            0,
+            0,
+            GameAction::CreateGame {
+                settings_json,
+                seed: self.seed,
+            },
+        );
+        event.id = format!("{}_create", self.game_id);
+        event
+    }
+}
+
+/// A single match in the bracket.
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct BracketMatch {
+    /// Match ID (`<tournament>_r<round>_m<index>`).
+    pub id: String,
+    /// Round number (0 = first round).
+    pub round: u32,
+    /// Index of the match within its round.
+    pub index: u32,
+    /// Pubkeys of the players in each slot.
+    pub players: [Option<String>; 2],
+    /// Winner's pubkey once decided.
+    pub winner: Option<String>,
+    /// Current status.
+    pub status: MatchStatus,
+    /// Generated lobby once both players are known.
+    pub lobby: Option<MatchLobby>,
+    /// Accepted result, if the match was played.
+    pub result: Option<MatchResult>,
+}
+
+impl BracketMatch {
+    fn new(tournament_id: &str, round: u32, index: u32) -> Self {
+        Self {
+            id: format!("{}_r{}_m{}", tournament_id, round, index),
+            round,
+            index,
+            players: [None, None],
+            winner: None,
+            status: MatchStatus::Pending,
+            lobby: None,
+            result: None,
+        }
+    }
+
+    /// Check if a pubkey is playing in this match.
+    pub fn has_player(&self, pubkey: &str) -> bool {
+        self.players.iter().flatten().any(|p| p == pubkey)
+    }
+
+    /// Get the opponent of the given player.
+    pub fn opponent_of(&self, pubkey: &str) -> Option<&str> {
+        match &self.players {
+            [Some(a), Some(b)] if a == pubkey => Some(b),
+            [Some(a), Some(b)] if b == pubkey => Some(a),
+            _ => None,
+        }
+    }
+}
+
+/// A player's signature over a match result.
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize, JsonSchema, TS)]
+pub struct ResultSignature {
+    /// Signer's public key.
+    pub pubkey: String,
+    /// Schnorr signature over the result digest by `pubkey`.
+    pub signature: Vec<u8>,
+}
+
+/// A match result, signed by the players of the match.
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize, JsonSchema, TS)]
+pub struct MatchResult {
+    /// Tournament the match belongs to.
+    pub tournament_id: String,
+    /// Match being reported.
+    pub match_id: String,
+    /// Game ID the match was played in.
+    pub game_id: GameId,
+    /// Winner's pubkey.
+    pub winner: String,
+    /// ID of the `EndGame` or `Concede` event that decided the match, if
+    /// available.
+    pub final_event_id: Option<String>,
+    /// Pubkey of the player who conceded, if the match ended by concession.
+    #[serde(default)]
+    pub conceded_by: Option<String>,
+    /// Player signatures.
+    pub signatures: Vec<ResultSignature>,
+}
+
+impl MatchResult {
+    /// Create an unsigned result.
+    pub fn new(
+        tournament_id: String,
+        match_id: String,
+        game_id: GameId,
+        winner: String,
+        final_event_id: Option<String>,
+    ) -> Self {
+        Self {
+            tournament_id,
+            match_id,
+            game_id,
+            winner,
+            final_event_id,
+            conceded_by: None,
+            signatures: Vec::new(),
+        }
+    }
+
+    /// Mark the result as decided by the given player conceding.
+    ///
+    /// A conceded result only needs the conceding player's signature.
+    pub fn with_concession(mut self, pubkey: impl Into<String>) -> Self {
+        self.conceded_by = Some(pubkey.into());
+        self
+    }
+
+    /// Digest of the result fields covered by signatures.
+    pub fn digest(&self) -> [u8; 32] {
+        let mut data = Vec::new();
+        data.extend_from_slice(self.tournament_id.as_bytes());
+        data.push(0);
+        data.extend_from_slice(self.match_id.as_bytes());
+        data.push(0);
+        data.extend_from_slice(self.game_id.as_str().as_bytes());
+        data.push(0);
+        data.extend_from_slice(self.winner.as_bytes());
+        data.push(0);
+        if let Some(ref id) = self.final_event_id {
+            data.extend_from_slice(id.as_bytes());
+        }
+        // Only appended when set so older results keep their digest
+        if let Some(ref pubkey) = self.conceded_by {
+            data.push(0);
+            data.extend_from_slice(pubkey.as_bytes());
+        }
+        simple_hash(&data)
+    }
+
+    /// Add a signature by the given player's key, replacing any previous
+    /// one from them.
+    pub fn sign(&mut self, key: &SigningKey) {
+        let pubkey = key.npub().to_string();
+        let signature = key.sign(&self.digest());
+        self.signatures.retain(|s| s.pubkey != pubkey);
+        self.signatures.push(ResultSignature { pubkey, signature });
+    }
+
+    /// Check whether the given player has a valid signature on this result.
+    pub fn is_signed_by(&self, pubkey: &str) -> bool {
+        let digest = self.digest();
+        self.signatures
+            .iter()
+            .any(|s| s.pubkey == pubkey && verify_signature(pubkey, &digest, &s.signature))
+    }
+}
+
+/// A tournament event ready to be published to relays.
+#[derive(Clone, Debug, Serialize, Deserialize, JsonSchema, TS)]
+pub struct TournamentEvent {
+    /// Nostr event kind.
+    pub kind: u32,
+    /// Tournament the event belongs to.
+    pub tournament_id: String,
+    /// Author pubkey.
+    pub pubkey: String,
+    /// JSON content.
+    pub content: String,
+    /// Nostr tags.
+    pub tags: Vec<Vec<String>>,
+    /// Unix timestamp.
+    pub created_at: u64,
+}
+
+/// A single-elimination tournament.
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct Tournament {
+    /// Unique tournament ID.
+    pub id: String,
+    /// Display name.
+    pub name: String,
+    /// Organizer's pubkey.
+    pub organizer: String,
+    /// Maximum number of participants.
+    pub max_participants: usize,
+    /// Template settings for every match game.
+    pub game_settings: GameSettings,
+    /// Registered participants.
+    pub participants: Vec<Participant>,
+    /// Bracket rounds (round 0 first).
+    pub rounds: Vec<Vec<BracketMatch>>,
+    /// Current status.
+    pub status: TournamentStatus,
+    /// Champion's pubkey once decided.
+    pub champion: Option<String>,
+    /// Seed used to derive match game seeds.
+    pub seed: [u8; 32],
+    /// Creation timestamp.
+    pub created_at: u64,
+}
+
+impl Tournament {
+    /// Create a new tournament open for registration.
+    pub fn new(
+        id: String,
+        name: String,
+        organizer: String,
+        max_participants: usize,
+        game_settings: GameSettings,
+    ) -> Self {
+        Self {
+            id,
+            name,
+            organizer,
+            max_participants,
+            game_settings,
+            participants: Vec::new(),
+            rounds: Vec::new(),
+            status: TournamentStatus::Registration,
+            champion: None,
+            seed: [0u8; 32],
+            created_at: current_timestamp(),
+        }
+    }
+
+    /// Register a participant.
+    pub fn join(&mut self, pubkey: String, name: String) -> Result<&Participant, TournamentError> {
+        if self.status != TournamentStatus::Registration {
+            return Err(TournamentError::RegistrationClosed);
+        }
+        if self.participants.iter().any(|p| p.pubkey == pubkey) {
+            return Err(TournamentError::AlreadyJoined);
+        }
+        if self.participants.len() >= self.max_participants {
+            return Err(TournamentError::TournamentFull);
+        }
+
+        let seed = self.participants.len() as u32 + 1;
+        self.participants.push(Participant { pubkey, name, seed });
+        Ok(self.participants.last().unwrap())
+    }
+
+    /// Remove a participant before the tournament starts.
+    pub fn leave(&mut self, pubkey: &str) -> Result<(), TournamentError> {
+        if self.status != TournamentStatus::Registration {
+            return Err(TournamentError::RegistrationClosed);
+        }
+        let before = self.participants.len();
+        self.participants.retain(|p| p.pubkey != pubkey);
+        if self.participants.len() == before {
+            return Err(TournamentError::NotParticipant);
+        }
+        for (i, p) in self.participants.iter_mut().enumerate() {
+            p.seed = i as u32 + 1;
+        }
+        Ok(())
+    }
+
+    /// Seed for [`start`](Self::start), derived from the tournament ID and
+    /// the set of participants so every client generates the same bracket.
+    pub fn bracket_seed(&self) -> [u8; 32] {
+        let mut pubkeys: Vec<&str> = self
+            .participants
+            .iter()
+            .map(|p| p.pubkey.as_str())
+            .collect();
+        pubkeys.sort_unstable();
+        let mut data = self.id.as_bytes().to_vec();
+        for pubkey in pubkeys {
+            data.push(0);
+            data.extend_from_slice(pubkey.as_bytes());
+        }
+        simple_hash(&data)
+    }
+
+    /// Close registration, generate the bracket, and create lobbies.
+    ///
+    /// Returns the lobbies for all first-round matches that are ready.
+    pub fn start(&mut self, seed: [u8; 32]) -> Result<Vec<MatchLobby>, TournamentError> {
+        if self.status != TournamentStatus::Registration {
+            return Err(TournamentError::AlreadyStarted);
+        }
+        if self.participants.len() < MIN_PARTICIPANTS {
+            return Err(TournamentError::NotEnoughParticipants);
+        }
+
+        self.seed = seed;
+        let size = self.participants.len().next_power_of_two();
+        let round_count = size.trailing_zeros();
+
+        self.rounds = (0..round_count)
+            .map(|round| {
+                let matches = size >> (round + 1);
+                (0..matches as u32)
+                    .map(|i| BracketMatch::new(&self.id, round, i))
+                    .collect()
+            })
+            .collect();
+
+        // Place participants by seed position, leaving byes in empty slots
+        for (slot, seed_number) in seed_order(size).into_iter().enumerate() {
+            let player = self
+                .participants
+                .iter()
+                .find(|p| p.seed == seed_number)
+                .map(|p| p.pubkey.clone());
+            self.rounds[0][slot / 2].players[slot % 2] = player;
+        }
+
+        self.status = TournamentStatus::InProgress;
+
+        // Resolve byes, which may cascade into later rounds
+        let mut lobbies = Vec::new();
+        for index in 0..self.rounds[0].len() {
+            lobbies.extend(self.refresh_match(0, index));
+        }
+        Ok(lobbies)
+    }
+
+    /// Submit a signed match result.
+    ///
+    /// The result must be signed by both players of the match. On success
+    /// the winner advances and any newly ready lobbies are returned.
+    pub fn submit_result(
+        &mut self,
+        result: MatchResult,
+    ) -> Result<Vec<MatchLobby>, TournamentError> {
+        if self.status != TournamentStatus::InProgress {
+            return Err(TournamentError::NotInProgress);
+        }
+        if result.tournament_id != self.id {
+            return Err(TournamentError::WrongTournament);
+        }
+
+        let (round, index) = self
+            .find_match(&result.match_id)
+            .ok_or_else(|| TournamentError::MatchNotFound(result.match_id.clone()))?;
+        let m = &self.rounds[round][index];
+
+        if m.status != MatchStatus::Ready {
+            return Err(TournamentError::MatchNotReady);
+        }
+        if !m.has_player(&result.winner) {
+            return Err(TournamentError::NotParticipant);
+        }
+        if let Some(ref lobby) = m.lobby {
+            if lobby.game_id != result.game_id {
+                return Err(TournamentError::InvalidResult(
+                    "game ID does not match lobby".to_string(),
+                ));
+            }
+        }
+        match result.conceded_by {
+            // The loser's word is enough when they concede
+            Some(ref conceder) => {
+                if !m.has_player(conceder) || *conceder == result.winner {
+                    return Err(TournamentError::InvalidResult(
+                        "conceding player must be the winner's opponent".to_string(),
+                    ));
+                }
+                if !result.is_signed_by(conceder) {
+                    return Err(TournamentError::MissingSignature(conceder.clone()));
+                }
+            }
+            None => {
+                for player in m.players.iter().flatten() {
+                    if !result.is_signed_by(player) {
+                        return Err(TournamentError::MissingSignature(player.clone()));
+                    }
+                }
+            }
+        }
+
+        let m = &mut self.rounds[round][index];
+        m.winner = Some(result.winner.clone());
+        m.status = MatchStatus::Completed;
+        m.result = Some(result);
+
+        Ok(self.advance_winner(round, index))
+    }
+
+    /// Get a match by ID.
+    pub fn get_match(&self, match_id: &str) -> Option<&BracketMatch> {
+        self.find_match(match_id)
+            .map(|(round, index)| &self.rounds[round][index])
+    }
+
+    /// Get all matches that are waiting to be played.
+    pub fn ready_matches(&self) -> Vec<&BracketMatch> {
+        self.rounds
+            .iter()
+            .flatten()
+            .filter(|m| m.status == MatchStatus::Ready)
+            .collect()
+    }
+
+    /// Get the ready match for a player, if any.
+    pub fn match_for_player(&self, pubkey: &str) -> Option<&BracketMatch> {
+        self.ready_matches()
+            .into_iter()
+            .find(|m| m.has_player(pubkey))
+    }
+
+    /// Get the earliest round that still has unfinished matches.
+    pub fn current_round(&self) -> Option<u32> {
+        self.rounds
+            .iter()
+            .position(|r| r.iter().any(|m| m.status != MatchStatus::Completed))
+            .map(|r| r as u32)
+    }
+
+    /// Check if the tournament has a champion.
+    pub fn is_complete(&self) -> bool {
+        self.status == TournamentStatus::Completed
+    }
+
+    /// Build the bracket event for publishing to relays.
+    pub fn bracket_event(&self) -> TournamentEvent {
+        let mut tags = vec![
+            vec!["d".to_string(), self.id.clone()],
+            vec!["t".to_string(), "nostr-nations-tournament".to_string()],
+            vec!["status".to_string(), format!("{:?}", self.status)],
+        ];
+        for participant in &self.participants {
+            tags.push(vec!["p".to_string(), participant.pubkey.clone()]);
+        }
+
+        TournamentEvent {
+            kind: kinds::TOURNAMENT_BRACKET,
+            tournament_id: self.id.clone(),
+            pubkey: self.organizer.clone(),
+            content: serde_json::to_string(self).unwrap_or_default(),
+            tags,
+            created_at: current_timestamp(),
+        }
+    }
+
+    /// Build a match result event for publishing to relays.
+    pub fn result_event(&self, result: &MatchResult, author: &str) -> TournamentEvent {
+        TournamentEvent {
+            kind: kinds::MATCH_RESULT,
+            tournament_id: self.id.clone(),
+            pubkey: author.to_string(),
+            content: serde_json::to_string(result).unwrap_or_default(),
+            tags: vec![
+                vec!["d".to_string(), result.match_id.clone()],
+                vec!["e".to_string(), self.id.clone()],
+                vec!["g".to_string(), result.game_id.to_string()],
+                vec!["p".to_string(), result.winner.clone()],
+            ],
+            created_at: current_timestamp(),
+        }
+    }
+
+    /// Restore a tournament from a bracket event's content.
+    pub fn from_bracket_event(event: &TournamentEvent) -> Result<Self, TournamentError> {
+        if event.kind != kinds::TOURNAMENT_BRACKET {
+            return Err(TournamentError::InvalidEvent);
+        }
+        serde_json::from_str(&event.content).map_err(|_| TournamentError::InvalidEvent)
+    }
+
+    fn find_match(&self, match_id: &str) -> Option<(usize, usize)> {
+        self.rounds.iter().enumerate().find_map(|(round, matches)| {
+            matches
+                .iter()
+                .position(|m| m.id == match_id)
+                .map(|index| (round, index))
+        })
+    }
+
+    /// Move a completed match's winner into the next round.
+    fn advance_winner(&mut self, round: usize, index: usize) -> Vec<MatchLobby> {
+        let winner = self.rounds[round][index].winner.clone();
+
+        if round + 1 == self.rounds.len() {
+            self.champion = winner;
+            self.status = TournamentStatus::Completed;
+            return Vec::new();
+        }
+
+        let next_index = index / 2;
+        self.rounds[round + 1][next_index].players[index % 2] = winner;
+        self.refresh_match(round + 1, next_index)
+    }
+
+    /// Update a match after its slots change, resolving byes and
+    /// generating a lobby once both players are known.
+    fn refresh_match(&mut self, round: usize, index: usize) -> Vec<MatchLobby> {
+        let m = &self.rounds[round][index];
+        if m.status != MatchStatus::Pending {
+            return Vec::new();
+        }
+
+        match &m.players {
+            [Some(a), Some(b)] => {
+                let lobby = self.create_lobby(&m.id, [a.clone(), b.clone()]);
+                let m = &mut self.rounds[round][index];
+                m.lobby = Some(lobby.clone());
+                m.status = MatchStatus::Ready;
+                vec![lobby]
+            }
+            [Some(p), None] | [None, Some(p)] if self.feeders_done(round, index) => {
+                let winner = p.clone();
+                let m = &mut self.rounds[round][index];
+                m.winner = Some(winner);
+                m.status = MatchStatus::Completed;
+                self.advance_winner(round, index)
+            }
+            _ => Vec::new(),
+        }
+    }
+
+    /// Check whether both feeder matches of a slot are finished (so an empty
+    /// slot is a real bye rather than a pending winner).
+    fn feeders_done(&self, round: usize, index: usize) -> bool {
+        if round == 0 {
+            return true;
+        }
+        let prev = &self.rounds[round - 1];
+        [index * 2, index * 2 + 1]
+            .iter()
+            .all(|&i| prev[i].status == MatchStatus::Completed)
+    }
+
+    fn create_lobby(&self, match_id: &str, players: [String; 2]) -> MatchLobby {
+        let mut data = self.seed.to_vec();
+        data.extend_from_slice(match_id.as_bytes());
+        let seed = simple_hash(&data);
+
+        let mut settings = self.game_settings.clone();
+        settings.name = format!("{} - {}", self.name, match_id);
+        settings.player_count = 2;
+
+        MatchLobby {
+            game_id: GameId(format!("game_{}", match_id)),
+            settings,
+            seed,
+            players,
+        }
+    }
+}
+
+/// Tournament errors.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum TournamentError {
+    /// Registration is closed.
+    RegistrationClosed,
+    /// Player is already registered.
+    AlreadyJoined,
+    /// Tournament has reached its participant limit.
+    TournamentFull,
+    /// Player is not part of the tournament or match.
+    NotParticipant,
+    /// Tournament has already started.
+    AlreadyStarted,
+    /// Not enough participants to start.
+    NotEnoughParticipants,
+    /// Tournament is not in progress.
+    NotInProgress,
+    /// Result belongs to another tournament.
+    WrongTournament,
+    /// Match not found.
+    MatchNotFound(String),
+    /// Match is not waiting for a result.
+    MatchNotReady,
+    /// Result is missing a player's signature.
+    MissingSignature(String),
+    /// Result is invalid.
+    InvalidResult(String),
+    /// Event could not be parsed as a tournament event.
+    InvalidEvent,
+}
+
+impl std::fmt::Display for TournamentError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            TournamentError::RegistrationClosed => write!(f, "Registration is closed"),
+            TournamentError::AlreadyJoined => write!(f, "Already joined this tournament"),
+            TournamentError::TournamentFull => write!(f, "Tournament is full"),
+            TournamentError::NotParticipant => write!(f, "Not a participant"),
+            TournamentError::AlreadyStarted => write!(f, "Tournament has already started"),
+            TournamentError::NotEnoughParticipants => {
+                write!(f, "Need at least {} participants", MIN_PARTICIPANTS)
+            }
+            TournamentError::NotInProgress => write!(f, "Tournament is not in progress"),
+            TournamentError::WrongTournament => write!(f, "Result is for a different tournament"),
+            TournamentError::MatchNotFound(id) => write!(f, "Match not found: {}", id),
+            TournamentError::MatchNotReady => write!(f, "Match is not awaiting a result"),
+            TournamentError::MissingSignature(pk) => write!(f, "Missing signature from {}", pk),
+            TournamentError::InvalidResult(msg) => write!(f, "Invalid result: {}", msg),
+            TournamentError::InvalidEvent => write!(f, "Invalid tournament event"),
+        }
+    }
+}
+
+impl std::error::Error for TournamentError {}
+
+/// Standard bracket seed order for a power-of-two bracket size.
+///
+/// For 8 slots this yields `[1, 8, 4, 5, 2, 7, 3, 6]`, so seeds 1 and 2
+/// can only meet in the final.
+fn seed_order(size: usize) -> Vec<u32> {
+    let mut order = vec![1u32];
+    while order.len() < size {
+        let n = order.len() as u32 * 2 + 1;
+        order = order.iter().flat_map(|&s| [s, n - s]).collect();
+    }
+    order
+}
+
+fn current_timestamp() -> u64 {
+    web_time::SystemTime::now()
+        .duration_since(web_time::UNIX_EPOCH)
+        .map(|d| d.as_secs())
+        .unwrap_or(0)
+}
+
+/// Simple hash function (placeholder for SHA-256).
+fn simple_hash(data: &[u8]) -> [u8; 32] {
+    let mut hash = [0u8; 32];
+    let mut state: u64 = 0xcbf29ce484222325;
+    for (i, chunk) in hash.chunks_mut(8).enumerate() {
+        for &byte in data {
+            state ^= byte as u64;
+            state = state.wrapping_mul(0x100000001b3);
+        }
+        state ^= i as u64;
+        state = state.wrapping_mul(0x100000001b3);
+        chunk.copy_from_slice(&state.to_le_bytes());
+    }
+    hash
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn create_tournament(players: usize) -> Tournament {
+        let mut t = Tournament::new(
+            "cup".to_string(),
+            "Test Cup".to_string(),
+            "organizer".to_string(),
+            16,
+            GameSettings::duel("Match".to_string()),
+        );
+        for i in 0..players {
+            t.join(pk(i + 1), format!("Player {}", i + 1)).unwrap();
+        }
+        t
+    }
+
+    fn key(i: usize) -> SigningKey {
+        SigningKey::from_secret_bytes(&[i as u8; 32]).unwrap()
+    }
+
+    fn pk(i: usize) -> String {
+        key(i).npub().to_string()
+    }
+
+    fn signed_result(t: &Tournament, m: &BracketMatch, winner: &str) -> MatchResult {
+        let mut result = MatchResult::new(
+            t.id.clone(),
+            m.id.clone(),
+            m.lobby.as_ref().unwrap().game_id.clone(),
+            winner.to_string(),
+            None,
+        );
+        for player in m.players.iter().flatten() {
+            let i = (1..=16).find(|&i| pk(i) == *player).unwrap();
+            result.sign(&key(i));
+        }
+        result
+    }
+
+    // ==================== Registration Tests ====================
+
+    #[test]
+    fn test_join_assigns_seeds() {
+        let t = create_tournament(3);
+        let seeds: Vec<u32> = t.participants.iter().map(|p| p.seed).collect();
+        assert_eq!(seeds, vec![1, 2, 3]);
+    }
+
+    #[test]
+    fn test_join_rejects_duplicates_and_full() {
+        let mut t = create_tournament(1);
+        assert_eq!(
+            t.join(pk(1), "Again".to_string()).unwrap_err(),
+            TournamentError::AlreadyJoined
+        );
+
+        t.max_participants = 1;
+        assert_eq!(
+            t.join(pk(2), "Late".to_string()).unwrap_err(),
+            TournamentError::TournamentFull
+        );
+    }
+
+    #[test]
+    fn test_leave_reseeds() {
+        let mut t = create_tournament(3);
+        t.leave(&pk(1)).unwrap();
+        assert_eq!(t.participants[0].pubkey, pk(2));
+        assert_eq!(t.participants[0].seed, 1);
+        assert_eq!(t.leave("missing"), Err(TournamentError::NotParticipant));
+    }
+
+    #[test]
+    fn test_start_requires_participants() {
+        let mut t = create_tournament(1);
+        assert_eq!(
+            t.start([1u8; 32]).unwrap_err(),
+            TournamentError::NotEnoughParticipants
+        );
+    }
+
+    #[test]
+    fn test_bracket_seed_depends_on_id_and_participants() {
+        let t = create_tournament(3);
+
+        // Join order doesn't matter
+        let mut reordered = create_tournament(0);
+        for i in [3, 1, 2] {
+            reordered.join(pk(i), format!("Player {}", i)).unwrap();
+        }
+        assert_eq!(t.bracket_seed(), reordered.bracket_seed());
+
+        let mut more = create_tournament(3);
+        more.join(pk(4), "Player 4".to_string()).unwrap();
+        assert_ne!(t.bracket_seed(), more.bracket_seed());
+
+        let mut renamed = create_tournament(3);
+        renamed.id = "other".to_string();
+        assert_ne!(t.bracket_seed(), renamed.bracket_seed());
+    }
+
+    // ==================== Bracket Tests ====================
+
+    #[test]
+    fn test_seed_order() {
+        assert_eq!(seed_order(2), vec![1, 2]);
+        assert_eq!(seed_order(4), vec![1, 4, 2, 3]);
+        assert_eq!(seed_order(8), vec![1, 8, 4, 5, 2, 7, 3, 6]);
+    }
+
+    #[test]
+    fn test_start_generates_lobbies() {
+        let mut t = create_tournament(4);
+        let lobbies = t.start([7u8; 32]).unwrap();
+
+        assert_eq!(t.status, TournamentStatus::InProgress);
+        assert_eq!(t.rounds.len(), 2);
+        assert_eq!(lobbies.len(), 2);
+        assert_eq!(lobbies[0].players, [pk(1), pk(4)]);
+        assert_eq!(lobbies[0].settings.player_count, 2);
+        assert_ne!(lobbies[0].seed, lobbies[1].seed);
+        assert!(t.join(pk(9), "Late".to_string()).is_err());
+    }
+
+    #[test]
+    fn test_byes_advance_top_seeds() {
+        let mut t = create_tournament(3);
+        let lobbies = t.start([7u8; 32]).unwrap();
+
+        // Seed 1 gets a bye, seeds 2 and 3 play
+        assert_eq!(lobbies.len(), 1);
+        assert_eq!(lobbies[0].players, [pk(2), pk(3)]);
+        assert_eq!(t.rounds[0][0].status, MatchStatus::Completed);
+        assert_eq!(t.rounds[1][0].players[0], Some(pk(1)));
+        assert_eq!(t.rounds[1][0].status, MatchStatus::Pending);
+    }
+
+    #[test]
+    fn test_lobby_create_event() {
+        let mut t = create_tournament(2);
+        let lobbies = t.start([7u8; 32]).unwrap();
+        let event = lobbies[0].create_game_event();
+
+        assert_eq!(event.game_id, lobbies[0].game_id);
+        match event.action {
+            GameAction::CreateGame { seed, .. } => assert_eq!(seed, lobbies[0].seed),
+            _ => panic!("Expected CreateGame"),
+        }
+    }
+
+    // ==================== Result Tests ====================
+
+    #[test]
+    fn test_result_requires_both_signatures() {
+        let mut t = create_tournament(2);
+        t.start([7u8; 32]).unwrap();
+        let m = t.ready_matches()[0].clone();
+
+        let mut result = MatchResult::new(
+            t.id.clone(),
+            m.id.clone(),
+            m.lobby.as_ref().unwrap().game_id.clone(),
+            pk(1),
+            None,
+        );
+        result.sign(&key(1));
+
+        assert_eq!(
+            t.submit_result(result.clone()).unwrap_err(),
+            TournamentError::MissingSignature(pk(2))
+        );
+
+        result.sign(&key(2));
+        assert!(t.submit_result(result).is_ok());
+        assert_eq!(t.champion, Some(pk(1)));
+        assert!(t.is_complete());
+    }
+
+    #[test]
+    fn test_conceded_result_needs_only_conceder() {
+        let mut t = create_tournament(2);
+        t.start([7u8; 32]).unwrap();
+        let m = t.ready_matches()[0].clone();
+
+        let base = MatchResult::new(
+            t.id.clone(),
+            m.id.clone(),
+            m.lobby.as_ref().unwrap().game_id.clone(),
+            pk(1),
+            Some("concede-event".to_string()),
+        );
+
+        // The winner can't claim their opponent conceded
+        let mut claimed = base.clone().with_concession(pk(2));
+        claimed.sign(&key(1));
+        assert_eq!(
+            t.submit_result(claimed).unwrap_err(),
+            TournamentError::MissingSignature(pk(2))
+        );
+
+        let mut self_conceded = base.clone().with_concession(pk(1));
+        self_conceded.sign(&key(1));
+        assert!(matches!(
+            t.submit_result(self_conceded),
+            Err(TournamentError::InvalidResult(_))
+        ));
+
+        let mut result = base.with_concession(pk(2));
+        result.sign(&key(2));
+        assert!(t.submit_result(result).is_ok());
+        assert_eq!(t.champion, Some(pk(1)));
+    }
+
+    #[test]
+    fn test_tampered_result_rejected() {
+        let mut t = create_tournament(2);
+        t.start([7u8; 32]).unwrap();
+        let m = t.ready_matches()[0].clone();
+
+        let mut result = signed_result(&t, &m, &pk(1));
+        result.winner = pk(2);
+
+        assert!(matches!(
+            t.submit_result(result),
+            Err(TournamentError::MissingSignature(_))
+        ));
+
+        // The winner can't sign on their opponent's behalf
+        let mut forged = MatchResult::new(
+            t.id.clone(),
+            m.id.clone(),
+            m.lobby.as_ref().unwrap().game_id.clone(),
+            pk(1),
+            None,
+        );
+        forged.sign(&key(1));
+        forged.signatures.push(ResultSignature {
+            pubkey: pk(2),
+            signature: key(1).sign(&forged.digest()),
+        });
+        assert_eq!(
+            t.submit_result(forged).unwrap_err(),
+            TournamentError::MissingSignature(pk(2))
+        );
+    }
+
+    #[test]
+    fn test_full_bracket_advances_winners() {
+        let mut t = create_tournament(4);
+        t.start([7u8; 32]).unwrap();
+        assert_eq!(t.current_round(), Some(0));
+
+        let first = t.ready_matches()[0].clone();
+        let lobbies = t.submit_result(signed_result(&t, &first, &pk(4))).unwrap();
+        assert!(lobbies.is_empty());
+
+        let second = t.ready_matches()[0].clone();
+        let lobbies = t.submit_result(signed_result(&t, &second, &pk(2))).unwrap();
+        assert_eq!(lobbies.len(), 1);
+        assert_eq!(lobbies[0].players, [pk(4), pk(2)]);
+        assert_eq!(t.current_round(), Some(1));
+
+        let last = t.match_for_player(&pk(2)).unwrap().clone();
+        t.submit_result(signed_result(&t, &last, &pk(2))).unwrap();
+        assert_eq!(t.champion, Some(pk(2)));
+        assert_eq!(t.current_round(), None);
+    }
+
+    #[test]
+    fn test_result_for_completed_match_rejected() {
+        let mut t = create_tournament(4);
+        t.start([7u8; 32]).unwrap();
+        let m = t.ready_matches()[0].clone();
+        let result = signed_result(&t, &m, &pk(1));
+
+        t.submit_result(result.clone()).unwrap();
+        assert_eq!(
+            t.submit_result(result).unwrap_err(),
+            TournamentError::MatchNotReady
+        );
+    }
+
+    // ==================== Event Tests ====================
+
+    #[test]
+    fn test_bracket_event_roundtrip() {
+        let mut t = create_tournament(4);
+        t.start([7u8; 32]).unwrap();
+
+        let event = t.bracket_event();
+        assert_eq!(event.kind, kinds::TOURNAMENT_BRACKET);
+        assert_eq!(event.tags.iter().filter(|t| t[0] == "p").count(), 4);
+
+        let restored = Tournament::from_bracket_event(&event).unwrap();
+        assert_eq!(restored.id, t.id);
+        assert_eq!(restored.ready_matches().len(), 2);
+    }
+
+    #[test]
+    fn test_result_event_tags() {
+        let mut t = create_tournament(2);
+        t.start([7u8; 32]).unwrap();
+        let m = t.ready_matches()[0].clone();
+        let result = signed_result(&t, &m, &pk(2));
+
+        let event = t.result_event(&result, &pk(2));
+        assert_eq!(event.kind, kinds::MATCH_RESULT);
+        assert!(event
+            .tags
+            .contains(&vec!["g".to_string(), result.game_id.to_string()]));
+    }
+}
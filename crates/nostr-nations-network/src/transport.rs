@@ -0,0 +1,394 @@
+//! Peer transport abstraction.
+//!
+//! [`PeerTransport`] is the byte-level link between peers: open or accept
+//! a connection, then send and receive frames. Everything above it
+//! ([`PeerMessage`](crate::peer::PeerMessage) framing, sequencing, sync,
+//! conflict resolution and encryption) only needs this trait, so the same
+//! logic runs over Iroh in the app and over [`LoopbackTransport`] in tests.
+//!
+//! The loopback transport delivers frames through in-process channels.
+//! Delivery is in order and never drops anything, which makes multiplayer
+//! tests deterministic without opening sockets. The Iroh implementation
+//! will sit behind this trait once the `iroh` dependency is enabled.
+//!
+//! # Example
+//!
+//! ```rust,ignore
+//! let network = LoopbackNetwork::new();
+//! let host = network.bind("host")?;
+//! let guest = network.bind("guest")?;
+//!
+//! guest.connect("host").await?;
+//! assert_eq!(host.accept().await?, "guest");
+//!
+//! guest.send("host", b"hello".to_vec()).await?;
+//! assert_eq!(host.recv().await?, ("guest".to_string(), b"hello".to_vec()));
+//! ```
+
+use crate::peer::PeerId;
+use std::collections::{HashMap, HashSet};
+use std::future::Future;
+use std::sync::{Arc, Mutex, MutexGuard};
+use tokio::sync::mpsc;
+
+/// A frame received from a peer.
+pub type Frame = (PeerId, Vec<u8>);
+
+/// A connection-oriented, frame-based link to other peers.
+pub trait PeerTransport: Send + Sync {
+    /// ID this transport is reachable at.
+    fn local_id(&self) -> &PeerId;
+
+    /// Open a connection to a peer.
+    fn connect(&self, peer_id: &str) -> impl Future<Output = Result<(), TransportError>> + Send;
+
+    /// Wait for the next incoming connection and return the peer's ID.
+    fn accept(&self) -> impl Future<Output = Result<PeerId, TransportError>> + Send;
+
+    /// Send a frame to a connected peer.
+    fn send(
+        &self,
+        peer_id: &str,
+        frame: Vec<u8>,
+    ) -> impl Future<Output = Result<(), TransportError>> + Send;
+
+    /// Wait for the next frame from any connected peer.
+    fn recv(&self) -> impl Future<Output = Result<Frame, TransportError>> + Send;
+
+    /// Receive a frame if one is waiting, without blocking.
+    fn try_recv(&self) -> Result<Option<Frame>, TransportError>;
+
+    /// Number of frames that have arrived but are held back from delivery.
+    ///
+    /// Only simulated links such as [`NetemTransport`](crate::netem::NetemTransport)
+    /// hold frames back.
+    fn in_flight(&self) -> usize {
+        0
+    }
+
+    /// Close the connection to a peer.
+    fn disconnect(&self, peer_id: &str);
+
+    /// Check if a peer is connected.
+    fn is_connected(&self, peer_id: &str) -> bool;
+}
+
+/// Transport errors.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum TransportError {
+    /// No peer is listening at the given ID.
+    Unreachable(PeerId),
+    /// The peer is not connected.
+    NotConnected(PeerId),
+    /// The local ID is already bound.
+    AddressInUse(PeerId),
+    /// The transport has been shut down.
+    Closed,
+}
+
+impl std::fmt::Display for TransportError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            TransportError::Unreachable(peer) => write!(f, "Peer {} is unreachable", peer),
+            TransportError::NotConnected(peer) => write!(f, "Peer {} is not connected", peer),
+            TransportError::AddressInUse(peer) => write!(f, "Address {} is already bound", peer),
+            TransportError::Closed => write!(f, "Transport closed"),
+        }
+    }
+}
+
+impl std::error::Error for TransportError {}
+
+/// Registry entry for one bound loopback transport.
+struct Endpoint {
+    frames: mpsc::UnboundedSender<Frame>,
+    accepts: mpsc::UnboundedSender<PeerId>,
+    connections: HashSet<PeerId>,
+}
+
+/// An in-process network that loopback transports bind to.
+///
+/// Cloning the network shares it.
+#[derive(Clone, Default)]
+pub struct LoopbackNetwork {
+    endpoints: Arc<Mutex<HashMap<PeerId, Endpoint>>>,
+}
+
+impl LoopbackNetwork {
+    /// Create an empty network.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Bind a transport to an ID on this network.
+    pub fn bind(&self, local_id: &str) -> Result<LoopbackTransport, TransportError> {
+        let mut endpoints = self.lock();
+        if endpoints.contains_key(local_id) {
+            return Err(TransportError::AddressInUse(PeerId::from(local_id)));
+        }
+
+        let (frames_tx, frames_rx) = mpsc::unbounded_channel();
+        let (accepts_tx, accepts_rx) = mpsc::unbounded_channel();
+        endpoints.insert(
+            PeerId::from(local_id),
+            Endpoint {
+                frames: frames_tx,
+                accepts: accepts_tx,
+                connections: HashSet::new(),
+            },
+        );
+
+        Ok(LoopbackTransport {
+            local_id: PeerId::from(local_id),
+            network: self.clone(),
+            frames: tokio::sync::Mutex::new(frames_rx),
+            accepts: tokio::sync::Mutex::new(accepts_rx),
+        })
+    }
+
+    /// IDs currently bound to the network.
+    pub fn bound_ids(&self) -> Vec<PeerId> {
+        let mut ids: Vec<PeerId> = self.lock().keys().cloned().collect();
+        ids.sort();
+        ids
+    }
+
+    fn lock(&self) -> MutexGuard<'_, HashMap<PeerId, Endpoint>> {
+        self.endpoints
+            .lock()
+            .unwrap_or_else(|poisoned| poisoned.into_inner())
+    }
+}
+
+impl std::fmt::Debug for LoopbackNetwork {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("LoopbackNetwork")
+            .field("bound_ids", &self.bound_ids())
+            .finish()
+    }
+}
+
+/// In-memory [`PeerTransport`] bound to a [`LoopbackNetwork`].
+///
+/// Dropping the transport unbinds it and disconnects its peers.
+pub struct LoopbackTransport {
+    local_id: PeerId,
+    network: LoopbackNetwork,
+    frames: tokio::sync::Mutex<mpsc::UnboundedReceiver<Frame>>,
+    accepts: tokio::sync::Mutex<mpsc::UnboundedReceiver<PeerId>>,
+}
+
+impl LoopbackTransport {
+    /// Peers this transport is connected to, sorted by ID.
+    pub fn connections(&self) -> Vec<PeerId> {
+        let endpoints = self.network.lock();
+        let mut peers: Vec<PeerId> = endpoints
+            .get(&self.local_id)
+            .map(|e| e.connections.iter().cloned().collect())
+            .unwrap_or_default();
+        peers.sort();
+        peers
+    }
+}
+
+impl PeerTransport for LoopbackTransport {
+    fn local_id(&self) -> &PeerId {
+        &self.local_id
+    }
+
+    async fn connect(&self, peer_id: &str) -> Result<(), TransportError> {
+        let mut endpoints = self.network.lock();
+        let remote = endpoints
+            .get_mut(peer_id)
+            .ok_or_else(|| TransportError::Unreachable(PeerId::from(peer_id)))?;
+        if !remote.connections.insert(self.local_id.clone()) {
+            return Ok(());
+        }
+        remote
+            .accepts
+            .send(self.local_id.clone())
+            .map_err(|_| TransportError::Unreachable(PeerId::from(peer_id)))?;
+
+        let local = endpoints
+            .get_mut(&self.local_id)
+            .ok_or(TransportError::Closed)?;
+        local.connections.insert(PeerId::from(peer_id));
+        Ok(())
+    }
+
+    async fn accept(&self) -> Result<PeerId, TransportError> {
+        self.accepts
+            .lock()
+            .await
+            .recv()
+            .await
+            .ok_or(TransportError::Closed)
+    }
+
+    async fn send(&self, peer_id: &str, frame: Vec<u8>) -> Result<(), TransportError> {
+        let endpoints = self.network.lock();
+        if !self.is_connected_locked(&endpoints, peer_id) {
+            return Err(TransportError::NotConnected(PeerId::from(peer_id)));
+        }
+        let remote = endpoints
+            .get(peer_id)
+            .ok_or_else(|| TransportError::NotConnected(PeerId::from(peer_id)))?;
+        remote
+            .frames
+            .send((self.local_id.clone(), frame))
+            .map_err(|_| TransportError::NotConnected(PeerId::from(peer_id)))
+    }
+
+    async fn recv(&self) -> Result<Frame, TransportError> {
+        self.frames
+            .lock()
+            .await
+            .recv()
+            .await
+            .ok_or(TransportError::Closed)
+    }
+
+    fn try_recv(&self) -> Result<Option<Frame>, TransportError> {
+        let Ok(mut frames) = self.frames.try_lock() else {
+            // Another task is already waiting in recv()
+            return Ok(None);
+        };
+        match frames.try_recv() {
+            Ok(frame) => Ok(Some(frame)),
+            Err(mpsc::error::TryRecvError::Empty) => Ok(None),
+            Err(mpsc::error::TryRecvError::Disconnected) => Err(TransportError::Closed),
+        }
+    }
+
+    fn disconnect(&self, peer_id: &str) {
+        let mut endpoints = self.network.lock();
+        if let Some(local) = endpoints.get_mut(&self.local_id) {
+            local.connections.remove(peer_id);
+        }
+        if let Some(remote) = endpoints.get_mut(peer_id) {
+            remote.connections.remove(&self.local_id);
+        }
+    }
+
+    fn is_connected(&self, peer_id: &str) -> bool {
+        self.is_connected_locked(&self.network.lock(), peer_id)
+    }
+}
+
+impl LoopbackTransport {
+    fn is_connected_locked(&self, endpoints: &HashMap<PeerId, Endpoint>, peer_id: &str) -> bool {
+        endpoints
+            .get(&self.local_id)
+            .is_some_and(|e| e.connections.contains(peer_id))
+    }
+}
+
+impl Drop for LoopbackTransport {
+    fn drop(&mut self) {
+        let mut endpoints = self.network.lock();
+        endpoints.remove(&self.local_id);
+        for endpoint in endpoints.values_mut() {
+            endpoint.connections.remove(&self.local_id);
+        }
+    }
+}
+
+impl std::fmt::Debug for LoopbackTransport {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("LoopbackTransport")
+            .field("local_id", &self.local_id)
+            .finish()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    async fn connected_pair(network: &LoopbackNetwork) -> (LoopbackTransport, LoopbackTransport) {
+        let host = network.bind("host").unwrap();
+        let guest = network.bind("guest").unwrap();
+        guest.connect("host").await.unwrap();
+        assert_eq!(host.accept().await.unwrap(), "guest");
+        (host, guest)
+    }
+
+    // ==================== Connection Tests ====================
+
+    #[tokio::test]
+    async fn test_connect_and_accept() {
+        let network = LoopbackNetwork::new();
+        let (host, guest) = connected_pair(&network).await;
+
+        assert!(host.is_connected("guest"));
+        assert!(guest.is_connected("host"));
+        assert_eq!(host.connections(), vec![PeerId::from("guest")]);
+        assert_eq!(
+            network.bound_ids(),
+            vec![PeerId::from("guest"), PeerId::from("host")]
+        );
+    }
+
+    #[tokio::test]
+    async fn test_connect_to_unknown_peer_fails() {
+        let network = LoopbackNetwork::new();
+        let guest = network.bind("guest").unwrap();
+        assert_eq!(
+            guest.connect("nobody").await,
+            Err(TransportError::Unreachable(PeerId::from("nobody")))
+        );
+    }
+
+    #[test]
+    fn test_bind_twice_fails() {
+        let network = LoopbackNetwork::new();
+        let _host = network.bind("host").unwrap();
+        assert!(matches!(
+            network.bind("host"),
+            Err(TransportError::AddressInUse(_))
+        ));
+    }
+
+    #[tokio::test]
+    async fn test_drop_unbinds_and_disconnects() {
+        let network = LoopbackNetwork::new();
+        let (host, guest) = connected_pair(&network).await;
+        drop(guest);
+
+        assert!(!host.is_connected("guest"));
+        assert_eq!(network.bound_ids(), vec![PeerId::from("host")]);
+        assert!(network.bind("guest").is_ok());
+    }
+
+    // ==================== Frame Tests ====================
+
+    #[tokio::test]
+    async fn test_frames_arrive_in_order() {
+        let network = LoopbackNetwork::new();
+        let (host, guest) = connected_pair(&network).await;
+
+        for i in 0..10u8 {
+            guest.send("host", vec![i]).await.unwrap();
+        }
+        for i in 0..10u8 {
+            assert_eq!(host.recv().await.unwrap(), (PeerId::from("guest"), vec![i]));
+        }
+        assert_eq!(host.try_recv().unwrap(), None);
+    }
+
+    #[tokio::test]
+    async fn test_send_requires_connection() {
+        let network = LoopbackNetwork::new();
+        let (host, guest) = connected_pair(&network).await;
+        let _other = network.bind("other").unwrap();
+
+        assert_eq!(
+            guest.send("other", vec![1]).await,
+            Err(TransportError::NotConnected(PeerId::from("other")))
+        );
+
+        host.disconnect("guest");
+        assert!(!guest.is_connected("host"));
+        assert!(guest.send("host", vec![1]).await.is_err());
+    }
+}
@@ -9,12 +9,13 @@
 #![allow(dead_code)]
 
 use nostr_nations_network::{
-    BackoffConfig, BackoffState, BatchConfig, CacheConfig, ConnectionPool,
+    BackoffConfig, BackoffState, BatchConfig, CacheConfig, Capabilities, ConnectionPool,
     ConnectionState, EventBatch, EventBatcher, EventCache, EventDeduplicator, EventUnbatcher,
-    PeerManager, PeerMessage, PoolConfig, PooledConnectionState,
+    PeerId, PeerManager, PeerMessage, PoolConfig, PooledConnectionState,
     PeerSyncTracker, SyncManager, SyncResponse, SyncState,
 };
 use nostr_nations_network::nostr_nations_core::events::{EventChain, GameAction, GameEvent};
+use nostr_nations_network::nostr_nations_core::types::{GameId, PlayerSlot};
 use std::collections::{HashMap, HashSet};
 use std::time::{Duration, Instant};
 
@@ -25,8 +26,8 @@ use std::time::{Duration, Instant};
 /// Create a test game event with the given parameters.
 fn create_test_event(id: &str, game_id: &str, turn: u32, seq: u32) -> GameEvent {
     let mut event = GameEvent::new(
-        game_id.to_string(),
-        0,
+        GameId::new(game_id),
+        PlayerSlot(0),
         None,
         turn,
         seq,
@@ -43,8 +44,8 @@ fn create_test_event(id: &str, game_id: &str, turn: u32, seq: u32) -> GameEvent
 /// Create a test event with previous event linkage (using owned strings).
 fn create_linked_event(id: &str, prev: Option<String>, game_id: &str, turn: u32, seq: u32) -> GameEvent {
     let mut event = GameEvent::new(
-        game_id.to_string(),
-        0,
+        GameId::new(game_id),
+        PlayerSlot(0),
         prev,
         turn,
         seq,
@@ -72,12 +73,12 @@ impl SimulatedPeer {
     fn new(id: &str, game_id: &str, is_host: bool) -> Self {
         Self {
             id: id.to_string(),
-            manager: PeerManager::new(id.to_string(), game_id.to_string(), is_host),
+            manager: PeerManager::new(PeerId::from(id), GameId::new(game_id), is_host),
             cache: EventCache::new(CacheConfig {
                 max_events: 1000,
                 ..Default::default()
             }),
-            sync_manager: SyncManager::new(game_id.to_string(), 0),
+            sync_manager: SyncManager::new(GameId::new(game_id), 0),
             received_events: Vec::new(),
             sent_events: Vec::new(),
         }
@@ -197,8 +198,8 @@ fn simple_rand_float() -> f64 {
 #[tokio::test]
 async fn test_peer_can_connect_to_another_peer() {
     // Create two peer managers
-    let host = PeerManager::new("host_node".to_string(), "game1".to_string(), true);
-    let _client = PeerManager::new("client_node".to_string(), "game1".to_string(), false);
+    let host = PeerManager::new(PeerId::from("host_node"), GameId::new("game1"), true);
+    let _client = PeerManager::new(PeerId::from("client_node"), GameId::new("game1"), false);
 
     // Host creates a connection ticket
     let ticket = host.create_ticket(vec!["192.168.1.1:4433".to_string()], 3600);
@@ -209,7 +210,7 @@ async fn test_peer_can_connect_to_another_peer() {
     assert!(!ticket.is_expired());
     
     // Simulate client connecting to host
-    host.add_peer("client_node".to_string()).await;
+    host.add_peer(PeerId::from("client_node")).await;
     
     // Verify connection was established
     assert_eq!(host.peer_count().await, 1);
@@ -221,16 +222,19 @@ async fn test_peer_can_connect_to_another_peer() {
 /// Test that connection handshake completes.
 #[tokio::test]
 async fn test_connection_handshake_completes() {
-    let host = PeerManager::new("host".to_string(), "game1".to_string(), true);
+    let host = PeerManager::new(PeerId::from("host"), GameId::new("game1"), true);
     
     // Simulate connection
-    host.add_peer("client".to_string()).await;
+    host.add_peer(PeerId::from("client")).await;
     
     // Simulate handshake message
     let hello = PeerMessage::Hello {
-        peer_id: "client".to_string(),
-        game_id: "game1".to_string(),
+        peer_id: PeerId::from("client"),
+        game_id: GameId::new("game1"),
         player_name: "Player1".to_string(),
+        npub: None,
+        capabilities: Capabilities::default(),
+        challenge: None,
     };
     
     host.handle_message("client", hello).await;
@@ -244,24 +248,24 @@ async fn test_connection_handshake_completes() {
     host.handle_message("client", join_request).await;
     
     // Host approves join
-    host.peer_joined("client", "Player1".to_string(), 1).await;
+    host.peer_joined("client", "Player1".to_string(), PlayerSlot(1)).await;
     
     // Verify handshake completed
     let peer_info = host.get_peer("client").await.unwrap();
     assert_eq!(peer_info.state, ConnectionState::Joined);
     assert_eq!(peer_info.player_name, Some("Player1".to_string()));
-    assert_eq!(peer_info.player_id, Some(1));
+    assert_eq!(peer_info.player_id, Some(PlayerSlot(1)));
 }
 
 /// Test that peers exchange identification.
 #[tokio::test]
 async fn test_peers_exchange_identification() {
-    let host = PeerManager::new("host".to_string(), "game1".to_string(), true);
+    let host = PeerManager::new(PeerId::from("host"), GameId::new("game1"), true);
     
     // Add multiple peers
-    host.add_peer("peer1".to_string()).await;
-    host.add_peer("peer2".to_string()).await;
-    host.add_peer("peer3".to_string()).await;
+    host.add_peer(PeerId::from("peer1")).await;
+    host.add_peer(PeerId::from("peer2")).await;
+    host.add_peer(PeerId::from("peer3")).await;
     
     // Each peer sends identification
     for (peer_id, name, civ) in [
@@ -270,9 +274,12 @@ async fn test_peers_exchange_identification() {
         ("peer3", "Carol", "persia"),
     ] {
         let hello = PeerMessage::Hello {
-            peer_id: peer_id.to_string(),
-            game_id: "game1".to_string(),
+            peer_id: PeerId::from(peer_id),
+            game_id: GameId::new("game1"),
             player_name: name.to_string(),
+            npub: None,
+            capabilities: Capabilities::default(),
+            challenge: None,
         };
         host.handle_message(peer_id, hello).await;
         
@@ -284,9 +291,9 @@ async fn test_peers_exchange_identification() {
     }
     
     // Approve all joins
-    host.peer_joined("peer1", "Alice".to_string(), 1).await;
-    host.peer_joined("peer2", "Bob".to_string(), 2).await;
-    host.peer_joined("peer3", "Carol".to_string(), 3).await;
+    host.peer_joined("peer1", "Alice".to_string(), PlayerSlot(1)).await;
+    host.peer_joined("peer2", "Bob".to_string(), PlayerSlot(2)).await;
+    host.peer_joined("peer3", "Carol".to_string(), PlayerSlot(3)).await;
     
     // Verify all peers are identified
     let peers = host.get_peers().await;
@@ -357,7 +364,7 @@ async fn test_state_recovery_after_reconnection() {
     }
     
     // Client has partial state (first 5 events)
-    let mut client_sync = SyncManager::new(game_id.to_string(), 0);
+    let mut client_sync = SyncManager::new(GameId::new(game_id), 0);
     
     // Simulate reconnection - client requests sync from turn 1, sequence 5
     let _request = client_sync.create_request();
@@ -365,7 +372,7 @@ async fn test_state_recovery_after_reconnection() {
     
     // Host sends remaining events
     let response = SyncResponse {
-        game_id: game_id.to_string(),
+        game_id: GameId::new(game_id),
         has_more: false,
         events: initial_events[5..].to_vec(),
         current_turn: 2,
@@ -484,7 +491,7 @@ fn test_messages_reach_all_peers() {
 /// Test peer discovery works.
 #[tokio::test]
 async fn test_peer_discovery_works() {
-    let host = PeerManager::new("host".to_string(), "game1".to_string(), true);
+    let host = PeerManager::new(PeerId::from("host"), GameId::new("game1"), true);
     
     // Create discovery ticket
     let ticket = host.create_ticket(
@@ -506,8 +513,8 @@ async fn test_peer_discovery_works() {
     // Simulate multiple peers joining via discovery
     for i in 0..4 {
         let peer_id = format!("discovered_peer_{}", i);
-        host.add_peer(peer_id.clone()).await;
-        host.peer_joined(&peer_id, format!("Player{}", i), i as u32).await;
+        host.add_peer(PeerId::from(peer_id.clone())).await;
+        host.peer_joined(&peer_id, format!("Player{}", i), PlayerSlot(i as u8)).await;
     }
     
     assert_eq!(host.peer_count().await, 4);
@@ -731,7 +738,7 @@ fn test_new_peer_syncs_full_state() {
     }
     
     // New peer requests full sync
-    let mut new_peer_sync = SyncManager::new(game_id.to_string(), 0);
+    let mut new_peer_sync = SyncManager::new(GameId::new(game_id), 0);
     let request = new_peer_sync.create_request();
     
     assert_eq!(request.last_turn, 0);
@@ -740,7 +747,7 @@ fn test_new_peer_syncs_full_state() {
     
     // Host sends all events
     let response = SyncResponse {
-        game_id: game_id.to_string(),
+        game_id: GameId::new(game_id),
         has_more: false,
         events: chain.events().to_vec(),
         current_turn: 4,
@@ -781,11 +788,11 @@ fn test_incremental_sync_after_reconnect() {
     }
     
     // Peer already has first 20 events
-    let mut peer_sync = SyncManager::new(game_id.to_string(), 0);
+    let mut peer_sync = SyncManager::new(GameId::new(game_id), 0);
     
     // Initial sync of first 20
     let initial_response = SyncResponse {
-        game_id: game_id.to_string(),
+        game_id: GameId::new(game_id),
         has_more: false,
         events: chain.events()[..20].to_vec(),
         current_turn: 2,
@@ -806,7 +813,7 @@ fn test_incremental_sync_after_reconnect() {
     
     // Host sends only new events
     let incremental_response = SyncResponse {
-        game_id: game_id.to_string(),
+        game_id: GameId::new(game_id),
         has_more: false,
         events: chain.events()[20..].to_vec(),
         current_turn: 3,
@@ -829,11 +836,11 @@ fn test_conflict_detection() {
     let game_id = "conflict_game";
     
     // Peer expects game1, receives response for game2
-    let mut peer_sync = SyncManager::new(game_id.to_string(), 0);
+    let mut peer_sync = SyncManager::new(GameId::new(game_id), 0);
     peer_sync.create_request();
     
     let wrong_game_response = SyncResponse {
-        game_id: "wrong_game".to_string(),
+        game_id: GameId::new("wrong_game"),
         has_more: false,
         events: vec![],
         current_turn: 1,
@@ -884,7 +891,7 @@ async fn test_works_with_high_latency() {
     let latencies_ms = [50, 100, 200, 500];
     
     for latency_ms in latencies_ms {
-        let mut sync_manager = SyncManager::new("latency_game".to_string(), 0);
+        let mut sync_manager = SyncManager::new(GameId::new("latency_game"), 0);
         
         let events: Vec<GameEvent> = (0..20)
             .map(|i| create_test_event(&format!("lat_evt_{}", i), "latency_game", 1, i))
@@ -901,7 +908,7 @@ async fn test_works_with_high_latency() {
         tokio::time::sleep(Duration::from_millis(latency_ms)).await;
         
         let response = SyncResponse {
-            game_id: "latency_game".to_string(),
+            game_id: GameId::new("latency_game"),
             has_more: false,
             events,
             current_turn: 1,
@@ -1097,12 +1104,12 @@ async fn test_duplicate_connection_rejected() {
 /// Test handling sync failure.
 #[test]
 fn test_sync_failure_handling() {
-    let mut sync_manager = SyncManager::new("game1".to_string(), 0);
+    let mut sync_manager = SyncManager::new(GameId::new("game1"), 0);
     
     sync_manager.create_request();
     
     let response = SyncResponse {
-        game_id: "game1".to_string(),
+        game_id: GameId::new("game1"),
         has_more: false,
         events: vec![create_test_event("evt1", "game1", 1, 1)],
         current_turn: 1,
@@ -1132,7 +1139,7 @@ async fn test_full_game_session_flow() {
     let game_id = "integration_game";
     
     // 1. Host creates game
-    let host = PeerManager::new("host".to_string(), game_id.to_string(), true);
+    let host = PeerManager::new(PeerId::from("host"), GameId::new(game_id), true);
     
     // 2. Host creates ticket
     let ticket = host.create_ticket(vec!["192.168.1.1:4433".to_string()], 3600);
@@ -1141,13 +1148,16 @@ async fn test_full_game_session_flow() {
     // 3. Three clients join
     for i in 0..3 {
         let peer_id = format!("client_{}", i);
-        host.add_peer(peer_id.clone()).await;
+        host.add_peer(PeerId::from(peer_id.clone())).await;
         
         // Handshake
         let hello = PeerMessage::Hello {
-            peer_id: peer_id.clone(),
-            game_id: game_id.to_string(),
+            peer_id: PeerId::from(peer_id.clone()),
+            game_id: GameId::new(game_id),
             player_name: format!("Player{}", i),
+            npub: None,
+            capabilities: Capabilities::default(),
+            challenge: None,
         };
         host.handle_message(&peer_id, hello).await;
         
@@ -1157,7 +1167,7 @@ async fn test_full_game_session_flow() {
         };
         host.handle_message(&peer_id, join).await;
         
-        host.peer_joined(&peer_id, format!("Player{}", i), i as u32).await;
+        host.peer_joined(&peer_id, format!("Player{}", i), PlayerSlot(i as u8)).await;
     }
     
     assert_eq!(host.peer_count().await, 3);
@@ -1202,8 +1212,8 @@ async fn test_full_game_session_flow() {
     assert_eq!(host.peer_count().await, 2);
     
     // 8. Client reconnects
-    host.add_peer("client_0".to_string()).await;
-    host.peer_joined("client_0", "Player0".to_string(), 0).await;
+    host.add_peer(PeerId::from("client_0")).await;
+    host.peer_joined("client_0", "Player0".to_string(), PlayerSlot(0)).await;
     assert_eq!(host.peer_count().await, 3);
 }
 
@@ -1227,7 +1237,7 @@ fn test_sync_protocol_end_to_end() {
     }
     
     // New client joins with no state
-    let mut client_sync = SyncManager::new(game_id.to_string(), 0);
+    let mut client_sync = SyncManager::new(GameId::new(game_id), 0);
     let mut client_cache = EventCache::with_defaults();
     
     // Client requests sync
@@ -1235,7 +1245,7 @@ fn test_sync_protocol_end_to_end() {
     
     // Host responds (paginated - first 25 events)
     let response1 = SyncResponse {
-        game_id: game_id.to_string(),
+        game_id: GameId::new(game_id),
         has_more: true,
         events: host_chain.events()[..25].to_vec(),
         current_turn: 3,
@@ -1258,7 +1268,7 @@ fn test_sync_protocol_end_to_end() {
     
     // Host responds with remaining events
     let response2 = SyncResponse {
-        game_id: game_id.to_string(),
+        game_id: GameId::new(game_id),
         has_more: false,
         events: host_chain.events()[25..].to_vec(),
         current_turn: 5,
@@ -1323,7 +1333,7 @@ fn stress_test_high_event_throughput() {
         .map(|i| create_test_event(&format!("evt_{}", i), game_id, (i / 100) as u32, (i % 100) as u32))
         .collect();
     
-    let mut sync_manager = SyncManager::new(game_id.to_string(), 0);
+    let mut sync_manager = SyncManager::new(GameId::new(game_id), 0);
     let mut cache = EventCache::new(CacheConfig {
         max_events: 15000,
         ..Default::default()
@@ -1335,7 +1345,7 @@ fn stress_test_high_event_throughput() {
     
     // Receive all events
     let response = SyncResponse {
-        game_id: game_id.to_string(),
+        game_id: GameId::new(game_id),
         has_more: false,
         events,
         current_turn: 100,
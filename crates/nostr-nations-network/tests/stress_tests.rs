@@ -12,6 +12,7 @@ use nostr_nations_network::{
     PoolConfig, PriorityQueueConfig, SyncManager, SyncResponse,
 };
 use nostr_nations_network::nostr_nations_core::events::{GameAction, GameEvent};
+use nostr_nations_network::nostr_nations_core::types::{GameId, PlayerSlot, UnitId};
 use std::collections::HashMap;
 use std::sync::atomic::{AtomicUsize, Ordering};
 use std::sync::Arc;
@@ -25,8 +26,8 @@ use tokio::sync::RwLock;
 /// Create a test game event with the given parameters.
 fn create_test_event(id: &str, game_id: &str, turn: u32, seq: u32) -> GameEvent {
     let mut event = GameEvent::new(
-        game_id.to_string(),
-        0,
+        GameId::new(game_id),
+        PlayerSlot(0),
         None,
         turn,
         seq,
@@ -44,8 +45,8 @@ fn create_test_event(id: &str, game_id: &str, turn: u32, seq: u32) -> GameEvent
 #[allow(dead_code)]
 fn create_linked_event(id: &str, prev: Option<&str>, turn: u32, seq: u32) -> GameEvent {
     let mut event = GameEvent::new(
-        "stress_test".to_string(),
-        0,
+        GameId::new("stress_test"),
+        PlayerSlot(0),
         prev.map(|s| s.to_string()),
         turn,
         seq,
@@ -62,14 +63,14 @@ fn create_linked_event(id: &str, prev: Option<&str>, turn: u32, seq: u32) -> Gam
 /// Create an event for combat (requires randomness in real scenarios).
 fn create_combat_event(id: &str, attacker: u64, defender: u64) -> GameEvent {
     let mut event = GameEvent::new(
-        "stress_test".to_string(),
-        0,
+        GameId::new("stress_test"),
+        PlayerSlot(0),
         None,
         1,
         1,
         GameAction::AttackUnit {
-            attacker_id: attacker,
-            defender_id: defender,
+            attacker_id: UnitId(attacker),
+            defender_id: UnitId(defender),
             random: 0.5,
         },
     );
@@ -80,13 +81,13 @@ fn create_combat_event(id: &str, attacker: u64, defender: u64) -> GameEvent {
 /// Create a move event.
 fn create_move_event(id: &str, unit_id: u64) -> GameEvent {
     let mut event = GameEvent::new(
-        "stress_test".to_string(),
-        0,
+        GameId::new("stress_test"),
+        PlayerSlot(0),
         None,
         1,
         1,
         GameAction::MoveUnit {
-            unit_id,
+            unit_id: UnitId(unit_id),
             path: vec![],
         },
     );
@@ -171,6 +172,7 @@ async fn stress_test_connection_pool_100_peers() {
         health_check_interval: Duration::from_secs(10),
         max_consecutive_failures: 3,
         auto_reconnect: true,
+        ..Default::default()
     };
     
     let pool = ConnectionPool::new(config);
@@ -294,6 +296,8 @@ fn stress_test_event_throughput_10000() {
         max_batch_timeout: Duration::from_millis(10),
         compression_threshold: 1024,
         compression_enabled: true,
+        adaptive_flush: false,
+        ..Default::default()
     };
     
     let mut batcher = EventBatcher::new(config);
@@ -430,13 +434,13 @@ fn stress_test_large_state_sync() {
     // Create 100 cities (one event each)
     for i in 0..100u64 {
         let mut event = GameEvent::new(
-            "large_game".to_string(),
-            (i % 8) as u8, // 8 players
+            GameId::new("large_game"),
+            PlayerSlot((i % 8) as u8), // 8 players
             None,
             1,
             i as u32 + 1,
             GameAction::FoundCity {
-                settler_id: i,
+                settler_id: UnitId(i),
                 name: format!("City_{}", i),
             },
         );
@@ -447,13 +451,13 @@ fn stress_test_large_state_sync() {
     // Create 1000 units (move events)
     for i in 0..1000u64 {
         let mut event = GameEvent::new(
-            "large_game".to_string(),
-            (i % 8) as u8,
+            GameId::new("large_game"),
+            PlayerSlot((i % 8) as u8),
             None,
             1,
             (100 + i) as u32,
             GameAction::MoveUnit {
-                unit_id: i,
+                unit_id: UnitId(i),
                 path: vec![],
             },
         );
@@ -469,7 +473,7 @@ fn stress_test_large_state_sync() {
     
     // Create sync response with all events
     let response = SyncResponse {
-        game_id: "large_game".to_string(),
+        game_id: GameId::new("large_game"),
         has_more: false,
         events: events.clone(),
         current_turn: 1,
@@ -546,6 +550,7 @@ async fn stress_test_concurrent_8_players() {
         fair_scheduling: true,
         max_consecutive: 10,
         age_promotion_threshold: 5000,
+        ..Default::default()
     })));
     
     let start = Instant::now();
@@ -569,8 +574,8 @@ async fn stress_test_concurrent_8_players() {
                     2 => create_test_event(&event_id, "concurrent_test", action_num as u32, 1),
                     _ => {
                         let mut e = GameEvent::new(
-                            "concurrent_test".to_string(),
-                            player_id as u8,
+                            GameId::new("concurrent_test"),
+                            PlayerSlot(player_id as u8),
                             None,
                             1,
                             action_num as u32,
@@ -666,7 +671,7 @@ async fn stress_test_network_latency_simulation() {
         let mut metrics = StressMetrics::default();
         
         // Create sync manager
-        let mut sync_manager = SyncManager::new("latency_test".to_string(), 0);
+        let mut sync_manager = SyncManager::new(GameId::new("latency_test"), 0);
         
         // Create events to sync
         let events: Vec<GameEvent> = (0..100)
@@ -683,7 +688,7 @@ async fn stress_test_network_latency_simulation() {
         
         // Create response
         let response = SyncResponse {
-            game_id: "latency_test".to_string(),
+            game_id: GameId::new("latency_test"),
             has_more: false,
             events: events.clone(),
             current_turn: 10,
@@ -748,7 +753,7 @@ async fn stress_test_reconnection_rapid() {
     
     for cycle in 0..reconnection_cycles {
         // Create a new sync manager (simulates reconnection)
-        let mut sync_manager = SyncManager::new("reconnect_test".to_string(), 0);
+        let mut sync_manager = SyncManager::new(GameId::new("reconnect_test"), 0);
         
         // Create events for this session
         let session_events: Vec<GameEvent> = (0..10)
@@ -769,7 +774,7 @@ async fn stress_test_reconnection_rapid() {
         
         // Receive response with new events
         let response = SyncResponse {
-            game_id: "reconnect_test".to_string(),
+            game_id: GameId::new("reconnect_test"),
             has_more: false,
             events: session_events.clone(),
             current_turn: last_turn + 2,
@@ -1126,6 +1131,7 @@ fn stress_test_priority_queue_mixed_load() {
         fair_scheduling: true,
         max_consecutive: 5,
         age_promotion_threshold: 1000,
+        ..Default::default()
     });
     
     let mut metrics = StressMetrics::default();
@@ -1139,12 +1145,12 @@ fn stress_test_priority_queue_mixed_load() {
             2 => create_test_event(&format!("pq_evt_{}", i), "pq_test", i as u32, 1), // Varies
             3 => {
                 let mut e = GameEvent::new(
-                    "pq_test".to_string(),
-                    0,
+                    GameId::new("pq_test"),
+                    PlayerSlot(0),
                     None,
                     1,
                     1,
-                    GameAction::FortifyUnit { unit_id: i as u64 }, // Low priority
+                    GameAction::FortifyUnit { unit_id: UnitId(i as u64) }, // Low priority
                 );
                 e.id = format!("pq_evt_{}", i);
                 e
@@ -1187,6 +1193,70 @@ fn stress_test_priority_queue_mixed_load() {
     println!("PASS: Priority queue mixed load test completed");
 }
 
+// ============================================================================
+// 9. Relay Storage Bulk Load Test
+// ============================================================================
+
+/// Compares storing 10,000 events one transaction at a time against
+/// batched writes on a WAL-mode database, as during an initial sync.
+///
+/// Pass criteria:
+/// - Every event is stored either way
+/// - Batched writes are faster than per-event writes
+#[cfg(feature = "sqlite")]
+#[test]
+#[ignore]
+fn stress_test_relay_storage_bulk_load() {
+    use nostr_nations_network::RelayStorage;
+
+    println!("\n>>> Starting Relay Storage Bulk Load Test (10,000 events)");
+
+    let events: Vec<GameEvent> = (0..10_000)
+        .map(|i| create_test_event(&format!("bulk_{}", i), "bulk_load", i / 100, i % 100))
+        .collect();
+    let dir = tempfile::tempdir().unwrap();
+
+    let single = RelayStorage::new(dir.path().join("single.db")).unwrap();
+    let mut single_metrics = StressMetrics::default();
+    let start = Instant::now();
+    for event in &events {
+        match single.store_event(event) {
+            Ok(()) => single_metrics.operations += 1,
+            Err(_) => single_metrics.errors += 1,
+        }
+    }
+    single_metrics.duration_ms = start.elapsed().as_millis() as u64;
+    single_metrics.print_summary("Per-Event Writes");
+
+    let batched = RelayStorage::new(dir.path().join("batched.db")).unwrap();
+    let mut batched_metrics = StressMetrics::default();
+    let start = Instant::now();
+    for chunk in events.chunks(500) {
+        match batched.store_events(chunk) {
+            Ok(stored) => batched_metrics.operations += stored as u64,
+            Err(_) => batched_metrics.errors += 1,
+        }
+    }
+    batched_metrics.duration_ms = start.elapsed().as_millis() as u64;
+    batched_metrics.print_summary("Batched Writes (500 per transaction)");
+
+    println!(
+        "Speedup: {:.1}x",
+        single_metrics.duration_ms as f64 / batched_metrics.duration_ms.max(1) as f64
+    );
+
+    // Pass criteria
+    assert_eq!(single.event_count().unwrap(), 10_000);
+    assert_eq!(batched.event_count().unwrap(), 10_000);
+    assert_eq!(batched_metrics.errors, 0, "Should have no errors");
+    assert!(
+        batched_metrics.duration_ms < single_metrics.duration_ms,
+        "Batched writes should beat per-event writes"
+    );
+
+    println!("PASS: Relay storage bulk load test completed");
+}
+
 // ============================================================================
 // Utility Functions
 // ============================================================================
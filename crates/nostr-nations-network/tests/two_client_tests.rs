@@ -0,0 +1,248 @@
+//! End-to-end multiplayer tests with two in-process clients.
+//!
+//! Each test drives a scripted game through [`TwoClientHarness`] and checks
+//! that both clients converge to the same state after every step.
+//!
+//! Run with: `cargo test -p nostr-nations-network --test two_client_tests`
+
+use nostr_nations_network::nostr_nations_core::audit;
+use nostr_nations_network::nostr_nations_core::events::GameAction;
+use nostr_nations_network::nostr_nations_core::hex::HexCoord;
+use nostr_nations_network::nostr_nations_core::replay::GameEngine;
+use nostr_nations_network::nostr_nations_core::settings::GameSettings;
+use nostr_nations_network::nostr_nations_core::types::PlayerSlot;
+use nostr_nations_network::nostr_nations_core::unit::{Unit, UnitType};
+use nostr_nations_network::{LoopbackTransport, NetemConfig, NetemTransport, TwoClientHarness};
+
+async fn new_game() -> TwoClientHarness {
+    let harness = TwoClientHarness::new(GameSettings::new("Duel".to_string()), [42u8; 32])
+        .await
+        .expect("harness should start a game");
+    harness.assert_converged();
+    harness
+}
+
+fn unit_of(engine: &GameEngine, owner: PlayerSlot, unit_type: UnitType) -> Option<Unit> {
+    engine
+        .state
+        .units
+        .values()
+        .find(|u| u.owner == owner && u.unit_type == unit_type)
+        .cloned()
+}
+
+fn open_neighbor(engine: &GameEngine, pos: HexCoord) -> Option<HexCoord> {
+    pos.neighbors().into_iter().find(|n| {
+        engine
+            .state
+            .map
+            .get(n)
+            .is_some_and(|t| t.is_passable_land())
+            && !engine.state.units.values().any(|u| u.position == *n)
+    })
+}
+
+/// Move a player's warrior one tile, if it can.
+async fn step_warrior(harness: &mut TwoClientHarness, player: PlayerSlot) {
+    let engine = harness.client(player).engine().unwrap();
+    let Some(warrior) = unit_of(engine, player, UnitType::Warrior) else {
+        return;
+    };
+    let Some(target) = open_neighbor(engine, warrior.position) else {
+        return;
+    };
+    let action = GameAction::MoveUnit {
+        unit_id: warrior.id,
+        path: vec![target],
+    };
+    if harness.act(player, action).await.is_ok() {
+        harness.assert_converged();
+    }
+}
+
+// ==================== Convergence Tests ====================
+
+#[tokio::test]
+async fn test_scripted_game_converges() {
+    let mut harness = new_game().await;
+
+    for player in [0, 1] {
+        let engine = harness.client(PlayerSlot(player)).engine().unwrap();
+        let settler = unit_of(engine, PlayerSlot(player), UnitType::Settler).unwrap();
+        harness
+            .act(
+                PlayerSlot(player),
+                GameAction::FoundCity {
+                    settler_id: settler.id,
+                    name: format!("Capital {}", player),
+                },
+            )
+            .await
+            .unwrap();
+        harness.assert_converged();
+        harness
+            .act(PlayerSlot(player), GameAction::EndTurn)
+            .await
+            .unwrap();
+        harness.assert_converged();
+    }
+
+    for _ in 0..5 {
+        for player in [0, 1] {
+            step_warrior(&mut harness, PlayerSlot(player)).await;
+            harness
+                .act(PlayerSlot(player), GameAction::EndTurn)
+                .await
+                .unwrap();
+            harness.assert_converged();
+        }
+    }
+
+    let engine = harness.host.engine().unwrap();
+    assert!(engine.state.turn > 5);
+    assert_eq!(engine.state.cities.len(), 2);
+}
+
+#[tokio::test]
+async fn test_relay_contents_replay_to_same_state() {
+    let mut harness = new_game().await;
+    for player in [0, 1, 0, 1] {
+        step_warrior(&mut harness, PlayerSlot(player)).await;
+        harness
+            .act(PlayerSlot(player), GameAction::EndTurn)
+            .await
+            .unwrap();
+    }
+    harness.assert_converged();
+
+    let expected = harness.host.state_hash().unwrap();
+    for client in [&harness.host, &harness.guest] {
+        let events = client.events().unwrap();
+        let replayed = GameEngine::from_events(&events).unwrap();
+        assert_eq!(audit::state_hash(&replayed.state), expected);
+    }
+}
+
+#[tokio::test]
+async fn test_out_of_turn_action_does_not_diverge() {
+    let mut harness = new_game().await;
+    let before = harness.host.events().unwrap().len();
+
+    assert!(harness
+        .act(PlayerSlot(1), GameAction::EndTurn)
+        .await
+        .is_err());
+    harness.assert_converged();
+    assert_eq!(harness.host.events().unwrap().len(), before);
+    assert_eq!(harness.guest.events().unwrap().len(), before);
+}
+
+// ==================== Bad Network Tests ====================
+
+#[tokio::test]
+async fn test_game_converges_with_latency_and_jitter() {
+    let netem = NetemConfig {
+        latency_ms: 8,
+        jitter_ms: 4,
+        seed: 21,
+        ..Default::default()
+    };
+    let mut harness =
+        TwoClientHarness::with_netem(GameSettings::new("Laggy".to_string()), [42u8; 32], netem)
+            .await
+            .expect("harness should start a game over a slow link");
+    harness.assert_converged();
+
+    for player in [0, 1, 0, 1] {
+        harness
+            .act(PlayerSlot(player), GameAction::EndTurn)
+            .await
+            .unwrap();
+        harness.assert_converged();
+    }
+    assert!(harness.host.engine().unwrap().state.turn > 2);
+}
+
+/// Play a few turns over an impaired link. Each turn's actions are sent
+/// back to back before syncing, so several are in flight at once.
+async fn play_over(netem: NetemConfig) -> TwoClientHarness<NetemTransport<LoopbackTransport>> {
+    let mut harness =
+        TwoClientHarness::with_netem(GameSettings::new("Lossy".to_string()), [42u8; 32], netem)
+            .await
+            .expect("harness should start a game over a bad link");
+    harness.assert_converged();
+
+    for player in [0, 1, 0, 1, 0, 1] {
+        let client = if player == 0 {
+            &mut harness.host
+        } else {
+            &mut harness.guest
+        };
+        let engine = client.engine().unwrap();
+        let moves: Vec<GameAction> = engine
+            .state
+            .units
+            .values()
+            .filter(|u| u.owner == PlayerSlot(player) && u.unit_type == UnitType::Warrior)
+            .filter_map(|u| {
+                open_neighbor(engine, u.position).map(|target| GameAction::MoveUnit {
+                    unit_id: u.id,
+                    path: vec![target],
+                })
+            })
+            .collect();
+        for action in moves {
+            // A move may be refused, e.g. when two units pick the same tile
+            let _ = client.perform(action).await;
+        }
+        client.perform(GameAction::EndTurn).await.unwrap();
+        harness.sync().await.unwrap();
+        harness.assert_converged();
+    }
+    assert!(harness.host.engine().unwrap().state.turn > 3);
+    harness
+}
+
+#[tokio::test]
+async fn test_game_converges_with_reordering() {
+    let netem = NetemConfig {
+        latency_ms: 6,
+        jitter_ms: 3,
+        reorder: 0.4,
+        seed: 33,
+        ..Default::default()
+    };
+    let harness = play_over(netem).await;
+
+    let reordered =
+        harness.host.transport().stats().reordered + harness.guest.transport().stats().reordered;
+    assert!(reordered > 0, "link should have reordered frames");
+}
+
+#[tokio::test]
+async fn test_game_converges_with_loss() {
+    let netem = NetemConfig {
+        latency_ms: 2,
+        jitter_ms: 1,
+        loss: 0.2,
+        seed: 44,
+        ..Default::default()
+    };
+    let harness = play_over(netem).await;
+
+    let dropped =
+        harness.host.transport().stats().dropped + harness.guest.transport().stats().dropped;
+    assert!(dropped > 0, "link should have dropped frames");
+}
+
+#[tokio::test]
+async fn test_game_converges_with_loss_and_reordering() {
+    let netem = NetemConfig {
+        latency_ms: 5,
+        jitter_ms: 4,
+        loss: 0.1,
+        reorder: 0.3,
+        seed: 55,
+    };
+    play_over(netem).await;
+}
@@ -0,0 +1,93 @@
+//! TypeScript bindings for frontend payloads.
+//!
+//! Every type in [`visit_payloads`] derives `ts_rs::TS`, so its TypeScript
+//! definition is generated from the Rust type instead of kept in sync by
+//! hand. Run
+//!
+//! ```text
+//! cargo run --manifest-path src-tauri/Cargo.toml -- --export-bindings src/bindings
+//! ```
+//!
+//! (or `npm run bindings`) to write one `.ts` file per type, plus an
+//! `index.ts` re-exporting them all.
+
+use crate::commands::schema::{visit_payloads, PayloadVisitor};
+use schemars::JsonSchema;
+use std::fs;
+use std::path::Path;
+use ts_rs::{ExportError, TS};
+
+/// Command-line flag that exports the bindings instead of starting the app.
+pub const EXPORT_FLAG: &str = "--export-bindings";
+
+/// Writes each visited type, and the types it uses, into a directory.
+struct Exporter<'a> {
+    out_dir: &'a Path,
+    error: Option<ExportError>,
+}
+
+impl PayloadVisitor for Exporter<'_> {
+    fn visit<T: JsonSchema + TS + 'static>(&mut self) {
+        if self.error.is_none() {
+            self.error = T::export_all_to(self.out_dir).err();
+        }
+    }
+}
+
+/// Write the TypeScript bindings for every frontend payload into
+/// `out_dir`, returning the number of types written.
+pub fn export_bindings(out_dir: &Path) -> Result<usize, ExportError> {
+    fs::create_dir_all(out_dir)?;
+    let mut exporter = Exporter {
+        out_dir,
+        error: None,
+    };
+    visit_payloads(&mut exporter);
+    if let Some(error) = exporter.error {
+        return Err(error);
+    }
+
+    let mut names = Vec::new();
+    for entry in fs::read_dir(out_dir)? {
+        let path = entry?.path();
+        let Some(name) = path.file_stem().and_then(|s| s.to_str()) else {
+            continue;
+        };
+        if path.extension().is_none_or(|ext| ext != "ts") || name == "index" {
+            continue;
+        }
+        // ts-rs types 64-bit integers as `bigint`, but they reach the
+        // frontend as JSON numbers
+        let source = fs::read_to_string(&path)?;
+        fs::write(&path, replace_word(&source, "bigint", "number"))?;
+        names.push(name.to_string());
+    }
+    names.sort();
+
+    let mut index = String::from("// Generated by `--export-bindings`; do not edit.\n\n");
+    for name in &names {
+        index.push_str(&format!("export type * from \"./{}\";\n", name));
+    }
+    fs::write(out_dir.join("index.ts"), index)?;
+    Ok(names.len())
+}
+
+/// Replace whole-word occurrences of `word`.
+fn replace_word(source: &str, word: &str, replacement: &str) -> String {
+    let is_ident = |c: char| c.is_ascii_alphanumeric() || c == '_';
+    let mut out = String::with_capacity(source.len());
+    let mut rest = source;
+    while let Some(pos) = rest.find(word) {
+        out.push_str(&rest[..pos]);
+        let before = out.chars().next_back();
+        let after = rest[pos + word.len()..].chars().next();
+        if before.is_some_and(is_ident) || after.is_some_and(is_ident) {
+            out.push_str(word);
+        } else {
+            out.push_str(replacement);
+        }
+        rest = &rest[pos + word.len()..];
+    }
+    out.push_str(rest);
+    out
+}
@@ -2,30 +2,222 @@
 //!
 //! These commands handle in-game actions like moving units, attacking, and building.
 
+use crate::error::AppError;
 use crate::events::{
-    emit_combat_resolved, emit_game_state_updated, emit_notification, CombatResolvedPayload,
-    CombatResults, CombatantInfo, GameStateUpdatedPayload, NotificationPayload, NotificationType,
-    UnitUpdate,
+    emit_combat_resolved, emit_game_action, emit_game_state_updated, emit_notification,
+    CombatResolvedPayload, CombatResults, CombatantInfo, GameActionPayload,
+    GameStateUpdatedPayload, NotificationPayload, NotificationType,
 };
-use crate::state::{AppError, AppState};
-use nostr_nations_core::{GameAction, HexCoord, Improvement};
+use crate::state::AppState;
+use nostr_nations_core::{
+    is_sandbox, ActionEffect, ActionRejection, CaptureChoice, CityId, CombatPreview, GameAction,
+    GameEngine, GameEvent, GameId, HexCoord, Improvement, LocalizedMessage, PlayerSlot, Promotion,
+    StateDiff, UnitId,
+};
+use nostr_nations_network::signing::decode_pubkey;
+use nostr_nations_network::OfflineManager;
+use schemars::JsonSchema;
 use serde::Serialize;
 use std::sync::Mutex;
 use std::time::{SystemTime, UNIX_EPOCH};
 use tauri::{AppHandle, State};
+use ts_rs::TS;
 
 /// Result of a game action.
-#[derive(Clone, Debug, Serialize)]
+#[derive(Clone, Debug, Serialize, JsonSchema, TS)]
 pub struct ActionResult {
     pub success: bool,
     pub message: Option<String>,
     pub effects: Vec<String>,
 }
 
+/// Result of validating an action without applying it.
+#[derive(Clone, Debug, Serialize, JsonSchema, TS)]
+pub struct ActionValidation {
+    pub valid: bool,
+    pub message: Option<String>,
+    pub rejection: Option<ActionRejection>,
+}
+
+/// Check whether an action would be accepted by the game engine.
+///
+/// Runs the same validation as execution but does not modify game state,
+/// so the frontend can disable actions that would fail.
+#[tauri::command]
+pub fn validate_action(
+    game_id: GameId,
+    action: GameAction,
+    state: State<'_, Mutex<AppState>>,
+) -> Result<ActionValidation, AppError> {
+    let state = state
+        .lock()
+        .map_err(|_| AppError::InvalidState("Lock poisoned".to_string()))?;
+
+    let engine = state.get_engine(&game_id)?;
+    let current_player = engine.state.current_player;
+
+    Ok(match engine.validate_action(current_player, &action) {
+        Ok(()) => ActionValidation {
+            valid: true,
+            message: None,
+            rejection: None,
+        },
+        Err(rejection) => ActionValidation {
+            valid: false,
+            message: Some(rejection.to_string()),
+            rejection: Some(rejection),
+        },
+    })
+}
+
+/// Status of the local undo buffer.
+#[derive(Clone, Debug, Serialize, JsonSchema, TS)]
+pub struct UndoStatus {
+    pub can_undo: bool,
+    pub can_redo: bool,
+    pub pending_actions: usize,
+    pub message: Option<String>,
+}
+
+impl UndoStatus {
+    fn from_engine(engine: &GameEngine, message: Option<String>) -> Self {
+        Self {
+            can_undo: engine.buffer.can_undo(),
+            can_redo: engine.buffer.can_redo(),
+            pending_actions: engine.buffer.pending().len(),
+            message,
+        }
+    }
+}
+
+/// Undo the most recent action taken this turn.
+#[tauri::command]
+pub fn undo_action(
+    game_id: GameId,
+    state: State<'_, Mutex<AppState>>,
+) -> Result<UndoStatus, AppError> {
+    let mut state = state
+        .lock()
+        .map_err(|_| AppError::InvalidState("Lock poisoned".to_string()))?;
+
+    let engine = state.get_engine_mut(&game_id)?;
+    let message = engine.undo().map(|action| action.description());
+
+    Ok(UndoStatus::from_engine(engine, message))
+}
+
+/// Redo the most recently undone action.
+#[tauri::command]
+pub fn redo_action(
+    game_id: GameId,
+    state: State<'_, Mutex<AppState>>,
+) -> Result<UndoStatus, AppError> {
+    let mut state = state
+        .lock()
+        .map_err(|_| AppError::InvalidState("Lock poisoned".to_string()))?;
+
+    let engine = state.get_engine_mut(&game_id)?;
+    let result = engine.redo()?;
+    let message = result.and_then(|r| r.error);
+
+    Ok(UndoStatus::from_engine(engine, message))
+}
+
+/// Enable or disable the undo buffer.
+///
+/// Simultaneous-turn games should disable buffering so every action is
+/// broadcast immediately.
+#[tauri::command]
+pub fn set_action_buffering(
+    app_handle: AppHandle,
+    game_id: GameId,
+    enabled: bool,
+    state: State<'_, Mutex<AppState>>,
+) -> Result<UndoStatus, AppError> {
+    let mut state = state
+        .lock()
+        .map_err(|_| AppError::InvalidState("Lock poisoned".to_string()))?;
+
+    let (engine, offline) = state.get_engine_for_action(&game_id)?;
+    engine.set_action_buffering(enabled);
+    broadcast_committed(&app_handle, engine, offline);
+
+    Ok(UndoStatus::from_engine(engine, None))
+}
+
+/// Emit committed actions so the frontend can sign and broadcast them.
+///
+/// While offline, committed actions are queued instead and sent on reconnect.
+/// A state snapshot that became due is published after the actions.
+/// Sandbox games never broadcast; their committed actions are dropped.
+///
+/// Each event carries the turn and sequence its action was buffered with,
+/// and links by NIP-01 ID to the event before it in the batch.
+pub(crate) fn broadcast_committed(
+    app_handle: &AppHandle,
+    engine: &mut GameEngine,
+    offline: &mut OfflineManager,
+) {
+    let mut committed = engine.drain_committed();
+    if is_sandbox(&engine.state.id) {
+        return;
+    }
+    if let Some(player_id) = committed.last().map(|c| c.player_id) {
+        if let Some(snapshot) = engine.take_snapshot() {
+            let turn = engine.state.turn;
+            engine
+                .buffer
+                .commit_action(player_id, GameAction::Snapshot { snapshot }, turn);
+            committed.extend(engine.drain_committed());
+        }
+    }
+    let created_at = SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map(|d| d.as_secs())
+        .unwrap_or(0);
+    let mut prev_event_id = engine.state.event_chain.last().cloned();
+    for entry in committed {
+        let description = entry.action.description();
+        let pubkey = signer_pubkey(engine, entry.player_id);
+        let mut event = GameEvent::new(
+            engine.state.id.clone(),
+            entry.player_id,
+            prev_event_id.take(),
+            entry.turn,
+            entry.sequence,
+            entry.action,
+        );
+        // The frontend publishes with this created_at, so the ID is the one
+        // the signed event will have and the next event can link to it
+        event.timestamp = created_at;
+        event.id = event.nostr_id(&pubkey);
+        prev_event_id = Some(event.id.clone());
+        if let Some(event) = offline.submit_event(event) {
+            let _ = emit_game_action(app_handle, GameActionPayload { event, description });
+        }
+    }
+}
+
+/// Hex public key a player signs their events with.
+///
+/// Players are listed by npub or hex key; a key that can't be decoded is
+/// used as it is.
+fn signer_pubkey(engine: &GameEngine, player_id: PlayerSlot) -> String {
+    let Some(player) = engine.state.get_player(player_id) else {
+        return String::new();
+    };
+    match decode_pubkey(player.pubkey.as_str()) {
+        Ok(key) => key.iter().map(|b| format!("{:02x}", b)).collect(),
+        Err(_) => player.pubkey.to_string(),
+    }
+}
+
 /// Move a unit to a destination.
 #[tauri::command]
 pub fn move_unit(
-    unit_id: u64,
+    app_handle: AppHandle,
+    game_id: GameId,
+    unit_id: UnitId,
     path: Vec<(i32, i32)>,
     state: State<'_, Mutex<AppState>>,
 ) -> Result<ActionResult, AppError> {
@@ -33,21 +225,20 @@ pub fn move_unit(
         .lock()
         .map_err(|_| AppError::InvalidState("Lock poisoned".to_string()))?;
 
-    let engine = state.get_engine_mut()?;
+    let (engine, offline) = state.get_engine_for_action(&game_id)?;
     let current_player = engine.state.current_player;
 
     // Convert path to HexCoords
     let hex_path: Vec<HexCoord> = path.into_iter().map(|(q, r)| HexCoord::new(q, r)).collect();
 
-    let result = engine
-        .apply_action(
-            current_player,
-            &GameAction::MoveUnit {
-                unit_id,
-                path: hex_path,
-            },
-        )
-        .map_err(|e| AppError::InvalidState(format!("{:?}", e)))?;
+    let result = engine.submit_action(
+        current_player,
+        &GameAction::MoveUnit {
+            unit_id,
+            path: hex_path,
+        },
+    )?;
+    broadcast_committed(&app_handle, engine, offline);
 
     Ok(ActionResult {
         success: result.success,
@@ -56,12 +247,31 @@ pub fn move_unit(
     })
 }
 
+/// Preview the expected outcome of attacking a unit.
+#[tauri::command]
+pub fn preview_attack(
+    game_id: GameId,
+    attacker_id: UnitId,
+    defender_id: UnitId,
+    state: State<'_, Mutex<AppState>>,
+) -> Result<CombatPreview, AppError> {
+    let state = state
+        .lock()
+        .map_err(|_| AppError::InvalidState("Lock poisoned".to_string()))?;
+
+    state
+        .get_engine(&game_id)?
+        .preview_attack(attacker_id, defender_id)
+        .ok_or_else(|| AppError::InvalidState("Unit not found".to_string()))
+}
+
 /// Attack an enemy unit.
 #[tauri::command]
 pub fn attack_unit(
     app_handle: AppHandle,
-    attacker_id: u64,
-    defender_id: u64,
+    game_id: GameId,
+    attacker_id: UnitId,
+    defender_id: UnitId,
     random: f32,
     state: State<'_, Mutex<AppState>>,
 ) -> Result<ActionResult, AppError> {
@@ -69,7 +279,7 @@ pub fn attack_unit(
         .lock()
         .map_err(|_| AppError::InvalidState("Lock poisoned".to_string()))?;
 
-    let engine = state.get_engine_mut()?;
+    let (engine, offline) = state.get_engine_for_action(&game_id)?;
     let current_player = engine.state.current_player;
 
     // Capture unit info before combat for event emission
@@ -77,7 +287,7 @@ pub fn attack_unit(
         let owner_name = engine
             .state
             .players
-            .get(u.owner as usize)
+            .get(u.owner.index())
             .map(|p| p.name.clone())
             .unwrap_or_else(|| format!("Player {}", u.owner));
         (
@@ -94,7 +304,7 @@ pub fn attack_unit(
         let owner_name = engine
             .state
             .players
-            .get(u.owner as usize)
+            .get(u.owner.index())
             .map(|p| p.name.clone())
             .unwrap_or_else(|| format!("Player {}", u.owner));
         (
@@ -107,16 +317,16 @@ pub fn attack_unit(
         )
     });
 
-    let result = engine
-        .apply_action(
-            current_player,
-            &GameAction::AttackUnit {
-                attacker_id,
-                defender_id,
-                random,
-            },
-        )
-        .map_err(|e| AppError::InvalidState(format!("{:?}", e)))?;
+    let before = engine.state.clone();
+    let result = engine.submit_action(
+        current_player,
+        &GameAction::AttackUnit {
+            attacker_id,
+            defender_id,
+            random,
+        },
+    )?;
+    broadcast_committed(&app_handle, engine, offline);
 
     // Emit combat event if we have the unit info
     if let (Some(atk_before), Some(def_before)) = (attacker_info_before, defender_info_before) {
@@ -183,100 +393,144 @@ pub fn attack_unit(
 
         // Emit notification based on combat outcome
         let notification = if defender_destroyed {
-            NotificationPayload {
-                notification_type: NotificationType::Combat,
-                title: "Victory!".to_string(),
-                message: format!("Your unit destroyed the enemy {}!", defender_unit_type),
-                icon: Some("sword".to_string()),
-                duration_ms: Some(4000),
-                action: None,
-            }
+            NotificationPayload::localized(
+                NotificationType::Combat,
+                LocalizedMessage::new("notify-combat-victory-title"),
+                LocalizedMessage::new("notify-combat-victory")
+                    .with_arg("unit", &defender_unit_type),
+            )
+            .with_icon("sword")
+            .with_duration(4000)
         } else if attacker_destroyed {
-            NotificationPayload {
-                notification_type: NotificationType::Combat,
-                title: "Unit Lost".to_string(),
-                message: format!("Your {} was destroyed in combat!", attacker_unit_type),
-                icon: Some("skull".to_string()),
-                duration_ms: Some(4000),
-                action: None,
-            }
+            NotificationPayload::localized(
+                NotificationType::Combat,
+                LocalizedMessage::new("notify-combat-unit-lost-title"),
+                LocalizedMessage::new("notify-combat-unit-lost")
+                    .with_arg("unit", &attacker_unit_type),
+            )
+            .with_icon("skull")
+            .with_duration(4000)
         } else {
-            NotificationPayload {
-                notification_type: NotificationType::Combat,
-                title: "Combat".to_string(),
-                message: format!(
-                    "Combat: {} dealt {} damage, received {} damage",
-                    attacker_unit_type, defender_damage, attacker_damage
-                ),
-                icon: Some("crossed-swords".to_string()),
-                duration_ms: Some(3000),
-                action: None,
-            }
+            NotificationPayload::localized(
+                NotificationType::Combat,
+                LocalizedMessage::new("notify-combat-title"),
+                LocalizedMessage::new("notify-combat")
+                    .with_arg("attacker", &attacker_unit_type)
+                    .with_arg("dealt", defender_damage)
+                    .with_arg("received", attacker_damage),
+            )
+            .with_icon("crossed-swords")
+            .with_duration(3000)
         };
         let _ = emit_notification(&app_handle, notification);
 
-        // Emit partial game state update with changed units
-        let mut changed_units = Vec::new();
-
-        if let Some(unit) = engine.state.units.get(&attacker_id) {
-            changed_units.push(UnitUpdate {
-                id: attacker_id,
-                owner: unit.owner,
-                unit_type: format!("{:?}", unit.unit_type),
-                position: (unit.position.q, unit.position.r),
-                health: unit.health,
-                movement_remaining: unit.movement,
-                is_destroyed: false,
-            });
-        } else {
-            changed_units.push(UnitUpdate {
-                id: attacker_id,
-                owner: atk_before.0,
-                unit_type: attacker_unit_type,
-                position: atk_before.5,
-                health: 0,
-                movement_remaining: 0,
-                is_destroyed: true,
-            });
+        if result
+            .effects
+            .iter()
+            .any(|e| matches!(e, ActionEffect::PromotionAvailable { .. }))
+        {
+            let _ = emit_notification(
+                &app_handle,
+                NotificationPayload::localized(
+                    NotificationType::Info,
+                    LocalizedMessage::new("notify-promotion-available-title"),
+                    LocalizedMessage::new("notify-promotion-available")
+                        .with_arg("unit", &attacker_unit_type),
+                )
+                .with_icon("chevron-up")
+                .with_duration(5000),
+            );
         }
 
-        if let Some(unit) = engine.state.units.get(&defender_id) {
-            changed_units.push(UnitUpdate {
-                id: defender_id,
-                owner: unit.owner,
-                unit_type: format!("{:?}", unit.unit_type),
-                position: (unit.position.q, unit.position.r),
-                health: unit.health,
-                movement_remaining: unit.movement,
-                is_destroyed: false,
-            });
+        // Emit partial game state update with exactly what changed
+        let diff = StateDiff::between(&before, &engine.state);
+        let _ = emit_game_state_updated(
+            &app_handle,
+            GameStateUpdatedPayload::partial(&engine.state, &diff),
+        );
+    }
+
+    Ok(ActionResult {
+        success: result.success,
+        message: result.error,
+        effects: result.effects.iter().map(|e| format!("{:?}", e)).collect(),
+    })
+}
+
+/// Bombard an enemy unit from a city.
+#[tauri::command]
+pub fn bombard_unit(
+    app_handle: AppHandle,
+    game_id: GameId,
+    city_id: CityId,
+    target_id: UnitId,
+    random: f32,
+    state: State<'_, Mutex<AppState>>,
+) -> Result<ActionResult, AppError> {
+    let mut state = state
+        .lock()
+        .map_err(|_| AppError::InvalidState("Lock poisoned".to_string()))?;
+
+    let (engine, offline) = state.get_engine_for_action(&game_id)?;
+    let current_player = engine.state.current_player;
+
+    let city_name = engine
+        .state
+        .cities
+        .get(&city_id)
+        .map(|c| c.name.clone())
+        .unwrap_or_else(|| format!("City {}", city_id));
+    let target_type = engine
+        .state
+        .units
+        .get(&target_id)
+        .map(|u| format!("{:?}", u.unit_type))
+        .unwrap_or_default();
+
+    let before = engine.state.clone();
+    let result = engine.submit_action(
+        current_player,
+        &GameAction::BombardUnit {
+            city_id,
+            target_id,
+            random,
+        },
+    )?;
+    broadcast_committed(&app_handle, engine, offline);
+
+    let damage = result.effects.iter().find_map(|e| match e {
+        ActionEffect::CityBombarded { damage, .. } => Some(*damage),
+        _ => None,
+    });
+    if let Some(damage) = damage {
+        let notification = if engine.state.units.contains_key(&target_id) {
+            NotificationPayload::localized(
+                NotificationType::Combat,
+                LocalizedMessage::new("notify-city-bombard-title"),
+                LocalizedMessage::new("notify-city-bombard")
+                    .with_arg("city", &city_name)
+                    .with_arg("unit", &target_type)
+                    .with_arg("damage", damage),
+            )
+            .with_icon("crossed-swords")
+            .with_duration(3000)
         } else {
-            changed_units.push(UnitUpdate {
-                id: defender_id,
-                owner: def_before.0,
-                unit_type: defender_unit_type,
-                position: def_before.5,
-                health: 0,
-                movement_remaining: 0,
-                is_destroyed: true,
-            });
-        }
+            NotificationPayload::localized(
+                NotificationType::Combat,
+                LocalizedMessage::new("notify-city-bombard-title"),
+                LocalizedMessage::new("notify-city-bombard-kill")
+                    .with_arg("city", &city_name)
+                    .with_arg("unit", &target_type),
+            )
+            .with_icon("sword")
+            .with_duration(4000)
+        };
+        let _ = emit_notification(&app_handle, notification);
 
-        let game = &engine.state;
+        let diff = StateDiff::between(&before, &engine.state);
         let _ = emit_game_state_updated(
             &app_handle,
-            GameStateUpdatedPayload {
-                game_id: game.id.clone(),
-                phase: format!("{:?}", game.phase),
-                turn: game.turn,
-                current_player: game.current_player,
-                player_count: game.players.len(),
-                map_dimensions: game.settings.map_size.dimensions(),
-                is_full_update: false,
-                changed_units: Some(changed_units),
-                changed_cities: None,
-                changed_tiles: None,
-            },
+            GameStateUpdatedPayload::partial(&engine.state, &diff),
         );
     }
 
@@ -287,10 +541,138 @@ pub fn attack_unit(
     })
 }
 
+/// Attack an enemy city, capturing it if its defenses fall.
+#[tauri::command]
+pub fn attack_city(
+    app_handle: AppHandle,
+    game_id: GameId,
+    attacker_id: UnitId,
+    city_id: CityId,
+    random: f32,
+    state: State<'_, Mutex<AppState>>,
+) -> Result<ActionResult, AppError> {
+    let mut state = state
+        .lock()
+        .map_err(|_| AppError::InvalidState("Lock poisoned".to_string()))?;
+
+    let (engine, offline) = state.get_engine_for_action(&game_id)?;
+    let current_player = engine.state.current_player;
+
+    let before = engine.state.clone();
+    let result = engine.submit_action(
+        current_player,
+        &GameAction::AttackCity {
+            attacker_id,
+            city_id,
+            random,
+        },
+    )?;
+    broadcast_committed(&app_handle, engine, offline);
+
+    let captured = result
+        .effects
+        .iter()
+        .any(|e| matches!(e, ActionEffect::CityCaptured { .. }));
+    if captured {
+        let city_name = engine
+            .state
+            .cities
+            .get(&city_id)
+            .map(|c| c.name.clone())
+            .unwrap_or_default();
+        let _ = emit_notification(
+            &app_handle,
+            NotificationPayload::localized(
+                NotificationType::Combat,
+                LocalizedMessage::new("notify-city-captured-title"),
+                LocalizedMessage::new("notify-city-captured").with_arg("city", &city_name),
+            )
+            .with_icon("flag")
+            .with_duration(5000),
+        );
+    }
+    if result.success {
+        let diff = StateDiff::between(&before, &engine.state);
+        let _ = emit_game_state_updated(
+            &app_handle,
+            GameStateUpdatedPayload::partial(&engine.state, &diff),
+        );
+    }
+
+    Ok(ActionResult {
+        success: result.success,
+        message: result.error,
+        effects: result.effects.iter().map(|e| format!("{:?}", e)).collect(),
+    })
+}
+
+/// Raze, annex or puppet a captured city.
+#[tauri::command]
+pub fn resolve_capture(
+    app_handle: AppHandle,
+    game_id: GameId,
+    city_id: CityId,
+    choice: CaptureChoice,
+    state: State<'_, Mutex<AppState>>,
+) -> Result<ActionResult, AppError> {
+    let mut state = state
+        .lock()
+        .map_err(|_| AppError::InvalidState("Lock poisoned".to_string()))?;
+
+    let (engine, offline) = state.get_engine_for_action(&game_id)?;
+    let current_player = engine.state.current_player;
+
+    let result = engine.submit_action(
+        current_player,
+        &GameAction::ResolveCapture { city_id, choice },
+    )?;
+    broadcast_committed(&app_handle, engine, offline);
+
+    Ok(ActionResult {
+        success: result.success,
+        message: result.error,
+        effects: result.effects.iter().map(|e| format!("{:?}", e)).collect(),
+    })
+}
+
+/// Give one of the current player's units to another player.
+#[tauri::command]
+pub fn gift_unit(
+    app_handle: AppHandle,
+    game_id: GameId,
+    unit_id: UnitId,
+    recipient: u8,
+    state: State<'_, Mutex<AppState>>,
+) -> Result<ActionResult, AppError> {
+    let mut state = state
+        .lock()
+        .map_err(|_| AppError::InvalidState("Lock poisoned".to_string()))?;
+
+    let (engine, offline) = state.get_engine_for_action(&game_id)?;
+    let current_player = engine.state.current_player;
+
+    let result = engine.submit_action(
+        current_player,
+        &GameAction::GiftUnit {
+            unit_id,
+            recipient: PlayerSlot(recipient),
+        },
+    )?;
+    broadcast_committed(&app_handle, engine, offline);
+
+    Ok(ActionResult {
+        success: result.success,
+        message: result.error,
+        effects: result.effects.iter().map(|e| format!("{:?}", e)).collect(),
+    })
+}
+
 /// Found a new city.
 #[tauri::command]
 pub fn found_city(
-    settler_id: u64,
+    app_handle: AppHandle,
+    game_id: GameId,
+    settler_id: UnitId,
     name: String,
     state: State<'_, Mutex<AppState>>,
 ) -> Result<ActionResult, AppError> {
@@ -298,12 +680,12 @@ pub fn found_city(
         .lock()
         .map_err(|_| AppError::InvalidState("Lock poisoned".to_string()))?;
 
-    let engine = state.get_engine_mut()?;
+    let (engine, offline) = state.get_engine_for_action(&game_id)?;
     let current_player = engine.state.current_player;
 
-    let result = engine
-        .apply_action(current_player, &GameAction::FoundCity { settler_id, name })
-        .map_err(|e| AppError::InvalidState(format!("{:?}", e)))?;
+    let result =
+        engine.submit_action(current_player, &GameAction::FoundCity { settler_id, name })?;
+    broadcast_committed(&app_handle, engine, offline);
 
     Ok(ActionResult {
         success: result.success,
@@ -315,7 +697,9 @@ pub fn found_city(
 /// Build an improvement on a tile.
 #[tauri::command]
 pub fn build_improvement(
-    unit_id: u64,
+    app_handle: AppHandle,
+    game_id: GameId,
+    unit_id: UnitId,
     improvement: String,
     state: State<'_, Mutex<AppState>>,
 ) -> Result<ActionResult, AppError> {
@@ -323,7 +707,7 @@ pub fn build_improvement(
         .lock()
         .map_err(|_| AppError::InvalidState("Lock poisoned".to_string()))?;
 
-    let engine = state.get_engine_mut()?;
+    let (engine, offline) = state.get_engine_for_action(&game_id)?;
     let current_player = engine.state.current_player;
 
     let improvement_type = match improvement.as_str() {
@@ -344,15 +728,139 @@ pub fn build_improvement(
         }
     };
 
-    let result = engine
-        .apply_action(
-            current_player,
-            &GameAction::BuildImprovement {
-                unit_id,
-                improvement: improvement_type,
-            },
-        )
-        .map_err(|e| AppError::InvalidState(format!("{:?}", e)))?;
+    let result = engine.submit_action(
+        current_player,
+        &GameAction::BuildImprovement {
+            unit_id,
+            improvement: improvement_type,
+        },
+    )?;
+    broadcast_committed(&app_handle, engine, offline);
+
+    Ok(ActionResult {
+        success: result.success,
+        message: result.error,
+        effects: result.effects.iter().map(|e| format!("{:?}", e)).collect(),
+    })
+}
+
+/// Start building a road (or railroad) on a worker's tile.
+#[tauri::command]
+pub fn build_road(
+    app_handle: AppHandle,
+    game_id: GameId,
+    unit_id: UnitId,
+    state: State<'_, Mutex<AppState>>,
+) -> Result<ActionResult, AppError> {
+    let mut state = state
+        .lock()
+        .map_err(|_| AppError::InvalidState("Lock poisoned".to_string()))?;
+
+    let (engine, offline) = state.get_engine_for_action(&game_id)?;
+    let current_player = engine.state.current_player;
+
+    let result = engine.submit_action(current_player, &GameAction::BuildRoad { unit_id })?;
+    broadcast_committed(&app_handle, engine, offline);
+
+    Ok(ActionResult {
+        success: result.success,
+        message: result.error,
+        effects: result.effects.iter().map(|e| format!("{:?}", e)).collect(),
+    })
+}
+
+/// Buy a tile for a city's territory with gold.
+#[tauri::command]
+pub fn buy_tile(
+    app_handle: AppHandle,
+    game_id: GameId,
+    city_id: CityId,
+    q: i32,
+    r: i32,
+    state: State<'_, Mutex<AppState>>,
+) -> Result<ActionResult, AppError> {
+    let mut state = state
+        .lock()
+        .map_err(|_| AppError::InvalidState("Lock poisoned".to_string()))?;
+
+    let (engine, offline) = state.get_engine_for_action(&game_id)?;
+    let current_player = engine.state.current_player;
+
+    let result = engine.submit_action(
+        current_player,
+        &GameAction::BuyTile {
+            city_id,
+            tile: HexCoord::new(q, r),
+        },
+    )?;
+    broadcast_committed(&app_handle, engine, offline);
+
+    Ok(ActionResult {
+        success: result.success,
+        message: result.error,
+        effects: result.effects.iter().map(|e| format!("{:?}", e)).collect(),
+    })
+}
+
+/// Promotion choices for a unit.
+#[derive(Clone, Debug, Serialize, JsonSchema, TS)]
+pub struct PromotionOptions {
+    pub unit_id: UnitId,
+    pub experience: u32,
+    pub next_threshold: u32,
+    pub can_promote: bool,
+    pub promotions: Vec<Promotion>,
+    pub available: Vec<Promotion>,
+}
+
+/// Get the promotions a unit has and the ones it could choose next.
+#[tauri::command]
+pub fn get_promotion_options(
+    game_id: GameId,
+    unit_id: UnitId,
+    state: State<'_, Mutex<AppState>>,
+) -> Result<PromotionOptions, AppError> {
+    let state = state
+        .lock()
+        .map_err(|_| AppError::InvalidState("Lock poisoned".to_string()))?;
+
+    let unit = state
+        .get_game_state(&game_id)?
+        .units
+        .get(&unit_id)
+        .ok_or_else(|| AppError::InvalidState(format!("Unit not found: {}", unit_id)))?;
+
+    Ok(PromotionOptions {
+        unit_id,
+        experience: unit.experience,
+        next_threshold: unit.next_promotion_threshold(),
+        can_promote: unit.can_promote(),
+        promotions: unit.promotions.clone(),
+        available: unit.available_promotions(),
+    })
+}
+
+/// Choose a promotion for a unit that has leveled up.
+#[tauri::command]
+pub fn choose_promotion(
+    app_handle: AppHandle,
+    game_id: GameId,
+    unit_id: UnitId,
+    promotion: Promotion,
+    state: State<'_, Mutex<AppState>>,
+) -> Result<ActionResult, AppError> {
+    let mut state = state
+        .lock()
+        .map_err(|_| AppError::InvalidState("Lock poisoned".to_string()))?;
+
+    let (engine, offline) = state.get_engine_for_action(&game_id)?;
+    let current_player = engine.state.current_player;
+
+    let result = engine.submit_action(
+        current_player,
+        &GameAction::ChoosePromotion { unit_id, promotion },
+    )?;
+    broadcast_committed(&app_handle, engine, offline);
 
     Ok(ActionResult {
         success: result.success,
@@ -364,6 +872,8 @@ pub fn build_improvement(
 /// Set the research target for the player.
 #[tauri::command]
 pub fn set_research(
+    app_handle: AppHandle,
+    game_id: GameId,
     tech_id: String,
     state: State<'_, Mutex<AppState>>,
 ) -> Result<ActionResult, AppError> {
@@ -371,12 +881,61 @@ pub fn set_research(
         .lock()
         .map_err(|_| AppError::InvalidState("Lock poisoned".to_string()))?;
 
-    let engine = state.get_engine_mut()?;
+    let (engine, offline) = state.get_engine_for_action(&game_id)?;
+    let current_player = engine.state.current_player;
+
+    let result = engine.submit_action(current_player, &GameAction::SetResearch { tech_id })?;
+    broadcast_committed(&app_handle, engine, offline);
+
+    Ok(ActionResult {
+        success: result.success,
+        message: result.error,
+        effects: result.effects.iter().map(|e| format!("{:?}", e)).collect(),
+    })
+}
+
+/// Research toward a technology, queuing its missing prerequisites.
+#[tauri::command]
+pub fn queue_research(
+    app_handle: AppHandle,
+    game_id: GameId,
+    tech_id: String,
+    state: State<'_, Mutex<AppState>>,
+) -> Result<ActionResult, AppError> {
+    let mut state = state
+        .lock()
+        .map_err(|_| AppError::InvalidState("Lock poisoned".to_string()))?;
+
+    let (engine, offline) = state.get_engine_for_action(&game_id)?;
+    let current_player = engine.state.current_player;
+
+    let result = engine.submit_action(current_player, &GameAction::QueueResearch { tech_id })?;
+    broadcast_committed(&app_handle, engine, offline);
+
+    Ok(ActionResult {
+        success: result.success,
+        message: result.error,
+        effects: result.effects.iter().map(|e| format!("{:?}", e)).collect(),
+    })
+}
+
+/// Replace the technologies queued after the current research.
+#[tauri::command]
+pub fn set_research_queue(
+    app_handle: AppHandle,
+    game_id: GameId,
+    queue: Vec<String>,
+    state: State<'_, Mutex<AppState>>,
+) -> Result<ActionResult, AppError> {
+    let mut state = state
+        .lock()
+        .map_err(|_| AppError::InvalidState("Lock poisoned".to_string()))?;
+
+    let (engine, offline) = state.get_engine_for_action(&game_id)?;
     let current_player = engine.state.current_player;
 
-    let result = engine
-        .apply_action(current_player, &GameAction::SetResearch { tech_id })
-        .map_err(|e| AppError::InvalidState(format!("{:?}", e)))?;
+    let result = engine.submit_action(current_player, &GameAction::SetResearchQueue { queue })?;
+    broadcast_committed(&app_handle, engine, offline);
 
     Ok(ActionResult {
         success: result.success,
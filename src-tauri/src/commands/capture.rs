@@ -0,0 +1,197 @@
+//! Map capture commands.
+//!
+//! These commands save the map as a PNG image, and keep a timelapse: with
+//! the `timelapse_capture` preference on, the map is captured every time
+//! a new turn starts, and the captures are listed in a `timelapse.json`
+//! next to them once the game ends.
+//!
+//! Captures go to `captures/<game id>/` in the active identity's storage
+//! and show the local player's explored map.
+
+use crate::error::AppError;
+use crate::events::{emit_notification, NotificationPayload, NotificationType};
+use crate::state::AppState;
+use nostr_nations_bevy::capture::{
+    timelapse_frame_name, CaptureOptions, CaptureRegion, MapCapture,
+};
+use nostr_nations_core::{GameId, LocalizedMessage, PlayerSlot};
+use schemars::JsonSchema;
+use serde::Serialize;
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::sync::Mutex;
+use tauri::{AppHandle, State};
+use ts_rs::TS;
+
+/// A saved map capture.
+#[derive(Clone, Debug, Serialize, JsonSchema, TS)]
+pub struct MapCaptureResult {
+    /// PNG file written.
+    pub path: String,
+    /// Width in pixels.
+    pub width: u32,
+    /// Height in pixels.
+    pub height: u32,
+}
+
+/// One turn of a timelapse.
+#[derive(Clone, Debug, Serialize, JsonSchema, TS)]
+pub struct TimelapseFrame {
+    /// Turn the capture was taken at the start of.
+    pub turn: u32,
+    /// PNG file of the capture.
+    pub path: String,
+}
+
+/// A game's timelapse captures.
+#[derive(Clone, Debug, Default, Serialize, JsonSchema, TS)]
+pub struct TimelapseInfo {
+    /// Directory holding the captures.
+    pub directory: String,
+    /// Captures, oldest first.
+    pub frames: Vec<TimelapseFrame>,
+    /// Whether the game has ended and the timelapse is finished.
+    pub complete: bool,
+}
+
+/// Directory for a game's captures.
+fn captures_dir(state: &AppState, game_id: &GameId) -> Result<PathBuf, AppError> {
+    Ok(state
+        .profile_storage()?
+        .ok_or_else(|| AppError::StorageError("Storage not initialized".to_string()))?
+        .captures_dir()
+        .join(game_id.as_str()))
+}
+
+/// Render and save a capture.
+fn save_capture(
+    state: &AppState,
+    game_id: &GameId,
+    region: CaptureRegion,
+    path: &Path,
+) -> Result<MapCapture, AppError> {
+    let game = state.get_game_state(game_id)?;
+    let capture = MapCapture::render(game, &CaptureOptions::new(region, PlayerSlot(0)))
+        .map_err(|e| AppError::InvalidState(e.to_string()))?;
+    capture
+        .save_png(path)
+        .map_err(|e| AppError::StorageError(e.to_string()))?;
+    Ok(capture)
+}
+
+/// Save the map as a PNG image.
+///
+/// Without a `path` the image goes to the game's captures directory. The
+/// full map can only be captured once fog of war no longer hides it.
+#[tauri::command]
+pub fn capture_map(
+    game_id: GameId,
+    full_map: bool,
+    path: Option<String>,
+    state: State<'_, Mutex<AppState>>,
+) -> Result<MapCaptureResult, AppError> {
+    let state = state
+        .lock()
+        .map_err(|_| AppError::InvalidState("Lock poisoned".to_string()))?;
+
+    let game = state.get_game_state(&game_id)?;
+    if full_map && game.settings.fog_of_war && !game.revealed {
+        return Err(AppError::InvalidState(
+            "The full map can only be captured once it is revealed".to_string(),
+        ));
+    }
+    let region = if full_map {
+        CaptureRegion::FullMap
+    } else {
+        CaptureRegion::Explored
+    };
+    let path = match path {
+        Some(path) => PathBuf::from(path),
+        None => captures_dir(&state, &game_id)?.join(format!("map_turn_{:04}.png", game.turn)),
+    };
+
+    let capture = save_capture(&state, &game_id, region, &path)?;
+    Ok(MapCaptureResult {
+        path: path.display().to_string(),
+        width: capture.width,
+        height: capture.height,
+    })
+}
+
+/// Get a game's timelapse captures.
+#[tauri::command]
+pub fn get_timelapse(
+    game_id: GameId,
+    state: State<'_, Mutex<AppState>>,
+) -> Result<TimelapseInfo, AppError> {
+    let state = state
+        .lock()
+        .map_err(|_| AppError::InvalidState("Lock poisoned".to_string()))?;
+
+    Ok(state.session(&game_id)?.timelapse.clone())
+}
+
+/// Capture the turn that just started for the timelapse, and finish the
+/// timelapse if the game has ended.
+///
+/// Does nothing unless the `timelapse_capture` preference is on. Each
+/// turn is captured once. Called after a turn ends; failures are logged
+/// rather than failing the turn.
+pub(crate) fn record_timelapse(app_handle: &AppHandle, state: &mut AppState, game_id: &GameId) {
+    if !state.preferences.timelapse_capture {
+        return;
+    }
+    if let Err(e) = try_record_timelapse(app_handle, state, game_id) {
+        tracing::warn!(error = %e, game = %game_id, "timelapse capture failed");
+    }
+}
+
+fn try_record_timelapse(
+    app_handle: &AppHandle,
+    state: &mut AppState,
+    game_id: &GameId,
+) -> Result<(), AppError> {
+    let session = state.session(game_id)?;
+    if session.timelapse.complete {
+        return Ok(());
+    }
+    let turn = session.engine.state.turn;
+    let ended = session.engine.is_ended();
+    let captured = session.timelapse.frames.last().map(|f| f.turn);
+
+    let dir = captures_dir(state, game_id)?.join("timelapse");
+    let mut frame = None;
+    if captured.is_none_or(|last| last < turn) {
+        let path = dir.join(timelapse_frame_name(turn));
+        save_capture(state, game_id, CaptureRegion::Explored, &path)?;
+        frame = Some(TimelapseFrame {
+            turn,
+            path: path.display().to_string(),
+        });
+    }
+
+    let timelapse = &mut state.session_mut(game_id)?.timelapse;
+    timelapse.directory = dir.display().to_string();
+    timelapse.frames.extend(frame);
+    if !ended {
+        return Ok(());
+    }
+
+    timelapse.complete = true;
+    let manifest = serde_json::to_vec_pretty(&timelapse)
+        .map_err(|e| AppError::SerializationError(e.to_string()))?;
+    fs::write(dir.join("timelapse.json"), manifest)
+        .map_err(|e| AppError::StorageError(e.to_string()))?;
+
+    let _ = emit_notification(
+        app_handle,
+        NotificationPayload::localized(
+            NotificationType::Info,
+            LocalizedMessage::new("notify-timelapse-ready-title"),
+            LocalizedMessage::new("notify-timelapse-ready")
+                .with_arg("frames", timelapse.frames.len())
+                .with_arg("directory", timelapse.directory.clone()),
+        ),
+    );
+    Ok(())
+}
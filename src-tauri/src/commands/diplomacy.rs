@@ -0,0 +1,199 @@
+//! Diplomacy commands.
+//!
+//! These commands handle war, peace, treaties, and trade offers. Diplomatic
+//! actions cannot be undone, so each one commits the turn's buffered actions
+//! and is emitted as a game event to be signed and broadcast.
+
+use crate::commands::actions::{broadcast_committed, ActionResult};
+use crate::error::AppError;
+use crate::events::{emit_notification, NotificationPayload, NotificationType};
+use crate::state::AppState;
+use nostr_nations_core::{
+    GameAction, GameEngine, GameId, LocalizedMessage, PlayerSlot, TradeItems, TreatyType,
+};
+use nostr_nations_network::OfflineManager;
+use std::sync::Mutex;
+use tauri::{AppHandle, State};
+
+/// Declare war on another player.
+#[tauri::command]
+pub fn declare_war(
+    app_handle: AppHandle,
+    game_id: GameId,
+    target_player: PlayerSlot,
+    state: State<'_, Mutex<AppState>>,
+) -> Result<ActionResult, AppError> {
+    let mut state = state
+        .lock()
+        .map_err(|_| AppError::InvalidState("Lock poisoned".to_string()))?;
+
+    let (engine, offline) = state.get_engine_for_action(&game_id)?;
+    let current_player = engine.state.current_player;
+
+    let result = submit_action(
+        &app_handle,
+        engine,
+        offline,
+        current_player,
+        GameAction::DeclareWar { target_player },
+    )?;
+
+    if result.success {
+        let _ = emit_notification(
+            &app_handle,
+            NotificationPayload::localized(
+                NotificationType::Diplomacy,
+                LocalizedMessage::new("notify-war-declared-title"),
+                LocalizedMessage::new("notify-war-declared").with_arg("player", target_player),
+            ),
+        );
+    }
+
+    Ok(result)
+}
+
+/// Propose peace to a player you are at war with.
+#[tauri::command]
+pub fn propose_peace(
+    app_handle: AppHandle,
+    game_id: GameId,
+    target_player: PlayerSlot,
+    state: State<'_, Mutex<AppState>>,
+) -> Result<ActionResult, AppError> {
+    let mut state = state
+        .lock()
+        .map_err(|_| AppError::InvalidState("Lock poisoned".to_string()))?;
+
+    let (engine, offline) = state.get_engine_for_action(&game_id)?;
+    let current_player = engine.state.current_player;
+
+    submit_action(
+        &app_handle,
+        engine,
+        offline,
+        current_player,
+        GameAction::ProposePeace { target_player },
+    )
+}
+
+/// Propose a treaty to another player.
+#[tauri::command]
+pub fn propose_treaty(
+    app_handle: AppHandle,
+    game_id: GameId,
+    target_player: PlayerSlot,
+    treaty_type: TreatyType,
+    state: State<'_, Mutex<AppState>>,
+) -> Result<ActionResult, AppError> {
+    let mut state = state
+        .lock()
+        .map_err(|_| AppError::InvalidState("Lock poisoned".to_string()))?;
+
+    let (engine, offline) = state.get_engine_for_action(&game_id)?;
+    let current_player = engine.state.current_player;
+
+    submit_action(
+        &app_handle,
+        engine,
+        offline,
+        current_player,
+        GameAction::ProposeTreaty {
+            target_player,
+            treaty_type,
+        },
+    )
+}
+
+/// Send a trade offer to another player.
+#[tauri::command]
+pub fn send_trade_offer(
+    app_handle: AppHandle,
+    game_id: GameId,
+    to_player: PlayerSlot,
+    offer: TradeItems,
+    request: TradeItems,
+    state: State<'_, Mutex<AppState>>,
+) -> Result<ActionResult, AppError> {
+    let mut state = state
+        .lock()
+        .map_err(|_| AppError::InvalidState("Lock poisoned".to_string()))?;
+
+    let (engine, offline) = state.get_engine_for_action(&game_id)?;
+    let current_player = engine.state.current_player;
+
+    submit_action(
+        &app_handle,
+        engine,
+        offline,
+        current_player,
+        GameAction::ProposeTrade {
+            to_player,
+            offer: Box::new(offer),
+            request: Box::new(request),
+        },
+    )
+}
+
+/// Accept or reject a trade offer addressed to the local player.
+#[tauri::command]
+pub fn respond_trade_offer(
+    app_handle: AppHandle,
+    game_id: GameId,
+    offer_id: u64,
+    accept: bool,
+    state: State<'_, Mutex<AppState>>,
+) -> Result<ActionResult, AppError> {
+    let mut state = state
+        .lock()
+        .map_err(|_| AppError::InvalidState("Lock poisoned".to_string()))?;
+
+    let (engine, offline) = state.get_engine_for_action(&game_id)?;
+    let offer =
+        engine.state.trades.get_offer(offer_id).ok_or_else(|| {
+            AppError::InvalidState(format!("Trade offer not found: {}", offer_id))
+        })?;
+    let (from_player, to_player) = (offer.from_player, offer.to_player);
+
+    let result = submit_action(
+        &app_handle,
+        engine,
+        offline,
+        to_player,
+        GameAction::RespondTrade {
+            offer_id,
+            from_player,
+            accept,
+        },
+    )?;
+
+    if result.success && accept {
+        let _ = emit_notification(
+            &app_handle,
+            NotificationPayload::localized(
+                NotificationType::Diplomacy,
+                LocalizedMessage::new("notify-trade-accepted-title"),
+                LocalizedMessage::new("notify-trade-accepted").with_arg("player", from_player),
+            ),
+        );
+    }
+
+    Ok(result)
+}
+
+/// Apply an action locally and broadcast anything it committed.
+fn submit_action(
+    app_handle: &AppHandle,
+    engine: &mut GameEngine,
+    offline: &mut OfflineManager,
+    player_id: PlayerSlot,
+    action: GameAction,
+) -> Result<ActionResult, AppError> {
+    let result = engine.submit_action(player_id, &action)?;
+    broadcast_committed(app_handle, engine, offline);
+
+    Ok(ActionResult {
+        success: result.success,
+        message: result.error,
+        effects: result.effects.iter().map(|e| format!("{:?}", e)).collect(),
+    })
+}
@@ -2,40 +2,81 @@
 //!
 //! These commands handle game lifecycle: creation, joining, starting, and state queries.
 
+use crate::commands::actions::broadcast_committed;
+use crate::commands::capture::record_timelapse;
+use crate::error::AppError;
 use crate::events::{
-    emit_game_state_updated, emit_notification, emit_turn_event, GameStateUpdatedPayload,
-    NotificationPayload, TurnEventPayload,
+    emit_game_state_updated, emit_notification, emit_operation_progress, emit_turn_event,
+    emit_turn_schedule, GameStateUpdatedPayload, NotificationPayload, NotificationType,
+    OperationProgressPayload, TurnEventPayload, TurnSchedulePayload,
 };
-use crate::state::{AppError, AppState};
-use nostr_nations_core::{Difficulty, GameAction, GamePhase, GameSettings, GameSpeed, MapSize};
+use crate::state::{AppState, GameSession, UserProfile};
+use crate::worker::{engine_worker, lock_state};
+use nostr_nations_core::{
+    project_treasury, recommend_production, wonders, ActionEffect, AiBudget, AiPlanner, CityId,
+    CityNeeds, Demographics, Difficulty, Era, GameAction, GameId, GamePhase, GameSettings,
+    GameSpeed, LocalizedMessage, LogEntry, LogFilter, MapSize, Npub, PauseState, PendingItem,
+    PlayerSlot, Recommendation, ReplayError, StateDiff, TurnAdvisor, TurnDigest, VictoryProof,
+    VisibilityFilter, DEFAULT_RESUME_COUNTDOWN_SECS,
+};
+use nostr_nations_network::game_events;
+use schemars::JsonSchema;
 use serde::{Deserialize, Serialize};
 use std::sync::Mutex;
 use tauri::{AppHandle, State};
+use ts_rs::TS;
 
 /// Response for game state queries.
-#[derive(Clone, Debug, Serialize)]
+#[derive(Clone, Debug, Serialize, JsonSchema, TS)]
 pub struct GameStateResponse {
-    pub game_id: String,
+    pub game_id: GameId,
     pub phase: String,
     pub turn: u32,
-    pub current_player: u8,
+    pub current_player: PlayerSlot,
     pub player_count: usize,
     pub map_width: u32,
     pub map_height: u32,
 }
 
 /// Options for creating a new game.
-#[derive(Clone, Debug, Deserialize)]
+///
+/// Fields left out fall back to the user profile's defaults.
+#[derive(Clone, Debug, Deserialize, JsonSchema, TS)]
 pub struct CreateGameOptions {
     pub name: String,
-    pub player_name: String,
-    pub civilization: String,
-    pub map_size: String,
-    pub difficulty: String,
-    pub game_speed: String,
+    pub player_name: Option<String>,
+    pub civilization: Option<String>,
+    pub map_size: Option<String>,
+    pub difficulty: Option<String>,
+    pub game_speed: Option<String>,
     pub seed: Option<String>,
 }
 
+impl CreateGameOptions {
+    /// Fill missing fields from the user profile.
+    pub fn with_profile_defaults(mut self, profile: &UserProfile) -> Self {
+        self.player_name = non_empty(self.player_name).or_else(|| profile_name(profile));
+        self.civilization =
+            non_empty(self.civilization).or_else(|| profile.default_civilization.clone());
+        self.map_size = non_empty(self.map_size).or_else(|| profile.default_map_size.clone());
+        self.difficulty = non_empty(self.difficulty).or_else(|| profile.default_difficulty.clone());
+        self.game_speed = non_empty(self.game_speed).or_else(|| profile.default_game_speed.clone());
+        self
+    }
+}
+
+fn non_empty(value: Option<String>) -> Option<String> {
+    value.filter(|v| !v.is_empty())
+}
+
+/// The profile's display name, if set.
+fn profile_name(profile: &UserProfile) -> Option<String> {
+    non_empty(Some(profile.display_name.clone()))
+}
+
+/// Civilization used when neither the request nor the profile picks one.
+const FALLBACK_CIVILIZATION: &str = "rome";
+
 /// Create a new game.
 #[tauri::command]
 pub fn create_game(
@@ -46,13 +87,11 @@ pub fn create_game(
         .lock()
         .map_err(|_| AppError::InvalidState("Lock poisoned".to_string()))?;
 
-    if state.has_active_game() {
-        return Err(AppError::GameAlreadyActive);
-    }
+    let options = options.with_profile_defaults(&state.profile);
 
     // Create settings from options
     let mut settings = GameSettings::new(options.name.clone());
-    settings.map_size = match options.map_size.as_str() {
+    settings.map_size = match options.map_size.as_deref().unwrap_or_default() {
         "duel" => MapSize::Duel,
         "small" => MapSize::Small,
         "standard" => MapSize::Standard,
@@ -60,14 +99,14 @@ pub fn create_game(
         "huge" => MapSize::Huge,
         _ => MapSize::Standard,
     };
-    settings.game_speed = match options.game_speed.as_str() {
+    settings.game_speed = match options.game_speed.as_deref().unwrap_or_default() {
         "quick" => GameSpeed::Quick,
         "normal" | "standard" => GameSpeed::Normal,
         "epic" => GameSpeed::Epic,
         "marathon" => GameSpeed::Marathon,
         _ => GameSpeed::Normal,
     };
-    settings.difficulty = match options.difficulty.as_str() {
+    settings.difficulty = match options.difficulty.as_deref().unwrap_or_default() {
         "settler" => Difficulty::Settler,
         "chieftain" => Difficulty::Chieftain,
         "normal" | "prince" => Difficulty::Normal,
@@ -102,22 +141,24 @@ pub fn create_game(
         seed
     };
 
-    state.create_game(settings, seed)?;
+    let game_id = state.create_game(settings, seed)?;
 
     // Join as first player
-    let engine = state.get_engine_mut()?;
-    engine
-        .apply_action(
-            0,
-            &GameAction::JoinGame {
-                player_name: options.player_name,
-                civilization_id: options.civilization,
-            },
-        )
-        .map_err(|e| AppError::InvalidState(format!("{:?}", e)))?;
+    let engine = state.get_engine_mut(&game_id)?;
+    engine.apply_action(
+        PlayerSlot(0),
+        &GameAction::JoinGame {
+            player_name: options.player_name.ok_or_else(|| {
+                AppError::InvalidState("No player name given or set in profile".to_string())
+            })?,
+            civilization_id: options
+                .civilization
+                .unwrap_or_else(|| FALLBACK_CIVILIZATION.to_string()),
+        },
+    )?;
 
     // Return game state
-    let game = state.get_game_state()?;
+    let game = state.get_game_state(&game_id)?;
     Ok(GameStateResponse {
         game_id: game.id.clone(),
         phase: format!("{:?}", game.phase),
@@ -132,26 +173,34 @@ pub fn create_game(
 /// Join an existing game.
 #[tauri::command]
 pub fn join_game(
-    player_name: String,
-    civilization: String,
+    game_id: GameId,
+    player_name: Option<String>,
+    civilization: Option<String>,
     state: State<'_, Mutex<AppState>>,
 ) -> Result<GameStateResponse, AppError> {
     let mut state = state
         .lock()
         .map_err(|_| AppError::InvalidState("Lock poisoned".to_string()))?;
 
-    let engine = state.get_engine_mut()?;
+    let player_name = non_empty(player_name)
+        .or_else(|| profile_name(&state.profile))
+        .ok_or_else(|| {
+            AppError::InvalidState("No player name given or set in profile".to_string())
+        })?;
+    let civilization = non_empty(civilization)
+        .or_else(|| state.profile.default_civilization.clone())
+        .unwrap_or_else(|| FALLBACK_CIVILIZATION.to_string());
+
+    let engine = state.get_engine_mut(&game_id)?;
     let player_id = engine.state.players.len() as u8;
 
-    engine
-        .apply_action(
-            player_id,
-            &GameAction::JoinGame {
-                player_name,
-                civilization_id: civilization,
-            },
-        )
-        .map_err(|e| AppError::InvalidState(format!("{:?}", e)))?;
+    engine.apply_action(
+        PlayerSlot(player_id),
+        &GameAction::JoinGame {
+            player_name,
+            civilization_id: civilization,
+        },
+    )?;
 
     let game = &engine.state;
     Ok(GameStateResponse {
@@ -166,16 +215,26 @@ pub fn join_game(
 }
 
 /// Start the game (transitions from Setup to Playing).
+///
+/// Generating the map can take a while, so this runs on the engine worker.
 #[tauri::command]
-pub fn start_game(
+pub async fn start_game(
     app_handle: AppHandle,
-    state: State<'_, Mutex<AppState>>,
+    game_id: GameId,
 ) -> Result<GameStateResponse, AppError> {
-    let mut state = state
-        .lock()
-        .map_err(|_| AppError::InvalidState("Lock poisoned".to_string()))?;
+    engine_worker(&app_handle)?
+        .run_session(game_id.clone(), move |app_handle, session| {
+            start_game_on_worker(app_handle, session, &game_id)
+        })
+        .await
+}
 
-    let engine = state.get_engine_mut()?;
+fn start_game_on_worker(
+    app_handle: &AppHandle,
+    session: &mut GameSession,
+    game_id: &GameId,
+) -> Result<GameStateResponse, AppError> {
+    let engine = &mut session.engine;
 
     if engine.state.phase != GamePhase::Setup {
         return Err(AppError::InvalidState(
@@ -183,9 +242,18 @@ pub fn start_game(
         ));
     }
 
-    engine
-        .apply_action(0, &GameAction::StartGame)
-        .map_err(|e| AppError::InvalidState(format!("{:?}", e)))?;
+    // Report map generation as `start_game:<game id>`
+    let progress_handle = app_handle.clone();
+    let operation_id = format!("start_game:{}", game_id);
+    engine.set_progress_callback(Some(Box::new(move |progress| {
+        let _ = emit_operation_progress(
+            &progress_handle,
+            OperationProgressPayload::new(operation_id.clone(), progress),
+        );
+    })));
+    let started = engine.apply_action(PlayerSlot(0), &GameAction::StartGame);
+    engine.set_progress_callback(None);
+    started?;
 
     let game = &engine.state;
 
@@ -198,7 +266,7 @@ pub fn start_game(
 
     // Emit game state update for game start
     let _ = emit_game_state_updated(
-        &app_handle,
+        app_handle,
         GameStateUpdatedPayload {
             game_id: game.id.clone(),
             phase: format!("{:?}", game.phase),
@@ -210,29 +278,28 @@ pub fn start_game(
             changed_units: None,
             changed_cities: None,
             changed_tiles: None,
+            changed_territory: None,
         },
     );
 
     // Emit turn started event for turn 1
     let _ = emit_turn_event(
-        &app_handle,
+        app_handle,
         TurnEventPayload::turn_started(
             game.turn,
             game.current_player,
             first_player_name.clone(),
-            game.current_player == 0, // Assuming player 0 is local
+            game.current_player == PlayerSlot(0), // Assuming player 0 is local
         ),
     );
 
     // Emit notification for game start
     let _ = emit_notification(
-        &app_handle,
-        NotificationPayload::success(
-            "Game Started",
-            format!(
-                "The game has begun! {} players competing.",
-                game.players.len()
-            ),
+        app_handle,
+        NotificationPayload::localized(
+            NotificationType::Success,
+            LocalizedMessage::new("notify-game-started-title"),
+            LocalizedMessage::new("notify-game-started").with_arg("count", game.players.len()),
         ),
     );
 
@@ -247,14 +314,17 @@ pub fn start_game(
     })
 }
 
-/// Get the current game state.
+/// Get a game's state.
 #[tauri::command]
-pub fn get_game_state(state: State<'_, Mutex<AppState>>) -> Result<GameStateResponse, AppError> {
+pub fn get_game_state(
+    game_id: GameId,
+    state: State<'_, Mutex<AppState>>,
+) -> Result<GameStateResponse, AppError> {
     let state = state
         .lock()
         .map_err(|_| AppError::InvalidState("Lock poisoned".to_string()))?;
 
-    let game = state.get_game_state()?;
+    let game = state.get_game_state(&game_id)?;
     Ok(GameStateResponse {
         game_id: game.id.clone(),
         phase: format!("{:?}", game.phase),
@@ -266,13 +336,29 @@ pub fn get_game_state(state: State<'_, Mutex<AppState>>) -> Result<GameStateResp
     })
 }
 
-/// End the current game and return to menu.
+/// Get a proof of a finished game's outcome for ladders and escrows.
 #[tauri::command]
-pub fn end_game(state: State<'_, Mutex<AppState>>) -> Result<(), AppError> {
+pub fn get_victory_proof(
+    game_id: GameId,
+    state: State<'_, Mutex<AppState>>,
+) -> Result<VictoryProof, AppError> {
+    let state = state
+        .lock()
+        .map_err(|_| AppError::InvalidState("Lock poisoned".to_string()))?;
+
+    state
+        .get_engine(&game_id)?
+        .victory_proof()
+        .map_err(|e| AppError::InvalidState(e.to_string()))
+}
+
+/// End a game and stop tracking it.
+#[tauri::command]
+pub fn end_game(game_id: GameId, state: State<'_, Mutex<AppState>>) -> Result<(), AppError> {
     let mut state = state
         .lock()
         .map_err(|_| AppError::InvalidState("Lock poisoned".to_string()))?;
-    state.end_game();
+    state.end_game(&game_id)?;
     Ok(())
 }
 
@@ -280,36 +366,144 @@ pub fn end_game(state: State<'_, Mutex<AppState>>) -> Result<(), AppError> {
 #[tauri::command]
 pub fn end_turn(
     app_handle: AppHandle,
+    game_id: GameId,
     state: State<'_, Mutex<AppState>>,
 ) -> Result<GameStateResponse, AppError> {
     let mut state = state
         .lock()
         .map_err(|_| AppError::InvalidState("Lock poisoned".to_string()))?;
 
-    let engine = state.get_engine_mut()?;
+    let block_on_pending = state.preferences.block_end_turn_on_pending;
+    let (engine, offline) = state.get_engine_for_action(&game_id)?;
     let previous_player = engine.state.current_player;
     let previous_turn = engine.state.turn;
 
+    if block_on_pending {
+        let advisor = TurnAdvisor::new(&engine.state, previous_player);
+        let pending = advisor.mandatory().count();
+        if pending > 0 {
+            return Err(AppError::PendingDecisions(pending));
+        }
+    }
+
     // Get player name before applying action
     let previous_player_name = engine
         .state
         .players
-        .get(previous_player as usize)
+        .get(previous_player.index())
         .map(|p| p.name.clone())
         .unwrap_or_else(|| format!("Player {}", previous_player));
 
-    engine
-        .apply_action(previous_player, &GameAction::EndTurn)
-        .map_err(|e| AppError::InvalidState(format!("{:?}", e)))?;
+    // Ending the turn commits any buffered actions for broadcast
+    let before = engine.state.clone();
+    let result = engine.submit_action(previous_player, &GameAction::EndTurn)?;
+    broadcast_committed(&app_handle, engine, offline);
 
     let game = &engine.state;
+    let player_name = |player_id: PlayerSlot| {
+        game.get_player(player_id)
+            .map(|p| p.name.clone())
+            .unwrap_or_else(|| format!("Player {}", player_id))
+    };
+    for effect in &result.effects {
+        match effect {
+            ActionEffect::EraEntered { player_id, era } => {
+                let _ = emit_notification(
+                    &app_handle,
+                    NotificationPayload::localized(
+                        NotificationType::Info,
+                        LocalizedMessage::new("notify-era-entered-title"),
+                        LocalizedMessage::new("notify-era-entered")
+                            .with_arg("player", player_name(*player_id))
+                            .with_arg("era", era),
+                    ),
+                );
+            }
+            ActionEffect::FirstContact {
+                player_id: PlayerSlot(0),
+                met_player,
+            } => {
+                let _ = emit_notification(
+                    &app_handle,
+                    NotificationPayload::localized(
+                        NotificationType::Info,
+                        LocalizedMessage::new("notify-first-contact-title"),
+                        LocalizedMessage::new("notify-first-contact")
+                            .with_arg("player", player_name(*met_player)),
+                    ),
+                );
+            }
+            ActionEffect::Circumnavigated { player_id, gold } => {
+                let _ = emit_notification(
+                    &app_handle,
+                    NotificationPayload::localized(
+                        NotificationType::Info,
+                        LocalizedMessage::new("notify-circumnavigated-title"),
+                        LocalizedMessage::new("notify-circumnavigated")
+                            .with_arg("player", player_name(*player_id))
+                            .with_arg("gold", *gold),
+                    ),
+                );
+            }
+            // Wonders are only announced to players who have met the builder
+            ActionEffect::WonderCompleted { wonder, .. } => {
+                let Some(built) = game.wonders.get(wonder) else {
+                    continue;
+                };
+                if !wonders::is_announced_to(game, built, PlayerSlot(0)) {
+                    continue;
+                }
+                let city = game
+                    .cities
+                    .get(&built.city_id)
+                    .map(|c| c.name.clone())
+                    .unwrap_or_default();
+                let _ = emit_notification(
+                    &app_handle,
+                    NotificationPayload::localized(
+                        NotificationType::Info,
+                        LocalizedMessage::new("notify-wonder-completed-title"),
+                        LocalizedMessage::new("notify-wonder-completed")
+                            .with_arg("player", player_name(built.player_id))
+                            .with_arg("wonder", format!("{:?}", wonder))
+                            .with_arg("city", city),
+                    ),
+                );
+            }
+            ActionEffect::WonderLost {
+                city_id,
+                player_id: PlayerSlot(0),
+                wonder,
+                gold,
+            } => {
+                let city = game
+                    .cities
+                    .get(city_id)
+                    .map(|c| c.name.clone())
+                    .unwrap_or_default();
+                let _ = emit_notification(
+                    &app_handle,
+                    NotificationPayload::localized(
+                        NotificationType::Warning,
+                        LocalizedMessage::new("notify-wonder-lost-title"),
+                        LocalizedMessage::new("notify-wonder-lost")
+                            .with_arg("wonder", format!("{:?}", wonder))
+                            .with_arg("city", city)
+                            .with_arg("gold", gold),
+                    ),
+                );
+            }
+            _ => {}
+        }
+    }
+
     let new_player = game.current_player;
     let new_turn = game.turn;
 
     // Get new player name
     let new_player_name = game
         .players
-        .get(new_player as usize)
+        .get(new_player.index())
         .map(|p| p.name.clone())
         .unwrap_or_else(|| format!("Player {}", new_player));
 
@@ -332,23 +526,181 @@ pub fn end_turn(
                 new_turn,
                 new_player,
                 new_player_name.clone(),
-                new_player == 0, // Assuming player 0 is local
+                new_player == PlayerSlot(0), // Assuming player 0 is local
             ),
         );
     }
 
-    // Emit player turn event for the new current player
+    // Emit player turn event for the new current player, with their
+    // projected gold flow
     let _ = emit_turn_event(
         &app_handle,
         TurnEventPayload::player_turn(
             new_turn,
             new_player,
             new_player_name.clone(),
-            new_player == 0, // Assuming player 0 is local
-        ),
+            new_player == PlayerSlot(0), // Assuming player 0 is local
+        )
+        .with_treasury(project_treasury(game, new_player))
+        .with_era(game.get_player(new_player).map_or(Era::Ancient, |p| p.era)),
+    );
+
+    // Emit game state update with what turn processing changed
+    let diff = StateDiff::between(&before, game);
+    let _ = emit_game_state_updated(&app_handle, GameStateUpdatedPayload::partial(game, &diff));
+
+    // Emit notification if it's now the local player's turn
+    if new_player == PlayerSlot(0) {
+        let _ = emit_notification(
+            &app_handle,
+            NotificationPayload::localized(
+                NotificationType::Info,
+                LocalizedMessage::new("notify-your-turn-title"),
+                LocalizedMessage::new("notify-your-turn").with_arg("turn", new_turn),
+            ),
+        );
+    }
+
+    let response = GameStateResponse {
+        game_id: game.id.clone(),
+        phase: format!("{:?}", game.phase),
+        turn: game.turn,
+        current_player: game.current_player,
+        player_count: game.players.len(),
+        map_width: game.settings.map_size.dimensions().0,
+        map_height: game.settings.map_size.dimensions().1,
+    };
+
+    // Time the finished turn and update the "next up" estimates
+    let session = state.session_mut(&game_id)?;
+    session.record_turn_end(previous_player);
+    let _ = emit_turn_schedule(
+        &app_handle,
+        TurnSchedulePayload::new(game_id.clone(), session.turn_schedule(), PlayerSlot(0)),
     );
+    record_timelapse(&app_handle, &mut state, &game_id);
+
+    Ok(response)
+}
+
+/// Progress of an AI seat's turn after one planning tick.
+#[derive(Clone, Debug, Serialize, JsonSchema, TS)]
+pub struct AiTickResponse {
+    /// The seat that was planned for.
+    pub player_id: PlayerSlot,
+    /// Number of actions taken this tick.
+    pub actions: usize,
+    /// Whether the seat finished planning and ended its turn.
+    pub turn_ended: bool,
+    /// Whether the seat ran out of thinking budget before finishing its plan.
+    pub out_of_budget: bool,
+}
+
+/// Let the AI think for one tick if the current player is an AI seat.
+///
+/// The frontend calls this repeatedly (e.g. once per frame) while an AI
+/// seat is playing. Each call stays within the preferences' per-tick
+/// budget; once the seat's plan is finished, or its per-turn budget is
+/// spent, it ends its turn. The AI thinks on the engine worker.
+#[tauri::command]
+pub async fn ai_tick(app_handle: AppHandle, game_id: GameId) -> Result<AiTickResponse, AppError> {
+    let budget = lock_state(&app_handle)?.preferences.ai_budget();
+    let response = engine_worker(&app_handle)?
+        .run_session(game_id.clone(), move |app_handle, session| {
+            ai_tick_on_worker(app_handle, session, &budget)
+        })
+        .await?;
+    if response.turn_ended {
+        record_timelapse(&app_handle, &mut *lock_state(&app_handle)?, &game_id);
+    }
+    Ok(response)
+}
+
+fn ai_tick_on_worker(
+    app_handle: &AppHandle,
+    session: &mut GameSession,
+    budget: &AiBudget,
+) -> Result<AiTickResponse, AppError> {
+    if !session.offline.allows_local_actions() {
+        return Err(AppError::NetworkError(
+            "Game is paused until reconnected".to_string(),
+        ));
+    }
+    let player_id = session.engine.state.current_player;
+    if session.engine.state.ai_persona(player_id).is_none() {
+        return Err(AppError::InvalidState(format!(
+            "Player {} is not an AI seat",
+            player_id
+        )));
+    }
+
+    let before = session.engine.state.clone();
+    let planner = session
+        .ai_planners
+        .entry(player_id)
+        .or_insert_with(|| AiPlanner::new(player_id));
+    let tick = planner.tick(&mut session.engine, budget)?;
+    if tick.finished {
+        session
+            .engine
+            .submit_action(player_id, &GameAction::EndTurn)?;
+        session.record_turn_end(player_id);
+    }
+    broadcast_committed(app_handle, &mut session.engine, &mut session.offline);
 
-    // Emit game state update
+    let game = &session.engine.state;
+    let diff = StateDiff::between(&before, game);
+    let _ = emit_game_state_updated(app_handle, GameStateUpdatedPayload::partial(game, &diff));
+
+    Ok(AiTickResponse {
+        player_id,
+        actions: tick.actions.len(),
+        turn_ended: tick.finished,
+        out_of_budget: tick.out_of_budget,
+    })
+}
+
+/// Get the turn order and the local player's expected wait.
+#[tauri::command]
+pub fn get_turn_schedule(
+    game_id: GameId,
+    state: State<'_, Mutex<AppState>>,
+) -> Result<TurnSchedulePayload, AppError> {
+    let state = state
+        .lock()
+        .map_err(|_| AppError::InvalidState("Lock poisoned".to_string()))?;
+
+    let schedule = state.session(&game_id)?.turn_schedule();
+    Ok(TurnSchedulePayload::new(game_id, schedule, PlayerSlot(0)))
+}
+
+/// Concede the game on behalf of the local player.
+///
+/// Their cities go to `to_player` (or the leading player), or are razed,
+/// depending on the game's concession policy.
+#[tauri::command]
+pub fn concede(
+    app_handle: AppHandle,
+    game_id: GameId,
+    to_player: Option<PlayerSlot>,
+    state: State<'_, Mutex<AppState>>,
+) -> Result<GameStateResponse, AppError> {
+    let mut state = state
+        .lock()
+        .map_err(|_| AppError::InvalidState("Lock poisoned".to_string()))?;
+
+    let (engine, offline) = state.get_engine_for_action(&game_id)?;
+
+    // Assuming player 0 is local
+    let result = engine.submit_action(PlayerSlot(0), &GameAction::Concede { to_player })?;
+    if !result.success {
+        return Err(AppError::InvalidState(
+            result.error.unwrap_or_else(|| "Cannot concede".to_string()),
+        ));
+    }
+    broadcast_committed(&app_handle, engine, offline);
+
+    let game = &engine.state;
     let _ = emit_game_state_updated(
         &app_handle,
         GameStateUpdatedPayload {
@@ -358,24 +710,529 @@ pub fn end_turn(
             current_player: game.current_player,
             player_count: game.players.len(),
             map_dimensions: game.settings.map_size.dimensions(),
-            is_full_update: false,
+            is_full_update: true,
             changed_units: None,
             changed_cities: None,
             changed_tiles: None,
+            changed_territory: None,
         },
     );
 
-    // Emit notification if it's now the local player's turn
-    if new_player == 0 {
+    Ok(GameStateResponse {
+        game_id: game.id.clone(),
+        phase: format!("{:?}", game.phase),
+        turn: game.turn,
+        current_player: game.current_player,
+        player_count: game.players.len(),
+        map_width: game.settings.map_size.dimensions().0,
+        map_height: game.settings.map_size.dimensions().1,
+    })
+}
+
+/// Hand an inactive player's seat to a new key.
+///
+/// Only the host can do this, and only once the player has been inactive
+/// for the game's `substitute_after_turns`.
+#[tauri::command]
+pub fn substitute_player(
+    app_handle: AppHandle,
+    game_id: GameId,
+    player_slot: u8,
+    new_pubkey: String,
+    new_name: Option<String>,
+    state: State<'_, Mutex<AppState>>,
+) -> Result<GameStateResponse, AppError> {
+    let mut state = state
+        .lock()
+        .map_err(|_| AppError::InvalidState("Lock poisoned".to_string()))?;
+
+    let (engine, offline) = state.get_engine_for_action(&game_id)?;
+
+    // Assuming player 0 is local
+    let action = GameAction::SubstitutePlayer {
+        player_slot: PlayerSlot(player_slot),
+        new_pubkey: Npub(new_pubkey),
+        new_name,
+    };
+    let result = engine.submit_action(PlayerSlot(0), &action)?;
+    if !result.success {
+        return Err(AppError::InvalidState(
+            result
+                .error
+                .unwrap_or_else(|| "Cannot substitute player".to_string()),
+        ));
+    }
+    broadcast_committed(&app_handle, engine, offline);
+
+    let game = &engine.state;
+    if let Some(player) = game.get_player(PlayerSlot(player_slot)) {
+        let _ = emit_notification(
+            &app_handle,
+            NotificationPayload::localized(
+                NotificationType::Info,
+                LocalizedMessage::new("notify-player-substituted-title"),
+                LocalizedMessage::new("notify-player-substituted")
+                    .with_arg("player", player_slot)
+                    .with_arg("name", player.name.clone()),
+            ),
+        );
+    }
+
+    Ok(GameStateResponse {
+        game_id: game.id.clone(),
+        phase: format!("{:?}", game.phase),
+        turn: game.turn,
+        current_player: game.current_player,
+        player_count: game.players.len(),
+        map_width: game.settings.map_size.dimensions().0,
+        map_height: game.settings.map_size.dimensions().1,
+    })
+}
+
+/// Reveal the full map and every player's stats after the game has ended.
+///
+/// Only the host can do this. Once the reveal is applied, visibility
+/// filtering stops redacting anything for every player.
+#[tauri::command]
+pub fn reveal_map(
+    app_handle: AppHandle,
+    game_id: GameId,
+    state: State<'_, Mutex<AppState>>,
+) -> Result<GameStateResponse, AppError> {
+    let mut state = state
+        .lock()
+        .map_err(|_| AppError::InvalidState("Lock poisoned".to_string()))?;
+
+    let (engine, offline) = state.get_engine_for_action(&game_id)?;
+
+    // Assuming player 0 is local
+    let result = engine.submit_action(PlayerSlot(0), &GameAction::RevealMap)?;
+    if !result.success {
+        return Err(AppError::InvalidState(
+            result
+                .error
+                .unwrap_or_else(|| "Cannot reveal map".to_string()),
+        ));
+    }
+    broadcast_committed(&app_handle, engine, offline);
+
+    let _ = emit_notification(
+        &app_handle,
+        NotificationPayload::localized(
+            NotificationType::Info,
+            LocalizedMessage::new("notify-map-revealed-title"),
+            LocalizedMessage::new("notify-map-revealed"),
+        ),
+    );
+
+    let game = &engine.state;
+    Ok(GameStateResponse {
+        game_id: game.id.clone(),
+        phase: format!("{:?}", game.phase),
+        turn: game.turn,
+        current_player: game.current_player,
+        player_count: game.players.len(),
+        map_width: game.settings.map_size.dimensions().0,
+        map_height: game.settings.map_size.dimensions().1,
+    })
+}
+
+/// Ask the other players to pause the game.
+#[tauri::command]
+pub fn request_pause(
+    app_handle: AppHandle,
+    game_id: GameId,
+    reason: Option<String>,
+    state: State<'_, Mutex<AppState>>,
+) -> Result<PauseState, AppError> {
+    submit_pause_action(
+        &app_handle,
+        &game_id,
+        GameAction::RequestPause { reason },
+        state,
+    )
+}
+
+/// Vote on another player's pause request.
+#[tauri::command]
+pub fn vote_pause(
+    app_handle: AppHandle,
+    game_id: GameId,
+    approve: bool,
+    state: State<'_, Mutex<AppState>>,
+) -> Result<PauseState, AppError> {
+    submit_pause_action(
+        &app_handle,
+        &game_id,
+        GameAction::VotePause { approve },
+        state,
+    )
+}
+
+/// Resume a paused game after a countdown.
+#[tauri::command]
+pub fn resume_game(
+    app_handle: AppHandle,
+    game_id: GameId,
+    countdown_secs: Option<u32>,
+    state: State<'_, Mutex<AppState>>,
+) -> Result<PauseState, AppError> {
+    let countdown_secs = countdown_secs.unwrap_or(DEFAULT_RESUME_COUNTDOWN_SECS);
+    submit_pause_action(
+        &app_handle,
+        &game_id,
+        GameAction::ResumeGame { countdown_secs },
+        state,
+    )
+}
+
+/// Get whether a game is running, voting on a pause, or paused.
+#[tauri::command]
+pub fn get_pause_state(
+    game_id: GameId,
+    state: State<'_, Mutex<AppState>>,
+) -> Result<PauseState, AppError> {
+    let state = state
+        .lock()
+        .map_err(|_| AppError::InvalidState("Lock poisoned".to_string()))?;
+
+    Ok(state.get_game_state(&game_id)?.pause.clone())
+}
+
+/// Get the local player's demographics report.
+///
+/// Only the local player's values are exact; other players are ranked
+/// without their values.
+#[tauri::command]
+pub fn get_demographics(
+    game_id: GameId,
+    state: State<'_, Mutex<AppState>>,
+) -> Result<Demographics, AppError> {
+    let state = state
+        .lock()
+        .map_err(|_| AppError::InvalidState("Lock poisoned".to_string()))?;
+
+    let game = state.get_game_state(&game_id)?;
+    let mut filter = VisibilityFilter::new(PlayerSlot(0));
+    filter.update_from_game_state(game);
+    Ok(filter.filter_demographics(game))
+}
+
+/// The local player's turn digest, with each item described.
+#[derive(Clone, Debug, Serialize, JsonSchema, TS)]
+pub struct TurnDigestResponse {
+    pub digest: TurnDigest,
+    /// Localized description of each digest item, in the same order.
+    pub messages: Vec<LocalizedMessage>,
+}
+
+/// Get what happened to the local player since they last ended their turn.
+///
+/// The digest is built from the game's events in the local relay, so
+/// events fetched from remote relays should be passed to
+/// `index_relay_events` first. If anything happened, a notification
+/// summarizing it is also sent.
+#[tauri::command]
+pub fn get_turn_digest(
+    app_handle: AppHandle,
+    game_id: GameId,
+    state: State<'_, Mutex<AppState>>,
+) -> Result<TurnDigestResponse, AppError> {
+    let state = state
+        .lock()
+        .map_err(|_| AppError::InvalidState("Lock poisoned".to_string()))?;
+
+    let game = state.get_game_state(&game_id)?;
+    let events = game_events(&state.local_relay()?, &game_id)?;
+    let digest = if events.is_empty() {
+        TurnDigest {
+            player_id: PlayerSlot(0),
+            since_turn: game.turn,
+            turn: game.turn,
+            items: Vec::new(),
+        }
+    } else {
+        TurnDigest::since_last_turn(&events, PlayerSlot(0))?
+    };
+
+    if !digest.is_empty() {
         let _ = emit_notification(
             &app_handle,
-            NotificationPayload::info(
-                "Your Turn",
-                format!("Turn {} has begun. It's your move!", new_turn),
+            NotificationPayload::localized(
+                NotificationType::Info,
+                LocalizedMessage::new("digest-title"),
+                LocalizedMessage::new("digest-summary")
+                    .with_arg("count", digest.items.len())
+                    .with_arg("turn", digest.since_turn),
+            ),
+        );
+    }
+    Ok(TurnDigestResponse {
+        messages: digest.messages(game),
+        digest,
+    })
+}
+
+/// What the current player should deal with before ending their turn.
+#[derive(Clone, Debug, Serialize, JsonSchema, TS)]
+pub struct PendingItemsResponse {
+    /// Pending items, in cycling order.
+    pub advisor: TurnAdvisor,
+    /// Description of each item, in the same order.
+    pub messages: Vec<LocalizedMessage>,
+    /// Whether `end_turn` would be refused with the current preferences.
+    pub end_turn_blocked: bool,
+}
+
+/// Get the current player's pending units, cities and research.
+#[tauri::command]
+pub fn get_pending_items(
+    game_id: GameId,
+    state: State<'_, Mutex<AppState>>,
+) -> Result<PendingItemsResponse, AppError> {
+    let state = state
+        .lock()
+        .map_err(|_| AppError::InvalidState("Lock poisoned".to_string()))?;
+
+    let game = state.get_game_state(&game_id)?;
+    let advisor = TurnAdvisor::new(game, game.current_player);
+    Ok(PendingItemsResponse {
+        messages: advisor.items.iter().map(PendingItem::message).collect(),
+        end_turn_blocked: state.preferences.block_end_turn_on_pending && !advisor.can_end_turn(),
+        advisor,
+    })
+}
+
+/// Ranked production choices for one of the current player's cities.
+#[derive(Clone, Debug, Serialize, JsonSchema, TS)]
+pub struct ProductionAdviceResponse {
+    /// What the recommendations were based on.
+    pub needs: CityNeeds,
+    /// Production options, best first.
+    pub recommendations: Vec<Recommendation>,
+    /// Reason for each recommendation, in the same order.
+    pub messages: Vec<LocalizedMessage>,
+}
+
+/// Get ranked production recommendations for a city, for advisor tooltips.
+///
+/// Only the current player's cities can be advised on; other cities are
+/// reported as not found.
+#[tauri::command]
+pub fn get_production_advice(
+    game_id: GameId,
+    city_id: CityId,
+    state: State<'_, Mutex<AppState>>,
+) -> Result<ProductionAdviceResponse, AppError> {
+    let state = state
+        .lock()
+        .map_err(|_| AppError::InvalidState("Lock poisoned".to_string()))?;
+
+    let game = state.get_game_state(&game_id)?;
+    let city = game
+        .cities
+        .get(&city_id)
+        .filter(|c| c.owner == game.current_player)
+        .ok_or(AppError::Engine(ReplayError::CityNotFound))?;
+    let recommendations = recommend_production(game, city_id);
+    Ok(ProductionAdviceResponse {
+        needs: CityNeeds::assess(game, city),
+        messages: recommendations
+            .iter()
+            .map(Recommendation::message)
+            .collect(),
+        recommendations,
+    })
+}
+
+/// Get the pending item after `current`, for the "next unit" hotkey.
+///
+/// Wraps around to the first item; returns `None` when nothing is pending.
+#[tauri::command]
+pub fn next_pending_item(
+    game_id: GameId,
+    current: Option<PendingItem>,
+    state: State<'_, Mutex<AppState>>,
+) -> Result<Option<PendingItem>, AppError> {
+    let state = state
+        .lock()
+        .map_err(|_| AppError::InvalidState("Lock poisoned".to_string()))?;
+
+    let game = state.get_game_state(&game_id)?;
+    let advisor = TurnAdvisor::new(game, game.current_player);
+    Ok(advisor.next_after(current.as_ref()).cloned())
+}
+
+/// Get entries from the game's event log, newest first.
+///
+/// Until the map is revealed at the end of the game, only entries
+/// involving the local player are returned, so the log doesn't show
+/// fights the player couldn't have seen.
+#[tauri::command]
+pub fn get_event_log(
+    game_id: GameId,
+    filter: Option<LogFilter>,
+    state: State<'_, Mutex<AppState>>,
+) -> Result<Vec<LogEntry>, AppError> {
+    let state = state
+        .lock()
+        .map_err(|_| AppError::InvalidState("Lock poisoned".to_string()))?;
+
+    let engine = &state.session(&game_id)?.engine;
+    let filter = filter.unwrap_or_default();
+    if engine.state.revealed {
+        return Ok(engine.log.query(&filter));
+    }
+    let limit = filter.limit.unwrap_or(usize::MAX);
+    Ok(engine
+        .log
+        .query(&LogFilter {
+            limit: None,
+            ..filter
+        })
+        .into_iter()
+        .filter(|entry| entry.involves(PlayerSlot(0)))
+        .take(limit)
+        .collect())
+}
+
+/// Apply a pause action for the local player, broadcast it, and notify the
+/// UI of the outcome.
+fn submit_pause_action(
+    app_handle: &AppHandle,
+    game_id: &GameId,
+    action: GameAction,
+    state: State<'_, Mutex<AppState>>,
+) -> Result<PauseState, AppError> {
+    let mut state = state
+        .lock()
+        .map_err(|_| AppError::InvalidState("Lock poisoned".to_string()))?;
+
+    // Pause actions go through even while offline play is paused
+    let session = state.session_mut(game_id)?;
+    let engine = &mut session.engine;
+
+    // Assuming player 0 is local
+    let result = engine.submit_action(PlayerSlot(0), &action)?;
+    if !result.success {
+        return Err(AppError::InvalidState(
+            result
+                .error
+                .unwrap_or_else(|| "Cannot change pause state".to_string()),
+        ));
+    }
+    broadcast_committed(app_handle, engine, &mut session.offline);
+
+    for effect in &result.effects {
+        let (key, message) = match effect {
+            ActionEffect::PauseRequested { player_id, .. } => (
+                "notify-pause-requested",
+                LocalizedMessage::new("notify-pause-requested").with_arg("player", *player_id),
+            ),
+            ActionEffect::GamePaused { requested_by } => (
+                "notify-game-paused",
+                LocalizedMessage::new("notify-game-paused").with_arg("player", *requested_by),
+            ),
+            ActionEffect::PauseRejected { requested_by } => (
+                "notify-pause-rejected",
+                LocalizedMessage::new("notify-pause-rejected").with_arg("player", *requested_by),
+            ),
+            ActionEffect::GameResumed { countdown_secs, .. } => (
+                "notify-game-resumed",
+                LocalizedMessage::new("notify-game-resumed").with_arg("seconds", *countdown_secs),
+            ),
+            _ => continue,
+        };
+        let _ = emit_notification(
+            app_handle,
+            NotificationPayload::localized(
+                NotificationType::Info,
+                LocalizedMessage::new(format!("{}-title", key)),
+                message,
             ),
         );
     }
 
+    Ok(engine.state.pause.clone())
+}
+
+/// Summary of a game in progress, for the game switcher.
+#[derive(Clone, Debug, Serialize, JsonSchema, TS)]
+pub struct ActiveGameInfo {
+    pub game_id: GameId,
+    pub name: String,
+    pub phase: String,
+    pub turn: u32,
+    pub current_player: PlayerSlot,
+    pub pending_actions: usize,
+    pub online: bool,
+    /// Whether this is the game currently shown in the UI.
+    pub is_active: bool,
+}
+
+/// List every game in progress, most recent turn first.
+#[tauri::command]
+pub fn list_active_games(
+    state: State<'_, Mutex<AppState>>,
+) -> Result<Vec<ActiveGameInfo>, AppError> {
+    let state = state
+        .lock()
+        .map_err(|_| AppError::InvalidState("Lock poisoned".to_string()))?;
+
+    let mut games: Vec<ActiveGameInfo> = state
+        .sessions
+        .iter()
+        .map(|(game_id, session)| {
+            let game = &session.engine.state;
+            ActiveGameInfo {
+                game_id: game_id.clone(),
+                name: game.settings.name.clone(),
+                phase: format!("{:?}", game.phase),
+                turn: game.turn,
+                current_player: game.current_player,
+                pending_actions: session.offline.pending_count(),
+                online: session.offline.is_online(),
+                is_active: state.active_game.as_ref() == Some(game_id),
+            }
+        })
+        .collect();
+    games.sort_by(|a, b| b.turn.cmp(&a.turn).then_with(|| a.game_id.cmp(&b.game_id)));
+
+    Ok(games)
+}
+
+/// Make a game the one shown in the UI.
+#[tauri::command]
+pub fn switch_game(
+    app_handle: AppHandle,
+    game_id: GameId,
+    state: State<'_, Mutex<AppState>>,
+) -> Result<GameStateResponse, AppError> {
+    let mut state = state
+        .lock()
+        .map_err(|_| AppError::InvalidState("Lock poisoned".to_string()))?;
+
+    state.switch_game(&game_id)?;
+    let game = state.get_game_state(&game_id)?;
+
+    // The frontend redraws everything for the newly shown game
+    let _ = emit_game_state_updated(
+        &app_handle,
+        GameStateUpdatedPayload {
+            game_id: game.id.clone(),
+            phase: format!("{:?}", game.phase),
+            turn: game.turn,
+            current_player: game.current_player,
+            player_count: game.players.len(),
+            map_dimensions: game.settings.map_size.dimensions(),
+            is_full_update: true,
+            changed_units: None,
+            changed_cities: None,
+            changed_tiles: None,
+            changed_territory: None,
+        },
+    );
+
     Ok(GameStateResponse {
         game_id: game.id.clone(),
         phase: format!("{:?}", game.phase),
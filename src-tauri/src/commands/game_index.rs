@@ -0,0 +1,115 @@
+//! "Continue game" browser commands.
+//!
+//! These commands list the games the active identity has played, from its
+//! local relay and from the remote relays it uses, and resume one from its
+//! newest snapshot.
+//!
+//! The frontend talks to remote relays itself: it subscribes with the
+//! filter from [`get_game_index_query`] and passes what each relay returns
+//! to [`index_relay_events`]. Those events are stored in the local relay,
+//! so a game found remotely can be resumed offline afterwards.
+
+use crate::commands::saves::LoadGameResponse;
+use crate::error::AppError;
+use crate::state::AppState;
+use crate::worker::{engine_worker, lock_state};
+use nostr_nations_core::{GameEngine, GameEvent, GameId};
+use nostr_nations_network::{remote_filter, resume_events, GameSummary};
+use schemars::JsonSchema;
+use serde::Serialize;
+use std::sync::Mutex;
+use tauri::{AppHandle, State};
+use ts_rs::TS;
+
+/// How to look for the active identity's games on remote relays.
+#[derive(Clone, Debug, Serialize, JsonSchema, TS)]
+pub struct GameIndexQuery {
+    /// Relays to query, in order of preference.
+    pub relays: Vec<String>,
+    /// NIP-01 filter to subscribe with, as JSON.
+    pub filter_json: String,
+}
+
+/// Get the relays and filter for finding the active identity's games.
+#[tauri::command]
+pub fn get_game_index_query(state: State<'_, Mutex<AppState>>) -> Result<GameIndexQuery, AppError> {
+    let state = state
+        .lock()
+        .map_err(|_| AppError::InvalidState("Lock poisoned".to_string()))?;
+
+    let npub = state
+        .profile
+        .npub
+        .as_deref()
+        .ok_or_else(|| AppError::InvalidState("Profile has no npub".to_string()))?;
+    let filter_json = serde_json::to_string(&remote_filter(npub))
+        .map_err(|e| AppError::SerializationError(e.to_string()))?;
+
+    Ok(GameIndexQuery {
+        relays: state.profile.preferred_relays.clone(),
+        filter_json,
+    })
+}
+
+/// List the active identity's games, most recently active first.
+///
+/// Rescans the local relay, so games played since the last call are
+/// included. Games found on remote relays stay listed until the identity
+/// changes.
+#[tauri::command]
+pub fn list_resumable_games(
+    state: State<'_, Mutex<AppState>>,
+) -> Result<Vec<GameSummary>, AppError> {
+    let mut state = state
+        .lock()
+        .map_err(|_| AppError::InvalidState("Lock poisoned".to_string()))?;
+
+    let relay = state.local_relay()?;
+    state.game_index.scan_relay(&relay)?;
+    Ok(state.game_index.games())
+}
+
+/// Add events a remote relay returned for the game index query.
+///
+/// The events are kept in the local relay. Returns the updated game list.
+#[tauri::command]
+pub fn index_relay_events(
+    relay_url: String,
+    events: Vec<GameEvent>,
+    state: State<'_, Mutex<AppState>>,
+) -> Result<Vec<GameSummary>, AppError> {
+    let mut state = state
+        .lock()
+        .map_err(|_| AppError::InvalidState("Lock poisoned".to_string()))?;
+
+    let relay = state.local_relay()?;
+    relay.publish_batch(&events)?;
+    for event in &events {
+        state.game_index.ingest(event, &relay_url);
+    }
+    Ok(state.game_index.games())
+}
+
+/// Resume a game from the local relay and make it active.
+///
+/// Replays from the game's newest snapshot. Replaying can take a while, so
+/// it runs on the engine worker without holding the application state.
+#[tauri::command]
+pub async fn resume_from_relay(
+    app_handle: AppHandle,
+    game_id: GameId,
+) -> Result<LoadGameResponse, AppError> {
+    engine_worker(&app_handle)?
+        .run(move |app_handle| {
+            let relay = lock_state(app_handle)?.local_relay()?;
+            let events = resume_events(&relay, &game_id)?;
+            if events.is_empty() {
+                return Err(AppError::GameNotFound(game_id.to_string()));
+            }
+
+            let engine = GameEngine::from_events(&events)?;
+            let game_id = lock_state(app_handle)?.add_session(engine)?;
+            Ok(LoadGameResponse { game_id })
+        })
+        .await
+}
@@ -0,0 +1,117 @@
+//! Localization commands.
+//!
+//! Game events carry message keys plus parameters; these commands give the
+//! frontend the catalogs it needs to render them in the player's language.
+
+use crate::error::AppError;
+use crate::state::AppState;
+use nostr_nations_core::{Catalog, GameId, LocalizedMessage};
+use schemars::JsonSchema;
+use serde::Serialize;
+use std::collections::BTreeMap;
+use std::sync::Mutex;
+use tauri::State;
+use ts_rs::TS;
+
+/// Messages for one locale, ready for the frontend.
+#[derive(Clone, Debug, Serialize, JsonSchema, TS)]
+#[serde(rename_all = "camelCase")]
+pub struct MessageCatalog {
+    /// Locale the messages belong to.
+    pub locale: String,
+    /// Message text by key.
+    pub messages: BTreeMap<String, String>,
+    /// English messages for keys the locale does not define.
+    pub fallback: BTreeMap<String, String>,
+}
+
+fn to_owned_map(catalog: &Catalog) -> BTreeMap<String, String> {
+    catalog
+        .messages()
+        .into_iter()
+        .map(|(k, v)| (k.to_string(), v.to_string()))
+        .collect()
+}
+
+/// Get the message catalog for a locale (defaults to the preferred locale).
+#[tauri::command]
+pub fn get_message_catalog(
+    locale: Option<String>,
+    state: State<'_, Mutex<AppState>>,
+) -> Result<MessageCatalog, AppError> {
+    let state = state
+        .lock()
+        .map_err(|_| AppError::InvalidState("Lock poisoned".to_string()))?;
+
+    let locale = locale.unwrap_or_else(|| state.preferences.locale.clone());
+    let language = locale.split(['-', '_']).next().unwrap_or(&locale);
+    let messages = state
+        .localizer
+        .catalog(&locale)
+        .or_else(|| state.localizer.catalog(language))
+        .map(to_owned_map)
+        .unwrap_or_default();
+
+    Ok(MessageCatalog {
+        locale,
+        messages,
+        fallback: to_owned_map(Catalog::english()),
+    })
+}
+
+/// Load a message catalog (Fluent-style `key = text` source) for a locale.
+///
+/// Returns the number of messages loaded.
+#[tauri::command]
+pub fn load_message_catalog(
+    locale: String,
+    source: String,
+    state: State<'_, Mutex<AppState>>,
+) -> Result<usize, AppError> {
+    let mut state = state
+        .lock()
+        .map_err(|_| AppError::InvalidState("Lock poisoned".to_string()))?;
+
+    let catalog =
+        Catalog::parse(locale, &source).map_err(|e| AppError::InvalidState(e.to_string()))?;
+    let count = catalog.len();
+    state.localizer.add_catalog(catalog);
+    Ok(count)
+}
+
+/// Set the preferred locale.
+#[tauri::command]
+pub fn set_locale(locale: String, state: State<'_, Mutex<AppState>>) -> Result<(), AppError> {
+    let mut state = state
+        .lock()
+        .map_err(|_| AppError::InvalidState("Lock poisoned".to_string()))?;
+
+    state.preferences.locale = locale;
+    crate::commands::settings::save_settings(&state)
+}
+
+/// Suggest a name for the current player's next city from their
+/// civilization's city name list.
+#[tauri::command]
+pub fn suggest_city_name(
+    game_id: GameId,
+    state: State<'_, Mutex<AppState>>,
+) -> Result<Option<LocalizedMessage>, AppError> {
+    let state = state
+        .lock()
+        .map_err(|_| AppError::InvalidState("Lock poisoned".to_string()))?;
+
+    let game = state.get_game_state(&game_id)?;
+    let player = game
+        .players
+        .get(game.current_player.index())
+        .ok_or_else(|| AppError::InvalidState("Current player not found".to_string()))?;
+    let used: Vec<String> = game
+        .cities
+        .values()
+        .filter(|c| c.owner == player.id)
+        .map(|c| c.name.clone())
+        .collect();
+
+    Ok(player.civilization.suggest_city_name(&used))
+}
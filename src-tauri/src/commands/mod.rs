@@ -4,6 +4,15 @@
 //! Each module groups related commands together.
 
 pub mod actions;
+pub mod capture;
+pub mod diplomacy;
 pub mod game;
+pub mod game_index;
+pub mod locale;
 pub mod network;
+pub mod pitboss;
+pub mod sandbox;
 pub mod saves;
+pub mod schema;
+pub mod settings;
+pub mod tournament;
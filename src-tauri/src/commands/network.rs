@@ -2,17 +2,28 @@
 //!
 //! These commands handle P2P networking: peer connections, QR codes, and sync.
 
+use crate::commands::sandbox::ensure_networked;
+use crate::error::AppError;
 use crate::events::{
-    emit_network_event, emit_notification, NetworkEventPayload, NotificationPayload,
+    emit_game_action, emit_network_event, emit_notification, emit_presence_changed, EventLog,
+    EventsSince, GameActionPayload, NetworkEventPayload, NotificationPayload, NotificationType,
+    PresenceChangedPayload,
 };
-use crate::state::{AppError, AppState};
-use nostr_nations_network::ConnectionTicket;
+use crate::state::{AppState, GameSession};
+use crate::worker::{engine_worker, lock_state};
+use nostr_nations_core::{GameEvent, GameId, LocalizedMessage, PlayerSlot};
+use nostr_nations_network::{
+    Capabilities, ConflictResolver, ConnectionTicket, NetworkDebugReport, PresenceEntry,
+    PresenceStatus, PresenceUpdate,
+};
+use schemars::JsonSchema;
 use serde::{Deserialize, Serialize};
-use std::sync::Mutex;
+use std::sync::{Arc, Mutex};
 use tauri::{AppHandle, State};
+use ts_rs::TS;
 
 /// Connection status response.
-#[derive(Clone, Debug, Serialize)]
+#[derive(Clone, Debug, Serialize, JsonSchema, TS)]
 pub struct ConnectionStatus {
     pub connected: bool,
     pub peer_count: usize,
@@ -20,47 +31,101 @@ pub struct ConnectionStatus {
 }
 
 /// Ticket info for serialization.
-#[derive(Clone, Debug, Serialize, Deserialize)]
+#[derive(Clone, Debug, Serialize, Deserialize, JsonSchema, TS)]
 pub struct TicketInfo {
     pub node_id: String,
     pub addresses: Vec<String>,
-    pub game_id: Option<String>,
+    pub game_id: Option<GameId>,
     pub expires_at: u64,
 }
 
+/// Offline queue status.
+#[derive(Clone, Debug, Serialize, JsonSchema, TS)]
+pub struct OfflineStatus {
+    pub online: bool,
+    pub pending_actions: usize,
+    pub can_act: bool,
+}
+
+/// Result of sending queued actions after reconnecting.
+#[derive(Clone, Debug, Serialize, JsonSchema, TS)]
+pub struct ReconnectSummary {
+    pub sent: usize,
+    pub rejected: Vec<String>,
+    pub needs_resync: bool,
+}
+
 /// Connect to a peer using a connection ticket.
+///
+/// Connecting to a game this client has no session for joins it as a
+/// guest. The peer manager is driven on the engine worker, so the app state
+/// stays unlocked while it works.
 #[tauri::command]
-pub fn connect_peer(
+pub async fn connect_peer(
     app_handle: AppHandle,
+    game_id: GameId,
     ticket: String,
-    state: State<'_, Mutex<AppState>>,
 ) -> Result<ConnectionStatus, AppError> {
-    let mut state = state
-        .lock()
-        .map_err(|_| AppError::InvalidState("Lock poisoned".to_string()))?;
+    ensure_networked(&game_id)?;
+    let (capabilities, peer_count) = match lock_state(&app_handle)?.session(&game_id) {
+        Ok(session) => (session.network.capabilities().clone(), session.peer_count),
+        // A guest advertises no ruleset until the host's state arrives
+        Err(_) => (Capabilities::default(), 0),
+    };
 
     // Parse the ticket (using the existing string format)
     let connection_ticket = ConnectionTicket::from_string(&ticket).map_err(|e| {
         // Emit connection error event
         let _ = emit_network_event(
             &app_handle,
-            NetworkEventPayload::connection_error(
-                format!("Invalid ticket: {}", e),
-                state.connected_peers(),
-            ),
+            NetworkEventPayload::connection_error(format!("Invalid ticket: {}", e), peer_count),
         );
         AppError::NetworkError(format!("Invalid ticket: {}", e))
     })?;
 
-    // Add peer (simplified for now)
-    state.add_peer();
-    let peer_count = state.connected_peers();
+    // Refuse hosts we can't play with before connecting
+    if let Err(e) = connection_ticket.negotiate(&game_id, &capabilities) {
+        let _ = emit_network_event(
+            &app_handle,
+            NetworkEventPayload::handshake_failed(
+                connection_ticket.node_id.to_string(),
+                e.to_string(),
+                peer_count,
+            ),
+        );
+        let _ = emit_notification(
+            &app_handle,
+            NotificationPayload::localized(
+                NotificationType::Error,
+                LocalizedMessage::new("notify-peer-incompatible-title"),
+                LocalizedMessage::new("notify-peer-incompatible").with_arg("reason", e.to_string()),
+            ),
+        );
+        return Err(AppError::NetworkError(e.to_string()));
+    }
+
+    lock_state(&app_handle)?.join_session(&game_id);
+    engine_worker(&app_handle)?
+        .run_session(game_id, move |app_handle, session| {
+            connect_peer_on_worker(app_handle, session, connection_ticket, ticket)
+        })
+        .await
+}
+
+fn connect_peer_on_worker(
+    app_handle: &AppHandle,
+    session: &mut GameSession,
+    connection_ticket: ConnectionTicket,
+    ticket: String,
+) -> Result<ConnectionStatus, AppError> {
+    session.add_peer(connection_ticket.node_id.clone());
+    let peer_count = session.peer_count;
 
     // Emit peer connected event
     let _ = emit_network_event(
-        &app_handle,
+        app_handle,
         NetworkEventPayload::peer_connected(
-            connection_ticket.node_id.clone(),
+            connection_ticket.node_id.to_string(),
             None, // Peer name not known yet
             peer_count,
         ),
@@ -68,10 +133,11 @@ pub fn connect_peer(
 
     // Emit notification
     let _ = emit_notification(
-        &app_handle,
-        NotificationPayload::success(
-            "Peer Connected",
-            format!("Successfully connected. {} peer(s) online.", peer_count),
+        app_handle,
+        NotificationPayload::localized(
+            NotificationType::Success,
+            LocalizedMessage::new("notify-peer-connected-title"),
+            LocalizedMessage::new("notify-peer-connected").with_arg("count", peer_count),
         ),
     );
 
@@ -83,28 +149,38 @@ pub fn connect_peer(
 }
 
 /// Disconnect from a peer.
+///
+/// Runs on the engine worker, like [`connect_peer`].
 #[tauri::command]
-pub fn disconnect_peer(
+pub async fn disconnect_peer(
     app_handle: AppHandle,
+    game_id: GameId,
     peer_id: String,
-    state: State<'_, Mutex<AppState>>,
 ) -> Result<ConnectionStatus, AppError> {
-    let mut state = state
-        .lock()
-        .map_err(|_| AppError::InvalidState("Lock poisoned".to_string()))?;
+    engine_worker(&app_handle)?
+        .run_session(game_id, move |app_handle, session| {
+            disconnect_peer_on_worker(app_handle, session, peer_id)
+        })
+        .await
+}
 
-    if state.connected_peers() == 0 {
-        return Err(AppError::NetworkError(
-            "Not connected to any peers".to_string(),
-        ));
+fn disconnect_peer_on_worker(
+    app_handle: &AppHandle,
+    session: &mut GameSession,
+    peer_id: String,
+) -> Result<ConnectionStatus, AppError> {
+    if !session.remove_peer(&peer_id) {
+        return Err(AppError::NetworkError(format!(
+            "Not connected to peer {}",
+            peer_id
+        )));
     }
 
-    state.remove_peer();
-    let peer_count = state.connected_peers();
+    let peer_count = session.peer_count;
 
     // Emit peer disconnected event
     let _ = emit_network_event(
-        &app_handle,
+        app_handle,
         NetworkEventPayload::peer_disconnected(
             peer_id, None, // Peer name
             peer_count,
@@ -113,10 +189,11 @@ pub fn disconnect_peer(
 
     // Emit notification
     let _ = emit_notification(
-        &app_handle,
-        NotificationPayload::info(
-            "Peer Disconnected",
-            format!("{} peer(s) remaining.", peer_count),
+        app_handle,
+        NotificationPayload::localized(
+            NotificationType::Info,
+            LocalizedMessage::new("notify-peer-disconnected-title"),
+            LocalizedMessage::new("notify-peer-disconnected").with_arg("count", peer_count),
         ),
     );
 
@@ -129,23 +206,19 @@ pub fn disconnect_peer(
 
 /// Get a connection ticket for others to connect to this client.
 #[tauri::command]
-pub fn get_connection_ticket(state: State<'_, Mutex<AppState>>) -> Result<String, AppError> {
+pub fn get_connection_ticket(
+    game_id: GameId,
+    state: State<'_, Mutex<AppState>>,
+) -> Result<String, AppError> {
     let state = state
         .lock()
         .map_err(|_| AppError::InvalidState("Lock poisoned".to_string()))?;
+    ensure_networked(&game_id)?;
 
     // Generate a connection ticket
     // In a real implementation, this would include the Iroh endpoint info
-    let game_id = state
-        .game_engine
-        .as_ref()
-        .map(|e| e.state.id.clone())
-        .unwrap_or_else(|| "no_game".to_string());
-
-    let ticket = ConnectionTicket::new(
-        "local_node_id".to_string(),
+    let ticket = state.session(&game_id)?.network.create_ticket(
         vec!["127.0.0.1:9000".to_string()],
-        game_id,
         3600, // 1 hour TTL
     );
 
@@ -164,9 +237,221 @@ pub fn scan_qr_code(qr_data: String) -> Result<TicketInfo, AppError> {
         .map_err(|e| AppError::NetworkError(format!("Invalid QR code: {}", e)))?;
 
     Ok(TicketInfo {
-        node_id: ticket.node_id,
+        node_id: ticket.node_id.to_string(),
         addresses: ticket.addresses,
         game_id: Some(ticket.game_id),
         expires_at: ticket.expires_at,
     })
 }
+
+/// Mark the client as disconnected so new actions are queued locally.
+#[tauri::command]
+pub fn go_offline(
+    game_id: GameId,
+    state: State<'_, Mutex<AppState>>,
+) -> Result<OfflineStatus, AppError> {
+    let mut state = state
+        .lock()
+        .map_err(|_| AppError::InvalidState("Lock poisoned".to_string()))?;
+
+    let offline = &mut state.session_mut(&game_id)?.offline;
+    offline.go_offline();
+
+    Ok(OfflineStatus {
+        online: false,
+        pending_actions: offline.pending_count(),
+        can_act: offline.allows_local_actions(),
+    })
+}
+
+/// Get the offline queue status.
+#[tauri::command]
+pub fn get_offline_status(
+    game_id: GameId,
+    state: State<'_, Mutex<AppState>>,
+) -> Result<OfflineStatus, AppError> {
+    let state = state
+        .lock()
+        .map_err(|_| AppError::InvalidState("Lock poisoned".to_string()))?;
+
+    let offline = &state.session(&game_id)?.offline;
+    Ok(OfflineStatus {
+        online: offline.is_online(),
+        pending_actions: offline.pending_count(),
+        can_act: offline.allows_local_actions(),
+    })
+}
+
+/// Set the local player's presence.
+///
+/// Returns the ephemeral presence event for the frontend to sign and
+/// publish. Call this periodically, and whenever the status or typing
+/// indicator changes.
+#[tauri::command]
+pub fn set_presence(
+    app_handle: AppHandle,
+    game_id: GameId,
+    status: PresenceStatus,
+    typing: bool,
+    state: State<'_, Mutex<AppState>>,
+) -> Result<GameEvent, AppError> {
+    let mut state = state
+        .lock()
+        .map_err(|_| AppError::InvalidState("Lock poisoned".to_string()))?;
+
+    let session = state.session_mut(&game_id)?;
+    let update = PresenceUpdate::new(status, typing);
+    if let Some(change) = session.presence.update(PlayerSlot(0), &update, now_ms()) {
+        let _ = emit_presence_changed(
+            &app_handle,
+            PresenceChangedPayload::new(game_id.clone(), change),
+        );
+    }
+
+    Ok(update.to_event(game_id, PlayerSlot(0), session.engine.state.turn))
+}
+
+/// Apply a presence event received from another player.
+///
+/// Also marks players who stopped announcing as disconnected, and returns
+/// the presence of every known player.
+#[tauri::command]
+pub fn receive_presence(
+    app_handle: AppHandle,
+    game_id: GameId,
+    event: GameEvent,
+    state: State<'_, Mutex<AppState>>,
+) -> Result<Vec<PresenceEntry>, AppError> {
+    let update = PresenceUpdate::from_event(&event)
+        .ok_or_else(|| AppError::SerializationError("Not a presence event".to_string()))?;
+
+    let mut state = state
+        .lock()
+        .map_err(|_| AppError::InvalidState("Lock poisoned".to_string()))?;
+
+    let presence = &mut state.session_mut(&game_id)?.presence;
+    let now = now_ms();
+    let changes = presence
+        .update(event.player_id, &update, now)
+        .into_iter()
+        .chain(presence.expire(now));
+    for change in changes {
+        let _ = emit_presence_changed(
+            &app_handle,
+            PresenceChangedPayload::new(game_id.clone(), change),
+        );
+    }
+
+    Ok(presence.iter().cloned().collect())
+}
+
+/// Get the presence of every known player.
+#[tauri::command]
+pub fn get_presence(
+    game_id: GameId,
+    state: State<'_, Mutex<AppState>>,
+) -> Result<Vec<PresenceEntry>, AppError> {
+    let state = state
+        .lock()
+        .map_err(|_| AppError::InvalidState("Lock poisoned".to_string()))?;
+
+    Ok(state.session(&game_id)?.presence.iter().cloned().collect())
+}
+
+/// Reconnect and send actions queued while offline.
+///
+/// Queued actions are conflict-checked against `remote_events` (events
+/// published by others while we were away) and the survivors are emitted
+/// in order to be signed and broadcast.
+#[tauri::command]
+pub fn reconnect(
+    app_handle: AppHandle,
+    game_id: GameId,
+    remote_events: Vec<GameEvent>,
+    state: State<'_, Mutex<AppState>>,
+) -> Result<ReconnectSummary, AppError> {
+    let mut state = state
+        .lock()
+        .map_err(|_| AppError::InvalidState("Lock poisoned".to_string()))?;
+    ensure_networked(&game_id)?;
+
+    let session = state.session_mut(&game_id)?;
+    let outcome = session
+        .offline
+        .reconcile(&remote_events, &ConflictResolver::default());
+    let turn = session.engine.state.turn;
+    session.offline.record_sync(turn);
+
+    let sent = outcome.accepted.len();
+    for event in outcome.accepted {
+        let description = event.action.description();
+        let _ = emit_game_action(&app_handle, GameActionPayload { event, description });
+    }
+
+    let rejected: Vec<String> = outcome
+        .rejected
+        .iter()
+        .map(|(event, _)| event.action.description())
+        .collect();
+    if !rejected.is_empty() {
+        let _ = emit_notification(
+            &app_handle,
+            NotificationPayload::localized(
+                NotificationType::Warning,
+                LocalizedMessage::new("notify-offline-discarded-title"),
+                LocalizedMessage::new("notify-offline-discarded").with_arg("count", rejected.len()),
+            ),
+        );
+    }
+
+    Ok(ReconnectSummary {
+        sent,
+        rejected,
+        needs_resync: outcome.needs_resync,
+    })
+}
+
+/// Get the events emitted after sequence number `seq`, so the frontend can
+/// catch up after a webview reload.
+///
+/// If the result isn't `complete`, events were missed that are no longer
+/// kept and the frontend should reload the full game state instead.
+#[tauri::command]
+pub fn get_events_since(seq: u64, log: State<'_, Arc<EventLog>>) -> Result<EventsSince, AppError> {
+    Ok(log.since(seq))
+}
+
+/// Get recent network spans and metrics for the debug overlay (F9).
+#[tauri::command]
+pub fn get_network_debug_report(
+    state: State<'_, Mutex<AppState>>,
+) -> Result<NetworkDebugReport, AppError> {
+    let state = state
+        .lock()
+        .map_err(|_| AppError::InvalidState("Lock poisoned".to_string()))?;
+
+    let pending: usize = state
+        .sessions
+        .values()
+        .map(|s| s.offline.pending_count())
+        .sum();
+    let online = state.sessions.values().all(|s| s.offline.is_online());
+    state
+        .debug
+        .set_metric("peers", state.connected_peers() as f64);
+    state.debug.set_metric("games", state.sessions.len() as f64);
+    state.debug.set_metric("offline_pending", pending as f64);
+    state
+        .debug
+        .set_metric("online", if online { 1.0 } else { 0.0 });
+
+    Ok(state.debug.report())
+}
+
+/// Current Unix time in milliseconds.
+fn now_ms() -> u64 {
+    std::time::SystemTime::now()
+        .duration_since(std::time::UNIX_EPOCH)
+        .unwrap_or_default()
+        .as_millis() as u64
+}
@@ -0,0 +1,151 @@
+//! Pitboss (play-by-relay) commands.
+//!
+//! These commands support asynchronous games: queuing turns played while
+//! offline, background mode, and surfacing "your turn" notifications that
+//! arrive from the host relay.
+
+use crate::error::AppError;
+use crate::events::{
+    emit_notification, emit_turn_event, NotificationPayload, NotificationType, TurnEventPayload,
+};
+use crate::state::AppState;
+use nostr_nations_core::{GameId, LocalizedMessage};
+use nostr_nations_network::{QueuedTurn, TurnNotification};
+use schemars::JsonSchema;
+use serde::Serialize;
+use std::sync::Mutex;
+use tauri::{AppHandle, Manager, State, UserAttentionType};
+use ts_rs::TS;
+
+/// Pitboss status for the frontend.
+#[derive(Clone, Debug, Serialize, JsonSchema, TS)]
+pub struct PitbossStatus {
+    pub background_mode: bool,
+    /// Queued turns across all games.
+    pub queued_turns: usize,
+}
+
+impl PitbossStatus {
+    fn from_state(state: &AppState) -> Self {
+        Self {
+            background_mode: state.background_mode,
+            queued_turns: state
+                .sessions
+                .values()
+                .map(|session| session.offline_turns.len())
+                .sum(),
+        }
+    }
+}
+
+/// Enable or disable background mode.
+///
+/// In background mode, closing the window hides it instead of exiting so
+/// turn notifications can still be delivered.
+#[tauri::command]
+pub fn set_background_mode(
+    enabled: bool,
+    state: State<'_, Mutex<AppState>>,
+) -> Result<PitbossStatus, AppError> {
+    let mut state = state
+        .lock()
+        .map_err(|_| AppError::InvalidState("Lock poisoned".to_string()))?;
+
+    state.background_mode = enabled;
+    Ok(PitbossStatus::from_state(&state))
+}
+
+/// Get the current pitboss status.
+#[tauri::command]
+pub fn get_pitboss_status(state: State<'_, Mutex<AppState>>) -> Result<PitbossStatus, AppError> {
+    let state = state
+        .lock()
+        .map_err(|_| AppError::InvalidState("Lock poisoned".to_string()))?;
+
+    Ok(PitbossStatus::from_state(&state))
+}
+
+/// Queue a turn played while disconnected from its game's host.
+#[tauri::command]
+pub fn queue_offline_turn(
+    turn: QueuedTurn,
+    state: State<'_, Mutex<AppState>>,
+) -> Result<PitbossStatus, AppError> {
+    let mut state = state
+        .lock()
+        .map_err(|_| AppError::InvalidState("Lock poisoned".to_string()))?;
+
+    state.session_mut(&turn.game_id)?.offline_turns.queue(turn);
+    Ok(PitbossStatus::from_state(&state))
+}
+
+/// Take a game's queued turns so the frontend can submit them to its host.
+#[tauri::command]
+pub fn take_offline_turns(
+    game_id: GameId,
+    state: State<'_, Mutex<AppState>>,
+) -> Result<Vec<QueuedTurn>, AppError> {
+    let mut state = state
+        .lock()
+        .map_err(|_| AppError::InvalidState("Lock poisoned".to_string()))?;
+
+    let mut turns = Vec::new();
+    state
+        .session_mut(&game_id)?
+        .offline_turns
+        .flush(|turn| {
+            turns.push(turn.clone());
+            Ok(())
+        })
+        .map_err(|e| AppError::InvalidState(e.to_string()))?;
+    Ok(turns)
+}
+
+/// Handle a decrypted turn notification received from the host relay.
+#[tauri::command]
+pub fn receive_turn_notification(
+    app_handle: AppHandle,
+    notification: TurnNotification,
+    state: State<'_, Mutex<AppState>>,
+) -> Result<(), AppError> {
+    let background_mode = state
+        .lock()
+        .map_err(|_| AppError::InvalidState("Lock poisoned".to_string()))?
+        .background_mode;
+
+    let _ = emit_turn_event(
+        &app_handle,
+        TurnEventPayload::player_turn(
+            notification.turn,
+            notification.player_id,
+            format!("Player {}", notification.player_id),
+            true,
+        ),
+    );
+    let _ = emit_notification(
+        &app_handle,
+        NotificationPayload::localized(
+            NotificationType::Info,
+            LocalizedMessage::new("notify-your-turn-title"),
+            LocalizedMessage::new(if notification.is_reminder {
+                "notify-pitboss-reminder"
+            } else {
+                "notify-pitboss-turn"
+            })
+            .with_arg("turn", notification.turn)
+            .with_arg("game", &notification.game_id),
+        ),
+    );
+
+    // Ask for attention if the window was hidden by background mode
+    if background_mode {
+        if let Some(window) = app_handle.get_webview_window("main") {
+            if !window.is_visible().unwrap_or(true) {
+                let _ = window.show();
+            }
+            let _ = window.request_user_attention(Some(UserAttentionType::Informational));
+        }
+    }
+
+    Ok(())
+}
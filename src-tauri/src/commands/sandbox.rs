@@ -0,0 +1,81 @@
+//! Sandbox commands for debugging and "what-if" analysis.
+//!
+//! A sandbox is a local copy of a game forked at an earlier turn. It gets
+//! its own game ID and plays like any other session, but its actions are
+//! never broadcast and it can't be connected to peers, so nothing done in
+//! it reaches the live chain.
+
+use crate::error::AppError;
+use crate::state::AppState;
+use nostr_nations_core::{fork_at, is_sandbox, sandbox_id, GameId};
+use nostr_nations_network::game_events;
+use schemars::JsonSchema;
+use serde::Serialize;
+use std::sync::Mutex;
+use tauri::State;
+use ts_rs::TS;
+
+/// Response for forking a game into a sandbox.
+#[derive(Clone, Debug, Serialize, JsonSchema, TS)]
+pub struct ForkGameResponse {
+    /// ID of the new sandbox game.
+    pub game_id: GameId,
+    /// Game the sandbox was forked from.
+    pub source_game_id: GameId,
+    /// Turn the sandbox starts at.
+    pub turn: u32,
+}
+
+/// Fork a game at the start of an earlier turn into a local sandbox, and
+/// make the sandbox active.
+///
+/// The fork is replayed from the game's events in the local relay. The
+/// live game keeps running untouched.
+#[tauri::command]
+pub fn fork_game(
+    game_id: GameId,
+    turn: u32,
+    state: State<'_, Mutex<AppState>>,
+) -> Result<ForkGameResponse, AppError> {
+    let mut state = state
+        .lock()
+        .map_err(|_| AppError::InvalidState("Lock poisoned".to_string()))?;
+
+    let current = state.get_game_state(&game_id)?.turn;
+    if turn == 0 || turn > current {
+        return Err(AppError::InvalidState(format!(
+            "Can only fork at turns 1 to {}",
+            current
+        )));
+    }
+
+    let events = game_events(&state.local_relay()?, &game_id)?;
+    if events.is_empty() {
+        return Err(AppError::GameNotFound(game_id.to_string()));
+    }
+
+    // Number repeat forks of the same turn
+    let mut n = 0;
+    while state.sessions.contains_key(&sandbox_id(&game_id, turn, n)) {
+        n += 1;
+    }
+    let engine = fork_at(&events, turn, sandbox_id(&game_id, turn, n))?;
+    let turn = engine.state.turn;
+    let fork_id = state.add_session(engine)?;
+
+    Ok(ForkGameResponse {
+        game_id: fork_id,
+        source_game_id: game_id,
+        turn,
+    })
+}
+
+/// Refuse network operations on sandbox games.
+pub(crate) fn ensure_networked(game_id: &GameId) -> Result<(), AppError> {
+    if is_sandbox(game_id) {
+        return Err(AppError::NetworkError(
+            "Sandbox games are local only".to_string(),
+        ));
+    }
+    Ok(())
+}
@@ -1,17 +1,28 @@
 //! Save game management commands.
 //!
 //! These commands handle saving, loading, and managing saved games.
-
-use crate::state::{AppError, AppState};
-use nostr_nations_core::GameEngine;
+//!
+//! Saves are encrypted at rest once the player sets a storage passphrase
+//! with [`unlock_storage`]. Saves written before that are still plaintext;
+//! they load as before and are encrypted when the passphrase is first set.
+//!
+//! Saves and the storage passphrase belong to the active identity: each
+//! npub has its own saves directory and key file.
+
+use crate::error::AppError;
+use crate::state::AppState;
+use nostr_nations_core::{GameEngine, GameId};
+use nostr_nations_network::at_rest::{self, AtRestKey, KdfParams, PassphraseKeyFile};
+use schemars::JsonSchema;
 use serde::{Deserialize, Serialize};
 use std::fs;
-use std::path::PathBuf;
+use std::path::{Path, PathBuf};
 use std::sync::Mutex;
-use tauri::{AppHandle, Manager, State};
+use tauri::State;
+use ts_rs::TS;
 
 /// Information about a saved game (metadata stored separately from full state).
-#[derive(Clone, Debug, Serialize, Deserialize)]
+#[derive(Clone, Debug, Serialize, Deserialize, JsonSchema, TS)]
 #[serde(rename_all = "camelCase")]
 pub struct SavedGame {
     pub id: String,
@@ -30,14 +41,12 @@ struct SaveData {
     pub seed: [u8; 32],
 }
 
-/// Get the saves directory path.
-fn get_saves_dir(app_handle: &AppHandle) -> Result<PathBuf, AppError> {
-    let app_data_dir = app_handle
-        .path()
-        .app_data_dir()
-        .map_err(|e| AppError::InvalidState(format!("Failed to get app data dir: {}", e)))?;
-
-    let saves_dir = app_data_dir.join("saves");
+/// Get the saves directory path of the active identity.
+fn get_saves_dir(state: &AppState) -> Result<PathBuf, AppError> {
+    let saves_dir = state
+        .profile_storage()?
+        .ok_or_else(|| AppError::StorageError("Storage not initialized".to_string()))?
+        .saves_dir();
 
     // Create saves directory if it doesn't exist
     if !saves_dir.exists() {
@@ -49,15 +58,42 @@ fn get_saves_dir(app_handle: &AppHandle) -> Result<PathBuf, AppError> {
 }
 
 /// Get the path for a specific save file.
-fn get_save_path(app_handle: &AppHandle, save_id: &str) -> Result<PathBuf, AppError> {
-    let saves_dir = get_saves_dir(app_handle)?;
+fn get_save_path(state: &AppState, save_id: &str) -> Result<PathBuf, AppError> {
+    let saves_dir = get_saves_dir(state)?;
     Ok(saves_dir.join(format!("{}.json", save_id)))
 }
 
+/// Read a save file, decrypting it if it is encrypted.
+fn read_save(path: &Path, key: Option<&AtRestKey>) -> Result<SaveData, AppError> {
+    let content = fs::read(path)
+        .map_err(|e| AppError::InvalidState(format!("Failed to read save file: {}", e)))?;
+    let content = at_rest::open(key, content)
+        .map_err(|e| AppError::StorageError(format!("Failed to decrypt save file: {}", e)))?;
+    serde_json::from_slice(&content)
+        .map_err(|e| AppError::InvalidState(format!("Failed to parse save file: {}", e)))
+}
+
+/// Write a save file, encrypted if there is a key.
+fn write_save(path: &Path, save_data: &SaveData, key: Option<&AtRestKey>) -> Result<(), AppError> {
+    let content = serde_json::to_vec_pretty(save_data)
+        .map_err(|e| AppError::SerializationError(format!("Failed to serialize save: {}", e)))?;
+    let content = at_rest::seal(key, content)
+        .map_err(|e| AppError::StorageError(format!("Failed to encrypt save file: {}", e)))?;
+    fs::write(path, content)
+        .map_err(|e| AppError::InvalidState(format!("Failed to write save file: {}", e)))
+}
+
 /// List all saved games.
+///
+/// Encrypted saves are left out until the storage is unlocked.
 #[tauri::command]
-pub fn list_saved_games(app_handle: AppHandle) -> Result<Vec<SavedGame>, AppError> {
-    let saves_dir = get_saves_dir(&app_handle)?;
+pub fn list_saved_games(state: State<'_, Mutex<AppState>>) -> Result<Vec<SavedGame>, AppError> {
+    let (key, saves_dir) = {
+        let app_state = state
+            .lock()
+            .map_err(|_| AppError::InvalidState("Lock poisoned".to_string()))?;
+        (app_state.storage_key.clone(), get_saves_dir(&app_state)?)
+    };
 
     let mut saves = Vec::new();
 
@@ -65,10 +101,8 @@ pub fn list_saved_games(app_handle: AppHandle) -> Result<Vec<SavedGame>, AppErro
         for entry in entries.flatten() {
             let path = entry.path();
             if path.extension().map_or(false, |ext| ext == "json") {
-                if let Ok(content) = fs::read_to_string(&path) {
-                    if let Ok(save_data) = serde_json::from_str::<SaveData>(&content) {
-                        saves.push(save_data.metadata);
-                    }
+                if let Ok(save_data) = read_save(&path, key.as_ref()) {
+                    saves.push(save_data.metadata);
                 }
             }
         }
@@ -81,63 +115,55 @@ pub fn list_saved_games(app_handle: AppHandle) -> Result<Vec<SavedGame>, AppErro
 }
 
 /// Response for loading a game.
-#[derive(Clone, Debug, Serialize)]
+#[derive(Clone, Debug, Serialize, JsonSchema, TS)]
 pub struct LoadGameResponse {
-    pub game_id: String,
+    pub game_id: GameId,
 }
 
 /// Load a saved game.
 #[tauri::command]
 pub fn load_game(
     save_id: String,
-    app_handle: AppHandle,
     state: State<'_, Mutex<AppState>>,
 ) -> Result<LoadGameResponse, AppError> {
     let mut app_state = state
         .lock()
         .map_err(|_| AppError::InvalidState("Lock poisoned".to_string()))?;
 
-    // Check if there's already an active game
-    if app_state.has_active_game() {
-        return Err(AppError::GameAlreadyActive);
-    }
-
     // Read save file
-    let save_path = get_save_path(&app_handle, &save_id)?;
-    let content = fs::read_to_string(&save_path)
-        .map_err(|e| AppError::InvalidState(format!("Failed to read save file: {}", e)))?;
+    let save_path = get_save_path(&app_state, &save_id)?;
+    let save_data = read_save(&save_path, app_state.storage_key.as_ref())?;
 
-    let save_data: SaveData = serde_json::from_str(&content)
-        .map_err(|e| AppError::InvalidState(format!("Failed to parse save file: {}", e)))?;
-
-    // Reconstruct game engine from saved state
+    // Reconstruct game engine from saved state; later saves go back to
+    // the same slot
     let engine = GameEngine::from_state(save_data.game_state, save_data.seed);
-    let game_id = engine.state.id.clone();
-
-    app_state.game_engine = Some(engine);
+    let game_id = app_state.add_session(engine)?;
+    app_state.session_mut(&game_id)?.save_slot = save_id;
 
     Ok(LoadGameResponse { game_id })
 }
 
-/// Save the current game.
+/// Save a game to its save slot.
 #[tauri::command]
 pub fn save_game(
+    game_id: GameId,
     name: String,
-    app_handle: AppHandle,
     state: State<'_, Mutex<AppState>>,
 ) -> Result<SavedGame, AppError> {
     let app_state = state
         .lock()
         .map_err(|_| AppError::InvalidState("Lock poisoned".to_string()))?;
 
-    // Check if there's an active game to save
-    let engine = app_state
-        .game_engine
-        .as_ref()
-        .ok_or(AppError::NoActiveGame)?;
+    // Don't write plaintext once the player has chosen encryption
+    if app_state.storage_encryption_enabled() && app_state.storage_key.is_none() {
+        return Err(AppError::StorageError(
+            "Storage is locked; enter the storage passphrase first".to_string(),
+        ));
+    }
 
-    let game = &engine.state;
-    let save_id = format!("save-{}", uuid::Uuid::new_v4());
+    let session = app_state.session(&game_id)?;
+    let game = &session.engine.state;
+    let save_id = session.save_slot.clone();
 
     let metadata = SavedGame {
         id: save_id.clone(),
@@ -159,20 +185,24 @@ pub fn save_game(
     };
 
     // Write to file
-    let save_path = get_save_path(&app_handle, &save_id)?;
-    let content = serde_json::to_string_pretty(&save_data)
-        .map_err(|e| AppError::SerializationError(format!("Failed to serialize save: {}", e)))?;
-
-    fs::write(&save_path, content)
-        .map_err(|e| AppError::InvalidState(format!("Failed to write save file: {}", e)))?;
+    let save_path = get_save_path(&app_state, &save_id)?;
+    write_save(&save_path, &save_data, app_state.storage_key.as_ref())?;
 
     Ok(metadata)
 }
 
 /// Delete a saved game.
 #[tauri::command]
-pub fn delete_saved_game(save_id: String, app_handle: AppHandle) -> Result<(), AppError> {
-    let save_path = get_save_path(&app_handle, &save_id)?;
+pub fn delete_saved_game(
+    save_id: String,
+    state: State<'_, Mutex<AppState>>,
+) -> Result<(), AppError> {
+    let save_path = {
+        let app_state = state
+            .lock()
+            .map_err(|_| AppError::InvalidState("Lock poisoned".to_string()))?;
+        get_save_path(&app_state, &save_id)?
+    };
 
     if !save_path.exists() {
         return Err(AppError::InvalidState(format!(
@@ -186,3 +216,97 @@ pub fn delete_saved_game(save_id: String, app_handle: AppHandle) -> Result<(), A
 
     Ok(())
 }
+
+/// Whether saves are encrypted, and whether they can currently be read.
+#[derive(Clone, Debug, Serialize, JsonSchema, TS)]
+#[serde(rename_all = "camelCase")]
+pub struct StorageEncryptionStatus {
+    /// A storage passphrase has been set.
+    pub enabled: bool,
+    /// The passphrase has been entered this session.
+    pub unlocked: bool,
+}
+
+/// Report whether saves are encrypted and unlocked.
+#[tauri::command]
+pub fn get_storage_encryption_status(
+    state: State<'_, Mutex<AppState>>,
+) -> Result<StorageEncryptionStatus, AppError> {
+    let app_state = state
+        .lock()
+        .map_err(|_| AppError::InvalidState("Lock poisoned".to_string()))?;
+    Ok(StorageEncryptionStatus {
+        enabled: app_state.storage_encryption_enabled(),
+        unlocked: app_state.storage_key.is_some(),
+    })
+}
+
+/// Unlock encrypted saves with the storage passphrase, setting it first if
+/// none has been set.
+///
+/// Setting the passphrase encrypts every existing plaintext save. Returns
+/// the number of saves encrypted.
+#[tauri::command]
+pub fn unlock_storage(
+    passphrase: String,
+    state: State<'_, Mutex<AppState>>,
+) -> Result<usize, AppError> {
+    let mut app_state = state
+        .lock()
+        .map_err(|_| AppError::InvalidState("Lock poisoned".to_string()))?;
+    let layout = app_state
+        .profile_storage()?
+        .ok_or_else(|| AppError::StorageError("Storage not initialized".to_string()))?;
+    let key_path = layout.at_rest_key_path();
+
+    let key = if key_path.exists() {
+        let content = fs::read_to_string(&key_path)
+            .map_err(|e| AppError::StorageError(format!("Failed to read key file: {}", e)))?;
+        let key_file: PassphraseKeyFile = serde_json::from_str(&content)
+            .map_err(|e| AppError::StorageError(format!("Failed to parse key file: {}", e)))?;
+        key_file
+            .unlock(&passphrase)
+            .map_err(|e| AppError::StorageError(format!("Failed to unlock storage: {}", e)))?
+    } else {
+        let (key_file, key) = PassphraseKeyFile::create(&passphrase, KdfParams::default())
+            .map_err(|e| AppError::StorageError(format!("Failed to set passphrase: {}", e)))?;
+        let content = serde_json::to_string_pretty(&key_file)
+            .map_err(|e| AppError::SerializationError(e.to_string()))?;
+        fs::create_dir_all(layout.data_dir())
+            .map_err(|e| AppError::StorageError(format!("Failed to create data dir: {}", e)))?;
+        fs::write(&key_path, content)
+            .map_err(|e| AppError::StorageError(format!("Failed to write key file: {}", e)))?;
+        key
+    };
+
+    let migrated = encrypt_plaintext_saves(&get_saves_dir(&app_state)?, &key)?;
+    app_state.storage_key = Some(key);
+    Ok(migrated)
+}
+
+/// Re-write every plaintext save in a directory encrypted.
+fn encrypt_plaintext_saves(saves_dir: &Path, key: &AtRestKey) -> Result<usize, AppError> {
+    let mut migrated = 0;
+    for entry in fs::read_dir(saves_dir)
+        .map_err(|e| AppError::StorageError(format!("Failed to read saves dir: {}", e)))?
+        .flatten()
+    {
+        let path = entry.path();
+        if path.extension().is_none_or(|ext| ext != "json") {
+            continue;
+        }
+        let Ok(content) = fs::read(&path) else {
+            continue;
+        };
+        if at_rest::is_encrypted(&content) {
+            continue;
+        }
+        let sealed = key
+            .encrypt(&content)
+            .map_err(|e| AppError::StorageError(format!("Failed to encrypt save file: {}", e)))?;
+        fs::write(&path, sealed)
+            .map_err(|e| AppError::StorageError(format!("Failed to write save file: {}", e)))?;
+        migrated += 1;
+    }
+    Ok(migrated)
+}
@@ -0,0 +1,167 @@
+//! Schema export commands.
+//!
+//! These commands describe every payload the frontend sends or receives,
+//! and every game event, as JSON Schema. The schemas are derived from the
+//! Rust types, so tools generating frontend types from them always match
+//! the backend. The same list of payload types drives the TypeScript
+//! bindings in [`crate::bindings`].
+
+use crate::commands::actions::{ActionResult, ActionValidation, PromotionOptions, UndoStatus};
+use crate::commands::capture::{MapCaptureResult, TimelapseInfo};
+use crate::commands::game::{
+    ActiveGameInfo, AiTickResponse, CreateGameOptions, GameStateResponse, PendingItemsResponse,
+    ProductionAdviceResponse, TurnDigestResponse,
+};
+use crate::commands::game_index::GameIndexQuery;
+use crate::commands::locale::MessageCatalog;
+use crate::commands::network::{ConnectionStatus, OfflineStatus, ReconnectSummary, TicketInfo};
+use crate::commands::pitboss::PitbossStatus;
+use crate::commands::sandbox::ForkGameResponse;
+use crate::commands::saves::{LoadGameResponse, SavedGame, StorageEncryptionStatus};
+use crate::commands::settings::SettingsResponse;
+use crate::commands::tournament::{CreateTournamentOptions, TournamentResponse};
+use crate::error::{AppError, ErrorPayload};
+use crate::events::{
+    CombatResolvedPayload, EventsSince, GameActionPayload, GameStateUpdatedPayload,
+    NetworkEventPayload, NotificationPayload, OperationProgressPayload, PresenceChangedPayload,
+    TournamentEventPayload, TurnEventPayload, TurnSchedulePayload,
+};
+use crate::state::{Preferences, UserProfile};
+use nostr_nations_core::{
+    event_schemas, CaptureChoice, CityNeed, CombatPreview, Demographics, GameAction, GameEvent,
+    LocalizedMessage, LogEntry, LogFilter, PauseState, PendingItem, Promotion, Recommendation,
+    SchemaExport, TradeItems, TreatyType, VictoryProof,
+};
+use nostr_nations_network::{
+    GameSummary, MatchResult, NetworkDebugReport, PresenceEntry, PresenceStatus, QueuedTurn,
+    TurnNotification,
+};
+use schemars::JsonSchema;
+use serde::Serialize;
+use ts_rs::TS;
+
+/// Something done once per payload type, such as adding it to a schema
+/// export or writing its TypeScript binding.
+pub trait PayloadVisitor {
+    /// Visit one payload type.
+    fn visit<T: JsonSchema + TS + 'static>(&mut self);
+}
+
+/// Visit every type crossing the IPC bridge: command arguments and
+/// results, and the payloads of emitted events.
+///
+/// This is the one list of frontend payloads; a new command or event type
+/// added here shows up in both the JSON Schemas and the TypeScript
+/// bindings.
+pub fn visit_payloads(visitor: &mut impl PayloadVisitor) {
+    // Emitted events
+    visitor.visit::<GameStateUpdatedPayload>();
+    visitor.visit::<TurnEventPayload>();
+    visitor.visit::<TurnSchedulePayload>();
+    visitor.visit::<CombatResolvedPayload>();
+    visitor.visit::<NetworkEventPayload>();
+    visitor.visit::<NotificationPayload>();
+    visitor.visit::<GameActionPayload>();
+    visitor.visit::<OperationProgressPayload>();
+    visitor.visit::<PresenceChangedPayload>();
+    visitor.visit::<TournamentEventPayload>();
+    // Command arguments
+    visitor.visit::<CreateGameOptions>();
+    visitor.visit::<CreateTournamentOptions>();
+    visitor.visit::<UserProfile>();
+    visitor.visit::<Preferences>();
+    visitor.visit::<GameAction>();
+    visitor.visit::<GameEvent>();
+    visitor.visit::<CaptureChoice>();
+    visitor.visit::<Promotion>();
+    visitor.visit::<TradeItems>();
+    visitor.visit::<TreatyType>();
+    visitor.visit::<PresenceStatus>();
+    visitor.visit::<MatchResult>();
+    visitor.visit::<QueuedTurn>();
+    visitor.visit::<TurnNotification>();
+    // Command results
+    visitor.visit::<ActionResult>();
+    visitor.visit::<ActionValidation>();
+    visitor.visit::<UndoStatus>();
+    visitor.visit::<PromotionOptions>();
+    visitor.visit::<CombatPreview>();
+    visitor.visit::<GameStateResponse>();
+    visitor.visit::<AiTickResponse>();
+    visitor.visit::<ActiveGameInfo>();
+    visitor.visit::<PauseState>();
+    visitor.visit::<Demographics>();
+    visitor.visit::<VictoryProof>();
+    visitor.visit::<TurnDigestResponse>();
+    visitor.visit::<LogFilter>();
+    visitor.visit::<LogEntry>();
+    visitor.visit::<PendingItemsResponse>();
+    visitor.visit::<PendingItem>();
+    visitor.visit::<ProductionAdviceResponse>();
+    visitor.visit::<Recommendation>();
+    visitor.visit::<CityNeed>();
+    visitor.visit::<MapCaptureResult>();
+    visitor.visit::<TimelapseInfo>();
+    visitor.visit::<LocalizedMessage>();
+    visitor.visit::<MessageCatalog>();
+    visitor.visit::<ConnectionStatus>();
+    visitor.visit::<TicketInfo>();
+    visitor.visit::<OfflineStatus>();
+    visitor.visit::<ReconnectSummary>();
+    visitor.visit::<PresenceEntry>();
+    visitor.visit::<NetworkDebugReport>();
+    visitor.visit::<PitbossStatus>();
+    visitor.visit::<SavedGame>();
+    visitor.visit::<LoadGameResponse>();
+    visitor.visit::<StorageEncryptionStatus>();
+    visitor.visit::<GameSummary>();
+    visitor.visit::<GameIndexQuery>();
+    visitor.visit::<ForkGameResponse>();
+    visitor.visit::<SettingsResponse>();
+    visitor.visit::<TournamentResponse>();
+    visitor.visit::<EventsSince>();
+    // Command errors
+    visitor.visit::<ErrorPayload>();
+}
+
+impl PayloadVisitor for SchemaExport {
+    fn visit<T: JsonSchema + TS + 'static>(&mut self) {
+        self.add::<T>();
+    }
+}
+
+/// Schemas for everything crossing the IPC bridge.
+pub fn payload_schemas() -> SchemaExport {
+    let mut export = SchemaExport::new("Nostr Nations frontend payloads");
+    visit_payloads(&mut export);
+    export
+}
+
+/// JSON Schema documents for the frontend.
+#[derive(Clone, Debug, Serialize)]
+pub struct JsonSchemas {
+    /// Command arguments and results, and event payloads.
+    pub payloads: serde_json::Value,
+    /// Game events as published to peers and relays.
+    pub events: serde_json::Value,
+}
+
+/// Get JSON Schemas for all frontend payloads and game events.
+#[tauri::command]
+pub fn get_json_schemas() -> Result<JsonSchemas, AppError> {
+    Ok(JsonSchemas {
+        payloads: payload_schemas().to_json(),
+        events: event_schemas().to_json(),
+    })
+}
+
+/// Get reference documentation for all payloads and game events, as
+/// Markdown.
+#[tauri::command]
+pub fn get_schema_docs() -> Result<String, AppError> {
+    Ok(format!(
+        "{}\n{}",
+        payload_schemas().to_markdown(),
+        event_schemas().to_markdown()
+    ))
+}
@@ -0,0 +1,193 @@
+//! Settings and profile commands.
+//!
+//! These commands read and update the user profile and UI preferences.
+//! Both are written to `settings.json` in the app data directory whenever
+//! they change, and loaded again at startup.
+
+use crate::error::AppError;
+use crate::state::{AppState, Preferences, UserProfile};
+use nostr_nations_network::GameIndex;
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::sync::Mutex;
+use tauri::State;
+use ts_rs::TS;
+
+/// Current version of the settings file format.
+///
+/// Bump this when a field is renamed or changes meaning, and add a step to
+/// [`migrate`]. Added fields don't need a bump; they fall back to their
+/// defaults when missing.
+pub const SETTINGS_SCHEMA_VERSION: u32 = 1;
+
+/// Settings as stored on disk.
+#[derive(Clone, Debug, Default, Serialize, Deserialize, JsonSchema, TS)]
+#[serde(default, rename_all = "camelCase")]
+pub struct SettingsFile {
+    pub schema_version: u32,
+    pub profile: UserProfile,
+    pub preferences: Preferences,
+}
+
+/// Settings returned to the frontend.
+#[derive(Clone, Debug, Serialize, JsonSchema, TS)]
+#[serde(rename_all = "camelCase")]
+pub struct SettingsResponse {
+    pub profile: UserProfile,
+    pub preferences: Preferences,
+    /// Whether changes are written to disk.
+    pub persisted: bool,
+}
+
+/// Get the settings file path, if app storage has been set up.
+fn settings_path(state: &AppState) -> Option<PathBuf> {
+    state
+        .storage
+        .as_ref()
+        .map(|layout| layout.data_dir().join("settings.json"))
+}
+
+/// Bring a settings document up to [`SETTINGS_SCHEMA_VERSION`].
+fn migrate(mut value: serde_json::Value) -> Result<SettingsFile, AppError> {
+    let version = value
+        .get("schemaVersion")
+        .and_then(|v| v.as_u64())
+        .unwrap_or(1) as u32;
+    if version > SETTINGS_SCHEMA_VERSION {
+        return Err(AppError::StorageError(format!(
+            "Settings were written by a newer version (schema {})",
+            version
+        )));
+    }
+
+    if let Some(object) = value.as_object_mut() {
+        object.insert(
+            "schemaVersion".to_string(),
+            serde_json::Value::from(SETTINGS_SCHEMA_VERSION),
+        );
+    }
+    serde_json::from_value(value).map_err(|e| AppError::SerializationError(e.to_string()))
+}
+
+/// Read the settings file. Returns `None` if it doesn't exist yet.
+pub fn read_settings(path: &Path) -> Result<Option<SettingsFile>, AppError> {
+    if !path.exists() {
+        return Ok(None);
+    }
+    let content = fs::read_to_string(path)
+        .map_err(|e| AppError::StorageError(format!("Failed to read settings: {}", e)))?;
+    let value =
+        serde_json::from_str(&content).map_err(|e| AppError::SerializationError(e.to_string()))?;
+    migrate(value).map(Some)
+}
+
+/// Load the settings file into the app state.
+pub fn load_settings(state: &mut AppState) -> Result<(), AppError> {
+    let Some(path) = settings_path(state) else {
+        return Ok(());
+    };
+    if let Some(settings) = read_settings(&path)? {
+        state.profile = settings.profile;
+        state.preferences = settings.preferences;
+    }
+    Ok(())
+}
+
+/// Write the profile and preferences to disk.
+///
+/// Does nothing until app storage has been set up.
+pub fn save_settings(state: &AppState) -> Result<(), AppError> {
+    let Some(path) = settings_path(state) else {
+        return Ok(());
+    };
+    let settings = SettingsFile {
+        schema_version: SETTINGS_SCHEMA_VERSION,
+        profile: state.profile.clone(),
+        preferences: state.preferences.clone(),
+    };
+    let json = serde_json::to_string_pretty(&settings)
+        .map_err(|e| AppError::SerializationError(e.to_string()))?;
+
+    // Write then rename so a crash mid-write keeps the old file
+    let tmp = path.with_extension("json.tmp");
+    fs::write(&tmp, json)
+        .and_then(|_| fs::rename(&tmp, &path))
+        .map_err(|e| AppError::StorageError(format!("Failed to write settings: {}", e)))
+}
+
+/// Check a profile before accepting it.
+fn validate_profile(profile: &UserProfile) -> Result<(), AppError> {
+    if let Some(npub) = &profile.npub {
+        if !npub.starts_with("npub1") {
+            return Err(AppError::InvalidState(format!("Invalid npub: {}", npub)));
+        }
+    }
+    if let Some(relay) = profile
+        .preferred_relays
+        .iter()
+        .find(|url| !url.starts_with("wss://") && !url.starts_with("ws://"))
+    {
+        return Err(AppError::InvalidState(format!(
+            "Invalid relay URL: {}",
+            relay
+        )));
+    }
+    Ok(())
+}
+
+/// Get the user profile and preferences.
+#[tauri::command]
+pub fn get_settings(state: State<'_, Mutex<AppState>>) -> Result<SettingsResponse, AppError> {
+    let state = state
+        .lock()
+        .map_err(|_| AppError::InvalidState("Lock poisoned".to_string()))?;
+
+    Ok(SettingsResponse {
+        profile: state.profile.clone(),
+        preferences: state.preferences.clone(),
+        persisted: state.storage.is_some(),
+    })
+}
+
+/// Replace the user profile.
+///
+/// Changing the npub switches to that identity's storage, so it is refused
+/// while games are open and locks the storage again.
+#[tauri::command]
+pub fn set_profile(
+    profile: UserProfile,
+    state: State<'_, Mutex<AppState>>,
+) -> Result<(), AppError> {
+    let mut state = state
+        .lock()
+        .map_err(|_| AppError::InvalidState("Lock poisoned".to_string()))?;
+
+    validate_profile(&profile)?;
+    if profile.npub != state.profile.npub {
+        if !state.sessions.is_empty() {
+            return Err(AppError::InvalidState(
+                "Close open games before switching identity".to_string(),
+            ));
+        }
+        state.storage_key = None;
+        state.game_index = GameIndex::new();
+    }
+    state.profile = profile;
+    save_settings(&state)
+}
+
+/// Replace the UI preferences.
+#[tauri::command]
+pub fn set_preferences(
+    preferences: Preferences,
+    state: State<'_, Mutex<AppState>>,
+) -> Result<(), AppError> {
+    let mut state = state
+        .lock()
+        .map_err(|_| AppError::InvalidState("Lock poisoned".to_string()))?;
+
+    state.preferences = preferences;
+    save_settings(&state)
+}
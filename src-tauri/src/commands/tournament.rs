@@ -0,0 +1,292 @@
+//! Tournament commands.
+//!
+//! These commands handle tournament brackets: creation, registration,
+//! match result reporting, and bracket queries.
+//!
+//! Bracket and result events are handed to the frontend to sign and
+//! publish to its relays. Lobbies for ready matches are opened on the local
+//! relay and broadcast like any other game event.
+
+use crate::error::AppError;
+use crate::events::{
+    emit_game_action, emit_notification, emit_tournament_event, GameActionPayload,
+    NotificationPayload, NotificationType, TournamentEventPayload,
+};
+use crate::state::AppState;
+use nostr_nations_core::{GameEvent, GameId, GameSettings, LocalizedMessage};
+use nostr_nations_network::{
+    MatchLobby, MatchResult, Tournament, TournamentEvent, TournamentStatus,
+};
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+use std::sync::Mutex;
+use tauri::{AppHandle, State};
+use ts_rs::TS;
+
+/// Options for creating a new tournament.
+#[derive(Clone, Debug, Deserialize, JsonSchema, TS)]
+pub struct CreateTournamentOptions {
+    pub name: String,
+    pub organizer_pubkey: String,
+    pub max_participants: usize,
+}
+
+/// Bracket match info for serialization.
+#[derive(Clone, Debug, Serialize, JsonSchema, TS)]
+pub struct BracketMatchInfo {
+    pub match_id: String,
+    pub round: u32,
+    pub players: Vec<Option<String>>,
+    pub winner: Option<String>,
+    pub status: String,
+    pub lobby_game_id: Option<GameId>,
+}
+
+/// Response for tournament bracket queries.
+#[derive(Clone, Debug, Serialize, JsonSchema, TS)]
+pub struct TournamentResponse {
+    pub tournament_id: String,
+    pub name: String,
+    pub status: String,
+    pub participants: Vec<String>,
+    pub rounds: Vec<Vec<BracketMatchInfo>>,
+    pub current_round: Option<u32>,
+    pub champion: Option<String>,
+}
+
+impl From<&Tournament> for TournamentResponse {
+    fn from(tournament: &Tournament) -> Self {
+        Self {
+            tournament_id: tournament.id.clone(),
+            name: tournament.name.clone(),
+            status: format!("{:?}", tournament.status),
+            participants: tournament
+                .participants
+                .iter()
+                .map(|p| p.name.clone())
+                .collect(),
+            rounds: tournament
+                .rounds
+                .iter()
+                .map(|round| {
+                    round
+                        .iter()
+                        .map(|m| BracketMatchInfo {
+                            match_id: m.id.clone(),
+                            round: m.round,
+                            players: m.players.to_vec(),
+                            winner: m.winner.clone(),
+                            status: format!("{:?}", m.status),
+                            lobby_game_id: m.lobby.as_ref().map(|l| l.game_id.clone()),
+                        })
+                        .collect()
+                })
+                .collect(),
+            current_round: tournament.current_round(),
+            champion: tournament.champion.clone(),
+        }
+    }
+}
+
+/// Create a new tournament and publish its bracket.
+#[tauri::command]
+pub fn create_tournament(
+    app_handle: AppHandle,
+    options: CreateTournamentOptions,
+    state: State<'_, Mutex<AppState>>,
+) -> Result<TournamentResponse, AppError> {
+    let mut state = state
+        .lock()
+        .map_err(|_| AppError::InvalidState("Lock poisoned".to_string()))?;
+
+    let timestamp = std::time::SystemTime::now()
+        .duration_since(std::time::UNIX_EPOCH)
+        .map(|d| d.as_millis())
+        .unwrap_or(0);
+    let tournament_id = format!("tournament_{:x}", timestamp);
+
+    let tournament = Tournament::new(
+        tournament_id.clone(),
+        options.name.clone(),
+        options.organizer_pubkey,
+        options.max_participants,
+        GameSettings::duel(options.name),
+    );
+
+    let response = TournamentResponse::from(&tournament);
+    publish(&app_handle, &state, vec![tournament.bracket_event()], &[]);
+    state.tournaments.insert(tournament_id, tournament);
+    Ok(response)
+}
+
+/// Join a tournament that is open for registration.
+#[tauri::command]
+pub fn join_tournament(
+    tournament_id: String,
+    pubkey: String,
+    player_name: String,
+    state: State<'_, Mutex<AppState>>,
+) -> Result<TournamentResponse, AppError> {
+    let mut state = state
+        .lock()
+        .map_err(|_| AppError::InvalidState("Lock poisoned".to_string()))?;
+
+    let tournament = state.get_tournament_mut(&tournament_id)?;
+    tournament
+        .join(pubkey, player_name)
+        .map_err(|e| AppError::InvalidState(e.to_string()))?;
+
+    Ok(TournamentResponse::from(&*tournament))
+}
+
+/// Close registration, generate the bracket, and publish it.
+///
+/// The bracket seed comes from the tournament ID and its participants, so
+/// every client generates the same bracket.
+#[tauri::command]
+pub fn start_tournament(
+    app_handle: AppHandle,
+    tournament_id: String,
+    state: State<'_, Mutex<AppState>>,
+) -> Result<TournamentResponse, AppError> {
+    let mut state = state
+        .lock()
+        .map_err(|_| AppError::InvalidState("Lock poisoned".to_string()))?;
+
+    let tournament = state.get_tournament_mut(&tournament_id)?;
+    let seed = tournament.bracket_seed();
+    let lobbies = tournament
+        .start(seed)
+        .map_err(|e| AppError::InvalidState(e.to_string()))?;
+    let bracket = tournament.bracket_event();
+    let response = TournamentResponse::from(&*tournament);
+
+    publish(&app_handle, &state, vec![bracket], &lobbies);
+    notify_lobbies(&app_handle, &lobbies);
+
+    Ok(response)
+}
+
+/// Report a signed match result, advance the winner, and publish the
+/// result and the updated bracket.
+#[tauri::command]
+pub fn report_match_result(
+    app_handle: AppHandle,
+    result: MatchResult,
+    state: State<'_, Mutex<AppState>>,
+) -> Result<TournamentResponse, AppError> {
+    let mut state = state
+        .lock()
+        .map_err(|_| AppError::InvalidState("Lock poisoned".to_string()))?;
+
+    let reporter = state.profile.npub.clone();
+    let tournament = state.get_tournament_mut(&result.tournament_id)?;
+    let reporter = reporter.unwrap_or_else(|| tournament.organizer.clone());
+    let result_event = tournament.result_event(&result, &reporter);
+    let lobbies = tournament
+        .submit_result(result)
+        .map_err(|e| AppError::InvalidState(e.to_string()))?;
+    let bracket = tournament.bracket_event();
+    let completed = tournament.status == TournamentStatus::Completed;
+    let name = tournament.name.clone();
+    let champion = tournament.champion.clone();
+    let response = TournamentResponse::from(&*tournament);
+
+    publish(&app_handle, &state, vec![result_event, bracket], &lobbies);
+    notify_lobbies(&app_handle, &lobbies);
+
+    if completed {
+        let _ = emit_notification(
+            &app_handle,
+            NotificationPayload::localized(
+                NotificationType::Success,
+                LocalizedMessage::new("notify-tournament-complete-title"),
+                LocalizedMessage::new("notify-tournament-complete")
+                    .with_arg("tournament", &name)
+                    .with_arg("champion", champion.unwrap_or_default()),
+            ),
+        );
+    }
+
+    Ok(response)
+}
+
+/// Get the bracket state of a tournament.
+#[tauri::command]
+pub fn get_tournament_bracket(
+    tournament_id: String,
+    state: State<'_, Mutex<AppState>>,
+) -> Result<TournamentResponse, AppError> {
+    let state = state
+        .lock()
+        .map_err(|_| AppError::InvalidState("Lock poisoned".to_string()))?;
+
+    let tournament = state
+        .tournaments
+        .get(&tournament_id)
+        .ok_or(AppError::TournamentNotFound(tournament_id))?;
+
+    Ok(TournamentResponse::from(tournament))
+}
+
+/// List all known tournaments.
+#[tauri::command]
+pub fn list_tournaments(
+    state: State<'_, Mutex<AppState>>,
+) -> Result<Vec<TournamentResponse>, AppError> {
+    let state = state
+        .lock()
+        .map_err(|_| AppError::InvalidState("Lock poisoned".to_string()))?;
+
+    Ok(state
+        .tournaments
+        .values()
+        .map(TournamentResponse::from)
+        .collect())
+}
+
+/// Publish tournament events and open lobbies for newly ready matches.
+///
+/// Failing to reach the local relay is logged rather than failing the
+/// command; the events still go to the frontend.
+fn publish(
+    app_handle: &AppHandle,
+    state: &AppState,
+    events: Vec<TournamentEvent>,
+    lobbies: &[MatchLobby],
+) {
+    for event in events {
+        let _ = emit_tournament_event(app_handle, TournamentEventPayload { event });
+    }
+    if lobbies.is_empty() {
+        return;
+    }
+
+    let lobby_events: Vec<GameEvent> = lobbies.iter().map(MatchLobby::create_game_event).collect();
+    if let Err(e) = state
+        .local_relay()
+        .and_then(|relay| Ok(relay.publish_batch(&lobby_events)?))
+    {
+        tracing::warn!(error = %e, "failed to open tournament lobbies on the local relay");
+    }
+    for event in lobby_events {
+        let description = event.action.description();
+        let _ = emit_game_action(app_handle, GameActionPayload { event, description });
+    }
+}
+
+/// Notify the frontend about newly generated match lobbies.
+fn notify_lobbies(app_handle: &AppHandle, lobbies: &[MatchLobby]) {
+    for lobby in lobbies {
+        let _ = emit_notification(
+            app_handle,
+            NotificationPayload::localized(
+                NotificationType::Info,
+                LocalizedMessage::new("notify-match-ready-title"),
+                LocalizedMessage::new("notify-match-ready")
+                    .with_arg("first", &lobby.players[0])
+                    .with_arg("second", &lobby.players[1]),
+            ),
+        );
+    }
+}
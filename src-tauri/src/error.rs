@@ -0,0 +1,258 @@
+//! Errors returned to the frontend.
+//!
+//! Every [`AppError`] maps to a stable [`ErrorCode`], and is sent over IPC
+//! as an [`ErrorPayload`] so the frontend can branch on the code and show a
+//! localized message instead of matching on English text.
+
+use nostr_nations_core::{GameError, LocalizedMessage, ReplayError};
+use nostr_nations_network::{NetworkError, OfflineStorageError, StorageError};
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+use ts_rs::TS;
+
+/// Application errors.
+#[derive(Debug, Clone, thiserror::Error)]
+pub enum AppError {
+    #[error("Game not found: {0}")]
+    GameNotFound(String),
+    #[error("Game already in progress")]
+    GameAlreadyActive,
+    #[error("Invalid game state: {0}")]
+    InvalidState(String),
+    #[error("{0}")]
+    Game(GameError),
+    #[error("{0}")]
+    Engine(ReplayError),
+    #[error("Network error: {0}")]
+    NetworkError(String),
+    #[error("Serialization error: {0}")]
+    SerializationError(String),
+    #[error("Tournament not found: {0}")]
+    TournamentNotFound(String),
+    #[error("Storage error: {0}")]
+    StorageError(String),
+    #[error("{0} decision(s) must be made before ending the turn")]
+    PendingDecisions(usize),
+}
+
+impl AppError {
+    /// Stable code for this error.
+    pub fn code(&self) -> ErrorCode {
+        match self {
+            AppError::GameNotFound(_) => ErrorCode::GameNotFound,
+            AppError::GameAlreadyActive => ErrorCode::GameAlreadyActive,
+            AppError::InvalidState(_) => ErrorCode::InvalidState,
+            AppError::Game(e) => ErrorCode::from(e),
+            AppError::Engine(e) => ErrorCode::from(e),
+            AppError::NetworkError(_) => ErrorCode::Network,
+            AppError::SerializationError(_) => ErrorCode::Serialization,
+            AppError::TournamentNotFound(_) => ErrorCode::TournamentNotFound,
+            AppError::StorageError(_) => ErrorCode::Storage,
+            AppError::PendingDecisions(_) => ErrorCode::PendingDecisions,
+        }
+    }
+
+    /// Free-form detail worth showing alongside the message, if any.
+    fn detail(&self) -> Option<String> {
+        match self {
+            AppError::GameNotFound(detail)
+            | AppError::InvalidState(detail)
+            | AppError::NetworkError(detail)
+            | AppError::SerializationError(detail)
+            | AppError::TournamentNotFound(detail)
+            | AppError::StorageError(detail) => Some(detail.clone()),
+            AppError::Engine(ReplayError::InvalidRandomnessProof(detail)) => Some(detail.clone()),
+            AppError::Engine(ReplayError::InvalidSnapshot(e)) => Some(e.to_string()),
+            AppError::GameAlreadyActive
+            | AppError::Game(_)
+            | AppError::Engine(_)
+            | AppError::PendingDecisions(_) => None,
+        }
+    }
+
+    /// Catalog message describing this error.
+    pub fn localized(&self) -> LocalizedMessage {
+        let message = LocalizedMessage::new(self.code().message_key());
+        match self.detail() {
+            Some(detail) => message.with_arg("detail", detail),
+            None => message,
+        }
+    }
+}
+
+impl From<GameError> for AppError {
+    fn from(e: GameError) -> Self {
+        AppError::Game(e)
+    }
+}
+
+impl From<ReplayError> for AppError {
+    fn from(e: ReplayError) -> Self {
+        match e {
+            ReplayError::GameError(e) => AppError::Game(e),
+            e => AppError::Engine(e),
+        }
+    }
+}
+
+impl From<NetworkError> for AppError {
+    fn from(e: NetworkError) -> Self {
+        AppError::NetworkError(e.to_string())
+    }
+}
+
+impl From<StorageError> for AppError {
+    fn from(e: StorageError) -> Self {
+        AppError::StorageError(e.to_string())
+    }
+}
+
+impl From<OfflineStorageError> for AppError {
+    fn from(e: OfflineStorageError) -> Self {
+        AppError::StorageError(e.to_string())
+    }
+}
+
+impl Serialize for AppError {
+    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: serde::Serializer,
+    {
+        ErrorPayload::from(self).serialize(serializer)
+    }
+}
+
+/// Stable identifier for an error, for the frontend to branch on.
+///
+/// Codes keep their name and number once released; retired codes are not
+/// reused. Numbers are grouped by area: 1xxx sessions, 2xxx game rules,
+/// 25xx event chains, 3xxx network, 4xxx storage.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash, Serialize, Deserialize, JsonSchema, TS)]
+#[serde(rename_all = "snake_case")]
+#[repr(u16)]
+pub enum ErrorCode {
+    InvalidState = 1000,
+    GameNotFound = 1001,
+    GameAlreadyActive = 1002,
+    TournamentNotFound = 1003,
+    PendingDecisions = 1004,
+    GameAlreadyStarted = 2000,
+    TooManyPlayers = 2001,
+    PlayerAlreadyJoined = 2002,
+    InvalidPhase = 2003,
+    NotEnoughPlayers = 2004,
+    NotPlayerTurn = 2005,
+    InvalidAction = 2006,
+    UnitNotFound = 2007,
+    CityNotFound = 2008,
+    NotOwner = 2009,
+    InvalidPosition = 2010,
+    InvalidEventChain = 2500,
+    InvalidSettings = 2501,
+    InvalidRandomness = 2502,
+    InvalidSnapshot = 2503,
+    Network = 3000,
+    Storage = 4000,
+    Serialization = 4001,
+}
+
+impl ErrorCode {
+    /// Numeric form of the code.
+    pub fn number(self) -> u16 {
+        self as u16
+    }
+
+    /// String form of the code, as serialized.
+    pub fn as_str(self) -> &'static str {
+        match self {
+            ErrorCode::InvalidState => "invalid_state",
+            ErrorCode::GameNotFound => "game_not_found",
+            ErrorCode::GameAlreadyActive => "game_already_active",
+            ErrorCode::TournamentNotFound => "tournament_not_found",
+            ErrorCode::PendingDecisions => "pending_decisions",
+            ErrorCode::GameAlreadyStarted => "game_already_started",
+            ErrorCode::TooManyPlayers => "too_many_players",
+            ErrorCode::PlayerAlreadyJoined => "player_already_joined",
+            ErrorCode::InvalidPhase => "invalid_phase",
+            ErrorCode::NotEnoughPlayers => "not_enough_players",
+            ErrorCode::NotPlayerTurn => "not_player_turn",
+            ErrorCode::InvalidAction => "invalid_action",
+            ErrorCode::UnitNotFound => "unit_not_found",
+            ErrorCode::CityNotFound => "city_not_found",
+            ErrorCode::NotOwner => "not_owner",
+            ErrorCode::InvalidPosition => "invalid_position",
+            ErrorCode::InvalidEventChain => "invalid_event_chain",
+            ErrorCode::InvalidSettings => "invalid_settings",
+            ErrorCode::InvalidRandomness => "invalid_randomness",
+            ErrorCode::InvalidSnapshot => "invalid_snapshot",
+            ErrorCode::Network => "network",
+            ErrorCode::Storage => "storage",
+            ErrorCode::Serialization => "serialization",
+        }
+    }
+
+    /// Message catalog key, e.g. `error-not_player_turn`.
+    pub fn message_key(self) -> String {
+        format!("error-{}", self.as_str())
+    }
+}
+
+impl From<&GameError> for ErrorCode {
+    fn from(e: &GameError) -> Self {
+        match e {
+            GameError::GameAlreadyStarted => ErrorCode::GameAlreadyStarted,
+            GameError::TooManyPlayers => ErrorCode::TooManyPlayers,
+            GameError::PlayerAlreadyJoined => ErrorCode::PlayerAlreadyJoined,
+            GameError::InvalidPhase => ErrorCode::InvalidPhase,
+            GameError::NotEnoughPlayers => ErrorCode::NotEnoughPlayers,
+            GameError::NotPlayerTurn => ErrorCode::NotPlayerTurn,
+            GameError::InvalidAction => ErrorCode::InvalidAction,
+        }
+    }
+}
+
+impl From<&ReplayError> for ErrorCode {
+    fn from(e: &ReplayError) -> Self {
+        match e {
+            ReplayError::EmptyEventChain
+            | ReplayError::MissingCreateGame
+            | ReplayError::InvalidEventChain => ErrorCode::InvalidEventChain,
+            ReplayError::InvalidSettings => ErrorCode::InvalidSettings,
+            ReplayError::NotPlayerTurn => ErrorCode::NotPlayerTurn,
+            ReplayError::UnitNotFound => ErrorCode::UnitNotFound,
+            ReplayError::CityNotFound => ErrorCode::CityNotFound,
+            ReplayError::NotOwner => ErrorCode::NotOwner,
+            ReplayError::InvalidPosition => ErrorCode::InvalidPosition,
+            ReplayError::GameError(e) => ErrorCode::from(e),
+            ReplayError::MissingRandomnessProof | ReplayError::InvalidRandomnessProof(_) => {
+                ErrorCode::InvalidRandomness
+            }
+            ReplayError::InvalidSnapshot(_) => ErrorCode::InvalidSnapshot,
+        }
+    }
+}
+
+/// An error as the frontend receives it.
+#[derive(Clone, Debug, Serialize, JsonSchema, TS)]
+pub struct ErrorPayload {
+    /// Stable code to branch on.
+    pub code: ErrorCode,
+    /// Numeric form of `code`.
+    pub number: u16,
+    /// English description, for logs.
+    pub message: String,
+    /// Message to show the player, in their locale.
+    pub localized: LocalizedMessage,
+}
+
+impl From<&AppError> for ErrorPayload {
+    fn from(e: &AppError) -> Self {
+        let code = e.code();
+        Self {
+            code,
+            number: code.number(),
+            message: e.to_string(),
+            localized: e.localized(),
+        }
+    }
+}
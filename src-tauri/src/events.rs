@@ -10,9 +10,33 @@
 //! - `combat_resolved` - Combat results with attacker, defender, and outcomes
 //! - `network_event` - P2P networking events (peer connect/disconnect, sync)
 //! - `notification` - User-facing notifications
-
+//! - `game_action` - Locally applied game events to be signed and broadcast
+//! - `presence_changed` - A player came online, went idle, started typing, etc.
+//! - `turn_schedule` - Turn order and expected wait for the "next up" widget
+//! - `operation_progress` - Progress of long operations such as map generation
+//!
+//! # Sequence Numbers
+//!
+//! Every emitted payload carries a `seq` field, increasing by one with
+//! each event of any type. Events fired while the webview reloads are
+//! lost, so the last [`EVENT_LOG_CAPACITY`] events are also kept in an
+//! [`EventLog`]; after a reload the frontend asks for everything after the
+//! last `seq` it saw (`get_events_since`) instead of refetching the whole
+//! game state.
+
+use nostr_nations_core::{
+    CityDiff, CityId, Era, GameEvent, GameId, GameState, LocalizedMessage, PlayerSlot, Progress,
+    ScheduledTurn, StateDiff, TileDiff, TreasuryProjection, TurnSchedule, UnitDiff, UnitId,
+};
+use nostr_nations_network::{
+    encode_tile_runs, PresenceChange, PresenceStatus, TileRun, TournamentEvent,
+};
+use schemars::JsonSchema;
 use serde::{Deserialize, Serialize};
-use tauri::{AppHandle, Emitter};
+use std::collections::VecDeque;
+use std::sync::{Arc, Mutex};
+use tauri::{AppHandle, Emitter, Manager};
+use ts_rs::TS;
 
 // =============================================================================
 // Event Names (constants for consistency)
@@ -33,6 +57,21 @@ pub const EVENT_NETWORK: &str = "network_event";
 /// Event name for notifications.
 pub const EVENT_NOTIFICATION: &str = "notification";
 
+/// Event name for game actions that should be broadcast to other players.
+pub const EVENT_GAME_ACTION: &str = "game_action";
+
+/// Event name for player presence changes.
+pub const EVENT_PRESENCE: &str = "presence_changed";
+
+/// Event name for turn schedule updates.
+pub const EVENT_TURN_SCHEDULE: &str = "turn_schedule";
+
+/// Event name for progress of long-running operations.
+pub const EVENT_OPERATION_PROGRESS: &str = "operation_progress";
+
+/// Event name for tournament events that should be published to relays.
+pub const EVENT_TOURNAMENT: &str = "tournament_event";
+
 // =============================================================================
 // Game State Event
 // =============================================================================
@@ -41,16 +80,16 @@ pub const EVENT_NOTIFICATION: &str = "notification";
 ///
 /// Can contain either a full game state snapshot or a partial update
 /// with only the changed fields.
-#[derive(Clone, Debug, Serialize, Deserialize)]
+#[derive(Clone, Debug, Serialize, Deserialize, JsonSchema, TS)]
 pub struct GameStateUpdatedPayload {
     /// The game ID this update applies to.
-    pub game_id: String,
+    pub game_id: GameId,
     /// Current game phase (Setup, Playing, Ended).
     pub phase: String,
     /// Current turn number.
     pub turn: u32,
     /// ID of the current player.
-    pub current_player: u8,
+    pub current_player: PlayerSlot,
     /// Total number of players.
     pub player_count: usize,
     /// Map dimensions.
@@ -66,13 +105,42 @@ pub struct GameStateUpdatedPayload {
     /// Optional: Changed tiles (for partial updates).
     #[serde(skip_serializing_if = "Option::is_none")]
     pub changed_tiles: Option<Vec<TileUpdate>>,
+    /// Optional: Tile ownership changes as runs along rows (for partial
+    /// updates), so large border changes don't need one record per tile.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub changed_territory: Option<Vec<TerritoryRun>>,
+}
+
+impl GameStateUpdatedPayload {
+    /// Build a partial update listing exactly what changed in `diff`.
+    pub fn partial(game: &GameState, diff: &StateDiff) -> Self {
+        let territory = encode_tile_runs(diff.territory.iter().copied());
+        Self {
+            game_id: game.id.clone(),
+            phase: format!("{:?}", game.phase),
+            turn: game.turn,
+            current_player: game.current_player,
+            player_count: game.players.len(),
+            map_dimensions: game.settings.map_size.dimensions(),
+            is_full_update: false,
+            changed_units: non_empty(diff.units.iter().map(UnitUpdate::from).collect()),
+            changed_cities: non_empty(diff.cities.iter().map(CityUpdate::from).collect()),
+            changed_tiles: non_empty(diff.tiles.iter().map(TileUpdate::from).collect()),
+            changed_territory: non_empty(territory.iter().map(TerritoryRun::from).collect()),
+        }
+    }
+}
+
+/// `None` for an empty list, so it's left out of the payload.
+fn non_empty<T>(items: Vec<T>) -> Option<Vec<T>> {
+    (!items.is_empty()).then_some(items)
 }
 
 /// Minimal unit update for partial state updates.
-#[derive(Clone, Debug, Serialize, Deserialize)]
+#[derive(Clone, Debug, Serialize, Deserialize, JsonSchema, TS)]
 pub struct UnitUpdate {
-    pub id: u64,
-    pub owner: u8,
+    pub id: UnitId,
+    pub owner: PlayerSlot,
     pub unit_type: String,
     pub position: (i32, i32),
     pub health: u32,
@@ -80,24 +148,82 @@ pub struct UnitUpdate {
     pub is_destroyed: bool,
 }
 
+impl From<&UnitDiff> for UnitUpdate {
+    fn from(unit: &UnitDiff) -> Self {
+        Self {
+            id: unit.id,
+            owner: unit.owner,
+            unit_type: format!("{:?}", unit.unit_type),
+            position: (unit.position.q, unit.position.r),
+            health: if unit.destroyed { 0 } else { unit.health },
+            movement_remaining: if unit.destroyed { 0 } else { unit.movement },
+            is_destroyed: unit.destroyed,
+        }
+    }
+}
+
 /// Minimal city update for partial state updates.
-#[derive(Clone, Debug, Serialize, Deserialize)]
+#[derive(Clone, Debug, Serialize, Deserialize, JsonSchema, TS)]
 pub struct CityUpdate {
-    pub id: u64,
-    pub owner: u8,
+    pub id: CityId,
+    pub owner: PlayerSlot,
     pub name: String,
     pub position: (i32, i32),
     pub population: u32,
     pub health: u32,
 }
 
+impl From<&CityDiff> for CityUpdate {
+    fn from(city: &CityDiff) -> Self {
+        Self {
+            id: city.id,
+            owner: city.owner,
+            name: city.name.clone(),
+            position: (city.position.q, city.position.r),
+            population: city.population,
+            health: city.health,
+        }
+    }
+}
+
 /// Minimal tile update for partial state updates.
-#[derive(Clone, Debug, Serialize, Deserialize)]
+#[derive(Clone, Debug, Serialize, Deserialize, JsonSchema, TS)]
 pub struct TileUpdate {
     pub position: (i32, i32),
     pub improvement: Option<String>,
     pub road: Option<String>,
-    pub owner: Option<u8>,
+    pub owner: Option<PlayerSlot>,
+}
+
+impl From<&TileDiff> for TileUpdate {
+    fn from(tile: &TileDiff) -> Self {
+        Self {
+            position: (tile.position.q, tile.position.r),
+            improvement: tile.improvement.map(|i| format!("{:?}", i)),
+            road: tile.road.map(|r| format!("{:?}", r)),
+            owner: tile.owner,
+        }
+    }
+}
+
+/// Contiguous tiles along a row that changed to the same owner.
+#[derive(Clone, Debug, Serialize, Deserialize, JsonSchema, TS)]
+pub struct TerritoryRun {
+    /// First tile of the run.
+    pub start: (i32, i32),
+    /// Number of tiles, counting along the row from `start`.
+    pub length: u32,
+    pub owner: Option<PlayerSlot>,
+}
+
+impl From<&TileRun> for TerritoryRun {
+    fn from(run: &TileRun) -> Self {
+        Self {
+            start: (run.start.q, run.start.r),
+            length: run.len,
+            owner: run.owner,
+        }
+    }
 }
 
 // =============================================================================
@@ -105,7 +231,7 @@ pub struct TileUpdate {
 // =============================================================================
 
 /// Types of turn events.
-#[derive(Clone, Debug, Serialize, Deserialize, PartialEq, Eq)]
+#[derive(Clone, Debug, Serialize, Deserialize, JsonSchema, TS, PartialEq, Eq)]
 #[serde(rename_all = "snake_case")]
 pub enum TurnEventType {
     /// A new turn has started (all players).
@@ -117,14 +243,14 @@ pub enum TurnEventType {
 }
 
 /// Payload for turn-related events.
-#[derive(Clone, Debug, Serialize, Deserialize)]
+#[derive(Clone, Debug, Serialize, Deserialize, JsonSchema, TS)]
 pub struct TurnEventPayload {
     /// Type of turn event.
     pub event_type: TurnEventType,
     /// Current turn number.
     pub turn: u32,
     /// Player whose turn it is (or was).
-    pub player_id: u8,
+    pub player_id: PlayerSlot,
     /// Player name for display.
     pub player_name: String,
     /// Previous turn number (for turn_started).
@@ -132,6 +258,46 @@ pub struct TurnEventPayload {
     pub previous_turn: Option<u32>,
     /// Whether this is the local player's turn.
     pub is_local_player: bool,
+    /// Projected gold flow for the player whose turn it is.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub treasury: Option<TreasuryProjection>,
+    /// Era of the player whose turn it is.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub era: Option<Era>,
+}
+
+/// Payload for turn schedule events.
+#[derive(Clone, Debug, Serialize, Deserialize, JsonSchema, TS)]
+pub struct TurnSchedulePayload {
+    /// Game the schedule is for.
+    pub game_id: GameId,
+    /// Current turn number.
+    pub turn: u32,
+    /// Player whose turn it is.
+    pub current_player: PlayerSlot,
+    /// Seconds the current player has spent on this turn, if known.
+    pub elapsed_secs: Option<u64>,
+    /// Remaining players in turn order, starting with the current player.
+    pub queue: Vec<ScheduledTurn>,
+    /// Turns before the local player plays (0 if playing now).
+    pub local_position: Option<usize>,
+    /// Estimated seconds until the local player's next turn.
+    pub local_eta_secs: Option<u64>,
+}
+
+impl TurnSchedulePayload {
+    /// Create a payload from a schedule, with estimates for `local_player`.
+    pub fn new(game_id: GameId, schedule: TurnSchedule, local_player: PlayerSlot) -> Self {
+        Self {
+            game_id,
+            turn: schedule.turn,
+            current_player: schedule.current_player,
+            elapsed_secs: schedule.elapsed_secs,
+            local_position: schedule.position(local_player),
+            local_eta_secs: schedule.eta(local_player),
+            queue: schedule.queue,
+        }
+    }
 }
 
 // =============================================================================
@@ -139,7 +305,7 @@ pub struct TurnEventPayload {
 // =============================================================================
 
 /// Payload for combat resolution events.
-#[derive(Clone, Debug, Serialize, Deserialize)]
+#[derive(Clone, Debug, Serialize, Deserialize, JsonSchema, TS)]
 pub struct CombatResolvedPayload {
     /// Attacker information.
     pub attacker: CombatantInfo,
@@ -154,12 +320,12 @@ pub struct CombatResolvedPayload {
 }
 
 /// Information about a unit involved in combat.
-#[derive(Clone, Debug, Serialize, Deserialize)]
+#[derive(Clone, Debug, Serialize, Deserialize, JsonSchema, TS)]
 pub struct CombatantInfo {
     /// Unit ID.
-    pub unit_id: u64,
+    pub unit_id: UnitId,
     /// Owner player ID.
-    pub owner_id: u8,
+    pub owner_id: PlayerSlot,
     /// Owner player name.
     pub owner_name: String,
     /// Unit type name.
@@ -173,7 +339,7 @@ pub struct CombatantInfo {
 }
 
 /// Results of a combat engagement.
-#[derive(Clone, Debug, Serialize, Deserialize)]
+#[derive(Clone, Debug, Serialize, Deserialize, JsonSchema, TS)]
 pub struct CombatResults {
     /// Damage dealt to defender.
     pub defender_damage: u32,
@@ -196,7 +362,7 @@ pub struct CombatResults {
 // =============================================================================
 
 /// Types of network events.
-#[derive(Clone, Debug, Serialize, Deserialize, PartialEq, Eq)]
+#[derive(Clone, Debug, Serialize, Deserialize, JsonSchema, TS, PartialEq, Eq)]
 #[serde(rename_all = "snake_case")]
 pub enum NetworkEventType {
     /// A new peer has connected.
@@ -209,10 +375,15 @@ pub enum NetworkEventType {
     SyncStarted,
     /// Connection error occurred.
     ConnectionError,
+    /// The app resumed from the background and connections must be
+    /// re-checked before use.
+    ConnectionsStale,
+    /// A peer failed the handshake (incompatible version, rules or game).
+    HandshakeFailed,
 }
 
 /// Payload for network-related events.
-#[derive(Clone, Debug, Serialize, Deserialize)]
+#[derive(Clone, Debug, Serialize, Deserialize, JsonSchema, TS)]
 pub struct NetworkEventPayload {
     /// Type of network event.
     pub event_type: NetworkEventType,
@@ -237,7 +408,7 @@ pub struct NetworkEventPayload {
 // =============================================================================
 
 /// Types of notifications.
-#[derive(Clone, Debug, Serialize, Deserialize, PartialEq, Eq)]
+#[derive(Clone, Debug, Serialize, Deserialize, JsonSchema, TS, PartialEq, Eq)]
 #[serde(rename_all = "snake_case")]
 pub enum NotificationType {
     /// Informational message.
@@ -261,7 +432,7 @@ pub enum NotificationType {
 }
 
 /// Payload for notification events.
-#[derive(Clone, Debug, Serialize, Deserialize)]
+#[derive(Clone, Debug, Serialize, Deserialize, JsonSchema, TS)]
 pub struct NotificationPayload {
     /// Type of notification.
     pub notification_type: NotificationType,
@@ -269,6 +440,14 @@ pub struct NotificationPayload {
     pub title: String,
     /// Notification message body.
     pub message: String,
+    /// Localization key and parameters for the title.
+    ///
+    /// `title` holds the English rendering for frontends that do not localize.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub title_key: Option<LocalizedMessage>,
+    /// Localization key and parameters for the message body.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub message_key: Option<LocalizedMessage>,
     /// Optional icon name.
     #[serde(skip_serializing_if = "Option::is_none")]
     pub icon: Option<String>,
@@ -281,7 +460,7 @@ pub struct NotificationPayload {
 }
 
 /// Action that can be triggered from a notification.
-#[derive(Clone, Debug, Serialize, Deserialize)]
+#[derive(Clone, Debug, Serialize, Deserialize, JsonSchema, TS)]
 pub struct NotificationAction {
     /// Action type.
     pub action_type: String,
@@ -292,6 +471,219 @@ pub struct NotificationAction {
     pub data: Option<serde_json::Value>,
 }
 
+// =============================================================================
+// Game Action Event
+// =============================================================================
+
+/// Payload for game action events.
+///
+/// Carries an unsigned game event that the frontend signs and publishes
+/// so remote players can apply the same action. The event's `timestamp` is
+/// its `created_at` and its `id` is the NIP-01 ID it gets when signed with
+/// the player's key; the next event in a batch links to that ID, so the
+/// frontend must publish the event with exactly these fields.
+#[derive(Clone, Debug, Serialize, Deserialize, JsonSchema, TS)]
+pub struct GameActionPayload {
+    /// The game event to sign and broadcast.
+    pub event: GameEvent,
+    /// Human-readable description of the action.
+    pub description: String,
+}
+
+// =============================================================================
+// Tournament Event
+// =============================================================================
+
+/// Payload for tournament events.
+///
+/// Carries an unsigned bracket or match result event that the frontend
+/// signs and publishes so the other participants see the bracket.
+#[derive(Clone, Debug, Serialize, Deserialize, JsonSchema, TS)]
+pub struct TournamentEventPayload {
+    /// The tournament event to sign and publish.
+    pub event: TournamentEvent,
+}
+
+// =============================================================================
+// Operation Progress Event
+// =============================================================================
+
+/// Payload for operation progress events.
+#[derive(Clone, Debug, Serialize, Deserialize, JsonSchema, TS)]
+pub struct OperationProgressPayload {
+    /// Identifies the operation, e.g. `start_game:<game id>`.
+    pub operation_id: String,
+    /// What the operation is doing.
+    pub phase: String,
+    /// How much of the operation is done, from 0 to 100.
+    pub percent: u8,
+}
+
+impl OperationProgressPayload {
+    /// Create a payload from a progress report.
+    pub fn new(operation_id: impl Into<String>, progress: Progress) -> Self {
+        Self {
+            operation_id: operation_id.into(),
+            phase: progress.phase,
+            percent: progress.percent,
+        }
+    }
+}
+
+// =============================================================================
+// Presence Event
+// =============================================================================
+
+/// Payload for presence change events.
+#[derive(Clone, Debug, Serialize, Deserialize, JsonSchema, TS)]
+pub struct PresenceChangedPayload {
+    /// Game the player is in.
+    pub game_id: GameId,
+    /// Player whose presence changed.
+    pub player_id: PlayerSlot,
+    /// Status before the change (None if first seen).
+    pub previous: Option<PresenceStatus>,
+    /// Current status.
+    pub status: PresenceStatus,
+    /// Whether the player is typing.
+    pub typing: bool,
+}
+
+impl PresenceChangedPayload {
+    /// Create a payload from a presence change.
+    pub fn new(game_id: GameId, change: PresenceChange) -> Self {
+        Self {
+            game_id,
+            player_id: change.player_id,
+            previous: change.previous,
+            status: change.status,
+            typing: change.typing,
+        }
+    }
+}
+
+// =============================================================================
+// Event Replay Log
+// =============================================================================
+
+/// Number of recent events kept for replay.
+pub const EVENT_LOG_CAPACITY: usize = 512;
+
+/// An emitted event as kept for replay.
+#[derive(Clone, Debug, Serialize, JsonSchema, TS)]
+pub struct LoggedEvent {
+    /// Sequence number of the event.
+    pub seq: u64,
+    /// Event name (e.g. `turn_event`).
+    pub event: String,
+    /// Payload as emitted, including its `seq`.
+    pub payload: serde_json::Value,
+}
+
+/// Events emitted after a given sequence number.
+#[derive(Clone, Debug, Serialize, JsonSchema, TS)]
+#[serde(rename_all = "camelCase")]
+pub struct EventsSince {
+    /// Events in the order they were emitted.
+    pub events: Vec<LoggedEvent>,
+    /// Sequence number of the latest event emitted (0 if none).
+    pub latest_seq: u64,
+    /// Whether `events` holds everything after the requested sequence
+    /// number. If not, older events have been dropped from the log and
+    /// the frontend should reload the full game state.
+    pub complete: bool,
+}
+
+#[derive(Debug, Default)]
+struct EventLogInner {
+    last_seq: u64,
+    events: VecDeque<LoggedEvent>,
+}
+
+/// Numbers emitted events and keeps the most recent ones for replay.
+///
+/// Shared between [`AppState`](crate::state::AppState) and Tauri's managed
+/// state, so events can be logged while the app state is locked.
+#[derive(Debug)]
+pub struct EventLog {
+    inner: Mutex<EventLogInner>,
+    capacity: usize,
+}
+
+impl EventLog {
+    /// Create a log keeping up to `capacity` events.
+    pub fn new(capacity: usize) -> Self {
+        Self {
+            inner: Mutex::new(EventLogInner::default()),
+            capacity: capacity.max(1),
+        }
+    }
+
+    /// Number an event, keep it, and hand it to `emit`.
+    ///
+    /// `emit` runs while the log is locked, so events reach the frontend in
+    /// the order of their sequence numbers even when emitted from several
+    /// threads.
+    fn record<S, R>(&self, event: &str, payload: S, emit: impl FnOnce(serde_json::Value) -> R) -> R
+    where
+        S: Serialize,
+    {
+        let mut inner = self.inner.lock().unwrap_or_else(|e| e.into_inner());
+        inner.last_seq += 1;
+        let seq = inner.last_seq;
+        let mut payload = serde_json::to_value(payload).unwrap_or(serde_json::Value::Null);
+        if let Some(object) = payload.as_object_mut() {
+            object.insert("seq".to_string(), seq.into());
+        }
+        if inner.events.len() == self.capacity {
+            inner.events.pop_front();
+        }
+        inner.events.push_back(LoggedEvent {
+            seq,
+            event: event.to_string(),
+            payload: payload.clone(),
+        });
+        emit(payload)
+    }
+
+    /// Events emitted after `seq`.
+    pub fn since(&self, seq: u64) -> EventsSince {
+        let inner = self.inner.lock().unwrap_or_else(|e| e.into_inner());
+        let oldest = inner.events.front().map_or(inner.last_seq + 1, |e| e.seq);
+        EventsSince {
+            events: inner
+                .events
+                .iter()
+                .filter(|e| e.seq > seq)
+                .cloned()
+                .collect(),
+            latest_seq: inner.last_seq,
+            // A number past the latest means the backend restarted
+            complete: seq <= inner.last_seq && seq + 1 >= oldest,
+        }
+    }
+}
+
+impl Default for EventLog {
+    fn default() -> Self {
+        Self::new(EVENT_LOG_CAPACITY)
+    }
+}
+
+/// Emit an event with the next sequence number, keeping it for replay.
+///
+/// Falls back to a plain emit if no event log is managed.
+fn emit_sequenced<S: Serialize + Clone>(
+    app_handle: &AppHandle,
+    event: &str,
+    payload: S,
+) -> Result<(), tauri::Error> {
+    match app_handle.try_state::<Arc<EventLog>>() {
+        Some(log) => log.record(event, payload, |payload| app_handle.emit(event, payload)),
+        None => app_handle.emit(event, payload),
+    }
+}
+
 // =============================================================================
 // Event Emission Helper Functions
 // =============================================================================
@@ -310,7 +702,7 @@ pub fn emit_game_state_updated(
     app_handle: &AppHandle,
     payload: GameStateUpdatedPayload,
 ) -> Result<(), tauri::Error> {
-    app_handle.emit(EVENT_GAME_STATE_UPDATED, payload)
+    emit_sequenced(app_handle, EVENT_GAME_STATE_UPDATED, payload)
 }
 
 /// Emit a turn event.
@@ -328,7 +720,24 @@ pub fn emit_turn_event(
     app_handle: &AppHandle,
     payload: TurnEventPayload,
 ) -> Result<(), tauri::Error> {
-    app_handle.emit(EVENT_TURN, payload)
+    emit_sequenced(app_handle, EVENT_TURN, payload)
+}
+
+/// Emit a turn schedule event.
+///
+/// # Arguments
+///
+/// * `app_handle` - The Tauri app handle.
+/// * `payload` - The turn schedule payload.
+///
+/// # Returns
+///
+/// Result indicating success or failure.
+pub fn emit_turn_schedule(
+    app_handle: &AppHandle,
+    payload: TurnSchedulePayload,
+) -> Result<(), tauri::Error> {
+    emit_sequenced(app_handle, EVENT_TURN_SCHEDULE, payload)
 }
 
 /// Emit a combat resolved event.
@@ -345,7 +754,7 @@ pub fn emit_combat_resolved(
     app_handle: &AppHandle,
     payload: CombatResolvedPayload,
 ) -> Result<(), tauri::Error> {
-    app_handle.emit(EVENT_COMBAT_RESOLVED, payload)
+    emit_sequenced(app_handle, EVENT_COMBAT_RESOLVED, payload)
 }
 
 /// Emit a network event.
@@ -362,7 +771,7 @@ pub fn emit_network_event(
     app_handle: &AppHandle,
     payload: NetworkEventPayload,
 ) -> Result<(), tauri::Error> {
-    app_handle.emit(EVENT_NETWORK, payload)
+    emit_sequenced(app_handle, EVENT_NETWORK, payload)
 }
 
 /// Emit a notification event.
@@ -379,7 +788,75 @@ pub fn emit_notification(
     app_handle: &AppHandle,
     payload: NotificationPayload,
 ) -> Result<(), tauri::Error> {
-    app_handle.emit(EVENT_NOTIFICATION, payload)
+    emit_sequenced(app_handle, EVENT_NOTIFICATION, payload)
+}
+
+/// Emit a game action event.
+///
+/// # Arguments
+///
+/// * `app_handle` - The Tauri app handle.
+/// * `payload` - The game action payload.
+///
+/// # Returns
+///
+/// Result indicating success or failure.
+pub fn emit_game_action(
+    app_handle: &AppHandle,
+    payload: GameActionPayload,
+) -> Result<(), tauri::Error> {
+    emit_sequenced(app_handle, EVENT_GAME_ACTION, payload)
+}
+
+/// Emit a presence changed event.
+///
+/// # Arguments
+///
+/// * `app_handle` - The Tauri app handle.
+/// * `payload` - The presence change payload.
+///
+/// # Returns
+///
+/// Result indicating success or failure.
+pub fn emit_presence_changed(
+    app_handle: &AppHandle,
+    payload: PresenceChangedPayload,
+) -> Result<(), tauri::Error> {
+    emit_sequenced(app_handle, EVENT_PRESENCE, payload)
+}
+
+/// Emit an operation progress event.
+///
+/// # Arguments
+///
+/// * `app_handle` - The Tauri app handle.
+/// * `payload` - The operation progress payload.
+///
+/// # Returns
+///
+/// Result indicating success or failure.
+pub fn emit_operation_progress(
+    app_handle: &AppHandle,
+    payload: OperationProgressPayload,
+) -> Result<(), tauri::Error> {
+    emit_sequenced(app_handle, EVENT_OPERATION_PROGRESS, payload)
+}
+
+/// Emit a tournament event.
+///
+/// # Arguments
+///
+/// * `app_handle` - The Tauri app handle.
+/// * `payload` - The tournament event payload.
+///
+/// # Returns
+///
+/// Result indicating success or failure.
+pub fn emit_tournament_event(
+    app_handle: &AppHandle,
+    payload: TournamentEventPayload,
+) -> Result<(), tauri::Error> {
+    emit_sequenced(app_handle, EVENT_TOURNAMENT, payload)
 }
 
 // =============================================================================
@@ -393,6 +870,8 @@ impl NotificationPayload {
             notification_type: NotificationType::Info,
             title: title.into(),
             message: message.into(),
+            title_key: None,
+            message_key: None,
             icon: None,
             duration_ms: Some(5000),
             action: None,
@@ -405,6 +884,8 @@ impl NotificationPayload {
             notification_type: NotificationType::Success,
             title: title.into(),
             message: message.into(),
+            title_key: None,
+            message_key: None,
             icon: None,
             duration_ms: Some(5000),
             action: None,
@@ -417,6 +898,8 @@ impl NotificationPayload {
             notification_type: NotificationType::Warning,
             title: title.into(),
             message: message.into(),
+            title_key: None,
+            message_key: None,
             icon: None,
             duration_ms: Some(7000),
             action: None,
@@ -429,12 +912,54 @@ impl NotificationPayload {
             notification_type: NotificationType::Error,
             title: title.into(),
             message: message.into(),
+            title_key: None,
+            message_key: None,
             icon: None,
             duration_ms: None, // User must dismiss errors
             action: None,
         }
     }
 
+    /// Create a diplomacy notification.
+    pub fn diplomacy(title: impl Into<String>, message: impl Into<String>) -> Self {
+        Self {
+            notification_type: NotificationType::Diplomacy,
+            title: title.into(),
+            message: message.into(),
+            title_key: None,
+            message_key: None,
+            icon: None,
+            duration_ms: Some(7000),
+            action: None,
+        }
+    }
+
+    /// Create a notification from localization keys.
+    ///
+    /// The English text is filled in from the built-in catalog, and the
+    /// keys travel with the payload so the frontend can localize.
+    pub fn localized(
+        notification_type: NotificationType,
+        title: LocalizedMessage,
+        message: LocalizedMessage,
+    ) -> Self {
+        let base = match notification_type {
+            NotificationType::Success => Self::success("", ""),
+            NotificationType::Warning => Self::warning("", ""),
+            NotificationType::Error => Self::error("", ""),
+            NotificationType::Diplomacy => Self::diplomacy("", ""),
+            _ => Self::info("", ""),
+        };
+        Self {
+            notification_type,
+            title: title.to_english(),
+            message: message.to_english(),
+            title_key: Some(title),
+            message_key: Some(message),
+            ..base
+        }
+    }
+
     /// Set the notification duration.
     pub fn with_duration(mut self, duration_ms: u64) -> Self {
         self.duration_ms = Some(duration_ms);
@@ -500,13 +1025,37 @@ impl NetworkEventPayload {
             sync_progress: None,
         }
     }
+
+    /// Create a handshake failed event.
+    pub fn handshake_failed(peer_id: String, error: impl Into<String>, peer_count: usize) -> Self {
+        Self {
+            event_type: NetworkEventType::HandshakeFailed,
+            peer_id: Some(peer_id),
+            peer_name: None,
+            peer_count,
+            error_message: Some(error.into()),
+            sync_progress: None,
+        }
+    }
+
+    /// Create a connections stale event.
+    pub fn connections_stale(peer_count: usize) -> Self {
+        Self {
+            event_type: NetworkEventType::ConnectionsStale,
+            peer_id: None,
+            peer_name: None,
+            peer_count,
+            error_message: None,
+            sync_progress: None,
+        }
+    }
 }
 
 impl TurnEventPayload {
     /// Create a turn started event.
     pub fn turn_started(
         turn: u32,
-        player_id: u8,
+        player_id: PlayerSlot,
         player_name: String,
         is_local_player: bool,
     ) -> Self {
@@ -517,13 +1066,15 @@ impl TurnEventPayload {
             player_name,
             previous_turn: Some(turn.saturating_sub(1)),
             is_local_player,
+            treasury: None,
+            era: None,
         }
     }
 
     /// Create a turn ended event.
     pub fn turn_ended(
         turn: u32,
-        player_id: u8,
+        player_id: PlayerSlot,
         player_name: String,
         is_local_player: bool,
     ) -> Self {
@@ -534,13 +1085,15 @@ impl TurnEventPayload {
             player_name,
             previous_turn: None,
             is_local_player,
+            treasury: None,
+            era: None,
         }
     }
 
     /// Create a player turn event.
     pub fn player_turn(
         turn: u32,
-        player_id: u8,
+        player_id: PlayerSlot,
         player_name: String,
         is_local_player: bool,
     ) -> Self {
@@ -551,8 +1104,22 @@ impl TurnEventPayload {
             player_name,
             previous_turn: None,
             is_local_player,
+            treasury: None,
+            era: None,
         }
     }
+
+    /// Attach a treasury projection to the event.
+    pub fn with_treasury(mut self, treasury: TreasuryProjection) -> Self {
+        self.treasury = Some(treasury);
+        self
+    }
+
+    /// Attach the player's era to the event.
+    pub fn with_era(mut self, era: Era) -> Self {
+        self.era = Some(era);
+        self
+    }
 }
 
 #[cfg(test)]
@@ -588,28 +1155,28 @@ mod tests {
 
     #[test]
     fn test_turn_event_builders() {
-        let started = TurnEventPayload::turn_started(5, 0, "Player1".to_string(), true);
+        let started = TurnEventPayload::turn_started(5, PlayerSlot(0), "Player1".to_string(), true);
         assert_eq!(started.event_type, TurnEventType::TurnStarted);
         assert_eq!(started.turn, 5);
         assert_eq!(started.previous_turn, Some(4));
 
-        let ended = TurnEventPayload::turn_ended(5, 0, "Player1".to_string(), true);
+        let ended = TurnEventPayload::turn_ended(5, PlayerSlot(0), "Player1".to_string(), true);
         assert_eq!(ended.event_type, TurnEventType::TurnEnded);
     }
 
     #[test]
     fn test_payload_serialization() {
         let payload = GameStateUpdatedPayload {
-            game_id: "game123".to_string(),
+            game_id: GameId::new("game123"),
             phase: "Playing".to_string(),
             turn: 10,
-            current_player: 1,
+            current_player: PlayerSlot(1),
             player_count: 4,
             map_dimensions: (80, 50),
             is_full_update: false,
             changed_units: Some(vec![UnitUpdate {
-                id: 1,
-                owner: 0,
+                id: UnitId(1),
+                owner: PlayerSlot(0),
                 unit_type: "Warrior".to_string(),
                 position: (5, 3),
                 health: 80,
@@ -618,6 +1185,7 @@ mod tests {
             }]),
             changed_cities: None,
             changed_tiles: None,
+            changed_territory: None,
         };
 
         let json = serde_json::to_string(&payload).unwrap();
@@ -627,12 +1195,41 @@ mod tests {
         assert!(!json.contains("changed_cities"));
     }
 
+    #[test]
+    fn test_event_log_emits_in_sequence_order() {
+        let log = Arc::new(EventLog::new(64));
+        let emitted = Arc::new(Mutex::new(Vec::new()));
+        let threads: Vec<_> = (0..4)
+            .map(|_| {
+                let log = Arc::clone(&log);
+                let emitted = Arc::clone(&emitted);
+                std::thread::spawn(move || {
+                    for _ in 0..16 {
+                        log.record("test_event", serde_json::json!({}), |payload| {
+                            emitted
+                                .lock()
+                                .unwrap()
+                                .push(payload["seq"].as_u64().unwrap());
+                        });
+                    }
+                })
+            })
+            .collect();
+        for thread in threads {
+            thread.join().unwrap();
+        }
+
+        let emitted = emitted.lock().unwrap();
+        assert_eq!(*emitted, (1..=64).collect::<Vec<u64>>());
+        assert_eq!(log.since(0).events.len(), 64);
+    }
+
     #[test]
     fn test_combat_payload_serialization() {
         let payload = CombatResolvedPayload {
             attacker: CombatantInfo {
-                unit_id: 1,
-                owner_id: 0,
+                unit_id: UnitId(1),
+                owner_id: PlayerSlot(0),
                 owner_name: "Player1".to_string(),
                 unit_type: "Swordsman".to_string(),
                 health_before: 100,
@@ -640,8 +1237,8 @@ mod tests {
                 strength: 14,
             },
             defender: CombatantInfo {
-                unit_id: 2,
-                owner_id: 1,
+                unit_id: UnitId(2),
+                owner_id: PlayerSlot(1),
                 owner_name: "Player2".to_string(),
                 unit_type: "Warrior".to_string(),
                 health_before: 100,
@@ -736,16 +1333,39 @@ mod tests {
         assert_eq!(notif.icon, Some("heart".to_string()));
     }
 
+    #[test]
+    fn test_localized_notification_carries_keys() {
+        let notif = NotificationPayload::localized(
+            NotificationType::Diplomacy,
+            LocalizedMessage::new("notify-war-declared-title"),
+            LocalizedMessage::new("notify-war-declared").with_arg("player", 2),
+        );
+
+        assert_eq!(notif.title, "War Declared");
+        assert_eq!(notif.message, "You are now at war with player 2");
+        assert_eq!(notif.duration_ms, Some(7000));
+
+        let json = serde_json::to_value(&notif).unwrap();
+        assert_eq!(json["message_key"]["key"], "notify-war-declared");
+        assert_eq!(json["message_key"]["args"]["player"], "2");
+    }
+
+    #[test]
+    fn test_plain_notification_omits_keys() {
+        let json = serde_json::to_string(&NotificationPayload::info("Hi", "There")).unwrap();
+        assert!(!json.contains("title_key"));
+    }
+
     // =========================================================================
     // Turn Event Tests
     // =========================================================================
 
     #[test]
     fn test_turn_started_event() {
-        let event = TurnEventPayload::turn_started(1, 0, "Alice".to_string(), true);
+        let event = TurnEventPayload::turn_started(1, PlayerSlot(0), "Alice".to_string(), true);
         assert_eq!(event.event_type, TurnEventType::TurnStarted);
         assert_eq!(event.turn, 1);
-        assert_eq!(event.player_id, 0);
+        assert_eq!(event.player_id, PlayerSlot(0));
         assert_eq!(event.player_name, "Alice");
         assert!(event.is_local_player);
         assert_eq!(event.previous_turn, Some(0)); // saturating_sub(1) from turn 1
@@ -753,16 +1373,16 @@ mod tests {
 
     #[test]
     fn test_turn_started_at_turn_zero() {
-        let event = TurnEventPayload::turn_started(0, 0, "Alice".to_string(), true);
+        let event = TurnEventPayload::turn_started(0, PlayerSlot(0), "Alice".to_string(), true);
         assert_eq!(event.previous_turn, Some(0)); // saturating_sub prevents underflow
     }
 
     #[test]
     fn test_turn_ended_event() {
-        let event = TurnEventPayload::turn_ended(10, 2, "Bob".to_string(), false);
+        let event = TurnEventPayload::turn_ended(10, PlayerSlot(2), "Bob".to_string(), false);
         assert_eq!(event.event_type, TurnEventType::TurnEnded);
         assert_eq!(event.turn, 10);
-        assert_eq!(event.player_id, 2);
+        assert_eq!(event.player_id, PlayerSlot(2));
         assert_eq!(event.player_name, "Bob");
         assert!(!event.is_local_player);
         assert!(event.previous_turn.is_none());
@@ -770,15 +1390,38 @@ mod tests {
 
     #[test]
     fn test_player_turn_event() {
-        let event = TurnEventPayload::player_turn(5, 1, "Charlie".to_string(), true);
+        let event = TurnEventPayload::player_turn(5, PlayerSlot(1), "Charlie".to_string(), true);
         assert_eq!(event.event_type, TurnEventType::PlayerTurn);
         assert_eq!(event.turn, 5);
-        assert_eq!(event.player_id, 1);
+        assert_eq!(event.player_id, PlayerSlot(1));
         assert_eq!(event.player_name, "Charlie");
         assert!(event.is_local_player);
         assert!(event.previous_turn.is_none());
     }
 
+    #[test]
+    fn test_player_turn_event_with_treasury() {
+        let event = TurnEventPayload::player_turn(5, PlayerSlot(1), "Charlie".to_string(), true);
+        let json = serde_json::to_string(&event).unwrap();
+        assert!(!json.contains("treasury"));
+
+        let treasury = TreasuryProjection {
+            gold: 10,
+            income: 3,
+            unit_upkeep: 2,
+            building_upkeep: 4,
+            road_upkeep: 1,
+            net: -4,
+            turns_until_bankrupt: Some(3),
+        };
+        let event = event.with_treasury(treasury);
+        let json = serde_json::to_string(&event).unwrap();
+        assert!(json.contains("\"turns_until_bankrupt\":3"));
+
+        let parsed: TurnEventPayload = serde_json::from_str(&json).unwrap();
+        assert_eq!(parsed.treasury, Some(treasury));
+    }
+
     #[test]
     fn test_turn_event_type_serialization() {
         let started = TurnEventType::TurnStarted;
@@ -912,17 +1555,17 @@ mod tests {
     #[test]
     fn test_game_state_partial_update_with_units() {
         let payload = GameStateUpdatedPayload {
-            game_id: "game_001".to_string(),
+            game_id: GameId::new("game_001"),
             phase: "Playing".to_string(),
             turn: 5,
-            current_player: 0,
+            current_player: PlayerSlot(0),
             player_count: 2,
             map_dimensions: (100, 100),
             is_full_update: false,
             changed_units: Some(vec![
                 UnitUpdate {
-                    id: 1,
-                    owner: 0,
+                    id: UnitId(1),
+                    owner: PlayerSlot(0),
                     unit_type: "Warrior".to_string(),
                     position: (10, 20),
                     health: 100,
@@ -930,8 +1573,8 @@ mod tests {
                     is_destroyed: false,
                 },
                 UnitUpdate {
-                    id: 2,
-                    owner: 1,
+                    id: UnitId(2),
+                    owner: PlayerSlot(1),
                     unit_type: "Archer".to_string(),
                     position: (15, 25),
                     health: 50,
@@ -941,6 +1584,7 @@ mod tests {
             ]),
             changed_cities: None,
             changed_tiles: None,
+            changed_territory: None,
         };
 
         assert!(!payload.is_full_update);
@@ -953,23 +1597,24 @@ mod tests {
     #[test]
     fn test_game_state_partial_update_with_cities() {
         let payload = GameStateUpdatedPayload {
-            game_id: "game_002".to_string(),
+            game_id: GameId::new("game_002"),
             phase: "Playing".to_string(),
             turn: 10,
-            current_player: 1,
+            current_player: PlayerSlot(1),
             player_count: 4,
             map_dimensions: (80, 60),
             is_full_update: false,
             changed_units: None,
             changed_cities: Some(vec![CityUpdate {
-                id: 100,
-                owner: 0,
+                id: CityId(100),
+                owner: PlayerSlot(0),
                 name: "New York".to_string(),
                 position: (50, 30),
                 population: 5,
                 health: 200,
             }]),
             changed_tiles: None,
+            changed_territory: None,
         };
 
         assert!(payload.changed_cities.is_some());
@@ -981,10 +1626,10 @@ mod tests {
     #[test]
     fn test_game_state_partial_update_with_tiles() {
         let payload = GameStateUpdatedPayload {
-            game_id: "game_003".to_string(),
+            game_id: GameId::new("game_003"),
             phase: "Playing".to_string(),
             turn: 15,
-            current_player: 2,
+            current_player: PlayerSlot(2),
             player_count: 3,
             map_dimensions: (120, 80),
             is_full_update: false,
@@ -995,15 +1640,16 @@ mod tests {
                     position: (5, 5),
                     improvement: Some("Farm".to_string()),
                     road: None,
-                    owner: Some(0),
+                    owner: Some(PlayerSlot(0)),
                 },
                 TileUpdate {
                     position: (6, 5),
                     improvement: None,
                     road: Some("Road".to_string()),
-                    owner: Some(0),
+                    owner: Some(PlayerSlot(0)),
                 },
             ]),
+            changed_territory: None,
         };
 
         assert!(payload.changed_tiles.is_some());
@@ -1016,16 +1662,17 @@ mod tests {
     #[test]
     fn test_game_state_full_update() {
         let payload = GameStateUpdatedPayload {
-            game_id: "game_full".to_string(),
+            game_id: GameId::new("game_full"),
             phase: "Setup".to_string(),
             turn: 0,
-            current_player: 0,
+            current_player: PlayerSlot(0),
             player_count: 2,
             map_dimensions: (50, 50),
             is_full_update: true,
             changed_units: None,
             changed_cities: None,
             changed_tiles: None,
+            changed_territory: None,
         };
 
         assert!(payload.is_full_update);
@@ -1053,7 +1700,8 @@ mod tests {
 
     #[test]
     fn test_turn_event_payload_roundtrip() {
-        let original = TurnEventPayload::turn_started(42, 3, "TestPlayer".to_string(), false);
+        let original =
+            TurnEventPayload::turn_started(42, PlayerSlot(3), "TestPlayer".to_string(), false);
 
         let json = serde_json::to_string(&original).unwrap();
         let deserialized: TurnEventPayload = serde_json::from_str(&json).unwrap();
@@ -1086,16 +1734,16 @@ mod tests {
     #[test]
     fn test_game_state_payload_roundtrip() {
         let original = GameStateUpdatedPayload {
-            game_id: "roundtrip_game".to_string(),
+            game_id: GameId::new("roundtrip_game"),
             phase: "Playing".to_string(),
             turn: 25,
-            current_player: 1,
+            current_player: PlayerSlot(1),
             player_count: 4,
             map_dimensions: (100, 80),
             is_full_update: false,
             changed_units: Some(vec![UnitUpdate {
-                id: 999,
-                owner: 2,
+                id: UnitId(999),
+                owner: PlayerSlot(2),
                 unit_type: "Knight".to_string(),
                 position: (33, 44),
                 health: 75,
@@ -1107,8 +1755,9 @@ mod tests {
                 position: (10, 10),
                 improvement: Some("Mine".to_string()),
                 road: Some("Railroad".to_string()),
-                owner: Some(2),
+                owner: Some(PlayerSlot(2)),
             }]),
+            changed_territory: None,
         };
 
         let json = serde_json::to_string(&original).unwrap();
@@ -1116,7 +1765,10 @@ mod tests {
 
         assert_eq!(deserialized.game_id, original.game_id);
         assert_eq!(deserialized.turn, original.turn);
-        assert_eq!(deserialized.changed_units.as_ref().unwrap()[0].id, 999);
+        assert_eq!(
+            deserialized.changed_units.as_ref().unwrap()[0].id,
+            UnitId(999)
+        );
         assert_eq!(
             deserialized.changed_tiles.as_ref().unwrap()[0].improvement,
             Some("Mine".to_string())
@@ -1127,8 +1779,8 @@ mod tests {
     fn test_combat_resolved_payload_roundtrip() {
         let original = CombatResolvedPayload {
             attacker: CombatantInfo {
-                unit_id: 10,
-                owner_id: 0,
+                unit_id: UnitId(10),
+                owner_id: PlayerSlot(0),
                 owner_name: "Attacker".to_string(),
                 unit_type: "Tank".to_string(),
                 health_before: 100,
@@ -1136,8 +1788,8 @@ mod tests {
                 strength: 50,
             },
             defender: CombatantInfo {
-                unit_id: 20,
-                owner_id: 1,
+                unit_id: UnitId(20),
+                owner_id: PlayerSlot(1),
                 owner_name: "Defender".to_string(),
                 unit_type: "Infantry".to_string(),
                 health_before: 100,
@@ -1196,10 +1848,14 @@ mod tests {
 
     #[test]
     fn test_turn_event_max_values() {
-        let event =
-            TurnEventPayload::turn_started(u32::MAX, u8::MAX, "MaxPlayer".to_string(), true);
+        let event = TurnEventPayload::turn_started(
+            u32::MAX,
+            PlayerSlot(u8::MAX),
+            "MaxPlayer".to_string(),
+            true,
+        );
         assert_eq!(event.turn, u32::MAX);
-        assert_eq!(event.player_id, u8::MAX);
+        assert_eq!(event.player_id, PlayerSlot(u8::MAX));
         assert_eq!(event.previous_turn, Some(u32::MAX - 1));
     }
 
@@ -1218,8 +1874,8 @@ mod tests {
     #[test]
     fn test_unit_update_destroyed_state() {
         let unit = UnitUpdate {
-            id: 1,
-            owner: 0,
+            id: UnitId(1),
+            owner: PlayerSlot(0),
             unit_type: "Warrior".to_string(),
             position: (0, 0),
             health: 0,
@@ -1250,16 +1906,17 @@ mod tests {
     #[test]
     fn test_game_state_zero_dimensions() {
         let payload = GameStateUpdatedPayload {
-            game_id: "zero_dim".to_string(),
+            game_id: GameId::new("zero_dim"),
             phase: "Setup".to_string(),
             turn: 0,
-            current_player: 0,
+            current_player: PlayerSlot(0),
             player_count: 0,
             map_dimensions: (0, 0),
             is_full_update: true,
             changed_units: None,
             changed_cities: None,
             changed_tiles: None,
+            changed_territory: None,
         };
 
         assert_eq!(payload.map_dimensions, (0, 0));
@@ -1269,8 +1926,8 @@ mod tests {
     #[test]
     fn test_city_update_zero_population() {
         let city = CityUpdate {
-            id: 1,
-            owner: 0,
+            id: CityId(1),
+            owner: PlayerSlot(0),
             name: "Ghost Town".to_string(),
             position: (25, 25),
             population: 0,
@@ -1340,6 +1997,8 @@ mod tests {
     fn test_notification_with_action() {
         let payload = NotificationPayload {
             notification_type: NotificationType::Combat,
+            title_key: None,
+            message_key: None,
             title: "Battle Won".to_string(),
             message: "Your warrior defeated an enemy".to_string(),
             icon: Some("sword".to_string()),
@@ -1359,16 +2018,17 @@ mod tests {
     #[test]
     fn test_skip_serializing_none_fields() {
         let payload = GameStateUpdatedPayload {
-            game_id: "test".to_string(),
+            game_id: GameId::new("test"),
             phase: "Playing".to_string(),
             turn: 1,
-            current_player: 0,
+            current_player: PlayerSlot(0),
             player_count: 2,
             map_dimensions: (10, 10),
             is_full_update: true,
             changed_units: None,
             changed_cities: None,
             changed_tiles: None,
+            changed_territory: None,
         };
 
         let json = serde_json::to_string(&payload).unwrap();
@@ -1392,8 +2052,8 @@ mod tests {
     #[test]
     fn test_combatant_info_zero_strength() {
         let combatant = CombatantInfo {
-            unit_id: 1,
-            owner_id: 0,
+            unit_id: UnitId(1),
+            owner_id: PlayerSlot(0),
             owner_name: "Test".to_string(),
             unit_type: "Scout".to_string(),
             health_before: 100,
@@ -1421,8 +2081,8 @@ mod tests {
     #[test]
     fn test_negative_positions() {
         let unit = UnitUpdate {
-            id: 1,
-            owner: 0,
+            id: UnitId(1),
+            owner: PlayerSlot(0),
             unit_type: "Warrior".to_string(),
             position: (-10, -20),
             health: 100,
@@ -1448,8 +2108,8 @@ mod tests {
     fn test_create_event_from_unit_movement() {
         // Simulate a unit moving from one position to another
         let unit_before_move = UnitUpdate {
-            id: 42,
-            owner: 0,
+            id: UnitId(42),
+            owner: PlayerSlot(0),
             unit_type: "Warrior".to_string(),
             position: (10, 10),
             health: 100,
@@ -1459,8 +2119,8 @@ mod tests {
 
         // After movement
         let unit_after_move = UnitUpdate {
-            id: 42,
-            owner: 0,
+            id: UnitId(42),
+            owner: PlayerSlot(0),
             unit_type: "Warrior".to_string(),
             position: (11, 10),
             health: 100,
@@ -1469,16 +2129,17 @@ mod tests {
         };
 
         let payload = GameStateUpdatedPayload {
-            game_id: "movement_test".to_string(),
+            game_id: GameId::new("movement_test"),
             phase: "Playing".to_string(),
             turn: 5,
-            current_player: 0,
+            current_player: PlayerSlot(0),
             player_count: 2,
             map_dimensions: (50, 50),
             is_full_update: false,
             changed_units: Some(vec![unit_after_move.clone()]),
             changed_cities: None,
             changed_tiles: None,
+            changed_territory: None,
         };
 
         assert!(payload.changed_units.is_some());
@@ -1495,8 +2156,8 @@ mod tests {
     fn test_create_event_from_city_growth() {
         // Simulate city population growth
         let city_before = CityUpdate {
-            id: 1,
-            owner: 0,
+            id: CityId(1),
+            owner: PlayerSlot(0),
             name: "Rome".to_string(),
             position: (25, 25),
             population: 3,
@@ -1504,8 +2165,8 @@ mod tests {
         };
 
         let city_after = CityUpdate {
-            id: 1,
-            owner: 0,
+            id: CityId(1),
+            owner: PlayerSlot(0),
             name: "Rome".to_string(),
             position: (25, 25),
             population: 4, // Grew by 1
@@ -1513,16 +2174,17 @@ mod tests {
         };
 
         let payload = GameStateUpdatedPayload {
-            game_id: "growth_test".to_string(),
+            game_id: GameId::new("growth_test"),
             phase: "Playing".to_string(),
             turn: 10,
-            current_player: 0,
+            current_player: PlayerSlot(0),
             player_count: 2,
             map_dimensions: (50, 50),
             is_full_update: false,
             changed_units: None,
             changed_cities: Some(vec![city_after.clone()]),
             changed_tiles: None,
+            changed_territory: None,
         };
 
         assert!(payload.changed_cities.is_some());
@@ -1537,27 +2199,28 @@ mod tests {
             position: (15, 20),
             improvement: None,
             road: None,
-            owner: Some(0),
+            owner: Some(PlayerSlot(0)),
         };
 
         let tile_after = TileUpdate {
             position: (15, 20),
             improvement: Some("Farm".to_string()),
             road: None,
-            owner: Some(0),
+            owner: Some(PlayerSlot(0)),
         };
 
         let payload = GameStateUpdatedPayload {
-            game_id: "improvement_test".to_string(),
+            game_id: GameId::new("improvement_test"),
             phase: "Playing".to_string(),
             turn: 8,
-            current_player: 0,
+            current_player: PlayerSlot(0),
             player_count: 2,
             map_dimensions: (50, 50),
             is_full_update: false,
             changed_units: None,
             changed_cities: None,
             changed_tiles: Some(vec![tile_after.clone()]),
+            changed_territory: None,
         };
 
         assert!(payload.changed_tiles.is_some());
@@ -1576,8 +2239,8 @@ mod tests {
 
         let payload = CombatResolvedPayload {
             attacker: CombatantInfo {
-                unit_id: 100,
-                owner_id: 0,
+                unit_id: UnitId(100),
+                owner_id: PlayerSlot(0),
                 owner_name: "Player 1".to_string(),
                 unit_type: "Swordsman".to_string(),
                 health_before: 100,
@@ -1585,8 +2248,8 @@ mod tests {
                 strength: 14,
             },
             defender: CombatantInfo {
-                unit_id: 200,
-                owner_id: 1,
+                unit_id: UnitId(200),
+                owner_id: PlayerSlot(1),
                 owner_name: "Player 2".to_string(),
                 unit_type: "Warrior".to_string(),
                 health_before: 100,
@@ -1628,9 +2291,11 @@ mod tests {
         let player_name = "Alice".to_string();
 
         let turn_started =
-            TurnEventPayload::turn_started(turn, player_id, player_name.clone(), true);
-        let player_turn = TurnEventPayload::player_turn(turn, player_id, player_name.clone(), true);
-        let turn_ended = TurnEventPayload::turn_ended(turn, player_id, player_name.clone(), true);
+            TurnEventPayload::turn_started(turn, PlayerSlot(player_id), player_name.clone(), true);
+        let player_turn =
+            TurnEventPayload::player_turn(turn, PlayerSlot(player_id), player_name.clone(), true);
+        let turn_ended =
+            TurnEventPayload::turn_ended(turn, PlayerSlot(player_id), player_name.clone(), true);
 
         // Verify event sequence
         assert_eq!(turn_started.event_type, TurnEventType::TurnStarted);
@@ -1643,9 +2308,9 @@ mod tests {
         assert_eq!(turn_ended.turn, turn);
 
         // All events should reference same player
-        assert_eq!(turn_started.player_id, player_id);
-        assert_eq!(player_turn.player_id, player_id);
-        assert_eq!(turn_ended.player_id, player_id);
+        assert_eq!(turn_started.player_id, PlayerSlot(player_id));
+        assert_eq!(player_turn.player_id, PlayerSlot(player_id));
+        assert_eq!(turn_ended.player_id, PlayerSlot(player_id));
     }
 
     #[test]
@@ -1683,6 +2348,8 @@ mod tests {
     fn test_notification_achievement_type() {
         let payload = NotificationPayload {
             notification_type: NotificationType::Achievement,
+            title_key: None,
+            message_key: None,
             title: "First Victory".to_string(),
             message: "Won your first battle!".to_string(),
             icon: Some("trophy".to_string()),
@@ -1699,6 +2366,8 @@ mod tests {
     fn test_notification_diplomacy_type() {
         let payload = NotificationPayload {
             notification_type: NotificationType::Diplomacy,
+            title_key: None,
+            message_key: None,
             title: "War Declared".to_string(),
             message: "Rome has declared war on you!".to_string(),
             icon: Some("war".to_string()),
@@ -1718,6 +2387,8 @@ mod tests {
     fn test_notification_research_type() {
         let payload = NotificationPayload {
             notification_type: NotificationType::Research,
+            title_key: None,
+            message_key: None,
             title: "Research Complete".to_string(),
             message: "You have discovered Bronze Working!".to_string(),
             icon: Some("science".to_string()),
@@ -1736,6 +2407,8 @@ mod tests {
     fn test_notification_production_type() {
         let payload = NotificationPayload {
             notification_type: NotificationType::Production,
+            title_key: None,
+            message_key: None,
             title: "Production Complete".to_string(),
             message: "Rome has finished building a Warrior".to_string(),
             icon: Some("hammer".to_string()),
@@ -1754,6 +2427,8 @@ mod tests {
     fn test_notification_combat_type() {
         let payload = NotificationPayload {
             notification_type: NotificationType::Combat,
+            title_key: None,
+            message_key: None,
             title: "Unit Attacked".to_string(),
             message: "Your Warrior was attacked by an enemy Archer".to_string(),
             icon: Some("sword".to_string()),
@@ -1781,8 +2456,8 @@ mod tests {
 
         let payload = CombatResolvedPayload {
             attacker: CombatantInfo {
-                unit_id: 1,
-                owner_id: 0,
+                unit_id: UnitId(1),
+                owner_id: PlayerSlot(0),
                 owner_name: "Test".to_string(),
                 unit_type: "Warrior".to_string(),
                 health_before: 100,
@@ -1790,8 +2465,8 @@ mod tests {
                 strength: 8,
             },
             defender: CombatantInfo {
-                unit_id: 2,
-                owner_id: 1,
+                unit_id: UnitId(2),
+                owner_id: PlayerSlot(1),
                 owner_name: "Enemy".to_string(),
                 unit_type: "Warrior".to_string(),
                 health_before: 100,
@@ -1820,8 +2495,8 @@ mod tests {
     fn test_timestamp_serialization() {
         let payload = CombatResolvedPayload {
             attacker: CombatantInfo {
-                unit_id: 1,
-                owner_id: 0,
+                unit_id: UnitId(1),
+                owner_id: PlayerSlot(0),
                 owner_name: "A".to_string(),
                 unit_type: "W".to_string(),
                 health_before: 100,
@@ -1829,8 +2504,8 @@ mod tests {
                 strength: 1,
             },
             defender: CombatantInfo {
-                unit_id: 2,
-                owner_id: 1,
+                unit_id: UnitId(2),
+                owner_id: PlayerSlot(1),
                 owner_name: "B".to_string(),
                 unit_type: "W".to_string(),
                 health_before: 100,
@@ -1866,8 +2541,8 @@ mod tests {
         // Test creating multiple unit updates from a battle involving several units
         let units = vec![
             UnitUpdate {
-                id: 1,
-                owner: 0,
+                id: UnitId(1),
+                owner: PlayerSlot(0),
                 unit_type: "Swordsman".to_string(),
                 position: (10, 10),
                 health: 60,
@@ -1875,8 +2550,8 @@ mod tests {
                 is_destroyed: false,
             },
             UnitUpdate {
-                id: 2,
-                owner: 1,
+                id: UnitId(2),
+                owner: PlayerSlot(1),
                 unit_type: "Warrior".to_string(),
                 position: (10, 11),
                 health: 0,
@@ -1884,8 +2559,8 @@ mod tests {
                 is_destroyed: true,
             },
             UnitUpdate {
-                id: 3,
-                owner: 0,
+                id: UnitId(3),
+                owner: PlayerSlot(0),
                 unit_type: "Archer".to_string(),
                 position: (9, 10),
                 health: 100,
@@ -1895,16 +2570,17 @@ mod tests {
         ];
 
         let payload = GameStateUpdatedPayload {
-            game_id: "battle_test".to_string(),
+            game_id: GameId::new("battle_test"),
             phase: "Playing".to_string(),
             turn: 15,
-            current_player: 0,
+            current_player: PlayerSlot(0),
             player_count: 2,
             map_dimensions: (50, 50),
             is_full_update: false,
             changed_units: Some(units),
             changed_cities: None,
             changed_tiles: None,
+            changed_territory: None,
         };
 
         let changed = payload.changed_units.unwrap();
@@ -1913,17 +2589,17 @@ mod tests {
         // Verify destroyed unit
         let destroyed = changed.iter().find(|u| u.is_destroyed).unwrap();
         assert_eq!(destroyed.health, 0);
-        assert_eq!(destroyed.owner, 1);
+        assert_eq!(destroyed.owner, PlayerSlot(1));
     }
 
     #[test]
     fn test_city_capture_events() {
         // Test events generated when a city is captured
-        let original_owner = 1;
-        let new_owner = 0;
+        let original_owner = PlayerSlot(1);
+        let new_owner = PlayerSlot(0);
 
         let city_update = CityUpdate {
-            id: 5,
+            id: CityId(5),
             owner: new_owner,
             name: "Conquered City".to_string(),
             position: (30, 30),
@@ -1932,7 +2608,7 @@ mod tests {
         };
 
         let payload = GameStateUpdatedPayload {
-            game_id: "capture_test".to_string(),
+            game_id: GameId::new("capture_test"),
             phase: "Playing".to_string(),
             turn: 20,
             current_player: new_owner,
@@ -1942,6 +2618,7 @@ mod tests {
             changed_units: None,
             changed_cities: Some(vec![city_update]),
             changed_tiles: None,
+            changed_territory: None,
         };
 
         let cities = payload.changed_cities.unwrap();
@@ -1953,16 +2630,16 @@ mod tests {
     fn test_combined_updates_payload() {
         // Test a complex payload with all types of changes
         let payload = GameStateUpdatedPayload {
-            game_id: "complex_test".to_string(),
+            game_id: GameId::new("complex_test"),
             phase: "Playing".to_string(),
             turn: 25,
-            current_player: 0,
+            current_player: PlayerSlot(0),
             player_count: 4,
             map_dimensions: (80, 60),
             is_full_update: false,
             changed_units: Some(vec![UnitUpdate {
-                id: 1,
-                owner: 0,
+                id: UnitId(1),
+                owner: PlayerSlot(0),
                 unit_type: "Settler".to_string(),
                 position: (40, 30),
                 health: 100,
@@ -1970,8 +2647,8 @@ mod tests {
                 is_destroyed: true, // Settler consumed to build city
             }]),
             changed_cities: Some(vec![CityUpdate {
-                id: 10,
-                owner: 0,
+                id: CityId(10),
+                owner: PlayerSlot(0),
                 name: "New City".to_string(),
                 position: (40, 30),
                 population: 1,
@@ -1982,21 +2659,22 @@ mod tests {
                     position: (40, 30),
                     improvement: None,
                     road: None,
-                    owner: Some(0),
+                    owner: Some(PlayerSlot(0)),
                 },
                 TileUpdate {
                     position: (39, 30),
                     improvement: None,
                     road: None,
-                    owner: Some(0), // City territory expanded
+                    owner: Some(PlayerSlot(0)), // City territory expanded
                 },
                 TileUpdate {
                     position: (41, 30),
                     improvement: None,
                     road: None,
-                    owner: Some(0),
+                    owner: Some(PlayerSlot(0)),
                 },
             ]),
+            changed_territory: None,
         };
 
         // Verify all components present
@@ -2046,28 +2724,29 @@ mod tests {
     fn test_all_event_types_produce_valid_json() {
         // GameStateUpdatedPayload
         let game_state = GameStateUpdatedPayload {
-            game_id: "test".to_string(),
+            game_id: GameId::new("test"),
             phase: "Playing".to_string(),
             turn: 1,
-            current_player: 0,
+            current_player: PlayerSlot(0),
             player_count: 2,
             map_dimensions: (10, 10),
             is_full_update: true,
             changed_units: None,
             changed_cities: None,
             changed_tiles: None,
+            changed_territory: None,
         };
         assert!(serde_json::to_string(&game_state).is_ok());
 
         // TurnEventPayload
-        let turn = TurnEventPayload::turn_started(1, 0, "Test".to_string(), true);
+        let turn = TurnEventPayload::turn_started(1, PlayerSlot(0), "Test".to_string(), true);
         assert!(serde_json::to_string(&turn).is_ok());
 
         // CombatResolvedPayload
         let combat = CombatResolvedPayload {
             attacker: CombatantInfo {
-                unit_id: 1,
-                owner_id: 0,
+                unit_id: UnitId(1),
+                owner_id: PlayerSlot(0),
                 owner_name: "A".to_string(),
                 unit_type: "W".to_string(),
                 health_before: 100,
@@ -2075,8 +2754,8 @@ mod tests {
                 strength: 8,
             },
             defender: CombatantInfo {
-                unit_id: 2,
-                owner_id: 1,
+                unit_id: UnitId(2),
+                owner_id: PlayerSlot(1),
                 owner_name: "B".to_string(),
                 unit_type: "W".to_string(),
                 health_before: 100,
@@ -2158,7 +2837,7 @@ mod tests {
         assert!(deserialized.message.contains("Nmes"));
 
         let player_name = "".to_string();
-        let turn = TurnEventPayload::turn_started(1, 0, player_name.clone(), true);
+        let turn = TurnEventPayload::turn_started(1, PlayerSlot(0), player_name.clone(), true);
         let json = serde_json::to_string(&turn).unwrap();
         let deserialized: TurnEventPayload = serde_json::from_str(&json).unwrap();
         assert_eq!(deserialized.player_name, player_name);
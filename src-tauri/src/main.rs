@@ -6,17 +6,116 @@
 // Prevents additional console window on Windows in release
 #![cfg_attr(not(debug_assertions), windows_subsystem = "windows")]
 
+mod bindings;
 mod commands;
+mod error;
 pub mod events;
 mod state;
+mod worker;
 
+use events::NetworkEventPayload;
+use nostr_nations_network::StorageLayout;
 use state::AppState;
 use std::sync::Mutex;
+use tauri::{AppHandle, Manager, RunEvent, WindowEvent};
+
+/// Flush queued actions and the game to disk before the OS suspends us.
+fn suspend(app_handle: &AppHandle) {
+    let state = app_handle.state::<Mutex<AppState>>();
+    let Ok(mut state) = state.lock() else {
+        return;
+    };
+    if let Err(e) = state.suspend() {
+        tracing::warn!(error = %e, "failed to flush state on suspend");
+    }
+}
+
+/// Restore state after returning to the foreground and tell the frontend
+/// to re-check connections if they may have gone stale.
+///
+/// Restoring reads every game's queued actions from disk, so it runs on
+/// the engine worker once that is started. Worker jobs run one at a time,
+/// so no game is checked out to another job meanwhile.
+fn resume(app_handle: &AppHandle) {
+    let submitted = worker::engine_worker(app_handle)
+        .and_then(|worker| worker.submit(Box::new(restore_on_resume)));
+    if submitted.is_err() {
+        restore_on_resume(app_handle);
+    }
+}
+
+fn restore_on_resume(app_handle: &AppHandle) {
+    let state = app_handle.state::<Mutex<AppState>>();
+    let Ok(mut state) = state.lock() else {
+        return;
+    };
+    match state.resume() {
+        Ok(Some(plan)) if plan.revalidate_connections => {
+            let _ = events::emit_network_event(
+                app_handle,
+                NetworkEventPayload::connections_stale(state.connected_peers()),
+            );
+        }
+        Ok(_) => {}
+        Err(e) => tracing::warn!(error = %e, "failed to restore state on resume"),
+    }
+}
 
 fn main() {
+    // `--export-bindings <dir>` writes the frontend's TypeScript types and
+    // exits without starting the app
+    let args: Vec<String> = std::env::args().skip(1).collect();
+    if let [flag, out_dir] = args.as_slice() {
+        if flag == bindings::EXPORT_FLAG {
+            match bindings::export_bindings(std::path::Path::new(out_dir)) {
+                Ok(count) => println!("Wrote {} TypeScript bindings to {}", count, out_dir),
+                Err(e) => {
+                    eprintln!("Failed to export TypeScript bindings: {}", e);
+                    std::process::exit(1);
+                }
+            }
+            return;
+        }
+    }
+
+    let state = AppState::new();
+    // Ignore failure if a subscriber is already installed
+    let _ = state.debug.install_global();
+
+    let event_log = state.events.clone();
+
     tauri::Builder::default()
         .plugin(tauri_plugin_shell::init())
-        .manage(Mutex::new(AppState::new()))
+        .manage(event_log)
+        .manage(Mutex::new(state))
+        .setup(|app| {
+            // Everything lives in the platform's sandboxed app directories,
+            // which are the only writable locations on iOS and Android
+            let layout =
+                StorageLayout::new(app.path().app_data_dir()?, app.path().app_cache_dir()?);
+            layout.prepare()?;
+            let worker = worker::EngineWorker::spawn(app.handle().clone())?;
+            let state = app.state::<Mutex<AppState>>();
+            if let Ok(mut state) = state.lock() {
+                state.storage = Some(layout);
+                state.worker = Some(worker);
+                commands::settings::load_settings(&mut state)?;
+            }
+            resume(app.handle());
+            Ok(())
+        })
+        .on_window_event(|window, event| {
+            // In background mode, hide instead of closing so pitboss
+            // turn notifications can still reach the player
+            if let WindowEvent::CloseRequested { api, .. } = event {
+                let state = window.state::<Mutex<AppState>>();
+                let background = state.lock().map(|s| s.background_mode).unwrap_or(false);
+                if background {
+                    api.prevent_close();
+                    let _ = window.hide();
+                }
+            }
+        })
         .invoke_handler(tauri::generate_handler![
             commands::game::create_game,
             commands::game::join_game,
@@ -24,20 +123,107 @@ fn main() {
             commands::game::get_game_state,
             commands::game::end_game,
             commands::game::end_turn,
+            commands::game::ai_tick,
+            commands::game::get_turn_schedule,
+            commands::game::concede,
+            commands::game::substitute_player,
+            commands::game::reveal_map,
+            commands::game::request_pause,
+            commands::game::vote_pause,
+            commands::game::resume_game,
+            commands::game::get_pause_state,
+            commands::game::get_demographics,
+            commands::game::get_turn_digest,
+            commands::game::get_event_log,
+            commands::game::get_pending_items,
+            commands::game::next_pending_item,
+            commands::game::get_production_advice,
+            commands::game::get_victory_proof,
+            commands::game::list_active_games,
+            commands::game::switch_game,
+            commands::capture::capture_map,
+            commands::capture::get_timelapse,
             commands::actions::move_unit,
+            commands::actions::preview_attack,
             commands::actions::attack_unit,
+            commands::actions::bombard_unit,
+            commands::actions::attack_city,
+            commands::actions::resolve_capture,
+            commands::actions::gift_unit,
             commands::actions::found_city,
+            commands::actions::buy_tile,
+            commands::actions::get_promotion_options,
+            commands::actions::choose_promotion,
             commands::actions::build_improvement,
+            commands::actions::build_road,
             commands::actions::set_research,
+            commands::actions::queue_research,
+            commands::actions::set_research_queue,
+            commands::actions::validate_action,
+            commands::actions::undo_action,
+            commands::actions::redo_action,
+            commands::actions::set_action_buffering,
+            commands::diplomacy::declare_war,
+            commands::diplomacy::propose_peace,
+            commands::diplomacy::propose_treaty,
+            commands::diplomacy::send_trade_offer,
+            commands::diplomacy::respond_trade_offer,
+            commands::locale::get_message_catalog,
+            commands::locale::load_message_catalog,
+            commands::locale::set_locale,
+            commands::locale::suggest_city_name,
             commands::network::connect_peer,
             commands::network::disconnect_peer,
             commands::network::get_connection_ticket,
             commands::network::scan_qr_code,
+            commands::network::go_offline,
+            commands::network::get_offline_status,
+            commands::network::reconnect,
+            commands::network::set_presence,
+            commands::network::receive_presence,
+            commands::network::get_presence,
+            commands::network::get_network_debug_report,
+            commands::network::get_events_since,
+            commands::pitboss::set_background_mode,
+            commands::pitboss::get_pitboss_status,
+            commands::pitboss::queue_offline_turn,
+            commands::pitboss::take_offline_turns,
+            commands::pitboss::receive_turn_notification,
             commands::saves::list_saved_games,
             commands::saves::load_game,
             commands::saves::save_game,
             commands::saves::delete_saved_game,
+            commands::saves::get_storage_encryption_status,
+            commands::saves::unlock_storage,
+            commands::game_index::get_game_index_query,
+            commands::game_index::list_resumable_games,
+            commands::game_index::index_relay_events,
+            commands::game_index::resume_from_relay,
+            commands::sandbox::fork_game,
+            commands::schema::get_json_schemas,
+            commands::schema::get_schema_docs,
+            commands::settings::get_settings,
+            commands::settings::set_profile,
+            commands::settings::set_preferences,
+            commands::tournament::create_tournament,
+            commands::tournament::join_tournament,
+            commands::tournament::start_tournament,
+            commands::tournament::report_match_result,
+            commands::tournament::get_tournament_bracket,
+            commands::tournament::list_tournaments,
         ])
-        .run(tauri::generate_context!())
-        .expect("error while running tauri application");
+        .build(tauri::generate_context!())
+        .expect("error while building tauri application")
+        .run(|app_handle, event| match event {
+            // Mobile apps are backgrounded by losing focus and may be killed
+            // without any further event
+            #[cfg(mobile)]
+            RunEvent::WindowEvent {
+                event: WindowEvent::Focused(false),
+                ..
+            } => suspend(app_handle),
+            RunEvent::ExitRequested { .. } => suspend(app_handle),
+            RunEvent::Resumed => resume(app_handle),
+            _ => {}
+        });
 }
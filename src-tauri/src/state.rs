@@ -3,78 +3,493 @@
 //! This module manages the global application state that is shared
 //! across all Tauri commands.
 
-use nostr_nations_core::{GameEngine, GameSettings, GameState};
-use std::collections::HashMap;
+use crate::commands::capture::TimelapseInfo;
+use crate::error::AppError;
+use crate::events::EventLog;
+use crate::worker::EngineWorker;
+use nostr_nations_core::audit::ruleset_hash;
+use nostr_nations_core::{
+    AiBudget, AiPlanner, GameEngine, GameId, GameSettings, GameState, Localizer, PlayerSlot,
+    TurnSchedule, TurnTimes,
+};
+use nostr_nations_network::{
+    AtRestKey, CancellationToken, Capabilities, ConnectionMonitor, DebugRecorder, GameIndex,
+    LifecycleConfig, LocalRelay, OfflineManager, OfflineStorage, OfflineTurnQueue, PeerId,
+    PeerManager, PresenceMap, ResumePlan, StorageLayout, Tournament,
+};
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+use std::collections::{HashMap, HashSet};
+use std::sync::Arc;
+use ts_rs::TS;
+
+/// One game this client is playing.
+pub struct GameSession {
+    /// The game engine.
+    pub engine: GameEngine,
+    /// Connectivity state and actions queued while disconnected.
+    pub offline: OfflineManager,
+    /// Connections to this game's peers.
+    pub network: PeerManager,
+    /// Peers connected through `network`, kept in step with it so the
+    /// count can be read without awaiting.
+    pub peer_count: usize,
+    /// Pitboss turns played while disconnected from the host.
+    pub offline_turns: OfflineTurnQueue,
+    /// Save slot this game is written to.
+    pub save_slot: String,
+    /// Latest presence announced by each player.
+    pub presence: PresenceMap,
+    /// How long each player takes per turn.
+    pub turn_times: TurnTimes,
+    /// Partial turn plans for AI seats, by player.
+    pub ai_planners: HashMap<PlayerSlot, AiPlanner>,
+    /// Cancelled when the game ends; network work for the game should use
+    /// a child of this token.
+    pub cancel: CancellationToken,
+    /// Turn captures taken for the timelapse.
+    pub timelapse: TimelapseInfo,
+}
+
+impl GameSession {
+    /// Create a session hosting a game created or loaded on this client,
+    /// saving to a slot named after the game.
+    pub fn new(engine: GameEngine, node_id: PeerId) -> Self {
+        let capabilities =
+            Capabilities::default().with_ruleset_hash(ruleset_hash(&engine.state.settings));
+        let network = PeerManager::new(node_id, engine.state.id.clone(), true)
+            .with_capabilities(capabilities);
+        Self::with_network(engine, network)
+    }
+
+    /// Create a session for joining a game hosted by a peer.
+    ///
+    /// The engine starts from an empty game in setup until the host's state
+    /// arrives. No ruleset is advertised yet, so any host's ruleset is
+    /// accepted.
+    pub fn join(game_id: GameId, node_id: PeerId) -> Self {
+        let seed = [0u8; 32];
+        let engine = GameEngine::from_state(
+            GameState::new(game_id.clone(), GameSettings::default(), seed),
+            seed,
+        );
+        let network = PeerManager::new(node_id, game_id, false);
+        Self::with_network(engine, network)
+    }
+
+    fn with_network(engine: GameEngine, network: PeerManager) -> Self {
+        let save_slot = format!("save-{}", engine.state.id);
+        let mut turn_times = TurnTimes::from_events(engine.events.events());
+        turn_times.start_turn(unix_now());
+        // Peers are refused game state until a redaction gate is installed
+        tauri::async_runtime::block_on(network.update_redaction(&engine.state));
+        Self {
+            engine,
+            offline: OfflineManager::new(),
+            network,
+            peer_count: 0,
+            offline_turns: OfflineTurnQueue::new(),
+            save_slot,
+            presence: PresenceMap::default(),
+            turn_times,
+            ai_planners: HashMap::new(),
+            cancel: CancellationToken::new(),
+            timelapse: TimelapseInfo::default(),
+        }
+    }
+
+    /// Record that a player ended their turn now.
+    pub fn record_turn_end(&mut self, player_id: PlayerSlot) {
+        self.turn_times.end_turn(player_id, unix_now());
+    }
+
+    /// Get the turn order and expected wait times.
+    pub fn turn_schedule(&self) -> TurnSchedule {
+        TurnSchedule::build(&self.engine.state, &self.turn_times, unix_now())
+    }
+
+    /// Refresh what peers may see after the game state changed.
+    ///
+    /// Blocks on the peer manager, like [`add_peer`](Self::add_peer).
+    pub fn refresh_redaction(&self) {
+        tauri::async_runtime::block_on(self.network.update_redaction(&self.engine.state));
+    }
+
+    /// Add a peer.
+    ///
+    /// Blocks on the peer manager, so call this on the engine worker with a
+    /// checked-out session rather than while holding the app state lock.
+    pub fn add_peer(&mut self, peer_id: PeerId) {
+        tauri::async_runtime::block_on(self.network.add_peer(peer_id));
+        self.sync_peers();
+    }
+
+    /// Remove a peer. Returns false if it wasn't connected.
+    ///
+    /// Blocks on the peer manager, like [`add_peer`](Self::add_peer).
+    pub fn remove_peer(&mut self, peer_id: &str) -> bool {
+        let network = &self.network;
+        let removed = tauri::async_runtime::block_on(async {
+            if network.get_peer(peer_id).await.is_none() {
+                return false;
+            }
+            network
+                .remove_peer(peer_id, "Disconnected".to_string())
+                .await;
+            true
+        });
+        self.sync_peers();
+        removed
+    }
+
+    /// Refresh the peer count after a connection change.
+    fn sync_peers(&mut self) {
+        // Commands report connection changes themselves; drop the manager's
+        // copies so its channel never fills
+        while self.network.try_recv_event().is_some() {}
+        self.peer_count = tauri::async_runtime::block_on(self.network.peer_count());
+    }
+}
 
 /// Main application state.
 #[allow(dead_code)]
 pub struct AppState {
-    /// Current game engine (if a game is active).
-    pub game_engine: Option<GameEngine>,
-    /// Network peer count (simplified for now).
-    pub peer_count: usize,
+    /// Games in progress, keyed by game ID.
+    pub sessions: HashMap<GameId, GameSession>,
+    /// Games whose session is checked out to the engine worker.
+    busy: HashSet<GameId>,
+    /// Node ID this client announces to peers, unique to this launch until
+    /// the transport assigns one.
+    pub node_id: PeerId,
+    /// The game currently shown in the UI.
+    pub active_game: Option<GameId>,
     /// Saved games list.
     pub saved_games: HashMap<String, String>,
     /// User preferences.
     pub preferences: Preferences,
+    /// Player identity and defaults for new games.
+    pub profile: UserProfile,
+    /// Tournaments this client is organizing or playing in.
+    pub tournaments: HashMap<String, Tournament>,
+    /// Keep running in the background when the window is closed.
+    pub background_mode: bool,
+    /// Health of peer and relay connections.
+    pub connection: ConnectionMonitor,
+    /// On-device storage locations, set once the app's paths are known.
+    ///
+    /// Game data is kept per identity; see [`profile_storage`](Self::profile_storage).
+    pub storage: Option<StorageLayout>,
+    /// Key for encrypting the active identity's saves, once the player has
+    /// entered their storage passphrase.
+    pub storage_key: Option<AtRestKey>,
+    /// Recent network spans and metrics for the debug overlay.
+    pub debug: DebugRecorder,
+    /// Loaded message catalogs for localizing game strings.
+    pub localizer: Localizer,
+    /// Worker thread for long engine operations, started once the app is
+    /// running.
+    pub worker: Option<EngineWorker>,
+    /// Recently emitted events, for the frontend to catch up after a
+    /// reload. Also managed on its own so events can be logged while this
+    /// state is locked.
+    pub events: Arc<EventLog>,
+    /// Games of the active identity found on the local and remote relays,
+    /// for the "continue game" browser.
+    pub game_index: GameIndex,
 }
 
 impl AppState {
     /// Create a new application state.
     pub fn new() -> Self {
         Self {
-            game_engine: None,
-            peer_count: 0,
+            sessions: HashMap::new(),
+            busy: HashSet::new(),
+            node_id: PeerId::from(uuid::Uuid::new_v4().simple().to_string()),
+            active_game: None,
             saved_games: HashMap::new(),
             preferences: Preferences::default(),
+            profile: UserProfile::default(),
+            tournaments: HashMap::new(),
+            background_mode: false,
+            connection: ConnectionMonitor::default(),
+            storage: None,
+            storage_key: None,
+            debug: DebugRecorder::default(),
+            localizer: Localizer::new(),
+            worker: None,
+            events: Arc::new(EventLog::default()),
+            game_index: GameIndex::new(),
         }
     }
 
-    /// Create a new game with the given settings.
-    pub fn create_game(&mut self, settings: GameSettings, seed: [u8; 32]) -> Result<(), AppError> {
-        let engine = GameEngine::new(settings, seed);
-        self.game_engine = Some(engine);
-        Ok(())
+    /// Create a new game with the given settings and make it active.
+    ///
+    /// Returns the new game's ID.
+    pub fn create_game(
+        &mut self,
+        settings: GameSettings,
+        seed: [u8; 32],
+    ) -> Result<GameId, AppError> {
+        self.add_session(GameEngine::new(settings, seed))
     }
 
-    /// Get the current game state.
-    pub fn get_game_state(&self) -> Result<&GameState, AppError> {
-        self.game_engine
-            .as_ref()
-            .map(|e| &e.state)
-            .ok_or(AppError::NoActiveGame)
+    /// Start tracking a game engine and make it active.
+    ///
+    /// Returns the game's ID.
+    pub fn add_session(&mut self, engine: GameEngine) -> Result<GameId, AppError> {
+        let game_id = engine.state.id.clone();
+        if self.sessions.contains_key(&game_id) || self.busy.contains(&game_id) {
+            return Err(AppError::GameAlreadyActive);
+        }
+        self.sessions.insert(
+            game_id.clone(),
+            GameSession::new(engine, self.node_id.clone()),
+        );
+        self.active_game = Some(game_id.clone());
+        Ok(game_id)
     }
 
-    /// Get mutable access to the game engine.
-    pub fn get_engine_mut(&mut self) -> Result<&mut GameEngine, AppError> {
-        self.game_engine.as_mut().ok_or(AppError::NoActiveGame)
+    /// Start a guest session for a game hosted by a peer and make it
+    /// active, unless this client already has a session for it.
+    pub fn join_session(&mut self, game_id: &GameId) {
+        if self.sessions.contains_key(game_id) || self.busy.contains(game_id) {
+            return;
+        }
+        self.sessions.insert(
+            game_id.clone(),
+            GameSession::join(game_id.clone(), self.node_id.clone()),
+        );
+        self.active_game = Some(game_id.clone());
     }
 
-    /// Check if a game is currently active.
-    pub fn has_active_game(&self) -> bool {
-        self.game_engine.is_some()
+    /// Get a game session.
+    pub fn session(&self, game_id: &GameId) -> Result<&GameSession, AppError> {
+        self.sessions
+            .get(game_id)
+            .ok_or_else(|| self.missing_session(game_id))
     }
 
-    /// End the current game.
-    #[allow(dead_code)]
-    pub fn end_game(&mut self) {
-        self.game_engine = None;
+    /// Get mutable access to a game session.
+    pub fn session_mut(&mut self, game_id: &GameId) -> Result<&mut GameSession, AppError> {
+        if self.busy.contains(game_id) {
+            return Err(self.missing_session(game_id));
+        }
+        self.sessions
+            .get_mut(game_id)
+            .ok_or_else(|| AppError::GameNotFound(game_id.to_string()))
+    }
+
+    /// Take a game's session out for the engine worker.
+    ///
+    /// Commands for the game fail as busy until the session is returned
+    /// with [`return_session`](Self::return_session).
+    pub fn checkout_session(&mut self, game_id: &GameId) -> Result<GameSession, AppError> {
+        let session = self
+            .sessions
+            .remove(game_id)
+            .ok_or_else(|| self.missing_session(game_id))?;
+        self.busy.insert(game_id.clone());
+        Ok(session)
+    }
+
+    /// Put back a session taken with [`checkout_session`](Self::checkout_session).
+    pub fn return_session(&mut self, game_id: GameId, session: GameSession) {
+        self.busy.remove(&game_id);
+        self.sessions.insert(game_id, session);
     }
 
-    /// Get peer count.
+    /// Drop a checked-out session that can't be returned, such as one left
+    /// half-updated by a panicking job.
+    pub fn evict_session(&mut self, game_id: &GameId) {
+        self.busy.remove(game_id);
+        if self.active_game.as_ref() == Some(game_id) {
+            self.active_game = None;
+        }
+    }
+
+    /// Error for a game that isn't in [`sessions`](Self::sessions).
+    fn missing_session(&self, game_id: &GameId) -> AppError {
+        if self.busy.contains(game_id) {
+            AppError::InvalidState(format!("Game {} is busy", game_id))
+        } else {
+            AppError::GameNotFound(game_id.to_string())
+        }
+    }
+
+    /// Get a game's state.
+    pub fn get_game_state(&self, game_id: &GameId) -> Result<&GameState, AppError> {
+        self.session(game_id).map(|s| &s.engine.state)
+    }
+
+    /// Get read-only access to a game's engine.
+    pub fn get_engine(&self, game_id: &GameId) -> Result<&GameEngine, AppError> {
+        self.session(game_id).map(|s| &s.engine)
+    }
+
+    /// Get mutable access to a game's engine.
+    pub fn get_engine_mut(&mut self, game_id: &GameId) -> Result<&mut GameEngine, AppError> {
+        self.session_mut(game_id).map(|s| &mut s.engine)
+    }
+
+    /// Get a game's engine for taking a local action, along with the
+    /// offline manager that routes the resulting events.
+    ///
+    /// Fails while offline if the sync strategy pauses play.
+    pub fn get_engine_for_action(
+        &mut self,
+        game_id: &GameId,
+    ) -> Result<(&mut GameEngine, &mut OfflineManager), AppError> {
+        let session = self.session_mut(game_id)?;
+        if !session.offline.allows_local_actions() {
+            return Err(AppError::NetworkError(
+                "Game is paused until reconnected".to_string(),
+            ));
+        }
+        Ok((&mut session.engine, &mut session.offline))
+    }
+
+    /// Make a game the one shown in the UI.
+    pub fn switch_game(&mut self, game_id: &GameId) -> Result<(), AppError> {
+        self.session(game_id)?;
+        self.active_game = Some(game_id.clone());
+        Ok(())
+    }
+
+    /// End a game and stop tracking it, cancelling its in-flight network
+    /// work.
+    pub fn end_game(&mut self, game_id: &GameId) -> Result<GameSession, AppError> {
+        let session = self
+            .sessions
+            .remove(game_id)
+            .ok_or_else(|| self.missing_session(game_id))?;
+        session.cancel.cancel();
+        if self.active_game.as_ref() == Some(game_id) {
+            self.active_game = self.sessions.keys().next().cloned();
+        }
+        Ok(session)
+    }
+
+    /// Whether the player has set a storage passphrase.
+    pub fn storage_encryption_enabled(&self) -> bool {
+        self.profile_storage()
+            .ok()
+            .flatten()
+            .is_some_and(|layout| layout.at_rest_key_path().exists())
+    }
+
+    /// Total peers connected across all games.
     pub fn connected_peers(&self) -> usize {
-        self.peer_count
+        self.sessions.values().map(|s| s.peer_count).sum()
     }
 
-    /// Add a peer.
-    pub fn add_peer(&mut self) {
-        self.peer_count += 1;
+    /// Get mutable access to a tournament.
+    pub fn get_tournament_mut(&mut self, tournament_id: &str) -> Result<&mut Tournament, AppError> {
+        self.tournaments
+            .get_mut(tournament_id)
+            .ok_or_else(|| AppError::TournamentNotFound(tournament_id.to_string()))
     }
 
-    /// Remove a peer.
-    pub fn remove_peer(&mut self) {
-        self.peer_count = self.peer_count.saturating_sub(1);
+    /// Storage for the active identity's games, saves and storage key.
+    ///
+    /// Once the profile has an npub this is that identity's own profile
+    /// directory, so several players sharing a device never read each
+    /// other's data. Without an npub it is the device-wide storage.
+    pub fn profile_storage(&self) -> Result<Option<StorageLayout>, AppError> {
+        let Some(layout) = &self.storage else {
+            return Ok(None);
+        };
+        match &self.profile.npub {
+            Some(npub) => layout
+                .for_owner(npub)
+                .map(Some)
+                .map_err(|e| AppError::StorageError(e.to_string())),
+            None => Ok(Some(layout.clone())),
+        }
     }
+
+    /// Open the active identity's local relay.
+    ///
+    /// Events are encrypted once the storage is unlocked. Fails if storage
+    /// isn't set up or the profile has no npub.
+    pub fn local_relay(&self) -> Result<LocalRelay, AppError> {
+        let layout = self
+            .storage
+            .as_ref()
+            .ok_or_else(|| AppError::StorageError("Storage not initialized".to_string()))?;
+        let npub = self
+            .profile
+            .npub
+            .as_deref()
+            .ok_or_else(|| AppError::InvalidState("Profile has no npub".to_string()))?;
+        let relay = LocalRelay::for_identity(layout, npub)?;
+        Ok(match &self.storage_key {
+            Some(key) => relay.with_encryption(key.clone()),
+            None => relay,
+        })
+    }
+
+    /// Offline storage for one game, kept in its own directory.
+    fn offline_storage(layout: &StorageLayout, game_id: &GameId) -> OfflineStorage {
+        OfflineStorage::new(layout.offline_dir().join(game_id.as_str()))
+    }
+
+    /// Flush every game's queued actions and state to disk before the app
+    /// is backgrounded.
+    pub fn suspend(&mut self) -> Result<(), AppError> {
+        let Some(layout) = self.profile_storage()? else {
+            return Ok(());
+        };
+        let now = unix_now();
+        for (game_id, session) in self.sessions.iter_mut() {
+            session.offline.suspend(
+                &Self::offline_storage(&layout, game_id),
+                Some(&session.engine.state),
+                now,
+            )?;
+        }
+        Ok(())
+    }
+
+    /// Restore queued actions and check connections after the app returns
+    /// to the foreground.
+    ///
+    /// Returns the combined plan for all games, or `None` if there are no
+    /// games or storage isn't set up.
+    pub fn resume(&mut self) -> Result<Option<ResumePlan>, AppError> {
+        let Some(layout) = self.profile_storage()? else {
+            return Ok(None);
+        };
+        let now = unix_now();
+        let config = LifecycleConfig::default();
+        let mut combined: Option<ResumePlan> = None;
+        for (game_id, session) in self.sessions.iter_mut() {
+            let plan = session.offline.resume(
+                &Self::offline_storage(&layout, game_id),
+                &mut self.connection,
+                &config,
+                now,
+            )?;
+            combined = Some(match combined {
+                None => plan,
+                Some(prev) => ResumePlan {
+                    suspended_for_secs: prev.suspended_for_secs.max(plan.suspended_for_secs),
+                    revalidate_connections: prev.revalidate_connections
+                        || plan.revalidate_connections,
+                    needs_resync: prev.needs_resync || plan.needs_resync,
+                    restored_events: prev.restored_events + plan.restored_events,
+                },
+            });
+        }
+        Ok(combined)
+    }
+}
+
+/// Current Unix time in seconds.
+fn unix_now() -> u64 {
+    std::time::SystemTime::now()
+        .duration_since(std::time::UNIX_EPOCH)
+        .unwrap_or_default()
+        .as_secs()
 }
 
 impl Default for AppState {
@@ -85,7 +500,8 @@ impl Default for AppState {
 
 /// User preferences.
 #[allow(dead_code)]
-#[derive(Clone, Debug)]
+#[derive(Clone, Debug, Serialize, Deserialize, JsonSchema, TS)]
+#[serde(default, rename_all = "camelCase")]
 pub struct Preferences {
     /// Enable sound effects.
     pub sound_enabled: bool,
@@ -101,6 +517,27 @@ pub struct Preferences {
     pub show_grid: bool,
     /// Show yield icons on tiles.
     pub show_yields: bool,
+    /// Preferred language for game strings (e.g. "en", "fr-CA").
+    pub locale: String,
+    /// Most candidates the AI may evaluate per engine tick.
+    pub ai_tick_nodes: u64,
+    /// Most candidates the AI may evaluate per turn.
+    pub ai_turn_nodes: u64,
+    /// Refuse to end the turn while research or a city's production is
+    /// unchosen.
+    pub block_end_turn_on_pending: bool,
+    /// Capture the map every turn and keep the captures as a timelapse.
+    pub timelapse_capture: bool,
+}
+
+impl Preferences {
+    /// The AI's thinking budget.
+    pub fn ai_budget(&self) -> AiBudget {
+        AiBudget {
+            tick_nodes: self.ai_tick_nodes,
+            turn_nodes: self.ai_turn_nodes,
+        }
+    }
 }
 
 impl Default for Preferences {
@@ -113,30 +550,31 @@ impl Default for Preferences {
             auto_save_turns: 5,
             show_grid: true,
             show_yields: true,
+            locale: nostr_nations_core::locale::FALLBACK_LOCALE.to_string(),
+            ai_tick_nodes: AiBudget::default().tick_nodes,
+            ai_turn_nodes: AiBudget::default().turn_nodes,
+            block_end_turn_on_pending: true,
+            timelapse_capture: false,
         }
     }
 }
 
-/// Application errors.
-#[derive(Debug, Clone, thiserror::Error)]
-pub enum AppError {
-    #[error("No active game")]
-    NoActiveGame,
-    #[error("Game already in progress")]
-    GameAlreadyActive,
-    #[error("Invalid game state: {0}")]
-    InvalidState(String),
-    #[error("Network error: {0}")]
-    NetworkError(String),
-    #[error("Serialization error: {0}")]
-    SerializationError(String),
-}
-
-impl serde::Serialize for AppError {
-    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
-    where
-        S: serde::Serializer,
-    {
-        serializer.serialize_str(&self.to_string())
-    }
+/// Player identity and the defaults used when creating or joining a game.
+#[derive(Clone, Debug, Default, Serialize, Deserialize, JsonSchema, TS)]
+#[serde(default, rename_all = "camelCase")]
+pub struct UserProfile {
+    /// Name shown to other players.
+    pub display_name: String,
+    /// Nostr public key (bech32 `npub1...`).
+    pub npub: Option<String>,
+    /// Relay URLs to publish and subscribe through, in order of preference.
+    pub preferred_relays: Vec<String>,
+    /// Civilization picked by default.
+    pub default_civilization: Option<String>,
+    /// Map size picked by default (e.g. "standard").
+    pub default_map_size: Option<String>,
+    /// Difficulty picked by default (e.g. "prince").
+    pub default_difficulty: Option<String>,
+    /// Game speed picked by default (e.g. "normal").
+    pub default_game_speed: Option<String>,
 }
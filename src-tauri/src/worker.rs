@@ -0,0 +1,132 @@
+//! Background worker for engine operations.
+//!
+//! Synchronous Tauri commands run on the main thread, so a slow engine
+//! operation there freezes the UI. Long operations such as map generation
+//! and AI turns are instead sent to a dedicated worker thread owned by
+//! [`AppState`]. Jobs run one at a time in the order they were submitted,
+//! so mutations from the worker never interleave with each other.
+//!
+//! Jobs never hold the application state lock while they compute. A job
+//! on one game checks that game's [`GameSession`] out of [`AppState`]
+//! with [`EngineWorker::run_session`] and returns it when done, so other
+//! commands keep running meanwhile; commands for the busy game fail
+//! instead of blocking the main thread. Other jobs lock the state only for
+//! the brief reads and writes around their work.
+//!
+//! Jobs get the [`AppHandle`], so they can emit progress events while they
+//! work.
+
+use crate::error::AppError;
+use crate::state::{AppState, GameSession};
+use nostr_nations_core::GameId;
+use std::panic::{self, AssertUnwindSafe};
+use std::sync::mpsc;
+use std::sync::{Mutex, MutexGuard};
+use std::thread;
+use tauri::{AppHandle, Manager};
+use tokio::sync::oneshot;
+
+/// A unit of work for the engine worker.
+pub type EngineJob = Box<dyn FnOnce(&AppHandle) + Send + 'static>;
+
+/// Handle to the engine worker thread.
+#[derive(Clone)]
+pub struct EngineWorker {
+    sender: mpsc::Sender<EngineJob>,
+}
+
+impl EngineWorker {
+    /// Start the worker thread.
+    ///
+    /// The thread exits once every handle to the worker is dropped.
+    pub fn spawn(app_handle: AppHandle) -> std::io::Result<Self> {
+        let (sender, receiver) = mpsc::channel::<EngineJob>();
+        thread::Builder::new()
+            .name("engine-worker".to_string())
+            .spawn(move || {
+                for job in receiver {
+                    // A panicking job drops its reply; keep serving the rest
+                    let _ = panic::catch_unwind(AssertUnwindSafe(|| job(&app_handle)));
+                }
+            })?;
+        Ok(Self { sender })
+    }
+
+    /// Queue a job without waiting for it.
+    pub fn submit(&self, job: EngineJob) -> Result<(), AppError> {
+        self.sender
+            .send(job)
+            .map_err(|_| AppError::InvalidState("Engine worker stopped".to_string()))
+    }
+
+    /// Run a job on the worker and wait for its result.
+    ///
+    /// The job doesn't hold the application state; it should lock it with
+    /// [`lock_state`] only around the reads and writes it needs.
+    pub async fn run<T, F>(&self, job: F) -> Result<T, AppError>
+    where
+        T: Send + 'static,
+        F: FnOnce(&AppHandle) -> Result<T, AppError> + Send + 'static,
+    {
+        let (reply, result) = oneshot::channel();
+        self.submit(Box::new(move |app_handle| {
+            let _ = reply.send(job(app_handle));
+        }))?;
+        result
+            .await
+            .map_err(|_| AppError::InvalidState("Engine job was dropped".to_string()))?
+    }
+
+    /// Run a job against one game's session and wait for its result.
+    ///
+    /// The session is checked out of the application state while the job
+    /// runs and returned afterwards, even if the job fails. If the job
+    /// panics the session may be half-updated, so it is evicted instead.
+    pub async fn run_session<T, F>(&self, game_id: GameId, job: F) -> Result<T, AppError>
+    where
+        T: Send + 'static,
+        F: FnOnce(&AppHandle, &mut GameSession) -> Result<T, AppError> + Send + 'static,
+    {
+        self.run(move |app_handle| {
+            let mut session = lock_state(app_handle)?.checkout_session(&game_id)?;
+            let outcome = panic::catch_unwind(AssertUnwindSafe(|| {
+                let outcome = job(app_handle, &mut session);
+                // Jobs are what advance the game, so keep the gate in step
+                session.refresh_redaction();
+                outcome
+            }));
+            let mut state = lock_state(app_handle)?;
+            match outcome {
+                Ok(outcome) => {
+                    state.return_session(game_id, session);
+                    outcome
+                }
+                Err(_) => {
+                    state.evict_session(&game_id);
+                    Err(AppError::InvalidState(format!(
+                        "Engine job for game {} panicked",
+                        game_id
+                    )))
+                }
+            }
+        })
+        .await
+    }
+}
+
+/// Lock the managed application state.
+pub fn lock_state(app_handle: &AppHandle) -> Result<MutexGuard<'_, AppState>, AppError> {
+    app_handle
+        .state::<Mutex<AppState>>()
+        .inner()
+        .lock()
+        .map_err(|_| AppError::InvalidState("Lock poisoned".to_string()))
+}
+
+/// Get the engine worker from the managed application state.
+pub fn engine_worker(app_handle: &AppHandle) -> Result<EngineWorker, AppError> {
+    lock_state(app_handle)?
+        .worker
+        .clone()
+        .ok_or_else(|| AppError::InvalidState("Engine worker not started".to_string()))
+}
@@ -21,8 +21,7 @@ type Screen =
 
 function App() {
   const [currentScreen, setCurrentScreen] = useState<Screen>('loading')
-  const [_activeGameId, setActiveGameId] = useState<string | null>(null)
-  const { initializeGame } = useGameStore()
+  const { activeGameId, setActiveGameId, initializeGame } = useGameStore()
 
   useEffect(() => {
     // Simulate initial loading
@@ -51,7 +50,9 @@ function App() {
   const handleBackToMenu = async () => {
     // End the game on the backend to allow starting a new one
     try {
-      await invoke('end_game')
+      if (activeGameId) {
+        await invoke('end_game', { gameId: activeGameId })
+      }
     } catch (e) {
       // Ignore error if no game was active
       console.debug('No active game to end:', e)
@@ -1,6 +1,6 @@
 import React, { useState, useCallback } from 'react'
 import { invoke } from '@tauri-apps/api/core'
-import { useIsTauri } from '@/hooks/useTauri'
+import { errorMessage, useIsTauri } from '@/hooks/useTauri'
 
 // Types for ticket info returned from scanning
 interface TicketInfo {
@@ -61,8 +61,7 @@ const JoinGameScreen: React.FC<JoinGameScreenProps> = ({
           })
         }
       } catch (err) {
-        const errorMessage = err instanceof Error ? err.message : String(err)
-        setError(errorMessage)
+        setError(errorMessage(err))
       } finally {
         setIsScanning(false)
       }
@@ -97,8 +96,7 @@ const JoinGameScreen: React.FC<JoinGameScreenProps> = ({
         })
       }
     } catch (err) {
-      const errorMessage = err instanceof Error ? err.message : String(err)
-      setError(errorMessage)
+      setError(errorMessage(err))
     } finally {
       setIsScanning(false)
     }
@@ -115,11 +113,17 @@ const JoinGameScreen: React.FC<JoinGameScreenProps> = ({
       const ticket = ticketInput.trim()
 
       if (isTauri) {
+        if (!ticketInfo.game_id) {
+          throw new Error('Ticket does not name a game')
+        }
+        const gameId = ticketInfo.game_id
+
         // First connect to the peer
-        await invoke('connect_peer', { ticket })
+        await invoke('connect_peer', { gameId, ticket })
 
         // Then join the game with player info
         const result = await invoke<{ game_id: string }>('join_game', {
+          gameId,
           playerName: playerName || 'Player',
           civilization: selectedCiv,
         })
@@ -131,8 +135,7 @@ const JoinGameScreen: React.FC<JoinGameScreenProps> = ({
         onConnected('mock-game-id')
       }
     } catch (err) {
-      const errorMessage = err instanceof Error ? err.message : String(err)
-      setError(errorMessage)
+      setError(errorMessage(err))
       setIsConnecting(false)
     }
   }
@@ -1,6 +1,6 @@
 import React, { useState, useEffect, useCallback } from 'react';
 import { invoke } from '@tauri-apps/api/core';
-import { useIsTauri } from '@/hooks/useTauri';
+import { errorMessage, useIsTauri } from '@/hooks/useTauri';
 
 // Types for saved game data
 interface SavedGame {
@@ -71,8 +71,7 @@ const LoadGameScreen: React.FC<LoadGameScreenProps> = ({ onBack, onGameLoaded })
         setSaves(MOCK_SAVES);
       }
     } catch (err) {
-      const errorMessage = err instanceof Error ? err.message : String(err);
-      setError(errorMessage);
+      setError(errorMessage(err));
     } finally {
       setIsLoading(false);
     }
@@ -99,8 +98,7 @@ const LoadGameScreen: React.FC<LoadGameScreenProps> = ({ onBack, onGameLoaded })
         onGameLoaded(selectedSave);
       }
     } catch (err) {
-      const errorMessage = err instanceof Error ? err.message : String(err);
-      setError(errorMessage);
+      setError(errorMessage(err));
       setIsLoadingGame(false);
     }
   };
@@ -124,8 +122,7 @@ const LoadGameScreen: React.FC<LoadGameScreenProps> = ({ onBack, onGameLoaded })
       }
       setDeleteConfirmId(null);
     } catch (err) {
-      const errorMessage = err instanceof Error ? err.message : String(err);
-      setError(errorMessage);
+      setError(errorMessage(err));
     } finally {
       setIsDeleting(false);
     }
@@ -1,6 +1,6 @@
 import React, { useState, useCallback } from 'react';
 import { invoke } from '@tauri-apps/api/core';
-import { useIsTauri } from '@/hooks/useTauri';
+import { errorMessage, useIsTauri } from '@/hooks/useTauri';
 
 // Types for game configuration matching Rust backend
 export type MapSize = 'duel' | 'small' | 'standard' | 'large' | 'huge';
@@ -213,8 +213,7 @@ const NewGameWizard: React.FC<NewGameWizardProps> = ({ onCancel, onGameCreated }
         onGameCreated('mock-game-id');
       }
     } catch (err) {
-      const errorMessage = err instanceof Error ? err.message : String(err);
-      setError(errorMessage);
+      setError(errorMessage(err));
       setIsCreating(false);
     }
   };
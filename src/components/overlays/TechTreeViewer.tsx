@@ -1,6 +1,7 @@
 import React, { useCallback, useMemo, useState } from 'react'
 import { create } from 'zustand'
 import { invoke } from '@tauri-apps/api/core'
+import { useGameStore } from '@/stores/gameStore'
 
 // Types
 type TechStatus = 'researched' | 'researching' | 'available' | 'locked'
@@ -328,6 +329,7 @@ const TechTreeViewer: React.FC<TechTreeViewerProps> = ({ isOpen, onClose }) => {
     sciencePerTurn,
     setCurrentResearch,
   } = useTechTreeStore()
+  const activeGameId = useGameStore((state) => state.activeGameId)
 
   const [selectedTech, setSelectedTech] = useState<Technology | null>(null)
 
@@ -377,7 +379,8 @@ const TechTreeViewer: React.FC<TechTreeViewerProps> = ({ isOpen, onClose }) => {
   const handleSetResearch = useCallback(
     async (techId: string) => {
       try {
-        await invoke('set_research', { tech_id: techId })
+        if (!activeGameId) throw new Error('No active game')
+        await invoke('set_research', { gameId: activeGameId, techId })
         setCurrentResearch(techId)
         setSelectedTech(null)
       } catch (error) {
@@ -387,7 +390,7 @@ const TechTreeViewer: React.FC<TechTreeViewerProps> = ({ isOpen, onClose }) => {
         setSelectedTech(null)
       }
     },
-    [setCurrentResearch]
+    [activeGameId, setCurrentResearch]
   )
 
   // Handle backdrop click
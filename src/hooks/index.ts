@@ -1,7 +1,10 @@
 export {
+  errorMessage,
+  isCommandError,
   useIsTauri,
   useTauriCommand,
   useTauriEvent,
   useGameCommands,
   useNostrCommands,
 } from './useTauri';
+export type { CommandError } from './useTauri';
@@ -16,6 +16,36 @@ export function useIsTauri(): boolean {
   return isTauri;
 }
 
+/**
+ * Error returned by a failed Tauri command.
+ *
+ * `code` is stable; branch on it rather than on `message`, which is English
+ * text for logs. `localized` is a message catalog key with its parameters.
+ */
+export interface CommandError {
+  code: string;
+  number: number;
+  message: string;
+  localized: { key: string; args?: Record<string, string> };
+}
+
+/**
+ * Check whether a caught value is a structured command error
+ */
+export function isCommandError(err: unknown): err is CommandError {
+  return typeof err === 'object' && err !== null && 'code' in err && 'message' in err;
+}
+
+/**
+ * Human-readable message for anything thrown by `invoke`
+ */
+export function errorMessage(err: unknown): string {
+  if (isCommandError(err) || err instanceof Error) {
+    return err.message;
+  }
+  return String(err);
+}
+
 /**
  * Hook for invoking Tauri commands with loading and error states
  */
@@ -42,8 +72,7 @@ export function useTauriCommand<T, Args extends unknown[] = []>(
         setData(result);
         return result;
       } catch (err) {
-        const errorMessage = err instanceof Error ? err.message : String(err);
-        setError(errorMessage);
+        setError(errorMessage(err));
         console.error(`Tauri command "${command}" failed:`, err);
         return null;
       } finally {
@@ -120,30 +149,33 @@ export function useGameCommands() {
   );
 
   const saveGame = useCallback(
-    async (gameState: unknown) => {
+    async (gameId: string, name: string) => {
       if (!isTauri) return null;
-      return invoke('save_game', { gameState });
+      return invoke('save_game', { gameId, name });
     },
     [isTauri]
   );
 
-  const endTurn = useCallback(async () => {
-    if (!isTauri) return null;
-    return invoke('end_turn');
-  }, [isTauri]);
+  const endTurn = useCallback(
+    async (gameId: string) => {
+      if (!isTauri) return null;
+      return invoke('end_turn', { gameId });
+    },
+    [isTauri]
+  );
 
   const moveUnit = useCallback(
-    async (unitId: string, destination: { q: number; r: number }) => {
+    async (gameId: string, unitId: number, path: [number, number][]) => {
       if (!isTauri) return null;
-      return invoke('move_unit', { unitId, destination });
+      return invoke('move_unit', { gameId, unitId, path });
     },
     [isTauri]
   );
 
   const foundCity = useCallback(
-    async (unitId: string) => {
+    async (gameId: string, settlerId: number, name: string) => {
       if (!isTauri) return null;
-      return invoke('found_city', { unitId });
+      return invoke('found_city', { gameId, settlerId, name });
     },
     [isTauri]
   );
@@ -17,6 +17,8 @@ import type {
 interface GameStore {
   // Game state
   gameState: GameState | null
+  // Backend game ID that game-scoped commands are sent for
+  activeGameId: string | null
   isLoading: boolean
   error: string | null
 
@@ -30,6 +32,7 @@ interface GameStore {
   cameraZoom: number
 
   // Actions - Game lifecycle
+  setActiveGameId: (gameId: string | null) => void
   initializeGame: (settings?: Partial<GameSettings>) => void
   loadGame: (gameId: string) => Promise<void>
   saveGame: () => Promise<void>
@@ -178,6 +181,7 @@ export const useGameStore = create<GameStore>()(
     gameState: null,
     isLoading: false,
     error: null,
+    activeGameId: null,
     selection: { type: 'none', id: null, coord: null },
     notifications: [],
     isPaused: false,
@@ -185,6 +189,10 @@ export const useGameStore = create<GameStore>()(
     cameraZoom: 1,
 
     // Game lifecycle actions
+    setActiveGameId: (gameId) => {
+      set({ activeGameId: gameId })
+    },
+
     initializeGame: (settings) => {
       set({
         gameState: createDefaultGameState(settings),